@@ -103,6 +103,7 @@ async fn write_data(
             region_number: 0,
             columns,
             row_count,
+            ..Default::default()
         };
         let now = Instant::now();
         db.insert(request).await.unwrap();
@@ -224,114 +225,133 @@ fn create_table_expr() -> CreateTableExpr {
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "tpep_pickup_datetime".to_string(),
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "tpep_dropoff_datetime".to_string(),
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "passenger_count".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "trip_distance".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "RatecodeID".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "store_and_fwd_flag".to_string(),
                 datatype: ColumnDataType::String as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "PULocationID".to_string(),
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "DOLocationID".to_string(),
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "payment_type".to_string(),
                 datatype: ColumnDataType::Int64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "fare_amount".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "extra".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "mta_tax".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "tip_amount".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "tolls_amount".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "improvement_surcharge".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "total_amount".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "congestion_surcharge".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "airport_fee".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
         ],
         time_index: "tpep_pickup_datetime".to_string(),
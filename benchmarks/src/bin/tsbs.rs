@@ -0,0 +1,528 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bench with the `cpu-only` use case of [TSBS](https://github.com/timescale/tsbs). The dataset
+//! is generated in place from a seed, so runs with the same arguments write the same data.
+
+#![allow(clippy::print_stdout)]
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use client::api::v1::column::{SemanticType, Values};
+use client::api::v1::{Column, ColumnDataType, ColumnDef, CreateTableExpr, InsertRequest, TableId};
+use client::{Client, Database};
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::task::JoinSet;
+
+const CATALOG_NAME: &str = "greptime";
+const SCHEMA_NAME: &str = "public";
+const TABLE_NAME: &str = "cpu";
+const TIMESTAMP_COLUMN: &str = "ts";
+
+const TAG_NAMES: [&str; 10] = [
+    "hostname",
+    "region",
+    "datacenter",
+    "rack",
+    "os",
+    "arch",
+    "team",
+    "service",
+    "service_version",
+    "service_environment",
+];
+const FIELD_NAMES: [&str; 10] = [
+    "usage_user",
+    "usage_system",
+    "usage_idle",
+    "usage_nice",
+    "usage_iowait",
+    "usage_irq",
+    "usage_softirq",
+    "usage_steal",
+    "usage_guest",
+    "usage_guest_nice",
+];
+
+const REGIONS: [&str; 9] = [
+    "us-east-1",
+    "us-west-1",
+    "us-west-2",
+    "eu-west-1",
+    "eu-central-1",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-northeast-1",
+    "sa-east-1",
+];
+const OS: [&str; 3] = ["Ubuntu16.10", "Ubuntu16.04LTS", "Ubuntu15.10"];
+const ARCH: [&str; 2] = ["x64", "x86"];
+const TEAMS: [&str; 4] = ["SF", "NYC", "LON", "CHI"];
+const SERVICE_ENVIRONMENTS: [&str; 3] = ["production", "staging", "test"];
+
+/// Interval between two readings of a host, the same as TSBS.
+const READING_INTERVAL_MS: i64 = 10_000;
+const HOUR_MS: i64 = 3_600_000;
+
+#[derive(Parser)]
+#[command(name = "TSBS benchmark runner")]
+struct Args {
+    /// Number of hosts to generate data for.
+    #[arg(long, default_value_t = 100)]
+    scale: usize,
+
+    /// Start of the dataset in milliseconds, defaults to 2016-01-01T00:00:00Z like TSBS.
+    #[arg(long = "timestamp-start", default_value_t = 1_451_606_400_000)]
+    timestamp_start: i64,
+
+    /// Hours of the dataset.
+    #[arg(long, default_value_t = 24)]
+    hours: i64,
+
+    /// Seed to generate the dataset and query parameters.
+    #[arg(long, default_value_t = 123)]
+    seed: u64,
+
+    /// Batch size of insert request.
+    #[arg(short = 's', long = "batch-size", default_value_t = 4096)]
+    batch_size: usize,
+
+    /// Number of client threads on write (parallel on host level)
+    #[arg(short = 't', long = "thread-num", default_value_t = 4)]
+    thread_num: usize,
+
+    /// Number of query iteration
+    #[arg(short = 'i', long = "iter-num", default_value_t = 10)]
+    iter_num: usize,
+
+    #[arg(long = "skip-write")]
+    skip_write: bool,
+
+    #[arg(long = "skip-read")]
+    skip_read: bool,
+
+    #[arg(short, long, default_value_t = String::from("127.0.0.1:3001"))]
+    endpoint: String,
+}
+
+/// Deterministic pseudo random generator (SplitMix64), so the dataset only depends
+/// on the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a float in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn gen_range(&mut self, end: usize) -> usize {
+        (self.next_u64() % end as u64) as usize
+    }
+
+    fn choose<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.gen_range(items.len())]
+    }
+}
+
+/// A simulated host, readings of its fields walk randomly in `[0, 100]`.
+struct Host {
+    tags: Vec<String>,
+    fields: [f64; FIELD_NAMES.len()],
+    rng: Rng,
+}
+
+impl Host {
+    fn new(index: usize, seed: u64) -> Host {
+        let mut rng = Rng::new(seed.wrapping_add(index as u64));
+        let region = rng.choose(&REGIONS);
+        let tags = vec![
+            format!("host_{index}"),
+            region.to_string(),
+            format!("{region}{}", (b'a' + rng.gen_range(3) as u8) as char),
+            rng.gen_range(100).to_string(),
+            rng.choose(&OS).to_string(),
+            rng.choose(&ARCH).to_string(),
+            rng.choose(&TEAMS).to_string(),
+            rng.gen_range(20).to_string(),
+            rng.gen_range(2).to_string(),
+            rng.choose(&SERVICE_ENVIRONMENTS).to_string(),
+        ];
+        let mut fields = [0.0; FIELD_NAMES.len()];
+        for field in &mut fields {
+            *field = rng.next_f64() * 100.0;
+        }
+        Host { tags, fields, rng }
+    }
+
+    /// Advances the readings to the next interval.
+    fn tick(&mut self) {
+        for field in &mut self.fields {
+            let step = (self.rng.next_f64() - 0.5) * 2.0;
+            *field = (*field + step).clamp(0.0, 100.0);
+        }
+    }
+}
+
+/// Rows of a batch, in columnar form.
+struct Batch {
+    tags: Vec<Vec<String>>,
+    fields: Vec<Vec<f64>>,
+    timestamps: Vec<i64>,
+}
+
+impl Batch {
+    fn new() -> Batch {
+        Batch {
+            tags: vec![Vec::new(); TAG_NAMES.len()],
+            fields: vec![Vec::new(); FIELD_NAMES.len()],
+            timestamps: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, host: &Host, ts: i64) {
+        for (values, tag) in self.tags.iter_mut().zip(&host.tags) {
+            values.push(tag.clone());
+        }
+        for (values, field) in self.fields.iter_mut().zip(host.fields) {
+            values.push(field);
+        }
+        self.timestamps.push(ts);
+    }
+
+    fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    fn into_request(self) -> InsertRequest {
+        let row_count = self.len() as u32;
+        let mut columns = Vec::with_capacity(TAG_NAMES.len() + FIELD_NAMES.len() + 1);
+        for (name, values) in TAG_NAMES.iter().zip(self.tags) {
+            columns.push(Column {
+                column_name: name.to_string(),
+                semantic_type: SemanticType::Tag as i32,
+                values: Some(Values {
+                    string_values: values,
+                    ..Default::default()
+                }),
+                null_mask: vec![],
+                datatype: ColumnDataType::String as i32,
+                compressed_values: vec![],
+            });
+        }
+        for (name, values) in FIELD_NAMES.iter().zip(self.fields) {
+            columns.push(Column {
+                column_name: name.to_string(),
+                semantic_type: SemanticType::Field as i32,
+                values: Some(Values {
+                    f64_values: values,
+                    ..Default::default()
+                }),
+                null_mask: vec![],
+                datatype: ColumnDataType::Float64 as i32,
+                compressed_values: vec![],
+            });
+        }
+        columns.push(Column {
+            column_name: TIMESTAMP_COLUMN.to_string(),
+            semantic_type: SemanticType::Timestamp as i32,
+            values: Some(Values {
+                ts_millisecond_values: self.timestamps,
+                ..Default::default()
+            }),
+            null_mask: vec![],
+            datatype: ColumnDataType::TimestampMillisecond as i32,
+            compressed_values: vec![],
+        });
+
+        InsertRequest {
+            table_name: TABLE_NAME.to_string(),
+            region_number: 0,
+            columns,
+            row_count,
+            ..Default::default()
+        }
+    }
+}
+
+fn create_table_expr() -> CreateTableExpr {
+    let mut column_defs = Vec::with_capacity(TAG_NAMES.len() + FIELD_NAMES.len() + 1);
+    for name in TAG_NAMES {
+        column_defs.push(ColumnDef {
+            name: name.to_string(),
+            datatype: ColumnDataType::String as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            comment: String::new(),
+        });
+    }
+    for name in FIELD_NAMES {
+        column_defs.push(ColumnDef {
+            name: name.to_string(),
+            datatype: ColumnDataType::Float64 as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            comment: String::new(),
+        });
+    }
+    column_defs.push(ColumnDef {
+        name: TIMESTAMP_COLUMN.to_string(),
+        datatype: ColumnDataType::TimestampMillisecond as i32,
+        is_nullable: false,
+        default_constraint: vec![],
+        comment: String::new(),
+    });
+
+    CreateTableExpr {
+        catalog_name: CATALOG_NAME.to_string(),
+        schema_name: SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        desc: "".to_string(),
+        column_defs,
+        time_index: TIMESTAMP_COLUMN.to_string(),
+        primary_keys: vec!["hostname".to_string()],
+        create_if_not_exists: true,
+        table_options: Default::default(),
+        region_ids: vec![0],
+        table_id: Some(TableId { id: 0 }),
+    }
+}
+
+/// Generates and writes data of hosts in `host_indices`, returns the latency of each request.
+async fn write_hosts(
+    args: &WriteArgs,
+    db: &Database,
+    host_indices: Vec<usize>,
+    progress_bar: ProgressBar,
+) -> Vec<Duration> {
+    let mut hosts: Vec<_> = host_indices
+        .into_iter()
+        .map(|i| Host::new(i, args.seed))
+        .collect();
+    let mut latencies = Vec::new();
+    let mut batch = Batch::new();
+    let end = args.timestamp_start + args.hours * HOUR_MS;
+
+    let mut ts = args.timestamp_start;
+    while ts < end {
+        for host in &mut hosts {
+            batch.push(host, ts);
+            host.tick();
+        }
+        ts += READING_INTERVAL_MS;
+
+        if batch.len() >= args.batch_size || (ts >= end && batch.len() > 0) {
+            let batch = std::mem::replace(&mut batch, Batch::new());
+            let row_count = batch.len();
+            let now = Instant::now();
+            db.insert(batch.into_request()).await.unwrap();
+            latencies.push(now.elapsed());
+            progress_bar.inc(row_count as _);
+        }
+    }
+    latencies
+}
+
+/// Arguments shared by write tasks.
+#[derive(Clone)]
+struct WriteArgs {
+    timestamp_start: i64,
+    hours: i64,
+    seed: u64,
+    batch_size: usize,
+}
+
+async fn do_write(args: &Args, db: &Database) {
+    let create_table_result = db.create(create_table_expr()).await;
+    println!("Create table result: {create_table_result:?}");
+
+    let total_rows = args.scale as u64 * (args.hours * HOUR_MS / READING_INTERVAL_MS) as u64;
+    let progress_bar = ProgressBar::new(total_rows);
+    progress_bar.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:60.cyan/blue} {pos:>7}/{len:7}")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+    let write_args = WriteArgs {
+        timestamp_start: args.timestamp_start,
+        hours: args.hours,
+        seed: args.seed,
+        batch_size: args.batch_size,
+    };
+
+    let now = Instant::now();
+    let mut write_jobs = JoinSet::new();
+    let thread_num = args.thread_num.max(1);
+    for thread in 0..thread_num {
+        let host_indices: Vec<_> = (thread..args.scale).step_by(thread_num).collect();
+        let write_args = write_args.clone();
+        let db = db.clone();
+        let progress_bar = progress_bar.clone();
+        write_jobs
+            .spawn(async move { write_hosts(&write_args, &db, host_indices, progress_bar).await });
+    }
+    let mut latencies = Vec::new();
+    while let Some(result) = write_jobs.join_next().await {
+        latencies.extend(result.unwrap());
+    }
+    let elapsed = now.elapsed();
+    progress_bar.finish();
+
+    println!(
+        "Wrote {} rows in {}ms, {:.2} rows/s",
+        total_rows,
+        elapsed.as_millis(),
+        total_rows as f64 / elapsed.as_secs_f64()
+    );
+    print_latencies("insert", &mut latencies);
+}
+
+/// Returns queries of the TSBS `cpu-only` use case, time ranges and hosts of the queries
+/// are chosen randomly within the dataset.
+fn query_set(args: &Args, rng: &mut Rng) -> Vec<(&'static str, String)> {
+    let end = args.timestamp_start + args.hours * HOUR_MS;
+    let mut random_range = |hours: i64| {
+        let hours = hours.min(args.hours);
+        let slots = ((args.hours - hours) as usize).max(1);
+        let start = args.timestamp_start + rng.gen_range(slots) as i64 * HOUR_MS;
+        (start, start + hours * HOUR_MS)
+    };
+    let (start_1h, end_1h) = random_range(1);
+    let (start_8h, end_8h) = random_range(8);
+    let (start_12h, end_12h) = random_range(12);
+
+    let hosts = |rng: &mut Rng, n: usize| {
+        (0..n)
+            .map(|_| format!("'host_{}'", rng.gen_range(args.scale.max(1))))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let host_1 = hosts(rng, 1);
+    let hosts_8 = hosts(rng, 8);
+    let max_all = FIELD_NAMES
+        .iter()
+        .map(|f| format!("MAX({f})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    vec![
+        (
+            "single-groupby-1-1-1",
+            format!("SELECT date_trunc('minute', {TIMESTAMP_COLUMN}) AS minute, MAX(usage_user) FROM {TABLE_NAME} WHERE hostname IN ({host_1}) AND {TIMESTAMP_COLUMN} >= {start_1h} AND {TIMESTAMP_COLUMN} < {end_1h} GROUP BY minute ORDER BY minute"),
+        ),
+        (
+            "single-groupby-5-8-1",
+            format!("SELECT date_trunc('minute', {TIMESTAMP_COLUMN}) AS minute, MAX(usage_user), MAX(usage_system), MAX(usage_idle), MAX(usage_nice), MAX(usage_iowait) FROM {TABLE_NAME} WHERE hostname IN ({hosts_8}) AND {TIMESTAMP_COLUMN} >= {start_1h} AND {TIMESTAMP_COLUMN} < {end_1h} GROUP BY minute ORDER BY minute"),
+        ),
+        (
+            "cpu-max-all-8",
+            format!("SELECT date_trunc('hour', {TIMESTAMP_COLUMN}) AS hour, {max_all} FROM {TABLE_NAME} WHERE hostname IN ({hosts_8}) AND {TIMESTAMP_COLUMN} >= {start_8h} AND {TIMESTAMP_COLUMN} < {end_8h} GROUP BY hour ORDER BY hour"),
+        ),
+        (
+            "double-groupby-1",
+            format!("SELECT date_trunc('hour', {TIMESTAMP_COLUMN}) AS hour, hostname, AVG(usage_user) FROM {TABLE_NAME} WHERE {TIMESTAMP_COLUMN} >= {start_12h} AND {TIMESTAMP_COLUMN} < {end_12h} GROUP BY hour, hostname ORDER BY hour, hostname"),
+        ),
+        (
+            "high-cpu-1",
+            format!("SELECT * FROM {TABLE_NAME} WHERE usage_user > 90.0 AND hostname IN ({host_1}) AND {TIMESTAMP_COLUMN} >= {start_12h} AND {TIMESTAMP_COLUMN} < {end_12h}"),
+        ),
+        (
+            "lastpoint",
+            format!("SELECT hostname, MAX({TIMESTAMP_COLUMN}) FROM {TABLE_NAME} GROUP BY hostname"),
+        ),
+        (
+            "groupby-orderby-limit",
+            format!("SELECT date_trunc('minute', {TIMESTAMP_COLUMN}) AS minute, MAX(usage_user) FROM {TABLE_NAME} WHERE {TIMESTAMP_COLUMN} < {end} GROUP BY minute ORDER BY minute DESC LIMIT 5"),
+        ),
+    ]
+}
+
+async fn do_query(args: &Args, db: &Database) {
+    let mut rng = Rng::new(args.seed);
+    // Queries of each iteration have different parameters.
+    let query_sets: Vec<_> = (0..args.iter_num)
+        .map(|_| query_set(args, &mut rng))
+        .collect();
+    let Some(first) = query_sets.first() else {
+        return;
+    };
+
+    for (i, (query_name, _)) in first.iter().enumerate() {
+        let mut latencies = Vec::with_capacity(query_sets.len());
+        for queries in &query_sets {
+            let query = &queries[i].1;
+            let now = Instant::now();
+            let _res = db.sql(query).await.unwrap();
+            latencies.push(now.elapsed());
+        }
+        print_latencies(query_name, &mut latencies);
+    }
+}
+
+/// Prints the percentiles of `latencies`.
+fn print_latencies(name: &str, latencies: &mut [Duration]) {
+    if latencies.is_empty() {
+        return;
+    }
+    latencies.sort_unstable();
+    let percentile = |p: usize| {
+        // Nearest-rank percentile.
+        let rank = (latencies.len() * p + 99) / 100;
+        latencies[rank.max(1) - 1].as_secs_f64() * 1000.0
+    };
+    let mean = latencies.iter().sum::<Duration>().as_secs_f64() * 1000.0 / latencies.len() as f64;
+    println!(
+        "{name}: count {}, mean {:.2}ms, p50 {:.2}ms, p90 {:.2}ms, p99 {:.2}ms, max {:.2}ms",
+        latencies.len(),
+        mean,
+        percentile(50),
+        percentile(90),
+        percentile(99),
+        percentile(100),
+    );
+}
+
+fn main() {
+    let args = Args::parse();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(args.thread_num)
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let client = Client::with_urls(vec![&args.endpoint]);
+            let db = Database::with_client(client);
+
+            if !args.skip_write {
+                do_write(&args, &db).await;
+            }
+
+            if !args.skip_read {
+                do_query(&args, &db).await;
+            }
+        })
+}
@@ -0,0 +1,41 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzes the InfluxDB line protocol parser and the conversion of parsed lines into
+//! gRPC insert requests.
+
+#![no_main]
+
+use api::v1::InsertRequest as GrpcInsertRequest;
+use common_grpc::writer::Precision;
+use libfuzzer_sys::fuzz_target;
+use servers::influxdb::InfluxdbRequest;
+
+fuzz_target!(|input: (u8, &str)| {
+    let (precision, lines) = input;
+    let precision = match precision % 7 {
+        0 => Some(Precision::Nanosecond),
+        1 => Some(Precision::Microsecond),
+        2 => Some(Precision::Millisecond),
+        3 => Some(Precision::Second),
+        4 => Some(Precision::Minute),
+        5 => Some(Precision::Hour),
+        _ => None,
+    };
+    let request = InfluxdbRequest {
+        precision,
+        lines: lines.to_string(),
+    };
+    let _ = Vec::<GrpcInsertRequest>::try_from(&request);
+});
@@ -0,0 +1,26 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzes the parser of OpenTSDB telnet `put` lines.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use servers::opentsdb::codec::DataPoint;
+
+fuzz_target!(|line: &str| {
+    if let Ok(data_point) = DataPoint::try_create(line) {
+        let _ = data_point.as_grpc_insert();
+    }
+});
@@ -0,0 +1,36 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzes the SQL statement parser, parsed statements are also walked through the
+//! accessors used by the datanode and frontend.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::statement::Statement;
+
+fuzz_target!(|data: &str| {
+    let Ok(statements) = ParserContext::create_with_dialect(data, &GenericDialect {}) else {
+        return;
+    };
+    for statement in statements {
+        if let Statement::Insert(insert) = statement {
+            let _ = insert.full_table_name();
+            let _ = insert.columns();
+            let _ = insert.values();
+        }
+    }
+});
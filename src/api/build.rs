@@ -16,10 +16,13 @@ fn main() {
     tonic_build::configure()
         .compile(
             &[
+                "greptime/v1/catalog.proto",
+                "greptime/v1/cdc.proto",
                 "greptime/v1/database.proto",
                 "greptime/v1/meta/common.proto",
                 "greptime/v1/meta/heartbeat.proto",
                 "greptime/v1/meta/route.proto",
+                "greptime/v1/meta/scheduler.proto",
                 "greptime/v1/meta/store.proto",
                 "prometheus/remote/remote.proto",
             ],
@@ -16,12 +16,12 @@ use common_base::BitVec;
 use common_time::timestamp::TimeUnit;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::types::TimestampType;
-use datatypes::value::Value;
+use datatypes::value::{Histogram, Value};
 use datatypes::vectors::VectorRef;
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
-use crate::v1::column::Values;
+use crate::v1::column::{Histogram as HistogramValue, Values};
 use crate::v1::{Column, ColumnDataType};
 
 #[derive(Debug, PartialEq, Eq)]
@@ -67,6 +67,8 @@ impl From<ColumnDataTypeWrapper> for ConcreteDataType {
             ColumnDataType::TimestampNanosecond => {
                 ConcreteDataType::timestamp_nanosecond_datatype()
             }
+            ColumnDataType::Decimal128 => ConcreteDataType::decimal128_default_datatype(),
+            ColumnDataType::Histogram => ConcreteDataType::histogram_datatype(),
         }
     }
 }
@@ -97,6 +99,8 @@ impl TryFrom<ConcreteDataType> for ColumnDataTypeWrapper {
                 TimestampType::Microsecond(_) => ColumnDataType::TimestampMicrosecond,
                 TimestampType::Nanosecond(_) => ColumnDataType::TimestampNanosecond,
             },
+            ConcreteDataType::Decimal128(_) => ColumnDataType::Decimal128,
+            ConcreteDataType::Histogram(_) => ColumnDataType::Histogram,
             ConcreteDataType::Null(_) | ConcreteDataType::List(_) => {
                 return error::IntoColumnDataTypeSnafu { from: datatype }.fail()
             }
@@ -184,10 +188,25 @@ impl Values {
                 ts_nanosecond_values: Vec::with_capacity(capacity),
                 ..Default::default()
             },
+            ColumnDataType::Decimal128 => Values {
+                decimal128_values: Vec::with_capacity(capacity),
+                ..Default::default()
+            },
+            ColumnDataType::Histogram => Values {
+                histogram_values: Vec::with_capacity(capacity),
+                ..Default::default()
+            },
         }
     }
 }
 
+impl From<Histogram> for HistogramValue {
+    fn from(histogram: Histogram) -> Self {
+        let (bounds, counts) = histogram.into_parts();
+        Self { bounds, counts }
+    }
+}
+
 impl Column {
     // The type of vals must be same.
     pub fn push_vals(&mut self, origin_count: usize, vector: VectorRef) {
@@ -220,6 +239,8 @@ impl Column {
                 TimeUnit::Microsecond => values.ts_microsecond_values.push(val.value()),
                 TimeUnit::Nanosecond => values.ts_nanosecond_values.push(val.value()),
             },
+            Value::Decimal128(val) => values.decimal128_values.push(val.to_string()),
+            Value::Histogram(val) => values.histogram_values.push(val.into()),
             Value::List(_) => unreachable!(),
         });
         self.null_mask = null_mask.into_vec();
@@ -294,6 +315,14 @@ mod tests {
         let values = Values::with_capacity(ColumnDataType::TimestampMillisecond, 2);
         let values = values.ts_millisecond_values;
         assert_eq!(2, values.capacity());
+
+        let values = Values::with_capacity(ColumnDataType::Decimal128, 2);
+        let values = values.decimal128_values;
+        assert_eq!(2, values.capacity());
+
+        let values = Values::with_capacity(ColumnDataType::Histogram, 2);
+        let values = values.histogram_values;
+        assert_eq!(2, values.capacity());
     }
 
     #[test]
@@ -362,6 +391,14 @@ mod tests {
             ConcreteDataType::timestamp_millisecond_datatype(),
             ColumnDataTypeWrapper(ColumnDataType::TimestampMillisecond).into()
         );
+        assert_eq!(
+            ConcreteDataType::decimal128_default_datatype(),
+            ColumnDataTypeWrapper(ColumnDataType::Decimal128).into()
+        );
+        assert_eq!(
+            ConcreteDataType::histogram_datatype(),
+            ColumnDataTypeWrapper(ColumnDataType::Histogram).into()
+        );
     }
 
     #[test]
@@ -432,6 +469,16 @@ mod tests {
                 .try_into()
                 .unwrap()
         );
+        assert_eq!(
+            ColumnDataTypeWrapper(ColumnDataType::Decimal128),
+            ConcreteDataType::decimal128_datatype(20, 4)
+                .try_into()
+                .unwrap()
+        );
+        assert_eq!(
+            ColumnDataTypeWrapper(ColumnDataType::Histogram),
+            ConcreteDataType::histogram_datatype().try_into().unwrap()
+        );
 
         let result: Result<ColumnDataTypeWrapper> = ConcreteDataType::null_datatype().try_into();
         assert!(result.is_err());
@@ -459,6 +506,7 @@ mod tests {
             }),
             null_mask: vec![],
             datatype: 0,
+            compressed_values: vec![],
         };
 
         let vector = Arc::new(TimestampNanosecondVector::from_vec(vec![1, 2, 3]));
@@ -503,6 +551,7 @@ mod tests {
             }),
             null_mask: vec![2],
             datatype: ColumnDataType::Boolean as i32,
+            compressed_values: vec![],
         };
         let row_count = 4;
 
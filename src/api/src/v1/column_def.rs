@@ -33,6 +33,7 @@ impl ColumnDef {
         };
 
         ColumnSchema::new(&self.name, data_type.into(), self.is_nullable)
+            .with_comment(&self.comment)
             .with_default_constraint(constraint)
             .context(error::InvalidColumnDefaultConstraintSnafu { column: &self.name })
     }
@@ -0,0 +1,181 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `system.ddl_history` table, records DDL operations for auditing.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_catalog::consts::{
+    DDL_HISTORY_TABLE_ID, DDL_HISTORY_TABLE_NAME, DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME,
+};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
+use datatypes::vectors::{BooleanVector, StringVector, TimestampMillisecondVector, VectorRef};
+use snafu::ResultExt;
+use table::requests::{CreateTableRequest, InsertRequest};
+
+use crate::error::{InsertDdlHistorySnafu, Result};
+use crate::helper::DdlHistoryValue;
+use crate::{CatalogManager, CatalogManagerRef, RegisterSystemTableRequest};
+
+/// Registers the `system.ddl_history` table, should be called before starting the
+/// catalog manager.
+pub async fn register_ddl_history_table(catalog_manager: &dyn CatalogManager) -> Result<()> {
+    let request = CreateTableRequest {
+        id: DDL_HISTORY_TABLE_ID,
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: SYSTEM_SCHEMA_NAME.to_string(),
+        table_name: DDL_HISTORY_TABLE_NAME.to_string(),
+        desc: Some("DDL history table".to_string()),
+        schema: Arc::new(build_ddl_history_schema()),
+        region_numbers: vec![0],
+        // Operations in the same millisecond are distinguished by their unique ids.
+        primary_key_indices: vec![3, 2, 7],
+        create_if_not_exists: true,
+        table_options: HashMap::default(),
+    };
+
+    catalog_manager
+        .register_system_table(RegisterSystemTableRequest {
+            create_table_request: request,
+            open_hook: None,
+        })
+        .await
+}
+
+/// Inserts a DDL operation into the `system.ddl_history` table, does nothing if the
+/// table is not registered.
+pub async fn insert_ddl_history(
+    catalog_manager: &CatalogManagerRef,
+    value: &DdlHistoryValue,
+) -> Result<()> {
+    let Some(table) = catalog_manager.table(
+        DEFAULT_CATALOG_NAME,
+        SYSTEM_SCHEMA_NAME,
+        DDL_HISTORY_TABLE_NAME,
+    )? else {
+        return Ok(());
+    };
+
+    let schema = build_ddl_history_schema();
+    let columns_values = schema
+        .column_schemas()
+        .iter()
+        .map(|column_schema| column_schema.name.clone())
+        .zip(ddl_history_to_columns(std::slice::from_ref(value)))
+        .collect();
+
+    let _ = table
+        .insert(InsertRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: SYSTEM_SCHEMA_NAME.to_string(),
+            table_name: DDL_HISTORY_TABLE_NAME.to_string(),
+            columns_values,
+        })
+        .await
+        .context(InsertDdlHistorySnafu)?;
+    Ok(())
+}
+
+/// Converts the DDL operations to columns of the `system.ddl_history` table.
+pub fn ddl_history_to_columns(values: &[DdlHistoryValue]) -> Vec<VectorRef> {
+    vec![
+        Arc::new(TimestampMillisecondVector::from_vec(
+            values.iter().map(|v| v.timestamp_millis).collect(),
+        )),
+        Arc::new(StringVector::from(
+            values
+                .iter()
+                .map(|v| v.username.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            values
+                .iter()
+                .map(|v| v.operation.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            values
+                .iter()
+                .map(|v| v.object_name.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            values
+                .iter()
+                .map(|v| v.statement.as_deref())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(BooleanVector::from(
+            values.iter().map(|v| v.success).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            values
+                .iter()
+                .map(|v| v.error.as_deref())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            values.iter().map(|v| v.id.as_str()).collect::<Vec<_>>(),
+        )),
+    ]
+}
+
+/// Returns the schema of the `system.ddl_history` table.
+pub fn build_ddl_history_schema() -> Schema {
+    let cols = vec![
+        ColumnSchema::new(
+            "ts".to_string(),
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new(
+            "username".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "operation".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "object_name".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "statement".to_string(),
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            "success".to_string(),
+            ConcreteDataType::boolean_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "error".to_string(),
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new("id".to_string(), ConcreteDataType::string_datatype(), false),
+    ];
+
+    // Schema is always valid here
+    SchemaBuilder::try_from(cols).unwrap().build().unwrap()
+}
@@ -114,6 +114,23 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Table {} is still being opened in background, retry later",
+        table_name
+    ))]
+    TableOpening {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Table {} failed to open in background: {}", table_name, msg))]
+    OpenTableInBackground {
+        table_name: String,
+        msg: String,
+        status_code: StatusCode,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to open table, table info: {}, source: {}", table_info, source))]
     OpenTable {
         table_info: String,
@@ -142,6 +159,12 @@ pub enum Error {
         source: table::error::Error,
     },
 
+    #[snafu(display("Failed to insert DDL history, source: {}", source))]
+    InsertDdlHistory {
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
     #[snafu(display(
         "Failed to deregister table, request: {:?}, source: {}",
         request,
@@ -153,6 +176,32 @@ pub enum Error {
         source: table::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to deregister schema {}.{}, source: {}",
+        catalog,
+        schema,
+        source
+    ))]
+    DeregisterSchema {
+        catalog: String,
+        schema: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to move table {} to schema {}, source: {}",
+        table_info,
+        schema,
+        source
+    ))]
+    MoveTable {
+        table_info: String,
+        schema: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
     #[snafu(display("Illegal catalog manager state: {}", msg))]
     IllegalManagerState { backtrace: Backtrace, msg: String },
 
@@ -233,9 +282,12 @@ impl ErrorExt for Error {
             Error::OpenSystemCatalog { source, .. }
             | Error::CreateSystemCatalog { source, .. }
             | Error::InsertCatalogRecord { source, .. }
+            | Error::InsertDdlHistory { source, .. }
             | Error::OpenTable { source, .. }
             | Error::CreateTable { source, .. }
-            | Error::DeregisterTable { source, .. } => source.status_code(),
+            | Error::MoveTable { source, .. }
+            | Error::DeregisterTable { source, .. }
+            | Error::DeregisterSchema { source, .. } => source.status_code(),
 
             Error::MetaSrv { source, .. } => source.status_code(),
             Error::SystemCatalogTableScan { source } => source.status_code(),
@@ -245,6 +297,9 @@ impl ErrorExt for Error {
             Error::SchemaProviderOperation { source } => source.status_code(),
 
             Error::Unimplemented { .. } => StatusCode::Unsupported,
+
+            Error::TableOpening { .. } => StatusCode::StorageUnavailable,
+            Error::OpenTableInBackground { status_code, .. } => *status_code,
         }
     }
 
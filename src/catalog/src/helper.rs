@@ -23,11 +23,13 @@ use regex::Regex;
 use serde::{Deserialize, Serialize, Serializer};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::{RawTableInfo, TableId, TableVersion};
+use uuid::Uuid;
 
 const CATALOG_KEY_PREFIX: &str = "__c";
 const SCHEMA_KEY_PREFIX: &str = "__s";
 const TABLE_GLOBAL_KEY_PREFIX: &str = "__tg";
 const TABLE_REGIONAL_KEY_PREFIX: &str = "__tr";
+const DDL_HISTORY_KEY_PREFIX: &str = "__ddl";
 
 const ALPHANUMERICS_NAME_PATTERN: &str = "[a-zA-Z_][a-zA-Z0-9_]*";
 
@@ -78,6 +80,10 @@ pub fn build_table_global_prefix(
     )
 }
 
+pub fn build_ddl_history_prefix() -> String {
+    format!("{DDL_HISTORY_KEY_PREFIX}-")
+}
+
 pub fn build_table_regional_prefix(
     catalog_name: impl AsRef<str>,
     schema_name: impl AsRef<str>,
@@ -250,8 +256,89 @@ impl SchemaKey {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SchemaValue;
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaValue {
+    /// Default options of tables created in the schema.
+    #[serde(default)]
+    pub default_table_options: HashMap<String, String>,
+    /// Key-value metadata of the schema, e.g. owner and description.
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl SchemaValue {
+    pub fn parse(s: impl AsRef<str>) -> Result<Self, Error> {
+        // Schema values were `null` before default table options are supported.
+        serde_json::from_str::<Option<Self>>(s.as_ref())
+            .map(Option::unwrap_or_default)
+            .context(DeserializeCatalogEntryValueSnafu { raw: s.as_ref() })
+    }
+
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        Self::parse(String::from_utf8_lossy(bytes.as_ref()))
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_string(self)
+            .context(SerializeCatalogEntryValueSnafu)?
+            .into_bytes())
+    }
+}
+
+/// Key of a DDL operation in the DDL history, ordered by the time of the operation.
+pub struct DdlHistoryKey {
+    pub timestamp_millis: i64,
+    /// Unique id of the operation, so operations in the same millisecond don't overwrite
+    /// each other.
+    pub id: String,
+    /// Full name of the object (database or table) the operation works on.
+    pub object_name: String,
+}
+
+impl Display for DdlHistoryKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(DDL_HISTORY_KEY_PREFIX)?;
+        f.write_str("-")?;
+        // Pads the timestamp so keys are ordered by time.
+        write!(f, "{:020}", self.timestamp_millis)?;
+        f.write_str("-")?;
+        f.write_str(&self.id)?;
+        f.write_str("-")?;
+        f.write_str(&self.object_name)
+    }
+}
+
+/// A DDL operation in the DDL history: who did what, when and the result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DdlHistoryValue {
+    pub timestamp_millis: i64,
+    /// Unique id of the operation, generated by [DdlHistoryValue::new_id].
+    pub id: String,
+    pub username: String,
+    /// Kind of the operation, e.g. `CREATE TABLE`.
+    pub operation: String,
+    pub object_name: String,
+    /// SQL of the operation, `None` if the operation is not issued by SQL, e.g. by gRPC.
+    pub statement: Option<String>,
+    pub success: bool,
+    /// Error message if the operation failed.
+    pub error: Option<String>,
+}
+
+impl DdlHistoryValue {
+    /// Generates a unique id for a DDL operation.
+    pub fn new_id() -> String {
+        Uuid::new_v4().to_string()
+    }
+
+    pub fn key(&self) -> DdlHistoryKey {
+        DdlHistoryKey {
+            timestamp_millis: self.timestamp_millis,
+            id: self.id.clone(),
+            object_name: self.object_name.clone(),
+        }
+    }
+}
 
 macro_rules! define_catalog_value {
     ( $($val_ty: ty), *) => {
@@ -280,7 +367,7 @@ define_catalog_value!(
     TableRegionalValue,
     TableGlobalValue,
     CatalogValue,
-    SchemaValue
+    DdlHistoryValue
 );
 
 #[cfg(test)]
@@ -318,6 +405,52 @@ mod tests {
         assert_eq!(key, &entry.to_string());
     }
 
+    #[test]
+    fn test_schema_value() {
+        let value = SchemaValue {
+            default_table_options: HashMap::from([("ttl".to_string(), "30d".to_string())]),
+            metadata: HashMap::from([("owner".to_string(), "alice".to_string())]),
+        };
+        let bytes = value.as_bytes().unwrap();
+        assert_eq!(value, SchemaValue::from_bytes(bytes).unwrap());
+
+        assert_eq!(SchemaValue::default(), SchemaValue::parse("null").unwrap());
+        assert_eq!(SchemaValue::default(), SchemaValue::parse("{}").unwrap());
+    }
+
+    #[test]
+    fn test_ddl_history_key_value() {
+        let value = DdlHistoryValue {
+            timestamp_millis: 1024,
+            id: "5f6c7d8e-0000-4000-8000-000000000001".to_string(),
+            username: "greptime".to_string(),
+            operation: "DROP TABLE".to_string(),
+            object_name: "greptime.public.demo".to_string(),
+            statement: Some("DROP TABLE demo".to_string()),
+            success: true,
+            error: None,
+        };
+        assert_eq!(
+            "__ddl-00000000000000001024-5f6c7d8e-0000-4000-8000-000000000001-greptime.public.demo",
+            value.key().to_string()
+        );
+
+        // Operations on the same object in the same millisecond have different keys.
+        let another = DdlHistoryValue {
+            id: DdlHistoryValue::new_id(),
+            ..value.clone()
+        };
+        assert_ne!(value.key().to_string(), another.key().to_string());
+        assert_ne!(DdlHistoryValue::new_id(), DdlHistoryValue::new_id());
+        assert!(value
+            .key()
+            .to_string()
+            .starts_with(&build_ddl_history_prefix()));
+
+        let bytes = value.as_bytes().unwrap();
+        assert_eq!(value, DdlHistoryValue::from_bytes(bytes).unwrap());
+    }
+
     #[test]
     fn test_build_prefix() {
         assert_eq!("__c-", build_catalog_prefix());
@@ -346,6 +479,8 @@ mod tests {
             value_indices: vec![2, 3],
             options: Default::default(),
             region_numbers: vec![1],
+            read_only: false,
+            column_statistics: None,
         };
 
         let table_info = RawTableInfo {
@@ -0,0 +1,401 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-table ingestion statistics, the `ingest_stats` table in system catalog exposes
+//! rows/bytes written to each table in total and in several rolling windows.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use async_stream::stream;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_NAME};
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::RecordBatch;
+use common_time::util;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef};
+use table::metadata::TableInfoRef;
+use table::table::scan::SimpleTableScan;
+use table::Table;
+
+use crate::error::Result;
+use crate::tables::TablesRecordBatchStream;
+use crate::CatalogManager;
+
+pub const INGEST_STATS_TABLE_NAME: &str = "ingest_stats";
+
+/// Width of the buckets backing the rolling windows, in seconds.
+const BUCKET_SECS: i64 = 10;
+
+/// Rolling windows of the statistics, as (column suffix, length in seconds).
+pub const INGEST_WINDOWS: [(&str, i64); 3] = [("1m", 60), ("5m", 300), ("1h", 3600)];
+
+const MAX_WINDOW_BUCKETS: i64 = INGEST_WINDOWS[INGEST_WINDOWS.len() - 1].1 / BUCKET_SECS;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IngestCounter {
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+impl IngestCounter {
+    fn add(&mut self, rows: u64, bytes: u64) {
+        self.rows += rows;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Default)]
+struct TableIngest {
+    total: IngestCounter,
+    /// Counters of recent buckets, ordered by bucket index.
+    buckets: VecDeque<(i64, IngestCounter)>,
+    last_write_millis: i64,
+}
+
+impl TableIngest {
+    fn record(&mut self, rows: u64, bytes: u64, now_millis: i64) {
+        self.total.add(rows, bytes);
+        self.last_write_millis = now_millis;
+
+        let bucket = bucket_of(now_millis);
+        match self.buckets.back_mut() {
+            Some((index, counter)) if *index == bucket => counter.add(rows, bytes),
+            _ => self
+                .buckets
+                .push_back((bucket, IngestCounter { rows, bytes })),
+        }
+        self.evict(bucket);
+    }
+
+    fn evict(&mut self, current_bucket: i64) {
+        while let Some((index, _)) = self.buckets.front() {
+            if *index > current_bucket - MAX_WINDOW_BUCKETS {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Sums the counters of buckets within the last `window_secs` seconds.
+    fn window(&self, window_secs: i64, now_millis: i64) -> IngestCounter {
+        let oldest = bucket_of(now_millis) - window_secs / BUCKET_SECS;
+        let mut counter = IngestCounter::default();
+        for (_, c) in self.buckets.iter().rev().take_while(|(i, _)| *i > oldest) {
+            counter.add(c.rows, c.bytes);
+        }
+        counter
+    }
+}
+
+fn bucket_of(millis: i64) -> i64 {
+    millis.div_euclid(BUCKET_SECS * 1000)
+}
+
+/// Ingestion statistics of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableIngestStats {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub total: IngestCounter,
+    /// Counters of [INGEST_WINDOWS], in the same order.
+    pub windows: [IngestCounter; INGEST_WINDOWS.len()],
+    pub last_write_millis: i64,
+}
+
+/// Keeps rows/bytes written to each table since the process started.
+#[derive(Debug, Default)]
+pub struct IngestStats {
+    tables: Mutex<HashMap<(String, String, String), TableIngest>>,
+}
+
+pub type IngestStatsRef = Arc<IngestStats>;
+
+impl IngestStats {
+    pub fn record(&self, catalog: &str, schema: &str, table: &str, rows: u64, bytes: u64) {
+        self.record_at(
+            catalog,
+            schema,
+            table,
+            rows,
+            bytes,
+            util::current_time_millis(),
+        )
+    }
+
+    fn record_at(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        rows: u64,
+        bytes: u64,
+        now_millis: i64,
+    ) {
+        let key = (catalog.to_string(), schema.to_string(), table.to_string());
+        self.tables
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .record(rows, bytes, now_millis);
+    }
+
+    /// Forgets the statistics of a table, e.g. after it is dropped.
+    pub fn remove(&self, catalog: &str, schema: &str, table: &str) {
+        let key = (catalog.to_string(), schema.to_string(), table.to_string());
+        let _ = self.tables.lock().unwrap().remove(&key);
+    }
+
+    /// Returns statistics of all tables, ordered by table name.
+    pub fn snapshot(&self) -> Vec<TableIngestStats> {
+        self.snapshot_at(util::current_time_millis())
+    }
+
+    fn snapshot_at(&self, now_millis: i64) -> Vec<TableIngestStats> {
+        let tables = self.tables.lock().unwrap();
+        let mut stats = tables
+            .iter()
+            .map(|((catalog, schema, table), ingest)| TableIngestStats {
+                catalog_name: catalog.clone(),
+                schema_name: schema.clone(),
+                table_name: table.clone(),
+                total: ingest.total,
+                windows: INGEST_WINDOWS.map(|(_, secs)| ingest.window(secs, now_millis)),
+                last_write_millis: ingest.last_write_millis,
+            })
+            .collect::<Vec<_>>();
+        stats.sort_unstable_by(|a, b| {
+            (&a.catalog_name, &a.schema_name, &a.table_name).cmp(&(
+                &b.catalog_name,
+                &b.schema_name,
+                &b.table_name,
+            ))
+        });
+        stats
+    }
+}
+
+/// Registers the `ingest_stats` table to `system.information_schema`, does nothing if
+/// the system catalog is absent, e.g. in distributed mode.
+pub fn register_ingest_stats_table(
+    catalog_manager: &dyn CatalogManager,
+    stats: IngestStatsRef,
+) -> Result<()> {
+    let Some(schema) = catalog_manager
+        .catalog(SYSTEM_CATALOG_NAME)?
+        .map(|catalog| catalog.schema(INFORMATION_SCHEMA_NAME))
+        .transpose()?
+        .flatten() else {
+        return Ok(());
+    };
+    if !schema.table_exist(INGEST_STATS_TABLE_NAME)? {
+        let _ = schema.register_table(
+            INGEST_STATS_TABLE_NAME.to_string(),
+            Arc::new(IngestStatsTable::new(stats)),
+        )?;
+    }
+    Ok(())
+}
+
+/// A virtual table that exposes [IngestStats].
+pub struct IngestStatsTable {
+    schema: SchemaRef,
+    stats: IngestStatsRef,
+}
+
+impl IngestStatsTable {
+    pub fn new(stats: IngestStatsRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_ingest_stats()),
+            stats,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for IngestStatsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("IngestStatsTable does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let schema_ref = self.schema.clone();
+        let columns = ingest_stats_to_record_batch(self.stats.snapshot());
+
+        let stream = stream!({
+            yield RecordBatch::new(schema_ref, columns);
+        });
+
+        let stream = Box::pin(TablesRecordBatchStream::new(
+            self.schema.clone(),
+            Box::pin(stream),
+        ));
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+}
+
+fn ingest_stats_to_record_batch(stats: Vec<TableIngestStats>) -> Vec<VectorRef> {
+    let strings = |f: fn(&TableIngestStats) -> &str| -> VectorRef {
+        Arc::new(StringVector::from(stats.iter().map(f).collect::<Vec<_>>()))
+    };
+    let counters = |f: &dyn Fn(&TableIngestStats) -> u64| -> VectorRef {
+        Arc::new(UInt64Vector::from_vec(stats.iter().map(f).collect()))
+    };
+
+    let mut columns = vec![
+        strings(|s| s.catalog_name.as_str()),
+        strings(|s| s.schema_name.as_str()),
+        strings(|s| s.table_name.as_str()),
+        counters(&|s| s.total.rows),
+        counters(&|s| s.total.bytes),
+    ];
+    for i in 0..INGEST_WINDOWS.len() {
+        columns.push(counters(&|s| s.windows[i].rows));
+        columns.push(counters(&|s| s.windows[i].bytes));
+    }
+    columns.push(Arc::new(TimestampMillisecondVector::from_vec(
+        stats.iter().map(|s| s.last_write_millis).collect(),
+    )));
+    columns
+}
+
+fn build_schema_for_ingest_stats() -> Schema {
+    let mut cols = vec![
+        ColumnSchema::new("catalog", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("table_name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("rows_total", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("bytes_total", ConcreteDataType::uint64_datatype(), false),
+    ];
+    for (suffix, _) in INGEST_WINDOWS {
+        cols.push(ColumnSchema::new(
+            format!("rows_{suffix}"),
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ));
+        cols.push(ColumnSchema::new(
+            format!("bytes_{suffix}"),
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ));
+    }
+    cols.push(ColumnSchema::new(
+        "last_write",
+        ConcreteDataType::timestamp_millisecond_datatype(),
+        false,
+    ));
+    Schema::new(cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::SessionContext;
+    use datatypes::value::Value;
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn test_rolling_windows() {
+        let stats = IngestStats::default();
+        let now = 10_000_000;
+        stats.record_at("greptime", "public", "cpu", 10, 100, now - 3_000_000);
+        stats.record_at("greptime", "public", "cpu", 5, 50, now - 120_000);
+        stats.record_at("greptime", "public", "cpu", 1, 10, now - 1_000);
+        stats.record_at("greptime", "public", "cpu", 2, 20, now);
+        stats.record_at("greptime", "public", "mem", 3, 30, now);
+
+        let snapshot = stats.snapshot_at(now);
+        assert_eq!(2, snapshot.len());
+        let cpu = &snapshot[0];
+        assert_eq!("cpu", cpu.table_name);
+        assert_eq!(
+            IngestCounter {
+                rows: 18,
+                bytes: 180
+            },
+            cpu.total
+        );
+        assert_eq!(IngestCounter { rows: 3, bytes: 30 }, cpu.windows[0]);
+        assert_eq!(IngestCounter { rows: 8, bytes: 80 }, cpu.windows[1]);
+        assert_eq!(
+            IngestCounter {
+                rows: 18,
+                bytes: 180
+            },
+            cpu.windows[2]
+        );
+        assert_eq!(now, cpu.last_write_millis);
+        assert_eq!("mem", snapshot[1].table_name);
+
+        // Old buckets are evicted but the total is kept.
+        let later = now + 7_200_000;
+        stats.record_at("greptime", "public", "cpu", 1, 1, later);
+        let cpu = &stats.snapshot_at(later)[0];
+        assert_eq!(
+            IngestCounter {
+                rows: 19,
+                bytes: 181
+            },
+            cpu.total
+        );
+        assert_eq!(IngestCounter { rows: 1, bytes: 1 }, cpu.windows[2]);
+
+        stats.remove("greptime", "public", "cpu");
+        assert_eq!(1, stats.snapshot_at(later).len());
+    }
+
+    #[tokio::test]
+    async fn test_ingest_stats_table() {
+        let stats = Arc::new(IngestStats::default());
+        stats.record("greptime", "public", "cpu", 2, 16);
+
+        let table = IngestStatsTable::new(stats);
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let session_ctx = SessionContext::new();
+        let mut stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert_eq!(12, batch.num_columns());
+        assert_eq!(
+            "cpu",
+            batch.column(2).get_ref(0).as_string().unwrap().unwrap()
+        );
+        assert_eq!(Value::UInt64(16), batch.column(4).get(0));
+        assert_eq!(Value::UInt64(2), batch.column(5).get(0));
+    }
+}
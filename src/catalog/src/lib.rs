@@ -15,23 +15,40 @@
 #![feature(assert_matches)]
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
+use api::v1::meta::{RegionStat, TableName};
+use common_error::ext::ErrorExt;
+use common_error::prelude::StatusCode;
 use common_telemetry::info;
+use futures_util::StreamExt;
 use snafu::{OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
-use table::requests::CreateTableRequest;
+use table::requests::{AlterDatabaseKind, CreateTableRequest};
+use table::table::TableStatistics;
 use table::TableRef;
 
-use crate::error::{CreateTableSnafu, Result};
+use crate::error::{
+    CatalogNotFoundSnafu, CreateTableSnafu, OpenTableInBackgroundSnafu, Result,
+    SchemaNotFoundSnafu, TableOpeningSnafu,
+};
+use crate::remote::{paginate_names, Page};
 pub use crate::schema::{SchemaProvider, SchemaProviderRef};
 
+pub mod ddl_history;
 pub mod error;
 pub mod helper;
+pub mod ingest_stats;
 pub mod local;
+pub mod pg_catalog;
+pub mod query_history;
 pub mod remote;
+pub mod resource_usage;
 pub mod schema;
 pub mod system;
 pub mod tables;
@@ -93,10 +110,17 @@ pub trait CatalogManager: CatalogList {
     /// returns whether the table deregistered.
     async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<bool>;
 
+    /// Creates a catalog with a default schema, returns whether the catalog is created,
+    /// `false` if the catalog already exists.
+    async fn create_catalog(&self, request: CreateCatalogRequest) -> Result<bool>;
+
     /// Register a schema with catalog name and schema name. Retuens whether the
     /// schema registered.
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool>;
 
+    /// Alters a schema, returns whether the schema is altered.
+    async fn alter_schema(&self, request: AlterSchemaRequest) -> Result<bool>;
+
     /// Rename a table to [RenameTableRequest::new_table_name], returns whether the table is renamed.
     async fn rename_table(&self, request: RenameTableRequest) -> Result<bool>;
 
@@ -108,6 +132,67 @@ pub trait CatalogManager: CatalogList {
 
     /// Returns the table by catalog, schema and table name.
     fn table(&self, catalog: &str, schema: &str, table_name: &str) -> Result<Option<TableRef>>;
+
+    /// Returns error if the table is still being opened in background on startup, or failed
+    /// to open there, as such tables are not registered. DDL must check it before creating a
+    /// table of the name.
+    fn ensure_table_opened(&self, _catalog: &str, _schema: &str, _table_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Lists at most `limit` schemas of the catalog in order, from the `token` returned by
+    /// the previous page, or from the first schema if `token` is `None`.
+    ///
+    /// Default implementation pages the schema names of the catalog provider, managers
+    /// reading schemas from a remote backend should read only one page from it.
+    async fn list_schemas(
+        &self,
+        catalog: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<String>> {
+        let names = self
+            .catalog(catalog)?
+            .context(CatalogNotFoundSnafu {
+                catalog_name: catalog,
+            })?
+            .schema_names()?;
+        Ok(paginate_names(names, token, limit))
+    }
+
+    /// Lists at most `limit` tables of the schema in order, from the `token` returned by
+    /// the previous page, or from the first table if `token` is `None`.
+    ///
+    /// Default implementation pages the table names of the schema provider, managers
+    /// reading tables from a remote backend should read only one page from it.
+    async fn list_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<String>> {
+        let names = self
+            .schema(catalog, schema)?
+            .context(SchemaNotFoundSnafu { catalog, schema })?
+            .table_names()?;
+        Ok(paginate_names(names, token, limit))
+    }
+
+    /// Returns the statistics of the tables in the schema keyed by table name, or `None` if
+    /// they should be collected from each table by [Table::collect_statistics].
+    ///
+    /// Managers whose tables' statistics are reported to a remote backend should read them
+    /// all at once here, rather than once per table.
+    ///
+    /// [Table::collect_statistics]: table::Table::collect_statistics
+    async fn schema_statistics(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+    ) -> Result<Option<HashMap<String, TableStatistics>>> {
+        Ok(None)
+    }
 }
 
 pub type CatalogManagerRef = Arc<dyn CatalogManager>;
@@ -161,10 +246,24 @@ pub struct DeregisterTableRequest {
     pub table_name: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct AlterSchemaRequest {
+    pub catalog: String,
+    pub schema: String,
+    pub alter_kind: AlterDatabaseKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCatalogRequest {
+    pub catalog: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct RegisterSchemaRequest {
     pub catalog: String,
     pub schema: String,
+    /// Default options of tables created in the schema.
+    pub default_table_options: HashMap<String, String>,
 }
 
 /// Formats table fully-qualified name
@@ -172,6 +271,55 @@ pub fn format_full_table_name(catalog: &str, schema: &str, table: &str) -> Strin
     format!("{catalog}.{schema}.{table}")
 }
 
+/// Tables opened in background on startup, keyed by full table name. A table is removed once
+/// opened, or keeps the error if it fails to open.
+#[derive(Default)]
+pub struct BackgroundOpenTables {
+    tables: RwLock<HashMap<String, Option<(StatusCode, String)>>>,
+}
+
+impl BackgroundOpenTables {
+    /// Marks the tables as being opened.
+    pub fn start(&self, table_names: impl IntoIterator<Item = String>) {
+        let mut tables = self.tables.write().unwrap();
+        for table_name in table_names {
+            let _ = tables.insert(table_name, None);
+        }
+    }
+
+    /// Records the result of opening the table.
+    pub fn finish(&self, table_name: &str, result: &Result<()>) {
+        let mut tables = self.tables.write().unwrap();
+        match result {
+            Ok(()) => {
+                let _ = tables.remove(table_name);
+            }
+            Err(e) => {
+                let _ = tables.insert(
+                    table_name.to_string(),
+                    Some((e.status_code(), e.to_string())),
+                );
+            }
+        }
+    }
+
+    /// See [CatalogManager::ensure_table_opened].
+    pub fn ensure_opened(&self, catalog: &str, schema: &str, table_name: &str) -> Result<()> {
+        let table_name = format_full_table_name(catalog, schema, table_name);
+        let tables = self.tables.read().unwrap();
+        match tables.get(&table_name) {
+            None => Ok(()),
+            Some(None) => TableOpeningSnafu { table_name }.fail(),
+            Some(Some((status_code, msg))) => OpenTableInBackgroundSnafu {
+                table_name,
+                msg,
+                status_code: *status_code,
+            }
+            .fail(),
+        }
+    }
+}
+
 pub trait CatalogProviderFactory {
     fn create(&self, catalog_name: String) -> CatalogProviderRef;
 }
@@ -221,10 +369,146 @@ pub(crate) async fn handle_system_table_request<'a, M: CatalogManager>(
     Ok(())
 }
 
+/// Tells whether the region of the table, given by table id and region number, is written
+/// recently. Tables with recently written regions are opened first on startup.
+pub type HotRegionCheckerRef = Arc<dyn Fn(TableId, u32) -> bool + Send + Sync>;
+
+/// Default number of tables opened concurrently on startup, opening a table replays the WAL
+/// of its regions.
+pub const DEFAULT_OPEN_TABLES_CONCURRENCY: usize = 16;
+
+/// Logs the progress of opening tables every this number of tables.
+const OPEN_TABLES_PROGRESS_INTERVAL: usize = 100;
+
+/// Opens `tables` by `open` with at most `concurrency` tables opening at the same time and
+/// logs the progress. Returns the results of `open` in the order tables are opened.
+pub(crate) async fn open_tables_concurrently<T, R, F, Fut>(
+    tables: Vec<T>,
+    concurrency: usize,
+    open: F,
+) -> Result<Vec<R>>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let total = tables.len();
+    let start = Instant::now();
+    let mut opened = Vec::with_capacity(total);
+    let mut results =
+        futures::stream::iter(tables.into_iter().map(open)).buffer_unordered(concurrency.max(1));
+    while let Some(result) = results.next().await {
+        opened.push(result?);
+        if opened.len() % OPEN_TABLES_PROGRESS_INTERVAL == 0 || opened.len() == total {
+            info!(
+                "Opened {}/{} tables, elapsed: {:?}",
+                opened.len(),
+                total,
+                start.elapsed()
+            );
+        }
+    }
+    Ok(opened)
+}
+
 /// The number of regions in the datanode node.
 pub fn region_number(catalog_manager: &CatalogManagerRef) -> Result<u64> {
     let mut region_number: u64 = 0;
+    visit_tables(catalog_manager, |_, _, _, table| {
+        let region_numbers = &table.table_info().meta.region_numbers;
+        region_number += region_numbers.len() as u64;
+    })?;
+
+    Ok(region_number)
+}
+
+/// The statistics of regions in the datanode node.
+pub fn region_stats(catalog_manager: &CatalogManagerRef) -> Result<Vec<RegionStat>> {
+    let mut region_stats = vec![];
+    visit_tables(
+        catalog_manager,
+        |catalog_name, schema_name, table_name, table| {
+            region_stats.extend(table.region_stats().into_iter().map(|stat| RegionStat {
+                region_id: stat.region_id,
+                table_name: Some(TableName {
+                    catalog_name: catalog_name.to_string(),
+                    schema_name: schema_name.to_string(),
+                    table_name: table_name.to_string(),
+                }),
+                approximate_bytes: (stat.memtable_bytes + stat.sst_bytes) as i64,
+                approximate_rows: stat.approximate_rows as i64,
+                memtable_bytes: stat.memtable_bytes as i64,
+                sst_bytes: stat.sst_bytes as i64,
+                written_rows: stat.written_rows as i64,
+                last_write_millis: stat.last_write_millis.unwrap_or_default(),
+                corrupted_files: stat.corrupted_files,
+                ..Default::default()
+            }));
+        },
+    )?;
+
+    Ok(region_stats)
+}
+
+/// Storage statistics of a table in the datanode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableStorageStat {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    /// Number of regions of the table, including the regions not opened.
+    pub regions: usize,
+    /// Number of regions without statistics, e.g. regions never opened since the datanode
+    /// starts, their bytes and files are unknown.
+    pub unknown_regions: usize,
+    pub memtable_bytes: u64,
+    pub sst_bytes: u64,
+    pub sst_files: u64,
+}
+
+/// The storage statistics of tables in the datanode, regions without statistics are not
+/// counted in the bytes and files. Virtual tables are skipped.
+pub fn table_storage_stats(catalog_manager: &CatalogManagerRef) -> Result<Vec<TableStorageStat>> {
+    let mut table_stats = vec![];
+    visit_tables(
+        catalog_manager,
+        |catalog_name, schema_name, table_name, table| {
+            let Some(table_info) = table.try_table_info() else {
+                return;
+            };
+            let regions = table_info.meta.region_numbers.len();
+            let region_stats = table.region_stats();
+            let mut table_stat = TableStorageStat {
+                catalog_name: catalog_name.to_string(),
+                schema_name: schema_name.to_string(),
+                table_name: table_name.to_string(),
+                regions,
+                unknown_regions: regions.saturating_sub(region_stats.len()),
+                ..Default::default()
+            };
+            for stat in region_stats {
+                table_stat.memtable_bytes += stat.memtable_bytes;
+                table_stat.sst_bytes += stat.sst_bytes;
+                table_stat.sst_files += stat.sst_files;
+            }
+            table_stats.push(table_stat);
+        },
+    )?;
 
+    Ok(table_stats)
+}
+
+/// All tables in the catalog manager.
+pub fn all_tables(catalog_manager: &CatalogManagerRef) -> Result<Vec<TableRef>> {
+    let mut tables = vec![];
+    visit_tables(catalog_manager, |_, _, _, table| tables.push(table.clone()))?;
+    Ok(tables)
+}
+
+/// Calls `f` with the catalog, schema and table name of each table in the catalog manager.
+fn visit_tables(
+    catalog_manager: &CatalogManagerRef,
+    mut f: impl FnMut(&str, &str, &str, &TableRef),
+) -> Result<()> {
     for catalog_name in catalog_manager.catalog_names()? {
         let catalog =
             catalog_manager
@@ -248,10 +532,74 @@ pub fn region_number(catalog_manager: &CatalogManagerRef) -> Result<u64> {
                         table_info: &table_name,
                     })?;
 
-                let region_numbers = &table.table_info().meta.region_numbers;
-                region_number += region_numbers.len() as u64;
+                f(&catalog_name, &schema_name, &table_name, &table);
             }
         }
     }
-    Ok(region_number)
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::error::{Error, UnimplementedSnafu};
+
+    #[tokio::test]
+    async fn test_open_tables_concurrently() {
+        let opening = AtomicUsize::new(0);
+        let max_opening = AtomicUsize::new(0);
+        let open = |i: usize| {
+            let opening = &opening;
+            let max_opening = &max_opening;
+            async move {
+                let n = opening.fetch_add(1, Ordering::Relaxed) + 1;
+                max_opening.fetch_max(n, Ordering::Relaxed);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                opening.fetch_sub(1, Ordering::Relaxed);
+                Ok::<_, Error>(i)
+            }
+        };
+
+        let mut opened = open_tables_concurrently((0..20).collect(), 4, open)
+            .await
+            .unwrap();
+        opened.sort();
+        assert_eq!((0..20).collect::<Vec<_>>(), opened);
+        assert_eq!(4, max_opening.load(Ordering::Relaxed));
+
+        let result = open_tables_concurrently((0..20).collect(), 4, |i: usize| async move {
+            if i == 10 {
+                UnimplementedSnafu { operation: "open" }.fail()
+            } else {
+                Ok(i)
+            }
+        })
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_background_open_tables() {
+        let tables = BackgroundOpenTables::default();
+        tables.start([
+            "greptime.public.a".to_string(),
+            "greptime.public.b".to_string(),
+        ]);
+        let err = tables.ensure_opened("greptime", "public", "a").unwrap_err();
+        assert_eq!(StatusCode::StorageUnavailable, err.status_code());
+        assert!(tables.ensure_opened("greptime", "public", "c").is_ok());
+
+        tables.finish("greptime.public.a", &Ok(()));
+        assert!(tables.ensure_opened("greptime", "public", "a").is_ok());
+        tables.finish(
+            "greptime.public.b",
+            &UnimplementedSnafu { operation: "open" }.fail(),
+        );
+        let err = tables.ensure_opened("greptime", "public", "b").unwrap_err();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+    }
 }
@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use common_catalog::consts::{
     DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, MIN_USER_TABLE_ID,
-    SYSTEM_CATALOG_NAME, SYSTEM_CATALOG_TABLE_NAME,
+    PG_CATALOG_NAME, SYSTEM_CATALOG_NAME, SYSTEM_CATALOG_TABLE_NAME, SYSTEM_SCHEMA_NAME,
 };
 use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::{error, info};
@@ -28,27 +29,30 @@ use futures_util::lock::Mutex;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
-use table::requests::OpenTableRequest;
+use table::requests::{AlterDatabaseKind, AlterKind, AlterTableRequest, OpenTableRequest};
 use table::table::numbers::NumbersTable;
 use table::table::TableIdProvider;
 use table::TableRef;
 
 use crate::error::{
-    self, CatalogNotFoundSnafu, IllegalManagerStateSnafu, OpenTableSnafu, ReadSystemCatalogSnafu,
-    Result, SchemaExistsSnafu, SchemaNotFoundSnafu, SystemCatalogSnafu,
+    self, CatalogNotFoundSnafu, IllegalManagerStateSnafu, MoveTableSnafu, OpenTableSnafu,
+    ReadSystemCatalogSnafu, Result, SchemaExistsSnafu, SchemaNotFoundSnafu, SystemCatalogSnafu,
     SystemCatalogTypeMismatchSnafu, TableExistsSnafu, TableNotFoundSnafu,
 };
-use crate::local::memory::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
+use crate::local::memory::{
+    alter_memory_schema, MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider,
+};
 use crate::system::{
-    decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ENTRY_TYPE_INDEX, KEY_INDEX,
-    VALUE_INDEX,
+    decode_system_catalog, Entry, SchemaEntryValue, SystemCatalogTable, TableEntry,
+    ENTRY_TYPE_INDEX, KEY_INDEX, VALUE_INDEX,
 };
 use crate::tables::SystemCatalog;
 use crate::{
-    format_full_table_name, handle_system_table_request, CatalogList, CatalogManager,
-    CatalogProvider, CatalogProviderRef, DeregisterTableRequest, RegisterSchemaRequest,
-    RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest, SchemaProvider,
-    SchemaProviderRef,
+    format_full_table_name, handle_system_table_request, open_tables_concurrently, pg_catalog,
+    AlterSchemaRequest, BackgroundOpenTables, CatalogList, CatalogManager, CatalogProvider,
+    CatalogProviderRef, CreateCatalogRequest, DeregisterTableRequest, HotRegionCheckerRef,
+    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
+    SchemaProvider, SchemaProviderRef, DEFAULT_OPEN_TABLES_CONCURRENCY,
 };
 
 /// A `CatalogManager` consists of a system catalog and a bunch of user catalogs.
@@ -60,6 +64,11 @@ pub struct LocalCatalogManager {
     init_lock: Mutex<bool>,
     register_lock: Mutex<()>,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
+    /// Max number of tables opened concurrently on startup.
+    open_tables_concurrency: usize,
+    /// Opens hot tables on startup and the others in background if set.
+    hot_region_checker: Option<HotRegionCheckerRef>,
+    background_open_tables: Arc<BackgroundOpenTables>,
 }
 
 impl LocalCatalogManager {
@@ -80,9 +89,26 @@ impl LocalCatalogManager {
             init_lock: Mutex::new(false),
             register_lock: Mutex::new(()),
             system_table_requests: Mutex::new(Vec::default()),
+            open_tables_concurrency: DEFAULT_OPEN_TABLES_CONCURRENCY,
+            hot_region_checker: None,
+            background_open_tables: Arc::new(BackgroundOpenTables::default()),
         })
     }
 
+    /// Sets the max number of tables opened concurrently on startup, each replays the WAL
+    /// of its regions.
+    pub fn with_open_tables_concurrency(mut self, concurrency: usize) -> Self {
+        self.open_tables_concurrency = concurrency;
+        self
+    }
+
+    /// Opens tables with regions `checker` tells hot on startup, and the other tables in
+    /// background, which are served once opened.
+    pub fn with_hot_region_checker(mut self, checker: Option<HotRegionCheckerRef>) -> Self {
+        self.hot_region_checker = checker;
+        self
+    }
+
     /// Scan all entries from system catalog table
     pub async fn init(&self) -> Result<()> {
         self.init_system_catalog()?;
@@ -110,12 +136,16 @@ impl LocalCatalogManager {
             SYSTEM_CATALOG_TABLE_NAME.to_string(),
             self.system.information_schema.system.clone(),
         )?;
+        system_schema.register_table(
+            "schemata".to_string(),
+            self.system.information_schema.schemata.clone(),
+        )?;
         let system_catalog = Arc::new(MemoryCatalogProvider::new());
         system_catalog.register_schema(INFORMATION_SCHEMA_NAME.to_string(), system_schema)?;
         self.catalogs
             .register_catalog(SYSTEM_CATALOG_NAME.to_string(), system_catalog)?;
 
-        let default_catalog = Arc::new(MemoryCatalogProvider::new());
+        let default_catalog = self.new_catalog(DEFAULT_CATALOG_NAME)?;
         let default_schema = Arc::new(MemorySchemaProvider::new());
 
         // Add numbers table for test
@@ -123,6 +153,11 @@ impl LocalCatalogManager {
         default_schema.register_table("numbers".to_string(), table)?;
 
         default_catalog.register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema)?;
+        // Schema for system tables like `ddl_history`.
+        default_catalog.register_schema(
+            SYSTEM_SCHEMA_NAME.to_string(),
+            Arc::new(MemorySchemaProvider::new()),
+        )?;
         self.catalogs
             .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)?;
         Ok(())
@@ -193,12 +228,13 @@ impl LocalCatalogManager {
     async fn handle_system_catalog_entries(&self, entries: Vec<Entry>) -> Result<TableId> {
         let entries = Self::sort_entries(entries);
         let mut max_table_id = 0;
+        let mut tables = vec![];
         for entry in entries {
             match entry {
                 Entry::Catalog(c) => {
                     self.catalogs.register_catalog_if_absent(
                         c.catalog_name.clone(),
-                        Arc::new(MemoryCatalogProvider::new()),
+                        self.new_catalog(&c.catalog_name)?,
                     );
                     info!("Register catalog: {}", c.catalog_name);
                 }
@@ -209,19 +245,63 @@ impl LocalCatalogManager {
                             .context(CatalogNotFoundSnafu {
                                 catalog_name: &s.catalog_name,
                             })?;
-                    catalog.register_schema(
-                        s.schema_name.clone(),
-                        Arc::new(MemorySchemaProvider::new()),
-                    )?;
+                    let schema = MemorySchemaProvider::new()
+                        .with_default_table_options(
+                            s.default_table_options.clone().into_iter().collect(),
+                        )
+                        .with_metadata(s.metadata.clone().into_iter().collect());
+                    catalog.register_schema(s.schema_name.clone(), Arc::new(schema))?;
                     info!("Registered schema: {:?}", s);
                 }
                 Entry::Table(t) => {
-                    self.open_and_register_table(&t).await?;
-                    info!("Registered table: {:?}", t);
                     max_table_id = max_table_id.max(t.table_id);
+                    tables.push(t);
                 }
             }
         }
+
+        // Tables are opened after all catalogs and schemas are registered.
+        let (hot_tables, cold_tables): (Vec<_>, Vec<_>) = match &self.hot_region_checker {
+            Some(checker) => tables.into_iter().partition(|t| checker(t.table_id, 0)),
+            None => (tables, vec![]),
+        };
+        open_tables_concurrently(hot_tables, self.open_tables_concurrency, |t| async move {
+            Self::open_and_register_table(&self.catalogs, &self.engine, &t).await?;
+            info!("Registered table: {:?}", t);
+            Ok(())
+        })
+        .await?;
+
+        if !cold_tables.is_empty() {
+            info!("Opening {} cold tables in background", cold_tables.len());
+            let full_table_name = |t: &TableEntry| {
+                format_full_table_name(&t.catalog_name, &t.schema_name, &t.table_name)
+            };
+            self.background_open_tables
+                .start(cold_tables.iter().map(full_table_name));
+            let catalogs = self.catalogs.clone();
+            let engine = self.engine.clone();
+            let background_open_tables = self.background_open_tables.clone();
+            let concurrency = self.open_tables_concurrency;
+            common_runtime::spawn_bg(async move {
+                let (catalogs, engine) = (&catalogs, &engine);
+                let background_open_tables = &background_open_tables;
+                let result = open_tables_concurrently(cold_tables, concurrency, |t| async move {
+                    let result = Self::open_and_register_table(catalogs, engine, &t).await;
+                    // Other tables are still opened, the error is returned by DDL on the table.
+                    match &result {
+                        Ok(()) => info!("Registered table: {:?}", t),
+                        Err(e) => error!(e; "Failed to open table in background: {:?}", t),
+                    }
+                    background_open_tables.finish(&full_table_name(&t), &result);
+                    Ok(())
+                })
+                .await;
+                if let Err(e) = result {
+                    error!(e; "Failed to open tables in background");
+                }
+            });
+        }
         Ok(max_table_id)
     }
 
@@ -232,9 +312,12 @@ impl LocalCatalogManager {
         entries
     }
 
-    async fn open_and_register_table(&self, t: &TableEntry) -> Result<()> {
-        let catalog = self
-            .catalogs
+    async fn open_and_register_table(
+        catalogs: &MemoryCatalogManager,
+        engine: &TableEngineRef,
+        t: &TableEntry,
+    ) -> Result<()> {
+        let catalog = catalogs
             .catalog(&t.catalog_name)?
             .context(CatalogNotFoundSnafu {
                 catalog_name: &t.catalog_name,
@@ -255,8 +338,7 @@ impl LocalCatalogManager {
             region_numbers: vec![0],
         };
 
-        let option = self
-            .engine
+        let option = engine
             .open_table(&context, request)
             .await
             .with_context(|_| OpenTableSnafu {
@@ -275,6 +357,62 @@ impl LocalCatalogManager {
         schema.register_table(t.table_name.clone(), option)?;
         Ok(())
     }
+
+    /// Returns a new catalog named `catalog_name` with only the `pg_catalog` schema.
+    fn new_catalog(&self, catalog_name: &str) -> Result<Arc<MemoryCatalogProvider>> {
+        let catalog = Arc::new(MemoryCatalogProvider::new());
+        catalog.register_schema(
+            PG_CATALOG_NAME.to_string(),
+            pg_catalog::new_pg_catalog_schema(self.catalogs.clone(), catalog_name)?,
+        )?;
+        Ok(catalog)
+    }
+
+    /// Moves table `table_name` of the schema being renamed by `request` to schema
+    /// `new_schema`, returns the table reopened in the new schema.
+    async fn move_table(
+        &self,
+        request: &AlterSchemaRequest,
+        new_schema: &str,
+        table_name: &str,
+    ) -> Result<TableRef> {
+        let alter_request = AlterTableRequest {
+            catalog_name: request.catalog.clone(),
+            schema_name: request.schema.clone(),
+            table_name: table_name.to_string(),
+            alter_kind: AlterKind::RenameSchema {
+                new_schema_name: new_schema.to_string(),
+            },
+        };
+        let table = self
+            .engine
+            .alter_table(&EngineContext::default(), alter_request)
+            .await
+            .with_context(|_| MoveTableSnafu {
+                table_info: format_full_table_name(&request.catalog, &request.schema, table_name),
+                schema: new_schema,
+            })?;
+
+        let table_id = table.table_info().ident.table_id;
+        self.system
+            .register_table(
+                request.catalog.clone(),
+                new_schema.to_string(),
+                table_name.to_string(),
+                table_id,
+            )
+            .await?;
+        let deregister_request = DeregisterTableRequest {
+            catalog: request.catalog.clone(),
+            schema: request.schema.clone(),
+            table_name: table_name.to_string(),
+        };
+        let _ = self
+            .system
+            .deregister_table(&deregister_request, table_id)
+            .await?;
+        Ok(table)
+    }
 }
 
 impl CatalogList for LocalCatalogManager {
@@ -451,6 +589,38 @@ impl CatalogManager for LocalCatalogManager {
         }
     }
 
+    async fn create_catalog(&self, request: CreateCatalogRequest) -> Result<bool> {
+        let started = self.init_lock.lock().await;
+        ensure!(
+            *started,
+            IllegalManagerStateSnafu {
+                msg: "Catalog manager not started",
+            }
+        );
+        let catalog_name = request.catalog;
+
+        let _lock = self.register_lock.lock().await;
+        if self.catalogs.catalog(&catalog_name)?.is_some() {
+            return Ok(false);
+        }
+        self.system.register_catalog(catalog_name.clone()).await?;
+        self.system
+            .register_schema(
+                catalog_name.clone(),
+                DEFAULT_SCHEMA_NAME.to_string(),
+                &SchemaEntryValue::default(),
+            )
+            .await?;
+
+        let catalog = self.new_catalog(&catalog_name)?;
+        catalog.register_schema(
+            DEFAULT_SCHEMA_NAME.to_string(),
+            Arc::new(MemorySchemaProvider::new()),
+        )?;
+        self.catalogs.register_catalog(catalog_name, catalog)?;
+        Ok(true)
+    }
+
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
         let started = self.init_lock.lock().await;
         ensure!(
@@ -475,10 +645,92 @@ impl CatalogManager for LocalCatalogManager {
                     schema: schema_name,
                 }
             );
+            let value = SchemaEntryValue {
+                default_table_options: request.default_table_options.clone().into_iter().collect(),
+                metadata: BTreeMap::new(),
+            };
             self.system
-                .register_schema(request.catalog, schema_name.clone())
+                .register_schema(request.catalog, schema_name.clone(), &value)
                 .await?;
-            catalog.register_schema(request.schema, Arc::new(MemorySchemaProvider::new()))?;
+            let schema = MemorySchemaProvider::new()
+                .with_default_table_options(request.default_table_options);
+            catalog.register_schema(request.schema, Arc::new(schema))?;
+            Ok(true)
+        }
+    }
+
+    async fn alter_schema(&self, request: AlterSchemaRequest) -> Result<bool> {
+        let started = self.init_lock.lock().await;
+        ensure!(
+            *started,
+            IllegalManagerStateSnafu {
+                msg: "Catalog manager not started",
+            }
+        );
+
+        let catalog = self
+            .catalogs
+            .catalog(&request.catalog)?
+            .context(CatalogNotFoundSnafu {
+                catalog_name: &request.catalog,
+            })?;
+
+        {
+            let _lock = self.register_lock.lock().await;
+            let schema = catalog
+                .schema(&request.schema)?
+                .with_context(|| SchemaNotFoundSnafu {
+                    catalog: &request.catalog,
+                    schema: &request.schema,
+                })?;
+            let mut value = SchemaEntryValue {
+                default_table_options: schema.default_table_options().into_iter().collect(),
+                metadata: schema.metadata().into_iter().collect(),
+            };
+
+            // The alteration is persisted before it's applied to the memory catalog, so the
+            // memory catalog is unchanged if persisting fails.
+            match &request.alter_kind {
+                AlterDatabaseKind::RenameDatabase { new_name } => {
+                    ensure!(
+                        catalog.schema(new_name)?.is_none(),
+                        SchemaExistsSnafu { schema: new_name }
+                    );
+                    self.system
+                        .register_schema(request.catalog.clone(), new_name.clone(), &value)
+                        .await?;
+                    let mut moved = Vec::new();
+                    for table_name in schema.table_names()? {
+                        let Some(table) = schema.table(&table_name)? else {
+                            continue;
+                        };
+                        // Tables not created by the engine, like the numbers table, are
+                        // not persisted and only moved in memory.
+                        if table.table_info().meta.engine != self.engine.name() {
+                            continue;
+                        }
+                        let table = self.move_table(&request, new_name, &table_name).await?;
+                        moved.push((table_name, table));
+                    }
+                    self.system
+                        .deregister_schema(&request.catalog, &request.schema)
+                        .await?;
+
+                    let schema = alter_memory_schema(&catalog, &request)?;
+                    // Replaces the tables with those reopened in the new schema.
+                    for (table_name, table) in moved {
+                        let _ = schema.deregister_table(&table_name)?;
+                        let _ = schema.register_table(table_name, table)?;
+                    }
+                }
+                AlterDatabaseKind::SetMetadata { metadata } => {
+                    value.metadata.extend(metadata.clone());
+                    self.system
+                        .register_schema(request.catalog.clone(), request.schema.clone(), &value)
+                        .await?;
+                    let _ = alter_memory_schema(&catalog, &request)?;
+                }
+            }
             Ok(true)
         }
     }
@@ -524,11 +776,17 @@ impl CatalogManager for LocalCatalogManager {
             })?;
         schema.table(table_name)
     }
+
+    fn ensure_table_opened(&self, catalog: &str, schema: &str, table_name: &str) -> Result<()> {
+        self.background_open_tables
+            .ensure_opened(catalog, schema, table_name)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
+    use std::collections::BTreeMap;
 
     use super::*;
     use crate::system::{CatalogEntry, SchemaEntry};
@@ -548,10 +806,14 @@ mod tests {
             Entry::Schema(SchemaEntry {
                 catalog_name: "C1".to_string(),
                 schema_name: "S1".to_string(),
+                default_table_options: BTreeMap::new(),
+                metadata: BTreeMap::new(),
             }),
             Entry::Schema(SchemaEntry {
                 catalog_name: "C2".to_string(),
                 schema_name: "S2".to_string(),
+                default_table_options: BTreeMap::new(),
+                metadata: BTreeMap::new(),
             }),
             Entry::Catalog(CatalogEntry {
                 catalog_name: "".to_string(),
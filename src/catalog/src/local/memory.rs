@@ -18,10 +18,11 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 
-use common_catalog::consts::MIN_USER_TABLE_ID;
+use common_catalog::consts::{DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
 use common_telemetry::error;
 use snafu::{ensure, OptionExt};
 use table::metadata::TableId;
+use table::requests::AlterDatabaseKind;
 use table::table::TableIdProvider;
 use table::TableRef;
 
@@ -30,9 +31,9 @@ use crate::error::{
 };
 use crate::schema::SchemaProvider;
 use crate::{
-    CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
-    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
-    SchemaProviderRef,
+    AlterSchemaRequest, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
+    CreateCatalogRequest, DeregisterTableRequest, RegisterSchemaRequest,
+    RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest, SchemaProviderRef,
 };
 
 /// Simple in-memory list of catalogs
@@ -130,6 +131,17 @@ impl CatalogManager for MemoryCatalogManager {
             .map(|v| v.is_some())
     }
 
+    async fn create_catalog(&self, request: CreateCatalogRequest) -> Result<bool> {
+        let catalog = Arc::new(MemoryCatalogProvider::new());
+        catalog.register_schema(
+            DEFAULT_SCHEMA_NAME.to_string(),
+            Arc::new(MemorySchemaProvider::new()),
+        )?;
+        Ok(self
+            .register_catalog_if_absent(request.catalog, catalog)
+            .is_none())
+    }
+
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
         let catalogs = self.catalogs.write().unwrap();
         let catalog = catalogs
@@ -137,7 +149,19 @@ impl CatalogManager for MemoryCatalogManager {
             .context(CatalogNotFoundSnafu {
                 catalog_name: &request.catalog,
             })?;
-        catalog.register_schema(request.schema, Arc::new(MemorySchemaProvider::new()))?;
+        let schema =
+            MemorySchemaProvider::new().with_default_table_options(request.default_table_options);
+        catalog.register_schema(request.schema, Arc::new(schema))?;
+        Ok(true)
+    }
+
+    async fn alter_schema(&self, request: AlterSchemaRequest) -> Result<bool> {
+        let catalog = self
+            .catalog(&request.catalog)?
+            .context(CatalogNotFoundSnafu {
+                catalog_name: &request.catalog,
+            })?;
+        let _ = alter_memory_schema(&catalog, &request)?;
         Ok(true)
     }
 
@@ -232,6 +256,11 @@ impl MemoryCatalogProvider {
             schemas: RwLock::new(HashMap::new()),
         }
     }
+
+    /// Removes the schema from this catalog and returns it.
+    pub fn deregister_schema(&self, name: &str) -> Option<SchemaProviderRef> {
+        self.schemas.write().unwrap().remove(name)
+    }
 }
 
 impl CatalogProvider for MemoryCatalogProvider {
@@ -266,6 +295,8 @@ impl CatalogProvider for MemoryCatalogProvider {
 /// Simple in-memory implementation of a schema.
 pub struct MemorySchemaProvider {
     tables: RwLock<HashMap<String, TableRef>>,
+    default_table_options: HashMap<String, String>,
+    metadata: RwLock<HashMap<String, String>>,
 }
 
 impl MemorySchemaProvider {
@@ -273,8 +304,27 @@ impl MemorySchemaProvider {
     pub fn new() -> Self {
         Self {
             tables: RwLock::new(HashMap::new()),
+            default_table_options: HashMap::new(),
+            metadata: RwLock::new(HashMap::new()),
         }
     }
+
+    /// Sets the default options of tables created in this schema.
+    pub fn with_default_table_options(mut self, options: HashMap<String, String>) -> Self {
+        self.default_table_options = options;
+        self
+    }
+
+    /// Sets the key-value metadata of this schema.
+    pub fn with_metadata(self, metadata: HashMap<String, String>) -> Self {
+        *self.metadata.write().unwrap() = metadata;
+        self
+    }
+
+    /// Merges `metadata` into the metadata of this schema, existing keys are overwritten.
+    pub fn set_metadata(&self, metadata: HashMap<String, String>) {
+        self.metadata.write().unwrap().extend(metadata);
+    }
 }
 
 impl Default for MemorySchemaProvider {
@@ -339,6 +389,54 @@ impl SchemaProvider for MemorySchemaProvider {
         let tables = self.tables.read().unwrap();
         Ok(tables.contains_key(name))
     }
+
+    fn default_table_options(&self) -> HashMap<String, String> {
+        self.default_table_options.clone()
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        self.metadata.read().unwrap().clone()
+    }
+}
+
+/// Alters the schema in the in-memory `catalog`, returns the altered schema.
+pub(crate) fn alter_memory_schema(
+    catalog: &CatalogProviderRef,
+    request: &AlterSchemaRequest,
+) -> Result<SchemaProviderRef> {
+    let schema = catalog
+        .schema(&request.schema)?
+        .with_context(|| SchemaNotFoundSnafu {
+            catalog: &request.catalog,
+            schema: &request.schema,
+        })?;
+
+    match &request.alter_kind {
+        AlterDatabaseKind::RenameDatabase { new_name } => {
+            ensure!(
+                catalog.schema(new_name)?.is_none(),
+                error::SchemaExistsSnafu { schema: new_name }
+            );
+            let memory_catalog = catalog
+                .as_any()
+                .downcast_ref::<MemoryCatalogProvider>()
+                .context(error::UnimplementedSnafu {
+                    operation: "rename schema",
+                })?;
+            let _ = memory_catalog.deregister_schema(&request.schema);
+            catalog.register_schema(new_name.clone(), schema.clone())?;
+        }
+        AlterDatabaseKind::SetMetadata { metadata } => {
+            schema
+                .as_any()
+                .downcast_ref::<MemorySchemaProvider>()
+                .context(error::UnimplementedSnafu {
+                    operation: "set schema metadata",
+                })?
+                .set_metadata(metadata.clone());
+        }
+    }
+    Ok(schema)
 }
 
 /// Create a memory catalog list contains a numbers table for test
@@ -374,6 +472,27 @@ mod tests {
         assert!(default_schema.table("not_exists").unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_create_catalog() {
+        let catalog_manager = MemoryCatalogManager::default();
+        let request = CreateCatalogRequest {
+            catalog: "tenant_a".to_string(),
+        };
+        assert!(catalog_manager
+            .create_catalog(request.clone())
+            .await
+            .unwrap());
+        assert!(!catalog_manager.create_catalog(request).await.unwrap());
+
+        assert!(catalog_manager
+            .schema("tenant_a", DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .is_some());
+        let mut catalog_names = catalog_manager.catalog_names().unwrap();
+        catalog_names.sort();
+        assert_eq!(vec![DEFAULT_CATALOG_NAME, "tenant_a"], catalog_names);
+    }
+
     #[tokio::test]
     async fn test_mem_provider() {
         let provider = MemorySchemaProvider::new();
@@ -473,6 +592,85 @@ mod tests {
         assert_eq!(registered_table.table_info().ident.table_id, table_id);
     }
 
+    #[tokio::test]
+    async fn test_catalog_alter_schema() {
+        let catalog = MemoryCatalogManager::default();
+        assert!(catalog
+            .register_schema(RegisterSchemaRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: "db1".to_string(),
+                default_table_options: HashMap::new(),
+            })
+            .await
+            .unwrap());
+
+        let set_metadata_req = AlterSchemaRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: "db1".to_string(),
+            alter_kind: AlterDatabaseKind::SetMetadata {
+                metadata: [("owner".to_string(), "alice".to_string())].into(),
+            },
+        };
+        assert!(catalog.alter_schema(set_metadata_req).await.unwrap());
+
+        let rename_req = AlterSchemaRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: "db1".to_string(),
+            alter_kind: AlterDatabaseKind::RenameDatabase {
+                new_name: "db2".to_string(),
+            },
+        };
+        assert!(catalog.alter_schema(rename_req).await.unwrap());
+        assert!(catalog
+            .schema(DEFAULT_CATALOG_NAME, "db1")
+            .unwrap()
+            .is_none());
+        let schema = catalog
+            .schema(DEFAULT_CATALOG_NAME, "db2")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            Some("alice"),
+            schema.metadata().get("owner").map(|s| s.as_str())
+        );
+
+        // Tables are moved along with the schema.
+        schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::default()))
+            .unwrap();
+        let rename_req = AlterSchemaRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: "db2".to_string(),
+            alter_kind: AlterDatabaseKind::RenameDatabase {
+                new_name: "db3".to_string(),
+            },
+        };
+        assert!(catalog.alter_schema(rename_req).await.unwrap());
+        assert!(catalog
+            .table(DEFAULT_CATALOG_NAME, "db3", "numbers")
+            .unwrap()
+            .is_some());
+
+        // Renaming to an existing schema fails.
+        assert!(catalog
+            .register_schema(RegisterSchemaRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: "db4".to_string(),
+                default_table_options: HashMap::new(),
+            })
+            .await
+            .unwrap());
+        let rename_req = AlterSchemaRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: "db3".to_string(),
+            alter_kind: AlterDatabaseKind::RenameDatabase {
+                new_name: "db4".to_string(),
+            },
+        };
+        let err = catalog.alter_schema(rename_req).await.unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
     #[test]
     pub fn test_register_if_absent() {
         let list = MemoryCatalogManager::default();
@@ -0,0 +1,546 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimal PostgreSQL system catalog, the `pg_catalog` schema with `pg_namespace`,
+//! `pg_class`, `pg_tables` and `pg_type` tables, so that Postgres clients could introspect
+//! schemas.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_stream::stream;
+use common_catalog::consts::PG_CATALOG_NAME;
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::RecordBatch;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{BooleanVector, Int16Vector, StringVector, UInt32Vector, VectorRef};
+use snafu::ResultExt;
+use table::error::TablesRecordBatchSnafu;
+use table::metadata::{TableInfoRef, TableType};
+use table::table::scan::SimpleTableScan;
+use table::{Table, TableRef};
+
+use crate::error::Result;
+use crate::local::MemorySchemaProvider;
+use crate::tables::TablesRecordBatchStream;
+use crate::{CatalogListRef, SchemaProvider, SchemaProviderRef};
+
+pub const PG_NAMESPACE_TABLE_NAME: &str = "pg_namespace";
+pub const PG_CLASS_TABLE_NAME: &str = "pg_class";
+pub const PG_TABLES_TABLE_NAME: &str = "pg_tables";
+pub const PG_TYPE_TABLE_NAME: &str = "pg_type";
+
+/// Owner of all schemas and tables, as there is no ownership in GreptimeDB.
+const OWNER: &str = "greptime";
+const OWNER_OID: u32 = 10;
+/// Oids of `pg_catalog` and `public` are the same as PostgreSQL.
+const PG_CATALOG_OID: u32 = 11;
+const PUBLIC_OID: u32 = 2200;
+/// Oids of other schemas start from the first oid PostgreSQL assigns to user objects.
+const FIRST_NORMAL_OID: u32 = 16384;
+
+/// Builtin types GreptimeDB data types are mapped to in the Postgres protocol, in the form
+/// of `(oid, typname, typlen)`.
+const PG_TYPES: &[(u32, &str, i16)] = &[
+    (16, "bool", 1),
+    (17, "bytea", -1),
+    (18, "char", 1),
+    (20, "int8", 8),
+    (21, "int2", 2),
+    (23, "int4", 4),
+    (700, "float4", 4),
+    (701, "float8", 8),
+    (1043, "varchar", -1),
+    (1082, "date", 4),
+    (1114, "timestamp", 8),
+    (1700, "numeric", -1),
+];
+
+/// Creates the `pg_catalog` schema of `catalog_name`, whose tables reflect the schemas
+/// and tables in `catalogs`.
+pub fn new_pg_catalog_schema(
+    catalogs: CatalogListRef,
+    catalog_name: &str,
+) -> Result<SchemaProviderRef> {
+    let schema = MemorySchemaProvider::new();
+    let _ = schema.register_table(
+        PG_NAMESPACE_TABLE_NAME.to_string(),
+        Arc::new(PgNamespace::new(catalogs.clone(), catalog_name)),
+    )?;
+    let _ = schema.register_table(
+        PG_CLASS_TABLE_NAME.to_string(),
+        Arc::new(PgClass::new(catalogs.clone(), catalog_name)),
+    )?;
+    let _ = schema.register_table(
+        PG_TABLES_TABLE_NAME.to_string(),
+        Arc::new(PgTables::new(catalogs, catalog_name)),
+    )?;
+    let _ = schema.register_table(PG_TYPE_TABLE_NAME.to_string(), Arc::new(PgType::new()))?;
+    Ok(Arc::new(schema))
+}
+
+/// Returns the sorted schema names of `catalog_name`.
+fn schema_names(catalogs: &CatalogListRef, catalog_name: &str) -> Result<Vec<String>> {
+    let Some(catalog) = catalogs.catalog(catalog_name)? else {
+        return Ok(vec![]);
+    };
+    let mut names = catalog.schema_names()?;
+    names.sort();
+    Ok(names)
+}
+
+/// Returns the oid of each schema in `names`, which should be sorted. Oids of schemas
+/// other than `pg_catalog` and `public` are only stable while no schema is created or
+/// dropped.
+fn namespace_oids(names: &[String]) -> Vec<u32> {
+    let mut next_oid = FIRST_NORMAL_OID;
+    names
+        .iter()
+        .map(|name| match name.as_str() {
+            PG_CATALOG_NAME => PG_CATALOG_OID,
+            "public" => PUBLIC_OID,
+            _ => {
+                next_oid += 1;
+                next_oid - 1
+            }
+        })
+        .collect()
+}
+
+/// A table and the schema it belongs to.
+struct TableEntry {
+    schema_name: String,
+    schema_oid: u32,
+    table_name: String,
+    table: TableRef,
+}
+
+/// Returns all tables of `catalog_name`, ordered by schema name and table name.
+fn list_tables(catalogs: &CatalogListRef, catalog_name: &str) -> Result<Vec<TableEntry>> {
+    let Some(catalog) = catalogs.catalog(catalog_name)? else {
+        return Ok(vec![]);
+    };
+    let schema_names = schema_names(catalogs, catalog_name)?;
+    let schema_oids = namespace_oids(&schema_names);
+    let mut entries = Vec::new();
+    for (schema_name, schema_oid) in schema_names.into_iter().zip(schema_oids) {
+        let Some(schema) = catalog.schema(&schema_name)? else {
+            continue;
+        };
+        let mut table_names = schema.table_names()?;
+        table_names.sort();
+        for table_name in table_names {
+            let Some(table) = schema.table(&table_name)? else {
+                continue;
+            };
+            entries.push(TableEntry {
+                schema_name: schema_name.clone(),
+                schema_oid,
+                table_name,
+                table,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn scan_batch(
+    schema: SchemaRef,
+    columns: impl FnOnce() -> Result<Vec<VectorRef>> + Send + 'static,
+) -> table::error::Result<PhysicalPlanRef> {
+    let stream_schema = schema.clone();
+    let stream = stream!({
+        let columns = columns()
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        yield RecordBatch::new(stream_schema, columns);
+    });
+    let stream = TablesRecordBatchStream::new(schema, Box::pin(stream));
+    Ok(Arc::new(SimpleTableScan::new(Box::pin(stream))))
+}
+
+/// The `pg_catalog.pg_namespace` table, which lists schemas.
+pub struct PgNamespace {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+    catalog_name: String,
+}
+
+impl PgNamespace {
+    pub fn new(catalogs: CatalogListRef, catalog_name: &str) -> Self {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("oid", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("nspname", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("nspowner", ConcreteDataType::uint32_datatype(), false),
+        ]);
+        Self {
+            schema: Arc::new(schema),
+            catalogs,
+            catalog_name: catalog_name.to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for PgNamespace {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("PgNamespace does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let catalogs = self.catalogs.clone();
+        let catalog_name = self.catalog_name.clone();
+        scan_batch(self.schema.clone(), move || {
+            let names = schema_names(&catalogs, &catalog_name)?;
+            let oids = namespace_oids(&names);
+            let owners = vec![OWNER_OID; names.len()];
+            Ok(vec![
+                Arc::new(UInt32Vector::from_vec(oids)) as _,
+                Arc::new(StringVector::from(names)) as _,
+                Arc::new(UInt32Vector::from_vec(owners)) as _,
+            ])
+        })
+    }
+}
+
+/// The `pg_catalog.pg_class` table, which lists tables of all schemas. The oid of a table
+/// is its table id, tables without table info, like tables of `pg_catalog`, have oid 0.
+pub struct PgClass {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+    catalog_name: String,
+}
+
+impl PgClass {
+    pub fn new(catalogs: CatalogListRef, catalog_name: &str) -> Self {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("oid", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("relname", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("relnamespace", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("relowner", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("relkind", ConcreteDataType::string_datatype(), false),
+        ]);
+        Self {
+            schema: Arc::new(schema),
+            catalogs,
+            catalog_name: catalog_name.to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for PgClass {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("PgClass does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let catalogs = self.catalogs.clone();
+        let catalog_name = self.catalog_name.clone();
+        scan_batch(self.schema.clone(), move || {
+            let entries = list_tables(&catalogs, &catalog_name)?;
+            let rows = entries.len();
+            let mut oids = Vec::with_capacity(rows);
+            let mut names = Vec::with_capacity(rows);
+            let mut namespaces = Vec::with_capacity(rows);
+            let mut kinds = Vec::with_capacity(rows);
+            for entry in entries {
+                let table_info = entry.table.try_table_info();
+                oids.push(table_info.as_ref().map_or(0, |info| info.ident.table_id));
+                // Views are "v", other tables are ordinary tables in PostgreSQL.
+                let kind = match table_info.map(|info| info.table_type) {
+                    Some(TableType::View) => "v",
+                    _ => "r",
+                };
+                kinds.push(kind);
+                names.push(entry.table_name);
+                namespaces.push(entry.schema_oid);
+            }
+            Ok(vec![
+                Arc::new(UInt32Vector::from_vec(oids)) as _,
+                Arc::new(StringVector::from(names)) as _,
+                Arc::new(UInt32Vector::from_vec(namespaces)) as _,
+                Arc::new(UInt32Vector::from_vec(vec![OWNER_OID; rows])) as _,
+                Arc::new(StringVector::from(kinds)) as _,
+            ])
+        })
+    }
+}
+
+/// The `pg_catalog.pg_tables` table, which lists tables of all schemas.
+pub struct PgTables {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+    catalog_name: String,
+}
+
+impl PgTables {
+    pub fn new(catalogs: CatalogListRef, catalog_name: &str) -> Self {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("schemaname", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("tablename", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("tableowner", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("tablespace", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("hasindexes", ConcreteDataType::boolean_datatype(), false),
+            ColumnSchema::new("hasrules", ConcreteDataType::boolean_datatype(), false),
+            ColumnSchema::new("hastriggers", ConcreteDataType::boolean_datatype(), false),
+            ColumnSchema::new("rowsecurity", ConcreteDataType::boolean_datatype(), false),
+        ]);
+        Self {
+            schema: Arc::new(schema),
+            catalogs,
+            catalog_name: catalog_name.to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for PgTables {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("PgTables does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let catalogs = self.catalogs.clone();
+        let catalog_name = self.catalog_name.clone();
+        scan_batch(self.schema.clone(), move || {
+            let (schemas, tables): (Vec<_>, Vec<_>) = list_tables(&catalogs, &catalog_name)?
+                .into_iter()
+                .map(|entry| (entry.schema_name, entry.table_name))
+                .unzip();
+
+            let rows = tables.len();
+            let falses = || Arc::new(BooleanVector::from(vec![false; rows])) as VectorRef;
+            Ok(vec![
+                Arc::new(StringVector::from(schemas)) as _,
+                Arc::new(StringVector::from(tables)) as _,
+                Arc::new(StringVector::from(vec![OWNER; rows])) as _,
+                Arc::new(StringVector::from(vec![None::<String>; rows])) as _,
+                falses(),
+                falses(),
+                falses(),
+                falses(),
+            ])
+        })
+    }
+}
+
+/// The `pg_catalog.pg_type` table, which lists the builtin types.
+pub struct PgType {
+    schema: SchemaRef,
+}
+
+impl PgType {
+    pub fn new() -> Self {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("oid", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("typname", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("typnamespace", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("typowner", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("typlen", ConcreteDataType::int16_datatype(), false),
+            ColumnSchema::new("typtype", ConcreteDataType::string_datatype(), false),
+        ]);
+        Self {
+            schema: Arc::new(schema),
+        }
+    }
+}
+
+impl Default for PgType {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for PgType {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("PgType does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        scan_batch(self.schema.clone(), || {
+            let rows = PG_TYPES.len();
+            Ok(vec![
+                Arc::new(UInt32Vector::from_values(PG_TYPES.iter().map(|t| t.0))) as _,
+                Arc::new(StringVector::from(
+                    PG_TYPES.iter().map(|t| t.1).collect::<Vec<_>>(),
+                )) as _,
+                Arc::new(UInt32Vector::from_vec(vec![PG_CATALOG_OID; rows])) as _,
+                Arc::new(UInt32Vector::from_vec(vec![OWNER_OID; rows])) as _,
+                Arc::new(Int16Vector::from_values(PG_TYPES.iter().map(|t| t.2))) as _,
+                // All of them are base types.
+                Arc::new(StringVector::from(vec!["b"; rows])) as _,
+            ])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_query::physical_plan::SessionContext;
+    use common_recordbatch::RecordBatches;
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+    use crate::local::memory::new_memory_catalog_list;
+    use crate::CatalogList;
+
+    async fn scan(table: &dyn Table) -> String {
+        let session_ctx = SessionContext::new();
+        let stream = table
+            .scan(None, &[], None)
+            .await
+            .unwrap()
+            .execute(0, session_ctx.task_ctx())
+            .unwrap();
+        RecordBatches::try_collect(stream)
+            .await
+            .unwrap()
+            .pretty_print()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pg_catalog() {
+        let catalogs = new_memory_catalog_list().unwrap();
+        let catalog = catalogs.catalog(DEFAULT_CATALOG_NAME).unwrap().unwrap();
+        let public = catalog.schema(DEFAULT_SCHEMA_NAME).unwrap().unwrap();
+        let _ = public
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::default()))
+            .unwrap();
+        let pg_catalog = new_pg_catalog_schema(catalogs.clone(), DEFAULT_CATALOG_NAME).unwrap();
+        let _ = catalog
+            .register_schema(PG_CATALOG_NAME.to_string(), pg_catalog.clone())
+            .unwrap();
+        let _ = catalog
+            .register_schema("test".to_string(), Arc::new(MemorySchemaProvider::new()))
+            .unwrap();
+
+        let namespace = pg_catalog.table(PG_NAMESPACE_TABLE_NAME).unwrap().unwrap();
+        let expected = "\
++-------+------------+----------+
+| oid   | nspname    | nspowner |
++-------+------------+----------+
+| 11    | pg_catalog | 10       |
+| 2200  | public     | 10       |
+| 16384 | test       | 10       |
++-------+------------+----------+";
+        assert_eq!(expected, scan(namespace.as_ref()).await);
+
+        let tables = pg_catalog.table(PG_TABLES_TABLE_NAME).unwrap().unwrap();
+        let expected = "\
++------------+--------------+------------+------------+------------+----------+-------------+-------------+
+| schemaname | tablename    | tableowner | tablespace | hasindexes | hasrules | hastriggers | rowsecurity |
++------------+--------------+------------+------------+------------+----------+-------------+-------------+
+| pg_catalog | pg_class     | greptime   |            | false      | false    | false       | false       |
+| pg_catalog | pg_namespace | greptime   |            | false      | false    | false       | false       |
+| pg_catalog | pg_tables    | greptime   |            | false      | false    | false       | false       |
+| pg_catalog | pg_type      | greptime   |            | false      | false    | false       | false       |
+| public     | numbers      | greptime   |            | false      | false    | false       | false       |
++------------+--------------+------------+------------+------------+----------+-------------+-------------+";
+        assert_eq!(expected, scan(tables.as_ref()).await);
+
+        let class = pg_catalog.table(PG_CLASS_TABLE_NAME).unwrap().unwrap();
+        let expected = "\
++-----+--------------+--------------+----------+---------+
+| oid | relname      | relnamespace | relowner | relkind |
++-----+--------------+--------------+----------+---------+
+| 0   | pg_class     | 11           | 10       | r       |
+| 0   | pg_namespace | 11           | 10       | r       |
+| 0   | pg_tables    | 11           | 10       | r       |
+| 0   | pg_type      | 11           | 10       | r       |
+| 0   | numbers      | 2200         | 10       | r       |
++-----+--------------+--------------+----------+---------+";
+        assert_eq!(expected, scan(class.as_ref()).await);
+
+        let types = pg_catalog.table(PG_TYPE_TABLE_NAME).unwrap().unwrap();
+        let output = scan(types.as_ref()).await;
+        assert!(
+            output.contains("| 1114 | timestamp | 11           | 10       | 8      | b       |")
+        );
+    }
+}
@@ -0,0 +1,458 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recent queries kept in a ring buffer, exposed as the `system.query_history` table.
+
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use async_stream::stream;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME};
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_query::Output;
+use common_recordbatch::error::Result as RecordBatchResult;
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use common_time::util;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{
+    BooleanVector, StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef,
+};
+use futures::Stream;
+use table::metadata::TableInfoRef;
+use table::table::scan::SimpleTableScan;
+use table::Table;
+
+use crate::error::Result;
+use crate::resource_usage::ResourceAccountantRef;
+use crate::tables::TablesRecordBatchStream;
+use crate::CatalogManager;
+
+pub const QUERY_HISTORY_TABLE_NAME: &str = "query_history";
+
+/// A finished query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryRecord {
+    pub start_millis: i64,
+    /// Hash of the SQL text, queries with the same text share the same hash.
+    pub sql_hash: u64,
+    pub duration_millis: u64,
+    /// Rows returned by queries, or rows affected by other statements.
+    pub rows: u64,
+    pub user: String,
+    pub protocol: String,
+    pub success: bool,
+}
+
+/// Keeps the latest `capacity` queries, older queries are evicted once it is full.
+#[derive(Debug)]
+pub struct QueryHistory {
+    capacity: usize,
+    records: Mutex<VecDeque<QueryRecord>>,
+}
+
+pub type QueryHistoryRef = Arc<QueryHistory>;
+
+impl QueryHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn record(&self, record: QueryRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            let _ = records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Returns the recorded queries, from the oldest to the latest.
+    pub fn records(&self) -> Vec<QueryRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Starts tracking a query, it's recorded when the returned [QueryTracker] finishes.
+    pub fn start(self: &Arc<Self>, sql: &str, user: &str, protocol: &str) -> QueryTracker {
+        QueryTracker::new(sql, user, protocol).with_history(self.clone())
+    }
+}
+
+pub fn sql_hash(sql: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    sql.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Tracks a query in execution, the query is recorded to the [QueryHistory] and
+/// [ResourceAccountant](crate::resource_usage::ResourceAccountant) it's attached to once it
+/// finishes.
+pub struct QueryTracker {
+    history: Option<QueryHistoryRef>,
+    /// The accountant and the catalog and schema the query is accounted to.
+    accountant: Option<(ResourceAccountantRef, String, String)>,
+    start: Instant,
+    start_millis: i64,
+    sql_hash: u64,
+    user: String,
+    protocol: String,
+}
+
+impl QueryTracker {
+    pub fn new(sql: &str, user: &str, protocol: &str) -> Self {
+        Self {
+            history: None,
+            accountant: None,
+            start: Instant::now(),
+            start_millis: util::current_time_millis(),
+            sql_hash: sql_hash(sql),
+            user: user.to_string(),
+            protocol: protocol.to_string(),
+        }
+    }
+
+    pub fn with_history(mut self, history: QueryHistoryRef) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn with_accountant(
+        mut self,
+        accountant: ResourceAccountantRef,
+        catalog: &str,
+        schema: &str,
+    ) -> Self {
+        self.accountant = Some((accountant, catalog.to_string(), schema.to_string()));
+        self
+    }
+
+    pub fn finish(self, rows: u64, success: bool) {
+        self.finish_inner(rows, false, success)
+    }
+
+    /// Finishes the query, `returned` indicates whether the `rows` are returned to
+    /// the client or just affected.
+    fn finish_inner(self, rows: u64, returned: bool, success: bool) {
+        let duration_millis = self.start.elapsed().as_millis() as u64;
+        if let Some((accountant, catalog, schema)) = &self.accountant {
+            let returned_rows = if returned { rows } else { 0 };
+            accountant.record_query(&self.user, catalog, schema, returned_rows, duration_millis);
+        }
+        if let Some(history) = &self.history {
+            history.record(QueryRecord {
+                start_millis: self.start_millis,
+                sql_hash: self.sql_hash,
+                duration_millis,
+                rows,
+                user: self.user,
+                protocol: self.protocol,
+                success,
+            });
+        }
+    }
+
+    /// Finishes tracking with the `output`. A stream output is finished once it is
+    /// exhausted or dropped, with the rows it has yielded.
+    pub fn finish_with_output(self, output: Output) -> Output {
+        match output {
+            Output::AffectedRows(rows) => {
+                self.finish(rows as u64, true);
+                Output::AffectedRows(rows)
+            }
+            Output::RecordBatches(batches) => {
+                let rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+                self.finish_inner(rows as u64, true, true);
+                Output::RecordBatches(batches)
+            }
+            Output::Stream(stream) => Output::Stream(Box::pin(TrackedRecordBatchStream {
+                stream,
+                tracker: Some(self),
+                rows: 0,
+                success: true,
+            })),
+        }
+    }
+}
+
+struct TrackedRecordBatchStream {
+    stream: SendableRecordBatchStream,
+    tracker: Option<QueryTracker>,
+    rows: u64,
+    success: bool,
+}
+
+impl TrackedRecordBatchStream {
+    fn finish(&mut self) {
+        if let Some(tracker) = self.tracker.take() {
+            tracker.finish_inner(self.rows, true, self.success);
+        }
+    }
+}
+
+impl Stream for TrackedRecordBatchStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.stream).poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => self.rows += batch.num_rows() as u64,
+            Poll::Ready(Some(Err(_))) => self.success = false,
+            Poll::Ready(None) => self.finish(),
+            Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for TrackedRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+impl Drop for TrackedRecordBatchStream {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Registers the `query_history` table to the `system` schema of default catalog,
+/// does nothing if the schema is absent.
+pub fn register_query_history_table(
+    catalog_manager: &dyn CatalogManager,
+    history: QueryHistoryRef,
+) -> Result<()> {
+    let Some(schema) = catalog_manager.schema(DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME)? else {
+        return Ok(());
+    };
+    if !schema.table_exist(QUERY_HISTORY_TABLE_NAME)? {
+        let _ = schema.register_table(
+            QUERY_HISTORY_TABLE_NAME.to_string(),
+            Arc::new(QueryHistoryTable::new(history)),
+        )?;
+    }
+    Ok(())
+}
+
+/// A virtual table that exposes [QueryHistory].
+pub struct QueryHistoryTable {
+    schema: SchemaRef,
+    history: QueryHistoryRef,
+}
+
+impl QueryHistoryTable {
+    pub fn new(history: QueryHistoryRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_query_history()),
+            history,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for QueryHistoryTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("QueryHistoryTable does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let schema_ref = self.schema.clone();
+        let columns = query_records_to_record_batch(self.history.records());
+
+        let stream = stream!({
+            yield RecordBatch::new(schema_ref, columns);
+        });
+
+        let stream = Box::pin(TablesRecordBatchStream::new(
+            self.schema.clone(),
+            Box::pin(stream),
+        ));
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+}
+
+fn query_records_to_record_batch(records: Vec<QueryRecord>) -> Vec<VectorRef> {
+    vec![
+        Arc::new(TimestampMillisecondVector::from_vec(
+            records.iter().map(|r| r.start_millis).collect(),
+        )),
+        Arc::new(StringVector::from(
+            records
+                .iter()
+                .map(|r| format!("{:016x}", r.sql_hash))
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt64Vector::from_vec(
+            records.iter().map(|r| r.duration_millis).collect(),
+        )),
+        Arc::new(UInt64Vector::from_vec(
+            records.iter().map(|r| r.rows).collect(),
+        )),
+        Arc::new(StringVector::from(
+            records.iter().map(|r| r.user.as_str()).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            records
+                .iter()
+                .map(|r| r.protocol.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(BooleanVector::from(
+            records.iter().map(|r| r.success).collect::<Vec<_>>(),
+        )),
+    ]
+}
+
+fn build_schema_for_query_history() -> Schema {
+    let cols = vec![
+        ColumnSchema::new(
+            "start_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+        ColumnSchema::new("sql_hash", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("duration_ms", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("rows", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("username", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("protocol", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("success", ConcreteDataType::boolean_datatype(), false),
+    ];
+    Schema::new(cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::SessionContext;
+    use common_recordbatch::RecordBatches;
+    use datatypes::value::Value;
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::resource_usage::ResourceAccountant;
+
+    #[test]
+    fn test_ring_buffer() {
+        let history = Arc::new(QueryHistory::new(2));
+        for i in 0..3 {
+            history
+                .start(&format!("select {i}"), "greptime", "mysql")
+                .finish(i, true);
+        }
+
+        let records = history.records();
+        assert_eq!(2, records.len());
+        assert_eq!(1, records[0].rows);
+        assert_eq!(2, records[1].rows);
+        assert_eq!(sql_hash("select 2"), records[1].sql_hash);
+        assert_ne!(records[0].sql_hash, records[1].sql_hash);
+
+        let disabled = Arc::new(QueryHistory::new(0));
+        disabled
+            .start("select 1", "greptime", "mysql")
+            .finish(1, true);
+        assert!(disabled.records().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_finish_with_stream_output() {
+        let history = Arc::new(QueryHistory::new(10));
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::uint64_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(UInt64Vector::from_slice([1, 2, 3])) as _],
+        )
+        .unwrap();
+        let batches = RecordBatches::try_new(schema, vec![batch.clone(), batch]).unwrap();
+
+        let accountant = Arc::new(ResourceAccountant::default());
+        let output = history
+            .start("select n from t", "root", "http")
+            .with_accountant(accountant.clone(), "greptime", "public")
+            .finish_with_output(Output::Stream(batches.as_stream()));
+        // Not recorded until the stream is consumed.
+        assert!(history.records().is_empty());
+        let Output::Stream(mut stream) = output else {
+            unreachable!()
+        };
+        while stream.next().await.is_some() {}
+
+        let records = history.records();
+        assert_eq!(1, records.len());
+        assert_eq!(6, records[0].rows);
+        assert_eq!("root", records[0].user);
+        assert_eq!("http", records[0].protocol);
+        assert!(records[0].success);
+
+        let usages = accountant.usages();
+        assert_eq!(1, usages.len());
+        assert_eq!(1, usages[0].queries);
+        assert_eq!(6, usages[0].returned_rows);
+    }
+
+    #[tokio::test]
+    async fn test_query_history_table() {
+        let history = Arc::new(QueryHistory::new(10));
+        history
+            .start("select 1", "greptime", "grpc")
+            .finish(1, false);
+
+        let table = QueryHistoryTable::new(history);
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let session_ctx = SessionContext::new();
+        let mut stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert_eq!(7, batch.num_columns());
+        assert_eq!(
+            format!("{:016x}", sql_hash("select 1")),
+            batch.column(1).get_ref(0).as_string().unwrap().unwrap()
+        );
+        assert_eq!(Value::UInt64(1), batch.column(3).get(0));
+        assert_eq!(Value::Boolean(false), batch.column(6).get(0));
+    }
+}
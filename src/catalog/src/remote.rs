@@ -12,23 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub use client::MetaKvBackend;
 use futures::Stream;
 use futures_util::StreamExt;
 pub use manager::{RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider};
+use snafu::ResultExt;
 
-use crate::error::Error;
+use crate::error::{Error, InvalidCatalogValueSnafu};
+use crate::helper::{build_schema_prefix, build_table_global_prefix, SchemaKey, TableGlobalKey};
 
 mod client;
 mod manager;
 
+/// Default max number of entries read from the backend in one page.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
 #[derive(Debug, Clone)]
 pub struct Kv(pub Vec<u8>, pub Vec<u8>);
 
+/// A page of a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Token to read the next page from, `None` if this is the last page.
+    pub next_token: Option<Vec<u8>>,
+}
+
 pub type ValueIter<'a, E> = Pin<Box<dyn Stream<Item = Result<Kv, E>> + Send + 'a>>;
 
 #[async_trait::async_trait]
@@ -59,6 +74,38 @@ pub trait KvBackend: Send + Sync {
         self.delete_range(key, &[]).await
     }
 
+    /// Reads at most `limit` entries whose keys are prefixed with `prefix`, from the `token`
+    /// returned by the previous page, or from the first key if `token` is `None`.
+    ///
+    /// Default implementation scans the `range` from the first key, backends supporting
+    /// range reads with limits should override it.
+    async fn range_page(
+        &self,
+        prefix: &[u8],
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<Kv>, Error> {
+        let mut iter = self.range(prefix);
+        let mut items = Vec::with_capacity(limit.min(DEFAULT_PAGE_SIZE));
+        while let Some(r) = iter.next().await {
+            let kv = r?;
+            if token.map_or(false, |token| kv.0.as_slice() < token) {
+                continue;
+            }
+            if items.len() >= limit {
+                return Ok(Page {
+                    items,
+                    next_token: Some(kv.0),
+                });
+            }
+            items.push(kv);
+        }
+        Ok(Page {
+            items,
+            next_token: None,
+        })
+    }
+
     /// Default get is implemented based on `range` method.
     async fn get(&self, key: &[u8]) -> Result<Option<Kv>, Error> {
         let mut iter = self.range(key);
@@ -74,6 +121,118 @@ pub trait KvBackend: Send + Sync {
 
 pub type KvBackendRef = Arc<dyn KvBackend>;
 
+/// Lists at most `limit` schemas of the catalog in the `backend`, from the `token` returned
+/// by the previous page.
+pub async fn list_schemas(
+    backend: &dyn KvBackend,
+    catalog_name: &str,
+    token: Option<&[u8]>,
+    limit: usize,
+) -> Result<Page<String>, Error> {
+    let schema_prefix = build_schema_prefix(catalog_name);
+    let page = backend
+        .range_page(schema_prefix.as_bytes(), token, limit)
+        .await?;
+    let items = page
+        .items
+        .iter()
+        .map(|Kv(k, _)| {
+            SchemaKey::parse(String::from_utf8_lossy(k))
+                .map(|key| key.schema_name)
+                .context(InvalidCatalogValueSnafu)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Page {
+        items,
+        next_token: page.next_token,
+    })
+}
+
+/// Lists at most `limit` tables of the schema in the `backend`, from the `token` returned
+/// by the previous page.
+pub async fn list_tables(
+    backend: &dyn KvBackend,
+    catalog_name: &str,
+    schema_name: &str,
+    token: Option<&[u8]>,
+    limit: usize,
+) -> Result<Page<String>, Error> {
+    let table_prefix = build_table_global_prefix(catalog_name, schema_name);
+    let page = backend
+        .range_page(table_prefix.as_bytes(), token, limit)
+        .await?;
+    let items = page
+        .items
+        .iter()
+        .map(|Kv(k, _)| {
+            TableGlobalKey::parse(String::from_utf8_lossy(k))
+                .map(|key| key.table_name)
+                .context(InvalidCatalogValueSnafu)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Page {
+        items,
+        next_token: page.next_token,
+    })
+}
+
+/// Returns a page of at most `limit` names, from the `token` returned by the previous page.
+/// The token is the first name of the next page, the same as the token of [KvBackend::range_page].
+pub fn paginate_names(mut names: Vec<String>, token: Option<&[u8]>, limit: usize) -> Page<String> {
+    names.sort_unstable();
+    names.dedup();
+    let start = token.map_or(0, |token| {
+        names.partition_point(|name| name.as_bytes() < token)
+    });
+    let mut items = names.split_off(start);
+    let next_token = if items.len() > limit {
+        items
+            .split_off(limit)
+            .into_iter()
+            .next()
+            .map(String::into_bytes)
+    } else {
+        None
+    };
+    Page { items, next_token }
+}
+
+/// How long a catalog found missing in the backend is considered missing.
+const MISSING_CATALOG_TTL: Duration = Duration::from_secs(10);
+
+/// Names of the catalogs recently found missing in the backend, so that looking up
+/// missing catalogs, e.g. by mistyped names, doesn't read the backend every time.
+#[derive(Debug, Default)]
+pub struct MissingCatalogs {
+    missing: Mutex<HashMap<String, Instant>>,
+}
+
+impl MissingCatalogs {
+    /// Returns true if the catalog `name` is found missing recently.
+    pub fn contains(&self, name: &str) -> bool {
+        let mut missing = self.missing.lock().unwrap();
+        match missing.get(name) {
+            Some(found_at) if found_at.elapsed() < MISSING_CATALOG_TTL => true,
+            Some(_) => {
+                let _ = missing.remove(name);
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn insert(&self, name: &str) {
+        let mut missing = self.missing.lock().unwrap();
+        // Expired names are removed, so the map doesn't grow with mistyped names.
+        missing.retain(|_, found_at| found_at.elapsed() < MISSING_CATALOG_TTL);
+        let _ = missing.insert(name.to_string(), Instant::now());
+    }
+
+    pub fn remove(&self, name: &str) {
+        let _ = self.missing.lock().unwrap().remove(name);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use async_stream::stream;
@@ -128,4 +287,37 @@ mod tests {
         let result = backend.get(3.to_string().as_bytes()).await;
         assert!(result.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_range_page() {
+        let backend = MockKvBackend {};
+        let keys = |page: &Page<Kv>| page.items.iter().map(|kv| kv.0.clone()).collect::<Vec<_>>();
+
+        let page = backend.range_page(b"", None, 2).await.unwrap();
+        assert_eq!(vec![b"0".to_vec(), b"1".to_vec()], keys(&page));
+        assert_eq!(Some(b"2".to_vec()), page.next_token);
+
+        let page = backend
+            .range_page(b"", page.next_token.as_deref(), 2)
+            .await
+            .unwrap();
+        assert_eq!(vec![b"2".to_vec()], keys(&page));
+        assert!(page.next_token.is_none());
+    }
+
+    #[test]
+    fn test_paginate_names() {
+        let names = vec!["c".to_string(), "a".to_string(), "b".to_string()];
+        let page = paginate_names(names.clone(), None, 2);
+        assert_eq!(vec!["a", "b"], page.items);
+        assert_eq!(Some(b"c".to_vec()), page.next_token);
+
+        let page = paginate_names(names.clone(), page.next_token.as_deref(), 2);
+        assert_eq!(vec!["c"], page.items);
+        assert!(page.next_token.is_none());
+
+        let page = paginate_names(names, None, usize::MAX);
+        assert_eq!(vec!["a", "b", "c"], page.items);
+        assert!(page.next_token.is_none());
+    }
 }
@@ -18,11 +18,12 @@ use std::sync::Arc;
 use async_stream::stream;
 use common_telemetry::info;
 use meta_client::client::MetaClient;
+use meta_client::rpc::util::get_prefix_end_key;
 use meta_client::rpc::{CompareAndPutRequest, DeleteRangeRequest, PutRequest, RangeRequest};
 use snafu::ResultExt;
 
 use crate::error::{Error, MetaSrvSnafu};
-use crate::remote::{Kv, KvBackend, ValueIter};
+use crate::remote::{Kv, KvBackend, Page, ValueIter, DEFAULT_PAGE_SIZE};
 #[derive(Debug)]
 pub struct MetaKvBackend {
     pub client: Arc<MetaClient>,
@@ -38,19 +39,54 @@ impl KvBackend for MetaKvBackend {
         'a: 'b,
     {
         let key = key.to_vec();
+        // Reads the range by pages, so a large range is not loaded into memory at once.
         Box::pin(stream!({
-            let mut resp = self
-                .client
-                .range(RangeRequest::new().with_prefix(key))
-                .await
-                .context(MetaSrvSnafu)?;
-            let kvs = resp.take_kvs();
-            for mut kv in kvs.into_iter() {
-                yield Ok(Kv(kv.take_key(), kv.take_value()))
+            let mut token = None;
+            loop {
+                let page = self
+                    .range_page(&key, token.as_deref(), DEFAULT_PAGE_SIZE)
+                    .await?;
+                for kv in page.items {
+                    yield Ok(kv)
+                }
+                token = page.next_token;
+                if token.is_none() {
+                    break;
+                }
             }
         }))
     }
 
+    async fn range_page(
+        &self,
+        prefix: &[u8],
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<Kv>, Error> {
+        // A limit of 0 means no limit to metasrv.
+        let limit = limit.max(1);
+        let start = token.filter(|token| *token > prefix).unwrap_or(prefix);
+        let req = RangeRequest::new()
+            .with_range(start.to_vec(), get_prefix_end_key(prefix))
+            .with_limit(limit as i64);
+        let mut resp = self.client.range(req).await.context(MetaSrvSnafu)?;
+        let items = resp
+            .take_kvs()
+            .into_iter()
+            .map(|mut kv| Kv(kv.take_key(), kv.take_value()))
+            .collect::<Vec<_>>();
+        // The next page starts from the smallest key greater than the last key.
+        let next_token = match items.last() {
+            Some(Kv(key, _)) if resp.more() => {
+                let mut token = key.clone();
+                token.push(0);
+                Some(token)
+            }
+            _ => None,
+        };
+        Ok(Page { items, next_token })
+    }
+
     async fn get(&self, key: &[u8]) -> Result<Option<Kv>, Error> {
         let mut response = self
             .client
@@ -20,7 +20,7 @@ use std::sync::Arc;
 use arc_swap::ArcSwap;
 use async_stream::stream;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
-use common_telemetry::{debug, info};
+use common_telemetry::{debug, error, info};
 use futures::Stream;
 use futures_util::StreamExt;
 use snafu::{OptionExt, ResultExt};
@@ -39,11 +39,13 @@ use crate::helper::{
     build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, CatalogValue,
     SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue, TableRegionalKey, TableRegionalValue,
 };
-use crate::remote::{Kv, KvBackendRef};
+use crate::remote::{self, Kv, KvBackendRef, MissingCatalogs, Page};
 use crate::{
-    handle_system_table_request, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
-    DeregisterTableRequest, RegisterSchemaRequest, RegisterSystemTableRequest,
-    RegisterTableRequest, RenameTableRequest, SchemaProvider, SchemaProviderRef,
+    format_full_table_name, handle_system_table_request, open_tables_concurrently,
+    AlterSchemaRequest, BackgroundOpenTables, CatalogList, CatalogManager, CatalogProvider,
+    CatalogProviderRef, CreateCatalogRequest, DeregisterTableRequest, HotRegionCheckerRef,
+    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
+    SchemaProvider, SchemaProviderRef, DEFAULT_OPEN_TABLES_CONCURRENCY,
 };
 
 /// Catalog manager based on metasrv.
@@ -51,9 +53,15 @@ pub struct RemoteCatalogManager {
     node_id: u64,
     backend: KvBackendRef,
     catalogs: Arc<ArcSwap<HashMap<String, CatalogProviderRef>>>,
+    missing_catalogs: Arc<MissingCatalogs>,
     engine: TableEngineRef,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
     mutex: Arc<Mutex<()>>,
+    /// Max number of tables opened concurrently on startup.
+    open_tables_concurrency: usize,
+    /// Opens hot tables on startup and the others in background if set.
+    hot_region_checker: Option<HotRegionCheckerRef>,
+    background_open_tables: Arc<BackgroundOpenTables>,
 }
 
 impl RemoteCatalogManager {
@@ -63,17 +71,73 @@ impl RemoteCatalogManager {
             node_id,
             backend,
             catalogs: Default::default(),
+            missing_catalogs: Default::default(),
             system_table_requests: Default::default(),
             mutex: Default::default(),
+            open_tables_concurrency: DEFAULT_OPEN_TABLES_CONCURRENCY,
+            hot_region_checker: None,
+            background_open_tables: Arc::new(BackgroundOpenTables::default()),
         }
     }
 
+    /// Sets the max number of tables opened concurrently on startup, each replays the WAL
+    /// of its regions.
+    pub fn with_open_tables_concurrency(mut self, concurrency: usize) -> Self {
+        self.open_tables_concurrency = concurrency;
+        self
+    }
+
+    /// Opens tables with regions `checker` tells hot on startup, and the other tables in
+    /// background, which are served once opened.
+    pub fn with_hot_region_checker(mut self, checker: Option<HotRegionCheckerRef>) -> Self {
+        self.hot_region_checker = checker;
+        self
+    }
+
     fn build_catalog_key(&self, catalog_name: impl AsRef<str>) -> CatalogKey {
         CatalogKey {
             catalog_name: catalog_name.as_ref().to_string(),
         }
     }
 
+    /// Loads catalog `name` from metasrv if it exists there, returns the loaded catalog.
+    fn refresh_catalog(&self, name: &str) -> Result<Option<CatalogProviderRef>> {
+        if self.missing_catalogs.contains(name) {
+            return Ok(None);
+        }
+        let key = self.build_catalog_key(name).to_string();
+        let name = name.to_string();
+        let catalog = self.new_catalog_provider(&name);
+        let backend = self.backend.clone();
+        let catalogs = self.catalogs.clone();
+        let missing_catalogs = self.missing_catalogs.clone();
+        let mutex = self.mutex.clone();
+
+        std::thread::spawn(|| {
+            common_runtime::block_on_write(async move {
+                if backend.get(key.as_bytes()).await?.is_none() {
+                    missing_catalogs.insert(&name);
+                    return Ok(None);
+                }
+
+                // Catalogs are also registered under the mutex, so none of them is lost.
+                let _guard = mutex.lock().await;
+                let prev_catalogs = catalogs.load();
+                if let Some(prev) = prev_catalogs.get(&name) {
+                    return Ok(Some(prev.clone()));
+                }
+                let mut new_catalogs = HashMap::with_capacity(prev_catalogs.len() + 1);
+                new_catalogs.clone_from(&prev_catalogs);
+                info!("Loaded catalog {} from metasrv", name);
+                new_catalogs.insert(name, catalog.clone());
+                catalogs.store(Arc::new(new_catalogs));
+                Ok(Some(catalog))
+            })
+        })
+        .join()
+        .unwrap()
+    }
+
     fn new_catalog_provider(&self, catalog_name: &str) -> CatalogProviderRef {
         Arc::new(RemoteCatalogProvider {
             node_id: self.node_id,
@@ -240,22 +304,98 @@ impl RemoteCatalogManager {
         catalog_name: &'a str,
         schema_name: &'a str,
         schema: SchemaProviderRef,
-        mut max_table_id: TableId,
+        max_table_id: TableId,
     ) -> Result<()> {
         info!("initializing tables in {}.{}", catalog_name, schema_name);
-        let mut table_num = 0;
+        let mut entries = vec![];
         let mut tables = self.iter_remote_tables(catalog_name, schema_name).await;
         while let Some(r) = tables.next().await {
-            let (table_key, table_value) = r?;
-            let table_ref = self.open_or_create_table(&table_key, &table_value).await?;
-            schema.register_table(table_key.table_name.to_string(), table_ref)?;
-            info!("Registered table {}", &table_key.table_name);
-            max_table_id = max_table_id.max(table_value.table_id());
-            table_num += 1;
+            entries.push(r?);
         }
+        let table_num = entries.len();
+        let max_table_id = entries
+            .iter()
+            .map(|(_, table_value)| table_value.table_id())
+            .fold(max_table_id, TableId::max);
+
+        let node_id = self.node_id;
+        let (hot_tables, cold_tables): (Vec<_>, Vec<_>) = match &self.hot_region_checker {
+            Some(checker) => entries.into_iter().partition(|(_, table_value)| {
+                let table_id = table_value.table_id();
+                table_value.regions_id_map[&node_id]
+                    .iter()
+                    .any(|region_number| checker(table_id, *region_number))
+            }),
+            None => (entries, vec![]),
+        };
+        let (engine, schema) = (&self.engine, &schema);
+        open_tables_concurrently(
+            hot_tables,
+            self.open_tables_concurrency,
+            |(table_key, table_value)| async move {
+                Self::open_and_register_table(engine, node_id, schema, &table_key, &table_value)
+                    .await
+            },
+        )
+        .await?;
+
+        if !cold_tables.is_empty() {
+            info!(
+                "Opening {} cold tables in {}.{} in background",
+                cold_tables.len(),
+                catalog_name,
+                schema_name
+            );
+            let full_table_name = |table_key: &TableGlobalKey| {
+                format_full_table_name(
+                    &table_key.catalog_name,
+                    &table_key.schema_name,
+                    &table_key.table_name,
+                )
+            };
+            self.background_open_tables.start(
+                cold_tables
+                    .iter()
+                    .map(|(table_key, _)| full_table_name(table_key)),
+            );
+            let engine = self.engine.clone();
+            let schema = schema.clone();
+            let background_open_tables = self.background_open_tables.clone();
+            let concurrency = self.open_tables_concurrency;
+            common_runtime::spawn_bg(async move {
+                let (engine, schema) = (&engine, &schema);
+                let background_open_tables = &background_open_tables;
+                let result = open_tables_concurrently(
+                    cold_tables,
+                    concurrency,
+                    |(table_key, table_value)| async move {
+                        let result = Self::open_and_register_table(
+                            engine,
+                            node_id,
+                            schema,
+                            &table_key,
+                            &table_value,
+                        )
+                        .await;
+                        // Other tables are still opened, the error is returned by DDL on the
+                        // table.
+                        if let Err(e) = &result {
+                            error!(e; "Failed to open table {} in background", table_key);
+                        }
+                        background_open_tables.finish(&full_table_name(&table_key), &result);
+                        Ok(())
+                    },
+                )
+                .await;
+                if let Err(e) = result {
+                    error!(e; "Failed to open tables in background");
+                }
+            });
+        }
+
         info!(
-            "initialized tables in {}.{}, total: {}",
-            catalog_name, schema_name, table_num
+            "initialized tables in {}.{}, total: {}, max table id: {}",
+            catalog_name, schema_name, table_num, max_table_id
         );
         Ok(())
     }
@@ -272,7 +412,7 @@ impl RemoteCatalogManager {
         self.backend
             .set(
                 schema_key.as_bytes(),
-                &SchemaValue {}
+                &SchemaValue::default()
                     .as_bytes()
                     .context(InvalidCatalogValueSnafu)?,
             )
@@ -295,8 +435,22 @@ impl RemoteCatalogManager {
         Ok(default_catalog)
     }
 
+    async fn open_and_register_table(
+        engine: &TableEngineRef,
+        node_id: u64,
+        schema: &SchemaProviderRef,
+        table_key: &TableGlobalKey,
+        table_value: &TableGlobalValue,
+    ) -> Result<()> {
+        let table_ref = Self::open_or_create_table(engine, node_id, table_key, table_value).await?;
+        schema.register_table(table_key.table_name.to_string(), table_ref)?;
+        info!("Registered table {}", &table_key.table_name);
+        Ok(())
+    }
+
     async fn open_or_create_table(
-        &self,
+        engine: &TableEngineRef,
+        node_id: u64,
         table_key: &TableGlobalKey,
         table_value: &TableGlobalValue,
     ) -> Result<TableRef> {
@@ -317,7 +471,7 @@ impl RemoteCatalogManager {
         } = table_value;
 
         // unwrap safety: checked in yielding this table when `iter_remote_tables`
-        let region_numbers = regions_id_map.get(&self.node_id).unwrap();
+        let region_numbers = regions_id_map.get(&node_id).unwrap();
 
         let request = OpenTableRequest {
             catalog_name: catalog_name.clone(),
@@ -326,8 +480,7 @@ impl RemoteCatalogManager {
             table_id,
             region_numbers: region_numbers.clone(),
         };
-        match self
-            .engine
+        match engine
             .open_table(&context, request)
             .await
             .with_context(|_| OpenTableSnafu {
@@ -368,7 +521,7 @@ impl RemoteCatalogManager {
                     table_options: meta.options.clone(),
                 };
 
-                self.engine
+                engine
                     .create_table(&context, req)
                     .await
                     .context(CreateTableSnafu {
@@ -438,6 +591,18 @@ impl CatalogManager for RemoteCatalogManager {
         .fail()
     }
 
+    async fn create_catalog(&self, request: CreateCatalogRequest) -> Result<bool> {
+        let catalog_name = request.catalog;
+        if self.catalog(&catalog_name)?.is_some() {
+            return Ok(false);
+        }
+        let catalog = self.new_catalog_provider(&catalog_name);
+        let schema = self.new_schema_provider(&catalog_name, DEFAULT_SCHEMA_NAME);
+        catalog.register_schema(DEFAULT_SCHEMA_NAME.to_string(), schema)?;
+        self.register_catalog(catalog_name, catalog)?;
+        Ok(true)
+    }
+
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
         let catalog_name = request.catalog;
         let schema_name = request.schema;
@@ -449,6 +614,13 @@ impl CatalogManager for RemoteCatalogManager {
         Ok(true)
     }
 
+    async fn alter_schema(&self, _request: AlterSchemaRequest) -> Result<bool> {
+        UnimplementedSnafu {
+            operation: "alter schema",
+        }
+        .fail()
+    }
+
     async fn rename_table(&self, _request: RenameTableRequest) -> Result<bool> {
         UnimplementedSnafu {
             operation: "rename table",
@@ -487,6 +659,30 @@ impl CatalogManager for RemoteCatalogManager {
             })?;
         schema.table(table_name)
     }
+
+    fn ensure_table_opened(&self, catalog: &str, schema: &str, table_name: &str) -> Result<()> {
+        self.background_open_tables
+            .ensure_opened(catalog, schema, table_name)
+    }
+
+    async fn list_schemas(
+        &self,
+        catalog: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<String>> {
+        remote::list_schemas(self.backend.as_ref(), catalog, token, limit).await
+    }
+
+    async fn list_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Page<String>> {
+        remote::list_tables(self.backend.as_ref(), catalog, schema, token, limit).await
+    }
 }
 
 impl CatalogList for RemoteCatalogManager {
@@ -503,10 +699,12 @@ impl CatalogList for RemoteCatalogManager {
         let backend = self.backend.clone();
         let mutex = self.mutex.clone();
         let catalogs = self.catalogs.clone();
+        let missing_catalogs = self.missing_catalogs.clone();
 
         std::thread::spawn(|| {
             common_runtime::block_on_write(async move {
                 let _guard = mutex.lock().await;
+                missing_catalogs.remove(&name);
                 backend
                     .set(
                         key.as_bytes(),
@@ -534,7 +732,11 @@ impl CatalogList for RemoteCatalogManager {
 
     /// Read catalog info of given name from metasrv.
     fn catalog(&self, name: &str) -> Result<Option<CatalogProviderRef>> {
-        Ok(self.catalogs.load().get(name).cloned())
+        if let Some(catalog) = self.catalogs.load().get(name) {
+            return Ok(Some(catalog.clone()));
+        }
+        // The catalog may be created by other nodes after this node started.
+        self.refresh_catalog(name)
     }
 }
 
@@ -633,7 +835,7 @@ impl CatalogProvider for RemoteCatalogProvider {
                 backend
                     .set(
                         key.as_bytes(),
-                        &SchemaValue {}
+                        &SchemaValue::default()
                             .as_bytes()
                             .context(InvalidCatalogValueSnafu)?,
                     )
@@ -0,0 +1,308 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resources consumed by each user in each database, exposed as the
+//! `system.resource_usage` table.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use async_stream::stream;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME};
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::RecordBatch;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, UInt64Vector, VectorRef};
+use table::metadata::TableInfoRef;
+use table::table::scan::SimpleTableScan;
+use table::Table;
+
+use crate::error::Result;
+use crate::tables::TablesRecordBatchStream;
+use crate::CatalogManager;
+
+pub const RESOURCE_USAGE_TABLE_NAME: &str = "resource_usage";
+
+/// Resources consumed by a user in a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub user: String,
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub queries: u64,
+    /// Bytes of data read from tables.
+    pub scanned_bytes: u64,
+    pub returned_rows: u64,
+    pub query_time_millis: u64,
+}
+
+#[derive(Debug, Default)]
+struct UsageCounters {
+    queries: AtomicU64,
+    scanned_bytes: Arc<AtomicU64>,
+    returned_rows: AtomicU64,
+    query_time_millis: AtomicU64,
+}
+
+type UsageKey = (String, String, String);
+
+/// Accumulates [ResourceUsage] of each user and database since the process started.
+#[derive(Debug, Default)]
+pub struct ResourceAccountant {
+    usages: RwLock<HashMap<UsageKey, Arc<UsageCounters>>>,
+}
+
+pub type ResourceAccountantRef = Arc<ResourceAccountant>;
+
+impl ResourceAccountant {
+    fn counters(&self, user: &str, catalog: &str, schema: &str) -> Arc<UsageCounters> {
+        let key = (user.to_string(), catalog.to_string(), schema.to_string());
+        if let Some(counters) = self.usages.read().unwrap().get(&key) {
+            return counters.clone();
+        }
+        self.usages.write().unwrap().entry(key).or_default().clone()
+    }
+
+    /// Returns the counter of bytes scanned by the user in the database, scans add the
+    /// bytes they read to it.
+    pub fn scanned_bytes_counter(&self, user: &str, catalog: &str, schema: &str) -> Arc<AtomicU64> {
+        self.counters(user, catalog, schema).scanned_bytes.clone()
+    }
+
+    pub fn record_query(
+        &self,
+        user: &str,
+        catalog: &str,
+        schema: &str,
+        returned_rows: u64,
+        query_time_millis: u64,
+    ) {
+        let counters = self.counters(user, catalog, schema);
+        counters.queries.fetch_add(1, Ordering::Relaxed);
+        counters
+            .returned_rows
+            .fetch_add(returned_rows, Ordering::Relaxed);
+        counters
+            .query_time_millis
+            .fetch_add(query_time_millis, Ordering::Relaxed);
+    }
+
+    /// Returns usages of all users, ordered by user and database.
+    pub fn usages(&self) -> Vec<ResourceUsage> {
+        let usages = self.usages.read().unwrap();
+        let mut usages = usages
+            .iter()
+            .map(|((user, catalog, schema), counters)| ResourceUsage {
+                user: user.clone(),
+                catalog_name: catalog.clone(),
+                schema_name: schema.clone(),
+                queries: counters.queries.load(Ordering::Relaxed),
+                scanned_bytes: counters.scanned_bytes.load(Ordering::Relaxed),
+                returned_rows: counters.returned_rows.load(Ordering::Relaxed),
+                query_time_millis: counters.query_time_millis.load(Ordering::Relaxed),
+            })
+            .collect::<Vec<_>>();
+        usages.sort_unstable_by(|a, b| {
+            (&a.user, &a.catalog_name, &a.schema_name).cmp(&(
+                &b.user,
+                &b.catalog_name,
+                &b.schema_name,
+            ))
+        });
+        usages
+    }
+}
+
+/// Registers the `resource_usage` table to the `system` schema of default catalog,
+/// does nothing if the schema is absent.
+pub fn register_resource_usage_table(
+    catalog_manager: &dyn CatalogManager,
+    accountant: ResourceAccountantRef,
+) -> Result<()> {
+    let Some(schema) = catalog_manager.schema(DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME)? else {
+        return Ok(());
+    };
+    if !schema.table_exist(RESOURCE_USAGE_TABLE_NAME)? {
+        let _ = schema.register_table(
+            RESOURCE_USAGE_TABLE_NAME.to_string(),
+            Arc::new(ResourceUsageTable::new(accountant)),
+        )?;
+    }
+    Ok(())
+}
+
+/// A virtual table that exposes [ResourceAccountant].
+pub struct ResourceUsageTable {
+    schema: SchemaRef,
+    accountant: ResourceAccountantRef,
+}
+
+impl ResourceUsageTable {
+    pub fn new(accountant: ResourceAccountantRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_resource_usage()),
+            accountant,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for ResourceUsageTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("ResourceUsageTable does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let schema_ref = self.schema.clone();
+        let columns = resource_usages_to_record_batch(self.accountant.usages());
+
+        let stream = stream!({
+            yield RecordBatch::new(schema_ref, columns);
+        });
+
+        let stream = Box::pin(TablesRecordBatchStream::new(
+            self.schema.clone(),
+            Box::pin(stream),
+        ));
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+}
+
+fn resource_usages_to_record_batch(usages: Vec<ResourceUsage>) -> Vec<VectorRef> {
+    let strings = |f: fn(&ResourceUsage) -> &str| -> VectorRef {
+        Arc::new(StringVector::from(usages.iter().map(f).collect::<Vec<_>>()))
+    };
+    let counters = |f: fn(&ResourceUsage) -> u64| -> VectorRef {
+        Arc::new(UInt64Vector::from_vec(usages.iter().map(f).collect()))
+    };
+
+    vec![
+        strings(|u| u.user.as_str()),
+        strings(|u| u.catalog_name.as_str()),
+        strings(|u| u.schema_name.as_str()),
+        counters(|u| u.queries),
+        counters(|u| u.scanned_bytes),
+        counters(|u| u.returned_rows),
+        counters(|u| u.query_time_millis),
+    ]
+}
+
+fn build_schema_for_resource_usage() -> Schema {
+    let cols = vec![
+        ColumnSchema::new("username", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("catalog", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("queries", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("scanned_bytes", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("returned_rows", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("query_time_ms", ConcreteDataType::uint64_datatype(), false),
+    ];
+    Schema::new(cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::SessionContext;
+    use datatypes::value::Value;
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn test_resource_accountant() {
+        let accountant = ResourceAccountant::default();
+        accountant.record_query("alice", "greptime", "public", 10, 5);
+        accountant.record_query("alice", "greptime", "public", 2, 3);
+        accountant.record_query("bob", "greptime", "public", 1, 1);
+        accountant
+            .scanned_bytes_counter("alice", "greptime", "public")
+            .fetch_add(1024, Ordering::Relaxed);
+        accountant
+            .scanned_bytes_counter("bob", "greptime", "test")
+            .fetch_add(16, Ordering::Relaxed);
+
+        let usages = accountant.usages();
+        assert_eq!(3, usages.len());
+        assert_eq!(
+            ResourceUsage {
+                user: "alice".to_string(),
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                queries: 2,
+                scanned_bytes: 1024,
+                returned_rows: 12,
+                query_time_millis: 8,
+            },
+            usages[0]
+        );
+        assert_eq!(
+            ("bob", "public", 1),
+            (
+                usages[1].user.as_str(),
+                usages[1].schema_name.as_str(),
+                usages[1].queries
+            )
+        );
+        assert_eq!(
+            ("test", 0, 16),
+            (
+                usages[2].schema_name.as_str(),
+                usages[2].queries,
+                usages[2].scanned_bytes
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_usage_table() {
+        let accountant = Arc::new(ResourceAccountant::default());
+        accountant.record_query("alice", "greptime", "public", 10, 5);
+
+        let table = ResourceUsageTable::new(accountant);
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let session_ctx = SessionContext::new();
+        let mut stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert_eq!(7, batch.num_columns());
+        assert_eq!(
+            "alice",
+            batch.column(0).get_ref(0).as_string().unwrap().unwrap()
+        );
+        assert_eq!(Value::UInt64(1), batch.column(3).get(0));
+        assert_eq!(Value::UInt64(10), batch.column(5).get(0));
+    }
+}
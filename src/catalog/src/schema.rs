@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use table::TableRef;
@@ -47,6 +48,16 @@ pub trait SchemaProvider: Sync + Send {
     /// If no matched table in the schema provider, return false.
     /// Otherwise, return true.
     fn table_exist(&self, name: &str) -> Result<bool>;
+
+    /// Returns the default options of tables created in this schema.
+    fn default_table_options(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Returns the key-value metadata of this schema, e.g. owner and description.
+    fn metadata(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
 }
 
 pub type SchemaProviderRef = Arc<dyn SchemaProvider>;
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use common_catalog::consts::{
@@ -233,17 +233,37 @@ fn build_primary_key_columns(entry_type: EntryType, key: &[u8]) -> HashMap<Strin
     m
 }
 
-pub fn build_schema_insert_request(catalog_name: String, schema_name: String) -> InsertRequest {
+pub fn build_catalog_insert_request(catalog_name: String) -> InsertRequest {
+    // The value of catalog entries is not used.
+    build_insert_request(EntryType::Catalog, catalog_name.as_bytes(), b"{}")
+}
+
+pub fn build_schema_insert_request(
+    catalog_name: String,
+    schema_name: String,
+    value: &SchemaEntryValue,
+) -> InsertRequest {
     let full_schema_name = format!("{catalog_name}.{schema_name}");
     build_insert_request(
         EntryType::Schema,
         full_schema_name.as_bytes(),
-        serde_json::to_string(&SchemaEntryValue {})
-            .unwrap()
-            .as_bytes(),
+        serde_json::to_string(value).unwrap().as_bytes(),
     )
 }
 
+pub(crate) fn build_schema_deletion_request(
+    catalog_name: &str,
+    schema_name: &str,
+) -> DeleteRequest {
+    let full_schema_name = format!("{catalog_name}.{schema_name}");
+    DeleteRequest {
+        key_column_values: build_primary_key_columns(
+            EntryType::Schema,
+            full_schema_name.as_bytes(),
+        ),
+    }
+}
+
 pub fn build_insert_request(entry_type: EntryType, key: &[u8], value: &[u8]) -> InsertRequest {
     let primary_key_columns = build_primary_key_columns(entry_type, key);
 
@@ -295,7 +315,9 @@ pub fn decode_system_catalog(
         }
         EntryType::Schema => {
             // As for schema entry, the key is a string with format: `<catalog_name>.<schema_name>`
-            // and the value is current not used.
+            // and the value is a JSON string with format:
+            // `{"default_table_options": {..}, "metadata": {..}}`, or `null` for schemas
+            // created before default table options are supported.
             let schema_parts = key.split('.').collect::<Vec<_>>();
             ensure!(
                 schema_parts.len() == 2,
@@ -303,9 +325,17 @@ pub fn decode_system_catalog(
                     key: Some(key.to_string())
                 }
             );
+            let schema_value = match value {
+                Some(value) => serde_json::from_slice::<Option<SchemaEntryValue>>(value)
+                    .context(ValueDeserializeSnafu)?
+                    .unwrap_or_default(),
+                None => SchemaEntryValue::default(),
+            };
             Ok(Entry::Schema(SchemaEntry {
                 catalog_name: schema_parts[0].to_string(),
                 schema_name: schema_parts[1].to_string(),
+                default_table_options: schema_value.default_table_options,
+                metadata: schema_value.metadata,
             }))
         }
 
@@ -373,10 +403,18 @@ pub struct CatalogEntry {
 pub struct SchemaEntry {
     pub catalog_name: String,
     pub schema_name: String,
+    pub default_table_options: BTreeMap<String, String>,
+    pub metadata: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct SchemaEntryValue;
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaEntryValue {
+    #[serde(default)]
+    pub default_table_options: BTreeMap<String, String>,
+    /// Key-value metadata of the schema, e.g. owner and description.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
 pub struct TableEntry {
@@ -439,6 +477,36 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_decode_schema_entry_value() {
+        let options = HashMap::from([("ttl".to_string(), "30d".to_string())]);
+        let value = serde_json::to_string(&SchemaEntryValue {
+            default_table_options: options.into_iter().collect(),
+            metadata: BTreeMap::from([("owner".to_string(), "alice".to_string())]),
+        })
+        .unwrap();
+        let entry = decode_system_catalog(
+            Some(EntryType::Schema as u8),
+            Some("some_catalog.some_schema".as_bytes()),
+            Some(value.as_bytes()),
+        )
+        .unwrap();
+        let Entry::Schema(e) = entry else { panic!("Unexpected type: {entry:?}") };
+        assert_eq!("30d", e.default_table_options["ttl"]);
+        assert_eq!("alice", e.metadata["owner"]);
+
+        // Value of schemas created before default table options are supported.
+        let entry = decode_system_catalog(
+            Some(EntryType::Schema as u8),
+            Some("some_catalog.some_schema".as_bytes()),
+            Some("null".as_bytes()),
+        )
+        .unwrap();
+        let Entry::Schema(e) = entry else { panic!("Unexpected type: {entry:?}") };
+        assert!(e.default_table_options.is_empty());
+        assert!(e.metadata.is_empty());
+    }
+
     #[test]
     pub fn test_decode_table() {
         let entry = decode_system_catalog(
@@ -15,6 +15,7 @@
 // The `tables` table in system catalog keeps a record of all tables created by user.
 
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -40,8 +41,8 @@ use table::{Table, TableRef};
 
 use crate::error::{self, Error, InsertCatalogRecordSnafu, Result as CatalogResult};
 use crate::system::{
-    build_schema_insert_request, build_table_deletion_request, build_table_insert_request,
-    SystemCatalogTable,
+    build_catalog_insert_request, build_schema_deletion_request, build_schema_insert_request,
+    build_table_deletion_request, build_table_insert_request, SchemaEntryValue, SystemCatalogTable,
 };
 use crate::{
     CatalogListRef, CatalogProvider, DeregisterTableRequest, SchemaProvider, SchemaProviderRef,
@@ -78,6 +79,10 @@ impl Table for Tables {
         unreachable!("Tables does not support table_info method")
     }
 
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
     async fn scan(
         &self,
         _projection: Option<&Vec<usize>>,
@@ -182,11 +187,128 @@ fn tables_to_record_batch(
     ]
 }
 
+/// Schemata holds all schemas and their metadata.
+pub struct Schemata {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+}
+
+impl Schemata {
+    pub fn new(catalogs: CatalogListRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_schemata()),
+            catalogs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for Schemata {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("Schemata does not support table_info method")
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let catalogs = self.catalogs.clone();
+        let schema_ref = self.schema.clone();
+
+        let stream = stream!({
+            for catalog_name in catalogs
+                .catalog_names()
+                .map_err(BoxedError::new)
+                .context(TablesRecordBatchSnafu)?
+            {
+                let catalog = catalogs
+                    .catalog(&catalog_name)
+                    .map_err(BoxedError::new)
+                    .context(TablesRecordBatchSnafu)?
+                    .unwrap();
+                let mut schemas = Vec::new();
+                for schema_name in catalog
+                    .schema_names()
+                    .map_err(BoxedError::new)
+                    .context(TablesRecordBatchSnafu)?
+                {
+                    let schema = catalog
+                        .schema(&schema_name)
+                        .map_err(BoxedError::new)
+                        .context(TablesRecordBatchSnafu)?
+                        .unwrap();
+                    let metadata: BTreeMap<_, _> = schema.metadata().into_iter().collect();
+                    // Safety: a map of strings is always serializable.
+                    schemas.push((schema_name, serde_json::to_string(&metadata).unwrap()));
+                }
+
+                let vec = schemata_to_record_batch(&catalog_name, schemas);
+                yield RecordBatch::new(schema_ref.clone(), vec);
+            }
+        });
+
+        let stream = Box::pin(TablesRecordBatchStream {
+            schema: self.schema.clone(),
+            stream: Box::pin(stream),
+        });
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+}
+
+/// Convert schemas and their metadata in json to `RecordBatch`.
+fn schemata_to_record_batch(catalog_name: &str, schemas: Vec<(String, String)>) -> Vec<VectorRef> {
+    let mut catalog_vec = ConcreteDataType::string_datatype().create_mutable_vector(schemas.len());
+    let mut schema_vec = ConcreteDataType::string_datatype().create_mutable_vector(schemas.len());
+    let mut metadata_vec = ConcreteDataType::string_datatype().create_mutable_vector(schemas.len());
+
+    for (schema_name, metadata) in schemas {
+        // Safety: All these vectors are string type.
+        catalog_vec
+            .push_value_ref(ValueRef::String(catalog_name))
+            .unwrap();
+        schema_vec
+            .push_value_ref(ValueRef::String(&schema_name))
+            .unwrap();
+        metadata_vec
+            .push_value_ref(ValueRef::String(&metadata))
+            .unwrap();
+    }
+
+    vec![
+        catalog_vec.to_vector(),
+        schema_vec.to_vector(),
+        metadata_vec.to_vector(),
+    ]
+}
+
 pub struct TablesRecordBatchStream {
     schema: SchemaRef,
     stream: Pin<Box<dyn Stream<Item = RecordBatchResult<RecordBatch>> + Send>>,
 }
 
+impl TablesRecordBatchStream {
+    pub fn new(
+        schema: SchemaRef,
+        stream: Pin<Box<dyn Stream<Item = RecordBatchResult<RecordBatch>> + Send>>,
+    ) -> Self {
+        Self { schema, stream }
+    }
+}
+
 impl Stream for TablesRecordBatchStream {
     type Item = RecordBatchResult<RecordBatch>;
 
@@ -203,6 +325,7 @@ impl RecordBatchStream for TablesRecordBatchStream {
 
 pub struct InformationSchema {
     pub tables: Arc<Tables>,
+    pub schemata: Arc<Schemata>,
     pub system: Arc<SystemCatalogTable>,
 }
 
@@ -214,6 +337,7 @@ impl SchemaProvider for InformationSchema {
     fn table_names(&self) -> Result<Vec<String>, Error> {
         Ok(vec![
             "tables".to_string(),
+            "schemata".to_string(),
             SYSTEM_CATALOG_TABLE_NAME.to_string(),
         ])
     }
@@ -221,6 +345,8 @@ impl SchemaProvider for InformationSchema {
     fn table(&self, name: &str) -> Result<Option<TableRef>, Error> {
         if name.eq_ignore_ascii_case("tables") {
             Ok(Some(self.tables.clone()))
+        } else if name.eq_ignore_ascii_case("schemata") {
+            Ok(Some(self.schemata.clone()))
         } else if name.eq_ignore_ascii_case(SYSTEM_CATALOG_TABLE_NAME) {
             Ok(Some(self.system.clone()))
         } else {
@@ -246,6 +372,7 @@ impl SchemaProvider for InformationSchema {
 
     fn table_exist(&self, name: &str) -> Result<bool, Error> {
         Ok(name.eq_ignore_ascii_case("tables")
+            || name.eq_ignore_ascii_case("schemata")
             || name.eq_ignore_ascii_case(SYSTEM_CATALOG_TABLE_NAME))
     }
 }
@@ -261,7 +388,8 @@ impl SystemCatalog {
         engine: TableEngineRef,
     ) -> Self {
         let schema = InformationSchema {
-            tables: Arc::new(Tables::new(catalogs, engine.name().to_string())),
+            tables: Arc::new(Tables::new(catalogs.clone(), engine.name().to_string())),
+            schemata: Arc::new(Schemata::new(catalogs)),
             system: Arc::new(system),
         };
         Self {
@@ -299,18 +427,40 @@ impl SystemCatalog {
             })
     }
 
+    pub async fn register_catalog(&self, catalog: String) -> crate::error::Result<usize> {
+        let request = build_catalog_insert_request(catalog);
+        self.information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)
+    }
+
     pub async fn register_schema(
         &self,
         catalog: String,
         schema: String,
+        value: &SchemaEntryValue,
     ) -> crate::error::Result<usize> {
-        let request = build_schema_insert_request(catalog, schema);
+        let request = build_schema_insert_request(catalog, schema, value);
         self.information_schema
             .system
             .insert(request)
             .await
             .context(InsertCatalogRecordSnafu)
     }
+
+    pub(crate) async fn deregister_schema(
+        &self,
+        catalog: &str,
+        schema: &str,
+    ) -> crate::error::Result<usize> {
+        self.information_schema
+            .system
+            .delete(build_schema_deletion_request(catalog, schema))
+            .await
+            .with_context(|_| error::DeregisterSchemaSnafu { catalog, schema })
+    }
 }
 
 impl CatalogProvider for SystemCatalog {
@@ -365,6 +515,27 @@ fn build_schema_for_tables() -> Schema {
     Schema::new(cols)
 }
 
+fn build_schema_for_schemata() -> Schema {
+    let cols = vec![
+        ColumnSchema::new(
+            "catalog".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "schema".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "metadata".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ];
+    Schema::new(cols)
+}
+
 #[cfg(test)]
 mod tests {
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
@@ -373,7 +544,7 @@ mod tests {
     use table::table::numbers::NumbersTable;
 
     use super::*;
-    use crate::local::memory::new_memory_catalog_list;
+    use crate::local::memory::{new_memory_catalog_list, MemorySchemaProvider};
     use crate::CatalogList;
 
     #[tokio::test]
@@ -438,4 +609,38 @@ mod tests {
             panic!("Record batch should not be empty!")
         }
     }
+
+    #[tokio::test]
+    async fn test_schemata() {
+        let catalog_list = new_memory_catalog_list().unwrap();
+        let schema = catalog_list
+            .catalog(DEFAULT_CATALOG_NAME)
+            .unwrap()
+            .unwrap()
+            .schema(DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap();
+        schema
+            .as_any()
+            .downcast_ref::<MemorySchemaProvider>()
+            .unwrap()
+            .set_metadata([("owner".to_string(), "alice".to_string())].into());
+
+        let schemata = Schemata::new(catalog_list);
+        let stream = schemata.scan(None, &[], None).await.unwrap();
+        let session_ctx = SessionContext::new();
+        let mut stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert_eq!(3, batch.num_columns());
+        assert_eq!(
+            "public",
+            batch.column(1).get_ref(0).as_string().unwrap().unwrap()
+        );
+        assert_eq!(
+            r#"{"owner":"alice"}"#,
+            batch.column(2).get_ref(0).as_string().unwrap().unwrap()
+        );
+    }
 }
@@ -17,7 +17,7 @@ mod tests {
     use std::sync::Arc;
 
     use catalog::local::LocalCatalogManager;
-    use catalog::{CatalogManager, RegisterTableRequest, RenameTableRequest};
+    use catalog::{CatalogManager, CreateCatalogRequest, RegisterTableRequest, RenameTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use common_telemetry::{error, info};
     use mito::config::EngineConfig;
@@ -76,6 +76,29 @@ mod tests {
         assert_eq!(registered_table.table_info().ident.table_id, table_id);
     }
 
+    #[tokio::test]
+    async fn test_create_catalog() {
+        common_telemetry::init_default_ut_logging();
+        let catalog_manager = create_local_catalog_manager().await.unwrap();
+        let request = CreateCatalogRequest {
+            catalog: "tenant_a".to_string(),
+        };
+        assert!(catalog_manager
+            .create_catalog(request.clone())
+            .await
+            .unwrap());
+        assert!(!catalog_manager.create_catalog(request).await.unwrap());
+
+        assert!(catalog_manager
+            .schema("tenant_a", DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .is_some());
+        assert!(catalog_manager
+            .table("tenant_a", DEFAULT_SCHEMA_NAME, "numbers")
+            .unwrap()
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_duplicate_register() {
         let catalog_manager = create_local_catalog_manager().await.unwrap();
@@ -22,7 +22,7 @@ mod tests {
     use std::collections::HashSet;
     use std::sync::Arc;
 
-    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue};
+    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue, TableGlobalKey};
     use catalog::remote::{
         KvBackend, KvBackendRef, RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider,
     };
@@ -59,7 +59,10 @@ mod tests {
         }
         .to_string();
         backend
-            .set(schema_key.as_bytes(), &SchemaValue {}.as_bytes().unwrap())
+            .set(
+                schema_key.as_bytes(),
+                &SchemaValue::default().as_bytes().unwrap(),
+            )
             .await
             .unwrap();
 
@@ -294,4 +297,57 @@ mod tests {
             new_catalog.schema_names().unwrap().into_iter().collect()
         )
     }
+
+    #[tokio::test]
+    async fn test_list_schemas_and_tables() {
+        let node_id = 42;
+        let (backend, _, catalog_manager) = prepare_components(node_id).await;
+
+        for schema_name in ["a", "b", "c"] {
+            let key = SchemaKey {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: schema_name.to_string(),
+            }
+            .to_string();
+            backend
+                .set(key.as_bytes(), &SchemaValue::default().as_bytes().unwrap())
+                .await
+                .unwrap();
+            let key = TableGlobalKey {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: "a".to_string(),
+                table_name: format!("t_{schema_name}"),
+            }
+            .to_string();
+            backend.set(key.as_bytes(), b"{}").await.unwrap();
+        }
+
+        let mut schemas = Vec::new();
+        let mut token = None;
+        loop {
+            let page = catalog_manager
+                .list_schemas(DEFAULT_CATALOG_NAME, token.as_deref(), 2)
+                .await
+                .unwrap();
+            assert!(page.items.len() <= 2);
+            schemas.extend(page.items);
+            token = page.next_token;
+            if token.is_none() {
+                break;
+            }
+        }
+        assert_eq!(vec!["a", "b", "c", DEFAULT_SCHEMA_NAME], schemas);
+
+        let page = catalog_manager
+            .list_tables(DEFAULT_CATALOG_NAME, "a", None, 2)
+            .await
+            .unwrap();
+        assert_eq!(vec!["t_a", "t_b"], page.items);
+        let page = catalog_manager
+            .list_tables(DEFAULT_CATALOG_NAME, "a", page.next_token.as_deref(), 2)
+            .await
+            .unwrap();
+        assert_eq!(vec!["t_c"], page.items);
+        assert!(page.next_token.is_none());
+    }
 }
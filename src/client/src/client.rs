@@ -16,16 +16,20 @@ use std::sync::Arc;
 
 use arrow_flight::flight_service_client::FlightServiceClient;
 use common_grpc::channel_manager::ChannelManager;
+use common_grpc::token::AttachTokenInterceptor;
 use parking_lot::RwLock;
 use snafu::{OptionExt, ResultExt};
+use tonic::codegen::InterceptedService;
 use tonic::transport::Channel;
 
 use crate::load_balance::{LoadBalance, Loadbalancer};
 use crate::{error, Result};
 
+type InnerFlightClient = FlightServiceClient<InterceptedService<Channel, AttachTokenInterceptor>>;
+
 pub(crate) struct FlightClient {
     addr: String,
-    client: FlightServiceClient<Channel>,
+    client: InnerFlightClient,
 }
 
 impl FlightClient {
@@ -33,7 +37,7 @@ impl FlightClient {
         &self.addr
     }
 
-    pub(crate) fn mut_inner(&mut self) -> &mut FlightServiceClient<Channel> {
+    pub(crate) fn mut_inner(&mut self) -> &mut InnerFlightClient {
         &mut self.client
     }
 }
@@ -133,7 +137,10 @@ impl Client {
             .context(error::CreateChannelSnafu { addr: &addr })?;
         Ok(FlightClient {
             addr,
-            client: FlightServiceClient::new(channel),
+            client: FlightServiceClient::with_interceptor(
+                channel,
+                self.inner.channel_manager.token_interceptor(),
+            ),
         })
     }
 }
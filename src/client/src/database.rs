@@ -12,19 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::str::FromStr;
+use std::time::Instant;
 
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
 use api::v1::{
-    AlterExpr, CreateTableExpr, DdlRequest, DropTableExpr, GreptimeRequest, InsertRequest,
-    QueryRequest, RequestHeader,
+    AlterExpr, Compression, CreateTableExpr, DdlRequest, DdlRequests, DeleteRequest, DropTableExpr,
+    FenceRequest, GreptimeRequest, InsertRequest, QueryRequest, RequestHeader, SnapshotLogicalPlan,
+    SnapshotRequest,
 };
 use arrow_flight::{FlightData, Ticket};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::prelude::*;
 use common_grpc::flight::{flight_messages_to_recordbatches, FlightDecoder, FlightMessage};
+use common_grpc_expr::insert::compress_insert_request;
 use common_query::Output;
 use futures_util::{TryFutureExt, TryStreamExt};
 use prost::Message;
@@ -40,6 +42,10 @@ pub struct Database {
     // They will be carried in the request header.
     catalog: String,
     schema: String,
+    // The compression of column values of insert requests.
+    compression: Compression,
+    // The deadline of the requests, it's carried to the server as the gRPC timeout.
+    deadline: Option<Instant>,
 
     client: Client,
 }
@@ -49,6 +55,8 @@ impl Database {
         Self {
             catalog: catalog.into(),
             schema: schema.into(),
+            compression: Compression::Uncompressed,
+            deadline: None,
             client,
         }
     }
@@ -61,10 +69,79 @@ impl Database {
         self.schema = schema.into();
     }
 
+    /// Sets the compression of column values of insert requests, it cuts the bandwidth of
+    /// inserting many rows at the cost of CPU. Values are not compressed by default.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    /// Sets the deadline of the requests, the server abandons the requests once the
+    /// deadline passes.
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
     pub async fn insert(&self, request: InsertRequest) -> Result<Output> {
+        let request = compress_insert_request(request, self.compression);
         self.do_get(Request::Insert(request)).await
     }
 
+    /// Deletes the rows whose keys are in the `request`.
+    pub async fn delete(&self, request: DeleteRequest) -> Result<Output> {
+        self.do_get(Request::Delete(request)).await
+    }
+
+    /// Inserts the `request`, the server acknowledges once the rows are in the WAL buffer
+    /// without waiting for the WAL to be synced. Returns the number of inserted rows and the
+    /// sequence of the write, which could be passed to [Database::fence].
+    pub async fn insert_with_wal_ack(&self, mut request: InsertRequest) -> Result<(usize, u64)> {
+        request.wal_ack = true;
+        let request = compress_insert_request(request, self.compression);
+        let flight_messages = self.do_get_messages(Request::Insert(request)).await?;
+        match flight_messages.as_slice() {
+            [FlightMessage::WalAck {
+                affected_rows,
+                sequence,
+            }] => Ok((*affected_rows, *sequence)),
+            // Nothing is written.
+            [FlightMessage::AffectedRows(rows)] => Ok((*rows, 0)),
+            _ => IllegalFlightMessagesSnafu {
+                reason: "Expect 'WalAck' Flight messages to be one and only!",
+            }
+            .fail(),
+        }
+    }
+
+    /// Waits until all writes to the table whose sequence is less than or equal to `sequence`
+    /// are durable.
+    pub async fn fence(&self, table_name: impl Into<String>, sequence: u64) -> Result<()> {
+        let _ = self
+            .do_get(Request::Fence(FenceRequest {
+                table_name: table_name.into(),
+                sequence,
+            }))
+            .await?;
+        Ok(())
+    }
+
+    /// Takes a snapshot of the table, returns the sequence of the latest write visible to the
+    /// snapshot, which could be passed to [Database::logical_plan_at_sequence].
+    pub async fn snapshot_sequence(&self, table_name: impl Into<String>) -> Result<u64> {
+        let request = Request::Snapshot(SnapshotRequest {
+            table_name: table_name.into(),
+        });
+        let flight_messages = self.do_get_messages(request).await?;
+        match flight_messages.as_slice() {
+            [FlightMessage::WalAck { sequence, .. }] => Ok(*sequence),
+            // Nothing is written to the table yet.
+            [FlightMessage::AffectedRows(_)] => Ok(0),
+            _ => IllegalFlightMessagesSnafu {
+                reason: "Expect 'WalAck' Flight messages to be one and only!",
+            }
+            .fail(),
+        }
+    }
+
     pub async fn sql(&self, sql: &str) -> Result<Output> {
         self.do_get(Request::Query(QueryRequest {
             query: Some(Query::Sql(sql.to_string())),
@@ -79,6 +156,22 @@ impl Database {
         .await
     }
 
+    /// Executes the logical plan, whose table scans only read the writes whose sequence is
+    /// less than or equal to `sequence`, see [Database::snapshot_sequence].
+    pub async fn logical_plan_at_sequence(
+        &self,
+        logical_plan: Vec<u8>,
+        sequence: u64,
+    ) -> Result<Output> {
+        self.do_get(Request::Query(QueryRequest {
+            query: Some(Query::SnapshotLogicalPlan(SnapshotLogicalPlan {
+                logical_plan,
+                sequence,
+            })),
+        }))
+        .await
+    }
+
     pub async fn create(&self, expr: CreateTableExpr) -> Result<Output> {
         self.do_get(Request::Ddl(DdlRequest {
             expr: Some(DdlExpr::CreateTable(expr)),
@@ -100,7 +193,39 @@ impl Database {
         .await
     }
 
+    /// Executes the DDL `requests` in order in one round trip, the execution stops at the
+    /// first failed request.
+    pub async fn ddls(&self, requests: Vec<DdlRequest>) -> Result<Output> {
+        self.do_get(Request::Ddls(DdlRequests { requests })).await
+    }
+
     async fn do_get(&self, request: Request) -> Result<Output> {
+        let flight_messages = self.do_get_messages(request).await?;
+
+        let output = match flight_messages.get(0) {
+            Some(FlightMessage::AffectedRows(rows))
+            | Some(FlightMessage::WalAck {
+                affected_rows: rows,
+                ..
+            }) => {
+                ensure!(
+                    flight_messages.len() == 1,
+                    IllegalFlightMessagesSnafu {
+                        reason: "Expect 'AffectedRows' Flight messages to be one and only!"
+                    }
+                );
+                Output::AffectedRows(*rows)
+            }
+            _ => {
+                let recordbatches = flight_messages_to_recordbatches(flight_messages)
+                    .context(ConvertFlightDataSnafu)?;
+                Output::RecordBatches(recordbatches)
+            }
+        };
+        Ok(output)
+    }
+
+    async fn do_get_messages(&self, request: Request) -> Result<Vec<FlightMessage>> {
         let request = GreptimeRequest {
             header: Some(RequestHeader {
                 catalog: self.catalog.clone(),
@@ -108,56 +233,55 @@ impl Database {
             }),
             request: Some(request),
         };
-        let request = Ticket {
+        let mut request = tonic::Request::new(Ticket {
             ticket: request.encode_to_vec(),
-        };
+        });
+        if let Some(deadline) = self.deadline {
+            request.set_timeout(deadline.saturating_duration_since(Instant::now()));
+        }
 
         let mut client = self.client.make_client()?;
 
+        // Scoped by the schema of the requests.
+        #[cfg(feature = "failpoints")]
+        if common_base::failpoint::triggered("client_do_get", &self.schema) {
+            let status = tonic::Status::unavailable("Injected failure at client_do_get");
+            return Err(flight_get_error(status, client.addr()));
+        }
+
         // TODO(LFC): Streaming get flight data.
         let flight_data: Vec<FlightData> = client
             .mut_inner()
             .do_get(request)
             .and_then(|response| response.into_inner().try_collect())
             .await
-            .map_err(|e| {
-                let code = get_metadata_value(&e, INNER_ERROR_CODE)
-                    .and_then(|s| StatusCode::from_str(&s).ok())
-                    .unwrap_or(StatusCode::Unknown);
-                let msg = get_metadata_value(&e, INNER_ERROR_MSG).unwrap_or(e.to_string());
-                error::ExternalSnafu { code, msg }
-                    .fail::<()>()
-                    .map_err(BoxedError::new)
-                    .context(error::FlightGetSnafu {
-                        tonic_code: e.code(),
-                        addr: client.addr(),
-                    })
-                    .unwrap_err()
-            })?;
+            .map_err(|e| flight_get_error(e, client.addr()))?;
 
         let decoder = &mut FlightDecoder::default();
-        let flight_messages = flight_data
+        flight_data
             .into_iter()
             .map(|x| decoder.try_decode(x).context(ConvertFlightDataSnafu))
-            .collect::<Result<Vec<_>>>()?;
-
-        let output = if let Some(FlightMessage::AffectedRows(rows)) = flight_messages.get(0) {
-            ensure!(
-                flight_messages.len() == 1,
-                IllegalFlightMessagesSnafu {
-                    reason: "Expect 'AffectedRows' Flight messages to be one and only!"
-                }
-            );
-            Output::AffectedRows(*rows)
-        } else {
-            let recordbatches = flight_messages_to_recordbatches(flight_messages)
-                .context(ConvertFlightDataSnafu)?;
-            Output::RecordBatches(recordbatches)
-        };
-        Ok(output)
+            .collect::<Result<Vec<_>>>()
     }
 }
 
+/// Converts the `status` of a failed `do_get` call to datanode at `addr` to an error,
+/// restoring the error code and message of datanode from the metadata.
+fn flight_get_error(status: tonic::Status, addr: &str) -> error::Error {
+    let code = get_metadata_value(&status, INNER_ERROR_CODE)
+        .and_then(|s| StatusCode::parse(&s))
+        .unwrap_or(StatusCode::Unknown);
+    let msg = get_metadata_value(&status, INNER_ERROR_MSG).unwrap_or(status.to_string());
+    error::ExternalSnafu { code, msg }
+        .fail::<()>()
+        .map_err(BoxedError::new)
+        .context(error::FlightGetSnafu {
+            tonic_code: status.code(),
+            addr,
+        })
+        .unwrap_err()
+}
+
 fn get_metadata_value(e: &tonic::Status, key: &str) -> Option<String> {
     e.metadata()
         .get(key)
@@ -260,6 +384,7 @@ mod tests {
             values: Some(values(&[vector.clone()]).unwrap()),
             null_mask: null_mask(&[vector.clone()], vector.len()),
             datatype: wrapper.datatype() as i32,
+            compressed_values: vec![],
         }
     }
 }
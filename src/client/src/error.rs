@@ -77,6 +77,20 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether the request is never handled by the peer because it is unreachable, so it's
+    /// safe to retry the request.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Error::FlightGet {
+                tonic_code: Code::Unavailable,
+                ..
+            }
+        )
+    }
+}
+
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
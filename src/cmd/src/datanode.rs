@@ -102,7 +102,7 @@ impl TryFrom<StartCommand> for DatanodeOptions {
         }
 
         if let Some(addr) = cmd.mysql_addr {
-            opts.mysql_addr = addr;
+            opts.mysql_addr = Some(addr);
         }
 
         if let Some(node_id) = cmd.node_id {
@@ -128,7 +128,10 @@ impl TryFrom<StartCommand> for DatanodeOptions {
         }
 
         if let Some(data_dir) = cmd.data_dir {
-            opts.storage = ObjectStoreConfig::File(FileConfig { data_dir });
+            opts.storage = ObjectStoreConfig::File(FileConfig {
+                data_dir,
+                ..Default::default()
+            });
         }
 
         if let Some(wal_dir) = cmd.wal_dir {
@@ -160,13 +163,16 @@ mod tests {
         let options: DatanodeOptions = cmd.try_into().unwrap();
         assert_eq!("127.0.0.1:3001".to_string(), options.rpc_addr);
         assert_eq!("/tmp/greptimedb/wal".to_string(), options.wal.dir);
-        assert_eq!("127.0.0.1:4406".to_string(), options.mysql_addr);
+        assert_eq!(None, options.mysql_addr);
         assert_eq!(4, options.mysql_runtime_size);
+        assert_eq!(512 * 1024 * 1024, options.rpc_max_message_size.0);
+        assert_eq!(None, options.rpc_max_in_flight_requests);
         let MetaClientOpts {
             metasrv_addrs: metasrv_addr,
             timeout_millis,
             connect_timeout_millis,
             tcp_nodelay,
+            ..
         } = options.meta_client_opts.unwrap();
 
         assert_eq!(vec!["127.0.0.1:3002".to_string()], metasrv_addr);
@@ -175,7 +181,7 @@ mod tests {
         assert!(!tcp_nodelay);
 
         match options.storage {
-            ObjectStoreConfig::File(FileConfig { data_dir }) => {
+            ObjectStoreConfig::File(FileConfig { data_dir, .. }) => {
                 assert_eq!("/tmp/greptimedb/data/".to_string(), data_dir)
             }
             ObjectStoreConfig::S3 { .. } => unreachable!(),
@@ -230,16 +236,29 @@ mod tests {
         assert_eq!(1024 * 1024 * 1024, dn_opts.wal.file_size.0);
         assert_eq!(1024 * 1024 * 1024 * 50, dn_opts.wal.purge_threshold.0);
         assert!(!dn_opts.wal.sync_write);
+        assert_eq!(3, dn_opts.object_store_request.max_retries);
+        assert_eq!(
+            Duration::from_secs(60),
+            dn_opts.object_store_request.retry_max_delay
+        );
+        assert_eq!(None, dn_opts.object_store_request.read_timeout);
         assert_eq!(Some(42), dn_opts.node_id);
         let MetaClientOpts {
             metasrv_addrs: metasrv_addr,
             timeout_millis,
             connect_timeout_millis,
             tcp_nodelay,
+            heartbeat_interval_millis,
+            reconnect_backoff_max_millis,
+            max_retries,
+            ..
         } = dn_opts.meta_client_opts.unwrap();
         assert_eq!(vec!["127.0.0.1:3002".to_string()], metasrv_addr);
         assert_eq!(3000, timeout_millis);
         assert_eq!(5000, connect_timeout_millis);
         assert!(!tcp_nodelay);
+        assert_eq!(5000, heartbeat_interval_millis);
+        assert_eq!(30000, reconnect_backoff_max_millis);
+        assert_eq!(3, max_retries);
     }
 }
@@ -260,6 +260,8 @@ mod tests {
             Duration::from_secs(30),
             fe_opts.http_options.as_ref().unwrap().timeout
         );
+        assert_eq!(1024, fe_opts.table_cache_capacity);
+        assert_eq!(600, fe_opts.table_cache_ttl_secs);
     }
 
     #[tokio::test]
@@ -153,5 +153,7 @@ mod tests {
         assert_eq!("127.0.0.1:2379".to_string(), options.store_addr);
         assert_eq!(15, options.datanode_lease_secs);
         assert_eq!(SelectorType::LeaseBased, options.selector);
+        assert_eq!(2000, options.max_clock_skew_millis);
+        assert_eq!(86400, options.table_tombstone_retention_secs);
     }
 }
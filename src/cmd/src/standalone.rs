@@ -13,28 +13,41 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Parser;
 use common_telemetry::info;
-use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig, WalConfig};
+use datanode::datanode::{
+    Datanode, DatanodeOptions, EncryptionConfig, ObjectStoreConfig, ObjectStoreRequestConfig,
+    ReplicationConfig, TableMetricsConfig, WalConfig, WriteBehindConfig,
+    DEFAULT_QUERY_HISTORY_SIZE,
+};
 use datanode::instance::InstanceRef;
+use frontend::error::Error as FrontendError;
 use frontend::frontend::{Frontend, FrontendOptions};
 use frontend::grpc::GrpcOptions;
 use frontend::influxdb::InfluxdbOptions;
-use frontend::instance::Instance as FeInstance;
+use frontend::instance::{FrontendInstance, Instance as FeInstance};
 use frontend::mysql::MysqlOptions;
 use frontend::opentsdb::OpentsdbOptions;
 use frontend::postgres::PostgresOptions;
 use frontend::prometheus::PrometheusOptions;
 use frontend::promql::PromqlOptions;
+use frontend::table_template::TableTemplate;
 use frontend::Plugins;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
+use servers::query_handler::grpc::GrpcQueryHandlerRef;
+use servers::query_handler::sql::SqlQueryHandlerRef;
 use servers::tls::{TlsMode, TlsOption};
 use servers::Mode;
 use snafu::ResultExt;
+use table::masking::MaskingPolicy;
 
-use crate::error::{Error, IllegalConfigSnafu, Result, StartDatanodeSnafu, StartFrontendSnafu};
+use crate::error::{
+    Error, IllegalConfigSnafu, Result, ShutdownDatanodeSnafu, StartDatanodeSnafu,
+    StartFrontendSnafu,
+};
 use crate::frontend::load_frontend_plugins;
 use crate::toml_loader;
 
@@ -77,7 +90,29 @@ pub struct StandaloneOptions {
     pub mode: Mode,
     pub wal: WalConfig,
     pub storage: ObjectStoreConfig,
+    pub object_store_request: ObjectStoreRequestConfig,
+    pub cold_storage: Option<ObjectStoreConfig>,
+    pub write_behind: Option<WriteBehindConfig>,
+    pub encryption: Option<EncryptionConfig>,
+    pub sst_token_index: bool,
     pub enable_memory_catalog: bool,
+    pub query_history_size: usize,
+    #[serde(with = "humantime_serde")]
+    pub max_future_timestamp: Option<Duration>,
+    pub max_insert_rows: Option<usize>,
+    pub lazy_open_tables: bool,
+    #[serde(with = "humantime_serde")]
+    pub idle_table_close_after: Option<Duration>,
+    #[serde(with = "humantime_serde")]
+    pub tiering_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub recycle_bin_retention: Duration,
+    pub table_metrics: Option<TableMetricsConfig>,
+    pub table_templates: Vec<TableTemplate>,
+    pub masking_policies: Vec<MaskingPolicy>,
+    pub alert_webhook_allowed_hosts: Vec<String>,
+    pub replication: Option<ReplicationConfig>,
+    pub enable_cpython_backend: bool,
 }
 
 impl Default for StandaloneOptions {
@@ -94,7 +129,25 @@ impl Default for StandaloneOptions {
             mode: Mode::Standalone,
             wal: WalConfig::default(),
             storage: ObjectStoreConfig::default(),
+            object_store_request: ObjectStoreRequestConfig::default(),
+            cold_storage: None,
+            write_behind: None,
+            encryption: None,
+            sst_token_index: false,
             enable_memory_catalog: false,
+            query_history_size: DEFAULT_QUERY_HISTORY_SIZE,
+            max_future_timestamp: None,
+            max_insert_rows: None,
+            lazy_open_tables: false,
+            idle_table_close_after: None,
+            tiering_interval: DatanodeOptions::default().tiering_interval,
+            recycle_bin_retention: DatanodeOptions::default().recycle_bin_retention,
+            table_metrics: None,
+            table_templates: vec![],
+            masking_policies: vec![],
+            alert_webhook_allowed_hosts: vec![],
+            replication: None,
+            enable_cpython_backend: false,
         }
     }
 }
@@ -112,6 +165,9 @@ impl StandaloneOptions {
             promql_options: self.promql_options,
             mode: self.mode,
             meta_client_opts: None,
+            table_templates: self.table_templates,
+            alert_webhook_allowed_hosts: self.alert_webhook_allowed_hosts,
+            ..Default::default()
         }
     }
 
@@ -119,7 +175,23 @@ impl StandaloneOptions {
         DatanodeOptions {
             wal: self.wal,
             storage: self.storage,
+            object_store_request: self.object_store_request,
+            cold_storage: self.cold_storage,
+            write_behind: self.write_behind,
+            encryption: self.encryption,
+            sst_token_index: self.sst_token_index,
             enable_memory_catalog: self.enable_memory_catalog,
+            query_history_size: self.query_history_size,
+            max_future_timestamp: self.max_future_timestamp,
+            max_insert_rows: self.max_insert_rows,
+            lazy_open_tables: self.lazy_open_tables,
+            idle_table_close_after: self.idle_table_close_after,
+            tiering_interval: self.tiering_interval,
+            recycle_bin_retention: self.recycle_bin_retention,
+            table_metrics: self.table_metrics,
+            masking_policies: self.masking_policies,
+            enable_cpython_backend: self.enable_cpython_backend,
+            replication: self.replication,
             ..Default::default()
         }
     }
@@ -197,10 +269,114 @@ async fn build_frontend(
     plugins: Arc<Plugins>,
     datanode_instance: InstanceRef,
 ) -> Result<Frontend<FeInstance>> {
+    let frontend_instance = build_frontend_instance(&fe_opts, plugins.clone(), datanode_instance);
+    Ok(Frontend::new(fe_opts, frontend_instance, plugins))
+}
+
+fn build_frontend_instance(
+    fe_opts: &FrontendOptions,
+    plugins: Arc<Plugins>,
+    datanode_instance: InstanceRef,
+) -> FeInstance {
     let mut frontend_instance = FeInstance::new_standalone(datanode_instance.clone());
     frontend_instance.set_script_handler(datanode_instance);
-    frontend_instance.set_plugins(plugins.clone());
-    Ok(Frontend::new(fe_opts, frontend_instance, plugins))
+    frontend_instance.set_plugins(plugins);
+    frontend_instance.set_table_templates(fe_opts.table_templates.clone());
+    frontend_instance.set_alert_webhook_allowed_hosts(fe_opts.alert_webhook_allowed_hosts.clone());
+    frontend_instance
+}
+
+/// A standalone GreptimeDB running in the current process, for applications embedding it
+/// as a library. Requests are handled by the in-process handlers, no server is started.
+///
+/// ```ignore
+/// let mut standalone = Standalone::builder(opts).build().await?;
+/// standalone.start().await?;
+/// let output = standalone
+///     .sql_handler()
+///     .do_query("SELECT * FROM monitor", QueryContext::arc())
+///     .await;
+/// standalone.stop().await?;
+/// ```
+pub struct Standalone {
+    datanode: Datanode,
+    frontend_instance: FeInstance,
+}
+
+impl Standalone {
+    pub fn builder(opts: StandaloneOptions) -> StandaloneBuilder {
+        StandaloneBuilder {
+            opts,
+            plugins: Plugins::new(),
+        }
+    }
+
+    /// Starts the datanode and frontend instances, requests could be handled after it
+    /// returns.
+    pub async fn start(&mut self) -> Result<()> {
+        self.datanode
+            .start_instance()
+            .await
+            .context(StartDatanodeSnafu)?;
+        self.frontend_instance
+            .start()
+            .await
+            .context(StartFrontendSnafu)?;
+        info!("Standalone instance started");
+        Ok(())
+    }
+
+    /// Stops the background tasks of the frontend and the datanode, and then the WAL. Data
+    /// written is durable in the WAL, so it could be recovered by another [Standalone] with
+    /// the same options.
+    pub async fn stop(self) -> Result<()> {
+        self.frontend_instance.shutdown();
+        self.datanode
+            .get_instance()
+            .shutdown()
+            .await
+            .context(ShutdownDatanodeSnafu)?;
+        info!("Standalone instance stopped");
+        Ok(())
+    }
+
+    /// Returns the handler of gRPC requests, e.g. inserts and DDLs.
+    pub fn grpc_handler(&self) -> GrpcQueryHandlerRef<FrontendError> {
+        Arc::new(self.frontend_instance.clone())
+    }
+
+    /// Returns the handler of SQL and PromQL queries.
+    pub fn sql_handler(&self) -> SqlQueryHandlerRef<FrontendError> {
+        Arc::new(self.frontend_instance.clone())
+    }
+}
+
+/// Builder of [Standalone].
+pub struct StandaloneBuilder {
+    opts: StandaloneOptions,
+    plugins: Plugins,
+}
+
+impl StandaloneBuilder {
+    /// Sets the plugins of the frontend, e.g. the user provider.
+    pub fn with_plugins(mut self, plugins: Plugins) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    pub async fn build(self) -> Result<Standalone> {
+        let mut fe_opts = self.opts.clone().frontend_options();
+        fe_opts.mode = Mode::Standalone;
+        let dn_opts = self.opts.datanode_options();
+
+        let datanode = Datanode::new(dn_opts).await.context(StartDatanodeSnafu)?;
+        let frontend_instance =
+            build_frontend_instance(&fe_opts, Arc::new(self.plugins), datanode.get_instance());
+        Ok(Standalone {
+            datanode,
+            frontend_instance,
+        })
+    }
 }
 
 impl TryFrom<StartCommand> for FrontendOptions {
@@ -284,7 +460,12 @@ impl TryFrom<StartCommand> for FrontendOptions {
 mod tests {
     use std::time::Duration;
 
+    use common_query::Output;
+    use common_recordbatch::util;
+    use datanode::datanode::FileConfig;
     use servers::auth::{Identity, Password, UserProviderRef};
+    use session::context::QueryContext;
+    use tempdir::TempDir;
 
     use super::*;
 
@@ -318,10 +499,9 @@ mod tests {
             Duration::from_secs(30),
             fe_opts.http_options.as_ref().unwrap().timeout
         );
-        assert_eq!(
-            "127.0.0.1:4001".to_string(),
-            fe_opts.grpc_options.unwrap().addr
-        );
+        let grpc_options = fe_opts.grpc_options.unwrap();
+        assert_eq!("127.0.0.1:4001".to_string(), grpc_options.addr);
+        assert_eq!(512 * 1024 * 1024, grpc_options.max_message_size.0);
         assert_eq!(
             "127.0.0.1:4002",
             fe_opts.mysql_options.as_ref().unwrap().addr
@@ -362,4 +542,70 @@ mod tests {
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_embedded_standalone() {
+        let wal_dir = TempDir::new("test_embedded_standalone_wal").unwrap();
+        let data_dir = TempDir::new("test_embedded_standalone_data").unwrap();
+        let opts = StandaloneOptions {
+            wal: WalConfig {
+                dir: wal_dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            },
+            storage: ObjectStoreConfig::File(FileConfig {
+                data_dir: data_dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut standalone = Standalone::builder(opts.clone()).build().await.unwrap();
+        standalone.start().await.unwrap();
+
+        let handler = standalone.sql_handler();
+        let execute = |sql: &'static str| {
+            let handler = handler.clone();
+            async move {
+                handler
+                    .do_query(sql, QueryContext::arc())
+                    .await
+                    .remove(0)
+                    .unwrap()
+            }
+        };
+        execute("CREATE TABLE demo (host STRING, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))")
+            .await;
+        let output =
+            execute("INSERT INTO demo VALUES ('host1', 1655276557000), ('host2', 1655276558000)")
+                .await;
+        assert!(matches!(output, Output::AffectedRows(2)));
+
+        let output = execute("SELECT host FROM demo ORDER BY host").await;
+        let Output::Stream(stream) = output else { unreachable!() };
+        let batches = util::collect_batches(stream).await.unwrap();
+        let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
+| host2 |
++-------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+
+        standalone.stop().await.unwrap();
+
+        // Rows are recovered from the WAL by a new instance with the same options.
+        let mut standalone = Standalone::builder(opts).build().await.unwrap();
+        standalone.start().await.unwrap();
+        let output = standalone
+            .sql_handler()
+            .do_query("SELECT host FROM demo ORDER BY host", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(expected, batches.pretty_print().unwrap());
+        standalone.stop().await.unwrap();
+    }
 }
@@ -0,0 +1,54 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fail points scoped by e.g. the name of a region or schema. A scoped fail point only
+//! triggers if the argument of its action, e.g. `return(my_region)`, is the scope where
+//! it's evaluated, so tests injecting failures don't fail other tests running
+//! concurrently in the same process.
+
+/// Returns true if the fail point `name` is configured to fail the `scope`, e.g. by
+/// `fail::cfg(name, "return(scope)")`. Actions without an argument never trigger.
+pub fn triggered(name: &str, scope: &str) -> bool {
+    triggered_by(name, |arg| arg == scope)
+}
+
+/// Returns true if the fail point `name` is configured with an argument `matches` accepts,
+/// e.g. a prefix of the key to fail.
+pub fn triggered_by(name: &str, matches: impl FnOnce(&str) -> bool) -> bool {
+    fail::eval(name, |arg| arg.map_or(false, |arg| matches(&arg))).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_failpoint() {
+        let scenario = fail::FailScenario::setup();
+
+        assert!(!triggered("test_scoped_failpoint", "a"));
+        fail::cfg("test_scoped_failpoint", "return").unwrap();
+        assert!(!triggered("test_scoped_failpoint", "a"));
+
+        fail::cfg("test_scoped_failpoint", "return(a)").unwrap();
+        assert!(triggered("test_scoped_failpoint", "a"));
+        assert!(!triggered("test_scoped_failpoint", "b"));
+        assert!(triggered_by("test_scoped_failpoint", |arg| "ab".starts_with(arg)));
+
+        fail::remove("test_scoped_failpoint");
+        assert!(!triggered("test_scoped_failpoint", "a"));
+
+        scenario.teardown();
+    }
+}
@@ -15,7 +15,10 @@
 pub mod bit_vec;
 pub mod buffer;
 pub mod bytes;
+#[cfg(feature = "failpoints")]
+pub mod failpoint;
 #[allow(clippy::all)]
 pub mod readable_size;
+pub mod tokenizer;
 
 pub use bit_vec::BitVec;
@@ -0,0 +1,44 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tokenizer of texts like log messages, used to build and search token indexes.
+
+/// Returns whether `c` is part of a token, tokens are separated by all other chars.
+#[inline]
+pub fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Splits `text` into lowercase tokens, in the order they appear in `text`.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c| !is_token_char(c))
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            vec!["get", "api", "v1", "users", "status_code", "404"],
+            tokenize("GET /api/v1/users, status_code=404").collect::<Vec<_>>()
+        );
+        assert!(tokenize("").next().is_none());
+        assert!(tokenize(" -- ").next().is_none());
+        assert_eq!(vec!["日志"], tokenize("日志!").collect::<Vec<_>>());
+    }
+}
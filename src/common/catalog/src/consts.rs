@@ -15,6 +15,11 @@
 pub const SYSTEM_CATALOG_NAME: &str = "system";
 pub const INFORMATION_SCHEMA_NAME: &str = "information_schema";
 pub const SYSTEM_CATALOG_TABLE_NAME: &str = "system_catalog";
+/// Schema of system tables in each catalog, e.g. `ddl_history`.
+pub const SYSTEM_SCHEMA_NAME: &str = "system";
+pub const DDL_HISTORY_TABLE_NAME: &str = "ddl_history";
+/// Schema of the PostgreSQL system catalog shims in each catalog, e.g. `pg_tables`.
+pub const PG_CATALOG_NAME: &str = "pg_catalog";
 pub const DEFAULT_CATALOG_NAME: &str = "greptime";
 pub const DEFAULT_SCHEMA_NAME: &str = "public";
 
@@ -25,3 +30,11 @@ pub const MIN_USER_TABLE_ID: u32 = 1024;
 pub const SYSTEM_CATALOG_TABLE_ID: u32 = 0;
 /// scripts table id
 pub const SCRIPTS_TABLE_ID: u32 = 1;
+/// ddl_history table id
+pub const DDL_HISTORY_TABLE_ID: u32 = 2;
+/// information_schema.region_peers table id
+pub const REGION_PEERS_TABLE_ID: u32 = 3;
+
+/// Prefix of the keys that metasrv persists the stats of datanodes to, frontends read
+/// the stats of regions from them.
+pub const DATANODE_STAT_KEY_PREFIX: &str = "__meta_dnstat";
@@ -14,10 +14,14 @@
 
 use std::fmt;
 
-use strum::EnumString;
+use strum::{EnumIter, EnumString, IntoEnumIterator};
 
 /// Common status code for public API.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+///
+/// The numeric value of each code is stable and is carried by all the protocols (the
+/// gRPC metadata, the `code` of HTTP JSON responses and the message of MySQL error
+/// packets), so clients could branch on it. Never reuse or change the value of a code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, EnumIter)]
 pub enum StatusCode {
     // ====== Begin of common status code ==============
     /// Success.
@@ -45,6 +49,10 @@ pub enum StatusCode {
     PlanQuery = 3000,
     /// The query engine fail to execute query.
     EngineExecuteQuery = 3001,
+    /// The query is cancelled, e.g. killed by the `KILL QUERY` command.
+    Cancelled = 3002,
+    /// The deadline supplied by the client passes before the query finishes.
+    DeadlineExceeded = 3003,
     // ====== End of query related status code =========
 
     // ====== Begin of catalog related status code =====
@@ -54,6 +62,8 @@ pub enum StatusCode {
     TableColumnNotFound = 4002,
     TableColumnExists = 4003,
     DatabaseNotFound = 4004,
+    /// Table is read-only and rejects writes.
+    TableReadOnly = 4005,
     // ====== End of catalog related status code =======
 
     // ====== Begin of storage related status code =====
@@ -64,6 +74,8 @@ pub enum StatusCode {
     // ====== Begin of server related status code =====
     /// Runtime resources exhausted, like creating threads failed.
     RuntimeResourcesExhausted = 6000,
+    /// Request is rejected as the rate limit is exceeded.
+    RateLimited = 6001,
     // ====== End of server related status code =======
 
     // ====== Begin of auth related status code =====
@@ -86,6 +98,21 @@ impl StatusCode {
     pub fn is_success(code: u32) -> bool {
         Self::Success as u32 == code
     }
+
+    /// Returns the status code of the numeric `value`, or `None` if `value` is unknown,
+    /// e.g. it is sent by a newer server.
+    pub fn from_u32(value: u32) -> Option<StatusCode> {
+        StatusCode::iter().find(|code| *code as u32 == value)
+    }
+
+    /// Parses the status code carried by protocols, which is either the numeric value or
+    /// the name of the code.
+    pub fn parse(s: &str) -> Option<StatusCode> {
+        match s.parse::<u32>() {
+            Ok(value) => Self::from_u32(value),
+            Err(_) => s.parse().ok(),
+        }
+    }
 }
 
 impl fmt::Display for StatusCode {
@@ -110,6 +137,27 @@ mod tests {
         assert_status_code_display(StatusCode::TableAlreadyExists, "TableAlreadyExists");
     }
 
+    #[test]
+    fn test_from_u32() {
+        for code in StatusCode::iter() {
+            assert_eq!(Some(code), StatusCode::from_u32(code as u32));
+        }
+        assert_eq!(Some(StatusCode::TableNotFound), StatusCode::from_u32(4001));
+        assert_eq!(Some(StatusCode::RateLimited), StatusCode::from_u32(6001));
+        assert_eq!(None, StatusCode::from_u32(9999));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Some(StatusCode::TableNotFound), StatusCode::parse("4001"));
+        assert_eq!(
+            Some(StatusCode::TableNotFound),
+            StatusCode::parse("TableNotFound")
+        );
+        assert_eq!(None, StatusCode::parse("9999"));
+        assert_eq!(None, StatusCode::parse("NotACode"));
+    }
+
     #[test]
     fn test_is_success() {
         assert!(StatusCode::is_success(0));
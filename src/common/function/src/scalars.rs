@@ -16,10 +16,12 @@ pub mod aggregate;
 pub mod expression;
 pub mod function;
 pub mod function_registry;
+pub mod histogram;
 pub mod math;
 pub mod numpy;
 #[cfg(test)]
 pub(crate) mod test;
+pub mod text;
 mod timestamp;
 pub mod udf;
 
@@ -15,6 +15,7 @@
 mod argmax;
 mod argmin;
 mod diff;
+mod histogram_merge;
 mod mean;
 mod percentile;
 mod polyval;
@@ -27,6 +28,7 @@ pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
 use common_query::logical_plan::AggregateFunctionCreatorRef;
 pub use diff::DiffAccumulatorCreator;
+pub use histogram_merge::HistogramMergeAccumulatorCreator;
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
 pub use polyval::PolyvalAccumulatorCreator;
@@ -94,5 +96,7 @@ impl AggregateFunctions {
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
+        register_aggr_func!("histogram_merge", 2, HistogramMergeAccumulatorCreator);
+        register_aggr_func!("histogram_sum", 1, HistogramMergeAccumulatorCreator);
     }
 }
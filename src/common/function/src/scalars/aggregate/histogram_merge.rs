@@ -0,0 +1,276 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, InvalidFuncArgsSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use snafu::ensure;
+
+use crate::scalars::histogram::{
+    f64s_to_list, histogram_from_lists, value_to_histogram, Histogram,
+};
+
+/// Merges histograms with the same bucket bounds by summing up their bucket counts.
+///
+/// `histogram_merge(bounds, counts)` takes the same arguments as `histogram_quantile()`
+/// except the quantile, and outputs the merged cumulative counts, so the result can be
+/// passed to `histogram_quantile()` again. `histogram_sum(histogram)` merges a histogram
+/// column and outputs a histogram.
+#[derive(Debug, Default)]
+pub struct HistogramMerge {
+    histogram: Option<Histogram>,
+    /// Whether the input is a histogram column instead of a pair of list columns.
+    typed: bool,
+}
+
+impl HistogramMerge {
+    fn new(typed: bool) -> Self {
+        Self {
+            histogram: None,
+            typed,
+        }
+    }
+
+    fn push(&mut self, histogram: Histogram) -> Result<()> {
+        match &mut self.histogram {
+            Some(merged) => {
+                ensure!(
+                    merged.merge(&histogram),
+                    InvalidFuncArgsSnafu {
+                        err_msg: format!(
+                            "Can not merge histograms with different bounds, expect: {:?}, have: {:?}",
+                            merged.bounds(),
+                            histogram.bounds()
+                        ),
+                    }
+                );
+            }
+            None => self.histogram = Some(histogram),
+        }
+        Ok(())
+    }
+
+    fn push_vectors(&mut self, vectors: &[VectorRef]) -> Result<()> {
+        if self.typed {
+            ensure!(vectors.len() == 1, InvalidInputStateSnafu);
+            for i in 0..vectors[0].len() {
+                if let Some(histogram) = value_to_histogram(vectors[0].get(i)) {
+                    self.push(histogram)?;
+                }
+            }
+        } else {
+            ensure!(vectors.len() == 2, InvalidInputStateSnafu);
+            ensure!(vectors[0].len() == vectors[1].len(), InvalidInputStateSnafu);
+            let (bounds, counts) = (&vectors[0], &vectors[1]);
+            for i in 0..bounds.len() {
+                if let Some(histogram) = histogram_from_lists(&bounds.get(i), &counts.get(i)) {
+                    self.push(histogram)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for HistogramMerge {
+    fn state(&self) -> Result<Vec<Value>> {
+        if self.typed {
+            return Ok(vec![self.evaluate()?]);
+        }
+        match &self.histogram {
+            Some(histogram) => Ok(vec![
+                f64s_to_list(histogram.bounds()),
+                f64s_to_list(histogram.counts()),
+            ]),
+            None => Ok(vec![Value::Null, Value::Null]),
+        }
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        self.push_vectors(values)
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let expect = if self.typed { 1 } else { 2 };
+        ensure!(
+            states.len() == expect,
+            BadAccumulatorImplSnafu {
+                err_msg: format!("expect {expect} states in `merge_batch`"),
+            }
+        );
+
+        self.push_vectors(states)
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        match &self.histogram {
+            Some(histogram) if self.typed => Ok(Value::Histogram(histogram.clone())),
+            Some(histogram) => Ok(f64s_to_list(histogram.counts())),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct HistogramMergeAccumulatorCreator {}
+
+impl AggregateFunctionCreator for HistogramMergeAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction =
+            Arc::new(move |types: &[ConcreteDataType]| match types {
+                [t] if t.is_histogram() => Ok(Box::new(HistogramMerge::new(true))),
+                [bounds, counts] if bounds.as_list().is_some() && counts.as_list().is_some() => {
+                    Ok(Box::new(HistogramMerge::new(false)))
+                }
+                _ => {
+                    let err_msg = format!(
+                        "\"HISTOGRAM_MERGE\" aggregate function not support data types {:?}",
+                        types
+                    );
+                    CreateAccumulatorSnafu { err_msg }.fail()?
+                }
+            });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        match input_types.len() {
+            1 => Ok(ConcreteDataType::histogram_datatype()),
+            2 => Ok(ConcreteDataType::list_datatype(
+                ConcreteDataType::float64_datatype(),
+            )),
+            _ => InvalidInputStateSnafu.fail(),
+        }
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        match input_types.len() {
+            1 => Ok(vec![ConcreteDataType::histogram_datatype()]),
+            2 => Ok(vec![
+                ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+                ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+            ]),
+            _ => InvalidInputStateSnafu.fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{HistogramVector, ListVectorBuilder};
+
+    use super::*;
+
+    fn new_list_vector(lists: &[&[f64]]) -> VectorRef {
+        let mut builder = ListVectorBuilder::with_type_capacity(
+            ConcreteDataType::float64_datatype(),
+            lists.len(),
+        );
+        for list in lists {
+            builder
+                .push_value_ref(f64s_to_list(list).as_value_ref())
+                .unwrap();
+        }
+        builder.to_vector()
+    }
+
+    #[test]
+    fn test_update_batch() {
+        // test update empty batch, expect not updating anything
+        let mut merge = HistogramMerge::default();
+        assert!(merge.update_batch(&[]).is_ok());
+        assert_eq!(Value::Null, merge.evaluate().unwrap());
+
+        let bounds = new_list_vector(&[&[1.0, f64::INFINITY], &[1.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[1.0, 2.0], &[3.0, 5.0]]);
+        let mut merge = HistogramMerge::default();
+        assert!(merge.update_batch(&[bounds, counts]).is_ok());
+        assert_eq!(f64s_to_list(&[4.0, 7.0]), merge.evaluate().unwrap());
+
+        // test update with different bounds
+        let bounds = new_list_vector(&[&[2.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[1.0, 1.0]]);
+        assert!(merge.update_batch(&[bounds, counts]).is_err());
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let bounds = new_list_vector(&[&[1.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[1.0, 2.0]]);
+        let mut merge = HistogramMerge::default();
+        merge.update_batch(&[bounds, counts]).unwrap();
+
+        let state = merge.state().unwrap();
+        let states = vec![
+            new_list_vector(&[&[1.0, f64::INFINITY]]),
+            new_list_vector(&[&[2.0, 2.0]]),
+        ];
+        assert_eq!(f64s_to_list(&[1.0, f64::INFINITY]), state[0]);
+        merge.merge_batch(&states).unwrap();
+        assert_eq!(f64s_to_list(&[3.0, 4.0]), merge.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_merge_histogram_type() {
+        let new_vector = |histograms: Vec<Option<Histogram>>| -> VectorRef {
+            Arc::new(HistogramVector::from_owned_iterator(histograms.into_iter()))
+        };
+        let h = |counts: Vec<f64>| Histogram::new(vec![1.0, f64::INFINITY], counts).unwrap();
+
+        let mut merge = HistogramMerge::new(true);
+        merge
+            .update_batch(&[new_vector(vec![
+                Some(h(vec![1.0, 2.0])),
+                None,
+                Some(h(vec![3.0, 5.0])),
+            ])])
+            .unwrap();
+        assert_eq!(
+            Value::Histogram(h(vec![4.0, 7.0])),
+            merge.evaluate().unwrap()
+        );
+
+        let state = merge.state().unwrap();
+        assert_eq!(vec![Value::Histogram(h(vec![4.0, 7.0]))], state);
+        merge
+            .merge_batch(&[new_vector(vec![Some(h(vec![1.0, 1.0]))])])
+            .unwrap();
+        assert_eq!(
+            Value::Histogram(h(vec![5.0, 8.0])),
+            merge.evaluate().unwrap()
+        );
+
+        // The list states are rejected.
+        let bounds = new_list_vector(&[&[1.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[1.0, 2.0]]);
+        assert!(merge.merge_batch(&[bounds, counts]).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Histogram functions.
+//!
+//! A histogram is either a value of the histogram type or a pair of list columns: the
+//! upper bounds of the buckets and the cumulative counts of the buckets, the same layout
+//! as Prometheus' classic histograms. The last bound must be `+Inf` so the last count is
+//! the total number of observations.
+
+mod quantile;
+
+use std::sync::Arc;
+
+use datatypes::prelude::ConcreteDataType;
+pub use datatypes::value::Histogram;
+use datatypes::value::{ListValue, Value};
+pub use quantile::HistogramQuantileFunction;
+
+use crate::scalars::function_registry::FunctionRegistry;
+
+pub(crate) struct HistogramFunction;
+
+impl HistogramFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(HistogramQuantileFunction::default()));
+    }
+}
+
+/// Creates a histogram from two list values, returns `None` if any of the lists is null,
+/// contains non-numeric items or the lists have different lengths.
+pub(crate) fn histogram_from_lists(bounds: &Value, counts: &Value) -> Option<Histogram> {
+    let bounds = list_to_f64s(bounds)?;
+    let counts = list_to_f64s(counts)?;
+    Histogram::new(bounds, counts)
+}
+
+/// Returns the histogram in `value`, or `None` if `value` is not a histogram.
+pub(crate) fn value_to_histogram(value: Value) -> Option<Histogram> {
+    match value {
+        Value::Histogram(h) => Some(h),
+        _ => None,
+    }
+}
+
+fn list_to_f64s(value: &Value) -> Option<Vec<f64>> {
+    let list = value.as_list().ok()??;
+    list.items()
+        .as_ref()?
+        .iter()
+        .map(value_to_f64)
+        .collect::<Option<Vec<_>>>()
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    let v = match value {
+        Value::Int8(v) => *v as f64,
+        Value::Int16(v) => *v as f64,
+        Value::Int32(v) => *v as f64,
+        Value::Int64(v) => *v as f64,
+        Value::UInt8(v) => *v as f64,
+        Value::UInt16(v) => *v as f64,
+        Value::UInt32(v) => *v as f64,
+        Value::UInt64(v) => *v as f64,
+        Value::Float32(v) => v.0 as f64,
+        Value::Float64(v) => v.0,
+        _ => return None,
+    };
+    Some(v)
+}
+
+/// Builds a float64 list value from `values`.
+pub(crate) fn f64s_to_list(values: &[f64]) -> Value {
+    let items = values.iter().map(|v| Value::from(*v)).collect::<Vec<_>>();
+    Value::List(ListValue::new(
+        Some(Box::new(items)),
+        ConcreteDataType::float64_datatype(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_from_lists() {
+        let bounds = f64s_to_list(&[1.0, f64::INFINITY]);
+        let counts = Value::List(ListValue::new(
+            Some(Box::new(vec![Value::UInt64(3), Value::UInt64(5)])),
+            ConcreteDataType::uint64_datatype(),
+        ));
+        let h = histogram_from_lists(&bounds, &counts).unwrap();
+        assert_eq!(&[3.0, 5.0], h.counts());
+
+        assert!(histogram_from_lists(&Value::Null, &counts).is_none());
+        assert!(histogram_from_lists(&f64s_to_list(&[1.0]), &counts).is_none());
+    }
+
+    #[test]
+    fn test_value_to_histogram() {
+        let h = Histogram::new(vec![1.0, f64::INFINITY], vec![1.0, 2.0]).unwrap();
+        assert_eq!(Some(h.clone()), value_to_histogram(Value::Histogram(h)));
+        assert_eq!(None, value_to_histogram(Value::Null));
+    }
+}
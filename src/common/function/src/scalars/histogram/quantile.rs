@@ -0,0 +1,218 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{self, Result};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
+use datatypes::prelude::*;
+use datatypes::vectors::{Float64Vector, VectorRef};
+use snafu::ensure;
+
+use crate::scalars::function::{Function, FunctionContext};
+use crate::scalars::histogram::{
+    histogram_from_lists, value_to_f64, value_to_histogram, Histogram,
+};
+
+const NAME: &str = "histogram_quantile";
+
+/// `histogram_quantile(q, histogram)` estimates the `q`-quantile of a histogram column,
+/// `histogram_quantile(q, bounds, counts)` estimates the `q`-quantile of the histogram
+/// whose bucket upper bounds are `bounds` and cumulative bucket counts are `counts`.
+#[derive(Clone, Debug, Default)]
+pub struct HistogramQuantileFunction;
+
+impl Function for HistogramQuantileFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::one_of(
+            vec![TypeSignature::Any(2), TypeSignature::Any(3)],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2 || columns.len() == 3,
+            error::InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 2 or 3, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let q = &columns[0];
+        ensure!(
+            q.data_type().is_null() || ConcreteDataType::numerics().contains(&q.data_type()),
+            error::InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The quantile of {NAME} must be a number, have: {:?}",
+                    q.data_type()
+                ),
+            }
+        );
+        ensure!(
+            columns.iter().all(|c| c.len() == q.len()),
+            error::InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The args of {NAME} must have the same length, have: {:?}",
+                    columns.iter().map(|c| c.len()).collect::<Vec<_>>()
+                ),
+            }
+        );
+
+        let histogram_at: Box<dyn Fn(usize) -> Option<Histogram>> = if columns.len() == 2 {
+            let histograms = &columns[1];
+            ensure!(
+                histograms.data_type().is_histogram(),
+                error::InvalidFuncArgsSnafu {
+                    err_msg: format!(
+                        "The second arg of {NAME} must be a histogram, have: {:?}",
+                        histograms.data_type()
+                    ),
+                }
+            );
+            Box::new(move |i| value_to_histogram(histograms.get(i)))
+        } else {
+            let bounds = &columns[1];
+            let counts = &columns[2];
+            ensure!(
+                bounds.data_type().as_list().is_some() && counts.data_type().as_list().is_some(),
+                error::InvalidFuncArgsSnafu {
+                    err_msg: format!(
+                        "The bounds and counts of {NAME} must be lists, have: {:?}, {:?}",
+                        bounds.data_type(),
+                        counts.data_type()
+                    ),
+                }
+            );
+            Box::new(move |i| histogram_from_lists(&bounds.get(i), &counts.get(i)))
+        };
+
+        let result = (0..q.len())
+            .map(|i| {
+                let q = value_to_f64(&q.get(i))?;
+                histogram_at(i).map(|h| h.quantile(q))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Arc::new(Float64Vector::from(result)))
+    }
+}
+
+impl fmt::Display for HistogramQuantileFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HISTOGRAM_QUANTILE")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::{ConstantVector, HistogramVector, ListVectorBuilder, StringVector};
+
+    use super::*;
+    use crate::scalars::histogram::f64s_to_list;
+
+    fn new_list_vector(lists: &[&[f64]]) -> VectorRef {
+        let mut builder = ListVectorBuilder::with_type_capacity(
+            ConcreteDataType::float64_datatype(),
+            lists.len(),
+        );
+        for list in lists {
+            builder
+                .push_value_ref(f64s_to_list(list).as_value_ref())
+                .unwrap();
+        }
+        builder.to_vector()
+    }
+
+    #[test]
+    fn test_histogram_quantile() {
+        let f = HistogramQuantileFunction::default();
+        assert_eq!("histogram_quantile", f.name());
+        assert_eq!(
+            ConcreteDataType::float64_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let q: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(Float64Vector::from_slice([0.5])),
+            2,
+        ));
+        let bounds = new_list_vector(&[&[1.0, 2.0, f64::INFINITY], &[1.0, 2.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[2.0, 4.0, 4.0], &[4.0, 4.0, 4.0]]);
+
+        let v = f
+            .eval(FunctionContext::default(), &[q, bounds, counts])
+            .unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([1.0, 0.5]));
+        assert_eq!(expect, v);
+    }
+
+    #[test]
+    fn test_histogram_quantile_invalid_args() {
+        let f = HistogramQuantileFunction::default();
+        let q: VectorRef = Arc::new(Float64Vector::from_slice([0.5]));
+        assert!(f
+            .eval(
+                FunctionContext::default(),
+                &[q.clone(), q.clone(), q.clone()]
+            )
+            .is_err());
+        assert!(f
+            .eval(FunctionContext::default(), &[q.clone(), q.clone()])
+            .is_err());
+
+        // The quantile must be a number.
+        let bounds = new_list_vector(&[&[1.0, f64::INFINITY]]);
+        let counts = new_list_vector(&[&[1.0, 2.0]]);
+        let s: VectorRef = Arc::new(StringVector::from(vec!["0.5"]));
+        assert!(f
+            .eval(
+                FunctionContext::default(),
+                &[s, bounds.clone(), counts.clone()]
+            )
+            .is_err());
+
+        // The args must have the same length.
+        let q: VectorRef = Arc::new(Float64Vector::from_slice([0.5, 0.9]));
+        assert!(f
+            .eval(FunctionContext::default(), &[q, bounds, counts])
+            .is_err());
+    }
+
+    #[test]
+    fn test_histogram_quantile_of_histogram_type() {
+        let f = HistogramQuantileFunction::default();
+        let q: VectorRef = Arc::new(Float64Vector::from(vec![Some(0.5), Some(0.5), None]));
+        let h = Histogram::new(vec![1.0, 2.0, f64::INFINITY], vec![2.0, 4.0, 4.0]).unwrap();
+        let histograms: VectorRef = Arc::new(HistogramVector::from_owned_iterator(
+            vec![Some(h.clone()), None, Some(h)].into_iter(),
+        ));
+
+        let v = f
+            .eval(FunctionContext::default(), &[q, histograms])
+            .unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from(vec![Some(1.0), None, None]));
+        assert_eq!(expect, v);
+    }
+}
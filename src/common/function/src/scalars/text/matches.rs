@@ -0,0 +1,125 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use common_base::tokenizer::tokenize;
+use common_query::error::{self, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::*;
+use datatypes::vectors::{BooleanVector, VectorRef};
+use snafu::ensure;
+
+use crate::scalars::function::{Function, FunctionContext};
+
+pub const MATCHES_FUNCTION_NAME: &str = "matches";
+
+/// `matches(field, query)` returns whether the text in `field` contains all tokens of
+/// the `query`, case insensitively. Texts are split into tokens by
+/// [tokenize](common_base::tokenizer::tokenize).
+#[derive(Clone, Debug, Default)]
+pub struct MatchesFunction;
+
+impl Function for MatchesFunction {
+    fn name(&self) -> &str {
+        MATCHES_FUNCTION_NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::boolean_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::string_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            error::InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly 2, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let fields = &columns[0];
+        let queries = &columns[1];
+        let len = fields.len().max(queries.len());
+        let result = (0..len)
+            .map(|i| match (fields.get(i), queries.get(i)) {
+                (Value::String(field), Value::String(query)) => {
+                    let field_tokens = tokenize(field.as_utf8()).collect::<HashSet<_>>();
+                    Some(tokenize(query.as_utf8()).all(|token| field_tokens.contains(&token)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Arc::new(BooleanVector::from(result)))
+    }
+}
+
+impl fmt::Display for MatchesFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MATCHES")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::{ConstantVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let f = MatchesFunction::default();
+        assert_eq!("matches", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        let fields: VectorRef = Arc::new(StringVector::from(vec![
+            Some("GET /api/v1/users 404"),
+            Some("POST /api/v1/users 200"),
+            Some("user not found"),
+            None,
+        ]));
+        let queries: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec!["users 404"])),
+            4,
+        ));
+        let v = f
+            .eval(FunctionContext::default(), &[fields, queries])
+            .unwrap();
+        let expect: VectorRef = Arc::new(BooleanVector::from(vec![
+            Some(true),
+            Some(false),
+            Some(false),
+            None,
+        ]));
+        assert_eq!(expect, v);
+    }
+}
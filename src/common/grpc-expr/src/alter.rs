@@ -15,7 +15,9 @@
 use std::sync::Arc;
 
 use api::v1::alter_expr::Kind;
-use api::v1::{AlterExpr, CreateTableExpr, DropColumns, RenameTable};
+use api::v1::{
+    AlterExpr, CreateTableExpr, DropColumns, RenameTable, SetColumnComment, SetComment, SetReadOnly,
+};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
 use snafu::{ensure, OptionExt, ResultExt};
@@ -51,6 +53,7 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<AlterTableRequest> {
                     Ok(AddColumnRequest {
                         column_schema: schema,
                         is_key: ac.is_key,
+                        add_if_not_exists: ac.add_if_not_exists,
                     })
                 })
                 .collect::<Result<Vec<_>>>()?;
@@ -90,6 +93,47 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<AlterTableRequest> {
             };
             Ok(request)
         }
+        Kind::SetReadOnly(SetReadOnly { read_only }) => {
+            let alter_kind = AlterKind::SetReadOnly { read_only };
+            let request = AlterTableRequest {
+                catalog_name,
+                schema_name,
+                table_name: expr.table_name,
+                alter_kind,
+            };
+            Ok(request)
+        }
+        Kind::SetComment(SetComment { comment }) => {
+            let comment = if comment.is_empty() {
+                None
+            } else {
+                Some(comment)
+            };
+            let alter_kind = AlterKind::SetComment { comment };
+            let request = AlterTableRequest {
+                catalog_name,
+                schema_name,
+                table_name: expr.table_name,
+                alter_kind,
+            };
+            Ok(request)
+        }
+        Kind::SetColumnComment(SetColumnComment {
+            column_name,
+            comment,
+        }) => {
+            let alter_kind = AlterKind::SetColumnComment {
+                name: column_name,
+                comment,
+            };
+            let request = AlterTableRequest {
+                catalog_name,
+                schema_name,
+                table_name: expr.table_name,
+                alter_kind,
+            };
+            Ok(request)
+        }
     }
 }
 
@@ -204,8 +248,10 @@ mod tests {
                         datatype: ColumnDataType::Float64 as i32,
                         is_nullable: false,
                         default_constraint: vec![],
+                        comment: String::new(),
                     }),
                     is_key: false,
+                    add_if_not_exists: false,
                 }],
             })),
         };
@@ -253,4 +299,56 @@ mod tests {
         assert_eq!(1, drop_names.len());
         assert_eq!("mem_usage".to_string(), drop_names.pop().unwrap());
     }
+
+    #[test]
+    fn test_set_read_only_expr() {
+        let expr = AlterExpr {
+            catalog_name: "test_catalog".to_string(),
+            schema_name: "test_schema".to_string(),
+            table_name: "monitor".to_string(),
+            kind: Some(Kind::SetReadOnly(SetReadOnly { read_only: true })),
+        };
+
+        let alter_request = alter_expr_to_request(expr).unwrap();
+        assert_eq!("monitor".to_string(), alter_request.table_name);
+        assert!(matches!(
+            alter_request.alter_kind,
+            AlterKind::SetReadOnly { read_only: true }
+        ));
+    }
+
+    #[test]
+    fn test_set_comment_expr() {
+        let expr = AlterExpr {
+            catalog_name: "test_catalog".to_string(),
+            schema_name: "test_schema".to_string(),
+            table_name: "monitor".to_string(),
+            kind: Some(Kind::SetComment(SetComment {
+                comment: String::new(),
+            })),
+        };
+        let alter_request = alter_expr_to_request(expr).unwrap();
+        assert!(matches!(
+            alter_request.alter_kind,
+            AlterKind::SetComment { comment: None }
+        ));
+
+        let expr = AlterExpr {
+            catalog_name: "test_catalog".to_string(),
+            schema_name: "test_schema".to_string(),
+            table_name: "monitor".to_string(),
+            kind: Some(Kind::SetColumnComment(SetColumnComment {
+                column_name: "cpu".to_string(),
+                comment: "cpu usage in percent".to_string(),
+            })),
+        };
+        let alter_request = alter_expr_to_request(expr).unwrap();
+        match alter_request.alter_kind {
+            AlterKind::SetColumnComment { name, comment } => {
+                assert_eq!("cpu", name);
+                assert_eq!("cpu usage in percent", comment);
+            }
+            _ => unreachable!(),
+        }
+    }
 }
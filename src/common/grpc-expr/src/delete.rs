@@ -0,0 +1,82 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::DeleteRequest as GrpcDeleteRequest;
+use snafu::ensure;
+use table::requests::DeleteRequest;
+
+use crate::error::{IllegalInsertDataSnafu, Result};
+use crate::insert::column_to_vector;
+
+/// Converts the key columns of a gRPC delete request to a table delete request.
+pub fn to_table_delete_request(request: GrpcDeleteRequest) -> Result<DeleteRequest> {
+    let mut key_column_values = HashMap::with_capacity(request.key_columns.len());
+    for column in &request.key_columns {
+        let vector = column_to_vector(column, request.row_count)?;
+        ensure!(
+            key_column_values
+                .insert(column.column_name.clone(), vector)
+                .is_none(),
+            IllegalInsertDataSnafu
+        );
+    }
+    Ok(DeleteRequest { key_column_values })
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::{SemanticType, Values};
+    use api::v1::{Column, ColumnDataType};
+    use datatypes::prelude::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_to_table_delete_request() {
+        let request = GrpcDeleteRequest {
+            table_name: "demo".to_string(),
+            key_columns: vec![
+                Column {
+                    column_name: "host".to_string(),
+                    semantic_type: SemanticType::Tag as i32,
+                    values: Some(Values {
+                        string_values: vec!["h1".to_string(), "h2".to_string()],
+                        ..Default::default()
+                    }),
+                    datatype: ColumnDataType::String as i32,
+                    ..Default::default()
+                },
+                Column {
+                    column_name: "ts".to_string(),
+                    semantic_type: SemanticType::Timestamp as i32,
+                    values: Some(Values {
+                        ts_millisecond_values: vec![1, 2],
+                        ..Default::default()
+                    }),
+                    datatype: ColumnDataType::TimestampMillisecond as i32,
+                    ..Default::default()
+                },
+            ],
+            row_count: 2,
+        };
+
+        let request = to_table_delete_request(request).unwrap();
+        assert_eq!(2, request.key_column_values.len());
+        let host = &request.key_column_values["host"];
+        assert_eq!(Value::from("h2"), host.get(1));
+        assert_eq!(2, request.key_column_values["ts"].len());
+    }
+}
@@ -13,10 +13,12 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::fmt;
 
 use api::DecodeError;
 use common_error::ext::ErrorExt;
 use common_error::prelude::{Snafu, StatusCode};
+use datatypes::data_type::ConcreteDataType;
 use snafu::{Backtrace, ErrorCompat};
 
 #[derive(Debug, Snafu)]
@@ -90,6 +92,120 @@ pub enum Error {
         #[snafu(backtrace)]
         source: api::error::Error,
     },
+
+    #[snafu(display("Unknown compression of insert request: {}", compression))]
+    UnknownCompression {
+        compression: i32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to decompress values of column {}, source: {}", column, source))]
+    DecompressColumnValues {
+        column: String,
+        source: lz4_flex::block::DecompressError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Decompressed size {} of column {} exceeds the limit {}",
+        size,
+        column,
+        limit
+    ))]
+    DecompressedSizeTooLarge {
+        column: String,
+        size: usize,
+        limit: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to decode values of column {}, source: {}", column, source))]
+    DecodeColumnValues {
+        column: String,
+        source: DecodeError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Invalid insert request of table {}, {}",
+        table_name,
+        InsertColumnErrors(errors)
+    ))]
+    InvalidInsertRequest {
+        table_name: String,
+        errors: Vec<InsertColumnError>,
+        backtrace: Backtrace,
+    },
+}
+
+/// Problem of a column in an insert request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertColumnError {
+    pub column_name: String,
+    pub kind: InsertColumnErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertColumnErrorKind {
+    /// The column appears more than once in the request.
+    Duplicated,
+    /// The column doesn't exist in the table.
+    NotFound,
+    /// The datatype of the column is not a valid `ColumnDataType`.
+    UnknownDatatype(i32),
+    DatatypeMismatch {
+        expected: ConcreteDataType,
+        actual: ConcreteDataType,
+    },
+    /// Length of the null mask in bytes is not the one of the row count.
+    NullMaskLength { expected: usize, actual: usize },
+    /// Number of values doesn't match the number of non-null rows.
+    ValuesCount { expected: usize, actual: usize },
+    /// Value at `row` is null but the column is not nullable.
+    NullValue { row: usize },
+    /// Value at `row` could not be parsed into the type of the column.
+    InvalidValue { row: usize },
+}
+
+impl fmt::Display for InsertColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: ", self.column_name)?;
+        match &self.kind {
+            InsertColumnErrorKind::Duplicated => write!(f, "duplicated"),
+            InsertColumnErrorKind::NotFound => write!(f, "not found"),
+            InsertColumnErrorKind::UnknownDatatype(datatype) => {
+                write!(f, "unknown datatype {datatype}")
+            }
+            InsertColumnErrorKind::DatatypeMismatch { expected, actual } => {
+                write!(f, "expect type {expected:?}, actual: {actual:?}")
+            }
+            InsertColumnErrorKind::NullMaskLength { expected, actual } => {
+                write!(f, "expect null mask of {expected} bytes, actual: {actual}")
+            }
+            InsertColumnErrorKind::ValuesCount { expected, actual } => {
+                write!(f, "expect {expected} values, actual: {actual}")
+            }
+            InsertColumnErrorKind::NullValue { row } => {
+                write!(f, "null value at row {row} of a non-null column")
+            }
+            InsertColumnErrorKind::InvalidValue { row } => write!(f, "invalid value at row {row}"),
+        }
+    }
+}
+
+/// Formats column errors as a semicolon separated list.
+struct InsertColumnErrors<'a>(&'a [InsertColumnError]);
+
+impl fmt::Display for InsertColumnErrors<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -105,8 +221,14 @@ impl ErrorExt for Error {
             Error::CreateSchema { .. }
             | Error::DuplicatedTimestampColumn { .. }
             | Error::MissingTimestampColumn { .. } => StatusCode::InvalidArguments,
-            Error::InvalidColumnProto { .. } => StatusCode::InvalidArguments,
+            Error::InvalidColumnProto { .. } | Error::InvalidInsertRequest { .. } => {
+                StatusCode::InvalidArguments
+            }
             Error::CreateVector { .. } => StatusCode::InvalidArguments,
+            Error::UnknownCompression { .. }
+            | Error::DecompressColumnValues { .. }
+            | Error::DecompressedSizeTooLarge { .. }
+            | Error::DecodeColumnValues { .. } => StatusCode::InvalidArguments,
             Error::MissingField { .. } => StatusCode::InvalidArguments,
             Error::ColumnDefaultConstraint { source, .. } => source.status_code(),
             Error::InvalidColumnDef { source, .. } => source.status_code(),
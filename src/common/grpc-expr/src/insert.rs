@@ -15,29 +15,38 @@
 use std::collections::{HashMap, HashSet};
 
 use api::helper::ColumnDataTypeWrapper;
-use api::v1::column::{SemanticType, Values};
+use api::v1::column::{Histogram as HistogramValue, SemanticType, Values};
 use api::v1::{
-    AddColumn, AddColumns, Column, ColumnDataType, ColumnDef, CreateTableExpr,
+    AddColumn, AddColumns, Column, ColumnDataType, ColumnDef, Compression, CreateTableExpr,
     InsertRequest as GrpcInsertRequest,
 };
 use common_base::BitVec;
 use common_time::timestamp::Timestamp;
 use common_time::{Date, DateTime};
 use datatypes::data_type::{ConcreteDataType, DataType};
+use datatypes::decimal::{DECIMAL128_DEFAULT_SCALE, DECIMAL128_MAX_PRECISION};
 use datatypes::prelude::{ValueRef, VectorRef};
 use datatypes::schema::SchemaRef;
-use datatypes::value::Value;
+use datatypes::types::TimestampType;
+use datatypes::value::{Decimal128, Histogram, HistogramValueRef, Value};
 use datatypes::vectors::MutableVector;
+use prost::Message;
 use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::ChangeBatch;
 use table::metadata::TableId;
 use table::requests::InsertRequest;
 
 use crate::error::{
-    ColumnDataTypeSnafu, CreateVectorSnafu, DuplicatedTimestampColumnSnafu, IllegalInsertDataSnafu,
-    InvalidColumnProtoSnafu, MissingTimestampColumnSnafu, Result,
+    ColumnDataTypeSnafu, CreateVectorSnafu, DecodeColumnValuesSnafu, DecompressColumnValuesSnafu,
+    DecompressedSizeTooLargeSnafu, DuplicatedTimestampColumnSnafu, IllegalInsertDataSnafu,
+    InsertColumnError, InsertColumnErrorKind, InvalidColumnProtoSnafu, InvalidInsertRequestSnafu,
+    MissingTimestampColumnSnafu, Result, UnknownCompressionSnafu,
 };
 const TAG_SEMANTIC_TYPE: i32 = SemanticType::Tag as i32;
 const TIMESTAMP_SEMANTIC_TYPE: i32 = SemanticType::Timestamp as i32;
+/// LZ4 compresses at most 255 bytes into 1 byte, a larger size prepended to the compressed
+/// values must be forged, so we reject it instead of allocating a buffer of that size.
+const LZ4_MAX_COMPRESSION_RATIO: usize = 255;
 
 #[inline]
 fn build_column_def(column_name: &str, datatype: i32, nullable: bool) -> ColumnDef {
@@ -46,6 +55,7 @@ fn build_column_def(column_name: &str, datatype: i32, nullable: bool) -> ColumnD
         datatype,
         is_nullable: nullable,
         default_constraint: vec![],
+        comment: String::new(),
     }
 }
 
@@ -66,6 +76,8 @@ pub fn find_new_columns(schema: &SchemaRef, columns: &[Column]) -> Result<Option
             columns_to_add.push(AddColumn {
                 column_def,
                 is_key: *semantic_type == TAG_SEMANTIC_TYPE,
+                // Concurrent inserts may add the same column.
+                add_if_not_exists: true,
             });
             new_columns.insert(column_name.to_string());
         }
@@ -80,15 +92,52 @@ pub fn find_new_columns(schema: &SchemaRef, columns: &[Column]) -> Result<Option
     }
 }
 
+/// Converts rows of a committed write read from the WAL to gRPC columns, the timestamp
+/// column and columns in `primary_keys` are marked by their semantic types.
+pub fn change_batch_to_columns(
+    batch: &ChangeBatch,
+    primary_keys: &[String],
+) -> Result<Vec<Column>> {
+    let timestamp_index = batch.schema.timestamp_index();
+    batch
+        .schema
+        .column_schemas()
+        .iter()
+        .zip(batch.columns.iter())
+        .enumerate()
+        .map(|(i, (column_schema, vector))| {
+            let datatype = ColumnDataTypeWrapper::try_from(column_schema.data_type.clone())
+                .context(ColumnDataTypeSnafu)?;
+            let semantic_type = if Some(i) == timestamp_index {
+                SemanticType::Timestamp
+            } else if primary_keys.contains(&column_schema.name) {
+                SemanticType::Tag
+            } else {
+                SemanticType::Field
+            };
+            let mut column = Column {
+                column_name: column_schema.name.clone(),
+                semantic_type: semantic_type.into(),
+                datatype: datatype.datatype() as i32,
+                ..Default::default()
+            };
+            column.push_vals(0, vector.clone());
+            Ok(column)
+        })
+        .collect()
+}
+
 pub fn column_to_vector(column: &Column, rows: u32) -> Result<VectorRef> {
     let wrapper = ColumnDataTypeWrapper::try_new(column.datatype).context(ColumnDataTypeSnafu)?;
     let column_datatype = wrapper.datatype();
 
     let rows = rows as usize;
-    let mut vector = ConcreteDataType::from(wrapper).create_mutable_vector(rows);
+    let mut vector =
+        column_concrete_datatype(wrapper, column.values.as_ref()).create_mutable_vector(rows);
 
     if let Some(values) = &column.values {
-        let values = collect_column_values(column_datatype, values);
+        let histograms = convert_histograms(values);
+        let values = collect_column_values(column_datatype, values, &histograms);
         let mut values_iter = values.into_iter();
 
         let null_mask = BitVec::from_slice(&column.null_mask);
@@ -108,6 +157,15 @@ pub fn column_to_vector(column: &Column, rows: u32) -> Result<VectorRef> {
                             i, &column.column_name
                         ),
                     })?;
+                ensure!(
+                    !value_ref.is_null(),
+                    InvalidColumnProtoSnafu {
+                        err_msg: format!(
+                            "invalid value at position {} of column {}",
+                            i, &column.column_name
+                        ),
+                    }
+                );
                 vector
                     .push_value_ref(value_ref)
                     .context(CreateVectorSnafu)?;
@@ -123,7 +181,34 @@ pub fn column_to_vector(column: &Column, rows: u32) -> Result<VectorRef> {
     Ok(vector.to_vector())
 }
 
-fn collect_column_values(column_datatype: ColumnDataType, values: &Values) -> Vec<ValueRef> {
+/// Decimals are transferred as strings, so the scale of a decimal column is inferred from
+/// its values to avoid truncating them.
+fn column_concrete_datatype(
+    wrapper: ColumnDataTypeWrapper,
+    values: Option<&Values>,
+) -> ConcreteDataType {
+    match (wrapper.datatype(), values) {
+        (ColumnDataType::Decimal128, Some(values)) => {
+            let scale = values
+                .decimal128_values
+                .iter()
+                .filter_map(|v| v.parse::<Decimal128>().ok())
+                .map(|v| v.scale())
+                .max()
+                .unwrap_or(DECIMAL128_DEFAULT_SCALE);
+            ConcreteDataType::decimal128_datatype(DECIMAL128_MAX_PRECISION, scale)
+        }
+        _ => wrapper.into(),
+    }
+}
+
+/// Collects the values of the column, values failed to parse, like invalid decimals, are
+/// collected as nulls, which are rejected by callers as the null mask says they are not.
+fn collect_column_values<'a>(
+    column_datatype: ColumnDataType,
+    values: &'a Values,
+    histograms: &'a [Option<Histogram>],
+) -> Vec<ValueRef<'a>> {
     macro_rules! collect_values {
         ($value: expr, $mapper: expr) => {
             $value.iter().map($mapper).collect::<Vec<ValueRef>>()
@@ -181,18 +266,58 @@ fn collect_column_values(column_datatype: ColumnDataType, values: &Values) -> Ve
             ))
         }
         ColumnDataType::TimestampMicrosecond => {
-            collect_values!(values.ts_millisecond_values, |v| ValueRef::Timestamp(
+            collect_values!(values.ts_microsecond_values, |v| ValueRef::Timestamp(
                 Timestamp::new_microsecond(*v)
             ))
         }
         ColumnDataType::TimestampNanosecond => {
-            collect_values!(values.ts_millisecond_values, |v| ValueRef::Timestamp(
+            collect_values!(values.ts_nanosecond_values, |v| ValueRef::Timestamp(
                 Timestamp::new_nanosecond(*v)
             ))
         }
+        ColumnDataType::Decimal128 => {
+            collect_values!(values.decimal128_values, |v| v
+                .parse::<Decimal128>()
+                .map(ValueRef::Decimal128)
+                .unwrap_or(ValueRef::Null))
+        }
+        ColumnDataType::Histogram => collect_values!(histograms, |v| ValueRef::from(
+            v.as_ref().map(|val| HistogramValueRef::Ref { val })
+        )),
     }
 }
 
+/// Histograms are transferred as messages, so they are converted before being collected
+/// by [collect_column_values]. Invalid histograms, whose bounds and counts have different
+/// lengths, are converted to `None`.
+fn convert_histograms(values: &Values) -> Vec<Option<Histogram>> {
+    values
+        .histogram_values
+        .iter()
+        .map(|v| Histogram::new(v.bounds.clone(), v.counts.clone()))
+        .collect()
+}
+
+fn parse_histogram(v: HistogramValue) -> Result<Histogram> {
+    let (bounds, counts) = (v.bounds.len(), v.counts.len());
+    Histogram::new(v.bounds, v.counts).with_context(|| InvalidColumnProtoSnafu {
+        err_msg: format!(
+            "invalid histogram value, bounds length {bounds} != counts length {counts}"
+        ),
+    })
+}
+
+/// Parses a decimal transferred as string, invalid decimals are rejected instead of being
+/// inserted as nulls.
+fn parse_decimal(v: &str) -> Result<Decimal128> {
+    v.parse::<Decimal128>().map_err(|e| {
+        InvalidColumnProtoSnafu {
+            err_msg: format!("invalid decimal value {v}: {e}"),
+        }
+        .build()
+    })
+}
+
 /// Try to build create table request from insert data.
 pub fn build_create_expr_from_insertion(
     catalog_name: &str,
@@ -266,11 +391,182 @@ pub fn build_create_expr_from_insertion(
     Ok(expr)
 }
 
+/// Validates columns of the insert `request` against the table `schema`, all the
+/// problems found are reported together so callers could tell which columns and rows
+/// are rejected.
+pub fn validate_insert_request(request: &GrpcInsertRequest, schema: &SchemaRef) -> Result<()> {
+    let row_count = request.row_count as usize;
+    let mut errors = Vec::new();
+    let mut column_names = HashSet::with_capacity(request.columns.len());
+
+    for column in &request.columns {
+        let column_name = &column.column_name;
+        let mut add_error = |kind| {
+            errors.push(InsertColumnError {
+                column_name: column_name.clone(),
+                kind,
+            })
+        };
+
+        if !column_names.insert(column_name) {
+            add_error(InsertColumnErrorKind::Duplicated);
+            continue;
+        }
+        // Columns without values are ignored by the insertion.
+        let Some(values) = &column.values else { continue };
+        let Some(column_schema) = schema.column_schema_by_name(column_name) else {
+            add_error(InsertColumnErrorKind::NotFound);
+            continue;
+        };
+        let Ok(wrapper) = ColumnDataTypeWrapper::try_new(column.datatype) else {
+            add_error(InsertColumnErrorKind::UnknownDatatype(column.datatype));
+            continue;
+        };
+        let datatype = column_concrete_datatype(wrapper, Some(values));
+        // Only compares the logical type, as the precision and scale of decimals are
+        // inferred from the values.
+        if datatype.logical_type_id() != column_schema.data_type.logical_type_id() {
+            add_error(InsertColumnErrorKind::DatatypeMismatch {
+                expected: column_schema.data_type.clone(),
+                actual: datatype,
+            });
+            continue;
+        }
+
+        let expected_mask_len = (row_count + 7) / 8;
+        if !column.null_mask.is_empty() && column.null_mask.len() != expected_mask_len {
+            add_error(InsertColumnErrorKind::NullMaskLength {
+                expected: expected_mask_len,
+                actual: column.null_mask.len(),
+            });
+            continue;
+        }
+        let null_mask = BitVec::from_slice(&column.null_mask);
+        let histograms = convert_histograms(values);
+        let values = collect_column_values(wrapper.datatype(), values, &histograms);
+        let expected_values = row_count.saturating_sub(null_mask.count_ones());
+        if values.len() != expected_values {
+            add_error(InsertColumnErrorKind::ValuesCount {
+                expected: expected_values,
+                actual: values.len(),
+            });
+            continue;
+        }
+
+        let mut values = values.into_iter();
+        for row in 0..row_count {
+            let kind = match is_null(&null_mask, row) {
+                Some(true) if !column_schema.is_nullable() => {
+                    InsertColumnErrorKind::NullValue { row }
+                }
+                Some(true) => continue,
+                // Only values failed to parse are null here.
+                _ => match values.next() {
+                    Some(ValueRef::Null) => InsertColumnErrorKind::InvalidValue { row },
+                    _ => continue,
+                },
+            };
+            // Reports the first invalid row of each column.
+            add_error(kind);
+            break;
+        }
+    }
+
+    ensure!(
+        errors.is_empty(),
+        InvalidInsertRequestSnafu {
+            table_name: &request.table_name,
+            errors,
+        }
+    );
+    Ok(())
+}
+
+/// Compresses values of all columns of the insert `request` by `compression`, it's undone
+/// by [decompress_insert_request] at the server side.
+pub fn compress_insert_request(
+    mut request: GrpcInsertRequest,
+    compression: Compression,
+) -> GrpcInsertRequest {
+    if compression == Compression::Uncompressed {
+        return request;
+    }
+
+    for column in &mut request.columns {
+        if let Some(values) = column.values.take() {
+            column.compressed_values = match compression {
+                Compression::Lz4 => lz4_flex::compress_prepend_size(&values.encode_to_vec()),
+                Compression::Uncompressed => unreachable!(),
+            };
+        }
+    }
+    request.compression = compression as i32;
+    request
+}
+
+/// Restores values of columns of the compressed insert `request`, the request is returned
+/// as is if it's not compressed.
+pub fn decompress_insert_request(mut request: GrpcInsertRequest) -> Result<GrpcInsertRequest> {
+    let compression =
+        Compression::from_i32(request.compression).context(UnknownCompressionSnafu {
+            compression: request.compression,
+        })?;
+    if compression == Compression::Uncompressed {
+        return Ok(request);
+    }
+
+    for column in &mut request.columns {
+        // Columns without values are not compressed.
+        if column.compressed_values.is_empty() {
+            continue;
+        }
+        let bytes = match compression {
+            Compression::Lz4 => {
+                check_lz4_decompressed_size(&column.column_name, &column.compressed_values)?;
+                lz4_flex::decompress_size_prepended(&column.compressed_values).context(
+                    DecompressColumnValuesSnafu {
+                        column: &column.column_name,
+                    },
+                )?
+            }
+            Compression::Uncompressed => unreachable!(),
+        };
+        let values = Values::decode(bytes.as_slice()).context(DecodeColumnValuesSnafu {
+            column: &column.column_name,
+        })?;
+        column.values = Some(values);
+        column.compressed_values = vec![];
+    }
+    request.compression = Compression::Uncompressed as i32;
+    Ok(request)
+}
+
+/// Checks the uncompressed size prepended to the LZ4 compressed values, which is used to
+/// allocate the output buffer by [lz4_flex::decompress_size_prepended].
+fn check_lz4_decompressed_size(column: &str, compressed: &[u8]) -> Result<()> {
+    // Too short input is rejected by the decompression.
+    let size = match compressed.get(..4) {
+        Some(size) => u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize,
+        None => return Ok(()),
+    };
+    let limit = (compressed.len() - 4).saturating_mul(LZ4_MAX_COMPRESSION_RATIO);
+    ensure!(
+        size <= limit,
+        DecompressedSizeTooLargeSnafu {
+            column,
+            size,
+            limit,
+        }
+    );
+    Ok(())
+}
+
 pub fn to_table_insert_request(
     catalog_name: &str,
     schema_name: &str,
     request: GrpcInsertRequest,
 ) -> Result<InsertRequest> {
+    let request = decompress_insert_request(request)?;
     let table_name = &request.table_name;
     let row_count = request.row_count as usize;
 
@@ -285,9 +581,8 @@ pub fn to_table_insert_request(
     {
         let Some(values) = values else { continue };
 
-        let datatype: ConcreteDataType = ColumnDataTypeWrapper::try_new(datatype)
-            .context(ColumnDataTypeSnafu)?
-            .into();
+        let wrapper = ColumnDataTypeWrapper::try_new(datatype).context(ColumnDataTypeSnafu)?;
+        let datatype = column_concrete_datatype(wrapper, Some(&values));
 
         let vector_builder = &mut datatype.create_mutable_vector(row_count);
 
@@ -316,7 +611,7 @@ fn add_values_to_builder(
     null_mask: Vec<u8>,
 ) -> Result<()> {
     let data_type = builder.data_type();
-    let values = convert_values(&data_type, values);
+    let values = convert_values(&data_type, values)?;
 
     if null_mask.is_empty() {
         ensure!(values.len() == row_count, IllegalInsertDataSnafu);
@@ -351,9 +646,9 @@ fn add_values_to_builder(
     Ok(())
 }
 
-fn convert_values(data_type: &ConcreteDataType, values: Values) -> Vec<Value> {
+fn convert_values(data_type: &ConcreteDataType, values: Values) -> Result<Vec<Value>> {
     // TODO(fys): use macros to optimize code
-    match data_type {
+    Ok(match data_type {
         ConcreteDataType::Int64(_) => values
             .i64_values
             .into_iter()
@@ -421,14 +716,43 @@ fn convert_values(data_type: &ConcreteDataType, values: Values) -> Vec<Value> {
             .into_iter()
             .map(|v| Value::Date(v.into()))
             .collect(),
-        ConcreteDataType::Timestamp(_) => values
+        ConcreteDataType::Timestamp(TimestampType::Second(_)) => values
+            .ts_second_values
+            .into_iter()
+            .map(|v| Value::Timestamp(Timestamp::new_second(v)))
+            .collect(),
+        ConcreteDataType::Timestamp(TimestampType::Millisecond(_)) => values
             .ts_millisecond_values
             .into_iter()
             .map(|v| Value::Timestamp(Timestamp::new_millisecond(v)))
             .collect(),
+        ConcreteDataType::Timestamp(TimestampType::Microsecond(_)) => values
+            .ts_microsecond_values
+            .into_iter()
+            .map(|v| Value::Timestamp(Timestamp::new_microsecond(v)))
+            .collect(),
+        ConcreteDataType::Timestamp(TimestampType::Nanosecond(_)) => values
+            .ts_nanosecond_values
+            .into_iter()
+            .map(|v| Value::Timestamp(Timestamp::new_nanosecond(v)))
+            .collect(),
+        ConcreteDataType::Decimal128(_) => {
+            return values
+                .decimal128_values
+                .iter()
+                .map(|v| parse_decimal(v).map(Value::Decimal128))
+                .collect();
+        }
+        ConcreteDataType::Histogram(_) => {
+            return values
+                .histogram_values
+                .into_iter()
+                .map(|v| parse_histogram(v).map(Value::Histogram))
+                .collect();
+        }
         ConcreteDataType::Null(_) => unreachable!(),
         ConcreteDataType::List(_) => unreachable!(),
-    }
+    })
 }
 
 fn is_null(null_mask: &BitVec, idx: usize) -> Option<bool> {
@@ -450,7 +774,9 @@ mod tests {
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
     use datatypes::value::Value;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
     use snafu::ResultExt;
+    use store_api::storage::OpType;
     use table::error::Result as TableResult;
     use table::metadata::TableInfoRef;
     use table::Table;
@@ -608,6 +934,7 @@ mod tests {
             columns,
             row_count,
             region_number: 0,
+            ..Default::default()
         };
         let insert_req = to_table_insert_request("greptime", "public", request).unwrap();
 
@@ -632,6 +959,219 @@ mod tests {
         assert_eq!(Value::Timestamp(Timestamp::new_millisecond(101)), ts.get(1));
     }
 
+    #[test]
+    fn test_compressed_insert_request() {
+        let (columns, row_count) = mock_insert_batch();
+        let request = GrpcInsertRequest {
+            table_name: "demo".to_string(),
+            columns,
+            row_count,
+            region_number: 0,
+            ..Default::default()
+        };
+
+        let compressed = compress_insert_request(request.clone(), Compression::Lz4);
+        assert_eq!(Compression::Lz4 as i32, compressed.compression);
+        for column in &compressed.columns {
+            assert!(column.values.is_none());
+            assert!(!column.compressed_values.is_empty());
+        }
+        assert_eq!(
+            request,
+            decompress_insert_request(compressed.clone()).unwrap()
+        );
+
+        let insert_req = to_table_insert_request("greptime", "public", compressed).unwrap();
+        let cpu = insert_req.columns_values.get("cpu").unwrap();
+        assert_eq!(Value::Float64(0.31.into()), cpu.get(0));
+        assert_eq!(Value::Null, cpu.get(1));
+
+        let mut corrupted = compress_insert_request(request.clone(), Compression::Lz4);
+        corrupted.columns[0].compressed_values.truncate(2);
+        assert!(decompress_insert_request(corrupted).is_err());
+
+        // A forged uncompressed size is rejected before allocating the buffer.
+        let mut forged = compress_insert_request(request.clone(), Compression::Lz4);
+        forged.columns[0].compressed_values[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = decompress_insert_request(forged).unwrap_err();
+        assert!(
+            matches!(err, error::Error::DecompressedSizeTooLarge { .. }),
+            "{err:?}"
+        );
+
+        let mut unknown = request;
+        unknown.compression = 100;
+        assert!(decompress_insert_request(unknown).is_err());
+    }
+
+    #[test]
+    fn test_validate_insert_request() {
+        let schema = DemoTable.schema();
+        let (columns, row_count) = mock_insert_batch();
+        let mut request = GrpcInsertRequest {
+            table_name: "demo".to_string(),
+            columns,
+            row_count,
+            region_number: 0,
+            ..Default::default()
+        };
+        validate_insert_request(&request, &schema).unwrap();
+
+        // host: null in a non-null column.
+        request.columns[0].null_mask = vec![2];
+        request.columns[0].values.as_mut().unwrap().string_values = vec!["host1".to_string()];
+        // cpu: datatype mismatch.
+        request.columns[1].datatype = ColumnDataType::Int64 as i32;
+        // memory: wrong null mask length.
+        request.columns[2].null_mask = vec![1, 0];
+        // ts: too many values.
+        request.columns[3]
+            .values
+            .as_mut()
+            .unwrap()
+            .ts_millisecond_values
+            .push(102);
+        request.columns.push(Column {
+            column_name: "disk".to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(column::Values {
+                f64_values: vec![1.0, 2.0],
+                ..Default::default()
+            }),
+            null_mask: vec![],
+            datatype: ColumnDataType::Float64 as i32,
+            compressed_values: vec![],
+        });
+
+        let err = validate_insert_request(&request, &schema).unwrap_err();
+        let error::Error::InvalidInsertRequest { table_name, errors, .. } = &err else {
+            unreachable!("{err:?}");
+        };
+        assert_eq!("demo", table_name);
+        let kinds = errors
+            .iter()
+            .map(|e| (e.column_name.as_str(), e.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("host", InsertColumnErrorKind::NullValue { row: 1 }),
+                (
+                    "cpu",
+                    InsertColumnErrorKind::DatatypeMismatch {
+                        expected: ConcreteDataType::float64_datatype(),
+                        actual: ConcreteDataType::int64_datatype(),
+                    }
+                ),
+                (
+                    "memory",
+                    InsertColumnErrorKind::NullMaskLength {
+                        expected: 1,
+                        actual: 2
+                    }
+                ),
+                (
+                    "ts",
+                    InsertColumnErrorKind::ValuesCount {
+                        expected: 2,
+                        actual: 3
+                    }
+                ),
+                ("disk", InsertColumnErrorKind::NotFound),
+            ],
+            kinds
+        );
+        assert!(err
+            .to_string()
+            .contains("column host: null value at row 1 of a non-null column; column cpu:"));
+    }
+
+    #[test]
+    fn test_u64_and_binary_column_to_vector() {
+        let counter = Column {
+            column_name: "counter".to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(column::Values {
+                u64_values: vec![u64::MAX, 1],
+                ..Default::default()
+            }),
+            null_mask: vec![2],
+            datatype: ColumnDataType::Uint64 as i32,
+            compressed_values: vec![],
+        };
+        let vector = column_to_vector(&counter, 3).unwrap();
+        assert_eq!(Value::UInt64(u64::MAX), vector.get(0));
+        assert_eq!(Value::Null, vector.get(1));
+        assert_eq!(Value::UInt64(1), vector.get(2));
+
+        let payload = Column {
+            column_name: "payload".to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(column::Values {
+                binary_values: vec![b"hello".to_vec()],
+                ..Default::default()
+            }),
+            null_mask: vec![5],
+            datatype: ColumnDataType::Binary as i32,
+            compressed_values: vec![],
+        };
+        let request = GrpcInsertRequest {
+            table_name: "demo".to_string(),
+            columns: vec![counter, payload],
+            row_count: 3,
+            region_number: 0,
+            ..Default::default()
+        };
+        let insert_req = to_table_insert_request("greptime", "public", request).unwrap();
+
+        let counter = insert_req.columns_values.get("counter").unwrap();
+        assert_eq!(Value::UInt64(u64::MAX), counter.get(0));
+        assert_eq!(Value::Null, counter.get(1));
+
+        let payload = insert_req.columns_values.get("payload").unwrap();
+        assert_eq!(Value::Null, payload.get(0));
+        assert_eq!(Value::Binary(b"hello".to_vec().into()), payload.get(1));
+        assert_eq!(Value::Null, payload.get(2));
+    }
+
+    #[test]
+    fn test_nanosecond_timestamp_column() {
+        let ts = Column {
+            column_name: "ts".to_string(),
+            semantic_type: TIMESTAMP_SEMANTIC_TYPE,
+            values: Some(column::Values {
+                ts_nanosecond_values: vec![1, 2],
+                ..Default::default()
+            }),
+            null_mask: vec![],
+            datatype: ColumnDataType::TimestampNanosecond as i32,
+            compressed_values: vec![],
+        };
+        let vector = column_to_vector(&ts, 2).unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_nanosecond(1)),
+            vector.get(0)
+        );
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_nanosecond(2)),
+            vector.get(1)
+        );
+
+        let request = GrpcInsertRequest {
+            table_name: "demo".to_string(),
+            columns: vec![ts],
+            row_count: 2,
+            region_number: 0,
+            ..Default::default()
+        };
+        let insert_req = to_table_insert_request("greptime", "public", request).unwrap();
+        let ts = insert_req.columns_values.get("ts").unwrap();
+        assert_eq!(
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+            ts.data_type()
+        );
+        assert_eq!(Value::Timestamp(Timestamp::new_nanosecond(2)), ts.get(1));
+    }
+
     #[test]
     fn test_convert_values() {
         let data_type = ConcreteDataType::float64_datatype();
@@ -640,7 +1180,7 @@ mod tests {
             ..Default::default()
         };
 
-        let result = convert_values(&data_type, values);
+        let result = convert_values(&data_type, values).unwrap();
 
         assert_eq!(
             vec![
@@ -652,6 +1192,89 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_convert_decimal_values() {
+        let values = Values {
+            decimal128_values: vec!["1.5".to_string(), "-0.25".to_string()],
+            ..Default::default()
+        };
+        let wrapper = ColumnDataTypeWrapper::try_new(ColumnDataType::Decimal128 as i32).unwrap();
+        let data_type = column_concrete_datatype(wrapper, Some(&values));
+        assert_eq!(ConcreteDataType::decimal128_datatype(38, 2), data_type);
+
+        let result = convert_values(&data_type, values).unwrap();
+        assert_eq!(
+            vec![
+                Value::Decimal128(Decimal128::new(15, 2, 1)),
+                Value::Decimal128(Decimal128::new(-25, 2, 2)),
+            ],
+            result
+        );
+
+        // Invalid decimals are rejected instead of being inserted as nulls.
+        let values = Values {
+            decimal128_values: vec!["1.5".to_string(), "abc".to_string()],
+            ..Default::default()
+        };
+        assert!(convert_values(&data_type, values.clone()).is_err());
+        let column = Column {
+            column_name: "d".to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(values),
+            null_mask: vec![],
+            datatype: ColumnDataType::Decimal128 as i32,
+            compressed_values: vec![],
+        };
+        assert!(column_to_vector(&column, 2).is_err());
+    }
+
+    #[test]
+    fn test_convert_histogram_values() {
+        let histogram = HistogramValue {
+            bounds: vec![1.0, f64::INFINITY],
+            counts: vec![2.0, 3.0],
+        };
+        let values = Values {
+            histogram_values: vec![histogram.clone()],
+            ..Default::default()
+        };
+        let expect = Histogram::new(vec![1.0, f64::INFINITY], vec![2.0, 3.0]).unwrap();
+        let data_type = ConcreteDataType::histogram_datatype();
+        assert_eq!(
+            vec![Value::Histogram(expect.clone())],
+            convert_values(&data_type, values.clone()).unwrap()
+        );
+
+        let column = Column {
+            column_name: "h".to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(values),
+            null_mask: vec![0b0000_0010],
+            datatype: ColumnDataType::Histogram as i32,
+            compressed_values: vec![],
+        };
+        let vector = column_to_vector(&column, 2).unwrap();
+        assert_eq!(data_type, vector.data_type());
+        assert_eq!(Value::Histogram(expect), vector.get(0));
+        assert_eq!(Value::Null, vector.get(1));
+
+        // Histograms whose bounds and counts have different lengths are rejected.
+        let values = Values {
+            histogram_values: vec![HistogramValue {
+                bounds: vec![1.0],
+                ..histogram
+            }],
+            ..Default::default()
+        };
+        assert!(convert_values(&data_type, values.clone()).is_err());
+        let column = Column {
+            values: Some(values),
+            null_mask: vec![],
+            ..column
+        };
+        assert!(column_to_vector(&column, 1).is_err());
+    }
+
     #[test]
     fn test_is_null() {
         let null_mask = BitVec::from_slice(&[0b0000_0001, 0b0000_1000]);
@@ -722,6 +1345,7 @@ mod tests {
             values: Some(host_vals),
             null_mask: vec![0],
             datatype: ColumnDataType::String as i32,
+            compressed_values: vec![],
         };
 
         let cpu_vals = column::Values {
@@ -734,6 +1358,7 @@ mod tests {
             values: Some(cpu_vals),
             null_mask: vec![2],
             datatype: ColumnDataType::Float64 as i32,
+            compressed_values: vec![],
         };
 
         let mem_vals = column::Values {
@@ -746,6 +1371,7 @@ mod tests {
             values: Some(mem_vals),
             null_mask: vec![1],
             datatype: ColumnDataType::Float64 as i32,
+            compressed_values: vec![],
         };
 
         let ts_vals = column::Values {
@@ -758,6 +1384,7 @@ mod tests {
             values: Some(ts_vals),
             null_mask: vec![0],
             datatype: ColumnDataType::TimestampMillisecond as i32,
+            compressed_values: vec![],
         };
 
         (
@@ -765,4 +1392,48 @@ mod tests {
             row_count,
         )
     }
+
+    #[test]
+    fn test_change_batch_to_columns() {
+        let schema = Arc::new(
+            SchemaBuilder::try_from(vec![
+                ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+                ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+                ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                )
+                .with_time_index(true),
+            ])
+            .unwrap()
+            .build()
+            .unwrap(),
+        );
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![Some("host1"), None])),
+            Arc::new(Float64Vector::from_slice([0.5, 0.6])),
+            Arc::new(TimestampMillisecondVector::from_slice([1000, 2000])),
+        ];
+        let batch = ChangeBatch {
+            sequence: 1,
+            op_type: OpType::Put,
+            schema,
+            columns: columns.clone(),
+        };
+
+        let grpc_columns = change_batch_to_columns(&batch, &["host".to_string()]).unwrap();
+        let semantic_types: Vec<_> = grpc_columns.iter().map(|c| c.semantic_type).collect();
+        assert_eq!(
+            vec![
+                SemanticType::Tag as i32,
+                SemanticType::Field as i32,
+                SemanticType::Timestamp as i32
+            ],
+            semantic_types
+        );
+        for (column, expect) in grpc_columns.iter().zip(columns) {
+            assert_eq!(expect, column_to_vector(column, 2).unwrap());
+        }
+    }
 }
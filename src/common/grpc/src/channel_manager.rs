@@ -24,6 +24,8 @@ use tower::make::MakeConnection;
 
 use crate::error;
 use crate::error::Result;
+use crate::tls::GrpcTlsOption;
+use crate::token::{AttachTokenInterceptor, ClusterToken};
 
 const RECYCLE_CHANNEL_INTERVAL_SECS: u64 = 60;
 
@@ -51,6 +53,15 @@ impl ChannelManager {
         common_runtime::spawn_bg(async {
             recycle_channel_in_loop(cloned_pool, RECYCLE_CHANNEL_INTERVAL_SECS).await;
         });
+        if let Some(tls) = &config.client_tls {
+            if let Some(interval) = tls.reload_interval() {
+                let pool = pool.clone();
+                let tls = tls.clone();
+                common_runtime::spawn_bg(async move {
+                    reload_tls_in_loop(pool, tls, interval).await;
+                });
+            }
+        }
 
         Self { config, pool }
     }
@@ -59,6 +70,11 @@ impl ChannelManager {
         &self.config
     }
 
+    /// Returns the interceptor attaching the cluster token of the config to requests.
+    pub fn token_interceptor(&self) -> AttachTokenInterceptor {
+        AttachTokenInterceptor::new(self.config.cluster_token.clone())
+    }
+
     pub fn get(&self, addr: impl AsRef<str>) -> Result<InnerChannel> {
         let addr = addr.as_ref();
         // It will acquire the read lock.
@@ -119,8 +135,19 @@ impl ChannelManager {
     }
 
     fn build_endpoint(&self, addr: &str) -> Result<Endpoint> {
+        let scheme = if self.config.client_tls.is_some() {
+            "https"
+        } else {
+            "http"
+        };
         let mut endpoint =
-            Endpoint::new(format!("http://{addr}")).context(error::CreateChannelSnafu)?;
+            Endpoint::new(format!("{scheme}://{addr}")).context(error::CreateChannelSnafu)?;
+
+        if let Some(tls) = &self.config.client_tls {
+            endpoint = endpoint
+                .tls_config(tls.client_tls_config()?)
+                .context(error::CreateChannelSnafu)?;
+        }
 
         if let Some(dur) = self.config.timeout {
             endpoint = endpoint.timeout(dur);
@@ -174,6 +201,8 @@ pub struct ChannelConfig {
     pub http2_adaptive_window: Option<bool>,
     pub tcp_keepalive: Option<Duration>,
     pub tcp_nodelay: bool,
+    pub client_tls: Option<GrpcTlsOption>,
+    pub cluster_token: Option<ClusterToken>,
 }
 
 impl Default for ChannelConfig {
@@ -191,6 +220,8 @@ impl Default for ChannelConfig {
             http2_adaptive_window: None,
             tcp_keepalive: None,
             tcp_nodelay: true,
+            client_tls: None,
+            cluster_token: None,
         }
     }
 }
@@ -307,6 +338,24 @@ impl ChannelConfig {
             ..self
         }
     }
+
+    /// Connects to servers over TLS by `tls`.
+    ///
+    /// Channels are recreated with the new certificates once the files are modified.
+    pub fn client_tls(self, tls: GrpcTlsOption) -> Self {
+        Self {
+            client_tls: Some(tls),
+            ..self
+        }
+    }
+
+    /// Attaches `token` to requests, it's verified by servers of the cluster.
+    pub fn cluster_token(self, token: ClusterToken) -> Self {
+        Self {
+            cluster_token: Some(token),
+            ..self
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -378,11 +427,31 @@ async fn recycle_channel_in_loop(pool: Arc<Pool>, interval_secs: u64) {
     }
 }
 
+/// Drops the channels built by the default connector once the TLS files are modified,
+/// so channels created afterwards use the new certificates.
+async fn reload_tls_in_loop(pool: Arc<Pool>, tls: GrpcTlsOption, interval: Duration) {
+    let mut modified = tls.modified();
+    let mut interval = tokio::time::interval(interval);
+    // The first tick completes immediately.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        let latest = tls.modified();
+        if latest != modified {
+            modified = latest;
+            pool.retain_channel(|_, c| !c.use_default_connector);
+            common_telemetry::info!("TLS files modified, recreating gRPC channels");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tower::service_fn;
 
     use super::*;
+    use crate::tls::tests::mutual_tls_option;
 
     #[should_panic]
     #[test]
@@ -443,6 +512,8 @@ mod tests {
                 http2_adaptive_window: None,
                 tcp_keepalive: None,
                 tcp_nodelay: true,
+                client_tls: None,
+                cluster_token: None,
             },
             default_cfg
         );
@@ -459,7 +530,9 @@ mod tests {
             .http2_keep_alive_while_idle(true)
             .http2_adaptive_window(true)
             .tcp_keepalive(Duration::from_secs(2))
-            .tcp_nodelay(false);
+            .tcp_nodelay(false)
+            .client_tls(GrpcTlsOption::default())
+            .cluster_token(ClusterToken::new("secret"));
 
         assert_eq!(
             ChannelConfig {
@@ -475,6 +548,8 @@ mod tests {
                 http2_adaptive_window: Some(true),
                 tcp_keepalive: Some(Duration::from_secs(2)),
                 tcp_nodelay: false,
+                client_tls: Some(GrpcTlsOption::default()),
+                cluster_token: Some(ClusterToken::new("secret")),
             },
             cfg
         );
@@ -503,6 +578,24 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn test_build_tls_endpoint() {
+        let config = ChannelConfig::new().client_tls(mutual_tls_option());
+        let mgr = ChannelManager {
+            pool: Arc::new(Pool::default()),
+            config,
+        };
+        let endpoint = mgr.build_endpoint("127.0.0.1:4001").unwrap();
+        assert_eq!(Some("https"), endpoint.uri().scheme_str());
+
+        let config = ChannelConfig::new().client_tls(GrpcTlsOption::default());
+        let mgr = ChannelManager {
+            pool: Arc::new(Pool::default()),
+            config,
+        };
+        assert!(mgr.build_endpoint("127.0.0.1:4001").is_err());
+    }
+
     #[tokio::test]
     async fn test_channel_with_connector() {
         let pool = Pool {
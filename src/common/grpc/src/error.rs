@@ -17,6 +17,8 @@ use std::any::Any;
 use common_error::prelude::{ErrorExt, StatusCode};
 use snafu::{Backtrace, ErrorCompat, Snafu};
 
+use crate::writer::Precision;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Snafu)]
@@ -38,12 +40,44 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Column {} is written more than once in a line", column_name))]
+    DuplicateColumn {
+        column_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Timestamp {} of column {} overflows in precision {:?}",
+        ts,
+        column_name,
+        precision
+    ))]
+    TimestampOverflow {
+        column_name: String,
+        ts: i64,
+        precision: Precision,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create gRPC channel, source: {}", source))]
     CreateChannel {
         source: tonic::transport::Error,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to read TLS file {}, source: {}", path, source))]
+    ReadTlsFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid TLS config: {}", reason))]
+    InvalidTlsConfig {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create RecordBatch, source: {}", source))]
     CreateRecordBatch {
         #[snafu(backtrace)]
@@ -83,9 +117,13 @@ impl ErrorExt for Error {
         match self {
             Error::MissingField { .. }
             | Error::TypeMismatch { .. }
-            | Error::InvalidFlightData { .. } => StatusCode::InvalidArguments,
+            | Error::DuplicateColumn { .. }
+            | Error::TimestampOverflow { .. }
+            | Error::InvalidFlightData { .. }
+            | Error::InvalidTlsConfig { .. } => StatusCode::InvalidArguments,
 
             Error::CreateChannel { .. }
+            | Error::ReadTlsFile { .. }
             | Error::Conversion { .. }
             | Error::DecodeFlightData { .. } => StatusCode::Internal,
 
@@ -37,6 +37,11 @@ pub enum FlightMessage {
     Schema(SchemaRef),
     Recordbatch(RecordBatch),
     AffectedRows(usize),
+    /// Rows affected by a write acknowledged once in the WAL buffer, with the sequence of it.
+    WalAck {
+        affected_rows: usize,
+        sequence: u64,
+    },
 }
 
 #[derive(Default)]
@@ -65,14 +70,26 @@ impl FlightEncoder {
 
                 flight_batch
             }
-            FlightMessage::AffectedRows(rows) => {
-                let metadata = FlightMetadata {
-                    affected_rows: Some(AffectedRows { value: rows as _ }),
-                }
-                .encode_to_vec();
-                FlightData::new(None, IpcMessage(build_none_flight_msg()), metadata, vec![])
-            }
+            FlightMessage::AffectedRows(rows) => Self::encode_affected_rows(AffectedRows {
+                value: rows as _,
+                sequence: 0,
+            }),
+            FlightMessage::WalAck {
+                affected_rows,
+                sequence,
+            } => Self::encode_affected_rows(AffectedRows {
+                value: affected_rows as _,
+                sequence,
+            }),
+        }
+    }
+
+    fn encode_affected_rows(affected_rows: AffectedRows) -> FlightData {
+        let metadata = FlightMetadata {
+            affected_rows: Some(affected_rows),
         }
+        .encode_to_vec();
+        FlightData::new(None, IpcMessage(build_none_flight_msg()), metadata, vec![])
     }
 }
 
@@ -93,8 +110,15 @@ impl FlightDecoder {
             MessageHeader::NONE => {
                 let metadata = FlightMetadata::decode(flight_data.app_metadata.as_slice())
                     .context(DecodeFlightDataSnafu)?;
-                if let Some(AffectedRows { value }) = metadata.affected_rows {
-                    return Ok(FlightMessage::AffectedRows(value as _));
+                if let Some(AffectedRows { value, sequence }) = metadata.affected_rows {
+                    return Ok(if sequence == 0 {
+                        FlightMessage::AffectedRows(value as _)
+                    } else {
+                        FlightMessage::WalAck {
+                            affected_rows: value as _,
+                            sequence,
+                        }
+                    });
                 }
                 InvalidFlightDataSnafu {
                     reason: "Expecting FlightMetadata have some meaningful content.",
@@ -256,6 +280,29 @@ mod test {
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_encode_decode_affected_rows() {
+        let encoder = FlightEncoder::default();
+        let decoder = &mut FlightDecoder::default();
+
+        let data = encoder.encode(FlightMessage::AffectedRows(3));
+        let message = decoder.try_decode(data).unwrap();
+        assert!(matches!(message, FlightMessage::AffectedRows(3)));
+
+        let data = encoder.encode(FlightMessage::WalAck {
+            affected_rows: 3,
+            sequence: 42,
+        });
+        let message = decoder.try_decode(data).unwrap();
+        assert!(matches!(
+            message,
+            FlightMessage::WalAck {
+                affected_rows: 3,
+                sequence: 42
+            }
+        ));
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
@@ -16,6 +16,8 @@ pub mod channel_manager;
 pub mod error;
 pub mod flight;
 pub mod select;
+pub mod tls;
+pub mod token;
 pub mod writer;
 
 pub use error::Error;
@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api::v1::column::Values;
+use api::v1::column::{Histogram as HistogramValue, Values};
 use common_base::BitVec;
+use datatypes::scalars::ScalarRef;
 use datatypes::types::{TimestampType, WrapperType};
 use datatypes::vectors::{
-    BinaryVector, BooleanVector, DateTimeVector, DateVector, Float32Vector, Float64Vector,
-    Int16Vector, Int32Vector, Int64Vector, Int8Vector, StringVector, TimestampMicrosecondVector,
-    TimestampMillisecondVector, TimestampNanosecondVector, TimestampSecondVector, UInt16Vector,
-    UInt32Vector, UInt64Vector, UInt8Vector, VectorRef,
+    BinaryVector, BooleanVector, DateTimeVector, DateVector, Decimal128Vector, Float32Vector,
+    Float64Vector, HistogramVector, Int16Vector, Int32Vector, Int64Vector, Int8Vector,
+    StringVector, TimestampMicrosecondVector, TimestampMillisecondVector,
+    TimestampNanosecondVector, TimestampSecondVector, UInt16Vector, UInt32Vector, UInt64Vector,
+    UInt8Vector, VectorRef,
 };
 use snafu::OptionExt;
 
@@ -167,6 +169,18 @@ pub fn values(arrays: &[VectorRef]) -> Result<Values> {
             TimestampNanosecondVector,
             ts_nanosecond_values,
             |x| { x.into_native() }
+        ),
+        (
+            ConcreteDataType::Decimal128(_),
+            Decimal128Vector,
+            decimal128_values,
+            |x| { x.to_string() }
+        ),
+        (
+            ConcreteDataType::Histogram(_),
+            HistogramVector,
+            histogram_values,
+            |x| { HistogramValue::from(x.to_owned_scalar()) }
         )
     )
 }
@@ -213,6 +227,38 @@ mod tests {
         assert_eq!(vec![true, false, false], values.bool_values);
     }
 
+    #[test]
+    fn test_convert_arrow_arrays_decimal() {
+        let array = Decimal128Vector::from_values(vec![12345, -5])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let array: VectorRef = Arc::new(array);
+
+        let values = values(&[array]).unwrap();
+
+        assert_eq!(vec!["123.45", "-0.05"], values.decimal128_values);
+    }
+
+    #[test]
+    fn test_convert_arrow_arrays_histogram() {
+        use datatypes::prelude::ScalarVector;
+        use datatypes::value::{Histogram, HistogramValueRef};
+
+        let histogram = Histogram::new(vec![1.0, f64::INFINITY], vec![2.0, 3.0]).unwrap();
+        let array = HistogramVector::from_slice(&[HistogramValueRef::Ref { val: &histogram }]);
+        let array: VectorRef = Arc::new(array);
+
+        let values = values(&[array]).unwrap();
+
+        assert_eq!(
+            vec![HistogramValue {
+                bounds: vec![1.0, f64::INFINITY],
+                counts: vec![2.0, 3.0],
+            }],
+            values.histogram_values
+        );
+    }
+
     #[test]
     fn test_convert_arrow_arrays_empty() {
         let array = BooleanVector::from(vec![None, None, None, None, None]);
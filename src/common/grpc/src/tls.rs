@@ -0,0 +1,399 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! TLS of gRPC channels between components.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use common_telemetry::{error, info, warn};
+use futures::Stream;
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Certificate as TonicCertificate, ClientTlsConfig, Identity};
+
+use crate::error::{InvalidTlsConfigSnafu, ReadTlsFileSnafu, Result};
+
+pub const DEFAULT_TLS_RELOAD_INTERVAL_SECS: u64 = 60;
+
+/// Max number of TLS connections handshaked but not yet served.
+const TLS_ACCEPT_BACKLOG: usize = 128;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS options of a gRPC server or the channels to it, all certificates and keys are PEM
+/// encoded files.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcTlsOption {
+    /// Certificate chain of this node, required by servers. Clients present it to servers
+    /// requiring mutual TLS.
+    pub cert_path: String,
+    /// Private key of the certificate.
+    pub key_path: String,
+    /// CA certificates to verify the peers. Required by clients to verify servers, and
+    /// servers require clients to present certificates signed by them if set.
+    pub ca_cert_path: String,
+    /// Name to verify the server certificate with, the host of the server address if
+    /// not set.
+    pub server_name: Option<String>,
+    /// Interval to check whether the files are modified, the certificates are reloaded
+    /// once the files are modified. Never reloads if it's 0.
+    pub reload_interval_secs: u64,
+}
+
+impl Default for GrpcTlsOption {
+    fn default() -> Self {
+        Self {
+            cert_path: String::new(),
+            key_path: String::new(),
+            ca_cert_path: String::new(),
+            server_name: None,
+            reload_interval_secs: DEFAULT_TLS_RELOAD_INTERVAL_SECS,
+        }
+    }
+}
+
+impl GrpcTlsOption {
+    pub(crate) fn reload_interval(&self) -> Option<Duration> {
+        (self.reload_interval_secs > 0).then(|| Duration::from_secs(self.reload_interval_secs))
+    }
+
+    /// Returns the latest modification time of the files, which changes once any of the
+    /// certificates is rotated.
+    pub(crate) fn modified(&self) -> Option<SystemTime> {
+        [&self.cert_path, &self.key_path, &self.ca_cert_path]
+            .into_iter()
+            .filter(|path| !path.is_empty())
+            .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .max()
+    }
+
+    /// Returns the config of servers, which prefer HTTP/2 for gRPC.
+    pub fn server_config(&self) -> Result<ServerConfig> {
+        ensure!(
+            !self.cert_path.is_empty() && !self.key_path.is_empty(),
+            InvalidTlsConfigSnafu {
+                reason: "cert_path and key_path are required by servers",
+            }
+        );
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = if self.ca_cert_path.is_empty() {
+            builder.with_no_client_auth()
+        } else {
+            let roots = load_roots(&self.ca_cert_path)?;
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+        };
+        let mut config = builder.with_single_cert(certs, key).map_err(|e| {
+            InvalidTlsConfigSnafu {
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+        // HTTP/1.1 is for servers also serving HTTP/1.1, like the admin APIs of metasrv.
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+
+    /// Returns the config of channels to servers.
+    pub(crate) fn client_tls_config(&self) -> Result<ClientTlsConfig> {
+        ensure!(
+            !self.ca_cert_path.is_empty(),
+            InvalidTlsConfigSnafu {
+                reason: "ca_cert_path is required by clients",
+            }
+        );
+        let ca_cert = read_file(&self.ca_cert_path)?;
+        let mut config = ClientTlsConfig::new().ca_certificate(TonicCertificate::from_pem(ca_cert));
+
+        match (self.cert_path.is_empty(), self.key_path.is_empty()) {
+            (true, true) => (),
+            (false, false) => {
+                let cert = read_file(&self.cert_path)?;
+                let key = read_file(&self.key_path)?;
+                config = config.identity(Identity::from_pem(cert, key));
+            }
+            _ => {
+                return InvalidTlsConfigSnafu {
+                    reason: "cert_path and key_path should be set together",
+                }
+                .fail()
+            }
+        }
+        if let Some(server_name) = &self.server_name {
+            config = config.domain_name(server_name);
+        }
+        Ok(config)
+    }
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).context(ReadTlsFileSnafu { path })
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let file = File::open(path).context(ReadTlsFileSnafu { path })?;
+    let certs =
+        rustls_pemfile::certs(&mut BufReader::new(file)).context(ReadTlsFileSnafu { path })?;
+    ensure!(
+        !certs.is_empty(),
+        InvalidTlsConfigSnafu {
+            reason: format!("no certificate found in {path}"),
+        }
+    );
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// Loads the first private key in the file, which is in PKCS#8, PKCS#1 (RSA) or SEC1 (EC)
+/// format.
+fn load_key(path: &str) -> Result<PrivateKey> {
+    let file = File::open(path).context(ReadTlsFileSnafu { path })?;
+    let items =
+        rustls_pemfile::read_all(&mut BufReader::new(file)).context(ReadTlsFileSnafu { path })?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .context(InvalidTlsConfigSnafu {
+            reason: format!("no private key found in {path}"),
+        })
+}
+
+fn load_roots(path: &str) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(&cert).map_err(|e| {
+            InvalidTlsConfigSnafu {
+                reason: format!("invalid CA certificate in {path}: {e}"),
+            }
+            .build()
+        })?;
+    }
+    Ok(roots)
+}
+
+/// Accepts TLS connections from `listener` for a gRPC server. The certificates are
+/// reloaded once their files are modified, which are used by connections accepted
+/// afterwards.
+pub fn tls_incoming(
+    listener: TcpListener,
+    option: GrpcTlsOption,
+) -> Result<impl Stream<Item = io::Result<TlsStream<TcpStream>>>> {
+    let mut acceptor = TlsAcceptor::from(Arc::new(option.server_config()?));
+    let (tx, rx) = mpsc::channel(TLS_ACCEPT_BACKLOG);
+
+    let _handle = tokio::spawn(async move {
+        let mut modified = option.modified();
+        let mut checked_at = Instant::now();
+        loop {
+            let (stream, addr) = tokio::select! {
+                _ = tx.closed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("Failed to accept gRPC connection: {}", e);
+                        // Backs off in case of errors like too many open files.
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                },
+            };
+
+            if let Some(interval) = option.reload_interval() {
+                if checked_at.elapsed() >= interval {
+                    checked_at = Instant::now();
+                    let latest = option.modified();
+                    if latest != modified {
+                        match option.server_config() {
+                            Ok(config) => {
+                                acceptor = TlsAcceptor::from(Arc::new(config));
+                                modified = latest;
+                                info!("Reloaded TLS certificates of gRPC server");
+                            }
+                            Err(e) => error!(e; "Failed to reload TLS certificates"),
+                        }
+                    }
+                }
+            }
+
+            // Handshakes in another task, so slow clients don't block accepting others.
+            let acceptor = acceptor.clone();
+            let tx = tx.clone();
+            let _handle = tokio::spawn(async move {
+                let accepted = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+                match accepted.await {
+                    Ok(Ok(stream)) => {
+                        let _ = tx.send(Ok(stream)).await;
+                    }
+                    Ok(Err(e)) => warn!("TLS handshake with {} failed: {}", addr, e),
+                    Err(_) => warn!("TLS handshake with {} timed out", addr),
+                }
+            });
+        }
+    });
+
+    Ok(ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use futures::StreamExt;
+    use rustls::{ClientConfig, ServerName};
+    use tokio_rustls::TlsConnector;
+
+    use super::*;
+    use crate::error::Error;
+
+    /// Returns the path of the certificate or key shared with the tests of servers.
+    pub(crate) fn ssl_file(name: &str) -> String {
+        format!(
+            "{}/../../servers/tests/ssl/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        )
+    }
+
+    pub(crate) fn mutual_tls_option() -> GrpcTlsOption {
+        GrpcTlsOption {
+            cert_path: ssl_file("server.crt"),
+            key_path: ssl_file("server-rsa.key"),
+            ca_cert_path: ssl_file("root-ca.crt"),
+            server_name: Some("localhost".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_server_config() {
+        let option = mutual_tls_option();
+        assert!(option.server_config().is_ok());
+
+        let option = GrpcTlsOption {
+            key_path: ssl_file("server-pkcs8.key"),
+            ca_cert_path: String::new(),
+            ..mutual_tls_option()
+        };
+        let config = option.server_config().unwrap();
+        assert_eq!(b"h2".to_vec(), config.alpn_protocols[0]);
+
+        let option = GrpcTlsOption {
+            cert_path: ssl_file("server-ec.crt"),
+            key_path: ssl_file("server-ec.key"),
+            ..mutual_tls_option()
+        };
+        assert!(option.server_config().is_ok());
+        let option = GrpcTlsOption {
+            key_path: ssl_file("server.crt"),
+            ..mutual_tls_option()
+        };
+        let err = option.server_config().unwrap_err();
+        assert!(matches!(err, Error::InvalidTlsConfig { .. }), "{err}");
+
+        let err = GrpcTlsOption::default().server_config().unwrap_err();
+        assert!(matches!(err, Error::InvalidTlsConfig { .. }), "{err}");
+        let option = GrpcTlsOption {
+            cert_path: ssl_file("not_exist.crt"),
+            ..mutual_tls_option()
+        };
+        let err = option.server_config().unwrap_err();
+        assert!(matches!(err, Error::ReadTlsFile { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_client_tls_config() {
+        assert!(mutual_tls_option().client_tls_config().is_ok());
+        let option = GrpcTlsOption {
+            ca_cert_path: ssl_file("root-ca.crt"),
+            ..Default::default()
+        };
+        assert!(option.client_tls_config().is_ok());
+
+        // The CA is required to verify servers.
+        let option = GrpcTlsOption {
+            ca_cert_path: String::new(),
+            ..mutual_tls_option()
+        };
+        assert!(option.client_tls_config().is_err());
+        let option = GrpcTlsOption {
+            key_path: String::new(),
+            ..mutual_tls_option()
+        };
+        assert!(option.client_tls_config().is_err());
+    }
+
+    #[test]
+    fn test_modified() {
+        assert!(mutual_tls_option().modified().is_some());
+        assert!(GrpcTlsOption::default().modified().is_none());
+        assert_eq!(
+            Some(Duration::from_secs(DEFAULT_TLS_RELOAD_INTERVAL_SECS)),
+            GrpcTlsOption::default().reload_interval()
+        );
+        let option = GrpcTlsOption {
+            reload_interval_secs: 0,
+            ..Default::default()
+        };
+        assert!(option.reload_interval().is_none());
+    }
+
+    async fn connect(addr: std::net::SocketAddr, with_cert: bool) -> io::Result<()> {
+        let roots = load_roots(&ssl_file("root-ca.crt")).unwrap();
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots);
+        let config = if with_cert {
+            let certs = load_certs(&ssl_file("server.crt")).unwrap();
+            let key = load_key(&ssl_file("server-rsa.key")).unwrap();
+            builder.with_single_cert(certs, key).unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+        let stream = TcpStream::connect(addr).await?;
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let mut stream = TlsConnector::from(Arc::new(config))
+            .connect(server_name, stream)
+            .await?;
+        tokio::io::AsyncWriteExt::write_all(&mut stream, b"ping").await
+    }
+
+    #[tokio::test]
+    async fn test_tls_incoming() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut incoming = Box::pin(tls_incoming(listener, mutual_tls_option()).unwrap());
+
+        connect(addr, true).await.unwrap();
+        let stream = incoming.next().await.unwrap().unwrap();
+        assert!(stream.get_ref().1.peer_certificates().is_some());
+
+        // Clients without certificates are rejected.
+        let _ = connect(addr, false).await;
+        let accepted = tokio::time::timeout(Duration::from_millis(500), incoming.next()).await;
+        assert!(accepted.is_err());
+    }
+}
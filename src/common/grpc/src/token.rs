@@ -0,0 +1,166 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared-secret tokens attached to the gRPC requests between components of a cluster, so
+//! that only components knowing the token could call each other.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// The gRPC metadata key carrying the cluster token.
+pub const CLUSTER_TOKEN_HEADER: &str = "x-greptime-cluster-token";
+
+/// A shared-secret token of the cluster, its value is hidden in the debug output.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ClusterToken(Arc<str>);
+
+impl ClusterToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(Arc::from(token.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Compares the token with `other` in constant time, so the token can't be guessed
+    /// byte by byte from the response time.
+    pub fn matches(&self, other: &[u8]) -> bool {
+        let token = self.0.as_bytes();
+        token.len() == other.len()
+            && token
+                .iter()
+                .zip(other)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl fmt::Debug for ClusterToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ClusterToken(******)")
+    }
+}
+
+/// Attaches the token to the requests of clients, does nothing if there is no token.
+#[derive(Clone, Debug, Default)]
+pub struct AttachTokenInterceptor {
+    token: Option<ClusterToken>,
+}
+
+impl AttachTokenInterceptor {
+    pub fn new(token: Option<ClusterToken>) -> Self {
+        Self { token }
+    }
+}
+
+impl Interceptor for AttachTokenInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(token) = &self.token {
+            let value: MetadataValue<Ascii> = token
+                .as_str()
+                .parse()
+                .map_err(|_| Status::invalid_argument("Cluster token must be visible ASCII"))?;
+            request.metadata_mut().insert(CLUSTER_TOKEN_HEADER, value);
+        }
+        Ok(request)
+    }
+}
+
+/// Rejects the requests to servers without the expected token, all requests are accepted
+/// if there is no token.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyTokenInterceptor {
+    token: Option<ClusterToken>,
+}
+
+impl VerifyTokenInterceptor {
+    pub fn new(token: Option<ClusterToken>) -> Self {
+        Self { token }
+    }
+}
+
+impl Interceptor for VerifyTokenInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(token) = &self.token else {
+            return Ok(request);
+        };
+        match request.metadata().get(CLUSTER_TOKEN_HEADER) {
+            Some(value) if token.matches(value.as_bytes()) => Ok(request),
+            Some(_) => Err(Status::unauthenticated("Invalid cluster token")),
+            None => Err(Status::unauthenticated("Missing cluster token")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Code;
+
+    use super::*;
+
+    #[test]
+    fn test_cluster_token_debug() {
+        let token = ClusterToken::new("secret");
+        assert_eq!("ClusterToken(******)", format!("{token:?}"));
+        assert_eq!("secret", token.as_str());
+
+        let token: ClusterToken = serde_json::from_str("\"secret\"").unwrap();
+        assert_eq!(ClusterToken::new("secret"), token);
+    }
+
+    #[test]
+    fn test_attach_and_verify_token() {
+        let token = Some(ClusterToken::new("secret"));
+        let request = AttachTokenInterceptor::new(token.clone())
+            .call(Request::new(()))
+            .unwrap();
+        assert_eq!(
+            "secret",
+            request.metadata().get(CLUSTER_TOKEN_HEADER).unwrap()
+        );
+        let mut verifier = VerifyTokenInterceptor::new(token);
+        assert!(verifier.call(request).is_ok());
+
+        let status = verifier.call(Request::new(())).unwrap_err();
+        assert_eq!(Code::Unauthenticated, status.code());
+
+        let request = AttachTokenInterceptor::new(Some(ClusterToken::new("secreT")))
+            .call(Request::new(()))
+            .unwrap();
+        let status = verifier.call(request).unwrap_err();
+        assert_eq!(Code::Unauthenticated, status.code());
+
+        // No token, nothing is attached or verified.
+        let request = AttachTokenInterceptor::default()
+            .call(Request::new(()))
+            .unwrap();
+        assert!(request.metadata().get(CLUSTER_TOKEN_HEADER).is_none());
+        assert!(VerifyTokenInterceptor::default().call(request).is_ok());
+    }
+
+    #[test]
+    fn test_attach_invalid_token() {
+        let status = AttachTokenInterceptor::new(Some(ClusterToken::new("bad\ntoken")))
+            .call(Request::new(()))
+            .unwrap_err();
+        assert_eq!(Code::InvalidArgument, status.code());
+    }
+}
@@ -17,9 +17,9 @@ use std::collections::HashMap;
 use api::v1::column::{SemanticType, Values};
 use api::v1::{Column, ColumnDataType};
 use common_base::BitVec;
-use snafu::ensure;
+use snafu::{ensure, OptionExt};
 
-use crate::error::{Result, TypeMismatchSnafu};
+use crate::error::{DuplicateColumnSnafu, Result, TimestampOverflowSnafu, TypeMismatchSnafu};
 
 type ColumnName = String;
 
@@ -43,11 +43,17 @@ impl LinesWriter {
     }
 
     pub fn write_ts(&mut self, column_name: &str, value: (i64, Precision)) -> Result<()> {
+        let (ts, precision) = value;
+        let ts = checked_to_ms_ts(precision, ts).context(TimestampOverflowSnafu {
+            column_name,
+            ts,
+            precision,
+        })?;
         let (idx, column) = self.mut_column(
             column_name,
             ColumnDataType::TimestampMillisecond,
             SemanticType::Timestamp,
-        );
+        )?;
         ensure!(
             column.datatype == ColumnDataType::TimestampMillisecond as i32,
             TypeMismatchSnafu {
@@ -58,15 +64,14 @@ impl LinesWriter {
         );
         // It is safe to use unwrap here, because values has been initialized in mut_column()
         let values = column.values.as_mut().unwrap();
-        values
-            .ts_millisecond_values
-            .push(to_ms_ts(value.1, value.0));
+        values.ts_millisecond_values.push(ts);
         self.null_masks[idx].push(false);
         Ok(())
     }
 
     pub fn write_tag(&mut self, column_name: &str, value: &str) -> Result<()> {
-        let (idx, column) = self.mut_column(column_name, ColumnDataType::String, SemanticType::Tag);
+        let (idx, column) =
+            self.mut_column(column_name, ColumnDataType::String, SemanticType::Tag)?;
         ensure!(
             column.datatype == ColumnDataType::String as i32,
             TypeMismatchSnafu {
@@ -84,7 +89,7 @@ impl LinesWriter {
 
     pub fn write_u64(&mut self, column_name: &str, value: u64) -> Result<()> {
         let (idx, column) =
-            self.mut_column(column_name, ColumnDataType::Uint64, SemanticType::Field);
+            self.mut_column(column_name, ColumnDataType::Uint64, SemanticType::Field)?;
         ensure!(
             column.datatype == ColumnDataType::Uint64 as i32,
             TypeMismatchSnafu {
@@ -102,7 +107,7 @@ impl LinesWriter {
 
     pub fn write_i64(&mut self, column_name: &str, value: i64) -> Result<()> {
         let (idx, column) =
-            self.mut_column(column_name, ColumnDataType::Int64, SemanticType::Field);
+            self.mut_column(column_name, ColumnDataType::Int64, SemanticType::Field)?;
         ensure!(
             column.datatype == ColumnDataType::Int64 as i32,
             TypeMismatchSnafu {
@@ -120,7 +125,7 @@ impl LinesWriter {
 
     pub fn write_f64(&mut self, column_name: &str, value: f64) -> Result<()> {
         let (idx, column) =
-            self.mut_column(column_name, ColumnDataType::Float64, SemanticType::Field);
+            self.mut_column(column_name, ColumnDataType::Float64, SemanticType::Field)?;
         ensure!(
             column.datatype == ColumnDataType::Float64 as i32,
             TypeMismatchSnafu {
@@ -138,7 +143,7 @@ impl LinesWriter {
 
     pub fn write_string(&mut self, column_name: &str, value: &str) -> Result<()> {
         let (idx, column) =
-            self.mut_column(column_name, ColumnDataType::String, SemanticType::Field);
+            self.mut_column(column_name, ColumnDataType::String, SemanticType::Field)?;
         ensure!(
             column.datatype == ColumnDataType::String as i32,
             TypeMismatchSnafu {
@@ -154,9 +159,27 @@ impl LinesWriter {
         Ok(())
     }
 
+    pub fn write_binary(&mut self, column_name: &str, value: &[u8]) -> Result<()> {
+        let (idx, column) =
+            self.mut_column(column_name, ColumnDataType::Binary, SemanticType::Field)?;
+        ensure!(
+            column.datatype == ColumnDataType::Binary as i32,
+            TypeMismatchSnafu {
+                column_name,
+                expected: "binary",
+                actual: format!("{:?}", column.datatype)
+            }
+        );
+        // It is safe to use unwrap here, because values has been initialized in mut_column()
+        let values = column.values.as_mut().unwrap();
+        values.binary_values.push(value.to_vec());
+        self.null_masks[idx].push(false);
+        Ok(())
+    }
+
     pub fn write_bool(&mut self, column_name: &str, value: bool) -> Result<()> {
         let (idx, column) =
-            self.mut_column(column_name, ColumnDataType::Boolean, SemanticType::Field);
+            self.mut_column(column_name, ColumnDataType::Boolean, SemanticType::Field)?;
         ensure!(
             column.datatype == ColumnDataType::Boolean as i32,
             TypeMismatchSnafu {
@@ -198,10 +221,17 @@ impl LinesWriter {
         column_name: &str,
         datatype: ColumnDataType,
         semantic_type: SemanticType,
-    ) -> (usize, &mut Column) {
+    ) -> Result<(usize, &mut Column)> {
         let column_names = &mut self.column_name_index;
         let column_idx = match column_names.get(column_name) {
-            Some(i) => *i,
+            Some(i) => {
+                // The column already has a value in the uncommitted line.
+                ensure!(
+                    self.null_masks[*i].len() <= self.batch.1 as usize,
+                    DuplicateColumnSnafu { column_name }
+                );
+                *i
+            }
             None => {
                 let new_idx = column_names.len();
                 let batch = &mut self.batch;
@@ -215,23 +245,30 @@ impl LinesWriter {
                     values: Some(Values::with_capacity(datatype, to_insert)),
                     datatype: datatype as i32,
                     null_mask: Vec::default(),
+                    compressed_values: Vec::default(),
                 });
                 column_names.insert(column_name.to_string(), new_idx);
                 new_idx
             }
         };
-        (column_idx, &mut self.batch.0[column_idx])
+        Ok((column_idx, &mut self.batch.0[column_idx]))
     }
 }
 
+/// Converts `ts` in precision `p` to milliseconds, saturates on overflow.
 pub fn to_ms_ts(p: Precision, ts: i64) -> i64 {
+    checked_to_ms_ts(p, ts).unwrap_or(if ts < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// Converts `ts` in precision `p` to milliseconds, returns `None` on overflow.
+pub fn checked_to_ms_ts(p: Precision, ts: i64) -> Option<i64> {
     match p {
-        Precision::Nanosecond => ts / 1_000_000,
-        Precision::Microsecond => ts / 1000,
-        Precision::Millisecond => ts,
-        Precision::Second => ts * 1000,
-        Precision::Minute => ts * 1000 * 60,
-        Precision::Hour => ts * 1000 * 60 * 60,
+        Precision::Nanosecond => Some(ts / 1_000_000),
+        Precision::Microsecond => Some(ts / 1000),
+        Precision::Millisecond => Some(ts),
+        Precision::Second => ts.checked_mul(1000),
+        Precision::Minute => ts.checked_mul(1000 * 60),
+        Precision::Hour => ts.checked_mul(1000 * 60 * 60),
     }
 }
 
@@ -252,7 +289,8 @@ mod tests {
     use common_base::BitVec;
 
     use super::LinesWriter;
-    use crate::writer::{to_ms_ts, Precision};
+    use crate::error::Error;
+    use crate::writer::{checked_to_ms_ts, to_ms_ts, Precision};
 
     #[test]
     fn test_lines_writer() {
@@ -360,6 +398,38 @@ mod tests {
         verify_null_mask(&column.null_mask, vec![true, true, false]);
     }
 
+    #[test]
+    fn test_lines_writer_u64_and_binary() {
+        let mut writer = LinesWriter::with_lines(2);
+
+        writer.write_u64("counter", u64::MAX).unwrap();
+        writer.write_binary("payload", b"hello").unwrap();
+        writer.commit();
+
+        writer.write_u64("counter", 1).unwrap();
+        assert!(writer.write_u64("payload", 1).is_err());
+        writer.commit();
+
+        let (columns, row_count) = writer.finish();
+        assert_eq!(2, row_count);
+
+        let column = &columns[0];
+        assert_eq!(ColumnDataType::Uint64 as i32, column.datatype);
+        assert_eq!(
+            vec![u64::MAX, 1],
+            column.values.as_ref().unwrap().u64_values
+        );
+        verify_null_mask(&column.null_mask, vec![false, false]);
+
+        let column = &columns[1];
+        assert_eq!(ColumnDataType::Binary as i32, column.datatype);
+        assert_eq!(
+            vec![b"hello".to_vec()],
+            column.values.as_ref().unwrap().binary_values
+        );
+        verify_null_mask(&column.null_mask, vec![false, true]);
+    }
+
     fn verify_null_mask(data: &[u8], expected: Vec<bool>) {
         let bitvec = BitVec::from_slice(data);
         for (idx, b) in expected.iter().enumerate() {
@@ -380,5 +450,33 @@ mod tests {
             100110000 * 1000 * 60 * 60,
             to_ms_ts(Precision::Hour, 100110000)
         );
+        assert_eq!(i64::MAX, to_ms_ts(Precision::Hour, i64::MAX / 1000));
+        assert_eq!(None, checked_to_ms_ts(Precision::Second, i64::MIN));
+    }
+
+    #[test]
+    fn test_lines_writer_invalid_line() {
+        let mut writer = LinesWriter::with_lines(2);
+        writer.write_tag("host", "host1").unwrap();
+        let err = writer.write_string("host", "host2").unwrap_err();
+        assert!(matches!(err, Error::DuplicateColumn { .. }), "{err:?}");
+        writer.write_f64("cpu", 0.5).unwrap();
+        let err = writer.write_f64("cpu", 0.4).unwrap_err();
+        assert!(matches!(err, Error::DuplicateColumn { .. }), "{err:?}");
+        let err = writer
+            .write_ts("ts", (i64::MAX, Precision::Second))
+            .unwrap_err();
+        assert!(matches!(err, Error::TimestampOverflow { .. }), "{err:?}");
+        writer.commit();
+
+        // The column could be written again in the next line.
+        writer.write_f64("cpu", 0.4).unwrap();
+        writer.commit();
+        let (columns, row_count) = writer.finish();
+        assert_eq!(2, row_count);
+        assert_eq!(
+            vec![0.5, 0.4],
+            columns[1].values.as_ref().unwrap().f64_values
+        );
     }
 }
@@ -34,6 +34,9 @@ pub enum Error {
     #[snafu(display("Loader {} is already registered", name))]
     LoaderConflict { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Loader {} is not registered", name))]
+    LoaderNotFound { name: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to serialize to json, source: {}", source))]
     ToJson {
         source: serde_json::Error,
@@ -46,6 +49,29 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Procedure {} of type {} cannot skip its current step",
+        procedure_id,
+        type_name
+    ))]
+    SkipStepUnsupported {
+        procedure_id: ProcedureId,
+        type_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Job {} not found", job_id))]
+    JobNotFound {
+        job_id: ProcedureId,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Job {} is already finished", job_id))]
+    JobFinished {
+        job_id: ProcedureId,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to put {}, source: {}", key, source))]
     PutState {
         key: String,
@@ -82,9 +108,12 @@ impl ErrorExt for Error {
             | Error::DeleteState { .. }
             | Error::ListState { .. }
             | Error::ReadState { .. } => StatusCode::Internal,
-            Error::LoaderConflict { .. } | Error::DuplicateProcedure { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::LoaderConflict { .. }
+            | Error::LoaderNotFound { .. }
+            | Error::DuplicateProcedure { .. }
+            | Error::SkipStepUnsupported { .. }
+            | Error::JobNotFound { .. }
+            | Error::JobFinished { .. } => StatusCode::InvalidArguments,
         }
     }
 
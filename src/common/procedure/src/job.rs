@@ -0,0 +1,469 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Jobs are long-running maintenance work, such as exports and backfills, that run in
+//! background. Records of jobs are persisted in the procedure state store so operators
+//! could track the status and progress of jobs and cancel them through one mechanism.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common_telemetry::logging;
+use futures::future::{AbortHandle, Abortable};
+use futures::TryStreamExt;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{JobFinishedSnafu, JobNotFoundSnafu, Result, ToJsonSnafu};
+use crate::store::state_store::{MemStateStore, ObjectStateStore, StateStoreRef};
+use crate::watchdog::{Watchdog, WatchdogRef};
+use crate::{ProcedureId, ProcedureState};
+
+/// Directory of the job records in the state store.
+const JOB_DIR: &str = "job/";
+/// Max number of finished jobs kept, the oldest ones are removed first.
+const MAX_FINISHED_JOBS: usize = 1024;
+/// Interval to check whether running jobs are stuck.
+const WATCHDOG_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Unique id of a job.
+pub type JobId = ProcedureId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Returns true if the job won't make any progress.
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Running)
+    }
+
+    /// Returns the state of the procedure running the job, cancelled jobs are not failed.
+    fn procedure_state(&self) -> ProcedureState {
+        match self {
+            JobStatus::Running => ProcedureState::Running,
+            JobStatus::Succeeded | JobStatus::Cancelled => ProcedureState::Done,
+            JobStatus::Failed => ProcedureState::Failed,
+        }
+    }
+}
+
+/// Persistent record of a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: JobId,
+    /// Kind of the job, e.g. `export`.
+    pub kind: String,
+    /// Human readable description of the job, e.g. the query to export.
+    pub description: String,
+    pub status: JobStatus,
+    /// Amount of work done, e.g. rows exported so far.
+    pub progress: u64,
+    /// Total amount of work, `None` if unknown.
+    pub total: Option<u64>,
+    /// Result of the succeeded job or the reason why the job failed.
+    pub message: Option<String>,
+    /// Unix timestamp in milliseconds when the job was submitted.
+    pub created_at: i64,
+    /// Unix timestamp in milliseconds when the record was last updated.
+    pub updated_at: i64,
+}
+
+impl JobRecord {
+    fn key(&self) -> String {
+        job_key(self.id)
+    }
+}
+
+fn job_key(id: JobId) -> String {
+    format!("{JOB_DIR}{id}.json")
+}
+
+fn current_time_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+struct JobEntry {
+    record: JobRecord,
+    /// Handle to abort the running job, `None` if the job is not run by this manager.
+    abort_handle: Option<AbortHandle>,
+}
+
+/// Context passed to a running job.
+#[derive(Clone)]
+pub struct JobContext {
+    id: JobId,
+    manager: JobManagerRef,
+}
+
+impl JobContext {
+    pub fn job_id(&self) -> JobId {
+        self.id
+    }
+
+    /// Reports the progress of the job. The progress is visible at once but only
+    /// persisted with the status of the job.
+    pub fn set_progress(&self, progress: u64, total: Option<u64>) {
+        let mut jobs = self.manager.jobs.lock().unwrap();
+        if let Some(entry) = jobs.get_mut(&self.id) {
+            entry.record.progress = progress;
+            entry.record.total = total;
+            entry.record.updated_at = current_time_millis();
+        }
+    }
+}
+
+pub type JobManagerRef = Arc<JobManager>;
+
+/// `JobManager` runs jobs in background and tracks their records.
+pub struct JobManager {
+    store: StateStoreRef,
+    jobs: Mutex<HashMap<JobId, JobEntry>>,
+    /// Records metrics of the jobs and flags the stuck ones.
+    watchdog: WatchdogRef,
+}
+
+impl Default for JobManager {
+    /// Returns a [JobManager] that keeps job records in memory only.
+    fn default() -> JobManager {
+        JobManager::with_state_store(Arc::new(MemStateStore::default()))
+    }
+}
+
+impl JobManager {
+    /// Returns a [JobManager] persisting job records to `store`.
+    pub fn new(store: ObjectStore) -> JobManager {
+        JobManager::with_state_store(Arc::new(ObjectStateStore::new(store)))
+    }
+
+    /// Returns a [JobManager] persisting job records to the state `store`.
+    pub fn with_state_store(store: StateStoreRef) -> JobManager {
+        JobManager {
+            store,
+            jobs: Mutex::new(HashMap::new()),
+            watchdog: Arc::new(Watchdog::default()),
+        }
+    }
+
+    /// Sets the watchdog watching the jobs run by this manager.
+    pub fn with_watchdog(mut self, watchdog: WatchdogRef) -> JobManager {
+        self.watchdog = watchdog;
+        self
+    }
+
+    /// Starts checking whether running jobs are stuck in background.
+    pub fn start_watchdog(&self) {
+        self.watchdog.start(WATCHDOG_CHECK_INTERVAL);
+    }
+
+    /// Loads persisted job records. Jobs still running when the process exited are
+    /// marked as failed since they were interrupted.
+    pub async fn recover(&self) -> Result<()> {
+        let mut records = Vec::new();
+        let mut key_values = self.store.walk_top_down(JOB_DIR).await?;
+        while let Some((key, value)) = key_values.try_next().await? {
+            match serde_json::from_slice::<JobRecord>(&value) {
+                Ok(record) => records.push(record),
+                Err(e) => logging::warn!("Failed to parse job record, key: {}, error: {}", key, e),
+            }
+        }
+
+        for mut record in records {
+            if !record.status.is_finished() {
+                record.status = JobStatus::Failed;
+                record.message = Some("Interrupted by restart".to_string());
+                record.updated_at = current_time_millis();
+                self.persist(&record).await?;
+            }
+            let _ = self.jobs.lock().unwrap().insert(
+                record.id,
+                JobEntry {
+                    record,
+                    abort_handle: None,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Submits a job of `kind` and runs it in background. The job returns a message
+    /// describing its result on success.
+    pub async fn submit<F, Fut>(
+        self: &Arc<Self>,
+        kind: &str,
+        description: &str,
+        job: F,
+    ) -> Result<JobRecord>
+    where
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        let now = current_time_millis();
+        let record = JobRecord {
+            id: JobId::random(),
+            kind: kind.to_string(),
+            description: description.to_string(),
+            status: JobStatus::Running,
+            progress: 0,
+            total: None,
+            message: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.persist(&record).await?;
+
+        let (abort_handle, registration) = AbortHandle::new_pair();
+        let _ = self.jobs.lock().unwrap().insert(
+            record.id,
+            JobEntry {
+                record: record.clone(),
+                abort_handle: Some(abort_handle),
+            },
+        );
+        self.watchdog.on_start(record.id, kind);
+        logging::info!("Job {} of kind {} submitted", record.id, kind);
+
+        let ctx = JobContext {
+            id: record.id,
+            manager: self.clone(),
+        };
+        let manager = self.clone();
+        let id = record.id;
+        common_runtime::spawn_bg(async move {
+            let result = Abortable::new(job(ctx), registration).await;
+            let (status, message) = match result {
+                Ok(Ok(message)) => (JobStatus::Succeeded, Some(message)),
+                Ok(Err(e)) => (JobStatus::Failed, Some(e.to_string())),
+                Err(_) => (JobStatus::Cancelled, None),
+            };
+            manager.finish(id, status, message).await;
+        });
+
+        Ok(record)
+    }
+
+    /// Cancels the running job `id`, the job is marked as cancelled once it stops.
+    pub fn cancel(&self, id: JobId) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get(&id).context(JobNotFoundSnafu { job_id: id })?;
+        ensure!(
+            !entry.record.status.is_finished(),
+            JobFinishedSnafu { job_id: id }
+        );
+        if let Some(abort_handle) = &entry.abort_handle {
+            abort_handle.abort();
+        }
+        Ok(())
+    }
+
+    /// Returns the record of job `id`.
+    pub fn job(&self, id: JobId) -> Option<JobRecord> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| entry.record.clone())
+    }
+
+    /// Returns records of all jobs, ordered by their submission time.
+    pub fn list_jobs(&self) -> Vec<JobRecord> {
+        let mut records = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.record.clone())
+            .collect::<Vec<_>>();
+        records.sort_by_key(|record| record.created_at);
+        records
+    }
+
+    async fn finish(&self, id: JobId, status: JobStatus, message: Option<String>) {
+        self.watchdog.on_finish(id, &status.procedure_state());
+        let (record, evicted) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(entry) = jobs.get_mut(&id) else { return };
+            entry.record.status = status;
+            entry.record.message = message;
+            entry.record.updated_at = current_time_millis();
+            if status == JobStatus::Succeeded {
+                if let Some(total) = entry.record.total {
+                    entry.record.progress = total;
+                }
+            }
+            entry.abort_handle = None;
+            let record = entry.record.clone();
+
+            let mut finished = jobs
+                .values()
+                .filter(|entry| entry.record.status.is_finished())
+                .map(|entry| (entry.record.updated_at, entry.record.id))
+                .collect::<Vec<_>>();
+            let mut evicted = Vec::new();
+            if finished.len() > MAX_FINISHED_JOBS {
+                finished.sort_unstable();
+                for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+                    let _ = jobs.remove(id);
+                    evicted.push(job_key(*id));
+                }
+            }
+            (record, evicted)
+        };
+
+        match status {
+            JobStatus::Failed => logging::warn!(
+                "Job {} failed, error: {}",
+                id,
+                record.message.as_deref().unwrap_or_default()
+            ),
+            _ => logging::info!("Job {} finished, status: {:?}", id, status),
+        }
+
+        if let Err(e) = self.persist(&record).await {
+            logging::error!(e; "Failed to persist record of job {}", id);
+        }
+        if !evicted.is_empty() {
+            if let Err(e) = self.store.delete(&evicted).await {
+                logging::error!(e; "Failed to delete records of evicted jobs");
+            }
+        }
+    }
+
+    async fn persist(&self, record: &JobRecord) -> Result<()> {
+        let value = serde_json::to_vec(record).context(ToJsonSnafu)?;
+        self.store.put(&record.key(), value).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::services::fs::Builder;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::error::LoaderNotFoundSnafu;
+
+    async fn wait_finished(manager: &JobManager, id: JobId) -> JobRecord {
+        loop {
+            let record = manager.job(id).unwrap();
+            if record.status.is_finished() {
+                return record;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn new_object_store(dir: &TempDir) -> ObjectStore {
+        let store_dir = dir.path().to_str().unwrap();
+        let accessor = Builder::default().root(store_dir).build().unwrap();
+        ObjectStore::new(accessor)
+    }
+
+    #[tokio::test]
+    async fn test_submit_job() {
+        let manager = Arc::new(JobManager::default());
+        let record = manager
+            .submit("test", "succeeded job", |ctx| async move {
+                ctx.set_progress(1, Some(2));
+                Ok("done".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(JobStatus::Running, record.status);
+        assert_eq!("test", record.kind);
+
+        let record = wait_finished(&manager, record.id).await;
+        assert_eq!(JobStatus::Succeeded, record.status);
+        assert_eq!(2, record.progress);
+        assert_eq!(Some(2), record.total);
+        assert_eq!(Some("done"), record.message.as_deref());
+
+        let failed = manager
+            .submit("test", "failed job", |_| async move {
+                LoaderNotFoundSnafu { name: "test" }.fail()
+            })
+            .await
+            .unwrap();
+        let failed = wait_finished(&manager, failed.id).await;
+        assert_eq!(JobStatus::Failed, failed.status);
+        assert!(failed.message.as_ref().unwrap().contains("Loader test"));
+
+        let records = manager.list_jobs();
+        assert_eq!(2, records.len());
+        assert!(records.contains(&record));
+        assert!(records.contains(&failed));
+        assert!(manager.cancel(record.id).is_err());
+        assert!(manager.cancel(JobId::random()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_job() {
+        // Jobs are stuck once they start.
+        let watchdog = Arc::new(Watchdog::new(Duration::ZERO));
+        let manager = Arc::new(JobManager::default().with_watchdog(watchdog.clone()));
+        let record = manager
+            .submit("test", "endless job", |_| async move {
+                futures::future::pending::<()>().await;
+                Ok(String::new())
+            })
+            .await
+            .unwrap();
+        let stuck = watchdog.check();
+        assert_eq!(1, stuck.len());
+        assert_eq!(record.id, stuck[0].procedure_id);
+        assert_eq!("test", stuck[0].type_name);
+
+        manager.cancel(record.id).unwrap();
+        let record = wait_finished(&manager, record.id).await;
+        assert_eq!(JobStatus::Cancelled, record.status);
+        assert!(watchdog.check().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recover_jobs() {
+        let dir = TempDir::new("job").unwrap();
+        let manager = Arc::new(JobManager::new(new_object_store(&dir)));
+        let finished = manager
+            .submit("test", "finished job", |_| async move { Ok(String::new()) })
+            .await
+            .unwrap();
+        let finished = wait_finished(&manager, finished.id).await;
+        let running = manager
+            .submit("test", "interrupted job", |_| async move {
+                futures::future::pending::<()>().await;
+                Ok(String::new())
+            })
+            .await
+            .unwrap();
+
+        let manager = JobManager::new(new_object_store(&dir));
+        manager.recover().await.unwrap();
+        assert_eq!(Some(finished), manager.job(finished.id));
+        let running = manager.job(running.id).unwrap();
+        assert_eq!(JobStatus::Failed, running.status);
+        assert_eq!(Some("Interrupted by restart"), running.message.as_deref());
+    }
+}
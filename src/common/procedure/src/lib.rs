@@ -15,15 +15,21 @@
 //! Common traits and structures for the procedure framework.
 
 pub mod error;
+pub mod job;
 #[allow(dead_code)]
 mod local;
+pub mod metric;
 mod procedure;
 // TODO(yingwen): Remove this attribute once ProcedureManager is implemented.
 #[allow(dead_code)]
 mod store;
+pub mod test_util;
+pub mod watchdog;
 
 pub use crate::error::{Error, Result};
 pub use crate::procedure::{
-    BoxedProcedure, Context, LockKey, Procedure, ProcedureId, ProcedureManager,
-    ProcedureManagerRef, ProcedureState, ProcedureWithId, Status,
+    sort_lock_keys, BoxedProcedure, BoxedProcedureLoader, Context, Intervention, LockKey, LockMode,
+    Procedure, ProcedureId, ProcedureManager, ProcedureManagerRef, ProcedureState, ProcedureWithId,
+    Status,
 };
+pub use crate::store::state_store::{KeyValue, KeyValueStream, StateStore, StateStoreRef};
@@ -18,7 +18,7 @@ use std::sync::{Arc, Mutex};
 
 use tokio::sync::Notify;
 
-use crate::{LockKey, ProcedureId, ProcedureState};
+use crate::{sort_lock_keys, LockKey, ProcedureId, ProcedureState};
 
 /// Mutable metadata of a procedure during execution.
 #[derive(Debug)]
@@ -47,26 +47,23 @@ struct ProcedureMeta {
     child_notify: Notify,
     /// Locks inherted from the parent procedure.
     parent_locks: Vec<LockKey>,
-    /// Lock not in `parent_locks` but required by this procedure.
+    /// Locks not in `parent_locks` but required by this procedure.
     ///
-    /// If the parent procedure already owns the lock that this procedure
-    /// needs, we set this field to `None`.
-    lock_key: Option<LockKey>,
+    /// Locks covered by `parent_locks` are not in this field, as the parent
+    /// procedure already owns them.
+    lock_keys: Vec<LockKey>,
     /// Mutable status during execution.
     exec_meta: Mutex<ExecMeta>,
 }
 
 impl ProcedureMeta {
-    /// Return all locks the procedure needs.
+    /// Return all locks the procedure needs, in the order to acquire them.
     fn locks_needed(&self) -> Vec<LockKey> {
-        let num_locks = self.parent_locks.len() + if self.lock_key.is_some() { 1 } else { 0 };
-        let mut locks = Vec::with_capacity(num_locks);
+        let mut locks = Vec::with_capacity(self.parent_locks.len() + self.lock_keys.len());
         locks.extend_from_slice(&self.parent_locks);
-        if let Some(key) = &self.lock_key {
-            locks.push(key.clone());
-        }
+        locks.extend_from_slice(&self.lock_keys);
 
-        locks
+        sort_lock_keys(locks)
     }
 }
 
@@ -82,7 +79,7 @@ fn procedure_meta_for_test() -> ProcedureMeta {
         parent_id: None,
         child_notify: Notify::new(),
         parent_locks: Vec::new(),
-        lock_key: None,
+        lock_keys: Vec::new(),
         exec_meta: Mutex::new(ExecMeta {
             state: ProcedureState::Running,
         }),
@@ -104,10 +101,15 @@ mod tests {
         let locks = meta.locks_needed();
         assert_eq!(parent_locks, locks);
 
-        meta.lock_key = Some(LockKey::new("c"));
+        meta.lock_keys = vec![LockKey::new("c"), LockKey::shared("0")];
         let locks = meta.locks_needed();
         assert_eq!(
-            vec![LockKey::new("a"), LockKey::new("b"), LockKey::new("c")],
+            vec![
+                LockKey::shared("0"),
+                LockKey::new("a"),
+                LockKey::new("b"),
+                LockKey::new("c")
+            ],
             locks
         );
     }
@@ -16,40 +16,66 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::RwLock;
 
 use crate::local::ProcedureMetaRef;
-use crate::ProcedureId;
+use crate::{LockKey, LockMode, ProcedureId};
 
 /// A lock entry.
 #[derive(Debug)]
 struct Lock {
-    /// Current lock owner.
-    owner: ProcedureMetaRef,
-    /// Waiter procedures.
-    waiters: VecDeque<ProcedureMetaRef>,
+    /// Current lock owners, only locks in [LockMode::Shared] could have multiple owners.
+    owners: Vec<ProcedureMetaRef>,
+    /// Mode of the lock held by current owners.
+    mode: LockMode,
+    /// Waiter procedures and the modes they are waiting for.
+    waiters: VecDeque<(ProcedureMetaRef, LockMode)>,
 }
 
 impl Lock {
     /// Returns a [Lock] with specific `owner` procedure.
-    fn from_owner(owner: ProcedureMetaRef) -> Lock {
+    fn from_owner(owner: ProcedureMetaRef, mode: LockMode) -> Lock {
         Lock {
-            owner,
+            owners: vec![owner],
+            mode,
             waiters: VecDeque::new(),
         }
     }
 
-    /// Try to pop a waiter from the waiter list, set it as owner
-    /// and wake up the new owner.
+    /// Returns true if the procedure could share the lock with current owners without
+    /// waiting.
+    ///
+    /// A shared lock with waiters is not shared anymore, so procedures waiting for the
+    /// exclusive lock won't starve.
+    fn can_share(&self, mode: LockMode) -> bool {
+        self.mode == LockMode::Shared && mode == LockMode::Shared && self.waiters.is_empty()
+    }
+
+    /// Try to pop waiters from the waiter list, set them as owners
+    /// and wake up the new owners. Waiters at the front of the list acquiring
+    /// the lock in shared mode become owners together.
     ///
     /// Returns false if there is no waiter in the waiter list.
     fn switch_owner(&mut self) -> bool {
-        if let Some(waiter) = self.waiters.pop_front() {
-            // Update owner.
-            self.owner = waiter.clone();
+        let Some((waiter, mode)) = self.waiters.pop_front() else {
+            return false;
+        };
+
+        // Update owners.
+        self.mode = mode;
+        self.owners = vec![waiter];
+        if mode == LockMode::Shared {
+            while let Some((_, LockMode::Shared)) = self.waiters.front() {
+                let (waiter, _) = self.waiters.pop_front().unwrap();
+                self.owners.push(waiter);
+            }
+        }
+        for owner in &self.owners {
             // We need to use notify_one() since the waiter may have not called `notified()` yet.
-            waiter.lock_notify.notify_one();
-            true
-        } else {
-            false
+            owner.lock_notify.notify_one();
         }
+        true
+    }
+
+    fn is_owner(&self, procedure_id: ProcedureId) -> bool {
+        self.owners.iter().any(|owner| owner.id == procedure_id)
     }
 }
 
@@ -66,14 +92,14 @@ impl LockMap {
         }
     }
 
-    /// Acquire lock by `key` for procedure with specific `meta`.
+    /// Acquire lock by `key` in `mode` for procedure with specific `meta`.
     ///
     /// Though `meta` is cloneable, callers must ensure that only one `meta`
     /// is acquiring and holding the lock at the same time.
     ///
     /// # Panics
     /// Panics if the procedure acquires the lock recursively.
-    async fn acquire_lock(&self, key: &str, meta: ProcedureMetaRef) {
+    async fn acquire_lock(&self, key: &str, mode: LockMode, meta: ProcedureMetaRef) {
         assert!(!self.hold_lock(key, meta.id));
 
         {
@@ -81,14 +107,20 @@ impl LockMap {
             if let Some(lock) = locks.get_mut(key) {
                 // Lock already exists, but we don't expect that a procedure acquires
                 // the same lock again.
-                assert_ne!(lock.owner.id, meta.id);
+                assert!(!lock.is_owner(meta.id));
+
+                if lock.can_share(mode) {
+                    lock.owners.push(meta);
+
+                    return;
+                }
 
                 // Add this procedure to the waiter list. Here we don't check
                 // whether the procedure is already in the waiter list as we
                 // expect that a procedure should not wait for two lock simultaneously.
-                lock.waiters.push_back(meta.clone());
+                lock.waiters.push_back((meta.clone(), mode));
             } else {
-                locks.insert(key.to_string(), Lock::from_owner(meta));
+                locks.insert(key.to_string(), Lock::from_owner(meta, mode));
 
                 return;
             }
@@ -100,15 +132,30 @@ impl LockMap {
         assert!(self.hold_lock(key, meta.id));
     }
 
+    /// Acquire all locks in `keys` one by one, `keys` should be sorted by
+    /// [sort_lock_keys](crate::sort_lock_keys) so procedures never wait for
+    /// each other in a cycle.
+    async fn acquire_locks(&self, keys: &[LockKey], meta: ProcedureMetaRef) {
+        for key in keys {
+            self.acquire_lock(key.key(), key.mode(), meta.clone()).await;
+        }
+    }
+
     /// Release lock by `key`.
     fn release_lock(&self, key: &str, procedure_id: ProcedureId) {
         let mut locks = self.locks.write().unwrap();
         if let Some(lock) = locks.get_mut(key) {
-            if lock.owner.id != procedure_id {
+            if !lock.is_owner(procedure_id) {
                 // This is not the lock owner.
                 return;
             }
 
+            lock.owners.retain(|owner| owner.id != procedure_id);
+            if !lock.owners.is_empty() {
+                // Other procedures still share the lock.
+                return;
+            }
+
             if !lock.switch_owner() {
                 // No body waits for this lock, we can remove the lock entry.
                 locks.remove(key);
@@ -116,13 +163,20 @@ impl LockMap {
         }
     }
 
+    /// Release all locks in `keys` in the reverse order of acquiring them.
+    fn release_locks(&self, keys: &[LockKey], procedure_id: ProcedureId) {
+        for key in keys.iter().rev() {
+            self.release_lock(key.key(), procedure_id);
+        }
+    }
+
     /// Returns true if the procedure with specific `procedure_id` holds the
     /// lock of `key`.
     fn hold_lock(&self, key: &str, procedure_id: ProcedureId) -> bool {
         let locks = self.locks.read().unwrap();
         locks
             .get(key)
-            .map(|lock| lock.owner.id == procedure_id)
+            .map(|lock| lock.is_owner(procedure_id))
             .unwrap_or(false)
     }
 
@@ -132,7 +186,7 @@ impl LockMap {
         let locks = self.locks.read().unwrap();
         locks
             .get(key)
-            .map(|lock| lock.waiters.iter().any(|meta| meta.id == procedure_id))
+            .map(|lock| lock.waiters.iter().any(|(meta, _)| meta.id == procedure_id))
             .unwrap_or(false)
     }
 }
@@ -147,7 +201,7 @@ mod tests {
     #[test]
     fn test_lock_no_waiter() {
         let meta = Arc::new(local::procedure_meta_for_test());
-        let mut lock = Lock::from_owner(meta);
+        let mut lock = Lock::from_owner(meta, LockMode::Exclusive);
 
         assert!(!lock.switch_owner());
     }
@@ -155,16 +209,18 @@ mod tests {
     #[tokio::test]
     async fn test_lock_with_waiter() {
         let owner = Arc::new(local::procedure_meta_for_test());
-        let mut lock = Lock::from_owner(owner);
+        let mut lock = Lock::from_owner(owner, LockMode::Exclusive);
 
         let waiter = Arc::new(local::procedure_meta_for_test());
-        lock.waiters.push_back(waiter.clone());
+        lock.waiters
+            .push_back((waiter.clone(), LockMode::Exclusive));
 
         assert!(lock.switch_owner());
         assert!(lock.waiters.is_empty());
 
         waiter.lock_notify.notified().await;
-        assert_eq!(lock.owner.id, waiter.id);
+        assert!(lock.is_owner(waiter.id));
+        assert_eq!(1, lock.owners.len());
     }
 
     #[tokio::test]
@@ -173,7 +229,9 @@ mod tests {
 
         let owner = Arc::new(local::procedure_meta_for_test());
         let lock_map = Arc::new(LockMap::new());
-        lock_map.acquire_lock(key, owner.clone()).await;
+        lock_map
+            .acquire_lock(key, LockMode::Exclusive, owner.clone())
+            .await;
 
         let waiter = Arc::new(local::procedure_meta_for_test());
         let waiter_id = waiter.id;
@@ -188,7 +246,9 @@ mod tests {
             assert!(!lock_map2.hold_lock(key, waiter_id));
 
             // Waiter wait for lock.
-            lock_map2.acquire_lock(key, waiter.clone()).await;
+            lock_map2
+                .acquire_lock(key, LockMode::Exclusive, waiter.clone())
+                .await;
 
             assert!(lock_map2.hold_lock(key, waiter_id));
         });
@@ -211,4 +271,97 @@ mod tests {
 
         lock_map.release_lock(key, waiter_id);
     }
+
+    #[tokio::test]
+    async fn test_switch_to_shared_owners() {
+        let owner = Arc::new(local::procedure_meta_for_test());
+        let mut lock = Lock::from_owner(owner, LockMode::Exclusive);
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| Arc::new(local::procedure_meta_for_test()))
+            .collect();
+        let writer = Arc::new(local::procedure_meta_for_test());
+        for reader in &readers {
+            lock.waiters.push_back((reader.clone(), LockMode::Shared));
+        }
+        lock.waiters
+            .push_back((writer.clone(), LockMode::Exclusive));
+
+        // Both readers own the lock, the writer still waits.
+        assert!(lock.switch_owner());
+        assert_eq!(LockMode::Shared, lock.mode);
+        for reader in &readers {
+            reader.lock_notify.notified().await;
+            assert!(lock.is_owner(reader.id));
+        }
+        assert!(!lock.is_owner(writer.id));
+        assert_eq!(1, lock.waiters.len());
+        assert!(!lock.can_share(LockMode::Shared));
+    }
+
+    #[tokio::test]
+    async fn test_lock_map_shared() {
+        let key = "catalog.schema";
+        let lock_map = Arc::new(LockMap::new());
+
+        // Readers share the lock.
+        let reader1 = Arc::new(local::procedure_meta_for_test());
+        let reader2 = Arc::new(local::procedure_meta_for_test());
+        lock_map
+            .acquire_lock(key, LockMode::Shared, reader1.clone())
+            .await;
+        lock_map
+            .acquire_lock(key, LockMode::Shared, reader2.clone())
+            .await;
+        assert!(lock_map.hold_lock(key, reader1.id));
+        assert!(lock_map.hold_lock(key, reader2.id));
+
+        // The writer waits for all readers.
+        let writer = Arc::new(local::procedure_meta_for_test());
+        let writer_id = writer.id;
+        let lock_map2 = lock_map.clone();
+        let handle = tokio::spawn(async move {
+            lock_map2
+                .acquire_lock(key, LockMode::Exclusive, writer)
+                .await;
+        });
+        while !lock_map.waiting_lock(key, writer_id) {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        lock_map.release_lock(key, reader1.id);
+        assert!(!lock_map.hold_lock(key, writer_id));
+        lock_map.release_lock(key, reader2.id);
+        handle.await.unwrap();
+        assert!(lock_map.hold_lock(key, writer_id));
+
+        lock_map.release_lock(key, writer_id);
+        assert!(lock_map.locks.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lock_map_multiple_keys() {
+        let keys = crate::sort_lock_keys(vec![
+            LockKey::new("catalog.schema.table"),
+            LockKey::shared("catalog.schema"),
+        ]);
+        let lock_map = LockMap::new();
+
+        let table_procedure = Arc::new(local::procedure_meta_for_test());
+        lock_map.acquire_locks(&keys, table_procedure.clone()).await;
+        // Another table procedure in the same schema only waits for the table lock.
+        let other = Arc::new(local::procedure_meta_for_test());
+        lock_map
+            .acquire_lock("catalog.schema", LockMode::Shared, other.clone())
+            .await;
+        assert!(lock_map.hold_lock("catalog.schema", table_procedure.id));
+        assert!(lock_map.hold_lock("catalog.schema.table", table_procedure.id));
+        assert!(lock_map.hold_lock("catalog.schema", other.id));
+
+        lock_map.release_locks(&keys, table_procedure.id);
+        assert!(!lock_map.hold_lock("catalog.schema", table_procedure.id));
+        assert!(!lock_map.hold_lock("catalog.schema.table", table_procedure.id));
+        lock_map.release_lock("catalog.schema", other.id);
+        assert!(lock_map.locks.read().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,22 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Procedure metrics
+
+pub const PROCEDURE_TYPE_LABEL: &str = "procedure.type";
+pub const METRIC_PROCEDURE_RUNNING: &str = "procedure.running";
+pub const METRIC_PROCEDURE_STEP_ELAPSED: &str = "procedure.step_elapsed";
+pub const METRIC_PROCEDURE_RETRIES_TOTAL: &str = "procedure.retries_total";
+pub const METRIC_PROCEDURE_FAILURES_TOTAL: &str = "procedure.failures_total";
+pub const METRIC_PROCEDURE_STUCK: &str = "procedure.stuck";
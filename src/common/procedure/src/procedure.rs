@@ -21,7 +21,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use uuid::Uuid;
 
-use crate::error::Result;
+use crate::error::{Result, SkipStepUnsupportedSnafu};
 
 /// Procedure execution status.
 pub enum Status {
@@ -78,26 +78,89 @@ pub trait Procedure: Send + Sync {
     /// Dump the state of the procedure to a string.
     fn dump(&self) -> Result<String>;
 
-    /// Returns the [LockKey] if this procedure needs to acquire lock.
-    fn lock_key(&self) -> Option<LockKey>;
+    /// Returns the [LockKey]s this procedure needs to acquire, the framework acquires them
+    /// in the order of [sort_lock_keys] to avoid dead lock.
+    fn lock_keys(&self) -> Vec<LockKey>;
+
+    /// Undo the steps already executed, called when the procedure is aborted by
+    /// [Intervention::Abort].
+    ///
+    /// The implementation must be idempotent. Procedures that leave nothing to undo
+    /// could keep the default implementation.
+    async fn rollback(&mut self, _ctx: &Context) -> Result<()> {
+        Ok(())
+    }
+
+    /// Moves to the next step as if the current step is done, called when operators
+    /// complete the current step manually by [Intervention::SkipStep].
+    ///
+    /// Returns an error by default as most steps can't be skipped safely.
+    fn skip_step(&mut self, ctx: &Context) -> Result<()> {
+        SkipStepUnsupportedSnafu {
+            procedure_id: ctx.procedure_id,
+            type_name: self.type_name(),
+        }
+        .fail()
+    }
+}
+
+/// Mode to acquire a lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LockMode {
+    /// The lock could be held by multiple procedures in shared mode at the same time,
+    /// like a read lock.
+    Shared,
+    /// The lock is held by only one procedure, like a write lock.
+    Exclusive,
 }
 
-/// A key to identify the lock.
-// We might hold multiple keys in this struct. When there are multiple keys, we need to sort the
-// keys lock all the keys in order to avoid dead lock.
+/// A key to identify the lock, and the mode to acquire the lock.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LockKey(String);
+pub struct LockKey {
+    key: String,
+    mode: LockMode,
+}
 
 impl LockKey {
-    /// Returns a new [LockKey].
+    /// Returns a new [LockKey] in exclusive mode.
     pub fn new(key: impl Into<String>) -> LockKey {
-        LockKey(key.into())
+        LockKey {
+            key: key.into(),
+            mode: LockMode::Exclusive,
+        }
+    }
+
+    /// Returns a new [LockKey] in shared mode.
+    pub fn shared(key: impl Into<String>) -> LockKey {
+        LockKey {
+            key: key.into(),
+            mode: LockMode::Shared,
+        }
     }
 
     /// Returns the lock key.
     pub fn key(&self) -> &str {
-        &self.0
+        &self.key
+    }
+
+    /// Returns the lock mode.
+    pub fn mode(&self) -> LockMode {
+        self.mode
     }
+
+    /// Returns true if holding this lock also grants the `other` lock.
+    pub fn covers(&self, other: &LockKey) -> bool {
+        self.key == other.key && self.mode >= other.mode
+    }
+}
+
+/// Sorts the `keys` by key so procedures acquire locks in the same order, and merges the
+/// locks on the same key into one, which is exclusive if any of them is exclusive.
+pub fn sort_lock_keys(mut keys: Vec<LockKey>) -> Vec<LockKey> {
+    // Exclusive locks go first among the locks on the same key, so `dedup_by` keeps them.
+    keys.sort_by(|a, b| a.key.cmp(&b.key).then(b.mode.cmp(&a.mode)));
+    keys.dedup_by(|a, b| a.key == b.key);
+    keys
 }
 
 /// Boxed [Procedure].
@@ -127,7 +190,7 @@ pub struct ParseIdError {
 }
 
 /// Unique id for [Procedure].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ProcedureId(Uuid);
 
 impl ProcedureId {
@@ -173,6 +236,62 @@ pub enum ProcedureState {
     Failed,
 }
 
+/// Manual intervention on a procedure, the escape hatches for operators when a
+/// procedure can't make progress by itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intervention {
+    /// Aborts the failed procedure and rolls it back by [Procedure::rollback].
+    Abort,
+    /// Retries the current step of the procedure now instead of waiting for the
+    /// next retry.
+    RetryNow,
+    /// Marks the current step of the procedure as done by [Procedure::skip_step], and
+    /// continues with the next step.
+    SkipStep,
+}
+
+impl Intervention {
+    /// Returns whether the intervention applies to a procedure in `state`.
+    pub fn applies_to(&self, state: &ProcedureState) -> bool {
+        match self {
+            Intervention::Abort => matches!(state, ProcedureState::Failed),
+            Intervention::RetryNow => matches!(state, ProcedureState::Running),
+            Intervention::SkipStep => {
+                matches!(state, ProcedureState::Running | ProcedureState::Failed)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Intervention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Intervention::Abort => write!(f, "abort"),
+            Intervention::RetryNow => write!(f, "retry-now"),
+            Intervention::SkipStep => write!(f, "skip-step"),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Unknown intervention: {}", name))]
+pub struct ParseInterventionError {
+    name: String,
+}
+
+impl FromStr for Intervention {
+    type Err = ParseInterventionError;
+
+    fn from_str(s: &str) -> std::result::Result<Intervention, ParseInterventionError> {
+        match s {
+            "abort" => Ok(Intervention::Abort),
+            "retry-now" => Ok(Intervention::RetryNow),
+            "skip-step" => Ok(Intervention::SkipStep),
+            _ => ParseInterventionSnafu { name: s }.fail(),
+        }
+    }
+}
+
 // TODO(yingwen): Shutdown
 /// `ProcedureManager` executes [Procedure] submitted to it.
 #[async_trait]
@@ -192,6 +311,12 @@ pub trait ProcedureManager: Send + Sync + 'static {
     ///
     /// Returns `Ok(None)` if the procedure doesn't exist.
     async fn procedure_state(&self, procedure_id: ProcedureId) -> Result<Option<ProcedureState>>;
+
+    /// Applies the manual `intervention` to the procedure.
+    ///
+    /// Returns an error if the procedure doesn't exist or the intervention doesn't apply
+    /// to the state of the procedure, see [Intervention::applies_to].
+    async fn intervene(&self, procedure_id: ProcedureId, intervention: Intervention) -> Result<()>;
 }
 
 /// Ref-counted pointer to the [ProcedureManager].
@@ -225,11 +350,101 @@ mod tests {
         assert!(!status.need_persist());
     }
 
+    #[test]
+    fn test_intervention_applies_to() {
+        let running = ProcedureState::Running;
+        let failed = ProcedureState::Failed;
+        let done = ProcedureState::Done;
+
+        assert!(!Intervention::Abort.applies_to(&running));
+        assert!(Intervention::Abort.applies_to(&failed));
+        assert!(Intervention::RetryNow.applies_to(&running));
+        assert!(!Intervention::RetryNow.applies_to(&failed));
+        assert!(Intervention::SkipStep.applies_to(&running));
+        assert!(Intervention::SkipStep.applies_to(&failed));
+        for intervention in [
+            Intervention::Abort,
+            Intervention::RetryNow,
+            Intervention::SkipStep,
+        ] {
+            assert!(!intervention.applies_to(&done));
+            assert_eq!(
+                intervention,
+                intervention.to_string().parse::<Intervention>().unwrap()
+            );
+        }
+
+        assert_eq!("retry-now", Intervention::RetryNow.to_string());
+        assert!("retry".parse::<Intervention>().is_err());
+    }
+
+    struct NoopProcedure;
+
+    #[async_trait]
+    impl Procedure for NoopProcedure {
+        fn type_name(&self) -> &str {
+            "NoopProcedure"
+        }
+
+        async fn execute(&mut self, _ctx: &Context) -> Result<Status> {
+            Ok(Status::Done)
+        }
+
+        fn dump(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn lock_keys(&self) -> Vec<LockKey> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_intervention_hooks() {
+        let ctx = Context {
+            procedure_id: ProcedureId::random(),
+        };
+        let mut procedure = NoopProcedure;
+        procedure.rollback(&ctx).await.unwrap();
+
+        let err = procedure.skip_step(&ctx).unwrap_err();
+        assert!(matches!(err, crate::Error::SkipStepUnsupported { .. }));
+    }
+
     #[test]
     fn test_lock_key() {
         let entity = "catalog.schema.my_table";
         let key = LockKey::new(entity);
         assert_eq!(entity, key.key());
+        assert_eq!(LockMode::Exclusive, key.mode());
+        let shared = LockKey::shared(entity);
+        assert_eq!(LockMode::Shared, shared.mode());
+
+        assert!(key.covers(&shared));
+        assert!(key.covers(&key));
+        assert!(!shared.covers(&key));
+        assert!(!key.covers(&LockKey::shared("catalog.schema")));
+    }
+
+    #[test]
+    fn test_sort_lock_keys() {
+        let keys = sort_lock_keys(vec![
+            LockKey::new("catalog.schema.b"),
+            LockKey::shared("catalog.schema"),
+            LockKey::shared("catalog.schema.a"),
+            LockKey::new("catalog.schema.a"),
+            LockKey::shared("catalog.schema.a"),
+            LockKey::shared("catalog.schema"),
+        ]);
+        assert_eq!(
+            vec![
+                LockKey::shared("catalog.schema"),
+                LockKey::new("catalog.schema.a"),
+                LockKey::new("catalog.schema.b"),
+            ],
+            keys
+        );
+        assert!(sort_lock_keys(vec![]).is_empty());
     }
 
     #[test]
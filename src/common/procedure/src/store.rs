@@ -15,6 +15,8 @@
 use std::collections::HashMap;
 use std::fmt;
 
+#[cfg(feature = "failpoints")]
+use common_base::failpoint;
 use common_telemetry::logging;
 use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
@@ -24,27 +26,27 @@ use crate::error::{Result, ToJsonSnafu};
 use crate::store::state_store::StateStoreRef;
 use crate::{BoxedProcedure, ProcedureId};
 
-mod state_store;
+pub(crate) mod state_store;
 
 /// Serialized data of a procedure.
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-struct ProcedureMessage {
+pub(crate) struct ProcedureMessage {
     /// Type name of the procedure. The procedure framework also use the type name to
     /// find a loader to load the procedure.
-    type_name: String,
+    pub(crate) type_name: String,
     /// The data of the procedure.
-    data: String,
+    pub(crate) data: String,
     /// Parent procedure id.
-    parent_id: Option<ProcedureId>,
+    pub(crate) parent_id: Option<ProcedureId>,
 }
 
 /// Procedure storage layer.
 #[derive(Clone)]
-struct ProcedureStore(StateStoreRef);
+pub(crate) struct ProcedureStore(pub(crate) StateStoreRef);
 
 impl ProcedureStore {
     /// Dump the `procedure` to the storage.
-    async fn store_procedure(
+    pub(crate) async fn store_procedure(
         &self,
         procedure_id: ProcedureId,
         step: u32,
@@ -67,26 +69,39 @@ impl ProcedureStore {
         .to_string();
         let value = serde_json::to_string(&message).context(ToJsonSnafu)?;
 
+        // Scoped by the procedure id, so is the commit below.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("procedure_store_step", &procedure_id.to_string()) {
+            return Err(injected_put_error("procedure_store_step", &key));
+        }
         self.0.put(&key, value.into_bytes()).await?;
 
         Ok(())
     }
 
     /// Write commit flag to the storage.
-    async fn commit_procedure(&self, procedure_id: ProcedureId, step: u32) -> Result<()> {
+    pub(crate) async fn commit_procedure(
+        &self,
+        procedure_id: ProcedureId,
+        step: u32,
+    ) -> Result<()> {
         let key = ParsedKey {
             procedure_id,
             step,
             is_committed: true,
         }
         .to_string();
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("procedure_commit", &procedure_id.to_string()) {
+            return Err(injected_put_error("procedure_commit", &key));
+        }
         self.0.put(&key, Vec::new()).await?;
 
         Ok(())
     }
 
     /// Load uncommitted procedures from the storage.
-    async fn load_messages(&self) -> Result<HashMap<ProcedureId, ProcedureMessage>> {
+    pub(crate) async fn load_messages(&self) -> Result<HashMap<ProcedureId, ProcedureMessage>> {
         let mut messages = HashMap::new();
         // Track the key-value pair by procedure id.
         let mut procedure_key_values: HashMap<_, (ParsedKey, Vec<u8>)> = HashMap::new();
@@ -134,6 +149,18 @@ impl ProcedureStore {
     }
 }
 
+/// Returns the error injected by the fail point `name` when putting `key`.
+#[cfg(feature = "failpoints")]
+fn injected_put_error(name: &str, key: &str) -> crate::error::Error {
+    use snafu::IntoError;
+
+    let e = object_store::Error::new(
+        object_store::ErrorKind::Unexpected,
+        &format!("Injected failure at {name}"),
+    );
+    crate::error::PutStateSnafu { key }.into_error(e)
+}
+
 /// Key to refer the procedure in the [ProcedureStore].
 #[derive(Debug, PartialEq, Eq)]
 struct ParsedKey {
@@ -287,8 +314,8 @@ mod tests {
             Ok(self.data.clone())
         }
 
-        fn lock_key(&self) -> Option<LockKey> {
-            None
+        fn lock_keys(&self) -> Vec<LockKey> {
+            Vec::new()
         }
     }
 
@@ -396,4 +423,44 @@ mod tests {
         let msg = messages.get(&id2).unwrap();
         assert_eq!("id2-0", msg.data);
     }
+
+    /// Tests injecting failures into storing procedures, the fail points are scoped by
+    /// the procedure ids.
+    #[cfg(feature = "failpoints")]
+    mod failpoint {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_store_procedure_failure() {
+            let dir = TempDir::new("store_procedure_failure").unwrap();
+            let store = new_procedure_store(&dir);
+
+            let procedure_id = ProcedureId::random();
+            let procedure: BoxedProcedure = Box::new(MockProcedure::new("step-0"));
+            store
+                .store_procedure(procedure_id, 0, &procedure, None)
+                .await
+                .unwrap();
+
+            // The procedure resumes from the last step stored.
+            fail::cfg("procedure_store_step", &format!("return({procedure_id})")).unwrap();
+            let procedure: BoxedProcedure = Box::new(MockProcedure::new("step-1"));
+            assert!(store
+                .store_procedure(procedure_id, 1, &procedure, None)
+                .await
+                .is_err());
+            fail::remove("procedure_store_step");
+            let messages = store.load_messages().await.unwrap();
+            assert_eq!("step-0", messages[&procedure_id].data);
+
+            // The procedure is still loaded if it fails to commit.
+            fail::cfg("procedure_commit", &format!("return({procedure_id})")).unwrap();
+            assert!(store.commit_procedure(procedure_id, 1).await.is_err());
+            fail::remove("procedure_commit");
+            assert_eq!(1, store.load_messages().await.unwrap().len());
+
+            store.commit_procedure(procedure_id, 1).await.unwrap();
+            assert!(store.load_messages().await.unwrap().is_empty());
+        }
+    }
 }
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use futures::{Stream, TryStreamExt};
@@ -25,14 +26,14 @@ use crate::error::{
 };
 
 /// Key value from state store.
-type KeyValue = (String, Vec<u8>);
+pub type KeyValue = (String, Vec<u8>);
 
 /// Stream that yields [KeyValue].
-type KeyValueStream = Pin<Box<dyn Stream<Item = Result<KeyValue>> + Send>>;
+pub type KeyValueStream = Pin<Box<dyn Stream<Item = Result<KeyValue>> + Send>>;
 
 /// Storage layer for persisting procedure's state.
 #[async_trait]
-pub(crate) trait StateStore: Send + Sync {
+pub trait StateStore: Send + Sync {
     /// Puts `key` and `value` into the store.
     async fn put(&self, key: &str, value: Vec<u8>) -> Result<()>;
 
@@ -48,7 +49,7 @@ pub(crate) trait StateStore: Send + Sync {
 }
 
 /// Reference counted pointer to [StateStore].
-pub(crate) type StateStoreRef = Arc<dyn StateStore>;
+pub type StateStoreRef = Arc<dyn StateStore>;
 
 /// [StateStore] based on [ObjectStore].
 #[derive(Debug)]
@@ -66,6 +67,16 @@ impl ObjectStateStore {
 #[async_trait]
 impl StateStore for ObjectStateStore {
     async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        // Scoped by the prefix of the key.
+        #[cfg(feature = "failpoints")]
+        if common_base::failpoint::triggered_by("state_store_put", |prefix| key.starts_with(prefix))
+        {
+            let e = object_store::Error::new(
+                object_store::ErrorKind::Unexpected,
+                "Injected failure at state_store_put",
+            );
+            return Err(e).context(PutStateSnafu { key });
+        }
         let object = self.store.object(key);
         object.write(value).await.context(PutStateSnafu { key })
     }
@@ -110,6 +121,42 @@ impl StateStore for ObjectStateStore {
     }
 }
 
+/// A [StateStore] that keeps all states in memory.
+#[derive(Debug, Default)]
+pub(crate) struct MemStateStore {
+    states: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl StateStore for MemStateStore {
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.states.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn walk_top_down(&self, path: &str) -> Result<KeyValueStream> {
+        // Keys in the store are relative to the root.
+        let prefix = path.trim_start_matches('/');
+        let key_values: Vec<_> = self
+            .states
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| Ok((key.clone(), value.clone())))
+            .collect();
+        Ok(Box::pin(futures::stream::iter(key_values)))
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        let mut states = self.states.lock().unwrap();
+        for key in keys {
+            states.remove(key);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use object_store::services::fs::Builder;
@@ -185,4 +232,31 @@ mod tests {
         data.sort_unstable_by(|a, b| a.0.cmp(&b.0));
         assert_eq!(vec![("a/1".to_string(), b"v1".to_vec()),], data);
     }
+
+    #[tokio::test]
+    async fn test_mem_state_store() {
+        let state_store = MemStateStore::default();
+        state_store.put("a/1", b"v1".to_vec()).await.unwrap();
+        state_store.put("a/2", b"v2".to_vec()).await.unwrap();
+        state_store.put("b/1", b"v3".to_vec()).await.unwrap();
+
+        let data: Vec<_> = state_store
+            .walk_top_down("/")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(3, data.len());
+
+        state_store.delete(&["a/2".to_string()]).await.unwrap();
+        let data: Vec<_> = state_store
+            .walk_top_down("a/")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(vec![("a/1".to_string(), b"v1".to_vec())], data);
+    }
 }
@@ -0,0 +1,431 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to test the crash recovery of procedures.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use snafu::{ensure, OptionExt};
+
+use crate::error::{LoaderConflictSnafu, LoaderNotFoundSnafu, Result};
+use crate::store::state_store::MemStateStore;
+use crate::store::{ProcedureMessage, ProcedureStore};
+use crate::{BoxedProcedure, BoxedProcedureLoader, Context, ProcedureId, ProcedureWithId, Status};
+
+/// A point to crash the procedure at, the step is the number of [Procedure::execute]
+/// calls before the crash, counting calls of subprocedures in.
+///
+/// [Procedure::execute]: crate::Procedure::execute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailPoint {
+    /// Index of the `execute()` call to crash after.
+    pub step: u32,
+    /// Whether to crash after the state returned by the call is persisted.
+    pub persisted: bool,
+}
+
+/// Drives procedures step by step on an in-memory procedure store, crashes them at
+/// specific [FailPoint]s and recovers them from the store like a restarted procedure
+/// manager does.
+pub struct RecoveryTester {
+    store: ProcedureStore,
+    loaders: HashMap<String, BoxedProcedureLoader>,
+    /// Step of the store, only needs to increase across all procedures.
+    next_step: AtomicU32,
+}
+
+impl Default for RecoveryTester {
+    fn default() -> RecoveryTester {
+        RecoveryTester {
+            store: new_mem_procedure_store(),
+            loaders: HashMap::new(),
+            next_step: AtomicU32::new(0),
+        }
+    }
+}
+
+impl RecoveryTester {
+    pub fn new() -> RecoveryTester {
+        RecoveryTester::default()
+    }
+
+    /// Registers loader for specific procedure type `name`.
+    pub fn register_loader(&mut self, name: &str, loader: BoxedProcedureLoader) -> Result<()> {
+        ensure!(
+            !self.loaders.contains_key(name),
+            LoaderConflictSnafu { name }
+        );
+        self.loaders.insert(name.to_string(), loader);
+        Ok(())
+    }
+
+    /// Clears all procedures in the store.
+    pub fn reset(&mut self) {
+        self.store = new_mem_procedure_store();
+    }
+
+    /// Executes the procedure until it is done, or crashes it at the `fail_point`.
+    ///
+    /// Returns whether the procedure crashed.
+    pub async fn execute(
+        &mut self,
+        procedure: BoxedProcedure,
+        fail_point: Option<FailPoint>,
+    ) -> Result<bool> {
+        let procedure = ProcedureWithId::with_random_id(procedure);
+        let mut executed = 0;
+        self.store
+            .store_procedure(procedure.id, self.next_step(), &procedure.procedure, None)
+            .await?;
+        self.run(procedure, None, fail_point, &mut executed).await
+    }
+
+    /// Loads unfinished procedures from the store and executes them until they are done,
+    /// subprocedures are recovered before their parents.
+    pub async fn recover(&mut self) -> Result<()> {
+        let messages = self.store.load_messages().await?;
+        // Deepest procedures go first, procedures of the same depth are ordered by their ids
+        // so the order doesn't depend on the iteration order of the map.
+        let mut procedure_ids: Vec<_> = messages
+            .keys()
+            .map(|id| (depth(&messages, *id), *id))
+            .collect();
+        procedure_ids.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        for (_, procedure_id) in procedure_ids {
+            let message = &messages[&procedure_id];
+            let loader = self
+                .loaders
+                .get(&message.type_name)
+                .context(LoaderNotFoundSnafu {
+                    name: &message.type_name,
+                })?;
+            let procedure = ProcedureWithId {
+                id: procedure_id,
+                procedure: loader(&message.data)?,
+            };
+            let mut executed = 0;
+            self.run(procedure, message.parent_id, None, &mut executed)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Crashes the procedures created by `new_procedure` at every possible [FailPoint]
+    /// and checks that they could be recovered, each run starts with an empty store.
+    ///
+    /// Procedures with side effects must be idempotent, so `new_procedure` should also
+    /// reset the environment the procedure works on.
+    ///
+    /// # Panics
+    /// Panics if any run fails, the message contains the [FailPoint] of the run.
+    pub async fn check_recovery<F>(&mut self, mut new_procedure: F)
+    where
+        F: FnMut() -> BoxedProcedure,
+    {
+        // Counts the steps without failure, a procedure never crashes if no fail point is set.
+        self.reset();
+        let procedure = ProcedureWithId::with_random_id(new_procedure());
+        self.store
+            .store_procedure(procedure.id, self.next_step(), &procedure.procedure, None)
+            .await
+            .unwrap();
+        let mut num_steps = 0;
+        self.run(procedure, None, None, &mut num_steps)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to execute procedure without failure: {e}"));
+
+        for step in 0..num_steps {
+            for persisted in [false, true] {
+                let fail_point = FailPoint { step, persisted };
+                self.reset();
+                let crashed = self
+                    .execute(new_procedure(), Some(fail_point))
+                    .await
+                    .unwrap_or_else(|e| panic!("Failed to execute procedure, {fail_point:?}: {e}"));
+                assert!(crashed, "Procedure doesn't crash at {fail_point:?}");
+
+                self.recover()
+                    .await
+                    .unwrap_or_else(|e| panic!("Failed to recover procedure, {fail_point:?}: {e}"));
+            }
+        }
+    }
+
+    fn next_step(&self) -> u32 {
+        self.next_step.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Executes the procedure, `executed` is the number of `execute()` calls so far.
+    fn run<'a>(
+        &'a mut self,
+        procedure: ProcedureWithId,
+        parent_id: Option<ProcedureId>,
+        fail_point: Option<FailPoint>,
+        executed: &'a mut u32,
+    ) -> BoxFuture<'a, Result<bool>> {
+        async move {
+            let ProcedureWithId { id, mut procedure } = procedure;
+            let ctx = Context { procedure_id: id };
+            loop {
+                let status = procedure.execute(&ctx).await?;
+                let crash = fail_point.filter(|p| p.step == *executed);
+                *executed += 1;
+
+                if matches!(
+                    crash,
+                    Some(FailPoint {
+                        persisted: false,
+                        ..
+                    })
+                ) {
+                    return Ok(true);
+                }
+                if status.need_persist() {
+                    self.store
+                        .store_procedure(id, self.next_step(), &procedure, parent_id)
+                        .await?;
+                }
+                if crash.is_some() {
+                    return Ok(true);
+                }
+
+                match status {
+                    Status::Executing { .. } => (),
+                    Status::Suspended { subprocedures, .. } => {
+                        for subprocedure in subprocedures {
+                            self.store
+                                .store_procedure(
+                                    subprocedure.id,
+                                    self.next_step(),
+                                    &subprocedure.procedure,
+                                    Some(id),
+                                )
+                                .await?;
+                            if self
+                                .run(subprocedure, Some(id), fail_point, executed)
+                                .await?
+                            {
+                                return Ok(true);
+                            }
+                        }
+                    }
+                    Status::Done => {
+                        self.store.commit_procedure(id, self.next_step()).await?;
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Returns the number of ancestors of procedure `id` in `messages`.
+fn depth(messages: &HashMap<ProcedureId, ProcedureMessage>, id: ProcedureId) -> usize {
+    let mut depth = 0;
+    let mut parent_id = messages.get(&id).and_then(|message| message.parent_id);
+    while let Some(id) = parent_id {
+        depth += 1;
+        parent_id = messages.get(&id).and_then(|message| message.parent_id);
+    }
+    depth
+}
+
+fn new_mem_procedure_store() -> ProcedureStore {
+    ProcedureStore(Arc::new(MemStateStore::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{LockKey, Procedure};
+
+    /// Procedure that needs `total` steps, each step records its index into `steps`.
+    struct StepProcedure {
+        step: u32,
+        total: u32,
+        steps: Arc<Mutex<Vec<u32>>>,
+        subprocedure: bool,
+    }
+
+    #[async_trait]
+    impl Procedure for StepProcedure {
+        fn type_name(&self) -> &str {
+            "StepProcedure"
+        }
+
+        async fn execute(&mut self, _ctx: &Context) -> Result<Status> {
+            self.steps.lock().unwrap().push(self.step);
+            self.step += 1;
+            if self.step == 1 && self.subprocedure {
+                let child = StepProcedure {
+                    step: 0,
+                    total: 2,
+                    steps: Arc::new(Mutex::new(Vec::new())),
+                    subprocedure: false,
+                };
+                return Ok(Status::Suspended {
+                    subprocedures: vec![ProcedureWithId::with_random_id(Box::new(child))],
+                    persist: true,
+                });
+            }
+            if self.step < self.total {
+                Ok(Status::executing(true))
+            } else {
+                Ok(Status::Done)
+            }
+        }
+
+        fn dump(&self) -> Result<String> {
+            Ok(format!(
+                "{},{},{}",
+                self.step, self.total, self.subprocedure
+            ))
+        }
+
+        fn lock_keys(&self) -> Vec<LockKey> {
+            Vec::new()
+        }
+    }
+
+    fn new_tester(steps: Arc<Mutex<Vec<u32>>>) -> RecoveryTester {
+        let mut tester = RecoveryTester::new();
+        tester
+            .register_loader(
+                "StepProcedure",
+                Box::new(move |data: &str| -> Result<BoxedProcedure> {
+                    let parts: Vec<_> = data.split(',').collect();
+                    Ok(Box::new(StepProcedure {
+                        step: parts[0].parse().unwrap(),
+                        total: parts[1].parse().unwrap(),
+                        steps: steps.clone(),
+                        subprocedure: parts[2].parse().unwrap(),
+                    }))
+                }),
+            )
+            .unwrap();
+        tester
+    }
+
+    fn new_procedure(steps: &Arc<Mutex<Vec<u32>>>, subprocedure: bool) -> BoxedProcedure {
+        Box::new(StepProcedure {
+            step: 0,
+            total: 3,
+            steps: steps.clone(),
+            subprocedure,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_recover_from_fail_point() {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let mut tester = new_tester(steps.clone());
+        assert!(tester
+            .register_loader(
+                "StepProcedure",
+                Box::new(|_: &str| -> Result<BoxedProcedure> { unreachable!() })
+            )
+            .is_err());
+
+        let fail_point = FailPoint {
+            step: 1,
+            persisted: true,
+        };
+        assert!(tester
+            .execute(new_procedure(&steps, false), Some(fail_point))
+            .await
+            .unwrap());
+        assert_eq!(vec![0, 1], *steps.lock().unwrap());
+        tester.recover().await.unwrap();
+        // Resumes from the persisted step.
+        assert_eq!(vec![0, 1, 2], *steps.lock().unwrap());
+
+        steps.lock().unwrap().clear();
+        let fail_point = FailPoint {
+            step: 1,
+            persisted: false,
+        };
+        assert!(tester
+            .execute(new_procedure(&steps, false), Some(fail_point))
+            .await
+            .unwrap());
+        tester.recover().await.unwrap();
+        // The step 1 is executed again.
+        assert_eq!(vec![0, 1, 1, 2], *steps.lock().unwrap());
+
+        // Nothing to recover.
+        steps.lock().unwrap().clear();
+        assert!(!tester
+            .execute(new_procedure(&steps, false), None)
+            .await
+            .unwrap());
+        tester.recover().await.unwrap();
+        assert_eq!(vec![0, 1, 2], *steps.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recover_without_loader() {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let mut tester = RecoveryTester::new();
+        let fail_point = FailPoint {
+            step: 0,
+            persisted: true,
+        };
+        assert!(tester
+            .execute(new_procedure(&steps, false), Some(fail_point))
+            .await
+            .unwrap());
+        assert!(tester.recover().await.is_err());
+    }
+
+    #[test]
+    fn test_depth() {
+        let new_message = |parent_id| ProcedureMessage {
+            type_name: "StepProcedure".to_string(),
+            data: String::new(),
+            parent_id,
+        };
+        let (root, child, grandchild) = (
+            ProcedureId::random(),
+            ProcedureId::random(),
+            ProcedureId::random(),
+        );
+        let messages = HashMap::from([
+            (root, new_message(None)),
+            (child, new_message(Some(root))),
+            (grandchild, new_message(Some(child))),
+        ]);
+        assert_eq!(0, depth(&messages, root));
+        assert_eq!(1, depth(&messages, child));
+        assert_eq!(2, depth(&messages, grandchild));
+    }
+
+    #[tokio::test]
+    async fn test_check_recovery() {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let mut tester = new_tester(steps.clone());
+        tester.check_recovery(|| new_procedure(&steps, true)).await;
+        // The parent procedure always finishes after the child.
+        assert_eq!(Some(&2), steps.lock().unwrap().last());
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Instruments the execution of procedures and watches for stuck procedures.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common_telemetry::logging;
+use metrics::{decrement_gauge, gauge, histogram, increment_counter, increment_gauge};
+
+use crate::metric::*;
+use crate::{ProcedureId, ProcedureState};
+
+/// Default age after which a running procedure is considered stuck.
+pub const DEFAULT_MAX_PROCEDURE_AGE: Duration = Duration::from_secs(30 * 60);
+
+/// A procedure running longer than the max age.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StuckProcedure {
+    pub procedure_id: ProcedureId,
+    pub type_name: String,
+    pub age: Duration,
+}
+
+#[derive(Debug)]
+struct RunningProcedure {
+    type_name: String,
+    start: Instant,
+    /// Whether the procedure has been reported as stuck.
+    stuck: bool,
+}
+
+/// `Watchdog` records metrics of procedures reported by the procedure runner, and flags
+/// the procedures running longer than `max_age` so stuck procedures are noticed.
+#[derive(Debug)]
+pub struct Watchdog {
+    max_age: Duration,
+    running: Mutex<HashMap<ProcedureId, RunningProcedure>>,
+}
+
+pub type WatchdogRef = Arc<Watchdog>;
+
+impl Default for Watchdog {
+    fn default() -> Watchdog {
+        Watchdog::new(DEFAULT_MAX_PROCEDURE_AGE)
+    }
+}
+
+impl Watchdog {
+    pub fn new(max_age: Duration) -> Watchdog {
+        Watchdog {
+            max_age,
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reports that the procedure starts running.
+    pub fn on_start(&self, procedure_id: ProcedureId, type_name: &str) {
+        let mut running = self.running.lock().unwrap();
+        if running.contains_key(&procedure_id) {
+            return;
+        }
+        running.insert(
+            procedure_id,
+            RunningProcedure {
+                type_name: type_name.to_string(),
+                start: Instant::now(),
+                stuck: false,
+            },
+        );
+        increment_gauge!(METRIC_PROCEDURE_RUNNING, 1.0, &type_labels(type_name));
+    }
+
+    /// Reports that a step of the procedure takes `elapsed` to execute.
+    pub fn on_step(&self, type_name: &str, elapsed: Duration) {
+        histogram!(
+            METRIC_PROCEDURE_STEP_ELAPSED,
+            elapsed,
+            &type_labels(type_name)
+        );
+    }
+
+    /// Reports that a failed step of the procedure is retried.
+    pub fn on_retry(&self, type_name: &str) {
+        increment_counter!(METRIC_PROCEDURE_RETRIES_TOTAL, &type_labels(type_name));
+    }
+
+    /// Reports that the procedure ends with `state`.
+    pub fn on_finish(&self, procedure_id: ProcedureId, state: &ProcedureState) {
+        let Some(procedure) = self.running.lock().unwrap().remove(&procedure_id) else {
+            return;
+        };
+
+        let labels = type_labels(&procedure.type_name);
+        decrement_gauge!(METRIC_PROCEDURE_RUNNING, 1.0, &labels);
+        if procedure.stuck {
+            logging::info!(
+                "Stuck procedure {} of type {} ends with state {:?} after {:?}",
+                procedure_id,
+                procedure.type_name,
+                state,
+                procedure.start.elapsed()
+            );
+        }
+        if let ProcedureState::Failed = state {
+            increment_counter!(METRIC_PROCEDURE_FAILURES_TOTAL, &labels);
+        }
+    }
+
+    /// Returns the procedures running longer than the max age, procedures become stuck
+    /// since the last check are logged.
+    pub fn check(&self) -> Vec<StuckProcedure> {
+        let mut running = self.running.lock().unwrap();
+        let mut stuck_procedures = Vec::new();
+        for (procedure_id, procedure) in running.iter_mut() {
+            let age = procedure.start.elapsed();
+            if age < self.max_age {
+                continue;
+            }
+            if !procedure.stuck {
+                procedure.stuck = true;
+                logging::warn!(
+                    "Procedure {} of type {} is running for {:?}, exceeds the max age {:?}",
+                    procedure_id,
+                    procedure.type_name,
+                    age,
+                    self.max_age
+                );
+            }
+            stuck_procedures.push(StuckProcedure {
+                procedure_id: *procedure_id,
+                type_name: procedure.type_name.clone(),
+                age,
+            });
+        }
+        gauge!(METRIC_PROCEDURE_STUCK, stuck_procedures.len() as f64);
+
+        stuck_procedures
+    }
+
+    /// Spawns a background task to check stuck procedures every `interval`.
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let watchdog = Arc::downgrade(self);
+        common_runtime::spawn_bg(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                // Stops once the watchdog is dropped.
+                match watchdog.upgrade() {
+                    Some(watchdog) => {
+                        watchdog.check();
+                    }
+                    None => return,
+                }
+            }
+        });
+    }
+}
+
+fn type_labels(type_name: &str) -> [(&'static str, String); 1] {
+    [(PROCEDURE_TYPE_LABEL, type_name.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_stuck_procedures() {
+        let watchdog = Watchdog::new(Duration::from_millis(50));
+        let old = ProcedureId::random();
+        watchdog.on_start(old, "old");
+        watchdog.on_step("old", Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(60));
+        let new = ProcedureId::random();
+        watchdog.on_start(new, "new");
+
+        let stuck = watchdog.check();
+        assert_eq!(1, stuck.len());
+        assert_eq!(old, stuck[0].procedure_id);
+        assert_eq!("old", stuck[0].type_name);
+        assert!(stuck[0].age >= Duration::from_millis(50));
+        // Still stuck in the next check.
+        assert_eq!(1, watchdog.check().len());
+
+        watchdog.on_retry("old");
+        watchdog.on_finish(old, &ProcedureState::Failed);
+        assert!(watchdog.check().is_empty());
+        watchdog.on_finish(new, &ProcedureState::Done);
+        assert!(watchdog.running.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_start_twice() {
+        let watchdog = Watchdog::default();
+        let procedure_id = ProcedureId::random();
+        let start = Instant::now();
+        watchdog.on_start(procedure_id, "a");
+        watchdog.on_start(procedure_id, "a");
+        let running = watchdog.running.lock().unwrap();
+        assert_eq!(1, running.len());
+        assert!(running[&procedure_id].start >= start);
+    }
+}
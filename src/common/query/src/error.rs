@@ -34,6 +34,9 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Fail to execute WebAssembly UDF, source: {}", msg))]
+    WasmUdf { msg: String, backtrace: Backtrace },
+
     #[snafu(display(
         "Fail to create temporary recordbatch when eval Python UDF, source: {}",
         source
@@ -186,6 +189,7 @@ impl ErrorExt for Error {
         match self {
             Error::UdfTempRecordBatch { .. }
             | Error::PyUdf { .. }
+            | Error::WasmUdf { .. }
             | Error::ExecuteFunction { .. }
             | Error::GenerateFunction { .. }
             | Error::CreateAccumulator { .. }
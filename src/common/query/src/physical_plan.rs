@@ -64,6 +64,12 @@ pub trait PhysicalPlan: Debug + Send + Sync {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream>;
+
+    /// Returns the estimated statistics of the output of this plan, which the planner uses
+    /// to choose join orders and aggregation strategies. Nothing is known by default.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
 }
 
 #[derive(Debug)]
@@ -130,6 +136,10 @@ impl PhysicalPlan for PhysicalPlanAdapter {
 
         Ok(Box::pin(adapter))
     }
+
+    fn statistics(&self) -> Statistics {
+        self.df_plan.statistics()
+    }
 }
 
 #[derive(Debug)]
@@ -188,8 +198,7 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
     }
 
     fn statistics(&self) -> Statistics {
-        // TODO(LFC): impl statistics
-        Statistics::default()
+        self.0.statistics()
     }
 }
 
@@ -33,6 +33,7 @@ use substrait_proto::protobuf::read_rel::{NamedTable, ReadType};
 use substrait_proto::protobuf::rel::RelType;
 use substrait_proto::protobuf::{FilterRel, Plan, PlanRel, ReadRel, Rel};
 use table::table::adapter::DfTableProviderAdapter;
+use table::table::epoch::EpochPinnedTable;
 
 use crate::context::ConvertorContext;
 use crate::df_expr::{expression_from_df_expr, to_df_expr};
@@ -71,6 +72,13 @@ impl SubstraitPlan for DFLogicalSubstraitConvertor {
 }
 
 impl DFLogicalSubstraitConvertor {
+    /// Converts the logical `plan` to substrait and prints it in the debug format of the
+    /// protobuf messages, for explaining the plan.
+    pub fn encode_to_text(&self, plan: LogicalPlan) -> Result<String, Error> {
+        let plan = self.convert_df_plan(plan)?;
+        Ok(format!("{plan:#?}"))
+    }
+
     fn convert_plan(
         &self,
         mut plan: Plan,
@@ -238,21 +246,29 @@ impl DFLogicalSubstraitConvertor {
             .context(TableNotFoundSnafu {
                 name: format!("{catalog_name}.{schema_name}.{table_name}"),
             })?;
-        let adapter = Arc::new(DefaultTableSource::new(Arc::new(
-            DfTableProviderAdapter::new(table_ref),
-        )));
 
         // Get schema directly from the table, and compare it with the schema retrieved from substrait proto.
-        let stored_schema = adapter.schema();
+        let current_schema = table_ref.schema().arrow_schema().clone();
         let retrieved_schema = to_schema(read_rel.base_schema.unwrap_or_default())?;
         let retrieved_arrow_schema = retrieved_schema.arrow_schema();
-        ensure!(
-            same_schema_without_metadata(&stored_schema, retrieved_arrow_schema),
-            SchemaNotMatchSnafu {
-                substrait_schema: retrieved_arrow_schema.clone(),
-                storage_schema: stored_schema
-            }
-        );
+        let table_ref = if same_schema_without_metadata(&current_schema, retrieved_arrow_schema) {
+            table_ref
+        } else {
+            // The plan is built at another schema epoch, e.g. the table is being altered, so
+            // the table is read at the epoch of the plan.
+            let pinned = EpochPinnedTable::try_new(table_ref, &retrieved_schema).context(
+                SchemaNotMatchSnafu {
+                    substrait_schema: retrieved_arrow_schema.clone(),
+                    storage_schema: current_schema,
+                },
+            )?;
+            debug!("Read table {catalog_name}.{schema_name}.{table_name} at the epoch of the plan");
+            Arc::new(pinned) as _
+        };
+        let adapter = Arc::new(DefaultTableSource::new(Arc::new(
+            DfTableProviderAdapter::new(table_ref),
+        )));
+        let stored_schema = adapter.schema();
 
         // Convert filter
         let filters = if let Some(filter) = read_rel.filter {
@@ -522,9 +538,12 @@ mod test {
     use catalog::{CatalogList, CatalogProvider, RegisterTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use datafusion::common::{DFSchema, ToDFSchema};
-    use datatypes::schema::Schema;
+    use datafusion_expr::LogicalPlanBuilder;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
     use table::requests::CreateTableRequest;
     use table::test_util::{EmptyTable, MockTableEngine};
+    use table::TableRef;
 
     use super::*;
     use crate::schema::test::supported_types;
@@ -615,6 +634,83 @@ mod test {
             fetch: None,
         });
 
+        let text = DFLogicalSubstraitConvertor
+            .encode_to_text(table_scan_plan.clone())
+            .unwrap();
+        assert!(text.contains("ReadRel"), "{text}");
+
         logical_plan_round_trip(table_scan_plan, catalog_manager).await;
     }
+
+    fn build_table_scan_plan(table_ref: TableRef) -> LogicalPlan {
+        LogicalPlanBuilder::scan(
+            format!("{DEFAULT_CATALOG_NAME}.{DEFAULT_SCHEMA_NAME}.{DEFAULT_TABLE_NAME}"),
+            Arc::new(DefaultTableSource::new(Arc::new(
+                DfTableProviderAdapter::new(table_ref),
+            ))),
+            Some(vec![1, 3]),
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_table_scan_at_pinned_epoch() {
+        let catalog_manager = build_mock_catalog_manager().await;
+        // The table is altered once.
+        let mut request = build_create_table_request(DEFAULT_TABLE_NAME);
+        let schema = SchemaBuilder::try_from(supported_types())
+            .unwrap()
+            .version(1)
+            .build()
+            .unwrap();
+        request.schema = Arc::new(schema);
+        let table_ref = Arc::new(EmptyTable::new(request));
+        catalog_manager
+            .register_table(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: DEFAULT_TABLE_NAME.to_string(),
+                table_id: 1,
+                table: table_ref,
+            })
+            .await
+            .unwrap();
+
+        // The plan is built before the last column is added to the table.
+        let mut request = build_create_table_request(DEFAULT_TABLE_NAME);
+        let mut column_schemas = supported_types();
+        let _ = column_schemas.pop();
+        request.schema = Arc::new(Schema::new(column_schemas.clone()));
+        let plan = build_table_scan_plan(Arc::new(EmptyTable::new(request)));
+
+        let convertor = DFLogicalSubstraitConvertor;
+        let proto = convertor.encode(plan.clone()).unwrap();
+        let tripped_plan = convertor.decode(proto, catalog_manager.clone()).unwrap();
+        assert_eq!(plan.schema(), tripped_plan.schema());
+        let LogicalPlan::TableScan(scan) = tripped_plan else {
+            unreachable!()
+        };
+        assert_eq!(
+            Schema::new(column_schemas).arrow_schema().fields(),
+            scan.source.schema().fields()
+        );
+
+        // The plan is built with a column absent in the table.
+        let mut request = build_create_table_request(DEFAULT_TABLE_NAME);
+        let mut column_schemas = supported_types();
+        column_schemas.push(ColumnSchema::new(
+            "dropped",
+            ConcreteDataType::int32_datatype(),
+            true,
+        ));
+        request.schema = Arc::new(Schema::new(column_schemas));
+        let plan = build_table_scan_plan(Arc::new(EmptyTable::new(request)));
+        let proto = convertor.encode(plan).unwrap();
+        assert!(matches!(
+            convertor.decode(proto, catalog_manager),
+            Err(Error::SchemaNotMatch { .. })
+        ));
+    }
 }
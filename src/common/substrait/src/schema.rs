@@ -12,21 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
+use snafu::ResultExt;
 use substrait_proto::protobuf::r#type::{Nullability, Struct as SubstraitStruct};
 use substrait_proto::protobuf::NamedStruct;
 
-use crate::error::Result;
+use crate::error::{self, Result};
 use crate::types::{from_concrete_type, to_concrete_type};
 
 pub fn to_schema(named_struct: NamedStruct) -> Result<Schema> {
-    if named_struct.r#struct.is_none() {
+    let Some(substrait_struct) = named_struct.r#struct else {
         return Ok(Schema::new(vec![]));
-    }
+    };
 
-    let column_schemas = named_struct
-        .r#struct
-        .unwrap()
+    let version = substrait_struct.type_variation_reference;
+    let column_schemas = substrait_struct
         .types
         .into_iter()
         .zip(named_struct.names.into_iter())
@@ -35,9 +35,11 @@ pub fn to_schema(named_struct: NamedStruct) -> Result<Schema> {
             let column_schema = ColumnSchema::new(name, concrete_type, is_nullable);
             Ok(column_schema)
         })
-        .collect::<Result<_>>()?;
+        .collect::<Result<Vec<_>>>()?;
 
-    Ok(Schema::new(column_schemas))
+    SchemaBuilder::try_from(column_schemas)
+        .and_then(|builder| builder.version(version).build())
+        .context(error::ConvertDfSchemaSnafu)
 }
 
 pub fn from_schema(schema: &Schema) -> Result<NamedStruct> {
@@ -53,10 +55,12 @@ pub fn from_schema(schema: &Schema) -> Result<NamedStruct> {
         types.push(substrait_type);
     }
 
-    // TODO(ruihang): `type_variation_reference` and `nullability` are unspecified.
+    // TODO(ruihang): `nullability` is unspecified.
+    // The struct has no type variation, so its variation reference carries the version of the
+    // schema, which is the schema epoch of the table read by the plan.
     let substrait_struct = SubstraitStruct {
         types,
-        type_variation_reference: 0,
+        type_variation_reference: schema.version(),
         nullability: Nullability::Unspecified as _,
     };
 
@@ -108,4 +112,19 @@ pub(crate) mod test {
 
         assert_eq!(schema, converted_schema);
     }
+
+    #[test]
+    fn test_schema_version_round_trip() {
+        let schema = SchemaBuilder::try_from(supported_types())
+            .unwrap()
+            .version(3)
+            .build()
+            .unwrap();
+
+        let named_struct = from_schema(&schema).unwrap();
+        let converted_schema = to_schema(named_struct).unwrap();
+
+        assert_eq!(3, converted_schema.version());
+        assert_eq!(schema, converted_schema);
+    }
 }
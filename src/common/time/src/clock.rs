@@ -0,0 +1,104 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Source of the current time. Components checking leases or expiry read the time from
+//! a [ClockRef] instead of the system time, so tests could control the time by a
+//! [MockClock].
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::util;
+
+/// Clock to read the current time from.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the time duration since UNIX_EPOCH in milliseconds.
+    fn now_millis(&self) -> i64;
+}
+
+pub type ClockRef = Arc<dyn Clock>;
+
+/// [Clock] reading the system time.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        util::current_time_millis()
+    }
+}
+
+/// Returns a [ClockRef] reading the system time.
+pub fn system_clock() -> ClockRef {
+    Arc::new(SystemClock)
+}
+
+/// [Clock] whose time only changes when it is set or advanced explicitly.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now_millis: AtomicI64,
+}
+
+impl MockClock {
+    pub fn new(now_millis: i64) -> Self {
+        Self {
+            now_millis: AtomicI64::new(now_millis),
+        }
+    }
+
+    pub fn set(&self, now_millis: i64) {
+        self.now_millis.store(now_millis, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now_millis
+            .fetch_add(duration.as_millis() as i64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> i64 {
+        self.now_millis.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let clock = system_clock();
+        let before = util::current_time_millis();
+        let now = clock.now_millis();
+        assert!(now >= before);
+        assert!(util::current_time_millis() >= now);
+    }
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new(1000);
+        assert_eq!(1000, clock.now_millis());
+        assert_eq!(1000, clock.now_millis());
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(3000, clock.now_millis());
+
+        clock.set(500);
+        assert_eq!(500, clock.now_millis());
+        assert_eq!(0, MockClock::default().now_millis());
+    }
+}
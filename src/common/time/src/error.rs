@@ -26,14 +26,16 @@ pub enum Error {
     ParseDateStr { raw: String, source: ParseError },
     #[snafu(display("Failed to parse a string into Timestamp, raw string: {}", raw))]
     ParseTimestamp { raw: String, backtrace: Backtrace },
+    #[snafu(display("Failed to parse a string into Interval, raw string: {}", raw))]
+    ParseInterval { raw: String, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
-            Error::ParseDateStr { .. } | Error::ParseTimestamp { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::ParseDateStr { .. }
+            | Error::ParseTimestamp { .. }
+            | Error::ParseInterval { .. } => StatusCode::InvalidArguments,
         }
     }
 
@@ -0,0 +1,197 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+use snafu::{ensure, OptionExt};
+
+use crate::error::{Error, ParseIntervalSnafu, Result};
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
+
+/// A calendar interval, months and days are kept apart from the sub-day part since
+/// their length varies, the same as arrow's `IntervalMonthDayNano`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    nanos: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, nanos: i64) -> Self {
+        Self {
+            months,
+            days,
+            nanos,
+        }
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Returns the interval as `(days, milliseconds)` if it has no month part and the
+    /// sub-day part is a whole number of milliseconds.
+    pub fn to_day_time(&self) -> Option<(i32, i32)> {
+        if self.months != 0 || self.nanos % NANOS_PER_MILLI != 0 {
+            return None;
+        }
+        let days = self.nanos / NANOS_PER_DAY;
+        let millis = (self.nanos % NANOS_PER_DAY) / NANOS_PER_MILLI;
+        let days = self.days.checked_add(i32::try_from(days).ok()?)?;
+        Some((days, millis as i32))
+    }
+
+    fn unit_to_interval(value: i64, unit: &str) -> Option<Self> {
+        let interval = match unit {
+            "y" | "year" | "years" => Self::new(i32::try_from(value.checked_mul(12)?).ok()?, 0, 0),
+            "mon" | "mons" | "month" | "months" => Self::new(i32::try_from(value).ok()?, 0, 0),
+            "w" | "week" | "weeks" => Self::new(0, i32::try_from(value.checked_mul(7)?).ok()?, 0),
+            "d" | "day" | "days" => Self::new(0, i32::try_from(value).ok()?, 0),
+            "h" | "hour" | "hours" => Self::new(0, 0, value.checked_mul(NANOS_PER_HOUR)?),
+            "m" | "min" | "mins" | "minute" | "minutes" => {
+                Self::new(0, 0, value.checked_mul(NANOS_PER_MINUTE)?)
+            }
+            "s" | "sec" | "secs" | "second" | "seconds" => {
+                Self::new(0, 0, value.checked_mul(NANOS_PER_SECOND)?)
+            }
+            "ms" | "millisecond" | "milliseconds" => {
+                Self::new(0, 0, value.checked_mul(NANOS_PER_MILLI)?)
+            }
+            "us" | "microsecond" | "microseconds" => {
+                Self::new(0, 0, value.checked_mul(NANOS_PER_MICRO)?)
+            }
+            "ns" | "nanosecond" | "nanoseconds" => Self::new(0, 0, value),
+            _ => return None,
+        };
+        Some(interval)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.nanos.checked_add(other.nanos)?,
+        ))
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    /// Parses intervals like `5 minutes`, `1 day 2 hours`, or the shorthand `15m`, `1h30m`.
+    /// A leading `-` negates the whole interval.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || ParseIntervalSnafu { raw: s };
+
+        let trimmed = s.trim();
+        let (negative, mut rest) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, trimmed),
+        };
+        ensure!(!rest.is_empty(), invalid());
+
+        let mut interval = Interval::default();
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            ensure!(digits > 0, invalid());
+            let value = rest[..digits].parse::<i64>().ok().with_context(invalid)?;
+            rest = rest[digits..].trim_start();
+
+            let unit_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let unit = rest[..unit_len].to_ascii_lowercase();
+            rest = rest[unit_len..].trim_start();
+
+            interval = Self::unit_to_interval(value, &unit)
+                .and_then(|v| interval.checked_add(&v))
+                .with_context(invalid)?;
+        }
+
+        if negative {
+            interval = Self::new(-interval.months, -interval.days, -interval.nanos);
+        }
+        Ok(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(
+            Interval::new(0, 0, 5 * NANOS_PER_MINUTE),
+            "5 minutes".parse::<Interval>().unwrap()
+        );
+        assert_eq!(
+            Interval::new(0, 0, 15 * NANOS_PER_MINUTE),
+            "15m".parse::<Interval>().unwrap()
+        );
+        assert_eq!(
+            Interval::new(0, 0, NANOS_PER_HOUR + 30 * NANOS_PER_MINUTE),
+            "1h30m".parse::<Interval>().unwrap()
+        );
+        assert_eq!(
+            Interval::new(14, 3, 2 * NANOS_PER_HOUR),
+            "1 year 2 months 3 days 2 HOURS"
+                .parse::<Interval>()
+                .unwrap()
+        );
+        assert_eq!(Interval::new(0, -14, 0), "-2w".parse::<Interval>().unwrap());
+        assert_eq!(
+            Interval::new(0, 0, 1500 * NANOS_PER_MICRO),
+            "1ms 500us".parse::<Interval>().unwrap()
+        );
+
+        assert!("".parse::<Interval>().is_err());
+        assert!("5".parse::<Interval>().is_err());
+        assert!("m".parse::<Interval>().is_err());
+        assert!("5 fortnights".parse::<Interval>().is_err());
+        assert!("1.5h".parse::<Interval>().is_err());
+        assert!("99999999999999999999s".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_to_day_time() {
+        assert_eq!(
+            Some((0, 90_000)),
+            "1m30s".parse::<Interval>().unwrap().to_day_time()
+        );
+        assert_eq!(
+            Some((2, 3_600_000)),
+            "1d 25h".parse::<Interval>().unwrap().to_day_time()
+        );
+        assert_eq!(None, "1 month".parse::<Interval>().unwrap().to_day_time());
+        assert_eq!(None, "1us".parse::<Interval>().unwrap().to_day_time());
+    }
+}
@@ -96,6 +96,13 @@ impl Timestamp {
         (sec_div, sec_mod * nsec_mul)
     }
 
+    /// Converts the timestamp to a [NaiveDateTime] in UTC, returns `None` if the timestamp
+    /// exceeds what chrono can represent.
+    pub fn to_chrono_datetime(&self) -> Option<NaiveDateTime> {
+        let (sec, nsec) = self.split();
+        NaiveDateTime::from_timestamp_opt(sec, nsec as u32)
+    }
+
     /// Format timestamp to ISO8601 string. If the timestamp exceeds what chrono timestamp can
     /// represent, this function simply print the timestamp unit and value in plain string.
     pub fn to_iso8601_string(&self) -> String {
@@ -477,6 +484,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_chrono_datetime() {
+        let ts = Timestamp::new_nanosecond(1_672_531_200_000_000_001);
+        assert_eq!(
+            "2023-01-01 00:00:00.000000001",
+            ts.to_chrono_datetime()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S%.9f")
+                .to_string()
+        );
+
+        let ts = Timestamp::new_millisecond(-1);
+        assert_eq!(
+            "1969-12-31 23:59:59.999",
+            ts.to_chrono_datetime()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S%.3f")
+                .to_string()
+        );
+
+        assert!(Timestamp::new_second(i64::MAX)
+            .to_chrono_datetime()
+            .is_none());
+    }
+
     #[test]
     pub fn test_from_i64() {
         let t: Timestamp = 42.into();
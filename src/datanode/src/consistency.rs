@@ -0,0 +1,212 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consistency check between the catalog and the storage on startup.
+//!
+//! A crash or a manual operation on the object store may leave the catalog and the storage
+//! inconsistent, e.g. SST files referenced by region manifests are lost, or directories of
+//! tables are left in the storage after the tables are removed from the catalog. The check
+//! reports them before the datanode serves requests.
+
+use std::collections::{BTreeSet, HashSet};
+
+use catalog::{all_tables, CatalogManagerRef};
+use common_catalog::consts::MIN_USER_TABLE_ID;
+use common_telemetry::{error, info, warn};
+use futures::TryStreamExt;
+use mito::engine::table_dir;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use table::metadata::{TableId, TableType};
+
+use crate::datanode::ConsistencyCheckConfig;
+use crate::error::{CatalogSnafu, ListTableDirsSnafu, Result};
+use crate::recycle_bin::RecycleBinRef;
+
+/// Result of a consistency check.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Number of tables checked.
+    pub checked_tables: usize,
+    /// Number of tables skipped as their regions are not opened yet.
+    pub skipped_tables: usize,
+    /// Full names of tables failed to check, e.g. the manifests of their regions are lost.
+    pub failed_tables: Vec<String>,
+    /// Number of files referenced by regions but not found in the storage.
+    pub missing_files: usize,
+    /// Number of files in the storage not referenced by any region.
+    pub orphan_files: usize,
+    /// Number of tables whose references to missing files are removed.
+    pub repaired_tables: usize,
+    /// Directories of tables neither in the catalog nor in the recycle bin.
+    pub orphan_table_dirs: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// Returns true if the catalog and the storage are consistent.
+    pub fn is_consistent(&self) -> bool {
+        self.failed_tables.is_empty()
+            && self.missing_files == 0
+            && self.orphan_files == 0
+            && self.orphan_table_dirs.is_empty()
+    }
+}
+
+/// Checks whether files referenced by tables in the catalog exist in the storage, and
+/// whether there are directories of tables unknown to the catalog.
+///
+/// Orphan files and directories are only reported, they may belong to operations in
+/// progress or tables to purge.
+#[derive(Clone)]
+pub struct ConsistencyChecker {
+    catalog_manager: CatalogManagerRef,
+    pub(crate) object_store: ObjectStore,
+    recycle_bin: RecycleBinRef,
+    config: ConsistencyCheckConfig,
+}
+
+impl ConsistencyChecker {
+    pub fn new(
+        catalog_manager: CatalogManagerRef,
+        object_store: ObjectStore,
+        recycle_bin: RecycleBinRef,
+        config: ConsistencyCheckConfig,
+    ) -> Self {
+        Self {
+            catalog_manager,
+            object_store,
+            recycle_bin,
+            config,
+        }
+    }
+
+    /// Checks all tables with regions in the catalog, failures of a table don't stop checking
+    /// others. Tables opened lazily are skipped until they are opened, so the check doesn't
+    /// open all regions at startup.
+    pub async fn check(&self) -> Result<ConsistencyReport> {
+        let mut report = ConsistencyReport::default();
+        let tables = all_tables(&self.catalog_manager).context(CatalogSnafu)?;
+
+        let mut table_ids = HashSet::with_capacity(tables.len());
+        let mut schemas = BTreeSet::new();
+        for table in tables {
+            // Virtual tables, e.g. tables of `information_schema`, have no files.
+            let Some(info) = table.try_table_info() else {
+                continue;
+            };
+            if info.table_type != TableType::Base || info.meta.region_numbers.is_empty() {
+                continue;
+            }
+            let table_name = format!("{}.{}.{}", info.catalog_name, info.schema_name, info.name);
+            table_ids.insert(info.ident.table_id);
+            schemas.insert(info.schema_name.clone());
+
+            if !table.is_opened().await {
+                report.skipped_tables += 1;
+                continue;
+            }
+            report.checked_tables += 1;
+            match table.check_files(self.config.repair).await {
+                Ok(stats) => {
+                    for stat in stats {
+                        report.missing_files += stat.missing_files.len();
+                        report.orphan_files += stat.orphan_files.len();
+                        if stat.repaired {
+                            report.repaired_tables += 1;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(e; "Failed to check files of table {}", table_name);
+                    report.failed_tables.push(table_name);
+                }
+            }
+        }
+
+        table_ids.extend(
+            self.recycle_bin
+                .dropped_tables()
+                .into_iter()
+                .map(|table| table.table_id),
+        );
+        for schema in schemas {
+            self.find_orphan_table_dirs(&schema, &table_ids, &mut report.orphan_table_dirs)
+                .await?;
+        }
+
+        Ok(report)
+    }
+
+    /// Runs the check in background, the datanode serves requests without waiting for it.
+    pub fn start(&self) {
+        let checker = self.clone();
+        common_runtime::spawn_bg(async move {
+            checker.check_and_log().await;
+        });
+    }
+
+    /// Runs the check and logs the result, the datanode still starts if the check fails.
+    pub async fn check_and_log(&self) {
+        match self.check().await {
+            Ok(report) if report.is_consistent() => {
+                info!(
+                    "Consistency check finished, checked tables: {}, skipped tables: {}",
+                    report.checked_tables, report.skipped_tables
+                );
+            }
+            Ok(report) => warn!("Catalog and storage are inconsistent: {:?}", report),
+            Err(e) => error!(e; "Failed to check consistency of catalog and storage"),
+        }
+    }
+
+    /// Finds directories of user tables under the `schema` dir whose ids are not in
+    /// `table_ids`.
+    async fn find_orphan_table_dirs(
+        &self,
+        schema: &str,
+        table_ids: &HashSet<TableId>,
+        orphan_dirs: &mut Vec<String>,
+    ) -> Result<()> {
+        let path = format!("{schema}/");
+        let dir = self.object_store.object(&path);
+        if !dir
+            .is_exist()
+            .await
+            .context(ListTableDirsSnafu { path: &path })?
+        {
+            return Ok(());
+        }
+
+        let mut names = dir
+            .list()
+            .await
+            .context(ListTableDirsSnafu { path: &path })?
+            .map_ok(|object| object.name().to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .context(ListTableDirsSnafu { path: &path })?;
+        names.sort_unstable();
+        for name in names {
+            let Ok(table_id) = name.trim_end_matches('/').parse::<TableId>() else {
+                continue;
+            };
+            if table_id >= MIN_USER_TABLE_ID && !table_ids.contains(&table_id) {
+                let table_dir = table_dir(schema, table_id);
+                warn!("Directory {} of unknown table found", table_dir);
+                orphan_dirs.push(table_dir);
+            }
+        }
+        Ok(())
+    }
+}
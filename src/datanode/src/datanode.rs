@@ -12,19 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use catalog::DEFAULT_OPEN_TABLES_CONCURRENCY;
 use common_base::readable_size::ReadableSize;
+use common_grpc::tls::GrpcTlsOption;
+use common_grpc::token::ClusterToken;
 use common_telemetry::info;
 use meta_client::MetaClientOpts;
+use mito::config::DEFAULT_TIERING_INTERVAL;
 use serde::{Deserialize, Serialize};
+use servers::grpc::DEFAULT_MAX_GRPC_MESSAGE_SIZE;
 use servers::Mode;
+use table::masking::MaskingPolicy;
 
 use crate::error::Result;
 use crate::instance::{Instance, InstanceRef};
 use crate::server::Services;
 
+pub const DEFAULT_QUERY_HISTORY_SIZE: usize = 1000;
+pub const DEFAULT_SCRUB_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+pub const DEFAULT_MYSQL_ADDR: &str = "127.0.0.1:4406";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ObjectStoreConfig {
@@ -37,6 +48,10 @@ pub enum ObjectStoreConfig {
 #[serde(default)]
 pub struct FileConfig {
     pub data_dir: String,
+    /// Extra data directories, like directories in other drives. Regions are placed to the
+    /// directory with the most available space among `data_dir` and these directories, while
+    /// metadata of tables is always stored in `data_dir`.
+    pub extra_data_dirs: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Default, Deserialize)]
@@ -60,15 +75,87 @@ pub struct OssConfig {
     pub endpoint: String,
 }
 
+/// Options of requests to the object store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectStoreRequestConfig {
+    /// Max times to retry a request failed with a temporary error, like a 503 of S3.
+    pub max_retries: usize,
+    /// Delay before the first retry, the delay doubles on each retry.
+    #[serde(with = "humantime_serde")]
+    pub retry_min_delay: Duration,
+    /// Max delay between two retries.
+    #[serde(with = "humantime_serde")]
+    pub retry_max_delay: Duration,
+    /// Timeout to open an object for reading, reading the opened object is not limited.
+    #[serde(with = "humantime_serde")]
+    pub read_timeout: Option<Duration>,
+    /// Timeout to write an object.
+    #[serde(with = "humantime_serde")]
+    pub write_timeout: Option<Duration>,
+    /// Timeout of operations on metadata of objects, e.g. stat, list and delete.
+    #[serde(with = "humantime_serde")]
+    pub metadata_timeout: Option<Duration>,
+}
+
+impl Default for ObjectStoreRequestConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            retry_min_delay: Duration::from_secs(1),
+            retry_max_delay: Duration::from_secs(60),
+            read_timeout: None,
+            write_timeout: None,
+            metadata_timeout: None,
+        }
+    }
+}
+
+/// Config of writing flushed SST files to local disk first and uploading them to the object
+/// store in background, so brief outages of the object store don't halt ingestion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WriteBehindConfig {
+    /// Local directory to buffer SST files.
+    pub dir: String,
+    /// Max number of SST files waiting for uploading, flushes wait once the backlog is full.
+    pub backlog: usize,
+}
+
+impl Default for WriteBehindConfig {
+    fn default() -> Self {
+        Self {
+            dir: "/tmp/greptimedb/write_behind/".to_string(),
+            backlog: 64,
+        }
+    }
+}
+
+/// Config of encrypting SST files and WAL entries at rest by AES-256-GCM.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Id of the key to encrypt new data.
+    pub current_key_id: String,
+    /// Hex-encoded 256-bit keys by their ids. Keys no longer used to encrypt new data should
+    /// be kept to decrypt old data.
+    pub keys: HashMap<String, String>,
+    /// Whether to read unencrypted SST files and WAL entries, only for migrating existing data
+    /// to encryption. Unencrypted data is rejected by default.
+    pub allow_plaintext: bool,
+}
+
 impl Default for ObjectStoreConfig {
     fn default() -> Self {
         ObjectStoreConfig::File(FileConfig {
             data_dir: "/tmp/greptimedb/data/".to_string(),
+            ..Default::default()
         })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WalConfig {
     // wal directory
     pub dir: String,
@@ -83,6 +170,11 @@ pub struct WalConfig {
     pub read_batch_size: usize,
     // whether to sync log file after every write
     pub sync_write: bool,
+    // max number of tables replaying the WAL concurrently on startup
+    pub replay_concurrency: usize,
+    // whether to open tables written since their last flush first on startup, and the
+    // others in background
+    pub replay_hot_first: bool,
 }
 
 impl Default for WalConfig {
@@ -94,6 +186,100 @@ impl Default for WalConfig {
             purge_interval: Duration::from_secs(600),
             read_batch_size: 128,
             sync_write: false,
+            replay_concurrency: DEFAULT_OPEN_TABLES_CONCURRENCY,
+            replay_hot_first: false,
+        }
+    }
+}
+
+/// Options of a standby datanode, which opens tables read-only from the shared object storage
+/// and takes over writes of them once promoted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StandbyConfig {
+    /// Interval to catch up with the data flushed by the datanodes writing the tables.
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+    /// Id of the datanode writing the tables to follow. The standby opens the tables
+    /// allocated to that datanode, while it heartbeats with its own `node_id`, so metasrv
+    /// routes reads with the hint `READ_REPLICA` to it. The standby follows the tables
+    /// allocated to its own `node_id` if not set.
+    pub primary_node_id: Option<u64>,
+}
+
+impl Default for StandbyConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(10),
+            primary_node_id: None,
+        }
+    }
+}
+
+/// Options to check whether the files referenced by tables in the catalog exist in the
+/// storage on startup, see [crate::consistency].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsistencyCheckConfig {
+    /// Removes references to missing files from the manifests of regions if true, rows in
+    /// those files are lost. Only reported if false.
+    pub repair: bool,
+}
+
+/// Options to export storage metrics of each table, see [crate::table_metrics].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableMetricsConfig {
+    /// Interval to update the metrics.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Max number of tables to export metrics of, which bounds the cardinality of the
+    /// metrics. The tables with the most bytes are exported if there are more tables.
+    pub max_tables: usize,
+}
+
+impl Default for TableMetricsConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            max_tables: 1000,
+        }
+    }
+}
+
+/// Options to replicate writes of tables to a remote cluster, see [crate::replication].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplicationConfig {
+    /// gRPC address of the frontend of the remote cluster.
+    pub remote_addr: String,
+    /// Tables to replicate, in format `catalog.schema.table`, `schema.table` of the default
+    /// catalog or `table` of the public schema. The tables must be created in the remote
+    /// cluster beforehand.
+    pub tables: Vec<String>,
+    /// Directory to buffer writes not sent yet and the resume tokens.
+    pub buffer_dir: String,
+    /// Writes are not read from the WAL while the buffer exceeds this size.
+    pub buffer_size: ReadableSize,
+    /// Interval to poll new writes from the WAL and send buffered writes.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Max number of writes of a table read from the WAL at a time.
+    pub read_batch_size: usize,
+    /// Connects to the remote cluster with TLS if set.
+    pub tls: Option<GrpcTlsOption>,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            remote_addr: "127.0.0.1:4001".to_string(),
+            tables: vec![],
+            buffer_dir: "/tmp/greptimedb/replication".to_string(),
+            buffer_size: ReadableSize::gb(1),
+            interval: Duration::from_secs(5),
+            read_batch_size: 128,
+            tls: None,
         }
     }
 }
@@ -105,13 +291,83 @@ pub struct DatanodeOptions {
     pub rpc_addr: String,
     pub rpc_hostname: Option<String>,
     pub rpc_runtime_size: usize,
-    pub mysql_addr: String,
+    /// Max size of a gRPC request message.
+    pub rpc_max_message_size: ReadableSize,
+    /// Max number of concurrent HTTP/2 streams of a gRPC connection, unlimited if not set.
+    pub rpc_max_concurrent_streams: Option<u32>,
+    /// Max number of in-flight gRPC requests of a connection, unlimited if not set.
+    pub rpc_max_in_flight_requests: Option<usize>,
+    /// Serves gRPC over TLS if set, frontends and other datanodes must connect with TLS
+    /// as well.
+    pub rpc_tls: Option<GrpcTlsOption>,
+    /// Shared secret of the cluster, attached to requests to metasrv and required in gRPC
+    /// requests to the datanode. Requests are not authenticated if not set.
+    pub cluster_token: Option<ClusterToken>,
+    /// Address of the MySQL server, [DEFAULT_MYSQL_ADDR] if not set. The MySQL server is only
+    /// served in distributed mode with `cluster_token` set, startup fails if the address is
+    /// set without the token.
+    pub mysql_addr: Option<String>,
     pub mysql_runtime_size: usize,
     pub meta_client_opts: Option<MetaClientOpts>,
     pub wal: WalConfig,
     pub storage: ObjectStoreConfig,
+    pub object_store_request: ObjectStoreRequestConfig,
+    /// Storage to move SST files of tables with the `cold_after` option to, tables keep all
+    /// files in `storage` if not set.
+    pub cold_storage: Option<ObjectStoreConfig>,
+    /// SST files are written to the object store directly if not set.
+    pub write_behind: Option<WriteBehindConfig>,
+    /// Data is not encrypted if not set.
+    pub encryption: Option<EncryptionConfig>,
+    /// Store token indexes of string columns in SST files to speed up `LIKE` and
+    /// `matches()` queries.
+    pub sst_token_index: bool,
+    /// Keeps the catalog in memory in standalone mode, tables could also be created by the
+    /// in-memory engine with `ENGINE = memory` then. Both are lost on restart.
     pub enable_memory_catalog: bool,
+    /// Max number of queries kept in `system.query_history`, 0 disables it.
+    pub query_history_size: usize,
     pub mode: Mode,
+    /// Labels of the datanode, like `zone` and `rack`, registered to metasrv at heartbeat
+    /// so that regions can be spread across failure domains.
+    pub labels: HashMap<String, String>,
+    /// Interval to verify the checksums of SST files, scrubbing is disabled if it's zero.
+    #[serde(with = "humantime_serde")]
+    pub scrub_interval: Option<Duration>,
+    /// Storage metrics of each table are not exported if not set.
+    pub table_metrics: Option<TableMetricsConfig>,
+    /// Inserts with timestamps later than now plus this bound are rejected, no bound if
+    /// not set.
+    #[serde(with = "humantime_serde")]
+    pub max_future_timestamp: Option<Duration>,
+    /// Max number of rows in an insert statement, no limit if not set.
+    pub max_insert_rows: Option<usize>,
+    /// Opens regions of tables on their first read or write instead of on startup, so the
+    /// datanode could host many rarely used tables.
+    pub lazy_open_tables: bool,
+    /// Closes regions of lazily opened tables not read or written for this duration, the
+    /// regions are kept opened if not set.
+    #[serde(with = "humantime_serde")]
+    pub idle_table_close_after: Option<Duration>,
+    /// Interval to move SST files of tables with the `cold_after` option to `cold_storage`.
+    #[serde(with = "humantime_serde")]
+    pub tiering_interval: Duration,
+    /// Dropped tables are kept for this duration before their data is purged, they could
+    /// be recovered by `UNDROP TABLE` meanwhile.
+    #[serde(with = "humantime_serde")]
+    pub recycle_bin_retention: Duration,
+    /// Policies to mask sensitive columns in query results.
+    pub masking_policies: Vec<MaskingPolicy>,
+    /// Writes are not replicated if not set.
+    pub replication: Option<ReplicationConfig>,
+    /// Runs the datanode as a standby serving reads from the shared object storage if set,
+    /// only supported in distributed mode.
+    pub standby: Option<StandbyConfig>,
+    /// Checks whether files referenced by tables exist in the storage on startup if set.
+    pub startup_consistency_check: Option<ConsistencyCheckConfig>,
+    /// Allows coprocessors declared with `backend="cpython"`, which can import any module
+    /// installed in the host. Requires the `pyo3_backend` feature.
+    pub enable_cpython_backend: bool,
 }
 
 impl Default for DatanodeOptions {
@@ -121,13 +377,38 @@ impl Default for DatanodeOptions {
             rpc_addr: "127.0.0.1:3001".to_string(),
             rpc_hostname: None,
             rpc_runtime_size: 8,
-            mysql_addr: "127.0.0.1:4406".to_string(),
+            rpc_max_message_size: ReadableSize(DEFAULT_MAX_GRPC_MESSAGE_SIZE as u64),
+            rpc_max_concurrent_streams: None,
+            rpc_max_in_flight_requests: None,
+            rpc_tls: None,
+            cluster_token: None,
+            mysql_addr: None,
             mysql_runtime_size: 2,
             meta_client_opts: None,
             wal: WalConfig::default(),
             storage: ObjectStoreConfig::default(),
+            object_store_request: ObjectStoreRequestConfig::default(),
+            cold_storage: None,
+            write_behind: None,
+            encryption: None,
+            sst_token_index: false,
             enable_memory_catalog: false,
+            query_history_size: DEFAULT_QUERY_HISTORY_SIZE,
             mode: Mode::Standalone,
+            labels: HashMap::new(),
+            scrub_interval: Some(DEFAULT_SCRUB_INTERVAL),
+            table_metrics: None,
+            max_future_timestamp: None,
+            max_insert_rows: None,
+            lazy_open_tables: false,
+            idle_table_close_after: None,
+            tiering_interval: DEFAULT_TIERING_INTERVAL,
+            recycle_bin_retention: Duration::from_secs(24 * 60 * 60),
+            masking_policies: vec![],
+            replication: None,
+            standby: None,
+            startup_consistency_check: None,
+            enable_cpython_backend: false,
         }
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::time::Duration;
 
 use common_error::prelude::*;
 use storage::error::Error as StorageError;
@@ -79,12 +80,38 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to undrop table {}, source: {}", table_name, source))]
+    UndropTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Dropped table not found in recycle bin: {}", table_name))]
+    DroppedTableNotFound {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound {
         table_name: String,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Table {} with id {} has been dropped", table_name, table_id))]
+    TableDropped {
+        table_name: String,
+        table_id: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Table already exists: {}", table_name))]
+    TableExists {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Column {} not found in table {}", column_name, table_name))]
     ColumnNotFound {
         column_name: String,
@@ -95,11 +122,25 @@ pub enum Error {
     MissingTimestampColumn { backtrace: Backtrace },
 
     #[snafu(display(
-        "Columns and values number mismatch, columns: {}, values: {}",
-        columns,
-        values
+        "Insert statement has {} rows, exceeds the max rows {}",
+        rows,
+        max_rows
     ))]
-    ColumnValuesNumberMismatch { columns: usize, values: usize },
+    TooManyInsertRows { rows: usize, max_rows: usize },
+
+    #[snafu(display(
+        "Timestamp {} of column {} in table {} is more than {:?} later than now",
+        timestamp,
+        column,
+        table_name,
+        max_future
+    ))]
+    FutureTimestamp {
+        table_name: String,
+        column: String,
+        timestamp: String,
+        max_future: Duration,
+    },
 
     #[snafu(display("Failed to parse sql value, source: {}", source))]
     ParseSqlValue {
@@ -107,6 +148,20 @@ pub enum Error {
         source: sql::error::Error,
     },
 
+    #[snafu(display("Failed to scan table: {}, source: {}", table_name, source))]
+    ScanTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to read table: {}, source: {}", table_name, source))]
+    ReadTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to insert value to table: {}, source: {}", table_name, source))]
     Insert {
         table_name: String,
@@ -114,6 +169,20 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display("Failed to fence writes to table: {}, source: {}", table_name, source))]
+    FenceTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to take snapshot of table: {}, source: {}", table_name, source))]
+    SnapshotTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display("Failed to start server, source: {}", source))]
     StartServer {
         #[snafu(backtrace)]
@@ -144,9 +213,21 @@ pub enum Error {
         source: log_store::error::Error,
     },
 
+    #[snafu(display("Failed to stop log store, source: {}", source))]
+    StopLogStore {
+        #[snafu(backtrace)]
+        source: log_store::error::Error,
+    },
+
     #[snafu(display("Failed to storage engine, source: {}", source))]
     OpenStorageEngine { source: StorageError },
 
+    #[snafu(display("Failed to start SST uploader, source: {}", source))]
+    StartSstUploader { source: StorageError },
+
+    #[snafu(display("Failed to init encryption, source: {}", source))]
+    InitEncryption { source: StorageError },
+
     #[snafu(display("Failed to init backend, config: {:#?}, source: {}", config, source))]
     InitBackend {
         config: Box<ObjectStoreConfig>,
@@ -208,9 +289,26 @@ pub enum Error {
         source: catalog::error::Error,
     },
 
+    #[snafu(display("Failed to alter database {}, source: {}", name, source))]
+    AlterDatabase {
+        name: String,
+        #[snafu(backtrace)]
+        source: catalog::error::Error,
+    },
+
     #[snafu(display("Schema already exists, name: {}", name))]
     SchemaExists { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Failed to create catalog {}, source: {}", name, source))]
+    CreateCatalog {
+        name: String,
+        #[snafu(backtrace)]
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Catalog already exists, name: {}", name))]
+    CatalogExists { name: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to convert alter expr to request: {}", source))]
     AlterExprToRequest {
         #[snafu(backtrace)]
@@ -270,11 +368,27 @@ pub enum Error {
         source: common_grpc_expr::error::Error,
     },
 
+    #[snafu(display("Failed to convert delete request, source: {}", source))]
+    DeleteData {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
+    #[snafu(display("Failed to delete from table: {}, source: {}", table_name, source))]
+    DeleteTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display(
         "Table id provider not found, cannot execute SQL directly on datanode in distributed mode"
     ))]
     TableIdProviderNotFound { backtrace: Backtrace },
 
+    #[snafu(display("Not supported: {}", feat))]
+    NotSupported { feat: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to bump table id, source: {}", source))]
     BumpTableId {
         #[snafu(backtrace)]
@@ -293,36 +407,112 @@ pub enum Error {
     #[snafu(display("Missing node id option in distributed mode"))]
     MissingMetasrvOpts { backtrace: Backtrace },
 
+    #[snafu(display(
+        "Missing cluster token option to serve MySQL on {} in distributed mode",
+        addr
+    ))]
+    MissingClusterToken { addr: String, backtrace: Backtrace },
+
     #[snafu(display("Missing required field: {}", name))]
     MissingRequiredField { name: String, backtrace: Backtrace },
 
     #[snafu(display("Cannot find requested database: {}-{}", catalog, schema))]
     DatabaseNotFound { catalog: String, schema: String },
 
+    #[snafu(display("Failed to describe schema for given statement, source: {}", source))]
+    DescribeStatement {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
+    #[snafu(display("Failed to read changes of table {}, source: {}", table_name, source))]
+    ReadChanges {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to access replication buffer {}, source: {}", path, source))]
+    ReplicationBuffer {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Corrupted replication buffer {}, reason: {}", path, reason))]
+    CorruptedReplicationBuffer {
+        path: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to serialize resume tokens to {}, source: {}", path, source))]
+    SerializeResumeTokens {
+        path: String,
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
-        "Failed to build default value, column: {}, source: {}",
-        column,
+        "Failed to convert changes of table {} to gRPC columns, source: {}",
+        table_name,
         source
     ))]
-    ColumnDefaultValue {
-        column: String,
+    ConvertChanges {
+        table_name: String,
         #[snafu(backtrace)]
-        source: datatypes::error::Error,
+        source: common_grpc_expr::error::Error,
     },
 
-    #[snafu(display(
-        "No valid default value can be built automatically, column: {}",
-        column,
-    ))]
-    ColumnNoneDefaultValue {
-        column: String,
+    #[snafu(display("Failed to replicate writes to {}, source: {}", addr, source))]
+    Replicate {
+        addr: String,
+        #[snafu(backtrace)]
+        source: client::Error,
+    },
+
+    #[snafu(display("Failed to recover jobs, source: {}", source))]
+    RecoverJobs {
+        #[snafu(backtrace)]
+        source: common_procedure::Error,
+    },
+
+    #[snafu(display("Failed to access recycle bin {}, source: {}", path, source))]
+    RecycleBin {
+        path: String,
+        source: object_store::Error,
         backtrace: Backtrace,
     },
 
-    #[snafu(display("Failed to describe schema for given statement, source: {}", source))]
-    DescribeStatement {
+    #[snafu(display("Corrupted record {} in recycle bin, source: {}", path, source))]
+    CorruptedRecycleBin {
+        path: String,
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to list table dirs in {}, source: {}", path, source))]
+    ListTableDirs {
+        path: String,
+        source: object_store::Error,
+    },
+
+    #[snafu(display("Failed to check files of table {}, source: {}", table_name, source))]
+    CheckTableFiles {
+        table_name: String,
         #[snafu(backtrace)]
-        source: query::error::Error,
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to set tables writable, source: {}", source))]
+    SetWritable {
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Permission denied: {}", reason))]
+    PermissionDenied {
+        reason: String,
+        backtrace: Backtrace,
     },
 }
 
@@ -340,11 +530,21 @@ impl ErrorExt for Error {
             Error::CreateTable { source, .. }
             | Error::GetTable { source, .. }
             | Error::AlterTable { source, .. } => source.status_code(),
-            Error::DropTable { source, .. } => source.status_code(),
-
-            Error::Insert { source, .. } => source.status_code(),
-
-            Error::TableNotFound { .. } => StatusCode::TableNotFound,
+            Error::DropTable { source, .. } | Error::UndropTable { source, .. } => {
+                source.status_code()
+            }
+            Error::AlterDatabase { source, .. } => source.status_code(),
+
+            Error::Insert { source, .. }
+            | Error::FenceTable { source, .. }
+            | Error::SnapshotTable { source, .. } => source.status_code(),
+            Error::ScanTable { source, .. } => source.status_code(),
+            Error::ReadTable { source, .. } => source.status_code(),
+
+            Error::TableNotFound { .. }
+            | Error::TableDropped { .. }
+            | Error::DroppedTableNotFound { .. } => StatusCode::TableNotFound,
+            Error::TableExists { .. } => StatusCode::TableAlreadyExists,
             Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
 
             Error::ParseSqlValue { source, .. } | Error::ParseSql { source, .. } => {
@@ -353,13 +553,16 @@ impl ErrorExt for Error {
 
             Error::AlterExprToRequest { source, .. }
             | Error::CreateExprToRequest { source }
-            | Error::InsertData { source } => source.status_code(),
+            | Error::InsertData { source }
+            | Error::DeleteData { source } => source.status_code(),
+            Error::DeleteTable { source, .. } => source.status_code(),
 
             Error::CreateSchema { source, .. }
             | Error::ConvertSchema { source, .. }
             | Error::VectorComputation { source } => source.status_code(),
 
-            Error::ColumnValuesNumberMismatch { .. }
+            Error::FutureTimestamp { .. }
+            | Error::TooManyInsertRows { .. }
             | Error::InvalidSql { .. }
             | Error::KeyColumnNotFound { .. }
             | Error::InvalidPrimaryKey { .. }
@@ -368,6 +571,7 @@ impl ErrorExt for Error {
             | Error::SchemaNotFound { .. }
             | Error::ConstraintNotSupported { .. }
             | Error::SchemaExists { .. }
+            | Error::CatalogExists { .. }
             | Error::ParseTimestamp { .. }
             | Error::DatabaseNotFound { .. } => StatusCode::InvalidArguments,
 
@@ -380,22 +584,41 @@ impl ErrorExt for Error {
             | Error::InsertSystemCatalog { .. }
             | Error::RenameTable { .. }
             | Error::RegisterSchema { .. }
+            | Error::CreateCatalog { .. }
             | Error::Catalog { .. }
             | Error::MissingRequiredField { .. }
             | Error::IncorrectInternalState { .. } => StatusCode::Internal,
 
             Error::InitBackend { .. } => StatusCode::StorageUnavailable,
-            Error::OpenLogStore { source } => source.status_code(),
+            Error::OpenLogStore { source } | Error::StopLogStore { source } => source.status_code(),
             Error::StartScriptManager { source } => source.status_code(),
-            Error::OpenStorageEngine { source } => source.status_code(),
+            Error::OpenStorageEngine { source }
+            | Error::StartSstUploader { source }
+            | Error::InitEncryption { source } => source.status_code(),
             Error::RuntimeResource { .. } => StatusCode::RuntimeResourcesExhausted,
             Error::MetaClientInit { source, .. } => source.status_code(),
-            Error::TableIdProviderNotFound { .. } => StatusCode::Unsupported,
+            Error::TableIdProviderNotFound { .. } | Error::NotSupported { .. } => {
+                StatusCode::Unsupported
+            }
             Error::BumpTableId { source, .. } => source.status_code(),
             Error::MissingNodeId { .. } => StatusCode::InvalidArguments,
             Error::MissingMetasrvOpts { .. } => StatusCode::InvalidArguments,
-            Error::ColumnDefaultValue { source, .. } => source.status_code(),
-            Error::ColumnNoneDefaultValue { .. } => StatusCode::InvalidArguments,
+            Error::MissingClusterToken { .. } => StatusCode::InvalidArguments,
+            Error::ReadChanges { source, .. } => source.status_code(),
+            Error::ReplicationBuffer { .. } | Error::CorruptedReplicationBuffer { .. } => {
+                StatusCode::StorageUnavailable
+            }
+            Error::SerializeResumeTokens { .. } => StatusCode::Internal,
+            Error::RecycleBin { .. } | Error::ListTableDirs { .. } => {
+                StatusCode::StorageUnavailable
+            }
+            Error::CorruptedRecycleBin { .. } => StatusCode::Internal,
+            Error::ConvertChanges { source, .. } => source.status_code(),
+            Error::Replicate { source, .. } => source.status_code(),
+            Error::RecoverJobs { source } => source.status_code(),
+            Error::SetWritable { source } => source.status_code(),
+            Error::CheckTableFiles { source, .. } => source.status_code(),
+            Error::PermissionDenied { .. } => StatusCode::AccessDenied,
         }
     }
 
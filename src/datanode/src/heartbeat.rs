@@ -12,17 +12,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, NodeStat, Peer};
-use catalog::{region_number, CatalogManagerRef};
+use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, NodeStat, Peer, TimeInterval};
+use catalog::{region_number, region_stats, CatalogManagerRef};
 use common_telemetry::{error, info, warn};
+use common_time::util::current_time_millis;
 use meta_client::client::{HeartbeatSender, MetaClient};
 use snafu::ResultExt;
+use table::metadata::TableId;
 
 use crate::error::{MetaClientInitSnafu, Result};
+use crate::standby::StandbyTask;
+
+/// Ids of the dropped tables whose regions are still held by this datanode, as notified
+/// by metasrv. Writes to these tables are rejected.
+#[derive(Debug, Default)]
+pub struct DroppedTables {
+    table_ids: RwLock<HashSet<TableId>>,
+}
+
+pub type DroppedTablesRef = Arc<DroppedTables>;
+
+impl DroppedTables {
+    pub fn contains(&self, table_id: TableId) -> bool {
+        self.table_ids.read().unwrap().contains(&table_id)
+    }
+
+    /// Replaces the dropped tables by `table_ids`.
+    pub(crate) fn reset(&self, table_ids: impl IntoIterator<Item = TableId>) {
+        let table_ids = table_ids.into_iter().collect::<HashSet<_>>();
+        let mut current = self.table_ids.write().unwrap();
+        if *current != table_ids {
+            info!("Dropped tables notified by metasrv: {:?}", table_ids);
+            *current = table_ids;
+        }
+    }
+}
 
 pub struct HeartbeatTask {
     node_id: u64,
@@ -32,11 +61,14 @@ pub struct HeartbeatTask {
     meta_client: Arc<MetaClient>,
     catalog_manager: CatalogManagerRef,
     interval: u64,
+    labels: HashMap<String, String>,
+    dropped_tables: DroppedTablesRef,
+    standby_task: Option<Arc<StandbyTask>>,
 }
 
 impl Drop for HeartbeatTask {
     fn drop(&mut self) {
-        self.running.store(false, Ordering::Release);
+        self.stop();
     }
 }
 
@@ -57,23 +89,54 @@ impl HeartbeatTask {
             meta_client,
             catalog_manager,
             interval: 5_000, // default interval is set to 5 secs
+            labels: HashMap::new(),
+            dropped_tables: Arc::new(DroppedTables::default()),
+            standby_task: None,
         }
     }
 
+    /// Returns the dropped tables notified by metasrv in heartbeat responses.
+    pub fn dropped_tables(&self) -> &DroppedTablesRef {
+        &self.dropped_tables
+    }
+
+    /// Sets the labels of this datanode, like `zone` and `rack`, reported to metasrv
+    /// in every heartbeat.
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Sets the standby task of this datanode, which is promoted once metasrv tells to.
+    pub(crate) fn with_standby_task(mut self, standby_task: Option<Arc<StandbyTask>>) -> Self {
+        self.standby_task = standby_task;
+        self
+    }
+
+    /// Sets the interval in millis of heartbeats sent to metasrv.
+    pub fn with_interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+
     pub async fn create_streams(
         meta_client: &MetaClient,
         running: Arc<AtomicBool>,
+        dropped_tables: DroppedTablesRef,
+        standby_task: Option<Arc<StandbyTask>>,
     ) -> Result<HeartbeatSender> {
+        // The stream to metasrv is reconnected by the meta client when broken, it only
+        // ends once the sender is dropped.
         let (tx, mut rx) = meta_client.heartbeat().await.context(MetaClientInitSnafu)?;
         common_runtime::spawn_bg(async move {
-            while let Some(res) = match rx.message().await {
-                Ok(m) => m,
-                Err(e) => {
-                    error!(e; "Error while reading heartbeat response");
-                    None
+            loop {
+                match rx.message().await {
+                    Ok(Some(res)) => {
+                        Self::handle_response(&dropped_tables, &standby_task, res).await
+                    }
+                    Ok(None) => break,
+                    Err(e) => error!(e; "Error while reading heartbeat response"),
                 }
-            } {
-                Self::handle_response(res).await;
                 if !running.load(Ordering::Acquire) {
                     info!("Heartbeat task shutdown");
                 }
@@ -83,8 +146,34 @@ impl HeartbeatTask {
         Ok(tx)
     }
 
-    async fn handle_response(resp: HeartbeatResponse) {
+    async fn handle_response(
+        dropped_tables: &DroppedTables,
+        standby_task: &Option<Arc<StandbyTask>>,
+        resp: HeartbeatResponse,
+    ) {
         info!("heartbeat response: {:?}", resp);
+        // Only the leader notifies dropped tables, responses from followers carry no
+        // header.
+        if resp.header.is_some() {
+            dropped_tables.reset(resp.dropped_table_ids);
+        }
+        if resp.promote {
+            let Some(standby_task) = standby_task.clone() else {
+                warn!("Asked to promote but this datanode is not a standby");
+                return;
+            };
+            // Promoting refreshes all tables, which should not block handling responses.
+            common_runtime::spawn_bg(async move {
+                if let Err(e) = standby_task.promote().await {
+                    error!(e; "Failed to promote standby datanode");
+                }
+            });
+        }
+    }
+
+    /// Stops the heartbeat task, it exits before sending the next heartbeat.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
     }
 
     /// Start heartbeat task, spawn background task.
@@ -100,11 +189,19 @@ impl HeartbeatTask {
         let interval = self.interval;
         let node_id = self.node_id;
         let addr = resolve_addr(&self.server_addr, &self.server_hostname);
-        let meta_client = self.meta_client.clone();
+        let labels = self.labels.clone();
 
         let catalog_manager_clone = self.catalog_manager.clone();
-        let mut tx = Self::create_streams(&meta_client, running.clone()).await?;
+        let standby_task = self.standby_task.clone();
+        let tx = Self::create_streams(
+            &self.meta_client,
+            running.clone(),
+            self.dropped_tables.clone(),
+            standby_task.clone(),
+        )
+        .await?;
         common_runtime::spawn_bg(async move {
+            let mut last_sent_at = 0;
             while running.load(Ordering::Acquire) {
                 let region_num = match region_number(&catalog_manager_clone) {
                     Ok(region_num) => region_num as i64,
@@ -114,6 +211,17 @@ impl HeartbeatTask {
                     }
                 };
 
+                let region_stats = match region_stats(&catalog_manager_clone) {
+                    Ok(region_stats) => region_stats,
+                    Err(e) => {
+                        error!("failed to get region stats, err: {e:?}");
+                        vec![]
+                    }
+                };
+
+                // Metasrv estimates the clock skew of this datanode by the time the
+                // heartbeat is sent at.
+                let sent_at = current_time_millis() as u64;
                 let req = HeartbeatRequest {
                     peer: Some(Peer {
                         id: node_id,
@@ -123,20 +231,22 @@ impl HeartbeatTask {
                         region_num,
                         ..Default::default()
                     }),
+                    region_stats,
+                    labels: labels.clone(),
+                    is_standby: standby_task
+                        .as_ref()
+                        .map(|task| task.is_standby())
+                        .unwrap_or(false),
+                    report_interval: Some(TimeInterval {
+                        start_timestamp_millis: last_sent_at,
+                        end_timestamp_millis: sent_at,
+                    }),
                     ..Default::default()
                 };
+                last_sent_at = sent_at;
 
                 if let Err(e) = tx.send(req).await {
-                    error!("Failed to send heartbeat to metasrv, error: {:?}", e);
-                    match Self::create_streams(&meta_client, running.clone()).await {
-                        Ok(new_tx) => {
-                            info!("Reconnected to metasrv");
-                            tx = new_tx;
-                        }
-                        Err(e) => {
-                            error!(e;"Failed to reconnect to metasrv!");
-                        }
-                    }
+                    error!(e; "Failed to send heartbeat to metasrv");
                 }
                 tokio::time::sleep(Duration::from_millis(interval)).await;
             }
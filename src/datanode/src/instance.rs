@@ -13,43 +13,65 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use std::time::Duration;
 use std::{fs, path};
 
 use backon::ExponentialBackoff;
+use catalog::query_history::{self, QueryHistory, QueryHistoryRef};
 use catalog::remote::MetaKvBackend;
-use catalog::{CatalogManager, CatalogManagerRef, RegisterTableRequest};
+use catalog::resource_usage::{self, ResourceAccountant, ResourceAccountantRef};
+use catalog::{
+    ddl_history, ingest_stats, CatalogManager, CatalogManagerRef, HotRegionCheckerRef,
+    RegisterTableRequest,
+};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
-use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
-use common_telemetry::logging::info;
+use common_grpc::channel_manager::ChannelManager;
+use common_grpc::token::ClusterToken;
+use common_procedure::job::{JobManager, JobManagerRef};
+use common_telemetry::logging::{info, warn};
+use common_time::clock;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use log_store::LogConfig;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOpts;
 use mito::config::EngineConfig as TableEngineConfig;
-use mito::engine::MitoEngine;
+use mito::engine::{self as mito_engine, MitoEngine};
 use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
 use object_store::services::fs::Builder as FsBuilder;
 use object_store::services::oss::Builder as OSSBuilder;
 use object_store::services::s3::Builder as S3Builder;
+use object_store::timeout::TimeoutLayer;
 use object_store::{util, ObjectStore};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
 use servers::Mode;
 use snafu::prelude::*;
 use storage::config::EngineConfig as StorageEngineConfig;
-use storage::EngineImpl;
+use storage::data_dir::DataDir;
+use storage::encryption::{Encryptor, EncryptorRef, StaticKeyProvider};
+use storage::write_behind::{SstUploader, SstUploaderRef};
+use storage::{EngineImpl, WalRetentionRef};
+use store_api::logstore::LogStore;
 use table::table::numbers::NumbersTable;
 use table::table::TableIdProviderRef;
+use table::test_util::MemoryTableEngine;
 use table::Table;
 
-use crate::datanode::{DatanodeOptions, ObjectStoreConfig, WalConfig};
+use crate::consistency::ConsistencyChecker;
+use crate::datanode::{
+    DatanodeOptions, EncryptionConfig, FileConfig, ObjectStoreConfig, ObjectStoreRequestConfig,
+    WalConfig, WriteBehindConfig,
+};
 use crate::error::{
     self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
-    NewCatalogSnafu, OpenLogStoreSnafu, Result,
+    NewCatalogSnafu, OpenLogStoreSnafu, RecoverJobsSnafu, Result, StopLogStoreSnafu,
 };
-use crate::heartbeat::HeartbeatTask;
+use crate::heartbeat::{DroppedTablesRef, HeartbeatTask};
+use crate::recycle_bin::{RecycleBin, RecycleBinRef};
+use crate::replication::{ReplicationProgress, ReplicationTask};
 use crate::script::ScriptExecutor;
+use crate::scrub::ScrubTask;
 use crate::sql::SqlHandler;
+use crate::standby::StandbyTask;
+use crate::table_metrics::TableMetricsTask;
 
 mod grpc;
 mod script;
@@ -60,19 +82,44 @@ pub(crate) type DefaultEngine = MitoEngine<EngineImpl<RaftEngineLogStore>>;
 // An abstraction to read/write services.
 pub struct Instance {
     pub(crate) query_engine: QueryEngineRef,
-    pub(crate) sql_handler: SqlHandler,
+    pub(crate) sql_handler: Arc<SqlHandler>,
     pub(crate) catalog_manager: CatalogManagerRef,
     pub(crate) script_executor: ScriptExecutor,
     pub(crate) table_id_provider: Option<TableIdProviderRef>,
     pub(crate) heartbeat_task: Option<HeartbeatTask>,
+    /// Dropped tables notified by metasrv, always empty in standalone mode.
+    pub(crate) dropped_tables: DroppedTablesRef,
+    pub(crate) scrub_task: Option<ScrubTask>,
+    pub(crate) table_metrics_task: Option<TableMetricsTask>,
+    pub(crate) replication_task: Option<ReplicationTask>,
+    pub(crate) standby_task: Option<Arc<StandbyTask>>,
+    pub(crate) query_history: QueryHistoryRef,
+    pub(crate) resource_accountant: ResourceAccountantRef,
+    pub(crate) recycle_bin: RecycleBinRef,
+    /// Jobs whose records are persisted in the object store of the datanode.
+    pub(crate) job_manager: JobManagerRef,
+    pub(crate) consistency_checker: Option<ConsistencyChecker>,
+    pub(crate) log_store: Arc<RaftEngineLogStore>,
 }
 
 pub type InstanceRef = Arc<Instance>;
 
 impl Instance {
     pub async fn new(opts: &DatanodeOptions) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage).await?;
+        let object_store = new_object_store(&opts.storage, &opts.object_store_request).await?;
         let logstore = Arc::new(create_log_store(&opts.wal).await?);
+        let data_dirs =
+            new_data_dirs(&opts.storage, &opts.object_store_request, &object_store).await?;
+        let encryptor = opts.encryption.as_ref().map(new_encryptor).transpose()?;
+        let job_manager = Arc::new(JobManager::new(object_store.clone()));
+        let sst_uploader = match &opts.write_behind {
+            Some(config) => Some(new_sst_uploader(config, object_store.clone()).await?),
+            None => None,
+        };
+        let cold_store = match &opts.cold_storage {
+            Some(config) => Some(new_object_store(config, &opts.object_store_request).await?),
+            None => None,
+        };
 
         let meta_client = match opts.mode {
             Mode::Standalone => None,
@@ -82,21 +129,68 @@ impl Instance {
                     opts.meta_client_opts
                         .as_ref()
                         .context(MissingMetasrvOptsSnafu)?,
+                    opts.cluster_token.clone(),
                 )
                 .await?;
                 Some(Arc::new(meta_client))
             }
         };
 
+        ensure!(
+            opts.standby.is_none() || opts.mode == Mode::Distributed,
+            error::NotSupportedSnafu {
+                feat: "Standby datanode in standalone mode",
+            }
+        );
+        // Loaded before opening tables, so WAL entries not replicated yet are retained.
+        let replication_progress = opts
+            .replication
+            .as_ref()
+            .map(ReplicationProgress::open)
+            .transpose()?;
+        // The table engine, SQL handler and recycle bin read the time from the same clock.
+        let clock = clock::system_clock();
         let table_engine = Arc::new(DefaultEngine::new(
-            TableEngineConfig::default(),
+            TableEngineConfig {
+                standby: opts.standby.is_some(),
+                lazy_open: opts.lazy_open_tables,
+                idle_close_after: opts.idle_table_close_after,
+                tiering_interval: opts.tiering_interval,
+                clock: clock.clone(),
+            },
             EngineImpl::new(
-                StorageEngineConfig::default(),
+                StorageEngineConfig {
+                    sst_uploader,
+                    data_dirs,
+                    encryptor,
+                    sst_token_index: opts.sst_token_index,
+                    cold_store,
+                    wal_retention: replication_progress
+                        .clone()
+                        .map(|progress| progress as WalRetentionRef),
+                    ..Default::default()
+                },
                 logstore.clone(),
                 object_store.clone(),
             ),
-            object_store,
+            object_store.clone(),
         ));
+        let recycle_bin = Arc::new(
+            RecycleBin::new(
+                object_store.clone(),
+                table_engine.clone(),
+                opts.recycle_bin_retention,
+            )
+            .with_clock(clock.clone()),
+        );
+
+        // A region is hot if its WAL has entries not flushed yet, i.e. written recently.
+        let hot_region_checker = opts.wal.replay_hot_first.then(|| {
+            let logstore = logstore.clone();
+            Arc::new(move |table_id, region_number| {
+                logstore.has_entries(mito_engine::region_id(table_id, region_number))
+            }) as HotRegionCheckerRef
+        });
 
         // create remote catalog manager
         let (catalog_manager, factory, table_id_provider) = match opts.mode {
@@ -127,8 +221,14 @@ impl Instance {
                     let catalog = Arc::new(
                         catalog::local::LocalCatalogManager::try_new(table_engine.clone())
                             .await
-                            .context(CatalogSnafu)?,
+                            .context(CatalogSnafu)?
+                            .with_open_tables_concurrency(opts.wal.replay_concurrency)
+                            .with_hot_region_checker(hot_region_checker),
                     );
+                    // Records DDL operations of the standalone instance.
+                    ddl_history::register_ddl_history_table(catalog.as_ref())
+                        .await
+                        .context(CatalogSnafu)?;
                     let factory = QueryEngineFactory::new(catalog.clone());
 
                     (
@@ -140,43 +240,136 @@ impl Instance {
             }
 
             Mode::Distributed => {
-                let catalog = Arc::new(catalog::remote::RemoteCatalogManager::new(
-                    table_engine.clone(),
-                    opts.node_id.context(MissingNodeIdSnafu)?,
-                    Arc::new(MetaKvBackend {
-                        client: meta_client.as_ref().unwrap().clone(),
-                    }),
-                ));
+                // A standby opens the tables of the datanode it follows.
+                let node_id = match opts.standby.as_ref().and_then(|s| s.primary_node_id) {
+                    Some(primary_node_id) => primary_node_id,
+                    None => opts.node_id.context(MissingNodeIdSnafu)?,
+                };
+                let catalog = Arc::new(
+                    catalog::remote::RemoteCatalogManager::new(
+                        table_engine.clone(),
+                        node_id,
+                        Arc::new(MetaKvBackend {
+                            client: meta_client.as_ref().unwrap().clone(),
+                        }),
+                    )
+                    .with_open_tables_concurrency(opts.wal.replay_concurrency)
+                    .with_hot_region_checker(hot_region_checker),
+                );
                 let factory = QueryEngineFactory::new(catalog.clone());
                 (catalog as CatalogManagerRef, factory, None)
             }
         };
 
         let query_engine = factory.query_engine();
-        let script_executor =
-            ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
-
+        let resource_accountant = Arc::new(ResourceAccountant::default());
+        query_engine.register_resource_accountant(resource_accountant.clone());
+        query_engine.register_masking_policies(opts.masking_policies.clone());
+        let mut sql_handler = SqlHandler::new(
+            table_engine.clone(),
+            catalog_manager.clone(),
+            query_engine.clone(),
+        )
+        .with_max_future_timestamp(opts.max_future_timestamp)
+        .with_max_insert_rows(opts.max_insert_rows)
+        .with_clock(clock);
+        // Dropped tables could only be recovered in standalone mode, where the datanode owns
+        // the catalog.
+        if opts.mode == Mode::Standalone {
+            sql_handler = sql_handler.with_recycle_bin(recycle_bin.clone());
+        }
+        // Tables of the memory engine are lost on restart, so they are only created with the
+        // memory catalog, which isn't persisted either.
+        if opts.mode == Mode::Standalone && opts.enable_memory_catalog {
+            sql_handler = sql_handler.with_table_engine(Arc::new(MemoryTableEngine::new()));
+        }
+        let sql_handler = Arc::new(sql_handler);
+        let script_executor = ScriptExecutor::new(
+            catalog_manager.clone(),
+            query_engine.clone(),
+            sql_handler.clone(),
+            opts.enable_cpython_backend,
+        )
+        .await?;
+
+        let standby_task = opts.standby.as_ref().map(|config| {
+            Arc::new(StandbyTask::new(
+                catalog_manager.clone(),
+                table_engine.clone(),
+                config.refresh_interval,
+            ))
+        });
         let heartbeat_task = match opts.mode {
             Mode::Standalone => None,
-            Mode::Distributed => Some(HeartbeatTask::new(
-                opts.node_id.context(MissingNodeIdSnafu)?,
-                opts.rpc_addr.clone(),
-                opts.rpc_hostname.clone(),
-                meta_client.as_ref().unwrap().clone(),
-                catalog_manager.clone(),
-            )),
+            Mode::Distributed => Some(
+                HeartbeatTask::new(
+                    opts.node_id.context(MissingNodeIdSnafu)?,
+                    opts.rpc_addr.clone(),
+                    opts.rpc_hostname.clone(),
+                    meta_client.as_ref().unwrap().clone(),
+                    catalog_manager.clone(),
+                )
+                .with_labels(opts.labels.clone())
+                .with_standby_task(standby_task.clone())
+                .with_interval(
+                    opts.meta_client_opts
+                        .as_ref()
+                        .context(MissingMetasrvOptsSnafu)?
+                        .heartbeat_interval_millis,
+                ),
+            ),
         };
+        let dropped_tables = heartbeat_task
+            .as_ref()
+            .map(|task| task.dropped_tables().clone())
+            .unwrap_or_default();
+        let scrub_task = opts
+            .scrub_interval
+            .filter(|interval| !interval.is_zero())
+            .map(|interval| ScrubTask::new(catalog_manager.clone(), interval));
+        let table_metrics_task = opts
+            .table_metrics
+            .clone()
+            .map(|config| TableMetricsTask::new(catalog_manager.clone(), config));
+        let replication_task = opts
+            .replication
+            .clone()
+            .zip(replication_progress)
+            .map(|(config, progress)| {
+                ReplicationTask::new(
+                    catalog_manager.clone(),
+                    config,
+                    opts.cluster_token.clone(),
+                    progress,
+                )
+            })
+            .transpose()?;
+        let consistency_checker = opts.startup_consistency_check.clone().map(|config| {
+            ConsistencyChecker::new(
+                catalog_manager.clone(),
+                object_store,
+                recycle_bin.clone(),
+                config,
+            )
+        });
         Ok(Self {
             query_engine: query_engine.clone(),
-            sql_handler: SqlHandler::new(
-                table_engine,
-                catalog_manager.clone(),
-                query_engine.clone(),
-            ),
+            sql_handler,
             catalog_manager,
             script_executor,
             heartbeat_task,
+            dropped_tables,
+            scrub_task,
+            table_metrics_task,
+            replication_task,
+            standby_task,
             table_id_provider,
+            query_history: Arc::new(QueryHistory::new(opts.query_history_size)),
+            resource_accountant,
+            recycle_bin,
+            job_manager,
+            consistency_checker,
+            log_store: logstore,
         })
     }
 
@@ -185,12 +378,81 @@ impl Instance {
             .start()
             .await
             .context(NewCatalogSnafu)?;
+        ingest_stats::register_ingest_stats_table(
+            self.catalog_manager.as_ref(),
+            self.sql_handler.ingest_stats().clone(),
+        )
+        .context(CatalogSnafu)?;
+        query_history::register_query_history_table(
+            self.catalog_manager.as_ref(),
+            self.query_history.clone(),
+        )
+        .context(CatalogSnafu)?;
+        resource_usage::register_resource_usage_table(
+            self.catalog_manager.as_ref(),
+            self.resource_accountant.clone(),
+        )
+        .context(CatalogSnafu)?;
+        self.job_manager.recover().await.context(RecoverJobsSnafu)?;
+        self.job_manager.start_watchdog();
+        self.recycle_bin.recover().await?;
+        // Checks after dropped tables are recovered, so their data is not reported as orphan.
+        if let Some(checker) = &self.consistency_checker {
+            checker.start();
+        }
+        self.recycle_bin.start();
         if let Some(task) = &self.heartbeat_task {
             task.start().await?;
         }
+        if let Some(task) = &self.scrub_task {
+            task.start();
+        }
+        if let Some(task) = &self.table_metrics_task {
+            task.start();
+        }
+        if let Some(task) = &self.replication_task {
+            task.start();
+        }
+        if let Some(task) = &self.standby_task {
+            task.start();
+        }
         Ok(())
     }
 
+    /// Promotes a standby datanode to take over writes of its tables, once the datanodes
+    /// writing the tables have stopped. Writes not flushed by them are not recovered.
+    ///
+    /// Metasrv asks a standby datanode to promote by heartbeats, once promoted by
+    /// the admin API `/admin/promote?node_id=<id>` of metasrv.
+    pub async fn promote(&self) -> Result<()> {
+        match &self.standby_task {
+            Some(task) => task.promote().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Stops the background tasks of the instance, e.g. heartbeat, scrub and replication,
+    /// and then the log store.
+    pub async fn shutdown(&self) -> Result<()> {
+        if let Some(task) = &self.standby_task {
+            task.stop();
+        }
+        if let Some(task) = &self.heartbeat_task {
+            task.stop();
+        }
+        if let Some(task) = &self.scrub_task {
+            task.stop();
+        }
+        if let Some(task) = &self.table_metrics_task {
+            task.stop();
+        }
+        if let Some(task) = &self.replication_task {
+            task.stop();
+        }
+        self.recycle_bin.stop();
+        self.log_store.stop().await.context(StopLogStoreSnafu)
+    }
+
     pub fn sql_handler(&self) -> &SqlHandler {
         &self.sql_handler
     }
@@ -198,19 +460,51 @@ impl Instance {
     pub fn catalog_manager(&self) -> &CatalogManagerRef {
         &self.catalog_manager
     }
+
+    pub fn query_history(&self) -> &QueryHistoryRef {
+        &self.query_history
+    }
+
+    pub fn resource_accountant(&self) -> &ResourceAccountantRef {
+        &self.resource_accountant
+    }
+
+    pub fn job_manager(&self) -> &JobManagerRef {
+        &self.job_manager
+    }
+
+    pub fn recycle_bin(&self) -> &RecycleBinRef {
+        &self.recycle_bin
+    }
 }
 
-pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+pub(crate) async fn new_object_store(
+    store_config: &ObjectStoreConfig,
+    request_config: &ObjectStoreRequestConfig,
+) -> Result<ObjectStore> {
     let object_store = match store_config {
         ObjectStoreConfig::File { .. } => new_fs_object_store(store_config).await,
         ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config).await,
         ObjectStoreConfig::Oss { .. } => new_oss_object_store(store_config).await,
     };
 
+    let backoff = ExponentialBackoff::default()
+        .with_max_times(request_config.max_retries)
+        .with_min_delay(request_config.retry_min_delay)
+        .with_max_delay(request_config.retry_max_delay)
+        .with_jitter();
+    let timeout = TimeoutLayer::default()
+        .with_read_timeout(request_config.read_timeout)
+        .with_write_timeout(request_config.write_timeout)
+        .with_metadata_timeout(request_config.metadata_timeout);
+    // A layer wraps the layers added before it, so a request passes the retry layer first and
+    // the timeout layer last. Each attempt of the request is measured by the metrics layer,
+    // including attempts timed out or failed and retried.
     object_store.map(|object_store| {
         object_store
-            .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
+            .layer(timeout)
             .layer(MetricsLayer)
+            .layer(RetryLayer::new(backoff))
             .layer(LoggingLayer::default())
             .layer(TracingLayer)
     })
@@ -299,21 +593,96 @@ pub(crate) async fn new_fs_object_store(store_config: &ObjectStoreConfig) -> Res
     Ok(ObjectStore::new(accessor))
 }
 
+/// Creates the local data directories to place regions to, returns an empty list if there
+/// is only one data directory.
+async fn new_data_dirs(
+    store_config: &ObjectStoreConfig,
+    request_config: &ObjectStoreRequestConfig,
+    object_store: &ObjectStore,
+) -> Result<Vec<DataDir>> {
+    let ObjectStoreConfig::File(file_config) = store_config else {
+        return Ok(Vec::new());
+    };
+    if file_config.extra_data_dirs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut data_dirs = vec![DataDir::new(
+        &util::normalize_dir(&file_config.data_dir),
+        object_store.clone(),
+    )];
+    for dir in &file_config.extra_data_dirs {
+        let config = ObjectStoreConfig::File(FileConfig {
+            data_dir: dir.clone(),
+            ..Default::default()
+        });
+        let object_store = new_object_store(&config, request_config).await?;
+        data_dirs.push(DataDir::new(&util::normalize_dir(dir), object_store));
+    }
+    Ok(data_dirs)
+}
+
+fn new_encryptor(config: &EncryptionConfig) -> Result<EncryptorRef> {
+    let key_provider = StaticKeyProvider::try_new(&config.current_key_id, &config.keys)
+        .context(error::InitEncryptionSnafu)?;
+    info!(
+        "Encrypt SST files and WAL entries by key {}",
+        config.current_key_id
+    );
+    if config.allow_plaintext {
+        warn!("Unencrypted SST files and WAL entries are readable, which is only for migration");
+    }
+    Ok(Arc::new(
+        Encryptor::new(Arc::new(key_provider)).with_allow_plaintext(config.allow_plaintext),
+    ))
+}
+
+/// Creates the uploader of SST files buffered in local disk by write-behind.
+async fn new_sst_uploader(
+    config: &WriteBehindConfig,
+    object_store: ObjectStore,
+) -> Result<SstUploaderRef> {
+    let local_config = ObjectStoreConfig::File(FileConfig {
+        data_dir: config.dir.clone(),
+        ..Default::default()
+    });
+    let local_store = new_fs_object_store(&local_config).await?;
+    info!(
+        "Write-behind SST files to {}, backlog: {}",
+        config.dir, config.backlog
+    );
+
+    SstUploader::start(
+        &util::normalize_dir(&config.dir),
+        local_store,
+        object_store,
+        config.backlog,
+    )
+    .await
+    .context(error::StartSstUploaderSnafu)
+}
+
 /// Create metasrv client instance and spawn heartbeat loop.
-async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOpts) -> Result<MetaClient> {
+async fn new_metasrv_client(
+    node_id: u64,
+    meta_config: &MetaClientOpts,
+    cluster_token: Option<ClusterToken>,
+) -> Result<MetaClient> {
     let cluster_id = 0; // TODO(hl): read from config
     let member_id = node_id;
 
-    let config = ChannelConfig::new()
-        .timeout(Duration::from_millis(meta_config.timeout_millis))
-        .connect_timeout(Duration::from_millis(meta_config.connect_timeout_millis))
-        .tcp_nodelay(meta_config.tcp_nodelay);
-    let channel_manager = ChannelManager::with_config(config);
+    let mut channel_config = meta_config.channel_config();
+    if let Some(token) = cluster_token {
+        channel_config = channel_config.cluster_token(token);
+    }
+    let channel_manager = ChannelManager::with_config(channel_config);
     let mut meta_client = MetaClientBuilder::new(cluster_id, member_id)
         .enable_heartbeat()
         .enable_router()
         .enable_store()
         .channel_manager(channel_manager)
+        .heartbeat_options(meta_config.heartbeat_options())
+        .retry_options(meta_config.retry_options())
         .build();
     meta_client
         .start(&meta_config.metasrv_addrs)
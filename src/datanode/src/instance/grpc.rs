@@ -15,8 +15,12 @@
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request as GrpcRequest;
 use api::v1::query_request::Query;
-use api::v1::{CreateDatabaseExpr, DdlRequest, InsertRequest};
+use api::v1::{
+    CreateDatabaseExpr, DdlRequest, DdlRequests, DeleteRequest, FenceRequest, InsertRequest,
+    SnapshotRequest,
+};
 use async_trait::async_trait;
+use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_query::Output;
 use query::parser::QueryLanguageParser;
 use query::plan::LogicalPlan;
@@ -24,16 +28,26 @@ use servers::query_handler::grpc::GrpcQueryHandler;
 use session::context::QueryContextRef;
 use snafu::prelude::*;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
+use table::engine::TableReference;
 use table::requests::CreateDatabaseRequest;
+use table::table::SCAN_SEQUENCE;
 
 use crate::error::{self, DecodeLogicalPlanSnafu, ExecuteSqlSnafu, Result};
 use crate::instance::Instance;
+use crate::sql::insert_request_bytes;
 
 impl Instance {
     pub(crate) async fn handle_create_database(&self, expr: CreateDatabaseExpr) -> Result<Output> {
+        let catalog_name = if expr.catalog_name.is_empty() {
+            DEFAULT_CATALOG_NAME.to_string()
+        } else {
+            expr.catalog_name
+        };
         let req = CreateDatabaseRequest {
+            catalog_name,
             db_name: expr.database_name,
             create_if_not_exists: expr.create_if_not_exists,
+            options: expr.options,
         };
         self.sql_handler().create_database(req).await
     }
@@ -56,6 +70,13 @@ impl Instance {
                 self.execute_stmt(stmt, ctx).await?
             }
             Query::LogicalPlan(plan) => self.execute_logical(plan).await?,
+            // Tables are scanned while the physical plan is created, so the scans pick up the
+            // sequence before the output is returned.
+            Query::SnapshotLogicalPlan(plan) => {
+                SCAN_SEQUENCE
+                    .scope(plan.sequence, self.execute_logical(plan.logical_plan))
+                    .await?
+            }
         })
     }
 
@@ -72,17 +93,105 @@ impl Instance {
             .table(catalog, schema, table_name)
             .context(error::CatalogSnafu)?
             .context(error::TableNotFoundSnafu { table_name })?;
+        // Rejects late writes to a dropped table, or to the table recreated with the same
+        // name after the write was routed. Tables without info, e.g. virtual tables, reject
+        // the insert themselves.
+        let table_id = request.table_id;
+        let id_matched = table
+            .try_table_info()
+            .map_or(true, |info| info.ident.table_id == table_id);
+        ensure!(
+            table_id == 0 || (id_matched && !self.dropped_tables.contains(table_id)),
+            error::TableDroppedSnafu {
+                table_name,
+                table_id,
+            }
+        );
 
+        let wal_ack = request.wal_ack;
+        let request = common_grpc_expr::insert::decompress_insert_request(request)
+            .context(error::InsertDataSnafu)?;
+        common_grpc_expr::insert::validate_insert_request(&request, &table.schema())
+            .context(error::InsertDataSnafu)?;
         let request = common_grpc_expr::insert::to_table_insert_request(catalog, schema, request)
             .context(error::InsertDataSnafu)?;
+        self.sql_handler.check_future_timestamps(&table, &request)?;
 
+        let bytes = insert_request_bytes(&request);
+        let affected_rows = if wal_ack {
+            let (affected_rows, sequence) = table
+                .insert_unsynced(request)
+                .await
+                .context(error::InsertSnafu { table_name })?;
+            ctx.set_write_sequence(sequence);
+            affected_rows
+        } else {
+            table
+                .insert(request)
+                .await
+                .context(error::InsertSnafu { table_name })?
+        };
+        self.sql_handler.record_ingestion(
+            &TableReference {
+                catalog,
+                schema,
+                table: table_name,
+            },
+            affected_rows,
+            bytes,
+        );
+        Ok(Output::AffectedRows(affected_rows))
+    }
+
+    async fn handle_delete(&self, request: DeleteRequest, ctx: QueryContextRef) -> Result<Output> {
+        let table_name = &request.table_name.clone();
+        let table = self
+            .catalog_manager
+            .table(&ctx.current_catalog(), &ctx.current_schema(), table_name)
+            .context(error::CatalogSnafu)?
+            .context(error::TableNotFoundSnafu { table_name })?;
+        let request = common_grpc_expr::delete::to_table_delete_request(request)
+            .context(error::DeleteDataSnafu)?;
         let affected_rows = table
-            .insert(request)
+            .delete(request)
             .await
-            .context(error::InsertSnafu { table_name })?;
+            .context(error::DeleteTableSnafu { table_name })?;
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    async fn handle_fence(&self, request: FenceRequest, ctx: QueryContextRef) -> Result<Output> {
+        let table_name = &request.table_name;
+        let table = self
+            .catalog_manager
+            .table(&ctx.current_catalog(), &ctx.current_schema(), table_name)
+            .context(error::CatalogSnafu)?
+            .context(error::TableNotFoundSnafu { table_name })?;
+        table
+            .fence(request.sequence)
+            .await
+            .context(error::FenceTableSnafu { table_name })?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    async fn handle_snapshot(
+        &self,
+        request: SnapshotRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let table_name = &request.table_name;
+        let table = self
+            .catalog_manager
+            .table(&ctx.current_catalog(), &ctx.current_schema(), table_name)
+            .context(error::CatalogSnafu)?
+            .context(error::TableNotFoundSnafu { table_name })?;
+        let sequence = table
+            .snapshot_sequence()
+            .await
+            .context(error::SnapshotTableSnafu { table_name })?;
+        ctx.set_write_sequence(sequence);
+        Ok(Output::AffectedRows(0))
+    }
+
     async fn handle_ddl(&self, request: DdlRequest) -> Result<Output> {
         let expr = request.expr.context(error::MissingRequiredFieldSnafu {
             name: "DdlRequest.expr",
@@ -94,6 +203,17 @@ impl Instance {
             DdlExpr::DropTable(expr) => self.handle_drop_table(expr).await,
         }
     }
+
+    async fn handle_ddls(&self, requests: DdlRequests) -> Result<Output> {
+        let mut affected_rows = 0;
+        for request in requests.requests {
+            match self.handle_ddl(request).await? {
+                Output::AffectedRows(rows) => affected_rows += rows,
+                _ => unreachable!("DDL should not yield output other than AffectedRows"),
+            }
+        }
+        Ok(Output::AffectedRows(affected_rows))
+    }
 }
 
 #[async_trait]
@@ -112,6 +232,10 @@ impl GrpcQueryHandler for Instance {
                 self.handle_query(query, ctx).await
             }
             GrpcRequest::Ddl(request) => self.handle_ddl(request).await,
+            GrpcRequest::Ddls(requests) => self.handle_ddls(requests).await,
+            GrpcRequest::Fence(request) => self.handle_fence(request, ctx).await,
+            GrpcRequest::Snapshot(request) => self.handle_snapshot(request, ctx).await,
+            GrpcRequest::Delete(request) => self.handle_delete(request, ctx).await,
         }
     }
 }
@@ -123,6 +247,7 @@ mod test {
         alter_expr, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef,
         CreateDatabaseExpr, CreateTableExpr, QueryRequest,
     };
+    use common_catalog::consts::DEFAULT_SCHEMA_NAME;
     use common_recordbatch::RecordBatches;
     use datatypes::prelude::*;
     use session::context::QueryContext;
@@ -139,6 +264,7 @@ mod test {
             expr: Some(DdlExpr::CreateDatabase(CreateDatabaseExpr {
                 database_name: "my_database".to_string(),
                 create_if_not_exists: true,
+                ..Default::default()
             })),
         });
         let output = instance.do_query(query, QueryContext::arc()).await.unwrap();
@@ -156,12 +282,14 @@ mod test {
                         datatype: ColumnDataType::String as i32,
                         is_nullable: true,
                         default_constraint: vec![],
+                        comment: String::new(),
                     },
                     ColumnDef {
                         name: "ts".to_string(),
                         datatype: ColumnDataType::TimestampMillisecond as i32,
                         is_nullable: false,
                         default_constraint: vec![],
+                        comment: String::new(),
                     },
                 ],
                 time_index: "ts".to_string(),
@@ -183,8 +311,10 @@ mod test {
                             datatype: ColumnDataType::Int32 as i32,
                             is_nullable: true,
                             default_constraint: vec![],
+                            comment: String::new(),
                         }),
                         is_key: true,
+                        add_if_not_exists: false,
                     }],
                 })),
             })),
@@ -219,6 +349,65 @@ mod test {
         assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_ddls() {
+        let instance = MockInstance::new("test_handle_ddls").await;
+        let instance = instance.inner();
+
+        let create_database = DdlRequest {
+            expr: Some(DdlExpr::CreateDatabase(CreateDatabaseExpr {
+                database_name: "my_database".to_string(),
+                create_if_not_exists: true,
+                ..Default::default()
+            })),
+        };
+        let create_table = |table_name: &str| DdlRequest {
+            expr: Some(DdlExpr::CreateTable(CreateTableExpr {
+                catalog_name: "greptime".to_string(),
+                schema_name: "my_database".to_string(),
+                table_name: table_name.to_string(),
+                column_defs: vec![ColumnDef {
+                    name: "ts".to_string(),
+                    datatype: ColumnDataType::TimestampMillisecond as i32,
+                    is_nullable: false,
+                    default_constraint: vec![],
+                    comment: String::new(),
+                }],
+                time_index: "ts".to_string(),
+                ..Default::default()
+            })),
+        };
+        let query = GrpcRequest::Ddls(DdlRequests {
+            requests: vec![
+                create_database,
+                create_table("my_table1"),
+                create_table("my_table2"),
+            ],
+        });
+        let output = instance.do_query(query, QueryContext::arc()).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        // Requests before the failed one are kept.
+        let query = GrpcRequest::Ddls(DdlRequests {
+            requests: vec![create_table("my_table3"), create_table("my_table1")],
+        });
+        assert!(instance.do_query(query, QueryContext::arc()).await.is_err());
+
+        let output = instance
+            .execute_sql("SHOW TABLES FROM my_database", QueryContext::arc())
+            .await
+            .unwrap();
+        let expected = "\
++-----------+
+| Tables    |
++-----------+
+| my_table1 |
+| my_table2 |
+| my_table3 |
++-----------+";
+        test_util::check_output_stream(output, expected.to_string()).await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_handle_insert() {
         let instance = MockInstance::new("test_handle_insert").await;
@@ -253,6 +442,7 @@ mod test {
                     null_mask: vec![2],
                     semantic_type: SemanticType::Field as i32,
                     datatype: ColumnDataType::Float64 as i32,
+                    compressed_values: vec![],
                 },
                 Column {
                     column_name: "ts".to_string(),
@@ -290,6 +480,119 @@ mod test {
         assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_insert_with_wal_ack() {
+        let instance = MockInstance::new("test_handle_insert_with_wal_ack").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let insert = InsertRequest {
+            table_name: "demo".to_string(),
+            columns: vec![
+                Column {
+                    column_name: "host".to_string(),
+                    values: Some(Values {
+                        string_values: vec!["host1".to_string()],
+                        ..Default::default()
+                    }),
+                    semantic_type: SemanticType::Tag as i32,
+                    datatype: ColumnDataType::String as i32,
+                    ..Default::default()
+                },
+                Column {
+                    column_name: "ts".to_string(),
+                    values: Some(Values {
+                        ts_millisecond_values: vec![1672384140000],
+                        ..Default::default()
+                    }),
+                    semantic_type: SemanticType::Timestamp as i32,
+                    datatype: ColumnDataType::TimestampMillisecond as i32,
+                    ..Default::default()
+                },
+            ],
+            row_count: 1,
+            wal_ack: true,
+            ..Default::default()
+        };
+
+        let ctx = QueryContext::arc();
+        let query = GrpcRequest::Insert(insert);
+        let output = instance.do_query(query, ctx.clone()).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        let sequence = ctx.write_sequence().unwrap();
+
+        let query = GrpcRequest::Fence(FenceRequest {
+            table_name: "demo".to_string(),
+            sequence,
+        });
+        let output = instance.do_query(query, QueryContext::arc()).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let query = GrpcRequest::Fence(FenceRequest {
+            table_name: "not_exist".to_string(),
+            sequence,
+        });
+        assert!(instance.do_query(query, QueryContext::arc()).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_insert_to_dropped_table() {
+        let instance = MockInstance::new("test_handle_insert_to_dropped_table").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+        let table_id = instance
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+            .unwrap()
+            .unwrap()
+            .table_info()
+            .ident
+            .table_id;
+
+        let new_insert = |table_id| InsertRequest {
+            table_name: "demo".to_string(),
+            columns: vec![Column {
+                column_name: "ts".to_string(),
+                values: Some(Values {
+                    ts_millisecond_values: vec![1672384140000],
+                    ..Default::default()
+                }),
+                semantic_type: SemanticType::Timestamp as i32,
+                datatype: ColumnDataType::TimestampMillisecond as i32,
+                ..Default::default()
+            }],
+            row_count: 1,
+            table_id,
+            ..Default::default()
+        };
+
+        for id in [0, table_id] {
+            let query = GrpcRequest::Insert(new_insert(id));
+            let output = instance.do_query(query, QueryContext::arc()).await.unwrap();
+            assert!(matches!(output, Output::AffectedRows(1)));
+        }
+
+        // The write was routed to a previous table with the same name.
+        let query = GrpcRequest::Insert(new_insert(table_id + 1));
+        let err = instance
+            .do_query(query, QueryContext::arc())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::TableDropped { .. }));
+
+        instance.dropped_tables.reset([table_id]);
+        let query = GrpcRequest::Insert(new_insert(table_id));
+        let err = instance
+            .do_query(query, QueryContext::arc())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::TableDropped { .. }));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_handle_query() {
         let instance = MockInstance::new("test_handle_query").await;
@@ -327,4 +630,53 @@ mod test {
         let actual = recordbatch.pretty_print().unwrap();
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_snapshot() {
+        let instance = MockInstance::new("test_handle_snapshot").await;
+        let instance = instance.inner();
+        test_util::create_test_table(instance, ConcreteDataType::timestamp_millisecond_datatype())
+            .await
+            .unwrap();
+
+        let execute_sql = |sql: &str| {
+            let query = GrpcRequest::Query(QueryRequest {
+                query: Some(Query::Sql(sql.to_string())),
+            });
+            instance.do_query(query, QueryContext::arc())
+        };
+        let count_rows = || async {
+            let output = execute_sql("SELECT ts FROM demo").await.unwrap();
+            let Output::Stream(stream) = output else { unreachable!() };
+            let batches = RecordBatches::try_collect(stream).await.unwrap();
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+        };
+
+        let output = execute_sql("INSERT INTO demo(host, ts) VALUES ('host1', 1672201025000)")
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let ctx = QueryContext::arc();
+        let query = GrpcRequest::Snapshot(SnapshotRequest {
+            table_name: "demo".to_string(),
+        });
+        let output = instance.do_query(query, ctx.clone()).await.unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let sequence = ctx.write_sequence().unwrap();
+
+        let output = execute_sql("INSERT INTO demo(host, ts) VALUES ('host2', 1672201026000)")
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        // Writes after the snapshot are invisible to the scans at the sequence.
+        assert_eq!(1, SCAN_SEQUENCE.scope(sequence, count_rows()).await);
+        assert_eq!(2, count_rows().await);
+
+        let query = GrpcRequest::Snapshot(SnapshotRequest {
+            table_name: "not_exist".to_string(),
+        });
+        assert!(instance.do_query(query, QueryContext::arc()).await.is_err());
+    }
 }
@@ -16,6 +16,7 @@ use async_trait::async_trait;
 use common_query::Output;
 use common_telemetry::timer;
 use servers::query_handler::ScriptHandler;
+use session::context::QueryContextRef;
 
 use crate::instance::Instance;
 use crate::metric;
@@ -27,8 +28,12 @@ impl ScriptHandler for Instance {
         self.script_executor.insert_script(name, script).await
     }
 
-    async fn execute_script(&self, name: &str) -> servers::error::Result<Output> {
+    async fn execute_script(
+        &self,
+        name: &str,
+        query_ctx: QueryContextRef,
+    ) -> servers::error::Result<Output> {
         let _timer = timer!(metric::METRIC_RUN_SCRIPT_ELAPSED);
-        self.script_executor.execute_script(name).await
+        self.script_executor.execute_script(name, query_ctx).await
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use async_trait::async_trait;
+use catalog::CreateCatalogRequest;
 use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
@@ -28,7 +29,10 @@ use snafu::prelude::*;
 use sql::ast::ObjectName;
 use sql::statements::statement::Statement;
 use table::engine::TableReference;
-use table::requests::{CreateDatabaseRequest, DropTableRequest};
+use table::requests::{
+    AnalyzeTableRequest, CheckTableRequest, CreateDatabaseRequest, DropTableRequest,
+    UndropTableRequest, ENGINE_OPTION,
+};
 
 use crate::error::{self, BumpTableIdSnafu, ExecuteSqlSnafu, Result, TableIdProviderNotFoundSnafu};
 use crate::instance::Instance;
@@ -67,14 +71,20 @@ impl Instance {
 
             QueryStatement::Sql(Statement::CreateDatabase(c)) => {
                 let request = CreateDatabaseRequest {
+                    catalog_name: query_ctx.current_catalog(),
                     db_name: c.name.to_string(),
                     create_if_not_exists: c.if_not_exists,
+                    options: c.options(),
                 };
 
                 info!("Creating a new database: {}", request.db_name);
 
                 self.sql_handler
-                    .execute(SqlRequest::CreateDatabase(request), query_ctx)
+                    .execute_statement(
+                        SqlRequest::CreateDatabase(request),
+                        Some(c.to_string()),
+                        query_ctx,
+                    )
                     .await
             }
 
@@ -86,29 +96,43 @@ impl Instance {
                     .next_table_id()
                     .await
                     .context(BumpTableIdSnafu)?;
-                let _engine_name = c.engine.clone();
-                // TODO(hl): Select table engine by engine_name
+                let engine_name = c.engine.clone();
+                let statement = c.to_string();
 
                 let name = c.name.clone();
                 let (catalog, schema, table) = table_idents_to_full_name(&name, query_ctx.clone())?;
                 let table_ref = TableReference::full(&catalog, &schema, &table);
-                let request = self
+                let mut request = self
                     .sql_handler
                     .create_to_request(table_id, c, &table_ref)?;
+                // The table is created by the engine named by `ENGINE = <name>`.
+                request
+                    .table_options
+                    .insert(ENGINE_OPTION.to_string(), engine_name);
                 let table_id = request.id;
                 info!("Creating table: {table_ref}, table id = {table_id}",);
 
                 self.sql_handler
-                    .execute(SqlRequest::CreateTable(request), query_ctx)
+                    .execute_statement(SqlRequest::CreateTable(request), Some(statement), query_ctx)
                     .await
             }
             QueryStatement::Sql(Statement::Alter(alter_table)) => {
                 let name = alter_table.table_name().clone();
                 let (catalog, schema, table) = table_idents_to_full_name(&name, query_ctx.clone())?;
                 let table_ref = TableReference::full(&catalog, &schema, &table);
+                let statement = alter_table.to_string();
                 let req = self.sql_handler.alter_to_request(alter_table, table_ref)?;
                 self.sql_handler
-                    .execute(SqlRequest::Alter(req), query_ctx)
+                    .execute_statement(SqlRequest::Alter(req), Some(statement), query_ctx)
+                    .await
+            }
+            QueryStatement::Sql(Statement::AlterDatabase(alter_database)) => {
+                let statement = alter_database.to_string();
+                let req = self
+                    .sql_handler
+                    .alter_database_to_request(alter_database, query_ctx.current_catalog());
+                self.sql_handler
+                    .execute_statement(SqlRequest::AlterDatabase(req), Some(statement), query_ctx)
                     .await
             }
             QueryStatement::Sql(Statement::DropTable(drop_table)) => {
@@ -120,7 +144,60 @@ impl Instance {
                     table_name,
                 };
                 self.sql_handler
-                    .execute(SqlRequest::DropTable(req), query_ctx)
+                    .execute_statement(
+                        SqlRequest::DropTable(req),
+                        Some(drop_table.to_string()),
+                        query_ctx,
+                    )
+                    .await
+            }
+            QueryStatement::Sql(Statement::UndropTable(undrop_table)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(undrop_table.table_name(), query_ctx.clone())?;
+                let req = UndropTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                };
+                self.sql_handler
+                    .execute_statement(
+                        SqlRequest::UndropTable(req),
+                        Some(undrop_table.to_string()),
+                        query_ctx,
+                    )
+                    .await
+            }
+            QueryStatement::Sql(Statement::AnalyzeTable(analyze)) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&analyze.table_name, query_ctx.clone())?;
+                let req = AnalyzeTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::AnalyzeTable(req), query_ctx)
+                    .await
+            }
+            QueryStatement::Sql(Statement::CheckTable(check)) => {
+                // Repairing removes references to files from manifests, which is not
+                // reversible.
+                ensure!(
+                    !check.repair || query_ctx.current_user().is_admin(),
+                    error::PermissionDeniedSnafu {
+                        reason: "REPAIR TABLE requires the admin role",
+                    }
+                );
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(&check.table_name, query_ctx.clone())?;
+                let req = CheckTableRequest {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    repair: check.repair,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::CheckTable(req), query_ctx)
                     .await
             }
             QueryStatement::Sql(Statement::ShowDatabases(stmt)) => {
@@ -133,26 +210,123 @@ impl Instance {
                     .execute(SqlRequest::ShowTables(stmt), query_ctx)
                     .await
             }
+            QueryStatement::Sql(Statement::ShowTableStatus(stmt)) => {
+                self.sql_handler
+                    .execute(SqlRequest::ShowTableStatus(stmt), query_ctx)
+                    .await
+            }
             QueryStatement::Sql(Statement::Explain(stmt)) => {
+                // Nothing is split from the plan if the query is executed by the datanode.
+                ensure!(
+                    stmt.format.is_none(),
+                    error::NotSupportedSnafu {
+                        feat: "EXPLAIN (FORMAT ...) of non-distributed queries",
+                    }
+                );
                 self.sql_handler
                     .execute(SqlRequest::Explain(Box::new(stmt)), query_ctx)
                     .await
             }
+            QueryStatement::Sql(Statement::ExplainDdl(explain)) => {
+                let request = match *explain.statement {
+                    Statement::CreateTable(c) => {
+                        // No table id is allocated since the table is not created.
+                        let name = c.name.clone();
+                        let (catalog, schema, table) =
+                            table_idents_to_full_name(&name, query_ctx.clone())?;
+                        let table_ref = TableReference::full(&catalog, &schema, &table);
+                        let request = self.sql_handler.create_to_request(0, c, &table_ref)?;
+                        SqlRequest::CreateTable(request)
+                    }
+                    Statement::Alter(alter_table) => {
+                        let name = alter_table.table_name().clone();
+                        let (catalog, schema, table) =
+                            table_idents_to_full_name(&name, query_ctx.clone())?;
+                        let table_ref = TableReference::full(&catalog, &schema, &table);
+                        let request = self.sql_handler.alter_to_request(alter_table, table_ref)?;
+                        SqlRequest::Alter(request)
+                    }
+                    stmt => {
+                        return error::InvalidSqlSnafu {
+                            msg: format!("EXPLAIN DDL does not support statement: {stmt:?}"),
+                        }
+                        .fail()
+                    }
+                };
+                self.sql_handler
+                    .execute(SqlRequest::ExplainDdl(Box::new(request)), query_ctx)
+                    .await
+            }
             QueryStatement::Sql(Statement::DescribeTable(stmt)) => {
                 self.sql_handler
                     .execute(SqlRequest::DescribeTable(stmt), query_ctx)
                     .await
             }
-            QueryStatement::Sql(Statement::ShowCreateTable(_stmt)) => {
-                unimplemented!("SHOW CREATE TABLE is unimplemented yet");
+            QueryStatement::Sql(Statement::ShowCreateTable(stmt)) => {
+                self.sql_handler
+                    .execute(SqlRequest::ShowCreateTable(stmt), query_ctx)
+                    .await
+            }
+            QueryStatement::Sql(Statement::ShowProcesslist(_) | Statement::Kill(_)) => {
+                error::NotSupportedSnafu {
+                    feat: "SHOW PROCESSLIST and KILL QUERY in datanode",
+                }
+                .fail()
+            }
+            QueryStatement::Sql(
+                Statement::CreateAlertRule(_)
+                | Statement::DropAlertRule(_)
+                | Statement::ShowAlertRules,
+            ) => error::NotSupportedSnafu {
+                feat: "alert rules in datanode",
             }
-            QueryStatement::Sql(Statement::Use(ref schema)) => {
-                let catalog = &query_ctx.current_catalog();
+            .fail(),
+            QueryStatement::Sql(Statement::CreateCatalog(c)) => {
+                ensure!(
+                    query_ctx.current_user().is_admin(),
+                    error::PermissionDeniedSnafu {
+                        reason: "CREATE CATALOG requires the admin role",
+                    }
+                );
+                let request = CreateCatalogRequest {
+                    catalog: c.name.clone(),
+                };
+                let created = self
+                    .catalog_manager
+                    .create_catalog(request)
+                    .await
+                    .context(error::CreateCatalogSnafu { name: &c.name })?;
+                ensure!(
+                    created || c.if_not_exists,
+                    error::CatalogExistsSnafu { name: &c.name }
+                );
+                if created {
+                    info!("Created catalog: {}", c.name);
+                }
+                Ok(Output::AffectedRows(created as usize))
+            }
+            QueryStatement::Sql(Statement::Use(db)) => {
+                let catalog = &db
+                    .catalog_name
+                    .unwrap_or_else(|| query_ctx.current_catalog());
+                let schema = &db.schema_name;
+                let user = query_ctx.current_user();
+                ensure!(
+                    user.can_access_catalog(catalog),
+                    error::PermissionDeniedSnafu {
+                        reason: format!(
+                            "user {} is not allowed to access catalog {}",
+                            user.username(),
+                            catalog
+                        ),
+                    }
+                );
                 ensure!(
                     self.is_valid_schema(catalog, schema)?,
                     error::DatabaseNotFoundSnafu { catalog, schema }
                 );
 
+                query_ctx.set_current_catalog(catalog);
                 query_ctx.set_current_schema(schema);
 
                 Ok(Output::RecordBatches(RecordBatches::empty()))
@@ -14,14 +14,20 @@
 
 #![feature(assert_matches)]
 
+pub mod consistency;
 pub mod datanode;
 pub mod error;
 mod heartbeat;
 pub mod instance;
 mod metric;
 mod mock;
+pub mod recycle_bin;
+pub mod replication;
 mod script;
+mod scrub;
 pub mod server;
 pub mod sql;
+mod standby;
+pub mod table_metrics;
 #[cfg(test)]
 mod tests;
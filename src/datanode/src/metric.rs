@@ -18,3 +18,13 @@ pub const METRIC_HANDLE_SQL_ELAPSED: &str = "datanode.handle_sql_elapsed";
 pub const METRIC_HANDLE_SCRIPTS_ELAPSED: &str = "datanode.handle_scripts_elapsed";
 pub const METRIC_RUN_SCRIPT_ELAPSED: &str = "datanode.run_script_elapsed";
 pub const METRIC_HANDLE_PROMQL_ELAPSED: &str = "datanode.handle_promql_elapsed";
+pub const METRIC_INGEST_ROWS_TOTAL: &str = "datanode.ingest_rows_total";
+pub const METRIC_INGEST_BYTES_TOTAL: &str = "datanode.ingest_bytes_total";
+pub const METRIC_REPLICATION_SENT_ROWS_TOTAL: &str = "datanode.replication.sent_rows_total";
+pub const METRIC_REPLICATION_SNAPSHOT_ROWS_TOTAL: &str = "datanode.replication.snapshot_rows_total";
+pub const METRIC_REPLICATION_LOST_WRITES_TOTAL: &str = "datanode.replication.lost_writes_total";
+pub const METRIC_TABLE_REGIONS: &str = "datanode.table.regions";
+pub const METRIC_TABLE_MEMTABLE_BYTES: &str = "datanode.table.memtable_bytes";
+pub const METRIC_TABLE_SST_BYTES: &str = "datanode.table.sst_bytes";
+pub const METRIC_TABLE_SST_FILES: &str = "datanode.table.sst_files";
+pub const METRIC_TABLE_OMITTED_TABLES: &str = "datanode.table.omitted_tables";
@@ -15,9 +15,12 @@
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use catalog::query_history::QueryHistory;
 use catalog::remote::MetaKvBackend;
-use catalog::CatalogManagerRef;
+use catalog::resource_usage::ResourceAccountant;
+use catalog::{ddl_history, CatalogManagerRef};
 use common_catalog::consts::MIN_USER_TABLE_ID;
+use common_procedure::job::JobManager;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_srv::mocks::MockInfo;
 use mito::config::EngineConfig as TableEngineConfig;
@@ -28,11 +31,14 @@ use storage::config::EngineConfig as StorageEngineConfig;
 use storage::EngineImpl;
 use table::metadata::TableId;
 use table::table::TableIdProvider;
+use table::test_util::MemoryTableEngine;
 
+use crate::consistency::ConsistencyChecker;
 use crate::datanode::DatanodeOptions;
 use crate::error::{CatalogSnafu, Result};
 use crate::heartbeat::HeartbeatTask;
 use crate::instance::{create_log_store, new_object_store, DefaultEngine, Instance};
+use crate::recycle_bin::RecycleBin;
 use crate::script::ScriptExecutor;
 use crate::sql::SqlHandler;
 
@@ -43,8 +49,9 @@ impl Instance {
     }
 
     pub async fn with_mock_meta_server(opts: &DatanodeOptions, meta_srv: MockInfo) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage).await?;
+        let object_store = new_object_store(&opts.storage, &opts.object_store_request).await?;
         let logstore = Arc::new(create_log_store(&opts.wal).await?);
+        let job_manager = Arc::new(JobManager::new(object_store.clone()));
         let meta_client = Arc::new(mock_meta_client(meta_srv, opts.node_id.unwrap_or(42)).await);
         let table_engine = Arc::new(DefaultEngine::new(
             TableEngineConfig::default(),
@@ -53,7 +60,12 @@ impl Instance {
                 logstore.clone(),
                 object_store.clone(),
             ),
-            object_store,
+            object_store.clone(),
+        ));
+        let recycle_bin = Arc::new(RecycleBin::new(
+            object_store.clone(),
+            table_engine.clone(),
+            opts.recycle_bin_retention,
         ));
 
         // By default, catalog manager and factory are created in standalone mode
@@ -64,6 +76,10 @@ impl Instance {
                         .await
                         .context(CatalogSnafu)?,
                 );
+                // Records DDL operations of the standalone instance.
+                ddl_history::register_ddl_history_table(catalog.as_ref())
+                    .await
+                    .context(CatalogSnafu)?;
                 let factory = QueryEngineFactory::new(catalog.clone());
                 (catalog as CatalogManagerRef, factory)
             }
@@ -80,8 +96,23 @@ impl Instance {
             }
         };
         let query_engine = factory.query_engine();
-        let script_executor =
-            ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
+        let resource_accountant = Arc::new(ResourceAccountant::default());
+        query_engine.register_resource_accountant(resource_accountant.clone());
+        query_engine.register_masking_policies(opts.masking_policies.clone());
+        let mut sql_handler =
+            SqlHandler::new(table_engine, catalog_manager.clone(), query_engine.clone())
+                .with_recycle_bin(recycle_bin.clone());
+        if opts.enable_memory_catalog {
+            sql_handler = sql_handler.with_table_engine(Arc::new(MemoryTableEngine::new()));
+        }
+        let sql_handler = Arc::new(sql_handler);
+        let script_executor = ScriptExecutor::new(
+            catalog_manager.clone(),
+            query_engine.clone(),
+            sql_handler.clone(),
+            opts.enable_cpython_backend,
+        )
+        .await?;
 
         let heartbeat_task = HeartbeatTask::new(
             opts.node_id.unwrap_or(42),
@@ -90,17 +121,32 @@ impl Instance {
             meta_client.clone(),
             catalog_manager.clone(),
         );
+        let consistency_checker = opts.startup_consistency_check.clone().map(|config| {
+            ConsistencyChecker::new(
+                catalog_manager.clone(),
+                object_store,
+                recycle_bin.clone(),
+                config,
+            )
+        });
         Ok(Self {
             query_engine: query_engine.clone(),
-            sql_handler: SqlHandler::new(
-                table_engine,
-                catalog_manager.clone(),
-                query_engine.clone(),
-            ),
+            sql_handler,
             catalog_manager,
             script_executor,
             table_id_provider: Some(Arc::new(LocalTableIdProvider::default())),
+            dropped_tables: heartbeat_task.dropped_tables().clone(),
             heartbeat_task: Some(heartbeat_task),
+            scrub_task: None,
+            table_metrics_task: None,
+            replication_task: None,
+            standby_task: None,
+            query_history: Arc::new(QueryHistory::new(opts.query_history_size)),
+            resource_accountant,
+            recycle_bin,
+            job_manager,
+            consistency_checker,
+            log_store: logstore,
         })
     }
 }
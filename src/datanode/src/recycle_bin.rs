@@ -0,0 +1,435 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recycle bin of dropped tables.
+//!
+//! Data of a dropped table is kept for a retention period, during which the table could
+//! be recovered by `UNDROP TABLE`. The data is purged in background after the period.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common_error::prelude::BoxedError;
+use common_telemetry::{error, info, warn};
+use common_time::clock::{self, ClockRef};
+use futures::TryStreamExt;
+use mito::engine::table_dir;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use store_api::storage::RegionNumber;
+use table::engine::{EngineContext, TableEngineRef, TableReference};
+use table::metadata::TableId;
+use table::requests::DropTableRequest;
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
+
+use crate::error::{CorruptedRecycleBinSnafu, DropTableSnafu, RecycleBinSnafu, Result};
+
+/// Directory of the dropped table records in the object store.
+const RECYCLE_BIN_DIR: &str = "recycle_bin/";
+/// Interval to purge the expired dropped tables.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Record of a dropped table in the recycle bin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedTable {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub table_id: TableId,
+    pub region_numbers: Vec<RegionNumber>,
+    /// Unix timestamp in milliseconds when the table was dropped.
+    pub dropped_at: i64,
+}
+
+fn record_path(table_id: TableId) -> String {
+    format!("{RECYCLE_BIN_DIR}{table_id}.json")
+}
+
+struct Entry {
+    table: DroppedTable,
+    /// When the data of the table could be purged, `dropped_at` is only used to compute it
+    /// when recovering records, as the wall clock may jump.
+    expire_at: Instant,
+}
+
+pub type RecycleBinRef = Arc<RecycleBin>;
+
+/// `RecycleBin` keeps the records of dropped tables, persisted in the object store, and
+/// purges data of the tables dropped for longer than the retention period.
+pub struct RecycleBin {
+    object_store: ObjectStore,
+    table_engine: TableEngineRef,
+    retention: Duration,
+    tables: Mutex<HashMap<TableId, Entry>>,
+    /// Serializes purging tables with recovering them, see [RecycleBin::lock].
+    purge_lock: AsyncMutex<()>,
+    running: Arc<AtomicBool>,
+    /// Clock to read the current time from when recovering records.
+    clock: ClockRef,
+}
+
+impl RecycleBin {
+    pub fn new(
+        object_store: ObjectStore,
+        table_engine: TableEngineRef,
+        retention: Duration,
+    ) -> Self {
+        Self {
+            object_store,
+            table_engine,
+            retention,
+            tables: Mutex::new(HashMap::new()),
+            purge_lock: AsyncMutex::new(()),
+            running: Arc::new(AtomicBool::new(false)),
+            clock: clock::system_clock(),
+        }
+    }
+
+    /// Replaces the system clock the recycle bin reads the time from.
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Loads the records of dropped tables from the object store.
+    pub async fn recover(&self) -> Result<()> {
+        let dir = self.object_store.object(RECYCLE_BIN_DIR);
+        let exists = dir.is_exist().await.context(RecycleBinSnafu {
+            path: RECYCLE_BIN_DIR,
+        })?;
+        if !exists {
+            return Ok(());
+        }
+
+        let objects = dir
+            .list()
+            .await
+            .context(RecycleBinSnafu {
+                path: RECYCLE_BIN_DIR,
+            })?
+            .try_collect::<Vec<_>>()
+            .await
+            .context(RecycleBinSnafu {
+                path: RECYCLE_BIN_DIR,
+            })?;
+        let now = Instant::now();
+        let now_millis = self.clock.now_millis();
+        let mut tables = HashMap::with_capacity(objects.len());
+        for object in objects {
+            let path = object.path();
+            let bytes = object.read().await.context(RecycleBinSnafu { path })?;
+            let table: DroppedTable =
+                serde_json::from_slice(&bytes).context(CorruptedRecycleBinSnafu { path })?;
+            let dropped_for = Duration::from_millis((now_millis - table.dropped_at).max(0) as u64);
+            let expire_at = now + self.retention.saturating_sub(dropped_for);
+            tables.insert(table.table_id, Entry { table, expire_at });
+        }
+        info!("Recovered {} dropped tables in recycle bin", tables.len());
+
+        *self.tables.lock().unwrap() = tables;
+        Ok(())
+    }
+
+    /// Puts the dropped `table` into the recycle bin.
+    pub async fn put(&self, table: DroppedTable) -> Result<()> {
+        let path = record_path(table.table_id);
+        // Serializing a struct of plain fields never fails.
+        let bytes = serde_json::to_vec(&table).unwrap();
+        self.object_store
+            .object(&path)
+            .write(bytes)
+            .await
+            .context(RecycleBinSnafu { path })?;
+
+        let entry = Entry {
+            table,
+            expire_at: Instant::now() + self.retention,
+        };
+        let _ = self
+            .tables
+            .lock()
+            .unwrap()
+            .insert(entry.table.table_id, entry);
+        Ok(())
+    }
+
+    /// Returns the latest dropped table with the name, `None` if there is no such table.
+    pub fn latest(&self, catalog: &str, schema: &str, table_name: &str) -> Option<DroppedTable> {
+        self.tables
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| &entry.table)
+            .filter(|t| {
+                t.catalog_name == catalog && t.schema_name == schema && t.table_name == table_name
+            })
+            .max_by_key(|t| (t.dropped_at, t.table_id))
+            .cloned()
+    }
+
+    /// Returns all dropped tables in the recycle bin, ordered by the time they were dropped.
+    pub fn dropped_tables(&self) -> Vec<DroppedTable> {
+        let mut tables: Vec<_> = self
+            .tables
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.table.clone())
+            .collect();
+        tables.sort_unstable_by_key(|t| (t.dropped_at, t.table_id));
+        tables
+    }
+
+    /// Removes the record of a dropped table from the recycle bin, the data of the table
+    /// is kept.
+    pub async fn remove(&self, table_id: TableId) -> Result<()> {
+        let path = record_path(table_id);
+        self.object_store
+            .object(&path)
+            .delete()
+            .await
+            .context(RecycleBinSnafu { path })?;
+
+        let _ = self.tables.lock().unwrap().remove(&table_id);
+        Ok(())
+    }
+
+    /// Locks the recycle bin to recover a dropped table, no table is purged until the
+    /// returned guard is dropped.
+    pub async fn lock(&self) -> AsyncMutexGuard<'_, ()> {
+        self.purge_lock.lock().await
+    }
+
+    /// Purges data of the tables dropped for longer than the retention period at `now`,
+    /// returns the number of purged tables.
+    pub async fn purge_expired(&self, now: Instant) -> Result<usize> {
+        let _guard = self.lock().await;
+        let expired: Vec<_> = self
+            .tables
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.expire_at <= now)
+            .map(|entry| entry.table.clone())
+            .collect();
+
+        for table in &expired {
+            self.close_table(table).await?;
+            let dir = table_dir(&table.schema_name, table.table_id);
+            self.object_store
+                .batch()
+                .remove_all(&dir)
+                .await
+                .context(RecycleBinSnafu { path: &dir })?;
+            // Removes the record after the data, so the purge is retried if it fails.
+            self.remove(table.table_id).await?;
+            info!(
+                "Purged dropped table {}.{}.{}, table id: {}",
+                table.catalog_name, table.schema_name, table.table_name, table.table_id
+            );
+        }
+        Ok(expired.len())
+    }
+
+    /// Closes the regions of the dropped `table` through the table engine if they are still
+    /// opened, so no region is writing to the directory being purged.
+    async fn close_table(&self, table: &DroppedTable) -> Result<()> {
+        let ctx = EngineContext::default();
+        let table_ref = TableReference {
+            catalog: &table.catalog_name,
+            schema: &table.schema_name,
+            table: &table.table_name,
+        };
+        let table_name = table_ref.to_string();
+        let opened = self
+            .table_engine
+            .get_table(&ctx, &table_ref)
+            .map_err(BoxedError::new)
+            .context(DropTableSnafu {
+                table_name: &table_name,
+            })?;
+        // The name may be taken by a table created later.
+        let is_dropped_table = opened
+            .and_then(|opened| opened.try_table_info())
+            .map(|info| info.ident.table_id == table.table_id)
+            .unwrap_or(false);
+        if !is_dropped_table {
+            return Ok(());
+        }
+
+        let request = DropTableRequest {
+            catalog_name: table.catalog_name.clone(),
+            schema_name: table.schema_name.clone(),
+            table_name: table.table_name.clone(),
+        };
+        self.table_engine
+            .drop_table(&ctx, request)
+            .await
+            .map_err(BoxedError::new)
+            .context(DropTableSnafu { table_name })?;
+        Ok(())
+    }
+
+    /// Starts to purge expired tables in background.
+    pub fn start(self: &Arc<Self>) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Recycle bin purger started multiple times");
+            return;
+        }
+        let recycle_bin = self.clone();
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                tokio::time::sleep(PURGE_INTERVAL).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                if let Err(e) = recycle_bin.purge_expired(Instant::now()).await {
+                    error!(e; "Failed to purge dropped tables");
+                }
+            }
+            info!("Recycle bin purger shutdown");
+        });
+    }
+
+    /// Stops the purger, it exits before the next purge.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_time::clock::{Clock, MockClock};
+    use log_store::NoopLogStore;
+    use mito::config::EngineConfig as TableEngineConfig;
+    use mito::engine::MitoEngine;
+    use mito::table::test_util::{new_create_request, schema_for_test};
+    use object_store::services::fs::Builder;
+    use storage::config::EngineConfig as StorageEngineConfig;
+    use storage::EngineImpl;
+    use table::engine::TableEngine;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn new_dropped_table(table_name: &str, table_id: TableId, dropped_at: i64) -> DroppedTable {
+        DroppedTable {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: table_name.to_string(),
+            table_id,
+            region_numbers: vec![0],
+            dropped_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recycle_bin() {
+        let dir = TempDir::new("test_recycle_bin").unwrap();
+        let store_dir = dir.path().to_str().unwrap();
+        let accessor = Builder::default().root(store_dir).build().unwrap();
+        let object_store = ObjectStore::new(accessor);
+        let table_engine = Arc::new(MitoEngine::<EngineImpl<NoopLogStore>>::new(
+            TableEngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store.clone(),
+        ));
+        let clock = Arc::new(MockClock::new(100_000));
+        let new_recycle_bin = || {
+            RecycleBin::new(
+                object_store.clone(),
+                table_engine.clone(),
+                Duration::from_secs(10),
+            )
+            .with_clock(clock.clone())
+        };
+        let recycle_bin = new_recycle_bin();
+
+        // The table dropped long ago is still opened by the engine.
+        let mut request = new_create_request(Arc::new(schema_for_test()));
+        request.id = 1024;
+        request.table_name = "demo".to_string();
+        table_engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+        recycle_bin
+            .put(new_dropped_table("demo", 1024, 1000))
+            .await
+            .unwrap();
+        let now = clock.now_millis();
+        recycle_bin
+            .put(new_dropped_table("demo", 1025, now))
+            .await
+            .unwrap();
+        recycle_bin
+            .put(new_dropped_table("other", 1026, now + 1))
+            .await
+            .unwrap();
+        let latest = recycle_bin.latest("greptime", "public", "demo").unwrap();
+        assert_eq!(1025, latest.table_id);
+        assert!(recycle_bin.latest("greptime", "public", "absent").is_none());
+        // Tables put into the recycle bin are kept for the retention period.
+        assert_eq!(0, recycle_bin.purge_expired(Instant::now()).await.unwrap());
+
+        // Records are persisted, the retention period counts from the time tables are dropped.
+        let recovered = new_recycle_bin();
+        recovered.recover().await.unwrap();
+        assert_eq!(recycle_bin.dropped_tables(), recovered.dropped_tables());
+
+        let data_file = format!("{}data", table_dir("public", 1024));
+        object_store.object(&data_file).write("data").await.unwrap();
+        // Tables are not purged while being recovered.
+        let guard = recovered.lock().await;
+        let purge = recovered.purge_expired(Instant::now());
+        tokio::pin!(purge);
+        assert!(tokio::time::timeout(Duration::from_millis(100), &mut purge)
+            .await
+            .is_err());
+        drop(guard);
+        assert_eq!(1, purge.await.unwrap());
+        assert!(!object_store.object(&data_file).is_exist().await.unwrap());
+        let table_ref = TableReference::full("greptime", "public", "demo");
+        assert!(!table_engine.table_exists(&EngineContext::default(), &table_ref));
+        let ids: Vec<_> = recovered
+            .dropped_tables()
+            .iter()
+            .map(|t| t.table_id)
+            .collect();
+        assert_eq!(vec![1025, 1026], ids);
+        let expire_at = Instant::now() + Duration::from_secs(10);
+        assert_eq!(2, recovered.purge_expired(expire_at).await.unwrap());
+
+        recycle_bin
+            .put(new_dropped_table("other", 1027, now))
+            .await
+            .unwrap();
+        recycle_bin.remove(1027).await.unwrap();
+        let recovered = new_recycle_bin();
+        recovered.recover().await.unwrap();
+        assert!(recovered.dropped_tables().is_empty());
+    }
+}
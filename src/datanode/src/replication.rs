@@ -0,0 +1,673 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replicates writes of tables to a remote cluster by tailing the WAL, so an edge instance
+//! with an intermittent connection could forward its data to the cloud.
+//!
+//! A table is synced by a snapshot first: rows visible at the committed sequence of the
+//! table are scanned and converted to gRPC insert requests, then committed writes after the
+//! sequence read from the WAL are converted to gRPC insert and delete requests. Requests are
+//! appended to a local buffer first, then the buffered requests are sent to the remote
+//! cluster in order. Requests stay in the buffer while the remote cluster is unreachable.
+//!
+//! The resume token of a table, the sequence of the next write to read, is persisted after
+//! the writes are buffered, so replication resumes where it stops after restart. The WAL
+//! entries from the resume token are retained after flush by [ReplicationProgress]. A write
+//! may be sent more than once if the instance crashes in between, which is harmless since
+//! rows with the same key overwrite each other.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use api::v1::column::SemanticType;
+use api::v1::greptime_request::Request;
+use api::v1::{DeleteRequest, GreptimeRequest, InsertRequest, RequestHeader};
+use catalog::CatalogManagerRef;
+use client::{Client, Database};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::prelude::BoxedError;
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::token::ClusterToken;
+use common_grpc_expr::change_batch_to_columns;
+use common_query::physical_plan::SessionContext;
+use common_telemetry::{error, info, warn};
+use futures::TryStreamExt;
+use metrics::counter;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use storage::WalRetention;
+use store_api::storage::{ChangeBatch, OpType, RegionId, SequenceNumber};
+use table::metadata::{TableId, TableInfoRef};
+use table::TableRef;
+use tokio::sync::Mutex;
+
+use crate::datanode::ReplicationConfig;
+use crate::error::{
+    ConvertChangesSnafu, CorruptedReplicationBufferSnafu, ReadChangesSnafu, ReadTableSnafu,
+    ReplicateSnafu, ReplicationBufferSnafu, Result, ScanTableSnafu, SerializeResumeTokensSnafu,
+    SnapshotTableSnafu,
+};
+use crate::metric::{
+    METRIC_REPLICATION_LOST_WRITES_TOTAL, METRIC_REPLICATION_SENT_ROWS_TOTAL,
+    METRIC_REPLICATION_SNAPSHOT_ROWS_TOTAL,
+};
+
+const BUFFER_FILE_EXTENSION: &str = "batch";
+const RESUME_TOKENS_FILE: &str = "resume_tokens.json";
+
+/// Position to resume replication of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeToken {
+    /// Id of the table, the token is reset if the table is recreated.
+    pub table_id: u32,
+    /// Sequence of the next write to read.
+    pub next_sequence: SequenceNumber,
+    /// Whether rows of the snapshot of the table are buffered. The snapshot is taken again
+    /// if the instance crashes before all rows of the snapshot are buffered.
+    pub synced: bool,
+}
+
+/// Task to replicate writes of tables to the remote cluster periodically.
+pub struct ReplicationTask {
+    running: Arc<AtomicBool>,
+    replicator: Arc<Mutex<Replicator>>,
+}
+
+impl Drop for ReplicationTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ReplicationTask {
+    pub fn new(
+        catalog_manager: CatalogManagerRef,
+        config: ReplicationConfig,
+        cluster_token: Option<ClusterToken>,
+        progress: Arc<ReplicationProgress>,
+    ) -> Result<Self> {
+        let replicator = Replicator::new(catalog_manager, config, cluster_token, progress)?;
+        Ok(Self {
+            running: Arc::new(AtomicBool::new(false)),
+            replicator: Arc::new(Mutex::new(replicator)),
+        })
+    }
+
+    /// Stops the replication task, it exits before the next round.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Start replication task, spawn background task.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Replication task started multiple times");
+            return;
+        }
+        let replicator = self.replicator.clone();
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                let interval = replicator.lock().await.config.interval;
+                tokio::time::sleep(interval).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                let mut replicator = replicator.lock().await;
+                if let Err(e) = replicator.fetch().await {
+                    error!(e; "Failed to read writes to replicate");
+                }
+                // Buffered writes are retried in the next round if the remote cluster is
+                // unreachable.
+                if let Err(e) = replicator.send().await {
+                    warn!(
+                        "Failed to send writes to remote cluster, buffered writes: {}, error: {}",
+                        replicator.buffered(),
+                        e
+                    );
+                }
+            }
+            info!("Replication task shutdown");
+        });
+    }
+}
+
+/// Reads writes of tables from the WAL into the buffer and sends buffered writes to the
+/// remote cluster.
+pub struct Replicator {
+    catalog_manager: CatalogManagerRef,
+    config: ReplicationConfig,
+    buffer: ReplicationBuffer,
+    progress: Arc<ReplicationProgress>,
+    client: Client,
+}
+
+/// Name of a table to replicate.
+struct ReplicatedTable<'a> {
+    catalog: &'a str,
+    schema: &'a str,
+    table: &'a str,
+}
+
+impl<'a> ReplicatedTable<'a> {
+    /// Parses the name in format `catalog.schema.table`, `schema.table` or `table`.
+    fn parse(name: &'a str) -> Self {
+        let mut parts = name.rsplitn(3, '.');
+        // Safety: `rsplitn` yields at least one part.
+        let table = parts.next().unwrap();
+        let schema = parts.next().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let catalog = parts.next().unwrap_or(DEFAULT_CATALOG_NAME);
+        Self {
+            catalog,
+            schema,
+            table,
+        }
+    }
+
+    /// Returns the key of the resume token of the table.
+    fn key(&self) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, self.table)
+    }
+
+    fn header(&self) -> RequestHeader {
+        RequestHeader {
+            catalog: self.catalog.to_string(),
+            schema: self.schema.to_string(),
+        }
+    }
+}
+
+impl Replicator {
+    /// Creates a replicator, the `cluster_token` is attached to the requests sent to the
+    /// remote cluster.
+    pub fn new(
+        catalog_manager: CatalogManagerRef,
+        config: ReplicationConfig,
+        cluster_token: Option<ClusterToken>,
+        progress: Arc<ReplicationProgress>,
+    ) -> Result<Self> {
+        let buffer = ReplicationBuffer::open(Path::new(&config.buffer_dir))?;
+        let mut channel_config = ChannelConfig::new();
+        if let Some(token) = cluster_token {
+            channel_config = channel_config.cluster_token(token);
+        }
+        if let Some(tls) = &config.tls {
+            channel_config = channel_config.client_tls(tls.clone());
+        }
+        let client = Client::with_manager_and_urls(
+            ChannelManager::with_config(channel_config),
+            vec![config.remote_addr.clone()],
+        );
+        info!(
+            "Replicate tables {:?} to {}, buffered writes: {}",
+            config.tables,
+            config.remote_addr,
+            buffer.len()
+        );
+
+        Ok(Self {
+            catalog_manager,
+            config,
+            buffer,
+            progress,
+            client,
+        })
+    }
+
+    /// Returns the number of buffered writes.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the resume token of the table `catalog.schema.table`.
+    pub fn resume_token(&self, table: &str) -> Option<ResumeToken> {
+        self.progress.token(table)
+    }
+
+    /// Reads new writes of the tables from the WAL into the buffer, returns the number of
+    /// buffered rows.
+    pub async fn fetch(&mut self) -> Result<usize> {
+        let mut buffered = 0;
+        for name in self.config.tables.clone() {
+            let name = ReplicatedTable::parse(&name);
+            let table = match self
+                .catalog_manager
+                .table(name.catalog, name.schema, name.table)
+            {
+                Ok(Some(table)) => table,
+                Ok(None) => {
+                    warn!("Table {} to replicate not found", name.key());
+                    continue;
+                }
+                Err(e) => {
+                    error!(e; "Failed to get table {} to replicate", name.key());
+                    continue;
+                }
+            };
+            buffered += self.fetch_table(&name, table).await?;
+        }
+        Ok(buffered)
+    }
+
+    async fn fetch_table(&mut self, name: &ReplicatedTable<'_>, table: TableRef) -> Result<usize> {
+        let key = name.key();
+        let table_info = table.table_info();
+        let table_id = table_info.ident.table_id;
+        let tags = primary_key_names(&table_info);
+
+        let mut buffered = 0;
+        let mut token = match self.progress.token(&key) {
+            Some(token) if token.table_id == table_id && token.synced => token,
+            _ => {
+                let (token, rows) = self.sync_table(name, &table, &tags).await?;
+                buffered += rows;
+                token
+            }
+        };
+
+        while self.buffer.bytes < self.config.buffer_size.0 {
+            let batches = table
+                .read_changes(token.next_sequence, self.config.read_batch_size)
+                .await
+                .context(ReadChangesSnafu {
+                    table_name: &table_info.name,
+                })?;
+            let last_sequence = match batches.last() {
+                Some(last) => last.sequence,
+                None => break,
+            };
+
+            let first_sequence = batches[0].sequence;
+            if first_sequence > token.next_sequence {
+                warn!(
+                    "Writes of table {} in [{}, {}) are purged from the WAL before replication",
+                    key, token.next_sequence, first_sequence
+                );
+                counter!(
+                    METRIC_REPLICATION_LOST_WRITES_TOTAL,
+                    first_sequence - token.next_sequence,
+                    "table" => key.clone()
+                );
+            }
+
+            for batch in batches {
+                let request = match batch.op_type {
+                    OpType::Put => {
+                        Request::Insert(to_insert_request(&table_info.name, &tags, &batch)?)
+                    }
+                    OpType::Delete => {
+                        Request::Delete(to_delete_request(&table_info.name, &tags, &batch)?)
+                    }
+                };
+                self.buffer.append(&GreptimeRequest {
+                    header: Some(name.header()),
+                    request: Some(request),
+                })?;
+                buffered += batch.num_rows();
+            }
+
+            // Persists the token after the writes are buffered.
+            token.next_sequence = last_sequence + 1;
+            self.progress.set_token(&key, token)?;
+        }
+        Ok(buffered)
+    }
+
+    /// Buffers rows of the snapshot of the table and returns the token to read writes
+    /// after the snapshot, and the number of buffered rows.
+    async fn sync_table(
+        &mut self,
+        name: &ReplicatedTable<'_>,
+        table: &TableRef,
+        tags: &[String],
+    ) -> Result<(ResumeToken, usize)> {
+        let key = name.key();
+        let table_info = table.table_info();
+        let table_name = &table_info.name;
+        let sequence = table
+            .snapshot_sequence()
+            .await
+            .context(SnapshotTableSnafu { table_name })?;
+        // Retains writes after the snapshot in the WAL before scanning the table. Rows of
+        // these writes may be scanned as well, and sent again later.
+        let mut token = ResumeToken {
+            table_id: table_info.ident.table_id,
+            next_sequence: sequence + 1,
+            synced: false,
+        };
+        self.progress.set_token(&key, token)?;
+        info!(
+            "Sync snapshot of table {} to replicate, sequence: {}",
+            key, sequence
+        );
+
+        let plan = table
+            .scan(None, &[], None)
+            .await
+            .context(ScanTableSnafu { table_name })?;
+        let session_ctx = SessionContext::new();
+        let mut rows = 0;
+        for partition in 0..plan.output_partitioning().partition_count() {
+            let mut stream = plan
+                .execute(partition, session_ctx.task_ctx())
+                .map_err(BoxedError::new)
+                .context(ReadTableSnafu { table_name })?;
+            while let Some(batch) = stream
+                .try_next()
+                .await
+                .map_err(BoxedError::new)
+                .context(ReadTableSnafu { table_name })?
+            {
+                let batch = ChangeBatch {
+                    sequence,
+                    op_type: OpType::Put,
+                    schema: batch.schema.clone(),
+                    columns: batch.columns().to_vec(),
+                };
+                if batch.num_rows() == 0 {
+                    continue;
+                }
+                self.buffer.append(&GreptimeRequest {
+                    header: Some(name.header()),
+                    request: Some(Request::Insert(to_insert_request(
+                        table_name, tags, &batch,
+                    )?)),
+                })?;
+                rows += batch.num_rows();
+            }
+        }
+        counter!(METRIC_REPLICATION_SNAPSHOT_ROWS_TOTAL, rows as u64, "table" => key.clone());
+
+        token.synced = true;
+        self.progress.set_token(&key, token)?;
+        Ok((token, rows))
+    }
+
+    /// Sends buffered writes to the remote cluster in order, returns the number of sent
+    /// requests. Stops at the first write failed to send.
+    pub async fn send(&mut self) -> Result<usize> {
+        let mut sent = 0;
+        while let Some(request) = self.buffer.front()? {
+            let (header, request) = match request {
+                GreptimeRequest {
+                    header: Some(header),
+                    request: Some(request @ (Request::Insert(_) | Request::Delete(_))),
+                } => (header, request),
+                _ => {
+                    let path = self.buffer.front_path();
+                    return CorruptedReplicationBufferSnafu {
+                        path: path.display().to_string(),
+                        reason: "expect an insert or delete request with header",
+                    }
+                    .fail();
+                }
+            };
+            let database = Database::new(header.catalog, header.schema, self.client.clone());
+            let (table_name, rows, result) = match request {
+                Request::Insert(insert) => (
+                    insert.table_name.clone(),
+                    insert.row_count as u64,
+                    database.insert(insert).await,
+                ),
+                Request::Delete(delete) => (
+                    delete.table_name.clone(),
+                    delete.row_count as u64,
+                    database.delete(delete).await,
+                ),
+                _ => unreachable!(),
+            };
+            let _ = result.context(ReplicateSnafu {
+                addr: &self.config.remote_addr,
+            })?;
+            counter!(METRIC_REPLICATION_SENT_ROWS_TOTAL, rows, "table" => table_name);
+
+            self.buffer.pop_front()?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
+
+fn primary_key_names(table_info: &TableInfoRef) -> Vec<String> {
+    let schema = &table_info.meta.schema;
+    table_info
+        .meta
+        .primary_key_indices
+        .iter()
+        .map(|i| schema.column_schemas()[*i].name.clone())
+        .collect()
+}
+
+/// Converts the rows of a put to an insert request of table `table_name`, columns in
+/// `tags` are sent as tags.
+fn to_insert_request(
+    table_name: &str,
+    tags: &[String],
+    batch: &ChangeBatch,
+) -> Result<InsertRequest> {
+    let columns =
+        change_batch_to_columns(batch, tags).context(ConvertChangesSnafu { table_name })?;
+    Ok(InsertRequest {
+        table_name: table_name.to_string(),
+        columns,
+        row_count: batch.num_rows() as u32,
+        ..Default::default()
+    })
+}
+
+/// Converts the keys of a delete to a delete request of table `table_name`, only the
+/// columns in `tags` and the time index are sent.
+fn to_delete_request(
+    table_name: &str,
+    tags: &[String],
+    batch: &ChangeBatch,
+) -> Result<DeleteRequest> {
+    let key_columns = change_batch_to_columns(batch, tags)
+        .context(ConvertChangesSnafu { table_name })?
+        .into_iter()
+        .filter(|column| column.semantic_type != SemanticType::Field as i32)
+        .collect();
+    Ok(DeleteRequest {
+        table_name: table_name.to_string(),
+        key_columns,
+        row_count: batch.num_rows() as u32,
+    })
+}
+
+/// Requests not sent yet, each request is stored in a file named by its id.
+struct ReplicationBuffer {
+    dir: PathBuf,
+    /// Ids and sizes of buffered files, in the order of ids.
+    files: VecDeque<(u64, u64)>,
+    /// Total size of buffered files.
+    bytes: u64,
+    next_id: u64,
+}
+
+impl ReplicationBuffer {
+    fn open(dir: &Path) -> Result<Self> {
+        let entries = fs::read_dir(dir).context(ReplicationBufferSnafu {
+            path: dir.display().to_string(),
+        })?;
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = entry.context(ReplicationBufferSnafu {
+                path: dir.display().to_string(),
+            })?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(BUFFER_FILE_EXTENSION) {
+                continue;
+            }
+            let id = path
+                .file_stem()
+                .and_then(|s| s.to_str()?.parse::<u64>().ok());
+            let Some(id) = id else { continue };
+            let metadata = entry.metadata().context(ReplicationBufferSnafu {
+                path: path.display().to_string(),
+            })?;
+            files.push((id, metadata.len()));
+        }
+        files.sort_unstable();
+
+        let bytes = files.iter().map(|(_, size)| size).sum();
+        let next_id = files.last().map(|(id, _)| id + 1).unwrap_or(0);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            files: files.into(),
+            bytes,
+            next_id,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    fn file_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{id:020}.{BUFFER_FILE_EXTENSION}"))
+    }
+
+    fn front_path(&self) -> PathBuf {
+        self.file_path(self.files.front().map(|(id, _)| *id).unwrap_or(0))
+    }
+
+    fn append(&mut self, request: &GreptimeRequest) -> Result<()> {
+        let id = self.next_id;
+        let path = self.file_path(id);
+        let data = request.encode_to_vec();
+        write_file(&path, &data)?;
+
+        self.files.push_back((id, data.len() as u64));
+        self.bytes += data.len() as u64;
+        self.next_id += 1;
+        Ok(())
+    }
+
+    fn front(&self) -> Result<Option<GreptimeRequest>> {
+        let Some((id, _)) = self.files.front() else { return Ok(None) };
+        let path = self.file_path(*id);
+        let data = fs::read(&path).context(ReplicationBufferSnafu {
+            path: path.display().to_string(),
+        })?;
+        let request = GreptimeRequest::decode(data.as_slice()).map_err(|e| {
+            CorruptedReplicationBufferSnafu {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+        Ok(Some(request))
+    }
+
+    fn pop_front(&mut self) -> Result<()> {
+        let Some((id, size)) = self.files.pop_front() else { return Ok(()) };
+        let path = self.file_path(id);
+        fs::remove_file(&path).context(ReplicationBufferSnafu {
+            path: path.display().to_string(),
+        })?;
+        self.bytes -= size;
+        Ok(())
+    }
+}
+
+/// Resume tokens of tables, keyed by `catalog.schema.table`. WAL entries of the tables
+/// from their resume tokens are retained after flush.
+#[derive(Debug)]
+pub struct ReplicationProgress {
+    path: PathBuf,
+    tokens: RwLock<BTreeMap<String, ResumeToken>>,
+}
+
+impl ReplicationProgress {
+    /// Loads the resume tokens from the buffer directory in `config`. It should be opened
+    /// before tables, so WAL entries not replicated are not purged on opening tables.
+    pub fn open(config: &ReplicationConfig) -> Result<Arc<Self>> {
+        fs::create_dir_all(&config.buffer_dir).context(ReplicationBufferSnafu {
+            path: &config.buffer_dir,
+        })?;
+        let path = Path::new(&config.buffer_dir).join(RESUME_TOKENS_FILE);
+        let tokens = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                CorruptedReplicationBufferSnafu {
+                    path: path.display().to_string(),
+                    reason: e.to_string(),
+                }
+                .build()
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(e).context(ReplicationBufferSnafu {
+                    path: path.display().to_string(),
+                })
+            }
+        };
+        Ok(Arc::new(Self {
+            path,
+            tokens: RwLock::new(tokens),
+        }))
+    }
+
+    fn token(&self, key: &str) -> Option<ResumeToken> {
+        self.tokens.read().unwrap().get(key).copied()
+    }
+
+    /// Updates the token of the table and saves all tokens to a temporary file, then
+    /// renames it, so the tokens are never half written.
+    fn set_token(&self, key: &str, token: ResumeToken) -> Result<()> {
+        let mut tokens = self.tokens.write().unwrap();
+        let _ = tokens.insert(key.to_string(), token);
+        let data = serde_json::to_vec(&*tokens).context(SerializeResumeTokensSnafu {
+            path: self.path.display().to_string(),
+        })?;
+        let tmp_path = self.path.with_extension("tmp");
+        write_file(&tmp_path, &data)?;
+        fs::rename(&tmp_path, &self.path).context(ReplicationBufferSnafu {
+            path: self.path.display().to_string(),
+        })
+    }
+}
+
+impl WalRetention for ReplicationProgress {
+    fn retained_sequence(&self, region_id: RegionId) -> Option<SequenceNumber> {
+        // Region ids of mito tables are `table_id << 32 | region_number`.
+        let table_id = (region_id >> 32) as TableId;
+        self.tokens
+            .read()
+            .unwrap()
+            .values()
+            .filter(|token| token.table_id == table_id)
+            .map(|token| token.next_sequence)
+            .min()
+    }
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    let mut file = fs::File::create(path).context(ReplicationBufferSnafu {
+        path: path.display().to_string(),
+    })?;
+    file.write_all(data)
+        .and_then(|_| file.sync_all())
+        .context(ReplicationBufferSnafu {
+            path: path.display().to_string(),
+        })
+}
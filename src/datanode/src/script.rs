@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use catalog::CatalogManagerRef;
 use common_query::Output;
 use query::QueryEngineRef;
+use session::context::QueryContextRef;
 
 use crate::error::Result;
+use crate::sql::SqlHandler;
 
 #[cfg(not(feature = "python"))]
 mod dummy {
@@ -28,6 +32,8 @@ mod dummy {
         pub async fn new(
             _catalog_manager: CatalogManagerRef,
             _query_engine: QueryEngineRef,
+            _sql_handler: Arc<SqlHandler>,
+            _enable_cpython_backend: bool,
         ) -> Result<Self> {
             Ok(Self {})
         }
@@ -40,7 +46,11 @@ mod dummy {
             servers::error::NotSupportedSnafu { feat: "script" }.fail()
         }
 
-        pub async fn execute_script(&self, _script: &str) -> servers::error::Result<Output> {
+        pub async fn execute_script(
+            &self,
+            _script: &str,
+            _query_ctx: QueryContextRef,
+        ) -> servers::error::Result<Output> {
             servers::error::NotSupportedSnafu { feat: "script" }.fail()
         }
     }
@@ -48,10 +58,13 @@ mod dummy {
 
 #[cfg(feature = "python")]
 mod python {
+    use async_trait::async_trait;
     use common_error::prelude::BoxedError;
     use common_telemetry::logging::error;
+    use script::engine::InsertHandler;
     use script::manager::ScriptManager;
     use snafu::ResultExt;
+    use table::requests::InsertRequest;
 
     use super::*;
 
@@ -60,14 +73,19 @@ mod python {
     }
 
     impl ScriptExecutor {
+        /// Creates the executor of scripts, which write tables by `sql_handler`.
         pub async fn new(
             catalog_manager: CatalogManagerRef,
             query_engine: QueryEngineRef,
+            sql_handler: Arc<SqlHandler>,
+            enable_cpython_backend: bool,
         ) -> Result<Self> {
             Ok(Self {
                 script_manager: ScriptManager::new(catalog_manager, query_engine)
                     .await
-                    .context(crate::error::StartScriptManagerSnafu)?,
+                    .context(crate::error::StartScriptManagerSnafu)?
+                    .with_insert_handler(sql_handler)
+                    .with_cpython_backend(enable_cpython_backend),
             })
         }
 
@@ -85,9 +103,13 @@ mod python {
             Ok(())
         }
 
-        pub async fn execute_script(&self, name: &str) -> servers::error::Result<Output> {
+        pub async fn execute_script(
+            &self,
+            name: &str,
+            query_ctx: QueryContextRef,
+        ) -> servers::error::Result<Output> {
             self.script_manager
-                .execute(name)
+                .execute(name, query_ctx)
                 .await
                 .map_err(|e| {
                     error!(e; "Instance failed to execute script");
@@ -96,6 +118,19 @@ mod python {
                 .context(servers::error::ExecuteScriptSnafu { name })
         }
     }
+
+    #[async_trait]
+    impl InsertHandler for SqlHandler {
+        async fn insert(
+            &self,
+            request: InsertRequest,
+            query_ctx: QueryContextRef,
+        ) -> std::result::Result<usize, BoxedError> {
+            self.insert_from_script(request, query_ctx)
+                .await
+                .map_err(BoxedError::new)
+        }
+    }
 }
 
 #[cfg(not(feature = "python"))]
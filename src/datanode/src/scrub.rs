@@ -0,0 +1,103 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::{all_tables, CatalogManagerRef};
+use common_telemetry::{error, info, warn};
+
+/// Task to verify the checksums of files of all tables in the datanode periodically.
+///
+/// Files failed to verify are logged, counted in metrics and reported to metasrv by the
+/// region stats in heartbeats.
+pub struct ScrubTask {
+    running: Arc<AtomicBool>,
+    catalog_manager: CatalogManagerRef,
+    interval: Duration,
+}
+
+impl Drop for ScrubTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ScrubTask {
+    pub fn new(catalog_manager: CatalogManagerRef, interval: Duration) -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            catalog_manager,
+            interval,
+        }
+    }
+
+    /// Stops the scrub task, it exits before the next scrub.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Start scrub task, spawn background task.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Scrub task started multiple times");
+            return;
+        }
+        let interval = self.interval;
+        let catalog_manager = self.catalog_manager.clone();
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                Self::scrub(&catalog_manager).await;
+            }
+            info!("Scrub task shutdown");
+        });
+    }
+
+    async fn scrub(catalog_manager: &CatalogManagerRef) {
+        let tables = match all_tables(catalog_manager) {
+            Ok(tables) => tables,
+            Err(e) => {
+                error!(e; "Failed to get tables to scrub");
+                return;
+            }
+        };
+
+        let (mut verified_files, mut corrupted_files) = (0, 0);
+        for table in tables {
+            match table.scrub().await {
+                Ok(stats) => {
+                    for stat in stats {
+                        verified_files += stat.verified_files;
+                        corrupted_files += stat.corrupted_files.len();
+                    }
+                }
+                Err(e) => error!(e; "Failed to scrub table {}", table.table_info().name),
+            }
+        }
+        info!(
+            "Scrub finished, verified files: {}, corrupted files: {}",
+            verified_files, corrupted_files
+        );
+    }
+}
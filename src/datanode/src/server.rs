@@ -17,20 +17,25 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 use common_runtime::Builder as RuntimeBuilder;
-use common_telemetry::tracing::log::info;
+use common_telemetry::tracing::log::{info, warn};
+use servers::auth::user_provider::StaticUserProvider;
+use servers::auth::UserProviderRef;
 use servers::error::Error::InternalIo;
-use servers::grpc::GrpcServer;
+use servers::grpc::{GrpcServer, GrpcServerConfig};
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdaptor;
 use servers::query_handler::sql::ServerSqlQueryHandlerAdaptor;
 use servers::server::Server;
 use servers::tls::TlsOption;
 use servers::Mode;
+use session::context::{ADMIN_ROLE, DEFAULT_USERNAME};
 use snafu::ResultExt;
 
-use crate::datanode::DatanodeOptions;
+use crate::datanode::{DatanodeOptions, DEFAULT_MYSQL_ADDR};
 use crate::error::Error::StartServer;
-use crate::error::{ParseAddrSnafu, Result, RuntimeResourceSnafu, StartServerSnafu};
+use crate::error::{
+    MissingClusterTokenSnafu, ParseAddrSnafu, Result, RuntimeResourceSnafu, StartServerSnafu,
+};
 use crate::instance::InstanceRef;
 
 pub mod grpc;
@@ -51,12 +56,26 @@ impl Services {
                 .context(RuntimeResourceSnafu)?,
         );
 
-        let mysql_server = match opts.mode {
-            Mode::Standalone => {
+        let mysql_server = match (&opts.mode, &opts.cluster_token) {
+            (Mode::Standalone, _) => {
                 info!("Disable MySQL server on datanode when running in standalone mode");
                 None
             }
-            Mode::Distributed => {
+            (Mode::Distributed, None) => {
+                // Serving without the token would expose the data of all databases.
+                if let Some(addr) = &opts.mysql_addr {
+                    return MissingClusterTokenSnafu { addr }.fail();
+                }
+                warn!("Disable MySQL server on datanode as no cluster token is configured");
+                None
+            }
+            (Mode::Distributed, Some(cluster_token)) => {
+                // The datanode serves the data of all databases, so only the cluster
+                // administrator knowing the cluster token is allowed to connect.
+                let user_provider: UserProviderRef = Arc::new(StaticUserProvider::new(vec![(
+                    format!("{DEFAULT_USERNAME}:{ADMIN_ROLE}"),
+                    cluster_token.as_str().as_bytes().to_vec(),
+                )]));
                 let mysql_io_runtime = Arc::new(
                     RuntimeBuilder::default()
                         .worker_threads(opts.mysql_runtime_size)
@@ -71,7 +90,7 @@ impl Services {
                     mysql_io_runtime,
                     Arc::new(MysqlSpawnRef::new(
                         ServerSqlQueryHandlerAdaptor::arc(instance.clone()),
-                        None,
+                        Some(user_provider),
                     )),
                     Arc::new(MysqlSpawnConfig::new(
                         tls.should_force_tls(),
@@ -86,11 +105,20 @@ impl Services {
             }
         };
 
+        let catalog_manager = instance.catalog_manager().clone();
         Ok(Self {
             grpc_server: GrpcServer::new(
                 ServerGrpcQueryHandlerAdaptor::arc(instance),
                 grpc_runtime,
-            ),
+            )
+            .with_change_stream(catalog_manager)
+            .with_config(GrpcServerConfig {
+                max_message_size: opts.rpc_max_message_size.0 as usize,
+                max_concurrent_streams: opts.rpc_max_concurrent_streams,
+                max_in_flight_requests_per_connection: opts.rpc_max_in_flight_requests,
+            })
+            .with_tls(opts.rpc_tls.clone())
+            .with_cluster_token(opts.cluster_token.clone()),
             mysql_server,
         })
     }
@@ -102,7 +130,7 @@ impl Services {
 
         let mut res = vec![self.grpc_server.start(grpc_addr)];
         if let Some(mysql_server) = &self.mysql_server {
-            let mysql_addr = &opts.mysql_addr;
+            let mysql_addr = opts.mysql_addr.as_deref().unwrap_or(DEFAULT_MYSQL_ADDR);
             let mysql_addr: SocketAddr = mysql_addr
                 .parse()
                 .context(ParseAddrSnafu { addr: mysql_addr })?;
@@ -145,6 +145,7 @@ mod tests {
             datatype: 1024,
             is_nullable: true,
             default_constraint: vec![],
+            comment: String::new(),
         };
         let result = column_def.try_as_column_schema();
         assert!(matches!(
@@ -157,6 +158,7 @@ mod tests {
             datatype: ColumnDataType::String as i32,
             is_nullable: true,
             default_constraint: vec![],
+            comment: String::new(),
         };
         let column_schema = column_def.try_as_column_schema().unwrap();
         assert_eq!(column_schema.name, "a");
@@ -169,11 +171,13 @@ mod tests {
             datatype: ColumnDataType::String as i32,
             is_nullable: true,
             default_constraint: default_constraint.clone().try_into().unwrap(),
+            comment: "a string column".to_string(),
         };
         let column_schema = column_def.try_as_column_schema().unwrap();
         assert_eq!(column_schema.name, "a");
         assert_eq!(column_schema.data_type, ConcreteDataType::string_datatype());
         assert!(column_schema.is_nullable());
+        assert_eq!(Some("a string column"), column_schema.comment());
         assert_eq!(
             default_constraint,
             *column_schema.default_constraint().unwrap()
@@ -187,24 +191,28 @@ mod tests {
                 datatype: ColumnDataType::String as i32,
                 is_nullable: false,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "ts".to_string(),
                 datatype: ColumnDataType::TimestampMillisecond as i32,
                 is_nullable: false,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "cpu".to_string(),
                 datatype: ColumnDataType::Float32 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
             ColumnDef {
                 name: "memory".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                comment: String::new(),
             },
         ];
         CreateTableExpr {
@@ -12,26 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use catalog::CatalogManagerRef;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::helper::DdlHistoryValue;
+use catalog::ingest_stats::{IngestStats, IngestStatsRef};
+use catalog::{ddl_history, format_full_table_name, CatalogManagerRef};
 use common_query::Output;
 use common_telemetry::error;
+use common_time::clock::{self, ClockRef};
+use metrics::counter;
 use query::query_engine::QueryEngineRef;
-use query::sql::{describe_table, explain, show_databases, show_tables};
+use query::sql::{
+    describe_table, explain, explain_ddl_output, show_create_table, show_databases,
+    show_table_status, show_tables,
+};
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
-use sql::statements::show::{ShowDatabases, ShowTables};
+use sql::statements::show::{ShowCreateTable, ShowDatabases, ShowTableStatus, ShowTables};
 use table::engine::{EngineContext, TableEngineRef, TableReference};
 use table::requests::*;
 use table::TableRef;
 
 use crate::error::{self, ExecuteSqlSnafu, GetTableSnafu, Result, TableNotFoundSnafu};
 use crate::instance::sql::table_idents_to_full_name;
+use crate::metric::{METRIC_INGEST_BYTES_TOTAL, METRIC_INGEST_ROWS_TOTAL};
+use crate::recycle_bin::RecycleBinRef;
 
 mod alter;
+mod analyze;
+mod check;
 mod create;
 mod drop_table;
+mod explain_ddl;
 mod insert;
 
 #[derive(Debug)]
@@ -40,18 +56,38 @@ pub enum SqlRequest {
     CreateTable(CreateTableRequest),
     CreateDatabase(CreateDatabaseRequest),
     Alter(AlterTableRequest),
+    AlterDatabase(AlterDatabaseRequest),
     DropTable(DropTableRequest),
+    UndropTable(UndropTableRequest),
+    AnalyzeTable(AnalyzeTableRequest),
+    CheckTable(CheckTableRequest),
     ShowDatabases(ShowDatabases),
     ShowTables(ShowTables),
+    ShowTableStatus(ShowTableStatus),
     DescribeTable(DescribeTable),
+    ShowCreateTable(ShowCreateTable),
     Explain(Box<Explain>),
+    /// Validates the wrapped `CreateTable` or `Alter` request without applying it.
+    ExplainDdl(Box<SqlRequest>),
 }
 
 // Handler to execute SQL except query
 pub struct SqlHandler {
     table_engine: TableEngineRef,
+    /// Other engines to create tables by `ENGINE = <name>`, by their names.
+    table_engines: HashMap<String, TableEngineRef>,
     catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
+    ingest_stats: IngestStatsRef,
+    /// Inserts with timestamps later than now plus this bound are rejected.
+    max_future_timestamp: Option<Duration>,
+    /// Max number of rows in the `VALUES` list of an insert statement.
+    max_insert_rows: Option<usize>,
+    /// Dropped tables are kept in the recycle bin if set, otherwise their data is left
+    /// in the storage.
+    recycle_bin: Option<RecycleBinRef>,
+    /// Clock to read the time tables are dropped or changed from.
+    clock: ClockRef,
 }
 
 impl SqlHandler {
@@ -62,29 +98,111 @@ impl SqlHandler {
     ) -> Self {
         Self {
             table_engine,
+            table_engines: HashMap::new(),
             catalog_manager,
             query_engine,
+            ingest_stats: Arc::new(IngestStats::default()),
+            max_future_timestamp: None,
+            max_insert_rows: None,
+            recycle_bin: None,
+            clock: clock::system_clock(),
         }
     }
 
+    /// Rejects inserts with timestamps more than `max_future_timestamp` later than now,
+    /// e.g. written by clients whose clocks drift.
+    pub fn with_max_future_timestamp(mut self, max_future_timestamp: Option<Duration>) -> Self {
+        self.max_future_timestamp = max_future_timestamp;
+        self
+    }
+
+    /// Rejects insert statements with more than `max_insert_rows` rows.
+    pub fn with_max_insert_rows(mut self, max_insert_rows: Option<usize>) -> Self {
+        self.max_insert_rows = max_insert_rows;
+        self
+    }
+
+    /// Registers another `table_engine`, tables are created by it if its name is given by
+    /// `ENGINE = <name>`.
+    pub fn with_table_engine(mut self, table_engine: TableEngineRef) -> Self {
+        self.table_engines
+            .insert(table_engine.name().to_string(), table_engine);
+        self
+    }
+
+    /// Keeps dropped tables in the `recycle_bin` so they could be recovered by
+    /// `UNDROP TABLE`.
+    pub fn with_recycle_bin(mut self, recycle_bin: RecycleBinRef) -> Self {
+        self.recycle_bin = Some(recycle_bin);
+        self
+    }
+
+    /// Replaces the system clock the handler reads the time from, e.g. by the clock of
+    /// the table engine.
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn ingest_stats(&self) -> &IngestStatsRef {
+        &self.ingest_stats
+    }
+
+    /// Records `rows` and `bytes` written to the table, both in the ingest statistics
+    /// and Prometheus metrics.
+    pub(crate) fn record_ingestion(&self, table_ref: &TableReference, rows: usize, bytes: usize) {
+        self.ingest_stats.record(
+            table_ref.catalog,
+            table_ref.schema,
+            table_ref.table,
+            rows as u64,
+            bytes as u64,
+        );
+        let table = table_ref.to_string();
+        counter!(METRIC_INGEST_ROWS_TOTAL, rows as u64, "table" => table.clone());
+        counter!(METRIC_INGEST_BYTES_TOTAL, bytes as u64, "table" => table);
+    }
+
     // TODO(LFC): Refactor consideration: a context awareness "Planner".
     // Now we have some query related state (like current using database in session context), maybe
     // we could create a new struct called `Planner` that stores context and handle these queries
     // there, instead of executing here in a "static" fashion.
     pub async fn execute(&self, request: SqlRequest, query_ctx: QueryContextRef) -> Result<Output> {
+        self.execute_statement(request, None, query_ctx).await
+    }
+
+    /// Executes the `request` converted from the SQL `statement`. DDL requests are recorded
+    /// into the DDL history with the statement, which is `None` for requests not from SQL.
+    pub async fn execute_statement(
+        &self,
+        request: SqlRequest,
+        statement: Option<String>,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let ddl = ddl_of_request(&request);
         let result = match request {
             SqlRequest::Insert(req) => self.insert(req).await,
             SqlRequest::CreateTable(req) => self.create_table(req).await,
             SqlRequest::CreateDatabase(req) => self.create_database(req).await,
             SqlRequest::Alter(req) => self.alter(req).await,
+            SqlRequest::AlterDatabase(req) => self.alter_database(req).await,
             SqlRequest::DropTable(req) => self.drop_table(req).await,
+            SqlRequest::UndropTable(req) => self.undrop_table(req).await,
+            SqlRequest::AnalyzeTable(req) => self.analyze_table(req).await,
+            SqlRequest::CheckTable(req) => self.check_table(req).await,
             SqlRequest::ShowDatabases(stmt) => {
-                show_databases(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
+                show_databases(stmt, self.catalog_manager.clone(), query_ctx.clone())
+                    .context(ExecuteSqlSnafu)
             }
             SqlRequest::ShowTables(stmt) => {
                 show_tables(stmt, self.catalog_manager.clone(), query_ctx.clone())
                     .context(ExecuteSqlSnafu)
             }
+            SqlRequest::ShowTableStatus(stmt) => {
+                show_table_status(stmt, self.catalog_manager.clone(), query_ctx.clone())
+                    .await
+                    .context(ExecuteSqlSnafu)
+            }
             SqlRequest::DescribeTable(stmt) => {
                 let (catalog, schema, table) =
                     table_idents_to_full_name(stmt.name(), query_ctx.clone())?;
@@ -97,20 +215,78 @@ impl SqlHandler {
                     })?;
                 describe_table(table).context(ExecuteSqlSnafu)
             }
+            SqlRequest::ShowCreateTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&stmt.table_name, query_ctx.clone())?;
+                let table = self
+                    .catalog_manager
+                    .table(&catalog, &schema, &table)
+                    .context(error::CatalogSnafu)?
+                    .with_context(|| TableNotFoundSnafu {
+                        table_name: stmt.table_name.to_string(),
+                    })?;
+                show_create_table(table).context(ExecuteSqlSnafu)
+            }
             SqlRequest::Explain(stmt) => {
                 explain(stmt, self.query_engine.clone(), query_ctx.clone())
                     .await
                     .context(ExecuteSqlSnafu)
             }
+            SqlRequest::ExplainDdl(req) => self
+                .explain_ddl(*req)
+                .await
+                .and_then(|items| explain_ddl_output(items).context(ExecuteSqlSnafu)),
         };
         if let Err(e) = &result {
             error!(e; "{query_ctx}");
         }
+        if let Some((operation, object_name)) = ddl {
+            let value = DdlHistoryValue {
+                timestamp_millis: self.clock.now_millis(),
+                id: DdlHistoryValue::new_id(),
+                username: query_ctx.current_user().username().to_string(),
+                operation: operation.to_string(),
+                object_name,
+                statement,
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            };
+            // Failing to record the history should not fail the DDL itself.
+            if let Err(e) = ddl_history::insert_ddl_history(&self.catalog_manager, &value).await {
+                error!(e; "Failed to record DDL history: {:?}", value);
+            }
+        }
         result
     }
 
+    /// Returns the engine named `name`, or the default engine if `name` is `None`.
+    pub(crate) fn table_engine_by_name(&self, name: Option<&str>) -> Result<TableEngineRef> {
+        match name {
+            None => Ok(self.table_engine.clone()),
+            Some(name) if name == self.table_engine.name() => Ok(self.table_engine.clone()),
+            Some(name) => {
+                self.table_engines
+                    .get(name)
+                    .cloned()
+                    .with_context(|| error::NotSupportedSnafu {
+                        feat: format!("table engine {name}"),
+                    })
+            }
+        }
+    }
+
+    /// Returns the engine having the table, the default engine if none has it.
+    pub(crate) fn table_engine_of(&self, table_ref: &TableReference) -> TableEngineRef {
+        let ctx = EngineContext::default();
+        self.table_engines
+            .values()
+            .find(|engine| engine.table_exists(&ctx, table_ref))
+            .cloned()
+            .unwrap_or_else(|| self.table_engine.clone())
+    }
+
     pub(crate) fn get_table(&self, table_ref: &TableReference) -> Result<TableRef> {
-        self.table_engine
+        self.table_engine_of(table_ref)
             .get_table(&EngineContext::default(), table_ref)
             .with_context(|_| GetTableSnafu {
                 table_name: table_ref.to_string(),
@@ -125,6 +301,48 @@ impl SqlHandler {
     }
 }
 
+/// Returns the operation and full name of the object of the DDL request, or `None` if the
+/// request is not a DDL.
+fn ddl_of_request(request: &SqlRequest) -> Option<(&'static str, String)> {
+    let (operation, object_name) = match request {
+        SqlRequest::CreateTable(req) => (
+            "CREATE TABLE",
+            format_full_table_name(&req.catalog_name, &req.schema_name, &req.table_name),
+        ),
+        SqlRequest::CreateDatabase(req) => (
+            "CREATE DATABASE",
+            format!("{}.{}", req.catalog_name, req.db_name),
+        ),
+        SqlRequest::Alter(req) => (
+            "ALTER TABLE",
+            format_full_table_name(&req.catalog_name, &req.schema_name, &req.table_name),
+        ),
+        SqlRequest::AlterDatabase(req) => (
+            "ALTER DATABASE",
+            format!("{}.{}", req.catalog_name, req.db_name),
+        ),
+        SqlRequest::DropTable(req) => (
+            "DROP TABLE",
+            format_full_table_name(&req.catalog_name, &req.schema_name, &req.table_name),
+        ),
+        SqlRequest::UndropTable(req) => (
+            "UNDROP TABLE",
+            format_full_table_name(&req.catalog_name, &req.schema_name, &req.table_name),
+        ),
+        _ => return None,
+    };
+    Some((operation, object_name))
+}
+
+/// Returns the memory size of values in the insert request.
+pub(crate) fn insert_request_bytes(request: &InsertRequest) -> usize {
+    request
+        .columns_values
+        .values()
+        .map(|vector| vector.memory_size())
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use std::any::Any;
@@ -12,13 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use catalog::RenameTableRequest;
+use catalog::{AlterSchemaRequest, RenameTableRequest};
 use common_query::Output;
+use common_telemetry::info;
 use snafu::prelude::*;
-use sql::statements::alter::{AlterTable, AlterTableOperation};
+use sql::statements::alter::{
+    AlterDatabase, AlterDatabaseOperation, AlterTable, AlterTableOperation,
+};
 use sql::statements::column_def_to_schema;
 use table::engine::{EngineContext, TableReference};
-use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest};
+use table::requests::{
+    AddColumnRequest, AlterDatabaseKind, AlterDatabaseRequest, AlterKind, AlterTableRequest,
+};
 
 use crate::error::{self, Result};
 use crate::sql::SqlHandler;
@@ -35,20 +40,20 @@ impl SqlHandler {
 
         let full_table_name = table_ref.to_string();
 
+        let table_engine = self.table_engine_of(&table_ref);
         ensure!(
-            self.table_engine.table_exists(&ctx, &table_ref),
+            table_engine.table_exists(&ctx, &table_ref),
             error::TableNotFoundSnafu {
                 table_name: &full_table_name,
             }
         );
         let is_rename = req.is_rename_table();
-        let table =
-            self.table_engine
-                .alter_table(&ctx, req)
-                .await
-                .context(error::AlterTableSnafu {
-                    table_name: full_table_name,
-                })?;
+        let table = table_engine
+            .alter_table(&ctx, req)
+            .await
+            .context(error::AlterTableSnafu {
+                table_name: full_table_name,
+            })?;
         if is_rename {
             let table_info = &table.table_info();
             let rename_table_req = RenameTableRequest {
@@ -67,6 +72,46 @@ impl SqlHandler {
         Ok(Output::AffectedRows(0))
     }
 
+    pub(crate) async fn alter_database(&self, req: AlterDatabaseRequest) -> Result<Output> {
+        let request = AlterSchemaRequest {
+            catalog: req.catalog_name.clone(),
+            schema: req.db_name.clone(),
+            alter_kind: req.alter_kind,
+        };
+        let _ = self
+            .catalog_manager
+            .alter_schema(request)
+            .await
+            .context(error::AlterDatabaseSnafu { name: &req.db_name })?;
+
+        info!("Successfully altered database: {}", req.db_name);
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(crate) fn alter_database_to_request(
+        &self,
+        alter_database: AlterDatabase,
+        catalog_name: String,
+    ) -> AlterDatabaseRequest {
+        let alter_kind = match alter_database.alter_operation() {
+            AlterDatabaseOperation::RenameDatabase { new_name } => {
+                AlterDatabaseKind::RenameDatabase {
+                    new_name: new_name.clone(),
+                }
+            }
+            operation @ AlterDatabaseOperation::SetMetadata { .. } => {
+                AlterDatabaseKind::SetMetadata {
+                    metadata: operation.metadata(),
+                }
+            }
+        };
+        AlterDatabaseRequest {
+            catalog_name,
+            db_name: alter_database.name().to_string(),
+            alter_kind,
+        }
+    }
+
     pub(crate) fn alter_to_request(
         &self,
         alter_table: AlterTable,
@@ -79,12 +124,16 @@ impl SqlHandler {
                 }
                 .fail()
             }
-            AlterTableOperation::AddColumn { column_def } => AlterKind::AddColumns {
+            AlterTableOperation::AddColumn {
+                column_def,
+                add_if_not_exists,
+            } => AlterKind::AddColumns {
                 columns: vec![AddColumnRequest {
                     column_schema: column_def_to_schema(column_def, false)
                         .context(error::ParseSqlSnafu)?,
                     // FIXME(dennis): supports adding key column
                     is_key: false,
+                    add_if_not_exists: *add_if_not_exists,
                 }],
             },
             AlterTableOperation::DropColumn { name } => AlterKind::DropColumns {
@@ -93,6 +142,18 @@ impl SqlHandler {
             AlterTableOperation::RenameTable { new_table_name } => AlterKind::RenameTable {
                 new_table_name: new_table_name.clone(),
             },
+            AlterTableOperation::SetReadOnly { read_only } => AlterKind::SetReadOnly {
+                read_only: *read_only,
+            },
+            AlterTableOperation::SetComment { comment } => AlterKind::SetComment {
+                comment: (!comment.is_empty()).then(|| comment.clone()),
+            },
+            AlterTableOperation::SetColumnComment { name, comment } => {
+                AlterKind::SetColumnComment {
+                    name: name.value.clone(),
+                    comment: comment.clone(),
+                }
+            }
         };
         Ok(AlterTableRequest {
             catalog_name: table_ref.catalog.to_string(),
@@ -178,4 +239,51 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_alter_to_request_with_setting_read_only() {
+        let handler = create_mock_sql_handler().await;
+        let alter_table = parse_sql("ALTER TABLE test_table SET READ_ONLY = true;");
+        let req = handler
+            .alter_to_request(
+                alter_table,
+                TableReference::full("greptime", "public", "test_table"),
+            )
+            .unwrap();
+        assert_eq!(req.table_name, "test_table");
+        assert_matches!(req.alter_kind, AlterKind::SetReadOnly { read_only: true });
+    }
+
+    #[tokio::test]
+    async fn test_alter_to_request_with_setting_comment() {
+        let handler = create_mock_sql_handler().await;
+        let alter_table = parse_sql("ALTER TABLE test_table COMMENT 'cpu usage of hosts';");
+        let req = handler
+            .alter_to_request(
+                alter_table,
+                TableReference::full("greptime", "public", "test_table"),
+            )
+            .unwrap();
+        match req.alter_kind {
+            AlterKind::SetComment { comment } => {
+                assert_eq!(Some("cpu usage of hosts"), comment.as_deref());
+            }
+            _ => unreachable!(),
+        }
+
+        let alter_table = parse_sql("ALTER TABLE test_table MODIFY COLUMN cpu COMMENT '';");
+        let req = handler
+            .alter_to_request(
+                alter_table,
+                TableReference::full("greptime", "public", "test_table"),
+            )
+            .unwrap();
+        match req.alter_kind {
+            AlterKind::SetColumnComment { name, comment } => {
+                assert_eq!("cpu", name);
+                assert!(comment.is_empty());
+            }
+            _ => unreachable!(),
+        }
+    }
 }
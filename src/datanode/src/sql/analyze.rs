@@ -0,0 +1,259 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use common_error::prelude::BoxedError;
+use common_query::physical_plan::SessionContext;
+use common_query::Output;
+use common_telemetry::info;
+use datatypes::value::Value;
+use futures::TryStreamExt;
+use snafu::ResultExt;
+use table::engine::{EngineContext, TableReference};
+use table::metadata::{ColumnStatistics, TableColumnStatistics};
+use table::requests::{AlterKind, AlterTableRequest, AnalyzeTableRequest};
+use table::TableRef;
+
+use crate::error::{self, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    /// Scans the whole table to collect statistics of its columns and stores them in the
+    /// table meta. Returns the number of rows scanned.
+    pub(crate) async fn analyze_table(&self, req: AnalyzeTableRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table_name = table_ref.to_string();
+        let table = self.get_table(&table_ref)?;
+        let statistics = collect_column_statistics(&table, &table_name).await?;
+        let row_count = statistics.row_count;
+
+        let alter_req = AlterTableRequest {
+            catalog_name: req.catalog_name.clone(),
+            schema_name: req.schema_name.clone(),
+            table_name: req.table_name.clone(),
+            alter_kind: AlterKind::SetColumnStatistics { statistics },
+        };
+        self.table_engine
+            .alter_table(&EngineContext::default(), alter_req)
+            .await
+            .context(error::AlterTableSnafu {
+                table_name: &table_name,
+            })?;
+
+        info!("Analyzed table {}, rows: {}", table_name, row_count);
+
+        Ok(Output::AffectedRows(row_count as usize))
+    }
+}
+
+/// Number of bits of the hash used to select a register of [HyperLogLog].
+const HLL_PRECISION: u32 = 14;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// A HyperLogLog sketch estimating the number of distinct values with a
+/// standard error of about 0.8% in 16KiB, no matter how many values a column has.
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // Sets a guard bit so the rank never exceeds `64 - HLL_PRECISION + 1`.
+        let remaining = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0usize), |(sum, zeros), register| {
+                (
+                    sum + 2f64.powi(-(*register as i32)),
+                    zeros + (*register == 0) as usize,
+                )
+            });
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// Hashes a non-null value. [Value] doesn't implement [Hash] because of its float
+/// variants, so nested types fall back to hashing their string representation.
+fn hash_value(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    match value {
+        Value::Boolean(v) => v.hash(&mut hasher),
+        Value::UInt8(v) => v.hash(&mut hasher),
+        Value::UInt16(v) => v.hash(&mut hasher),
+        Value::UInt32(v) => v.hash(&mut hasher),
+        Value::UInt64(v) => v.hash(&mut hasher),
+        Value::Int8(v) => v.hash(&mut hasher),
+        Value::Int16(v) => v.hash(&mut hasher),
+        Value::Int32(v) => v.hash(&mut hasher),
+        Value::Int64(v) => v.hash(&mut hasher),
+        Value::Float32(v) => v.0.to_bits().hash(&mut hasher),
+        Value::Float64(v) => v.0.to_bits().hash(&mut hasher),
+        Value::String(v) => v.as_utf8().hash(&mut hasher),
+        Value::Binary(v) => v[..].hash(&mut hasher),
+        Value::Date(v) => v.val().hash(&mut hasher),
+        Value::DateTime(v) => v.val().hash(&mut hasher),
+        Value::Timestamp(v) => v.value().hash(&mut hasher),
+        Value::Null | Value::Decimal128(_) | Value::List(_) | Value::Histogram(_) => {
+            value.to_string().hash(&mut hasher)
+        }
+    }
+    hasher.finish()
+}
+
+/// Accumulates the values of a column.
+#[derive(Default)]
+struct ColumnAccumulator {
+    distinct: HyperLogLog,
+    null_count: u64,
+    min_value: Option<Value>,
+    max_value: Option<Value>,
+}
+
+impl ColumnAccumulator {
+    fn update(&mut self, value: Value) {
+        if value.is_null() {
+            self.null_count += 1;
+            return;
+        }
+
+        self.distinct.insert_hash(hash_value(&value));
+        if self.min_value.as_ref().map_or(true, |min| value < *min) {
+            self.min_value = Some(value.clone());
+        }
+        if self.max_value.as_ref().map_or(true, |max| value > *max) {
+            self.max_value = Some(value);
+        }
+    }
+
+    fn finish(self) -> ColumnStatistics {
+        ColumnStatistics {
+            distinct_count: self.distinct.estimate(),
+            null_count: self.null_count,
+            min_value: self.min_value.unwrap_or(Value::Null),
+            max_value: self.max_value.unwrap_or(Value::Null),
+        }
+    }
+}
+
+async fn collect_column_statistics(
+    table: &TableRef,
+    table_name: &str,
+) -> Result<TableColumnStatistics> {
+    let schema = table.schema();
+    let mut accumulators = schema
+        .column_schemas()
+        .iter()
+        .map(|_| ColumnAccumulator::default())
+        .collect::<Vec<_>>();
+    let mut row_count = 0;
+
+    let plan = table
+        .scan(None, &[], None)
+        .await
+        .context(error::ScanTableSnafu { table_name })?;
+    let session_ctx = SessionContext::new();
+    for partition in 0..plan.output_partitioning().partition_count() {
+        let mut stream = plan
+            .execute(partition, session_ctx.task_ctx())
+            .map_err(BoxedError::new)
+            .context(error::ReadTableSnafu { table_name })?;
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ReadTableSnafu { table_name })?
+        {
+            row_count += batch.num_rows() as u64;
+            for (accumulator, column) in accumulators.iter_mut().zip(batch.columns()) {
+                for i in 0..column.len() {
+                    accumulator.update(column.get(i));
+                }
+            }
+        }
+    }
+
+    let columns = schema
+        .column_schemas()
+        .iter()
+        .zip(accumulators)
+        .map(|(column_schema, accumulator)| (column_schema.name.clone(), accumulator.finish()))
+        .collect::<HashMap<_, _>>();
+    Ok(TableColumnStatistics::new(row_count, columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_accumulator() {
+        let mut accumulator = ColumnAccumulator::default();
+        for i in 0..100_000i64 {
+            accumulator.update(Value::Int64(i % 50_000));
+        }
+        accumulator.update(Value::Null);
+
+        let statistics = accumulator.finish();
+        assert_eq!(1, statistics.null_count);
+        assert_eq!(Value::Int64(0), statistics.min_value);
+        assert_eq!(Value::Int64(49_999), statistics.max_value);
+        let error = (statistics.distinct_count as f64 - 50_000.0).abs() / 50_000.0;
+        assert!(
+            error < 0.05,
+            "distinct_count: {}",
+            statistics.distinct_count
+        );
+
+        let mut accumulator = ColumnAccumulator::default();
+        for s in ["a", "b", "a", "c"] {
+            accumulator.update(Value::from(s));
+        }
+        let statistics = accumulator.finish();
+        assert_eq!(3, statistics.distinct_count);
+        assert_eq!(Value::from("a"), statistics.min_value);
+        assert_eq!(Value::from("c"), statistics.max_value);
+    }
+}
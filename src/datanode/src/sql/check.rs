@@ -0,0 +1,70 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::Output;
+use common_telemetry::info;
+use query::sql::check_table_output;
+use snafu::ResultExt;
+use table::engine::TableReference;
+use table::requests::CheckTableRequest;
+
+use crate::error::{self, ExecuteSqlSnafu, Result};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    /// Checks whether files referenced by the table exist in the storage, one row is
+    /// returned for each missing or orphan file.
+    pub(crate) async fn check_table(&self, req: CheckTableRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table_name = table_ref.to_string();
+        let table = self.get_table(&table_ref)?;
+        let stats = table
+            .check_files(req.repair)
+            .await
+            .context(error::CheckTableFilesSnafu {
+                table_name: &table_name,
+            })?;
+
+        let mut messages = Vec::new();
+        for stat in &stats {
+            for file in &stat.missing_files {
+                messages.push(("error".to_string(), format!("Missing file {file}")));
+            }
+            for file in &stat.orphan_files {
+                messages.push(("warning".to_string(), format!("Orphan file {file}")));
+            }
+            if stat.repaired {
+                messages.push((
+                    "status".to_string(),
+                    format!("Removed {} missing files", stat.missing_files.len()),
+                ));
+            }
+        }
+        if messages.is_empty() {
+            messages.push(("status".to_string(), "OK".to_string()));
+        }
+
+        info!(
+            "Checked files of table {}, repair: {}, stats: {:?}",
+            table_name, req.repair, stats
+        );
+
+        let op = if req.repair { "repair" } else { "check" };
+        check_table_output(&table_name, op, messages).context(ExecuteSqlSnafu)
+    }
+}
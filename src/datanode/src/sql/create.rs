@@ -16,7 +16,6 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use catalog::{RegisterSchemaRequest, RegisterTableRequest};
-use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_query::Output;
 use common_telemetry::tracing::info;
 use common_telemetry::tracing::log::error;
@@ -40,9 +39,21 @@ use crate::sql::SqlHandler;
 impl SqlHandler {
     pub(crate) async fn create_database(&self, req: CreateDatabaseRequest) -> Result<Output> {
         let schema = req.db_name;
+        if req.create_if_not_exists
+            && self
+                .catalog_manager
+                .schema(&req.catalog_name, &schema)
+                .context(CatalogSnafu)?
+                .is_some()
+        {
+            info!("Database {:?} already exists, skip creating", schema);
+            return Ok(Output::AffectedRows(0));
+        }
+
         let reg_req = RegisterSchemaRequest {
-            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            catalog: req.catalog_name,
             schema: schema.clone(),
+            default_table_options: req.options,
         };
         let success = self
             .catalog_manager
@@ -61,7 +72,7 @@ impl SqlHandler {
         Ok(Output::AffectedRows(1))
     }
 
-    pub(crate) async fn create_table(&self, req: CreateTableRequest) -> Result<Output> {
+    pub(crate) async fn create_table(&self, mut req: CreateTableRequest) -> Result<Output> {
         let ctx = EngineContext {};
         // first check if catalog and schema exist
         let catalog = self
@@ -77,7 +88,7 @@ impl SqlHandler {
                     name: &req.catalog_name,
                 }
             })?;
-        catalog
+        let schema = catalog
             .schema(&req.schema_name)
             .context(CatalogSnafu)?
             .with_context(|| {
@@ -89,11 +100,35 @@ impl SqlHandler {
                     name: &req.schema_name,
                 }
             })?;
+        // Tables opened in background are not registered yet, creating them again duplicates
+        // the tables.
+        self.catalog_manager
+            .ensure_table_opened(&req.catalog_name, &req.schema_name, &req.table_name)
+            .context(CatalogSnafu)?;
+        if req.create_if_not_exists
+            && schema
+                .table(&req.table_name)
+                .context(CatalogSnafu)?
+                .is_some()
+        {
+            info!(
+                "Table {}.{}.{} already exists, skip creating",
+                req.catalog_name, req.schema_name, req.table_name
+            );
+            return Ok(Output::AffectedRows(0));
+        }
+        // Tables inherit the default options of the schema unless they override them.
+        for (key, value) in schema.default_table_options() {
+            req.table_options.entry(key).or_insert(value);
+        }
+
+        // The engine is not an option of the table, but the engine to create it.
+        let engine_name = req.table_options.remove(ENGINE_OPTION);
+        let table_engine = self.table_engine_by_name(engine_name.as_deref())?;
 
         // determine catalog and schema from the very beginning
         let table_name = req.table_name.clone();
-        let table = self
-            .table_engine
+        let table = table_engine
             .create_table(&ctx, req)
             .await
             .with_context(|_| CreateTableSnafu {
@@ -211,12 +246,12 @@ impl SqlHandler {
             catalog_name: table_ref.catalog.to_string(),
             schema_name: table_ref.schema.to_string(),
             table_name: table_ref.table.to_string(),
-            desc: None,
+            desc: stmt.comment.clone(),
             schema,
             region_numbers: vec![0],
             primary_key_indices: primary_keys,
             create_if_not_exists: stmt.if_not_exists,
-            table_options: HashMap::new(),
+            table_options: stmt.table_options(),
         };
         Ok(request)
     }
@@ -269,6 +304,33 @@ mod tests {
         assert_eq!(4, c.schema.column_schemas().len());
     }
 
+    #[tokio::test]
+    pub async fn test_create_with_comment_to_request() {
+        let handler = create_mock_sql_handler().await;
+        let parsed_stmt = sql_to_statement(
+            r#"create table demo_table(
+                       host string comment 'host name',
+                       ts timestamp,
+                       cpu double,
+                       TIME INDEX (ts),
+                       PRIMARY KEY(host)) engine=mito comment 'cpu usage of hosts';"#,
+        );
+        let c = handler
+            .create_to_request(42, parsed_stmt, &TableReference::bare("demo_table"))
+            .unwrap();
+        assert_eq!(Some("cpu usage of hosts"), c.desc.as_deref());
+        assert_eq!(
+            Some("host name"),
+            c.schema.column_schema_by_name("host").unwrap().comment()
+        );
+        assert!(c
+            .schema
+            .column_schema_by_name("cpu")
+            .unwrap()
+            .comment()
+            .is_none());
+    }
+
     #[tokio::test]
     pub async fn test_primary_key_not_specified() {
         let handler = create_mock_sql_handler().await;
@@ -12,15 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use catalog::DeregisterTableRequest;
+use catalog::{DeregisterTableRequest, RegisterTableRequest};
 use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_telemetry::info;
-use snafu::ResultExt;
+use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableReference};
-use table::requests::DropTableRequest;
+use table::requests::{DropTableRequest, OpenTableRequest, UndropTableRequest};
 
 use crate::error::{self, Result};
+use crate::recycle_bin::DroppedTable;
 use crate::sql::SqlHandler;
 
 impl SqlHandler {
@@ -37,6 +38,32 @@ impl SqlHandler {
             table: &req.table_name,
         };
         let table_full_name = table_reference.to_string();
+        let table_engine = self.table_engine_of(&table_reference);
+
+        let dropped_table = match self
+            .catalog_manager
+            .table(&req.catalog_name, &req.schema_name, &req.table_name)
+            .context(error::CatalogSnafu)?
+        {
+            Some(table) => {
+                // Virtual tables, e.g. tables in `pg_catalog`, are not created by any engine.
+                let table_info =
+                    table
+                        .try_table_info()
+                        .with_context(|| error::NotSupportedSnafu {
+                            feat: format!("DROP TABLE on virtual table {table_full_name}"),
+                        })?;
+                Some(DroppedTable {
+                    catalog_name: req.catalog_name.clone(),
+                    schema_name: req.schema_name.clone(),
+                    table_name: req.table_name.clone(),
+                    table_id: table_info.ident.table_id,
+                    region_numbers: table_info.meta.region_numbers.clone(),
+                    dropped_at: self.clock.now_millis(),
+                })
+            }
+            None => None,
+        };
 
         self.catalog_manager
             .deregister_table(deregister_table_req)
@@ -45,9 +72,14 @@ impl SqlHandler {
             .context(error::DropTableSnafu {
                 table_name: table_full_name.clone(),
             })?;
+        self.ingest_stats.remove(
+            table_reference.catalog,
+            table_reference.schema,
+            table_reference.table,
+        );
 
         let ctx = EngineContext {};
-        self.table_engine()
+        table_engine
             .drop_table(&ctx, req)
             .await
             .map_err(BoxedError::new)
@@ -55,8 +87,95 @@ impl SqlHandler {
                 table_name: table_full_name.clone(),
             })?;
 
+        // Only tables of the default engine could be opened again from the recycle bin.
+        let recyclable = table_engine.name() == self.table_engine.name();
+        if let (Some(recycle_bin), Some(dropped_table), true) =
+            (&self.recycle_bin, dropped_table, recyclable)
+        {
+            recycle_bin.put(dropped_table).await?;
+        }
+
         info!("Successfully dropped table: {}", table_full_name);
 
         Ok(Output::AffectedRows(1))
     }
+
+    /// Recovers the latest dropped table with the name from the recycle bin.
+    pub async fn undrop_table(&self, req: UndropTableRequest) -> Result<Output> {
+        let table_reference = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table_full_name = table_reference.to_string();
+
+        let recycle_bin = self
+            .recycle_bin
+            .as_ref()
+            .context(error::NotSupportedSnafu {
+                feat: "UNDROP TABLE in distributed mode",
+            })?;
+        // Holds the lock until the table is recovered, so it's not purged meanwhile.
+        let _guard = recycle_bin.lock().await;
+        let dropped_table = recycle_bin
+            .latest(&req.catalog_name, &req.schema_name, &req.table_name)
+            .context(error::DroppedTableNotFoundSnafu {
+                table_name: &table_full_name,
+            })?;
+        self.catalog_manager
+            .ensure_table_opened(&req.catalog_name, &req.schema_name, &req.table_name)
+            .context(error::CatalogSnafu)?;
+        ensure!(
+            self.catalog_manager
+                .table(&req.catalog_name, &req.schema_name, &req.table_name)
+                .context(error::CatalogSnafu)?
+                .is_none(),
+            error::TableExistsSnafu {
+                table_name: &table_full_name,
+            }
+        );
+
+        let table_id = dropped_table.table_id;
+        let open_table_req = OpenTableRequest {
+            catalog_name: req.catalog_name.clone(),
+            schema_name: req.schema_name.clone(),
+            table_name: req.table_name.clone(),
+            table_id,
+            region_numbers: dropped_table.region_numbers,
+        };
+        let table = self
+            .table_engine()
+            .open_table(&EngineContext::default(), open_table_req)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::UndropTableSnafu {
+                table_name: &table_full_name,
+            })?
+            .context(error::DroppedTableNotFoundSnafu {
+                table_name: &table_full_name,
+            })?;
+
+        let register_table_req = RegisterTableRequest {
+            catalog: req.catalog_name,
+            schema: req.schema_name,
+            table_name: req.table_name,
+            table_id,
+            table,
+        };
+        self.catalog_manager
+            .register_table(register_table_req)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::UndropTableSnafu {
+                table_name: &table_full_name,
+            })?;
+        recycle_bin.remove(table_id).await?;
+
+        info!(
+            "Successfully undropped table: {}, table id: {}",
+            table_full_name, table_id
+        );
+
+        Ok(Output::AffectedRows(1))
+    }
 }
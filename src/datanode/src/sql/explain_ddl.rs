@@ -0,0 +1,123 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use query::sql::estimated_affected_rows;
+use snafu::{ensure, OptionExt, ResultExt};
+use table::engine::{EngineContext, TableReference};
+use table::requests::{AlterKind, AlterTableRequest, CreateTableRequest};
+
+use crate::error::{self, CatalogSnafu, Result};
+use crate::sql::{SqlHandler, SqlRequest};
+
+impl SqlHandler {
+    /// Validates the DDL request against current metadata, and returns the `(item, detail)`
+    /// pairs describing what the request would do. Nothing is applied.
+    pub(crate) async fn explain_ddl(&self, req: SqlRequest) -> Result<Vec<(String, String)>> {
+        match req {
+            SqlRequest::CreateTable(req) => self.explain_create_table(req),
+            SqlRequest::Alter(req) => self.explain_alter(req).await,
+            req => error::InvalidSqlSnafu {
+                msg: format!("EXPLAIN DDL does not support request: {req:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    fn explain_create_table(&self, req: CreateTableRequest) -> Result<Vec<(String, String)>> {
+        let schema = self
+            .catalog_manager
+            .catalog(&req.catalog_name)
+            .context(CatalogSnafu)?
+            .context(error::CatalogNotFoundSnafu {
+                name: &req.catalog_name,
+            })?
+            .schema(&req.schema_name)
+            .context(CatalogSnafu)?
+            .context(error::SchemaNotFoundSnafu {
+                name: &req.schema_name,
+            })?;
+
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let full_table_name = table_ref.to_string();
+        let exists = schema.table_exist(&req.table_name).context(CatalogSnafu)?;
+        ensure!(
+            !exists || req.create_if_not_exists,
+            error::TableExistsSnafu {
+                table_name: &full_table_name,
+            }
+        );
+        if exists {
+            return Ok(vec![(
+                "Action".to_string(),
+                format!("none, table {full_table_name} already exists"),
+            )]);
+        }
+
+        let mut items = vec![(
+            "Action".to_string(),
+            format!("CREATE TABLE {full_table_name}"),
+        )];
+        items.extend(
+            req.region_numbers
+                .iter()
+                .map(|region| (format!("Region {region}"), "local datanode".to_string())),
+        );
+        items.push(("Affected rows".to_string(), "0".to_string()));
+        Ok(items)
+    }
+
+    async fn explain_alter(&self, req: AlterTableRequest) -> Result<Vec<(String, String)>> {
+        let table_ref = TableReference::full(&req.catalog_name, &req.schema_name, &req.table_name);
+        let full_table_name = table_ref.to_string();
+        let table = self.get_table(&table_ref)?;
+
+        // Builds the new table meta to check the alteration is applicable.
+        let table_info = table.table_info();
+        let _ = table_info
+            .meta
+            .builder_with_alter_kind(&req.table_name, &req.alter_kind)
+            .context(error::AlterTableSnafu {
+                table_name: &full_table_name,
+            })?;
+        if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            let new_table_ref =
+                TableReference::full(&req.catalog_name, &req.schema_name, new_table_name);
+            ensure!(
+                !self
+                    .table_engine
+                    .table_exists(&EngineContext::default(), &new_table_ref),
+                error::TableExistsSnafu {
+                    table_name: new_table_ref.to_string(),
+                }
+            );
+        }
+
+        let mut items = vec![(
+            "Action".to_string(),
+            format!("ALTER TABLE {full_table_name} {}", req.alter_kind),
+        )];
+        items.extend(
+            table_info
+                .meta
+                .region_numbers
+                .iter()
+                .map(|region| (format!("Region {region}"), "local datanode".to_string())),
+        );
+        items.push((
+            "Affected rows".to_string(),
+            estimated_affected_rows(table.statistics()),
+        ));
+        Ok(items)
+    }
+}
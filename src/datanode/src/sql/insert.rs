@@ -13,25 +13,27 @@
 // limitations under the License.
 
 use catalog::CatalogManagerRef;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, MIN_USER_TABLE_ID, SYSTEM_CATALOG_NAME};
 use common_query::Output;
+use common_time::Timestamp;
+use datatypes::arrow::compute;
 use datatypes::data_type::DataType;
-use datatypes::schema::ColumnSchema;
-use datatypes::vectors::MutableVector;
+use datatypes::error::ArrowComputeSnafu;
+use datatypes::value::ValueRef;
+use datatypes::vectors::Helper;
+use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
-use sql::ast::Value as SqlValue;
 use sql::statements::insert::Insert;
-use sql::statements::{self};
 use table::engine::TableReference;
 use table::requests::*;
+use table::TableRef;
 
 use crate::error::{
-    CatalogSnafu, ColumnDefaultValueSnafu, ColumnNoneDefaultValueSnafu, ColumnNotFoundSnafu,
-    ColumnValuesNumberMismatchSnafu, InsertSnafu, ParseSqlSnafu, ParseSqlValueSnafu, Result,
-    TableNotFoundSnafu,
+    CatalogSnafu, ColumnNotFoundSnafu, FutureTimestampSnafu, IncorrectInternalStateSnafu,
+    InsertSnafu, ParseSqlValueSnafu, PermissionDeniedSnafu, Result, TableNotFoundSnafu,
+    TooManyInsertRowsSnafu, VectorComputationSnafu,
 };
-use crate::sql::{SqlHandler, SqlRequest};
-
-const DEFAULT_PLACEHOLDER_VALUE: &str = "default";
+use crate::sql::{insert_request_bytes, SqlHandler, SqlRequest};
 
 impl SqlHandler {
     pub(crate) async fn insert(&self, req: InsertRequest) -> Result<Output> {
@@ -44,14 +46,128 @@ impl SqlHandler {
         };
 
         let table = self.get_table(&table_ref)?;
+        self.check_future_timestamps(&table, &req)?;
 
+        let bytes = insert_request_bytes(&req);
         let affected_rows = table.insert(req).await.with_context(|_| InsertSnafu {
             table_name: table_ref.to_string(),
         })?;
+        self.record_ingestion(&table_ref, affected_rows, bytes);
 
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    /// Inserts the rows written by scripts executed by the user in `query_ctx`, whose
+    /// columns are cast to the types of the table and checked as the inserts from SQL.
+    /// Returns the number of rows inserted.
+    ///
+    /// Scripts can't write system tables, e.g. the table storing scripts, and only admins
+    /// could write tables outside the database the user connects to.
+    pub(crate) async fn insert_from_script(
+        &self,
+        mut req: InsertRequest,
+        query_ctx: QueryContextRef,
+    ) -> Result<usize> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let user = query_ctx.current_user();
+        ensure!(
+            user.is_admin()
+                || (req.catalog_name == query_ctx.current_catalog()
+                    && req.schema_name == query_ctx.current_schema()),
+            PermissionDeniedSnafu {
+                reason: format!(
+                    "user {} is not allowed to write table {} in scripts",
+                    user.username(),
+                    table_ref
+                ),
+            }
+        );
+        let table = self.get_table(&table_ref)?;
+        let is_system_table = req.catalog_name == SYSTEM_CATALOG_NAME
+            || req.schema_name == INFORMATION_SCHEMA_NAME
+            || table
+                .try_table_info()
+                .map_or(true, |info| info.ident.table_id < MIN_USER_TABLE_ID);
+        ensure!(
+            !is_system_table,
+            PermissionDeniedSnafu {
+                reason: format!("system table {table_ref} can't be written in scripts"),
+            }
+        );
+        let schema = table.schema();
+        for (column_name, vector) in req.columns_values.iter_mut() {
+            let column_schema =
+                schema
+                    .column_schema_by_name(column_name)
+                    .with_context(|| ColumnNotFoundSnafu {
+                        table_name: table_ref.to_string(),
+                        column_name,
+                    })?;
+            if vector.data_type() != column_schema.data_type {
+                let cast = compute::cast(
+                    &vector.to_arrow_array(),
+                    &column_schema.data_type.as_arrow_type(),
+                )
+                .context(ArrowComputeSnafu)
+                .and_then(Helper::try_into_vector)
+                .context(VectorComputationSnafu)?;
+                *vector = cast;
+            }
+        }
+        if let Some(max_rows) = self.max_insert_rows {
+            let rows = req.columns_values.values().next().map_or(0, |v| v.len());
+            ensure!(rows <= max_rows, TooManyInsertRowsSnafu { rows, max_rows });
+        }
+
+        match self.insert(req).await? {
+            Output::AffectedRows(rows) => Ok(rows),
+            output => IncorrectInternalStateSnafu {
+                state: format!("unexpected output of insert: {output:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Returns error if any timestamp of the time index column in `req` is later than
+    /// now plus the `max_future_timestamp`.
+    pub(crate) fn check_future_timestamps(
+        &self,
+        table: &TableRef,
+        req: &InsertRequest,
+    ) -> Result<()> {
+        let Some(max_future) = self.max_future_timestamp else {
+            return Ok(());
+        };
+        let schema = table.schema();
+        let Some(column) = schema.timestamp_column() else {
+            return Ok(());
+        };
+        let Some(vector) = req.columns_values.get(&column.name) else {
+            return Ok(());
+        };
+
+        let bound =
+            Timestamp::new_millisecond(self.clock.now_millis() + max_future.as_millis() as i64);
+        for i in 0..vector.len() {
+            if let ValueRef::Timestamp(ts) = vector.get_ref(i) {
+                ensure!(
+                    ts <= bound,
+                    FutureTimestampSnafu {
+                        table_name: &req.table_name,
+                        column: &column.name,
+                        timestamp: ts.to_iso8601_string(),
+                        max_future,
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn insert_to_request(
         &self,
         catalog_manager: CatalogManagerRef,
@@ -59,7 +175,16 @@ impl SqlHandler {
         table_ref: TableReference,
     ) -> Result<SqlRequest> {
         let columns = stmt.columns();
-        let values = stmt.values().context(ParseSqlValueSnafu)?;
+        let rows_num = stmt.rows_num().context(ParseSqlValueSnafu)?;
+        if let Some(max_rows) = self.max_insert_rows {
+            ensure!(
+                rows_num <= max_rows,
+                TooManyInsertRowsSnafu {
+                    rows: rows_num,
+                    max_rows,
+                }
+            );
+        }
 
         let table = catalog_manager
             .table(table_ref.catalog, table_ref.schema, table_ref.table)
@@ -68,84 +193,34 @@ impl SqlHandler {
                 table_name: table_ref.table,
             })?;
         let schema = table.schema();
-        let columns_num = if columns.is_empty() {
-            schema.column_schemas().len()
+        let column_schemas = if columns.is_empty() {
+            schema.column_schemas().iter().collect::<Vec<_>>()
         } else {
-            columns.len()
-        };
-        let rows_num = values.len();
-
-        let mut columns_builders: Vec<(&ColumnSchema, Box<dyn MutableVector>)> =
-            Vec::with_capacity(columns_num);
-
-        if columns.is_empty() {
-            for column_schema in schema.column_schemas() {
-                let data_type = &column_schema.data_type;
-                columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
-            }
-        } else {
-            for column_name in columns {
-                let column_schema =
-                    schema.column_schema_by_name(column_name).with_context(|| {
-                        ColumnNotFoundSnafu {
+            columns
+                .into_iter()
+                .map(|column_name| {
+                    schema
+                        .column_schema_by_name(column_name)
+                        .with_context(|| ColumnNotFoundSnafu {
                             table_name: table_ref.table,
                             column_name: column_name.to_string(),
-                        }
-                    })?;
-                let data_type = &column_schema.data_type;
-                columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
-            }
-        }
-
-        // Convert rows into columns
-        for row in values {
-            ensure!(
-                row.len() == columns_num,
-                ColumnValuesNumberMismatchSnafu {
-                    columns: columns_num,
-                    values: row.len(),
-                }
-            );
-
-            for (sql_val, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
-                add_row_to_vector(column_schema, sql_val, builder)?;
-            }
-        }
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        let vectors = stmt
+            .values_to_vectors(&column_schemas)
+            .context(ParseSqlValueSnafu)?;
 
         Ok(SqlRequest::Insert(InsertRequest {
             catalog_name: table_ref.catalog.to_string(),
             schema_name: table_ref.schema.to_string(),
             table_name: table_ref.table.to_string(),
-            columns_values: columns_builders
-                .into_iter()
-                .map(|(cs, mut b)| (cs.name.to_string(), b.to_vector()))
+            columns_values: column_schemas
+                .iter()
+                .map(|column_schema| column_schema.name.clone())
+                .zip(vectors)
                 .collect(),
         }))
     }
 }
-
-fn add_row_to_vector(
-    column_schema: &ColumnSchema,
-    sql_val: &SqlValue,
-    builder: &mut Box<dyn MutableVector>,
-) -> Result<()> {
-    let value = if replace_default(sql_val) {
-        column_schema
-            .create_default()
-            .context(ColumnDefaultValueSnafu {
-                column: column_schema.name.to_string(),
-            })?
-            .context(ColumnNoneDefaultValueSnafu {
-                column: column_schema.name.to_string(),
-            })?
-    } else {
-        statements::sql_value_to_value(&column_schema.name, &column_schema.data_type, sql_val)
-            .context(ParseSqlSnafu)?
-    };
-    builder.push_value_ref(value.as_value_ref()).unwrap();
-    Ok(())
-}
-
-fn replace_default(sql_val: &SqlValue) -> bool {
-    matches!(sql_val, SqlValue::Placeholder(s) if s.to_lowercase() == DEFAULT_PLACEHOLDER_VALUE)
-}
@@ -0,0 +1,133 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::{all_tables, CatalogManagerRef};
+use common_telemetry::{error, info, warn};
+use snafu::ResultExt;
+
+use crate::error::{Result, SetWritableSnafu};
+use crate::instance::DefaultEngine;
+
+/// Task to refresh tables of a standby datanode periodically, so the datanode serves reads
+/// of data flushed by the datanodes writing the tables.
+///
+/// Tables of a standby datanode are opened read-only from the shared object storage, without
+/// owning the WAL. The datanode takes over writes of the tables once it is promoted.
+pub struct StandbyTask {
+    running: Arc<AtomicBool>,
+    promoted: AtomicBool,
+    catalog_manager: CatalogManagerRef,
+    table_engine: Arc<DefaultEngine>,
+    refresh_interval: Duration,
+}
+
+impl Drop for StandbyTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl StandbyTask {
+    pub(crate) fn new(
+        catalog_manager: CatalogManagerRef,
+        table_engine: Arc<DefaultEngine>,
+        refresh_interval: Duration,
+    ) -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            promoted: AtomicBool::new(false),
+            catalog_manager,
+            table_engine,
+            refresh_interval,
+        }
+    }
+
+    /// Returns true if the datanode is still a standby, i.e. not promoted.
+    pub fn is_standby(&self) -> bool {
+        !self.promoted.load(Ordering::Acquire)
+    }
+
+    /// Stops the standby task, it exits before the next refresh.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Start standby task, spawn background task.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Standby task started multiple times");
+            return;
+        }
+        let interval = self.refresh_interval;
+        let catalog_manager = self.catalog_manager.clone();
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                Self::refresh(&catalog_manager).await;
+            }
+            info!("Standby task shutdown");
+        });
+    }
+
+    /// Stops refreshing and turns all tables writable, the datanodes writing the tables
+    /// must have stopped.
+    pub async fn promote(&self) -> Result<()> {
+        if self
+            .promoted
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Standby datanode is already promoted");
+            return Ok(());
+        }
+        self.stop();
+        // Catches up with the data flushed before the writers stopped.
+        Self::refresh(&self.catalog_manager).await;
+        if let Err(e) = self.table_engine.set_writable().await {
+            // Allows promoting again.
+            self.promoted.store(false, Ordering::Release);
+            return Err(e).context(SetWritableSnafu);
+        }
+        info!("Standby datanode is promoted");
+        Ok(())
+    }
+
+    async fn refresh(catalog_manager: &CatalogManagerRef) {
+        let tables = match all_tables(catalog_manager) {
+            Ok(tables) => tables,
+            Err(e) => {
+                error!(e; "Failed to get tables to refresh");
+                return;
+            }
+        };
+
+        for table in tables {
+            if let Err(e) = table.refresh().await {
+                error!(e; "Failed to refresh table {}", table.table_info().name);
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage metrics of each table, labeled by the catalog, schema and table name.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use catalog::{table_storage_stats, CatalogManagerRef, TableStorageStat};
+use common_telemetry::{error, info, warn};
+use metrics::gauge;
+
+use crate::datanode::TableMetricsConfig;
+use crate::metric::{
+    METRIC_TABLE_MEMTABLE_BYTES, METRIC_TABLE_OMITTED_TABLES, METRIC_TABLE_REGIONS,
+    METRIC_TABLE_SST_BYTES, METRIC_TABLE_SST_FILES,
+};
+
+const CATALOG_LABEL: &str = "catalog";
+const SCHEMA_LABEL: &str = "schema";
+const TABLE_LABEL: &str = "table";
+
+/// Full name of a table, in the order of catalog, schema and table name.
+type TableLabels = (String, String, String);
+
+/// Task to update storage metrics of tables in the datanode periodically.
+pub struct TableMetricsTask {
+    running: Arc<AtomicBool>,
+    catalog_manager: CatalogManagerRef,
+    config: TableMetricsConfig,
+}
+
+impl Drop for TableMetricsTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl TableMetricsTask {
+    pub fn new(catalog_manager: CatalogManagerRef, config: TableMetricsConfig) -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            catalog_manager,
+            config,
+        }
+    }
+
+    /// Stops the task, it exits before the next update.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Starts the task in background.
+    pub fn start(&self) {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Table metrics task started multiple times");
+            return;
+        }
+        let catalog_manager = self.catalog_manager.clone();
+        let config = self.config.clone();
+
+        common_runtime::spawn_bg(async move {
+            let mut exported = HashSet::new();
+            loop {
+                tokio::time::sleep(config.interval).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                match table_storage_stats(&catalog_manager) {
+                    Ok(stats) => update_metrics(stats, config.max_tables, &mut exported),
+                    Err(e) => error!(e; "Failed to get storage statistics of tables"),
+                }
+            }
+            info!("Table metrics task shutdown");
+        });
+    }
+}
+
+/// Sets the metrics of at most `max_tables` tables with the most bytes in `stats`, and
+/// zeroes the metrics of tables `exported` before but not this time, e.g. dropped tables.
+///
+/// Bytes and files of tables with regions without statistics are not updated, as reporting
+/// zero for them is misleading, the values exported before are kept.
+fn update_metrics(
+    mut stats: Vec<TableStorageStat>,
+    max_tables: usize,
+    exported: &mut HashSet<TableLabels>,
+) {
+    stats.sort_unstable_by_key(|stat| Reverse(stat.memtable_bytes + stat.sst_bytes));
+    let omitted = stats.len().saturating_sub(max_tables);
+    stats.truncate(max_tables);
+
+    let mut current = HashSet::with_capacity(stats.len());
+    for stat in stats {
+        let table = (stat.catalog_name, stat.schema_name, stat.table_name);
+        let labels = metric_labels(&table);
+        gauge!(METRIC_TABLE_REGIONS, stat.regions as f64, &labels);
+        if stat.unknown_regions > 0 {
+            let _ = current.insert(table);
+            continue;
+        }
+        gauge!(
+            METRIC_TABLE_MEMTABLE_BYTES,
+            stat.memtable_bytes as f64,
+            &labels
+        );
+        gauge!(METRIC_TABLE_SST_BYTES, stat.sst_bytes as f64, &labels);
+        gauge!(METRIC_TABLE_SST_FILES, stat.sst_files as f64, &labels);
+        let _ = current.insert(table);
+    }
+    for table in exported.difference(&current) {
+        let labels = metric_labels(table);
+        for name in [
+            METRIC_TABLE_REGIONS,
+            METRIC_TABLE_MEMTABLE_BYTES,
+            METRIC_TABLE_SST_BYTES,
+            METRIC_TABLE_SST_FILES,
+        ] {
+            gauge!(name, 0.0, &labels);
+        }
+    }
+    *exported = current;
+    gauge!(METRIC_TABLE_OMITTED_TABLES, omitted as f64);
+}
+
+fn metric_labels(table: &TableLabels) -> [(&'static str, String); 3] {
+    [
+        (CATALOG_LABEL, table.0.clone()),
+        (SCHEMA_LABEL, table.1.clone()),
+        (TABLE_LABEL, table.2.clone()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_stat(table_name: &str, sst_bytes: u64) -> TableStorageStat {
+        TableStorageStat {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: table_name.to_string(),
+            regions: 1,
+            sst_bytes,
+            sst_files: 1,
+            ..Default::default()
+        }
+    }
+
+    fn table_labels(table_name: &str) -> TableLabels {
+        (
+            "greptime".to_string(),
+            "public".to_string(),
+            table_name.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_update_metrics() {
+        let mut exported = HashSet::new();
+        let stats = vec![new_stat("a", 10), new_stat("b", 30), new_stat("c", 20)];
+        update_metrics(stats, 2, &mut exported);
+        assert_eq!(
+            HashSet::from([table_labels("b"), table_labels("c")]),
+            exported
+        );
+
+        // Table `b` is dropped.
+        let stats = vec![new_stat("a", 10), new_stat("c", 20)];
+        update_metrics(stats, 2, &mut exported);
+        assert_eq!(
+            HashSet::from([table_labels("a"), table_labels("c")]),
+            exported
+        );
+
+        // Regions of table `a` are not opened after restart, it's still exported.
+        let mut stat = new_stat("a", 0);
+        stat.unknown_regions = 1;
+        update_metrics(vec![stat, new_stat("c", 20)], 2, &mut exported);
+        assert_eq!(
+            HashSet::from([table_labels("a"), table_labels("c")]),
+            exported
+        );
+    }
+}
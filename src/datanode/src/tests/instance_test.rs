@@ -12,15 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
 use common_recordbatch::util;
+use common_time::util::current_time_millis;
 use datatypes::data_type::ConcreteDataType;
-use datatypes::vectors::{Int64Vector, StringVector, UInt64Vector, VectorRef};
-use session::context::QueryContext;
-
+use datatypes::value::Value;
+use datatypes::vectors::{
+    Int64Vector, StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef,
+};
+use session::context::{QueryContext, UserInfo};
+use storage::WalRetention;
+use table::masking::{MaskingMethod, MaskingPolicy};
+use table::requests::DeleteRequest;
+use tempdir::TempDir;
+
+use crate::datanode::{ConsistencyCheckConfig, ReplicationConfig};
+use crate::replication::{ReplicationProgress, Replicator};
 use crate::tests::test_util::{self, check_output_stream, setup_test_instance, MockInstance};
 
 #[tokio::test(flavor = "multi_thread")]
@@ -173,6 +186,446 @@ async fn test_execute_insert() {
     assert!(matches!(output, Output::AffectedRows(2)));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_future_timestamp() {
+    let instance = MockInstance::with_opts("reject_future_timestamp", |opts| {
+        opts.max_future_timestamp = Some(Duration::from_secs(3600));
+    })
+    .await;
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp, time index(ts))",
+    )
+    .await;
+
+    let now = current_time_millis();
+    let output = execute_sql(
+        &instance,
+        &format!("insert into demo values ('host1', 1.0, 1655276557000), ('host2', 2.0, {now})"),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let future = now + 2 * 3600 * 1000;
+    let err = try_execute_sql(
+        &instance,
+        &format!("insert into demo values ('host1', 1.0, {now}), ('host2', 2.0, {future})"),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("is more than 3600s later than now"),
+        "{err}"
+    );
+
+    let output = execute_sql(&instance, "select count(*) from demo").await;
+    let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 2               |
++-----------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_memory_engine() {
+    let instance = MockInstance::with_opts("memory_engine", |opts| {
+        opts.enable_memory_catalog = true;
+    })
+    .await;
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp, time index(ts)) engine=memory",
+    )
+    .await;
+    let output = execute_sql(
+        &instance,
+        "insert into demo values ('host1', 1.0, 1655276557000), ('host2', 2.0, 1655276558000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "select host, cpu from demo order by host").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1   |
+| host2 | 2   |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(&instance, "drop table demo").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    assert!(try_execute_sql(&instance, "select * from demo")
+        .await
+        .is_err());
+
+    let err = try_execute_sql(
+        &instance,
+        "create table t(ts timestamp, time index(ts)) engine=no_such_engine",
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("no_such_engine"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ingest_stats() {
+    let instance = setup_test_instance("test_ingest_stats").await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000),
+                           ('host2', 88.8,  333.3, 1655276558000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(
+        &instance,
+        "select table_name, rows_total, rows_1m, rows_1h, bytes_total > 0 as has_bytes \
+         from system.information_schema.ingest_stats",
+    )
+    .await;
+    let expected = "\
++------------+------------+---------+---------+-----------+
+| table_name | rows_total | rows_1m | rows_1h | has_bytes |
++------------+------------+---------+---------+-----------+
+| demo       | 2          | 2       | 2       | true      |
++------------+------------+---------+---------+-----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_analyze_table() {
+    let instance = setup_test_instance("test_analyze_table").await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000),
+                           ('host2', 88.8, null, 1655276558000),
+                           ('host1', 11.1, null, 1655276559000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let output = execute_sql(&instance, "analyze table demo").await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let table = instance
+        .inner()
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+        .unwrap()
+        .unwrap();
+    let statistics = table.table_info().meta.column_statistics.clone().unwrap();
+    assert_eq!(3, statistics.row_count);
+
+    let host = &statistics.columns["host"];
+    assert_eq!(2, host.distinct_count);
+    assert_eq!(0, host.null_count);
+    assert_eq!(Value::from("host1"), host.min_value);
+    assert_eq!(Value::from("host2"), host.max_value);
+
+    let cpu = &statistics.columns["cpu"];
+    assert_eq!(Value::from(11.1), cpu.min_value);
+    assert_eq!(Value::from(88.8), cpu.max_value);
+
+    let memory = &statistics.columns["memory"];
+    assert_eq!(1, memory.distinct_count);
+    assert_eq!(2, memory.null_count);
+    assert_eq!(Some(2.0 / 3.0), statistics.null_fraction("memory"));
+
+    assert!(try_execute_sql(&instance, "analyze table not_exist")
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_check_table() {
+    let instance = setup_test_instance("test_check_table").await;
+    let output = execute_sql(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host1', 66.6, 1024, 1655276557000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(&instance, "check table demo").await;
+    let expected = "\
++----------------------+-------+----------+----------+
+| Table                | Op    | Msg_type | Msg_text |
++----------------------+-------+----------+----------+
+| greptime.public.demo | check | status   | OK       |
++----------------------+-------+----------+----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(&instance, "repair table demo").await;
+    let expected = "\
++----------------------+--------+----------+----------+
+| Table                | Op     | Msg_type | Msg_text |
++----------------------+--------+----------+----------+
+| greptime.public.demo | repair | status   | OK       |
++----------------------+--------+----------+----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let query_ctx = Arc::new(QueryContext::new());
+    query_ctx.set_current_user(UserInfo::new("guest"));
+    let err = instance
+        .inner()
+        .execute_sql("repair table demo", query_ctx)
+        .await
+        .unwrap_err();
+    assert_eq!(StatusCode::AccessDenied, err.status_code());
+
+    assert!(try_execute_sql(&instance, "check table not_exist")
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_show_table_status() {
+    let instance = setup_test_instance("test_show_table_status").await;
+    let before_write = current_time_millis();
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000),
+                           ('host2', 88.8,  333.3, 1655276558000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "show table status like 'de%'").await;
+    let Output::RecordBatches(batches) = output else {
+        unreachable!()
+    };
+    let batches = batches.take();
+    assert_eq!(1, batches.len());
+    let batch = &batches[0];
+    assert_eq!(18, batch.num_columns());
+    assert_eq!(1, batch.num_rows());
+
+    let column = |name| batch.column_by_name(name).unwrap().get(0);
+    assert_eq!(Value::from("demo"), column("Name"));
+    assert_eq!(Value::from("mito"), column("Engine"));
+    assert_eq!(Value::UInt64(2), column("Rows"));
+    assert!(matches!(column("Data_length"), Value::UInt64(bytes) if bytes > 0));
+    assert!(matches!(column("Create_time"), Value::Timestamp(_)));
+    let Value::Timestamp(update_time) = column("Update_time") else {
+        unreachable!()
+    };
+    assert!(update_time.value() >= before_write);
+    assert_eq!(Value::Null, column("Auto_increment"));
+
+    let output = execute_sql(&instance, "show table status like 'not_exist'").await;
+    let Output::RecordBatches(batches) = output else {
+        unreachable!()
+    };
+    assert!(batches.take().iter().all(|batch| batch.num_rows() == 0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_startup_consistency_check() {
+    let instance = MockInstance::with_opts("startup_consistency_check", |opts| {
+        opts.startup_consistency_check = Some(ConsistencyCheckConfig::default());
+    })
+    .await;
+    test_util::create_test_table(
+        instance.inner(),
+        ConcreteDataType::timestamp_millisecond_datatype(),
+    )
+    .await
+    .unwrap();
+
+    let checker = instance.inner().consistency_checker.as_ref().unwrap();
+    let report = checker.check().await.unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+    assert!(report.checked_tables > 0);
+
+    // Data of a table unknown to the catalog.
+    checker
+        .object_store
+        .object("public/99999/manifest/00000000000000000000.json")
+        .write("{}")
+        .await
+        .unwrap();
+    let report = checker.check().await.unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(vec!["public/99999/".to_string()], report.orphan_table_dirs);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_masking_policies() {
+    let instance = MockInstance::new("masking_policies").await;
+    instance
+        .inner()
+        .query_engine
+        .register_masking_policies(vec![MaskingPolicy {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table: "users".to_string(),
+            column: "phone".to_string(),
+            method: MaskingMethod::Partial,
+            salt: String::new(),
+            unmasked_roles: vec!["admin".to_string()],
+        }]);
+
+    execute_sql(
+        &instance,
+        "create table users(name string, phone string, ts timestamp, time index(ts))",
+    )
+    .await;
+    execute_sql(
+        &instance,
+        "insert into users values ('alice', '13800001234', 1), ('bob', '13900005678', 2)",
+    )
+    .await;
+
+    let output = execute_sql(&instance, "select name, phone from users order by name").await;
+    let expected = "\
++-------+-------------+
+| name  | phone       |
++-------+-------------+
+| alice | *******1234 |
+| bob   | *******5678 |
++-------+-------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Filters are evaluated against the masked values.
+    let output = execute_sql(
+        &instance,
+        "select name from users where phone = '13800001234'",
+    )
+    .await;
+    match output {
+        Output::Stream(s) => {
+            let batches = util::collect(s).await.unwrap();
+            assert!(batches.iter().all(|batch| batch.num_rows() == 0));
+        }
+        _ => unreachable!(),
+    }
+
+    let query_ctx = Arc::new(QueryContext::new());
+    query_ctx.set_current_user(UserInfo::new("root").with_roles(vec!["admin".to_string()]));
+    let output = instance
+        .inner()
+        .execute_sql("select name, phone from users order by name", query_ctx)
+        .await
+        .unwrap();
+    let expected = "\
++-------+-------------+
+| name  | phone       |
++-------+-------------+
+| alice | 13800001234 |
+| bob   | 13900005678 |
++-------+-------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pg_catalog() {
+    let instance = setup_test_instance("test_pg_catalog").await;
+
+    let output = execute_sql(
+        &instance,
+        "select schemaname, tablename, tableowner from pg_catalog.pg_tables \
+         where schemaname = 'public' order by tablename",
+    )
+    .await;
+    let expected = "\
++------------+-----------+------------+
+| schemaname | tablename | tableowner |
++------------+-----------+------------+
+| public     | demo      | greptime   |
+| public     | numbers   | greptime   |
++------------+-----------+------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select nspname from pg_catalog.pg_namespace order by nspname",
+    )
+    .await;
+    let expected = "\
++------------+
+| nspname    |
++------------+
+| pg_catalog |
+| public     |
+| system     |
++------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select c.relname, c.relkind from pg_catalog.pg_class c \
+         join pg_catalog.pg_namespace n on c.relnamespace = n.oid \
+         where n.nspname = 'public' order by c.relname",
+    )
+    .await;
+    let expected = "\
++---------+---------+
+| relname | relkind |
++---------+---------+
+| demo    | r       |
+| numbers | r       |
++---------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select typname from pg_catalog.pg_type where typname = 'timestamp'",
+    )
+    .await;
+    let expected = "\
++-----------+
+| typname   |
++-----------+
+| timestamp |
++-----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Virtual tables can't be dropped.
+    for sql in [
+        "drop table pg_catalog.pg_class",
+        "drop table system.information_schema.ingest_stats",
+    ] {
+        let err = try_execute_sql(&instance, sql).await.unwrap_err();
+        assert!(err.to_string().contains("virtual table"), "{sql}: {err}");
+    }
+    let output = execute_sql(&instance, "select count(*) from pg_catalog.pg_class").await;
+    assert!(matches!(output, Output::Stream(_)));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_execute_insert_query_with_i64_timestamp() {
     let instance = MockInstance::new("insert_query_i64_timestamp").await;
@@ -253,11 +706,15 @@ async fn test_execute_show_databases_tables() {
         Output::RecordBatches(databases) => {
             let databases = databases.take();
             assert_eq!(1, databases[0].num_columns());
-            assert_eq!(databases[0].column(0).len(), 1);
+            assert_eq!(databases[0].column(0).len(), 3);
 
             assert_eq!(
                 *databases[0].column(0),
-                Arc::new(StringVector::from(vec![Some("public")])) as VectorRef
+                Arc::new(StringVector::from(vec![
+                    Some("pg_catalog"),
+                    Some("public"),
+                    Some("system")
+                ])) as VectorRef
             );
         }
         _ => unreachable!(),
@@ -529,50 +986,175 @@ async fn test_alter_table() {
     check_output_stream(output, expected).await;
 }
 
-async fn test_insert_with_default_value_for_type(type_name: &str) {
-    let instance = MockInstance::new("execute_create").await;
-
-    let create_sql = format!(
-        r#"create table test_table(
-        host string,
-        ts {type_name} DEFAULT CURRENT_TIMESTAMP,
-        cpu double default 0,
-        TIME INDEX (ts),
-        PRIMARY KEY(host)
-    ) engine=mito with(regions=1);"#,
-    );
-    let output = execute_sql(&instance, &create_sql).await;
-    assert!(matches!(output, Output::AffectedRows(0)));
+#[tokio::test(flavor = "multi_thread")]
+async fn test_explain_ddl() {
+    let instance = setup_test_instance("test_explain_ddl").await;
 
-    // Insert with ts.
-    let output = execute_sql(
+    execute_sql(
         &instance,
-        "insert into test_table(host, cpu, ts) values ('host1', 1.1, 1000)",
+        "insert into demo(host, cpu, memory, ts) values ('host1', 1.1, 100, 1000), ('host2', 2.2, 200, 2000)",
     )
     .await;
-    assert!(matches!(output, Output::AffectedRows(1)));
 
-    // Insert without ts, so it should be filled by default value.
     let output = execute_sql(
         &instance,
-        "insert into test_table(host, cpu) values ('host2', 2.2)",
+        "explain ddl create table foo(ts timestamp time index, v double)",
     )
     .await;
-    assert!(matches!(output, Output::AffectedRows(1)));
-
-    let output = execute_sql(&instance, "select host, cpu from test_table").await;
     let expected = "\
-+-------+-----+
-| host  | cpu |
-+-------+-----+
-| host1 | 1.1 |
-| host2 | 2.2 |
-+-------+-----+\
++---------------+----------------------------------+
+| Item          | Detail                           |
++---------------+----------------------------------+
+| Action        | CREATE TABLE greptime.public.foo |
+| Region 0      | local datanode                   |
+| Affected rows | 0                                |
++---------------+----------------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+    // Nothing is created.
+    assert!(try_execute_sql(&instance, "select * from foo")
+        .await
+        .is_err());
+
+    let output = execute_sql(
+        &instance,
+        "explain ddl alter table demo add my_tag string null",
+    )
+    .await;
+    let expected = "\
++---------------+-------------------------------------------------------+
+| Item          | Detail                                                |
++---------------+-------------------------------------------------------+
+| Action        | ALTER TABLE greptime.public.demo add columns [my_tag] |
+| Region 0      | local datanode                                        |
+| Affected rows | 2                                                     |
++---------------+-------------------------------------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+    // The column is not added.
+    assert!(try_execute_sql(&instance, "select my_tag from demo")
+        .await
+        .is_err());
+
+    for sql in [
+        "explain ddl create table demo(ts timestamp time index)",
+        "explain ddl alter table demo add host string",
+        "explain ddl alter table demo drop column ts",
+        "explain ddl alter table not_exist add my_tag string null",
+    ] {
+        assert!(try_execute_sql(&instance, sql).await.is_err(), "{sql}");
+    }
+    let output = execute_sql(
+        &instance,
+        "explain ddl create table if not exists demo(ts timestamp time index)",
+    )
+    .await;
+    let expected = "\
++--------+-------------------------------------------------+
+| Item   | Detail                                          |
++--------+-------------------------------------------------+
+| Action | none, table greptime.public.demo already exists |
++--------+-------------------------------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+async fn test_insert_with_default_value_for_type(type_name: &str) {
+    let instance = MockInstance::new("execute_create").await;
+
+    let create_sql = format!(
+        r#"create table test_table(
+        host string,
+        ts {type_name} DEFAULT CURRENT_TIMESTAMP,
+        cpu double default 0,
+        TIME INDEX (ts),
+        PRIMARY KEY(host)
+    ) engine=mito with(regions=1);"#,
+    );
+    let output = execute_sql(&instance, &create_sql).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // Insert with ts.
+    let output = execute_sql(
+        &instance,
+        "insert into test_table(host, cpu, ts) values ('host1', 1.1, 1000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    // Insert without ts, so it should be filled by default value.
+    let output = execute_sql(
+        &instance,
+        "insert into test_table(host, cpu) values ('host2', 2.2)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(&instance, "select host, cpu from test_table").await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 1.1 |
+| host2 | 2.2 |
++-------+-----+\
     "
     .to_string();
     check_output_stream(output, expected).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_insert_many_rows() {
+    let instance = MockInstance::with_opts("insert_many_rows", |opts| {
+        opts.max_insert_rows = Some(3000);
+    })
+    .await;
+    execute_sql(
+        &instance,
+        "create table demo(host string, cpu double default 0, ts timestamp, time index(ts))",
+    )
+    .await;
+
+    // Spans multiple chunks of the values list.
+    let values = (0..2500)
+        .map(|i| format!("('host{}', default, {i})", i % 10))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let output = execute_sql(&instance, &format!("insert into demo values {values}")).await;
+    assert!(matches!(output, Output::AffectedRows(2500)));
+
+    let output = execute_sql(
+        &instance,
+        "select count(*) from demo where host = 'host9' and cpu = 0",
+    )
+    .await;
+    let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 250             |
++-----------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let values = (0..3001)
+        .map(|i| format!("('host1', 1.0, {i})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let err = try_execute_sql(&instance, &format!("insert into demo values {values}"))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Insert statement has 3001 rows, exceeds the max rows 3000"),
+        "{err}"
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_insert_with_default_value() {
     test_insert_with_default_value_for_type("timestamp").await;
@@ -638,10 +1220,919 @@ async fn test_use_database() {
     check_output_stream(output, expected).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_catalog_and_use() {
+    let instance = MockInstance::new("test_create_catalog_and_use").await;
+
+    let output = execute_sql(&instance, "create catalog tenant_a").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    assert!(try_execute_sql(&instance, "create catalog tenant_a")
+        .await
+        .is_err());
+    let output = execute_sql(&instance, "create catalog if not exists tenant_a").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let query_ctx = QueryContext::arc();
+    let output = instance
+        .inner()
+        .execute_sql("use tenant_a.public", query_ctx.clone())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::RecordBatches(_)));
+    assert_eq!("tenant_a", query_ctx.current_catalog());
+    assert_eq!("public", query_ctx.current_schema());
+
+    for sql in [
+        "create database db1",
+        "create table tb1(col_i32 int, ts bigint, TIME INDEX(ts))",
+        "insert into tb1(col_i32, ts) values (1, 1655276557000)",
+    ] {
+        let _ = instance
+            .inner()
+            .execute_sql(sql, query_ctx.clone())
+            .await
+            .unwrap();
+    }
+
+    let output = instance
+        .inner()
+        .execute_sql("show databases", query_ctx.clone())
+        .await
+        .unwrap();
+    let expected = "\
++------------+
+| Schemas    |
++------------+
+| db1        |
+| pg_catalog |
+| public     |
++------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // The pg_catalog of the new catalog lists its own tables.
+    let output = instance
+        .inner()
+        .execute_sql(
+            "select schemaname, tablename from pg_catalog.pg_tables where schemaname = 'public'",
+            query_ctx.clone(),
+        )
+        .await
+        .unwrap();
+    let expected = "\
++------------+-----------+
+| schemaname | tablename |
++------------+-----------+
+| public     | tb1       |
++------------+-----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Tables in the new catalog are invisible to the default catalog.
+    assert!(try_execute_sql(&instance, "select col_i32 from tb1")
+        .await
+        .is_err());
+    let output = instance
+        .inner()
+        .execute_sql("select col_i32 from tb1", query_ctx.clone())
+        .await
+        .unwrap();
+    let expected = "\
++---------+
+| col_i32 |
++---------+
+| 1       |
++---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Switching to a schema of a missing catalog fails.
+    assert!(instance
+        .inner()
+        .execute_sql("use tenant_b.public", query_ctx.clone())
+        .await
+        .is_err());
+    assert_eq!("tenant_a", query_ctx.current_catalog());
+
+    // Users could only access the catalogs granted to them.
+    let user_ctx = QueryContext::arc();
+    user_ctx.set_current_user(UserInfo::new("alice"));
+    for sql in [
+        "create catalog tenant_b",
+        "use tenant_a.public",
+        "select col_i32 from tenant_a.public.tb1",
+    ] {
+        let err = instance
+            .inner()
+            .execute_sql(sql, user_ctx.clone())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err.status_code(),
+                StatusCode::AccessDenied | StatusCode::PlanQuery
+            ),
+            "{sql}: {err:?}"
+        );
+    }
+    user_ctx
+        .set_current_user(UserInfo::new("alice").with_roles(vec!["catalog:tenant_a".to_string()]));
+    let _ = instance
+        .inner()
+        .execute_sql("use tenant_a.public", user_ctx.clone())
+        .await
+        .unwrap();
+    let output = instance
+        .inner()
+        .execute_sql("select col_i32 from tb1", user_ctx)
+        .await
+        .unwrap();
+    let expected = "\
++---------+
+| col_i32 |
++---------+
+| 1       |
++---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_undrop_table() {
+    let instance = MockInstance::new("test_undrop_table").await;
+
+    for sql in [
+        "create table demo(host string, ts timestamp time index)",
+        "insert into demo(host, ts) values ('host1', 1655276557000)",
+        "drop table demo",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+    assert!(try_execute_sql(&instance, "select host from demo")
+        .await
+        .is_err());
+    assert_eq!(1, instance.inner().recycle_bin().dropped_tables().len());
+
+    let output = execute_sql(&instance, "undrop table demo").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql(&instance, "select host from demo").await;
+    let expected = "\
++-------+
+| host  |
++-------+
+| host1 |
++-------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+    assert!(instance.inner().recycle_bin().dropped_tables().is_empty());
+
+    // The name is taken by a new table.
+    for sql in [
+        "drop table demo",
+        "create table demo(host string, ts timestamp time index)",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+    assert!(try_execute_sql(&instance, "undrop table demo")
+        .await
+        .is_err());
+
+    // Recovers the latest dropped table, i.e. the empty one.
+    let _ = execute_sql(&instance, "drop table demo").await;
+    let output = execute_sql(&instance, "undrop table demo").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql(&instance, "select count(*) from demo").await;
+    let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 0               |
++-----------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+    assert_eq!(1, instance.inner().recycle_bin().dropped_tables().len());
+
+    assert!(try_execute_sql(&instance, "undrop table absent")
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_idempotent_ddl() {
+    let instance = MockInstance::new("idempotent_ddl").await;
+
+    let create_database = "create database if not exists idempotent";
+    let create_table = r#"create table if not exists idempotent.demo(
+                            host string,
+                            ts timestamp,
+                            TIME INDEX (ts),
+                            PRIMARY KEY(host)
+                        )"#;
+    let add_column = "alter table idempotent.demo add column if not exists cpu double";
+    for _ in 0..2 {
+        execute_sql(&instance, create_database).await;
+        execute_sql(&instance, create_table).await;
+        execute_sql(&instance, add_column).await;
+    }
+
+    assert!(try_execute_sql(&instance, "create database idempotent")
+        .await
+        .is_err());
+    assert!(try_execute_sql(
+        &instance,
+        "alter table idempotent.demo add column cpu double"
+    )
+    .await
+    .is_err());
+
+    let output = execute_sql(&instance, "desc table idempotent.demo").await;
+    let Output::RecordBatches(batches) = output else { unreachable!() };
+    let batches = batches.take();
+    assert_eq!(3, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_database_default_table_options() {
+    let instance = MockInstance::new("test_database_default_table_options").await;
+
+    assert!(
+        try_execute_sql(&instance, "create database db1 with (ttl='abc')")
+            .await
+            .is_err()
+    );
+
+    let output = execute_sql(
+        &instance,
+        "create database db1 with (ttl='30d', compression='zstd')",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql_in_db(
+        &instance,
+        "create table tb1(col_i32 int, ts bigint, TIME INDEX(ts)) with (ttl='7d')",
+        "db1",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "create table tb2(col_i32 int, ts bigint, TIME INDEX(ts))",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let table = instance
+        .inner()
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, "db1", "tb1")
+        .unwrap()
+        .unwrap();
+    let options = &table.table_info().meta.options;
+    // Options of the table override the defaults of the database.
+    assert_eq!("7d", options["ttl"]);
+    assert_eq!("zstd", options["compression"]);
+
+    let table = instance
+        .inner()
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "tb2")
+        .unwrap()
+        .unwrap();
+    assert!(table.table_info().meta.options.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ddl_history() {
+    let instance = MockInstance::new("test_ddl_history").await;
+
+    let output = execute_sql(&instance, "create database db1").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql_in_db(
+        &instance,
+        "create table tb1(col_i32 int, ts bigint, TIME INDEX(ts))",
+        "db1",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    // Repeated operations on the same object are all recorded, even in the same millisecond.
+    for _ in 0..2 {
+        assert!(try_execute_sql_in_db(&instance, "drop table tb2", "db1")
+            .await
+            .is_err());
+    }
+    // Queries are not recorded.
+    let _ = execute_sql_in_db(&instance, "select * from tb1", "db1").await;
+
+    let output = execute_sql(
+        &instance,
+        "select username, operation, object_name, success from system.ddl_history order by object_name",
+    )
+    .await;
+    let expected = "\
++----------+-----------------+------------------+---------+
+| username | operation       | object_name      | success |
++----------+-----------------+------------------+---------+
+| greptime | CREATE DATABASE | greptime.db1     | true    |
+| greptime | CREATE TABLE    | greptime.db1.tb1 | true    |
+| greptime | DROP TABLE      | greptime.db1.tb2 | false   |
+| greptime | DROP TABLE      | greptime.db1.tb2 | false   |
++----------+-----------------+------------------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Statements are recorded as SQL.
+    let output = execute_sql(
+        &instance,
+        "select statement from system.ddl_history order by object_name",
+    )
+    .await;
+    let expected = "\
++------------------------------------------------------------------------+
+| statement                                                              |
++------------------------------------------------------------------------+
+| CREATE DATABASE db1                                                    |
+| CREATE TABLE tb1 (col_i32 INT, ts BIGINT, TIME INDEX (ts)) ENGINE=mito |
+| DROP TABLE tb2                                                         |
+| DROP TABLE tb2                                                         |
++------------------------------------------------------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_alter_database() {
+    let instance = MockInstance::new("test_alter_database").await;
+
+    let output = execute_sql(&instance, "create database db1").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql(
+        &instance,
+        "alter database db1 set (owner='alice', comment='test db')",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(&instance, "alter database db1 rename to db2").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        "select schema, metadata from system.information_schema.schemata where catalog = 'greptime' order by schema",
+    )
+    .await;
+    let expected = "\
++------------+---------------------------------------+
+| schema     | metadata                              |
++------------+---------------------------------------+
+| db2        | {\"comment\":\"test db\",\"owner\":\"alice\"} |
+| pg_catalog | {}                                    |
+| public     | {}                                    |
+| system     | {}                                    |
++------------+---------------------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Tables are moved along with their database.
+    let output = execute_sql_in_db(
+        &instance,
+        "create table tb1(col_i32 int, ts bigint, TIME INDEX(ts))",
+        "db2",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql_in_db(&instance, "insert into tb1 values(1, 1000)", "db2").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = execute_sql(&instance, "alter database db2 rename to db3").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert!(try_execute_sql_in_db(&instance, "select * from tb1", "db2")
+        .await
+        .is_err());
+    let output = execute_sql_in_db(&instance, "select * from tb1", "db3").await;
+    let expected = "\
++---------+------+
+| col_i32 | ts   |
++---------+------+
+| 1       | 1000 |
++---------+------+\
+"
+    .to_string();
+    check_output_stream(output, expected).await;
+    assert!(
+        try_execute_sql_in_db(&instance, "alter database db4 set (owner='bob')", "db3")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_table_and_column_comments() {
+    let instance = MockInstance::new("test_table_and_column_comments").await;
+
+    let output = execute_sql(
+        &instance,
+        r#"create table demo(
+                host string comment 'host name',
+                cpu double,
+                ts timestamp time index,
+                primary key(host)
+            ) engine=mito comment 'cpu usage of hosts'"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "alter table demo comment = 'usage of hosts'").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "alter table demo modify column cpu comment 'cpu usage in percent'",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert!(try_execute_sql(
+        &instance,
+        "alter table demo modify column memory comment 'not exists'"
+    )
+    .await
+    .is_err());
+
+    let table = instance
+        .inner()
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+        .unwrap()
+        .unwrap();
+    let table_info = table.table_info();
+    assert_eq!(Some("usage of hosts"), table_info.desc.as_deref());
+    let schema = &table_info.meta.schema;
+    assert_eq!(
+        Some("host name"),
+        schema.column_schema_by_name("host").unwrap().comment()
+    );
+    assert_eq!(
+        Some("cpu usage in percent"),
+        schema.column_schema_by_name("cpu").unwrap().comment()
+    );
+
+    let output = execute_sql(&instance, "show create table demo").await;
+    let batches = match output {
+        Output::RecordBatches(batches) => batches.take(),
+        Output::Stream(stream) => util::collect(stream).await.unwrap(),
+        _ => unreachable!(),
+    };
+    let create_table = batches[0]
+        .column(1)
+        .get_ref(0)
+        .as_string()
+        .unwrap()
+        .unwrap();
+    assert!(create_table.contains("host STRING NULL COMMENT 'host name'"));
+    assert!(create_table.contains("cpu DOUBLE NULL COMMENT 'cpu usage in percent'"));
+    assert!(create_table.contains("COMMENT 'usage of hosts'"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replication_buffer() {
+    let instance = setup_test_instance("test_replication_buffer").await;
+    let buffer_dir = TempDir::new("replication_buffer").unwrap();
+    let config = ReplicationConfig {
+        // Nothing listens on this address, so writes stay in the buffer.
+        remote_addr: "127.0.0.1:1".to_string(),
+        tables: vec!["demo".to_string()],
+        buffer_dir: buffer_dir.path().to_str().unwrap().to_string(),
+        ..Default::default()
+    };
+    let new_replicator = || {
+        let progress = ReplicationProgress::open(&config).unwrap();
+        let replicator = Replicator::new(
+            instance.inner().catalog_manager().clone(),
+            config.clone(),
+            None,
+            progress.clone(),
+        )
+        .unwrap();
+        (replicator, progress)
+    };
+
+    for sql in [
+        "insert into demo(host, cpu, memory, ts) values ('host1', 66.6, 1024, 1655276557000)",
+        "insert into demo(host, cpu, memory, ts) values ('host2', 88.8, 333.3, 1655276558000)",
+    ] {
+        assert!(matches!(
+            execute_sql(&instance, sql).await,
+            Output::AffectedRows(1)
+        ));
+    }
+
+    // Rows written before replication are synced by a snapshot.
+    let (mut replicator, _) = new_replicator();
+    assert_eq!(2, replicator.fetch().await.unwrap());
+    assert!(replicator.send().await.is_err());
+    let buffered = replicator.buffered();
+    assert!(buffered > 0);
+    let token = replicator.resume_token("greptime.public.demo").unwrap();
+    assert!(token.synced);
+
+    // Buffered writes and the resume token survive restart.
+    let (mut replicator, progress) = new_replicator();
+    assert_eq!(buffered, replicator.buffered());
+    assert_eq!(Some(token), replicator.resume_token("greptime.public.demo"));
+    assert_eq!(0, replicator.fetch().await.unwrap());
+    // WAL entries from the token are retained.
+    let region_id = mito::engine::region_id(token.table_id, 0);
+    assert_eq!(
+        Some(token.next_sequence),
+        progress.retained_sequence(region_id)
+    );
+
+    let _ = execute_sql(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host3', 99.9, 10, 1655276559000)",
+    )
+    .await;
+    let table = instance
+        .inner()
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+        .unwrap()
+        .unwrap();
+    let key_column_values = HashMap::from([
+        (
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])) as VectorRef,
+        ),
+        (
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1655276557000])) as VectorRef,
+        ),
+    ]);
+    let _ = table
+        .delete(DeleteRequest { key_column_values })
+        .await
+        .unwrap();
+
+    // Both the insert and the delete are buffered.
+    assert_eq!(2, replicator.fetch().await.unwrap());
+    assert_eq!(buffered + 2, replicator.buffered());
+    assert!(
+        replicator
+            .resume_token("greptime.public.demo")
+            .unwrap()
+            .next_sequence
+            > token.next_sequence
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_asof_join() {
+    let instance = MockInstance::new("test_asof_join").await;
+
+    for sql in [
+        "create table metrics(host string, cpu double, ts timestamp time index, primary key(host))",
+        "create table deploys(host string, version string, ts timestamp time index, \
+         primary key(host))",
+        "insert into metrics(host, cpu, ts) values ('host1', 1, 1000), ('host1', 2, 3000), \
+         ('host1', 5, 5000), ('host2', 3, 3000), ('host2', 6, 5000), ('host3', 4, 3000)",
+        "insert into deploys(host, version, ts) values ('host1', 'v1', 2000), \
+         ('host1', 'v2', 3000), ('host2', 'v1', 4000)",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+
+    let output = execute_sql(
+        &instance,
+        "select m.host, m.ts, m.cpu, d.version from metrics m \
+         asof join deploys d on m.host = d.host and m.ts >= d.ts order by m.host, m.ts",
+    )
+    .await;
+    let expected = "\
++-------+---------------------+-----+---------+
+| host  | ts                  | cpu | version |
++-------+---------------------+-----+---------+
+| host1 | 1970-01-01T00:00:03 | 2   | v2      |
+| host1 | 1970-01-01T00:00:05 | 5   | v2      |
+| host2 | 1970-01-01T00:00:05 | 6   | v1      |
++-------+---------------------+-----+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // The timestamp condition is required.
+    assert!(try_execute_sql(
+        &instance,
+        "select * from metrics m asof join deploys d on m.host = d.host"
+    )
+    .await
+    .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_lateral_join() {
+    let instance = MockInstance::new("test_lateral_join").await;
+
+    for sql in [
+        "create table metrics(host string, cpu double, ts timestamp time index, primary key(host))",
+        "create table incidents(host string, start_ts timestamp, ts timestamp time index, \
+         primary key(host))",
+        "insert into metrics(host, cpu, ts) values ('host1', 1, 1000), ('host1', 2, 3000), \
+         ('host1', 5, 5000), ('host2', 3, 3000), ('host2', 6, 5000), ('host3', 4, 3000)",
+        "insert into incidents(host, start_ts, ts) values ('host1', 2000, 5000), \
+         ('host2', 4000, 5000), ('host3', 8000, 9000)",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+
+    // Metrics of each incident, between the start and the end of the incident.
+    let output = execute_sql(
+        &instance,
+        "select i.host, l.cpu from incidents i, lateral (select m.cpu from metrics m \
+         where m.host = i.host and m.ts between i.start_ts and i.ts) l order by i.host, l.cpu",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 2   |
+| host1 | 5   |
+| host2 | 6   |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select i.host, l.cpu from incidents i left join lateral (select m.cpu from metrics m \
+         where m.host = i.host and m.ts between i.start_ts and i.ts) l on true \
+         order by i.host, l.cpu",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host1 | 2   |
+| host1 | 5   |
+| host2 | 6   |
+| host3 |     |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select i.host, (select max(m.cpu) from metrics m where m.host = i.host \
+         and m.ts between i.start_ts and i.ts) as max_cpu from incidents i order by i.host",
+    )
+    .await;
+    let expected = "\
++-------+---------+
+| host  | max_cpu |
++-------+---------+
+| host1 | 5       |
+| host2 | 6       |
+| host3 |         |
++-------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Decorrelated to a join with the aggregation grouped by host.
+    let output = execute_sql(
+        &instance,
+        "select i.host, (select max(m.cpu) from metrics m where m.host = i.host \
+         and m.ts > 1000) as max_cpu from incidents i order by i.host",
+    )
+    .await;
+    let expected = "\
++-------+---------+
+| host  | max_cpu |
++-------+---------+
+| host1 | 5       |
+| host2 | 6       |
+| host3 | 4       |
++-------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Counts of hosts without any matching metric are 0.
+    let output = execute_sql(
+        &instance,
+        "select i.host, (select count(m.cpu) from metrics m where m.host = i.host \
+         and m.ts > 4000) as num from incidents i order by i.host",
+    )
+    .await;
+    let expected = "\
++-------+-----+
+| host  | num |
++-------+-----+
+| host1 | 1   |
+| host2 | 1   |
+| host3 | 0   |
++-------+-----+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_series_table_functions() {
+    let instance = MockInstance::new("test_series_table_functions").await;
+
+    for sql in [
+        "create table series(cpu double, ts timestamp time index)",
+        "insert into series(cpu, ts) values (1, 1000), (2, 2000), (3, 3000), (1, 4000), \
+         (2, 5000), (3, 6000)",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+
+    let output = execute_sql(
+        &instance,
+        "select ts, value from seasonal_forecast(series, '2s', '3s') order by ts",
+    )
+    .await;
+    let expected = "\
++---------------------+-------+
+| ts                  | value |
++---------------------+-------+
+| 1970-01-01T00:00:07 | 1     |
+| 1970-01-01T00:00:08 | 2     |
++---------------------+-------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select f.ts, f.value from forecast((select ts, cpu from series where cpu = 2), '3s') f",
+    )
+    .await;
+    let expected = "\
++---------------------+-------+
+| ts                  | value |
++---------------------+-------+
+| 1970-01-01T00:00:08 | 2     |
++---------------------+-------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select ts, score from anomaly_score((select ts, 1.0 as v from series)) order by ts",
+    )
+    .await;
+    let expected = "\
++---------------------+-------+
+| ts                  | score |
++---------------------+-------+
+| 1970-01-01T00:00:01 |       |
+| 1970-01-01T00:00:02 |       |
+| 1970-01-01T00:00:03 | 0     |
+| 1970-01-01T00:00:04 | 0     |
+| 1970-01-01T00:00:05 | 0     |
+| 1970-01-01T00:00:06 | 0     |
++---------------------+-------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Durations are required to be positive.
+    assert!(
+        try_execute_sql(&instance, "select * from forecast(series, '0s')")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_hints() {
+    let instance = MockInstance::new("test_query_hints").await;
+
+    for sql in [
+        "create table metrics(host string, cpu double, ts timestamp time index, primary key(host))",
+        "insert into metrics(host, cpu, ts) values ('host1', 1, 1000), ('host2', 2, 1000), \
+         ('host3', 3, 1000)",
+    ] {
+        let _ = execute_sql(&instance, sql).await;
+    }
+
+    let output = execute_sql(
+        &instance,
+        "select /*+ READ_REPLICA, MAX_SCAN_ROWS(3), NO_CACHE */ count(*) from metrics",
+    )
+    .await;
+    let expected = "\
++-----------------+
+| COUNT(UInt8(1)) |
++-----------------+
+| 3               |
++-----------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Scanning more rows than the hinted fails the query.
+    let Output::Stream(stream) =
+        execute_sql(&instance, "select /*+ MAX_SCAN_ROWS(2) */ * from metrics").await else {
+        unreachable!()
+    };
+    let err = util::collect(stream).await.unwrap_err();
+    assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+    assert!(
+        try_execute_sql(&instance, "select /*+ NO_SUCH_HINT */ * from metrics")
+            .await
+            .is_err()
+    );
+}
+
+#[cfg(feature = "python")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_insert_from_script() {
+    use servers::query_handler::ScriptHandler;
+
+    let instance = MockInstance::new("test_insert_from_script").await;
+    let _ = execute_sql(
+        &instance,
+        "create table squares(n int, square bigint, ts timestamp time index)",
+    )
+    .await;
+
+    let script = r#"
+import greptime as gt
+
+@copr(args=["number"], returns=["rows"], sql="select number from numbers limit 3")
+def squares(number):
+    ts = gt.vector([1000, 2000, 3000])
+    return gt.insert("squares", {"n": number, "square": number * number, "ts": ts})
+"#;
+    let run_script = |script: String, user: &str| {
+        let query_ctx = Arc::new(QueryContext::new());
+        query_ctx.set_current_user(UserInfo::new(user));
+        let instance = instance.inner();
+        async move {
+            instance.insert_script("squares", &script).await.unwrap();
+            let Output::Stream(stream) = instance
+                .execute_script("squares", query_ctx)
+                .await
+                .unwrap() else {
+                unreachable!()
+            };
+            util::collect(stream).await
+        }
+    };
+    let _ = run_script(script.to_string(), "guest").await.unwrap();
+
+    let output = execute_sql(&instance, "select n, square from squares order by n").await;
+    let expected = "\
++---+--------+
+| n | square |
++---+--------+
+| 0 | 0      |
+| 1 | 1      |
+| 2 | 4      |
++---+--------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Columns not in the table are rejected as the inserts from SQL.
+    let cube_script = script.replace("\"square\"", "\"cube\"");
+    assert!(run_script(cube_script, "guest").await.is_err());
+
+    // Scripts can't write system tables, and only admins could write tables outside the
+    // database of the user.
+    let system_script = script.replace("gt.insert(\"squares\"", "gt.insert(\"scripts\"");
+    assert!(run_script(system_script, "greptime").await.is_err());
+    let other_db_script = script.replace("gt.insert(\"squares\"", "gt.insert(\"other.squares\"");
+    let err = run_script(other_db_script, "guest").await.unwrap_err();
+    assert!(err.to_string().contains("not allowed"), "{err}");
+}
+
 async fn execute_sql(instance: &MockInstance, sql: &str) -> Output {
     execute_sql_in_db(instance, sql, DEFAULT_SCHEMA_NAME).await
 }
 
+async fn try_execute_sql(
+    instance: &MockInstance,
+    sql: &str,
+) -> Result<Output, crate::error::Error> {
+    try_execute_sql_in_db(instance, sql, DEFAULT_SCHEMA_NAME).await
+}
+
 async fn try_execute_sql_in_db(
     instance: &MockInstance,
     sql: &str,
@@ -41,7 +41,16 @@ pub(crate) struct MockInstance {
 
 impl MockInstance {
     pub(crate) async fn new(name: &str) -> Self {
-        let (opts, _guard) = create_tmp_dir_and_datanode_opts(name);
+        Self::with_opts(name, |_| {}).await
+    }
+
+    /// Creates an instance with the options updated by `update_opts`.
+    pub(crate) async fn with_opts(
+        name: &str,
+        update_opts: impl FnOnce(&mut DatanodeOptions),
+    ) -> Self {
+        let (mut opts, _guard) = create_tmp_dir_and_datanode_opts(name);
+        update_opts(&mut opts);
 
         let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
         instance.start().await.unwrap();
@@ -69,6 +78,7 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
         },
         storage: ObjectStoreConfig::File(FileConfig {
             data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
         }),
         mode: Mode::Standalone,
         ..Default::default()
@@ -22,10 +22,10 @@ use serde::{Deserialize, Serialize};
 use crate::error::{self, Error, Result};
 use crate::type_id::LogicalTypeId;
 use crate::types::{
-    BinaryType, BooleanType, DateTimeType, DateType, Float32Type, Float64Type, Int16Type,
-    Int32Type, Int64Type, Int8Type, ListType, NullType, StringType, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, TimestampType,
-    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    BinaryType, BooleanType, DateTimeType, DateType, Decimal128Type, Float32Type, Float64Type,
+    HistogramType, Int16Type, Int32Type, Int64Type, Int8Type, ListType, NullType, StringType,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use crate::value::Value;
 use crate::vectors::MutableVector;
@@ -47,6 +47,7 @@ pub enum ConcreteDataType {
     UInt64(UInt64Type),
     Float32(Float32Type),
     Float64(Float64Type),
+    Decimal128(Decimal128Type),
 
     // String types:
     Binary(BinaryType),
@@ -59,6 +60,7 @@ pub enum ConcreteDataType {
 
     // Compound types:
     List(ListType),
+    Histogram(HistogramType),
 }
 
 // TODO(yingwen): Refactor these `is_xxx()` methods, such as adding a `properties()` method
@@ -71,14 +73,23 @@ impl ConcreteDataType {
         )
     }
 
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, ConcreteDataType::Decimal128(_))
+    }
+
     pub fn is_boolean(&self) -> bool {
         matches!(self, ConcreteDataType::Boolean(_))
     }
 
+    pub fn is_string(&self) -> bool {
+        matches!(self, ConcreteDataType::String(_))
+    }
+
     pub fn is_stringifiable(&self) -> bool {
         matches!(
             self,
             ConcreteDataType::String(_)
+                | ConcreteDataType::Binary(_)
                 | ConcreteDataType::Date(_)
                 | ConcreteDataType::DateTime(_)
                 | ConcreteDataType::Timestamp(_)
@@ -92,6 +103,7 @@ impl ConcreteDataType {
                 | ConcreteDataType::Int16(_)
                 | ConcreteDataType::Int32(_)
                 | ConcreteDataType::Int64(_)
+                | ConcreteDataType::Decimal128(_)
                 | ConcreteDataType::Date(_)
                 | ConcreteDataType::DateTime(_)
                 | ConcreteDataType::Timestamp(_)
@@ -135,6 +147,18 @@ impl ConcreteDataType {
         matches!(self, ConcreteDataType::Null(NullType))
     }
 
+    /// Try to cast the type as a [`Decimal128Type`].
+    pub fn as_decimal128(&self) -> Option<Decimal128Type> {
+        match self {
+            ConcreteDataType::Decimal128(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    pub fn is_histogram(&self) -> bool {
+        matches!(self, ConcreteDataType::Histogram(_))
+    }
+
     /// Try to cast the type as a [`ListType`].
     pub fn as_list(&self) -> Option<&ListType> {
         match self {
@@ -164,11 +188,17 @@ impl TryFrom<&ArrowDataType> for ConcreteDataType {
             ArrowDataType::Date32 => Self::date_datatype(),
             ArrowDataType::Date64 => Self::datetime_datatype(),
             ArrowDataType::Timestamp(u, _) => ConcreteDataType::from_arrow_time_unit(u),
+            ArrowDataType::Decimal128(precision, scale) => {
+                Self::decimal128_datatype(*precision, *scale)
+            }
             ArrowDataType::Binary | ArrowDataType::LargeBinary => Self::binary_datatype(),
             ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 => Self::string_datatype(),
             ArrowDataType::List(field) => Self::List(ListType::new(
                 ConcreteDataType::from_arrow_type(field.data_type()),
             )),
+            ArrowDataType::Struct(fields) if HistogramType::is_histogram_fields(fields) => {
+                Self::histogram_datatype()
+            }
             _ => {
                 return error::UnsupportedArrowTypeSnafu {
                     arrow_type: dt.clone(),
@@ -240,9 +270,21 @@ impl ConcreteDataType {
         }
     }
 
+    pub fn decimal128_datatype(precision: u8, scale: i8) -> ConcreteDataType {
+        ConcreteDataType::Decimal128(Decimal128Type::new(precision, scale))
+    }
+
+    pub fn decimal128_default_datatype() -> ConcreteDataType {
+        ConcreteDataType::Decimal128(Decimal128Type::default())
+    }
+
     pub fn list_datatype(item_type: ConcreteDataType) -> ConcreteDataType {
         ConcreteDataType::List(ListType::new(item_type))
     }
+
+    pub fn histogram_datatype() -> ConcreteDataType {
+        ConcreteDataType::Histogram(HistogramType)
+    }
 }
 
 /// Data type abstraction.
@@ -360,6 +402,10 @@ mod tests {
             ConcreteDataType::from_arrow_type(&ArrowDataType::Date32),
             ConcreteDataType::Date(_)
         ));
+        assert_eq!(
+            ConcreteDataType::from_arrow_type(&ArrowDataType::Decimal128(10, 2)),
+            ConcreteDataType::decimal128_datatype(10, 2)
+        );
     }
 
     #[test]
@@ -434,6 +480,7 @@ mod tests {
         assert!(!ConcreteDataType::int32_datatype().is_stringifiable());
         assert!(!ConcreteDataType::float32_datatype().is_stringifiable());
         assert!(ConcreteDataType::string_datatype().is_stringifiable());
+        assert!(ConcreteDataType::binary_datatype().is_stringifiable());
         assert!(ConcreteDataType::date_datatype().is_stringifiable());
         assert!(ConcreteDataType::datetime_datatype().is_stringifiable());
         assert!(ConcreteDataType::timestamp_second_datatype().is_stringifiable());
@@ -454,6 +501,7 @@ mod tests {
         assert!(ConcreteDataType::timestamp_millisecond_datatype().is_signed());
         assert!(ConcreteDataType::timestamp_microsecond_datatype().is_signed());
         assert!(ConcreteDataType::timestamp_nanosecond_datatype().is_signed());
+        assert!(ConcreteDataType::decimal128_default_datatype().is_signed());
 
         assert!(!ConcreteDataType::uint8_datatype().is_signed());
         assert!(!ConcreteDataType::uint16_datatype().is_signed());
@@ -492,6 +540,16 @@ mod tests {
         assert_eq!(10, nums.len());
     }
 
+    #[test]
+    fn test_as_decimal128() {
+        assert_eq!(
+            Some(Decimal128Type::new(10, 2)),
+            ConcreteDataType::decimal128_datatype(10, 2).as_decimal128()
+        );
+        assert!(ConcreteDataType::decimal128_default_datatype().is_decimal());
+        assert!(ConcreteDataType::int32_datatype().as_decimal128().is_none());
+    }
+
     #[test]
     fn test_as_list() {
         let list_type = ConcreteDataType::list_datatype(ConcreteDataType::int32_datatype());
@@ -501,4 +559,15 @@ mod tests {
         );
         assert!(ConcreteDataType::int32_datatype().as_list().is_none());
     }
+
+    #[test]
+    fn test_histogram_datatype() {
+        let histogram_type = ConcreteDataType::histogram_datatype();
+        assert!(histogram_type.is_histogram());
+        assert_eq!(
+            histogram_type,
+            ConcreteDataType::from_arrow_type(&histogram_type.as_arrow_type())
+        );
+        assert!(ConcreteDataType::try_from(&ArrowDataType::Struct(vec![])).is_err());
+    }
 }
@@ -0,0 +1,338 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error::{self, Error, Result};
+
+/// The max precision of a 128-bit decimal, the same as arrow's `DECIMAL128_MAX_PRECISION`.
+pub const DECIMAL128_MAX_PRECISION: u8 = 38;
+/// The max scale of a 128-bit decimal, the same as arrow's `DECIMAL128_MAX_SCALE`.
+pub const DECIMAL128_MAX_SCALE: i8 = 38;
+/// Default precision and scale of `DECIMAL` without arguments, follows MySQL.
+pub const DECIMAL128_DEFAULT_PRECISION: u8 = 10;
+pub const DECIMAL128_DEFAULT_SCALE: i8 = 0;
+
+/// 128-bit decimal, the real value is `value * 10^(-scale)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Decimal128 {
+    value: i128,
+    precision: u8,
+    scale: i8,
+}
+
+impl Decimal128 {
+    pub fn new(value: i128, precision: u8, scale: i8) -> Self {
+        Self {
+            value,
+            precision,
+            scale,
+        }
+    }
+
+    /// Creates a decimal, returns error if the precision or scale is invalid.
+    pub fn try_new(value: i128, precision: u8, scale: i8) -> Result<Self> {
+        validate_precision_and_scale(precision, scale)?;
+        Ok(Self::new(value, precision, scale))
+    }
+
+    /// Returns the unscaled value.
+    pub fn val(&self) -> i128 {
+        self.value
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn scale(&self) -> i8 {
+        self.scale
+    }
+
+    /// Converts the decimal to f64, may lose precision.
+    pub fn to_f64(&self) -> f64 {
+        self.value as f64 / 10_f64.powi(self.scale as i32)
+    }
+
+    /// Rescales the decimal to `scale`, digits are rounded half away from zero if `scale`
+    /// is less than the current scale. Returns `None` if the value has more digits than
+    /// [DECIMAL128_MAX_PRECISION] after rescaling.
+    pub fn rescale(&self, scale: i8) -> Option<Self> {
+        if scale == self.scale {
+            return Some(*self);
+        }
+        let value = self.rescaled_value(scale)?;
+        let precision = (self.precision as i16 + scale as i16 - self.scale as i16)
+            .clamp(1, DECIMAL128_MAX_PRECISION as i16) as u8;
+        fits_precision(value, DECIMAL128_MAX_PRECISION).then(|| Self::new(value, precision, scale))
+    }
+
+    /// Rescales the decimal to `scale` like [Decimal128::rescale], and checks the value
+    /// fits the `precision`. Returns `None` on overflow.
+    pub fn rescale_to(&self, precision: u8, scale: i8) -> Option<Self> {
+        let value = self.rescale(scale)?.val();
+        fits_precision(value, precision).then(|| Self::new(value, precision, scale))
+    }
+
+    /// Rescales the unscaled value to `scale`, returns `None` on overflow.
+    fn rescaled_value(&self, scale: i8) -> Option<i128> {
+        let diff = scale as i32 - self.scale as i32;
+        if diff >= 0 {
+            10_i128
+                .checked_pow(diff as u32)
+                .and_then(|m| self.value.checked_mul(m))
+        } else {
+            let Some(d) = 10_i128.checked_pow((-diff) as u32) else {
+                // All digits are dropped, and the value is less than half of `d`.
+                return Some(0);
+            };
+            let (quotient, remainder) = (self.value / d, self.value % d);
+            // Rounds half away from zero, the remainder has the same sign as the value.
+            if remainder.unsigned_abs() >= (d / 2) as u128 {
+                Some(quotient + self.value.signum())
+            } else {
+                Some(quotient)
+            }
+        }
+    }
+}
+
+/// Returns true if the unscaled `value` has no more than `precision` digits.
+fn fits_precision(value: i128, precision: u8) -> bool {
+    match 10_u128.checked_pow(precision as u32) {
+        Some(bound) => value.unsigned_abs() < bound,
+        None => true,
+    }
+}
+
+impl Default for Decimal128 {
+    fn default() -> Self {
+        Self::new(0, DECIMAL128_DEFAULT_PRECISION, DECIMAL128_DEFAULT_SCALE)
+    }
+}
+
+/// Checks whether the `precision` and `scale` are valid for [Decimal128].
+pub fn validate_precision_and_scale(precision: u8, scale: i8) -> Result<()> {
+    ensure!(
+        precision > 0 && precision <= DECIMAL128_MAX_PRECISION,
+        error::InvalidPrecisionOrScaleSnafu {
+            reason: format!(
+                "precision {precision} should be in range [1, {DECIMAL128_MAX_PRECISION}]"
+            ),
+        }
+    );
+    ensure!(
+        scale <= DECIMAL128_MAX_SCALE && scale as i16 <= precision as i16,
+        error::InvalidPrecisionOrScaleSnafu {
+            reason: format!("scale {scale} should not be greater than precision {precision}"),
+        }
+    );
+    Ok(())
+}
+
+impl PartialOrd for Decimal128 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal128 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = if self.scale == other.scale {
+            self.value.cmp(&other.value)
+        } else {
+            let scale = self.scale.max(other.scale);
+            match (self.rescaled_value(scale), other.rescaled_value(scale)) {
+                (Some(v1), Some(v2)) => v1.cmp(&v2),
+                _ => self.to_f64().total_cmp(&other.to_f64()),
+            }
+        };
+        // Keep consistent with `Eq`.
+        ordering
+            .then(self.scale.cmp(&other.scale))
+            .then(self.precision.cmp(&other.precision))
+    }
+}
+
+impl Display for Decimal128 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.scale <= 0 {
+            let zeros = "0".repeat((-self.scale) as usize);
+            return if self.value == 0 {
+                write!(f, "0")
+            } else {
+                write!(f, "{}{zeros}", self.value)
+            };
+        }
+
+        let scale = self.scale as usize;
+        let digits = self.value.unsigned_abs().to_string();
+        let sign = if self.value < 0 { "-" } else { "" };
+        if digits.len() > scale {
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{sign}{integer}.{fraction}")
+        } else {
+            write!(f, "{sign}0.{digits:0>scale$}")
+        }
+    }
+}
+
+impl FromStr for Decimal128 {
+    type Err = Error;
+
+    /// Parses a decimal literal like `-123.45`, the precision and scale are inferred
+    /// from the digits of the literal.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || error::ParseDecimalSnafu { raw: s.to_string() }.build();
+
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (unsigned, ""),
+        };
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let digits = format!("{integer}{fraction}");
+        let digits = digits.trim_start_matches('0');
+        let scale = fraction.len();
+        let precision = digits.len().max(scale).max(1);
+        if precision > DECIMAL128_MAX_PRECISION as usize {
+            return Err(invalid());
+        }
+        let value = if digits.is_empty() {
+            0
+        } else {
+            digits.parse::<i128>().map_err(|_| invalid())?
+        };
+        let value = if negative { -value } else { value };
+
+        Ok(Self::new(value, precision as u8, scale as i8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!("123.45", Decimal128::new(12345, 5, 2).to_string());
+        assert_eq!("-123.45", Decimal128::new(-12345, 5, 2).to_string());
+        assert_eq!("0.05", Decimal128::new(5, 3, 2).to_string());
+        assert_eq!("-0.005", Decimal128::new(-5, 3, 3).to_string());
+        assert_eq!("12300", Decimal128::new(123, 5, -2).to_string());
+        assert_eq!("0", Decimal128::new(0, 5, -2).to_string());
+        assert_eq!("42", Decimal128::new(42, 10, 0).to_string());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            Decimal128::new(12345, 5, 2),
+            "123.45".parse::<Decimal128>().unwrap()
+        );
+        assert_eq!(
+            Decimal128::new(-12345, 5, 2),
+            "-123.45".parse::<Decimal128>().unwrap()
+        );
+        assert_eq!(
+            Decimal128::new(5, 3, 3),
+            "0.005".parse::<Decimal128>().unwrap()
+        );
+        assert_eq!(
+            Decimal128::new(42, 2, 0),
+            "+42".parse::<Decimal128>().unwrap()
+        );
+        assert_eq!(Decimal128::new(0, 1, 0), "0".parse::<Decimal128>().unwrap());
+
+        assert!("".parse::<Decimal128>().is_err());
+        assert!(".".parse::<Decimal128>().is_err());
+        assert!("1.2.3".parse::<Decimal128>().is_err());
+        assert!("abc".parse::<Decimal128>().is_err());
+        assert!("1".repeat(39).parse::<Decimal128>().is_err());
+    }
+
+    #[test]
+    fn test_cmp() {
+        let a = Decimal128::new(12345, 5, 2);
+        let b = Decimal128::new(123450, 6, 3);
+        let c = Decimal128::new(12346, 5, 2);
+        assert!(a < c);
+        assert!(b < c);
+        assert_eq!(Ordering::Less, a.cmp(&b));
+        assert_eq!(Ordering::Equal, a.cmp(&a));
+        assert!(Decimal128::new(-1, 5, 2) < Decimal128::new(0, 5, 2));
+    }
+
+    #[test]
+    fn test_validate_precision_and_scale() {
+        assert!(Decimal128::try_new(1, 38, 10).is_ok());
+        assert!(Decimal128::try_new(1, 10, -2).is_ok());
+        assert!(Decimal128::try_new(1, 0, 0).is_err());
+        assert!(Decimal128::try_new(1, 39, 0).is_err());
+        assert!(Decimal128::try_new(1, 10, 11).is_err());
+    }
+
+    #[test]
+    fn test_rescale() {
+        let d = Decimal128::new(15, 5, 1);
+        assert_eq!(Some(Decimal128::new(150, 6, 2)), d.rescale(2));
+        assert_eq!(Some(Decimal128::new(2, 4, 0)), d.rescale(0));
+        assert_eq!(Some(d), d.rescale(1));
+        assert_eq!(None, Decimal128::new(i128::MAX, 38, 0).rescale(2));
+        assert_eq!(None, Decimal128::new(10_i128.pow(37), 38, 0).rescale(1));
+
+        // Rounds half away from zero.
+        let round = |value, scale| Decimal128::new(value, 10, 3).rescale(scale).unwrap().val();
+        assert_eq!(123, round(1234, 2));
+        assert_eq!(124, round(1235, 2));
+        assert_eq!(-124, round(-1235, 2));
+        assert_eq!(-123, round(-1234, 2));
+        assert_eq!(1, round(500, 0));
+        assert_eq!(0, round(499, 0));
+        assert_eq!(0, round(1234, -60));
+    }
+
+    #[test]
+    fn test_rescale_to() {
+        let d = Decimal128::new(12345, 5, 2);
+        assert_eq!(Some(Decimal128::new(1235, 4, 1)), d.rescale_to(4, 1));
+        assert_eq!(Some(Decimal128::new(123450, 6, 3)), d.rescale_to(6, 3));
+        // 123.450 doesn't fit DECIMAL(5, 3).
+        assert_eq!(None, d.rescale_to(5, 3));
+        assert_eq!(None, Decimal128::new(-99995, 5, 2).rescale_to(4, 1));
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(123.45, Decimal128::new(12345, 5, 2).to_f64());
+        assert_eq!(-0.5, Decimal128::new(-5, 2, 1).to_f64());
+    }
+}
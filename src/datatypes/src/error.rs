@@ -105,6 +105,28 @@ pub enum Error {
         reason: String,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Invalid precision or scale of decimal, reason: {}", reason))]
+    InvalidPrecisionOrScale {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to parse decimal from {}", raw))]
+    ParseDecimal { raw: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "Decimal {} overflows the type Decimal128({}, {})",
+        value,
+        precision,
+        scale
+    ))]
+    DecimalOverflow {
+        value: String,
+        precision: u8,
+        scale: i8,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for Error {
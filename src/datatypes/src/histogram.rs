@@ -0,0 +1,279 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// A histogram with explicit bucket boundaries, the same layout as Prometheus' classic
+/// histograms: `bounds` are the upper bounds of the buckets in ascending order and `counts`
+/// are the cumulative counts of the buckets. The last bound should be `+Inf` so the last
+/// count is the total number of observations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<f64>,
+}
+
+impl Histogram {
+    /// Creates a histogram from bucket bounds and cumulative counts. Returns `None`
+    /// if the bounds and counts have different lengths.
+    pub fn new(bounds: Vec<f64>, counts: Vec<f64>) -> Option<Self> {
+        if bounds.len() != counts.len() {
+            return None;
+        }
+        Some(Self { bounds, counts })
+    }
+
+    pub fn bounds(&self) -> &[f64] {
+        &self.bounds
+    }
+
+    pub fn counts(&self) -> &[f64] {
+        &self.counts
+    }
+
+    /// Converts the histogram to JSON like `{"bounds": [1.0, "+Inf"], "counts": [2.0, 3.0]}`.
+    /// Non-finite values are encoded as strings `"+Inf"`, `"-Inf"` and `"NaN"` as Prometheus
+    /// does, since JSON numbers can't represent them.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "bounds": f64s_to_json(&self.bounds),
+            "counts": f64s_to_json(&self.counts),
+        })
+    }
+
+    /// Consumes the histogram and returns its bounds and counts.
+    pub fn into_parts(self) -> (Vec<f64>, Vec<f64>) {
+        (self.bounds, self.counts)
+    }
+
+    /// Estimates the `q`-quantile of the histogram, follows the semantics of Prometheus'
+    /// `histogram_quantile()`:
+    /// - returns `-Inf` if `q < 0` and `+Inf` if `q > 1`;
+    /// - returns `NaN` if there are less than two buckets, the last bound is not `+Inf`
+    ///   or there is no observation;
+    /// - otherwise assumes the observations are distributed linearly in the bucket.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if q.is_nan() {
+            return f64::NAN;
+        }
+        if q < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if q > 1.0 {
+            return f64::INFINITY;
+        }
+
+        let n = self.bounds.len().min(self.counts.len());
+        if n < 2 || self.bounds[n - 1] != f64::INFINITY {
+            return f64::NAN;
+        }
+        let observations = self.counts[n - 1];
+        if observations.is_nan() || observations <= 0.0 {
+            return f64::NAN;
+        }
+
+        let mut rank = q * observations;
+        let b = self.counts[..n]
+            .iter()
+            .position(|count| *count >= rank)
+            .unwrap_or(n - 1);
+
+        if b == n - 1 {
+            return self.bounds[n - 2];
+        }
+        if b == 0 && self.bounds[0] <= 0.0 {
+            return self.bounds[0];
+        }
+
+        let (bucket_start, count_start) = if b > 0 {
+            (self.bounds[b - 1], self.counts[b - 1])
+        } else {
+            (0.0, 0.0)
+        };
+        let bucket_end = self.bounds[b];
+        let count = self.counts[b] - count_start;
+        rank -= count_start;
+        if count <= 0.0 {
+            return bucket_end;
+        }
+        bucket_start + (bucket_end - bucket_start) * (rank / count)
+    }
+
+    /// Merges `other` into this histogram by summing up the counts of each bucket.
+    /// Returns false and leaves this histogram untouched if the bounds of the two
+    /// histograms are different.
+    pub fn merge(&mut self, other: &Histogram) -> bool {
+        if self.bounds != other.bounds {
+            return false;
+        }
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += *other;
+        }
+        true
+    }
+}
+
+fn f64s_to_json(values: &[f64]) -> Vec<serde_json::Value> {
+    values
+        .iter()
+        .map(|v| {
+            if v.is_nan() {
+                serde_json::Value::from("NaN")
+            } else if *v == f64::INFINITY {
+                serde_json::Value::from("+Inf")
+            } else if *v == f64::NEG_INFINITY {
+                serde_json::Value::from("-Inf")
+            } else {
+                serde_json::Value::from(*v)
+            }
+        })
+        .collect()
+}
+
+/// Compares two float slices by [f64::total_cmp] so histograms have a total order.
+fn cmp_f64s(lhs: &[f64], rhs: &[f64]) -> Ordering {
+    lhs.iter()
+        .zip(rhs.iter())
+        .map(|(l, r)| l.total_cmp(r))
+        .find(|ord| *ord != Ordering::Equal)
+        .unwrap_or_else(|| lhs.len().cmp(&rhs.len()))
+}
+
+impl PartialEq for Histogram {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Histogram {}
+
+impl PartialOrd for Histogram {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Histogram {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_f64s(&self.bounds, &other.bounds).then_with(|| cmp_f64s(&self.counts, &other.counts))
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "{{bounds: [{}], counts: [{}]}}",
+            join(&self.bounds),
+            join(&self.counts)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_histogram(counts: Vec<f64>) -> Histogram {
+        Histogram::new(vec![1.0, 2.0, 4.0, f64::INFINITY], counts).unwrap()
+    }
+
+    #[test]
+    fn test_to_json() {
+        let h = Histogram::new(
+            vec![f64::NEG_INFINITY, 1.0, f64::INFINITY],
+            vec![0.0, 1.0, f64::NAN],
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::json!({"bounds": ["-Inf", 1.0, "+Inf"], "counts": [0.0, 1.0, "NaN"]}),
+            h.to_json()
+        );
+    }
+
+    #[test]
+    fn test_quantile() {
+        let h = new_histogram(vec![10.0, 20.0, 30.0, 40.0]);
+        assert_eq!(0.5, h.quantile(0.125));
+        assert_eq!(1.0, h.quantile(0.25));
+        assert_eq!(1.5, h.quantile(0.375));
+        assert_eq!(3.0, h.quantile(0.625));
+        // Falls into the +Inf bucket, returns the upper bound of the second last bucket.
+        assert_eq!(4.0, h.quantile(0.9));
+
+        assert_eq!(f64::NEG_INFINITY, h.quantile(-0.1));
+        assert_eq!(f64::INFINITY, h.quantile(1.1));
+        assert!(h.quantile(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_quantile_invalid() {
+        assert!(new_histogram(vec![0.0, 0.0, 0.0, 0.0])
+            .quantile(0.5)
+            .is_nan());
+        assert!(new_histogram(vec![0.0, 0.0, 0.0, f64::NAN])
+            .quantile(0.5)
+            .is_nan());
+
+        let h = Histogram::new(vec![1.0, 2.0], vec![1.0, 2.0]).unwrap();
+        assert!(h.quantile(0.5).is_nan());
+
+        let h = Histogram::new(vec![f64::INFINITY], vec![1.0]).unwrap();
+        assert!(h.quantile(0.5).is_nan());
+
+        assert!(Histogram::default().quantile(0.5).is_nan());
+        assert!(Histogram::new(vec![1.0], vec![]).is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut h = new_histogram(vec![1.0, 2.0, 3.0, 4.0]);
+        assert!(h.merge(&new_histogram(vec![1.0, 1.0, 1.0, 1.0])));
+        assert_eq!(&[2.0, 3.0, 4.0, 5.0], h.counts());
+
+        let other = Histogram::new(vec![1.0, f64::INFINITY], vec![1.0, 1.0]).unwrap();
+        assert!(!h.merge(&other));
+        assert_eq!(&[2.0, 3.0, 4.0, 5.0], h.counts());
+    }
+
+    #[test]
+    fn test_cmp() {
+        let h1 = new_histogram(vec![1.0, 2.0, 3.0, 4.0]);
+        let h2 = new_histogram(vec![1.0, 2.0, 3.0, 5.0]);
+        assert!(h1 < h2);
+        assert_eq!(h1, h1.clone());
+
+        let nan = new_histogram(vec![f64::NAN, 2.0, 3.0, 4.0]);
+        assert_eq!(nan, nan.clone());
+
+        let short = Histogram::new(vec![1.0, 2.0], vec![1.0, 2.0]).unwrap();
+        assert!(short < h1);
+    }
+
+    #[test]
+    fn test_display() {
+        let h = Histogram::new(vec![0.5, f64::INFINITY], vec![1.0, 3.0]).unwrap();
+        assert_eq!("{bounds: [0.5, inf], counts: [1, 3]}", h.to_string());
+    }
+}
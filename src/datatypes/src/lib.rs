@@ -14,7 +14,9 @@
 
 pub mod arrow_array;
 pub mod data_type;
+pub mod decimal;
 pub mod error;
+pub mod histogram;
 pub mod macros;
 pub mod prelude;
 pub mod scalars;
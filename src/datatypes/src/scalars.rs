@@ -16,14 +16,16 @@ use std::any::Any;
 
 use common_time::{Date, DateTime};
 
+use crate::decimal::Decimal128;
+use crate::histogram::Histogram;
 use crate::types::{
     Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
     UInt64Type, UInt8Type,
 };
-use crate::value::{ListValue, ListValueRef, Value};
+use crate::value::{HistogramValueRef, ListValue, ListValueRef, Value};
 use crate::vectors::{
-    BinaryVector, BooleanVector, DateTimeVector, DateVector, ListVector, MutableVector,
-    PrimitiveVector, StringVector, Vector,
+    BinaryVector, BooleanVector, DateTimeVector, DateVector, Decimal128Vector, HistogramVector,
+    ListVector, MutableVector, PrimitiveVector, StringVector, Vector,
 };
 
 fn get_iter_capacity<T, I: Iterator<Item = T>>(iter: &I) -> usize {
@@ -298,6 +300,27 @@ impl<'a> ScalarRef<'a> for DateTime {
     }
 }
 
+impl Scalar for Decimal128 {
+    type VectorType = Decimal128Vector;
+    type RefType<'a> = Decimal128;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        *self
+    }
+
+    fn upcast_gat<'short, 'long: 'short>(long: Self::RefType<'long>) -> Self::RefType<'short> {
+        long
+    }
+}
+
+impl<'a> ScalarRef<'a> for Decimal128 {
+    type ScalarType = Decimal128;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        *self
+    }
+}
+
 // Timestamp types implement Scalar and ScalarRef in `src/timestamp.rs`.
 
 impl Scalar for ListValue {
@@ -332,6 +355,36 @@ impl<'a> ScalarRef<'a> for ListValueRef<'a> {
     }
 }
 
+impl Scalar for Histogram {
+    type VectorType = HistogramVector;
+    type RefType<'a> = HistogramValueRef<'a>;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        HistogramValueRef::Ref { val: self }
+    }
+
+    fn upcast_gat<'short, 'long: 'short>(long: Self::RefType<'long>) -> Self::RefType<'short> {
+        long
+    }
+}
+
+impl<'a> ScalarRef<'a> for HistogramValueRef<'a> {
+    type ScalarType = Histogram;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        match self {
+            HistogramValueRef::Indexed { vector, idx } => match vector.get(*idx) {
+                // The same as `ListValueRef`, returns an empty histogram for a null
+                // value instead of panic.
+                Value::Null => Histogram::default(),
+                Value::Histogram(v) => v,
+                _ => unreachable!(),
+            },
+            HistogramValueRef::Ref { val } => (*val).clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,7 +20,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use arrow::datatypes::{Field, Schema as ArrowSchema};
-pub use column_schema::TIME_INDEX_KEY;
+pub use column_schema::{COMMENT_KEY, TIME_INDEX_KEY};
 use datafusion_common::DFSchemaRef;
 use snafu::{ensure, ResultExt};
 
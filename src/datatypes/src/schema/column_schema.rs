@@ -30,6 +30,8 @@ pub type Metadata = HashMap<String, String>;
 pub const TIME_INDEX_KEY: &str = "greptime:time_index";
 /// Key used to store default constraint in arrow field's metadata.
 const DEFAULT_CONSTRAINT_KEY: &str = "greptime:default_constraint";
+/// Key used to store the comment of the column in arrow field's metadata.
+pub const COMMENT_KEY: &str = "greptime:comment";
 
 /// Schema of a column, used as an immutable struct.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,6 +80,12 @@ impl ColumnSchema {
         &self.metadata
     }
 
+    /// Returns the comment of the column, `None` if the column has no comment.
+    #[inline]
+    pub fn comment(&self) -> Option<&str> {
+        self.metadata.get(COMMENT_KEY).map(|s| s.as_str())
+    }
+
     pub fn with_time_index(mut self, is_time_index: bool) -> Self {
         self.is_time_index = is_time_index;
         if is_time_index {
@@ -101,6 +109,17 @@ impl ColumnSchema {
         Ok(self)
     }
 
+    /// Sets the comment of the column, an empty comment removes the existing comment.
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        let comment = comment.into();
+        if comment.is_empty() {
+            self.metadata.remove(COMMENT_KEY);
+        } else {
+            self.metadata.insert(COMMENT_KEY.to_string(), comment);
+        }
+        self
+    }
+
     /// Creates a new [`ColumnSchema`] with given metadata.
     pub fn with_metadata(mut self, metadata: Metadata) -> Self {
         self.metadata = metadata;
@@ -259,6 +278,20 @@ mod tests {
         assert_eq!(column_schema, new_column_schema);
     }
 
+    #[test]
+    fn test_column_schema_with_comment() {
+        let column_schema = ColumnSchema::new("test", ConcreteDataType::int32_datatype(), true)
+            .with_comment("cpu usage in percent");
+        assert_eq!(Some("cpu usage in percent"), column_schema.comment());
+
+        let field = Field::try_from(&column_schema).unwrap();
+        let new_column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(Some("cpu usage in percent"), new_column_schema.comment());
+
+        let column_schema = column_schema.with_comment("");
+        assert!(column_schema.comment().is_none());
+    }
+
     #[test]
     fn test_column_schema_with_metadata() {
         let mut metadata = Metadata::new();
@@ -29,6 +29,7 @@ pub enum LogicalTypeId {
     UInt64,
     Float32,
     Float64,
+    Decimal128,
 
     // String types:
     String,
@@ -48,6 +49,7 @@ pub enum LogicalTypeId {
     TimestampNanosecond,
 
     List,
+    Histogram,
 }
 
 impl LogicalTypeId {
@@ -73,6 +75,7 @@ impl LogicalTypeId {
             LogicalTypeId::UInt64 => ConcreteDataType::uint64_datatype(),
             LogicalTypeId::Float32 => ConcreteDataType::float32_datatype(),
             LogicalTypeId::Float64 => ConcreteDataType::float64_datatype(),
+            LogicalTypeId::Decimal128 => ConcreteDataType::decimal128_default_datatype(),
             LogicalTypeId::String => ConcreteDataType::string_datatype(),
             LogicalTypeId::Binary => ConcreteDataType::binary_datatype(),
             LogicalTypeId::Date => ConcreteDataType::date_datatype(),
@@ -88,6 +91,7 @@ impl LogicalTypeId {
             LogicalTypeId::List => {
                 ConcreteDataType::list_datatype(ConcreteDataType::null_datatype())
             }
+            LogicalTypeId::Histogram => ConcreteDataType::histogram_datatype(),
         }
     }
 }
@@ -16,6 +16,8 @@ mod binary_type;
 mod boolean_type;
 mod date_type;
 mod datetime_type;
+mod decimal_type;
+mod histogram_type;
 mod list_type;
 mod null_type;
 mod primitive_type;
@@ -27,6 +29,8 @@ pub use binary_type::BinaryType;
 pub use boolean_type::BooleanType;
 pub use date_type::DateType;
 pub use datetime_type::DateTimeType;
+pub use decimal_type::Decimal128Type;
+pub use histogram_type::{HistogramType, HISTOGRAM_BOUNDS_FIELD, HISTOGRAM_COUNTS_FIELD};
 pub use list_type::ListType;
 pub use null_type::NullType;
 pub use primitive_type::{
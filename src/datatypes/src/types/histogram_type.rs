@@ -0,0 +1,104 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use arrow::datatypes::{DataType as ArrowDataType, Field};
+use serde::{Deserialize, Serialize};
+
+use crate::data_type::DataType;
+use crate::histogram::Histogram;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{HistogramVectorBuilder, MutableVector};
+
+/// Name of the field holding the bucket upper bounds in the arrow struct of a histogram.
+pub const HISTOGRAM_BOUNDS_FIELD: &str = "bounds";
+/// Name of the field holding the cumulative bucket counts in the arrow struct of a histogram.
+pub const HISTOGRAM_COUNTS_FIELD: &str = "counts";
+
+/// Histogram type with explicit bucket boundaries, stored as an arrow struct of two
+/// float64 lists: the bucket upper bounds and the cumulative bucket counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistogramType;
+
+impl HistogramType {
+    /// Returns the fields of the arrow struct of a histogram.
+    pub fn arrow_fields() -> Vec<Field> {
+        vec![
+            Field::new(HISTOGRAM_BOUNDS_FIELD, float64_list_type(), true),
+            Field::new(HISTOGRAM_COUNTS_FIELD, float64_list_type(), true),
+        ]
+    }
+
+    /// Returns true if `fields` are the fields of the arrow struct of a histogram.
+    pub fn is_histogram_fields(fields: &[Field]) -> bool {
+        fields.len() == 2
+            && fields[0].name() == HISTOGRAM_BOUNDS_FIELD
+            && fields[1].name() == HISTOGRAM_COUNTS_FIELD
+            && fields
+                .iter()
+                .all(|field| *field.data_type() == float64_list_type())
+    }
+}
+
+fn float64_list_type() -> ArrowDataType {
+    ArrowDataType::List(Box::new(Field::new("item", ArrowDataType::Float64, true)))
+}
+
+impl DataType for HistogramType {
+    fn name(&self) -> &str {
+        "Histogram"
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Histogram
+    }
+
+    fn default_value(&self) -> Value {
+        Value::Histogram(Histogram::default())
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::Struct(Self::arrow_fields())
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(HistogramVectorBuilder::with_capacity(capacity))
+    }
+
+    fn is_timestamp_compatible(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_type() {
+        let t = HistogramType;
+        assert_eq!("Histogram", t.name());
+        assert_eq!(LogicalTypeId::Histogram, t.logical_type_id());
+        assert_eq!(Value::Histogram(Histogram::default()), t.default_value());
+
+        let fields = match t.as_arrow_type() {
+            ArrowDataType::Struct(fields) => fields,
+            other => unreachable!("Unexpected arrow type {other:?}"),
+        };
+        assert!(HistogramType::is_histogram_fields(&fields));
+        assert!(!HistogramType::is_histogram_fields(&fields[..1]));
+        let fields = vec![fields[1].clone(), fields[0].clone()];
+        assert!(!HistogramType::is_histogram_fields(&fields));
+    }
+}
@@ -25,14 +25,16 @@ use common_time::timestamp::{TimeUnit, Timestamp};
 use datafusion_common::ScalarValue;
 pub use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
-use snafu::ensure;
+use snafu::{ensure, OptionExt};
 
+pub use crate::decimal::Decimal128;
 use crate::error;
 use crate::error::Result;
+pub use crate::histogram::Histogram;
 use crate::prelude::*;
 use crate::type_id::LogicalTypeId;
-use crate::types::ListType;
-use crate::vectors::ListVector;
+use crate::types::{HistogramType, ListType};
+use crate::vectors::{HistogramVector, ListVector};
 
 pub type OrderedF32 = OrderedFloat<f32>;
 pub type OrderedF64 = OrderedFloat<f64>;
@@ -56,6 +58,7 @@ pub enum Value {
     Int64(i64),
     Float32(OrderedF32),
     Float64(OrderedF64),
+    Decimal128(Decimal128),
 
     // String types:
     String(StringBytes),
@@ -67,6 +70,7 @@ pub enum Value {
     Timestamp(Timestamp),
 
     List(ListValue),
+    Histogram(Histogram),
 }
 
 impl Display for Value {
@@ -84,6 +88,7 @@ impl Display for Value {
             Value::Int64(v) => write!(f, "{v}"),
             Value::Float32(v) => write!(f, "{v}"),
             Value::Float64(v) => write!(f, "{v}"),
+            Value::Decimal128(v) => write!(f, "{v}"),
             Value::String(v) => write!(f, "{}", v.as_utf8()),
             Value::Binary(v) => {
                 let hex = v
@@ -106,6 +111,7 @@ impl Display for Value {
                     .join(", ");
                 write!(f, "{}[{}]", v.datatype.name(), items)
             }
+            Value::Histogram(v) => write!(f, "{v}"),
         }
     }
 }
@@ -129,12 +135,14 @@ impl Value {
             Value::Int64(_) => ConcreteDataType::int64_datatype(),
             Value::Float32(_) => ConcreteDataType::float32_datatype(),
             Value::Float64(_) => ConcreteDataType::float64_datatype(),
+            Value::Decimal128(v) => ConcreteDataType::decimal128_datatype(v.precision(), v.scale()),
             Value::String(_) => ConcreteDataType::string_datatype(),
             Value::Binary(_) => ConcreteDataType::binary_datatype(),
             Value::Date(_) => ConcreteDataType::date_datatype(),
             Value::DateTime(_) => ConcreteDataType::datetime_datatype(),
             Value::Timestamp(v) => ConcreteDataType::timestamp_datatype(v.unit()),
             Value::List(list) => ConcreteDataType::list_datatype(list.datatype().clone()),
+            Value::Histogram(_) => ConcreteDataType::histogram_datatype(),
         }
     }
 
@@ -170,11 +178,13 @@ impl Value {
             Value::Int64(v) => ValueRef::Int64(*v),
             Value::Float32(v) => ValueRef::Float32(*v),
             Value::Float64(v) => ValueRef::Float64(*v),
+            Value::Decimal128(v) => ValueRef::Decimal128(*v),
             Value::String(v) => ValueRef::String(v.as_utf8()),
             Value::Binary(v) => ValueRef::Binary(v),
             Value::Date(v) => ValueRef::Date(*v),
             Value::DateTime(v) => ValueRef::DateTime(*v),
             Value::List(v) => ValueRef::List(ListValueRef::Ref { val: v }),
+            Value::Histogram(v) => ValueRef::Histogram(HistogramValueRef::Ref { val: v }),
             Value::Timestamp(v) => ValueRef::Timestamp(*v),
         }
     }
@@ -194,9 +204,11 @@ impl Value {
             Value::Int64(_) => LogicalTypeId::Int64,
             Value::Float32(_) => LogicalTypeId::Float32,
             Value::Float64(_) => LogicalTypeId::Float64,
+            Value::Decimal128(_) => LogicalTypeId::Decimal128,
             Value::String(_) => LogicalTypeId::String,
             Value::Binary(_) => LogicalTypeId::Binary,
             Value::List(_) => LogicalTypeId::List,
+            Value::Histogram(_) => LogicalTypeId::Histogram,
             Value::Date(_) => LogicalTypeId::Date,
             Value::DateTime(_) => LogicalTypeId::DateTime,
             Value::Timestamp(t) => match t.unit() {
@@ -234,6 +246,22 @@ impl Value {
             Value::Int64(v) => ScalarValue::Int64(Some(*v)),
             Value::Float32(v) => ScalarValue::Float32(Some(v.0)),
             Value::Float64(v) => ScalarValue::Float64(Some(v.0)),
+            Value::Decimal128(v) => {
+                // Rescale the value to the output type as the scale of the value might be
+                // different from the scale of the column.
+                let (precision, scale) = output_type
+                    .as_decimal128()
+                    .map(|t| (t.precision(), t.scale()))
+                    .unwrap_or((v.precision(), v.scale()));
+                let value = v.rescale_to(precision, scale).with_context(|| {
+                    error::DecimalOverflowSnafu {
+                        value: v.to_string(),
+                        precision,
+                        scale,
+                    }
+                })?;
+                ScalarValue::Decimal128(Some(value.val()), precision, scale)
+            }
             Value::String(v) => ScalarValue::Utf8(Some(v.as_utf8().to_string())),
             Value::Binary(v) => ScalarValue::LargeBinary(Some(v.to_vec())),
             Value::Date(v) => ScalarValue::Date32(Some(v.val())),
@@ -244,6 +272,7 @@ impl Value {
                 let list_type = output_type.as_list().unwrap();
                 list.try_to_scalar_value(list_type)?
             }
+            Value::Histogram(h) => histogram_to_scalar_value(Some(h)),
             Value::Timestamp(t) => timestamp_to_scalar_value(t.unit(), Some(t.value())),
         };
 
@@ -265,6 +294,7 @@ fn to_null_value(output_type: &ConcreteDataType) -> ScalarValue {
         ConcreteDataType::UInt64(_) => ScalarValue::UInt64(None),
         ConcreteDataType::Float32(_) => ScalarValue::Float32(None),
         ConcreteDataType::Float64(_) => ScalarValue::Float64(None),
+        ConcreteDataType::Decimal128(t) => ScalarValue::Decimal128(None, t.precision(), t.scale()),
         ConcreteDataType::Binary(_) => ScalarValue::LargeBinary(None),
         ConcreteDataType::String(_) => ScalarValue::Utf8(None),
         ConcreteDataType::Date(_) => ScalarValue::Date32(None),
@@ -273,6 +303,7 @@ fn to_null_value(output_type: &ConcreteDataType) -> ScalarValue {
         ConcreteDataType::List(_) => {
             ScalarValue::List(None, Box::new(new_item_field(output_type.as_arrow_type())))
         }
+        ConcreteDataType::Histogram(_) => histogram_to_scalar_value(None),
     }
 }
 
@@ -280,6 +311,41 @@ fn new_item_field(data_type: ArrowDataType) -> Field {
     Field::new("item", data_type, false)
 }
 
+fn histogram_to_scalar_value(histogram: Option<&Histogram>) -> ScalarValue {
+    let to_list = |values: &[f64]| {
+        ScalarValue::List(
+            Some(
+                values
+                    .iter()
+                    .map(|v| ScalarValue::Float64(Some(*v)))
+                    .collect(),
+            ),
+            Box::new(Field::new("item", ArrowDataType::Float64, true)),
+        )
+    };
+    let values = histogram.map(|h| vec![to_list(h.bounds()), to_list(h.counts())]);
+    ScalarValue::Struct(values, Box::new(HistogramType::arrow_fields()))
+}
+
+/// Converts the values of a histogram struct scalar to [Histogram], returns `None` if
+/// the bounds or counts are not lists of non-null floats.
+fn scalar_values_to_histogram(values: &[ScalarValue]) -> Option<Histogram> {
+    let to_f64s = |value: &ScalarValue| match value {
+        ScalarValue::List(Some(items), _) => items
+            .iter()
+            .map(|item| match item {
+                ScalarValue::Float64(v) => *v,
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+    match values {
+        [bounds, counts] => Histogram::new(to_f64s(bounds)?, to_f64s(counts)?),
+        _ => None,
+    }
+}
+
 fn timestamp_to_scalar_value(unit: TimeUnit, val: Option<i64>) -> ScalarValue {
     match unit {
         TimeUnit::Second => ScalarValue::TimestampSecond(val, None),
@@ -329,12 +395,14 @@ macro_rules! impl_ord_for_value_like {
                 ($Type::Int64(v1), $Type::Int64(v2)) => v1.cmp(v2),
                 ($Type::Float32(v1), $Type::Float32(v2)) => v1.cmp(v2),
                 ($Type::Float64(v1), $Type::Float64(v2)) => v1.cmp(v2),
+                ($Type::Decimal128(v1), $Type::Decimal128(v2)) => v1.cmp(v2),
                 ($Type::String(v1), $Type::String(v2)) => v1.cmp(v2),
                 ($Type::Binary(v1), $Type::Binary(v2)) => v1.cmp(v2),
                 ($Type::Date(v1), $Type::Date(v2)) => v1.cmp(v2),
                 ($Type::DateTime(v1), $Type::DateTime(v2)) => v1.cmp(v2),
                 ($Type::Timestamp(v1), $Type::Timestamp(v2)) => v1.cmp(v2),
                 ($Type::List(v1), $Type::List(v2)) => v1.cmp(v2),
+                ($Type::Histogram(v1), $Type::Histogram(v2)) => v1.cmp(v2),
                 _ => panic!(
                     "Cannot compare different values {:?} and {:?}",
                     $left, $right
@@ -386,6 +454,7 @@ impl_value_from!(Int32, i32);
 impl_value_from!(Int64, i64);
 impl_value_from!(Float32, f32);
 impl_value_from!(Float64, f64);
+impl_value_from!(Decimal128, Decimal128);
 impl_value_from!(String, StringBytes);
 impl_value_from!(Binary, Bytes);
 impl_value_from!(Date, Date);
@@ -433,11 +502,13 @@ impl TryFrom<Value> for serde_json::Value {
             Value::Int64(v) => serde_json::Value::from(v),
             Value::Float32(v) => serde_json::Value::from(v.0),
             Value::Float64(v) => serde_json::Value::from(v.0),
+            Value::Decimal128(v) => serde_json::Value::String(v.to_string()),
             Value::String(bytes) => serde_json::Value::String(bytes.as_utf8().to_string()),
             Value::Binary(bytes) => serde_json::to_value(bytes)?,
             Value::Date(v) => serde_json::Value::Number(v.val().into()),
             Value::DateTime(v) => serde_json::Value::Number(v.val().into()),
             Value::List(v) => serde_json::to_value(v)?,
+            Value::Histogram(v) => v.to_json(),
             Value::Timestamp(v) => serde_json::to_value(v.value())?,
         };
 
@@ -565,8 +636,22 @@ impl TryFrom<ScalarValue> for Value {
             ScalarValue::TimestampNanosecond(t, _) => t
                 .map(|x| Value::Timestamp(Timestamp::new(x, TimeUnit::Nanosecond)))
                 .unwrap_or(Value::Null),
-            ScalarValue::Decimal128(_, _, _)
-            | ScalarValue::IntervalYearMonth(_)
+            ScalarValue::Decimal128(v, precision, scale) => v
+                .map(|x| Value::Decimal128(Decimal128::new(x, precision, scale)))
+                .unwrap_or(Value::Null),
+            ScalarValue::Struct(values, fields) if HistogramType::is_histogram_fields(&fields) => {
+                match values {
+                    Some(values) => {
+                        Value::Histogram(scalar_values_to_histogram(&values).with_context(
+                            || error::CastTypeSnafu {
+                                msg: format!("Failed to cast {values:?} to histogram value"),
+                            },
+                        )?)
+                    }
+                    None => Value::Null,
+                }
+            }
+            ScalarValue::IntervalYearMonth(_)
             | ScalarValue::IntervalDayTime(_)
             | ScalarValue::IntervalMonthDayNano(_)
             | ScalarValue::Struct(_, _)
@@ -602,6 +687,7 @@ pub enum ValueRef<'a> {
     Int64(i64),
     Float32(OrderedF32),
     Float64(OrderedF64),
+    Decimal128(Decimal128),
 
     // String types:
     String(&'a str),
@@ -612,6 +698,7 @@ pub enum ValueRef<'a> {
     DateTime(DateTime),
     Timestamp(Timestamp),
     List(ListValueRef<'a>),
+    Histogram(HistogramValueRef<'a>),
 }
 
 macro_rules! impl_as_for_value_ref {
@@ -662,6 +749,11 @@ impl<'a> ValueRef<'a> {
         impl_as_for_value_ref!(self, DateTime)
     }
 
+    /// Cast itself to [Decimal128].
+    pub fn as_decimal128(&self) -> Result<Option<Decimal128>> {
+        impl_as_for_value_ref!(self, Decimal128)
+    }
+
     pub fn as_timestamp(&self) -> Result<Option<Timestamp>> {
         impl_as_for_value_ref!(self, Timestamp)
     }
@@ -670,6 +762,11 @@ impl<'a> ValueRef<'a> {
     pub fn as_list(&self) -> Result<Option<ListValueRef>> {
         impl_as_for_value_ref!(self, List)
     }
+
+    /// Cast itself to [HistogramValueRef].
+    pub fn as_histogram(&self) -> Result<Option<HistogramValueRef>> {
+        impl_as_for_value_ref!(self, Histogram)
+    }
 }
 
 impl<'a> PartialOrd for ValueRef<'a> {
@@ -714,6 +811,7 @@ impl_value_ref_from!(Int32, i32);
 impl_value_ref_from!(Int64, i64);
 impl_value_ref_from!(Float32, f32);
 impl_value_ref_from!(Float64, f64);
+impl_value_ref_from!(Decimal128, Decimal128);
 impl_value_ref_from!(Date, Date);
 impl_value_ref_from!(DateTime, DateTime);
 impl_value_ref_from!(Timestamp, Timestamp);
@@ -782,6 +880,57 @@ impl<'a> PartialOrd for ListValueRef<'a> {
     }
 }
 
+impl<'a> From<Option<HistogramValueRef<'a>>> for ValueRef<'a> {
+    fn from(histogram: Option<HistogramValueRef>) -> ValueRef {
+        match histogram {
+            Some(v) => ValueRef::Histogram(v),
+            None => ValueRef::Null,
+        }
+    }
+}
+
+/// Reference to a [Histogram].
+#[derive(Debug, Clone, Copy)]
+pub enum HistogramValueRef<'a> {
+    Indexed {
+        vector: &'a HistogramVector,
+        idx: usize,
+    },
+    Ref {
+        val: &'a Histogram,
+    },
+}
+
+impl<'a> HistogramValueRef<'a> {
+    /// Convert self to [Value]. This method would clone the underlying data.
+    fn to_value(self) -> Value {
+        match self {
+            HistogramValueRef::Indexed { vector, idx } => vector.get(idx),
+            HistogramValueRef::Ref { val } => Value::Histogram(val.clone()),
+        }
+    }
+}
+
+impl<'a> PartialEq for HistogramValueRef<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_value().eq(&other.to_value())
+    }
+}
+
+impl<'a> Eq for HistogramValueRef<'a> {}
+
+impl<'a> Ord for HistogramValueRef<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_value().cmp(&other.to_value())
+    }
+}
+
+impl<'a> PartialOrd for HistogramValueRef<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::datatypes::DataType as ArrowDataType;
@@ -1593,6 +1742,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decimal_value_to_scalar_value() {
+        let decimal_type = ConcreteDataType::decimal128_datatype(10, 2);
+        assert_eq!(
+            ScalarValue::Decimal128(Some(12345), 10, 2),
+            Value::Decimal128(Decimal128::new(12345, 5, 2))
+                .try_to_scalar_value(&decimal_type)
+                .unwrap()
+        );
+        // The value is rescaled to the scale of the output type.
+        assert_eq!(
+            ScalarValue::Decimal128(Some(150), 10, 2),
+            Value::Decimal128(Decimal128::new(15, 2, 1))
+                .try_to_scalar_value(&decimal_type)
+                .unwrap()
+        );
+        assert_eq!(
+            ScalarValue::Decimal128(None, 10, 2),
+            Value::Null.try_to_scalar_value(&decimal_type).unwrap()
+        );
+        assert!(Value::Decimal128(Decimal128::new(10_i128.pow(9), 10, 0))
+            .try_to_scalar_value(&decimal_type)
+            .is_err());
+        assert_eq!(
+            Value::Decimal128(Decimal128::new(12345, 10, 2)),
+            ScalarValue::Decimal128(Some(12345), 10, 2)
+                .try_into()
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Null,
+            ScalarValue::Decimal128(None, 10, 2).try_into().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_histogram_value_to_scalar_value() {
+        let histogram_type = ConcreteDataType::histogram_datatype();
+        let histogram = Histogram::new(vec![1.0, f64::INFINITY], vec![2.0, 3.0]).unwrap();
+        let value = Value::Histogram(histogram.clone());
+        assert_eq!(histogram_type, value.data_type());
+        assert_eq!(LogicalTypeId::Histogram, value.logical_type_id());
+
+        let scalar = value.try_to_scalar_value(&histogram_type).unwrap();
+        assert_eq!(histogram_type.as_arrow_type(), scalar.get_datatype());
+        assert_eq!(value, Value::try_from(scalar).unwrap());
+
+        let null = Value::Null.try_to_scalar_value(&histogram_type).unwrap();
+        assert!(null.is_null());
+        assert_eq!(Value::Null, Value::try_from(null).unwrap());
+
+        let invalid = ScalarValue::Struct(
+            Some(vec![ScalarValue::Float64(Some(1.0)), ScalarValue::Null]),
+            Box::new(HistogramType::arrow_fields()),
+        );
+        assert!(Value::try_from(invalid).is_err());
+
+        assert_eq!(
+            serde_json::json!({"bounds": [1.0, "+Inf"], "counts": [2.0, 3.0]}),
+            serde_json::Value::try_from(value.clone()).unwrap()
+        );
+        assert_eq!(
+            ValueRef::Histogram(HistogramValueRef::Ref { val: &histogram }),
+            value.as_value_ref()
+        );
+        assert_eq!("{bounds: [1, inf], counts: [2, 3]}", value.to_string());
+    }
+
     #[test]
     fn test_timestamp_to_scalar_value() {
         assert_eq!(
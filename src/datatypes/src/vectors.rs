@@ -30,8 +30,10 @@ mod boolean;
 mod constant;
 mod date;
 mod datetime;
+mod decimal;
 mod eq;
 mod helper;
+mod histogram;
 mod list;
 mod null;
 mod operations;
@@ -45,7 +47,9 @@ pub use boolean::{BooleanVector, BooleanVectorBuilder};
 pub use constant::ConstantVector;
 pub use date::{DateVector, DateVectorBuilder};
 pub use datetime::{DateTimeVector, DateTimeVectorBuilder};
+pub use decimal::{Decimal128Iter, Decimal128Vector, Decimal128VectorBuilder};
 pub use helper::Helper;
+pub use histogram::{HistogramIter, HistogramVector, HistogramVectorBuilder};
 pub use list::{ListIter, ListVector, ListVectorBuilder};
 pub use null::{NullVector, NullVectorBuilder};
 pub use primitive::{
@@ -0,0 +1,408 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayBuilder, ArrayData, ArrayIter, ArrayRef, Decimal128Array, Decimal128Builder,
+};
+use arrow::datatypes::DataType as ArrowDataType;
+use snafu::{OptionExt, ResultExt};
+
+use crate::data_type::ConcreteDataType;
+use crate::decimal::Decimal128;
+use crate::error::{self, Result};
+use crate::scalars::{ScalarVector, ScalarVectorBuilder};
+use crate::serialize::Serializable;
+use crate::types::Decimal128Type;
+use crate::value::{Value, ValueRef};
+use crate::vectors::{self, MutableVector, Validity, Vector, VectorRef};
+
+/// Vector of [Decimal128].
+#[derive(Debug, PartialEq)]
+pub struct Decimal128Vector {
+    array: Decimal128Array,
+}
+
+impl Decimal128Vector {
+    /// Creates a vector from unscaled values with default precision and scale.
+    pub fn from_values<I: IntoIterator<Item = i128>>(iter: I) -> Self {
+        Self::from(
+            Decimal128Array::from_iter_values(iter)
+                .with_precision_and_scale(
+                    Decimal128Type::default().precision(),
+                    Decimal128Type::default().scale(),
+                )
+                .unwrap(),
+        )
+    }
+
+    /// Returns a new vector with given `precision` and `scale`, the unscaled values
+    /// are kept unchanged.
+    pub fn with_precision_and_scale(self, precision: u8, scale: i8) -> Result<Self> {
+        let array = self
+            .array
+            .with_precision_and_scale(precision, scale)
+            .context(error::ArrowComputeSnafu)?;
+        Ok(Self { array })
+    }
+
+    pub fn decimal_type(&self) -> Decimal128Type {
+        match self.array.data_type() {
+            ArrowDataType::Decimal128(precision, scale) => Decimal128Type::new(*precision, *scale),
+            // Safety: the data type of a `Decimal128Array` is always `Decimal128`.
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn as_arrow(&self) -> &dyn Array {
+        &self.array
+    }
+
+    fn to_array_data(&self) -> ArrayData {
+        self.array.data().clone()
+    }
+
+    fn from_array_data(data: ArrayData) -> Self {
+        Self {
+            array: Decimal128Array::from(data),
+        }
+    }
+
+    fn new_decimal(&self, value: i128) -> Decimal128 {
+        let decimal_type = self.decimal_type();
+        Decimal128::new(value, decimal_type.precision(), decimal_type.scale())
+    }
+}
+
+impl From<Decimal128Array> for Decimal128Vector {
+    fn from(array: Decimal128Array) -> Self {
+        Self { array }
+    }
+}
+
+impl Vector for Decimal128Vector {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::Decimal128(self.decimal_type())
+    }
+
+    fn vector_type_name(&self) -> String {
+        "Decimal128Vector".to_string()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    fn to_arrow_array(&self) -> ArrayRef {
+        let data = self.to_array_data();
+        Arc::new(Decimal128Array::from(data))
+    }
+
+    fn to_boxed_arrow_array(&self) -> Box<dyn Array> {
+        let data = self.to_array_data();
+        Box::new(Decimal128Array::from(data))
+    }
+
+    fn validity(&self) -> Validity {
+        vectors::impl_validity_for_vector!(self.array)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.array.get_buffer_memory_size()
+    }
+
+    fn null_count(&self) -> usize {
+        self.array.null_count()
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        self.array.is_null(row)
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> VectorRef {
+        let data = self.array.data().slice(offset, length);
+        Arc::new(Self::from_array_data(data))
+    }
+
+    fn get(&self, index: usize) -> Value {
+        match self.get_data(index) {
+            Some(v) => Value::Decimal128(v),
+            None => Value::Null,
+        }
+    }
+
+    fn get_ref(&self, index: usize) -> ValueRef {
+        match self.get_data(index) {
+            Some(v) => ValueRef::Decimal128(v),
+            None => ValueRef::Null,
+        }
+    }
+}
+
+/// Iterator of [Decimal128Vector].
+pub struct Decimal128Iter<'a> {
+    precision: u8,
+    scale: i8,
+    iter: ArrayIter<&'a Decimal128Array>,
+}
+
+impl<'a> Iterator for Decimal128Iter<'a> {
+    type Item = Option<Decimal128>;
+
+    fn next(&mut self) -> Option<Option<Decimal128>> {
+        self.iter
+            .next()
+            .map(|item| item.map(|v| Decimal128::new(v, self.precision, self.scale)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ScalarVector for Decimal128Vector {
+    type OwnedItem = Decimal128;
+    type RefItem<'a> = Decimal128;
+    type Iter<'a> = Decimal128Iter<'a>;
+    type Builder = Decimal128VectorBuilder;
+
+    fn get_data(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        if self.array.is_valid(idx) {
+            Some(self.new_decimal(self.array.value(idx)))
+        } else {
+            None
+        }
+    }
+
+    fn iter_data(&self) -> Self::Iter<'_> {
+        let decimal_type = self.decimal_type();
+        Decimal128Iter {
+            precision: decimal_type.precision(),
+            scale: decimal_type.scale(),
+            iter: self.array.iter(),
+        }
+    }
+}
+
+pub struct Decimal128VectorBuilder {
+    decimal_type: Decimal128Type,
+    mutable_array: Decimal128Builder,
+}
+
+impl Decimal128VectorBuilder {
+    /// Creates a builder of decimal vector whose type is `decimal_type`.
+    pub fn with_type_capacity(decimal_type: Decimal128Type, capacity: usize) -> Self {
+        Self {
+            decimal_type,
+            mutable_array: Decimal128Builder::with_capacity(capacity),
+        }
+    }
+}
+
+impl MutableVector for Decimal128VectorBuilder {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::Decimal128(self.decimal_type)
+    }
+
+    fn len(&self) -> usize {
+        self.mutable_array.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn to_vector(&mut self) -> VectorRef {
+        Arc::new(self.finish())
+    }
+
+    fn push_value_ref(&mut self, value: ValueRef) -> Result<()> {
+        match value.as_decimal128()? {
+            Some(v) => {
+                let (precision, scale) = (self.decimal_type.precision(), self.decimal_type.scale());
+                let v = v.rescale_to(precision, scale).with_context(|| {
+                    error::DecimalOverflowSnafu {
+                        value: v.to_string(),
+                        precision,
+                        scale,
+                    }
+                })?;
+                self.mutable_array.append_value(v.val());
+            }
+            None => self.mutable_array.append_null(),
+        }
+        Ok(())
+    }
+
+    fn extend_slice_of(&mut self, vector: &dyn Vector, offset: usize, length: usize) -> Result<()> {
+        vectors::impl_extend_for_builder!(self, vector, Decimal128Vector, offset, length)
+    }
+}
+
+impl ScalarVectorBuilder for Decimal128VectorBuilder {
+    type VectorType = Decimal128Vector;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self::with_type_capacity(Decimal128Type::default(), capacity)
+    }
+
+    /// Pushes a decimal into the builder, the decimal is rescaled to the scale of
+    /// the builder if their scales are different. Decimals overflowing the type of the
+    /// builder are pushed as nulls, use [MutableVector::push_value_ref] to reject them.
+    fn push(&mut self, value: Option<<Self::VectorType as ScalarVector>::RefItem<'_>>) {
+        let (precision, scale) = (self.decimal_type.precision(), self.decimal_type.scale());
+        match value.and_then(|v| v.rescale_to(precision, scale)) {
+            Some(v) => self.mutable_array.append_value(v.val()),
+            None => self.mutable_array.append_null(),
+        }
+    }
+
+    fn finish(&mut self) -> Self::VectorType {
+        // Safety: precision and scale of the decimal type should have been validated.
+        let array = self
+            .mutable_array
+            .finish()
+            .with_precision_and_scale(self.decimal_type.precision(), self.decimal_type.scale())
+            .unwrap();
+        Decimal128Vector { array }
+    }
+}
+
+impl Serializable for Decimal128Vector {
+    fn serialize_to_json(&self) -> Result<Vec<serde_json::Value>> {
+        self.iter_data()
+            .map(|v| match v {
+                None => Ok(serde_json::Value::Null),
+                // Serializes to string to avoid losing precision.
+                Some(v) => serde_json::to_value(v.to_string()),
+            })
+            .collect::<serde_json::Result<_>>()
+            .context(error::SerializeSnafu)
+    }
+}
+
+vectors::impl_try_from_arrow_array_for_vector!(Decimal128Array, Decimal128Vector);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_type::DataType;
+
+    fn new_decimal_vector(values: &[Option<i128>]) -> Decimal128Vector {
+        let mut builder =
+            Decimal128VectorBuilder::with_type_capacity(Decimal128Type::new(10, 2), values.len());
+        for v in values {
+            builder.push(v.map(|v| Decimal128::new(v, 10, 2)));
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_decimal_vector_misc() {
+        let v = new_decimal_vector(&[Some(12345), None, Some(-1)]);
+
+        assert_eq!(3, v.len());
+        assert_eq!("Decimal128Vector", v.vector_type_name());
+        assert!(!v.is_const());
+        assert_eq!(1, v.null_count());
+        assert_eq!(
+            ConcreteDataType::Decimal128(Decimal128Type::new(10, 2)),
+            v.data_type()
+        );
+
+        assert_eq!(Value::Decimal128(Decimal128::new(12345, 10, 2)), v.get(0));
+        assert_eq!(Value::Null, v.get(1));
+        assert_eq!(
+            ValueRef::Decimal128(Decimal128::new(-1, 10, 2)),
+            v.get_ref(2)
+        );
+
+        let arrow_arr = v.to_arrow_array();
+        assert_eq!(3, arrow_arr.len());
+        assert_eq!(&ArrowDataType::Decimal128(10, 2), arrow_arr.data_type());
+
+        let sliced = v.slice(1, 2);
+        assert_eq!(Value::Null, sliced.get(0));
+        assert_eq!(Value::Decimal128(Decimal128::new(-1, 10, 2)), sliced.get(1));
+    }
+
+    #[test]
+    fn test_decimal_vector_iter() {
+        let v = new_decimal_vector(&[Some(1), None, Some(2)]);
+        let items = v.iter_data().collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Some(Decimal128::new(1, 10, 2)),
+                None,
+                Some(Decimal128::new(2, 10, 2))
+            ],
+            items
+        );
+    }
+
+    #[test]
+    fn test_decimal_vector_builder() {
+        let input = new_decimal_vector(&[Some(1), Some(2), Some(3)]);
+
+        let mut builder = Decimal128Type::new(10, 2).create_mutable_vector(3);
+        // Rescales 1.5 to scale 2.
+        builder
+            .push_value_ref(ValueRef::Decimal128(Decimal128::new(15, 10, 1)))
+            .unwrap();
+        assert!(builder.push_value_ref(ValueRef::Int32(123)).is_err());
+        // 1e8 doesn't fit Decimal128(10, 2).
+        assert!(builder
+            .push_value_ref(ValueRef::Decimal128(Decimal128::new(100_000_000, 10, 0)))
+            .is_err());
+        builder.extend_slice_of(&input, 1, 2).unwrap();
+        assert!(builder
+            .extend_slice_of(&crate::vectors::Int32Vector::from_slice(&[13]), 0, 1)
+            .is_err());
+        let vector = builder.to_vector();
+
+        let expect: VectorRef = Arc::new(new_decimal_vector(&[Some(150), Some(2), Some(3)]));
+        assert_eq!(expect, vector);
+    }
+
+    #[test]
+    fn test_serialize_decimal_vector_to_json() {
+        let v = new_decimal_vector(&[Some(12345), None]);
+        let json_value = v.serialize_to_json().unwrap();
+        assert_eq!(
+            "[\"123.45\",null]",
+            serde_json::to_string(&json_value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_arrow_array() {
+        let array = Decimal128Array::from_iter_values([1, 2])
+            .with_precision_and_scale(5, 1)
+            .unwrap();
+        let array: ArrayRef = Arc::new(array);
+        let v = Decimal128Vector::try_from_arrow_array(array).unwrap();
+        assert_eq!(Decimal128Type::new(5, 1), v.decimal_type());
+        assert_eq!(Value::Decimal128(Decimal128::new(2, 5, 1)), v.get(1));
+    }
+}
@@ -18,9 +18,9 @@ use crate::data_type::DataType;
 use crate::types::TimestampType;
 use crate::vectors::constant::ConstantVector;
 use crate::vectors::{
-    BinaryVector, BooleanVector, DateTimeVector, DateVector, ListVector, PrimitiveVector,
-    StringVector, TimestampMicrosecondVector, TimestampMillisecondVector,
-    TimestampNanosecondVector, TimestampSecondVector, Vector,
+    BinaryVector, BooleanVector, DateTimeVector, DateVector, Decimal128Vector, HistogramVector,
+    ListVector, PrimitiveVector, StringVector, TimestampMicrosecondVector,
+    TimestampMillisecondVector, TimestampNanosecondVector, TimestampSecondVector, Vector,
 };
 use crate::with_match_primitive_type_id;
 
@@ -79,6 +79,7 @@ fn equal(lhs: &dyn Vector, rhs: &dyn Vector) -> bool {
         String(_) => is_vector_eq!(StringVector, lhs, rhs),
         Date(_) => is_vector_eq!(DateVector, lhs, rhs),
         DateTime(_) => is_vector_eq!(DateTimeVector, lhs, rhs),
+        Decimal128(_) => is_vector_eq!(Decimal128Vector, lhs, rhs),
         Timestamp(t) => match t {
             TimestampType::Second(_) => {
                 is_vector_eq!(TimestampSecondVector, lhs, rhs)
@@ -94,6 +95,7 @@ fn equal(lhs: &dyn Vector, rhs: &dyn Vector) -> bool {
             }
         },
         List(_) => is_vector_eq!(ListVector, lhs, rhs),
+        Histogram(_) => is_vector_eq!(HistogramVector, lhs, rhs),
         UInt8(_) | UInt16(_) | UInt32(_) | UInt64(_) | Int8(_) | Int16(_) | Int32(_) | Int64(_)
         | Float32(_) | Float64(_) => {
             with_match_primitive_type_id!(lhs_type.logical_type_id(), |$T| {
@@ -17,7 +17,7 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::array::{Array, ArrayRef, Decimal128Array, StringArray};
 use arrow::compute;
 use arrow::compute::kernels::comparison;
 use arrow::datatypes::{DataType as ArrowDataType, TimeUnit};
@@ -27,13 +27,15 @@ use snafu::{OptionExt, ResultExt};
 use crate::data_type::ConcreteDataType;
 use crate::error::{self, Result};
 use crate::scalars::{Scalar, ScalarVectorBuilder};
-use crate::value::{ListValue, ListValueRef};
+use crate::types::HistogramType;
+use crate::value::{ListValue, ListValueRef, Value};
 use crate::vectors::{
-    BinaryVector, BooleanVector, ConstantVector, DateTimeVector, DateVector, Float32Vector,
-    Float64Vector, Int16Vector, Int32Vector, Int64Vector, Int8Vector, ListVector,
-    ListVectorBuilder, MutableVector, NullVector, StringVector, TimestampMicrosecondVector,
-    TimestampMillisecondVector, TimestampNanosecondVector, TimestampSecondVector, UInt16Vector,
-    UInt32Vector, UInt64Vector, UInt8Vector, Vector, VectorRef,
+    BinaryVector, BooleanVector, ConstantVector, DateTimeVector, DateVector, Decimal128Vector,
+    Float32Vector, Float64Vector, HistogramVector, HistogramVectorBuilder, Int16Vector,
+    Int32Vector, Int64Vector, Int8Vector, ListVector, ListVectorBuilder, MutableVector, NullVector,
+    StringVector, TimestampMicrosecondVector, TimestampMillisecondVector,
+    TimestampNanosecondVector, TimestampSecondVector, UInt16Vector, UInt32Vector, UInt64Vector,
+    UInt8Vector, Vector, VectorRef,
 };
 
 /// Helper functions for `Vector`.
@@ -194,8 +196,21 @@ impl Helper {
                 // Timezone is unimplemented now.
                 ConstantVector::new(Arc::new(TimestampNanosecondVector::from(vec![v])), length)
             }
-            ScalarValue::Decimal128(_, _, _)
-            | ScalarValue::IntervalYearMonth(_)
+            ScalarValue::Decimal128(v, precision, scale) => {
+                let vector = Decimal128Vector::from(
+                    Decimal128Array::from_iter([v])
+                        .with_precision_and_scale(precision, scale)
+                        .context(error::ArrowComputeSnafu)?,
+                );
+                ConstantVector::new(Arc::new(vector), length)
+            }
+            ScalarValue::Struct(values, fields) if HistogramType::is_histogram_fields(&fields) => {
+                let value = Value::try_from(ScalarValue::Struct(values, fields))?;
+                let mut builder = HistogramVectorBuilder::with_capacity(1);
+                builder.push_value_ref(value.as_value_ref())?;
+                ConstantVector::new(builder.to_vector(), length)
+            }
+            ScalarValue::IntervalYearMonth(_)
             | ScalarValue::IntervalDayTime(_)
             | ScalarValue::IntervalMonthDayNano(_)
             | ScalarValue::Struct(_, _)
@@ -237,6 +252,12 @@ impl Helper {
             ArrowDataType::Date32 => Arc::new(DateVector::try_from_arrow_array(array)?),
             ArrowDataType::Date64 => Arc::new(DateTimeVector::try_from_arrow_array(array)?),
             ArrowDataType::List(_) => Arc::new(ListVector::try_from_arrow_array(array)?),
+            ArrowDataType::Decimal128(_, _) => {
+                Arc::new(Decimal128Vector::try_from_arrow_array(array)?)
+            }
+            ArrowDataType::Struct(fields) if HistogramType::is_histogram_fields(fields) => {
+                Arc::new(HistogramVector::try_from_arrow_array(array)?)
+            }
             ArrowDataType::Timestamp(unit, _) => match unit {
                 TimeUnit::Second => Arc::new(TimestampSecondVector::try_from_arrow_array(array)?),
                 TimeUnit::Millisecond => {
@@ -262,7 +283,6 @@ impl Helper {
             | ArrowDataType::Struct(_)
             | ArrowDataType::Union(_, _, _)
             | ArrowDataType::Dictionary(_, _)
-            | ArrowDataType::Decimal128(_, _)
             | ArrowDataType::Decimal256(_, _)
             | ArrowDataType::Map(_, _) => {
                 unimplemented!("Arrow array datatype: {:?}", array.as_ref().data_type())
@@ -349,6 +369,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_from_scalar_decimal_value() {
+        let vector =
+            Helper::try_from_scalar_value(ScalarValue::Decimal128(Some(42), 10, 2), 3).unwrap();
+        assert_eq!(
+            ConcreteDataType::decimal128_datatype(10, 2),
+            vector.data_type()
+        );
+        assert_eq!(3, vector.len());
+        for i in 0..vector.len() {
+            assert_eq!(
+                Value::Decimal128(crate::decimal::Decimal128::new(42, 10, 2)),
+                vector.get(i)
+            );
+        }
+    }
+
     #[test]
     fn test_try_from_list_value() {
         let value = ScalarValue::List(
@@ -430,5 +467,10 @@ mod tests {
         check_try_into_vector(TimestampMillisecondArray::from(vec![1, 2, 3]));
         check_try_into_vector(TimestampMicrosecondArray::from(vec![1, 2, 3]));
         check_try_into_vector(TimestampNanosecondArray::from(vec![1, 2, 3]));
+        check_try_into_vector(
+            Decimal128Array::from_iter_values([1, 2, 3])
+                .with_precision_and_scale(10, 2)
+                .unwrap(),
+        );
     }
 }
@@ -0,0 +1,437 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayData, ArrayRef, BooleanBufferBuilder, Float64Array, ListArray, StructArray,
+};
+use arrow::buffer::Buffer;
+use snafu::{OptionExt, ResultExt};
+
+use crate::data_type::{ConcreteDataType, DataType};
+use crate::error::{self, Result};
+use crate::histogram::Histogram;
+use crate::scalars::{ScalarVector, ScalarVectorBuilder};
+use crate::serialize::Serializable;
+use crate::types::HistogramType;
+use crate::value::{HistogramValueRef, Value, ValueRef};
+use crate::vectors::{self, MutableVector, Validity, Vector, VectorRef};
+
+/// Vector of [Histogram]s, backed by an arrow `StructArray` whose children are the list
+/// of bucket bounds and the list of cumulative bucket counts.
+#[derive(Debug, PartialEq)]
+pub struct HistogramVector {
+    array: StructArray,
+}
+
+impl HistogramVector {
+    /// Creates a vector from an arrow array, returns error if the array is not a struct
+    /// array of histograms.
+    pub fn try_from_arrow_array(array: impl AsRef<dyn Array>) -> Result<HistogramVector> {
+        let array = array.as_ref();
+        let data = array
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .filter(|array| match array.data_type() {
+                arrow::datatypes::DataType::Struct(fields) => {
+                    HistogramType::is_histogram_fields(fields)
+                }
+                _ => false,
+            })
+            .with_context(|| error::ConversionSnafu {
+                from: format!("{:?}", array.data_type()),
+            })?
+            .data()
+            .clone();
+        Ok(Self {
+            array: StructArray::from(data),
+        })
+    }
+
+    pub(crate) fn as_arrow(&self) -> &dyn Array {
+        &self.array
+    }
+
+    fn to_array_data(&self) -> ArrayData {
+        self.array.data().clone()
+    }
+
+    /// Returns the float values of the list in the `column` of the struct at `idx`.
+    fn f64s_at(&self, column: usize, idx: usize) -> Vec<f64> {
+        // Safety: the children of the struct are checked when creating the vector.
+        let list = self
+            .array
+            .column(column)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        if list.is_null(idx) {
+            return Vec::new();
+        }
+        let values = list.value(idx);
+        let values = values.as_any().downcast_ref::<Float64Array>().unwrap();
+        values.iter().map(|v| v.unwrap_or(f64::NAN)).collect()
+    }
+
+    fn histogram_at(&self, idx: usize) -> Histogram {
+        let bounds = self.f64s_at(0, idx);
+        let counts = self.f64s_at(1, idx);
+        // A histogram whose bounds and counts have different lengths could only come from
+        // external arrow data, treats it as an empty histogram.
+        Histogram::new(bounds, counts).unwrap_or_default()
+    }
+}
+
+impl Vector for HistogramVector {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::histogram_datatype()
+    }
+
+    fn vector_type_name(&self) -> String {
+        "HistogramVector".to_string()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    fn to_arrow_array(&self) -> ArrayRef {
+        Arc::new(StructArray::from(self.to_array_data()))
+    }
+
+    fn to_boxed_arrow_array(&self) -> Box<dyn Array> {
+        Box::new(StructArray::from(self.to_array_data()))
+    }
+
+    fn validity(&self) -> Validity {
+        vectors::impl_validity_for_vector!(self.array)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.array.get_buffer_memory_size()
+    }
+
+    fn null_count(&self) -> usize {
+        self.array.null_count()
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        self.array.is_null(row)
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> VectorRef {
+        let data = self.array.data().slice(offset, length);
+        Arc::new(Self {
+            array: StructArray::from(data),
+        })
+    }
+
+    fn get(&self, index: usize) -> Value {
+        if self.array.is_valid(index) {
+            Value::Histogram(self.histogram_at(index))
+        } else {
+            Value::Null
+        }
+    }
+
+    fn get_ref(&self, index: usize) -> ValueRef {
+        ValueRef::from(self.get_data(index))
+    }
+}
+
+impl Serializable for HistogramVector {
+    fn serialize_to_json(&self) -> Result<Vec<serde_json::Value>> {
+        (0..self.len())
+            .map(|i| serde_json::Value::try_from(self.get(i)))
+            .collect::<serde_json::Result<_>>()
+            .context(error::SerializeSnafu)
+    }
+}
+
+/// Iterator of [HistogramVector].
+pub struct HistogramIter<'a> {
+    vector: &'a HistogramVector,
+    idx: usize,
+}
+
+impl<'a> Iterator for HistogramIter<'a> {
+    type Item = Option<HistogramValueRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.vector.len() {
+            return None;
+        }
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.vector.get_data(idx))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vector.len() - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ScalarVector for HistogramVector {
+    type OwnedItem = Histogram;
+    type RefItem<'a> = HistogramValueRef<'a>;
+    type Iter<'a> = HistogramIter<'a>;
+    type Builder = HistogramVectorBuilder;
+
+    fn get_data(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        if self.array.is_valid(idx) {
+            Some(HistogramValueRef::Indexed { vector: self, idx })
+        } else {
+            None
+        }
+    }
+
+    fn iter_data(&self) -> Self::Iter<'_> {
+        HistogramIter {
+            vector: self,
+            idx: 0,
+        }
+    }
+}
+
+/// Builder of [HistogramVector].
+pub struct HistogramVectorBuilder {
+    bounds: Float64ListBuilder,
+    counts: Float64ListBuilder,
+    validity: BooleanBufferBuilder,
+}
+
+impl HistogramVectorBuilder {
+    fn push_histogram(&mut self, histogram: &Histogram) {
+        self.bounds.push(histogram.bounds());
+        self.counts.push(histogram.counts());
+        self.validity.append(true);
+    }
+
+    fn push_null(&mut self) {
+        // Pushes empty lists so the children are aligned with the struct.
+        self.bounds.push(&[]);
+        self.counts.push(&[]);
+        self.validity.append(false);
+    }
+}
+
+impl MutableVector for HistogramVectorBuilder {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::histogram_datatype()
+    }
+
+    fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn to_vector(&mut self) -> VectorRef {
+        Arc::new(self.finish())
+    }
+
+    fn push_value_ref(&mut self, value: ValueRef) -> Result<()> {
+        match value.as_histogram()? {
+            Some(HistogramValueRef::Indexed { vector, idx }) => match vector.get(idx) {
+                Value::Histogram(histogram) => self.push_histogram(&histogram),
+                _ => self.push_null(),
+            },
+            Some(HistogramValueRef::Ref { val }) => self.push_histogram(val),
+            None => self.push_null(),
+        }
+        Ok(())
+    }
+
+    fn extend_slice_of(&mut self, vector: &dyn Vector, offset: usize, length: usize) -> Result<()> {
+        vectors::impl_extend_for_builder!(self, vector, HistogramVector, offset, length)
+    }
+}
+
+impl ScalarVectorBuilder for HistogramVectorBuilder {
+    type VectorType = HistogramVector;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bounds: Float64ListBuilder::with_capacity(capacity),
+            counts: Float64ListBuilder::with_capacity(capacity),
+            validity: BooleanBufferBuilder::new(capacity),
+        }
+    }
+
+    fn push(&mut self, value: Option<<Self::VectorType as ScalarVector>::RefItem<'_>>) {
+        // Pushing a histogram never fails.
+        self.push_value_ref(value.into()).unwrap();
+    }
+
+    fn finish(&mut self) -> Self::VectorType {
+        let mut fields = HistogramType::arrow_fields().into_iter();
+        // Safety: a histogram struct always has two fields.
+        let children = vec![
+            (fields.next().unwrap(), self.bounds.finish()),
+            (fields.next().unwrap(), self.counts.finish()),
+        ];
+        let array = StructArray::from((children, self.validity.finish()));
+        HistogramVector { array }
+    }
+}
+
+/// Builds a list array of float64 values.
+struct Float64ListBuilder {
+    values: Vec<f64>,
+    offsets: Vec<i32>,
+}
+
+impl Float64ListBuilder {
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            values: Vec::new(),
+            offsets,
+        }
+    }
+
+    fn push(&mut self, values: &[f64]) {
+        self.values.extend_from_slice(values);
+        self.offsets.push(i32::try_from(self.values.len()).unwrap());
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        let values = Float64Array::from(std::mem::take(&mut self.values));
+        let offsets = std::mem::replace(&mut self.offsets, vec![0]);
+        let data_type =
+            ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()).as_arrow_type();
+        let array_data_builder = ArrayData::builder(data_type)
+            .len(offsets.len() - 1)
+            .add_buffer(Buffer::from_slice_ref(&offsets))
+            .add_child_data(values.data().clone());
+        // Safety: the offsets are monotonically increasing and within the bounds of values.
+        let array_data = unsafe { array_data_builder.build_unchecked() };
+        Arc::new(ListArray::from(array_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_histogram(counts: &[f64]) -> Histogram {
+        Histogram::new(vec![1.0, f64::INFINITY], counts.to_vec()).unwrap()
+    }
+
+    fn new_histogram_vector(histograms: &[Option<Histogram>]) -> HistogramVector {
+        let mut builder = HistogramVectorBuilder::with_capacity(histograms.len());
+        for h in histograms {
+            builder.push(h.as_ref().map(|val| HistogramValueRef::Ref { val }));
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_histogram_vector() {
+        let h1 = new_histogram(&[1.0, 2.0]);
+        let h2 = new_histogram(&[3.0, 5.0]);
+        let vector = new_histogram_vector(&[Some(h1.clone()), None, Some(h2.clone())]);
+
+        assert_eq!(3, vector.len());
+        assert_eq!("HistogramVector", vector.vector_type_name());
+        assert_eq!(ConcreteDataType::histogram_datatype(), vector.data_type());
+        assert_eq!(1, vector.null_count());
+        assert!(vector.is_null(1));
+        assert_eq!(Value::Histogram(h1.clone()), vector.get(0));
+        assert_eq!(Value::Null, vector.get(1));
+        assert_eq!(Value::Histogram(h2.clone()), vector.get(2));
+        assert_eq!(ValueRef::Null, vector.get_ref(1));
+
+        let sliced = vector.slice(1, 2);
+        assert_eq!(2, sliced.len());
+        assert_eq!(Value::Null, sliced.get(0));
+        assert_eq!(Value::Histogram(h2.clone()), sliced.get(1));
+
+        let owned = vector
+            .iter_data()
+            .map(|v| v.map(|v| crate::scalars::ScalarRef::to_owned_scalar(&v)))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Some(h1), None, Some(h2)], owned);
+    }
+
+    #[test]
+    fn test_histogram_vector_arrow() {
+        let vector = new_histogram_vector(&[Some(new_histogram(&[1.0, 2.0])), None]);
+        let array = vector.to_arrow_array();
+        assert_eq!(
+            ConcreteDataType::histogram_datatype(),
+            ConcreteDataType::from_arrow_type(array.data_type())
+        );
+        let converted = HistogramVector::try_from_arrow_array(array).unwrap();
+        assert_eq!(vector, converted);
+
+        let sliced = vector.slice(1, 1).to_arrow_array();
+        let converted = HistogramVector::try_from_arrow_array(sliced).unwrap();
+        assert_eq!(Value::Null, converted.get(0));
+
+        let array: ArrayRef = Arc::new(Float64Array::from(vec![1.0]));
+        assert!(HistogramVector::try_from_arrow_array(array).is_err());
+    }
+
+    #[test]
+    fn test_histogram_vector_builder() {
+        let mut builder = HistogramType.create_mutable_vector(2);
+        let h = new_histogram(&[1.0, 2.0]);
+        builder
+            .push_value_ref(ValueRef::Histogram(HistogramValueRef::Ref { val: &h }))
+            .unwrap();
+        builder.push_value_ref(ValueRef::Null).unwrap();
+        assert!(builder.push_value_ref(ValueRef::Int32(1)).is_err());
+
+        let input = new_histogram_vector(&[None, Some(new_histogram(&[3.0, 4.0]))]);
+        builder.extend_slice_of(&input, 1, 1).unwrap();
+        assert!(builder
+            .extend_slice_of(&crate::vectors::Int32Vector::from_slice([1]), 0, 1)
+            .is_err());
+
+        let vector = builder.to_vector();
+        let expect: VectorRef = Arc::new(new_histogram_vector(&[
+            Some(h),
+            None,
+            Some(new_histogram(&[3.0, 4.0])),
+        ]));
+        assert_eq!(expect, vector);
+    }
+
+    #[test]
+    fn test_serialize_histogram_vector() {
+        let vector = new_histogram_vector(&[Some(new_histogram(&[1.0, 2.0])), None]);
+        assert_eq!(
+            vec![
+                serde_json::json!({"bounds": [1.0, "+Inf"], "counts": [1.0, 2.0]}),
+                serde_json::Value::Null,
+            ],
+            vector.serialize_to_json().unwrap()
+        );
+    }
+}
@@ -22,8 +22,8 @@ use crate::error::Result;
 use crate::types::LogicalPrimitiveType;
 use crate::vectors::constant::ConstantVector;
 use crate::vectors::{
-    BinaryVector, BooleanVector, ListVector, NullVector, PrimitiveVector, StringVector, Vector,
-    VectorRef,
+    BinaryVector, BooleanVector, Decimal128Vector, HistogramVector, ListVector, NullVector,
+    PrimitiveVector, StringVector, Vector, VectorRef,
 };
 
 /// Vector compute operations.
@@ -78,7 +78,14 @@ macro_rules! impl_scalar_vector_op {
     )+};
 }
 
-impl_scalar_vector_op!(BinaryVector, BooleanVector, ListVector, StringVector);
+impl_scalar_vector_op!(
+    BinaryVector,
+    BooleanVector,
+    Decimal128Vector,
+    HistogramVector,
+    ListVector,
+    StringVector
+);
 
 impl<T: LogicalPrimitiveType> VectorOp for PrimitiveVector<T> {
     fn replicate(&self, offsets: &[usize]) -> VectorRef {
@@ -0,0 +1,862 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Alert rules created by `CREATE ALERT RULE`. The frontend evaluates the rules
+//! periodically, a rule fires while its expression returns any row, and the webhook of
+//! the rule is notified once the rule starts firing or is resolved.
+//!
+//! Rules and their states are persisted in the `alert_rules` table of the default
+//! schema, so they survive restarts of the frontend. In the distributed mode, only the
+//! frontend holding the alert lease in metasrv evaluates the rules, so webhooks are not
+//! notified by every frontend.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use catalog::remote::KvBackendRef;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch, RecordBatches};
+use common_telemetry::{debug, error, info, warn};
+use datatypes::prelude::{ConcreteDataType, Value};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, VectorRef};
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Client, Request, Uri};
+use serde::Serialize;
+use servers::promql::PromqlHandlerRef;
+use servers::query_handler::sql::SqlQueryHandlerRef;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::statements::alert::{AlertExpr, CreateAlertRule, DropAlertRule};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::error::{self, Error, Result};
+
+pub const ALERT_RULES_TABLE_NAME: &str = "alert_rules";
+
+/// How often the scheduler checks whether any rule is due for evaluation.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(1);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the rules are reloaded, so that rules created or dropped by other frontends
+/// are picked up.
+const RULES_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Key of the lease of evaluating the alert rules in the distributed mode.
+const ALERT_LEASE_KEY: &str = "__alert_lease";
+/// The lease is renewed at every schedule, other frontends take it over once it expires.
+const ALERT_LEASE_DURATION: Duration = Duration::from_secs(10);
+
+// All rows of a rule share the same timestamp, so updating a rule overwrites its row.
+const CREATE_ALERT_RULES_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS alert_rules (
+    rule_name STRING,
+    catalog_name STRING,
+    schema_name STRING,
+    expr_type STRING,
+    expr STRING,
+    interval_ms BIGINT,
+    webhook STRING,
+    state STRING,
+    state_changed_at BIGINT,
+    dropped BOOLEAN,
+    ts TIMESTAMP,
+    TIME INDEX (ts),
+    PRIMARY KEY (rule_name)
+) ENGINE=mito"#;
+
+const SELECT_ALERT_RULES: &str = "SELECT rule_name, catalog_name, schema_name, expr_type, \
+    expr, interval_ms, webhook, state, state_changed_at, dropped FROM alert_rules";
+
+const EXPR_TYPE_SQL: &str = "sql";
+const EXPR_TYPE_PROMQL: &str = "promql";
+
+pub type AlertManagerRef = Arc<AlertManager>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Inactive,
+    Firing,
+}
+
+impl AlertState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AlertState::Inactive => "inactive",
+            AlertState::Firing => "firing",
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        if name == AlertState::Firing.as_str() {
+            AlertState::Firing
+        } else {
+            AlertState::Inactive
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AlertRule {
+    name: String,
+    /// Catalog and schema the SQL expression is evaluated in.
+    catalog: String,
+    schema: String,
+    expr: AlertExpr,
+    interval: Duration,
+    webhook: String,
+    state: AlertState,
+    /// Unix timestamp in milliseconds when the rule entered its current state.
+    state_changed_at: i64,
+    /// When the rule was evaluated last time by this frontend.
+    last_evaluated: Option<Instant>,
+}
+
+impl AlertRule {
+    fn is_due(&self, now: Instant) -> bool {
+        self.last_evaluated
+            .map_or(true, |last| now.duration_since(last) >= self.interval)
+    }
+
+    fn expr_type_and_expr(&self) -> (&'static str, &str) {
+        match &self.expr {
+            AlertExpr::Sql(sql) => (EXPR_TYPE_SQL, sql),
+            AlertExpr::Promql(promql) => (EXPR_TYPE_PROMQL, promql),
+        }
+    }
+}
+
+/// Body of the request posted to the webhook of a rule.
+#[derive(Serialize)]
+struct Notification<'a> {
+    rule: &'a str,
+    /// Either `firing` or `resolved`.
+    state: &'a str,
+    expr: &'a str,
+    /// Number of rows returned by the expression.
+    rows: usize,
+    timestamp: i64,
+}
+
+#[derive(Default)]
+struct LoadedRules {
+    /// Rules by names.
+    rules: HashMap<String, AlertRule>,
+    /// When the rules were loaded from the `alert_rules` table, None if not loaded yet.
+    loaded_at: Option<Instant>,
+}
+
+/// Value of the alert lease.
+struct AlertLease {
+    holder: String,
+    expire_at_millis: i64,
+}
+
+impl AlertLease {
+    fn encode(&self) -> Vec<u8> {
+        format!("{}@{}", self.holder, self.expire_at_millis).into_bytes()
+    }
+
+    fn decode(value: &[u8]) -> Option<Self> {
+        let value = std::str::from_utf8(value).ok()?;
+        let (holder, expire_at_millis) = value.rsplit_once('@')?;
+        Some(Self {
+            holder: holder.to_string(),
+            expire_at_millis: expire_at_millis.parse().ok()?,
+        })
+    }
+}
+
+/// Election of the frontend evaluating the alert rules among the frontends sharing the
+/// same metasrv.
+struct AlertElection {
+    backend: KvBackendRef,
+    /// Identifies this frontend as the holder of the lease.
+    holder: String,
+    leading: AtomicBool,
+}
+
+impl AlertElection {
+    fn new(backend: KvBackendRef) -> Self {
+        let holder = format!(
+            "{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos()
+        );
+        Self {
+            backend,
+            holder,
+            leading: AtomicBool::new(false),
+        }
+    }
+
+    /// Acquires the lease if it's not held by other frontends or has expired, or renews
+    /// the lease held by this frontend. Returns true if this frontend holds the lease.
+    async fn campaign(&self) -> Result<bool> {
+        let now = current_time_millis();
+        let key = ALERT_LEASE_KEY.as_bytes();
+        let current = self.backend.get(key).await.context(error::CatalogSnafu)?;
+        let expect = match current {
+            Some(kv) => {
+                // Corrupted leases are taken over.
+                if let Some(lease) = AlertLease::decode(&kv.1) {
+                    if lease.holder != self.holder && lease.expire_at_millis > now {
+                        return Ok(false);
+                    }
+                }
+                kv.1
+            }
+            None => vec![],
+        };
+
+        let lease = AlertLease {
+            holder: self.holder.clone(),
+            expire_at_millis: now + ALERT_LEASE_DURATION.as_millis() as i64,
+        };
+        let acquired = self
+            .backend
+            .compare_and_set(key, &expect, &lease.encode())
+            .await
+            .context(error::CatalogSnafu)?
+            .is_ok();
+        Ok(acquired)
+    }
+}
+
+/// Manages the alert rules and evaluates them periodically once started.
+pub struct AlertManager {
+    running: Arc<AtomicBool>,
+    sql_handler: SqlQueryHandlerRef<Error>,
+    /// PromQL handler is None in distributed mode, so are PromQL alert rules.
+    promql_handler: Option<PromqlHandlerRef>,
+    rules: Mutex<LoadedRules>,
+    /// Election of the frontend evaluating the rules, None in standalone mode.
+    election: Option<AlertElection>,
+    /// Hosts webhooks could be on even if they are internal addresses.
+    allowed_webhook_hosts: RwLock<Vec<String>>,
+    http_client: Client<HttpConnector>,
+}
+
+impl Drop for AlertManager {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl AlertManager {
+    pub fn new(
+        sql_handler: SqlQueryHandlerRef<Error>,
+        promql_handler: Option<PromqlHandlerRef>,
+    ) -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            sql_handler,
+            promql_handler,
+            rules: Mutex::new(LoadedRules::default()),
+            election: None,
+            allowed_webhook_hosts: RwLock::new(vec![]),
+            http_client: Client::new(),
+        }
+    }
+
+    /// Evaluates the rules only if this frontend holds the alert lease in `backend`.
+    pub fn with_election(mut self, backend: KvBackendRef) -> Self {
+        self.election = Some(AlertElection::new(backend));
+        self
+    }
+
+    /// Allows webhooks on `hosts` even if they are internal addresses, which are rejected
+    /// by default so that alert rules can't be used to reach internal services.
+    pub fn set_allowed_webhook_hosts(&self, hosts: Vec<String>) {
+        *self.allowed_webhook_hosts.write().unwrap() = hosts;
+    }
+
+    /// Starts the scheduler evaluating the rules in background.
+    pub fn start(self: &Arc<Self>) {
+        if self
+            .running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Alert rule scheduler started multiple times");
+            return;
+        }
+        let running = self.running.clone();
+        let manager = Arc::downgrade(self);
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                tokio::time::sleep(SCHEDULE_INTERVAL).await;
+                if !running.load(Ordering::Acquire) {
+                    break;
+                }
+                let Some(manager) = manager.upgrade() else { break };
+                if manager.is_leader().await {
+                    manager.evaluate_due_rules().await;
+                }
+            }
+            info!("Alert rule scheduler shutdown");
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Returns true if this frontend should evaluate the rules.
+    async fn is_leader(&self) -> bool {
+        let Some(election) = &self.election else { return true };
+        let leading = match election.campaign().await {
+            Ok(leading) => leading,
+            Err(e) => {
+                error!(e; "Failed to campaign for the alert lease");
+                false
+            }
+        };
+        let was_leading = election.leading.swap(leading, Ordering::AcqRel);
+        if leading && !was_leading {
+            info!("Frontend {} starts evaluating alert rules", election.holder);
+            // States of the rules may be changed by the previous leader.
+            self.rules.lock().await.loaded_at = None;
+        }
+        leading
+    }
+
+    pub async fn create_rule(
+        &self,
+        stmt: CreateAlertRule,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        if matches!(stmt.expr, AlertExpr::Promql(_)) {
+            ensure!(
+                self.promql_handler.is_some(),
+                error::NotSupportedSnafu {
+                    feat: "PromQL alert rules in distributed mode",
+                }
+            );
+        }
+        validate_webhook(&stmt.webhook, &self.allowed_webhook_hosts.read().unwrap())?;
+
+        let mut rules = self.loaded_rules().await?;
+        if rules.rules.contains_key(&stmt.name) {
+            ensure!(
+                stmt.if_not_exists,
+                error::AlertRuleExistsSnafu { name: &stmt.name }
+            );
+            return Ok(Output::AffectedRows(0));
+        }
+
+        let rule = AlertRule {
+            name: stmt.name,
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            expr: stmt.expr,
+            interval: stmt.interval,
+            webhook: stmt.webhook,
+            state: AlertState::Inactive,
+            state_changed_at: current_time_millis(),
+            last_evaluated: None,
+        };
+        self.persist(&rule, false).await?;
+        info!("Alert rule {} created", rule.name);
+        rules.rules.insert(rule.name.clone(), rule);
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub async fn drop_rule(&self, stmt: DropAlertRule) -> Result<Output> {
+        let mut rules = self.loaded_rules().await?;
+        let Some(rule) = rules.rules.get(&stmt.name) else {
+            ensure!(stmt.if_exists, error::AlertRuleNotFoundSnafu { name: &stmt.name });
+            return Ok(Output::AffectedRows(0));
+        };
+        self.persist(rule, true).await?;
+        rules.rules.remove(&stmt.name);
+        info!("Alert rule {} dropped", stmt.name);
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub async fn show_rules(&self) -> Result<Output> {
+        let mut rules = self
+            .loaded_rules()
+            .await?
+            .rules
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        rules.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let strings = |f: &dyn Fn(&AlertRule) -> String| {
+            Arc::new(StringVector::from(rules.iter().map(f).collect::<Vec<_>>())) as VectorRef
+        };
+        let columns = vec![
+            strings(&|rule| rule.name.clone()),
+            strings(&|rule| rule.schema.clone()),
+            strings(&|rule| rule.expr_type_and_expr().0.to_string()),
+            strings(&|rule| rule.expr_type_and_expr().1.to_string()),
+            strings(&|rule| humantime::format_duration(rule.interval).to_string()),
+            strings(&|rule| rule.webhook.clone()),
+            strings(&|rule| rule.state.as_str().to_string()),
+        ];
+        let records = RecordBatches::try_from_columns(alert_rules_schema(), columns)
+            .context(error::CreateRecordBatchesSnafu)?;
+        Ok(Output::RecordBatches(records))
+    }
+
+    /// Evaluates the rules due for evaluation, the webhook of a rule is notified if its
+    /// state changes.
+    pub(crate) async fn evaluate_due_rules(&self) {
+        let now = Instant::now();
+        let due_rules = match self.loaded_rules().await {
+            Ok(rules) => rules
+                .rules
+                .values()
+                .filter(|rule| rule.is_due(now))
+                .cloned()
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                error!(e; "Failed to load alert rules");
+                return;
+            }
+        };
+
+        for rule in due_rules {
+            let rows = self.evaluate(&rule).await;
+            match self.update_state(&rule.name, rows, now).await {
+                Ok(Some((rule, rows))) => self.notify(&rule, rows).await,
+                Ok(None) => {}
+                Err(e) => error!(e; "Failed to update state of alert rule {}", rule.name),
+            }
+        }
+    }
+
+    /// Returns the number of rows returned by the expression of the `rule`.
+    async fn evaluate(&self, rule: &AlertRule) -> Result<usize> {
+        let output = match &rule.expr {
+            AlertExpr::Sql(sql) => {
+                let query_ctx = Arc::new(QueryContext::with(&rule.catalog, &rule.schema));
+                self.execute(sql, query_ctx).await?
+            }
+            AlertExpr::Promql(promql) => self
+                .promql_handler
+                .as_ref()
+                .context(error::NotSupportedSnafu {
+                    feat: "PromQL alert rules in distributed mode",
+                })?
+                .do_query(promql)
+                .await
+                .context(error::ExecutePromqlSnafu { query: promql })?,
+        };
+        let batches = collect_output(output).await?;
+        Ok(batches.iter().map(|batch| batch.num_rows()).sum())
+    }
+
+    /// Records the evaluation result of the rule `name`, returns the rule and the number
+    /// of returned rows if the state of the rule is changed.
+    async fn update_state(
+        &self,
+        name: &str,
+        rows: Result<usize>,
+        now: Instant,
+    ) -> Result<Option<(AlertRule, usize)>> {
+        let mut rules = self.rules.lock().await;
+        // The rule might be dropped during evaluation.
+        let Some(rule) = rules.rules.get_mut(name) else { return Ok(None) };
+        rule.last_evaluated = Some(now);
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("Failed to evaluate alert rule {}, error: {}", name, e);
+                return Ok(None);
+            }
+        };
+
+        let state = if rows > 0 {
+            AlertState::Firing
+        } else {
+            AlertState::Inactive
+        };
+        if state == rule.state {
+            return Ok(None);
+        }
+        let mut updated = rule.clone();
+        updated.state = state;
+        updated.state_changed_at = current_time_millis();
+        self.persist(&updated, false).await?;
+        *rule = updated.clone();
+        Ok(Some((updated, rows)))
+    }
+
+    async fn notify(&self, rule: &AlertRule, rows: usize) {
+        // The host may resolve to other addresses since the rule is created.
+        let allowed_hosts = self.allowed_webhook_hosts.read().unwrap().clone();
+        if let Err(e) = resolve_webhook(&rule.webhook, &allowed_hosts).await {
+            warn!("Skip notifying alert rule {}: {}", rule.name, e);
+            return;
+        }
+        let notification = Notification {
+            rule: &rule.name,
+            state: match rule.state {
+                AlertState::Firing => "firing",
+                AlertState::Inactive => "resolved",
+            },
+            expr: rule.expr_type_and_expr().1,
+            rows,
+            timestamp: rule.state_changed_at,
+        };
+        let request = serde_json::to_vec(&notification)
+            .map_err(|e| e.to_string())
+            .and_then(|body| {
+                Request::post(&rule.webhook)
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(body))
+                    .map_err(|e| e.to_string())
+            });
+        let request = match request {
+            Ok(request) => request,
+            Err(e) => {
+                warn!(
+                    "Failed to build webhook request of alert rule {}: {}",
+                    rule.name, e
+                );
+                return;
+            }
+        };
+
+        match tokio::time::timeout(WEBHOOK_TIMEOUT, self.http_client.request(request)).await {
+            Ok(Ok(response)) if response.status().is_success() => {}
+            Ok(Ok(response)) => warn!(
+                "Webhook {} of alert rule {} responded with status {}",
+                rule.webhook,
+                rule.name,
+                response.status()
+            ),
+            Ok(Err(e)) => warn!(
+                "Failed to notify webhook {} of alert rule {}: {}",
+                rule.webhook, rule.name, e
+            ),
+            Err(_) => warn!(
+                "Timeout notifying webhook {} of alert rule {}",
+                rule.webhook, rule.name
+            ),
+        }
+    }
+
+    /// Returns the rules, which are reloaded if they are loaded too long ago.
+    async fn loaded_rules(&self) -> Result<MutexGuard<'_, LoadedRules>> {
+        let mut loaded = self.rules.lock().await;
+        let now = Instant::now();
+        let loaded_at = loaded.loaded_at;
+        if loaded_at.map_or(false, |at| now.duration_since(at) < RULES_RELOAD_INTERVAL) {
+            return Ok(loaded);
+        }
+
+        let mut rules = self.load_rules(loaded_at.is_none()).await?;
+        // Rules are evaluated by their intervals since they were evaluated last time.
+        for (name, rule) in rules.iter_mut() {
+            if let Some(loaded_rule) = loaded.rules.get(name) {
+                rule.last_evaluated = loaded_rule.last_evaluated;
+            }
+        }
+        loaded.rules = rules;
+        loaded.loaded_at = Some(now);
+        Ok(loaded)
+    }
+
+    /// Loads the rules from the `alert_rules` table, which is created first if
+    /// `create_table` is true.
+    async fn load_rules(&self, create_table: bool) -> Result<HashMap<String, AlertRule>> {
+        if create_table {
+            self.execute(CREATE_ALERT_RULES_TABLE, system_query_ctx())
+                .await?;
+        }
+        let output = self.execute(SELECT_ALERT_RULES, system_query_ctx()).await?;
+
+        let mut rules = HashMap::new();
+        for batch in collect_output(output).await? {
+            for row in 0..batch.num_rows() {
+                if let Some(rule) = rule_from_row(&batch, row) {
+                    rules.insert(rule.name.clone(), rule);
+                }
+            }
+        }
+        debug!("Loaded {} alert rules", rules.len());
+        Ok(rules)
+    }
+
+    async fn persist(&self, rule: &AlertRule, dropped: bool) -> Result<()> {
+        let (expr_type, expr) = rule.expr_type_and_expr();
+        let sql = format!(
+            "INSERT INTO {ALERT_RULES_TABLE_NAME}(rule_name, catalog_name, schema_name, \
+             expr_type, expr, interval_ms, webhook, state, state_changed_at, dropped, ts) \
+             VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {dropped}, 0)",
+            quote(&rule.name),
+            quote(&rule.catalog),
+            quote(&rule.schema),
+            quote(expr_type),
+            quote(expr),
+            rule.interval.as_millis(),
+            quote(&rule.webhook),
+            quote(rule.state.as_str()),
+            rule.state_changed_at,
+        );
+        self.execute(&sql, system_query_ctx()).await?;
+        Ok(())
+    }
+
+    async fn execute(&self, sql: &str, query_ctx: QueryContextRef) -> Result<Output> {
+        self.sql_handler.do_query(sql, query_ctx).await.remove(0)
+    }
+}
+
+/// Only plain HTTP webhooks are supported. Webhooks on internal addresses are rejected
+/// unless their hosts are in `allowed_hosts`.
+fn validate_webhook(webhook: &str, allowed_hosts: &[String]) -> Result<()> {
+    let uri = parse_webhook(webhook)?;
+    let host = webhook_host(&uri);
+    if allowed_hosts.iter().any(|allowed| allowed == host) {
+        return Ok(());
+    }
+    let internal = host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().map_or(false, is_internal_ip);
+    ensure!(
+        !internal,
+        error::InvalidWebhookSnafu {
+            webhook,
+            reason: "internal addresses are not allowed",
+        }
+    );
+    Ok(())
+}
+
+/// Resolves the host of the `webhook`, fails if it's not allowed or any of its addresses
+/// is internal.
+async fn resolve_webhook(webhook: &str, allowed_hosts: &[String]) -> Result<()> {
+    validate_webhook(webhook, allowed_hosts)?;
+    let uri = parse_webhook(webhook)?;
+    let host = webhook_host(&uri);
+    if allowed_hosts.iter().any(|allowed| allowed == host) {
+        return Ok(());
+    }
+    let addrs = tokio::net::lookup_host((host, uri.port_u16().unwrap_or(80)))
+        .await
+        .map_err(|e| {
+            error::InvalidWebhookSnafu {
+                webhook,
+                reason: e.to_string(),
+            }
+            .build()
+        })?
+        .collect::<Vec<_>>();
+    ensure!(
+        !addrs.iter().any(|addr| is_internal_ip(addr.ip())),
+        error::InvalidWebhookSnafu {
+            webhook,
+            reason: "internal addresses are not allowed",
+        }
+    );
+    Ok(())
+}
+
+fn parse_webhook(webhook: &str) -> Result<Uri> {
+    let uri = webhook.parse::<Uri>().map_err(|e| {
+        error::InvalidWebhookSnafu {
+            webhook,
+            reason: e.to_string(),
+        }
+        .build()
+    })?;
+    ensure!(
+        uri.scheme_str() == Some("http") && uri.host().is_some(),
+        error::InvalidWebhookSnafu {
+            webhook,
+            reason: "expect an absolute http url",
+        }
+    );
+    Ok(uri)
+}
+
+/// Returns the host of the webhook `uri`, without brackets of IPv6 addresses.
+fn webhook_host(uri: &Uri) -> &str {
+    let host = uri.host().unwrap_or_default();
+    host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+/// Returns true if `ip` is only reachable inside the network of the cluster.
+fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // Shared address space of carrier-grade NAT, 100.64.0.0/10.
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local addresses, fc00::/7.
+                || (first & 0xfe00) == 0xfc00
+                // Link-local addresses, fe80::/10.
+                || (first & 0xffc0) == 0xfe80
+                || ip
+                    .to_ipv4_mapped()
+                    .map_or(false, |ip| is_internal_ip(IpAddr::V4(ip)))
+        }
+    }
+}
+
+fn rule_from_row(batch: &RecordBatch, row: usize) -> Option<AlertRule> {
+    let value = |idx: usize| batch.column(idx).get(row);
+    let string = |idx: usize| match value(idx) {
+        Value::String(s) => Some(s.as_utf8().to_string()),
+        _ => None,
+    };
+    let int64 = |idx: usize| match value(idx) {
+        Value::Int64(v) => Some(v),
+        _ => None,
+    };
+
+    if value(9) == Value::Boolean(true) {
+        return None;
+    }
+    let expr = match string(3)?.as_str() {
+        EXPR_TYPE_SQL => AlertExpr::Sql(string(4)?),
+        EXPR_TYPE_PROMQL => AlertExpr::Promql(string(4)?),
+        _ => return None,
+    };
+    Some(AlertRule {
+        name: string(0)?,
+        catalog: string(1)?,
+        schema: string(2)?,
+        expr,
+        interval: Duration::from_millis(int64(5)?.max(1) as u64),
+        webhook: string(6)?,
+        state: AlertState::from_name(&string(7)?),
+        state_changed_at: int64(8).unwrap_or_default(),
+        last_evaluated: None,
+    })
+}
+
+async fn collect_output(output: Output) -> Result<Vec<RecordBatch>> {
+    match output {
+        Output::AffectedRows(_) => Ok(vec![]),
+        Output::RecordBatches(batches) => Ok(batches.take()),
+        Output::Stream(stream) => util::collect(stream)
+            .await
+            .context(error::CollectRecordBatchesSnafu),
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn system_query_ctx() -> QueryContextRef {
+    Arc::new(QueryContext::with(
+        DEFAULT_CATALOG_NAME,
+        DEFAULT_SCHEMA_NAME,
+    ))
+}
+
+fn current_time_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn alert_rules_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new("Name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Db", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Type", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Expr", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Interval", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Webhook", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("State", ConcreteDataType::string_datatype(), false),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_webhook() {
+        assert!(validate_webhook("http://203.0.113.1:8080/alert", &[]).is_ok());
+        assert!(validate_webhook("http://alert.example.com/alert", &[]).is_ok());
+        assert!(validate_webhook("https://203.0.113.1:8080/alert", &[]).is_err());
+        assert!(validate_webhook("/alert", &[]).is_err());
+        assert!(validate_webhook("not a url", &[]).is_err());
+
+        for webhook in [
+            "http://127.0.0.1:8080/alert",
+            "http://localhost/alert",
+            "http://10.0.0.1/alert",
+            "http://192.168.1.1/alert",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/alert",
+            "http://0.0.0.0/alert",
+            "http://[::1]/alert",
+            "http://[fd00::1]/alert",
+            "http://[::ffff:127.0.0.1]/alert",
+        ] {
+            assert!(validate_webhook(webhook, &[]).is_err(), "{webhook}");
+        }
+        let allowed = vec!["127.0.0.1".to_string(), "::1".to_string()];
+        assert!(validate_webhook("http://127.0.0.1:8080/alert", &allowed).is_ok());
+        assert!(validate_webhook("http://[::1]:8080/alert", &allowed).is_ok());
+        assert!(validate_webhook("http://10.0.0.1/alert", &allowed).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_webhook() {
+        assert!(resolve_webhook("http://203.0.113.1:8080/alert", &[])
+            .await
+            .is_ok());
+        let allowed = vec!["localhost".to_string()];
+        assert!(resolve_webhook("http://localhost:8080/alert", &allowed)
+            .await
+            .is_ok());
+        assert!(resolve_webhook("http://localhost:8080/alert", &[])
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_alert_lease() {
+        let lease = AlertLease {
+            holder: "1@2".to_string(),
+            expire_at_millis: 1000,
+        };
+        let decoded = AlertLease::decode(&lease.encode()).unwrap();
+        assert_eq!("1@2", decoded.holder);
+        assert_eq!(1000, decoded.expire_at_millis);
+        assert!(AlertLease::decode(b"corrupted").is_none());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("'abc'", quote("abc"));
+        assert_eq!("'it''s'", quote("it's"));
+    }
+}
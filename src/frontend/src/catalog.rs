@@ -13,34 +13,52 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use catalog::error::{self as catalog_err, InvalidCatalogValueSnafu};
 use catalog::helper::{
-    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, SchemaKey,
-    TableGlobalKey, TableGlobalValue,
+    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, CatalogValue,
+    SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue,
 };
-use catalog::remote::{Kv, KvBackendRef};
+use catalog::remote::{self, Kv, KvBackendRef, MissingCatalogs, Page};
 use catalog::{
-    CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
-    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
-    SchemaProvider, SchemaProviderRef,
+    AlterSchemaRequest, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
+    CreateCatalogRequest, DeregisterTableRequest, RegisterSchemaRequest,
+    RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest, SchemaProvider,
+    SchemaProviderRef,
 };
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, SYSTEM_SCHEMA_NAME,
+};
+use common_error::prelude::BoxedError;
 use futures::StreamExt;
 use meta_client::rpc::TableName;
 use partition::manager::PartitionRuleManagerRef;
 use snafu::prelude::*;
+use table::requests::AlterDatabaseKind;
+use table::table::TableStatistics;
 use table::TableRef;
 
+use crate::catalog::information_schema::{latest_region_stats, table_statistics};
+use crate::catalog::table_cache::TableCache;
 use crate::datanode::DatanodeClients;
 use crate::table::DistTable;
 
+pub(crate) mod information_schema;
+pub(crate) mod system_schema;
+pub(crate) mod table_cache;
+
 #[derive(Clone)]
 pub struct FrontendCatalogManager {
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    table_cache: Arc<TableCache>,
+    /// Catalogs known to exist, catalogs are never dropped so they are cached forever.
+    catalogs: Arc<RwLock<HashSet<String>>>,
+    missing_catalogs: Arc<MissingCatalogs>,
 }
 
 impl FrontendCatalogManager {
@@ -53,13 +71,81 @@ impl FrontendCatalogManager {
             backend,
             partition_manager,
             datanode_clients,
+            table_cache: Arc::new(TableCache::default()),
+            catalogs: Default::default(),
+            missing_catalogs: Default::default(),
+        }
+    }
+
+    /// Returns true if catalog `name` exists in meta.
+    fn catalog_exists(&self, name: &str) -> catalog::error::Result<bool> {
+        if self.catalogs.read().unwrap().contains(name) {
+            return Ok(true);
+        }
+        if self.missing_catalogs.contains(name) {
+            return Ok(false);
+        }
+
+        let backend = self.backend.clone();
+        let key = CatalogKey {
+            catalog_name: name.to_string(),
         }
+        .to_string();
+        let exists = std::thread::spawn(|| {
+            common_runtime::block_on_read(async move {
+                backend.get(key.as_bytes()).await.map(|kv| kv.is_some())
+            })
+        })
+        .join()
+        .unwrap()?;
+        if exists {
+            let _ = self.catalogs.write().unwrap().insert(name.to_string());
+        } else {
+            self.missing_catalogs.insert(name);
+        }
+        Ok(exists)
+    }
+
+    fn new_catalog_provider(&self, name: &str) -> CatalogProviderRef {
+        Arc::new(FrontendCatalogProvider {
+            catalog_name: name.to_string(),
+            backend: self.backend.clone(),
+            partition_manager: self.partition_manager.clone(),
+            datanode_clients: self.datanode_clients.clone(),
+            table_cache: self.table_cache.clone(),
+        })
+    }
+
+    /// Caches at most `capacity` tables, each for at most `ttl`.
+    pub(crate) fn with_table_cache(mut self, capacity: u64, ttl: Duration) -> Self {
+        self.table_cache = Arc::new(TableCache::new(capacity, ttl));
+        self
     }
 
     pub(crate) fn backend(&self) -> KvBackendRef {
         self.backend.clone()
     }
 
+    pub(crate) fn table_cache(&self) -> &Arc<TableCache> {
+        &self.table_cache
+    }
+
+    /// Invalidates the cached metadata and routes of the table, they are loaded from meta
+    /// on next access.
+    pub(crate) async fn invalidate_table(&self, table_name: &TableName) {
+        self.table_cache.invalidate(table_name);
+        self.partition_manager
+            .table_routes()
+            .invalidate(table_name)
+            .await;
+    }
+
+    /// Invalidates the cached metadata and routes of all tables.
+    pub(crate) fn invalidate_all_tables(&self) {
+        self.table_cache.invalidate_all();
+        self.partition_manager.table_routes().invalidate_all();
+    }
+
     #[cfg(test)]
     pub(crate) fn partition_manager(&self) -> PartitionRuleManagerRef {
         self.partition_manager.clone()
@@ -90,6 +176,38 @@ impl CatalogManager for FrontendCatalogManager {
         unimplemented!()
     }
 
+    async fn create_catalog(&self, request: CreateCatalogRequest) -> catalog_err::Result<bool> {
+        let catalog_name = request.catalog;
+        // The default schema is created before the catalog, so the catalog is never seen
+        // without its default schema even if the creation is interrupted.
+        let schema_key = SchemaKey {
+            catalog_name: catalog_name.clone(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        };
+        let schema_value = SchemaValue::default()
+            .as_bytes()
+            .context(InvalidCatalogValueSnafu)?;
+        let _ = self
+            .backend
+            .compare_and_set(schema_key.to_string().as_bytes(), &[], &schema_value)
+            .await?;
+
+        let catalog_key = CatalogKey {
+            catalog_name: catalog_name.clone(),
+        };
+        let catalog_value = CatalogValue {}
+            .as_bytes()
+            .context(InvalidCatalogValueSnafu)?;
+        let created = self
+            .backend
+            .compare_and_set(catalog_key.to_string().as_bytes(), &[], &catalog_value)
+            .await?
+            .is_ok();
+        self.missing_catalogs.remove(&catalog_name);
+        let _ = self.catalogs.write().unwrap().insert(catalog_name);
+        Ok(created)
+    }
+
     async fn register_schema(
         &self,
         _request: RegisterSchemaRequest,
@@ -97,6 +215,36 @@ impl CatalogManager for FrontendCatalogManager {
         unimplemented!()
     }
 
+    async fn alter_schema(&self, request: AlterSchemaRequest) -> catalog_err::Result<bool> {
+        let metadata = match request.alter_kind {
+            // Regions of the tables are kept by datanodes under the name of the schema.
+            AlterDatabaseKind::RenameDatabase { .. } => {
+                return catalog_err::UnimplementedSnafu {
+                    operation: "rename schema in distributed mode",
+                }
+                .fail();
+            }
+            AlterDatabaseKind::SetMetadata { metadata } => metadata,
+        };
+
+        let key = SchemaKey {
+            catalog_name: request.catalog.clone(),
+            schema_name: request.schema.clone(),
+        }
+        .to_string();
+        let kv = self.backend.get(key.as_bytes()).await?.with_context(|| {
+            catalog_err::SchemaNotFoundSnafu {
+                catalog: &request.catalog,
+                schema: &request.schema,
+            }
+        })?;
+        let mut value = SchemaValue::from_bytes(kv.1).context(InvalidCatalogValueSnafu)?;
+        value.metadata.extend(metadata);
+        let value = value.as_bytes().context(InvalidCatalogValueSnafu)?;
+        self.backend.set(key.as_bytes(), &value).await?;
+        Ok(true)
+    }
+
     async fn rename_table(&self, _request: RenameTableRequest) -> catalog_err::Result<bool> {
         unimplemented!()
     }
@@ -126,10 +274,50 @@ impl CatalogManager for FrontendCatalogManager {
         schema: &str,
         table_name: &str,
     ) -> catalog::error::Result<Option<TableRef>> {
+        // Cached tables skip checking the catalog and schema in meta.
+        if let Some(table) = self
+            .table_cache
+            .get(&TableName::new(catalog, schema, table_name))
+        {
+            return Ok(Some(table));
+        }
         self.schema(catalog, schema)?
             .context(catalog::error::SchemaNotFoundSnafu { catalog, schema })?
             .table(table_name)
     }
+
+    async fn list_schemas(
+        &self,
+        catalog: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> catalog_err::Result<Page<String>> {
+        remote::list_schemas(self.backend.as_ref(), catalog, token, limit).await
+    }
+
+    async fn list_tables(
+        &self,
+        catalog: &str,
+        schema: &str,
+        token: Option<&[u8]>,
+        limit: usize,
+    ) -> catalog_err::Result<Page<String>> {
+        remote::list_tables(self.backend.as_ref(), catalog, schema, token, limit).await
+    }
+
+    /// Reads the stats of all regions reported by datanodes at once, instead of once per
+    /// table.
+    async fn schema_statistics(
+        &self,
+        catalog: &str,
+        schema: &str,
+    ) -> catalog_err::Result<Option<HashMap<String, TableStatistics>>> {
+        let region_stats = latest_region_stats(&self.backend)
+            .await
+            .map_err(BoxedError::new)
+            .context(catalog_err::SchemaProviderOperationSnafu)?;
+        Ok(Some(table_statistics(region_stats, catalog, schema)))
+    }
 }
 
 impl CatalogList for FrontendCatalogManager {
@@ -137,12 +325,30 @@ impl CatalogList for FrontendCatalogManager {
         self
     }
 
+    /// Persists catalog `name` in meta, the `catalog` is not kept since catalog providers of
+    /// the frontend read everything from meta.
     fn register_catalog(
         &self,
-        _name: String,
+        name: String,
         _catalog: CatalogProviderRef,
     ) -> catalog::error::Result<Option<CatalogProviderRef>> {
-        unimplemented!("Frontend catalog list does not support register catalog")
+        let existed = self.catalog_exists(&name)?;
+        let backend = self.backend.clone();
+        let key = CatalogKey {
+            catalog_name: name.clone(),
+        }
+        .to_string();
+        let value = CatalogValue {}
+            .as_bytes()
+            .context(InvalidCatalogValueSnafu)?;
+        std::thread::spawn(|| {
+            common_runtime::block_on_write(async move { backend.set(key.as_bytes(), &value).await })
+        })
+        .join()
+        .unwrap()?;
+        self.missing_catalogs.remove(&name);
+        let _ = self.catalogs.write().unwrap().insert(name.clone());
+        Ok(existed.then(|| self.new_catalog_provider(&name)))
     }
 
     fn catalog_names(&self) -> catalog::error::Result<Vec<String>> {
@@ -168,14 +374,8 @@ impl CatalogList for FrontendCatalogManager {
     }
 
     fn catalog(&self, name: &str) -> catalog::error::Result<Option<CatalogProviderRef>> {
-        let all_catalogs = self.catalog_names()?;
-        if all_catalogs.contains(&name.to_string()) {
-            Ok(Some(Arc::new(FrontendCatalogProvider {
-                catalog_name: name.to_string(),
-                backend: self.backend.clone(),
-                partition_manager: self.partition_manager.clone(),
-                datanode_clients: self.datanode_clients.clone(),
-            })))
+        if self.catalog_exists(name)? {
+            Ok(Some(self.new_catalog_provider(name)))
         } else {
             Ok(None)
         }
@@ -187,6 +387,7 @@ pub struct FrontendCatalogProvider {
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    table_cache: Arc<TableCache>,
 }
 
 impl CatalogProvider for FrontendCatalogProvider {
@@ -226,6 +427,25 @@ impl CatalogProvider for FrontendCatalogProvider {
     }
 
     fn schema(&self, name: &str) -> catalog::error::Result<Option<SchemaProviderRef>> {
+        // The information schema is not stored in the kv backend, so it is not listed
+        // by `schema_names`.
+        if name.eq_ignore_ascii_case(INFORMATION_SCHEMA_NAME) {
+            return Ok(Some(information_schema::information_schema_provider(
+                &self.catalog_name,
+                self.backend.clone(),
+                self.partition_manager.clone(),
+            )));
+        }
+        // Like the standalone mode, the DDL history is in the system schema of the default
+        // catalog.
+        if self.catalog_name == DEFAULT_CATALOG_NAME
+            && name.eq_ignore_ascii_case(SYSTEM_SCHEMA_NAME)
+        {
+            return Ok(Some(system_schema::system_schema_provider(
+                self.backend.clone(),
+            )));
+        }
+
         let all_schemas = self.schema_names()?;
         if all_schemas.contains(&name.to_string()) {
             Ok(Some(Arc::new(FrontendSchemaProvider {
@@ -234,6 +454,7 @@ impl CatalogProvider for FrontendCatalogProvider {
                 backend: self.backend.clone(),
                 partition_manager: self.partition_manager.clone(),
                 datanode_clients: self.datanode_clients.clone(),
+                table_cache: self.table_cache.clone(),
             })))
         } else {
             Ok(None)
@@ -247,6 +468,7 @@ pub struct FrontendSchemaProvider {
     backend: KvBackendRef,
     partition_manager: PartitionRuleManagerRef,
     datanode_clients: Arc<DatanodeClients>,
+    table_cache: Arc<TableCache>,
 }
 
 impl SchemaProvider for FrontendSchemaProvider {
@@ -279,6 +501,14 @@ impl SchemaProvider for FrontendSchemaProvider {
     }
 
     fn table(&self, name: &str) -> catalog::error::Result<Option<TableRef>> {
+        let table_name = TableName::new(&self.catalog_name, &self.schema_name, name);
+        if let Some(table) = self.table_cache.get(&table_name) {
+            return Ok(Some(table));
+        }
+        // Reads the generation before loading, the loaded table is stale if the table is
+        // invalidated meanwhile.
+        let generation = self.table_cache.generation();
+
         let table_global_key = TableGlobalKey {
             catalog_name: self.catalog_name.clone(),
             schema_name: self.schema_name.clone(),
@@ -288,7 +518,7 @@ impl SchemaProvider for FrontendSchemaProvider {
         let backend = self.backend.clone();
         let partition_manager = self.partition_manager.clone();
         let datanode_clients = self.datanode_clients.clone();
-        let table_name = TableName::new(&self.catalog_name, &self.schema_name, name);
+        let dist_table_name = table_name.clone();
         let result: Result<Option<TableRef>, catalog::error::Error> = std::thread::spawn(|| {
             common_runtime::block_on_read(async move {
                 let res = match backend.get(table_global_key.to_string().as_bytes()).await? {
@@ -300,7 +530,7 @@ impl SchemaProvider for FrontendSchemaProvider {
                 let val = TableGlobalValue::from_bytes(res.1).context(InvalidCatalogValueSnafu)?;
 
                 let table = Arc::new(DistTable::new(
-                    table_name,
+                    dist_table_name,
                     Arc::new(
                         val.table_info
                             .try_into()
@@ -315,6 +545,10 @@ impl SchemaProvider for FrontendSchemaProvider {
         })
         .join()
         .unwrap();
+        if let Ok(Some(table)) = &result {
+            self.table_cache
+                .insert(table_name, table.clone(), generation);
+        }
         result
     }
 
@@ -0,0 +1,605 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `information_schema` of the distributed frontend, its `region_peers` table shows
+//! where the regions of each table are placed and how large they are.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use catalog::error::UnimplementedSnafu;
+use catalog::helper::{build_schema_prefix, build_table_global_prefix, SchemaKey, TableGlobalKey};
+use catalog::remote::{Kv, KvBackendRef};
+use catalog::{SchemaProvider, SchemaProviderRef};
+use common_catalog::consts::{
+    DATANODE_STAT_KEY_PREFIX, INFORMATION_SCHEMA_NAME, REGION_PEERS_TABLE_ID,
+};
+use common_error::prelude::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_telemetry::warn;
+use datatypes::prelude::{ConcreteDataType, Value};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{BooleanVector, StringVector, UInt32Vector, UInt64Vector, VectorRef};
+use futures::StreamExt;
+use meta_client::rpc::{Partition as MetaPartition, TableName};
+use partition::manager::PartitionRuleManagerRef;
+use partition::partition::{PartitionBound, PartitionDef};
+use serde::Deserialize;
+use snafu::ResultExt;
+use store_api::storage::RegionNumber;
+use table::error::{TableOperationSnafu, TablesRecordBatchSnafu};
+use table::metadata::{TableId, TableInfoBuilder, TableInfoRef, TableMetaBuilder, TableType};
+use table::table::scan::SimpleTableScan;
+use table::table::TableStatistics;
+use table::{Table, TableRef};
+
+use crate::error::{self, CatalogEntrySerdeSnafu, CatalogSnafu, DeserializePartitionSnafu, Result};
+
+pub(crate) const REGION_PEERS_TABLE_NAME: &str = "region_peers";
+
+pub(crate) fn information_schema_provider(
+    catalog_name: &str,
+    backend: KvBackendRef,
+    partition_manager: PartitionRuleManagerRef,
+) -> SchemaProviderRef {
+    Arc::new(InformationSchemaProvider {
+        region_peers: Arc::new(RegionPeersTable::new(
+            catalog_name.to_string(),
+            backend,
+            partition_manager,
+        )),
+    })
+}
+
+struct InformationSchemaProvider {
+    region_peers: TableRef,
+}
+
+impl SchemaProvider for InformationSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> catalog::error::Result<Vec<String>> {
+        Ok(vec![REGION_PEERS_TABLE_NAME.to_string()])
+    }
+
+    fn table(&self, name: &str) -> catalog::error::Result<Option<TableRef>> {
+        if name.eq_ignore_ascii_case(REGION_PEERS_TABLE_NAME) {
+            Ok(Some(self.region_peers.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn register_table(
+        &self,
+        _name: String,
+        _table: TableRef,
+    ) -> catalog::error::Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "register table in information schema",
+        }
+        .fail()
+    }
+
+    fn rename_table(&self, _name: &str, _new_name: String) -> catalog::error::Result<TableRef> {
+        UnimplementedSnafu {
+            operation: "rename table in information schema",
+        }
+        .fail()
+    }
+
+    fn deregister_table(&self, _name: &str) -> catalog::error::Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "deregister table in information schema",
+        }
+        .fail()
+    }
+
+    fn table_exist(&self, name: &str) -> catalog::error::Result<bool> {
+        Ok(name.eq_ignore_ascii_case(REGION_PEERS_TABLE_NAME))
+    }
+}
+
+/// A peer serving a region of a table, the leader and each follower of a region
+/// are different peers.
+#[derive(Debug, PartialEq, Eq)]
+struct RegionPeer {
+    schema_name: String,
+    table_name: String,
+    region_number: RegionNumber,
+    /// The right bound of the region, `None` if the table is not partitioned.
+    partition: Option<String>,
+    peer_id: u64,
+    peer_addr: String,
+    is_leader: bool,
+    /// Approximate bytes of the region reported by the peer, `None` if the peer has
+    /// not reported it yet.
+    approximate_bytes: Option<u64>,
+}
+
+/// A virtual table lists the peers of all regions in a catalog.
+struct RegionPeersTable {
+    table_info: TableInfoRef,
+    catalog_name: String,
+    backend: KvBackendRef,
+    partition_manager: PartitionRuleManagerRef,
+}
+
+impl RegionPeersTable {
+    fn new(
+        catalog_name: String,
+        backend: KvBackendRef,
+        partition_manager: PartitionRuleManagerRef,
+    ) -> Self {
+        let table_info = virtual_table_info(
+            REGION_PEERS_TABLE_ID,
+            &catalog_name,
+            INFORMATION_SCHEMA_NAME,
+            REGION_PEERS_TABLE_NAME,
+            Arc::new(build_schema_for_region_peers()),
+        );
+        Self {
+            table_info,
+            catalog_name,
+            backend,
+            partition_manager,
+        }
+    }
+
+    async fn region_peers(&self) -> Result<Vec<RegionPeer>> {
+        let region_bytes = self.region_bytes().await?;
+
+        let mut peers = vec![];
+        for schema_name in self.schema_names().await? {
+            for table_name in self.table_names(&schema_name).await? {
+                let table = TableName::new(&self.catalog_name, &schema_name, &table_name);
+                let route = self
+                    .partition_manager
+                    .find_table_route(&table)
+                    .await
+                    .with_context(|_| error::FindTableRouteSnafu {
+                        table_name: table.to_string(),
+                    })?;
+
+                for region_route in &route.region_routes {
+                    let region_number = region_route.region.id as RegionNumber;
+                    let partition = region_route
+                        .region
+                        .partition
+                        .clone()
+                        .map(partition_to_string)
+                        .transpose()?;
+                    let leader = region_route.leader_peer.iter().map(|peer| (peer, true));
+                    let followers = region_route.follower_peers.iter().map(|peer| (peer, false));
+                    for (peer, is_leader) in leader.chain(followers) {
+                        let key = (
+                            peer.id,
+                            schema_name.clone(),
+                            table_name.clone(),
+                            region_number,
+                        );
+                        peers.push(RegionPeer {
+                            schema_name: schema_name.clone(),
+                            table_name: table_name.clone(),
+                            region_number,
+                            partition: partition.clone(),
+                            peer_id: peer.id,
+                            peer_addr: peer.addr.clone(),
+                            is_leader,
+                            approximate_bytes: region_bytes.get(&key).copied(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(peers)
+    }
+
+    async fn schema_names(&self) -> Result<Vec<String>> {
+        let key = build_schema_prefix(&self.catalog_name);
+        let mut iter = self.backend.range(key.as_bytes());
+        let mut schema_names = vec![];
+        while let Some(kv) = iter.next().await {
+            let Kv(k, _) = kv.context(CatalogSnafu)?;
+            let key =
+                SchemaKey::parse(String::from_utf8_lossy(&k)).context(CatalogEntrySerdeSnafu)?;
+            schema_names.push(key.schema_name);
+        }
+        schema_names.sort_unstable();
+        Ok(schema_names)
+    }
+
+    async fn table_names(&self, schema_name: &str) -> Result<Vec<String>> {
+        let key = build_table_global_prefix(&self.catalog_name, schema_name);
+        let mut iter = self.backend.range(key.as_bytes());
+        let mut table_names = vec![];
+        while let Some(kv) = iter.next().await {
+            let Kv(k, _) = kv.context(CatalogSnafu)?;
+            let key = TableGlobalKey::parse(String::from_utf8_lossy(&k))
+                .context(CatalogEntrySerdeSnafu)?;
+            table_names.push(key.table_name);
+        }
+        table_names.sort_unstable();
+        Ok(table_names)
+    }
+
+    /// Returns approximate bytes of the regions in this catalog, keyed by the peer id,
+    /// schema name, table name and region number.
+    async fn region_bytes(&self) -> Result<HashMap<(u64, String, String, RegionNumber), u64>> {
+        let mut region_bytes = HashMap::new();
+        for (peer_id, region) in latest_region_stats(&self.backend).await? {
+            if region.catalog != self.catalog_name {
+                continue;
+            }
+            let key = (
+                peer_id,
+                region.schema,
+                region.table,
+                region.id as RegionNumber,
+            );
+            let _ = region_bytes.insert(key, region.approximate_bytes.max(0) as u64);
+        }
+        Ok(region_bytes)
+    }
+}
+
+/// Returns the latest stats of regions reported by each datanode, paired with the id of
+/// the datanode.
+pub(crate) async fn latest_region_stats(
+    backend: &KvBackendRef,
+) -> Result<Vec<(u64, DatanodeRegionStat)>> {
+    let prefix = format!("{DATANODE_STAT_KEY_PREFIX}-");
+    let mut iter = backend.range(prefix.as_bytes());
+    let mut region_stats = vec![];
+    while let Some(kv) = iter.next().await {
+        let Kv(k, v) = kv.context(CatalogSnafu)?;
+        let stats = match serde_json::from_slice::<DatanodeStats>(&v) {
+            Ok(stats) => stats,
+            Err(e) => {
+                warn!(
+                    "Failed to deserialize datanode stats {}, error: {e}",
+                    String::from_utf8_lossy(&k)
+                );
+                continue;
+            }
+        };
+        // Stats are appended in the order they are reported, the last is the latest.
+        let Some(stat) = stats.stats.into_iter().last() else {
+            continue;
+        };
+        region_stats.extend(
+            stat.region_stats
+                .into_iter()
+                .map(|region| (stat.id, region)),
+        );
+    }
+    Ok(region_stats)
+}
+
+/// Aggregates the statistics of the tables in the schema `catalog.schema` from the
+/// `region_stats`, keyed by the table name. Followers of a region report the region as
+/// well, so the largest statistics of each region are taken.
+pub(crate) fn table_statistics(
+    region_stats: Vec<(u64, DatanodeRegionStat)>,
+    catalog: &str,
+    schema: &str,
+) -> HashMap<String, TableStatistics> {
+    let mut regions: HashMap<(String, RegionNumber), TableStatistics> = HashMap::new();
+    for (_, region) in region_stats {
+        if region.catalog != catalog || region.schema != schema {
+            continue;
+        }
+        let stat = regions
+            .entry((region.table, region.id as RegionNumber))
+            .or_default();
+        stat.num_rows = stat.num_rows.max(region.approximate_rows.max(0) as u64);
+        stat.num_bytes = stat.num_bytes.max(region.approximate_bytes.max(0) as u64);
+        if region.last_write_millis > 0 {
+            stat.last_write_millis = stat.last_write_millis.max(Some(region.last_write_millis));
+        }
+    }
+
+    let mut tables: HashMap<String, TableStatistics> = HashMap::new();
+    for ((table, _), region) in regions {
+        let stat = tables.entry(table).or_default();
+        stat.num_rows += region.num_rows;
+        stat.num_bytes += region.num_bytes;
+        stat.last_write_millis = stat.last_write_millis.max(region.last_write_millis);
+    }
+    tables
+}
+
+/// The datanode stats persisted by metasrv, only fields needed by the frontend are
+/// deserialized.
+#[derive(Deserialize)]
+struct DatanodeStats {
+    stats: Vec<DatanodeStat>,
+}
+
+#[derive(Deserialize)]
+struct DatanodeStat {
+    id: u64,
+    region_stats: Vec<DatanodeRegionStat>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DatanodeRegionStat {
+    /// The region id, its lower 32 bits is the region number.
+    pub(crate) id: u64,
+    pub(crate) catalog: String,
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) approximate_bytes: i64,
+    pub(crate) approximate_rows: i64,
+    /// Unix timestamp in milliseconds of the last write, 0 if not written or reported by old
+    /// datanodes.
+    #[serde(default)]
+    pub(crate) last_write_millis: i64,
+}
+
+/// Formats the partition of a region like it is declared in `CREATE TABLE`, e.g.
+/// `VALUES LESS THAN ('a', MAXVALUE)`.
+fn partition_to_string(partition: MetaPartition) -> Result<String> {
+    let partition = PartitionDef::try_from(partition).context(DeserializePartitionSnafu)?;
+    let bounds = partition
+        .partition_bounds()
+        .iter()
+        .map(|bound| match bound {
+            PartitionBound::Value(Value::String(s)) => format!("'{}'", s.as_utf8()),
+            PartitionBound::Value(v) => v.to_string(),
+            PartitionBound::MaxValue => "MAXVALUE".to_string(),
+        })
+        .collect::<Vec<_>>();
+    Ok(format!("VALUES LESS THAN ({})", bounds.join(", ")))
+}
+
+#[async_trait::async_trait]
+impl Table for RegionPeersTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let peers = self
+            .region_peers()
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+        let columns = region_peers_to_record_batch(&self.catalog_name, &peers);
+        let schema = self.schema();
+        let batch = RecordBatch::new(schema.clone(), columns)
+            .and_then(|batch| RecordBatches::try_new(schema, vec![batch]))
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(batch.as_stream())))
+    }
+}
+
+/// Builds the info of a virtual table, which is not stored in meta and has no regions.
+pub(crate) fn virtual_table_info(
+    table_id: TableId,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    schema: SchemaRef,
+) -> TableInfoRef {
+    let next_column_id = schema.num_columns() as u32;
+    let meta = TableMetaBuilder::default()
+        .schema(schema)
+        .primary_key_indices(vec![])
+        .next_column_id(next_column_id)
+        .build()
+        // All required fields are set.
+        .unwrap();
+    Arc::new(
+        TableInfoBuilder::default()
+            .table_id(table_id)
+            .name(table_name)
+            .catalog_name(catalog_name)
+            .schema_name(schema_name)
+            .table_version(0)
+            .table_type(TableType::Temporary)
+            .meta(meta)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn region_peers_to_record_batch(catalog_name: &str, peers: &[RegionPeer]) -> Vec<VectorRef> {
+    vec![
+        Arc::new(StringVector::from(vec![catalog_name; peers.len()])),
+        Arc::new(StringVector::from(
+            peers
+                .iter()
+                .map(|p| p.schema_name.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            peers
+                .iter()
+                .map(|p| p.table_name.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt32Vector::from_vec(
+            peers.iter().map(|p| p.region_number).collect(),
+        )),
+        Arc::new(StringVector::from(
+            peers
+                .iter()
+                .map(|p| p.partition.as_deref())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt64Vector::from_vec(
+            peers.iter().map(|p| p.peer_id).collect(),
+        )),
+        Arc::new(StringVector::from(
+            peers
+                .iter()
+                .map(|p| p.peer_addr.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(BooleanVector::from(
+            peers.iter().map(|p| p.is_leader).collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt64Vector::from(
+            peers
+                .iter()
+                .map(|p| p.approximate_bytes)
+                .collect::<Vec<_>>(),
+        )),
+    ]
+}
+
+fn build_schema_for_region_peers() -> Schema {
+    let cols = vec![
+        ColumnSchema::new("table_catalog", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("table_schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("table_name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("region_id", ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new("partition", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("peer_id", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("peer_addr", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("is_leader", ConcreteDataType::boolean_datatype(), false),
+        ColumnSchema::new(
+            "approximate_bytes",
+            ConcreteDataType::uint64_datatype(),
+            true,
+        ),
+    ];
+    Schema::new(cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_to_string() {
+        let partition = PartitionDef::new(
+            vec!["host".to_string(), "n".to_string()],
+            vec![
+                PartitionBound::Value(Value::from("a")),
+                PartitionBound::Value(Value::from(10i32)),
+            ],
+        );
+        assert_eq!(
+            "VALUES LESS THAN ('a', 10)",
+            partition_to_string(partition.try_into().unwrap()).unwrap()
+        );
+
+        let partition = PartitionDef::new(vec!["host".to_string()], vec![PartitionBound::MaxValue]);
+        assert_eq!(
+            "VALUES LESS THAN (MAXVALUE)",
+            partition_to_string(partition.try_into().unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_datanode_stats() {
+        let json = r#"{"stats":[{"timestamp_millis":1,"cluster_id":0,"id":2,"addr":"a",
+            "region_stats":[]},{"timestamp_millis":2,"cluster_id":0,"id":2,"addr":"a",
+            "region_stats":[{"id":4294967297,"catalog":"greptime","schema":"public",
+            "table":"cpu","rcus":0,"wcus":0,"approximate_bytes":1024,"approximate_rows":8}]}]}"#;
+        let stats: DatanodeStats = serde_json::from_str(json).unwrap();
+        let stat = stats.stats.into_iter().last().unwrap();
+        assert_eq!(2, stat.id);
+        let region = &stat.region_stats[0];
+        assert_eq!(1, region.id as RegionNumber);
+        assert_eq!("cpu", region.table);
+        assert_eq!(1024, region.approximate_bytes);
+        assert_eq!(8, region.approximate_rows);
+        assert_eq!(0, region.last_write_millis);
+    }
+
+    #[test]
+    fn test_table_statistics() {
+        let region = |peer, table: &str, region, rows, last_write_millis| {
+            let stat = DatanodeRegionStat {
+                id: region,
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table: table.to_string(),
+                approximate_bytes: rows * 10,
+                approximate_rows: rows,
+                last_write_millis,
+            };
+            (peer, stat)
+        };
+        let region_stats = vec![
+            region(1, "cpu", 0, 8, 1000),
+            // A follower of the region lagging behind the leader.
+            region(2, "cpu", 0, 6, 900),
+            region(2, "cpu", 1, 2, 0),
+            region(1, "mem", 0, 4, 2000),
+        ];
+        let mut tables = table_statistics(region_stats, "greptime", "public");
+        assert_eq!(2, tables.len());
+        let expected = TableStatistics {
+            num_rows: 10,
+            num_bytes: 100,
+            last_write_millis: Some(1000),
+        };
+        assert_eq!(Some(expected), tables.remove("cpu"));
+        let expected = TableStatistics {
+            num_rows: 4,
+            num_bytes: 40,
+            last_write_millis: Some(2000),
+        };
+        assert_eq!(Some(expected), tables.remove("mem"));
+
+        let region_stats = vec![region(1, "cpu", 0, 8, 1000)];
+        assert!(table_statistics(region_stats, "greptime", "other").is_empty());
+    }
+
+    #[test]
+    fn test_virtual_table_info() {
+        let schema = Arc::new(build_schema_for_region_peers());
+        let table_info = virtual_table_info(
+            REGION_PEERS_TABLE_ID,
+            "greptime",
+            INFORMATION_SCHEMA_NAME,
+            REGION_PEERS_TABLE_NAME,
+            schema.clone(),
+        );
+        assert_eq!(REGION_PEERS_TABLE_ID, table_info.ident.table_id);
+        assert_eq!("greptime", table_info.catalog_name);
+        assert_eq!(INFORMATION_SCHEMA_NAME, table_info.schema_name);
+        assert_eq!(TableType::Temporary, table_info.table_type);
+        assert_eq!(schema, table_info.meta.schema);
+        assert!(table_info.meta.region_numbers.is_empty());
+        assert_eq!(schema.num_columns() as u32, table_info.meta.next_column_id);
+    }
+}
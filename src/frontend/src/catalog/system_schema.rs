@@ -0,0 +1,167 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `system` schema of the default catalog in the distributed frontend, its
+//! `ddl_history` table reads the DDL operations recorded in meta.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use catalog::ddl_history::{build_ddl_history_schema, ddl_history_to_columns};
+use catalog::error::UnimplementedSnafu;
+use catalog::helper::{build_ddl_history_prefix, DdlHistoryValue};
+use catalog::remote::{Kv, KvBackendRef};
+use catalog::{SchemaProvider, SchemaProviderRef};
+use common_catalog::consts::{
+    DDL_HISTORY_TABLE_ID, DDL_HISTORY_TABLE_NAME, DEFAULT_CATALOG_NAME, SYSTEM_SCHEMA_NAME,
+};
+use common_error::prelude::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::schema::SchemaRef;
+use futures::StreamExt;
+use snafu::ResultExt;
+use table::error::{TableOperationSnafu, TablesRecordBatchSnafu};
+use table::metadata::{TableInfoRef, TableType};
+use table::table::scan::SimpleTableScan;
+use table::{Table, TableRef};
+
+use crate::catalog::information_schema::virtual_table_info;
+use crate::error::{CatalogEntrySerdeSnafu, CatalogSnafu, Result};
+
+pub(crate) fn system_schema_provider(backend: KvBackendRef) -> SchemaProviderRef {
+    Arc::new(SystemSchemaProvider {
+        ddl_history: Arc::new(DdlHistoryTable {
+            table_info: virtual_table_info(
+                DDL_HISTORY_TABLE_ID,
+                DEFAULT_CATALOG_NAME,
+                SYSTEM_SCHEMA_NAME,
+                DDL_HISTORY_TABLE_NAME,
+                Arc::new(build_ddl_history_schema()),
+            ),
+            backend,
+        }),
+    })
+}
+
+struct SystemSchemaProvider {
+    ddl_history: TableRef,
+}
+
+impl SchemaProvider for SystemSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> catalog::error::Result<Vec<String>> {
+        Ok(vec![DDL_HISTORY_TABLE_NAME.to_string()])
+    }
+
+    fn table(&self, name: &str) -> catalog::error::Result<Option<TableRef>> {
+        if name.eq_ignore_ascii_case(DDL_HISTORY_TABLE_NAME) {
+            Ok(Some(self.ddl_history.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn register_table(
+        &self,
+        _name: String,
+        _table: TableRef,
+    ) -> catalog::error::Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "register table in system schema",
+        }
+        .fail()
+    }
+
+    fn rename_table(&self, _name: &str, _new_name: String) -> catalog::error::Result<TableRef> {
+        UnimplementedSnafu {
+            operation: "rename table in system schema",
+        }
+        .fail()
+    }
+
+    fn deregister_table(&self, _name: &str) -> catalog::error::Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "deregister table in system schema",
+        }
+        .fail()
+    }
+
+    fn table_exist(&self, name: &str) -> catalog::error::Result<bool> {
+        Ok(name.eq_ignore_ascii_case(DDL_HISTORY_TABLE_NAME))
+    }
+}
+
+/// A virtual table lists the DDL operations recorded in meta, in the order they are
+/// executed.
+struct DdlHistoryTable {
+    table_info: TableInfoRef,
+    backend: KvBackendRef,
+}
+
+impl DdlHistoryTable {
+    async fn ddl_history(&self) -> Result<Vec<DdlHistoryValue>> {
+        let key = build_ddl_history_prefix();
+        let mut iter = self.backend.range(key.as_bytes());
+        let mut values = vec![];
+        while let Some(kv) = iter.next().await {
+            let Kv(_, v) = kv.context(CatalogSnafu)?;
+            values.push(DdlHistoryValue::from_bytes(v).context(CatalogEntrySerdeSnafu)?);
+        }
+        Ok(values)
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for DdlHistoryTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let values = self
+            .ddl_history()
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)?;
+        let columns = ddl_history_to_columns(&values);
+        let schema = self.schema();
+        let batch = RecordBatch::new(schema.clone(), columns)
+            .and_then(|batch| RecordBatches::try_new(schema, vec![batch]))
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(batch.as_stream())))
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use api::v1::meta::CatalogVersion;
+use meta_client::rpc::TableName;
+use moka::sync::Cache;
+use table::TableRef;
+
+pub(crate) const DEFAULT_TABLE_CACHE_CAPACITY: u64 = 1024;
+pub(crate) const DEFAULT_TABLE_CACHE_TTL_SECS: u64 = 10 * 60;
+
+/// Cache of tables loaded from meta, entries are invalidated by the table changes
+/// notified in heartbeats.
+pub(crate) struct TableCache {
+    tables: Cache<TableName, TableRef>,
+    /// Increased by every invalidation, so tables loaded before an invalidation are not
+    /// cached after it.
+    generation: AtomicU64,
+    /// Catalog version of meta the cached tables are up to date with, `None` before the
+    /// first heartbeat response.
+    version: Mutex<Option<CatalogVersion>>,
+}
+
+impl Default for TableCache {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_TABLE_CACHE_CAPACITY,
+            Duration::from_secs(DEFAULT_TABLE_CACHE_TTL_SECS),
+        )
+    }
+}
+
+impl TableCache {
+    pub(crate) fn new(capacity: u64, ttl: Duration) -> Self {
+        Self {
+            tables: Cache::builder()
+                .max_capacity(capacity)
+                .time_to_live(ttl)
+                .build(),
+            generation: AtomicU64::new(0),
+            version: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn get(&self, table_name: &TableName) -> Option<TableRef> {
+        self.tables.get(table_name)
+    }
+
+    /// Returns the generation to pass to [TableCache::insert] for tables loaded after.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Caches the `table` loaded at `generation`, unless any table is invalidated since.
+    pub(crate) fn insert(&self, table_name: TableName, table: TableRef, generation: u64) {
+        if self.generation() != generation {
+            return;
+        }
+        self.tables.insert(table_name.clone(), table);
+        // The invalidation may happen between the check and the insertion.
+        if self.generation() != generation {
+            self.tables.invalidate(&table_name);
+        }
+    }
+
+    pub(crate) fn invalidate(&self, table_name: &TableName) {
+        let _ = self.generation.fetch_add(1, Ordering::SeqCst);
+        self.tables.invalidate(table_name);
+    }
+
+    pub(crate) fn invalidate_all(&self) {
+        let _ = self.generation.fetch_add(1, Ordering::SeqCst);
+        self.tables.invalidate_all();
+    }
+
+    pub(crate) fn version(&self) -> Option<CatalogVersion> {
+        self.version.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_version(&self, version: Option<CatalogVersion>) {
+        *self.version.lock().unwrap() = version;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use table::test_util::MemTable;
+
+    use super::*;
+
+    fn new_table() -> TableRef {
+        Arc::new(MemTable::default_numbers_table())
+    }
+
+    #[test]
+    fn test_table_cache() {
+        let cache = TableCache::default();
+        let name = TableName::new("greptime", "public", "demo");
+        assert!(cache.get(&name).is_none());
+
+        let generation = cache.generation();
+        cache.insert(name.clone(), new_table(), generation);
+        assert!(cache.get(&name).is_some());
+
+        cache.invalidate(&name);
+        assert!(cache.get(&name).is_none());
+
+        // Tables loaded before the invalidation are not cached.
+        cache.insert(name.clone(), new_table(), generation);
+        assert!(cache.get(&name).is_none());
+
+        cache.insert(name.clone(), new_table(), cache.generation());
+        assert!(cache.get(&name).is_some());
+        cache.invalidate_all();
+        assert!(cache.get(&name).is_none());
+    }
+}
@@ -12,22 +12,91 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use client::Client;
-use common_grpc::channel_manager::ChannelManager;
+use client::{Client, Database};
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::tls::GrpcTlsOption;
 use meta_client::rpc::Peer;
 use moka::future::{Cache, CacheBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Options of the clients connecting to datanodes in the distributed mode.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatanodeClientOptions {
+    pub timeout_millis: u64,
+    pub connect_timeout_millis: u64,
+    pub tcp_nodelay: bool,
+    /// Interval of HTTP2 keep-alive pings to datanodes, detects dead connections even if
+    /// they are idle.
+    pub keep_alive_interval_millis: u64,
+    /// Timeout of HTTP2 keep-alive pings, the connection is closed if the ping is not
+    /// acknowledged in time.
+    pub keep_alive_timeout_millis: u64,
+    /// Connects to datanodes with TLS if set.
+    pub tls: Option<GrpcTlsOption>,
+}
+
+impl Default for DatanodeClientOptions {
+    fn default() -> Self {
+        Self {
+            timeout_millis: 10_000,
+            connect_timeout_millis: 5_000,
+            tcp_nodelay: true,
+            keep_alive_interval_millis: 10_000,
+            keep_alive_timeout_millis: 20_000,
+            tls: None,
+        }
+    }
+}
+
+impl DatanodeClientOptions {
+    /// Returns the config of channels to datanodes.
+    pub fn channel_config(&self) -> ChannelConfig {
+        let config = ChannelConfig::new()
+            .timeout(Duration::from_millis(self.timeout_millis))
+            .connect_timeout(Duration::from_millis(self.connect_timeout_millis))
+            .tcp_nodelay(self.tcp_nodelay)
+            .http2_keep_alive_interval(Duration::from_millis(self.keep_alive_interval_millis))
+            .http2_keep_alive_timeout(Duration::from_millis(self.keep_alive_timeout_millis))
+            .http2_keep_alive_while_idle(true);
+        match &self.tls {
+            Some(tls) => config.client_tls(tls.clone()),
+            None => config,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Deadline of the request being handled, the datanodes abandon the requests sent on
+    /// behalf of it once the deadline passes.
+    pub(crate) static REQUEST_DEADLINE: Option<Instant>;
+}
+
+/// Sets the deadline of the request being handled, if any, to the requests of `db`.
+pub(crate) fn with_request_deadline(mut db: Database) -> Database {
+    if let Ok(Some(deadline)) = REQUEST_DEADLINE.try_with(|deadline| *deadline) {
+        db.set_deadline(deadline);
+    }
+    db
+}
 
 pub(crate) struct DatanodeClients {
     channel_manager: ChannelManager,
     clients: Cache<Peer, Client>,
 }
 
+impl Default for DatanodeClients {
+    fn default() -> Self {
+        Self::new(ChannelConfig::default())
+    }
+}
+
 impl DatanodeClients {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: ChannelConfig) -> Self {
         Self {
-            channel_manager: ChannelManager::new(),
+            channel_manager: ChannelManager::with_config(config),
             clients: CacheBuilder::new(1024)
                 .time_to_live(Duration::from_secs(30 * 60))
                 .time_to_idle(Duration::from_secs(5 * 60))
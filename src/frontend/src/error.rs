@@ -128,13 +128,13 @@ pub enum Error {
     },
 
     #[snafu(display(
-        "Columns and values number mismatch, columns: {}, values: {}",
-        columns,
-        values,
+        "Insert statement has {} rows, exceeds the max rows {}",
+        rows,
+        max_rows
     ))]
-    ColumnValuesNumberMismatch {
-        columns: usize,
-        values: usize,
+    TooManyInsertRows {
+        rows: usize,
+        max_rows: usize,
         backtrace: Backtrace,
     },
 
@@ -202,6 +202,15 @@ pub enum Error {
         source: common_grpc_expr::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to convert GRPC DeleteRequest to table DeleteRequest, source: {}",
+        source
+    ))]
+    ToTableDeleteRequest {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
     #[snafu(display("Failed to find catalog by name: {}", catalog_name))]
     CatalogNotFound {
         catalog_name: String,
@@ -273,6 +282,12 @@ pub enum Error {
     #[snafu(display("Table already exists: `{}`", table))]
     TableAlreadyExist { table: String, backtrace: Backtrace },
 
+    #[snafu(display("Schema already exists: `{}`", name))]
+    SchemaExists { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Catalog already exists: `{}`", name))]
+    CatalogExists { name: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to encode Substrait logical plan, source: {}", source))]
     EncodeSubstraitLogicalPlan {
         #[snafu(backtrace)]
@@ -302,26 +317,6 @@ pub enum Error {
         source: common_grpc_expr::error::Error,
     },
 
-    #[snafu(display(
-        "Failed to build default value, column: {}, source: {}",
-        column,
-        source
-    ))]
-    ColumnDefaultValue {
-        column: String,
-        #[snafu(backtrace)]
-        source: datatypes::error::Error,
-    },
-
-    #[snafu(display(
-        "No valid default value can be built automatically, column: {}",
-        column,
-    ))]
-    ColumnNoneDefaultValue {
-        column: String,
-        backtrace: Backtrace,
-    },
-
     #[snafu(display("SQL execution intercepted, source: {}", source))]
     SqlExecIntercepted {
         #[snafu(backtrace)]
@@ -350,6 +345,56 @@ pub enum Error {
         #[snafu(backtrace)]
         source: query::error::Error,
     },
+
+    #[snafu(display("Query {} is killed", id))]
+    QueryKilled { id: u64, backtrace: Backtrace },
+
+    #[snafu(display("Unknown query id: {}", id))]
+    ProcessNotFound { id: u64, backtrace: Backtrace },
+
+    #[snafu(display("Permission denied: {}", reason))]
+    PermissionDenied {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("User {} is not allowed to kill query {}", user, id))]
+    KillQueryDenied {
+        user: String,
+        id: u64,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to create record batches, source: {}", source))]
+    CreateRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Failed to collect record batches, source: {}", source))]
+    CollectRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Alert rule already exists: {}", name))]
+    AlertRuleExists { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Alert rule not found: {}", name))]
+    AlertRuleNotFound { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid webhook {} of alert rule, reason: {}", webhook, reason))]
+    InvalidWebhook {
+        webhook: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to recover jobs, source: {}", source))]
+    RecoverJobs {
+        #[snafu(backtrace)]
+        source: common_procedure::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -360,7 +405,7 @@ impl ErrorExt for Error {
             Error::ParseAddr { .. }
             | Error::InvalidSql { .. }
             | Error::InvalidInsertRequest { .. }
-            | Error::ColumnValuesNumberMismatch { .. } => StatusCode::InvalidArguments,
+            | Error::TooManyInsertRows { .. } => StatusCode::InvalidArguments,
 
             Error::NotSupported { .. } => StatusCode::Unsupported,
 
@@ -410,6 +455,7 @@ impl ErrorExt for Error {
 
             Error::BuildCreateExprOnInsertion { source }
             | Error::ToTableInsertRequest { source }
+            | Error::ToTableDeleteRequest { source }
             | Error::FindNewColumnsOnInsertion { source } => source.status_code(),
 
             Error::PrimaryKeyNotFound { .. } => StatusCode::InvalidArguments,
@@ -420,14 +466,27 @@ impl ErrorExt for Error {
             Error::AlterExprToRequest { source, .. } => source.status_code(),
             Error::LeaderNotFound { .. } => StatusCode::StorageUnavailable,
             Error::TableAlreadyExist { .. } => StatusCode::TableAlreadyExists,
+            Error::SchemaExists { .. } | Error::CatalogExists { .. } => {
+                StatusCode::InvalidArguments
+            }
             Error::EncodeSubstraitLogicalPlan { source } => source.status_code(),
             Error::InvokeDatanode { source } => source.status_code(),
-            Error::ColumnDefaultValue { source, .. } => source.status_code(),
-            Error::ColumnNoneDefaultValue { .. } => StatusCode::InvalidArguments,
             Error::External { source } => source.status_code(),
             Error::DeserializePartition { source, .. } | Error::FindTableRoute { source, .. } => {
                 source.status_code()
             }
+            Error::QueryKilled { .. } => StatusCode::Cancelled,
+            Error::ProcessNotFound { .. } => StatusCode::InvalidArguments,
+            Error::KillQueryDenied { .. } | Error::PermissionDenied { .. } => {
+                StatusCode::AccessDenied
+            }
+            Error::CreateRecordBatches { source } | Error::CollectRecordBatches { source } => {
+                source.status_code()
+            }
+            Error::AlertRuleExists { .. }
+            | Error::AlertRuleNotFound { .. }
+            | Error::InvalidWebhook { .. } => StatusCode::InvalidArguments,
+            Error::RecoverJobs { source } => source.status_code(),
         }
     }
 
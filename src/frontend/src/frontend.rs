@@ -14,12 +14,16 @@
 
 use std::sync::Arc;
 
+use common_grpc::token::ClusterToken;
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::Mode;
 use snafu::prelude::*;
+use table::masking::MaskingPolicy;
 
+use crate::catalog::table_cache::{DEFAULT_TABLE_CACHE_CAPACITY, DEFAULT_TABLE_CACHE_TTL_SECS};
+use crate::datanode::DatanodeClientOptions;
 use crate::error::{self, Result};
 use crate::grpc::GrpcOptions;
 use crate::influxdb::InfluxdbOptions;
@@ -30,6 +34,7 @@ use crate::postgres::PostgresOptions;
 use crate::prometheus::PrometheusOptions;
 use crate::promql::PromqlOptions;
 use crate::server::Services;
+use crate::table_template::TableTemplate;
 use crate::Plugins;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,6 +50,26 @@ pub struct FrontendOptions {
     pub promql_options: Option<PromqlOptions>,
     pub mode: Mode,
     pub meta_client_opts: Option<MetaClientOpts>,
+    /// Options of the clients connecting to datanodes in the distributed mode.
+    pub datanode_client_opts: DatanodeClientOptions,
+    /// Shared secret of the cluster attached to requests to metasrv and datanodes in the
+    /// distributed mode.
+    pub cluster_token: Option<ClusterToken>,
+    /// Templates of tables auto-created on insertion, the first matched one is used.
+    pub table_templates: Vec<TableTemplate>,
+    /// Policies to mask sensitive columns in query results of the distributed mode.
+    pub masking_policies: Vec<MaskingPolicy>,
+    /// Max number of rows in the `VALUES` list of an insert statement in the distributed
+    /// mode, no limit if not set.
+    pub max_insert_rows: Option<usize>,
+    /// Hosts the webhooks of alert rules could be on even if they are internal addresses,
+    /// which are rejected otherwise.
+    pub alert_webhook_allowed_hosts: Vec<String>,
+    /// Max number of tables cached by the frontend in the distributed mode.
+    pub table_cache_capacity: u64,
+    /// Cached tables are reloaded from meta after this TTL even if no invalidation is
+    /// received, in case of invalidations lost, e.g. while the heartbeat stream is broken.
+    pub table_cache_ttl_secs: u64,
 }
 
 impl Default for FrontendOptions {
@@ -60,6 +85,14 @@ impl Default for FrontendOptions {
             promql_options: Some(PromqlOptions::default()),
             mode: Mode::Standalone,
             meta_client_opts: None,
+            datanode_client_opts: DatanodeClientOptions::default(),
+            cluster_token: None,
+            table_templates: vec![],
+            masking_policies: vec![],
+            max_insert_rows: None,
+            alert_webhook_allowed_hosts: vec![],
+            table_cache_capacity: DEFAULT_TABLE_CACHE_CAPACITY,
+            table_cache_ttl_secs: DEFAULT_TABLE_CACHE_TTL_SECS,
         }
     }
 }
@@ -12,12 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::readable_size::ReadableSize;
+use common_grpc::tls::GrpcTlsOption;
 use serde::{Deserialize, Serialize};
+use servers::grpc::{GrpcServerConfig, DEFAULT_MAX_GRPC_MESSAGE_SIZE};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GrpcOptions {
     pub addr: String,
     pub runtime_size: usize,
+    /// Max size of a request message.
+    pub max_message_size: ReadableSize,
+    /// Max number of concurrent HTTP/2 streams of a connection, unlimited if not set.
+    pub max_concurrent_streams: Option<u32>,
+    /// Max number of in-flight requests of a connection, unlimited if not set.
+    pub max_in_flight_requests: Option<usize>,
+    /// Serves over TLS if set.
+    pub tls: Option<GrpcTlsOption>,
 }
 
 impl Default for GrpcOptions {
@@ -25,6 +37,20 @@ impl Default for GrpcOptions {
         Self {
             addr: "127.0.0.1:4001".to_string(),
             runtime_size: 8,
+            max_message_size: ReadableSize(DEFAULT_MAX_GRPC_MESSAGE_SIZE as u64),
+            max_concurrent_streams: None,
+            max_in_flight_requests: None,
+            tls: None,
+        }
+    }
+}
+
+impl GrpcOptions {
+    pub fn server_config(&self) -> GrpcServerConfig {
+        GrpcServerConfig {
+            max_message_size: self.max_message_size.0 as usize,
+            max_concurrent_streams: self.max_concurrent_streams,
+            max_in_flight_requests_per_connection: self.max_in_flight_requests,
         }
     }
 }
@@ -0,0 +1,202 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::meta::{HeartbeatRequest, HeartbeatResponse};
+use common_telemetry::{error, info, warn};
+use meta_client::client::{HeartbeatSender, MetaClient};
+use snafu::ResultExt;
+
+use crate::catalog::FrontendCatalogManager;
+use crate::error::{RequestMetaSnafu, Result};
+
+/// Sends heartbeats carrying the catalog version of the table cache to metasrv, and
+/// invalidates the cached tables changed since the version.
+pub struct HeartbeatTask {
+    running: Arc<AtomicBool>,
+    meta_client: Arc<MetaClient>,
+    catalog_manager: Arc<FrontendCatalogManager>,
+    interval: Duration,
+}
+
+impl Drop for HeartbeatTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl HeartbeatTask {
+    pub fn new(meta_client: Arc<MetaClient>, catalog_manager: Arc<FrontendCatalogManager>) -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            meta_client,
+            catalog_manager,
+            // The interval bounds how long a frontend reads the stale metadata of tables
+            // changed by others.
+            interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the interval of heartbeats sent to metasrv.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    async fn create_streams(
+        meta_client: &MetaClient,
+        catalog_manager: Arc<FrontendCatalogManager>,
+    ) -> Result<HeartbeatSender> {
+        // The stream to metasrv is reconnected by the meta client when broken, it only
+        // ends once the sender is dropped.
+        let (tx, mut rx) = meta_client.heartbeat().await.context(RequestMetaSnafu)?;
+        common_runtime::spawn_bg(async move {
+            loop {
+                match rx.message().await {
+                    Ok(Some(res)) => Self::handle_response(&catalog_manager, res).await,
+                    Ok(None) => break,
+                    Err(e) => error!(e; "Error while reading heartbeat response"),
+                }
+            }
+            info!("Heartbeat handling loop exit.")
+        });
+        Ok(tx)
+    }
+
+    async fn handle_response(catalog_manager: &FrontendCatalogManager, resp: HeartbeatResponse) {
+        // Responses without a catalog version are from metasrv nodes not tracking
+        // table changes, e.g. followers.
+        let Some(version) = resp.catalog_version else {
+            return;
+        };
+        if resp.invalidate_all {
+            catalog_manager.invalidate_all_tables();
+        } else {
+            for table_name in resp.invalidated_tables {
+                catalog_manager.invalidate_table(&table_name.into()).await;
+            }
+        }
+        catalog_manager.table_cache().set_version(Some(version));
+    }
+
+    /// Stops the heartbeat task, it exits before sending the next heartbeat.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    /// Starts the heartbeat task in background.
+    pub async fn start(&self) -> Result<()> {
+        let running = self.running.clone();
+        if running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Heartbeat task started multiple times");
+            return Ok(());
+        }
+        let interval = self.interval;
+        let catalog_manager = self.catalog_manager.clone();
+
+        let tx = Self::create_streams(&self.meta_client, catalog_manager.clone()).await?;
+        common_runtime::spawn_bg(async move {
+            while running.load(Ordering::Acquire) {
+                // Metasrv notifies all tables as changed for the default version, since the
+                // cache may miss any change before the first response.
+                let version = catalog_manager.table_cache().version();
+                let req = HeartbeatRequest {
+                    catalog_version: Some(version.unwrap_or_default()),
+                    ..Default::default()
+                };
+                if let Err(e) = tx.send(req).await {
+                    error!(e; "Failed to send heartbeat to metasrv");
+                }
+                tokio::time::sleep(interval).await;
+            }
+            info!("Heartbeat task shutdown");
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::meta::{CatalogVersion, TableName as PbTableName};
+    use catalog::remote::MetaKvBackend;
+    use meta_client::rpc::TableName;
+    use partition::manager::PartitionRuleManager;
+    use partition::route::TableRoutes;
+    use table::test_util::MemTable;
+
+    use super::*;
+    use crate::datanode::DatanodeClients;
+
+    #[tokio::test]
+    async fn test_handle_response() {
+        let meta_client = Arc::new(MetaClient::default());
+        let catalog_manager = FrontendCatalogManager::new(
+            Arc::new(MetaKvBackend {
+                client: meta_client.clone(),
+            }),
+            Arc::new(PartitionRuleManager::new(Arc::new(TableRoutes::new(
+                meta_client,
+            )))),
+            Arc::new(DatanodeClients::default()),
+        );
+        let table_cache = catalog_manager.table_cache();
+        let demo = TableName::new("greptime", "public", "demo");
+        let other = TableName::new("greptime", "public", "other");
+        for name in [&demo, &other] {
+            table_cache.insert(
+                name.clone(),
+                Arc::new(MemTable::default_numbers_table()),
+                table_cache.generation(),
+            );
+        }
+
+        // Responses without catalog versions are ignored.
+        HeartbeatTask::handle_response(&catalog_manager, HeartbeatResponse::default()).await;
+        assert!(table_cache.get(&demo).is_some());
+        assert!(table_cache.version().is_none());
+
+        let version = CatalogVersion {
+            epoch: 1,
+            version: 2,
+        };
+        let resp = HeartbeatResponse {
+            catalog_version: Some(version.clone()),
+            invalidated_tables: vec![PbTableName {
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                table_name: "demo".to_string(),
+            }],
+            ..Default::default()
+        };
+        HeartbeatTask::handle_response(&catalog_manager, resp).await;
+        assert!(table_cache.get(&demo).is_none());
+        assert!(table_cache.get(&other).is_some());
+        assert_eq!(Some(version.clone()), table_cache.version());
+
+        let resp = HeartbeatResponse {
+            catalog_version: Some(version),
+            invalidate_all: true,
+            ..Default::default()
+        };
+        HeartbeatTask::handle_response(&catalog_manager, resp).await;
+        assert!(table_cache.get(&other).is_none());
+    }
+}
@@ -27,10 +27,14 @@ use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::{AddColumns, AlterExpr, Column, DdlRequest, DropTableExpr, InsertRequest};
 use async_trait::async_trait;
+use catalog::query_history::{QueryHistoryRef, QueryTracker};
 use catalog::remote::MetaKvBackend;
+use catalog::resource_usage::ResourceAccountantRef;
 use catalog::CatalogManagerRef;
 use common_error::ext::BoxedError;
-use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::channel_manager::ChannelManager;
+use common_grpc::token::ClusterToken;
+use common_procedure::job::{JobManager, JobManagerRef};
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use common_telemetry::logging::{debug, info};
@@ -56,7 +60,9 @@ use snafu::prelude::*;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::statement::Statement;
+use sql::statements::use_database::UseDatabase;
 
+use crate::alert::{AlertManager, AlertManagerRef};
 use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
 use crate::error::{
@@ -64,7 +70,11 @@ use crate::error::{
 };
 use crate::expr_factory::{CreateExprFactoryRef, DefaultCreateExprFactory};
 use crate::frontend::FrontendOptions;
+use crate::heartbeat::HeartbeatTask;
 use crate::instance::standalone::{StandaloneGrpcQueryHandler, StandaloneSqlQueryHandler};
+use crate::job::KvStateStore;
+use crate::process::ProcessManagerRef;
+use crate::table_template::{find_table_template, TableTemplate};
 use crate::Plugins;
 
 #[async_trait]
@@ -81,6 +91,13 @@ pub trait FrontendInstance:
     + 'static
 {
     async fn start(&mut self) -> Result<()>;
+
+    /// Returns the catalog manager browsed by the catalog gRPC service.
+    fn catalog_manager(&self) -> CatalogManagerRef;
+
+    /// Returns the manager of background jobs whose records are persisted, `None` if
+    /// jobs are not persisted by this instance.
+    fn job_manager(&self) -> Option<JobManagerRef>;
 }
 
 pub type FrontendInstanceRef = Arc<dyn FrontendInstance>;
@@ -94,8 +111,26 @@ pub struct Instance {
     sql_handler: SqlQueryHandlerRef<Error>,
     grpc_query_handler: GrpcQueryHandlerRef<Error>,
     promql_handler: Option<PromqlHandlerRef>,
+    /// Query history is None in distributed mode, only works on standalone mode.
+    query_history: Option<QueryHistoryRef>,
+    /// Resource accountant is None in distributed mode, only works on standalone mode.
+    resource_accountant: Option<ResourceAccountantRef>,
+    /// Job manager persists job records to the datanode in standalone mode and to meta in
+    /// distributed mode, it is None if the HTTP server, which runs jobs, is disabled.
+    job_manager: Option<JobManagerRef>,
+    /// Running queries of this frontend.
+    process_manager: ProcessManagerRef,
+    /// Alert rules evaluated by this frontend.
+    alert_manager: AlertManagerRef,
 
     create_expr_factory: CreateExprFactoryRef,
+    /// Templates of tables auto-created on insertion.
+    table_templates: Vec<TableTemplate>,
+    /// Dist instance is None in standalone mode, it creates partitioned tables on insertion.
+    dist_instance: Option<Arc<DistInstance>>,
+    /// Heartbeat task is None in standalone mode, it invalidates the cached tables changed
+    /// in meta.
+    heartbeat_task: Option<Arc<HeartbeatTask>>,
 
     /// plugins: this map holds extensions to customize query or auth
     /// behaviours.
@@ -104,74 +139,118 @@ pub struct Instance {
 
 impl Instance {
     pub async fn try_new_distributed(opts: &FrontendOptions) -> Result<Self> {
-        let meta_client = Self::create_meta_client(opts).await?;
+        let meta_config = opts
+            .meta_client_opts
+            .as_ref()
+            .context(MissingMetasrvOptsSnafu)?;
+        let meta_client = Self::create_meta_client(meta_config, opts.cluster_token.clone()).await?;
 
         let meta_backend = Arc::new(MetaKvBackend {
             client: meta_client.clone(),
         });
         let table_routes = Arc::new(TableRoutes::new(meta_client.clone()));
         let partition_manager = Arc::new(PartitionRuleManager::new(table_routes));
-        let datanode_clients = Arc::new(DatanodeClients::new());
+        let mut datanode_channel_config = opts.datanode_client_opts.channel_config();
+        if let Some(token) = &opts.cluster_token {
+            datanode_channel_config = datanode_channel_config.cluster_token(token.clone());
+        }
+        let datanode_clients = Arc::new(DatanodeClients::new(datanode_channel_config));
 
-        let catalog_manager = Arc::new(FrontendCatalogManager::new(
-            meta_backend,
-            partition_manager,
-            datanode_clients.clone(),
-        ));
+        let catalog_manager = Arc::new(
+            FrontendCatalogManager::new(
+                meta_backend.clone(),
+                partition_manager,
+                datanode_clients.clone(),
+            )
+            .with_table_cache(
+                opts.table_cache_capacity,
+                Duration::from_secs(opts.table_cache_ttl_secs),
+            ),
+        );
+        let heartbeat_task = Arc::new(
+            HeartbeatTask::new(meta_client.clone(), catalog_manager.clone())
+                .with_interval(Duration::from_millis(meta_config.heartbeat_interval_millis)),
+        );
 
         let dist_instance =
-            DistInstance::new(meta_client, catalog_manager.clone(), datanode_clients);
+            DistInstance::new(meta_client, catalog_manager.clone(), datanode_clients)
+                .with_max_insert_rows(opts.max_insert_rows);
+        dist_instance.register_masking_policies(opts.masking_policies.clone());
         let dist_instance = Arc::new(dist_instance);
+        let job_manager = opts.http_options.as_ref().map(|http_options| {
+            let store = KvStateStore::new(meta_backend.clone(), &http_options.addr);
+            Arc::new(JobManager::with_state_store(Arc::new(store)))
+        });
+        let alert_manager =
+            AlertManager::new(dist_instance.clone(), None).with_election(meta_backend);
+        alert_manager.set_allowed_webhook_hosts(opts.alert_webhook_allowed_hosts.clone());
 
         Ok(Instance {
             catalog_manager,
             script_handler: None,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
+            table_templates: opts.table_templates.clone(),
+            dist_instance: Some(dist_instance.clone()),
+            heartbeat_task: Some(heartbeat_task),
             sql_handler: dist_instance.clone(),
-            grpc_query_handler: dist_instance,
+            grpc_query_handler: dist_instance.clone(),
             promql_handler: None,
+            query_history: None,
+            resource_accountant: None,
+            job_manager,
+            process_manager: Default::default(),
+            alert_manager: Arc::new(alert_manager),
             plugins: Default::default(),
         })
     }
 
-    async fn create_meta_client(opts: &FrontendOptions) -> Result<Arc<MetaClient>> {
-        let metasrv_addr = &opts
-            .meta_client_opts
-            .as_ref()
-            .context(MissingMetasrvOptsSnafu)?
-            .metasrv_addrs;
+    async fn create_meta_client(
+        meta_config: &MetaClientOpts,
+        cluster_token: Option<ClusterToken>,
+    ) -> Result<Arc<MetaClient>> {
         info!(
             "Creating Frontend instance in distributed mode with Meta server addr {:?}",
-            metasrv_addr
+            meta_config.metasrv_addrs
         );
 
-        let meta_config = MetaClientOpts::default();
-        let channel_config = ChannelConfig::new()
-            .timeout(Duration::from_millis(meta_config.timeout_millis))
-            .connect_timeout(Duration::from_millis(meta_config.connect_timeout_millis))
-            .tcp_nodelay(meta_config.tcp_nodelay);
+        let mut channel_config = meta_config.channel_config();
+        if let Some(token) = cluster_token {
+            channel_config = channel_config.cluster_token(token);
+        }
         let channel_manager = ChannelManager::with_config(channel_config);
-
         let mut meta_client = MetaClientBuilder::new(0, 0)
+            .enable_heartbeat()
             .enable_router()
             .enable_store()
             .channel_manager(channel_manager)
+            .heartbeat_options(meta_config.heartbeat_options())
+            .retry_options(meta_config.retry_options())
             .build();
         meta_client
-            .start(metasrv_addr)
+            .start(&meta_config.metasrv_addrs)
             .await
             .context(error::StartMetaClientSnafu)?;
         Ok(Arc::new(meta_client))
     }
 
     pub fn new_standalone(dn_instance: DnInstanceRef) -> Self {
+        let sql_handler = StandaloneSqlQueryHandler::arc(dn_instance.clone());
+        let promql_handler: PromqlHandlerRef = dn_instance.clone();
         Instance {
             catalog_manager: dn_instance.catalog_manager().clone(),
             script_handler: None,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
-            sql_handler: StandaloneSqlQueryHandler::arc(dn_instance.clone()),
+            table_templates: vec![],
+            dist_instance: None,
+            heartbeat_task: None,
+            sql_handler: sql_handler.clone(),
             grpc_query_handler: StandaloneGrpcQueryHandler::arc(dn_instance.clone()),
-            promql_handler: Some(dn_instance.clone()),
+            promql_handler: Some(promql_handler.clone()),
+            query_history: Some(dn_instance.query_history().clone()),
+            resource_accountant: Some(dn_instance.resource_accountant().clone()),
+            job_manager: Some(dn_instance.job_manager().clone()),
+            process_manager: Default::default(),
+            alert_manager: Arc::new(AlertManager::new(sql_handler, Some(promql_handler))),
             plugins: Default::default(),
         }
     }
@@ -182,9 +261,17 @@ impl Instance {
             catalog_manager: dist_instance.catalog_manager(),
             script_handler: None,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
+            table_templates: vec![],
+            dist_instance: Some(dist_instance.clone()),
+            heartbeat_task: None,
             sql_handler: dist_instance.clone(),
-            grpc_query_handler: dist_instance,
+            grpc_query_handler: dist_instance.clone(),
             promql_handler: None,
+            query_history: None,
+            resource_accountant: None,
+            job_manager: None,
+            process_manager: Default::default(),
+            alert_manager: Arc::new(AlertManager::new(dist_instance, None)),
             plugins: Default::default(),
         }
     }
@@ -287,16 +374,31 @@ impl Instance {
         let schema_name = &ctx.current_schema();
 
         // Create table automatically, build schema from data.
-        let create_expr = self
+        let mut create_expr = self
             .create_expr_factory
             .create_expr_by_columns(catalog_name, schema_name, table_name, columns)
             .await?;
+        let mut partitions = None;
+        if let Some(template) = find_table_template(&self.table_templates, table_name) {
+            template.apply(&mut create_expr);
+            partitions = template.partitions(&create_expr);
+        }
 
         info!(
-            "Try to create table: {} automatically with request: {:?}",
-            table_name, create_expr,
+            "Try to create table: {} automatically with request: {:?}, partitions: {:?}",
+            table_name, create_expr, partitions,
         );
 
+        if let (Some(partitions), Some(dist_instance)) = (partitions, &self.dist_instance) {
+            return dist_instance
+                .handle_ddl(
+                    DdlExpr::CreateTable(create_expr),
+                    Some(partitions),
+                    None,
+                    ctx,
+                )
+                .await;
+        }
         self.grpc_query_handler
             .do_query(
                 Request::Ddl(DdlRequest {
@@ -334,21 +436,47 @@ impl Instance {
             .await
     }
 
-    fn handle_use(&self, db: String, query_ctx: QueryContextRef) -> Result<Output> {
-        let catalog = &query_ctx.current_catalog();
+    fn handle_use(&self, db: UseDatabase, query_ctx: QueryContextRef) -> Result<Output> {
+        let catalog = &db
+            .catalog_name
+            .unwrap_or_else(|| query_ctx.current_catalog());
+        let schema = &db.schema_name;
+        let user = query_ctx.current_user();
+        ensure!(
+            user.can_access_catalog(catalog),
+            error::PermissionDeniedSnafu {
+                reason: format!(
+                    "user {} is not allowed to access catalog {}",
+                    user.username(),
+                    catalog
+                ),
+            }
+        );
         ensure!(
             self.catalog_manager
-                .schema(catalog, &db)
+                .schema(catalog, schema)
                 .context(error::CatalogSnafu)?
                 .is_some(),
-            error::SchemaNotFoundSnafu { schema_info: &db }
+            error::SchemaNotFoundSnafu {
+                schema_info: format!("{catalog}.{schema}"),
+            }
         );
 
-        query_ctx.set_current_schema(&db);
+        query_ctx.set_current_catalog(catalog);
+        query_ctx.set_current_schema(schema);
 
         Ok(Output::RecordBatches(RecordBatches::empty()))
     }
 
+    pub fn set_table_templates(&mut self, templates: Vec<TableTemplate>) {
+        self.table_templates = templates;
+    }
+
+    /// Allows alert rules to notify webhooks on `hosts` even if they are internal addresses.
+    pub fn set_alert_webhook_allowed_hosts(&self, hosts: Vec<String>) {
+        self.alert_manager.set_allowed_webhook_hosts(hosts);
+    }
+
     pub fn set_plugins(&mut self, map: Arc<Plugins>) {
         self.plugins = map;
     }
@@ -356,34 +484,110 @@ impl Instance {
     pub fn plugins(&self) -> Arc<Plugins> {
         self.plugins.clone()
     }
+
+    /// Stops the background tasks started by [FrontendInstance::start], e.g. the heartbeat
+    /// and the alert rule scheduler.
+    pub fn shutdown(&self) {
+        if let Some(heartbeat_task) = &self.heartbeat_task {
+            heartbeat_task.stop();
+        }
+        self.alert_manager.stop();
+    }
 }
 
 #[async_trait]
 impl FrontendInstance for Instance {
     async fn start(&mut self) -> Result<()> {
         // TODO(hl): Frontend init should move to here
+        if let Some(heartbeat_task) = &self.heartbeat_task {
+            heartbeat_task.start().await?;
+        }
+        // Jobs of the standalone mode are recovered by the datanode.
+        if self.dist_instance.is_some() {
+            if let Some(job_manager) = &self.job_manager {
+                job_manager
+                    .recover()
+                    .await
+                    .context(error::RecoverJobsSnafu)?;
+                job_manager.start_watchdog();
+            }
+        }
+        self.alert_manager.start();
         Ok(())
     }
+
+    fn catalog_manager(&self) -> CatalogManagerRef {
+        self.catalog_manager.clone()
+    }
+
+    fn job_manager(&self) -> Option<JobManagerRef> {
+        self.job_manager.clone()
+    }
 }
 
 fn parse_stmt(sql: &str) -> Result<Vec<Statement>> {
     ParserContext::create_with_dialect(sql, &GenericDialect {}).context(error::ParseSqlSnafu)
 }
 
+fn finish_tracking(tracker: Option<QueryTracker>, result: Result<Output>) -> Result<Output> {
+    let Some(tracker) = tracker else {
+        return result;
+    };
+    match result {
+        Ok(output) => Ok(tracker.finish_with_output(output)),
+        Err(e) => {
+            tracker.finish(0, false);
+            Err(e)
+        }
+    }
+}
+
 impl Instance {
+    fn track_query(&self, query: &str, query_ctx: &QueryContextRef) -> Option<QueryTracker> {
+        if self.query_history.is_none() && self.resource_accountant.is_none() {
+            return None;
+        }
+        let protocol = query_ctx
+            .channel()
+            .map(|channel| channel.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let mut tracker = QueryTracker::new(query, query_ctx.current_user().username(), &protocol);
+        if let Some(history) = &self.query_history {
+            tracker = tracker.with_history(history.clone());
+        }
+        if let Some(accountant) = &self.resource_accountant {
+            tracker = tracker.with_accountant(
+                accountant.clone(),
+                &query_ctx.current_catalog(),
+                &query_ctx.current_schema(),
+            );
+        }
+        Some(tracker)
+    }
+
     async fn query_statement(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
-        // TODO(sunng87): provide a better form to log or track statement
-        let query = &format!("{:?}", &stmt);
         match stmt.clone() {
-            Statement::CreateDatabase(_)
+            Statement::CreateCatalog(_)
+            | Statement::CreateDatabase(_)
             | Statement::ShowDatabases(_)
             | Statement::CreateTable(_)
             | Statement::ShowTables(_)
+            | Statement::ShowTableStatus(_)
             | Statement::DescribeTable(_)
+            | Statement::ShowCreateTable(_)
             | Statement::Explain(_)
+            | Statement::ExplainDdl(_)
             | Statement::Query(_)
             | Statement::Insert(_)
-            | Statement::Alter(_) => {
+            | Statement::Alter(_)
+            | Statement::AlterDatabase(_)
+            | Statement::AnalyzeTable(_)
+            | Statement::CheckTable(_)
+            | Statement::UndropTable(_) => {
+                return self.sql_handler.do_statement_query(stmt, query_ctx).await;
+            }
+            // The datanode records the statement into the DDL history in standalone mode.
+            Statement::DropTable(_) if self.dist_instance.is_none() => {
                 return self.sql_handler.do_statement_query(stmt, query_ctx).await;
             }
             Statement::DropTable(drop_stmt) => {
@@ -406,8 +610,18 @@ impl Instance {
                     )
                     .await;
             }
-            Statement::ShowCreateTable(_) => error::NotSupportedSnafu { feat: query }.fail(),
             Statement::Use(db) => self.handle_use(db, query_ctx),
+            Statement::ShowProcesslist(stmt) => self.process_manager.show_processlist(&stmt),
+            Statement::Kill(kill) => {
+                self.process_manager
+                    .kill(kill.query_id, &query_ctx.current_user())?;
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::CreateAlertRule(stmt) => {
+                self.alert_manager.create_rule(stmt, query_ctx).await
+            }
+            Statement::DropAlertRule(stmt) => self.alert_manager.drop_rule(stmt).await,
+            Statement::ShowAlertRules => self.alert_manager.show_rules().await,
         }
     }
 }
@@ -434,17 +648,27 @@ impl SqlQueryHandler for Instance {
                     // LogicalPlan as to this hook.
                     if let Err(e) = query_interceptor.pre_execute(&stmt, None, query_ctx.clone()) {
                         results.push(Err(e));
+                        if query_ctx.continue_on_error() {
+                            continue;
+                        }
                         break;
                     }
-                    match self.query_statement(stmt, query_ctx.clone()).await {
+                    let tracker = self.track_query(query.as_ref(), &query_ctx);
+                    let process = self.process_manager.register(query.as_ref(), &query_ctx);
+                    match process
+                        .run(self.query_statement(stmt, query_ctx.clone()))
+                        .await
+                    {
                         Ok(output) => {
                             let output_result =
                                 query_interceptor.post_execute(output, query_ctx.clone());
-                            results.push(output_result);
+                            results.push(finish_tracking(tracker, output_result));
                         }
                         Err(e) => {
-                            results.push(Err(e));
-                            break;
+                            results.push(finish_tracking(tracker, Err(e)));
+                            if !query_ctx.continue_on_error() {
+                                break;
+                            }
                         }
                     }
                 }
@@ -512,9 +736,13 @@ impl ScriptHandler for Instance {
         }
     }
 
-    async fn execute_script(&self, script: &str) -> server_error::Result<Output> {
+    async fn execute_script(
+        &self,
+        script: &str,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
         if let Some(handler) = &self.script_handler {
-            handler.execute_script(script).await
+            handler.execute_script(script, query_ctx).await
         } else {
             server_error::NotSupportedSnafu {
                 feat: "Script execution in Frontend",
@@ -543,7 +771,8 @@ mod tests {
     use std::borrow::Cow;
     use std::sync::atomic::AtomicU32;
 
-    use session::context::QueryContext;
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use session::context::{Channel, QueryContext};
 
     use super::*;
     use crate::tests;
@@ -644,6 +873,36 @@ mod tests {
         };
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_continue_on_error() {
+        let query_ctx = Arc::new(QueryContext::new());
+
+        let standalone = tests::create_standalone_instance("test_continue_on_error").await;
+        let instance = standalone.instance;
+
+        let sql = "CREATE TABLE demo(host STRING, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))";
+        let output = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let sql = "insert into demo values ('host1', 1000); \
+            insert into not_exist values ('host2', 2000); \
+            insert into demo values ('host3', 3000)";
+        let outputs = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone()).await;
+        assert_eq!(2, outputs.len());
+        assert!(matches!(outputs[0], Ok(Output::AffectedRows(1))));
+        assert!(outputs[1].is_err());
+
+        query_ctx.set_continue_on_error(true);
+        let outputs = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone()).await;
+        assert_eq!(3, outputs.len());
+        assert!(matches!(outputs[0], Ok(Output::AffectedRows(1))));
+        assert!(outputs[1].is_err());
+        assert!(matches!(outputs[2], Ok(Output::AffectedRows(1))));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_sql_interceptor_plugin() {
         #[derive(Default)]
@@ -809,4 +1068,243 @@ mod tests {
             unreachable!();
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_query_history() {
+        let standalone = tests::create_standalone_instance("test_query_history").await;
+        let instance = standalone.instance;
+
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_channel(Channel::Mysql);
+        let output = SqlQueryHandler::do_query(&*instance, "select 1", query_ctx.clone())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let _ = common_recordbatch::util::collect(stream).await.unwrap();
+        assert!(SqlQueryHandler::do_query(
+            &*instance,
+            "select * from not_exist",
+            query_ctx.clone()
+        )
+        .await
+        .remove(0)
+        .is_err());
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "select rows, username, protocol, success from system.query_history",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let batches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++------+----------+----------+---------+
+| rows | username | protocol | success |
++------+----------+----------+---------+
+| 1    | greptime | mysql    | true    |
+| 0    | greptime | mysql    | false   |
++------+----------+----------+---------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_resource_usage() {
+        let standalone = tests::create_standalone_instance("test_resource_usage").await;
+        let instance = standalone.instance;
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "select * from numbers limit 5",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let _ = common_recordbatch::util::collect(stream).await.unwrap();
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "select username, schema, queries, returned_rows, scanned_bytes > 0 as scanned \
+             from system.resource_usage",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let batches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++----------+--------+---------+---------------+---------+
+| username | schema | queries | returned_rows | scanned |
++----------+--------+---------+---------------+---------+
+| greptime | public | 1       | 5             | true    |
++----------+--------+---------+---------------+---------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_show_processlist_and_kill() {
+        let standalone = tests::create_standalone_instance("test_show_processlist_and_kill").await;
+        let instance = standalone.instance;
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "select * from numbers limit 5",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+
+        let output = SqlQueryHandler::do_query(&*instance, "show processlist", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else {
+            unreachable!()
+        };
+        let expected = "\
++----+----------+--------+----------+------+-------------------------------+
+| Id | User     | Db     | Protocol | Time | Info                          |
++----+----------+--------+----------+------+-------------------------------+
+| 1  | greptime | public | unknown  | 0    | select * from numbers limit 5 |
+| 2  | greptime | public | unknown  | 0    | show processlist              |
++----+----------+--------+----------+------+-------------------------------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+
+        let output = SqlQueryHandler::do_query(&*instance, "kill query 1", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let err = common_recordbatch::util::collect(stream).await.unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+
+        let err = SqlQueryHandler::do_query(&*instance, "kill query 1", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    async fn show_alert_rules(instance: &Instance) -> String {
+        let output = SqlQueryHandler::do_query(instance, "show alert rules", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else {
+            unreachable!()
+        };
+        batches.pretty_print().unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_alert_rules() {
+        let standalone = tests::create_standalone_instance("test_alert_rules").await;
+        let instance = standalone.instance;
+
+        // Webhooks on internal addresses are rejected unless they are allowed.
+        let err = SqlQueryHandler::do_query(
+            &*instance,
+            "create alert rule high_cpu every '1m' webhook 'http://127.0.0.1:1/alert' as select 1",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        instance.set_alert_webhook_allowed_hosts(vec!["127.0.0.1".to_string()]);
+
+        for sql in [
+            "create table monitor(host string, cpu double, ts timestamp, \
+             time index(ts), primary key(host))",
+            "create alert rule high_cpu every '1m' webhook 'http://127.0.0.1:1/alert' \
+             as select host from monitor where cpu > 90",
+        ] {
+            let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc())
+                .await
+                .remove(0)
+                .unwrap();
+            assert!(matches!(output, Output::AffectedRows(0)));
+        }
+        let err = SqlQueryHandler::do_query(
+            &*instance,
+            "create alert rule high_cpu every '1m' webhook 'http://127.0.0.1:1/alert' as select 1",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let expected = "\
++----------+--------+------+-----------------------------------------+----------+--------------------------+----------+
+| Name     | Db     | Type | Expr                                    | Interval | Webhook                  | State    |
++----------+--------+------+-----------------------------------------+----------+--------------------------+----------+
+| high_cpu | public | sql  | SELECT host FROM monitor WHERE cpu > 90 | 1m       | http://127.0.0.1:1/alert | inactive |
++----------+--------+------+-----------------------------------------+----------+--------------------------+----------+";
+        assert_eq!(expected, show_alert_rules(&instance).await);
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "insert into monitor(host, cpu, ts) values ('host1', 95, 1000)",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        // The webhook is unreachable, which doesn't prevent the rule from firing.
+        instance.alert_manager.evaluate_due_rules().await;
+        assert!(show_alert_rules(&instance).await.contains("| firing"));
+
+        // The firing state is loaded from the persisted rules.
+        let alert_manager = AlertManager::new(instance.sql_handler.clone(), None);
+        let Output::RecordBatches(batches) = alert_manager.show_rules().await.unwrap() else {
+            unreachable!()
+        };
+        assert!(batches.pretty_print().unwrap().contains("| firing"));
+
+        let output =
+            SqlQueryHandler::do_query(&*instance, "drop alert rule high_cpu", QueryContext::arc())
+                .await
+                .remove(0)
+                .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+        let expected = "\
++------+----+------+------+----------+---------+-------+
+| Name | Db | Type | Expr | Interval | Webhook | State |
++------+----+------+------+----------+---------+-------+
++------+----+------+------+----------+---------+-------+";
+        assert_eq!(expected, show_alert_rules(&instance).await);
+
+        let err =
+            SqlQueryHandler::do_query(&*instance, "drop alert rule high_cpu", QueryContext::arc())
+                .await
+                .remove(0)
+                .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let alert_manager = AlertManager::new(instance.sql_handler.clone(), None);
+        let Output::RecordBatches(batches) = alert_manager.show_rules().await.unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
 }
@@ -12,17 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod explain;
 mod grpc;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
-use api::v1::{AlterExpr, CreateDatabaseExpr, CreateTableExpr, InsertRequest, TableId};
+use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::{
+    AlterExpr, CreateDatabaseExpr, CreateTableExpr, DeleteRequest, InsertRequest, TableId,
+};
 use async_trait::async_trait;
-use catalog::helper::{SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue};
-use catalog::{CatalogList, CatalogManager};
-use chrono::DateTime;
+use catalog::helper::{DdlHistoryValue, SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue};
+use catalog::{
+    format_full_table_name, AlterSchemaRequest, CatalogList, CatalogManager, CreateCatalogRequest,
+};
+use chrono::{DateTime, Utc};
 use client::Database;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::prelude::BoxedError;
@@ -38,20 +44,26 @@ use meta_client::rpc::{
 };
 use partition::partition::{PartitionBound, PartitionDef};
 use query::parser::QueryStatement;
-use query::sql::{describe_table, explain, show_databases, show_tables};
+use query::sql::{
+    describe_table, estimated_affected_rows, explain, explain_ddl_output, show_create_table,
+    show_databases, show_table_status, show_tables,
+};
 use query::{QueryEngineFactory, QueryEngineRef};
 use servers::query_handler::sql::SqlQueryHandler;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::Value as SqlValue;
-use sql::statements::create::Partitions;
+use sql::statements::alter::{AlterDatabase, AlterDatabaseOperation};
+use sql::statements::create::{CreateCatalog, CreateTable, Partitions};
 use sql::statements::sql_value_to_value;
 use sql::statements::statement::Statement;
+use table::masking::MaskingPolicy;
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+use table::requests::{AlterDatabaseKind, AlterKind};
 use table::table::AlterContext;
 
 use crate::catalog::FrontendCatalogManager;
-use crate::datanode::DatanodeClients;
+use crate::datanode::{with_request_deadline, DatanodeClients, REQUEST_DEADLINE};
 use crate::error::{
     self, AlterExprToRequestSnafu, CatalogEntrySerdeSnafu, CatalogNotFoundSnafu, CatalogSnafu,
     ColumnDataTypeSnafu, DeserializePartitionSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu,
@@ -61,6 +73,7 @@ use crate::error::{
 use crate::expr_factory::{CreateExprFactory, DefaultCreateExprFactory};
 use crate::instance::parse_stmt;
 use crate::sql::insert_to_request;
+use crate::table::{ScanContext, SCAN_CONTEXT};
 
 #[derive(Clone)]
 pub(crate) struct DistInstance {
@@ -68,6 +81,8 @@ pub(crate) struct DistInstance {
     catalog_manager: Arc<FrontendCatalogManager>,
     datanode_clients: Arc<DatanodeClients>,
     query_engine: QueryEngineRef,
+    /// Max number of rows in the `VALUES` list of an insert statement.
+    max_insert_rows: Option<usize>,
 }
 
 impl DistInstance {
@@ -82,15 +97,36 @@ impl DistInstance {
             catalog_manager,
             datanode_clients,
             query_engine,
+            max_insert_rows: None,
         }
     }
 
+    /// Rejects insert statements with more than `max_insert_rows` rows.
+    pub(crate) fn with_max_insert_rows(mut self, max_insert_rows: Option<usize>) -> Self {
+        self.max_insert_rows = max_insert_rows;
+        self
+    }
+
+    pub(crate) fn register_masking_policies(&self, policies: Vec<MaskingPolicy>) {
+        self.query_engine.register_masking_policies(policies);
+    }
+
     pub(crate) async fn create_table(
         &self,
         create_table: &mut CreateTableExpr,
         partitions: Option<Partitions>,
     ) -> Result<Output> {
-        let response = self.create_table_in_meta(create_table, partitions).await?;
+        if create_table.create_if_not_exists && self.table_exists(create_table)? {
+            info!(
+                "Table {:?}.{:?}.{} already exists, skip creating",
+                create_table.catalog_name, create_table.schema_name, create_table.table_name
+            );
+            return Ok(Output::AffectedRows(0));
+        }
+        self.fill_default_table_options(create_table).await?;
+        let response = self
+            .create_table_in_meta(create_table, partitions, false)
+            .await?;
         let table_routes = response.table_routes;
         ensure!(
             table_routes.len() == 1,
@@ -121,7 +157,7 @@ impl DistInstance {
 
         for datanode in table_route.find_leaders() {
             let client = self.datanode_clients.get_client(&datanode).await;
-            let client = Database::with_client(client);
+            let client = with_request_deadline(Database::with_client(client));
 
             let regions = table_route.find_leader_regions(&datanode);
             let mut create_expr_for_region = create_table.clone();
@@ -142,34 +178,271 @@ impl DistInstance {
         Ok(Output::AffectedRows(0))
     }
 
+    /// Validates the `CREATE TABLE` statement and previews the region placement picked by
+    /// the selector of meta, without creating anything.
+    async fn explain_create_table(&self, stmt: CreateTable) -> Result<Vec<(String, String)>> {
+        let mut create_table = DefaultCreateExprFactory.create_expr_by_stmt(&stmt).await?;
+        self.fill_default_table_options(&mut create_table).await?;
+
+        let catalog_name = if create_table.catalog_name.is_empty() {
+            DEFAULT_CATALOG_NAME
+        } else {
+            create_table.catalog_name.as_str()
+        };
+        let schema_name = if create_table.schema_name.is_empty() {
+            DEFAULT_SCHEMA_NAME
+        } else {
+            create_table.schema_name.as_str()
+        };
+        let table_name = create_table.table_name.as_str();
+        let full_table_name = format_full_table_name(catalog_name, schema_name, table_name);
+        let exists = self
+            .catalog_manager
+            .catalog(catalog_name)
+            .context(CatalogSnafu)?
+            .context(CatalogNotFoundSnafu { catalog_name })?
+            .schema(schema_name)
+            .context(CatalogSnafu)?
+            .context(SchemaNotFoundSnafu {
+                schema_info: format!("{catalog_name}.{schema_name}"),
+            })?
+            .table(table_name)
+            .context(CatalogSnafu)?
+            .is_some();
+        ensure!(
+            !exists || create_table.create_if_not_exists,
+            error::TableAlreadyExistSnafu {
+                table: &full_table_name
+            }
+        );
+        if exists {
+            return Ok(vec![(
+                "Action".to_string(),
+                format!("none, table {full_table_name} already exists"),
+            )]);
+        }
+
+        let response = self
+            .create_table_in_meta(&create_table, stmt.partitions, true)
+            .await?;
+        let table_route = response
+            .table_routes
+            .first()
+            .context(error::CreateTableRouteSnafu {
+                table_name: &full_table_name,
+            })?;
+
+        let mut items = vec![(
+            "Action".to_string(),
+            format!("CREATE TABLE {full_table_name}"),
+        )];
+        items.extend(table_route.region_routes.iter().map(|route| {
+            let peer = route
+                .leader_peer
+                .as_ref()
+                .map(|peer| format!("datanode {} ({})", peer.id, peer.addr))
+                .unwrap_or_else(|| "no datanode".to_string());
+            (format!("Region {}", route.region.id), peer)
+        }));
+        items.push(("Affected rows".to_string(), "0".to_string()));
+        Ok(items)
+    }
+
+    /// Validates the `ALTER TABLE` expr against the table info, and lists the datanodes
+    /// serving the regions to alter, without altering anything.
+    async fn explain_alter_table(&self, expr: AlterExpr) -> Result<Vec<(String, String)>> {
+        let catalog_name = expr.catalog_name.as_str();
+        let schema_name = expr.schema_name.as_str();
+        let table_name = expr.table_name.as_str();
+        let full_table_name = format_full_table_name(catalog_name, schema_name, table_name);
+        let table = self
+            .catalog_manager
+            .table(catalog_name, schema_name, table_name)
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu {
+                table_name: &full_table_name,
+            })?;
+
+        let request = common_grpc_expr::alter_expr_to_request(expr.clone())
+            .context(AlterExprToRequestSnafu)?;
+        // Builds the new table meta to check the alteration is applicable.
+        let _ = table
+            .table_info()
+            .meta
+            .builder_with_alter_kind(table_name, &request.alter_kind)
+            .context(TableSnafu)?;
+        if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
+            let exists = self
+                .catalog_manager
+                .table(catalog_name, schema_name, new_table_name)
+                .context(CatalogSnafu)?
+                .is_some();
+            ensure!(
+                !exists,
+                error::TableAlreadyExistSnafu {
+                    table: format_full_table_name(catalog_name, schema_name, new_table_name),
+                }
+            );
+        }
+
+        let table_route = self
+            .catalog_manager
+            .partition_manager()
+            .find_table_route(&TableName::new(catalog_name, schema_name, table_name))
+            .await
+            .with_context(|_| error::FindTableRouteSnafu {
+                table_name: &full_table_name,
+            })?;
+        let statistics = table.collect_statistics().await.context(TableSnafu)?;
+
+        let mut items = vec![(
+            "Action".to_string(),
+            format!("ALTER TABLE {full_table_name} {}", request.alter_kind),
+        )];
+        items.extend(table_route.region_routes.iter().map(|route| {
+            let peer = route
+                .leader_peer
+                .as_ref()
+                .map(|peer| format!("datanode {} ({})", peer.id, peer.addr))
+                .unwrap_or_else(|| "no datanode".to_string());
+            (format!("Region {}", route.region.id), peer)
+        }));
+        items.push((
+            "Affected rows".to_string(),
+            estimated_affected_rows(statistics),
+        ));
+        Ok(items)
+    }
+
+    /// Returns true if the table to create in `create_table` already exists.
+    fn table_exists(&self, create_table: &CreateTableExpr) -> Result<bool> {
+        let catalog_name = if create_table.catalog_name.is_empty() {
+            DEFAULT_CATALOG_NAME
+        } else {
+            create_table.catalog_name.as_str()
+        };
+        let schema_name = if create_table.schema_name.is_empty() {
+            DEFAULT_SCHEMA_NAME
+        } else {
+            create_table.schema_name.as_str()
+        };
+        Ok(self
+            .catalog_manager
+            .table(catalog_name, schema_name, &create_table.table_name)
+            .context(CatalogSnafu)?
+            .is_some())
+    }
+
+    /// Fills the default table options of the schema into `create_table`, options of the
+    /// table itself take precedence.
+    async fn fill_default_table_options(&self, create_table: &mut CreateTableExpr) -> Result<()> {
+        let key = SchemaKey {
+            catalog_name: if create_table.catalog_name.is_empty() {
+                DEFAULT_CATALOG_NAME.to_string()
+            } else {
+                create_table.catalog_name.clone()
+            },
+            schema_name: if create_table.schema_name.is_empty() {
+                DEFAULT_SCHEMA_NAME.to_string()
+            } else {
+                create_table.schema_name.clone()
+            },
+        };
+        let Some(kv) = self
+            .catalog_manager
+            .backend()
+            .get(key.to_string().as_bytes())
+            .await
+            .context(CatalogSnafu)? else {
+            return Ok(());
+        };
+        let value = SchemaValue::from_bytes(kv.1).context(CatalogEntrySerdeSnafu)?;
+        for (key, value) in value.default_table_options {
+            create_table.table_options.entry(key).or_insert(value);
+        }
+        Ok(())
+    }
+
     async fn handle_statement(
         &self,
         stmt: Statement,
         query_ctx: QueryContextRef,
     ) -> Result<Output> {
         match stmt {
-            Statement::Query(_) => {
+            Statement::Query(query) => {
+                let scan_ctx = ScanContext {
+                    read_replica: query.hints.read_replica,
+                };
                 let plan = self
                     .query_engine
-                    .statement_to_plan(QueryStatement::Sql(stmt), query_ctx)
+                    .statement_to_plan(QueryStatement::Sql(Statement::Query(query)), query_ctx)
                     .context(error::ExecuteStatementSnafu {})?;
-                self.query_engine.execute(&plan).await
+                // Scans of the tables are planned in the execution, which request the
+                // datanodes in the context of the query.
+                SCAN_CONTEXT
+                    .scope(scan_ctx, self.query_engine.execute(&plan))
+                    .await
             }
             Statement::CreateDatabase(stmt) => {
+                let statement = stmt.to_string();
                 let expr = CreateDatabaseExpr {
                     database_name: stmt.name.to_string(),
                     create_if_not_exists: stmt.if_not_exists,
+                    options: stmt.options(),
+                    catalog_name: query_ctx.current_catalog(),
                 };
-                Ok(self.handle_create_database(expr).await?)
+                Ok(self
+                    .handle_ddl(
+                        DdlExpr::CreateDatabase(expr),
+                        None,
+                        Some(statement),
+                        query_ctx,
+                    )
+                    .await?)
             }
             Statement::CreateTable(stmt) => {
-                let create_expr = &mut DefaultCreateExprFactory.create_expr_by_stmt(&stmt).await?;
-                Ok(self.create_table(create_expr, stmt.partitions).await?)
+                let statement = stmt.to_string();
+                let create_expr = DefaultCreateExprFactory.create_expr_by_stmt(&stmt).await?;
+                Ok(self
+                    .handle_ddl(
+                        DdlExpr::CreateTable(create_expr),
+                        stmt.partitions,
+                        Some(statement),
+                        query_ctx,
+                    )
+                    .await?)
+            }
+            Statement::Alter(stmt) => {
+                let statement = stmt.to_string();
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(stmt.table_name(), query_ctx.clone())
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let expr = AlterExpr {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    ..AlterExpr::try_from(stmt).context(error::AlterExprFromStmtSnafu)?
+                };
+                Ok(self
+                    .handle_ddl(DdlExpr::Alter(expr), None, Some(statement), query_ctx)
+                    .await?)
+            }
+            Statement::CreateCatalog(stmt) => {
+                Ok(self.handle_create_catalog(stmt, query_ctx).await?)
+            }
+            Statement::AlterDatabase(stmt) => {
+                Ok(self.handle_alter_database(stmt, query_ctx).await?)
+            }
+            Statement::ShowDatabases(stmt) => {
+                show_databases(stmt, self.catalog_manager.clone(), query_ctx)
             }
-            Statement::ShowDatabases(stmt) => show_databases(stmt, self.catalog_manager.clone()),
             Statement::ShowTables(stmt) => {
                 show_tables(stmt, self.catalog_manager.clone(), query_ctx)
             }
+            Statement::ShowTableStatus(stmt) => {
+                show_table_status(stmt, self.catalog_manager.clone(), query_ctx).await
+            }
             Statement::DescribeTable(stmt) => {
                 let (catalog, schema, table) = table_idents_to_full_name(stmt.name(), query_ctx)
                     .map_err(BoxedError::new)
@@ -183,8 +456,62 @@ impl DistInstance {
                     })?;
                 describe_table(table)
             }
+            Statement::ShowCreateTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&stmt.table_name, query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(error::ExternalSnafu)?;
+                let table = self
+                    .catalog_manager
+                    .table(&catalog, &schema, &table)
+                    .context(CatalogSnafu)?
+                    .with_context(|| TableNotFoundSnafu {
+                        table_name: stmt.table_name.to_string(),
+                    })?;
+                show_create_table(table)
+            }
             Statement::Explain(stmt) => {
-                explain(Box::new(stmt), self.query_engine.clone(), query_ctx).await
+                if let Some(format) = stmt.format {
+                    return self.explain_distributed(stmt, format, query_ctx).await;
+                }
+                // The query is executed by `EXPLAIN ANALYZE`.
+                let scan_ctx = ScanContext {
+                    read_replica: stmt.hints.read_replica,
+                };
+                SCAN_CONTEXT
+                    .scope(
+                        scan_ctx,
+                        explain(Box::new(stmt), self.query_engine.clone(), query_ctx),
+                    )
+                    .await
+            }
+            Statement::ExplainDdl(stmt) => {
+                let items = match *stmt.statement {
+                    Statement::CreateTable(create_table) => {
+                        self.explain_create_table(create_table).await?
+                    }
+                    Statement::Alter(alter_table) => {
+                        let (catalog_name, schema_name, table_name) =
+                            table_idents_to_full_name(alter_table.table_name(), query_ctx)
+                                .map_err(BoxedError::new)
+                                .context(error::ExternalSnafu)?;
+                        let expr = AlterExpr {
+                            catalog_name,
+                            schema_name,
+                            table_name,
+                            ..AlterExpr::try_from(alter_table)
+                                .context(error::AlterExprFromStmtSnafu)?
+                        };
+                        self.explain_alter_table(expr).await?
+                    }
+                    stmt => {
+                        return error::NotSupportedSnafu {
+                            feat: format!("EXPLAIN DDL of {stmt:?} in distributed mode"),
+                        }
+                        .fail()
+                    }
+                };
+                explain_ddl_output(items)
             }
             Statement::Insert(insert) => {
                 let (catalog, schema, table) = insert.full_table_name().context(ParseSqlSnafu)?;
@@ -195,12 +522,30 @@ impl DistInstance {
                     .context(CatalogSnafu)?
                     .context(TableNotFoundSnafu { table_name: table })?;
 
-                let insert_request = insert_to_request(&table, *insert)?;
+                let insert_request = insert_to_request(&table, *insert, self.max_insert_rows)?;
 
                 return Ok(Output::AffectedRows(
                     table.insert(insert_request).await.context(TableSnafu)?,
                 ));
             }
+            Statement::AnalyzeTable(_) => {
+                return error::NotSupportedSnafu {
+                    feat: "ANALYZE TABLE in distributed mode",
+                }
+                .fail();
+            }
+            Statement::CheckTable(_) => {
+                return error::NotSupportedSnafu {
+                    feat: "CHECK TABLE in distributed mode",
+                }
+                .fail();
+            }
+            Statement::UndropTable(_) => {
+                return error::NotSupportedSnafu {
+                    feat: "UNDROP TABLE in distributed mode",
+                }
+                .fail();
+            }
             _ => unreachable!(),
         }
         .context(error::ExecuteStatementSnafu)
@@ -218,7 +563,7 @@ impl DistInstance {
 
                     results.push(result);
 
-                    if is_err {
+                    if is_err && !query_ctx.continue_on_error() {
                         break;
                     }
                 }
@@ -229,27 +574,190 @@ impl DistInstance {
         }
     }
 
-    /// Handles distributed database creation
-    async fn handle_create_database(&self, expr: CreateDatabaseExpr) -> Result<Output> {
-        let key = SchemaKey {
-            // TODO(sunng87): custom catalog
-            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
-            schema_name: expr.database_name,
+    /// Handles the DDL and records it into the DDL history in meta, with the SQL `statement`
+    /// of the DDL if it's issued by SQL.
+    pub(crate) async fn handle_ddl(
+        &self,
+        expr: DdlExpr,
+        partitions: Option<Partitions>,
+        statement: Option<String>,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (operation, object_name) = match &expr {
+            DdlExpr::CreateDatabase(expr) => (
+                "CREATE DATABASE",
+                format!(
+                    "{}.{}",
+                    catalog_or_default(&expr.catalog_name),
+                    expr.database_name
+                ),
+            ),
+            DdlExpr::CreateTable(expr) => (
+                "CREATE TABLE",
+                full_table_name(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+            ),
+            DdlExpr::Alter(expr) => (
+                "ALTER TABLE",
+                full_table_name(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+            ),
+            DdlExpr::DropTable(expr) => (
+                "DROP TABLE",
+                full_table_name(&expr.catalog_name, &expr.schema_name, &expr.table_name),
+            ),
         };
-        let value = SchemaValue {};
+        let result = match expr {
+            DdlExpr::CreateDatabase(expr) => self.handle_create_database(expr).await,
+            DdlExpr::CreateTable(mut expr) => {
+                // TODO(LFC): Support creating distributed table through GRPC interface.
+                // Currently only SQL supports it; how to design the fields in CreateTableExpr?
+                self.create_table(&mut expr, partitions).await
+            }
+            DdlExpr::Alter(expr) => self.handle_alter_table(expr).await,
+            DdlExpr::DropTable(_) => {
+                // TODO(LFC): Implement distributed drop table.
+                // Seems the whole "drop table through GRPC interface" feature is not implemented?
+                unimplemented!()
+            }
+        };
+
+        let value = DdlHistoryValue {
+            timestamp_millis: Utc::now().timestamp_millis(),
+            id: DdlHistoryValue::new_id(),
+            username: query_ctx.current_user().username().to_string(),
+            operation: operation.to_string(),
+            object_name,
+            statement,
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+        // Failing to record the history should not fail the DDL itself.
+        if let Err(e) = self.put_ddl_history(&value).await {
+            error!(e; "Failed to record DDL history: {:?}", value);
+        }
+        result
+    }
+
+    async fn put_ddl_history(&self, value: &DdlHistoryValue) -> Result<()> {
         let client = self
             .meta_client
             .store_client()
             .context(StartMetaClientSnafu)?;
 
         let request = PutRequest::default()
-            .with_key(key.to_string())
+            .with_key(value.key().to_string())
             .with_value(value.as_bytes().context(CatalogEntrySerdeSnafu)?);
         client.put(request.into()).await.context(RequestMetaSnafu)?;
+        Ok(())
+    }
+
+    /// Handles distributed catalog creation, the catalog is created with a default schema.
+    async fn handle_create_catalog(
+        &self,
+        stmt: CreateCatalog,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        ensure!(
+            query_ctx.current_user().is_admin(),
+            error::PermissionDeniedSnafu {
+                reason: "CREATE CATALOG requires the admin role",
+            }
+        );
+        let request = CreateCatalogRequest {
+            catalog: stmt.name.clone(),
+        };
+        let created = self
+            .catalog_manager
+            .create_catalog(request)
+            .await
+            .context(CatalogSnafu)?;
+        if !created {
+            ensure!(
+                stmt.if_not_exists,
+                error::CatalogExistsSnafu { name: stmt.name }
+            );
+            return Ok(Output::AffectedRows(0));
+        }
+
+        info!("Created catalog: {}", stmt.name);
+        Ok(Output::AffectedRows(1))
+    }
+
+    /// Handles distributed database creation
+    async fn handle_create_database(&self, expr: CreateDatabaseExpr) -> Result<Output> {
+        let catalog_name = catalog_or_default(&expr.catalog_name);
+        let _ = self
+            .catalog_manager
+            .catalog(catalog_name)
+            .context(CatalogSnafu)?
+            .context(CatalogNotFoundSnafu { catalog_name })?;
+        let key = SchemaKey {
+            catalog_name: catalog_name.to_string(),
+            schema_name: expr.database_name,
+        };
+        let value = SchemaValue {
+            default_table_options: expr.options,
+            metadata: HashMap::new(),
+        };
+
+        // Only puts the schema if it is absent, so creating an existing schema won't
+        // overwrite its options.
+        let created = self
+            .catalog_manager
+            .backend()
+            .compare_and_set(
+                key.to_string().as_bytes(),
+                &[],
+                &value.as_bytes().context(CatalogEntrySerdeSnafu)?,
+            )
+            .await
+            .context(CatalogSnafu)?
+            .is_ok();
+        if !created {
+            ensure!(
+                expr.create_if_not_exists,
+                error::SchemaExistsSnafu {
+                    name: &key.schema_name
+                }
+            );
+            return Ok(Output::AffectedRows(0));
+        }
 
         Ok(Output::AffectedRows(1))
     }
 
+    /// Handles distributed database altering, only setting metadata is supported for now.
+    async fn handle_alter_database(
+        &self,
+        stmt: AlterDatabase,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let alter_kind = match stmt.alter_operation() {
+            AlterDatabaseOperation::RenameDatabase { .. } => {
+                return error::NotSupportedSnafu {
+                    feat: "renaming database in distributed mode",
+                }
+                .fail();
+            }
+            operation @ AlterDatabaseOperation::SetMetadata { .. } => {
+                AlterDatabaseKind::SetMetadata {
+                    metadata: operation.metadata(),
+                }
+            }
+        };
+        let request = AlterSchemaRequest {
+            catalog: query_ctx.current_catalog(),
+            schema: stmt.name().to_string(),
+            alter_kind,
+        };
+        let _ = self
+            .catalog_manager
+            .alter_schema(request)
+            .await
+            .context(CatalogSnafu)?;
+
+        Ok(Output::AffectedRows(0))
+    }
+
     async fn handle_alter_table(&self, expr: AlterExpr) -> Result<Output> {
         let catalog_name = if expr.catalog_name.is_empty() {
             DEFAULT_CATALOG_NAME
@@ -284,7 +792,13 @@ impl DistInstance {
         let mut context = AlterContext::with_capacity(1);
         context.insert(expr);
 
-        table.alter(context, &request).await.context(TableSnafu)?;
+        let result = table.alter(context, &request).await.context(TableSnafu);
+        // The cached table keeps the table info before altering, which may be partially
+        // altered even on failure.
+        self.catalog_manager
+            .invalidate_table(&TableName::new(catalog_name, schema_name, table_name))
+            .await;
+        result?;
 
         Ok(Output::AffectedRows(0))
     }
@@ -293,6 +807,7 @@ impl DistInstance {
         &self,
         create_table: &CreateTableExpr,
         partitions: Option<Partitions>,
+        dry_run: bool,
     ) -> Result<RouteResponse> {
         let mut catalog_name = create_table.catalog_name.clone();
         if catalog_name.is_empty() {
@@ -308,6 +823,7 @@ impl DistInstance {
         let request = MetaCreateRequest {
             table_name,
             partitions,
+            dry_run,
         };
         self.meta_client
             .create_route(request)
@@ -365,6 +881,15 @@ impl DistInstance {
         request: InsertRequest,
         ctx: QueryContextRef,
     ) -> Result<Output> {
+        // Writes to different datanodes have unrelated sequences, no single sequence could
+        // be returned to fence on.
+        ensure!(
+            !request.wal_ack,
+            error::NotSupportedSnafu {
+                feat: "WAL acknowledged insertion in distributed mode",
+            }
+        );
+
         let catalog = &ctx.current_catalog();
         let schema = &ctx.current_schema();
         let table_name = &request.table_name;
@@ -381,6 +906,24 @@ impl DistInstance {
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    async fn handle_dist_delete(
+        &self,
+        request: DeleteRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let table_name = &request.table_name;
+        let table = self
+            .catalog_manager
+            .table(&ctx.current_catalog(), &ctx.current_schema(), table_name)
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu { table_name })?;
+
+        let request = common_grpc_expr::delete::to_table_delete_request(request)
+            .context(error::ToTableDeleteRequestSnafu)?;
+        let affected_rows = table.delete(request).await.context(TableSnafu)?;
+        Ok(Output::AffectedRows(affected_rows))
+    }
+
     #[cfg(test)]
     pub(crate) fn catalog_manager(&self) -> Arc<FrontendCatalogManager> {
         self.catalog_manager.clone()
@@ -392,7 +935,12 @@ impl SqlQueryHandler for DistInstance {
     type Error = error::Error;
 
     async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Vec<Result<Output>> {
-        self.handle_sql(query, query_ctx).await
+        REQUEST_DEADLINE
+            .scope(
+                query_ctx.deadline(),
+                self.handle_sql(query, query_ctx.clone()),
+            )
+            .await
     }
 
     async fn do_promql_query(
@@ -408,7 +956,9 @@ impl SqlQueryHandler for DistInstance {
         stmt: Statement,
         query_ctx: QueryContextRef,
     ) -> Result<Output> {
-        self.handle_statement(stmt, query_ctx).await
+        REQUEST_DEADLINE
+            .scope(query_ctx.deadline(), self.handle_statement(stmt, query_ctx))
+            .await
     }
 
     fn do_describe(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Option<Schema>> {
@@ -430,6 +980,27 @@ impl SqlQueryHandler for DistInstance {
     }
 }
 
+/// Returns the default catalog name if `catalog_name` is empty.
+fn catalog_or_default(catalog_name: &str) -> &str {
+    if catalog_name.is_empty() {
+        DEFAULT_CATALOG_NAME
+    } else {
+        catalog_name
+    }
+}
+
+/// Formats the full name of the table, empty catalog or schema names are
+/// treated as the default ones.
+fn full_table_name(catalog_name: &str, schema_name: &str, table_name: &str) -> String {
+    let catalog_name = catalog_or_default(catalog_name);
+    let schema_name = if schema_name.is_empty() {
+        DEFAULT_SCHEMA_NAME
+    } else {
+        schema_name
+    };
+    format_full_table_name(catalog_name, schema_name, table_name)
+}
+
 fn create_table_global_value(
     create_table: &CreateTableExpr,
     table_route: &TableRoute,
@@ -508,6 +1079,8 @@ fn create_table_global_value(
         engine_options: HashMap::new(),
         options: HashMap::new(),
         created_on: DateTime::default(),
+        read_only: false,
+        column_statistics: None,
     };
 
     let desc = if create_table.desc.is_empty() {
@@ -620,9 +1193,12 @@ fn find_partition_columns(
 
 #[cfg(test)]
 mod test {
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use datatypes::prelude::{ScalarVector, Vector};
+    use datatypes::vectors::StringVector;
     use itertools::Itertools;
     use servers::query_handler::sql::SqlQueryHandlerRef;
-    use session::context::QueryContext;
+    use session::context::{QueryContext, UserInfo};
     use sql::dialect::GenericDialect;
     use sql::parser::ParserContext;
     use sql::statements::statement::Statement;
@@ -657,6 +1233,18 @@ PARTITION BY RANGE COLUMNS (b, a) (
 ENGINE=mito",
                 r#"[{"column_list":"b,a","value_list":"{\"Value\":{\"String\":\"hz\"}},{\"Value\":{\"Int32\":10}}"},{"column_list":"b,a","value_list":"{\"Value\":{\"String\":\"sh\"}},{\"Value\":{\"Int32\":20}}"},{"column_list":"b,a","value_list":"\"MaxValue\",\"MaxValue\""}]"#,
             ),
+            (
+                // Partitioned by tenant while rows in a region are still ordered by
+                // device and time.
+                r"
+CREATE TABLE metrics ( tenant STRING, device STRING, v DOUBLE, ts TIMESTAMP, TIME INDEX (ts), PRIMARY KEY (device) )
+PARTITION BY RANGE COLUMNS (tenant) (
+  PARTITION r0 VALUES LESS THAN ('m'),
+  PARTITION r1 VALUES LESS THAN (MAXVALUE),
+)
+ENGINE=mito",
+                r#"[{"column_list":"tenant","value_list":"{\"Value\":{\"String\":\"m\"}}"},{"column_list":"tenant","value_list":"\"MaxValue\""}]"#,
+            ),
         ];
         for (sql, expected) in cases {
             let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
@@ -726,6 +1314,49 @@ ENGINE=mito",
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_catalog() {
+        let instance = crate::tests::create_distributed_instance("test_create_catalog").await;
+        let dist_instance = &instance.dist_instance;
+        let catalog_manager = dist_instance.catalog_manager();
+        assert!(catalog_manager.catalog("tenant1").unwrap().is_none());
+
+        let output = dist_instance
+            .handle_sql("create catalog tenant1", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        // The catalog is created with its default schema.
+        assert!(catalog_manager
+            .schema("tenant1", DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .is_some());
+
+        let err = dist_instance
+            .handle_sql("create catalog tenant1", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        let output = dist_instance
+            .handle_sql("create catalog if not exists tenant1", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        let err = dist_instance
+            .handle_sql("create catalog tenant2", query_ctx)
+            .await
+            .remove(0)
+            .unwrap_err();
+        assert_eq!(StatusCode::AccessDenied, err.status_code());
+        assert!(catalog_manager.catalog("tenant2").unwrap().is_none());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_show_tables() {
         let instance = crate::tests::create_distributed_instance("test_show_tables").await;
@@ -785,4 +1416,254 @@ ENGINE=mito",
             assert_show_tables(StandaloneSqlQueryHandler::arc(x.clone())).await
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_if_not_exists() {
+        let instance = crate::tests::create_distributed_instance("test_create_if_not_exists").await;
+        let dist_instance = &instance.dist_instance;
+
+        let execute = |sql: &'static str| async move {
+            dist_instance
+                .handle_sql(sql, QueryContext::arc())
+                .await
+                .remove(0)
+        };
+
+        let create_database = "create database if not exists test_create_if_not_exists";
+        let create_table = "
+            CREATE TABLE IF NOT EXISTS greptime.test_create_if_not_exists.demo (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            ENGINE=mito";
+        let add_column = "
+            ALTER TABLE greptime.test_create_if_not_exists.demo
+            ADD COLUMN IF NOT EXISTS host STRING";
+        assert!(matches!(
+            execute(create_database).await.unwrap(),
+            Output::AffectedRows(1)
+        ));
+        for _ in 0..2 {
+            execute(create_table).await.unwrap();
+            execute(add_column).await.unwrap();
+        }
+        assert!(matches!(
+            execute(create_database).await.unwrap(),
+            Output::AffectedRows(0)
+        ));
+
+        assert!(execute("create database test_create_if_not_exists")
+            .await
+            .is_err());
+        assert!(execute(
+            "CREATE TABLE greptime.test_create_if_not_exists.demo (ts BIGINT, TIME INDEX (ts))"
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_explain_ddl_alter() {
+        let instance = crate::tests::create_distributed_instance("test_explain_ddl_alter").await;
+        let dist_instance = &instance.dist_instance;
+
+        let execute = |sql: &'static str| async move {
+            dist_instance
+                .handle_sql(sql, QueryContext::arc())
+                .await
+                .remove(0)
+        };
+
+        execute(
+            "
+            CREATE TABLE demo (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito",
+        )
+        .await
+        .unwrap();
+
+        let output = execute("EXPLAIN DDL ALTER TABLE demo ADD COLUMN host STRING")
+            .await
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let pretty = batches.pretty_print().unwrap();
+        assert!(pretty.contains("ALTER TABLE greptime.public.demo add columns [host]"));
+        assert!(pretty.contains("Region 0"));
+        assert!(pretty.contains("Region 1"));
+        assert!(pretty.contains("Affected rows"));
+
+        // Nothing is altered.
+        assert!(execute("SELECT host FROM demo").await.is_err());
+        // The alteration is validated.
+        assert!(execute("EXPLAIN DDL ALTER TABLE demo DROP COLUMN ts")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_explain_format() {
+        let instance = crate::tests::create_distributed_instance("test_explain_format").await;
+        let dist_instance = &instance.dist_instance;
+
+        let sql = "
+            CREATE TABLE dist_numbers (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (20),
+                PARTITION r2 VALUES LESS THAN (50),
+                PARTITION r3 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito";
+        dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+
+        let sql = "EXPLAIN (FORMAT substrait) SELECT n FROM dist_numbers WHERE n < 15";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let batches = batches.take();
+        let fragments = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .unwrap();
+        let plans = batches[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .unwrap();
+        assert_eq!(Some("Frontend"), fragments.get_data(0));
+        // Only the datanodes of partitions r0 and r1 are read.
+        let datanode_fragments = fragments.len() - 1;
+        assert!(datanode_fragments > 0);
+        for i in 1..fragments.len() {
+            assert!(fragments.get_data(i).unwrap().starts_with("Datanode"));
+            assert!(plans.get_data(i).unwrap().contains("FilterRel"));
+        }
+
+        let sql = "EXPLAIN (FORMAT json) SELECT n FROM dist_numbers WHERE n < 15";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let batches = batches.take();
+        let plan = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringVector>()
+            .unwrap()
+            .get_data(0)
+            .unwrap();
+        let plan: serde_json::Value = serde_json::from_str(plan).unwrap();
+        assert!(plan["frontend"].is_string());
+        let fragments = plan["fragments"].as_array().unwrap();
+        assert_eq!(datanode_fragments, fragments.len());
+        assert!(fragments[0]["plan"].as_str().unwrap().contains("Filter"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_region_peers() {
+        let instance = crate::tests::create_distributed_instance("test_region_peers").await;
+        let dist_instance = &instance.dist_instance;
+
+        let sql = "
+            CREATE TABLE dist_hosts (
+                ts BIGINT,
+                host STRING,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (host) (
+                PARTITION r0 VALUES LESS THAN ('m'),
+                PARTITION r1 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito";
+        dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+
+        let sql = "SELECT table_name, region_id, partition, is_leader, approximate_bytes \
+            FROM information_schema.region_peers WHERE table_name = 'dist_hosts' \
+            ORDER BY region_id";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let expected = "\
++------------+-----------+-----------------------------+-----------+-------------------+
+| table_name | region_id | partition                   | is_leader | approximate_bytes |
++------------+-----------+-----------------------------+-----------+-------------------+
+| dist_hosts | 0         | VALUES LESS THAN ('m')      | true      |                   |
+| dist_hosts | 1         | VALUES LESS THAN (MAXVALUE) | true      |                   |
++------------+-----------+-----------------------------+-----------+-------------------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+
+        let sql = "SELECT DISTINCT peer_id FROM information_schema.region_peers";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let peer_ids = batches.take()[0].num_rows();
+        assert!(peer_ids > 0 && peer_ids <= instance.datanodes.len());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ddl_history() {
+        let instance = crate::tests::create_distributed_instance("test_ddl_history").await;
+        let dist_instance = &instance.dist_instance;
+
+        for sql in [
+            "CREATE TABLE dist_ddl (ts BIGINT, host STRING, TIME INDEX (ts),) ENGINE=mito",
+            "ALTER TABLE dist_ddl ADD COLUMN cpu DOUBLE",
+        ] {
+            dist_instance
+                .handle_sql(sql, QueryContext::arc())
+                .await
+                .remove(0)
+                .unwrap();
+        }
+
+        let sql = "SELECT operation, statement FROM system.ddl_history \
+            WHERE object_name = 'greptime.public.dist_ddl' ORDER BY operation";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        let expected = "\
++--------------+-----------------------------------------------------------------------------+
+| operation    | statement                                                                   |
++--------------+-----------------------------------------------------------------------------+
+| ALTER TABLE  | ALTER TABLE dist_ddl ADD COLUMN cpu DOUBLE                                  |
+| CREATE TABLE | CREATE TABLE dist_ddl (ts BIGINT, host STRING, TIME INDEX (ts)) ENGINE=mito |
++--------------+-----------------------------------------------------------------------------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
 }
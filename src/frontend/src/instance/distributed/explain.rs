@@ -0,0 +1,150 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `EXPLAIN (FORMAT substrait|json) <query>` of distributed queries, which shows the plan
+//! executed by the frontend and the fragments shipped to datanodes.
+
+use std::sync::Arc;
+
+use common_query::physical_plan::PhysicalPlanAdapter;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use datafusion::physical_plan::displayable;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{StringVector, VectorRef};
+use query::parser::QueryStatement;
+use serde_json::json;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::statements::explain::{Explain, ExplainFormat};
+use sql::statements::statement::Statement;
+use substrait::DFLogicalSubstraitConvertor;
+
+use crate::error::{self, Result};
+use crate::instance::distributed::DistInstance;
+use crate::table::{collect_plan_fragments, PlanFragment};
+
+const FRAGMENT_COLUMN: &str = "Fragment";
+const PLAN_COLUMN: &str = "Plan";
+const FRONTEND_FRAGMENT: &str = "Frontend";
+
+impl DistInstance {
+    /// Explains how the query is split into the plan executed by the frontend and the
+    /// fragments shipped to datanodes, without executing the query.
+    pub(crate) async fn explain_distributed(
+        &self,
+        explain: Explain,
+        format: ExplainFormat,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let query = explain.query().context(error::NotSupportedSnafu {
+            feat: "EXPLAIN (FORMAT ...) of statements other than queries",
+        })?;
+        let plan = self
+            .query_engine
+            .statement_to_plan(
+                QueryStatement::Sql(Statement::Query(Box::new(query))),
+                query_ctx,
+            )
+            .context(error::ExecuteStatementSnafu)?;
+        let physical_plan = self
+            .query_engine
+            .to_physical_plan(&plan)
+            .await
+            .context(error::ExecuteStatementSnafu)?;
+        let df_plan = physical_plan
+            .as_any()
+            .downcast_ref::<PhysicalPlanAdapter>()
+            .context(error::NotSupportedSnafu {
+                feat: "EXPLAIN (FORMAT ...) of plans not created by DataFusion",
+            })?
+            .df_plan();
+
+        let frontend_plan = displayable(df_plan.as_ref()).indent().to_string();
+        let fragments = collect_plan_fragments(&df_plan)?;
+        match format {
+            ExplainFormat::Substrait => substrait_output(frontend_plan, fragments),
+            ExplainFormat::Json => json_output(frontend_plan, fragments),
+        }
+    }
+}
+
+fn datanode_fragment_name(fragment: &PlanFragment) -> String {
+    format!(
+        "Datanode {} ({})",
+        fragment.datanode.id, fragment.datanode.addr
+    )
+}
+
+/// One row for each fragment, the fragments of datanodes are shown as substrait plans.
+fn substrait_output(frontend_plan: String, fragments: Vec<PlanFragment>) -> Result<Output> {
+    let mut names = Vec::with_capacity(fragments.len() + 1);
+    let mut plans = Vec::with_capacity(fragments.len() + 1);
+    names.push(FRONTEND_FRAGMENT.to_string());
+    plans.push(frontend_plan);
+    for fragment in fragments {
+        names.push(datanode_fragment_name(&fragment));
+        plans.push(
+            DFLogicalSubstraitConvertor
+                .encode_to_text(fragment.plan)
+                .context(error::EncodeSubstraitLogicalPlanSnafu)?,
+        );
+    }
+
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new(FRAGMENT_COLUMN, ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(PLAN_COLUMN, ConcreteDataType::string_datatype(), false),
+    ]));
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(names)),
+        Arc::new(StringVector::from(plans)),
+    ];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// A single row of the whole distributed plan in JSON.
+fn json_output(frontend_plan: String, fragments: Vec<PlanFragment>) -> Result<Output> {
+    let fragments = fragments
+        .iter()
+        .map(|fragment| {
+            json!({
+                "name": datanode_fragment_name(fragment),
+                "datanode": {
+                    "id": fragment.datanode.id,
+                    "addr": fragment.datanode.addr,
+                },
+                "plan": fragment.plan.display_indent().to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let plan = json!({
+        "frontend": frontend_plan,
+        "fragments": fragments,
+    });
+
+    let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+        PLAN_COLUMN,
+        ConcreteDataType::string_datatype(),
+        false,
+    )]));
+    let columns: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec![
+        serde_json::to_string_pretty(&plan).unwrap(),
+    ]))];
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .context(error::CreateRecordBatchesSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
@@ -12,7 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use async_trait::async_trait;
 use common_query::Output;
@@ -20,6 +19,7 @@ use servers::query_handler::grpc::GrpcQueryHandler;
 use session::context::QueryContextRef;
 use snafu::OptionExt;
 
+use crate::datanode::REQUEST_DEADLINE;
 use crate::error::{self, Result};
 use crate::instance::distributed::DistInstance;
 
@@ -28,8 +28,17 @@ impl GrpcQueryHandler for DistInstance {
     type Error = error::Error;
 
     async fn do_query(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
+        REQUEST_DEADLINE
+            .scope(ctx.deadline(), self.handle_request(request, ctx))
+            .await
+    }
+}
+
+impl DistInstance {
+    async fn handle_request(&self, request: Request, ctx: QueryContextRef) -> Result<Output> {
         match request {
             Request::Insert(request) => self.handle_dist_insert(request, ctx).await,
+            Request::Delete(request) => self.handle_dist_delete(request, ctx).await,
             Request::Query(_) => {
                 unreachable!("Query should have been handled directly in Frontend Instance!")
             }
@@ -37,21 +46,29 @@ impl GrpcQueryHandler for DistInstance {
                 let expr = request.expr.context(error::IncompleteGrpcResultSnafu {
                     err_msg: "Missing 'expr' in DDL request",
                 })?;
-                match expr {
-                    DdlExpr::CreateDatabase(expr) => self.handle_create_database(expr).await,
-                    DdlExpr::CreateTable(mut expr) => {
-                        // TODO(LFC): Support creating distributed table through GRPC interface.
-                        // Currently only SQL supports it; how to design the fields in CreateTableExpr?
-                        self.create_table(&mut expr, None).await
-                    }
-                    DdlExpr::Alter(expr) => self.handle_alter_table(expr).await,
-                    DdlExpr::DropTable(_) => {
-                        // TODO(LFC): Implement distributed drop table.
-                        // Seems the whole "drop table through GRPC interface" feature is not implemented?
-                        unimplemented!()
+                self.handle_ddl(expr, None, None, ctx).await
+            }
+            Request::Ddls(requests) => {
+                let mut affected_rows = 0;
+                for request in requests.requests {
+                    let expr = request.expr.context(error::IncompleteGrpcResultSnafu {
+                        err_msg: "Missing 'expr' in DDL request",
+                    })?;
+                    match self.handle_ddl(expr, None, None, ctx.clone()).await? {
+                        Output::AffectedRows(rows) => affected_rows += rows,
+                        _ => unreachable!("DDL should not yield output other than AffectedRows"),
                     }
                 }
+                Ok(Output::AffectedRows(affected_rows))
+            }
+            Request::Fence(_) => error::NotSupportedSnafu {
+                feat: "fence writes in distributed mode",
+            }
+            .fail(),
+            Request::Snapshot(_) => error::NotSupportedSnafu {
+                feat: "take snapshots of tables in distributed mode",
             }
+            .fail(),
         }
     }
 }
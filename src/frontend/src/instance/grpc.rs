@@ -48,7 +48,7 @@ impl GrpcQueryHandler for Instance {
                         );
                         result.remove(0)?
                     }
-                    Query::LogicalPlan(_) => {
+                    Query::LogicalPlan(_) | Query::SnapshotLogicalPlan(_) => {
                         return error::NotSupportedSnafu {
                             feat: "Execute LogicalPlan in Frontend",
                         }
@@ -56,9 +56,12 @@ impl GrpcQueryHandler for Instance {
                     }
                 }
             }
-            Request::Ddl(request) => {
-                let query = Request::Ddl(request);
-                GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await?
+            Request::Ddl(_)
+            | Request::Ddls(_)
+            | Request::Fence(_)
+            | Request::Snapshot(_)
+            | Request::Delete(_) => {
+                GrpcQueryHandler::do_query(&*self.grpc_query_handler, request, ctx).await?
             }
         };
         Ok(output)
@@ -109,6 +112,7 @@ mod test {
             expr: Some(DdlExpr::CreateDatabase(CreateDatabaseExpr {
                 database_name: "database_created_through_grpc".to_string(),
                 create_if_not_exists: true,
+                ..Default::default()
             })),
         });
         let output = GrpcQueryHandler::do_query(instance.as_ref(), query, QueryContext::arc())
@@ -127,12 +131,14 @@ mod test {
                         datatype: ColumnDataType::String as _,
                         is_nullable: true,
                         default_constraint: vec![],
+                        comment: String::new(),
                     },
                     ColumnDef {
                         name: "ts".to_string(),
                         datatype: ColumnDataType::TimestampMillisecond as _,
                         is_nullable: false,
                         default_constraint: vec![],
+                        comment: String::new(),
                     },
                 ],
                 time_index: "ts".to_string(),
@@ -156,8 +162,10 @@ mod test {
                             datatype: ColumnDataType::Int32 as _,
                             is_nullable: true,
                             default_constraint: vec![],
+                            comment: String::new(),
                         }),
                         is_key: false,
+                        add_if_not_exists: false,
                     }],
                 })),
             })),
@@ -224,6 +232,7 @@ CREATE TABLE {table_name} (
         verify_data_distribution(
             &instance,
             table_name,
+            "ts, a",
             HashMap::from([
                 (
                     0u32,
@@ -275,6 +284,7 @@ CREATE TABLE {table_name} (
         verify_data_distribution(
             &instance,
             "auto_created_table",
+            "ts, a",
             HashMap::from([(
                 0u32,
                 "\
@@ -293,6 +303,76 @@ CREATE TABLE {table_name} (
         .await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_distributed_insert_partitioned_by_non_primary_key() {
+        common_telemetry::init_default_ut_logging();
+
+        let instance = tests::create_distributed_instance(
+            "test_distributed_insert_partitioned_by_non_primary_key",
+        )
+        .await;
+        let frontend = &instance.frontend;
+
+        // Partitioned by tenant while rows in a region are ordered by device and time.
+        let table_name = "tenant_metrics";
+        let sql = format!(
+            r"
+CREATE TABLE {table_name} (
+    tenant STRING,
+    device STRING,
+    v DOUBLE,
+    ts TIMESTAMP,
+    TIME INDEX (ts),
+    PRIMARY KEY (device)
+) PARTITION BY RANGE COLUMNS(tenant) (
+    PARTITION r0 VALUES LESS THAN ('m'),
+    PARTITION r1 VALUES LESS THAN (MAXVALUE),
+)"
+        );
+        create_table(frontend, sql).await;
+
+        let query = Request::Query(QueryRequest {
+            query: Some(Query::Sql(format!(
+                "INSERT INTO {table_name}(tenant, device, v, ts) VALUES \
+                 ('alice', 'd2', 1.0, 1000), ('zoe', 'd1', 2.0, 1000), \
+                 ('bob', 'd1', 3.0, 2000), ('tom', 'd2', 4.0, 2000)"
+            ))),
+        });
+        let output = GrpcQueryHandler::do_query(frontend.as_ref(), query, QueryContext::arc())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(4)));
+
+        verify_data_distribution(
+            &instance,
+            table_name,
+            "device, tenant",
+            HashMap::from([
+                (
+                    0u32,
+                    "\
++--------+--------+
+| device | tenant |
++--------+--------+
+| d1     | bob    |
+| d2     | alice  |
++--------+--------+",
+                ),
+                (
+                    1u32,
+                    "\
++--------+--------+
+| device | tenant |
++--------+--------+
+| d1     | zoe    |
+| d2     | tom    |
++--------+--------+",
+                ),
+            ]),
+        )
+        .await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_standalone_insert_and_query() {
         common_telemetry::init_default_ut_logging();
@@ -333,6 +413,7 @@ CREATE TABLE {table_name} (
                     null_mask: vec![4],
                     semantic_type: SemanticType::Field as i32,
                     datatype: ColumnDataType::Int32 as i32,
+                    compressed_values: vec![],
                 },
                 Column {
                     column_name: "ts".to_string(),
@@ -390,9 +471,11 @@ CREATE TABLE {table_name} (
         assert_eq!(recordbatches.pretty_print().unwrap(), expected);
     }
 
+    /// Checks the rows of `columns` in each region of the table, ordered by the columns.
     async fn verify_data_distribution(
         instance: &MockDistributedInstance,
         table_name: &str,
+        columns: &str,
         expected_distribution: HashMap<u32, &str>,
     ) {
         let table = instance
@@ -422,7 +505,7 @@ CREATE TABLE {table_name} (
             let dn = instance.datanodes.get(dn).unwrap();
             let output = dn
                 .execute_sql(
-                    &format!("SELECT ts, a FROM {table_name} ORDER BY ts"),
+                    &format!("SELECT {columns} FROM {table_name} ORDER BY {columns}"),
                     QueryContext::arc(),
                 )
                 .await
@@ -449,6 +532,7 @@ CREATE TABLE {table_name} (
                     null_mask: vec![2],
                     semantic_type: SemanticType::Field as i32,
                     datatype: ColumnDataType::Int32 as i32,
+                    compressed_values: vec![],
                 },
                 Column {
                     column_name: "ts".to_string(),
@@ -484,6 +568,7 @@ CREATE TABLE {table_name} (
                     null_mask: vec![2],
                     semantic_type: SemanticType::Field as i32,
                     datatype: ColumnDataType::String as i32,
+                    compressed_values: vec![],
                 },
                 Column {
                     column_name: "ts".to_string(),
@@ -14,8 +14,9 @@
 
 use async_trait::async_trait;
 use common_error::prelude::BoxedError;
+use common_query::Output;
 use servers::influxdb::InfluxdbRequest;
-use servers::query_handler::InfluxdbLineProtocolHandler;
+use servers::query_handler::{InfluxdbLineProtocolHandler, PartialWriteOutput, WriteFailure};
 use session::context::QueryContextRef;
 use snafu::ResultExt;
 
@@ -35,6 +36,32 @@ impl InfluxdbLineProtocolHandler for Instance {
             .context(servers::error::ExecuteGrpcQuerySnafu)?;
         Ok(())
     }
+
+    async fn exec_partial(
+        &self,
+        request: &InfluxdbRequest,
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<PartialWriteOutput> {
+        let partial = request.to_partial_inserts();
+        let mut output = PartialWriteOutput {
+            success: 0,
+            failures: partial.failures,
+        };
+        for insert in partial.inserts {
+            match self.handle_insert(insert.request, ctx.clone()).await {
+                Ok(Output::AffectedRows(rows)) => output.success += rows,
+                Ok(_) => unreachable!("Insert should not yield output other than AffectedRows"),
+                Err(e) => output.failures.extend(
+                    insert
+                        .line_indices
+                        .into_iter()
+                        .map(|index| WriteFailure::new(index, &e)),
+                ),
+            }
+        }
+        output.failures.sort_by_key(|failure| failure.index);
+        Ok(output)
+    }
 }
 
 #[cfg(test)]
@@ -67,6 +94,55 @@ mod test {
         test_put_influxdb_lines(instance).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_standalone_put_influxdb_lines_partial() {
+        let standalone =
+            tests::create_standalone_instance("test_standalone_put_influxdb_lines_partial").await;
+        let instance = &standalone.instance;
+
+        // Creates the table with a float cpu column first.
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: "monitor2,host=host1 cpu=66.6 1663840496100023100".to_string(),
+        };
+        instance.exec(&request, QueryContext::arc()).await.unwrap();
+
+        let lines = r"monitor1,host=host1 cpu=66.6 1663840496100023100
+monitor1,host=host2 cpu=
+monitor2,host=host2 cpu=true 1663840496400340001";
+        let request = InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+        let output = instance
+            .exec_partial(&request, QueryContext::arc())
+            .await
+            .unwrap();
+        assert_eq!(1, output.success);
+        let failures = output
+            .failures
+            .iter()
+            .map(|failure| failure.index)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 2], failures);
+
+        let mut output = instance
+            .do_query("SELECT host, cpu FROM monitor1", QueryContext::arc())
+            .await;
+        let output = output.remove(0).unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        assert_eq!(
+            recordbatches.pretty_print().unwrap(),
+            "\
++-------+------+
+| host  | cpu  |
++-------+------+
+| host1 | 66.6 |
++-------+------+"
+        );
+    }
+
     async fn test_put_influxdb_lines(instance: &Arc<Instance>) {
         let lines = r"
 monitor1,host=host1 cpu=66.6,memory=1024 1663840496100023100
@@ -0,0 +1,132 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persists records of the jobs run by a frontend in distributed mode to meta, so operators
+//! could still track the jobs after the frontend restarts.
+
+use async_trait::async_trait;
+use catalog::remote::{Kv, KvBackendRef};
+use common_procedure::{KeyValueStream, StateStore};
+use futures::StreamExt;
+
+/// Prefix of the keys of job records in meta.
+const JOB_KEY_PREFIX: &str = "__frontend_job";
+
+/// A [StateStore] over the kv backend of meta.
+pub(crate) struct KvStateStore {
+    backend: KvBackendRef,
+    /// Prefix of all keys put by this store.
+    prefix: String,
+}
+
+impl KvStateStore {
+    /// Returns a store whose keys are placed under the `addr` of the frontend, so frontends
+    /// don't recover the jobs of each other.
+    pub(crate) fn new(backend: KvBackendRef, addr: &str) -> Self {
+        Self {
+            backend,
+            prefix: format!("{JOB_KEY_PREFIX}/{addr}/"),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key.trim_start_matches('/'))
+    }
+}
+
+#[async_trait]
+impl StateStore for KvStateStore {
+    async fn put(&self, key: &str, value: Vec<u8>) -> common_procedure::Result<()> {
+        self.backend
+            .set(self.key(key).as_bytes(), &value)
+            .await
+            .map_err(common_procedure::Error::external)
+    }
+
+    async fn walk_top_down(&self, path: &str) -> common_procedure::Result<KeyValueStream> {
+        let prefix = self.key(path);
+        let mut iter = self.backend.range(prefix.as_bytes());
+        let mut key_values = Vec::new();
+        while let Some(kv) = iter.next().await {
+            let Kv(key, value) = kv.map_err(common_procedure::Error::external)?;
+            let key = String::from_utf8_lossy(&key[self.prefix.len()..]).to_string();
+            key_values.push(Ok((key, value)));
+        }
+        Ok(Box::pin(futures::stream::iter(key_values)))
+    }
+
+    async fn delete(&self, keys: &[String]) -> common_procedure::Result<()> {
+        for key in keys {
+            self.backend
+                .delete(self.key(key).as_bytes())
+                .await
+                .map_err(common_procedure::Error::external)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use catalog::remote::MetaKvBackend;
+    use common_procedure::job::{JobManager, JobStatus};
+    use meta_client::client::MetaClientBuilder;
+    use meta_srv::mocks::{self, MockInfo};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recover_jobs_from_meta() {
+        let MockInfo {
+            server_addr,
+            channel_manager,
+        } = mocks::mock_with_memstore().await;
+        let mut meta_client = MetaClientBuilder::new(1000, 0)
+            .enable_store()
+            .channel_manager(channel_manager)
+            .build();
+        meta_client.start(&[&server_addr]).await.unwrap();
+        let backend: KvBackendRef = Arc::new(MetaKvBackend {
+            client: Arc::new(meta_client),
+        });
+        let manager = Arc::new(JobManager::with_state_store(Arc::new(KvStateStore::new(
+            backend.clone(),
+            "127.0.0.1:4000",
+        ))));
+        let job = manager
+            .submit("test", "never finishes", |_ctx| async {
+                futures::future::pending::<()>().await;
+                Ok(String::new())
+            })
+            .await
+            .unwrap();
+
+        // Jobs of other frontends are not recovered.
+        let other = JobManager::with_state_store(Arc::new(KvStateStore::new(
+            backend.clone(),
+            "127.0.0.2:4000",
+        )));
+        other.recover().await.unwrap();
+        assert!(other.list_jobs().is_empty());
+
+        let restarted =
+            JobManager::with_state_store(Arc::new(KvStateStore::new(backend, "127.0.0.1:4000")));
+        restarted.recover().await.unwrap();
+        let recovered = restarted.job(job.id).unwrap();
+        assert_eq!(JobStatus::Failed, recovered.status);
+        assert_eq!("never finishes", recovered.description);
+    }
+}
@@ -16,21 +16,26 @@
 
 pub type Plugins = anymap::Map<dyn core::any::Any + Send + Sync>;
 
+pub mod alert;
 mod catalog;
-mod datanode;
+pub mod datanode;
 pub mod error;
 mod expr_factory;
 pub mod frontend;
 pub mod grpc;
+mod heartbeat;
 pub mod influxdb;
 pub mod instance;
+mod job;
 pub mod mysql;
 pub mod opentsdb;
 pub mod postgres;
+pub mod process;
 pub mod prometheus;
 pub mod promql;
 mod server;
 mod sql;
 mod table;
+pub mod table_template;
 #[cfg(test)]
 mod tests;
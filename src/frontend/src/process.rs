@@ -0,0 +1,415 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Running queries of the frontend, which are listed by `SHOW PROCESSLIST` and
+//! could be cancelled by `KILL QUERY <id>`.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::error::{
+    ExternalSnafu as RecordBatchExternalSnafu, Result as RecordBatchResult,
+};
+use common_recordbatch::{
+    RecordBatch, RecordBatchStream, RecordBatches, SendableRecordBatchStream,
+};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, UInt64Vector, VectorRef};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
+use futures::Stream;
+use session::context::{QueryContextRef, UserInfo};
+use snafu::{ensure, IntoError, ResultExt};
+use sql::statements::show::ShowProcesslist;
+
+use crate::error::{self, Result};
+
+/// Queries longer than this are truncated unless `SHOW FULL PROCESSLIST` is used, the
+/// same as MySQL.
+const TRUNCATED_QUERY_LEN: usize = 100;
+
+pub type ProcessManagerRef = Arc<ProcessManager>;
+
+/// Registry of the running queries.
+#[derive(Default)]
+pub struct ProcessManager {
+    next_id: AtomicU64,
+    processes: Mutex<HashMap<u64, Process>>,
+}
+
+/// Information of a running query.
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub id: u64,
+    pub user: String,
+    pub database: String,
+    pub protocol: String,
+    pub query: String,
+    pub start: Instant,
+}
+
+struct Process {
+    info: ProcessInfo,
+    /// Handle to abort the current execution phase of the query, which is either the
+    /// execution of the statement or the polling of its output stream.
+    abort_handle: Option<AbortHandle>,
+    killed: bool,
+}
+
+impl ProcessManager {
+    /// Registers a running `query`, the query is deregistered once the returned guard
+    /// (and the output stream it is moved into) is dropped.
+    pub fn register(self: &Arc<Self>, query: &str, query_ctx: &QueryContextRef) -> ProcessGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let info = ProcessInfo {
+            id,
+            user: query_ctx.current_user().username().to_string(),
+            database: query_ctx.current_schema(),
+            protocol: query_ctx
+                .channel()
+                .map(|channel| channel.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            query: query.to_string(),
+            start: Instant::now(),
+        };
+        let _ = self.processes.lock().unwrap().insert(
+            id,
+            Process {
+                info,
+                abort_handle: None,
+                killed: false,
+            },
+        );
+        ProcessGuard {
+            id,
+            manager: self.clone(),
+        }
+    }
+
+    /// Returns the running queries ordered by their ids.
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        let mut processes = self
+            .processes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|process| process.info.clone())
+            .collect::<Vec<_>>();
+        processes.sort_unstable_by_key(|info| info.id);
+        processes
+    }
+
+    /// Cancels the running query `id` on behalf of the `user`, returns error if there is
+    /// no such query. Users could only kill their own queries unless they are admins.
+    pub fn kill(&self, id: u64, user: &UserInfo) -> Result<()> {
+        let mut processes = self.processes.lock().unwrap();
+        let process = processes
+            .get_mut(&id)
+            .ok_or_else(|| error::ProcessNotFoundSnafu { id }.build())?;
+        ensure!(
+            user.is_admin() || process.info.user == user.username(),
+            error::KillQueryDeniedSnafu {
+                user: user.username(),
+                id,
+            }
+        );
+        process.killed = true;
+        if let Some(handle) = &process.abort_handle {
+            handle.abort();
+        }
+        Ok(())
+    }
+
+    /// Lists the running queries as the output of `SHOW [FULL] PROCESSLIST`.
+    pub fn show_processlist(&self, stmt: &ShowProcesslist) -> Result<Output> {
+        let processes = self.processes();
+        let now = Instant::now();
+        let query = |info: &ProcessInfo| {
+            if stmt.full {
+                info.query.clone()
+            } else {
+                info.query.chars().take(TRUNCATED_QUERY_LEN).collect()
+            }
+        };
+
+        let strings = |f: fn(&ProcessInfo) -> &str| {
+            Arc::new(StringVector::from(
+                processes.iter().map(f).collect::<Vec<_>>(),
+            )) as VectorRef
+        };
+        let columns = vec![
+            Arc::new(UInt64Vector::from_values(
+                processes.iter().map(|info| info.id),
+            )) as _,
+            strings(|info| info.user.as_str()),
+            strings(|info| info.database.as_str()),
+            strings(|info| info.protocol.as_str()),
+            Arc::new(UInt64Vector::from_values(
+                processes
+                    .iter()
+                    .map(|info| now.duration_since(info.start).as_secs()),
+            )) as _,
+            Arc::new(StringVector::from(
+                processes.iter().map(query).collect::<Vec<String>>(),
+            )) as _,
+        ];
+        let records = RecordBatches::try_from_columns(processlist_schema(), columns)
+            .context(error::CreateRecordBatchesSnafu)?;
+        Ok(Output::RecordBatches(records))
+    }
+
+    /// Installs a new abort handle for the query `id` and returns its registration,
+    /// the registration is aborted at once if the query has been killed.
+    fn abort_registration(&self, id: u64) -> AbortRegistration {
+        let (handle, registration) = AbortHandle::new_pair();
+        if let Some(process) = self.processes.lock().unwrap().get_mut(&id) {
+            if process.killed {
+                handle.abort();
+            }
+            process.abort_handle = Some(handle);
+        }
+        registration
+    }
+}
+
+fn processlist_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new("Id", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("User", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Db", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Protocol", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Time", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("Info", ConcreteDataType::string_datatype(), false),
+    ]))
+}
+
+/// Keeps a query registered in the [ProcessManager] while it is alive.
+pub struct ProcessGuard {
+    id: u64,
+    manager: ProcessManagerRef,
+}
+
+impl ProcessGuard {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Runs the query `future` so that it could be killed. If the query outputs a
+    /// stream, the stream could still be killed until it is drained or dropped.
+    pub async fn run<F>(self, future: F) -> Result<Output>
+    where
+        F: Future<Output = Result<Output>>,
+    {
+        let registration = self.manager.abort_registration(self.id);
+        match Abortable::new(future, registration).await {
+            Ok(result) => result.map(|output| self.track_output(output)),
+            Err(_) => error::QueryKilledSnafu { id: self.id }.fail(),
+        }
+    }
+
+    fn track_output(self, output: Output) -> Output {
+        match output {
+            Output::Stream(stream) => {
+                let registration = self.manager.abort_registration(self.id);
+                Output::Stream(Box::pin(KillableRecordBatchStream {
+                    schema: stream.schema(),
+                    stream: Abortable::new(stream, registration),
+                    guard: self,
+                    done: false,
+                }))
+            }
+            output => output,
+        }
+    }
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        let _ = self.manager.processes.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Output stream of a query that ends with an error once the query is killed.
+struct KillableRecordBatchStream {
+    schema: SchemaRef,
+    stream: Abortable<SendableRecordBatchStream>,
+    guard: ProcessGuard,
+    done: bool,
+}
+
+impl Stream for KillableRecordBatchStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut self.stream).poll_next(cx) {
+            Poll::Ready(None) => {
+                self.done = true;
+                if self.stream.is_aborted() {
+                    let error = error::QueryKilledSnafu { id: self.guard.id }.build();
+                    Poll::Ready(Some(Err(
+                        RecordBatchExternalSnafu.into_error(BoxedError::new(error))
+                    )))
+                } else {
+                    Poll::Ready(None)
+                }
+            }
+            poll => poll,
+        }
+    }
+}
+
+impl RecordBatchStream for KillableRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use futures::StreamExt;
+    use session::context::{QueryContext, ADMIN_ROLE};
+
+    use super::*;
+
+    #[test]
+    fn test_register_and_kill() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = Arc::new(QueryContext::new());
+        let guard1 = manager.register("SELECT 1", &query_ctx);
+        let guard2 = manager.register("SELECT 2", &query_ctx);
+        assert_ne!(guard1.id(), guard2.id());
+
+        let processes = manager.processes();
+        assert_eq!(2, processes.len());
+        assert_eq!(guard1.id(), processes[0].id);
+        assert_eq!("SELECT 2", processes[1].query);
+
+        manager.kill(guard1.id(), &UserInfo::default()).unwrap();
+        let id = guard1.id();
+        drop(guard1);
+        assert_eq!(1, manager.processes().len());
+        assert_eq!(
+            StatusCode::InvalidArguments,
+            manager
+                .kill(id, &UserInfo::default())
+                .unwrap_err()
+                .status_code()
+        );
+
+        let output = manager
+            .show_processlist(&ShowProcesslist { full: false })
+            .unwrap();
+        let Output::RecordBatches(batches) = output else {
+            unreachable!()
+        };
+        let expected = "\
++----+----------+--------+----------+------+----------+
+| Id | User     | Db     | Protocol | Time | Info     |
++----+----------+--------+----------+------+----------+
+| 2  | greptime | public | unknown  | 0    | SELECT 2 |
++----+----------+--------+----------+------+----------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
+
+    #[test]
+    fn test_kill_permission() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = Arc::new(QueryContext::new());
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        let guard = manager.register("SELECT 1", &query_ctx);
+
+        let err = manager.kill(guard.id(), &UserInfo::new("bob")).unwrap_err();
+        assert_eq!(StatusCode::AccessDenied, err.status_code());
+        manager.kill(guard.id(), &UserInfo::new("alice")).unwrap();
+        let admin = UserInfo::new("bob").with_roles(vec![ADMIN_ROLE.to_string()]);
+        manager.kill(guard.id(), &admin).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_kill_running_query() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = Arc::new(QueryContext::new());
+        let guard = manager.register("SELECT sleep", &query_ctx);
+        let id = guard.id();
+
+        let handle = tokio::spawn(guard.run(async {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(Output::AffectedRows(0))
+        }));
+        manager.kill(id, &UserInfo::default()).unwrap();
+
+        let err = handle.await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+        assert!(manager.processes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_kill_output_stream() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = Arc::new(QueryContext::new());
+        let guard = manager.register("SELECT *", &query_ctx);
+        let id = guard.id();
+
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::uint64_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(UInt64Vector::from_slice([1, 2])) as _],
+        )
+        .unwrap();
+        let stream = RecordBatches::try_new(schema, vec![batch.clone(), batch])
+            .unwrap()
+            .as_stream();
+
+        let output = guard
+            .run(async move { Ok(Output::Stream(stream)) })
+            .await
+            .unwrap();
+        let Output::Stream(mut stream) = output else {
+            unreachable!()
+        };
+        assert!(stream.next().await.unwrap().is_ok());
+        manager.kill(id, &UserInfo::default()).unwrap();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+        assert!(stream.next().await.is_none());
+
+        drop(stream);
+        assert!(manager.processes().is_empty());
+
+        let guard = manager.register("SELECT *", &query_ctx);
+        let output = guard
+            .run(async { Ok(Output::AffectedRows(1)) })
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+        assert!(manager.processes().is_empty());
+    }
+}
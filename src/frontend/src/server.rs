@@ -28,6 +28,7 @@ use servers::promql::PromqlServer;
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdaptor;
 use servers::query_handler::sql::ServerSqlQueryHandlerAdaptor;
 use servers::server::Server;
+use servers::Mode;
 use snafu::ResultExt;
 use tokio::try_join;
 
@@ -52,6 +53,7 @@ impl Services {
     {
         info!("Starting frontend servers");
         let user_provider = plugins.get::<UserProviderRef>().cloned();
+        let mode = opts.mode.clone();
 
         let grpc_server_and_addr = if let Some(opts) = &opts.grpc_options {
             let grpc_addr = parse_addr(&opts.addr)?;
@@ -67,7 +69,18 @@ impl Services {
             let grpc_server = GrpcServer::new(
                 ServerGrpcQueryHandlerAdaptor::arc(instance.clone()),
                 grpc_runtime,
-            );
+            )
+            .with_config(opts.server_config())
+            .with_tls(opts.tls.clone())
+            .with_catalog_manager(instance.catalog_manager())
+            .with_user_provider(user_provider.clone());
+            // Tables are stored locally in standalone mode, so their changes could be read
+            // from the WAL.
+            let grpc_server = if mode == Mode::Standalone {
+                grpc_server.with_change_stream(instance.catalog_manager())
+            } else {
+                grpc_server
+            };
 
             Some((Box::new(grpc_server) as _, grpc_addr))
         } else {
@@ -158,6 +171,9 @@ impl Services {
             if let Some(user_provider) = user_provider.clone() {
                 http_server.set_user_provider(user_provider);
             }
+            if let Some(job_manager) = instance.job_manager() {
+                http_server.set_job_manager(job_manager);
+            }
 
             if opentsdb_server_and_addr.is_some() {
                 http_server.set_opentsdb_handler(instance.clone());
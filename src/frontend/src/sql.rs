@@ -13,104 +13,60 @@
 // limitations under the License.
 
 use common_error::snafu::ensure;
-use datatypes::data_type::DataType;
-use datatypes::prelude::MutableVector;
-use datatypes::schema::ColumnSchema;
 use snafu::{OptionExt, ResultExt};
-use sql::ast::Value as SqlValue;
-use sql::statements;
 use sql::statements::insert::Insert;
 use table::requests::InsertRequest;
 use table::TableRef;
 
 use crate::error::{self, Result};
 
-const DEFAULT_PLACEHOLDER_VALUE: &str = "default";
-
-// TODO(fys): Extract the common logic in datanode and frontend in the future.
-#[allow(dead_code)]
-pub(crate) fn insert_to_request(table: &TableRef, stmt: Insert) -> Result<InsertRequest> {
+pub(crate) fn insert_to_request(
+    table: &TableRef,
+    stmt: Insert,
+    max_insert_rows: Option<usize>,
+) -> Result<InsertRequest> {
     let columns = stmt.columns();
-    let values = stmt.values().context(error::ParseSqlSnafu)?;
+    let rows_num = stmt.rows_num().context(error::ParseSqlSnafu)?;
+    if let Some(max_rows) = max_insert_rows {
+        ensure!(
+            rows_num <= max_rows,
+            error::TooManyInsertRowsSnafu {
+                rows: rows_num,
+                max_rows,
+            }
+        );
+    }
     let (catalog_name, schema_name, table_name) =
         stmt.full_table_name().context(error::ParseSqlSnafu)?;
 
     let schema = table.schema();
-    let columns_num = if columns.is_empty() {
-        schema.column_schemas().len()
+    let column_schemas = if columns.is_empty() {
+        schema.column_schemas().iter().collect::<Vec<_>>()
     } else {
-        columns.len()
+        columns
+            .into_iter()
+            .map(|column_name| {
+                schema.column_schema_by_name(column_name).with_context(|| {
+                    error::ColumnNotFoundSnafu {
+                        table_name: &table_name,
+                        column_name: column_name.to_string(),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
     };
-    let rows_num = values.len();
-
-    let mut columns_builders: Vec<(&ColumnSchema, Box<dyn MutableVector>)> =
-        Vec::with_capacity(columns_num);
-
-    if columns.is_empty() {
-        for column_schema in schema.column_schemas() {
-            let data_type = &column_schema.data_type;
-            columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
-        }
-    } else {
-        for column_name in columns {
-            let column_schema = schema.column_schema_by_name(column_name).with_context(|| {
-                error::ColumnNotFoundSnafu {
-                    table_name: &table_name,
-                    column_name: column_name.to_string(),
-                }
-            })?;
-            let data_type = &column_schema.data_type;
-            columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
-        }
-    }
-
-    for row in values {
-        ensure!(
-            row.len() == columns_num,
-            error::ColumnValuesNumberMismatchSnafu {
-                columns: columns_num,
-                values: row.len(),
-            }
-        );
-
-        for (sql_val, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
-            add_row_to_vector(column_schema, sql_val, builder)?;
-        }
-    }
+    let vectors = stmt
+        .values_to_vectors(&column_schemas)
+        .context(error::ParseSqlSnafu)?;
 
     Ok(InsertRequest {
         catalog_name,
         schema_name,
         table_name,
-        columns_values: columns_builders
-            .into_iter()
-            .map(|(cs, mut b)| (cs.name.to_string(), b.to_vector()))
+        columns_values: column_schemas
+            .iter()
+            .map(|column_schema| column_schema.name.clone())
+            .zip(vectors)
             .collect(),
     })
 }
-
-fn add_row_to_vector(
-    column_schema: &ColumnSchema,
-    sql_val: &SqlValue,
-    builder: &mut Box<dyn MutableVector>,
-) -> Result<()> {
-    let value = if replace_default(sql_val) {
-        column_schema
-            .create_default()
-            .context(error::ColumnDefaultValueSnafu {
-                column: column_schema.name.to_string(),
-            })?
-            .context(error::ColumnNoneDefaultValueSnafu {
-                column: column_schema.name.to_string(),
-            })?
-    } else {
-        statements::sql_value_to_value(&column_schema.name, &column_schema.data_type, sql_val)
-            .context(error::ParseSqlSnafu)?
-    };
-    builder.push_value_ref(value.as_value_ref()).unwrap();
-    Ok(())
-}
-
-fn replace_default(sql_val: &SqlValue) -> bool {
-    matches!(sql_val, SqlValue::Placeholder(s) if s.to_lowercase() == DEFAULT_PLACEHOLDER_VALUE)
-}
@@ -14,6 +14,7 @@
 
 use std::any::Any;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::AlterExpr;
 use async_trait::async_trait;
@@ -23,34 +24,52 @@ use client::Database;
 use common_error::prelude::BoxedError;
 use common_query::error::Result as QueryResult;
 use common_query::logical_plan::Expr;
-use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalPlanRef};
 use common_query::Output;
 use common_recordbatch::adapter::AsyncRecordBatchStreamAdapter;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
-use common_telemetry::debug;
+use common_telemetry::{debug, warn};
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::{
-    Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
+    ExecutionPlan, Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
 };
 use datafusion_common::DataFusionError;
+use datafusion_expr::LogicalPlan;
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
-use meta_client::rpc::TableName;
+use meta_client::rpc::{Peer, TableName};
 use partition::manager::PartitionRuleManagerRef;
 use snafu::prelude::*;
-use table::error::TableOperationSnafu;
+use table::error::{TableOperationSnafu, TableReadOnlySnafu};
 use table::metadata::{FilterPushDownType, TableInfo, TableInfoRef};
-use table::requests::{AlterTableRequest, InsertRequest};
-use table::table::AlterContext;
+use table::requests::{AlterKind, AlterTableRequest, DeleteRequest, InsertRequest};
+use table::table::scan::ScannedBytesCountingScan;
+use table::table::{AlterContext, TableStatistics};
 use table::Table;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
-use crate::datanode::DatanodeClients;
+use crate::catalog::information_schema::{latest_region_stats, table_statistics};
+use crate::datanode::{with_request_deadline, DatanodeClients};
 use crate::error::{self, Result};
 use crate::table::scan::{DatanodeInstance, TableScanPlan};
 
+mod delete;
 pub mod insert;
 pub(crate) mod scan;
 
+/// Context of the query being planned, which decides how the scans of the query request
+/// the datanodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ScanContext {
+    /// Whether to read the regions from their followers, see
+    /// [sql::statements::hint::Hints::read_replica].
+    pub(crate) read_replica: bool,
+}
+
+tokio::task_local! {
+    /// Context of the query being planned.
+    pub(crate) static SCAN_CONTEXT: ScanContext;
+}
+
 #[derive(Clone)]
 pub struct DistTable {
     table_name: TableName,
@@ -75,6 +94,7 @@ impl Table for DistTable {
     }
 
     async fn insert(&self, request: InsertRequest) -> table::Result<usize> {
+        self.ensure_writable()?;
         let split = self
             .partition_manager
             .split_insert_request(&self.table_name, request)
@@ -109,22 +129,30 @@ impl Table for DistTable {
             .find_regions_by_filters(partition_rule, filters)
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
-        let datanodes = self
-            .partition_manager
-            .find_region_datanodes(&self.table_name, regions)
-            .await
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)?;
+        let scan_ctx = SCAN_CONTEXT.try_with(|ctx| *ctx).unwrap_or_default();
+        let datanodes = if scan_ctx.read_replica {
+            self.partition_manager
+                .find_region_follower_datanodes(&self.table_name, regions)
+                .await
+        } else {
+            self.partition_manager
+                .find_region_datanodes(&self.table_name, regions)
+                .await
+        }
+        .map_err(BoxedError::new)
+        .context(TableOperationSnafu)?;
 
         let table_name = &self.table_name;
         let mut partition_execs = Vec::with_capacity(datanodes.len());
         for (datanode, _regions) in datanodes.iter() {
             let client = self.datanode_clients.get_client(datanode).await;
             let db = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
+            let db = with_request_deadline(db);
             let datanode_instance = DatanodeInstance::new(Arc::new(self.clone()) as _, db);
 
             // TODO(LFC): Pass in "regions" when Datanode supports multi regions for a table.
             partition_execs.push(Arc::new(PartitionExec {
+                datanode: datanode.clone(),
                 table_name: table_name.clone(),
                 datanode_instance,
                 projection: projection.cloned(),
@@ -137,6 +165,7 @@ impl Table for DistTable {
         let dist_scan = DistTableScan {
             schema: project_schema(self.schema(), projection),
             partition_execs,
+            initialized: Arc::new(Mutex::new(false)),
         };
         Ok(Arc::new(dist_scan))
     }
@@ -151,9 +180,35 @@ impl Table for DistTable {
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)
     }
+
+    async fn collect_statistics(&self) -> table::Result<Option<TableStatistics>> {
+        self.reported_statistics()
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)
+    }
+
+    async fn delete(&self, request: DeleteRequest) -> table::Result<usize> {
+        self.ensure_writable()?;
+        self.dist_delete(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(TableOperationSnafu)
+    }
 }
 
 impl DistTable {
+    /// Returns error if the table is read-only.
+    fn ensure_writable(&self) -> table::Result<()> {
+        ensure!(
+            !self.table_info.meta.read_only,
+            TableReadOnlySnafu {
+                table_name: &self.table_info.name,
+            }
+        );
+        Ok(())
+    }
+
     pub(crate) fn new(
         table_name: TableName,
         table_info: TableInfoRef,
@@ -170,6 +225,18 @@ impl DistTable {
         }
     }
 
+    /// Aggregates the statistics of the regions of the table reported by datanodes in their
+    /// heartbeats, `None` if no region is reported.
+    async fn reported_statistics(&self) -> Result<Option<TableStatistics>> {
+        let region_stats = latest_region_stats(&self.backend).await?;
+        let TableName {
+            catalog_name,
+            schema_name,
+            table_name,
+        } = &self.table_name;
+        Ok(table_statistics(region_stats, catalog_name, schema_name).remove(table_name))
+    }
+
     pub(crate) async fn table_global_value(
         &self,
         key: &TableGlobalKey,
@@ -198,18 +265,59 @@ impl DistTable {
             .context(error::CatalogSnafu)
     }
 
+    /// Alters the table atomically to readers without a transaction across its regions.
+    ///
+    /// Readers pin the schema epoch of the table published in meta when their queries are
+    /// planned, and datanodes read the regions at the pinned epoch (see
+    /// [table::table::epoch]). So the ALTER is propagated to the regions of the table before
+    /// its new epoch is published, then readers of the new epoch never request the added
+    /// columns from the regions without them. Dropping columns is the opposite, the new
+    /// epoch is published first to stop new readers from requesting the dropped columns, and
+    /// the previous epoch is restored if the ALTER fails to reach all the regions.
     async fn handle_alter(&self, context: AlterContext, request: &AlterTableRequest) -> Result<()> {
         let alter_expr = context
             .get::<AlterExpr>()
             .context(error::ContextValueNotFoundSnafu { key: "AlterExpr" })?;
 
-        self.alter_by_expr(alter_expr).await?;
+        if let AlterKind::DropColumns { .. } = request.alter_kind {
+            let previous = self.publish_altered_info(alter_expr, request).await?;
+            let result = self.alter_by_expr(alter_expr).await;
+            if let (Err(e), Some(previous)) = (&result, previous) {
+                warn!(
+                    "Failed to drop columns of table {}, restores its schema epoch, error: {e}",
+                    self.table_name
+                );
+                // Regions that have dropped the columns fail the readers of the restored
+                // epoch requesting them, until the ALTER is executed again.
+                self.set_table_global_value(table_global_key(alter_expr), previous)
+                    .await?;
+            }
+            result
+        } else {
+            self.alter_by_expr(alter_expr).await?;
+            self.publish_altered_info(alter_expr, request)
+                .await
+                .map(|_| ())
+        }
+    }
 
+    /// Publishes the table info altered by the `request` to meta, which bumps the schema
+    /// epoch of the table if the schema is altered. Returns the previous global value of the
+    /// table if it's altered.
+    async fn publish_altered_info(
+        &self,
+        alter_expr: &AlterExpr,
+        request: &AlterTableRequest,
+    ) -> Result<Option<TableGlobalValue>> {
         let table_info = self.table_info();
         let table_name = &table_info.name;
+        let schema = &table_info.meta.schema;
+        let Some(alter_kind) = request.alter_kind.skip_existing_columns(schema) else {
+            return Ok(None);
+        };
         let new_meta = table_info
             .meta
-            .builder_with_alter_kind(table_name, &request.alter_kind)
+            .builder_with_alter_kind(table_name, &alter_kind)
             .context(error::TableSnafu)?
             .build()
             .context(error::BuildTableMetaSnafu {
@@ -219,22 +327,23 @@ impl DistTable {
         let mut new_info = TableInfo::clone(&*table_info);
         new_info.ident.version = table_info.ident.version + 1;
         new_info.meta = new_meta;
+        if let AlterKind::SetComment { comment } = &alter_kind {
+            new_info.desc = comment.clone();
+        }
 
-        let key = TableGlobalKey {
-            catalog_name: alter_expr.catalog_name.clone(),
-            schema_name: alter_expr.schema_name.clone(),
-            table_name: alter_expr.table_name.clone(),
-        };
-        let mut value =
-            self.table_global_value(&key)
-                .await?
-                .context(error::TableNotFoundSnafu {
-                    table_name: alter_expr.table_name.clone(),
-                })?;
+        let key = table_global_key(alter_expr);
+        let previous = self
+            .table_global_value(&key)
+            .await?
+            .context(error::TableNotFoundSnafu {
+                table_name: alter_expr.table_name.clone(),
+            })?;
 
+        let mut value = previous.clone();
         value.table_info = new_info.into();
 
-        self.set_table_global_value(key, value).await
+        self.set_table_global_value(key, value).await?;
+        Ok(Some(previous))
     }
 
     /// Define a `alter_by_expr` instead of impl [`Table::alter`] to avoid redundant conversion between
@@ -259,12 +368,19 @@ impl DistTable {
         );
         for datanode in leaders {
             let client = self.datanode_clients.get_client(&datanode).await;
-            let db = Database::with_client(client);
+            let db = with_request_deadline(Database::with_client(client));
             debug!("Sending {:?} to {:?}", expr, db);
-            let result = db
-                .alter(expr.clone())
-                .await
-                .context(error::RequestDatanodeSnafu)?;
+            let mut retries = 0;
+            let result = loop {
+                match db.alter(expr.clone()).await {
+                    Err(e) if e.is_retryable() && retries < ALTER_MAX_RETRIES => {
+                        retries += 1;
+                        warn!("Failed to alter table on {datanode:?}, retry {retries}, error: {e}");
+                        tokio::time::sleep(ALTER_RETRY_INTERVAL * retries).await;
+                    }
+                    result => break result.context(error::RequestDatanodeSnafu)?,
+                }
+            };
             debug!("Alter table result: {:?}", result);
             // TODO(hl): We should further check and track alter result in some global DDL task tracker
         }
@@ -272,6 +388,18 @@ impl DistTable {
     }
 }
 
+/// Max times to retry altering a table on a datanode that is unreachable.
+const ALTER_MAX_RETRIES: u32 = 3;
+const ALTER_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+fn table_global_key(expr: &AlterExpr) -> TableGlobalKey {
+    TableGlobalKey {
+        catalog_name: expr.catalog_name.clone(),
+        schema_name: expr.schema_name.clone(),
+        table_name: expr.table_name.clone(),
+    }
+}
+
 fn project_schema(table_schema: SchemaRef, projection: Option<&Vec<usize>>) -> SchemaRef {
     if let Some(projection) = projection {
         let columns = table_schema.column_schemas();
@@ -289,6 +417,59 @@ fn project_schema(table_schema: SchemaRef, projection: Option<&Vec<usize>>) -> S
 struct DistTableScan {
     schema: SchemaRef,
     partition_execs: Vec<Arc<PartitionExec>>,
+    /// Whether all the partitions have been read from datanodes.
+    initialized: Arc<Mutex<bool>>,
+}
+
+impl DistTableScan {
+    /// Returns the plans shipped to datanodes to read the partitions.
+    pub(crate) fn fragments(&self) -> Result<Vec<PlanFragment>> {
+        self.partition_execs
+            .iter()
+            .map(|exec| {
+                Ok(PlanFragment {
+                    datanode: exec.datanode.clone(),
+                    plan: exec
+                        .datanode_instance
+                        .build_logical_plan(&exec.table_scan_plan())?,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads all the partitions from datanodes together when any of them is executed.
+    ///
+    /// Partitions could be executed at different times, e.g. one after another when
+    /// they are merged sequentially, and reading a partition takes a while. To keep the
+    /// query reading a consistent view of the table, the snapshots of the table on all
+    /// datanodes are taken together first, which is cheap, then each partition only reads
+    /// the writes visible to the snapshot of its datanode. A write landed during the scan
+    /// is either read by all the partitions or by none of them, unless it lands between
+    /// the snapshots.
+    async fn init_partitions(
+        partition_execs: &[Arc<PartitionExec>],
+        initialized: &Mutex<bool>,
+    ) -> Result<()> {
+        let mut initialized = initialized.lock().await;
+        if *initialized {
+            return Ok(());
+        }
+
+        let sequences = futures::future::try_join_all(partition_execs.iter().map(|exec| {
+            exec.datanode_instance
+                .grpc_snapshot_sequence(&exec.table_name)
+        }))
+        .await?;
+        let _ = futures::future::try_join_all(
+            partition_execs
+                .iter()
+                .zip(sequences)
+                .map(|(exec, sequence)| exec.maybe_init(sequence)),
+        )
+        .await?;
+        *initialized = true;
+        Ok(())
+    }
 }
 
 impl PhysicalPlan for DistTableScan {
@@ -318,8 +499,10 @@ impl PhysicalPlan for DistTableScan {
         _context: Arc<TaskContext>,
     ) -> QueryResult<SendableRecordBatchStream> {
         let exec = self.partition_execs[partition].clone();
+        let partition_execs = self.partition_execs.clone();
+        let initialized = self.initialized.clone();
         let stream = Box::pin(async move {
-            exec.maybe_init()
+            Self::init_partitions(&partition_execs, &initialized)
                 .await
                 .map_err(|e| DataFusionError::External(Box::new(e)))?;
             exec.as_stream().await
@@ -329,8 +512,35 @@ impl PhysicalPlan for DistTableScan {
     }
 }
 
+/// A fragment of the distributed query plan, which is executed by the `datanode`.
+#[derive(Debug)]
+pub(crate) struct PlanFragment {
+    pub datanode: Peer,
+    pub plan: LogicalPlan,
+}
+
+/// Collects the fragments shipped to datanodes by all distributed table scans in `plan`.
+pub(crate) fn collect_plan_fragments(plan: &Arc<dyn ExecutionPlan>) -> Result<Vec<PlanFragment>> {
+    if let Some(adapter) = plan.as_any().downcast_ref::<DfPhysicalPlanAdapter>() {
+        let mut scan = &adapter.0;
+        if let Some(counting) = scan.as_any().downcast_ref::<ScannedBytesCountingScan>() {
+            scan = counting.inner();
+        }
+        if let Some(scan) = scan.as_any().downcast_ref::<DistTableScan>() {
+            return scan.fragments();
+        }
+    }
+
+    let mut fragments = Vec::new();
+    for child in plan.children() {
+        fragments.extend(collect_plan_fragments(&child)?);
+    }
+    Ok(fragments)
+}
+
 #[derive(Debug)]
 struct PartitionExec {
+    datanode: Peer,
     table_name: TableName,
     datanode_instance: DatanodeInstance,
     projection: Option<Vec<usize>>,
@@ -340,7 +550,9 @@ struct PartitionExec {
 }
 
 impl PartitionExec {
-    async fn maybe_init(&self) -> Result<()> {
+    /// Reads the partition from the datanode, only the writes whose sequence is less than
+    /// or equal to `sequence` are read.
+    async fn maybe_init(&self, sequence: u64) -> Result<()> {
         if self.batches.read().await.is_some() {
             return Ok(());
         }
@@ -350,15 +562,22 @@ impl PartitionExec {
             return Ok(());
         }
 
-        let plan = TableScanPlan {
+        let plan = self.table_scan_plan();
+        let result = self
+            .datanode_instance
+            .grpc_table_scan(plan, sequence)
+            .await?;
+        let _ = batches.insert(result);
+        Ok(())
+    }
+
+    fn table_scan_plan(&self) -> TableScanPlan {
+        TableScanPlan {
             table_name: self.table_name.clone(),
             projection: self.projection.clone(),
             filters: self.filters.clone(),
             limit: self.limit,
-        };
-        let result = self.datanode_instance.grpc_table_scan(plan).await?;
-        let _ = batches.insert(result);
-        Ok(())
+        }
     }
 
     /// Notice: the record batch will be consumed.
@@ -379,12 +598,11 @@ mod test {
     use api::v1::{column, Column, ColumnDataType, InsertRequest};
     use catalog::error::Result;
     use catalog::remote::{KvBackend, ValueIter};
-    use common_query::physical_plan::DfPhysicalPlanAdapter;
+    use common_error::prelude::{ErrorExt, StatusCode};
     use common_recordbatch::adapter::RecordBatchStreamAdapter;
     use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
     use datafusion::physical_plan::expressions::{col as physical_col, PhysicalSortExpr};
     use datafusion::physical_plan::sorts::sort::SortExec;
-    use datafusion::physical_plan::ExecutionPlan;
     use datafusion::prelude::SessionContext;
     use datafusion::sql::sqlparser;
     use datafusion_expr::expr_fn::{and, binary_expr, col, or};
@@ -396,7 +614,7 @@ mod test {
     use itertools::Itertools;
     use meta_client::client::MetaClient;
     use meta_client::rpc::router::RegionRoute;
-    use meta_client::rpc::{Region, Table, TableRoute};
+    use meta_client::rpc::{Peer, Region, Table, TableRoute};
     use partition::columns::RangeColumnsPartitionRule;
     use partition::manager::PartitionRuleManager;
     use partition::partition::{PartitionBound, PartitionDef};
@@ -412,6 +630,7 @@ mod test {
 
     use super::*;
     use crate::expr_factory::{CreateExprFactory, DefaultCreateExprFactory};
+    use crate::tests::MockDistributedInstance;
 
     struct DummyKvBackend;
 
@@ -620,6 +839,68 @@ mod test {
         assert_eq!(range_columns_rule.regions(), &vec![1, 2, 3]);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_find_region_follower_datanodes() {
+        let table_name = TableName::new("greptime", "public", "foo");
+        let table_routes = Arc::new(TableRoutes::new(Arc::new(MetaClient::default())));
+        let partition_manager = PartitionRuleManager::new(table_routes.clone());
+
+        let region = |id| Region {
+            id,
+            name: format!("r{id}"),
+            partition: None,
+            attrs: HashMap::new(),
+        };
+        let table_route = TableRoute {
+            table: Table {
+                id: 1,
+                table_name: table_name.clone(),
+                table_schema: vec![],
+            },
+            region_routes: vec![
+                RegionRoute {
+                    region: region(1),
+                    leader_peer: Some(Peer::new(1, "a1")),
+                    follower_peers: vec![Peer::new(2, "a2"), Peer::new(3, "a3")],
+                },
+                RegionRoute {
+                    region: region(2),
+                    leader_peer: Some(Peer::new(1, "a1")),
+                    follower_peers: vec![],
+                },
+            ],
+        };
+        table_routes
+            .insert_table_route(table_name.clone(), Arc::new(table_route))
+            .await;
+
+        // Successive reads of the region are spread among its followers.
+        let mut readers = Vec::new();
+        for _ in 0..2 {
+            let datanodes = partition_manager
+                .find_region_follower_datanodes(&table_name, vec![1])
+                .await
+                .unwrap();
+            assert_eq!(1, datanodes.len());
+            readers.extend(datanodes.into_keys().map(|peer| peer.id));
+        }
+        readers.sort_unstable();
+        assert_eq!(vec![2, 3], readers);
+
+        // Regions without followers are read from their leaders.
+        let datanodes = partition_manager
+            .find_region_follower_datanodes(&table_name, vec![2])
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![1],
+            datanodes
+                .into_keys()
+                .map(|peer| peer.id)
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_dist_table_scan() {
         common_telemetry::init_default_ut_logging();
@@ -742,6 +1023,81 @@ mod test {
         exec_table_scan(table.clone(), projection, filters, 4, expected_output).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_read_only() {
+        let table = new_dist_table("test_dist_table_read_only").await;
+        let mut table_info = TableInfo::clone(&table.table_info);
+        table_info.meta.read_only = true;
+        let table = DistTable {
+            table_info: Arc::new(table_info),
+            ..table
+        };
+
+        let request = table::requests::InsertRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "dist_numbers".to_string(),
+            columns_values: HashMap::new(),
+        };
+        let err = table.insert(request).await.unwrap_err();
+        assert_eq!(StatusCode::TableReadOnly, err.status_code());
+
+        let request = DeleteRequest {
+            key_column_values: HashMap::new(),
+        };
+        let err = table.delete(request).await.unwrap_err();
+        assert_eq!(StatusCode::TableReadOnly, err.status_code());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_scan_at_pinned_epoch() {
+        common_telemetry::init_default_ut_logging();
+        let instance =
+            crate::tests::create_distributed_instance("test_dist_table_scan_at_pinned_epoch").await;
+        let table = Arc::new(new_dist_table_in(&instance).await);
+
+        // Alters the table in one datanode only, as if the ALTER is being propagated.
+        let datanode = instance.datanodes.values().next().unwrap();
+        let output = datanode
+            .execute_sql(
+                "alter table greptime.public.dist_numbers add column b int",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        // The table is read at the epoch pinned before the ALTER.
+        // select * from numbers
+        let expected_output = vec![
+            "+----+-----+--------+",
+            "| ts | a   | row_id |",
+            "+----+-----+--------+",
+            "| 1  | 0   | 1      |",
+            "| 2  | 1   | 2      |",
+            "| 3  | 2   | 3      |",
+            "| 4  | 3   | 4      |",
+            "| 5  | 4   | 5      |",
+            "| 6  | 10  | 1      |",
+            "| 7  | 11  | 2      |",
+            "| 8  | 12  | 3      |",
+            "| 9  | 13  | 4      |",
+            "| 10 | 14  | 5      |",
+            "| 11 | 30  | 1      |",
+            "| 12 | 31  | 2      |",
+            "| 13 | 32  | 3      |",
+            "| 14 | 33  | 4      |",
+            "| 15 | 34  | 5      |",
+            "| 16 | 100 | 1      |",
+            "| 17 | 101 | 2      |",
+            "| 18 | 102 | 3      |",
+            "| 19 | 103 | 4      |",
+            "| 20 | 104 | 5      |",
+            "+----+-----+--------+",
+        ];
+        exec_table_scan(table, None, vec![], 4, expected_output).await;
+    }
+
     async fn exec_table_scan(
         table: TableRef,
         projection: Option<Vec<usize>>,
@@ -782,6 +1138,11 @@ mod test {
     }
 
     async fn new_dist_table(test_name: &str) -> DistTable {
+        let instance = crate::tests::create_distributed_instance(test_name).await;
+        new_dist_table_in(&instance).await
+    }
+
+    async fn new_dist_table_in(instance: &MockDistributedInstance) -> DistTable {
         let column_schemas = vec![
             ColumnSchema::new("ts", ConcreteDataType::int64_datatype(), false),
             ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
@@ -789,9 +1150,8 @@ mod test {
         ];
         let schema = Arc::new(Schema::new(column_schemas.clone()));
 
-        let instance = crate::tests::create_distributed_instance(test_name).await;
         let dist_instance = &instance.dist_instance;
-        let datanode_instances = instance.datanodes;
+        let datanode_instances = &instance.datanodes;
 
         let catalog_manager = dist_instance.catalog_manager();
         let partition_manager = catalog_manager.partition_manager();
@@ -924,6 +1284,7 @@ mod test {
             columns,
             row_count,
             region_number: 0,
+            ..Default::default()
         };
         dn_instance
             .handle_insert(request, QueryContext::arc())
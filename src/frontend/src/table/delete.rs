@@ -0,0 +1,74 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use api::v1::DeleteRequest as GrpcDeleteRequest;
+use client::Database;
+use common_query::Output;
+use snafu::ResultExt;
+use table::requests::DeleteRequest;
+
+use super::DistTable;
+use crate::datanode::with_request_deadline;
+use crate::error::{self, FindTableRouteSnafu, Result};
+use crate::table::insert::vectors_to_columns;
+
+impl DistTable {
+    /// Deletes the keys from the datanodes of all regions of the table, since the regions
+    /// of the keys are unknown if the partition columns are not in the keys. Deleting keys
+    /// absent from a region has no effect on query results.
+    pub async fn dist_delete(&self, request: DeleteRequest) -> Result<usize> {
+        let table_name = &self.table_name;
+        let route = self
+            .partition_manager
+            .find_table_route(table_name)
+            .await
+            .with_context(|_| FindTableRouteSnafu {
+                table_name: table_name.to_string(),
+            })?;
+        let datanodes = route
+            .region_routes
+            .iter()
+            .filter_map(|route| route.leader_peer.clone())
+            .collect::<HashSet<_>>();
+
+        let (key_columns, row_count) = vectors_to_columns(&request.key_column_values)?;
+        let request = GrpcDeleteRequest {
+            table_name: table_name.table_name.clone(),
+            key_columns,
+            row_count,
+        };
+
+        let mut joins = Vec::with_capacity(datanodes.len());
+        for datanode in datanodes {
+            let client = self.datanode_clients.get_client(&datanode).await;
+            let db = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
+            let db = with_request_deadline(db);
+            let request = request.clone();
+            let join = tokio::spawn(async move {
+                db.delete(request)
+                    .await
+                    .context(error::RequestDatanodeSnafu)
+            });
+            joins.push(join);
+        }
+
+        for join in joins {
+            let output = join.await.context(error::JoinTaskSnafu)??;
+            debug_assert!(matches!(output, Output::AffectedRows(_)));
+        }
+        Ok(row_count as usize)
+    }
+}
@@ -20,12 +20,14 @@ use api::v1::column::SemanticType;
 use api::v1::{Column, InsertRequest as GrpcInsertRequest};
 use client::Database;
 use common_query::Output;
-use datatypes::prelude::ConcreteDataType;
+use datatypes::prelude::{ConcreteDataType, VectorRef};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::RegionNumber;
+use table::metadata::TableId;
 use table::requests::InsertRequest;
 
 use super::DistTable;
+use crate::datanode::with_request_deadline;
 use crate::error;
 use crate::error::{FindTableRouteSnafu, Result};
 use crate::table::scan::DatanodeInstance;
@@ -36,6 +38,7 @@ impl DistTable {
         inserts: HashMap<RegionNumber, InsertRequest>,
     ) -> Result<Output> {
         let table_name = &self.table_name;
+        let table_id = self.table_info.ident.table_id;
         let route = self
             .partition_manager
             .find_table_route(&self.table_name)
@@ -60,12 +63,13 @@ impl DistTable {
 
             let client = self.datanode_clients.get_client(&datanode).await;
             let db = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
+            let db = with_request_deadline(db);
             let instance = DatanodeInstance::new(Arc::new(self.clone()) as _, db);
 
             // TODO(fys): a separate runtime should be used here.
             let join = tokio::spawn(async move {
                 instance
-                    .grpc_insert(to_grpc_insert_request(region_id, insert)?)
+                    .grpc_insert(to_grpc_insert_request(table_id, region_id, insert)?)
                     .await
                     .context(error::RequestDatanodeSnafu)
             });
@@ -84,10 +88,17 @@ impl DistTable {
 }
 
 pub fn insert_request_to_insert_batch(insert: &InsertRequest) -> Result<(Vec<Column>, u32)> {
+    vectors_to_columns(&insert.columns_values)
+}
+
+/// Converts vectors keyed by column names to gRPC columns, returns the columns and the
+/// row count.
+pub(crate) fn vectors_to_columns(
+    columns_values: &HashMap<String, VectorRef>,
+) -> Result<(Vec<Column>, u32)> {
     let mut row_count = None;
 
-    let columns = insert
-        .columns_values
+    let columns = columns_values
         .iter()
         .map(|(column_name, vector)| {
             match row_count {
@@ -132,6 +143,7 @@ pub fn insert_request_to_insert_batch(insert: &InsertRequest) -> Result<(Vec<Col
 }
 
 fn to_grpc_insert_request(
+    table_id: TableId,
     region_number: RegionNumber,
     insert: InsertRequest,
 ) -> Result<GrpcInsertRequest> {
@@ -142,6 +154,8 @@ fn to_grpc_insert_request(
         region_number,
         columns,
         row_count,
+        table_id,
+        ..Default::default()
     })
 }
 
@@ -161,7 +175,7 @@ mod tests {
     fn test_to_grpc_insert_request() {
         let insert_request = mock_insert_request();
 
-        let request = to_grpc_insert_request(12, insert_request).unwrap();
+        let request = to_grpc_insert_request(1024, 12, insert_request).unwrap();
 
         verify_grpc_insert_request(request);
     }
@@ -212,5 +226,6 @@ mod tests {
 
         let region_number = request.region_number;
         assert_eq!(12, region_number);
+        assert_eq!(1024, request.table_id);
     }
 }
@@ -51,7 +51,22 @@ impl DatanodeInstance {
         self.db.insert(request).await
     }
 
-    pub(crate) async fn grpc_table_scan(&self, plan: TableScanPlan) -> Result<RecordBatches> {
+    /// Takes a snapshot of the table on the datanode, returns the sequence to pass to
+    /// [DatanodeInstance::grpc_table_scan].
+    pub(crate) async fn grpc_snapshot_sequence(&self, table_name: &TableName) -> Result<u64> {
+        self.db
+            .snapshot_sequence(&table_name.table_name)
+            .await
+            .context(error::RequestDatanodeSnafu)
+    }
+
+    /// Scans the table on the datanode, only the writes whose sequence is less than or equal
+    /// to `sequence` are read.
+    pub(crate) async fn grpc_table_scan(
+        &self,
+        plan: TableScanPlan,
+        sequence: u64,
+    ) -> Result<RecordBatches> {
         let logical_plan = self.build_logical_plan(&plan)?;
 
         let substrait_plan = DFLogicalSubstraitConvertor
@@ -60,14 +75,14 @@ impl DatanodeInstance {
 
         let result = self
             .db
-            .logical_plan(substrait_plan.to_vec())
+            .logical_plan_at_sequence(substrait_plan.to_vec(), sequence)
             .await
             .context(error::RequestDatanodeSnafu)?;
         let Output::RecordBatches(recordbatches) = result else { unreachable!() };
         Ok(recordbatches)
     }
 
-    fn build_logical_plan(&self, table_scan: &TableScanPlan) -> Result<LogicalPlan> {
+    pub(crate) fn build_logical_plan(&self, table_scan: &TableScanPlan) -> Result<LogicalPlan> {
         let table_provider = Arc::new(DfTableProviderAdapter::new(self.table.clone()));
 
         let mut builder = LogicalPlanBuilder::scan_with_filters(
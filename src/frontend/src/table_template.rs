@@ -0,0 +1,243 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Templates of tables auto-created on insertion, e.g. by the Prometheus, InfluxDB and
+//! OpenTSDB write paths.
+
+use std::collections::HashMap;
+
+use api::v1::{ColumnDataType, CreateTableExpr};
+use serde::{Deserialize, Serialize};
+use sql::ast::{Ident, Value as SqlValue};
+use sql::statements::create::{PartitionEntry, Partitions};
+
+/// Physical design of the tables auto-created on insertion.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableTemplate {
+    /// Only tables whose names start with the prefix are created by the template, an empty
+    /// prefix matches all tables.
+    pub table_prefix: String,
+    /// Leading columns of the primary key in order, columns that are not tags of the
+    /// inserted data are ignored.
+    pub primary_keys: Vec<String>,
+    /// Whether the tags not in `primary_keys` are appended to the primary key.
+    pub append_other_tags: bool,
+    /// Options of created tables, e.g. `ttl`.
+    pub table_options: HashMap<String, String>,
+    /// Range partitioning of created tables, only works in distributed mode.
+    pub partition: Option<PartitionTemplate>,
+}
+
+impl Default for TableTemplate {
+    fn default() -> Self {
+        Self {
+            table_prefix: String::new(),
+            primary_keys: vec![],
+            append_other_tags: true,
+            table_options: HashMap::new(),
+            partition: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartitionTemplate {
+    /// The column to partition tables by.
+    pub column: String,
+    /// Exclusive upper bounds of partitions in ascending order, the last partition holds
+    /// the values not less than the last bound.
+    pub bounds: Vec<String>,
+}
+
+/// Finds the first template that matches the `table_name`.
+pub fn find_table_template<'a>(
+    templates: &'a [TableTemplate],
+    table_name: &str,
+) -> Option<&'a TableTemplate> {
+    templates
+        .iter()
+        .find(|template| table_name.starts_with(&template.table_prefix))
+}
+
+impl TableTemplate {
+    /// Applies the primary key layout and table options of the template to `create_expr`.
+    pub fn apply(&self, create_expr: &mut CreateTableExpr) {
+        let tags = std::mem::take(&mut create_expr.primary_keys);
+        let mut primary_keys = self
+            .primary_keys
+            .iter()
+            .filter(|key| tags.contains(key))
+            .cloned()
+            .collect::<Vec<_>>();
+        if self.append_other_tags {
+            for tag in tags {
+                if !primary_keys.contains(&tag) {
+                    primary_keys.push(tag);
+                }
+            }
+        }
+        create_expr.primary_keys = primary_keys;
+
+        for (key, value) in &self.table_options {
+            create_expr
+                .table_options
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+
+    /// Returns the partitions of the table to create by `create_expr`, or `None` if the
+    /// template has no partitioning or the partition column is not in the table.
+    pub fn partitions(&self, create_expr: &CreateTableExpr) -> Option<Partitions> {
+        let partition = self.partition.as_ref()?;
+        let column_def = create_expr
+            .column_defs
+            .iter()
+            .find(|c| c.name == partition.column)?;
+        let is_string = column_def.datatype == ColumnDataType::String as i32;
+
+        let entries = partition
+            .bounds
+            .iter()
+            .map(|bound| {
+                if is_string {
+                    SqlValue::SingleQuotedString(bound.clone())
+                } else {
+                    SqlValue::Number(bound.clone(), false)
+                }
+            })
+            .chain(std::iter::once(SqlValue::Number(
+                "MAXVALUE".to_string(),
+                false,
+            )))
+            .enumerate()
+            .map(|(i, value)| PartitionEntry {
+                name: Ident::new(format!("r{i}")),
+                value_list: vec![value],
+            })
+            .collect::<Vec<_>>();
+
+        Some(Partitions {
+            column_list: vec![Ident::new(&partition.column)],
+            entries,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::ColumnDef;
+
+    use super::*;
+
+    fn new_create_expr() -> CreateTableExpr {
+        let column_def = |name: &str, datatype: ColumnDataType| ColumnDef {
+            name: name.to_string(),
+            datatype: datatype as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            comment: String::new(),
+        };
+        CreateTableExpr {
+            table_name: "cpu_usage".to_string(),
+            column_defs: vec![
+                column_def("host", ColumnDataType::String),
+                column_def("region", ColumnDataType::String),
+                column_def("idc", ColumnDataType::String),
+                column_def("value", ColumnDataType::Float64),
+                column_def("ts", ColumnDataType::TimestampMillisecond),
+            ],
+            time_index: "ts".to_string(),
+            primary_keys: vec!["host".to_string(), "region".to_string(), "idc".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_table_template() {
+        let templates = vec![
+            TableTemplate {
+                table_prefix: "cpu_".to_string(),
+                ..Default::default()
+            },
+            TableTemplate::default(),
+        ];
+        assert_eq!(
+            &templates[0],
+            find_table_template(&templates, "cpu_usage").unwrap()
+        );
+        assert_eq!(
+            &templates[1],
+            find_table_template(&templates, "memory").unwrap()
+        );
+        assert!(find_table_template(&templates[..1], "memory").is_none());
+    }
+
+    #[test]
+    fn test_apply_template() {
+        let mut template = TableTemplate {
+            primary_keys: vec!["idc".to_string(), "unknown".to_string(), "host".to_string()],
+            table_options: HashMap::from([("ttl".to_string(), "7d".to_string())]),
+            ..Default::default()
+        };
+        let mut create_expr = new_create_expr();
+        template.apply(&mut create_expr);
+        assert_eq!(vec!["idc", "host", "region"], create_expr.primary_keys);
+        assert_eq!("7d", create_expr.table_options["ttl"]);
+
+        template.append_other_tags = false;
+        let mut create_expr = new_create_expr();
+        template.apply(&mut create_expr);
+        assert_eq!(vec!["idc", "host"], create_expr.primary_keys);
+    }
+
+    #[test]
+    fn test_template_partitions() {
+        let mut template = TableTemplate::default();
+        let create_expr = new_create_expr();
+        assert!(template.partitions(&create_expr).is_none());
+
+        template.partition = Some(PartitionTemplate {
+            column: "host".to_string(),
+            bounds: vec!["h".to_string(), "p".to_string()],
+        });
+        let partitions = template.partitions(&create_expr).unwrap();
+        assert_eq!(vec![Ident::new("host")], partitions.column_list);
+        assert_eq!(
+            vec![
+                PartitionEntry {
+                    name: Ident::new("r0"),
+                    value_list: vec![SqlValue::SingleQuotedString("h".to_string())],
+                },
+                PartitionEntry {
+                    name: Ident::new("r1"),
+                    value_list: vec![SqlValue::SingleQuotedString("p".to_string())],
+                },
+                PartitionEntry {
+                    name: Ident::new("r2"),
+                    value_list: vec![SqlValue::Number("MAXVALUE".to_string(), false)],
+                },
+            ],
+            partitions.entries
+        );
+
+        template.partition = Some(PartitionTemplate {
+            column: "unknown".to_string(),
+            bounds: vec![],
+        });
+        assert!(template.partitions(&create_expr).is_none());
+    }
+}
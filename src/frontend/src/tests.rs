@@ -42,6 +42,9 @@ use crate::datanode::DatanodeClients;
 use crate::instance::distributed::DistInstance;
 use crate::instance::Instance;
 
+#[cfg(feature = "failpoints")]
+mod failpoint_test;
+
 /// Guard against the `TempDir`s that used in unit tests.
 /// (The `TempDir` will be deleted once it goes out of scope.)
 pub struct TestGuard {
@@ -84,6 +87,7 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
         },
         storage: ObjectStoreConfig::File(FileConfig {
             data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
         }),
         mode: Mode::Standalone,
         ..Default::default()
@@ -170,6 +174,7 @@ async fn create_distributed_datanode(
         },
         storage: ObjectStoreConfig::File(FileConfig {
             data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
         }),
         mode: Mode::Distributed,
         ..Default::default()
@@ -209,7 +214,7 @@ pub(crate) async fn create_distributed_instance(test_name: &str) -> MockDistribu
     let kv_store: KvStoreRef = Arc::new(MemStore::default()) as _;
     let meta_srv = meta_srv::mocks::mock(MetaSrvOptions::default(), kv_store.clone(), None).await;
 
-    let datanode_clients = Arc::new(DatanodeClients::new());
+    let datanode_clients = Arc::new(DatanodeClients::default());
 
     let mut test_guards = vec![];
 
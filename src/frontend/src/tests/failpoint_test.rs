@@ -0,0 +1,137 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests that inject failures into the RPCs between frontend, datanode and meta by
+//! fail points. Requires the `failpoints` feature. The fail points are scoped by the
+//! schema each test creates its table in, so the tests run concurrently.
+
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use servers::query_handler::sql::SqlQueryHandler;
+use session::context::QueryContext;
+
+use crate::error::Result;
+use crate::tests::{self, MockDistributedInstance};
+
+async fn execute(instance: &MockDistributedInstance, sql: &str) -> Result<Output> {
+    SqlQueryHandler::do_query(&*instance.frontend, sql, QueryContext::arc())
+        .await
+        .remove(0)
+}
+
+async fn select(instance: &MockDistributedInstance, sql: &str) -> Result<String> {
+    let batches = match execute(instance, sql).await? {
+        Output::Stream(stream) => RecordBatches::try_collect(stream).await.unwrap(),
+        Output::RecordBatches(batches) => batches,
+        Output::AffectedRows(_) => unreachable!(),
+    };
+    Ok(batches.pretty_print().unwrap())
+}
+
+async fn create_demo_table(instance: &MockDistributedInstance, schema: &str) -> Result<Output> {
+    execute(
+        instance,
+        &format!(
+            "CREATE TABLE {schema}.demo (
+            host STRING,
+            cpu DOUBLE,
+            ts TIMESTAMP,
+            TIME INDEX (ts),
+            PRIMARY KEY (host),
+        )
+        PARTITION BY RANGE COLUMNS (host) (
+            PARTITION r0 VALUES LESS THAN ('host2'),
+            PARTITION r1 VALUES LESS THAN (MAXVALUE),
+        )
+        ENGINE=mito"
+        ),
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_datanode_rpc_failure() {
+    common_telemetry::init_default_ut_logging();
+    let schema = "datanode_rpc_failure";
+
+    let instance = tests::create_distributed_instance("test_datanode_rpc_failure").await;
+    execute(&instance, &format!("CREATE DATABASE {schema}"))
+        .await
+        .unwrap();
+    create_demo_table(&instance, schema).await.unwrap();
+
+    fail::cfg("client_do_get", &format!("return({schema})")).unwrap();
+    let insert =
+        format!("INSERT INTO {schema}.demo VALUES ('host1', 1.0, 1000), ('host3', 3.0, 1000)");
+    assert!(execute(&instance, &insert).await.is_err());
+    let sql = format!("SELECT * FROM {schema}.demo");
+    assert!(select(&instance, &sql).await.is_err());
+    fail::remove("client_do_get");
+
+    let insert =
+        format!("INSERT INTO {schema}.demo VALUES ('host1', 1.1, 2000), ('host3', 3.1, 2000)");
+    assert!(matches!(
+        execute(&instance, &insert).await.unwrap(),
+        Output::AffectedRows(2)
+    ));
+
+    // A transient failure only fails the request it happens in.
+    fail::cfg("client_do_get", &format!("return({schema})")).unwrap();
+    let sql = format!("SELECT host, cpu, ts FROM {schema}.demo ORDER BY host");
+    assert!(select(&instance, &sql).await.is_err());
+    fail::remove("client_do_get");
+    let expected = "\
++-------+-----+---------------------+
+| host  | cpu | ts                  |
++-------+-----+---------------------+
+| host1 | 1.1 | 1970-01-01T00:00:02 |
+| host3 | 3.1 | 1970-01-01T00:00:02 |
++-------+-----+---------------------+";
+    assert_eq!(expected, select(&instance, &sql).await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_meta_rpc_failure() {
+    common_telemetry::init_default_ut_logging();
+    let schema = "meta_rpc_failure";
+
+    let instance = tests::create_distributed_instance("test_meta_rpc_failure").await;
+    execute(&instance, &format!("CREATE DATABASE {schema}"))
+        .await
+        .unwrap();
+
+    // Nothing is created if meta fails to allocate routes for the table.
+    fail::cfg("meta_client_create_route", &format!("return({schema})")).unwrap();
+    assert!(create_demo_table(&instance, schema).await.is_err());
+    fail::remove("meta_client_create_route");
+    let sql = format!("SELECT * FROM {schema}.demo");
+    assert!(select(&instance, &sql).await.is_err());
+
+    create_demo_table(&instance, schema).await.unwrap();
+    let insert =
+        format!("INSERT INTO {schema}.demo VALUES ('host1', 1.0, 1000), ('host3', 3.0, 1000)");
+    assert!(matches!(
+        execute(&instance, &insert).await.unwrap(),
+        Output::AffectedRows(2)
+    ));
+    let expected = "\
++-------+-----+---------------------+
+| host  | cpu | ts                  |
++-------+-----+---------------------+
+| host1 | 1.0 | 1970-01-01T00:00:01 |
+| host3 | 3.0 | 1970-01-01T00:00:01 |
++-------+-----+---------------------+";
+    let sql = format!("SELECT host, cpu, ts FROM {schema}.demo ORDER BY host");
+    assert_eq!(expected, select(&instance, &sql).await.unwrap());
+}
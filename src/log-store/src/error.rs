@@ -61,6 +61,9 @@ pub enum Error {
         source: raft_engine::Error,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Injected failure at {}", name))]
+    InjectedFailure { name: String, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
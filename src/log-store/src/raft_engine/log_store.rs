@@ -73,6 +73,12 @@ impl RaftEngineLogStore {
         self.started.load(Ordering::Relaxed)
     }
 
+    /// Returns whether the namespace has entries not obsoleted yet, i.e. the region is
+    /// written after its last flush.
+    pub fn has_entries(&self, namespace_id: u64) -> bool {
+        self.engine.first_index(namespace_id).is_some()
+    }
+
     async fn start(&self) -> Result<(), Error> {
         let engine_clone = self.engine.clone();
         let interval = self.config.purge_interval;
@@ -109,6 +115,20 @@ impl RaftEngineLogStore {
         info!("RaftEngineLogStore started with config: {:?}", self.config);
         Ok(())
     }
+
+    fn append_entry(&self, e: Entry, sync: bool) -> Result<AppendResponse, Error> {
+        ensure!(self.started(), IllegalStateSnafu);
+        let entry_id = e.id;
+        let mut batch = LogBatch::with_capacity(1);
+        batch
+            .add_entries::<MessageType>(e.namespace_id, &[e])
+            .context(AddEntryLogBatchSnafu)?;
+
+        self.engine
+            .write(&mut batch, sync)
+            .context(RaftEngineSnafu)?;
+        Ok(AppendResponse { entry_id })
+    }
 }
 
 impl Debug for RaftEngineLogStore {
@@ -153,17 +173,16 @@ impl LogStore for RaftEngineLogStore {
 
     /// Append an entry to logstore. Currently of existence of entry's namespace is not checked.
     async fn append(&self, e: Self::Entry) -> Result<AppendResponse, Self::Error> {
-        ensure!(self.started(), IllegalStateSnafu);
-        let entry_id = e.id;
-        let mut batch = LogBatch::with_capacity(1);
-        batch
-            .add_entries::<MessageType>(e.namespace_id, &[e])
-            .context(AddEntryLogBatchSnafu)?;
+        self.append_entry(e, self.config.sync_write)
+    }
 
-        self.engine
-            .write(&mut batch, self.config.sync_write)
-            .context(RaftEngineSnafu)?;
-        Ok(AppendResponse { entry_id })
+    async fn append_unsynced(&self, e: Self::Entry) -> Result<AppendResponse, Self::Error> {
+        self.append_entry(e, false)
+    }
+
+    async fn sync(&self) -> Result<(), Self::Error> {
+        ensure!(self.started(), IllegalStateSnafu);
+        self.engine.sync().context(RaftEngineSnafu)
     }
 
     /// Append a batch of entries to logstore. `RaftEngineLogStore` assures the atomicity of
@@ -311,6 +330,14 @@ impl LogStore for RaftEngineLogStore {
 
     async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<(), Self::Error> {
         ensure!(self.started(), IllegalStateSnafu);
+        // Scoped by the namespace id.
+        #[cfg(feature = "failpoints")]
+        if common_base::failpoint::triggered("wal_compact", &namespace.id().to_string()) {
+            return crate::error::InjectedFailureSnafu {
+                name: "wal_compact",
+            }
+            .fail();
+        }
         let obsoleted = self.engine.compact_to(namespace.id(), id + 1);
         info!(
             "Namespace {} obsoleted {} entries",
@@ -424,6 +451,38 @@ mod tests {
         assert_eq!((0..cnt).into_iter().collect::<HashSet<_>>(), entries);
     }
 
+    #[tokio::test]
+    async fn test_append_unsynced_and_sync() {
+        let dir = TempDir::new("raft-engine-logstore-test").unwrap();
+        let logstore = RaftEngineLogStore::try_new(LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            sync_write: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        logstore.start().await.unwrap();
+
+        let namespace = Namespace::with_id(1);
+        for i in 0..10 {
+            let response = logstore
+                .append_unsynced(Entry::create(i, namespace.id, vec![i as u8]))
+                .await
+                .unwrap();
+            assert_eq!(i, response.entry_id);
+        }
+        logstore.sync().await.unwrap();
+
+        let entries = collect_entries(logstore.read(&namespace, 0).await.unwrap()).await;
+        assert_eq!(
+            (0..10).collect::<Vec<_>>(),
+            entries.iter().map(|e| e.id).collect::<Vec<_>>()
+        );
+
+        logstore.stop().await.unwrap();
+        assert!(logstore.sync().await.is_err());
+    }
+
     async fn collect_entries(mut s: SendableEntryStream<'_, Entry, Error>) -> Vec<Entry> {
         let mut res = vec![];
         while let Some(r) = s.next().await {
@@ -542,12 +601,52 @@ mod tests {
             logstore.append(entry).await.unwrap();
         }
 
+        assert!(!logstore.has_entries(43));
+        assert!(logstore.has_entries(namespace.id));
         logstore.obsolete(namespace.clone(), 100).await.unwrap();
         assert_eq!(101, logstore.engine.first_index(namespace.id).unwrap());
+        assert!(logstore.has_entries(namespace.id));
 
         let res = logstore.read(&namespace, 100).await.unwrap();
         let mut vec = collect_entries(res).await;
         vec.sort_by(|a, b| a.id.partial_cmp(&b.id).unwrap());
         assert_eq!(101, vec.first().unwrap().id);
+
+        logstore.obsolete(namespace.clone(), 1023).await.unwrap();
+        assert!(!logstore.has_entries(namespace.id));
+    }
+
+    /// Tests injecting failures into the log store, the fail points are scoped by the
+    /// namespace ids.
+    #[cfg(feature = "failpoints")]
+    mod failpoint {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_compact_failure() {
+            common_telemetry::init_default_ut_logging();
+            let dir = TempDir::new("raft-engine-logstore-compact-failure").unwrap();
+            let config = LogConfig {
+                log_file_dir: dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            };
+            let logstore = RaftEngineLogStore::try_new(config).await.unwrap();
+            logstore.start().await.unwrap();
+            let namespace = Namespace::with_id(4242);
+            for id in 0..16 {
+                let entry = Entry::create(id, namespace.id(), [b'x'; 16].to_vec());
+                logstore.append(entry).await.unwrap();
+            }
+
+            // Entries are kept if the compaction fails, so the next one compacts them.
+            fail::cfg("wal_compact", "return(4242)").unwrap();
+            let err = logstore.obsolete(namespace.clone(), 7).await.unwrap_err();
+            assert!(matches!(err, Error::InjectedFailure { .. }));
+            fail::remove("wal_compact");
+            assert_eq!(0, logstore.engine.first_index(namespace.id).unwrap());
+
+            logstore.obsolete(namespace.clone(), 7).await.unwrap();
+            assert_eq!(8, logstore.engine.first_index(namespace.id).unwrap());
+        }
     }
 }
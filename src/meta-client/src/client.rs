@@ -14,17 +14,22 @@
 
 mod heartbeat;
 mod load_balance;
+mod retry;
 mod router;
 mod store;
 
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::token::AttachTokenInterceptor;
 use common_telemetry::info;
 use heartbeat::Client as HeartbeatClient;
 use router::Client as RouterClient;
 use snafu::OptionExt;
 use store::Client as StoreClient;
+use tonic::codegen::InterceptedService;
+use tonic::transport::Channel;
 
-pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
+pub use self::heartbeat::{HeartbeatOptions, HeartbeatSender, HeartbeatStream};
+pub use self::retry::RetryOptions;
 use crate::error;
 use crate::error::Result;
 use crate::rpc::router::DeleteRequest;
@@ -36,6 +41,9 @@ use crate::rpc::{
 
 pub type Id = (u64, u64);
 
+/// Channel to metasrv attaching the cluster token to requests.
+type TokenChannel = InterceptedService<Channel, AttachTokenInterceptor>;
+
 #[derive(Clone, Debug, Default)]
 pub struct MetaClientBuilder {
     id: Id,
@@ -43,6 +51,8 @@ pub struct MetaClientBuilder {
     enable_router: bool,
     enable_store: bool,
     channel_manager: Option<ChannelManager>,
+    heartbeat_options: HeartbeatOptions,
+    retry_options: RetryOptions,
 }
 
 impl MetaClientBuilder {
@@ -81,6 +91,20 @@ impl MetaClientBuilder {
         }
     }
 
+    pub fn heartbeat_options(self, heartbeat_options: HeartbeatOptions) -> Self {
+        Self {
+            heartbeat_options,
+            ..self
+        }
+    }
+
+    pub fn retry_options(self, retry_options: RetryOptions) -> Self {
+        Self {
+            retry_options,
+            ..self
+        }
+    }
+
     pub fn build(self) -> MetaClient {
         let mut client = if let Some(mgr) = self.channel_manager {
             MetaClient::with_channel_manager(self.id, mgr)
@@ -97,13 +121,21 @@ impl MetaClientBuilder {
         let mgr = client.channel_manager.clone();
 
         if self.enable_heartbeat {
-            client.heartbeat = Some(HeartbeatClient::new(self.id, mgr.clone()));
+            client.heartbeat = Some(HeartbeatClient::with_options(
+                self.id,
+                mgr.clone(),
+                self.heartbeat_options,
+            ));
         }
         if self.enable_router {
-            client.router = Some(RouterClient::new(self.id, mgr.clone()));
+            client.router = Some(RouterClient::with_options(
+                self.id,
+                mgr.clone(),
+                self.retry_options.clone(),
+            ));
         }
         if self.enable_store {
-            client.store = Some(StoreClient::new(self.id, mgr));
+            client.store = Some(StoreClient::with_options(self.id, mgr, self.retry_options));
         }
 
         client
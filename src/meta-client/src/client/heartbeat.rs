@@ -14,18 +14,21 @@
 
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::meta::heartbeat_client::HeartbeatClient;
 use api::v1::meta::{AskLeaderRequest, HeartbeatRequest, HeartbeatResponse, RequestHeader};
+#[cfg(feature = "failpoints")]
+use common_base::failpoint;
 use common_grpc::channel_manager::ChannelManager;
-use common_telemetry::{debug, info};
+use common_telemetry::{debug, info, warn};
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::{mpsc, RwLock};
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::transport::Channel;
 use tonic::Streaming;
 
-use crate::client::Id;
+use crate::client::retry::Backoff;
+use crate::client::{Id, TokenChannel};
 use crate::error;
 use crate::error::Result;
 use crate::rpc::util;
@@ -48,6 +51,14 @@ impl HeartbeatSender {
 
     #[inline]
     pub async fn send(&self, mut req: HeartbeatRequest) -> Result<()> {
+        // Scoped by the member id of the client, i.e. the node id.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("meta_client_send_heartbeat", &self.id.1.to_string()) {
+            return error::SendHeartbeatSnafu {
+                err_msg: "Injected failure at meta_client_send_heartbeat",
+            }
+            .fail();
+        }
         req.set_header(self.id);
         self.sender.send(req).await.map_err(|e| {
             error::SendHeartbeatSnafu {
@@ -61,13 +72,13 @@ impl HeartbeatSender {
 #[derive(Debug)]
 pub struct HeartbeatStream {
     id: Id,
-    stream: Streaming<HeartbeatResponse>,
+    receiver: mpsc::Receiver<HeartbeatResponse>,
 }
 
 impl HeartbeatStream {
     #[inline]
-    fn new(id: Id, stream: Streaming<HeartbeatResponse>) -> Self {
-        Self { id, stream }
+    fn new(id: Id, receiver: mpsc::Receiver<HeartbeatResponse>) -> Self {
+        Self { id, receiver }
     }
 
     #[inline]
@@ -75,24 +86,55 @@ impl HeartbeatStream {
         self.id
     }
 
-    /// Fetch the next message from this stream.
+    /// Fetch the next message from this stream, returns `None` if the stream is closed.
+    ///
+    /// The stream to metasrv is reconnected in background when broken, so errors of the
+    /// connection are not returned.
     #[inline]
     pub async fn message(&mut self) -> Result<Option<HeartbeatResponse>> {
-        let res = self.stream.message().await.context(error::TonicStatusSnafu);
-        if let Ok(Some(heartbeat)) = &res {
+        let res = self.receiver.recv().await;
+        if let Some(heartbeat) = &res {
             util::check_response_header(heartbeat.header.as_ref())?;
         }
-        res
+        Ok(res)
+    }
+}
+
+/// Options of the heartbeat stream, which is reconnected to the leader of metasrv when
+/// broken.
+#[derive(Clone, Debug)]
+pub struct HeartbeatOptions {
+    /// Delay before the first attempt to reconnect, doubled after each failed attempt.
+    pub reconnect_backoff_base: Duration,
+    /// Max delay between attempts to reconnect.
+    pub reconnect_backoff_max: Duration,
+}
+
+impl Default for HeartbeatOptions {
+    fn default() -> Self {
+        Self {
+            reconnect_backoff_base: Duration::from_millis(500),
+            reconnect_backoff_max: Duration::from_secs(30),
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: Arc<RwLock<Inner>>,
+    options: HeartbeatOptions,
 }
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_options(id, channel_manager, HeartbeatOptions::default())
+    }
+
+    pub fn with_options(
+        id: Id,
+        channel_manager: ChannelManager,
+        options: HeartbeatOptions,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
@@ -100,7 +142,7 @@ impl Client {
             leader: None,
         }));
 
-        Self { inner }
+        Self { inner, options }
     }
 
     pub async fn start<U, A>(&mut self, urls: A) -> Result<()>
@@ -117,10 +159,30 @@ impl Client {
         inner.ask_leader().await
     }
 
+    /// Creates the heartbeat stream to the leader of metasrv, which is kept alive by a
+    /// background task until the returned sender or stream is dropped.
     pub async fn heartbeat(&mut self) -> Result<(HeartbeatSender, HeartbeatStream)> {
-        let mut inner = self.inner.write().await;
-        inner.ask_leader().await?;
-        inner.heartbeat().await
+        let (id, connection) = {
+            let mut inner = self.inner.write().await;
+            inner.ask_leader().await?;
+            (inner.id, inner.connect().await?)
+        };
+
+        let (request_sender, requests) = mpsc::channel(16);
+        let (responses, response_receiver) = mpsc::channel(128);
+        let keeper = StreamKeeper {
+            inner: self.inner.clone(),
+            options: self.options.clone(),
+            requests,
+            responses,
+            pending: None,
+        };
+        let _handle = tokio::spawn(keeper.run(connection));
+
+        Ok((
+            HeartbeatSender::new(id, request_sender),
+            HeartbeatStream::new(id, response_receiver),
+        ))
     }
 
     pub async fn is_started(&self) -> bool {
@@ -190,7 +252,8 @@ impl Inner {
         Ok(())
     }
 
-    async fn heartbeat(&self) -> Result<(HeartbeatSender, HeartbeatStream)> {
+    /// Connects the heartbeat stream to the leader.
+    async fn connect(&self) -> Result<Connection> {
         let leader = self.leader.as_ref().context(error::NoLeaderSnafu)?;
         let mut leader = self.make_client(leader)?;
 
@@ -220,19 +283,19 @@ impl Inner {
             .context(error::CreateHeartbeatStreamSnafu)?;
         info!("Success to create heartbeat stream to server: {:#?}", res);
 
-        Ok((
-            HeartbeatSender::new(self.id, sender),
-            HeartbeatStream::new(self.id, stream),
-        ))
+        Ok((sender, stream))
     }
 
-    fn make_client(&self, addr: impl AsRef<str>) -> Result<HeartbeatClient<Channel>> {
+    fn make_client(&self, addr: impl AsRef<str>) -> Result<HeartbeatClient<TokenChannel>> {
         let channel = self
             .channel_manager
             .get(addr)
             .context(error::CreateChannelSnafu)?;
 
-        Ok(HeartbeatClient::new(channel))
+        Ok(HeartbeatClient::with_interceptor(
+            channel,
+            self.channel_manager.token_interceptor(),
+        ))
     }
 
     #[inline]
@@ -241,8 +304,120 @@ impl Inner {
     }
 }
 
+/// The (request sender, response stream) of a heartbeat stream to metasrv.
+type Connection = (mpsc::Sender<HeartbeatRequest>, Streaming<HeartbeatResponse>);
+
+/// Forwards heartbeats between the [HeartbeatSender]/[HeartbeatStream] and the stream to
+/// the leader of metasrv, and reconnects the stream with exponential backoff when broken.
+struct StreamKeeper {
+    inner: Arc<RwLock<Inner>>,
+    options: HeartbeatOptions,
+    requests: mpsc::Receiver<HeartbeatRequest>,
+    responses: mpsc::Sender<HeartbeatResponse>,
+    /// The latest request not sent to metasrv yet. A heartbeat reports the whole state of
+    /// the node, so only the latest one is kept while reconnecting, older ones are stale.
+    pending: Option<HeartbeatRequest>,
+}
+
+impl StreamKeeper {
+    async fn run(mut self, mut connection: Connection) {
+        while self.forward(&mut connection).await {
+            match self.reconnect().await {
+                Some(new_connection) => connection = new_connection,
+                None => break,
+            }
+        }
+        debug!("Heartbeat stream keeper exit");
+    }
+
+    /// Forwards requests and responses until the stream is broken. Returns false if the
+    /// [HeartbeatSender] or [HeartbeatStream] is dropped.
+    async fn forward(&mut self, (sender, stream): &mut Connection) -> bool {
+        if let Some(req) = self.pending.take() {
+            if let Err(e) = sender.send(req).await {
+                self.pending = Some(e.0);
+                return true;
+            }
+        }
+        loop {
+            tokio::select! {
+                req = self.requests.recv() => {
+                    let Some(req) = req else {
+                        return false;
+                    };
+                    if let Err(e) = sender.send(req).await {
+                        self.pending = Some(e.0);
+                        return true;
+                    }
+                }
+                res = stream.message() => match res {
+                    Ok(Some(res)) if res.is_not_leader() => {
+                        // Metasrv closes the stream after telling it is no longer the
+                        // leader, the leader is discovered again on reconnecting.
+                        warn!("Heartbeat stream is connected to a follower of metasrv");
+                        return true;
+                    }
+                    Ok(Some(res)) => {
+                        if self.responses.send(res).await.is_err() {
+                            return false;
+                        }
+                    }
+                    Ok(None) => {
+                        warn!("Heartbeat stream is closed by metasrv");
+                        return true;
+                    }
+                    Err(status) => {
+                        warn!("Heartbeat stream is broken: {}", status);
+                        return true;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Reconnects to the leader of metasrv, which is discovered again before each attempt
+    /// since the leader may change. Returns `None` if the [HeartbeatSender] is dropped.
+    async fn reconnect(&mut self) -> Option<Connection> {
+        let mut backoff = Backoff::new(
+            self.options.reconnect_backoff_base,
+            self.options.reconnect_backoff_max,
+        );
+        loop {
+            let delay = tokio::time::sleep(backoff.next_delay());
+            tokio::pin!(delay);
+            loop {
+                tokio::select! {
+                    _ = &mut delay => break,
+                    req = self.requests.recv() => match req {
+                        Some(req) => self.pending = Some(req),
+                        None => return None,
+                    },
+                }
+            }
+
+            let connection = {
+                let mut inner = self.inner.write().await;
+                match inner.ask_leader().await {
+                    Ok(()) => inner.connect().await,
+                    Err(e) => Err(e),
+                }
+            };
+            match connection {
+                Ok(connection) => {
+                    info!("Heartbeat stream is reconnected to metasrv");
+                    return Some(connection);
+                }
+                Err(e) => warn!("Failed to reconnect heartbeat stream to metasrv: {}", e),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use meta_srv::metasrv::MetaSrvOptions;
+    use meta_srv::mocks::{self as server_mock, MockElection, MockInfo};
+
     use super::*;
 
     #[tokio::test]
@@ -282,6 +457,51 @@ mod test {
         assert_eq!(1, client.inner.write().await.peers.len());
     }
 
+    #[tokio::test]
+    async fn test_reconnect_heartbeat_stream() {
+        let election = Arc::new(MockElection::new(MetaSrvOptions::default().server_addr));
+        let MockInfo {
+            server_addr,
+            channel_manager,
+        } = server_mock::mock_with_election(election.clone()).await;
+        let options = HeartbeatOptions {
+            reconnect_backoff_base: Duration::from_millis(10),
+            reconnect_backoff_max: Duration::from_millis(10),
+        };
+        let mut client = Client::with_options((1000, 2000), channel_manager, options);
+        client.start(&[&server_addr]).await.unwrap();
+        let (sender, mut stream) = client.heartbeat().await.unwrap();
+
+        // Heartbeats sent to a broken stream may be lost, so keep sending until one is
+        // answered.
+        async fn heartbeat(
+            sender: &HeartbeatSender,
+            stream: &mut HeartbeatStream,
+        ) -> HeartbeatResponse {
+            for _ in 0..50 {
+                sender.send(HeartbeatRequest::default()).await.unwrap();
+                let res = tokio::time::timeout(Duration::from_millis(100), stream.message());
+                if let Ok(res) = res.await {
+                    return res.unwrap().unwrap();
+                }
+            }
+            panic!("Heartbeat stream is not reconnected");
+        }
+        assert!(!heartbeat(&sender, &mut stream).await.is_not_leader());
+
+        // Metasrv closes the stream once it is no longer the leader, the stream is
+        // reconnected after it becomes the leader again.
+        election.set_leader(false);
+        sender.send(HeartbeatRequest::default()).await.unwrap();
+        let res = tokio::time::timeout(Duration::from_millis(100), stream.message()).await;
+        assert!(
+            res.is_err(),
+            "responses of followers should not be forwarded"
+        );
+        election.set_leader(true);
+        assert!(!heartbeat(&sender, &mut stream).await.is_not_leader());
+    }
+
     #[tokio::test]
     async fn test_heartbeat_stream() {
         let (sender, mut receiver) = mpsc::channel::<HeartbeatRequest>(100);
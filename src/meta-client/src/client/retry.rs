@@ -0,0 +1,157 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::time::Duration;
+
+use common_telemetry::warn;
+
+use crate::error::Result;
+
+/// Options of retrying requests to metasrv that fail because metasrv is unreachable,
+/// e.g. during a restart or a change of the leader.
+#[derive(Clone, Debug)]
+pub struct RetryOptions {
+    /// Max number of retries of a request, `0` disables retrying.
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled after each failed retry.
+    pub backoff_base: Duration,
+    /// Max delay between retries.
+    pub backoff_max: Duration,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff_base: Duration::from_millis(500),
+            backoff_max: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Runs `op` until it succeeds, fails with an error that is not retryable, or runs out
+/// of retries. Every attempt calls `op` again, so it picks the peer to send to again.
+pub(crate) async fn retry<T, F, Fut>(options: &RetryOptions, name: &str, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut backoff = Backoff::new(options.backoff_base, options.backoff_max);
+    let mut retries = 0;
+    loop {
+        match op().await {
+            Err(e) if e.is_retryable() && retries < options.max_retries => {
+                retries += 1;
+                let delay = backoff.next_delay();
+                warn!("Failed to {name}, retry {retries} after {delay:?}, error: {e}");
+                tokio::time::sleep(delay).await;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Exponential backoff between attempts.
+pub(crate) struct Backoff {
+    base: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub(crate) fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            next: base,
+        }
+    }
+
+    /// Returns the delay before the next attempt.
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = self.next.checked_mul(2).unwrap_or(self.max).min(self.max);
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use snafu::ResultExt;
+
+    use super::*;
+    use crate::error;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(500));
+        let delays = (0..5).map(|_| backoff.next_delay()).collect::<Vec<_>>();
+        assert_eq!(
+            [100, 200, 400, 500, 500]
+                .map(Duration::from_millis)
+                .to_vec(),
+            delays
+        );
+        assert_eq!(Duration::from_millis(100), backoff.base);
+    }
+
+    fn fail_with(code: tonic::Code) -> Result<()> {
+        Err(tonic::Status::new(code, "mock")).context(error::TonicStatusSnafu)
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let options = RetryOptions {
+            max_retries: 2,
+            backoff_base: Duration::from_millis(1),
+            backoff_max: Duration::from_millis(1),
+        };
+
+        // Succeeds once metasrv is reachable again.
+        let attempts = AtomicUsize::new(0);
+        let res = retry(&options, "mock", || async {
+            if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+                fail_with(tonic::Code::Unavailable)
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert!(res.is_ok());
+        assert_eq!(2, attempts.load(Ordering::Relaxed));
+
+        // Gives up after the max retries.
+        let attempts = AtomicUsize::new(0);
+        let res = retry(&options, "mock", || async {
+            let _ = attempts.fetch_add(1, Ordering::Relaxed);
+            fail_with(tonic::Code::Unavailable)
+        })
+        .await;
+        assert!(res.is_err());
+        assert_eq!(3, attempts.load(Ordering::Relaxed));
+
+        // Errors returned by metasrv are not retried.
+        let attempts = AtomicUsize::new(0);
+        let res = retry(&options, "mock", || async {
+            let _ = attempts.fetch_add(1, Ordering::Relaxed);
+            fail_with(tonic::Code::InvalidArgument)
+        })
+        .await;
+        assert!(res.is_err());
+        assert_eq!(1, attempts.load(Ordering::Relaxed));
+    }
+}
@@ -13,33 +13,52 @@
 // limitations under the License.
 
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use api::v1::meta::heartbeat_client::HeartbeatClient;
 use api::v1::meta::router_client::RouterClient;
-use api::v1::meta::{CreateRequest, DeleteRequest, RouteRequest, RouteResponse};
+use api::v1::meta::{
+    AskLeaderRequest, CreateRequest, DeleteRequest, RequestHeader, RouteRequest, RouteResponse,
+};
+#[cfg(feature = "failpoints")]
+use common_base::failpoint;
 use common_grpc::channel_manager::ChannelManager;
+use common_telemetry::debug;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
-use tonic::transport::Channel;
 
-use crate::client::{load_balance as lb, Id};
+use crate::client::retry::{self, RetryOptions};
+use crate::client::{load_balance as lb, Id, TokenChannel};
 use crate::error;
 use crate::error::Result;
 
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: Arc<RwLock<Inner>>,
+    retry_options: RetryOptions,
 }
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_options(id, channel_manager, RetryOptions::default())
+    }
+
+    pub fn with_options(
+        id: Id,
+        channel_manager: ChannelManager,
+        retry_options: RetryOptions,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
             peers: vec![],
+            leader: Mutex::new(None),
         }));
 
-        Self { inner }
+        Self {
+            inner,
+            retry_options,
+        }
     }
 
     pub async fn start<U, A>(&mut self, urls: A) -> Result<()>
@@ -58,17 +77,26 @@ impl Client {
 
     pub async fn create(&self, req: CreateRequest) -> Result<RouteResponse> {
         let inner = self.inner.read().await;
-        inner.create(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "create route", || {
+            inner.create(req.clone())
+        })
+        .await
     }
 
     pub async fn route(&self, req: RouteRequest) -> Result<RouteResponse> {
         let inner = self.inner.read().await;
-        inner.route(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "route", || inner.route(req.clone())).await
     }
 
     pub async fn delete(&self, req: DeleteRequest) -> Result<RouteResponse> {
         let inner = self.inner.read().await;
-        inner.delete(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "delete route", || {
+            inner.delete(req.clone())
+        })
+        .await
     }
 }
 
@@ -77,6 +105,9 @@ struct Inner {
     id: Id,
     channel_manager: ChannelManager,
     peers: Vec<String>,
+    /// Address of the leader of metasrv, asked on demand and forgotten once a request to
+    /// it fails retryably.
+    leader: Mutex<Option<String>>,
 }
 
 impl Inner {
@@ -104,14 +135,36 @@ impl Inner {
     }
 
     async fn create(&self, mut req: CreateRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
+        // Scoped by the schema of the table.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered_by("meta_client_create_route", |schema| {
+            req.table_name
+                .as_ref()
+                .map_or(false, |t| t.schema_name == schema)
+        }) {
+            return Err(tonic::Status::unavailable(
+                "Injected failure at meta_client_create_route",
+            ))
+            .context(error::TonicStatusSnafu);
+        }
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client.create(req).await.context(error::TonicStatusSnafu)?;
+        let res = client.create(req).await.context(error::TonicStatusSnafu);
 
-        Ok(res.into_inner())
+        self.check_leader(res.map(|res| res.into_inner()))
     }
 
     async fn route(&self, mut req: RouteRequest) -> Result<RouteResponse> {
+        // Scoped by the schema of the tables.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered_by("meta_client_route", |schema| {
+            req.table_names.iter().any(|t| t.schema_name == schema)
+        }) {
+            return Err(tonic::Status::unavailable(
+                "Injected failure at meta_client_route",
+            ))
+            .context(error::TonicStatusSnafu);
+        }
         let mut client = self.random_client()?;
         req.set_header(self.id);
         let res = client.route(req).await.context(error::TonicStatusSnafu)?;
@@ -120,14 +173,14 @@ impl Inner {
     }
 
     async fn delete(&self, mut req: DeleteRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client.delete(req).await.context(error::TonicStatusSnafu)?;
+        let res = client.delete(req).await.context(error::TonicStatusSnafu);
 
-        Ok(res.into_inner())
+        self.check_leader(res.map(|res| res.into_inner()))
     }
 
-    fn random_client(&self) -> Result<RouterClient<Channel>> {
+    fn random_client(&self) -> Result<RouterClient<TokenChannel>> {
         let len = self.peers.len();
         let peer = lb::random_get(len, |i| Some(&self.peers[i])).context(
             error::IllegalGrpcClientStateSnafu {
@@ -138,13 +191,69 @@ impl Inner {
         self.make_client(peer)
     }
 
-    fn make_client(&self, addr: impl AsRef<str>) -> Result<RouterClient<Channel>> {
+    /// Returns the client to the leader of metasrv, which routes are only mutated by.
+    async fn leader_client(&self) -> Result<RouterClient<TokenChannel>> {
+        let leader = self.leader.lock().unwrap().clone();
+        let leader = match leader {
+            Some(leader) => leader,
+            None => {
+                let leader = self.ask_leader().await?;
+                *self.leader.lock().unwrap() = Some(leader.clone());
+                leader
+            }
+        };
+        self.make_client(leader)
+    }
+
+    /// Forgets the cached leader if `res` failed retryably, e.g. the leader is unreachable
+    /// or has lost the leadership, so the leader is asked again by the next attempt.
+    fn check_leader<T>(&self, res: Result<T>) -> Result<T> {
+        if let Err(e) = &res {
+            if e.is_retryable() {
+                let _ = self.leader.lock().unwrap().take();
+            }
+        }
+        res
+    }
+
+    /// Asks the peers for the address of the leader.
+    async fn ask_leader(&self) -> Result<String> {
+        for addr in &self.peers {
+            let channel = self
+                .channel_manager
+                .get(addr)
+                .context(error::CreateChannelSnafu)?;
+            let req = AskLeaderRequest {
+                header: Some(RequestHeader::new(self.id)),
+            };
+            let mut client = HeartbeatClient::with_interceptor(
+                channel,
+                self.channel_manager.token_interceptor(),
+            );
+            match client.ask_leader(req).await {
+                Ok(res) => {
+                    if let Some(leader) = res.into_inner().leader {
+                        return Ok(leader.addr);
+                    }
+                }
+                Err(status) => {
+                    debug!("Failed to ask leader from: {}, {}", addr, status);
+                }
+            }
+        }
+        error::AskLeaderSnafu.fail()
+    }
+
+    fn make_client(&self, addr: impl AsRef<str>) -> Result<RouterClient<TokenChannel>> {
         let channel = self
             .channel_manager
             .get(addr)
             .context(error::CreateChannelSnafu)?;
 
-        Ok(RouterClient::new(channel))
+        Ok(RouterClient::with_interceptor(
+            channel,
+            self.channel_manager.token_interceptor(),
+        ))
     }
 
     #[inline]
@@ -194,4 +303,25 @@ mod test {
 
         assert_eq!(1, client.inner.write().await.peers.len());
     }
+
+    #[tokio::test]
+    async fn test_forget_leader_on_retryable_error() {
+        let client = Client::new((0, 0), ChannelManager::default());
+        let inner = client.inner.read().await;
+        let fail_with = |code| -> Result<()> {
+            Err(tonic::Status::new(code, "mock")).context(error::TonicStatusSnafu)
+        };
+
+        *inner.leader.lock().unwrap() = Some("127.0.0.1:1000".to_string());
+        assert!(inner.check_leader(Ok(())).is_ok());
+        assert!(inner
+            .check_leader(fail_with(tonic::Code::InvalidArgument))
+            .is_err());
+        assert!(inner.leader.lock().unwrap().is_some());
+
+        assert!(inner
+            .check_leader(fail_with(tonic::Code::Unavailable))
+            .is_err());
+        assert!(inner.leader.lock().unwrap().is_none());
+    }
 }
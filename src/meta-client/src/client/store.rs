@@ -21,29 +21,43 @@ use api::v1::meta::{
     DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse, PutRequest,
     PutResponse, RangeRequest, RangeResponse,
 };
+#[cfg(feature = "failpoints")]
+use common_base::failpoint;
 use common_grpc::channel_manager::ChannelManager;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
-use tonic::transport::Channel;
 
-use crate::client::{load_balance as lb, Id};
+use crate::client::retry::{self, RetryOptions};
+use crate::client::{load_balance as lb, Id, TokenChannel};
 use crate::error;
 use crate::error::Result;
 
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: Arc<RwLock<Inner>>,
+    retry_options: RetryOptions,
 }
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_options(id, channel_manager, RetryOptions::default())
+    }
+
+    pub fn with_options(
+        id: Id,
+        channel_manager: ChannelManager,
+        retry_options: RetryOptions,
+    ) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
             channel_manager,
             peers: vec![],
         }));
 
-        Self { inner }
+        Self {
+            inner,
+            retry_options,
+        }
     }
 
     pub async fn start<U, A>(&mut self, urls: A) -> Result<()>
@@ -62,19 +76,27 @@ impl Client {
 
     pub async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
         let inner = self.inner.read().await;
-        inner.range(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "range", || inner.range(req.clone())).await
     }
 
     pub async fn put(&self, req: PutRequest) -> Result<PutResponse> {
         let inner = self.inner.read().await;
-        inner.put(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "put", || inner.put(req.clone())).await
     }
 
     pub async fn batch_put(&self, req: BatchPutRequest) -> Result<BatchPutResponse> {
         let inner = self.inner.read().await;
-        inner.batch_put(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "batch put", || {
+            inner.batch_put(req.clone())
+        })
+        .await
     }
 
+    /// Not retried as it may have been applied before the failure, retrying reports a
+    /// mismatch of the expected value then.
     pub async fn compare_and_put(
         &self,
         req: CompareAndPutRequest,
@@ -85,9 +107,15 @@ impl Client {
 
     pub async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
         let inner = self.inner.read().await;
-        inner.delete_range(req).await
+        let inner = &*inner;
+        retry::retry(&self.retry_options, "delete range", || {
+            inner.delete_range(req.clone())
+        })
+        .await
     }
 
+    /// Not retried as it may have been applied before the failure, retrying moves
+    /// nothing then.
     pub async fn move_value(&self, req: MoveValueRequest) -> Result<MoveValueResponse> {
         let inner = self.inner.read().await;
         inner.move_value(req).await
@@ -126,6 +154,16 @@ impl Inner {
     }
 
     async fn range(&self, mut req: RangeRequest) -> Result<RangeResponse> {
+        // Scoped by the prefix of the key.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered_by("meta_client_range", |prefix| {
+            req.key.starts_with(prefix.as_bytes())
+        }) {
+            return Err(tonic::Status::unavailable(
+                "Injected failure at meta_client_range",
+            ))
+            .context(error::TonicStatusSnafu);
+        }
         let mut client = self.random_client()?;
         req.set_header(self.id);
         let res = client.range(req).await.context(error::TonicStatusSnafu)?;
@@ -134,6 +172,16 @@ impl Inner {
     }
 
     async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
+        // Scoped by the prefix of the key.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered_by("meta_client_put", |prefix| {
+            req.key.starts_with(prefix.as_bytes())
+        }) {
+            return Err(tonic::Status::unavailable(
+                "Injected failure at meta_client_put",
+            ))
+            .context(error::TonicStatusSnafu);
+        }
         let mut client = self.random_client()?;
         req.set_header(self.id);
         let res = client.put(req).await.context(error::TonicStatusSnafu)?;
@@ -156,6 +204,16 @@ impl Inner {
         &self,
         mut req: CompareAndPutRequest,
     ) -> Result<CompareAndPutResponse> {
+        // Scoped by the prefix of the key.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered_by("meta_client_compare_and_put", |prefix| {
+            req.key.starts_with(prefix.as_bytes())
+        }) {
+            return Err(tonic::Status::unavailable(
+                "Injected failure at meta_client_compare_and_put",
+            ))
+            .context(error::TonicStatusSnafu);
+        }
         let mut client = self.random_client()?;
         req.set_header(self.id);
         let res = client
@@ -188,7 +246,7 @@ impl Inner {
         Ok(res.into_inner())
     }
 
-    fn random_client(&self) -> Result<StoreClient<Channel>> {
+    fn random_client(&self) -> Result<StoreClient<TokenChannel>> {
         let len = self.peers.len();
         let peer = lb::random_get(len, |i| Some(&self.peers[i])).context(
             error::IllegalGrpcClientStateSnafu {
@@ -199,13 +257,16 @@ impl Inner {
         self.make_client(peer)
     }
 
-    fn make_client(&self, addr: impl AsRef<str>) -> Result<StoreClient<Channel>> {
+    fn make_client(&self, addr: impl AsRef<str>) -> Result<StoreClient<TokenChannel>> {
         let channel = self
             .channel_manager
             .get(addr)
             .context(error::CreateChannelSnafu)?;
 
-        Ok(StoreClient::new(channel))
+        Ok(StoreClient::with_interceptor(
+            channel,
+            self.channel_manager.token_interceptor(),
+        ))
     }
 
     #[inline]
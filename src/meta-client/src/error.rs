@@ -77,6 +77,22 @@ pub enum Error {
 #[allow(dead_code)]
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Returns true if the request may succeed if retried, i.e. metasrv or its leader is
+    /// unreachable for now.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::TonicStatus { source, .. } => {
+                source.code() == tonic::Code::Unavailable
+                    || std::error::Error::source(source)
+                        .map_or(false, |e| e.is::<tonic::transport::Error>())
+            }
+            Error::AskLeader { .. } | Error::NoLeader { .. } => true,
+            _ => false,
+        }
+    }
+}
+
 impl ErrorExt for Error {
     fn backtrace_opt(&self) -> Option<&Backtrace> {
         ErrorCompat::backtrace(self)
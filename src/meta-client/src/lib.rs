@@ -12,8 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
+use common_grpc::channel_manager::ChannelConfig;
+use common_grpc::tls::GrpcTlsOption;
 use serde::{Deserialize, Serialize};
 
+use crate::client::{HeartbeatOptions, RetryOptions};
+
 pub mod client;
 pub mod error;
 #[cfg(test)]
@@ -22,11 +28,30 @@ pub mod rpc;
 
 // Options for meta client in datanode instance.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetaClientOpts {
     pub metasrv_addrs: Vec<String>,
     pub timeout_millis: u64,
     pub connect_timeout_millis: u64,
     pub tcp_nodelay: bool,
+    /// Interval of HTTP2 keep-alive pings to metasrv, detects dead connections even if
+    /// they are idle.
+    pub keep_alive_interval_millis: u64,
+    /// Timeout of HTTP2 keep-alive pings, the connection is closed if the ping is not
+    /// acknowledged in time.
+    pub keep_alive_timeout_millis: u64,
+    /// Interval of heartbeats sent to metasrv.
+    pub heartbeat_interval_millis: u64,
+    /// Delay before the first attempt to reconnect the broken heartbeat stream or to retry
+    /// a failed request, doubled after each failed attempt.
+    pub reconnect_backoff_base_millis: u64,
+    /// Max delay between attempts to reconnect the broken heartbeat stream or to retry a
+    /// failed request.
+    pub reconnect_backoff_max_millis: u64,
+    /// Max number of retries of a request that fails as metasrv is unreachable.
+    pub max_retries: usize,
+    /// Connects to metasrv with TLS if set.
+    pub tls: Option<GrpcTlsOption>,
 }
 
 impl Default for MetaClientOpts {
@@ -36,6 +61,47 @@ impl Default for MetaClientOpts {
             timeout_millis: 3_000u64,
             connect_timeout_millis: 5_000u64,
             tcp_nodelay: true,
+            keep_alive_interval_millis: 10_000u64,
+            keep_alive_timeout_millis: 20_000u64,
+            heartbeat_interval_millis: 5_000u64,
+            reconnect_backoff_base_millis: 500u64,
+            reconnect_backoff_max_millis: 30_000u64,
+            max_retries: 3,
+            tls: None,
+        }
+    }
+}
+
+impl MetaClientOpts {
+    /// Returns the config of channels to metasrv.
+    pub fn channel_config(&self) -> ChannelConfig {
+        let config = ChannelConfig::new()
+            .timeout(Duration::from_millis(self.timeout_millis))
+            .connect_timeout(Duration::from_millis(self.connect_timeout_millis))
+            .tcp_nodelay(self.tcp_nodelay)
+            .http2_keep_alive_interval(Duration::from_millis(self.keep_alive_interval_millis))
+            .http2_keep_alive_timeout(Duration::from_millis(self.keep_alive_timeout_millis))
+            .http2_keep_alive_while_idle(true);
+        match &self.tls {
+            Some(tls) => config.client_tls(tls.clone()),
+            None => config,
+        }
+    }
+
+    /// Returns the options of the heartbeat stream to metasrv.
+    pub fn heartbeat_options(&self) -> HeartbeatOptions {
+        HeartbeatOptions {
+            reconnect_backoff_base: Duration::from_millis(self.reconnect_backoff_base_millis),
+            reconnect_backoff_max: Duration::from_millis(self.reconnect_backoff_max_millis),
+        }
+    }
+
+    /// Returns the options of retrying requests to metasrv.
+    pub fn retry_options(&self) -> RetryOptions {
+        RetryOptions {
+            max_retries: self.max_retries,
+            backoff_base: Duration::from_millis(self.reconnect_backoff_base_millis),
+            backoff_max: Duration::from_millis(self.reconnect_backoff_max_millis),
         }
     }
 }
@@ -30,6 +30,7 @@ use crate::rpc::{util, Peer, TableName};
 pub struct CreateRequest {
     pub table_name: TableName,
     pub partitions: Vec<Partition>,
+    pub dry_run: bool,
 }
 
 impl From<CreateRequest> for PbCreateRequest {
@@ -38,6 +39,7 @@ impl From<CreateRequest> for PbCreateRequest {
             header: None,
             table_name: Some(req.table_name.into()),
             partitions: req.partitions.drain(..).map(Into::into).collect(),
+            dry_run: req.dry_run,
         }
     }
 }
@@ -48,9 +50,17 @@ impl CreateRequest {
         Self {
             table_name,
             partitions: vec![],
+            dry_run: false,
         }
     }
 
+    /// Only previews the table route, nothing is saved in meta.
+    #[inline]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
     #[inline]
     pub fn add_partition(mut self, partition: Partition) -> Self {
         self.partitions.push(partition);
@@ -319,11 +329,13 @@ mod tests {
                     value_list: vec![b"v11".to_vec(), b"v22".to_vec()],
                 },
             ],
+            dry_run: true,
         };
 
         let into_req: PbCreateRequest = req.into();
 
         assert!(into_req.header.is_none());
+        assert!(into_req.dry_run);
         let table_name = into_req.table_name;
         assert_eq!("c1", table_name.as_ref().unwrap().catalog_name);
         assert_eq!("s1", table_name.as_ref().unwrap().schema_name);
@@ -17,13 +17,17 @@ use std::sync::Arc;
 use api::v1::meta::heartbeat_server::HeartbeatServer;
 use api::v1::meta::router_server::RouterServer;
 use api::v1::meta::store_server::StoreServer;
+use common_grpc::tls;
+use common_grpc::token::VerifyTokenInterceptor;
 use snafu::ResultExt;
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
+use tonic::codegen::InterceptedService;
 use tonic::transport::server::Router;
 
 use crate::election::etcd::EtcdElection;
 use crate::metasrv::{MetaSrv, MetaSrvOptions};
+use crate::selector::build_selector;
 use crate::service::admin;
 use crate::service::store::etcd::EtcdStore;
 use crate::service::store::memory::MemStore;
@@ -41,23 +45,44 @@ pub async fn bootstrap_meta_srv_with_router(opts: MetaSrvOptions, router: Router
         .context(error::TcpBindSnafu {
             addr: &opts.bind_addr,
         })?;
-    let listener = TcpListenerStream::new(listener);
-
-    router
-        .serve_with_incoming(listener)
-        .await
-        .context(error::StartGrpcSnafu)?;
+    let served = match opts.tls {
+        Some(option) => {
+            let incoming = tls::tls_incoming(listener, option).context(error::GrpcTlsSnafu)?;
+            router.serve_with_incoming(incoming).await
+        }
+        None => {
+            router
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+        }
+    };
+    served.context(error::StartGrpcSnafu)?;
 
     Ok(())
 }
 
 pub fn router(meta_srv: MetaSrv) -> Router {
+    // Only components of the cluster knowing the token could call the gRPC services, the
+    // admin services expect the token in the `x-greptime-cluster-token` HTTP header.
+    let verifier = VerifyTokenInterceptor::new(meta_srv.options().cluster_token.clone());
     tonic::transport::Server::builder()
         .accept_http1(true) // for admin services
-        .add_service(HeartbeatServer::new(meta_srv.clone()))
-        .add_service(RouterServer::new(meta_srv.clone()))
-        .add_service(StoreServer::new(meta_srv.clone()))
-        .add_service(admin::make_admin_service(meta_srv))
+        .add_service(InterceptedService::new(
+            HeartbeatServer::new(meta_srv.clone()),
+            verifier.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            RouterServer::new(meta_srv.clone()),
+            verifier.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            StoreServer::new(meta_srv.clone()),
+            verifier.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            admin::make_admin_service(meta_srv),
+            verifier,
+        ))
 }
 
 pub async fn make_meta_srv(opts: MetaSrvOptions) -> Result<MetaSrv> {
@@ -69,7 +94,7 @@ pub async fn make_meta_srv(opts: MetaSrvOptions) -> Result<MetaSrv> {
             Some(EtcdElection::with_endpoints(&opts.server_addr, [&opts.store_addr]).await?),
         )
     };
-    let selector = opts.selector.clone().into();
+    let selector = build_selector(&opts)?;
     let meta_srv = MetaSrv::new(opts, kv_store, Some(selector), election, None).await;
     meta_srv.start().await;
     Ok(meta_srv)
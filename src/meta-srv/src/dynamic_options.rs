@@ -0,0 +1,143 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Options of the metasrv that could be changed at runtime by putting a JSON value like
+//! `{"datanode_lease_secs": 5, "selector": "LoadBased"}` to [META_SRV_OPTIONS_KEY] in the
+//! kv store, so failover could be tuned without restarting metasrv nodes.
+
+use std::time::Duration;
+
+use api::v1::meta::{PutRequest, RangeRequest};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::error::{self, Result};
+use crate::keys::META_SRV_OPTIONS_KEY;
+use crate::metasrv::MetaSrvOptions;
+use crate::selector::SelectorType;
+use crate::service::store::kv::KvStoreRef;
+
+/// Interval to check the kv store for changed options.
+pub(crate) const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Overrides of [MetaSrvOptions], options not set keep their configured values.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DynamicOptions {
+    pub datanode_lease_secs: Option<i64>,
+    pub selector: Option<SelectorType>,
+}
+
+impl DynamicOptions {
+    /// Loads the options from `kv_store`, nothing is overridden if the key is absent.
+    pub async fn load(kv_store: &KvStoreRef) -> Result<DynamicOptions> {
+        let req = RangeRequest {
+            key: META_SRV_OPTIONS_KEY.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        let Some(kv) = kv_store.range(req).await?.kvs.pop() else {
+            return Ok(DynamicOptions::default());
+        };
+        let value = String::from_utf8_lossy(&kv.value);
+        let options: DynamicOptions =
+            serde_json::from_str(&value).context(error::DeserializeFromJsonSnafu {
+                input: value.to_string(),
+            })?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Saves the options to `kv_store`, metasrv nodes apply them on their next reload.
+    pub async fn save(&self, kv_store: &KvStoreRef) -> Result<()> {
+        self.validate()?;
+        let value = serde_json::to_vec(self).context(error::SerializeToJsonSnafu {
+            input: format!("{self:?}"),
+        })?;
+        let req = PutRequest {
+            key: META_SRV_OPTIONS_KEY.as_bytes().to_vec(),
+            value,
+            ..Default::default()
+        };
+        kv_store.put(req).await?;
+        Ok(())
+    }
+
+    /// Returns `options` overridden by the dynamic options.
+    pub fn apply(&self, options: &MetaSrvOptions) -> MetaSrvOptions {
+        let mut options = options.clone();
+        if let Some(datanode_lease_secs) = self.datanode_lease_secs {
+            options.datanode_lease_secs = datanode_lease_secs;
+        }
+        if let Some(selector) = &self.selector {
+            options.selector = selector.clone();
+        }
+        options
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(datanode_lease_secs) = self.datanode_lease_secs {
+            ensure!(
+                datanode_lease_secs > 0,
+                error::InvalidArgumentsSnafu {
+                    err_msg: format!(
+                        "datanode_lease_secs should be positive, have: {datanode_lease_secs}"
+                    ),
+                }
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_load_and_save() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        assert_eq!(
+            DynamicOptions::default(),
+            DynamicOptions::load(&kv_store).await.unwrap()
+        );
+
+        let options = DynamicOptions {
+            datanode_lease_secs: Some(5),
+            selector: Some(SelectorType::LoadBased),
+        };
+        options.save(&kv_store).await.unwrap();
+        assert_eq!(options, DynamicOptions::load(&kv_store).await.unwrap());
+
+        let applied = options.apply(&MetaSrvOptions::default());
+        assert_eq!(5, applied.datanode_lease_secs);
+        assert_eq!(SelectorType::LoadBased, applied.selector);
+
+        let invalid = DynamicOptions {
+            datanode_lease_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(invalid.save(&kv_store).await.is_err());
+
+        let req = PutRequest {
+            key: META_SRV_OPTIONS_KEY.as_bytes().to_vec(),
+            value: br#"{"selector": "Unknown"}"#.to_vec(),
+            ..Default::default()
+        };
+        kv_store.put(req).await.unwrap();
+        assert!(DynamicOptions::load(&kv_store).await.is_err());
+    }
+}
@@ -14,6 +14,8 @@
 
 pub mod etcd;
 
+use api::v1::meta::FencingToken;
+
 use crate::error::Result;
 
 pub const LEASE_SECS: i64 = 3;
@@ -27,6 +29,13 @@ pub trait Election: Send + Sync {
     /// Returns `true` if current node is the leader.
     fn is_leader(&self) -> bool;
 
+    /// Returns the fencing token of the leadership held by current node, `None` if
+    /// current node is not the leader.
+    ///
+    /// The token is attached to leader-only mutations of the kv store, which rejects
+    /// them once the leadership is lost, even if current node has not noticed it yet.
+    fn fencing_token(&self) -> Option<FencingToken>;
+
     /// When a new leader is born, it may need some initialization
     /// operations (asynchronous), this method tells us when these
     /// initialization operations can be performed.
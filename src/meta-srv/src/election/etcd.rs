@@ -16,8 +16,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+use api::v1::meta::FencingToken;
 use common_telemetry::{info, warn};
 use etcd_client::Client;
+use parking_lot::RwLock;
 use snafu::{OptionExt, ResultExt};
 
 use crate::election::{Election, ELECTION_KEY, KEEP_ALIVE_PERIOD_SECS, LEASE_SECS};
@@ -30,6 +32,7 @@ pub struct EtcdElection {
     client: Client,
     is_leader: AtomicBool,
     infancy: AtomicBool,
+    fencing_token: RwLock<Option<FencingToken>>,
 }
 
 impl EtcdElection {
@@ -48,6 +51,7 @@ impl EtcdElection {
             client,
             is_leader: AtomicBool::new(false),
             infancy: AtomicBool::new(false),
+            fencing_token: RwLock::new(None),
         }))
     }
 }
@@ -60,6 +64,10 @@ impl Election for EtcdElection {
         self.is_leader.load(Ordering::Relaxed)
     }
 
+    fn fencing_token(&self) -> Option<FencingToken> {
+        self.fencing_token.read().clone()
+    }
+
     fn in_infancy(&self) -> bool {
         self.infancy
             .compare_exchange(true, false, Ordering::Relaxed, Ordering::Relaxed)
@@ -99,37 +107,53 @@ impl Election for EtcdElection {
 
             let mut keep_alive_interval =
                 tokio::time::interval(Duration::from_secs(KEEP_ALIVE_PERIOD_SECS));
-            loop {
-                keep_alive_interval.tick().await;
-                keeper.keep_alive().await.context(error::EtcdFailedSnafu)?;
-
-                if let Some(res) = receiver.message().await.context(error::EtcdFailedSnafu)? {
-                    if res.ttl() > 0 {
-                        // Only after a successful `keep_alive` is the leader considered official.
-                        if self
-                            .is_leader
-                            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
-                            .is_ok()
-                        {
-                            self.infancy.store(true, Ordering::Relaxed);
-                            info!(
-                                "[{}] becoming leader: {:?}, lease: {}",
-                                &self.leader_value,
-                                leader.name_str(),
+            // Leadership is lost once the keep-alive fails, the state must be reset even
+            // on errors.
+            let res: Result<()> = async {
+                loop {
+                    keep_alive_interval.tick().await;
+                    keeper.keep_alive().await.context(error::EtcdFailedSnafu)?;
+
+                    if let Some(res) = receiver.message().await.context(error::EtcdFailedSnafu)? {
+                        if res.ttl() > 0 {
+                            // Only after a successful `keep_alive` is the leader considered
+                            // official.
+                            //
+                            // The leader key is created at a new revision in each term, which
+                            // fences mutations of the previous terms.
+                            *self.fencing_token.write() = Some(FencingToken {
+                                leader_key: leader.key().to_vec(),
+                                term: leader.rev(),
+                            });
+                            if self
+                                .is_leader
+                                .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+                                .is_ok()
+                            {
+                                self.infancy.store(true, Ordering::Relaxed);
+                                info!(
+                                    "[{}] becoming leader: {:?}, lease: {}",
+                                    &self.leader_value,
+                                    leader.name_str(),
+                                    leader.lease()
+                                );
+                            }
+                        } else {
+                            warn!(
+                                "Failed to keep-alive, lease: {}, will re-initiate election",
                                 leader.lease()
                             );
+                            break;
                         }
-                    } else {
-                        warn!(
-                            "Failed to keep-alive, lease: {}, will re-initiate election",
-                            leader.lease()
-                        );
-                        break;
                     }
                 }
+                Ok(())
             }
+            .await;
 
             self.is_leader.store(false, Ordering::Relaxed);
+            *self.fencing_token.write() = None;
+            res?;
         }
 
         Ok(())
@@ -49,6 +49,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to setup TLS of gRPC server, source: {}", source))]
+    GrpcTls {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
     #[snafu(display("Empty table name"))]
     EmptyTableName { backtrace: Backtrace },
 
@@ -58,6 +64,12 @@ pub enum Error {
     #[snafu(display("Invalid datanode stat key: {}", key))]
     InvalidStatKey { key: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid datanode maintenance key: {}", key))]
+    InvalidMaintenanceKey { key: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid table tombstone key: {}", key))]
+    InvalidTableTombstoneKey { key: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to parse datanode lease key from utf8: {}", source))]
     LeaseKeyFromUtf8 {
         source: std::string::FromUtf8Error,
@@ -145,6 +157,15 @@ pub enum Error {
     #[snafu(display("MetaSrv has no leader at this moment"))]
     NoLeader { backtrace: Backtrace },
 
+    #[snafu(display("Injected failure at {}", name))]
+    InjectedFailure { name: String, backtrace: Backtrace },
+
+    #[snafu(display("MetaSrv is not the leader, the leader-only operation is rejected"))]
+    NotLeader { backtrace: Backtrace },
+
+    #[snafu(display("The leadership of term {} is lost, the mutation is fenced", term))]
+    Fenced { term: i64, backtrace: Backtrace },
+
     #[snafu(display("Table {} not found", name))]
     TableNotFound { name: String, backtrace: Backtrace },
 
@@ -160,6 +181,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Invalid address of the scheduler: {}, source: {}", addr, source))]
+    InvalidSchedulerAddr {
+        addr: String,
+        source: tonic::transport::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("An error occurred in Meta, source: {}", source))]
     MetaBoxedError {
         #[snafu(backtrace)]
@@ -171,7 +199,12 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 impl From<Error> for Status {
     fn from(err: Error) -> Self {
-        Status::new(Code::Internal, err.to_string())
+        // Clients retry on the new leader once the leadership of this node is lost.
+        let code = match err {
+            Error::NotLeader { .. } | Error::Fenced { .. } => Code::Unavailable,
+            _ => Code::Internal,
+        };
+        Status::new(code, err.to_string())
     }
 }
 
@@ -194,13 +227,18 @@ impl ErrorExt for Error {
             | Error::DeserializeFromJson { .. }
             | Error::DecodeTableRoute { .. }
             | Error::NoLeader { .. }
+            | Error::NotLeader { .. }
+            | Error::Fenced { .. }
             | Error::StartGrpc { .. } => StatusCode::Internal,
             Error::EmptyKey { .. }
             | Error::EmptyTableName { .. }
             | Error::InvalidLeaseKey { .. }
             | Error::InvalidStatKey { .. }
+            | Error::InvalidMaintenanceKey { .. }
+            | Error::InvalidTableTombstoneKey { .. }
             | Error::ParseNum { .. }
             | Error::UnsupportedSelectorType { .. }
+            | Error::InvalidSchedulerAddr { .. }
             | Error::InvalidArguments { .. } => StatusCode::InvalidArguments,
             Error::LeaseKeyFromUtf8 { .. }
             | Error::LeaseValueFromUtf8 { .. }
@@ -210,10 +248,12 @@ impl ErrorExt for Error {
             | Error::TableRouteNotFound { .. }
             | Error::NextSequence { .. }
             | Error::MoveValue { .. }
+            | Error::InjectedFailure { .. }
             | Error::InvalidTxnResult { .. } => StatusCode::Unexpected,
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
             Error::InvalidCatalogValue { source, .. } => source.status_code(),
             Error::MetaBoxedError { source } => source.status_code(),
+            Error::GrpcTls { source } => source.status_code(),
         }
     }
 }
@@ -403,4 +443,13 @@ mod tests {
         assert!(e.backtrace_opt().is_some());
         assert_eq!(e.status_code(), StatusCode::Unexpected);
     }
+
+    #[test]
+    fn test_not_leader_status() {
+        let e = throw_none_option().context(NotLeaderSnafu).err().unwrap();
+        assert_eq!(Code::Unavailable, Status::from(e).code());
+
+        let e = throw_none_option().context(StreamNoneSnafu).err().unwrap();
+        assert_eq!(Code::Internal, Status::from(e).code());
+    }
 }
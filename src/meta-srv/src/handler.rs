@@ -12,13 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use check_clock_skew_handler::CheckClockSkewHandler;
 pub use check_leader_handler::CheckLeaderHandler;
 pub use collect_stats_handler::CollectStatsHandler;
 pub use keep_lease_handler::KeepLeaseHandler;
 pub use on_leader_start::OnLeaderStartHandler;
 pub use persist_stats_handler::PersistStatsHandler;
+pub use promote_standby_handler::PromoteStandbyHandler;
 pub use response_header_handler::ResponseHeaderHandler;
+pub use table_changes_handler::TableChangesHandler;
+pub use table_tombstones_handler::TableTombstonesHandler;
 
+mod check_clock_skew_handler;
 mod check_leader_handler;
 mod collect_stats_handler;
 mod instruction;
@@ -26,12 +31,17 @@ mod keep_lease_handler;
 pub mod node_stat;
 mod on_leader_start;
 mod persist_stats_handler;
+mod promote_standby_handler;
 mod response_header_handler;
+mod table_changes_handler;
+mod table_tombstones_handler;
 
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, ResponseHeader};
+use api::v1::meta::{
+    CatalogVersion, HeartbeatRequest, HeartbeatResponse, ResponseHeader, TableName,
+};
 use common_telemetry::info;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
@@ -56,6 +66,11 @@ pub struct HeartbeatAccumulator {
     pub header: Option<ResponseHeader>,
     pub stats: Vec<Stat>,
     pub instructions: Vec<Instruction>,
+    pub catalog_version: Option<CatalogVersion>,
+    pub invalidated_tables: Vec<TableName>,
+    pub invalidate_all: bool,
+    pub dropped_table_ids: Vec<u32>,
+    pub promote: bool,
 }
 
 impl HeartbeatAccumulator {
@@ -104,9 +119,18 @@ impl HeartbeatHandlerGroup {
             h.handle(&req, &mut ctx, &mut acc).await?;
         }
         let header = std::mem::take(&mut acc.header);
+        let catalog_version = std::mem::take(&mut acc.catalog_version);
+        let invalidated_tables = std::mem::take(&mut acc.invalidated_tables);
+        let invalidate_all = acc.invalidate_all;
+        let dropped_table_ids = std::mem::take(&mut acc.dropped_table_ids);
         let res = HeartbeatResponse {
             header,
             payload: acc.into_payload(),
+            catalog_version,
+            invalidated_tables,
+            invalidate_all,
+            dropped_table_ids,
+            promote: acc.promote,
         };
         Ok(res)
     }
@@ -0,0 +1,148 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::HeartbeatRequest;
+use common_telemetry::warn;
+use metrics::{gauge, increment_counter};
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+use crate::metrics::{
+    METRIC_DATANODE_CLOCK_SKEW_EXCEEDED_TOTAL, METRIC_DATANODE_CLOCK_SKEW_MILLIS,
+};
+
+/// Estimates the clock skew of datanodes by the time they send the heartbeats at,
+/// and warns if it exceeds `max_skew_millis`.
+pub struct CheckClockSkewHandler {
+    max_skew_millis: i64,
+}
+
+impl CheckClockSkewHandler {
+    pub fn new(max_skew_millis: i64) -> Self {
+        Self { max_skew_millis }
+    }
+}
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for CheckClockSkewHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        _acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() {
+            return Ok(());
+        }
+
+        let Some(peer) = &req.peer else {
+            return Ok(());
+        };
+        let Some(skew) = clock_skew_millis(req, ctx.clock.now_millis()) else {
+            return Ok(());
+        };
+
+        let node_id = peer.id.to_string();
+        gauge!(METRIC_DATANODE_CLOCK_SKEW_MILLIS, skew as f64, "node_id" => node_id.clone());
+        if skew.abs() > self.max_skew_millis {
+            increment_counter!(METRIC_DATANODE_CLOCK_SKEW_EXCEEDED_TOTAL, "node_id" => node_id);
+            warn!(
+                "Clock of datanode {:?} drifts {skew}ms from metasrv, exceeds the max skew {}ms",
+                peer, self.max_skew_millis
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns how many milliseconds the clock of the datanode sending `req` is ahead of
+/// `now_millis`, negative if it's behind, `None` if the datanode doesn't report the
+/// time it sends the heartbeat at.
+///
+/// The network latency is counted as the datanode being behind, which is negligible
+/// compared to the skew worth warning.
+fn clock_skew_millis(req: &HeartbeatRequest, now_millis: i64) -> Option<i64> {
+    let sent_at = req.report_interval.as_ref()?.end_timestamp_millis;
+    (sent_at > 0).then(|| sent_at as i64 - now_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{Peer, TimeInterval};
+    use common_time::clock::MockClock;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    fn new_request(sent_at: u64) -> HeartbeatRequest {
+        HeartbeatRequest {
+            peer: Some(Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }),
+            report_interval: Some(TimeInterval {
+                start_timestamp_millis: 0,
+                end_timestamp_millis: sent_at,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clock_skew_millis() {
+        assert_eq!(Some(500), clock_skew_millis(&new_request(10_500), 10_000));
+        assert_eq!(Some(-3_000), clock_skew_millis(&new_request(7_000), 10_000));
+        assert_eq!(None, clock_skew_millis(&new_request(0), 10_000));
+        assert_eq!(
+            None,
+            clock_skew_millis(&HeartbeatRequest::default(), 10_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_clock_skew() {
+        let clock = Arc::new(MockClock::new(10_000));
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: Arc::new(MemStore::new()),
+            election: None,
+            clock: clock.clone(),
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+        let handler = CheckClockSkewHandler::new(2_000);
+        let mut acc = HeartbeatAccumulator::default();
+
+        handler
+            .handle(&new_request(10_500), &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        clock.set(20_000);
+        handler
+            .handle(&new_request(10_500), &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(acc.header.is_none());
+        assert!(acc.instructions.is_empty());
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use api::v1::meta::HeartbeatRequest;
 use common_telemetry::debug;
@@ -29,6 +29,9 @@ type StatKey = (u64, u64);
 pub struct CollectStatsHandler {
     max_cached_stats_per_key: usize,
     cache: DashMap<StatKey, VecDeque<Stat>>,
+    /// The time and written rows of regions in the last heartbeat of each node, to calculate
+    /// the write rate of regions.
+    written_rows: DashMap<StatKey, (i64, HashMap<u64, i64>)>,
 }
 
 impl Default for CollectStatsHandler {
@@ -42,6 +45,39 @@ impl CollectStatsHandler {
         Self {
             max_cached_stats_per_key,
             cache: DashMap::new(),
+            written_rows: DashMap::new(),
+        }
+    }
+
+    /// Sets the write rate of regions in `stat` by the written rows of the last heartbeat.
+    fn update_write_rate(&self, stat: &mut Stat) {
+        let written_rows = stat
+            .region_stats
+            .iter()
+            .map(|region_stat| (region_stat.id, region_stat.written_rows))
+            .collect();
+        let last = self.written_rows.insert(
+            (stat.cluster_id, stat.id),
+            (stat.timestamp_millis, written_rows),
+        );
+        let Some((last_millis, last_written_rows)) = last else {
+            return;
+        };
+        let elapsed_secs = (stat.timestamp_millis - last_millis) as f64 / 1000.0;
+        if elapsed_secs <= 0.0 {
+            return;
+        }
+
+        for region_stat in &mut stat.region_stats {
+            if let Some(last) = last_written_rows.get(&region_stat.id) {
+                // The written rows restart from 0 if the region is reopened.
+                let delta = if region_stat.written_rows >= *last {
+                    region_stat.written_rows - last
+                } else {
+                    region_stat.written_rows
+                };
+                region_stat.write_rate = delta as f64 / elapsed_secs;
+            }
         }
     }
 }
@@ -58,8 +94,9 @@ impl HeartbeatHandler for CollectStatsHandler {
             return Ok(());
         }
 
-        match Stat::try_from(req.clone()) {
-            Ok(stat) => {
+        match Stat::from_heartbeat(req.clone(), ctx.clock.now_millis()) {
+            Some(mut stat) => {
+                self.update_write_rate(&mut stat);
                 let key = (stat.cluster_id, stat.id);
                 match self.cache.entry(key) {
                     Entry::Occupied(mut e) => {
@@ -76,7 +113,7 @@ impl HeartbeatHandler for CollectStatsHandler {
                     }
                 }
             }
-            Err(_) => {
+            None => {
                 debug!("Incomplete heartbeat data: {:?}", req);
             }
         };
@@ -84,3 +121,62 @@ impl HeartbeatHandler for CollectStatsHandler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::node_stat::RegionStat;
+
+    fn new_stat(timestamp_millis: i64, written_rows: &[(u64, i64)]) -> Stat {
+        Stat {
+            timestamp_millis,
+            cluster_id: 0,
+            id: 1,
+            region_stats: written_rows
+                .iter()
+                .map(|(id, written_rows)| RegionStat {
+                    id: *id,
+                    catalog: String::new(),
+                    schema: String::new(),
+                    table: String::new(),
+                    rcus: 0,
+                    wcus: 0,
+                    approximate_bytes: 0,
+                    approximate_rows: 0,
+                    memtable_bytes: 0,
+                    sst_bytes: 0,
+                    written_rows: *written_rows,
+                    write_rate: 0.0,
+                    corrupted_files: vec![],
+                    last_write_millis: 0,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn write_rates(stat: &Stat) -> Vec<f64> {
+        stat.region_stats
+            .iter()
+            .map(|region_stat| region_stat.write_rate)
+            .collect()
+    }
+
+    #[test]
+    fn test_update_write_rate() {
+        let handler = CollectStatsHandler::default();
+
+        let mut stat = new_stat(1000, &[(1, 100), (2, 100)]);
+        handler.update_write_rate(&mut stat);
+        assert_eq!(vec![0.0, 0.0], write_rates(&stat));
+
+        // Region 2 is reopened and region 3 is new.
+        let mut stat = new_stat(3000, &[(1, 300), (2, 50), (3, 10)]);
+        handler.update_write_rate(&mut stat);
+        assert_eq!(vec![100.0, 25.0, 0.0], write_rates(&stat));
+
+        let mut stat = new_stat(3000, &[(1, 400)]);
+        handler.update_write_rate(&mut stat);
+        assert_eq!(vec![0.0], write_rates(&stat));
+    }
+}
@@ -14,7 +14,6 @@
 
 use api::v1::meta::{BatchPutRequest, HeartbeatRequest, KeyValue};
 use common_telemetry::{info, warn};
-use common_time::util as time_util;
 use tokio::sync::mpsc::{self, Sender};
 
 use crate::error::Result;
@@ -65,15 +64,21 @@ impl HeartbeatHandler for KeepLeaseHandler {
             return Ok(());
         }
 
-        let HeartbeatRequest { header, peer, .. } = req;
+        let HeartbeatRequest {
+            header,
+            peer,
+            labels,
+            ..
+        } = req;
         if let Some(peer) = &peer {
             let key = LeaseKey {
                 cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
                 node_id: peer.id,
             };
             let value = LeaseValue {
-                timestamp_millis: time_util::current_time_millis(),
+                timestamp_millis: ctx.clock.now_millis(),
                 node_addr: peer.addr.clone(),
+                labels: labels.clone(),
             };
 
             info!("Receive a heartbeat: {key:?}, {value:?}");
@@ -13,7 +13,6 @@
 // limitations under the License.
 
 use api::v1::meta::HeartbeatRequest;
-use common_time::util as time_util;
 use serde::{Deserialize, Serialize};
 
 use crate::keys::StatKey;
@@ -26,6 +25,10 @@ pub struct Stat {
     pub addr: String,
     /// Leader node
     pub is_leader: bool,
+    /// Whether the datanode is a standby serving reads of the regions, which are followers
+    /// of the regions
+    #[serde(default)]
+    pub is_standby: bool,
     /// The read capacity units during this period
     pub rcus: i64,
     /// The write capacity units during this period
@@ -44,7 +47,7 @@ pub struct Stat {
     pub region_stats: Vec<RegionStat>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RegionStat {
     pub id: u64,
     pub catalog: String,
@@ -58,6 +61,25 @@ pub struct RegionStat {
     pub approximate_bytes: i64,
     /// Approximate number of rows in this region
     pub approximate_rows: i64,
+    /// Bytes allocated by memtables of this region
+    #[serde(default)]
+    pub memtable_bytes: i64,
+    /// Bytes of SST files of this region
+    #[serde(default)]
+    pub sst_bytes: i64,
+    /// Number of rows written since this region is opened
+    #[serde(default)]
+    pub written_rows: i64,
+    /// Rows written per second since the last heartbeat
+    #[serde(default)]
+    pub write_rate: f64,
+    /// SST files failed to verify by the last scrub
+    #[serde(default)]
+    pub corrupted_files: Vec<String>,
+    /// Unix timestamp in milliseconds of the last write since this region is opened, 0 if
+    /// not written yet
+    #[serde(default)]
+    pub last_write_millis: i64,
 }
 
 impl Stat {
@@ -67,18 +89,17 @@ impl Stat {
             node_id: self.id,
         }
     }
-}
-
-impl TryFrom<HeartbeatRequest> for Stat {
-    type Error = ();
 
-    fn try_from(value: HeartbeatRequest) -> Result<Self, Self::Error> {
+    /// Builds the stat reported by the heartbeat `value` received at `timestamp_millis`,
+    /// `None` if the heartbeat is incomplete.
+    pub fn from_heartbeat(value: HeartbeatRequest, timestamp_millis: i64) -> Option<Self> {
         let HeartbeatRequest {
             header,
             peer,
             is_leader,
             node_stat,
             region_stats,
+            is_standby,
             ..
         } = value;
 
@@ -89,12 +110,13 @@ impl TryFrom<HeartbeatRequest> for Stat {
                 } else {
                     None
                 };
-                Ok(Self {
-                    timestamp_millis: time_util::current_time_millis(),
+                Some(Self {
+                    timestamp_millis,
                     cluster_id: header.cluster_id,
                     id: peer.id,
                     addr: peer.addr,
                     is_leader,
+                    is_standby,
                     rcus: node_stat.rcus,
                     wcus: node_stat.wcus,
                     table_num: node_stat.table_num,
@@ -106,7 +128,7 @@ impl TryFrom<HeartbeatRequest> for Stat {
                     region_stats: region_stats.into_iter().map(RegionStat::from).collect(),
                 })
             }
-            _ => Err(()),
+            _ => None,
         }
     }
 }
@@ -123,6 +145,12 @@ impl From<api::v1::meta::RegionStat> for RegionStat {
             wcus: value.wcus,
             approximate_bytes: value.approximate_bytes,
             approximate_rows: value.approximate_rows,
+            memtable_bytes: value.memtable_bytes,
+            sst_bytes: value.sst_bytes,
+            written_rows: value.written_rows,
+            write_rate: 0.0,
+            corrupted_files: value.corrupted_files,
+            last_write_millis: value.last_write_millis,
         }
     }
 }
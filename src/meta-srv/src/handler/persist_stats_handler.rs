@@ -79,6 +79,7 @@ mod tests {
             in_memory,
             kv_store,
             election: None,
+            clock: common_time::clock::system_clock(),
             skip_all: Arc::new(AtomicBool::new(false)),
             catalog: None,
             schema: None,
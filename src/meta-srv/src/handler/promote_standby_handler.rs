@@ -0,0 +1,112 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::HeartbeatRequest;
+use common_telemetry::info;
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+use crate::standby::take_promotion;
+
+/// Tells standby datanodes to promote once promotions are requested for them.
+#[derive(Default)]
+pub struct PromoteStandbyHandler;
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for PromoteStandbyHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() || !req.is_standby {
+            return Ok(());
+        }
+        let Some(peer) = &req.peer else {
+            return Ok(());
+        };
+
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if take_promotion(cluster_id, peer.id, &ctx.kv_store).await? {
+            info!("Promote standby datanode {}", peer.id);
+            acc.promote = true;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::Peer;
+
+    use super::*;
+    use crate::service::store::kv::KvStoreRef;
+    use crate::service::store::memory::MemStore;
+    use crate::standby::request_promotion;
+
+    #[tokio::test]
+    async fn test_handle_promote_standby() {
+        let kv_store = Arc::new(MemStore::new());
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: kv_store.clone(),
+            election: None,
+            clock: common_time::clock::system_clock(),
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+        let kv_store: KvStoreRef = kv_store;
+        request_promotion(0, 1, &kv_store).await.unwrap();
+
+        let mut req = HeartbeatRequest {
+            peer: Some(Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }),
+            ..Default::default()
+        };
+        // Only standby datanodes are promoted.
+        let mut acc = HeartbeatAccumulator::default();
+        PromoteStandbyHandler
+            .handle(&req, &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(!acc.promote);
+
+        req.is_standby = true;
+        let mut acc = HeartbeatAccumulator::default();
+        PromoteStandbyHandler
+            .handle(&req, &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(acc.promote);
+
+        // The promotion is only sent once.
+        let mut acc = HeartbeatAccumulator::default();
+        PromoteStandbyHandler
+            .handle(&req, &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(!acc.promote);
+    }
+}
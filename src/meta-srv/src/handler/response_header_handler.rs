@@ -61,6 +61,7 @@ mod tests {
             in_memory,
             kv_store,
             election: None,
+            clock: common_time::clock::system_clock(),
             skip_all: Arc::new(AtomicBool::new(false)),
             catalog: None,
             schema: None,
@@ -82,6 +83,7 @@ mod tests {
         let res = HeartbeatResponse {
             header,
             payload: acc.into_payload(),
+            ..Default::default()
         };
         assert_eq!(1, res.header.unwrap().cluster_id);
     }
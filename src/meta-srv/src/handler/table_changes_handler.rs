@@ -0,0 +1,132 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::HeartbeatRequest;
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+use crate::table_changes::TableChangeLogRef;
+
+/// Notifies nodes caching table metadata, which report the catalog versions of their
+/// caches in heartbeats, of tables changed since the versions.
+pub struct TableChangesHandler {
+    table_changes: TableChangeLogRef,
+}
+
+impl TableChangesHandler {
+    pub fn new(table_changes: TableChangeLogRef) -> Self {
+        Self { table_changes }
+    }
+}
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for TableChangesHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() {
+            return Ok(());
+        }
+        let Some(version) = &req.catalog_version else {
+            return Ok(());
+        };
+
+        // Reads the current version first, so changes made meanwhile are notified again in
+        // the next heartbeat.
+        acc.catalog_version = Some(self.table_changes.current_version());
+        match self.table_changes.changes_since(version) {
+            Some(tables) => acc.invalidated_tables = tables,
+            None => acc.invalidate_all = true,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{CatalogVersion, TableName};
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+    use crate::table_changes::TableChangeLog;
+
+    #[tokio::test]
+    async fn test_handle_table_changes() {
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: Arc::new(MemStore::new()),
+            election: None,
+            clock: common_time::clock::system_clock(),
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+        let table_changes = Arc::new(TableChangeLog::new(1));
+        table_changes.record_keys([b"__tg-greptime-public-demo".as_slice()]);
+        let handler = TableChangesHandler::new(table_changes);
+
+        // Nodes not caching table metadata are not notified.
+        let mut acc = HeartbeatAccumulator::default();
+        handler
+            .handle(&HeartbeatRequest::default(), &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(acc.catalog_version.is_none());
+
+        let mut req = HeartbeatRequest {
+            catalog_version: Some(CatalogVersion {
+                epoch: 1,
+                version: 0,
+            }),
+            ..Default::default()
+        };
+        let mut acc = HeartbeatAccumulator::default();
+        handler.handle(&req, &mut ctx, &mut acc).await.unwrap();
+        let current = CatalogVersion {
+            epoch: 1,
+            version: 1,
+        };
+        assert_eq!(Some(current.clone()), acc.catalog_version);
+        assert_eq!(
+            vec![TableName {
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                table_name: "demo".to_string(),
+            }],
+            acc.invalidated_tables
+        );
+        assert!(!acc.invalidate_all);
+
+        // Versions of another epoch invalidate all tables.
+        req.catalog_version = Some(CatalogVersion {
+            epoch: 0,
+            version: 1,
+        });
+        let mut acc = HeartbeatAccumulator::default();
+        handler.handle(&req, &mut ctx, &mut acc).await.unwrap();
+        assert_eq!(Some(current), acc.catalog_version);
+        assert!(acc.invalidated_tables.is_empty());
+        assert!(acc.invalidate_all);
+    }
+}
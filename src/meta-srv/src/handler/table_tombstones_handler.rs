@@ -0,0 +1,177 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use api::v1::meta::HeartbeatRequest;
+use common_telemetry::warn;
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+use crate::table_tombstones::{purge_table_tombstones, table_tombstones};
+
+/// Interval to reload the tombstones from the store and purge the expired ones, so a table
+/// dropped by another metasrv is notified to datanodes at most this interval later.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Notifies datanodes of the dropped tables whose regions they still report, so they
+/// could reject late writes to these tables. Tombstones are cached instead of read from
+/// the store for each heartbeat, and the expired ones are purged on reloading.
+pub struct TableTombstonesHandler {
+    retention_secs: u64,
+    /// Ids of the dropped tables and when they are loaded.
+    dropped_tables: Mutex<Option<(Instant, Arc<HashSet<u32>>)>>,
+}
+
+impl TableTombstonesHandler {
+    pub fn new(retention_secs: u64) -> Self {
+        Self {
+            retention_secs,
+            dropped_tables: Mutex::new(None),
+        }
+    }
+
+    async fn dropped_tables(&self, ctx: &Context) -> Result<Arc<HashSet<u32>>> {
+        let mut dropped_tables = self.dropped_tables.lock().await;
+        if let Some((loaded_at, table_ids)) = &*dropped_tables {
+            if loaded_at.elapsed() < RELOAD_INTERVAL {
+                return Ok(table_ids.clone());
+            }
+        }
+
+        let now_millis = ctx.clock.now_millis();
+        if let Err(e) = purge_table_tombstones(self.retention_secs, now_millis, &ctx.kv_store).await
+        {
+            warn!("Failed to purge expired table tombstones: {}", e);
+        }
+        let table_ids = Arc::new(
+            table_tombstones(&ctx.kv_store)
+                .await?
+                .into_keys()
+                .collect::<HashSet<_>>(),
+        );
+        *dropped_tables = Some((Instant::now(), table_ids.clone()));
+        Ok(table_ids)
+    }
+}
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for TableTombstonesHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() || req.region_stats.is_empty() {
+            return Ok(());
+        }
+
+        let dropped_tables = self.dropped_tables(ctx).await?;
+        let table_ids = req
+            .region_stats
+            .iter()
+            .map(|stat| (stat.region_id >> 32) as u32)
+            .collect::<HashSet<_>>();
+        let mut dropped_table_ids = table_ids
+            .into_iter()
+            .filter(|table_id| dropped_tables.contains(table_id))
+            .collect::<Vec<_>>();
+        dropped_table_ids.sort_unstable();
+        acc.dropped_table_ids = dropped_table_ids;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::RegionStat;
+    use common_time::clock::MockClock;
+
+    use super::*;
+    use crate::service::store::kv::KvStoreRef;
+    use crate::service::store::memory::MemStore;
+    use crate::table_tombstones::put_table_tombstone;
+
+    #[tokio::test]
+    async fn test_handle_table_tombstones() {
+        let kv_store = Arc::new(MemStore::new());
+        let mut ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: kv_store.clone(),
+            election: None,
+            clock: Arc::new(MockClock::new(2_000)),
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        };
+        let kv_store: KvStoreRef = kv_store;
+        put_table_tombstone(1024, "greptime.public.a".to_string(), 1_000, &kv_store)
+            .await
+            .unwrap();
+        put_table_tombstone(1025, "greptime.public.b".to_string(), 1_000, &kv_store)
+            .await
+            .unwrap();
+
+        let region_stat = |table_id: u64, region_number: u64| RegionStat {
+            region_id: (table_id << 32) | region_number,
+            ..Default::default()
+        };
+        let req = HeartbeatRequest {
+            region_stats: vec![
+                region_stat(1024, 0),
+                region_stat(1024, 1),
+                region_stat(1026, 0),
+            ],
+            ..Default::default()
+        };
+        let handler = TableTombstonesHandler::new(86_400);
+        let mut acc = HeartbeatAccumulator::default();
+        handler.handle(&req, &mut ctx, &mut acc).await.unwrap();
+        assert_eq!(vec![1024], acc.dropped_table_ids);
+
+        // Tombstones are cached until the next reload.
+        put_table_tombstone(1026, "greptime.public.c".to_string(), 1_000, &kv_store)
+            .await
+            .unwrap();
+        let mut acc = HeartbeatAccumulator::default();
+        handler.handle(&req, &mut ctx, &mut acc).await.unwrap();
+        assert_eq!(vec![1024], acc.dropped_table_ids);
+
+        // Nodes without regions are not notified.
+        let mut acc = HeartbeatAccumulator::default();
+        handler
+            .handle(&HeartbeatRequest::default(), &mut ctx, &mut acc)
+            .await
+            .unwrap();
+        assert!(acc.dropped_table_ids.is_empty());
+
+        // Expired tombstones are purged on reloading.
+        let handler = TableTombstonesHandler::new(0);
+        let mut acc = HeartbeatAccumulator::default();
+        handler.handle(&req, &mut ctx, &mut acc).await.unwrap();
+        assert!(acc.dropped_table_ids.is_empty());
+        assert!(table_tombstones(&kv_store).await.unwrap().is_empty());
+    }
+}
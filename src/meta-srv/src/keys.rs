@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use api::v1::meta::TableName;
 use catalog::helper::TableGlobalKey;
+use common_catalog::consts::DATANODE_STAT_KEY_PREFIX;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -27,16 +29,25 @@ use crate::handler::node_stat::Stat;
 
 pub(crate) const REMOVED_PREFIX: &str = "__removed";
 pub(crate) const DN_LEASE_PREFIX: &str = "__meta_dnlease";
+pub(crate) const DN_MAINTENANCE_PREFIX: &str = "__meta_dnmaint";
+pub(crate) const DN_PROMOTION_PREFIX: &str = "__meta_dnpromote";
 pub(crate) const SEQ_PREFIX: &str = "__meta_seq";
 pub(crate) const TABLE_ROUTE_PREFIX: &str = "__meta_table_route";
+pub(crate) const TABLE_TOMBSTONE_PREFIX: &str = "__meta_table_tombstone";
 
-pub const DN_STAT_PREFIX: &str = "__meta_dnstat";
+pub const DN_STAT_PREFIX: &str = DATANODE_STAT_KEY_PREFIX;
+/// Key of the options overriding [MetaSrvOptions](crate::metasrv::MetaSrvOptions) at runtime.
+pub const META_SRV_OPTIONS_KEY: &str = "__meta_srv_options";
 
 lazy_static! {
     static ref DATANODE_LEASE_KEY_PATTERN: Regex =
         Regex::new(&format!("^{DN_LEASE_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
     static ref DATANODE_STAT_KEY_PATTERN: Regex =
         Regex::new(&format!("^{DN_STAT_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
+    static ref DATANODE_MAINTENANCE_KEY_PATTERN: Regex =
+        Regex::new(&format!("^{DN_MAINTENANCE_PREFIX}-([0-9]+)-([0-9]+)$")).unwrap();
+    static ref TABLE_TOMBSTONE_KEY_PATTERN: Regex =
+        Regex::new(&format!("^{TABLE_TOMBSTONE_PREFIX}-([0-9]+)$")).unwrap();
 }
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct LeaseKey {
@@ -97,6 +108,9 @@ pub struct LeaseValue {
     // last activity
     pub timestamp_millis: i64,
     pub node_addr: String,
+    /// Labels of the datanode, like zone and rack.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
 }
 
 impl FromStr for LeaseValue {
@@ -271,6 +285,195 @@ impl TryFrom<Vec<u8>> for StatValue {
     }
 }
 
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct MaintenanceKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+}
+
+impl From<MaintenanceKey> for Vec<u8> {
+    fn from(value: MaintenanceKey) -> Self {
+        format!(
+            "{}-{}-{}",
+            DN_MAINTENANCE_PREFIX, value.cluster_id, value.node_id
+        )
+        .into_bytes()
+    }
+}
+
+impl FromStr for MaintenanceKey {
+    type Err = error::Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        let caps = DATANODE_MAINTENANCE_KEY_PATTERN
+            .captures(key)
+            .context(error::InvalidMaintenanceKeySnafu { key })?;
+
+        ensure!(caps.len() == 3, error::InvalidMaintenanceKeySnafu { key });
+
+        let cluster_id = caps[1].to_string();
+        let node_id = caps[2].to_string();
+        let cluster_id: u64 = cluster_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid cluster_id: {cluster_id}"),
+        })?;
+        let node_id: u64 = node_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid node_id: {node_id}"),
+        })?;
+
+        Ok(Self {
+            cluster_id,
+            node_id,
+        })
+    }
+}
+
+impl TryFrom<Vec<u8>> for MaintenanceKey {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8_lossy(&bytes).parse()
+    }
+}
+
+/// Key of the pending promotion of a standby datanode.
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct PromotionKey {
+    pub cluster_id: u64,
+    pub node_id: u64,
+}
+
+impl From<PromotionKey> for Vec<u8> {
+    fn from(value: PromotionKey) -> Self {
+        format!(
+            "{}-{}-{}",
+            DN_PROMOTION_PREFIX, value.cluster_id, value.node_id
+        )
+        .into_bytes()
+    }
+}
+
+/// Maintenance state of a datanode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MaintenanceState {
+    /// No new regions are placed on the datanode.
+    Cordoned,
+    /// Like `Cordoned`, and the datanode is going to be emptied for decommission.
+    Draining,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceValue {
+    pub state: MaintenanceState,
+    /// When the datanode enters the state.
+    pub timestamp_millis: i64,
+}
+
+impl FromStr for MaintenanceValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for MaintenanceValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8_lossy(&bytes).parse()
+    }
+}
+
+impl TryFrom<MaintenanceValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: MaintenanceValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
+/// Key of the tombstone of a dropped table.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TableTombstoneKey {
+    pub table_id: u32,
+}
+
+impl From<TableTombstoneKey> for Vec<u8> {
+    fn from(value: TableTombstoneKey) -> Self {
+        format!("{}-{}", TABLE_TOMBSTONE_PREFIX, value.table_id).into_bytes()
+    }
+}
+
+impl FromStr for TableTombstoneKey {
+    type Err = error::Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        let caps = TABLE_TOMBSTONE_KEY_PATTERN
+            .captures(key)
+            .context(error::InvalidTableTombstoneKeySnafu { key })?;
+
+        ensure!(
+            caps.len() == 2,
+            error::InvalidTableTombstoneKeySnafu { key }
+        );
+
+        let table_id = caps[1].to_string();
+        let table_id: u32 = table_id.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid table_id: {table_id}"),
+        })?;
+
+        Ok(Self { table_id })
+    }
+}
+
+impl TryFrom<Vec<u8>> for TableTombstoneKey {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8_lossy(&bytes).parse()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TableTombstoneValue {
+    /// Full name of the dropped table.
+    pub table_name: String,
+    /// When the table is dropped.
+    pub timestamp_millis: i64,
+}
+
+impl FromStr for TableTombstoneValue {
+    type Err = error::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        serde_json::from_str(value).context(error::DeserializeFromJsonSnafu { input: value })
+    }
+}
+
+impl TryFrom<Vec<u8>> for TableTombstoneValue {
+    type Error = error::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8_lossy(&bytes).parse()
+    }
+}
+
+impl TryFrom<TableTombstoneValue> for Vec<u8> {
+    type Error = error::Error;
+
+    fn try_from(value: TableTombstoneValue) -> Result<Self> {
+        Ok(serde_json::to_string(&value)
+            .context(error::SerializeToJsonSnafu {
+                input: format!("{value:?}"),
+            })?
+            .into_bytes())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,12 +535,58 @@ mod tests {
         let value = LeaseValue {
             timestamp_millis: 111,
             node_addr: "127.0.0.1:3002".to_string(),
+            labels: HashMap::from([("zone".to_string(), "z1".to_string())]),
         };
 
         let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
         let new_value: LeaseValue = value_bytes.try_into().unwrap();
 
         assert_eq!(new_value, value);
+
+        // Lease values written before labels are introduced.
+        let old_value: LeaseValue = r#"{"timestamp_millis":111,"node_addr":"127.0.0.1:3002"}"#
+            .parse()
+            .unwrap();
+        assert!(old_value.labels.is_empty());
+    }
+
+    #[test]
+    fn test_maintenance_kv_round_trip() {
+        let key = MaintenanceKey {
+            cluster_id: 0,
+            node_id: 1,
+        };
+        let key_bytes: Vec<u8> = key.clone().into();
+        assert_eq!(b"__meta_dnmaint-0-1".to_vec(), key_bytes);
+        let new_key: MaintenanceKey = key_bytes.try_into().unwrap();
+        assert_eq!(key, new_key);
+        assert!(MaintenanceKey::try_from(b"__meta_dnlease-0-1".to_vec()).is_err());
+
+        let value = MaintenanceValue {
+            state: MaintenanceState::Draining,
+            timestamp_millis: 111,
+        };
+        let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
+        let new_value: MaintenanceValue = value_bytes.try_into().unwrap();
+        assert_eq!(value, new_value);
+    }
+
+    #[test]
+    fn test_table_tombstone_kv_round_trip() {
+        let key = TableTombstoneKey { table_id: 1024 };
+        let key_bytes: Vec<u8> = key.into();
+        assert_eq!(b"__meta_table_tombstone-1024".to_vec(), key_bytes);
+        let new_key: TableTombstoneKey = key_bytes.try_into().unwrap();
+        assert_eq!(key, new_key);
+        assert!(TableTombstoneKey::try_from(b"__meta_table_tombstone-a".to_vec()).is_err());
+
+        let value = TableTombstoneValue {
+            table_name: "greptime.public.demo".to_string(),
+            timestamp_millis: 111,
+        };
+        let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
+        let new_value: TableTombstoneValue = value_bytes.try_into().unwrap();
+        assert_eq!(value, new_value);
     }
 
     #[test]
@@ -17,7 +17,7 @@ use api::v1::meta::RangeRequest;
 use crate::error::Result;
 use crate::keys::{LeaseKey, LeaseValue, DN_LEASE_PREFIX};
 use crate::service::store::kv::KvStoreRef;
-use crate::util;
+use crate::{maintenance, util};
 
 pub async fn alive_datanodes<P>(
     cluster_id: u64,
@@ -51,6 +51,23 @@ where
     Ok(lease_kvs)
 }
 
+/// Returns the alive datanodes that new regions can be placed on, that is, the ones
+/// not under maintenance.
+pub async fn schedulable_datanodes<P>(
+    cluster_id: u64,
+    kv_store: &KvStoreRef,
+    predicate: P,
+) -> Result<Vec<(LeaseKey, LeaseValue)>>
+where
+    P: Fn(&LeaseKey, &LeaseValue) -> bool,
+{
+    let maintenance_nodes = maintenance::maintenance_nodes(cluster_id, kv_store).await?;
+    alive_datanodes(cluster_id, kv_store, |k, v| {
+        !maintenance_nodes.contains_key(&k.node_id) && predicate(k, v)
+    })
+    .await
+}
+
 #[inline]
 pub fn get_lease_prefix(cluster_id: u64) -> Vec<u8> {
     format!("{DN_LEASE_PREFIX}-{cluster_id}").into_bytes()
@@ -14,17 +14,23 @@
 
 #![feature(btree_drain_filter)]
 pub mod bootstrap;
+pub mod dynamic_options;
 pub mod election;
 pub mod error;
 pub mod handler;
 pub mod keys;
 pub mod lease;
+pub mod maintenance;
 pub mod metasrv;
+mod metrics;
 #[cfg(feature = "mock")]
 pub mod mocks;
 pub mod selector;
 mod sequence;
 pub mod service;
+pub mod standby;
+pub mod table_changes;
+pub mod table_tombstones;
 pub mod util;
 
 pub use crate::error::Result;
@@ -0,0 +1,365 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Maintenance of datanodes. Selectors no longer place new regions on a cordoned or
+//! draining datanode, and the regions of a draining datanode are moved to the standby
+//! datanodes following them.
+
+use std::collections::{BTreeSet, HashMap};
+
+use api::v1::meta::{
+    DeleteRangeRequest, Peer, PutRequest, RangeRequest, TableRoute, TableRouteValue,
+};
+use common_time::clock::ClockRef;
+use serde::Serialize;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::keys::{
+    MaintenanceKey, MaintenanceState, MaintenanceValue, DN_MAINTENANCE_PREFIX, TABLE_ROUTE_PREFIX,
+};
+use crate::service::store::kv::KvStoreRef;
+use crate::standby::request_promotion;
+use crate::table_changes::TableChangeLog;
+use crate::util;
+
+/// Puts the datanode into maintenance `state` at the time read from `clock`, or brings it
+/// back to service if `state` is `None`.
+pub async fn set_maintenance(
+    cluster_id: u64,
+    node_id: u64,
+    state: Option<MaintenanceState>,
+    kv_store: &KvStoreRef,
+    clock: &ClockRef,
+) -> Result<()> {
+    let key = MaintenanceKey {
+        cluster_id,
+        node_id,
+    }
+    .into();
+    match state {
+        Some(state) => {
+            let value = MaintenanceValue {
+                state,
+                timestamp_millis: clock.now_millis(),
+            };
+            let req = PutRequest {
+                key,
+                value: value.try_into()?,
+                ..Default::default()
+            };
+            kv_store.put(req).await?;
+        }
+        None => {
+            let req = DeleteRangeRequest {
+                key,
+                ..Default::default()
+            };
+            kv_store.delete_range(req).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Regions led by a draining datanode.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DrainedRegions {
+    /// Regions moved to the standby datanodes following them.
+    pub moved: usize,
+    /// Regions staying on the datanode as no standby datanode follows them.
+    pub pending: usize,
+}
+
+/// Moves the regions led by the draining datanode `node_id` to the standby datanodes
+/// following them, which are in `followers` keyed by region ids. The routes of the regions
+/// are pointed to the standby datanodes, which are requested to promote to take over writes
+/// of the regions. The datanode should be stopped once no region is pending, writes it
+/// hasn't flushed are not taken over by the standby datanodes.
+pub async fn drain_regions(
+    cluster_id: u64,
+    node_id: u64,
+    followers: &HashMap<u64, Vec<Peer>>,
+    kv_store: &KvStoreRef,
+    table_changes: &TableChangeLog,
+) -> Result<DrainedRegions> {
+    let key = format!("{TABLE_ROUTE_PREFIX}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+
+    let mut drained = DrainedRegions::default();
+    let mut promoted = BTreeSet::new();
+    for kv in kv_store.range(req).await?.kvs {
+        let mut value: TableRouteValue = kv
+            .value
+            .as_slice()
+            .try_into()
+            .context(error::DecodeTableRouteSnafu)?;
+        let Some(table_route) = &mut value.table_route else {
+            continue;
+        };
+        let moved = move_leaders(node_id, &mut value.peers, table_route, followers);
+        drained.pending += table_route
+            .region_routes
+            .iter()
+            .filter(|rr| {
+                value
+                    .peers
+                    .get(rr.leader_peer_index as usize)
+                    .map_or(false, |peer| peer.id == node_id)
+            })
+            .count();
+        if moved.is_empty() {
+            continue;
+        }
+        drained.moved += moved.len();
+        promoted.extend(moved);
+
+        let table_name = table_route
+            .table
+            .as_ref()
+            .and_then(|table| table.table_name.clone());
+        let req = PutRequest {
+            key: kv.key,
+            value: value.into(),
+            ..Default::default()
+        };
+        kv_store.put(req).await?;
+        // Nodes caching the routes of the table are notified.
+        if let Some(table_name) = table_name {
+            table_changes.record(table_name);
+        }
+    }
+
+    for standby_id in promoted {
+        request_promotion(cluster_id, standby_id, kv_store).await?;
+    }
+    Ok(drained)
+}
+
+/// Moves the leaders of the regions led by the datanode `node_id` to the first of their
+/// `followers`, returns the ids of the new leaders of the moved regions.
+fn move_leaders(
+    node_id: u64,
+    peers: &mut Vec<Peer>,
+    table_route: &mut TableRoute,
+    followers: &HashMap<u64, Vec<Peer>>,
+) -> Vec<u64> {
+    let table_id = table_route.table.as_ref().map_or(0, |t| t.id);
+    let mut new_leaders = Vec::new();
+    for rr in &mut table_route.region_routes {
+        match peers.get(rr.leader_peer_index as usize) {
+            Some(leader) if leader.id == node_id => {}
+            _ => continue,
+        }
+        let region_number = rr.region.as_ref().map_or(0, |r| r.id);
+        let region_id = (table_id << 32) | region_number;
+        let follower = match followers
+            .get(&region_id)
+            .and_then(|peers| peers.iter().find(|peer| peer.id != node_id))
+        {
+            Some(follower) => follower,
+            None => continue,
+        };
+
+        let index = match peers.iter().position(|peer| peer.id == follower.id) {
+            Some(index) => index,
+            None => {
+                peers.push(follower.clone());
+                peers.len() - 1
+            }
+        } as u64;
+        rr.leader_peer_index = index;
+        rr.follower_peer_indexes.retain(|i| *i != index);
+        new_leaders.push(follower.id);
+    }
+    new_leaders
+}
+
+/// Returns the datanodes under maintenance in the cluster, keyed by node id.
+pub async fn maintenance_nodes(
+    cluster_id: u64,
+    kv_store: &KvStoreRef,
+) -> Result<HashMap<u64, MaintenanceValue>> {
+    let key = format!("{DN_MAINTENANCE_PREFIX}-{cluster_id}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+
+    let kvs = kv_store.range(req).await?.kvs;
+    let mut nodes = HashMap::with_capacity(kvs.len());
+    for kv in kvs {
+        let key: MaintenanceKey = kv.key.try_into()?;
+        let value: MaintenanceValue = kv.value.try_into()?;
+        nodes.insert(key.node_id, value);
+    }
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::{CatalogVersion, Region, RegionRoute, Table, TableName};
+    use common_time::clock::MockClock;
+
+    use super::*;
+    use crate::keys::TableRouteKey;
+    use crate::service::store::memory::MemStore;
+    use crate::standby::take_promotion;
+
+    #[tokio::test]
+    async fn test_set_maintenance() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let clock: ClockRef = Arc::new(MockClock::new(1000));
+        assert!(maintenance_nodes(0, &kv_store).await.unwrap().is_empty());
+
+        set_maintenance(0, 1, Some(MaintenanceState::Cordoned), &kv_store, &clock)
+            .await
+            .unwrap();
+        set_maintenance(0, 11, Some(MaintenanceState::Draining), &kv_store, &clock)
+            .await
+            .unwrap();
+        set_maintenance(1, 1, Some(MaintenanceState::Draining), &kv_store, &clock)
+            .await
+            .unwrap();
+
+        let nodes = maintenance_nodes(0, &kv_store).await.unwrap();
+        assert_eq!(2, nodes.len());
+        assert_eq!(MaintenanceState::Cordoned, nodes[&1].state);
+        assert_eq!(MaintenanceState::Draining, nodes[&11].state);
+        assert_eq!(1000, nodes[&1].timestamp_millis);
+
+        // Cordoned to draining.
+        set_maintenance(0, 1, Some(MaintenanceState::Draining), &kv_store, &clock)
+            .await
+            .unwrap();
+        let nodes = maintenance_nodes(0, &kv_store).await.unwrap();
+        assert_eq!(MaintenanceState::Draining, nodes[&1].state);
+
+        set_maintenance(0, 1, None, &kv_store, &clock)
+            .await
+            .unwrap();
+        let nodes = maintenance_nodes(0, &kv_store).await.unwrap();
+        assert_eq!(vec![11], nodes.keys().copied().collect::<Vec<_>>());
+        let nodes = maintenance_nodes(1, &kv_store).await.unwrap();
+        assert_eq!(vec![1], nodes.keys().copied().collect::<Vec<_>>());
+    }
+
+    fn peer(id: u64) -> Peer {
+        Peer {
+            id,
+            addr: format!("127.0.0.1:{id}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_regions() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let table_changes = TableChangeLog::new(0);
+        let table_id = 1024;
+        let table_name = TableName {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+        };
+        // Regions 0 and 1 are led by the draining datanode 1, region 2 by datanode 2.
+        let region_routes = [0, 0, 1]
+            .into_iter()
+            .enumerate()
+            .map(|(region, leader_peer_index)| RegionRoute {
+                region: Some(Region {
+                    id: region as u64,
+                    ..Default::default()
+                }),
+                leader_peer_index,
+                follower_peer_indexes: vec![],
+            })
+            .collect();
+        let value = TableRouteValue {
+            peers: vec![peer(1), peer(2)],
+            table_route: Some(TableRoute {
+                table: Some(Table {
+                    id: table_id,
+                    table_name: Some(table_name.clone()),
+                    ..Default::default()
+                }),
+                region_routes,
+            }),
+        };
+        let key = TableRouteKey::with_table_name(table_id, &table_name).key();
+        let req = PutRequest {
+            key: key.clone().into_bytes(),
+            value: value.into(),
+            ..Default::default()
+        };
+        kv_store.put(req).await.unwrap();
+
+        // Only region 0 is followed by the standby datanode 3.
+        let followers = HashMap::from([(table_id << 32, vec![peer(3)])]);
+        let drained = drain_regions(0, 1, &followers, &kv_store, &table_changes)
+            .await
+            .unwrap();
+        assert_eq!(
+            DrainedRegions {
+                moved: 1,
+                pending: 1
+            },
+            drained
+        );
+
+        let req = RangeRequest {
+            key: key.into_bytes(),
+            ..Default::default()
+        };
+        let kvs = kv_store.range(req).await.unwrap().kvs;
+        let value: TableRouteValue = kvs[0].value.as_slice().try_into().unwrap();
+        assert_eq!(vec![peer(1), peer(2), peer(3)], value.peers);
+        let leaders = value
+            .table_route
+            .unwrap()
+            .region_routes
+            .iter()
+            .map(|rr| value.peers[rr.leader_peer_index as usize].id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![3, 1, 2], leaders);
+        assert!(take_promotion(0, 3, &kv_store).await.unwrap());
+        let changes = table_changes
+            .changes_since(&CatalogVersion {
+                epoch: 0,
+                version: 0,
+            })
+            .unwrap();
+        assert_eq!(vec![table_name], changes);
+
+        // Nothing to move if no new standby datanode follows the pending region.
+        let drained = drain_regions(0, 1, &followers, &kv_store, &table_changes)
+            .await
+            .unwrap();
+        assert_eq!(
+            DrainedRegions {
+                moved: 0,
+                pending: 1
+            },
+            drained
+        );
+    }
+}
@@ -13,22 +13,31 @@
 // limitations under the License.
 
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use api::v1::meta::Peer;
+use common_grpc::tls::GrpcTlsOption;
+use common_grpc::token::ClusterToken;
 use common_telemetry::{info, warn};
+use common_time::clock::{self, ClockRef};
 use serde::{Deserialize, Serialize};
 
+use crate::dynamic_options::{DynamicOptions, RELOAD_INTERVAL};
 use crate::election::Election;
+use crate::error::Result;
 use crate::handler::{
-    CheckLeaderHandler, CollectStatsHandler, HeartbeatHandlerGroup, KeepLeaseHandler,
-    OnLeaderStartHandler, PersistStatsHandler, ResponseHeaderHandler,
+    CheckClockSkewHandler, CheckLeaderHandler, CollectStatsHandler, HeartbeatHandlerGroup,
+    KeepLeaseHandler, OnLeaderStartHandler, PersistStatsHandler, PromoteStandbyHandler,
+    ResponseHeaderHandler, TableChangesHandler, TableTombstonesHandler,
 };
 use crate::selector::lease_based::LeaseBasedSelector;
-use crate::selector::{Selector, SelectorType};
+use crate::selector::remote::RemoteSelectorOptions;
+use crate::selector::{build_selector, Selector, SelectorType};
 use crate::sequence::{Sequence, SequenceRef};
+use crate::service::store::fenced::FencedKvStore;
 use crate::service::store::kv::{KvStoreRef, ResetableKvStoreRef};
 use crate::service::store::memory::MemStore;
+use crate::table_changes::{TableChangeLog, TableChangeLogRef};
 
 pub const TABLE_ID_SEQ: &str = "table_id";
 
@@ -40,7 +49,26 @@ pub struct MetaSrvOptions {
     pub store_addr: String,
     pub datanode_lease_secs: i64,
     pub selector: SelectorType,
+    /// Options of the `Remote` selector.
+    pub remote_selector: RemoteSelectorOptions,
+    /// Labels of datanodes from the widest failure domain to the narrowest, like
+    /// `["zone", "rack"]`, that the selected peers are spread across. Empty disables spreading.
+    pub placement_labels: Vec<String>,
     pub use_memory_store: bool,
+    /// Max milliseconds the clock of a datanode could drift from the clock of the
+    /// metasrv before warning.
+    pub max_clock_skew_millis: i64,
+    /// Seconds to keep the tombstones of dropped tables, datanodes reject writes to the
+    /// dropped tables in the period.
+    pub table_tombstone_retention_secs: u64,
+    /// Serves over TLS if set, datanodes and frontends must connect with TLS as well.
+    pub tls: Option<GrpcTlsOption>,
+    /// Rejects gRPC requests without this token, datanodes and frontends must be
+    /// configured with the same token.
+    pub cluster_token: Option<ClusterToken>,
+    /// Token operators must present as `Authorization: Bearer <token>` to call the admin
+    /// APIs changing the cluster, e.g. draining datanodes, such APIs are disabled if unset.
+    pub admin_token: Option<ClusterToken>,
 }
 
 impl Default for MetaSrvOptions {
@@ -51,7 +79,14 @@ impl Default for MetaSrvOptions {
             store_addr: "127.0.0.1:2379".to_string(),
             datanode_lease_secs: 15,
             selector: SelectorType::default(),
+            remote_selector: RemoteSelectorOptions::default(),
+            placement_labels: vec![],
             use_memory_store: false,
+            max_clock_skew_millis: 2_000,
+            table_tombstone_retention_secs: 86_400,
+            tls: None,
+            cluster_token: None,
+            admin_token: None,
         }
     }
 }
@@ -63,6 +98,8 @@ pub struct Context {
     pub in_memory: ResetableKvStoreRef,
     pub kv_store: KvStoreRef,
     pub election: Option<ElectionRef>,
+    /// Clock to check the leases of datanodes against.
+    pub clock: ClockRef,
     pub skip_all: Arc<AtomicBool>,
     pub catalog: Option<String>,
     pub schema: Option<String>,
@@ -88,18 +125,30 @@ pub struct LeaderValue(pub String);
 pub type SelectorRef = Arc<dyn Selector<Context = Context, Output = Vec<Peer>>>;
 pub type ElectionRef = Arc<dyn Election<Leader = LeaderValue>>;
 
+/// State derived from the [DynamicOptions] applied to the metasrv.
+struct DynamicState {
+    options: DynamicOptions,
+    datanode_lease_secs: i64,
+    selector: SelectorRef,
+}
+
 #[derive(Clone)]
 pub struct MetaSrv {
     started: Arc<AtomicBool>,
     options: MetaSrvOptions,
+    dynamic: Arc<RwLock<DynamicState>>,
     // It is only valid at the leader node and is used to temporarily
     // store some data that will not be persisted.
     in_memory: ResetableKvStoreRef,
     kv_store: KvStoreRef,
+    // Kv store for leader-only mutations, which are rejected once the leadership is lost.
+    leader_kv_store: KvStoreRef,
     table_id_sequence: SequenceRef,
     selector: SelectorRef,
     handler_group: HeartbeatHandlerGroup,
     election: Option<ElectionRef>,
+    clock: ClockRef,
+    table_changes: TableChangeLogRef,
 }
 
 impl MetaSrv {
@@ -111,9 +160,22 @@ impl MetaSrv {
         handler_group: Option<HeartbeatHandlerGroup>,
     ) -> Self {
         let started = Arc::new(AtomicBool::new(false));
-        let table_id_sequence = Arc::new(Sequence::new(TABLE_ID_SEQ, 1024, 10, kv_store.clone()));
+        let leader_kv_store = match &election {
+            Some(election) => Arc::new(FencedKvStore::new(kv_store.clone(), election.clone())) as _,
+            None => kv_store.clone(),
+        };
+        let table_id_sequence = Arc::new(Sequence::new(
+            TABLE_ID_SEQ,
+            1024,
+            10,
+            leader_kv_store.clone(),
+        ));
         let selector = selector.unwrap_or_else(|| Arc::new(LeaseBasedSelector {}));
         let in_memory = Arc::new(MemStore::default());
+        let clock = clock::system_clock();
+        // Versions of table changes recorded by a previous metasrv process are unknown, the
+        // startup time distinguishes them.
+        let table_changes = Arc::new(TableChangeLog::new(clock.now_millis() as u64));
         let handler_group = match handler_group {
             Some(hg) => hg,
             None => {
@@ -124,7 +186,19 @@ impl MetaSrv {
                 // because even if the current meta-server node is no longer the leader it can
                 // still help the datanode to keep lease.
                 group.add_handler(keep_lease_handler).await;
+                group
+                    .add_handler(CheckClockSkewHandler::new(options.max_clock_skew_millis))
+                    .await;
                 group.add_handler(CheckLeaderHandler::default()).await;
+                group
+                    .add_handler(TableChangesHandler::new(table_changes.clone()))
+                    .await;
+                group
+                    .add_handler(TableTombstonesHandler::new(
+                        options.table_tombstone_retention_secs,
+                    ))
+                    .await;
+                group.add_handler(PromoteStandbyHandler::default()).await;
                 group.add_handler(OnLeaderStartHandler::default()).await;
                 group.add_handler(CollectStatsHandler::default()).await;
                 group.add_handler(PersistStatsHandler::default()).await;
@@ -132,18 +206,35 @@ impl MetaSrv {
             }
         };
 
+        let dynamic = Arc::new(RwLock::new(DynamicState {
+            options: DynamicOptions::default(),
+            datanode_lease_secs: options.datanode_lease_secs,
+            selector: selector.clone(),
+        }));
+
         Self {
             started,
             options,
+            dynamic,
             in_memory,
             kv_store,
+            leader_kv_store,
             table_id_sequence,
             selector,
             handler_group,
             election,
+            clock,
+            table_changes,
         }
     }
 
+    /// Replaces the system clock the metasrv reads the time from, e.g. by a mock clock
+    /// in tests.
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub async fn start(&self) {
         if self
             .started
@@ -169,9 +260,42 @@ impl MetaSrv {
             });
         }
 
+        let meta_srv = self.clone();
+        common_runtime::spawn_bg(async move {
+            while meta_srv.started.load(Ordering::Relaxed) {
+                if let Err(e) = meta_srv.reload_dynamic_options().await {
+                    warn!("Failed to reload dynamic options of MetaSrv: {}", e);
+                }
+                tokio::time::sleep(RELOAD_INTERVAL).await;
+            }
+        });
+
         info!("MetaSrv started");
     }
 
+    /// Applies the [DynamicOptions] in the kv store if they are changed. The selector is
+    /// rebuilt if it is overridden, otherwise the selector given at creation is used.
+    pub async fn reload_dynamic_options(&self) -> Result<()> {
+        let dynamic_options = DynamicOptions::load(&self.kv_store).await?;
+        if self.dynamic.read().unwrap().options == dynamic_options {
+            return Ok(());
+        }
+
+        let options = dynamic_options.apply(&self.options);
+        let selector = if options.selector == self.options.selector {
+            self.selector.clone()
+        } else {
+            build_selector(&options)?
+        };
+        info!("Apply dynamic options of MetaSrv: {:?}", dynamic_options);
+        *self.dynamic.write().unwrap() = DynamicState {
+            options: dynamic_options,
+            datanode_lease_secs: options.datanode_lease_secs,
+            selector,
+        };
+        Ok(())
+    }
+
     pub fn shutdown(&self) {
         self.started.store(false, Ordering::Relaxed);
     }
@@ -191,14 +315,28 @@ impl MetaSrv {
         self.kv_store.clone()
     }
 
+    /// Returns the kv store for leader-only mutations, which are rejected once the
+    /// leadership of current node is lost.
+    #[inline]
+    pub fn leader_kv_store(&self) -> KvStoreRef {
+        self.leader_kv_store.clone()
+    }
+
     #[inline]
     pub fn table_id_sequence(&self) -> SequenceRef {
         self.table_id_sequence.clone()
     }
 
+    /// Returns the selector in use, which may be overridden by [DynamicOptions].
     #[inline]
     pub fn selector(&self) -> SelectorRef {
-        self.selector.clone()
+        self.dynamic.read().unwrap().selector.clone()
+    }
+
+    /// Returns the lease of datanodes in use, which may be overridden by [DynamicOptions].
+    #[inline]
+    pub fn datanode_lease_secs(&self) -> i64 {
+        self.dynamic.read().unwrap().datanode_lease_secs
     }
 
     #[inline]
@@ -211,13 +349,24 @@ impl MetaSrv {
         self.election.clone()
     }
 
+    #[inline]
+    pub fn clock(&self) -> ClockRef {
+        self.clock.clone()
+    }
+
+    #[inline]
+    pub fn table_changes(&self) -> &TableChangeLogRef {
+        &self.table_changes
+    }
+
     #[inline]
     pub fn new_ctx(&self) -> Context {
-        let datanode_lease_secs = self.options().datanode_lease_secs;
+        let datanode_lease_secs = self.datanode_lease_secs();
         let server_addr = self.options().server_addr.clone();
         let in_memory = self.in_memory();
         let kv_store = self.kv_store();
         let election = self.election();
+        let clock = self.clock();
         let skip_all = Arc::new(AtomicBool::new(false));
         Context {
             datanode_lease_secs,
@@ -225,10 +374,59 @@ impl MetaSrv {
             in_memory,
             kv_store,
             election,
+            clock,
             skip_all,
             catalog: None,
             schema: None,
             table: None,
         }
     }
+
+    /// Creates the context of leader-only operations, whose mutations to the kv store are
+    /// rejected once the leadership of current node is lost.
+    pub fn new_leader_ctx(&self) -> Context {
+        Context {
+            kv_store: self.leader_kv_store(),
+            ..self.new_ctx()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::store::kv::KvStoreRef;
+
+    #[tokio::test]
+    async fn test_reload_dynamic_options() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let meta_srv = MetaSrv::new(
+            MetaSrvOptions::default(),
+            kv_store.clone(),
+            None,
+            None,
+            None,
+        )
+        .await;
+        let selector = meta_srv.selector();
+        meta_srv.reload_dynamic_options().await.unwrap();
+        assert_eq!(15, meta_srv.new_ctx().datanode_lease_secs);
+
+        DynamicOptions {
+            datanode_lease_secs: Some(5),
+            selector: Some(SelectorType::LoadBased),
+        }
+        .save(&kv_store)
+        .await
+        .unwrap();
+        meta_srv.reload_dynamic_options().await.unwrap();
+        assert_eq!(5, meta_srv.new_ctx().datanode_lease_secs);
+        assert!(!Arc::ptr_eq(&selector, &meta_srv.selector()));
+
+        // Removing the overrides restores the configured options.
+        DynamicOptions::default().save(&kv_store).await.unwrap();
+        meta_srv.reload_dynamic_options().await.unwrap();
+        assert_eq!(15, meta_srv.datanode_lease_secs());
+        assert!(Arc::ptr_eq(&selector, &meta_srv.selector()));
+    }
 }
@@ -0,0 +1,19 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! metasrv metrics
+
+pub const METRIC_DATANODE_CLOCK_SKEW_MILLIS: &str = "meta.datanode_clock_skew_millis";
+pub const METRIC_DATANODE_CLOCK_SKEW_EXCEEDED_TOTAL: &str =
+    "meta.datanode_clock_skew_exceeded_total";
@@ -12,15 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use api::v1::meta::heartbeat_server::HeartbeatServer;
 use api::v1::meta::router_server::RouterServer;
 use api::v1::meta::store_server::StoreServer;
+use api::v1::meta::FencingToken;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_time::clock::{self, ClockRef};
 use tower::service_fn;
 
-use crate::metasrv::{MetaSrv, MetaSrvOptions, SelectorRef};
+use crate::election::Election;
+use crate::error::Result;
+use crate::metasrv::{ElectionRef, LeaderValue, MetaSrv, MetaSrvOptions, SelectorRef};
 use crate::service::store::etcd::EtcdStore;
 use crate::service::store::kv::KvStoreRef;
 use crate::service::store::memory::MemStore;
@@ -51,8 +56,32 @@ pub async fn mock(
     kv_store: KvStoreRef,
     selector: Option<SelectorRef>,
 ) -> MockInfo {
-    let server_addr = opts.server_addr.clone();
-    let meta_srv = MetaSrv::new(opts, kv_store, selector, None, None).await;
+    mock_with_clock(opts, kv_store, selector, clock::system_clock()).await
+}
+
+/// Mocks a metasrv reading the time from `clock`.
+pub async fn mock_with_clock(
+    opts: MetaSrvOptions,
+    kv_store: KvStoreRef,
+    selector: Option<SelectorRef>,
+    clock: ClockRef,
+) -> MockInfo {
+    let meta_srv = MetaSrv::new(opts, kv_store, selector, None, None)
+        .await
+        .with_clock(clock);
+    serve(meta_srv)
+}
+
+/// Mocks a metasrv whose leadership is switched by `election`.
+pub async fn mock_with_election(election: Arc<MockElection>) -> MockInfo {
+    let kv_store = Arc::new(MemStore::default());
+    let election = election as ElectionRef;
+    let meta_srv = MetaSrv::new(Default::default(), kv_store, None, Some(election), None).await;
+    serve(meta_srv)
+}
+
+fn serve(meta_srv: MetaSrv) -> MockInfo {
+    let server_addr = meta_srv.options().server_addr.clone();
     let (client, server) = tokio::io::duplex(1024);
     tokio::spawn(async move {
         tonic::transport::Server::builder()
@@ -93,3 +122,56 @@ pub async fn mock(
         channel_manager,
     }
 }
+
+/// The election of a single metasrv, whose leadership is switched by hand.
+pub struct MockElection {
+    leader: String,
+    is_leader: AtomicBool,
+}
+
+impl MockElection {
+    /// Creates the election of the metasrv serving at `leader`, which is the leader.
+    pub fn new(leader: impl Into<String>) -> Self {
+        Self {
+            leader: leader.into(),
+            is_leader: AtomicBool::new(true),
+        }
+    }
+
+    pub fn set_leader(&self, is_leader: bool) {
+        self.is_leader.store(is_leader, Ordering::Relaxed);
+    }
+}
+
+#[async_trait::async_trait]
+impl Election for MockElection {
+    type Leader = LeaderValue;
+
+    fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::Relaxed)
+    }
+
+    fn fencing_token(&self) -> Option<FencingToken> {
+        self.is_leader().then(|| FencingToken {
+            leader_key: self.leader.as_bytes().to_vec(),
+            term: 1,
+        })
+    }
+
+    fn in_infancy(&self) -> bool {
+        false
+    }
+
+    async fn campaign(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn leader(&self) -> Result<LeaderValue> {
+        Ok(LeaderValue(self.leader.clone()))
+    }
+
+    async fn resign(&self) -> Result<()> {
+        self.set_leader(false);
+        Ok(())
+    }
+}
@@ -14,6 +14,8 @@
 
 pub mod lease_based;
 pub mod load_based;
+pub mod remote;
+pub mod spread;
 
 use std::sync::Arc;
 
@@ -21,9 +23,11 @@ use serde::{Deserialize, Serialize};
 
 use self::lease_based::LeaseBasedSelector;
 use self::load_based::LoadBasedSelector;
+use self::remote::RemoteSelector;
+use self::spread::SpreadSelector;
 use crate::error;
 use crate::error::Result;
-use crate::metasrv::SelectorRef;
+use crate::metasrv::{MetaSrvOptions, SelectorRef};
 
 pub type Namespace = u64;
 
@@ -39,14 +43,26 @@ pub trait Selector: Send + Sync {
 pub enum SelectorType {
     LoadBased,
     LeaseBased,
+    /// Delegates to an external scheduling service, see [RemoteSelector].
+    Remote,
 }
 
-impl From<SelectorType> for SelectorRef {
-    fn from(selector_type: SelectorType) -> Self {
-        match selector_type {
-            SelectorType::LoadBased => Arc::new(LoadBasedSelector) as SelectorRef,
-            SelectorType::LeaseBased => Arc::new(LeaseBasedSelector) as SelectorRef,
+/// Builds the selector configured in `opts`.
+pub fn build_selector(opts: &MetaSrvOptions) -> Result<SelectorRef> {
+    let selector = match opts.selector {
+        SelectorType::LoadBased => Arc::new(LoadBasedSelector) as SelectorRef,
+        SelectorType::LeaseBased => Arc::new(LeaseBasedSelector) as SelectorRef,
+        SelectorType::Remote => {
+            Arc::new(RemoteSelector::try_new(&opts.remote_selector)?) as SelectorRef
         }
+    };
+    if opts.placement_labels.is_empty() {
+        Ok(selector)
+    } else {
+        Ok(Arc::new(SpreadSelector::new(
+            selector,
+            opts.placement_labels.clone(),
+        )))
     }
 }
 
@@ -63,6 +79,7 @@ impl TryFrom<&str> for SelectorType {
         match value {
             "LoadBased" => Ok(SelectorType::LoadBased),
             "LeaseBased" => Ok(SelectorType::LeaseBased),
+            "Remote" => Ok(SelectorType::Remote),
             other => error::UnsupportedSelectorTypeSnafu {
                 selector_type: other,
             }
@@ -91,6 +108,10 @@ mod tests {
         let selector_type = loadbased.try_into().unwrap();
         assert_eq!(SelectorType::LoadBased, selector_type);
 
+        let remote = "Remote";
+        let selector_type = remote.try_into().unwrap();
+        assert_eq!(SelectorType::Remote, selector_type);
+
         let unknow = "unknow";
         let selector_type: Result<SelectorType> = unknow.try_into();
         assert!(selector_type.is_err());
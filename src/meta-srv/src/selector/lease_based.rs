@@ -13,7 +13,6 @@
 // limitations under the License.
 
 use api::v1::meta::Peer;
-use common_time::util as time_util;
 
 use crate::error::Result;
 use crate::keys::{LeaseKey, LeaseValue};
@@ -31,9 +30,9 @@ impl Selector for LeaseBasedSelector {
     async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
         // filter out the nodes out lease
         let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
-            time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
+            ctx.clock.now_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
         };
-        let mut lease_kvs = lease::alive_datanodes(ns, &ctx.kv_store, lease_filter).await?;
+        let mut lease_kvs = lease::schedulable_datanodes(ns, &ctx.kv_store, lease_filter).await?;
         // TODO(jiachun): At the moment we are just pushing the latest to the forefront,
         // and it is better to use load-based strategies in the future.
         lease_kvs.sort_by(|a, b| b.1.timestamp_millis.cmp(&a.1.timestamp_millis));
@@ -49,3 +48,73 @@ impl Selector for LeaseBasedSelector {
         Ok(peers)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use api::v1::meta::PutRequest;
+    use common_time::clock::{Clock, MockClock};
+
+    use super::*;
+    use crate::metasrv::{MetaSrv, MetaSrvOptions};
+    use crate::service::store::kv::KvStoreRef;
+    use crate::service::store::memory::MemStore;
+
+    async fn put_lease(kv_store: &KvStoreRef, node_id: u64, timestamp_millis: i64) {
+        let put = PutRequest {
+            key: LeaseKey {
+                cluster_id: 0,
+                node_id,
+            }
+            .try_into()
+            .unwrap(),
+            value: LeaseValue {
+                timestamp_millis,
+                node_addr: format!("127.0.0.1:400{node_id}"),
+                labels: Default::default(),
+            }
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(put).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_select_by_lease() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let clock = Arc::new(MockClock::new(100_000));
+        let meta_srv = MetaSrv::new(
+            MetaSrvOptions::default(),
+            kv_store.clone(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .with_clock(clock.clone());
+        let ctx = meta_srv.new_ctx();
+        assert_eq!(15, ctx.datanode_lease_secs);
+
+        put_lease(&kv_store, 1, 90_000).await;
+        put_lease(&kv_store, 2, 95_000).await;
+        let peers = LeaseBasedSelector.select(0, &ctx).await.unwrap();
+        // The latest heartbeat goes first.
+        assert_eq!(vec![2, 1], peers.iter().map(|p| p.id).collect::<Vec<_>>());
+
+        // The lease of datanode 1 expires.
+        clock.advance(Duration::from_secs(5));
+        let peers = LeaseBasedSelector.select(0, &ctx).await.unwrap();
+        assert_eq!(vec![2], peers.iter().map(|p| p.id).collect::<Vec<_>>());
+
+        clock.advance(Duration::from_secs(5));
+        assert!(LeaseBasedSelector.select(0, &ctx).await.unwrap().is_empty());
+
+        // Datanode 1 renews its lease.
+        put_lease(&kv_store, 1, clock.now_millis()).await;
+        let peers = LeaseBasedSelector.select(0, &ctx).await.unwrap();
+        assert_eq!(vec![1], peers.iter().map(|p| p.id).collect::<Vec<_>>());
+    }
+}
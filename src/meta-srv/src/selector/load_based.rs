@@ -15,7 +15,6 @@
 use std::collections::HashMap;
 
 use api::v1::meta::{Peer, RangeRequest};
-use common_time::util as time_util;
 
 use super::{Namespace, Selector};
 use crate::error::Result;
@@ -34,10 +33,10 @@ impl Selector for LoadBasedSelector {
     async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
         // get alive datanodes
         let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
-            time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
+            ctx.clock.now_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
         };
         let lease_kvs: HashMap<LeaseKey, LeaseValue> =
-            lease::alive_datanodes(ns, &ctx.kv_store, lease_filter)
+            lease::schedulable_datanodes(ns, &ctx.kv_store, lease_filter)
                 .await?
                 .into_iter()
                 .collect();
@@ -45,9 +44,11 @@ impl Selector for LoadBasedSelector {
         // get stats of datanodes
         let stat_kvs = all_stat_kvs(ns, &ctx.kv_store).await?;
 
-        // filter out expired datanodes and nodes that cannot get region number
+        // filter out expired datanodes, standby datanodes that only follow regions of other
+        // datanodes and nodes that cannot get region number
         let mut tuples: Vec<_> = stat_kvs
             .iter()
+            .filter(|(_, stat_val)| !stat_val.stats.last().map_or(false, |s| s.is_standby))
             .filter_map(|(stat_key, stat_val)| {
                 match (
                     lease_kvs.get(&to_lease_key(stat_key)),
@@ -0,0 +1,254 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use api::v1::meta::scheduler_client::SchedulerClient;
+use api::v1::meta::{Peer, RequestHeader, SelectRequest, TableName};
+use common_telemetry::warn;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::error::{self, Result};
+use crate::metasrv::Context;
+use crate::selector::lease_based::LeaseBasedSelector;
+use crate::selector::{Namespace, Selector};
+
+/// Options of the [RemoteSelector].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSelectorOptions {
+    /// Address of the external scheduling service, like `127.0.0.1:3100`.
+    pub addr: String,
+    /// Timeout of a selection, the peers selected by [LeaseBasedSelector] are used
+    /// once it's exceeded.
+    pub timeout_millis: u64,
+}
+
+impl Default for RemoteSelectorOptions {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:3100".to_string(),
+            timeout_millis: 3000,
+        }
+    }
+}
+
+/// Selector that delegates the selection to an external scheduling service over gRPC,
+/// so operators can integrate their own placement logic.
+///
+/// The scheduling service chooses among the alive datanodes found by [LeaseBasedSelector],
+/// which are also the fallback if it fails, times out or selects nothing.
+pub struct RemoteSelector {
+    client: SchedulerClient<Channel>,
+    timeout: Duration,
+}
+
+impl RemoteSelector {
+    pub fn try_new(opts: &RemoteSelectorOptions) -> Result<Self> {
+        let timeout = Duration::from_millis(opts.timeout_millis);
+        let channel = Endpoint::from_shared(format!("http://{}", opts.addr))
+            .context(error::InvalidSchedulerAddrSnafu { addr: &opts.addr })?
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .connect_lazy();
+        Ok(Self {
+            client: SchedulerClient::new(channel),
+            timeout,
+        })
+    }
+
+    async fn remote_select(
+        &self,
+        ns: Namespace,
+        ctx: &Context,
+        candidates: &[Peer],
+    ) -> std::result::Result<Vec<Peer>, String> {
+        let table_name = ctx.table.as_ref().map(|table| TableName {
+            catalog_name: ctx.catalog.clone().unwrap_or_default(),
+            schema_name: ctx.schema.clone().unwrap_or_default(),
+            table_name: table.clone(),
+        });
+        let request = SelectRequest {
+            header: Some(RequestHeader::new((ns, 0))),
+            table_name,
+            candidates: candidates.to_vec(),
+        };
+
+        let mut client = self.client.clone();
+        let response = tokio::time::timeout(self.timeout, client.select(request))
+            .await
+            .map_err(|_| format!("timeout after {:?}", self.timeout))?
+            .map_err(|status| status.to_string())?
+            .into_inner();
+        if let Some(err) = response.header.and_then(|header| header.error) {
+            return Err(err.err_msg);
+        }
+
+        // Only alive datanodes could hold the regions.
+        let candidates = candidates.iter().collect::<HashSet<_>>();
+        Ok(response
+            .peers
+            .into_iter()
+            .filter(|peer| candidates.contains(peer))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Selector for RemoteSelector {
+    type Context = Context;
+    type Output = Vec<Peer>;
+
+    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
+        let candidates = LeaseBasedSelector.select(ns, ctx).await?;
+        if candidates.is_empty() {
+            return Ok(candidates);
+        }
+
+        match self.remote_select(ns, ctx, &candidates).await {
+            Ok(peers) if !peers.is_empty() => Ok(peers),
+            Ok(_) => {
+                warn!("External scheduler selected no alive datanode, fallback to lease based");
+                Ok(candidates)
+            }
+            Err(e) => {
+                warn!("Failed to select via external scheduler: {e}, fallback to lease based");
+                Ok(candidates)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::scheduler_server::{Scheduler, SchedulerServer};
+    use api::v1::meta::{PutRequest, SelectResponse};
+    use common_time::util as time_util;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{Request, Response, Status};
+
+    use super::*;
+    use crate::keys::{LeaseKey, LeaseValue};
+    use crate::metasrv::{MetaSrv, MetaSrvOptions};
+    use crate::service::store::kv::KvStore;
+    use crate::service::store::memory::MemStore;
+
+    /// Selects the candidates in reversed order, plus a datanode that is not alive.
+    struct ReversedScheduler;
+
+    #[async_trait::async_trait]
+    impl Scheduler for ReversedScheduler {
+        async fn select(
+            &self,
+            request: Request<SelectRequest>,
+        ) -> std::result::Result<Response<SelectResponse>, Status> {
+            let request = request.into_inner();
+            assert_eq!("t", request.table_name.unwrap().table_name);
+            let mut peers = request.candidates;
+            peers.reverse();
+            peers.push(Peer {
+                id: 42,
+                addr: "127.0.0.1:42".to_string(),
+            });
+            Ok(Response::new(SelectResponse {
+                header: None,
+                peers,
+            }))
+        }
+    }
+
+    async fn new_ctx() -> Context {
+        let kv_store = Arc::new(MemStore::new());
+        for node_id in [1, 2] {
+            let put = PutRequest {
+                key: LeaseKey {
+                    cluster_id: 0,
+                    node_id,
+                }
+                .try_into()
+                .unwrap(),
+                value: LeaseValue {
+                    timestamp_millis: time_util::current_time_millis(),
+                    node_addr: format!("127.0.0.1:400{node_id}"),
+                    labels: Default::default(),
+                }
+                .try_into()
+                .unwrap(),
+                ..Default::default()
+            };
+            kv_store.put(put).await.unwrap();
+        }
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None, None).await;
+        let mut ctx = meta_srv.new_ctx();
+        ctx.catalog = Some("c".to_string());
+        ctx.schema = Some("s".to_string());
+        ctx.table = Some("t".to_string());
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_remote_select() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let _handle = tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(SchedulerServer::new(ReversedScheduler))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let ctx = new_ctx().await;
+        let lease_peers = LeaseBasedSelector.select(0, &ctx).await.unwrap();
+        assert_eq!(2, lease_peers.len());
+
+        let selector = RemoteSelector::try_new(&RemoteSelectorOptions {
+            addr,
+            timeout_millis: 3000,
+        })
+        .unwrap();
+        let peers = selector.select(0, &ctx).await.unwrap();
+        let mut expected = lease_peers;
+        expected.reverse();
+        assert_eq!(expected, peers);
+    }
+
+    #[tokio::test]
+    async fn test_remote_select_fallback() {
+        let ctx = new_ctx().await;
+        let lease_peers = LeaseBasedSelector.select(0, &ctx).await.unwrap();
+
+        // Nothing listens on the address.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let selector = RemoteSelector::try_new(&RemoteSelectorOptions {
+            addr,
+            timeout_millis: 500,
+        })
+        .unwrap();
+        let peers = selector.select(0, &ctx).await.unwrap();
+        assert_eq!(lease_peers, peers);
+
+        assert!(RemoteSelector::try_new(&RemoteSelectorOptions {
+            addr: "not a valid addr".to_string(),
+            timeout_millis: 500,
+        })
+        .is_err());
+    }
+}
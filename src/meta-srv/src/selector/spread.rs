@@ -0,0 +1,211 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::meta::Peer;
+
+use crate::error::Result;
+use crate::lease;
+use crate::metasrv::{Context, SelectorRef};
+use crate::selector::{Namespace, Selector};
+
+/// Selector that spreads the peers selected by the inner selector across failure domains,
+/// so that regions assigned to consecutive peers land in different zones or racks.
+///
+/// The failure domains are described by `labels` of the datanodes registered at heartbeat,
+/// ordered from the widest to the narrowest, like `["zone", "rack"]`.
+pub struct SpreadSelector {
+    inner: SelectorRef,
+    labels: Vec<String>,
+}
+
+impl SpreadSelector {
+    pub fn new(inner: SelectorRef, labels: Vec<String>) -> Self {
+        Self { inner, labels }
+    }
+}
+
+#[async_trait::async_trait]
+impl Selector for SpreadSelector {
+    type Context = Context;
+    type Output = Vec<Peer>;
+
+    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
+        let peers = self.inner.select(ns, ctx).await?;
+        if peers.len() <= 1 || self.labels.is_empty() {
+            return Ok(peers);
+        }
+
+        let node_labels = lease::alive_datanodes(ns, &ctx.kv_store, |_, _| true)
+            .await?
+            .into_iter()
+            .map(|(k, v)| (k.node_id, v.labels))
+            .collect::<HashMap<_, _>>();
+        let peers = peers
+            .into_iter()
+            .map(|peer| {
+                let values = self
+                    .labels
+                    .iter()
+                    .map(|label| {
+                        node_labels
+                            .get(&peer.id)
+                            .and_then(|labels| labels.get(label))
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                (peer, values)
+            })
+            .collect();
+        Ok(spread(peers, 0))
+    }
+}
+
+/// Groups the `peers` by their label values at `level`, spreads each group by the next
+/// levels, then interleaves the groups in a round robin way. Groups are ordered by their
+/// first peer, and peers keep their relative order in each group.
+fn spread(peers: Vec<(Peer, Vec<String>)>, level: usize) -> Vec<Peer> {
+    if peers.iter().all(|(_, values)| values.len() <= level) {
+        return peers.into_iter().map(|(peer, _)| peer).collect();
+    }
+
+    let mut groups: Vec<(String, Vec<(Peer, Vec<String>)>)> = vec![];
+    for (peer, values) in peers {
+        let value = values.get(level).cloned().unwrap_or_default();
+        match groups.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, group)) => group.push((peer, values)),
+            None => groups.push((value, vec![(peer, values)])),
+        }
+    }
+    let mut groups = groups
+        .into_iter()
+        .map(|(_, group)| spread(group, level + 1).into_iter())
+        .collect::<Vec<_>>();
+
+    let mut spread_peers = vec![];
+    loop {
+        let len = spread_peers.len();
+        spread_peers.extend(groups.iter_mut().filter_map(|group| group.next()));
+        if spread_peers.len() == len {
+            return spread_peers;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::PutRequest;
+    use common_time::util as time_util;
+
+    use super::*;
+    use crate::keys::{LeaseKey, LeaseValue};
+    use crate::metasrv::{MetaSrv, MetaSrvOptions};
+    use crate::selector::lease_based::LeaseBasedSelector;
+    use crate::service::store::kv::KvStore;
+    use crate::service::store::memory::MemStore;
+
+    fn peer(id: u64) -> Peer {
+        Peer {
+            id,
+            addr: format!("127.0.0.1:{id}"),
+        }
+    }
+
+    fn ids(peers: &[Peer]) -> Vec<u64> {
+        peers.iter().map(|peer| peer.id).collect()
+    }
+
+    #[test]
+    fn test_spread() {
+        let peers = [
+            (1, "z1", "r1"),
+            (2, "z1", "r1"),
+            (3, "z1", "r2"),
+            (4, "z2", "r1"),
+            (5, "z2", "r1"),
+            (6, "z3", "r1"),
+        ]
+        .into_iter()
+        .map(|(id, zone, rack)| (peer(id), vec![zone.to_string(), rack.to_string()]))
+        .collect::<Vec<_>>();
+
+        // Zones are interleaved, and racks are interleaved within a zone.
+        assert_eq!(vec![1, 4, 6, 3, 5, 2], ids(&spread(peers.clone(), 0)));
+        // Only spreads by the zone.
+        let zones = peers
+            .into_iter()
+            .map(|(peer, values)| (peer, values[..1].to_vec()))
+            .collect();
+        assert_eq!(vec![1, 4, 6, 2, 5, 3], ids(&spread(zones, 0)));
+
+        assert!(spread(vec![], 0).is_empty());
+        let no_labels = vec![(peer(1), vec![]), (peer(2), vec![])];
+        assert_eq!(vec![1, 2], ids(&spread(no_labels, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_spread_selector() {
+        let kv_store = Arc::new(MemStore::new());
+        // Node 5 has no labels.
+        let zones = [
+            (1, Some("z1")),
+            (2, Some("z2")),
+            (3, Some("z1")),
+            (4, Some("z1")),
+            (5, None),
+        ];
+        for (node_id, zone) in zones {
+            let labels = zone
+                .map(|zone| HashMap::from([("zone".to_string(), zone.to_string())]))
+                .unwrap_or_default();
+            let put = PutRequest {
+                key: LeaseKey {
+                    cluster_id: 0,
+                    node_id,
+                }
+                .try_into()
+                .unwrap(),
+                value: LeaseValue {
+                    // Newer nodes are selected first by the lease based selector.
+                    timestamp_millis: time_util::current_time_millis() + node_id as i64,
+                    node_addr: format!("127.0.0.1:{node_id}"),
+                    labels,
+                }
+                .try_into()
+                .unwrap(),
+                ..Default::default()
+            };
+            kv_store.put(put).await.unwrap();
+        }
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None, None).await;
+        let ctx = meta_srv.new_ctx();
+
+        let lease_based = Arc::new(LeaseBasedSelector) as SelectorRef;
+        let peers = lease_based.select(0, &ctx).await.unwrap();
+        assert_eq!(vec![5, 4, 3, 2, 1], ids(&peers));
+
+        let selector = SpreadSelector::new(lease_based.clone(), vec!["zone".to_string()]);
+        let peers = selector.select(0, &ctx).await.unwrap();
+        assert_eq!(vec![5, 4, 2, 3, 1], ids(&peers));
+
+        // Unknown labels are treated as the same failure domain.
+        let selector = SpreadSelector::new(lease_based, vec!["rack".to_string()]);
+        let peers = selector.select(0, &ctx).await.unwrap();
+        assert_eq!(vec![5, 4, 3, 2, 1], ids(&peers));
+    }
+}
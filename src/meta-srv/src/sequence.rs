@@ -15,7 +15,7 @@
 use std::ops::Range;
 use std::sync::Arc;
 
-use api::v1::meta::CompareAndPutRequest;
+use api::v1::meta::{CompareAndPutRequest, RangeRequest};
 use snafu::ensure;
 use tokio::sync::Mutex;
 
@@ -50,6 +50,13 @@ impl Sequence {
         let mut inner = self.inner.lock().await;
         inner.next().await
     }
+
+    /// Returns the value the next call of [`Sequence::next`] would most likely yield,
+    /// without allocating it.
+    pub async fn peek(&self) -> Result<u64> {
+        let inner = self.inner.lock().await;
+        inner.peek().await
+    }
 }
 
 struct Inner {
@@ -97,6 +104,35 @@ impl Inner {
         .fail()
     }
 
+    /// Returns the `next` value if it is in the `range`, otherwise the start of
+    /// the range the `generator` would hand out, without fetching it.
+    pub async fn peek(&self) -> Result<u64> {
+        if let Some(range) = &self.range {
+            if range.contains(&self.next) {
+                return Ok(self.next);
+            }
+        }
+
+        let req = RangeRequest {
+            key: self.name.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        let res = self.generator.range(req).await?;
+        match res.kvs.into_iter().next() {
+            Some(kv) => {
+                let value = kv.value;
+                ensure!(
+                    value.len() == std::mem::size_of::<u64>(),
+                    error::UnexceptedSequenceValueSnafu {
+                        err_msg: format!("key={}, unexpected value={:?}", self.name, value)
+                    }
+                );
+                Ok(u64::from_le_bytes(value.try_into().unwrap()))
+            }
+            None => Ok(self.initial),
+        }
+    }
+
     pub async fn next_range(&self) -> Result<Range<u64>> {
         let key = self.name.as_bytes();
         let mut start = self.next;
@@ -165,6 +201,27 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_sequence_peek() {
+        let kv_store = Arc::new(MemStore::new());
+        let initial = 1024;
+        let seq = Sequence::new("test_seq", initial, 10, kv_store.clone());
+
+        assert_eq!(initial, seq.peek().await.unwrap());
+        assert_eq!(initial, seq.peek().await.unwrap());
+        assert_eq!(initial, seq.next().await.unwrap());
+
+        for i in initial + 1..initial + 25 {
+            assert_eq!(i, seq.peek().await.unwrap());
+            assert_eq!(i, seq.next().await.unwrap());
+        }
+
+        // Another sequence sharing the generator starts from the next unallocated range.
+        let other = Sequence::new("test_seq", initial, 10, kv_store);
+        assert_eq!(initial + 30, other.peek().await.unwrap());
+        assert_eq!(initial + 30, other.next().await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_sequence_fouce_quit() {
         struct Noop;
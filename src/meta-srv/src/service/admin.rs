@@ -13,24 +13,51 @@
 // limitations under the License.
 
 mod health;
+mod maintenance;
+mod scrub;
+mod standby;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use common_grpc::token::ClusterToken;
 use tonic::body::BoxBody;
 use tonic::codegen::{empty_body, http, BoxFuture, Service};
 use tonic::transport::NamedService;
 
 use crate::metasrv::MetaSrv;
 
-pub fn make_admin_service(_: MetaSrv) -> Admin {
-    let router = Router::new().route("/health", health::HealthHandler);
+pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
+    let maintenance_handler = || maintenance::MaintenanceHandler {
+        kv_store: meta_srv.leader_kv_store(),
+        clock: meta_srv.clock(),
+        table_changes: meta_srv.table_changes().clone(),
+        datanode_lease_secs: meta_srv.datanode_lease_secs(),
+    };
+    let router = Router::new()
+        .route("/health", health::HealthHandler)
+        .route("/maintenance", maintenance_handler())
+        .route_mut("/cordon", maintenance_handler())
+        .route_mut("/drain", maintenance_handler())
+        .route_mut("/uncordon", maintenance_handler())
+        .route_mut(
+            "/promote",
+            standby::PromoteHandler {
+                kv_store: meta_srv.leader_kv_store(),
+            },
+        )
+        .route(
+            "/corrupted-files",
+            scrub::CorruptedFilesHandler {
+                kv_store: meta_srv.leader_kv_store(),
+            },
+        );
 
     let router = Router::nest("/admin", router);
 
-    Admin::new(router)
+    Admin::new(router).with_admin_token(meta_srv.options().admin_token.clone())
 }
 
 #[async_trait::async_trait]
@@ -48,14 +75,33 @@ where
     Self: Send,
 {
     router: Arc<Router>,
+    admin_token: Option<ClusterToken>,
 }
 
 impl Admin {
     pub fn new(router: Router) -> Self {
         Self {
             router: Arc::new(router),
+            admin_token: None,
         }
     }
+
+    /// Sets the token authorizing the requests changing the cluster, such requests are
+    /// all rejected if there is no token.
+    pub fn with_admin_token(mut self, admin_token: Option<ClusterToken>) -> Self {
+        self.admin_token = admin_token;
+        self
+    }
+
+    fn is_authorized<T>(&self, req: &http::Request<T>) -> bool {
+        let Some(token) = &self.admin_token else {
+            return false;
+        };
+        req.headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.as_bytes().strip_prefix(b"Bearer "))
+            .map_or(false, |value| token.matches(value))
+    }
 }
 
 impl NamedService for Admin {
@@ -86,19 +132,25 @@ where
             })
             .unwrap_or_else(HashMap::new);
         let path = req.uri().path().to_owned();
-        Box::pin(async move { router.call(&path, query_params).await })
+        let method = req.method().clone();
+        let authorized = self.is_authorized(&req);
+        Box::pin(async move { router.call(&method, &path, query_params, authorized).await })
     }
 }
 
 #[derive(Default)]
 pub struct Router {
     handlers: HashMap<String, Box<dyn HttpHandler>>,
+    /// Paths of the handlers changing the cluster, which only accept authorized POST
+    /// requests.
+    mutating_paths: HashSet<String>,
 }
 
 impl Router {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::default(),
+            mutating_paths: HashSet::default(),
         }
     }
 
@@ -110,8 +162,16 @@ impl Router {
             .into_iter()
             .map(|(url, handler)| (format!("{path}{url}"), handler))
             .collect();
+        let mutating_paths = router
+            .mutating_paths
+            .into_iter()
+            .map(|url| format!("{path}{url}"))
+            .collect();
 
-        Self { handlers }
+        Self {
+            handlers,
+            mutating_paths,
+        }
     }
 
     pub fn route(mut self, path: &str, handler: impl HttpHandler + 'static) -> Self {
@@ -122,10 +182,22 @@ impl Router {
         self
     }
 
+    /// Routes the `path` to the `handler` changing the cluster.
+    pub fn route_mut(mut self, path: &str, handler: impl HttpHandler + 'static) -> Self {
+        self = self.route(path, handler);
+        self.mutating_paths.insert(path.to_owned());
+
+        self
+    }
+
+    /// Calls the handler of the `path`, `authorized` tells whether the request carries the
+    /// admin token.
     pub async fn call(
         &self,
+        method: &http::Method,
         path: &str,
         params: HashMap<String, String>,
+        authorized: bool,
     ) -> Result<http::Response<BoxBody>, Infallible> {
         let handler = match self.handlers.get(path) {
             Some(handler) => handler,
@@ -136,6 +208,21 @@ impl Router {
                     .unwrap())
             }
         };
+        if self.mutating_paths.contains(path) {
+            if method != http::Method::POST {
+                return Ok(http::Response::builder()
+                    .status(http::StatusCode::METHOD_NOT_ALLOWED)
+                    .header(http::header::ALLOW, "POST")
+                    .body(empty_body())
+                    .unwrap());
+            }
+            if !authorized {
+                return Ok(http::Response::builder()
+                    .status(http::StatusCode::UNAUTHORIZED)
+                    .body(boxed("Invalid or missing admin token\n".to_string()))
+                    .unwrap());
+            }
+        }
 
         let res = match handler.handle(path, &params).await {
             Ok(res) => res.map(boxed),
@@ -223,7 +310,12 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+                false,
+            )
             .await
             .unwrap();
 
@@ -235,7 +327,12 @@ mod tests {
         let router = Router::new();
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+                false,
+            )
             .await
             .unwrap();
 
@@ -249,10 +346,57 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                &http::Method::GET,
+                "/test_root/test_node",
+                HashMap::default(),
+                false,
+            )
             .await
             .unwrap();
 
         assert_eq!(http::StatusCode::INTERNAL_SERVER_ERROR, res.status());
     }
+
+    #[tokio::test]
+    async fn test_route_call_mutating() {
+        let router = Router::new().route_mut("/test_node", MockOkHandler);
+        let router = Router::nest("/test_root", router);
+        let call = |method, authorized| {
+            router.call(
+                method,
+                "/test_root/test_node",
+                HashMap::default(),
+                authorized,
+            )
+        };
+
+        let res = call(&http::Method::GET, true).await.unwrap();
+        assert_eq!(http::StatusCode::METHOD_NOT_ALLOWED, res.status());
+        let res = call(&http::Method::POST, false).await.unwrap();
+        assert_eq!(http::StatusCode::UNAUTHORIZED, res.status());
+        let res = call(&http::Method::POST, true).await.unwrap();
+        assert!(res.status().is_success());
+    }
+
+    #[test]
+    fn test_admin_authorized() {
+        let request = |authorization: Option<&str>| {
+            let mut builder = http::Request::builder();
+            if let Some(authorization) = authorization {
+                builder = builder.header(http::header::AUTHORIZATION, authorization);
+            }
+            builder.body(()).unwrap()
+        };
+
+        // Nothing is authorized without the admin token.
+        let admin = Admin::new(Router::new());
+        assert!(!admin.is_authorized(&request(Some("Bearer secret"))));
+
+        let admin = admin.with_admin_token(Some(ClusterToken::new("secret")));
+        assert!(admin.is_authorized(&request(Some("Bearer secret"))));
+        assert!(!admin.is_authorized(&request(Some("Bearer secreT"))));
+        assert!(!admin.is_authorized(&request(Some("secret"))));
+        assert!(!admin.is_authorized(&request(None)));
+    }
 }
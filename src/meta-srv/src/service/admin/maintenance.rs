@@ -0,0 +1,281 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_time::clock::ClockRef;
+use serde::Serialize;
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::keys::MaintenanceState;
+use crate::maintenance;
+use crate::selector::load_based::all_stat_kvs;
+use crate::service::admin::HttpHandler;
+use crate::service::router::standby_followers;
+use crate::service::store::kv::KvStoreRef;
+use crate::table_changes::TableChangeLogRef;
+
+/// Handles the maintenance of datanodes:
+/// - `/cordon?node_id=1` stops placing new regions on the datanode.
+/// - `/drain?node_id=1` cordons the datanode for decommission and moves its regions to the
+///   standby datanodes following them, responds the numbers of `moved` and `pending`
+///   regions. Draining again moves the pending regions followed by new standby datanodes.
+/// - `/uncordon?node_id=1` brings the datanode back to service.
+/// - `/maintenance` lists the datanodes under maintenance.
+///
+/// All paths accept an optional `cluster_id`, defaults to 0.
+pub struct MaintenanceHandler {
+    pub kv_store: KvStoreRef,
+    pub clock: ClockRef,
+    pub table_changes: TableChangeLogRef,
+    pub datanode_lease_secs: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct MaintenanceStatus {
+    node_id: u64,
+    state: MaintenanceState,
+    timestamp_millis: i64,
+    /// Regions still on the datanode, reported by its latest heartbeat.
+    region_num: Option<u64>,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for MaintenanceHandler {
+    async fn handle(
+        &self,
+        path: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let cluster_id = match params.get("cluster_id") {
+            Some(cluster_id) => parse_id(cluster_id)?,
+            None => 0,
+        };
+
+        let state = if path.ends_with("/cordon") {
+            Some(MaintenanceState::Cordoned)
+        } else if path.ends_with("/drain") {
+            Some(MaintenanceState::Draining)
+        } else if path.ends_with("/uncordon") {
+            None
+        } else {
+            return self.list(cluster_id).await;
+        };
+
+        let node_id = params
+            .get("node_id")
+            .context(error::InvalidArgumentsSnafu {
+                err_msg: "missing node_id",
+            })?;
+        let node_id = parse_id(node_id)?;
+        maintenance::set_maintenance(cluster_id, node_id, state, &self.kv_store, &self.clock)
+            .await?;
+        if state == Some(MaintenanceState::Draining) {
+            return self.drain(cluster_id, node_id).await;
+        }
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body("OK\n".to_string())
+            .unwrap())
+    }
+}
+
+impl MaintenanceHandler {
+    async fn drain(&self, cluster_id: u64, node_id: u64) -> Result<http::Response<String>> {
+        let followers = standby_followers(
+            cluster_id,
+            &self.kv_store,
+            &self.clock,
+            self.datanode_lease_secs,
+        )
+        .await?;
+        let drained = maintenance::drain_regions(
+            cluster_id,
+            node_id,
+            &followers,
+            &self.kv_store,
+            &self.table_changes,
+        )
+        .await?;
+
+        let body = serde_json::to_string(&drained).context(error::SerializeToJsonSnafu {
+            input: format!("{drained:?}"),
+        })?;
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+
+    async fn list(&self, cluster_id: u64) -> Result<http::Response<String>> {
+        let nodes = maintenance::maintenance_nodes(cluster_id, &self.kv_store).await?;
+        let region_nums = all_stat_kvs(cluster_id, &self.kv_store)
+            .await?
+            .into_iter()
+            .map(|(k, v)| (k.node_id, v.region_num()))
+            .collect::<HashMap<_, _>>();
+
+        let mut statuses = nodes
+            .into_iter()
+            .map(|(node_id, value)| MaintenanceStatus {
+                node_id,
+                state: value.state,
+                timestamp_millis: value.timestamp_millis,
+                region_num: region_nums.get(&node_id).copied().flatten(),
+            })
+            .collect::<Vec<_>>();
+        statuses.sort_by_key(|status| status.node_id);
+
+        let body = serde_json::to_string(&statuses).context(error::SerializeToJsonSnafu {
+            input: format!("{statuses:?}"),
+        })?;
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+pub(super) fn parse_id(id: &str) -> Result<u64> {
+    id.parse().context(error::ParseNumSnafu {
+        err_msg: format!("invalid id: {id}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::PutRequest;
+    use common_time::clock::{Clock, MockClock};
+
+    use super::*;
+    use crate::handler::node_stat::Stat;
+    use crate::keys::{LeaseKey, LeaseValue, StatKey, StatValue};
+    use crate::metasrv::{MetaSrv, MetaSrvOptions};
+    use crate::selector::Selector;
+    use crate::service::store::memory::MemStore;
+
+    async fn put_datanode(kv_store: &KvStoreRef, node_id: u64, region_num: u64, now_millis: i64) {
+        let put = PutRequest {
+            key: LeaseKey {
+                cluster_id: 0,
+                node_id,
+            }
+            .try_into()
+            .unwrap(),
+            value: LeaseValue {
+                timestamp_millis: now_millis,
+                node_addr: format!("127.0.0.1:{node_id}"),
+                labels: Default::default(),
+            }
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(put).await.unwrap();
+
+        let put = PutRequest {
+            key: StatKey {
+                cluster_id: 0,
+                node_id,
+            }
+            .into(),
+            value: StatValue {
+                stats: vec![Stat {
+                    region_num: Some(region_num),
+                    ..Default::default()
+                }],
+            }
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(put).await.unwrap();
+    }
+
+    async fn selected_ids(meta_srv: &MetaSrv) -> Vec<u64> {
+        let mut ids = meta_srv
+            .selector()
+            .select(0, &meta_srv.new_ctx())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|peer| peer.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_handle() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let clock = Arc::new(MockClock::new(100_000));
+        for node_id in 1..=3 {
+            put_datanode(&kv_store, node_id, node_id * 10, clock.now_millis()).await;
+        }
+        let meta_srv = MetaSrv::new(
+            MetaSrvOptions::default(),
+            kv_store.clone(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .with_clock(clock.clone());
+        let handler = MaintenanceHandler {
+            kv_store,
+            clock: clock.clone(),
+            table_changes: meta_srv.table_changes().clone(),
+            datanode_lease_secs: meta_srv.datanode_lease_secs(),
+        };
+        assert_eq!(vec![1, 2, 3], selected_ids(&meta_srv).await);
+
+        let params = HashMap::from([("node_id".to_string(), "1".to_string())]);
+        let res = handler.handle("/admin/cordon", &params).await.unwrap();
+        assert!(res.status().is_success());
+        let params = HashMap::from([("node_id".to_string(), "2".to_string())]);
+        let res = handler.handle("/admin/drain", &params).await.unwrap();
+        // No table is routed to the datanode.
+        assert_eq!(r#"{"moved":0,"pending":0}"#, res.body());
+        assert_eq!(vec![3], selected_ids(&meta_srv).await);
+
+        let res = handler
+            .handle("/admin/maintenance", &HashMap::new())
+            .await
+            .unwrap();
+        let statuses: serde_json::Value = serde_json::from_str(res.body()).unwrap();
+        let statuses = statuses.as_array().unwrap();
+        assert_eq!(2, statuses.len());
+        assert_eq!(1, statuses[0]["node_id"]);
+        assert_eq!("Cordoned", statuses[0]["state"]);
+        assert_eq!(2, statuses[1]["node_id"]);
+        assert_eq!("Draining", statuses[1]["state"]);
+        assert_eq!(20, statuses[1]["region_num"]);
+
+        let params = HashMap::from([("node_id".to_string(), "1".to_string())]);
+        handler.handle("/admin/uncordon", &params).await.unwrap();
+        assert_eq!(vec![1, 3], selected_ids(&meta_srv).await);
+
+        assert!(handler
+            .handle("/admin/cordon", &HashMap::new())
+            .await
+            .is_err());
+        let params = HashMap::from([("node_id".to_string(), "a".to_string())]);
+        assert!(handler.handle("/admin/drain", &params).await.is_err());
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use snafu::ResultExt;
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::selector::load_based::all_stat_kvs;
+use crate::service::admin::maintenance::parse_id;
+use crate::service::admin::HttpHandler;
+use crate::service::store::kv::KvStoreRef;
+
+/// Lists SST files failed to verify by the last scrub of each region, reported by the
+/// latest heartbeats of datanodes: `/corrupted-files?cluster_id=0`.
+pub struct CorruptedFilesHandler {
+    pub kv_store: KvStoreRef,
+}
+
+#[derive(Debug, Serialize)]
+struct CorruptedFiles {
+    node_id: u64,
+    region_id: u64,
+    catalog: String,
+    schema: String,
+    table: String,
+    files: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for CorruptedFilesHandler {
+    async fn handle(
+        &self,
+        _path: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let cluster_id = match params.get("cluster_id") {
+            Some(cluster_id) => parse_id(cluster_id)?,
+            None => 0,
+        };
+
+        let mut corrupted = vec![];
+        for (key, value) in all_stat_kvs(cluster_id, &self.kv_store).await? {
+            // The latest stat is at the front.
+            let Some(stat) = value.stats.into_iter().next() else {
+                continue;
+            };
+            corrupted.extend(
+                stat.region_stats
+                    .into_iter()
+                    .filter(|region_stat| !region_stat.corrupted_files.is_empty())
+                    .map(|region_stat| CorruptedFiles {
+                        node_id: key.node_id,
+                        region_id: region_stat.id,
+                        catalog: region_stat.catalog,
+                        schema: region_stat.schema,
+                        table: region_stat.table,
+                        files: region_stat.corrupted_files,
+                    }),
+            );
+        }
+        corrupted.sort_by_key(|files| (files.node_id, files.region_id));
+
+        let body = serde_json::to_string(&corrupted).context(error::SerializeToJsonSnafu {
+            input: format!("{corrupted:?}"),
+        })?;
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::PutRequest;
+
+    use super::*;
+    use crate::handler::node_stat::{RegionStat, Stat};
+    use crate::keys::{StatKey, StatValue};
+    use crate::service::store::memory::MemStore;
+
+    fn new_region_stat(id: u64, corrupted_files: Vec<String>) -> RegionStat {
+        RegionStat {
+            id,
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "demo".to_string(),
+            rcus: 0,
+            wcus: 0,
+            approximate_bytes: 0,
+            approximate_rows: 0,
+            memtable_bytes: 0,
+            sst_bytes: 0,
+            written_rows: 0,
+            write_rate: 0.0,
+            corrupted_files,
+            last_write_millis: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_corrupted_files() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let put = PutRequest {
+            key: StatKey {
+                cluster_id: 0,
+                node_id: 1,
+            }
+            .into(),
+            value: StatValue {
+                stats: vec![
+                    Stat {
+                        region_stats: vec![
+                            new_region_stat(1, vec![]),
+                            new_region_stat(2, vec!["a.parquet".to_string()]),
+                        ],
+                        ..Default::default()
+                    },
+                    // Stale stat.
+                    Stat {
+                        region_stats: vec![new_region_stat(1, vec!["b.parquet".to_string()])],
+                        ..Default::default()
+                    },
+                ],
+            }
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        };
+        kv_store.put(put).await.unwrap();
+
+        let handler = CorruptedFilesHandler { kv_store };
+        let res = handler
+            .handle("/admin/corrupted-files", &HashMap::new())
+            .await
+            .unwrap();
+        let corrupted: serde_json::Value = serde_json::from_str(res.body()).unwrap();
+        let corrupted = corrupted.as_array().unwrap();
+        assert_eq!(1, corrupted.len());
+        assert_eq!(1, corrupted[0]["node_id"]);
+        assert_eq!(2, corrupted[0]["region_id"]);
+        assert_eq!("demo", corrupted[0]["table"]);
+        assert_eq!(serde_json::json!(["a.parquet"]), corrupted[0]["files"]);
+    }
+}
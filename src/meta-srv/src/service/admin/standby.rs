@@ -0,0 +1,82 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::OptionExt;
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::service::admin::maintenance::parse_id;
+use crate::service::admin::HttpHandler;
+use crate::service::store::kv::KvStoreRef;
+use crate::standby;
+
+/// Promotes a standby datanode to take over writes of its tables: `/promote?node_id=1`,
+/// with an optional `cluster_id` defaults to 0. The datanodes writing the tables must
+/// have stopped. The datanode is promoted on its next heartbeat.
+pub struct PromoteHandler {
+    pub kv_store: KvStoreRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for PromoteHandler {
+    async fn handle(
+        &self,
+        _path: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let cluster_id = match params.get("cluster_id") {
+            Some(cluster_id) => parse_id(cluster_id)?,
+            None => 0,
+        };
+        let node_id = params
+            .get("node_id")
+            .context(error::InvalidArgumentsSnafu {
+                err_msg: "missing node_id",
+            })?;
+        let node_id = parse_id(node_id)?;
+        standby::request_promotion(cluster_id, node_id, &self.kv_store).await?;
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body("OK\n".to_string())
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_promote_handle() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        let handler = PromoteHandler {
+            kv_store: kv_store.clone(),
+        };
+        assert!(handler
+            .handle("/admin/promote", &HashMap::new())
+            .await
+            .is_err());
+
+        let params = HashMap::from([("node_id".to_string(), "1".to_string())]);
+        let res = handler.handle("/admin/promote", &params).await.unwrap();
+        assert_eq!(http::StatusCode::OK, res.status());
+        assert!(standby::take_promotion(0, 1, &kv_store).await.unwrap());
+    }
+}
@@ -43,7 +43,8 @@ impl heartbeat_server::Heartbeat for MetaSrv {
         let mut in_stream = req.into_inner();
         let (tx, rx) = mpsc::channel(128);
         let handler_group = self.handler_group();
-        let ctx = self.new_ctx();
+        // Handlers after `CheckLeaderHandler` mutate the kv store only on the leader.
+        let ctx = self.new_leader_ctx();
         common_runtime::spawn_bg(async move {
             let mut pusher_key = None;
             while let Some(msg) = in_stream.next().await {
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use api::v1::meta::{
     router_server, CreateRequest, DeleteRequest, Error, MoveValueRequest, Peer, PeerDict,
     PutRequest, RangeRequest, Region, RegionRoute, ResponseHeader, RouteRequest, RouteResponse,
@@ -19,16 +21,19 @@ use api::v1::meta::{
 };
 use catalog::helper::{TableGlobalKey, TableGlobalValue};
 use common_telemetry::warn;
+use common_time::clock::ClockRef;
 use snafu::{OptionExt, ResultExt};
 use tonic::{Request, Response};
 
-use crate::error;
 use crate::error::Result;
-use crate::keys::TableRouteKey;
+use crate::keys::{LeaseKey, LeaseValue, TableRouteKey};
 use crate::metasrv::{Context, MetaSrv, SelectorRef};
+use crate::selector::load_based::all_stat_kvs;
 use crate::sequence::SequenceRef;
 use crate::service::store::kv::KvStoreRef;
 use crate::service::GrpcResult;
+use crate::table_tombstones::put_table_tombstone;
+use crate::{error, lease};
 
 #[async_trait::async_trait]
 impl router_server::Router for MetaSrv {
@@ -37,12 +42,14 @@ impl router_server::Router for MetaSrv {
 
         let CreateRequest { table_name, .. } = &req;
         let table_name = table_name.clone().context(error::EmptyTableNameSnafu)?;
-        let ctx = self.create_ctx(table_name);
+        let ctx = self.create_ctx(table_name.clone());
 
         let selector = self.selector();
         let table_id_sequence = self.table_id_sequence();
 
         let res = handle_create(req, ctx, selector, table_id_sequence).await?;
+        // Routes of a dropped table with the same name may be cached.
+        self.table_changes().record(table_name);
 
         Ok(Response::new(res))
     }
@@ -57,8 +64,12 @@ impl router_server::Router for MetaSrv {
 
     async fn delete(&self, req: Request<DeleteRequest>) -> GrpcResult<RouteResponse> {
         let req = req.into_inner();
-        let ctx = self.new_ctx();
+        let table_name = req.table_name.clone();
+        let ctx = self.new_leader_ctx();
         let res = handle_delete(req, ctx).await?;
+        if let Some(table_name) = table_name {
+            self.table_changes().record(table_name);
+        }
 
         Ok(Response::new(res))
     }
@@ -66,7 +77,7 @@ impl router_server::Router for MetaSrv {
 
 impl MetaSrv {
     fn create_ctx(&self, table_name: TableName) -> Context {
-        let mut ctx = self.new_ctx();
+        let mut ctx = self.new_leader_ctx();
         let TableName {
             catalog_name,
             schema_name,
@@ -89,6 +100,7 @@ async fn handle_create(
         header,
         table_name,
         partitions,
+        dry_run,
     } = req;
     let table_name = table_name.context(error::EmptyTableNameSnafu)?;
     let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
@@ -104,7 +116,14 @@ async fn handle_create(
             ..Default::default()
         });
     }
-    let id = table_id_sequence.next().await?;
+    // The table id is not allocated in dry run mode, so that previewing the
+    // placement does not leave holes in the sequence. The previewed id is the one
+    // the table would most likely get, though a concurrent creation may take it.
+    let id = if dry_run {
+        table_id_sequence.peek().await?
+    } else {
+        table_id_sequence.next().await?
+    };
     let table_route_key = TableRouteKey::with_table_name(id, &table_name)
         .key()
         .into_bytes();
@@ -124,7 +143,9 @@ async fn handle_create(
         let region_route = RegionRoute {
             region: Some(region),
             leader_peer_index: (i % peers.len()) as u64,
-            follower_peer_indexes: vec![], // follower_peers is not supported at the moment
+            // Followers are the standby datanodes serving the region, which are filled by
+            // their heartbeats when the route is requested.
+            follower_peer_indexes: vec![],
         };
         region_routes.push(region_route);
     }
@@ -133,12 +154,14 @@ async fn handle_create(
         region_routes,
     };
 
-    // save table route data into meta store
-    let table_route_value = TableRouteValue {
-        peers: peers.clone(),
-        table_route: Some(table_route.clone()),
-    };
-    put_into_store(&ctx.kv_store, table_route_key, table_route_value).await?;
+    if !dry_run {
+        // save table route data into meta store
+        let table_route_value = TableRouteValue {
+            peers: peers.clone(),
+            table_route: Some(table_route.clone()),
+        };
+        put_into_store(&ctx.kv_store, table_route_key, table_route_value).await?;
+    }
 
     let header = Some(ResponseHeader::success(cluster_id));
     Ok(RouteResponse {
@@ -160,7 +183,14 @@ async fn handle_route(req: RouteRequest, ctx: Context) -> Result<RouteResponse>
         table_name: t.table_name,
     });
     let tables = fetch_tables(&ctx.kv_store, table_global_keys).await?;
-    let (peers, table_routes) = fill_table_routes(tables)?;
+    let followers = standby_followers(
+        cluster_id,
+        &ctx.kv_store,
+        &ctx.clock,
+        ctx.datanode_lease_secs,
+    )
+    .await?;
+    let (peers, table_routes) = fill_table_routes(tables, &followers)?;
 
     let header = Some(ResponseHeader::success(cluster_id));
     Ok(RouteResponse {
@@ -188,7 +218,14 @@ async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse
         })?;
     let trk = TableRouteKey::with_table_global_key(tgv.table_id() as u64, &tgk);
     let (_, trv) = remove_table_route_value(&ctx.kv_store, &trk).await?;
-    let (peers, table_routes) = fill_table_routes(vec![(tgv, trv)])?;
+
+    // Datanodes may still receive writes to the dropped table, e.g. from frontends
+    // caching its routes, the tombstone lets them reject the writes. Expired tombstones are
+    // purged by the `TableTombstonesHandler`.
+    let now_millis = ctx.clock.now_millis();
+    put_table_tombstone(tgv.table_id(), tgk.to_string(), now_millis, &ctx.kv_store).await?;
+
+    let (peers, table_routes) = fill_table_routes(vec![(tgv, trv)], &HashMap::new())?;
 
     let header = Some(ResponseHeader::success(cluster_id));
     Ok(RouteResponse {
@@ -198,8 +235,50 @@ async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse
     })
 }
 
+/// Returns the alive standby datanodes by the ids of the regions they serve, which are
+/// the followers of the regions.
+pub(crate) async fn standby_followers(
+    cluster_id: u64,
+    kv_store: &KvStoreRef,
+    clock: &ClockRef,
+    datanode_lease_secs: i64,
+) -> Result<HashMap<u64, Vec<Peer>>> {
+    let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
+        clock.now_millis() - v.timestamp_millis < datanode_lease_secs * 1000
+    };
+    let lease_kvs: HashMap<LeaseKey, LeaseValue> =
+        lease::alive_datanodes(cluster_id, kv_store, lease_filter)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut followers: HashMap<u64, Vec<Peer>> = HashMap::new();
+    for (stat_key, stat_val) in all_stat_kvs(cluster_id, kv_store).await? {
+        let lease_key = LeaseKey {
+            cluster_id: stat_key.cluster_id,
+            node_id: stat_key.node_id,
+        };
+        let (lease_val, stat) = match (lease_kvs.get(&lease_key), stat_val.stats.last()) {
+            (Some(lease_val), Some(stat)) if stat.is_standby => (lease_val, stat),
+            _ => continue,
+        };
+        let peer = Peer {
+            id: stat_key.node_id,
+            addr: lease_val.node_addr.clone(),
+        };
+        for region_stat in &stat.region_stats {
+            followers
+                .entry(region_stat.id)
+                .or_default()
+                .push(peer.clone());
+        }
+    }
+    Ok(followers)
+}
+
 fn fill_table_routes(
     tables: Vec<(TableGlobalValue, TableRouteValue)>,
+    followers: &HashMap<u64, Vec<Peer>>,
 ) -> Result<(Vec<Peer>, Vec<TableRoute>)> {
     let mut peer_dict = PeerDict::default();
     let mut table_routes = vec![];
@@ -219,6 +298,7 @@ fn fill_table_routes(
                     }
                 }
             }
+            fill_followers(table_route, &mut peer_dict, followers);
 
             if let Some(table) = &mut table_route.table {
                 table.table_schema = tgv.as_bytes().context(error::InvalidCatalogValueSnafu)?;
@@ -232,6 +312,26 @@ fn fill_table_routes(
     Ok((peer_dict.into_peers(), table_routes))
 }
 
+/// Adds the standby datanodes serving the regions of the table to the followers of the
+/// regions.
+fn fill_followers(
+    table_route: &mut TableRoute,
+    peer_dict: &mut PeerDict,
+    followers: &HashMap<u64, Vec<Peer>>,
+) {
+    let table_id = table_route.table.as_ref().map_or(0, |t| t.id);
+    for rr in &mut table_route.region_routes {
+        let region_number = rr.region.as_ref().map_or(0, |r| r.id);
+        let region_id = (table_id << 32) | region_number;
+        for peer in followers.get(&region_id).into_iter().flatten() {
+            let index = peer_dict.get_or_insert(peer.clone()) as u64;
+            if !rr.follower_peer_indexes.contains(&index) {
+                rr.follower_peer_indexes.push(index);
+            }
+        }
+    }
+}
+
 async fn fetch_tables(
     kv_store: &KvStoreRef,
     keys: impl Iterator<Item = TableGlobalKey>,
@@ -350,3 +450,141 @@ async fn get_from_store(kv_store: &KvStoreRef, key: Vec<u8>) -> Result<Option<Ve
         Ok(Some(kvs.pop().unwrap().value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::Partition;
+
+    use super::*;
+    use crate::handler::node_stat::{RegionStat, Stat};
+    use crate::keys::{StatKey, StatValue};
+    use crate::service::store::memory::MemStore;
+
+    fn new_context() -> Context {
+        Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            in_memory: Arc::new(MemStore::new()),
+            kv_store: Arc::new(MemStore::new()),
+            election: None,
+            clock: common_time::clock::system_clock(),
+            skip_all: Arc::new(AtomicBool::new(false)),
+            catalog: None,
+            schema: None,
+            table: None,
+        }
+    }
+
+    async fn put_datanode(ctx: &Context, node_id: u64, is_standby: bool, region_ids: &[u64]) {
+        let lease_key = LeaseKey {
+            cluster_id: 0,
+            node_id,
+        };
+        let lease_value = LeaseValue {
+            timestamp_millis: ctx.clock.now_millis(),
+            node_addr: format!("127.0.0.1:{node_id}"),
+            labels: Default::default(),
+        };
+        put_into_store(
+            &ctx.kv_store,
+            Vec::<u8>::try_from(lease_key).unwrap(),
+            Vec::<u8>::try_from(lease_value).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let stat_key = StatKey {
+            cluster_id: 0,
+            node_id,
+        };
+        let stat_value = StatValue {
+            stats: vec![Stat {
+                id: node_id,
+                is_standby,
+                region_stats: region_ids
+                    .iter()
+                    .map(|id| RegionStat {
+                        id: *id,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+        };
+        put_into_store(
+            &ctx.kv_store,
+            stat_key,
+            Vec::<u8>::try_from(stat_value).unwrap(),
+        )
+        .await
+        .unwrap();
+    }
+
+    fn new_table_route(table_id: u64) -> TableRoute {
+        let region_routes = (0..2)
+            .map(|i| RegionRoute {
+                region: Some(Region {
+                    id: i,
+                    partition: Some(Partition::default()),
+                    ..Default::default()
+                }),
+                leader_peer_index: 0,
+                follower_peer_indexes: vec![],
+            })
+            .collect();
+        TableRoute {
+            table: Some(Table {
+                id: table_id,
+                ..Default::default()
+            }),
+            region_routes,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_standby_followers() {
+        let ctx = new_context();
+        let table_id = 1024;
+        let region_id = |n: u64| (table_id << 32) | n;
+        // The leader of the regions isn't their follower.
+        put_datanode(&ctx, 1, false, &[region_id(0), region_id(1)]).await;
+        // Standby datanodes only follow the regions they serve.
+        put_datanode(&ctx, 2, true, &[region_id(0), region_id(1)]).await;
+        put_datanode(&ctx, 3, true, &[region_id(1)]).await;
+
+        let followers = standby_followers(0, &ctx.kv_store, &ctx.clock, ctx.datanode_lease_secs)
+            .await
+            .unwrap();
+        let mut peer_dict = PeerDict::default();
+        let leader = Peer {
+            id: 1,
+            addr: "127.0.0.1:1".to_string(),
+        };
+        assert_eq!(0, peer_dict.get_or_insert(leader));
+        let mut table_route = new_table_route(table_id);
+        fill_followers(&mut table_route, &mut peer_dict, &followers);
+        let peers = peer_dict.into_peers();
+
+        let follower_ids = |region: usize| {
+            let mut ids = table_route.region_routes[region]
+                .follower_peer_indexes
+                .iter()
+                .map(|i| peers[*i as usize].id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(3, peers.len());
+        assert_eq!(vec![2], follower_ids(0));
+        assert_eq!(vec![2, 3], follower_ids(1));
+        // Routes of other tables have no followers.
+        let mut table_route = new_table_route(table_id + 1);
+        fill_followers(&mut table_route, &mut PeerDict::default(), &followers);
+        assert!(table_route.region_routes[0]
+            .follower_peer_indexes
+            .is_empty());
+    }
+}
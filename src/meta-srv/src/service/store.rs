@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod etcd;
+pub mod fenced;
 pub mod kv;
 pub mod memory;
 
@@ -30,21 +31,26 @@ use crate::service::GrpcResult;
 impl store_server::Store for MetaSrv {
     async fn range(&self, req: Request<RangeRequest>) -> GrpcResult<RangeResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().range(req).await?;
+        let res = self.leader_kv_store().range(req).await?;
 
         Ok(Response::new(res))
     }
 
     async fn put(&self, req: Request<PutRequest>) -> GrpcResult<PutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().put(req).await?;
+        let key = req.key.clone();
+        let res = self.leader_kv_store().put(req).await?;
+        self.table_changes().record_keys([key.as_slice()]);
 
         Ok(Response::new(res))
     }
 
     async fn batch_put(&self, req: Request<BatchPutRequest>) -> GrpcResult<BatchPutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().batch_put(req).await?;
+        let keys = req.kvs.iter().map(|kv| kv.key.clone()).collect::<Vec<_>>();
+        let res = self.leader_kv_store().batch_put(req).await?;
+        self.table_changes()
+            .record_keys(keys.iter().map(|key| key.as_slice()));
 
         Ok(Response::new(res))
     }
@@ -54,7 +60,11 @@ impl store_server::Store for MetaSrv {
         req: Request<CompareAndPutRequest>,
     ) -> GrpcResult<CompareAndPutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().compare_and_put(req).await?;
+        let key = req.key.clone();
+        let res = self.leader_kv_store().compare_and_put(req).await?;
+        if res.success {
+            self.table_changes().record_keys([key.as_slice()]);
+        }
 
         Ok(Response::new(res))
     }
@@ -64,14 +74,19 @@ impl store_server::Store for MetaSrv {
         req: Request<DeleteRangeRequest>,
     ) -> GrpcResult<DeleteRangeResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().delete_range(req).await?;
+        let key = req.key.clone();
+        let res = self.leader_kv_store().delete_range(req).await?;
+        self.table_changes().record_keys([key.as_slice()]);
 
         Ok(Response::new(res))
     }
 
     async fn move_value(&self, req: Request<MoveValueRequest>) -> GrpcResult<MoveValueResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().move_value(req).await?;
+        let keys = [req.from_key.clone(), req.to_key.clone()];
+        let res = self.leader_kv_store().move_value(req).await?;
+        self.table_changes()
+            .record_keys(keys.iter().map(|key| key.as_slice()));
 
         Ok(Response::new(res))
     }
@@ -99,6 +114,35 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_range_limit() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None, None).await;
+        for key in ["a", "b", "c"] {
+            let req = PutRequest {
+                key: key.as_bytes().to_vec(),
+                ..Default::default()
+            };
+            meta_srv.put(req.into_request()).await.unwrap();
+        }
+
+        let range = |limit| RangeRequest {
+            key: b"a".to_vec(),
+            range_end: b"d".to_vec(),
+            limit,
+            ..Default::default()
+        };
+        let res = meta_srv.range(range(2).into_request()).await.unwrap();
+        let res = res.into_inner();
+        assert_eq!(2, res.kvs.len());
+        assert!(res.more);
+
+        let res = meta_srv.range(range(3).into_request()).await.unwrap();
+        let res = res.into_inner();
+        assert_eq!(3, res.kvs.len());
+        assert!(!res.more);
+    }
+
     #[tokio::test]
     async fn test_put() {
         let kv_store = Arc::new(MemStore::new());
@@ -148,4 +192,32 @@ mod tests {
 
         assert!(res.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_record_table_changes() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None, None).await;
+        let version = meta_srv.table_changes().current_version();
+
+        let req = PutRequest {
+            key: b"not-a-table-key".to_vec(),
+            ..Default::default()
+        };
+        meta_srv.put(req.into_request()).await.unwrap();
+        let req = PutRequest {
+            key: b"__tg-greptime-public-demo".to_vec(),
+            ..Default::default()
+        };
+        meta_srv.put(req.into_request()).await.unwrap();
+
+        let tables = meta_srv.table_changes().changes_since(&version).unwrap();
+        assert_eq!(
+            vec![TableName {
+                catalog_name: "greptime".to_string(),
+                schema_name: "public".to_string(),
+                table_name: "demo".to_string(),
+            }],
+            tables
+        );
+    }
 }
@@ -16,13 +16,15 @@ use std::sync::Arc;
 
 use api::v1::meta::{
     BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
-    DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest, MoveValueResponse,
-    PutRequest, PutResponse, RangeRequest, RangeResponse, ResponseHeader,
+    DeleteRangeRequest, DeleteRangeResponse, FencingToken, KeyValue, MoveValueRequest,
+    MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse, RequestHeader,
+    ResponseHeader,
 };
 use common_error::prelude::*;
 use common_telemetry::warn;
 use etcd_client::{
     Client, Compare, CompareOp, DeleteOptions, GetOptions, PutOptions, Txn, TxnOp, TxnOpResponse,
+    TxnResponse,
 };
 
 use crate::error;
@@ -45,6 +47,62 @@ impl EtcdStore {
 
         Ok(Arc::new(Self { client }))
     }
+
+    /// Executes the `ops` in a transaction, returns error if the leadership of the
+    /// `fencing_token` is lost.
+    async fn fenced_txn(
+        &self,
+        ops: Vec<TxnOp>,
+        fencing_token: &Option<FencingToken>,
+    ) -> Result<TxnResponse> {
+        let txn = Txn::new().when(fence(fencing_token)).and_then(ops);
+        let txn_res = self
+            .client
+            .kv_client()
+            .txn(txn)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+        if let Some(token) = fencing_token {
+            ensure!(txn_res.succeeded(), error::FencedSnafu { term: token.term });
+        }
+        Ok(txn_res)
+    }
+
+    /// Returns error if the leadership of the `fencing_token` is lost, used to tell
+    /// whether a failed transaction is fenced.
+    async fn check_fence(&self, fencing_token: &Option<FencingToken>) -> Result<()> {
+        let Some(token) = fencing_token else {
+            return Ok(());
+        };
+        let res = self
+            .client
+            .kv_client()
+            .get(token.leader_key.clone(), None)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+        ensure!(
+            res.kvs()
+                .first()
+                .map_or(false, |kv| kv.create_revision() == token.term),
+            error::FencedSnafu { term: token.term }
+        );
+        Ok(())
+    }
+}
+
+/// Returns the comparisons that succeed only while the leadership of the `fencing_token`
+/// is held, i.e. the leader key is not recreated by a new term or deleted.
+fn fence(fencing_token: &Option<FencingToken>) -> Vec<Compare> {
+    fencing_token
+        .iter()
+        .map(|token| {
+            Compare::create_revision(token.leader_key.clone(), CompareOp::Equal, token.term)
+        })
+        .collect()
+}
+
+fn fencing_token(header: &Option<RequestHeader>) -> Option<FencingToken> {
+    header.as_ref().and_then(|h| h.fencing_token.clone())
 }
 
 #[async_trait::async_trait]
@@ -79,16 +137,25 @@ impl KvStore for EtcdStore {
             key,
             value,
             options,
+            fencing_token,
         } = req.try_into()?;
 
-        let res = self
-            .client
-            .kv_client()
-            .put(key, value, options)
-            .await
-            .context(error::EtcdFailedSnafu)?;
-
-        let prev_kv = res.prev_key().map(KvPair::to_kv);
+        let prev_kv = if fencing_token.is_some() {
+            let put = TxnOp::put(key, value, options);
+            let txn_res = self.fenced_txn(vec![put], &fencing_token).await?;
+            match txn_res.op_responses().pop() {
+                Some(TxnOpResponse::Put(res)) => res.prev_key().map(KvPair::to_kv),
+                _ => None,
+            }
+        } else {
+            let res = self
+                .client
+                .kv_client()
+                .put(key, value, options)
+                .await
+                .context(error::EtcdFailedSnafu)?;
+            res.prev_key().map(KvPair::to_kv)
+        };
 
         let header = Some(ResponseHeader::success(cluster_id));
         Ok(PutResponse { header, prev_kv })
@@ -99,20 +166,14 @@ impl KvStore for EtcdStore {
             cluster_id,
             kvs,
             options,
+            fencing_token,
         } = req.try_into()?;
 
         let put_ops = kvs
             .into_iter()
             .map(|kv| (TxnOp::put(kv.key, kv.value, options.clone())))
             .collect::<Vec<_>>();
-        let txn = Txn::new().and_then(put_ops);
-
-        let txn_res = self
-            .client
-            .kv_client()
-            .txn(txn)
-            .await
-            .context(error::EtcdFailedSnafu)?;
+        let txn_res = self.fenced_txn(put_ops, &fencing_token).await?;
 
         let mut prev_kvs = vec![];
         for op_res in txn_res.op_responses() {
@@ -137,6 +198,7 @@ impl KvStore for EtcdStore {
             expect,
             value,
             put_options,
+            fencing_token,
         } = req.try_into()?;
 
         let compare = if expect.is_empty() {
@@ -149,8 +211,10 @@ impl KvStore for EtcdStore {
         };
         let put = TxnOp::put(key.clone(), value, put_options);
         let get = TxnOp::get(key, None);
+        let mut compares = fence(&fencing_token);
+        compares.push(compare);
         let txn = Txn::new()
-            .when(vec![compare])
+            .when(compares)
             .and_then(vec![put])
             .or_else(vec![get]);
 
@@ -162,6 +226,9 @@ impl KvStore for EtcdStore {
             .context(error::EtcdFailedSnafu)?;
 
         let success = txn_res.succeeded();
+        if !success {
+            self.check_fence(&fencing_token).await?;
+        }
         let op_res = txn_res
             .op_responses()
             .pop()
@@ -188,21 +255,36 @@ impl KvStore for EtcdStore {
             cluster_id,
             key,
             options,
+            fencing_token,
         } = req.try_into()?;
 
-        let res = self
-            .client
-            .kv_client()
-            .delete(key, options)
-            .await
-            .context(error::EtcdFailedSnafu)?;
-
-        let prev_kvs = res.prev_kvs().iter().map(KvPair::to_kv).collect::<Vec<_>>();
+        let (deleted, prev_kvs) = if fencing_token.is_some() {
+            let delete = TxnOp::delete(key, options);
+            let txn_res = self.fenced_txn(vec![delete], &fencing_token).await?;
+            match txn_res.op_responses().pop() {
+                Some(TxnOpResponse::Delete(res)) => (
+                    res.deleted(),
+                    res.prev_kvs().iter().map(KvPair::to_kv).collect(),
+                ),
+                _ => (0, vec![]),
+            }
+        } else {
+            let res = self
+                .client
+                .kv_client()
+                .delete(key, options)
+                .await
+                .context(error::EtcdFailedSnafu)?;
+            (
+                res.deleted(),
+                res.prev_kvs().iter().map(KvPair::to_kv).collect(),
+            )
+        };
 
         let header = Some(ResponseHeader::success(cluster_id));
         Ok(DeleteRangeResponse {
             header,
-            deleted: res.deleted(),
+            deleted,
             prev_kvs,
         })
     }
@@ -213,6 +295,7 @@ impl KvStore for EtcdStore {
             from_key,
             to_key,
             delete_options,
+            fencing_token,
         } = req.try_into()?;
 
         let mut client = self.client.kv_client();
@@ -233,23 +316,26 @@ impl KvStore for EtcdStore {
                 None => {
                     // get `to_key` if `from_key` absent
                     // revision 0 means key was not exist
-                    let compare = Compare::create_revision(from_key, CompareOp::Equal, 0);
+                    let mut compares = fence(&fencing_token);
+                    compares.push(Compare::create_revision(from_key, CompareOp::Equal, 0));
                     let get = TxnOp::get(to_key, None);
-                    Txn::new().when(vec![compare]).and_then(vec![get])
+                    Txn::new().when(compares).and_then(vec![get])
                 }
                 Some(kv) => {
                     // compare `from_key` and move to `to_key`
                     let value = kv.value();
-                    let compare = Compare::value(from_key, CompareOp::Equal, value);
+                    let mut compares = fence(&fencing_token);
+                    compares.push(Compare::value(from_key, CompareOp::Equal, value));
                     let delete = TxnOp::delete(from_key, delete_options.clone());
                     let put = TxnOp::put(to_key, value, None);
-                    Txn::new().when(vec![compare]).and_then(vec![delete, put])
+                    Txn::new().when(compares).and_then(vec![delete, put])
                 }
             };
 
             let txn_res = client.txn(txn).await.context(error::EtcdFailedSnafu)?;
 
             if !txn_res.succeeded() {
+                self.check_fence(&fencing_token).await?;
                 warn!(
                     "Failed to atomically move {:?} to {:?}, try again...",
                     String::from_utf8_lossy(from_key),
@@ -329,6 +415,7 @@ struct Put {
     key: Vec<u8>,
     value: Vec<u8>,
     options: Option<PutOptions>,
+    fencing_token: Option<FencingToken>,
 }
 
 impl TryFrom<PutRequest> for Put {
@@ -348,10 +435,11 @@ impl TryFrom<PutRequest> for Put {
         }
 
         Ok(Put {
-            cluster_id: header.map_or(0, |h| h.cluster_id),
+            cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
             key,
             value,
             options: Some(options),
+            fencing_token: fencing_token(&header),
         })
     }
 }
@@ -360,6 +448,7 @@ struct BatchPut {
     cluster_id: u64,
     kvs: Vec<KeyValue>,
     options: Option<PutOptions>,
+    fencing_token: Option<FencingToken>,
 }
 
 impl TryFrom<BatchPutRequest> for BatchPut {
@@ -378,9 +467,10 @@ impl TryFrom<BatchPutRequest> for BatchPut {
         }
 
         Ok(BatchPut {
-            cluster_id: header.map_or(0, |h| h.cluster_id),
+            cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
             kvs,
             options: Some(options),
+            fencing_token: fencing_token(&header),
         })
     }
 }
@@ -391,6 +481,7 @@ struct CompareAndPut {
     expect: Vec<u8>,
     value: Vec<u8>,
     put_options: Option<PutOptions>,
+    fencing_token: Option<FencingToken>,
 }
 
 impl TryFrom<CompareAndPutRequest> for CompareAndPut {
@@ -405,11 +496,12 @@ impl TryFrom<CompareAndPutRequest> for CompareAndPut {
         } = req;
 
         Ok(CompareAndPut {
-            cluster_id: header.map_or(0, |h| h.cluster_id),
+            cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
             key,
             expect,
             value,
             put_options: Some(PutOptions::default().with_prev_key()),
+            fencing_token: fencing_token(&header),
         })
     }
 }
@@ -418,6 +510,7 @@ struct Delete {
     cluster_id: u64,
     key: Vec<u8>,
     options: Option<DeleteOptions>,
+    fencing_token: Option<FencingToken>,
 }
 
 impl TryFrom<DeleteRangeRequest> for Delete {
@@ -442,9 +535,10 @@ impl TryFrom<DeleteRangeRequest> for Delete {
         }
 
         Ok(Delete {
-            cluster_id: header.map_or(0, |h| h.cluster_id),
+            cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
             key,
             options: Some(options),
+            fencing_token: fencing_token(&header),
         })
     }
 }
@@ -454,6 +548,7 @@ struct MoveValue {
     from_key: Vec<u8>,
     to_key: Vec<u8>,
     delete_options: Option<DeleteOptions>,
+    fencing_token: Option<FencingToken>,
 }
 
 impl TryFrom<MoveValueRequest> for MoveValue {
@@ -467,10 +562,11 @@ impl TryFrom<MoveValueRequest> for MoveValue {
         } = req;
 
         Ok(MoveValue {
-            cluster_id: header.map_or(0, |h| h.cluster_id),
+            cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
             from_key,
             to_key,
             delete_options: Some(DeleteOptions::default().with_prev_key()),
+            fencing_token: fencing_token(&header),
         })
     }
 }
@@ -533,6 +629,28 @@ mod tests {
         assert_eq!(b"test_key".to_vec(), put.key);
         assert_eq!(b"test_value".to_vec(), put.value);
         assert!(put.options.is_some());
+        assert!(put.fencing_token.is_none());
+    }
+
+    #[test]
+    fn test_parse_fencing_token() {
+        let token = FencingToken {
+            leader_key: b"leader_key".to_vec(),
+            term: 42,
+        };
+        let mut header = RequestHeader::new((0, 0));
+        header.fencing_token = Some(token.clone());
+        let req = DeleteRangeRequest {
+            header: Some(header),
+            key: b"test_key".to_vec(),
+            ..Default::default()
+        };
+
+        let delete: Delete = req.try_into().unwrap();
+
+        assert_eq!(Some(token.clone()), delete.fencing_token);
+        assert_eq!(1, fence(&delete.fencing_token).len());
+        assert!(fence(&None).is_empty());
     }
 
     #[test]
@@ -0,0 +1,221 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{
+    BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
+    DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse, PutRequest,
+    PutResponse, RangeRequest, RangeResponse, RequestHeader,
+};
+use snafu::OptionExt;
+
+use crate::error;
+use crate::error::Result;
+use crate::metasrv::ElectionRef;
+use crate::service::store::kv::{KvStore, KvStoreRef};
+
+/// Kv store for leader-only mutations, which attaches the fencing token of the current
+/// leadership to each mutation, so the underlying store rejects the mutation once the
+/// leadership is lost.
+pub struct FencedKvStore {
+    inner: KvStoreRef,
+    election: ElectionRef,
+}
+
+impl FencedKvStore {
+    pub fn new(inner: KvStoreRef, election: ElectionRef) -> Self {
+        Self { inner, election }
+    }
+
+    /// Attaches the fencing token to the `header`, returns error if current node is not
+    /// the leader.
+    fn fence(&self, header: &mut Option<RequestHeader>) -> Result<()> {
+        let token = self
+            .election
+            .fencing_token()
+            .context(error::NotLeaderSnafu)?;
+        header.get_or_insert_with(Default::default).fencing_token = Some(token);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl KvStore for FencedKvStore {
+    async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
+        self.inner.range(req).await
+    }
+
+    async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
+        self.fence(&mut req.header)?;
+        self.inner.put(req).await
+    }
+
+    async fn batch_put(&self, mut req: BatchPutRequest) -> Result<BatchPutResponse> {
+        self.fence(&mut req.header)?;
+        self.inner.batch_put(req).await
+    }
+
+    async fn compare_and_put(
+        &self,
+        mut req: CompareAndPutRequest,
+    ) -> Result<CompareAndPutResponse> {
+        self.fence(&mut req.header)?;
+        self.inner.compare_and_put(req).await
+    }
+
+    async fn delete_range(&self, mut req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+        self.fence(&mut req.header)?;
+        self.inner.delete_range(req).await
+    }
+
+    async fn move_value(&self, mut req: MoveValueRequest) -> Result<MoveValueResponse> {
+        self.fence(&mut req.header)?;
+        self.inner.move_value(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::FencingToken;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::election::Election;
+    use crate::metasrv::LeaderValue;
+    use crate::service::store::memory::MemStore;
+
+    struct MockElection {
+        fencing_token: Mutex<Option<FencingToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Election for MockElection {
+        type Leader = LeaderValue;
+
+        fn is_leader(&self) -> bool {
+            self.fencing_token.lock().is_some()
+        }
+
+        fn fencing_token(&self) -> Option<FencingToken> {
+            self.fencing_token.lock().clone()
+        }
+
+        fn in_infancy(&self) -> bool {
+            false
+        }
+
+        async fn campaign(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn leader(&self) -> Result<LeaderValue> {
+            Ok(LeaderValue("127.0.0.1:3002".to_string()))
+        }
+
+        async fn resign(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Records the fencing tokens of the mutations.
+    #[derive(Default)]
+    struct RecordingStore {
+        inner: MemStore,
+        fencing_tokens: Mutex<Vec<Option<FencingToken>>>,
+    }
+
+    impl RecordingStore {
+        fn record(&self, header: &Option<RequestHeader>) {
+            let token = header.as_ref().and_then(|h| h.fencing_token.clone());
+            self.fencing_tokens.lock().push(token);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl KvStore for RecordingStore {
+        async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
+            self.record(&req.header);
+            self.inner.range(req).await
+        }
+
+        async fn put(&self, req: PutRequest) -> Result<PutResponse> {
+            self.record(&req.header);
+            self.inner.put(req).await
+        }
+
+        async fn batch_put(&self, req: BatchPutRequest) -> Result<BatchPutResponse> {
+            self.record(&req.header);
+            self.inner.batch_put(req).await
+        }
+
+        async fn compare_and_put(
+            &self,
+            req: CompareAndPutRequest,
+        ) -> Result<CompareAndPutResponse> {
+            self.record(&req.header);
+            self.inner.compare_and_put(req).await
+        }
+
+        async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+            self.record(&req.header);
+            self.inner.delete_range(req).await
+        }
+
+        async fn move_value(&self, req: MoveValueRequest) -> Result<MoveValueResponse> {
+            self.record(&req.header);
+            self.inner.move_value(req).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fenced_kv_store() {
+        let token = FencingToken {
+            leader_key: b"__meta_srv_election/1".to_vec(),
+            term: 7,
+        };
+        let election = Arc::new(MockElection {
+            fencing_token: Mutex::new(Some(token.clone())),
+        });
+        let inner = Arc::new(RecordingStore::default());
+        let store = FencedKvStore::new(inner.clone(), election.clone());
+
+        let put = PutRequest {
+            header: Some(RequestHeader::new((1, 2))),
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+            ..Default::default()
+        };
+        store.put(put.clone()).await.unwrap();
+        let range = RangeRequest {
+            key: b"key".to_vec(),
+            ..Default::default()
+        };
+        let kvs = store.range(range).await.unwrap().kvs;
+        assert_eq!(b"value".to_vec(), kvs[0].value);
+        // Reads are not fenced.
+        assert_eq!(vec![Some(token), None], *inner.fencing_tokens.lock());
+
+        // Mutations are rejected once the leadership is lost.
+        *election.fencing_token.lock() = None;
+        let err = store.put(put).await.unwrap_err();
+        assert!(matches!(err, error::Error::NotLeader { .. }));
+        let delete = DeleteRangeRequest {
+            key: b"key".to_vec(),
+            ..Default::default()
+        };
+        assert!(store.delete_range(delete).await.is_err());
+        assert_eq!(2, inner.fencing_tokens.lock().len());
+    }
+}
@@ -75,8 +75,16 @@ impl KvStore for MemStore {
                 start: key,
                 end: range_end,
             };
+            // Takes one more entry to tell whether there are more, so reading a large range
+            // by pages doesn't copy the rest of the range for every page.
+            let take = if limit > 0 {
+                limit as usize + 1
+            } else {
+                usize::MAX
+            };
             memory
                 .range(range)
+                .take(take)
                 .map(|kv| KeyValue {
                     key: kv.0.clone(),
                     value: if keys_only { vec![] } else { kv.1.clone() },
@@ -84,12 +92,10 @@ impl KvStore for MemStore {
                 .collect::<Vec<_>>()
         };
 
-        let more = if limit > 0 {
+        let more = limit > 0 && kvs.len() > limit as usize;
+        if more {
             kvs.truncate(limit as usize);
-            true
-        } else {
-            false
-        };
+        }
 
         let cluster_id = header.map_or(0, |h| h.cluster_id);
         let header = Some(ResponseHeader::success(cluster_id));
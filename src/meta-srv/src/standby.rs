@@ -0,0 +1,98 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Promotion of standby datanodes. A pending promotion is kept in the kv store until the
+//! standby datanode is told to promote by a heartbeat response.
+
+use api::v1::meta::{DeleteRangeRequest, PutRequest};
+
+use crate::error::Result;
+use crate::keys::PromotionKey;
+use crate::service::store::kv::KvStoreRef;
+
+/// Requests the standby datanode to take over writes of its tables, the datanodes
+/// writing the tables must have stopped.
+pub async fn request_promotion(cluster_id: u64, node_id: u64, kv_store: &KvStoreRef) -> Result<()> {
+    let req = PutRequest {
+        key: PromotionKey {
+            cluster_id,
+            node_id,
+        }
+        .into(),
+        ..Default::default()
+    };
+    kv_store.put(req).await?;
+    Ok(())
+}
+
+/// Removes the pending promotion of the datanode, returns true if there was one.
+pub async fn take_promotion(cluster_id: u64, node_id: u64, kv_store: &KvStoreRef) -> Result<bool> {
+    // Scoped by the node id.
+    #[cfg(feature = "failpoints")]
+    if common_base::failpoint::triggered("meta_take_promotion", &node_id.to_string()) {
+        return crate::error::InjectedFailureSnafu {
+            name: "meta_take_promotion",
+        }
+        .fail();
+    }
+    let req = DeleteRangeRequest {
+        key: PromotionKey {
+            cluster_id,
+            node_id,
+        }
+        .into(),
+        ..Default::default()
+    };
+    let resp = kv_store.delete_range(req).await?;
+    Ok(resp.deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_promotion() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        assert!(!take_promotion(0, 1, &kv_store).await.unwrap());
+
+        request_promotion(0, 1, &kv_store).await.unwrap();
+        assert!(!take_promotion(0, 2, &kv_store).await.unwrap());
+        assert!(!take_promotion(1, 1, &kv_store).await.unwrap());
+        assert!(take_promotion(0, 1, &kv_store).await.unwrap());
+        // Taken only once.
+        assert!(!take_promotion(0, 1, &kv_store).await.unwrap());
+    }
+
+    /// Tests injecting failures into promotions, the fail points are scoped by node ids.
+    #[cfg(feature = "failpoints")]
+    mod failpoint {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_take_promotion_failure() {
+            let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+            request_promotion(0, 1001, &kv_store).await.unwrap();
+
+            // The promotion is kept for the next heartbeat of the standby datanode.
+            fail::cfg("meta_take_promotion", "return(1001)").unwrap();
+            assert!(take_promotion(0, 1001, &kv_store).await.is_err());
+            fail::remove("meta_take_promotion");
+            assert!(take_promotion(0, 1001, &kv_store).await.unwrap());
+        }
+    }
+}
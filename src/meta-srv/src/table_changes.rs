@@ -0,0 +1,183 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Changes of table metadata, which are notified to nodes caching the metadata by
+//! heartbeats.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use api::v1::meta::{CatalogVersion, TableName};
+use catalog::helper::TableGlobalKey;
+
+/// Max number of recent table changes kept, nodes with older catalog versions invalidate
+/// all cached table metadata.
+const MAX_TABLE_CHANGES: usize = 1024;
+
+pub type TableChangeLogRef = Arc<TableChangeLog>;
+
+/// Log of recent table metadata changes, each change increases the catalog version.
+///
+/// Only changes made through this metasrv are recorded, nodes caching table metadata
+/// bound the staleness of changes made through other metasrv nodes by the TTL of caches.
+pub struct TableChangeLog {
+    epoch: u64,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    version: u64,
+    /// Changed tables and the versions after the changes, in ascending order of versions.
+    changes: VecDeque<(u64, TableName)>,
+}
+
+impl TableChangeLog {
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn current_version(&self) -> CatalogVersion {
+        CatalogVersion {
+            epoch: self.epoch,
+            version: self.inner.lock().unwrap().version,
+        }
+    }
+
+    /// Records the change of tables whose metadata is stored under the `keys`, keys not
+    /// of table metadata are ignored.
+    pub fn record_keys<'a>(&self, keys: impl IntoIterator<Item = &'a [u8]>) {
+        for key in keys {
+            let Ok(key) = TableGlobalKey::parse(String::from_utf8_lossy(key)) else {
+                continue;
+            };
+            self.record(TableName {
+                catalog_name: key.catalog_name,
+                schema_name: key.schema_name,
+                table_name: key.table_name,
+            });
+        }
+    }
+
+    pub fn record(&self, table_name: TableName) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.version += 1;
+        let version = inner.version;
+        inner.changes.push_back((version, table_name));
+        if inner.changes.len() > MAX_TABLE_CHANGES {
+            let _ = inner.changes.pop_front();
+        }
+    }
+
+    /// Returns tables changed since the `version`, `None` if the changes are unknown, e.g.
+    /// the `version` is of another epoch or too old.
+    pub fn changes_since(&self, version: &CatalogVersion) -> Option<Vec<TableName>> {
+        if version.epoch != self.epoch {
+            return None;
+        }
+        let inner = self.inner.lock().unwrap();
+        if version.version > inner.version {
+            return None;
+        }
+        // Changes after `version.version + 1` are all kept if the oldest kept change is
+        // not newer than it.
+        let oldest = inner.changes.front().map_or(inner.version, |(v, _)| *v);
+        if version.version + 1 < oldest {
+            return None;
+        }
+
+        let mut tables: Vec<_> = inner
+            .changes
+            .iter()
+            .filter(|(v, _)| *v > version.version)
+            .map(|(_, table_name)| table_name.clone())
+            .collect();
+        tables.sort_by(|a, b| {
+            (&a.catalog_name, &a.schema_name, &a.table_name).cmp(&(
+                &b.catalog_name,
+                &b.schema_name,
+                &b.table_name,
+            ))
+        });
+        tables.dedup();
+        Some(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_name(name: &str) -> TableName {
+        TableName {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: name.to_string(),
+        }
+    }
+
+    fn version(epoch: u64, version: u64) -> CatalogVersion {
+        CatalogVersion { epoch, version }
+    }
+
+    #[test]
+    fn test_table_changes() {
+        let log = TableChangeLog::new(1);
+        assert_eq!(version(1, 0), log.current_version());
+        assert_eq!(Some(vec![]), log.changes_since(&version(1, 0)));
+
+        log.record_keys([
+            b"__tg-greptime-public-a".as_slice(),
+            b"__c-greptime".as_slice(),
+            b"__tg-greptime-public-b".as_slice(),
+            b"__tg-greptime-public-a".as_slice(),
+        ]);
+        assert_eq!(version(1, 3), log.current_version());
+        assert_eq!(
+            Some(vec![table_name("a"), table_name("b")]),
+            log.changes_since(&version(1, 0))
+        );
+        assert_eq!(
+            Some(vec![table_name("a")]),
+            log.changes_since(&version(1, 2))
+        );
+        assert_eq!(Some(vec![]), log.changes_since(&version(1, 3)));
+
+        // Versions of other epochs or newer than the current one are unknown.
+        assert_eq!(None, log.changes_since(&version(0, 3)));
+        assert_eq!(None, log.changes_since(&version(1, 4)));
+    }
+
+    #[test]
+    fn test_table_changes_too_old() {
+        let log = TableChangeLog::new(1);
+        for i in 0..MAX_TABLE_CHANGES + 2 {
+            log.record(table_name(&i.to_string()));
+        }
+        let current = log.current_version().version;
+        assert_eq!(None, log.changes_since(&version(1, 0)));
+        assert_eq!(None, log.changes_since(&version(1, 1)));
+        assert_eq!(
+            MAX_TABLE_CHANGES,
+            log.changes_since(&version(1, 2)).unwrap().len()
+        );
+        assert_eq!(
+            1,
+            log.changes_since(&version(1, current - 1)).unwrap().len()
+        );
+    }
+}
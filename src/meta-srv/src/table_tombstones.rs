@@ -0,0 +1,132 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tombstones of dropped tables. A tombstone is kept for a while after the table is
+//! dropped, so datanodes still holding regions of the table reject late writes to it,
+//! instead of resurrecting the table, even if a table of the same name is created again.
+
+use std::collections::HashMap;
+
+use api::v1::meta::{DeleteRangeRequest, PutRequest, RangeRequest};
+use common_telemetry::info;
+
+use crate::error::Result;
+use crate::keys::{TableTombstoneKey, TableTombstoneValue, TABLE_TOMBSTONE_PREFIX};
+use crate::service::store::kv::KvStoreRef;
+use crate::util;
+
+/// Puts the tombstone of the table `table_id` dropped at `timestamp_millis`.
+pub async fn put_table_tombstone(
+    table_id: u32,
+    table_name: String,
+    timestamp_millis: i64,
+    kv_store: &KvStoreRef,
+) -> Result<()> {
+    let value = TableTombstoneValue {
+        table_name,
+        timestamp_millis,
+    };
+    let req = PutRequest {
+        key: TableTombstoneKey { table_id }.into(),
+        value: value.try_into()?,
+        ..Default::default()
+    };
+    kv_store.put(req).await?;
+    Ok(())
+}
+
+/// Returns the tombstones of dropped tables, keyed by table id.
+pub async fn table_tombstones(kv_store: &KvStoreRef) -> Result<HashMap<u32, TableTombstoneValue>> {
+    let key = format!("{TABLE_TOMBSTONE_PREFIX}-").into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+
+    let kvs = kv_store.range(req).await?.kvs;
+    let mut tombstones = HashMap::with_capacity(kvs.len());
+    for kv in kvs {
+        let key: TableTombstoneKey = kv.key.try_into()?;
+        let value: TableTombstoneValue = kv.value.try_into()?;
+        tombstones.insert(key.table_id, value);
+    }
+
+    Ok(tombstones)
+}
+
+/// Removes the tombstones of tables dropped more than `retention_secs` before `now_millis`,
+/// returns the number of removed tombstones.
+pub async fn purge_table_tombstones(
+    retention_secs: u64,
+    now_millis: i64,
+    kv_store: &KvStoreRef,
+) -> Result<usize> {
+    let expire_before = now_millis - (retention_secs * 1000) as i64;
+    let mut purged = 0;
+    for (table_id, value) in table_tombstones(kv_store).await? {
+        if value.timestamp_millis >= expire_before {
+            continue;
+        }
+        let req = DeleteRangeRequest {
+            key: TableTombstoneKey { table_id }.into(),
+            ..Default::default()
+        };
+        kv_store.delete_range(req).await?;
+        info!(
+            "Purged tombstone of table {}, id: {}",
+            value.table_name, table_id
+        );
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_table_tombstones() {
+        let kv_store = Arc::new(MemStore::new()) as KvStoreRef;
+        assert!(table_tombstones(&kv_store).await.unwrap().is_empty());
+
+        put_table_tombstone(1024, "greptime.public.a".to_string(), 1_000, &kv_store)
+            .await
+            .unwrap();
+        put_table_tombstone(1025, "greptime.public.b".to_string(), 5_000, &kv_store)
+            .await
+            .unwrap();
+        let tombstones = table_tombstones(&kv_store).await.unwrap();
+        assert_eq!(2, tombstones.len());
+        assert_eq!("greptime.public.a", tombstones[&1024].table_name);
+        assert_eq!(5_000, tombstones[&1025].timestamp_millis);
+
+        // Retains tombstones of tables dropped in the last 3 seconds.
+        assert_eq!(
+            1,
+            purge_table_tombstones(3, 6_000, &kv_store).await.unwrap()
+        );
+        let tombstones = table_tombstones(&kv_store).await.unwrap();
+        assert_eq!(vec![1025], tombstones.keys().copied().collect::<Vec<_>>());
+        assert_eq!(
+            0,
+            purge_table_tombstones(3, 6_000, &kv_store).await.unwrap()
+        );
+    }
+}
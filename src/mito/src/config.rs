@@ -14,5 +14,40 @@
 
 //! Table Engine config
 
-#[derive(Debug, Clone, Default)]
-pub struct EngineConfig {}
+use std::time::Duration;
+
+use common_time::clock::{self, ClockRef};
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Opens tables as a standby of the datanode writing them, regions of the tables are
+    /// opened read-only and creating or altering tables is rejected until the engine is
+    /// set writable.
+    pub standby: bool,
+    /// Clock that tasks downsampling tables or moving cold files read the time from.
+    pub clock: ClockRef,
+    /// Opens the region of a table on the first read or write of the table instead of on
+    /// opening the table, so the WAL of rarely used tables is not replayed on startup. Tables
+    /// opened by a standby engine always open their regions.
+    pub lazy_open: bool,
+    /// Closes regions of lazily opened tables not read or written for this duration, the
+    /// regions are opened again on next access.
+    pub idle_close_after: Option<Duration>,
+    /// Interval to move SST files of tables with the `cold_after` option to the cold tier.
+    pub tiering_interval: Duration,
+}
+
+/// Default interval to move cold files of tables to the cold tier.
+pub const DEFAULT_TIERING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            standby: false,
+            clock: clock::system_clock(),
+            lazy_open: false,
+            idle_close_after: None,
+            tiering_interval: DEFAULT_TIERING_INTERVAL,
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dedup window of tables, which drops points identical to the last point of their series
+//! on write.
+//!
+//! Chatty sensors often report unchanged values. A table with the `dedup_window` option
+//! drops a point if its fields equal the fields of the last accepted point of the same series
+//! and it is less than the window later than that point. Reads carrying the last value
+//! forward, like step interpolation, return the same results as long as their lookback is not
+//! shorter than the window.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use common_time::timestamp::TimeUnit;
+use datatypes::prelude::*;
+use datatypes::schema::SchemaRef;
+use datatypes::vectors::{BooleanVector, VectorRef};
+use snafu::ensure;
+
+use crate::error::{InvalidDedupWindowSnafu, Result};
+
+/// Table option of the dedup window, e.g. `10m`.
+pub const DEDUP_WINDOW_KEY: &str = "dedup_window";
+/// Table option of the max number of series whose last points are kept. All points are
+/// forgotten once exceeded, so the next point of each series is accepted.
+pub const DEDUP_WINDOW_MAX_SERIES_KEY: &str = "dedup_window_max_series";
+const DEFAULT_MAX_SERIES: usize = 100_000;
+
+/// Parses the value of the [DEDUP_WINDOW_KEY] table option.
+pub fn parse_dedup_window(value: &str) -> Result<Duration> {
+    let window = humantime::parse_duration(value).map_err(|e| {
+        InvalidDedupWindowSnafu {
+            value,
+            reason: e.to_string(),
+        }
+        .build()
+    })?;
+    ensure!(
+        !window.is_zero(),
+        InvalidDedupWindowSnafu {
+            value,
+            reason: "duration must be positive",
+        }
+    );
+    Ok(window)
+}
+
+/// Parses the value of the [DEDUP_WINDOW_MAX_SERIES_KEY] table option.
+pub fn parse_dedup_window_max_series(value: &str) -> Result<usize> {
+    let max_series = value.parse::<usize>().map_err(|e| {
+        InvalidDedupWindowSnafu {
+            value,
+            reason: e.to_string(),
+        }
+        .build()
+    })?;
+    ensure!(
+        max_series > 0,
+        InvalidDedupWindowSnafu {
+            value,
+            reason: "max series must be positive",
+        }
+    );
+    Ok(max_series)
+}
+
+/// The last accepted point of a series.
+#[derive(Debug)]
+struct LastPoint {
+    timestamp_millis: i64,
+    fields: Vec<Value>,
+}
+
+/// Points accepted by [DedupWindow::filter], which are remembered by [DedupWindow::commit]
+/// once written.
+#[derive(Debug, Default)]
+pub struct AcceptedPoints(BTreeMap<Vec<Value>, LastPoint>);
+
+/// Filter of points identical to the last accepted points of their series in a window.
+#[derive(Debug)]
+pub struct DedupWindow {
+    window_millis: i64,
+    max_series: usize,
+    /// Last accepted points, keyed by the primary key of the series.
+    last_points: Mutex<BTreeMap<Vec<Value>, LastPoint>>,
+}
+
+impl DedupWindow {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window_millis: window.as_millis() as i64,
+            max_series: DEFAULT_MAX_SERIES,
+            last_points: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Sets the max number of series whose last points are kept.
+    pub fn with_max_series(mut self, max_series: usize) -> Self {
+        self.max_series = max_series;
+        self
+    }
+
+    /// Returns the dedup window of the table with `options`, `None` if the table doesn't
+    /// set the option. The options are validated on table creation, so invalid values are
+    /// ignored.
+    pub fn from_table_options(options: &HashMap<String, String>) -> Option<Self> {
+        let max_series = options
+            .get(DEDUP_WINDOW_MAX_SERIES_KEY)
+            .and_then(|max_series| parse_dedup_window_max_series(max_series).ok())
+            .unwrap_or(DEFAULT_MAX_SERIES);
+        options
+            .get(DEDUP_WINDOW_KEY)
+            .and_then(|window| parse_dedup_window(window).ok())
+            .map(|window| Self::new(window).with_max_series(max_series))
+    }
+
+    /// Removes rows of `columns_values` identical to the last accepted points of their
+    /// series in the window. The accepted points are returned and only remembered once
+    /// passed to [DedupWindow::commit] after they are written. `schema` is the schema of
+    /// the table, whose primary key columns are `primary_key_indices`.
+    pub fn filter(
+        &self,
+        schema: &SchemaRef,
+        primary_key_indices: &[usize],
+        columns_values: HashMap<String, VectorRef>,
+    ) -> datatypes::Result<(HashMap<String, VectorRef>, AcceptedPoints)> {
+        let Some(ts_index) = schema.timestamp_index() else {
+            return Ok((columns_values, AcceptedPoints::default()));
+        };
+        let column_schemas = schema.column_schemas();
+        let Some(ts_vector) = columns_values.get(&column_schemas[ts_index].name) else {
+            return Ok((columns_values, AcceptedPoints::default()));
+        };
+        let column_of = |index: usize| columns_values.get(&column_schemas[index].name);
+        let key_vectors: Vec<_> = primary_key_indices.iter().map(|i| column_of(*i)).collect();
+        let field_vectors: Vec<_> = (0..column_schemas.len())
+            .filter(|i| *i != ts_index && !primary_key_indices.contains(i))
+            .map(column_of)
+            .collect();
+        let row_values = |vectors: &[Option<&VectorRef>], row: usize| -> Vec<Value> {
+            vectors
+                .iter()
+                .map(|vector| vector.map_or(Value::Null, |vector| vector.get(row)))
+                .collect()
+        };
+
+        let last_points = self.last_points.lock().unwrap();
+        let mut accepted_points = AcceptedPoints::default();
+        let mut accepted = Vec::with_capacity(ts_vector.len());
+        for row in 0..ts_vector.len() {
+            let timestamp_millis = match ts_vector.get(row) {
+                Value::Timestamp(ts) => ts.convert_to(TimeUnit::Millisecond).map(|ts| ts.value()),
+                _ => None,
+            };
+            let Some(timestamp_millis) = timestamp_millis else {
+                accepted.push(true);
+                continue;
+            };
+            let key = row_values(&key_vectors, row);
+            let fields = row_values(&field_vectors, row);
+
+            // Points accepted earlier in the batch are newer than the remembered ones.
+            let last = accepted_points
+                .0
+                .get(&key)
+                .or_else(|| last_points.get(&key));
+            let duplicate = last.map_or(false, |last| {
+                let elapsed = timestamp_millis - last.timestamp_millis;
+                (0..self.window_millis).contains(&elapsed) && last.fields == fields
+            });
+            if !duplicate {
+                let _ = accepted_points.0.insert(
+                    key,
+                    LastPoint {
+                        timestamp_millis,
+                        fields,
+                    },
+                );
+            }
+            accepted.push(!duplicate);
+        }
+        drop(last_points);
+
+        if accepted.iter().all(|accepted| *accepted) {
+            return Ok((columns_values, accepted_points));
+        }
+        let filter = BooleanVector::from(accepted);
+        let columns_values = columns_values
+            .into_iter()
+            .map(|(name, vector)| Ok((name, vector.filter(&filter)?)))
+            .collect::<datatypes::Result<_>>()?;
+        Ok((columns_values, accepted_points))
+    }
+
+    /// Remembers the points accepted by [DedupWindow::filter] once they are written.
+    pub fn commit(&self, accepted_points: AcceptedPoints) {
+        let mut last_points = self.last_points.lock().unwrap();
+        last_points.extend(accepted_points.0);
+        if last_points.len() > self.max_series {
+            last_points.clear();
+        }
+    }
+
+    /// Forgets the last points of the series with rows deleted, `key_column_values` holds the
+    /// primary key columns of the deleted rows. The next point of each series is accepted, as
+    /// the last point may be deleted.
+    pub fn invalidate(
+        &self,
+        schema: &SchemaRef,
+        primary_key_indices: &[usize],
+        key_column_values: &HashMap<String, VectorRef>,
+    ) {
+        let column_schemas = schema.column_schemas();
+        let key_vectors: Vec<_> = primary_key_indices
+            .iter()
+            .map(|i| key_column_values.get(&column_schemas[*i].name))
+            .collect();
+        let num_rows = key_column_values
+            .values()
+            .next()
+            .map_or(0, |vector| vector.len());
+
+        let mut last_points = self.last_points.lock().unwrap();
+        for row in 0..num_rows {
+            let key: Vec<_> = key_vectors
+                .iter()
+                .map(|vector| vector.map_or(Value::Null, |vector| vector.get(row)))
+                .collect();
+            let _ = last_points.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn new_schema() -> SchemaRef {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ];
+        Arc::new(Schema::try_new(column_schemas).unwrap())
+    }
+
+    fn new_columns_values(
+        hosts: &[&str],
+        timestamps: &[i64],
+        cpus: &[f64],
+    ) -> HashMap<String, VectorRef> {
+        HashMap::from([
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(hosts.to_vec())) as VectorRef,
+            ),
+            (
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(timestamps.to_vec())) as VectorRef,
+            ),
+            (
+                "cpu".to_string(),
+                Arc::new(Float64Vector::from_slice(cpus)) as VectorRef,
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_parse_dedup_window() {
+        assert_eq!(Duration::from_secs(600), parse_dedup_window("10m").unwrap());
+        assert!(parse_dedup_window("0s").is_err());
+        assert!(parse_dedup_window("abc").is_err());
+
+        assert_eq!(10, parse_dedup_window_max_series("10").unwrap());
+        assert!(parse_dedup_window_max_series("0").is_err());
+        assert!(parse_dedup_window_max_series("-1").is_err());
+    }
+
+    #[test]
+    fn test_dedup_window_filter() {
+        let schema = new_schema();
+        let dedup_window = DedupWindow::new(Duration::from_millis(100));
+
+        let columns_values = new_columns_values(
+            &["a", "a", "b", "a", "a"],
+            &[0, 10, 10, 20, 30],
+            &[1.0, 1.0, 1.0, 2.0, 2.0],
+        );
+        let (filtered, accepted) = dedup_window.filter(&schema, &[0], columns_values).unwrap();
+        let expect = new_columns_values(&["a", "b", "a"], &[0, 10, 20], &[1.0, 1.0, 2.0]);
+        assert_eq!(expect, filtered);
+        dedup_window.commit(accepted);
+
+        // Points out of the window or changed are accepted.
+        let columns_values = new_columns_values(&["a", "b", "b"], &[120, 50, 60], &[2.0, 1.0, 3.0]);
+        let (filtered, accepted) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        let expect = new_columns_values(&["a", "b"], &[120, 60], &[2.0, 3.0]);
+        assert_eq!(expect, filtered);
+        dedup_window.commit(accepted);
+
+        // Nothing is filtered.
+        let columns_values = new_columns_values(&["c"], &[0], &[1.0]);
+        let (filtered, _) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        assert_eq!(columns_values, filtered);
+        // Points not committed, e.g. failed to write, are not remembered.
+        let (filtered, _) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        assert_eq!(columns_values, filtered);
+
+        // The next point of a series with rows deleted is accepted.
+        let columns_values = new_columns_values(&["b"], &[70], &[3.0]);
+        let (filtered, _) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        assert!(filtered["host"].is_empty());
+        let key_column_values = HashMap::from([(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["b"])) as VectorRef,
+        )]);
+        dedup_window.invalidate(&schema, &[0], &key_column_values);
+        let (filtered, _) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        assert_eq!(columns_values, filtered);
+    }
+
+    #[test]
+    fn test_dedup_window_max_series() {
+        let schema = new_schema();
+        let dedup_window = DedupWindow::new(Duration::from_millis(100)).with_max_series(2);
+
+        let columns_values = new_columns_values(&["a", "b", "c"], &[0, 0, 0], &[1.0, 1.0, 1.0]);
+        let (_, accepted) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        dedup_window.commit(accepted);
+        // All points are forgotten once there are too many series.
+        let (filtered, _) = dedup_window
+            .filter(&schema, &[0], columns_values.clone())
+            .unwrap();
+        assert_eq!(columns_values, filtered);
+    }
+}
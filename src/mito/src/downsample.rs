@@ -0,0 +1,1208 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Downsampling of tables.
+//!
+//! A table with downsample tiers rolls up rows older than the `after` threshold of a tier to
+//! the `resolution` of the tier: rows of a series in the same time bucket are replaced by one
+//! row at the start of the bucket, whose fields are aggregated by the aggregate function of
+//! each field. Rolled up rows are normal rows, so queries read them transparently.
+//!
+//! Rows are rolled up by a background task periodically, and also while reading by
+//! [RollupReader], so queries read rows at the resolution of their tiers even if the task
+//! hasn't rolled them up yet, e.g. rows written late to old buckets.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datatypes::prelude::*;
+use datatypes::schema::SchemaRef;
+use snafu::{ensure, OptionExt};
+use store_api::storage::{Chunk, ChunkReader};
+
+use crate::error::{InvalidDownsampleOptionsSnafu, MissingTimestampIndexSnafu, Result};
+
+/// Table option of the downsample tiers, in the form of `{after}:{resolution}` pairs separated
+/// by commas, e.g. `7d:1m,30d:1h` rolls up rows older than 7 days to 1 minute resolution and
+/// rows older than 30 days to 1 hour resolution.
+pub const DOWNSAMPLE_TIERS_KEY: &str = "downsample_tiers";
+/// Table option of the aggregate functions of fields, in the form of `{field}:{aggregate}`
+/// pairs separated by commas, e.g. `cpu:max,requests:sum`. Fields are averaged by default.
+pub const DOWNSAMPLE_AGGREGATES_KEY: &str = "downsample_aggregates";
+/// Table option of the field counting the raw rows each row represents, which must be a
+/// nullable `uint64` field, rows with a null count are raw rows. Averages are weighted by the
+/// counts, so rows rolled up again by a coarser tier keep exact averages. It's required if
+/// any numeric field is averaged.
+pub const DOWNSAMPLE_COUNT_KEY: &str = "downsample_count";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownsampleTier {
+    /// Rows older than this are rolled up.
+    pub after: Duration,
+    /// Time resolution the rows are rolled up to.
+    pub resolution: Duration,
+}
+
+/// Function to aggregate values of a field in a time bucket, null values are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aggregate {
+    /// Average of numeric values weighted by their counts, other values keep the last one.
+    #[default]
+    Avg,
+    /// Sum of numeric values, other values keep the last one. Rows are not rolled up if
+    /// the sum overflows.
+    Sum,
+    Min,
+    Max,
+    First,
+    Last,
+}
+
+impl Aggregate {
+    fn from_name(name: &str) -> Option<Aggregate> {
+        match name.to_lowercase().as_str() {
+            "avg" => Some(Aggregate::Avg),
+            "sum" => Some(Aggregate::Sum),
+            "min" => Some(Aggregate::Min),
+            "max" => Some(Aggregate::Max),
+            "first" => Some(Aggregate::First),
+            "last" => Some(Aggregate::Last),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownsampleOptions {
+    /// Tiers ordered by `after`, the resolution of a tier is a multiple of the resolution of
+    /// its previous tier.
+    pub tiers: Vec<DownsampleTier>,
+    /// Aggregate functions of fields.
+    pub aggregates: HashMap<String, Aggregate>,
+    /// Field counting the raw rows each row represents, see [DOWNSAMPLE_COUNT_KEY].
+    pub count_column: Option<String>,
+}
+
+impl DownsampleOptions {
+    /// Parses the downsample options from table `options`, returns `None` if the table has no
+    /// downsample tiers.
+    pub fn from_table_options(
+        options: &HashMap<String, String>,
+    ) -> Result<Option<DownsampleOptions>> {
+        let Some(tiers) = options.get(DOWNSAMPLE_TIERS_KEY) else {
+            return Ok(None);
+        };
+        let tiers = parse_tiers(tiers)?;
+        let aggregates = match options.get(DOWNSAMPLE_AGGREGATES_KEY) {
+            Some(aggregates) => parse_aggregates(aggregates)?,
+            None => HashMap::new(),
+        };
+
+        let count_column = options.get(DOWNSAMPLE_COUNT_KEY).cloned();
+
+        Ok(Some(DownsampleOptions {
+            tiers,
+            aggregates,
+            count_column,
+        }))
+    }
+
+    /// Validates the options against the `schema` of the table whose primary key columns are
+    /// `primary_key_indices`.
+    pub fn validate(&self, schema: &SchemaRef, primary_key_indices: &[usize]) -> Result<()> {
+        let ts_column = schema
+            .timestamp_column()
+            .context(MissingTimestampIndexSnafu {
+                table_name: "downsample",
+            })?;
+        let unit_nanos = timestamp_unit_nanos(&ts_column.data_type) as u128;
+        for tier in &self.tiers {
+            ensure!(
+                tier.resolution.as_nanos() % unit_nanos == 0,
+                InvalidDownsampleOptionsSnafu {
+                    reason: format!(
+                        "resolution {:?} is finer than the unit of the time index",
+                        tier.resolution
+                    ),
+                }
+            );
+        }
+
+        let is_field = |index: usize| {
+            Some(index) != schema.timestamp_index() && !primary_key_indices.contains(&index)
+        };
+        for field in self.aggregates.keys() {
+            let index = schema.column_index_by_name(field);
+            ensure!(
+                index.map_or(false, is_field) && Some(field) != self.count_column.as_ref(),
+                InvalidDownsampleOptionsSnafu {
+                    reason: format!("{field} is not a field column to aggregate"),
+                }
+            );
+        }
+
+        if let Some(count_column) = &self.count_column {
+            let index = schema.column_index_by_name(count_column);
+            let valid = index.map_or(false, |index| {
+                let column = &schema.column_schemas()[index];
+                is_field(index)
+                    && column.is_nullable()
+                    && column.data_type == ConcreteDataType::uint64_datatype()
+            });
+            ensure!(
+                valid,
+                InvalidDownsampleOptionsSnafu {
+                    reason: format!("{count_column} is not a nullable uint64 field"),
+                }
+            );
+        }
+
+        // Averages of rolled up rows are only exact if they are weighted by the counts.
+        let averaged = schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .find(|(index, column)| {
+                is_field(*index)
+                    && Some(&column.name) != self.count_column.as_ref()
+                    && self.aggregate(&column.name) == Aggregate::Avg
+                    && f64_to_value(0.0, &column.data_type).is_some()
+            });
+        if let Some((_, column)) = averaged {
+            ensure!(
+                self.count_column.is_some(),
+                InvalidDownsampleOptionsSnafu {
+                    reason: format!(
+                        "{} is averaged but the table doesn't set `{}`",
+                        column.name, DOWNSAMPLE_COUNT_KEY
+                    ),
+                }
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the finest resolution of the tiers.
+    pub fn finest_resolution(&self) -> Duration {
+        // Safety: Tiers are not empty and ordered by resolution.
+        self.tiers[0].resolution
+    }
+
+    /// Returns the aggregate function of the `field`.
+    fn aggregate(&self, field: &str) -> Aggregate {
+        self.aggregates.get(field).copied().unwrap_or_default()
+    }
+}
+
+/// Parses comma separated `{key}:{value}` pairs.
+fn parse_pairs(s: &str) -> impl Iterator<Item = Result<(&str, &str)>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            pair.split_once(':')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .with_context(|| InvalidDownsampleOptionsSnafu {
+                    reason: format!("expect `key:value`, found `{pair}`"),
+                })
+        })
+}
+
+fn parse_duration(s: &str) -> Result<Duration> {
+    humantime::parse_duration(s)
+        .ok()
+        .filter(|duration| !duration.is_zero())
+        .with_context(|| InvalidDownsampleOptionsSnafu {
+            reason: format!("invalid duration `{s}`"),
+        })
+}
+
+fn parse_tiers(s: &str) -> Result<Vec<DownsampleTier>> {
+    let mut tiers = parse_pairs(s)
+        .map(|pair| {
+            let (after, resolution) = pair?;
+            Ok(DownsampleTier {
+                after: parse_duration(after)?,
+                resolution: parse_duration(resolution)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    tiers.sort_by_key(|tier| tier.after);
+
+    ensure!(
+        !tiers.is_empty(),
+        InvalidDownsampleOptionsSnafu {
+            reason: "no downsample tier",
+        }
+    );
+    for pair in tiers.windows(2) {
+        let (finer, coarser) = (&pair[0], &pair[1]);
+        ensure!(
+            finer.after < coarser.after,
+            InvalidDownsampleOptionsSnafu {
+                reason: format!("duplicate tiers after {:?}", finer.after),
+            }
+        );
+        // Buckets of a coarser tier must be made up of buckets of finer tiers, so rows rolled
+        // up by a finer tier could be rolled up again by a coarser tier.
+        ensure!(
+            coarser.resolution >= finer.resolution
+                && coarser.resolution.as_nanos() % finer.resolution.as_nanos() == 0,
+            InvalidDownsampleOptionsSnafu {
+                reason: format!(
+                    "resolution {:?} is not a multiple of the resolution {:?} of the finer tier",
+                    coarser.resolution, finer.resolution
+                ),
+            }
+        );
+    }
+    Ok(tiers)
+}
+
+fn parse_aggregates(s: &str) -> Result<HashMap<String, Aggregate>> {
+    parse_pairs(s)
+        .map(|pair| {
+            let (field, aggregate) = pair?;
+            let aggregate =
+                Aggregate::from_name(aggregate).with_context(|| InvalidDownsampleOptionsSnafu {
+                    reason: format!("unknown aggregate function `{aggregate}`"),
+                })?;
+            Ok((field.to_string(), aggregate))
+        })
+        .collect()
+}
+
+/// Returns nanoseconds of the unit of the timestamp `data_type`.
+fn timestamp_unit_nanos(data_type: &ConcreteDataType) -> i64 {
+    match data_type {
+        ConcreteDataType::Timestamp(t) => t.unit().factor(),
+        _ => unreachable!(
+            "The time index should be a timestamp, found {:?}",
+            data_type
+        ),
+    }
+}
+
+/// Rows to write to roll up rows.
+#[derive(Debug)]
+pub(crate) struct RollupWrites {
+    /// Rolled up rows to put.
+    pub puts: HashMap<String, VectorRef>,
+    /// Keys of raw rows to delete.
+    pub deletes: Option<HashMap<String, VectorRef>>,
+    /// Number of raw rows rolled up.
+    pub num_rows: usize,
+}
+
+/// A downsample tier in the unit of the time index.
+#[derive(Debug, Clone, Copy)]
+struct Tier {
+    after: i64,
+    resolution: i64,
+}
+
+impl Tier {
+    /// Returns the start of the bucket containing `ts`.
+    fn bucket_of(&self, ts: i64) -> i64 {
+        ts - ts.rem_euclid(self.resolution)
+    }
+}
+
+/// Rolls up rows pushed in the order of (primary key, timestamp).
+pub(crate) struct Rollup {
+    schema: SchemaRef,
+    ts_index: usize,
+    primary_key_indices: Vec<usize>,
+    /// Aggregate function of each column, `None` for primary key columns, time index and
+    /// the count column.
+    aggregates: Vec<Option<Aggregate>>,
+    /// Index of the column counting the raw rows each row represents.
+    count_index: Option<usize>,
+    /// Tiers ordered by `after`, coarsest tier first.
+    tiers: Vec<Tier>,
+    /// Current time in the unit of the time index.
+    now: i64,
+    /// Time of the last rollup in the unit of the time index, only buckets becoming old
+    /// enough since then are rolled up if it's set.
+    since: Option<i64>,
+    /// Whether the rows are rolled up while reading, rows not rolled up are output as is.
+    reading: bool,
+    current: Option<Group>,
+    rows_to_put: Vec<Vec<Value>>,
+    keys_to_delete: Vec<Vec<Value>>,
+    /// Rows to output while reading.
+    rows_to_output: Vec<Vec<Value>>,
+    num_rows: usize,
+}
+
+/// Rows of a series in a time bucket.
+struct Group {
+    keys: Vec<Value>,
+    bucket: i64,
+    states: Vec<AggregateState>,
+    timestamps: Vec<i64>,
+    /// Number of raw rows the rows of the group represent.
+    count: u64,
+    /// Rows of the group, only kept while reading.
+    rows: Vec<Vec<Value>>,
+    /// Whether the rows could not be rolled up as aggregating them overflows.
+    overflowed: bool,
+}
+
+impl Rollup {
+    pub(crate) fn new(
+        schema: SchemaRef,
+        primary_key_indices: &[usize],
+        options: &DownsampleOptions,
+        now_millis: i64,
+    ) -> Result<Rollup> {
+        let ts_index = schema
+            .timestamp_index()
+            .context(MissingTimestampIndexSnafu {
+                table_name: "downsample",
+            })?;
+        let unit_nanos = timestamp_unit_nanos(&schema.column_schemas()[ts_index].data_type);
+        let to_unit = |duration: Duration| (duration.as_nanos() / unit_nanos as u128) as i64;
+
+        let count_index = options
+            .count_column
+            .as_ref()
+            .and_then(|column| schema.column_index_by_name(column));
+        let aggregates = schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                (index != ts_index
+                    && !primary_key_indices.contains(&index)
+                    && Some(index) != count_index)
+                    .then(|| options.aggregate(&column.name))
+            })
+            .collect();
+        let tiers = options
+            .tiers
+            .iter()
+            .rev()
+            .map(|tier| Tier {
+                after: to_unit(tier.after),
+                resolution: to_unit(tier.resolution),
+            })
+            .collect();
+
+        Ok(Rollup {
+            schema,
+            ts_index,
+            primary_key_indices: primary_key_indices.to_vec(),
+            aggregates,
+            count_index,
+            tiers,
+            now: millis_to_unit(now_millis, unit_nanos),
+            since: None,
+            reading: false,
+            current: None,
+            rows_to_put: Vec::new(),
+            keys_to_delete: Vec::new(),
+            rows_to_output: Vec::new(),
+            num_rows: 0,
+        })
+    }
+
+    /// Only rolls up the buckets becoming old enough since `since_millis`, when the last
+    /// rollup happened. Older buckets are either rolled up by then, or hold rows written
+    /// late, which are rolled up while reading.
+    pub(crate) fn with_since(mut self, since_millis: i64) -> Rollup {
+        let unit_nanos =
+            timestamp_unit_nanos(&self.schema.column_schemas()[self.ts_index].data_type);
+        self.since = Some(millis_to_unit(since_millis, unit_nanos));
+        self
+    }
+
+    /// Rolls up rows while reading them, see [RollupReader].
+    fn with_reading(mut self) -> Rollup {
+        self.reading = true;
+        self
+    }
+
+    /// Returns the time index column and the time ranges `[start, end)` of the rows to roll
+    /// up, the ranges are empty if there is nothing to roll up.
+    pub(crate) fn time_ranges(&self) -> (&str, Vec<(Option<Value>, Value)>) {
+        let ts_column = &self.schema.column_schemas()[self.ts_index];
+        let ConcreteDataType::Timestamp(ts_type) = &ts_column.data_type else {
+            unreachable!()
+        };
+        let timestamp = |ts| Value::Timestamp(common_time::Timestamp::new(ts, ts_type.unit()));
+
+        let ranges = match self.since {
+            // Buckets of a tier becoming old enough since the last rollup.
+            Some(since) => self
+                .tiers
+                .iter()
+                .filter_map(|tier| {
+                    let start = tier.bucket_of(since - tier.after);
+                    let end = tier.bucket_of(self.now - tier.after);
+                    (start < end).then(|| (Some(timestamp(start)), timestamp(end)))
+                })
+                .collect(),
+            None => {
+                // Safety: There is at least one tier.
+                let finest = self.tiers.last().unwrap();
+                vec![(None, timestamp(self.now - finest.after))]
+            }
+        };
+        (&ts_column.name, ranges)
+    }
+
+    /// Returns the start of the bucket of the coarsest tier whose bucket containing `ts` is
+    /// older than the `after` of the tier.
+    fn bucket_of(&self, ts: i64) -> Option<i64> {
+        let (tier, bucket) = self.tiers.iter().find_map(|tier| {
+            let bucket = tier.bucket_of(ts);
+            (bucket + tier.resolution <= self.now - tier.after).then_some((tier, bucket))
+        })?;
+        match self.since {
+            // The bucket is rolled up by the last rollup.
+            Some(since) if bucket + tier.resolution <= since - tier.after => None,
+            _ => Some(bucket),
+        }
+    }
+
+    /// Pushes rows in `columns`, which are in the order of the schema.
+    pub(crate) fn push_columns(&mut self, columns: &[VectorRef]) {
+        let num_rows = columns.first().map(|c| c.len()).unwrap_or(0);
+        for row in 0..num_rows {
+            let bucket = match columns[self.ts_index].get(row) {
+                Value::Timestamp(ts) => self
+                    .bucket_of(ts.value())
+                    .map(|bucket| (ts.value(), bucket)),
+                _ => None,
+            };
+            let Some((ts, bucket)) = bucket else {
+                if self.reading {
+                    // Keeps the order of rows.
+                    self.finish_group();
+                    self.rows_to_output.push(row_values(columns, row));
+                }
+                continue;
+            };
+            let keys: Vec<_> = self
+                .primary_key_indices
+                .iter()
+                .map(|index| columns[*index].get(row))
+                .collect();
+
+            let in_current = self
+                .current
+                .as_ref()
+                .map_or(false, |group| group.bucket == bucket && group.keys == keys);
+            if !in_current {
+                self.finish_group();
+                self.current = Some(Group {
+                    keys,
+                    bucket,
+                    states: self
+                        .aggregates
+                        .iter()
+                        .map(|aggregate| AggregateState::new(aggregate.unwrap_or_default()))
+                        .collect(),
+                    timestamps: Vec::new(),
+                    count: 0,
+                    rows: Vec::new(),
+                    overflowed: false,
+                });
+            }
+
+            // Rows without counts are raw rows.
+            let count = match self.count_index.map(|index| columns[index].get(row)) {
+                Some(Value::UInt64(count)) => count,
+                _ => 1,
+            };
+            // Safety: The current group is set above.
+            let group = self.current.as_mut().unwrap();
+            group.timestamps.push(ts);
+            match group.count.checked_add(count) {
+                Some(sum) => group.count = sum,
+                None => group.overflowed = true,
+            }
+            for (index, state) in group.states.iter_mut().enumerate() {
+                if self.aggregates[index].is_some() && !state.update(columns[index].get(row), count)
+                {
+                    group.overflowed = true;
+                }
+            }
+            if self.reading {
+                group.rows.push(row_values(columns, row));
+            }
+        }
+    }
+
+    /// Finishes the current group, rows pushed later belong to new groups.
+    pub(crate) fn finish_group(&mut self) {
+        let Some(group) = self.current.take() else {
+            return;
+        };
+        if group.overflowed {
+            // The rolled up row can't hold the aggregated values, so the rows are kept.
+            self.rows_to_output.extend(group.rows);
+            return;
+        }
+        if !self.reading && group.timestamps == [group.bucket] {
+            // Already rolled up.
+            return;
+        }
+
+        let ts_type = &self.schema.column_schemas()[self.ts_index].data_type;
+        let ConcreteDataType::Timestamp(ts_type) = ts_type else {
+            unreachable!()
+        };
+        let unit = ts_type.unit();
+        let timestamp = |ts| Value::Timestamp(common_time::Timestamp::new(ts, unit));
+
+        let mut keys = group.keys.into_iter();
+        let mut states = group.states.into_iter();
+        let row = self
+            .schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let state = states.next();
+                if index == self.ts_index {
+                    timestamp(group.bucket)
+                } else if Some(index) == self.count_index {
+                    Value::UInt64(group.count)
+                } else if self.aggregates[index].is_some() {
+                    // Safety: There is a state for each column.
+                    state.unwrap().finish(&column.data_type)
+                } else {
+                    // Safety: Primary key columns are in the same order as the keys.
+                    keys.next().unwrap()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if self.reading {
+            self.rows_to_output.push(row);
+            return;
+        }
+
+        for ts in &group.timestamps {
+            if *ts == group.bucket {
+                // Overwritten by the rolled up row.
+                continue;
+            }
+            let mut key: Vec<_> = self
+                .primary_key_indices
+                .iter()
+                .map(|index| row[*index].clone())
+                .collect();
+            key.push(timestamp(*ts));
+            self.keys_to_delete.push(key);
+        }
+        self.num_rows += group.timestamps.len();
+        self.rows_to_put.push(row);
+    }
+
+    /// Takes the rows to write for finished groups, returns `None` if there is nothing to
+    /// write.
+    pub(crate) fn take_writes(&mut self) -> Option<RollupWrites> {
+        if self.rows_to_put.is_empty() {
+            return None;
+        }
+
+        let column_schemas = self.schema.column_schemas();
+        let rows = std::mem::take(&mut self.rows_to_put);
+        let puts = column_schemas
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let values = rows.iter().map(|row| &row[index]);
+                (column.name.clone(), build_vector(&column.data_type, values))
+            })
+            .collect();
+
+        let keys = std::mem::take(&mut self.keys_to_delete);
+        let deletes = (!keys.is_empty()).then(|| {
+            self.primary_key_indices
+                .iter()
+                .chain(std::iter::once(&self.ts_index))
+                .enumerate()
+                .map(|(i, index)| {
+                    let column = &column_schemas[*index];
+                    let values = keys.iter().map(|key| &key[i]);
+                    (column.name.clone(), build_vector(&column.data_type, values))
+                })
+                .collect()
+        });
+
+        Some(RollupWrites {
+            puts,
+            deletes,
+            num_rows: std::mem::take(&mut self.num_rows),
+        })
+    }
+
+    /// Takes the rows to output while reading, returns `None` if there is nothing to output.
+    fn take_output(&mut self) -> Option<Chunk> {
+        if self.rows_to_output.is_empty() {
+            return None;
+        }
+
+        let rows = std::mem::take(&mut self.rows_to_output);
+        let columns = self
+            .schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                build_vector(&column.data_type, rows.iter().map(|row| &row[index]))
+            })
+            .collect();
+        Some(Chunk::new(columns))
+    }
+
+    /// Returns whether all rows in `columns` are output as is.
+    fn is_pass_through(&self, columns: &[VectorRef]) -> bool {
+        let ts_column = &columns[self.ts_index];
+        self.current.is_none()
+            && self.rows_to_output.is_empty()
+            && (0..ts_column.len()).all(|row| match ts_column.get(row) {
+                Value::Timestamp(ts) => self.bucket_of(ts.value()).is_none(),
+                _ => true,
+            })
+    }
+}
+
+/// Reader rolling up the rows older than the downsample tiers while reading, the rows must be
+/// in the order of (primary key, timestamp) and contain all columns of the table.
+pub(crate) struct RollupReader<R> {
+    reader: R,
+    rollup: Rollup,
+    finished: bool,
+}
+
+impl<R> RollupReader<R> {
+    pub(crate) fn new(
+        reader: R,
+        schema: SchemaRef,
+        primary_key_indices: &[usize],
+        options: &DownsampleOptions,
+        now_millis: i64,
+    ) -> Result<RollupReader<R>> {
+        let rollup = Rollup::new(schema, primary_key_indices, options, now_millis)?.with_reading();
+        Ok(RollupReader {
+            reader,
+            rollup,
+            finished: false,
+        })
+    }
+}
+
+#[async_trait]
+impl<R: ChunkReader> ChunkReader for RollupReader<R> {
+    type Error = R::Error;
+
+    fn schema(&self) -> &SchemaRef {
+        self.reader.schema()
+    }
+
+    async fn next_chunk(&mut self) -> std::result::Result<Option<Chunk>, R::Error> {
+        while !self.finished {
+            let Some(chunk) = self.reader.next_chunk().await? else {
+                self.finished = true;
+                self.rollup.finish_group();
+                break;
+            };
+            // Most chunks of recent rows are output as is.
+            if self.rollup.is_pass_through(&chunk.columns) {
+                return Ok(Some(chunk));
+            }
+            self.rollup.push_columns(&chunk.columns);
+            if let Some(chunk) = self.rollup.take_output() {
+                return Ok(Some(chunk));
+            }
+        }
+        Ok(self.rollup.take_output())
+    }
+}
+
+/// Converts `millis` to the unit whose nanoseconds are `unit_nanos`.
+fn millis_to_unit(millis: i64, unit_nanos: i64) -> i64 {
+    millis.saturating_mul(1_000_000) / unit_nanos
+}
+
+fn row_values(columns: &[VectorRef], row: usize) -> Vec<Value> {
+    columns.iter().map(|column| column.get(row)).collect()
+}
+
+fn build_vector<'a>(
+    data_type: &ConcreteDataType,
+    values: impl ExactSizeIterator<Item = &'a Value>,
+) -> VectorRef {
+    let mut builder = data_type.create_mutable_vector(values.len());
+    for value in values {
+        // Safety: Values are read from or aggregated to the column of the data type.
+        builder.push_value_ref(value.as_value_ref()).unwrap();
+    }
+    builder.to_vector()
+}
+
+/// State of aggregating values of a field.
+struct AggregateState {
+    aggregate: Aggregate,
+    value: Value,
+    /// Sum and count of numeric values to compute the average, values are weighted by the
+    /// number of raw rows they represent.
+    sum: f64,
+    count: f64,
+}
+
+impl AggregateState {
+    fn new(aggregate: Aggregate) -> AggregateState {
+        AggregateState {
+            aggregate,
+            value: Value::Null,
+            sum: 0.0,
+            count: 0.0,
+        }
+    }
+
+    /// Updates the state by the `value` representing `count` raw rows, returns false if the
+    /// aggregated value overflows.
+    fn update(&mut self, value: Value, count: u64) -> bool {
+        if value.is_null() {
+            return true;
+        }
+
+        match self.aggregate {
+            Aggregate::Avg => {
+                if let Some(v) = value_as_f64(&value) {
+                    self.sum += v * count as f64;
+                    self.count += count as f64;
+                }
+                self.value = value;
+            }
+            Aggregate::Sum => match sum_values(&self.value, &value) {
+                Some(Some(sum)) => self.value = sum,
+                Some(None) => return false,
+                // Not numeric values, keeps the last one.
+                None => self.value = value,
+            },
+            Aggregate::Min => {
+                if self.value.is_null() || value < self.value {
+                    self.value = value;
+                }
+            }
+            Aggregate::Max => {
+                if self.value.is_null() || value > self.value {
+                    self.value = value;
+                }
+            }
+            Aggregate::First => {
+                if self.value.is_null() {
+                    self.value = value;
+                }
+            }
+            Aggregate::Last => self.value = value,
+        }
+        true
+    }
+
+    fn finish(self, data_type: &ConcreteDataType) -> Value {
+        if self.aggregate == Aggregate::Avg && self.count > 0.0 {
+            if let Some(avg) = f64_to_value(self.sum / self.count, data_type) {
+                return avg;
+            }
+        }
+        self.value
+    }
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    let v = match value {
+        Value::UInt8(v) => *v as f64,
+        Value::UInt16(v) => *v as f64,
+        Value::UInt32(v) => *v as f64,
+        Value::UInt64(v) => *v as f64,
+        Value::Int8(v) => *v as f64,
+        Value::Int16(v) => *v as f64,
+        Value::Int32(v) => *v as f64,
+        Value::Int64(v) => *v as f64,
+        Value::Float32(v) => v.0 as f64,
+        Value::Float64(v) => v.0,
+        _ => return None,
+    };
+    Some(v)
+}
+
+fn f64_to_value(v: f64, data_type: &ConcreteDataType) -> Option<Value> {
+    let value = match data_type {
+        ConcreteDataType::UInt8(_) => Value::UInt8(v.round() as _),
+        ConcreteDataType::UInt16(_) => Value::UInt16(v.round() as _),
+        ConcreteDataType::UInt32(_) => Value::UInt32(v.round() as _),
+        ConcreteDataType::UInt64(_) => Value::UInt64(v.round() as _),
+        ConcreteDataType::Int8(_) => Value::Int8(v.round() as _),
+        ConcreteDataType::Int16(_) => Value::Int16(v.round() as _),
+        ConcreteDataType::Int32(_) => Value::Int32(v.round() as _),
+        ConcreteDataType::Int64(_) => Value::Int64(v.round() as _),
+        ConcreteDataType::Float32(_) => Value::from(v as f32),
+        ConcreteDataType::Float64(_) => Value::from(v),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Adds two values of the same type, returns `None` if they are not numeric values and
+/// `Some(None)` if the sum overflows.
+fn sum_values(lhs: &Value, rhs: &Value) -> Option<Option<Value>> {
+    macro_rules! sum_values {
+        ($lhs: ident, $rhs: ident, $($Type: ident),*) => {
+            match ($lhs, $rhs) {
+                (Value::Null, v) | (v, Value::Null) => Some(Some(v.clone())),
+                $((Value::$Type(a), Value::$Type(b)) => Some(a.checked_add(*b).map(Value::$Type)),)*
+                (Value::Float32(a), Value::Float32(b)) => {
+                    let sum = a.0 + b.0;
+                    Some(sum.is_finite().then(|| Value::from(sum)))
+                }
+                (Value::Float64(a), Value::Float64(b)) => {
+                    let sum = a.0 + b.0;
+                    Some(sum.is_finite().then(|| Value::from(sum)))
+                }
+                _ => None,
+            }
+        };
+    }
+
+    sum_values!(lhs, rhs, UInt8, UInt16, UInt32, UInt64, Int8, Int16, Int32, Int64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnSchema, SchemaBuilder};
+    use datatypes::vectors::{
+        Float64Vector, Int64Vector, StringVector, TimestampSecondVector, UInt64Vector,
+    };
+
+    use super::*;
+    use crate::error::Error;
+
+    fn new_options(tiers: &str, aggregates: Option<&str>) -> Result<Option<DownsampleOptions>> {
+        let mut options = HashMap::from([(DOWNSAMPLE_TIERS_KEY.to_string(), tiers.to_string())]);
+        if let Some(aggregates) = aggregates {
+            options.insert(
+                DOWNSAMPLE_AGGREGATES_KEY.to_string(),
+                aggregates.to_string(),
+            );
+        }
+        DownsampleOptions::from_table_options(&options)
+    }
+
+    fn new_schema() -> SchemaRef {
+        new_schema_with_fields(vec![ColumnSchema::new(
+            "requests",
+            ConcreteDataType::int64_datatype(),
+            true,
+        )])
+    }
+
+    fn new_schema_with_fields(fields: Vec<ColumnSchema>) -> SchemaRef {
+        let mut column_schemas = vec![ColumnSchema::new(
+            "host",
+            ConcreteDataType::string_datatype(),
+            false,
+        )];
+        column_schemas.extend(fields);
+        column_schemas.push(
+            ColumnSchema::new("ts", ConcreteDataType::timestamp_second_datatype(), false)
+                .with_time_index(true),
+        );
+        Arc::new(
+            SchemaBuilder::try_from(column_schemas)
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            None,
+            DownsampleOptions::from_table_options(&HashMap::new()).unwrap()
+        );
+
+        let options = new_options("30d:1h, 7d:1m", Some("cpu:max,requests:SUM"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![
+                DownsampleTier {
+                    after: Duration::from_secs(7 * 86400),
+                    resolution: Duration::from_secs(60),
+                },
+                DownsampleTier {
+                    after: Duration::from_secs(30 * 86400),
+                    resolution: Duration::from_secs(3600),
+                },
+            ],
+            options.tiers
+        );
+        assert_eq!(Duration::from_secs(60), options.finest_resolution());
+        assert_eq!(Aggregate::Max, options.aggregate("cpu"));
+        assert_eq!(Aggregate::Sum, options.aggregate("requests"));
+        assert_eq!(Aggregate::Avg, options.aggregate("memory"));
+
+        for tiers in [
+            "",
+            "7d",
+            "7d:0s",
+            "7d:1x",
+            "7d:1m,7d:1h",
+            "7d:1h,30d:1m",
+            "7d:1m,30d:90s",
+        ] {
+            assert!(new_options(tiers, None).is_err(), "{tiers}");
+        }
+        assert!(new_options("7d:1m", Some("cpu:median")).is_err());
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let schema = new_schema();
+        let options = new_options("7d:1m", Some("requests:sum")).unwrap().unwrap();
+        options.validate(&schema, &[0]).unwrap();
+
+        for aggregates in ["host:sum", "ts:max", "unknown:min"] {
+            let options = new_options("7d:1m", Some(aggregates)).unwrap().unwrap();
+            assert!(options.validate(&schema, &[0]).is_err(), "{aggregates}");
+        }
+        let options = new_options("7d:500ms", Some("requests:sum"))
+            .unwrap()
+            .unwrap();
+        assert!(options.validate(&schema, &[0]).is_err());
+
+        // Averaged fields require the count column.
+        let options = new_options("7d:1m", None).unwrap().unwrap();
+        assert!(options.validate(&schema, &[0]).is_err());
+        let schema = new_schema_with_fields(vec![
+            ColumnSchema::new("requests", ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new("count", ConcreteDataType::uint64_datatype(), true),
+        ]);
+        let mut options = new_options("7d:1m", None).unwrap().unwrap();
+        options.count_column = Some("count".to_string());
+        options.validate(&schema, &[0]).unwrap();
+        for count_column in ["requests", "host", "unknown"] {
+            options.count_column = Some(count_column.to_string());
+            assert!(options.validate(&schema, &[0]).is_err(), "{count_column}");
+        }
+        options.count_column = Some("count".to_string());
+        options
+            .aggregates
+            .insert("count".to_string(), Aggregate::Sum);
+        assert!(options.validate(&schema, &[0]).is_err());
+    }
+
+    #[test]
+    fn test_rollup() {
+        let options = new_options("1h:1m,1d:1h", Some("requests:sum"))
+            .unwrap()
+            .unwrap();
+        let now_millis = 2 * 86400 * 1000;
+        let mut rollup = Rollup::new(new_schema(), &[0], &options, now_millis).unwrap();
+        let (ts_column, ranges) = rollup.time_ranges();
+        assert_eq!("ts", ts_column);
+        assert_eq!(
+            vec![(
+                None,
+                Value::Timestamp(common_time::Timestamp::new_second(2 * 86400 - 3600))
+            )],
+            ranges
+        );
+
+        let hosts = ["a", "a", "a", "a", "b", "b"];
+        let requests = [Some(1), None, Some(2), Some(3), Some(4), Some(5)];
+        // Rows of `a` in the first hour and the same minute of the second day, rows of `b`
+        // are too recent or already rolled up.
+        let timestamps = [10, 20, 100_000, 100_010, 0, 172_000];
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(hosts.to_vec())),
+            Arc::new(Int64Vector::from(requests.to_vec())),
+            Arc::new(TimestampSecondVector::from_slice(timestamps)),
+        ];
+        let check_writes = |writes: RollupWrites, requests, bucket, deleted: [i64; 2]| {
+            assert_eq!(2, writes.num_rows);
+            let expect: VectorRef = Arc::new(Int64Vector::from_slice([requests]));
+            assert_eq!(expect, writes.puts["requests"]);
+            let expect: VectorRef = Arc::new(TimestampSecondVector::from_slice([bucket]));
+            assert_eq!(expect, writes.puts["ts"]);
+            let expect: VectorRef = Arc::new(TimestampSecondVector::from_slice(deleted));
+            assert_eq!(expect, writes.deletes.unwrap()["ts"]);
+        };
+
+        // Pushes rows in two chunks, the group of the minute spans both chunks.
+        let chunk = |offset| {
+            columns
+                .iter()
+                .map(|c| c.slice(offset, 3))
+                .collect::<Vec<_>>()
+        };
+        rollup.push_columns(&chunk(0));
+        check_writes(rollup.take_writes().unwrap(), 1, 0, [10, 20]);
+        rollup.push_columns(&chunk(3));
+        rollup.finish_group();
+        check_writes(rollup.take_writes().unwrap(), 5, 99_960, [100_000, 100_010]);
+        assert!(rollup.take_writes().is_none());
+    }
+
+    #[test]
+    fn test_rollup_since() {
+        let options = new_options("1h:1m,1d:1h", Some("requests:sum"))
+            .unwrap()
+            .unwrap();
+        let now_millis = 2 * 86400 * 1000;
+        let rollup = Rollup::new(new_schema(), &[0], &options, now_millis)
+            .unwrap()
+            .with_since(now_millis - 60_000);
+        let timestamp = |ts| Value::Timestamp(common_time::Timestamp::new_second(ts));
+        // Buckets of each tier becoming old enough in the last minute.
+        let (_, ranges) = rollup.time_ranges();
+        assert_eq!(
+            vec![
+                (Some(timestamp(82_800)), timestamp(86_400)),
+                (Some(timestamp(169_140)), timestamp(169_200)),
+            ],
+            ranges
+        );
+        // Buckets rolled up by the last rollup are skipped.
+        assert_eq!(None, rollup.bucket_of(10));
+        assert_eq!(Some(82_800), rollup.bucket_of(86_000));
+        assert_eq!(Some(169_140), rollup.bucket_of(169_150));
+        assert_eq!(None, rollup.bucket_of(169_200));
+    }
+
+    #[test]
+    fn test_rollup_weighted_avg() {
+        let schema = new_schema_with_fields(vec![
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("count", ConcreteDataType::uint64_datatype(), true),
+        ]);
+        let mut options = new_options("1m:1m,1h:1h", None).unwrap().unwrap();
+        options.count_column = Some("count".to_string());
+        options.validate(&schema, &[0]).unwrap();
+        let mut rollup = Rollup::new(schema, &[0], &options, 7200 * 1000).unwrap();
+
+        // A row rolled up from 3 raw rows by the finer tier and a raw row.
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["a", "a"])),
+            Arc::new(Float64Vector::from_slice([1.0, 5.0])),
+            Arc::new(UInt64Vector::from(vec![Some(3), None])),
+            Arc::new(TimestampSecondVector::from_slice([0, 60])),
+        ];
+        rollup.push_columns(&columns);
+        rollup.finish_group();
+        let writes = rollup.take_writes().unwrap();
+        assert_eq!(2, writes.num_rows);
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([2.0]));
+        assert_eq!(expect, writes.puts["cpu"]);
+        let expect: VectorRef = Arc::new(UInt64Vector::from_slice([4]));
+        assert_eq!(expect, writes.puts["count"]);
+        let expect: VectorRef = Arc::new(TimestampSecondVector::from_slice([60]));
+        assert_eq!(expect, writes.deletes.unwrap()["ts"]);
+    }
+
+    #[test]
+    fn test_rollup_overflow() {
+        let options = new_options("1h:1m", Some("requests:sum")).unwrap().unwrap();
+        let mut rollup = Rollup::new(new_schema(), &[0], &options, 86400 * 1000).unwrap();
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["a", "a"])),
+            Arc::new(Int64Vector::from_slice([i64::MAX, 1])),
+            Arc::new(TimestampSecondVector::from_slice([0, 10])),
+        ];
+        // Rows whose sum overflows are kept.
+        rollup.push_columns(&columns);
+        rollup.finish_group();
+        assert!(rollup.take_writes().is_none());
+    }
+
+    struct VecReader {
+        schema: SchemaRef,
+        chunks: VecDeque<Chunk>,
+    }
+
+    #[async_trait]
+    impl ChunkReader for VecReader {
+        type Error = Error;
+
+        fn schema(&self) -> &SchemaRef {
+            &self.schema
+        }
+
+        async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
+            Ok(self.chunks.pop_front())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rollup_reader() {
+        let options = new_options("1h:1m,1d:1h", Some("requests:sum"))
+            .unwrap()
+            .unwrap();
+        let schema = new_schema();
+        let hosts = ["a", "a", "a", "a", "b", "b", "b"];
+        let requests = [Some(1), None, Some(2), Some(3), Some(4), Some(5), Some(6)];
+        let timestamps = [10, 20, 100_000, 100_010, 0, 172_000, 172_100];
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(hosts.to_vec())),
+            Arc::new(Int64Vector::from(requests.to_vec())),
+            Arc::new(TimestampSecondVector::from_slice(timestamps)),
+        ];
+        // The last chunk only has recent rows.
+        let chunks = [(0, 3), (3, 2), (5, 2)]
+            .into_iter()
+            .map(|(offset, len)| Chunk::new(columns.iter().map(|c| c.slice(offset, len)).collect()))
+            .collect();
+        let reader = VecReader {
+            schema: schema.clone(),
+            chunks,
+        };
+        let mut reader =
+            RollupReader::new(reader, schema, &[0], &options, 2 * 86400 * 1000).unwrap();
+
+        let mut rows = Vec::new();
+        while let Some(chunk) = reader.next_chunk().await.unwrap() {
+            rows.extend((0..chunk.columns[0].len()).map(|row| row_values(&chunk.columns, row)));
+        }
+        let expect: Vec<_> = [
+            ("a", 1, 0),
+            ("a", 5, 99_960),
+            ("b", 4, 0),
+            ("b", 5, 172_000),
+            ("b", 6, 172_100),
+        ]
+        .into_iter()
+        .map(|(host, requests, ts)| {
+            vec![
+                Value::from(host),
+                Value::Int64(requests),
+                Value::Timestamp(common_time::Timestamp::new_second(ts)),
+            ]
+        })
+        .collect();
+        assert_eq!(expect, rows);
+    }
+}
@@ -13,18 +13,23 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_telemetry::logging;
+use common_time::clock::ClockRef;
 use datatypes::schema::SchemaRef;
-use object_store::ObjectStore;
+use futures::TryStreamExt;
+use object_store::{ObjectMode, ObjectStore};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::{
     ColumnDescriptorBuilder, ColumnFamilyDescriptor, ColumnFamilyDescriptorBuilder, ColumnId,
-    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, RegionDescriptorBuilder,
-    RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
+    CreateOptions, DedupStrategy, EngineContext as StorageEngineContext, OpenOptions, Region,
+    RegionDescriptorBuilder, RegionId, RegionStat, RowKeyDescriptor, RowKeyDescriptorBuilder,
+    StorageEngine,
 };
 use table::engine::{EngineContext, TableEngine, TableReference};
 use table::metadata::{TableId, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion};
@@ -33,19 +38,40 @@ use table::requests::{
 };
 use table::table::{AlterContext, TableRef};
 use table::{error as table_error, Result as TableResult, Table};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock as AsyncRwLock};
+use tokio::time::Instant;
 
-use crate::config::EngineConfig;
+use crate::config::{EngineConfig, DEFAULT_TIERING_INTERVAL};
+use crate::dedup_window::{
+    parse_dedup_window, parse_dedup_window_max_series, DEDUP_WINDOW_KEY,
+    DEDUP_WINDOW_MAX_SERIES_KEY,
+};
+use crate::downsample::DownsampleOptions;
 use crate::error::{
     self, BuildColumnDescriptorSnafu, BuildColumnFamilyDescriptorSnafu, BuildRegionDescriptorSnafu,
-    BuildRowKeyDescriptorSnafu, InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu, Result,
-    TableExistsSnafu,
+    BuildRowKeyDescriptorSnafu, InvalidAppendModeSnafu, InvalidColdAfterSnafu,
+    InvalidDedupStrategySnafu, InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu,
+    MoveTableDataSnafu, Result, StandbyEngineSnafu, TableExistsSnafu,
 };
+use crate::table::lazy_region::{LazyRegion, RegionLoader};
 use crate::table::MitoTable;
 
 pub const MITO_ENGINE: &str = "mito";
 pub const INIT_COLUMN_ID: ColumnId = 0;
+/// Table option to set how rows with the same primary key and timestamp are resolved,
+/// see [DedupStrategy] for available strategies.
+pub const DEDUP_STRATEGY_KEY: &str = "dedup_strategy";
+/// Table option to make the table append-only, rows with the same primary key and
+/// timestamp are all kept and deletes are rejected.
+pub const APPEND_MODE_KEY: &str = "append_mode";
+/// Table option to move SST files whose rows are all older than the duration, e.g. `7d`, to
+/// the cold tier of the storage.
+pub const COLD_AFTER_KEY: &str = "cold_after";
 const INIT_TABLE_VERSION: TableVersion = 0;
+/// Minimal interval to downsample a table.
+const MIN_DOWNSAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+/// Max interval to check whether a lazily opened table is idle.
+const MAX_IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Generate region name in the form of "{TABLE_ID}_{REGION_NUMBER}"
 #[inline]
@@ -53,13 +79,15 @@ fn region_name(table_id: TableId, n: u32) -> String {
     format!("{table_id}_{n:010}")
 }
 
+/// Returns the id of the region numbered `n` of the table, which is also the namespace id of
+/// the region in the WAL.
 #[inline]
-fn region_id(table_id: TableId, n: u32) -> RegionId {
+pub fn region_id(table_id: TableId, n: u32) -> RegionId {
     (u64::from(table_id) << 32) | u64::from(n)
 }
 
 #[inline]
-fn table_dir(schema_name: &str, table_id: TableId) -> String {
+pub fn table_dir(schema_name: &str, table_id: TableId) -> String {
     format!("{schema_name}/{table_id}/")
 }
 
@@ -78,6 +106,12 @@ impl<S: StorageEngine> MitoEngine<S> {
             inner: Arc::new(MitoEngineInner::new(config, storage_engine, object_store)),
         }
     }
+
+    /// Turns a standby engine and all tables opened by it writable, so the datanode could
+    /// take over writes once the datanode writing the tables has stopped.
+    pub async fn set_writable(&self) -> TableResult<()> {
+        self.inner.set_writable().await
+    }
 }
 
 #[async_trait]
@@ -150,13 +184,27 @@ impl<S: StorageEngine> TableEngine for MitoEngine<S> {
 struct MitoEngineInner<S: StorageEngine> {
     /// All tables opened by the engine. Map key is formatted [TableReference].
     ///
-    /// Writing to `tables` should also hold the `table_mutex`.
+    /// Writing to `tables` should also hold the lock of the table, see
+    /// [MitoEngineInner::lock_table].
     tables: RwLock<HashMap<String, TableRef>>,
     object_store: ObjectStore,
     storage_engine: S,
-    /// Table mutex is used to protect the operations such as creating/opening/closing
-    /// a table, to avoid things like opening the same table simultaneously.
-    table_mutex: Mutex<()>,
+    /// Locks to protect the operations such as creating/opening/closing a table, to avoid
+    /// things like opening the same table simultaneously. Map key is formatted
+    /// [TableReference], so opening a table doesn't block opening others.
+    table_locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Creating or opening tables holds the read lock, so tables are not opened read-only
+    /// while the engine is becoming writable.
+    standby_lock: AsyncRwLock<()>,
+    /// Whether the engine is a standby, see [EngineConfig::standby].
+    standby: AtomicBool,
+    clock: ClockRef,
+    /// See [EngineConfig::lazy_open].
+    lazy_open: bool,
+    /// See [EngineConfig::idle_close_after].
+    idle_close_after: Option<Duration>,
+    /// See [EngineConfig::tiering_interval].
+    tiering_interval: Duration,
 }
 
 fn build_row_key_desc(
@@ -284,9 +332,208 @@ fn validate_create_table_request(request: &CreateTableRequest) -> Result<()> {
         }
     );
 
+    if let Some(strategy) = request.table_options.get(DEDUP_STRATEGY_KEY) {
+        ensure!(
+            DedupStrategy::from_name(strategy).is_some(),
+            InvalidDedupStrategySnafu { strategy }
+        );
+    }
+
+    if let Some(append_mode) = request.table_options.get(APPEND_MODE_KEY) {
+        let append_mode =
+            append_mode
+                .parse::<bool>()
+                .ok()
+                .with_context(|| InvalidAppendModeSnafu {
+                    reason: format!("{append_mode} is not a boolean"),
+                })?;
+        ensure!(
+            !append_mode || !request.table_options.contains_key(DEDUP_STRATEGY_KEY),
+            InvalidAppendModeSnafu {
+                reason: "rows of append-only table are not deduplicated",
+            }
+        );
+    }
+
+    if let Some(cold_after) = request.table_options.get(COLD_AFTER_KEY) {
+        let _ = parse_cold_after(cold_after)?;
+    }
+
+    if let Some(window) = request.table_options.get(DEDUP_WINDOW_KEY) {
+        let _ = parse_dedup_window(window)?;
+    }
+    if let Some(max_series) = request.table_options.get(DEDUP_WINDOW_MAX_SERIES_KEY) {
+        let _ = parse_dedup_window_max_series(max_series)?;
+    }
+
+    if let Some(options) = DownsampleOptions::from_table_options(&request.table_options)? {
+        options.validate(&request.schema, &request.primary_key_indices)?;
+    }
+
     Ok(())
 }
 
+/// Parses the value of the [COLD_AFTER_KEY] table option.
+pub(crate) fn parse_cold_after(value: &str) -> Result<Duration> {
+    let cold_after = humantime::parse_duration(value).map_err(|e| {
+        InvalidColdAfterSnafu {
+            value,
+            reason: e.to_string(),
+        }
+        .build()
+    })?;
+    ensure!(
+        !cold_after.is_zero(),
+        InvalidColdAfterSnafu {
+            value,
+            reason: "duration must be positive",
+        }
+    );
+    Ok(cold_after)
+}
+
+/// Spawns a task to downsample the `table` periodically if it has downsample tiers, the task
+/// exits once the table is released.
+fn spawn_downsample_task<R: Region>(table: &Arc<MitoTable<R>>, clock: ClockRef) {
+    let options = DownsampleOptions::from_table_options(&table.table_info().meta.options);
+    let Ok(Some(options)) = options else {
+        return;
+    };
+    // No need to downsample more frequently than the finest resolution.
+    let period = options.finest_resolution().max(MIN_DOWNSAMPLE_INTERVAL);
+    let table = Arc::downgrade(table);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let Some(table) = table.upgrade() else {
+                return;
+            };
+            // Rows are rolled up by the datanode writing the table.
+            if table.is_standby() {
+                continue;
+            }
+            if let Err(e) = table.downsample(clock.now_millis()).await {
+                logging::error!(e; "Failed to downsample table {}", table.table_info().name);
+            }
+        }
+    });
+}
+
+/// Spawns a task to move cold files of the `table` to the cold tier periodically if the table
+/// sets [COLD_AFTER_KEY], the task exits once the table is released.
+fn spawn_tiering_task<R: Region>(
+    table: &Arc<MitoTable<R>>,
+    clock: ClockRef,
+    tiering_interval: Duration,
+) {
+    if table.cold_after().is_none() {
+        return;
+    }
+    let table = Arc::downgrade(table);
+
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval_at(Instant::now() + tiering_interval, tiering_interval);
+        loop {
+            interval.tick().await;
+            let Some(table) = table.upgrade() else {
+                return;
+            };
+            if let Err(e) = table.move_cold_files(clock.now_millis()).await {
+                logging::error!(
+                    e; "Failed to move cold files of table {}", table.table_info().name
+                );
+            }
+        }
+    });
+}
+
+/// Spawns a task to close the region of a lazily opened `table` once the table is not read or
+/// written for `idle_close_after`, the task exits once the table is released.
+fn spawn_idle_close_task<R: Region>(table: &Arc<MitoTable<R>>, idle_close_after: Duration) {
+    let period = idle_close_after.min(MAX_IDLE_CHECK_INTERVAL);
+    let table = Arc::downgrade(table);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let Some(table) = table.upgrade() else {
+                return;
+            };
+            if let Err(e) = table.close_if_idle(idle_close_after).await {
+                logging::error!(
+                    e; "Failed to close idle region of table {}", table.table_info().name
+                );
+            }
+        }
+    });
+}
+
+/// Spawns a task to load the statistics of the region of a lazily opened `table` from the
+/// storage, so the table reports its size before it's accessed.
+fn spawn_load_stat_task<R: Region>(table: &Arc<MitoTable<R>>) {
+    let table = Arc::downgrade(table);
+
+    tokio::spawn(async move {
+        let Some(table) = table.upgrade() else {
+            return;
+        };
+        if let Err(e) = table.load_region_stat().await {
+            logging::warn!(
+                "Failed to load region statistics of table {}, err: {:?}",
+                table.table_info().name,
+                e
+            );
+        }
+    });
+}
+
+/// Opens and closes regions of lazily opened tables by the storage engine.
+struct StorageRegionLoader<S: StorageEngine> {
+    storage_engine: S,
+    region_name: String,
+    opts: OpenOptions,
+}
+
+#[async_trait]
+impl<S: StorageEngine> RegionLoader<S::Region> for StorageRegionLoader<S> {
+    fn region_name(&self) -> &str {
+        &self.region_name
+    }
+
+    async fn open_region(&self) -> Result<S::Region> {
+        let region_name = &self.region_name;
+        self.storage_engine
+            .open_region(&StorageEngineContext::default(), region_name, &self.opts)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::OpenRegionSnafu { region_name })?
+            .context(error::RegionNotFoundSnafu { region_name })
+    }
+
+    async fn close_region(&self, region: S::Region) -> Result<()> {
+        self.storage_engine
+            .close_region(&StorageEngineContext::default(), region)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::CloseRegionSnafu {
+                region_name: &self.region_name,
+            })
+    }
+
+    async fn load_stat(&self) -> Result<Option<RegionStat>> {
+        let region_name = &self.region_name;
+        self.storage_engine
+            .region_stat(&StorageEngineContext::default(), region_name, &self.opts)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::LoadRegionStatSnafu { region_name })
+    }
+}
+
 impl<S: StorageEngine> MitoEngineInner<S> {
     async fn create_table(
         &self,
@@ -303,6 +550,13 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         };
 
         validate_create_table_request(&request)?;
+        ensure!(
+            !self.standby.load(Ordering::Acquire),
+            StandbyEngineSnafu {
+                operation: "create",
+                table_name,
+            }
+        );
 
         if let Some(table) = self.get_table(&table_ref) {
             if request.create_if_not_exists {
@@ -348,7 +602,8 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 region_name,
             })?;
 
-        let _lock = self.table_mutex.lock().await;
+        let _standby_lock = self.standby_lock.read().await;
+        let _lock = self.lock_table(&table_ref).await;
         // Checks again, read lock should be enough since we are guarded by the mutex.
         if let Some(table) = self.get_table(&table_ref) {
             if request.create_if_not_exists {
@@ -369,6 +624,16 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             .await
             .map_err(BoxedError::new)
             .context(error::CreateRegionSnafu)?;
+        let region = if self.lazy_open {
+            let opts = OpenOptions {
+                parent_dir: table_dir.clone(),
+                read_only: false,
+            };
+            let region_name = region.name().to_string();
+            self.lazy_region(Some(region), &region_name, opts)
+        } else {
+            LazyRegion::opened(region)
+        };
 
         let table_meta = TableMetaBuilder::default()
             .schema(request.schema)
@@ -376,6 +641,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             .next_column_id(next_column_id)
             .primary_key_indices(request.primary_key_indices.clone())
             .region_numbers(vec![region_number])
+            .options(request.table_options)
             .build()
             .context(error::BuildTableMetaSnafu { table_name })?;
 
@@ -396,11 +662,19 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 table_info,
                 region,
                 self.object_store.clone(),
+                self.clock.clone(),
             )
             .await?,
         );
 
         logging::info!("Mito engine created table: {:?}.", table.table_info());
+        spawn_downsample_task(&table, self.clock.clone());
+        spawn_tiering_task(&table, self.clock.clone(), self.tiering_interval);
+        if self.lazy_open {
+            if let Some(idle_close_after) = self.idle_close_after {
+                spawn_idle_close_task(&table, idle_close_after);
+            }
+        }
 
         self.tables
             .write()
@@ -431,7 +705,8 @@ impl<S: StorageEngine> MitoEngineInner<S> {
 
         // Acquires the mutex before opening a new table.
         let table = {
-            let _lock = self.table_mutex.lock().await;
+            let _standby_lock = self.standby_lock.read().await;
+            let _lock = self.lock_table(&table_ref).await;
             // Checks again, read lock should be enough since we are guarded by the mutex.
             if let Some(table) = self.get_table(&table_ref) {
                 return Ok(Some(table));
@@ -440,8 +715,10 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             let table_id = request.table_id;
             let engine_ctx = StorageEngineContext::default();
             let table_dir = table_dir(schema_name, table_id);
+            let standby = self.standby.load(Ordering::Acquire);
             let opts = OpenOptions {
                 parent_dir: table_dir.to_string(),
+                read_only: standby,
             };
 
             // TODO(dennis): supports multi regions;
@@ -449,28 +726,51 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             let region_number = request.region_numbers[0];
             let region_name = region_name(table_id, region_number);
 
-            let region = match self
-                .storage_engine
-                .open_region(&engine_ctx, &region_name, &opts)
-                .await
-                .map_err(BoxedError::new)
-                .context(table_error::TableOperationSnafu)?
-            {
-                None => return Ok(None),
-                Some(region) => region,
+            // Regions of a standby engine are refreshed from the writer, so they are always
+            // opened.
+            let lazy = self.lazy_open && !standby;
+            let region = if lazy {
+                self.lazy_region(None, &region_name, opts)
+            } else {
+                match self
+                    .storage_engine
+                    .open_region(&engine_ctx, &region_name, &opts)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(table_error::TableOperationSnafu)?
+                {
+                    None => return Ok(None),
+                    Some(region) => LazyRegion::opened(region),
+                }
             };
 
             let table = Arc::new(
-                MitoTable::open(table_name, &table_dir, region, self.object_store.clone())
-                    .await
-                    .map_err(BoxedError::new)
-                    .context(table_error::TableOperationSnafu)?,
+                MitoTable::open(
+                    table_name,
+                    &table_dir,
+                    region_number,
+                    region,
+                    self.object_store.clone(),
+                    standby,
+                    self.clock.clone(),
+                )
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?,
             );
 
             self.tables
                 .write()
                 .unwrap()
                 .insert(table_ref.to_string(), table.clone());
+            spawn_downsample_task(&table, self.clock.clone());
+            spawn_tiering_task(&table, self.clock.clone(), self.tiering_interval);
+            if lazy {
+                spawn_load_stat_task(&table);
+                if let Some(idle_close_after) = self.idle_close_after {
+                    spawn_idle_close_task(&table, idle_close_after);
+                }
+            }
             Some(table as _)
         };
 
@@ -479,6 +779,21 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(table)
     }
 
+    /// Returns a region opened by the storage engine on first access if `region` is `None`.
+    fn lazy_region(
+        &self,
+        region: Option<S::Region>,
+        region_name: &str,
+        opts: OpenOptions,
+    ) -> LazyRegion<S::Region> {
+        let loader = StorageRegionLoader {
+            storage_engine: self.storage_engine.clone(),
+            region_name: region_name.to_string(),
+            opts,
+        };
+        LazyRegion::with_loader(region, Arc::new(loader), self.clock.clone())
+    }
+
     fn get_table(&self, table_ref: &TableReference) -> Option<TableRef> {
         self.tables
             .read()
@@ -491,6 +806,17 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         let catalog_name = &req.catalog_name;
         let schema_name = &req.schema_name;
         let table_name = &req.table_name;
+        ensure!(
+            !self.standby.load(Ordering::Acquire),
+            StandbyEngineSnafu {
+                operation: "alter",
+                table_name,
+            }
+        );
+
+        if let AlterKind::RenameSchema { new_schema_name } = &req.alter_kind {
+            return self.move_table(&req, new_schema_name).await;
+        }
 
         if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
             let table_ref = TableReference {
@@ -531,6 +857,118 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(table)
     }
 
+    /// Moves the table to schema `new_schema_name`. The data of the table is moved to the
+    /// directory of the new schema, then the table is reopened from there.
+    async fn move_table(&self, req: &AlterTableRequest, new_schema_name: &str) -> Result<TableRef> {
+        let table_name = &req.table_name;
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: table_name,
+        };
+        let new_table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: new_schema_name,
+            table: table_name,
+        };
+        if self.get_table(&new_table_ref).is_some() {
+            return TableExistsSnafu {
+                table_name: new_table_ref.to_string(),
+            }
+            .fail();
+        }
+
+        let _lock = self.lock_table(&table_ref).await;
+        let table = self
+            .get_table(&table_ref)
+            .context(error::TableNotFoundSnafu { table_name })?;
+        let table_info = table.table_info();
+        let table_id = table_info.ident.table_id;
+
+        logging::info!("start altering table {} with request {:?}", table_name, req);
+        // Persists the new schema name to the manifest first, so the table is opened with
+        // it from the new directory.
+        table
+            .alter(AlterContext::new(), req)
+            .await
+            .context(error::AlterTableSnafu { table_name })?;
+
+        // Closes the region so nothing is written to the directory being moved.
+        let _ = self.tables.write().unwrap().remove(&table_ref.to_string());
+        let region = match table.as_any().downcast_ref::<MitoTable<S::Region>>() {
+            Some(table) => table.take_region().await,
+            None => None,
+        };
+        if let Some(region) = region {
+            let region_name = region.name().to_string();
+            self.storage_engine
+                .close_region(&StorageEngineContext::default(), region)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CloseRegionSnafu { region_name })?;
+        }
+
+        let from = table_dir(&req.schema_name, table_id);
+        let to = table_dir(new_schema_name, table_id);
+        self.copy_dir(table_name, &from, &to).await?;
+
+        let request = OpenTableRequest {
+            catalog_name: req.catalog_name.clone(),
+            schema_name: new_schema_name.to_string(),
+            table_name: table_name.clone(),
+            table_id,
+            region_numbers: table_info.meta.region_numbers.clone(),
+        };
+        let table = self
+            .open_table(&EngineContext::default(), request)
+            .await
+            .context(error::AlterTableSnafu { table_name })?
+            .context(error::TableNotFoundSnafu { table_name })?;
+
+        // Removes the old directory only after the table is opened from the new one.
+        self.object_store
+            .batch()
+            .remove_all(&from)
+            .await
+            .context(MoveTableDataSnafu {
+                table_name,
+                from: &from,
+                to: &to,
+            })?;
+        Ok(table)
+    }
+
+    /// Copies all files under directory `from` to directory `to`.
+    async fn copy_dir(&self, table_name: &str, from: &str, to: &str) -> Result<()> {
+        let context = || MoveTableDataSnafu {
+            table_name,
+            from,
+            to,
+        };
+        let mut entries = self
+            .object_store
+            .batch()
+            .walk_top_down(from)
+            .with_context(|_| context())?;
+        while let Some(entry) = entries.try_next().await.with_context(|_| context())? {
+            match entry.mode().await.with_context(|_| context())? {
+                ObjectMode::FILE => {
+                    let Some(relative) = entry.path().strip_prefix(from) else {
+                        continue;
+                    };
+                    let bytes = entry.read().await.with_context(|_| context())?;
+                    self.object_store
+                        .object(&format!("{to}{relative}"))
+                        .write(bytes)
+                        .await
+                        .with_context(|_| context())?;
+                }
+                ObjectMode::DIR | ObjectMode::Unknown => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Drop table. Returns whether a table is dropped (true) or not exist (false).
     async fn drop_table(&self, req: DropTableRequest) -> Result<bool> {
         let table_reference = TableReference {
@@ -538,32 +976,87 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             schema: &req.schema_name,
             table: &req.table_name,
         };
-        // todo(ruihang): reclaim persisted data
-        Ok(self
+        let removed = self
             .tables
             .write()
             .unwrap()
-            .remove(&table_reference.to_string())
-            .is_some())
+            .remove(&table_reference.to_string());
+        let Some(table) = removed else {
+            return Ok(false);
+        };
+
+        // Closes the region so the data of the table could be purged later, the data itself
+        // is purged by the recycle bin of the datanode.
+        let region = match table.as_any().downcast_ref::<MitoTable<S::Region>>() {
+            Some(table) => table.take_region().await,
+            None => None,
+        };
+        if let Some(region) = region {
+            let region_name = region.name().to_string();
+            self.storage_engine
+                .close_region(&StorageEngineContext::default(), region)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CloseRegionSnafu { region_name })?;
+        }
+        Ok(true)
     }
 }
 
 impl<S: StorageEngine> MitoEngineInner<S> {
-    fn new(_config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
+    fn new(config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
         Self {
             tables: RwLock::new(HashMap::default()),
             storage_engine,
             object_store,
-            table_mutex: Mutex::new(()),
+            table_locks: std::sync::Mutex::new(HashMap::new()),
+            standby_lock: AsyncRwLock::new(()),
+            standby: AtomicBool::new(config.standby),
+            clock: config.clock,
+            lazy_open: config.lazy_open,
+            idle_close_after: config.idle_close_after.filter(|idle| !idle.is_zero()),
+            tiering_interval: if config.tiering_interval.is_zero() {
+                DEFAULT_TIERING_INTERVAL
+            } else {
+                config.tiering_interval
+            },
+        }
+    }
+
+    /// Locks the table to create or open it. The lock of a table is kept once created, as
+    /// there is only one per table name.
+    async fn lock_table(&self, table_ref: &TableReference<'_>) -> OwnedMutexGuard<()> {
+        let lock = self
+            .table_locks
+            .lock()
+            .unwrap()
+            .entry(table_ref.to_string())
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+
+    async fn set_writable(&self) -> TableResult<()> {
+        // Holds the lock so tables are not opened read-only meanwhile.
+        let _lock = self.standby_lock.write().await;
+        let tables: Vec<_> = self.tables.read().unwrap().values().cloned().collect();
+        for table in tables {
+            table.set_writable().await?;
         }
+        self.standby.store(false, Ordering::Release);
+
+        logging::info!("Mito engine is writable");
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_error::prelude::{ErrorExt, StatusCode};
     use common_query::physical_plan::SessionContext;
     use common_recordbatch::util;
+    use common_time::clock::MockClock;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
     use datatypes::value::Value;
@@ -572,6 +1065,7 @@ mod tests {
     };
     use log_store::NoopLogStore;
     use storage::config::EngineConfig as StorageEngineConfig;
+    use storage::region::RegionImpl;
     use storage::EngineImpl;
     use store_api::manifest::Manifest;
     use store_api::storage::ReadContext;
@@ -579,6 +1073,9 @@ mod tests {
     use tempdir::TempDir;
 
     use super::*;
+    use crate::downsample::{
+        DOWNSAMPLE_AGGREGATES_KEY, DOWNSAMPLE_COUNT_KEY, DOWNSAMPLE_TIERS_KEY,
+    };
     use crate::table::test_util;
     use crate::table::test_util::{new_insert_request, schema_for_test, MockRegion, TABLE_NAME};
 
@@ -753,6 +1250,73 @@ mod tests {
 
         request.primary_key_indices = vec![0];
         assert!(validate_create_table_request(&request).is_ok());
+
+        request
+            .table_options
+            .insert(DEDUP_STRATEGY_KEY.to_string(), "avg".to_string());
+        let err = validate_create_table_request(&request).unwrap_err();
+        assert!(err.to_string().contains("Invalid dedup strategy: avg"));
+
+        request
+            .table_options
+            .insert(DEDUP_STRATEGY_KEY.to_string(), "sum".to_string());
+        assert!(validate_create_table_request(&request).is_ok());
+
+        request
+            .table_options
+            .insert(APPEND_MODE_KEY.to_string(), "true".to_string());
+        let err = validate_create_table_request(&request).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("rows of append-only table are not deduplicated"));
+
+        request.table_options.remove(DEDUP_STRATEGY_KEY);
+        assert!(validate_create_table_request(&request).is_ok());
+
+        request
+            .table_options
+            .insert(APPEND_MODE_KEY.to_string(), "yes".to_string());
+        let err = validate_create_table_request(&request).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid append mode: yes is not a boolean"));
+    }
+
+    #[tokio::test]
+    async fn test_insert_unsynced_and_fence() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let insert_req = new_insert_request("demo".to_string(), HashMap::default());
+        assert_eq!((0, 0), table.insert_unsynced(insert_req).await.unwrap());
+
+        let new_columns_values = |ts: i64| {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1"]));
+            let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5]));
+            let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64]));
+            let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![ts]));
+            columns_values.insert("host".to_string(), hosts);
+            columns_values.insert("cpu".to_string(), cpus);
+            columns_values.insert("memory".to_string(), memories);
+            columns_values.insert("ts".to_string(), tss);
+            columns_values
+        };
+
+        let insert_req = new_insert_request("demo".to_string(), new_columns_values(1));
+        let (rows, first_sequence) = table.insert_unsynced(insert_req).await.unwrap();
+        assert_eq!(1, rows);
+        let insert_req = new_insert_request("demo".to_string(), new_columns_values(2));
+        let (rows, sequence) = table.insert_unsynced(insert_req).await.unwrap();
+        assert_eq!(1, rows);
+        assert!(sequence > first_sequence);
+
+        table.fence(sequence).await.unwrap();
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
     }
 
     #[tokio::test]
@@ -970,60 +1534,235 @@ mod tests {
         assert_eq!(reopened.manifest().last_version(), 1);
     }
 
-    #[test]
-    fn test_region_id() {
-        assert_eq!(1, region_id(0, 1));
-        assert_eq!(4294967296, region_id(1, 0));
-        assert_eq!(4294967297, region_id(1, 1));
-        assert_eq!(4294967396, region_id(1, 100));
-        assert_eq!(8589934602, region_id(2, 10));
-        assert_eq!(18446744069414584330, region_id(u32::MAX, 10));
-    }
+    #[tokio::test]
+    async fn test_open_tables_concurrently() {
+        let ctx = EngineContext::default();
+        let (table_engine, table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+        let mut request = test_util::new_create_request(schema);
+        request.id = 2;
+        request.table_name = "other".to_string();
+        table_engine.create_table(&ctx, request).await.unwrap();
 
-    fn new_add_columns_req(new_tag: &ColumnSchema, new_field: &ColumnSchema) -> AlterTableRequest {
-        AlterTableRequest {
+        let new_open_request = |table_name: &str, table_id| OpenTableRequest {
             catalog_name: DEFAULT_CATALOG_NAME.to_string(),
             schema_name: DEFAULT_SCHEMA_NAME.to_string(),
-            table_name: TABLE_NAME.to_string(),
-            alter_kind: AlterKind::AddColumns {
-                columns: vec![
-                    AddColumnRequest {
-                        column_schema: new_tag.clone(),
-                        is_key: true,
-                    },
-                    AddColumnRequest {
-                        column_schema: new_field.clone(),
-                        is_key: false,
-                    },
-                ],
-            },
-        }
-    }
-
-    #[tokio::test]
-    async fn test_alter_table_add_column() {
-        let (_engine, table_engine, table, _object_store, _dir) =
-            test_util::setup_mock_engine_and_table().await;
-
-        let old_info = table.table_info();
-        let old_meta = &old_info.meta;
-        let old_schema = &old_meta.schema;
+            table_name: table_name.to_string(),
+            table_id,
+            region_numbers: vec![0],
+        };
+        let object_store = table_engine.inner.object_store.clone();
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
 
-        let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
-        let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
-        let req = new_add_columns_req(&new_tag, &new_field);
-        let table = table_engine
-            .alter_table(&EngineContext::default(), req)
+        // Opening a table doesn't wait for other tables being opened.
+        let table_ref = TableReference::full(
+            DEFAULT_CATALOG_NAME,
+            DEFAULT_SCHEMA_NAME,
+            test_util::TABLE_NAME,
+        );
+        let lock = table_engine.inner.lock_table(&table_ref).await;
+        let opening = table_engine.open_table(&ctx, new_open_request(test_util::TABLE_NAME, 1));
+        tokio::pin!(opening);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), &mut opening)
+                .await
+                .is_err()
+        );
+        let other = table_engine
+            .open_table(&ctx, new_open_request("other", 2))
             .await
+            .unwrap()
             .unwrap();
+        assert_eq!("other", other.table_info().name);
+        drop(lock);
+        let reopened = opening.await.unwrap().unwrap();
+        assert_eq!(table.table_info(), reopened.table_info());
 
-        let new_info = table.table_info();
-        let new_meta = &new_info.meta;
-        let new_schema = &new_meta.schema;
+        // The same table is only opened once.
+        let (first, second) = tokio::join!(
+            table_engine.open_table(&ctx, new_open_request("other", 2)),
+            table_engine.open_table(&ctx, new_open_request("other", 2)),
+        );
+        assert!(Arc::ptr_eq(&other, &first.unwrap().unwrap()));
+        assert!(Arc::ptr_eq(&other, &second.unwrap().unwrap()));
+    }
 
-        assert_eq!(&[0, 4], &new_meta.primary_key_indices[..]);
-        assert_eq!(&[1, 2, 3, 5], &new_meta.value_indices[..]);
-        assert_eq!(new_schema.num_columns(), old_schema.num_columns() + 2);
+    #[tokio::test]
+    async fn test_lazy_open_table() {
+        common_telemetry::init_default_ut_logging();
+
+        let ctx = EngineContext::default();
+        let (table_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+        let object_store = table_engine.inner.object_store.clone();
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: test_util::TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+
+        // Opens the table by a new engine lazily, as after restarting the datanode.
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+        );
+        let clock = Arc::new(MockClock::new(0));
+        let table_engine = MitoEngine::new(
+            EngineConfig {
+                lazy_open: true,
+                clock: clock.clone(),
+                ..Default::default()
+            },
+            storage_engine.clone(),
+            object_store,
+        );
+        let reopened = table_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(table.table_info(), reopened.table_info());
+
+        let storage_ctx = StorageEngineContext::default();
+        let region_name = region_name(1, 0);
+        let region_opened = || {
+            storage_engine
+                .get_region(&storage_ctx, &region_name)
+                .unwrap()
+                .is_some()
+        };
+        // The region is not opened until the table is accessed, but its statistics are
+        // loaded from the manifest.
+        assert!(!region_opened());
+        let mito_table = reopened
+            .as_any()
+            .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+            .unwrap();
+        mito_table.load_region_stat().await.unwrap();
+        assert_eq!(0, reopened.statistics().unwrap().num_rows);
+        assert!(!region_opened());
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1", "host2"])),
+        );
+        columns_values.insert(
+            "memory".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64])),
+        );
+        columns_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2])),
+        );
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(2, reopened.insert(insert_req).await.unwrap());
+        assert!(region_opened());
+        assert_eq!(2, reopened.statistics().unwrap().num_rows);
+
+        let reopened = reopened
+            .as_any()
+            .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+            .unwrap();
+        let idle = Duration::from_secs(60);
+        assert!(!reopened.close_if_idle(idle).await.unwrap());
+        clock.advance(idle);
+        assert!(reopened.close_if_idle(idle).await.unwrap());
+        assert!(!region_opened());
+        assert!(!reopened.close_if_idle(idle).await.unwrap());
+        // Statistics of the closed region are kept.
+        assert_eq!(2, reopened.statistics().unwrap().num_rows);
+        // The memtable is flushed before closing the region.
+        let stat = storage_engine
+            .region_stat(
+                &storage_ctx,
+                &region_name,
+                &OpenOptions {
+                    parent_dir: table_dir(DEFAULT_SCHEMA_NAME, 1),
+                    read_only: false,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(2, stat.approximate_rows);
+        assert_eq!(1, stat.sst_files);
+
+        // The region is opened again on next access, the rows are read from the SST as the
+        // WAL is not persisted by the noop log store.
+        let session_ctx = SessionContext::new();
+        let stream = reopened.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+        assert!(region_opened());
+    }
+
+    #[test]
+    fn test_region_id() {
+        assert_eq!(1, region_id(0, 1));
+        assert_eq!(4294967296, region_id(1, 0));
+        assert_eq!(4294967297, region_id(1, 1));
+        assert_eq!(4294967396, region_id(1, 100));
+        assert_eq!(8589934602, region_id(2, 10));
+        assert_eq!(18446744069414584330, region_id(u32::MAX, 10));
+    }
+
+    fn new_add_columns_req(new_tag: &ColumnSchema, new_field: &ColumnSchema) -> AlterTableRequest {
+        AlterTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::AddColumns {
+                columns: vec![
+                    AddColumnRequest {
+                        column_schema: new_tag.clone(),
+                        is_key: true,
+                        add_if_not_exists: false,
+                    },
+                    AddColumnRequest {
+                        column_schema: new_field.clone(),
+                        is_key: false,
+                        add_if_not_exists: false,
+                    },
+                ],
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alter_table_add_column() {
+        let (_engine, table_engine, table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+
+        let old_info = table.table_info();
+        let old_meta = &old_info.meta;
+        let old_schema = &old_meta.schema;
+
+        let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
+        let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
+        let req = new_add_columns_req(&new_tag, &new_field);
+        let table = table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .unwrap();
+
+        let new_info = table.table_info();
+        let new_meta = &new_info.meta;
+        let new_schema = &new_meta.schema;
+
+        assert_eq!(&[0, 4], &new_meta.primary_key_indices[..]);
+        assert_eq!(&[1, 2, 3, 5], &new_meta.value_indices[..]);
+        assert_eq!(new_schema.num_columns(), old_schema.num_columns() + 2);
         assert_eq!(
             &new_schema.column_schemas()[..old_schema.num_columns()],
             old_schema.column_schemas()
@@ -1041,6 +1780,44 @@ mod tests {
         assert_eq!(new_meta.next_column_id, old_meta.next_column_id + 2);
     }
 
+    #[tokio::test]
+    async fn test_alter_table_add_column_if_not_exists() {
+        let (_engine, table_engine, table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let old_version = table.table_info().ident.version;
+
+        let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
+        let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
+        let new_req = || {
+            let mut req = new_add_columns_req(&new_tag, &new_field);
+            if let AlterKind::AddColumns { columns } = &mut req.alter_kind {
+                columns.iter_mut().for_each(|c| c.add_if_not_exists = true);
+            }
+            req
+        };
+        table_engine
+            .alter_table(&EngineContext::default(), new_req())
+            .await
+            .unwrap();
+        let info = table.table_info();
+
+        // Applying the same alteration again is a no-op.
+        let table = table_engine
+            .alter_table(&EngineContext::default(), new_req())
+            .await
+            .unwrap();
+        let new_info = table.table_info();
+        assert_eq!(old_version + 1, new_info.ident.version);
+        assert_eq!(info.meta.schema, new_info.meta.schema);
+
+        // Fails without `add_if_not_exists`.
+        let req = new_add_columns_req(&new_tag, &new_field);
+        assert!(table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_alter_table_remove_column() {
         let (_engine, table_engine, _table, _object_store, _dir) =
@@ -1167,6 +1944,49 @@ mod tests {
         assert_eq!(reopened.manifest().last_version(), 2);
     }
 
+    #[tokio::test]
+    async fn test_alter_rename_schema() {
+        let (_engine, table_engine, table, object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let ctx = EngineContext::default();
+        let table_id = table.table_info().ident.table_id;
+
+        let new_schema_name = "another_schema";
+        let req = AlterTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::RenameSchema {
+                new_schema_name: new_schema_name.to_string(),
+            },
+        };
+        let moved = table_engine.alter_table(&ctx, req).await.unwrap();
+        assert_eq!(moved.table_info().schema_name, new_schema_name);
+        assert_eq!(moved.table_info().ident.table_id, table_id);
+
+        let old_ref = TableReference {
+            catalog: DEFAULT_CATALOG_NAME,
+            schema: DEFAULT_SCHEMA_NAME,
+            table: TABLE_NAME,
+        };
+        let new_ref = TableReference {
+            catalog: DEFAULT_CATALOG_NAME,
+            schema: new_schema_name,
+            table: TABLE_NAME,
+        };
+        assert!(!table_engine.table_exists(&ctx, &old_ref));
+        assert!(table_engine.table_exists(&ctx, &new_ref));
+
+        // The data of the table is moved to the directory of the new schema.
+        let old_dir = table_dir(DEFAULT_SCHEMA_NAME, table_id);
+        assert!(!object_store.object(&old_dir).is_exist().await.unwrap());
+        let moved = moved
+            .as_any()
+            .downcast_ref::<MitoTable<MockRegion>>()
+            .unwrap();
+        assert_eq!(moved.manifest().last_version(), 2);
+    }
+
     #[tokio::test]
     async fn test_drop_table() {
         common_telemetry::init_default_ut_logging();
@@ -1259,6 +2079,11 @@ mod tests {
         let del_req = DeleteRequest { key_column_values };
         table.delete(del_req).await.unwrap();
 
+        let region_stats = table.region_stats();
+        assert_eq!(1, region_stats.len());
+        assert_eq!(6, region_stats[0].written_rows);
+        assert!(region_stats[0].memtable_bytes > 0);
+
         let session_ctx = SessionContext::new();
         let stream = table.scan(None, &[], None).await.unwrap();
         let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
@@ -1275,4 +2100,646 @@ mod tests {
 +-------+-----+--------+-------------------------+"
         );
     }
+
+    #[tokio::test]
+    async fn test_scan_statistics() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef =
+            Arc::new(StringVector::from(vec!["host1", "host2", "host3", "host4"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2, 3, 4]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(4, table.insert(insert_req).await.unwrap());
+
+        let statistics = table.statistics().unwrap();
+        assert_eq!(4, statistics.num_rows);
+        assert!(statistics.num_bytes > 0);
+
+        let scan = table.scan(None, &[], None).await.unwrap();
+        let scan_statistics = scan.statistics();
+        assert_eq!(Some(4), scan_statistics.num_rows);
+        assert_eq!(
+            Some(statistics.num_bytes as usize),
+            scan_statistics.total_byte_size
+        );
+        assert!(!scan_statistics.is_exact);
+
+        let scan = table.scan(None, &[], Some(2)).await.unwrap();
+        let scan_statistics = scan.statistics();
+        assert_eq!(Some(2), scan_statistics.num_rows);
+        assert_eq!(
+            Some(statistics.num_bytes as usize / 2),
+            scan_statistics.total_byte_size
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alter_table_set_read_only() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let new_columns_values = || {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert(
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1"])),
+            );
+            columns_values.insert(
+                "cpu".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0])),
+            );
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1])),
+            );
+            columns_values
+        };
+        let insert_req = new_insert_request("demo".to_string(), new_columns_values());
+        assert_eq!(1, table.insert(insert_req).await.unwrap());
+
+        let new_set_read_only_req = |read_only| AlterTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::SetReadOnly { read_only },
+        };
+        table
+            .alter(AlterContext::new(), &new_set_read_only_req(true))
+            .await
+            .unwrap();
+        assert!(table.table_info().meta.read_only);
+
+        // Writes are rejected.
+        let insert_req = new_insert_request("demo".to_string(), new_columns_values());
+        let err = table.insert(insert_req).await.unwrap_err();
+        assert_eq!(StatusCode::TableReadOnly, err.status_code());
+        let mut key_column_values: HashMap<String, VectorRef> = HashMap::with_capacity(2);
+        key_column_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])),
+        );
+        key_column_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1])),
+        );
+        let err = table
+            .delete(DeleteRequest { key_column_values })
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::TableReadOnly, err.status_code());
+
+        // Reads are still served.
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches[0].num_rows());
+
+        table
+            .alter(AlterContext::new(), &new_set_read_only_req(false))
+            .await
+            .unwrap();
+        assert!(!table.table_info().meta.read_only);
+        let insert_req = new_insert_request("demo".to_string(), new_columns_values());
+        assert_eq!(1, table.insert(insert_req).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_row_version() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+        assert!(table.supports_row_version());
+
+        for (hosts, cpus) in [
+            (vec!["host1", "host2"], vec![1.0, 2.0]),
+            (vec!["host1"], vec![3.0]),
+        ] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            let num_rows = hosts.len();
+            columns_values.insert("host".to_string(), Arc::new(StringVector::from(hosts)));
+            columns_values.insert("cpu".to_string(), Arc::new(Float64Vector::from_vec(cpus)));
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0; num_rows])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1; num_rows])),
+            );
+            let insert_req = new_insert_request("demo".to_string(), columns_values);
+            assert_eq!(num_rows, table.insert(insert_req).await.unwrap());
+        }
+
+        // __version, host, cpu
+        let session_ctx = SessionContext::new();
+        let stream = table
+            .scan_with_row_version(Some(&vec![4, 0, 1]), &[], None)
+            .await
+            .unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(
+            batches.pretty_print().unwrap(),
+            "\
++-----------+-------+-----+
+| __version | host  | cpu |
++-----------+-------+-----+
+| 2         | host1 | 3   |
+| 1         | host2 | 2   |
++-----------+-------+-----+"
+        );
+
+        // Only the version.
+        let stream = table
+            .scan_with_row_version(Some(&vec![4]), &[], None)
+            .await
+            .unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(1, batches.schema().num_columns());
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_dedup_strategy() {
+        let (engine, _table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let request = CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "counters".to_string(),
+            desc: None,
+            schema,
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::from([(DEDUP_STRATEGY_KEY.to_string(), "sum".to_string())]),
+            region_numbers: vec![0],
+        };
+        let table = engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("sum"),
+            table
+                .table_info()
+                .meta
+                .options
+                .get(DEDUP_STRATEGY_KEY)
+                .map(|s| s.as_str())
+        );
+
+        for (hosts, cpus) in [
+            (vec!["host1", "host2"], vec![Some(1.0), Some(2.0)]),
+            (vec!["host1", "host2"], vec![Some(3.0), None]),
+        ] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            let num_rows = hosts.len();
+            columns_values.insert("host".to_string(), Arc::new(StringVector::from(hosts)));
+            columns_values.insert("cpu".to_string(), Arc::new(Float64Vector::from(cpus)));
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0; num_rows])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1; num_rows])),
+            );
+            let insert_req = new_insert_request("counters".to_string(), columns_values);
+            assert_eq!(num_rows, table.insert(insert_req).await.unwrap());
+        }
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        assert_eq!(
+            batches.pretty_print().unwrap(),
+            "\
++-------+-----+--------+-------------------------+
+| host  | cpu | memory | ts                      |
++-------+-----+--------+-------------------------+
+| host1 | 4   | 2      | 1970-01-01T00:00:00.001 |
+| host2 | 2   | 2      | 1970-01-01T00:00:00.001 |
++-------+-----+--------+-------------------------+"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_mode() {
+        let (engine, _table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let request = CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "events".to_string(),
+            desc: None,
+            schema,
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::from([(APPEND_MODE_KEY.to_string(), "true".to_string())]),
+            region_numbers: vec![0],
+        };
+        let table = engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+
+        for cpus in [vec![1.0, 2.0], vec![3.0, 4.0]] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert(
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1", "host1"])),
+            );
+            columns_values.insert("cpu".to_string(), Arc::new(Float64Vector::from_vec(cpus)));
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0; 2])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1; 2])),
+            );
+            let insert_req = new_insert_request("events".to_string(), columns_values);
+            assert_eq!(2, table.insert(insert_req).await.unwrap());
+        }
+
+        // All rows with the same key are kept.
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        let mut cpus = batches
+            .iter()
+            .flat_map(|batch| {
+                let cpu = batch.column_by_name("cpu").unwrap();
+                (0..cpu.len()).map(|i| cpu.get(i)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        cpus.sort();
+        assert_eq!(
+            vec![
+                Value::from(1.0),
+                Value::from(2.0),
+                Value::from(3.0),
+                Value::from(4.0)
+            ],
+            cpus
+        );
+
+        let mut key_column_values: HashMap<String, VectorRef> = HashMap::with_capacity(2);
+        key_column_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])),
+        );
+        key_column_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1])),
+        );
+        let err = table
+            .delete(DeleteRequest { key_column_values })
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+    }
+
+    #[tokio::test]
+    async fn test_downsample() {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("count", ConcreteDataType::uint64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ];
+        let schema = Arc::new(
+            SchemaBuilder::try_from(column_schemas)
+                .unwrap()
+                .build()
+                .unwrap(),
+        );
+
+        let (_dir, object_store) = test_util::new_test_object_store("test_downsample").await;
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig::default(),
+            Arc::new(NoopLogStore::default()),
+            object_store.clone(),
+        );
+        let now = 2 * 24 * 3600 * 1000;
+        let engine = MitoEngine::new(
+            EngineConfig {
+                clock: Arc::new(MockClock::new(now)),
+                ..Default::default()
+            },
+            storage_engine,
+            object_store,
+        );
+
+        let mut request = CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "metrics".to_string(),
+            desc: None,
+            schema,
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::from([
+                (DOWNSAMPLE_TIERS_KEY.to_string(), "1h:1m,1d:1h".to_string()),
+                (
+                    DOWNSAMPLE_AGGREGATES_KEY.to_string(),
+                    "host:sum".to_string(),
+                ),
+                (DOWNSAMPLE_COUNT_KEY.to_string(), "count".to_string()),
+            ]),
+            region_numbers: vec![0],
+        };
+        let err = engine
+            .create_table(&EngineContext::default(), request.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        request.table_options.insert(
+            DOWNSAMPLE_AGGREGATES_KEY.to_string(),
+            "memory:sum".to_string(),
+        );
+        let table = engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+
+        let insert = |cpu: Vec<f64>, memory: Vec<f64>, ts: Vec<i64>| {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert(
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1"; ts.len()])),
+            );
+            columns_values.insert("cpu".to_string(), Arc::new(Float64Vector::from_vec(cpu)));
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(memory)),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(ts)),
+            );
+            new_insert_request("metrics".to_string(), columns_values)
+        };
+        let scan_rows = |table: TableRef| async move {
+            let session_ctx = SessionContext::new();
+            let stream = table.scan(None, &[], None).await.unwrap();
+            let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+            let batches = util::collect_batches(stream).await.unwrap();
+            batches
+                .iter()
+                .flat_map(|batch| batch.rows())
+                .collect::<Vec<_>>()
+        };
+        let expect_rows = |rows: &[(f64, f64, Option<u64>, i64)]| {
+            rows.iter()
+                .map(|(cpu, memory, count, ts)| {
+                    vec![
+                        Value::from("host1"),
+                        Value::from(*cpu),
+                        Value::from(*memory),
+                        count.map(Value::from).unwrap_or(Value::Null),
+                        Value::Timestamp(common_time::Timestamp::new_millisecond(*ts)),
+                    ]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Rows in the first hour, rows in the same minute of the second day and a recent row.
+        let request = insert(
+            vec![1.0, 3.0, 2.0, 4.0, 5.0],
+            vec![1.0, 2.0, 3.0, 4.0, 5.0],
+            vec![1_000, 2_000, 100_000_000, 100_010_000, 172_000_000],
+        );
+        assert_eq!(5, table.insert(request).await.unwrap());
+
+        let expect = expect_rows(&[
+            (2.0, 3.0, Some(2), 0),
+            (3.0, 7.0, Some(2), 99_960_000),
+            (5.0, 5.0, None, 172_000_000),
+        ]);
+        // Queries see the resolution of the tiers before the rows are rolled up.
+        assert_eq!(expect, scan_rows(table.clone()).await);
+
+        let mito_table = table
+            .as_any()
+            .downcast_ref::<MitoTable<<EngineImpl<NoopLogStore> as StorageEngine>::Region>>()
+            .unwrap();
+        assert_eq!(4, mito_table.downsample(now).await.unwrap());
+        // Rows already rolled up are not rolled up again.
+        assert_eq!(0, mito_table.downsample(now).await.unwrap());
+        assert_eq!(expect, scan_rows(table.clone()).await);
+
+        // A row written late to a rolled up bucket is weighted by the count of the bucket.
+        let request = insert(vec![5.0], vec![1.0], vec![3_000]);
+        assert_eq!(1, table.insert(request).await.unwrap());
+        assert_eq!(0, mito_table.downsample(now).await.unwrap());
+        let expect = expect_rows(&[
+            (3.0, 4.0, Some(3), 0),
+            (3.0, 7.0, Some(2), 99_960_000),
+            (5.0, 5.0, None, 172_000_000),
+        ]);
+        assert_eq!(expect, scan_rows(table.clone()).await);
+    }
+
+    #[tokio::test]
+    async fn test_cold_after() {
+        let (engine, _table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut request = CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "metrics".to_string(),
+            desc: None,
+            schema,
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::from([(COLD_AFTER_KEY.to_string(), "0s".to_string())]),
+            region_numbers: vec![0],
+        };
+        let err = engine
+            .create_table(&EngineContext::default(), request.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        request
+            .table_options
+            .insert(COLD_AFTER_KEY.to_string(), "7d".to_string());
+        let table = engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+        let mito_table = table
+            .as_any()
+            .downcast_ref::<MitoTable<<EngineImpl<NoopLogStore> as StorageEngine>::Region>>()
+            .unwrap();
+        assert_eq!(
+            Some(Duration::from_secs(7 * 24 * 3600)),
+            mito_table.cold_after()
+        );
+        // The storage has no cold tier.
+        assert_eq!(0, mito_table.move_cold_files(i64::MAX).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dedup_window() {
+        let (engine, _table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut request = CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "sensors".to_string(),
+            desc: None,
+            schema,
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::from([(DEDUP_WINDOW_KEY.to_string(), "0s".to_string())]),
+            region_numbers: vec![0],
+        };
+        let err = engine
+            .create_table(&EngineContext::default(), request.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        request
+            .table_options
+            .insert(DEDUP_WINDOW_KEY.to_string(), "1m".to_string());
+        let table = engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+
+        for (cpus, timestamps) in [
+            (vec![1.0, 1.0, 2.0], vec![1_000, 2_000, 3_000]),
+            (vec![2.0, 2.0], vec![4_000, 70_000]),
+        ] {
+            let rows = cpus.len();
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert(
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1"; rows])),
+            );
+            columns_values.insert("cpu".to_string(), Arc::new(Float64Vector::from_vec(cpus)));
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0; rows])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(timestamps)),
+            );
+            let insert_req = new_insert_request("sensors".to_string(), columns_values);
+            assert_eq!(rows, table.insert(insert_req).await.unwrap());
+        }
+
+        // Points identical to the last point of the series in the window are dropped.
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        let mut timestamps = batches
+            .iter()
+            .flat_map(|batch| {
+                let ts = batch.column_by_name("ts").unwrap();
+                (0..ts.len()).map(|i| ts.get(i)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        timestamps.sort();
+        let expect: Vec<_> = [1_000, 3_000, 70_000]
+            .into_iter()
+            .map(|ts| Value::Timestamp(common_time::Timestamp::new_millisecond(ts)))
+            .collect();
+        assert_eq!(expect, timestamps);
+    }
+
+    #[tokio::test]
+    async fn test_standby_engine() {
+        let ctx = EngineContext::default();
+        let (engine, table_engine, table, object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+
+        let standby_engine = MitoEngine::new(
+            EngineConfig {
+                standby: true,
+                ..Default::default()
+            },
+            engine,
+            object_store,
+        );
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+        let standby = standby_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        let standby_table = standby
+            .as_any()
+            .downcast_ref::<MitoTable<MockRegion>>()
+            .unwrap();
+        assert!(standby_table.is_standby());
+
+        let schema = Arc::new(schema_for_test());
+        let mut create_req = test_util::new_create_request(schema);
+        create_req.id = 2;
+        create_req.table_name = "standby_table".to_string();
+        let err = standby_engine
+            .create_table(&ctx, create_req.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+        let new_tag = ColumnSchema::new("my_tag", ConcreteDataType::string_datatype(), true);
+        let new_field = ColumnSchema::new("my_field", ConcreteDataType::string_datatype(), true);
+        let alter_req = new_add_columns_req(&new_tag, &new_field);
+        let err = standby_engine
+            .alter_table(&ctx, alter_req)
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::Unsupported, err.status_code());
+
+        // Catch up with the alteration by the writer.
+        let alter_req = new_add_columns_req(&new_tag, &new_field);
+        let table = table_engine.alter_table(&ctx, alter_req).await.unwrap();
+        assert_ne!(table.schema(), standby.schema());
+        standby.refresh().await.unwrap();
+        assert_eq!(table.schema(), standby.schema());
+        assert_eq!(
+            table.table_info().ident.version,
+            standby.table_info().ident.version
+        );
+
+        standby_engine.set_writable().await.unwrap();
+        assert!(!standby_table.is_standby());
+        standby_engine.create_table(&ctx, create_req).await.unwrap();
+    }
 }
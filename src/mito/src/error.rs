@@ -52,6 +52,45 @@ pub enum Error {
     #[snafu(display("Invalid primary key: {}", msg))]
     InvalidPrimaryKey { msg: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid dedup strategy: {}", strategy))]
+    InvalidDedupStrategy {
+        strategy: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid append mode: {}", reason))]
+    InvalidAppendMode {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid cold_after option {}: {}", value, reason))]
+    InvalidColdAfter {
+        value: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid dedup_window option {}: {}", value, reason))]
+    InvalidDedupWindow {
+        value: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Cannot {} table {} on a standby engine", operation, table_name))]
+    StandbyEngine {
+        operation: String,
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid downsample options: {}", reason))]
+    InvalidDownsampleOptions {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Missing timestamp index for table: {}", table_name))]
     MissingTimestampIndex {
         table_name: String,
@@ -128,6 +167,44 @@ pub enum Error {
         table_name: String,
     },
 
+    #[snafu(display("Failed to open region {}, source: {}", region_name, source))]
+    OpenRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to close region {}, source: {}", region_name, source))]
+    CloseRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to flush region {}, source: {}", region_name, source))]
+    FlushRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display(
+        "Failed to load statistics of region {}, source: {}",
+        region_name,
+        source
+    ))]
+    LoadRegionStat {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Region not found: {}", region_name))]
+    RegionNotFound {
+        backtrace: Backtrace,
+        region_name: String,
+    },
+
     #[snafu(display("Table info not found in manifest, table: {}", table_name))]
     TableInfoNotFound {
         backtrace: Backtrace,
@@ -153,6 +230,20 @@ pub enum Error {
         source: table::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to move data of table {} from {} to {}, source: {}",
+        table_name,
+        from,
+        to,
+        source
+    ))]
+    MoveTableData {
+        table_name: String,
+        from: String,
+        to: String,
+        source: object_store::Error,
+    },
+
     #[snafu(display(
         "Projected columnd not found in region, column: {}",
         column_qualified_name
@@ -179,7 +270,11 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            CreateRegion { source, .. } => source.status_code(),
+            CreateRegion { source, .. }
+            | OpenRegion { source, .. }
+            | CloseRegion { source, .. }
+            | FlushRegion { source, .. }
+            | LoadRegionStat { source, .. } => source.status_code(),
 
             AlterTable { source, .. } => source.status_code(),
 
@@ -192,12 +287,23 @@ impl ErrorExt for Error {
             | TableExists { .. }
             | ProjectedColumnNotFound { .. }
             | InvalidPrimaryKey { .. }
+            | InvalidDedupStrategy { .. }
+            | InvalidAppendMode { .. }
+            | InvalidColdAfter { .. }
+            | InvalidDedupWindow { .. }
+            | InvalidDownsampleOptions { .. }
             | MissingTimestampIndex { .. }
             | TableNotFound { .. } => StatusCode::InvalidArguments,
 
-            TableInfoNotFound { .. } | ConvertRaw { .. } => StatusCode::Unexpected,
+            TableInfoNotFound { .. } | RegionNotFound { .. } | ConvertRaw { .. } => {
+                StatusCode::Unexpected
+            }
+
+            StandbyEngine { .. } => StatusCode::Unsupported,
 
-            ScanTableManifest { .. } | UpdateTableManifest { .. } => StatusCode::StorageUnavailable,
+            ScanTableManifest { .. } | UpdateTableManifest { .. } | MoveTableData { .. } => {
+                StatusCode::StorageUnavailable
+            }
         }
     }
 
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 pub mod config;
+pub mod dedup_window;
+pub mod downsample;
 pub mod engine;
 pub mod error;
 mod manifest;
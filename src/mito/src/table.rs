@@ -12,12 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod lazy_region;
 #[cfg(any(test, feature = "test"))]
 pub mod test_util;
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
@@ -27,14 +31,21 @@ use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
 use common_recordbatch::{RecordBatch, RecordBatchStream};
 use common_telemetry::logging;
+use common_time::clock::ClockRef;
+use common_time::Timestamp;
+use datafusion::logical_expr::utils::expr_to_columns;
+use datafusion::physical_plan::ColumnStatistics;
+use datafusion::prelude::{col, lit};
+use datatypes::value::Value;
 use futures::task::{Context, Poll};
 use futures::Stream;
 use object_store::ObjectStore;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AddColumn, AlterOperation, AlterRequest, ChunkReader, ReadContext, Region, RegionMeta,
-    ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, ChangeBatch, ChunkReader, DedupStrategy, Durability,
+    FileCheckStat, ReadContext, Region, RegionMeta, RegionNumber, RegionStat, ScanRequest, Schema,
+    SchemaRef, ScrubStat, SequenceNumber, Snapshot, WriteContext, WriteRequest,
 };
 use table::error as table_error;
 use table::error::Result as TableResult;
@@ -45,15 +56,19 @@ use table::requests::{
     AddColumnRequest, AlterKind, AlterTableRequest, DeleteRequest, InsertRequest,
 };
 use table::table::scan::SimpleTableScan;
-use table::table::{AlterContext, Table};
-use tokio::sync::Mutex;
+use table::table::{schema_with_row_version, AlterContext, Table, SCAN_SEQUENCE};
+use tokio::sync::{Mutex, RwLock};
 
+use crate::dedup_window::DedupWindow;
+use crate::downsample::{DownsampleOptions, Rollup, RollupReader, RollupWrites};
+use crate::engine::{parse_cold_after, APPEND_MODE_KEY, COLD_AFTER_KEY, DEDUP_STRATEGY_KEY};
 use crate::error::{
     self, ProjectedColumnNotFoundSnafu, Result, ScanTableManifestSnafu, TableInfoNotFoundSnafu,
     UpdateTableManifestSnafu,
 };
 use crate::manifest::action::*;
 use crate::manifest::TableManifest;
+use crate::table::lazy_region::{LazyRegion, RegionGuard};
 
 #[inline]
 fn table_manifest_dir(table_dir: &str) -> String {
@@ -66,8 +81,17 @@ pub struct MitoTable<R: Region> {
     // guarded by `self.alter_lock`
     table_info: ArcSwap<TableInfo>,
     // TODO(dennis): a table contains multi regions
-    region: R,
+    region: LazyRegion<R>,
     alter_lock: Mutex<()>,
+    /// Whether the region of the table is opened read-only as a standby.
+    standby: AtomicBool,
+    /// Filter of points identical to the last points of their series, `None` if the table
+    /// doesn't set the dedup window option.
+    dedup_window: Option<DedupWindow>,
+    /// Writes hold the read lock while rolling up rows holds the write lock, see
+    /// [MitoTable::downsample]. It guards the time of the last rollup.
+    downsample_lock: RwLock<Option<i64>>,
+    clock: ClockRef,
 }
 
 #[async_trait]
@@ -81,35 +105,34 @@ impl<R: Region> Table for MitoTable<R> {
     }
 
     async fn insert(&self, request: InsertRequest) -> TableResult<usize> {
-        if request.columns_values.is_empty() {
-            return Ok(0);
-        }
-
-        let mut write_request = self.region.write_request();
-
-        let columns_values = request.columns_values;
-        // columns_values is not empty, it's safe to unwrap
-        let rows_num = columns_values.values().next().unwrap().len();
+        let (rows_num, _) = self.write_insert(request, Durability::Sync).await?;
+        Ok(rows_num)
+    }
 
-        logging::trace!(
-            "Insert into table {} with data: {:?}",
-            self.table_info().name,
-            columns_values
-        );
+    async fn insert_unsynced(
+        &self,
+        request: InsertRequest,
+    ) -> TableResult<(usize, SequenceNumber)> {
+        self.write_insert(request, Durability::Async).await
+    }
 
-        write_request
-            .put(columns_values)
+    async fn fence(&self, sequence: SequenceNumber) -> TableResult<()> {
+        self.region()
+            .await?
+            .fence(sequence)
+            .await
             .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
+            .context(table_error::TableOperationSnafu)
+    }
 
-        let _resp = self
-            .region
-            .write(&WriteContext::default(), write_request)
-            .await
+    async fn snapshot_sequence(&self) -> TableResult<SequenceNumber> {
+        let snapshot = self
+            .region()
+            .await?
+            .snapshot(&ReadContext::default())
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
-
-        Ok(rows_num)
+        Ok(snapshot.sequence())
     }
 
     fn table_type(&self) -> TableType {
@@ -124,43 +147,77 @@ impl<R: Region> Table for MitoTable<R> {
         &self,
         projection: Option<&Vec<usize>>,
         filters: &[Expr],
-        _limit: Option<usize>,
+        limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
-        let read_ctx = ReadContext::default();
-        let snapshot = self
-            .region
-            .snapshot(&read_ctx)
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
-
-        let projection = self
-            .transform_projection(&self.region, projection.cloned())
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
-        let filters = filters.into();
-        let scan_request = ScanRequest {
-            projection,
-            filters,
-            ..Default::default()
-        };
-        let mut reader = snapshot
-            .scan(&read_ctx, scan_request)
-            .await
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?
-            .reader;
+        if let Some(options) = self.downsample_options()? {
+            return self
+                .scan_with_rollup(&options, projection, filters, limit)
+                .await;
+        }
 
+        let reader = self
+            .read_region(projection.cloned(), filters, false)
+            .await?;
         let schema = reader.schema().clone();
-        let stream_schema = schema.clone();
 
-        let stream = Box::pin(async_stream::try_stream! {
-            while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
-                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
-            }
-        });
+        Ok(self.new_table_scan(reader, schema, None, limit))
+    }
 
-        let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
+    fn supports_row_version(&self) -> bool {
+        true
+    }
+
+    async fn scan_with_row_version(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let table_schema = self.schema();
+        let schema_with_version = schema_with_row_version(&table_schema)?;
+        let version_index = table_schema.num_columns();
+        let projection = projection
+            .cloned()
+            .unwrap_or_else(|| (0..=version_index).collect());
+
+        // The region needs at least one column to read, so we read the time index if
+        // only the version is projected.
+        let mut columns_to_read: Vec<_> = projection
+            .iter()
+            .copied()
+            .filter(|idx| *idx != version_index)
+            .collect();
+        if columns_to_read.is_empty() {
+            columns_to_read.push(table_schema.timestamp_index().unwrap_or_default());
+        }
+        // Chunks read from the region contain the `columns_to_read` and the sequence
+        // of rows at last.
+        let chunk_indices = projection
+            .iter()
+            .map(|idx| {
+                if *idx == version_index {
+                    columns_to_read.len()
+                } else {
+                    // Safety: `columns_to_read` contains all projected columns except the version.
+                    columns_to_read.iter().position(|i| i == idx).unwrap()
+                }
+            })
+            .collect();
+        let column_schemas = projection
+            .iter()
+            .map(|idx| schema_with_version.column_schemas()[*idx].clone())
+            .collect();
+        let schema = Arc::new(Schema::try_new(column_schemas).context(
+            table_error::SchemaBuildSnafu {
+                msg: "Failed to project schema with row version",
+            },
+        )?);
+
+        let reader = self
+            .read_region(Some(columns_to_read), filters, true)
+            .await?;
+
+        Ok(self.new_table_scan(reader, schema, Some(chunk_indices), limit))
     }
 
     fn supports_filter_pushdown(&self, _filter: &Expr) -> table::error::Result<FilterPushDownType> {
@@ -173,17 +230,34 @@ impl<R: Region> Table for MitoTable<R> {
 
         let table_info = self.table_info();
         let table_name = &table_info.name;
+        let Some(alter_kind) = req.alter_kind.skip_existing_columns(&table_info.meta.schema) else {
+            logging::debug!(
+                "all columns to add already exist in table {}, skip altering",
+                table_name
+            );
+            return Ok(());
+        };
 
         let mut new_info = TableInfo::clone(&*table_info);
         // setup new table info
-        match &req.alter_kind {
+        match &alter_kind {
             AlterKind::RenameTable { new_table_name } => {
                 new_info.name = new_table_name.clone();
             }
-            AlterKind::AddColumns { .. } | AlterKind::DropColumns { .. } => {
+            AlterKind::RenameSchema { new_schema_name } => {
+                new_info.schema_name = new_schema_name.clone();
+            }
+            AlterKind::SetComment { comment } => {
+                new_info.desc = comment.clone();
+            }
+            AlterKind::AddColumns { .. }
+            | AlterKind::DropColumns { .. }
+            | AlterKind::SetReadOnly { .. }
+            | AlterKind::SetColumnStatistics { .. }
+            | AlterKind::SetColumnComment { .. } => {
                 let table_meta = &table_info.meta;
                 let new_meta = table_meta
-                    .builder_with_alter_kind(table_name, &req.alter_kind)?
+                    .builder_with_alter_kind(table_name, &alter_kind)?
                     .build()
                     .context(error::BuildTableMetaSnafu { table_name })
                     .map_err(BoxedError::new)
@@ -213,12 +287,11 @@ impl<R: Region> Table for MitoTable<R> {
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
 
-        if let Some(alter_op) =
-            create_alter_operation(table_name, &req.alter_kind, &mut new_info.meta)?
+        if let Some(alter_op) = create_alter_operation(table_name, &alter_kind, &mut new_info.meta)?
         {
             // TODO(yingwen): Error handling. Maybe the region need to provide a method to
             // validate the request first.
-            let region = self.region();
+            let region = self.region().await?;
             let region_meta = region.in_memory_metadata();
             let alter_req = AlterRequest {
                 operation: alter_op,
@@ -247,8 +320,16 @@ impl<R: Region> Table for MitoTable<R> {
         if request.key_column_values.is_empty() {
             return Ok(0);
         }
+        self.ensure_writable()?;
+        ensure!(
+            !self.append_mode(),
+            table_error::UnsupportedSnafu {
+                operation: "delete from append-only table",
+            }
+        );
 
-        let mut write_request = self.region.write_request();
+        let region = self.region().await?;
+        let mut write_request = region.write_request();
 
         let key_column_values = request.key_column_values;
         // Safety: key_column_values isn't empty.
@@ -260,11 +341,22 @@ impl<R: Region> Table for MitoTable<R> {
             key_column_values
         );
 
+        // The last points of the series may be deleted, so the next points are accepted. The
+        // window is invalidated even if the delete fails, which only accepts more points.
+        if let Some(dedup_window) = &self.dedup_window {
+            let table_info = self.table_info();
+            dedup_window.invalidate(
+                &table_info.meta.schema,
+                &table_info.meta.primary_key_indices,
+                &key_column_values,
+            );
+        }
         write_request
             .delete(key_column_values)
             .map_err(BoxedError::new)
             .context(table_error::TableOperationSnafu)?;
-        self.region
+        let _downsample_guard = self.downsample_lock.read().await;
+        region
             .write(&WriteContext::default(), write_request)
             .await
             .map_err(BoxedError::new)
@@ -272,6 +364,78 @@ impl<R: Region> Table for MitoTable<R> {
 
         Ok(rows_num)
     }
+
+    fn region_stats(&self) -> Vec<RegionStat> {
+        // Statistics of regions not opened are loaded from the storage in background.
+        self.region.stat().into_iter().collect()
+    }
+
+    async fn scrub(&self) -> TableResult<Vec<ScrubStat>> {
+        // Files of closed regions are verified once the regions are opened again.
+        let Some(region) = self.region.get_opened().await else {
+            return Ok(vec![]);
+        };
+        let stat = region
+            .scrub()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        Ok(vec![stat])
+    }
+
+    async fn check_files(&self, repair: bool) -> TableResult<Vec<FileCheckStat>> {
+        // Opens the region if it's not opened yet, so a region whose manifest is lost is
+        // also reported.
+        let region = self.region().await?;
+        let stat = region
+            .check_files(repair)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        Ok(vec![stat])
+    }
+
+    async fn is_opened(&self) -> bool {
+        self.region.get_opened().await.is_some()
+    }
+
+    async fn refresh(&self) -> TableResult<()> {
+        // The lock serializes refreshes and the transition to writable.
+        let _lock = self.alter_lock.lock().await;
+        if !self.is_standby() {
+            return Ok(());
+        }
+        self.refresh_standby().await
+    }
+
+    async fn set_writable(&self) -> TableResult<()> {
+        let _lock = self.alter_lock.lock().await;
+        if !self.is_standby() {
+            return Ok(());
+        }
+        self.refresh_standby().await?;
+        self.region()
+            .await?
+            .set_writable()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        self.standby.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    async fn read_changes(
+        &self,
+        start_sequence: SequenceNumber,
+        limit: usize,
+    ) -> TableResult<Vec<ChangeBatch>> {
+        self.region()
+            .await?
+            .read_changes(start_sequence, limit)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
 }
 
 struct ChunkStream {
@@ -299,12 +463,22 @@ fn column_qualified_name(table_name: &str, region_name: &str, column_name: &str)
 }
 
 impl<R: Region> MitoTable<R> {
-    fn new(table_info: TableInfo, region: R, manifest: TableManifest) -> Self {
+    fn new(
+        table_info: TableInfo,
+        region: LazyRegion<R>,
+        manifest: TableManifest,
+        clock: ClockRef,
+    ) -> Self {
+        let dedup_window = DedupWindow::from_table_options(&table_info.meta.options);
         Self {
             table_info: ArcSwap::new(Arc::new(table_info)),
             region,
             manifest,
             alter_lock: Mutex::new(()),
+            standby: AtomicBool::new(false),
+            dedup_window,
+            downsample_lock: RwLock::new(None),
+            clock,
         }
     }
 
@@ -368,8 +542,9 @@ impl<R: Region> MitoTable<R> {
         table_name: &str,
         table_dir: &str,
         table_info: TableInfo,
-        region: R,
+        region: LazyRegion<R>,
         object_store: ObjectStore,
+        clock: ClockRef,
     ) -> Result<MitoTable<R>> {
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
 
@@ -383,22 +558,58 @@ impl<R: Region> MitoTable<R> {
             .await
             .context(UpdateTableManifestSnafu { table_name })?;
 
-        Ok(MitoTable::new(table_info, region, manifest))
+        Ok(MitoTable::new(table_info, region, manifest, clock))
     }
 
+    /// Opens the table, the `region` numbered `region_number` should be opened read-only if
+    /// the table is opened as a `standby`.
     pub async fn open(
         table_name: &str,
         table_dir: &str,
-        region: R,
+        region_number: RegionNumber,
+        region: LazyRegion<R>,
         object_store: ObjectStore,
+        standby: bool,
+        clock: ClockRef,
     ) -> Result<MitoTable<R>> {
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
 
         let mut table_info = Self::recover_table_info(table_name, &manifest)
             .await?
             .context(TableInfoNotFoundSnafu { table_name })?;
-        table_info.meta.region_numbers = vec![(region.id() & 0xFFFFFFFF) as u32];
-        Ok(MitoTable::new(table_info, region, manifest))
+        table_info.meta.region_numbers = vec![region_number];
+        let table = MitoTable::new(table_info, region, manifest, clock);
+        table.standby.store(standby, Ordering::Release);
+        Ok(table)
+    }
+
+    /// Returns whether the table is opened as a standby.
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::Acquire)
+    }
+
+    /// Catches up with the region and the table info persisted by the writer of the table,
+    /// should be guarded by the `alter_lock`.
+    async fn refresh_standby(&self) -> TableResult<()> {
+        self.region()
+            .await?
+            .refresh()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let table_info = self.table_info();
+        let new_info = Self::recover_table_info(&table_info.name, &self.manifest)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        if let Some(mut new_info) =
+            new_info.filter(|info| info.ident.version != table_info.ident.version)
+        {
+            new_info.meta.region_numbers = table_info.meta.region_numbers.clone();
+            self.set_table_info(new_info);
+        }
+        Ok(())
     }
 
     async fn recover_table_info(
@@ -448,15 +659,427 @@ impl<R: Region> MitoTable<R> {
         Ok(table_info)
     }
 
-    #[inline]
-    pub fn region(&self) -> &R {
-        &self.region
+    /// Returns the region of the table, opens the region if it's not opened yet.
+    pub async fn region(&self) -> TableResult<RegionGuard<'_, R>> {
+        self.region
+            .get()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Closes the region of a lazily opened table if the table is not read or written for
+    /// `idle` duration, returns whether the region is closed.
+    pub async fn close_if_idle(&self, idle: Duration) -> TableResult<bool> {
+        self.region
+            .close_if_idle(idle)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Loads the statistics of the region of a lazily opened table before the region is
+    /// opened, see [LazyRegion::load_stat].
+    pub async fn load_region_stat(&self) -> TableResult<()> {
+        self.region
+            .load_stat()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Takes the region of the table out to close it, see [LazyRegion::take].
+    pub async fn take_region(&self) -> Option<R> {
+        self.region.take().await
     }
 
     pub fn set_table_info(&self, table_info: TableInfo) {
         self.table_info.swap(Arc::new(table_info));
     }
 
+    async fn read_region(
+        &self,
+        projection: Option<Vec<usize>>,
+        filters: &[Expr],
+        with_sequence: bool,
+    ) -> TableResult<<R::Snapshot as Snapshot>::Reader> {
+        let read_ctx = ReadContext::default();
+        let region = self.region().await?;
+        let snapshot = region
+            .snapshot(&read_ctx)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let projection = self
+            .transform_projection(&region, projection)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let filters = filters.into();
+        let scan_request = ScanRequest {
+            sequence: SCAN_SEQUENCE.try_with(|sequence| *sequence).ok(),
+            projection,
+            filters,
+            with_sequence,
+            dedup_strategy: self.dedup_strategy(),
+            append_mode: self.append_mode(),
+            ..Default::default()
+        };
+        let reader = snapshot
+            .scan(&read_ctx, scan_request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?
+            .reader;
+
+        Ok(reader)
+    }
+
+    /// Creates a scan plan that outputs chunks from the `reader` as record batches of
+    /// `schema`. If `chunk_indices` is `Some`, the columns of each record batch are
+    /// picked from the chunk by these indices.
+    /// Creates a scan of the rows returned by the `reader`, which are estimated by the
+    /// statistics of the region.
+    fn new_table_scan<C>(
+        &self,
+        mut reader: C,
+        schema: SchemaRef,
+        chunk_indices: Option<Vec<usize>>,
+        limit: Option<usize>,
+    ) -> PhysicalPlanRef
+    where
+        C: ChunkReader + 'static,
+        C::Error: 'static,
+    {
+        let stream_schema = schema.clone();
+
+        let stream = Box::pin(async_stream::try_stream! {
+            while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
+                let columns = match &chunk_indices {
+                    Some(indices) => indices.iter().map(|idx| chunk.columns[*idx].clone()).collect(),
+                    None => chunk.columns,
+                };
+                yield RecordBatch::new(stream_schema.clone(), columns)?
+            }
+        });
+
+        let column_statistics = self.scan_column_statistics(&schema);
+        let stream = Box::pin(ChunkStream { schema, stream });
+        let scan = SimpleTableScan::new(stream);
+        match self.statistics() {
+            Some(statistics) => {
+                let mut statistics = statistics.scan_statistics(limit);
+                statistics.column_statistics = column_statistics;
+                Arc::new(scan.with_statistics(statistics))
+            }
+            None => Arc::new(scan),
+        }
+    }
+
+    /// Returns the statistics of columns in the `schema` of a scan collected by the last
+    /// `ANALYZE TABLE`, `None` if the table is never analyzed.
+    fn scan_column_statistics(&self, schema: &SchemaRef) -> Option<Vec<ColumnStatistics>> {
+        let table_info = self.table_info();
+        let analyzed = table_info.meta.column_statistics.as_ref()?;
+        let to_scalar = |value: &Value, data_type| {
+            if value.is_null() {
+                None
+            } else {
+                value.try_to_scalar_value(data_type).ok()
+            }
+        };
+        let column_statistics = schema
+            .column_schemas()
+            .iter()
+            .map(
+                |column_schema| match analyzed.columns.get(&column_schema.name) {
+                    Some(stats) => ColumnStatistics {
+                        null_count: Some(stats.null_count as usize),
+                        max_value: to_scalar(&stats.max_value, &column_schema.data_type),
+                        min_value: to_scalar(&stats.min_value, &column_schema.data_type),
+                        distinct_count: Some(stats.distinct_count as usize),
+                    },
+                    None => ColumnStatistics::default(),
+                },
+            )
+            .collect();
+        Some(column_statistics)
+    }
+
+    /// Returns the dedup strategy in table options, the strategy is validated on table
+    /// creation, so unknown strategy is treated as default.
+    fn dedup_strategy(&self) -> DedupStrategy {
+        self.table_info()
+            .meta
+            .options
+            .get(DEDUP_STRATEGY_KEY)
+            .and_then(|strategy| DedupStrategy::from_name(strategy))
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the table is append-only.
+    fn append_mode(&self) -> bool {
+        self.table_info()
+            .meta
+            .options
+            .get(APPEND_MODE_KEY)
+            .map_or(false, |append_mode| append_mode == "true")
+    }
+
+    /// Writes the values of the insert `request` to the region, returns number of inserted
+    /// rows and the sequence of the write.
+    async fn write_insert(
+        &self,
+        request: InsertRequest,
+        durability: Durability,
+    ) -> TableResult<(usize, SequenceNumber)> {
+        if request.columns_values.is_empty() {
+            return Ok((0, 0));
+        }
+        self.ensure_writable()?;
+
+        let region = self.region().await?;
+        let mut write_request = region.write_request();
+
+        let columns_values = request.columns_values;
+        // columns_values is not empty, it's safe to unwrap
+        let rows_num = columns_values.values().next().unwrap().len();
+
+        let (columns_values, accepted_points) = match &self.dedup_window {
+            Some(dedup_window) => {
+                let table_info = self.table_info();
+                let (columns_values, accepted_points) = dedup_window
+                    .filter(
+                        &table_info.meta.schema,
+                        &table_info.meta.primary_key_indices,
+                        columns_values,
+                    )
+                    .map_err(BoxedError::new)
+                    .context(table_error::TableOperationSnafu)?;
+                (columns_values, Some(accepted_points))
+            }
+            None => (columns_values, None),
+        };
+        // All rows are identical to the last points of their series, which are considered
+        // as inserted.
+        if columns_values.values().next().unwrap().is_empty() {
+            return Ok((rows_num, 0));
+        }
+
+        logging::trace!(
+            "Insert into table {} with data: {:?}",
+            self.table_info().name,
+            columns_values
+        );
+
+        write_request
+            .put(columns_values)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        let write_ctx = WriteContext { durability };
+        let _downsample_guard = self.downsample_lock.read().await;
+        let resp = region
+            .write(&write_ctx, write_request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        // Points are only deduplicated against points written.
+        if let (Some(dedup_window), Some(accepted_points)) = (&self.dedup_window, accepted_points) {
+            dedup_window.commit(accepted_points);
+        }
+
+        Ok((rows_num, resp.sequence))
+    }
+
+    /// Returns the age after which SST files of the table are moved to the cold tier, the
+    /// option is validated on table creation.
+    pub(crate) fn cold_after(&self) -> Option<Duration> {
+        self.table_info()
+            .meta
+            .options
+            .get(COLD_AFTER_KEY)
+            .and_then(|cold_after| parse_cold_after(cold_after).ok())
+    }
+
+    /// Moves SST files whose rows are all older than the `cold_after` option of the table at
+    /// `now_millis` to the cold tier, returns the number of files moved.
+    pub async fn move_cold_files(&self, now_millis: i64) -> TableResult<usize> {
+        let Some(cold_after) = self.cold_after() else {
+            return Ok(0);
+        };
+        // Files of closed regions are moved once the regions are opened again.
+        let Some(region) = self.region.get_opened().await else {
+            return Ok(0);
+        };
+        let before = Timestamp::new_millisecond(now_millis - cold_after.as_millis() as i64);
+        region
+            .move_cold_files(before)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Rolls up rows older than the downsample tiers of the table at `now_millis`, returns
+    /// the number of raw rows rolled up.
+    pub async fn downsample(&self, now_millis: i64) -> TableResult<usize> {
+        let table_info = self.table_info();
+        let options = DownsampleOptions::from_table_options(&table_info.meta.options)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let Some(options) = options else {
+            return Ok(0);
+        };
+        self.ensure_writable()?;
+        // Rows of closed regions are rolled up once the regions are opened again.
+        if self.region.get_opened().await.is_none() {
+            return Ok(0);
+        }
+
+        // Blocks writes until the rollup is done, otherwise rows written between reading a
+        // bucket and deleting its raw rows are lost.
+        let mut last_rollup = self.downsample_lock.write().await;
+        let mut rollup = Rollup::new(
+            table_info.meta.schema.clone(),
+            &table_info.meta.primary_key_indices,
+            &options,
+            now_millis,
+        )
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
+        if let Some(since) = *last_rollup {
+            rollup = rollup.with_since(since);
+        }
+        let Some(filter) = rollup_filter(&rollup)? else {
+            *last_rollup = Some(now_millis);
+            return Ok(0);
+        };
+
+        let mut reader = self.read_region(None, &[filter], false).await?;
+        let mut num_rows = 0;
+        while let Some(chunk) = reader
+            .next_chunk()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?
+        {
+            rollup.push_columns(&chunk.columns);
+            if let Some(writes) = rollup.take_writes() {
+                num_rows += self.write_rollup(writes).await?;
+            }
+        }
+        rollup.finish_group();
+        if let Some(writes) = rollup.take_writes() {
+            num_rows += self.write_rollup(writes).await?;
+        }
+        *last_rollup = Some(now_millis);
+
+        if num_rows > 0 {
+            logging::info!(
+                "Downsampled table {}, rolled up {} rows",
+                table_info.name,
+                num_rows
+            );
+        }
+        Ok(num_rows)
+    }
+
+    fn downsample_options(&self) -> TableResult<Option<DownsampleOptions>> {
+        DownsampleOptions::from_table_options(&self.table_info().meta.options)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    }
+
+    /// Scans the table and rolls up rows older than the downsample tiers while reading, so
+    /// queries see the resolution of the tiers even if the rows are not rolled up yet.
+    async fn scan_with_rollup(
+        &self,
+        options: &DownsampleOptions,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let table_info = self.table_info();
+        let table_schema = table_info.meta.schema.clone();
+        let primary_key_indices = &table_info.meta.primary_key_indices;
+
+        // Rows are rolled up by buckets, so only filters on the primary key, which keep or
+        // drop whole buckets, are pushed down.
+        let filters: Vec<_> = filters
+            .iter()
+            .filter(|filter| {
+                let mut columns = HashSet::new();
+                expr_to_columns(filter.df_expr(), &mut columns).is_ok()
+                    && columns.iter().all(|column| {
+                        table_schema
+                            .column_index_by_name(&column.name)
+                            .map(|index| primary_key_indices.contains(&index))
+                            .unwrap_or(false)
+                    })
+            })
+            .cloned()
+            .collect();
+        let reader = self.read_region(None, &filters, false).await?;
+        let reader = RollupReader::new(
+            reader,
+            table_schema.clone(),
+            primary_key_indices,
+            options,
+            self.clock.now_millis(),
+        )
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
+
+        let Some(projection) = projection else {
+            return Ok(self.new_table_scan(reader, table_schema, None, limit));
+        };
+        let column_schemas = projection
+            .iter()
+            .map(|idx| table_schema.column_schemas()[*idx].clone())
+            .collect();
+        let schema = Arc::new(Schema::try_new(column_schemas).context(
+            table_error::SchemaBuildSnafu {
+                msg: "Failed to project schema of downsampled table",
+            },
+        )?);
+        Ok(self.new_table_scan(reader, schema, Some(projection.clone()), limit))
+    }
+
+    /// Puts the rolled up rows and deletes the raw rows in one write batch, so readers never
+    /// see both of them.
+    async fn write_rollup(&self, writes: RollupWrites) -> TableResult<usize> {
+        let region = self.region().await?;
+        let mut write_request = region.write_request();
+        write_request
+            .put(writes.puts)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        if let Some(deletes) = writes.deletes {
+            write_request
+                .delete(deletes)
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+        }
+        region
+            .write(&WriteContext::default(), write_request)
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
+        Ok(writes.num_rows)
+    }
+
+    /// Returns error if the table is read-only.
+    fn ensure_writable(&self) -> TableResult<()> {
+        let table_info = self.table_info();
+        ensure!(
+            !table_info.meta.read_only,
+            table_error::TableReadOnlySnafu {
+                table_name: &table_info.name,
+            }
+        );
+        Ok(())
+    }
+
     #[inline]
     pub fn manifest(&self) -> &TableManifest {
         &self.manifest
@@ -468,6 +1091,29 @@ impl<R: Region> MitoTable<R> {
     }
 }
 
+/// Builds the filter of rows to roll up, returns `None` if there is nothing to roll up.
+fn rollup_filter(rollup: &Rollup) -> TableResult<Option<Expr>> {
+    let (ts_column, ranges) = rollup.time_ranges();
+    let to_scalar = |value: &Value| {
+        value
+            .try_to_scalar_value(&value.data_type())
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)
+    };
+    let mut filter = None;
+    for (start, end) in ranges {
+        let mut range = col(ts_column).lt(lit(to_scalar(&end)?));
+        if let Some(start) = start {
+            range = col(ts_column).gt_eq(lit(to_scalar(&start)?)).and(range);
+        }
+        filter = Some(match filter {
+            Some(filter) => range.or(filter),
+            None => range,
+        });
+    }
+    Ok(filter.map(Expr::from))
+}
+
 /// Create [`AlterOperation`] according to given `alter_kind`.
 fn create_alter_operation(
     table_name: &str,
@@ -482,7 +1128,13 @@ fn create_alter_operation(
             names: names.to_vec(),
         })),
         // No need to build alter operation when reaming tables.
-        AlterKind::RenameTable { .. } => Ok(None),
+        AlterKind::RenameTable { .. } | AlterKind::RenameSchema { .. } => Ok(None),
+        // Read-only is a table level flag, the regions are unchanged.
+        AlterKind::SetReadOnly { .. } => Ok(None),
+        // Statistics only describe the data of the regions.
+        AlterKind::SetColumnStatistics { .. } => Ok(None),
+        // Comments are only kept in the table info.
+        AlterKind::SetComment { .. } | AlterKind::SetColumnComment { .. } => Ok(None),
     }
 }
 
@@ -0,0 +1,226 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region of a table that could be opened on first access and closed once idle.
+
+use std::ops::Deref;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use common_telemetry::logging;
+use common_time::clock::ClockRef;
+use snafu::ResultExt;
+use store_api::storage::{Region, RegionStat};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::error::{self, Result};
+
+pub type RegionLoaderRef<R> = Arc<dyn RegionLoader<R>>;
+
+/// Opens and closes the region of a table lazily.
+#[async_trait]
+pub trait RegionLoader<R: Region>: Send + Sync {
+    fn region_name(&self) -> &str;
+
+    /// Opens the region, recovering it from its manifest and WAL.
+    async fn open_region(&self) -> Result<R>;
+
+    async fn close_region(&self, region: R) -> Result<()>;
+
+    /// Reads the statistics of the region without opening it, returns `None` if the region
+    /// doesn't exist.
+    async fn load_stat(&self) -> Result<Option<RegionStat>>;
+}
+
+/// The region of a table, the region is always opened if there is no [RegionLoader],
+/// otherwise it's opened on first access and could be closed once idle.
+pub struct LazyRegion<R: Region> {
+    region_name: String,
+    region: RwLock<Option<R>>,
+    /// Copy of the opened region to read its statistics without waiting for the region to
+    /// be opened or closed.
+    opened: Mutex<Option<R>>,
+    loader: Option<RegionLoaderRef<R>>,
+    clock: Option<ClockRef>,
+    last_access_millis: AtomicI64,
+    /// Statistics of the region when it's not opened, loaded by [LazyRegion::load_stat] or
+    /// saved when the region is closed.
+    closed_stat: Mutex<Option<RegionStat>>,
+}
+
+impl<R: Region> LazyRegion<R> {
+    /// Creates a region that is always opened.
+    pub fn opened(region: R) -> Self {
+        Self {
+            region_name: region.name().to_string(),
+            opened: Mutex::new(Some(region.clone())),
+            region: RwLock::new(Some(region)),
+            loader: None,
+            clock: None,
+            last_access_millis: AtomicI64::new(0),
+            closed_stat: Mutex::new(None),
+        }
+    }
+
+    /// Creates a region opened by the `loader` if `region` is `None`, the access time of the
+    /// region is read from the `clock`.
+    pub fn with_loader(region: Option<R>, loader: RegionLoaderRef<R>, clock: ClockRef) -> Self {
+        let last_access_millis = AtomicI64::new(clock.now_millis());
+        Self {
+            region_name: loader.region_name().to_string(),
+            opened: Mutex::new(region.clone()),
+            region: RwLock::new(region),
+            loader: Some(loader),
+            clock: Some(clock),
+            last_access_millis,
+            closed_stat: Mutex::new(None),
+        }
+    }
+
+    /// Returns the region, opens it if it's not opened yet. The region is not closed until
+    /// the returned guard is dropped.
+    pub async fn get(&self) -> Result<RegionGuard<'_, R>> {
+        self.touch();
+        loop {
+            if let Some(region) = self.get_opened().await {
+                return Ok(region);
+            }
+
+            let mut region = self.region.write().await;
+            if region.is_none() {
+                // Regions without loader are only closed once the table is dropped.
+                let Some(loader) = self.loader.as_ref() else {
+                    return error::RegionNotFoundSnafu {
+                        region_name: &self.region_name,
+                    }
+                    .fail();
+                };
+                logging::info!("Opening region {} on first access", loader.region_name());
+                let opened = loader.open_region().await?;
+                *self.opened.lock().unwrap() = Some(opened.clone());
+                *region = Some(opened);
+            }
+        }
+    }
+
+    /// Returns the region if it's opened, without opening or touching it.
+    pub async fn get_opened(&self) -> Option<RegionGuard<'_, R>> {
+        let region = self.region.read().await;
+        region.is_some().then_some(RegionGuard(region))
+    }
+
+    /// Returns the statistics of the region, or the statistics read from the storage if it's
+    /// not opened. Returns `None` if the statistics of the region are not loaded yet.
+    pub fn stat(&self) -> Option<RegionStat> {
+        // Clones the region so its statistics are not collected under the lock.
+        let opened = self.opened.lock().unwrap().clone();
+        match opened {
+            Some(region) => Some(region.stat()),
+            None => self.closed_stat.lock().unwrap().clone(),
+        }
+    }
+
+    /// Loads the statistics of the region from the storage if it's not opened, so the
+    /// statistics of the region are reported before it's opened.
+    pub async fn load_stat(&self) -> Result<()> {
+        let loader = match &self.loader {
+            Some(loader) => loader,
+            None => return Ok(()),
+        };
+        if self.opened.lock().unwrap().is_some() || self.closed_stat.lock().unwrap().is_some() {
+            return Ok(());
+        }
+
+        let stat = loader.load_stat().await?;
+        // Holds the lock so the region is not opened meanwhile.
+        let region = self.region.read().await;
+        let mut closed_stat = self.closed_stat.lock().unwrap();
+        if region.is_none() && closed_stat.is_none() {
+            *closed_stat = stat;
+        }
+        Ok(())
+    }
+
+    /// Closes the region if it's opened by a loader and not accessed for `idle` duration,
+    /// returns whether the region is closed.
+    pub async fn close_if_idle(&self, idle: Duration) -> Result<bool> {
+        let (Some(loader), Some(clock)) = (&self.loader, &self.clock) else {
+            return Ok(false);
+        };
+        let is_idle = || {
+            clock.now_millis() - self.last_access_millis.load(Ordering::Relaxed)
+                >= idle.as_millis() as i64
+        };
+        if !is_idle() {
+            return Ok(false);
+        }
+
+        let mut region = self.region.write().await;
+        // The region may be accessed while waiting for the lock.
+        if region.is_none() || !is_idle() {
+            return Ok(false);
+        }
+        // Flushes the memtables so the WAL is not replayed once the region is opened again,
+        // the region is kept opened if the flush fails.
+        // Safety: checked above.
+        let opened = region.as_ref().unwrap();
+        opened
+            .flush()
+            .await
+            .map_err(BoxedError::new)
+            .context(error::FlushRegionSnafu {
+                region_name: &self.region_name,
+            })?;
+
+        let closing = region.take().unwrap();
+        *self.opened.lock().unwrap() = None;
+        *self.closed_stat.lock().unwrap() = Some(closing.stat());
+        loader.close_region(closing).await?;
+        logging::info!("Closed idle region {}", loader.region_name());
+        Ok(true)
+    }
+
+    /// Takes the region out to close it, waiting for the region to be released by readers
+    /// and writers. Returns `None` if the region is not opened.
+    pub async fn take(&self) -> Option<R> {
+        let region = self.region.write().await.take();
+        *self.opened.lock().unwrap() = None;
+        if let Some(region) = &region {
+            *self.closed_stat.lock().unwrap() = Some(region.stat());
+        }
+        region
+    }
+
+    fn touch(&self) {
+        if let Some(clock) = &self.clock {
+            self.last_access_millis
+                .store(clock.now_millis(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Guard of an opened region.
+pub struct RegionGuard<'a, R>(RwLockReadGuard<'a, Option<R>>);
+
+impl<R> Deref for RegionGuard<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        // Safety: the guard is only created for opened regions.
+        self.0.as_ref().unwrap()
+    }
+}
@@ -21,14 +21,16 @@ use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use common_error::mock::MockError;
 use common_telemetry::logging;
+use common_time::Timestamp;
 use datatypes::prelude::{DataType, Value, VectorRef};
 use datatypes::schema::{ColumnSchema, Schema};
 use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::WriteBatch;
 use store_api::storage::{
-    AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, GetRequest, GetResponse,
-    OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest, ScanResponse,
-    SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    AlterRequest, ChangeBatch, Chunk, ChunkReader, CreateOptions, EngineContext, FileCheckStat,
+    GetRequest, GetResponse, OpenOptions, ReadContext, Region, RegionDescriptor, RegionId,
+    RegionStat, ScanRequest, ScanResponse, SchemaRef, ScrubStat, SequenceNumber, Snapshot,
+    StorageEngine, WriteContext, WriteResponse,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -85,6 +87,10 @@ impl Snapshot for MockSnapshot {
         &self.schema
     }
 
+    fn sequence(&self) -> SequenceNumber {
+        0
+    }
+
     async fn scan(
         &self,
         _ctx: &ReadContext,
@@ -162,7 +168,11 @@ impl Region for MockRegion {
 
     async fn write(&self, _ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
         self.inner.write(request);
-        Ok(WriteResponse {})
+        Ok(WriteResponse { sequence: 0 })
+    }
+
+    async fn fence(&self, _sequence: SequenceNumber) -> Result<()> {
+        Ok(())
     }
 
     fn snapshot(&self, _ctx: &ReadContext) -> Result<MockSnapshot> {
@@ -188,6 +198,55 @@ impl Region for MockRegion {
 
         Ok(())
     }
+
+    fn stat(&self) -> RegionStat {
+        let num_rows = self
+            .inner
+            .memtable
+            .read()
+            .unwrap()
+            .values()
+            .next()
+            .map_or(0, |column| column.len()) as u64;
+        RegionStat {
+            region_id: self.id(),
+            approximate_rows: num_rows,
+            written_rows: num_rows,
+            ..Default::default()
+        }
+    }
+
+    async fn scrub(&self) -> Result<ScrubStat> {
+        Ok(ScrubStat::default())
+    }
+
+    async fn move_cold_files(&self, _before: Timestamp) -> Result<usize> {
+        Ok(0)
+    }
+
+    async fn check_files(&self, _repair: bool) -> Result<FileCheckStat> {
+        Ok(FileCheckStat::default())
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_writable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn read_changes(
+        &self,
+        _start_sequence: SequenceNumber,
+        _limit: usize,
+    ) -> Result<Vec<ChangeBatch>> {
+        Ok(vec![])
+    }
 }
 
 impl MockRegionInner {
@@ -275,8 +334,16 @@ impl StorageEngine for MockEngine {
         return Ok(None);
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: MockRegion) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: MockRegion) -> Result<()> {
+        logging::info!("Mock engine close region, name: {}", region.name());
+
+        let mut regions = self.regions.lock().unwrap();
+        if let Some(region) = regions.opened_regions.remove(region.name()) {
+            regions
+                .closed_regions
+                .insert(region.name().to_string(), region);
+        }
+        Ok(())
     }
 
     async fn create_region(
@@ -310,4 +377,18 @@ impl StorageEngine for MockEngine {
         let regions = self.regions.lock().unwrap();
         Ok(regions.opened_regions.get(name).cloned())
     }
+
+    async fn region_stat(
+        &self,
+        _ctx: &EngineContext,
+        name: &str,
+        _opts: &OpenOptions,
+    ) -> Result<Option<RegionStat>> {
+        let regions = self.regions.lock().unwrap();
+        Ok(regions
+            .opened_regions
+            .get(name)
+            .or_else(|| regions.closed_regions.get(name))
+            .map(|region| region.stat()))
+    }
 }
@@ -14,8 +14,9 @@
 
 pub use opendal::{
     layers, services, Error, ErrorKind, Layer, Object, ObjectLister, ObjectMetadata, ObjectMode,
-    Operator as ObjectStore, Result,
+    ObjectMultipart, ObjectPart, Operator as ObjectStore, Result,
 };
 pub mod backend;
 pub mod test_util;
+pub mod timeout;
 pub mod util;
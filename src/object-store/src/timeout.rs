@@ -0,0 +1,197 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A layer fails object store operations that don't finish in time, so requests stuck on
+//! an unresponsive backend could be retried by the retry layer wrapping it.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use opendal::raw::*;
+use opendal::{Error, ErrorKind, Layer, Result};
+
+/// Timeouts of operations, operations without timeouts wait until they finish.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeoutLayer {
+    /// Timeout to open an object for reading, reading the opened object is not limited.
+    read: Option<Duration>,
+    /// Timeout to write an object.
+    write: Option<Duration>,
+    /// Timeout of operations on metadata, e.g. stat, list, create and delete.
+    metadata: Option<Duration>,
+}
+
+impl TimeoutLayer {
+    pub fn with_read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read = timeout;
+        self
+    }
+
+    pub fn with_write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.write = timeout;
+        self
+    }
+
+    pub fn with_metadata_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.metadata = timeout;
+        self
+    }
+}
+
+impl Layer for TimeoutLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(TimeoutAccessor {
+            inner,
+            timeouts: *self,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct TimeoutAccessor {
+    inner: Arc<dyn Accessor>,
+    timeouts: TimeoutLayer,
+}
+
+#[async_trait]
+impl Accessor for TimeoutAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        with_timeout(
+            self.timeouts.metadata,
+            Operation::Create,
+            path,
+            self.inner.create(path, args),
+        )
+        .await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, BytesReader)> {
+        with_timeout(
+            self.timeouts.read,
+            Operation::Read,
+            path,
+            self.inner.read(path, args),
+        )
+        .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<RpWrite> {
+        with_timeout(
+            self.timeouts.write,
+            Operation::Write,
+            path,
+            self.inner.write(path, args, r),
+        )
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        with_timeout(
+            self.timeouts.metadata,
+            Operation::Stat,
+            path,
+            self.inner.stat(path, args),
+        )
+        .await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        with_timeout(
+            self.timeouts.metadata,
+            Operation::Delete,
+            path,
+            self.inner.delete(path, args),
+        )
+        .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, ObjectPager)> {
+        with_timeout(
+            self.timeouts.metadata,
+            Operation::List,
+            path,
+            self.inner.list(path, args),
+        )
+        .await
+    }
+}
+
+/// Runs the operation `fut` on `path`, fails with a temporary error if it doesn't finish
+/// in `timeout`.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    op: Operation,
+    path: &str,
+    fut: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return fut.await,
+    };
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(result) => result,
+        Err(_) => Err(Error::new(ErrorKind::Unexpected, "operation timeout")
+            .with_operation(op.into_static())
+            .with_context("path", path)
+            .with_context("timeout", format!("{timeout:?}"))
+            .set_temporary()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fs;
+    use crate::ObjectStore;
+
+    #[tokio::test]
+    async fn test_timeout_layer() {
+        let dir = tempdir::TempDir::new("test_timeout_layer").unwrap();
+        let accessor = fs::Builder::default()
+            .root(&dir.path().to_string_lossy())
+            .build()
+            .unwrap();
+        let object_store = ObjectStore::new(accessor).layer(
+            TimeoutLayer::default()
+                .with_read_timeout(Some(Duration::from_secs(10)))
+                .with_write_timeout(Some(Duration::from_secs(10))),
+        );
+
+        // Operations finished in time are not affected.
+        let object = object_store.object("test_file");
+        object.write("Hello, World!").await.unwrap();
+        assert_eq!("Hello, World!".as_bytes(), object.read().await.unwrap());
+
+        let result = with_timeout(
+            Some(Duration::from_millis(10)),
+            Operation::Read,
+            "test_file",
+            async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            },
+        )
+        .await;
+        let err = result.unwrap_err();
+        assert!(err.is_temporary());
+        assert_eq!(ErrorKind::Unexpected, err.kind());
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use common_query::prelude::Expr;
@@ -39,11 +40,21 @@ pub type PartitionRuleManagerRef = Arc<PartitionRuleManager>;
 /// - filters (in case of select, deletion and update)
 pub struct PartitionRuleManager {
     table_routes: Arc<TableRoutes>,
+    /// Rotates among the followers of a region to read from, see
+    /// [PartitionRuleManager::find_region_follower_datanodes].
+    next_follower: AtomicUsize,
 }
 
 impl PartitionRuleManager {
     pub fn new(table_routes: Arc<TableRoutes>) -> Self {
-        Self { table_routes }
+        Self {
+            table_routes,
+            next_follower: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn table_routes(&self) -> &Arc<TableRoutes> {
+        &self.table_routes
     }
 
     /// Find table route of given table name.
@@ -82,6 +93,43 @@ impl PartitionRuleManager {
         Ok(datanodes)
     }
 
+    /// Find datanodes to read the corresponding regions of given table from, which are the
+    /// followers of the regions, or the leaders if the regions have no followers.
+    pub async fn find_region_follower_datanodes(
+        &self,
+        table: &TableName,
+        regions: Vec<RegionNumber>,
+    ) -> Result<HashMap<Peer, Vec<RegionNumber>>> {
+        let route = self.table_routes.get_route(table).await?;
+        let mut datanodes = HashMap::with_capacity(regions.len());
+        for region in regions.iter() {
+            let datanode = route
+                .region_routes
+                .iter()
+                .find(|x| x.region.id == *region as u64)
+                .and_then(|x| {
+                    if x.follower_peers.is_empty() {
+                        x.leader_peer.clone()
+                    } else {
+                        // Spreads reads of the regions among the followers, successive
+                        // reads of a region are served by different followers.
+                        let index = self.next_follower.fetch_add(1, Ordering::Relaxed)
+                            % x.follower_peers.len();
+                        Some(x.follower_peers[index].clone())
+                    }
+                })
+                .context(error::FindDatanodeSnafu {
+                    table: table.to_string(),
+                    region: *region,
+                })?;
+            datanodes
+                .entry(datanode)
+                .or_insert_with(Vec::new)
+                .push(*region);
+        }
+        Ok(datanodes)
+    }
+
     /// Get partition rule of given table.
     pub async fn find_table_partition_rule(&self, table: &TableName) -> Result<PartitionRuleRef> {
         let route = self.table_routes.get_route(table).await?;
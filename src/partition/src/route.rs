@@ -72,4 +72,14 @@ impl TableRoutes {
     pub async fn insert_table_route(&self, table_name: TableName, table_route: Arc<TableRoute>) {
         self.cache.insert(table_name, table_route).await
     }
+
+    /// Removes the cached route of the table, it is fetched from meta on next access.
+    pub async fn invalidate(&self, table_name: &TableName) {
+        self.cache.invalidate(table_name).await
+    }
+
+    /// Removes all cached routes.
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all()
+    }
 }
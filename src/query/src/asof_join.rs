@@ -0,0 +1,772 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ASOF join, which joins each row of the left input with the latest row of the right input
+//! at or before the timestamp of the row and with the same join keys.
+//!
+//! `a ASOF JOIN b ON a.host = b.host AND a.ts >= b.ts` is parsed to a join whose condition is
+//! marked by [ASOF_JOIN_MARKER]. [rewrite_asof_joins()] replaces such joins in the logical
+//! plan by [AsofJoin] nodes, which are executed by [AsofJoinExec]. Like inner joins, rows of
+//! the left input without any matching right row are dropped.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use common_query::error::InvalidFuncArgsSnafu;
+use common_query::prelude::{create_udf, ScalarFunctionImplementation, ScalarUdf, Volatility};
+use datafusion::arrow::array::{Array, ArrayRef, Int64Array, UInt32Array};
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::error::{ArrowError, Result as ArrowResult};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::planner::ExtensionPlanner;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalPlanner, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion_common::{Column, DFSchemaRef, DataFusionError, ScalarValue};
+use datafusion_expr::utils::from_plan;
+use datafusion_expr::{
+    BinaryExpr, Expr, Extension, JoinType, LogicalPlan, Operator, UserDefinedLogicalNode,
+};
+use datatypes::arrow::compute;
+use datatypes::prelude::ConcreteDataType;
+use futures::{ready, Stream, StreamExt};
+use sql::statements::query::ASOF_JOIN_MARKER;
+
+/// Returns the UDF of the [ASOF_JOIN_MARKER]. Markers are removed on planning, so the UDF
+/// is only evaluated if the ASOF JOIN is not recognized by the planner.
+pub(crate) fn asof_join_marker() -> ScalarUdf {
+    let fun: ScalarFunctionImplementation = Arc::new(|_| {
+        InvalidFuncArgsSnafu {
+            err_msg: "ASOF JOIN is not supported in subqueries",
+        }
+        .fail()
+    });
+    create_udf(
+        ASOF_JOIN_MARKER,
+        vec![],
+        Arc::new(ConcreteDataType::boolean_datatype()),
+        Volatility::Immutable,
+        fun,
+    )
+}
+
+/// Replaces the joins marked by [ASOF_JOIN_MARKER] in `plan` by [AsofJoin] nodes.
+pub(crate) fn rewrite_asof_joins(plan: &LogicalPlan) -> DfResult<LogicalPlan> {
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(rewrite_asof_joins)
+        .collect::<DfResult<Vec<_>>>()?;
+
+    let asof_join = match plan {
+        LogicalPlan::Join(join) => {
+            let asof_join = AsofJoin::try_new(
+                &inputs[0],
+                &inputs[1],
+                join.on.clone(),
+                join.filter.as_ref(),
+            )?;
+            if asof_join.is_some() && join.join_type != JoinType::Inner {
+                return Err(DataFusionError::Plan(format!(
+                    "ASOF JOIN can't be combined with {} JOIN",
+                    join.join_type
+                )));
+            }
+            asof_join
+        }
+        // Joins without equal conditions are planned as filtered cross joins. The ASOF join
+        // takes the children of the rewritten cross join, so ASOF joins nested in them are
+        // rewritten too.
+        LogicalPlan::Filter(filter) => match filter.input.as_ref() {
+            LogicalPlan::CrossJoin(_) => {
+                let children = inputs[0].inputs();
+                AsofJoin::try_new(
+                    children[0],
+                    children[1],
+                    Vec::new(),
+                    Some(filter.predicate()),
+                )?
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match asof_join {
+        Some(asof_join) => Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(asof_join),
+        })),
+        None => from_plan(plan, &plan.expressions(), &inputs),
+    }
+}
+
+pub(crate) fn split_conjunction<'a>(expr: &'a Expr, conjuncts: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => {
+            split_conjunction(left, conjuncts);
+            split_conjunction(right, conjuncts);
+        }
+        Expr::Nested(expr) => split_conjunction(expr, conjuncts),
+        _ => conjuncts.push(expr),
+    }
+}
+
+fn is_asof_join_marker(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF { fun, .. } if fun.name == ASOF_JOIN_MARKER)
+}
+
+/// Logical plan node of the ASOF join.
+#[derive(Debug)]
+pub struct AsofJoin {
+    left: LogicalPlan,
+    right: LogicalPlan,
+    /// Equal join keys, in pairs of (left column, right column).
+    on: Vec<(Column, Column)>,
+    left_timestamp: Column,
+    right_timestamp: Column,
+    schema: DFSchemaRef,
+}
+
+impl AsofJoin {
+    /// Creates the ASOF join of `left` and `right` if the join `condition` has the
+    /// [ASOF_JOIN_MARKER], returns `None` if the join is not an ASOF join.
+    ///
+    /// Besides the equal join keys in `on`, the condition must consist of equal keys and a
+    /// single `left.ts >= right.ts` comparison.
+    fn try_new(
+        left: &LogicalPlan,
+        right: &LogicalPlan,
+        mut on: Vec<(Column, Column)>,
+        condition: Option<&Expr>,
+    ) -> DfResult<Option<Self>> {
+        let Some(condition) = condition else {
+            return Ok(None);
+        };
+        let mut conjuncts = Vec::new();
+        split_conjunction(condition, &mut conjuncts);
+        let Some(marker) = conjuncts.iter().position(|expr| is_asof_join_marker(expr)) else {
+            return Ok(None);
+        };
+        let _ = conjuncts.remove(marker);
+
+        let invalid_condition = |expr: &Expr| {
+            DataFusionError::Plan(format!(
+                "ASOF JOIN condition must consist of equal keys and a `left.ts >= right.ts` \
+                 comparison, have: {expr}"
+            ))
+        };
+        let mut timestamps = None;
+        for expr in conjuncts {
+            let Expr::BinaryExpr(BinaryExpr { left: lhs, op, right: rhs }) = expr else {
+                return Err(invalid_condition(expr));
+            };
+            let (Expr::Column(lhs), Expr::Column(rhs)) = (lhs.as_ref(), rhs.as_ref()) else {
+                return Err(invalid_condition(expr));
+            };
+            // Orients the columns as (left column, right column).
+            let (left_column, right_column, op) =
+                if left.schema().has_column(lhs) && right.schema().has_column(rhs) {
+                    (lhs, rhs, *op)
+                } else if left.schema().has_column(rhs) && right.schema().has_column(lhs) {
+                    let op = match op {
+                        Operator::GtEq => Operator::LtEq,
+                        Operator::LtEq => Operator::GtEq,
+                        op => *op,
+                    };
+                    (rhs, lhs, op)
+                } else {
+                    return Err(invalid_condition(expr));
+                };
+            match op {
+                Operator::Eq => on.push((left_column.clone(), right_column.clone())),
+                Operator::GtEq if timestamps.is_none() => {
+                    timestamps = Some((left_column.clone(), right_column.clone()))
+                }
+                _ => return Err(invalid_condition(expr)),
+            }
+        }
+        let Some((left_timestamp, right_timestamp)) = timestamps else {
+            return Err(invalid_condition(condition));
+        };
+
+        let schema = Arc::new(left.schema().join(right.schema())?);
+        Ok(Some(Self {
+            left: left.clone(),
+            right: right.clone(),
+            on,
+            left_timestamp,
+            right_timestamp,
+            schema,
+        }))
+    }
+}
+
+impl UserDefinedLogicalNode for AsofJoin {
+    fn as_any(&self) -> &dyn Any {
+        self as _
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.left, &self.right]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.on
+            .iter()
+            .chain([(self.left_timestamp.clone(), self.right_timestamp.clone())].iter())
+            .flat_map(|(left, right)| [Expr::Column(left.clone()), Expr::Column(right.clone())])
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(left, right)| format!("{left} = {right}"))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "AsofJoin: on=[{}], timestamp=[{} >= {}]",
+            on.join(", "),
+            self.left_timestamp,
+            self.right_timestamp
+        )
+    }
+
+    fn from_template(
+        &self,
+        exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode> {
+        assert_eq!(2, inputs.len());
+
+        let mut columns = exprs.iter().filter_map(|expr| match expr {
+            Expr::Column(column) => Some(column.clone()),
+            _ => None,
+        });
+        let mut on = Vec::with_capacity(self.on.len());
+        for (left, right) in &self.on {
+            on.push((
+                columns.next().unwrap_or_else(|| left.clone()),
+                columns.next().unwrap_or_else(|| right.clone()),
+            ));
+        }
+        let left_timestamp = columns
+            .next()
+            .unwrap_or_else(|| self.left_timestamp.clone());
+        let right_timestamp = columns
+            .next()
+            .unwrap_or_else(|| self.right_timestamp.clone());
+        let schema = inputs[0]
+            .schema()
+            .join(inputs[1].schema())
+            .map(Arc::new)
+            .unwrap_or_else(|_| self.schema.clone());
+
+        Arc::new(Self {
+            left: inputs[0].clone(),
+            right: inputs[1].clone(),
+            on,
+            left_timestamp,
+            right_timestamp,
+            schema,
+        })
+    }
+}
+
+/// Plans [AsofJoin] nodes to [AsofJoinExec].
+pub struct AsofJoinPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for AsofJoinPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(node) = node.as_any().downcast_ref::<AsofJoin>() else {
+            return Ok(None);
+        };
+        let (left, right) = (logical_inputs[0].schema(), logical_inputs[1].schema());
+        let on = node
+            .on
+            .iter()
+            .map(|(l, r)| Ok((left.index_of_column(l)?, right.index_of_column(r)?)))
+            .collect::<DfResult<Vec<_>>>()?;
+        let timestamps = (
+            left.index_of_column(&node.left_timestamp)?,
+            right.index_of_column(&node.right_timestamp)?,
+        );
+
+        Ok(Some(Arc::new(AsofJoinExec::new(
+            physical_inputs[0].clone(),
+            physical_inputs[1].clone(),
+            on,
+            timestamps,
+        ))))
+    }
+}
+
+/// Coalesces the partitions of `plan` if it has more than one partition.
+fn coalesce_partitions(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+    if plan.output_partitioning().partition_count() > 1 {
+        Arc::new(CoalescePartitionsExec::new(plan))
+    } else {
+        plan
+    }
+}
+
+/// Executes the ASOF join by collecting the right input and indexing its rows by the join
+/// keys, then looking up the index for each row of the left input.
+#[derive(Debug)]
+pub struct AsofJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    /// Indices of the equal join keys, in pairs of (left column, right column).
+    on: Vec<(usize, usize)>,
+    /// Indices of the (left, right) timestamp columns.
+    timestamps: (usize, usize),
+    schema: SchemaRef,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl AsofJoinExec {
+    pub fn new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: Vec<(usize, usize)>,
+        timestamps: (usize, usize),
+    ) -> Self {
+        let (left, right) = (coalesce_partitions(left), coalesce_partitions(right));
+        let (left_schema, right_schema) = (left.schema(), right.schema());
+        let fields = left_schema
+            .fields()
+            .iter()
+            .chain(right_schema.fields())
+            .cloned()
+            .collect();
+        Self {
+            left,
+            right,
+            on,
+            timestamps,
+            schema: Arc::new(Schema::new(fields)),
+            metric: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for AsofJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(2, children.len());
+        Ok(Arc::new(Self::new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            self.timestamps,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let left = self.left.execute(0, context.clone())?;
+        let right = self.right.execute(0, context)?;
+        Ok(Box::pin(AsofJoinStream {
+            schema: self.schema.clone(),
+            on: self.on.clone(),
+            timestamps: self.timestamps,
+            left,
+            right,
+            right_batches: Vec::new(),
+            right_index: None,
+            metric: BaselineMetrics::new(&self.metric, partition),
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "AsofJoinExec: on={:?}, timestamps={:?}",
+                self.on, self.timestamps
+            ),
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Rows of the right input, indexed by the join keys.
+struct RightIndex {
+    batch: RecordBatch,
+    /// (timestamp, row) of each series, sorted by timestamp.
+    series: HashMap<Vec<ScalarValue>, Vec<(i64, u32)>>,
+}
+
+impl RightIndex {
+    fn try_new(batch: RecordBatch, keys: &[usize], timestamp: usize) -> ArrowResult<Self> {
+        let row_keys = row_keys(&batch, keys)?;
+        let timestamps = timestamp_values(batch.column(timestamp))?;
+        let mut series: HashMap<_, Vec<_>> = HashMap::new();
+        for (row, key) in row_keys.into_iter().enumerate() {
+            if let Some(key) = key {
+                if !timestamps.is_null(row) {
+                    series
+                        .entry(key)
+                        .or_default()
+                        .push((timestamps.value(row), row as u32));
+                }
+            }
+        }
+        // The sort is stable, so the last of the rows with the same timestamp is matched.
+        series
+            .values_mut()
+            .for_each(|rows| rows.sort_by_key(|(ts, _)| *ts));
+        Ok(Self { batch, series })
+    }
+
+    /// Returns the latest row of the series `key` at or before `timestamp`.
+    fn find(&self, key: &[ScalarValue], timestamp: i64) -> Option<u32> {
+        let rows = self.series.get(key)?;
+        let index = rows.partition_point(|(ts, _)| *ts <= timestamp);
+        (index > 0).then(|| rows[index - 1].1)
+    }
+}
+
+/// Returns the values of the `columns` of each row, `None` if any of the values is null.
+fn row_keys(batch: &RecordBatch, columns: &[usize]) -> ArrowResult<Vec<Option<Vec<ScalarValue>>>> {
+    (0..batch.num_rows())
+        .map(|row| {
+            let mut key = Vec::with_capacity(columns.len());
+            for column in columns {
+                let value = ScalarValue::try_from_array(batch.column(*column), row)
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                if value.is_null() {
+                    return Ok(None);
+                }
+                key.push(value);
+            }
+            Ok(Some(key))
+        })
+        .collect()
+}
+
+/// Casts timestamps to nanoseconds, so timestamps of different units are comparable.
+fn timestamp_values(array: &ArrayRef) -> ArrowResult<Int64Array> {
+    let array = match array.data_type() {
+        DataType::Timestamp(_, _) => {
+            compute::cast(array, &DataType::Timestamp(TimeUnit::Nanosecond, None))?
+        }
+        _ => array.clone(),
+    };
+    let array = compute::cast(&array, &DataType::Int64)?;
+    Ok(array
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .expect("casted to int64")
+        .clone())
+}
+
+pub(crate) fn concat_batches(
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+) -> ArrowResult<RecordBatch> {
+    if batches.is_empty() {
+        return Ok(RecordBatch::new_empty(schema));
+    }
+    let columns = (0..schema.fields().len())
+        .map(|i| {
+            let arrays = batches
+                .iter()
+                .map(|batch| batch.column(i).as_ref())
+                .collect::<Vec<_>>();
+            compute::concat(&arrays)
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    RecordBatch::try_new(schema, columns)
+}
+
+struct AsofJoinStream {
+    schema: SchemaRef,
+    on: Vec<(usize, usize)>,
+    timestamps: (usize, usize),
+    left: SendableRecordBatchStream,
+    right: SendableRecordBatchStream,
+    right_batches: Vec<RecordBatch>,
+    /// Built once the right input is exhausted.
+    right_index: Option<RightIndex>,
+    metric: BaselineMetrics,
+}
+
+impl RecordBatchStream for AsofJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for AsofJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.right_index.is_none() {
+            while let Some(batch) = ready!(this.right.poll_next_unpin(cx)) {
+                match batch {
+                    Ok(batch) => this.right_batches.push(batch),
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                }
+            }
+            let _timer = this.metric.elapsed_compute().timer();
+            let batches = std::mem::take(&mut this.right_batches);
+            let keys = this.on.iter().map(|(_, right)| *right).collect::<Vec<_>>();
+            let index = concat_batches(this.right.schema(), &batches)
+                .and_then(|batch| RightIndex::try_new(batch, &keys, this.timestamps.1));
+            match index {
+                Ok(index) => this.right_index = Some(index),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+
+        let poll = match this.left.poll_next_unpin(cx) {
+            Poll::Ready(batch) => {
+                let _timer = this.metric.elapsed_compute().timer();
+                Poll::Ready(batch.map(|batch| batch.and_then(|batch| this.join(batch))))
+            }
+            Poll::Pending => Poll::Pending,
+        };
+        this.metric.record_poll(poll)
+    }
+}
+
+impl AsofJoinStream {
+    fn join(&self, left: RecordBatch) -> ArrowResult<RecordBatch> {
+        // The right index is built before polling the left input.
+        let right = self.right_index.as_ref().unwrap();
+        let keys = self.on.iter().map(|(left, _)| *left).collect::<Vec<_>>();
+        let row_keys = row_keys(&left, &keys)?;
+        let timestamps = timestamp_values(left.column(self.timestamps.0))?;
+
+        let mut left_rows = Vec::with_capacity(left.num_rows());
+        let mut right_rows = Vec::with_capacity(left.num_rows());
+        for (row, key) in row_keys.iter().enumerate() {
+            let Some(key) = key else {
+                continue;
+            };
+            if timestamps.is_null(row) {
+                continue;
+            }
+            if let Some(right_row) = right.find(key, timestamps.value(row)) {
+                left_rows.push(row as u32);
+                right_rows.push(right_row);
+            }
+        }
+
+        let (left_rows, right_rows) = (UInt32Array::from(left_rows), UInt32Array::from(right_rows));
+        let columns = left
+            .columns()
+            .iter()
+            .map(|array| compute::take(array, &left_rows, None))
+            .chain(
+                right
+                    .batch
+                    .columns()
+                    .iter()
+                    .map(|array| compute::take(array, &right_rows, None)),
+            )
+            .collect::<ArrowResult<Vec<_>>>()?;
+        RecordBatch::try_new(self.schema.clone(), columns)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampMillisecondArray};
+    use datafusion::arrow::datatypes::Field;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+    use datafusion_expr::logical_plan::{table_scan, CrossJoin, Filter};
+    use datafusion_expr::{col, ScalarUDF};
+
+    use super::*;
+
+    fn scan(name: &str) -> LogicalPlan {
+        table_scan(
+            Some(name),
+            &Schema::new(vec![
+                Field::new("host", DataType::Utf8, true),
+                Field::new("ts", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+            ]),
+            None,
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+    }
+
+    /// Plans `left ASOF JOIN right ON left.ts >= right.ts` as the SQL planner does for joins
+    /// without equal conditions.
+    fn filtered_cross_join(left: LogicalPlan, right: &str, left_ts: &str) -> LogicalPlan {
+        let right_ts = format!("{right}.ts");
+        let right = scan(right);
+        let schema = Arc::new(left.schema().join(right.schema()).unwrap());
+        let cross_join = LogicalPlan::CrossJoin(CrossJoin {
+            left: Arc::new(left),
+            right: Arc::new(right),
+            schema,
+        });
+        let marker = Expr::ScalarUDF {
+            fun: Arc::new(ScalarUDF::from(asof_join_marker().into_df_udf())),
+            args: vec![],
+        };
+        let predicate = marker.and(col(left_ts).gt_eq(col(&right_ts)));
+        LogicalPlan::Filter(Filter::try_new(predicate, Arc::new(cross_join)).unwrap())
+    }
+
+    fn as_asof_join(plan: &LogicalPlan) -> &AsofJoin {
+        let LogicalPlan::Extension(extension) = plan else {
+            panic!("expect an ASOF join, have: {plan:?}")
+        };
+        extension.node.as_any().downcast_ref::<AsofJoin>().unwrap()
+    }
+
+    #[test]
+    fn test_rewrite_nested_filtered_cross_joins() {
+        let inner = filtered_cross_join(scan("a"), "b", "a.ts");
+        let plan = filtered_cross_join(inner, "c", "a.ts");
+
+        let plan = rewrite_asof_joins(&plan).unwrap();
+        let outer = as_asof_join(&plan);
+        assert_eq!("c.ts", outer.right_timestamp.flat_name());
+        let inner = as_asof_join(&outer.left);
+        assert_eq!("a.ts", inner.left_timestamp.flat_name());
+        assert_eq!("b.ts", inner.right_timestamp.flat_name());
+    }
+
+    fn new_memory_exec(
+        value_column: &str,
+        batches: Vec<(Vec<&str>, Vec<i64>, Vec<f64>)>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("ts", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+            Field::new(value_column, DataType::Float64, true),
+        ]));
+        let batches = batches
+            .into_iter()
+            .map(|(hosts, timestamps, values)| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(StringArray::from(hosts)) as _,
+                        Arc::new(TimestampMillisecondArray::from(timestamps)) as _,
+                        Arc::new(Float64Array::from(values)) as _,
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_exec() {
+        let left = new_memory_exec(
+            "cpu",
+            vec![
+                (vec!["a", "a", "b"], vec![5, 15, 15], vec![1.0, 2.0, 3.0]),
+                (vec!["a", "c"], vec![30, 30], vec![4.0, 5.0]),
+            ],
+        );
+        let right = new_memory_exec(
+            "version",
+            vec![
+                (vec!["a", "b"], vec![10, 20], vec![1.0, 1.0]),
+                (vec!["a", "a"], vec![20, 20], vec![2.0, 3.0]),
+            ],
+        );
+        let exec = Arc::new(AsofJoinExec::new(left, right, vec![(0, 0)], (1, 1)));
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        // `a` at 5 is before any right row, `b` at 15 is before the row of `b` and `c` has
+        // no right row. Rows of `a` at 20 has the same timestamp, the last one is matched.
+        let expected = String::from(
+            "+------+-------------------------+-----+------+-------------------------+---------+\
+            \n| host | ts                      | cpu | host | ts                      | version |\
+            \n+------+-------------------------+-----+------+-------------------------+---------+\
+            \n| a    | 1970-01-01T00:00:00.015 | 2   | a    | 1970-01-01T00:00:00.010 | 1       |\
+            \n| a    | 1970-01-01T00:00:00.030 | 4   | a    | 1970-01-01T00:00:00.020 | 3       |\
+            \n+------+-------------------------+-----+------+-------------------------+---------+",
+        );
+        assert_eq!(expected, result_literal);
+    }
+}
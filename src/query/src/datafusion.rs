@@ -21,6 +21,7 @@ mod planner;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use catalog::resource_usage::ResourceAccountantRef;
 use catalog::CatalogListRef;
 use common_error::prelude::BoxedError;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
@@ -35,11 +36,14 @@ use common_telemetry::timer;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
 use datatypes::schema::Schema;
+use metrics::increment_counter;
 use promql::planner::PromPlanner;
 use promql_parser::parser::EvalStmt;
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
+use sql::statements::hint::Hints;
 use sql::statements::statement::Statement;
+use table::masking::MaskingPolicy;
 
 pub use crate::datafusion::catalog_adapter::DfCatalogListAdapter;
 pub use crate::datafusion::planner::DfContextProviderAdapter;
@@ -51,6 +55,7 @@ use crate::parser::QueryStatement;
 use crate::physical_optimizer::PhysicalOptimizer;
 use crate::physical_planner::PhysicalPlanner;
 use crate::plan::LogicalPlan;
+use crate::plan_cache::PlanCacheKey;
 use crate::planner::Planner;
 use crate::query_engine::{QueryEngineContext, QueryEngineState};
 use crate::{metric, QueryEngine};
@@ -67,12 +72,34 @@ impl DatafusionQueryEngine {
     }
 
     fn plan_sql_stmt(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
-        let context_provider = DfContextProviderAdapter::new(self.state.clone(), query_ctx);
-        let planner = DfPlanner::new(&context_provider);
-        planner
+        let cache_key = PlanCacheKey::try_new(&stmt, &query_ctx);
+        if let Some(key) = &cache_key {
+            let plan_cache = self.state.plan_cache();
+            if let Some(plan) = plan_cache.get(key, self.state.catalog_list()) {
+                increment_counter!(metric::METRIC_PLAN_CACHE_HIT);
+                return Ok(plan);
+            }
+            increment_counter!(metric::METRIC_PLAN_CACHE_MISS);
+        }
+
+        let hints = match &stmt {
+            Statement::Query(query) => query.hints.clone(),
+            Statement::Explain(explain) => explain.hints.clone(),
+            _ => Hints::default(),
+        };
+        let context_provider =
+            DfContextProviderAdapter::new(self.state.clone(), query_ctx).with_hints(hints);
+        let plan = DfPlanner::new(&context_provider)
             .statement_to_plan(stmt)
             .map_err(BoxedError::new)
-            .context(QueryPlanSnafu)
+            .context(QueryPlanSnafu)?;
+
+        if let (Some(key), Some(table_versions)) = (cache_key, context_provider.table_versions()) {
+            self.state
+                .plan_cache()
+                .insert(key, plan.clone(), table_versions);
+        }
+        Ok(plan)
     }
 
     // TODO(ruihang): test this method once parser is ready.
@@ -113,16 +140,21 @@ impl QueryEngine for DatafusionQueryEngine {
     }
 
     async fn execute(&self, plan: &LogicalPlan) -> Result<Output> {
-        let mut ctx = QueryEngineContext::new(self.state.clone());
-        let logical_plan = self.optimize_logical_plan(&mut ctx, plan)?;
-        let physical_plan = self.create_physical_plan(&mut ctx, &logical_plan).await?;
-        let physical_plan = self.optimize_physical_plan(&mut ctx, physical_plan)?;
+        let ctx = QueryEngineContext::new(self.state.clone());
+        let physical_plan = self.to_physical_plan(plan).await?;
 
         Ok(Output::Stream(
             self.execute_stream(&ctx, &physical_plan).await?,
         ))
     }
 
+    async fn to_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn PhysicalPlan>> {
+        let mut ctx = QueryEngineContext::new(self.state.clone());
+        let logical_plan = self.optimize_logical_plan(&mut ctx, plan)?;
+        let physical_plan = self.create_physical_plan(&mut ctx, &logical_plan).await?;
+        self.optimize_physical_plan(&mut ctx, physical_plan)
+    }
+
     async fn execute_physical(&self, plan: &Arc<dyn PhysicalPlan>) -> Result<Output> {
         let ctx = QueryEngineContext::new(self.state.clone());
         Ok(Output::Stream(self.execute_stream(&ctx, plan).await?))
@@ -146,6 +178,14 @@ impl QueryEngine for DatafusionQueryEngine {
     fn register_function(&self, func: FunctionRef) {
         self.state.register_udf(create_udf(func));
     }
+
+    fn register_resource_accountant(&self, accountant: ResourceAccountantRef) {
+        self.state.register_resource_accountant(accountant);
+    }
+
+    fn register_masking_policies(&self, policies: Vec<MaskingPolicy>) {
+        self.state.register_masking_policies(policies);
+    }
 }
 
 impl LogicalOptimizer for DatafusionQueryEngine {
@@ -289,10 +329,11 @@ mod tests {
     use session::context::QueryContext;
     use table::table::numbers::NumbersTable;
 
+    use super::*;
     use crate::parser::QueryLanguageParser;
     use crate::query_engine::{QueryEngineFactory, QueryEngineRef};
 
-    fn create_test_engine() -> QueryEngineRef {
+    fn create_test_catalog_list() -> (CatalogListRef, Arc<MemorySchemaProvider>) {
         let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
 
         let default_schema = Arc::new(MemorySchemaProvider::new());
@@ -307,9 +348,60 @@ mod tests {
             .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)
             .unwrap();
 
+        (catalog_list, default_schema)
+    }
+
+    fn create_test_engine() -> QueryEngineRef {
+        let (catalog_list, _) = create_test_catalog_list();
         QueryEngineFactory::new(catalog_list).query_engine()
     }
 
+    #[test]
+    fn test_plan_cache() {
+        let (catalog_list, default_schema) = create_test_catalog_list();
+        let engine = DatafusionQueryEngine::new(catalog_list.clone());
+        let query_ctx = Arc::new(QueryContext::new());
+        let QueryStatement::Sql(stmt) =
+            QueryLanguageParser::parse_sql("select number from numbers").unwrap() else {
+            unreachable!()
+        };
+        let key = PlanCacheKey::try_new(&stmt, &query_ctx).unwrap();
+        let cached_plan = || engine.state.plan_cache().get(&key, &catalog_list);
+        assert!(cached_plan().is_none());
+
+        let plan = engine
+            .statement_to_plan(QueryStatement::Sql(stmt.clone()), query_ctx.clone())
+            .unwrap();
+        assert_eq!(format!("{plan:?}"), format!("{:?}", cached_plan().unwrap()));
+
+        // The same statement with different whitespaces and keyword cases.
+        let QueryStatement::Sql(same_stmt) =
+            QueryLanguageParser::parse_sql("SELECT number\n  FROM numbers").unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(key, PlanCacheKey::try_new(&same_stmt, &query_ctx).unwrap());
+
+        // Plans are planned with the hints.
+        let hinted_key = |sql| {
+            let QueryStatement::Sql(stmt) = QueryLanguageParser::parse_sql(sql).unwrap() else {
+                unreachable!()
+            };
+            PlanCacheKey::try_new(&stmt, &query_ctx)
+        };
+        assert!(hinted_key("select /*+ NO_CACHE */ number from numbers").is_none());
+        assert_ne!(
+            key,
+            hinted_key("select /*+ MAX_SCAN_ROWS(10) */ number from numbers").unwrap()
+        );
+
+        // Recreating the table invalidates the plan.
+        default_schema.deregister_table("numbers").unwrap();
+        default_schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::new(1024)))
+            .unwrap();
+        assert!(cached_plan().is_none());
+    }
+
     #[test]
     fn test_sql_to_plan() {
         let engine = create_test_engine();
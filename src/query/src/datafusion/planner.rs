@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use common_error::prelude::BoxedError;
 use common_query::logical_plan::create_aggregate_function;
@@ -28,40 +28,61 @@ use datatypes::prelude::DataType as DataTypeTrait;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
 use sql::statements::explain::Explain;
-use sql::statements::query::Query;
+use sql::statements::hint::Hints;
+use sql::statements::query::{Query, ASOF_JOIN_MARKER};
 use sql::statements::statement::Statement;
 
+use crate::asof_join::rewrite_asof_joins;
 use crate::datafusion::error;
 use crate::error::{QueryPlanSnafu, Result};
+use crate::lateral_join::{has_lateral, plan_lateral_query, rewrite_scalar_subqueries};
 use crate::plan::LogicalPlan;
+use crate::plan_cache::TableVersion;
 use crate::planner::Planner;
 use crate::query_engine::QueryEngineState;
+use crate::table_function::{has_table_function, plan_table_function_query};
 
 pub struct DfPlanner<'a, S: ContextProvider> {
     sql_to_rel: SqlToRel<'a, S>,
+    schema_provider: &'a S,
 }
 
 impl<'a, S: ContextProvider + Send + Sync> DfPlanner<'a, S> {
     /// Creates a DataFusion planner instance
     pub fn new(schema_provider: &'a S) -> Self {
         let rel = SqlToRel::new(schema_provider);
-        Self { sql_to_rel: rel }
+        Self {
+            sql_to_rel: rel,
+            schema_provider,
+        }
     }
 
     /// Converts QUERY statement to logical plan.
     pub fn query_to_plan(&self, query: Box<Query>) -> Result<LogicalPlan> {
         // todo(hl): original SQL should be provided as an argument
         let sql = query.inner.to_string();
-        let mut context = PlannerContext::new_with_prepare_param_data_types(
-            query
-                .param_types()
-                .iter()
-                .map(|v| v.as_arrow_type())
-                .collect(),
-        );
-        let result = self
-            .sql_to_rel
-            .query_to_plan(query.inner, &mut context)
+        let param_types = query
+            .param_types()
+            .iter()
+            .map(|v| v.as_arrow_type())
+            .collect::<Vec<_>>();
+        let result = if has_lateral(&query.inner) {
+            plan_lateral_query(self.schema_provider, query.inner, &param_types)
+        } else if has_table_function(&query.inner) {
+            plan_table_function_query(self.schema_provider, query.inner, &param_types)
+        } else {
+            let mut context = PlannerContext::new_with_prepare_param_data_types(param_types);
+            self.sql_to_rel.query_to_plan(query.inner, &mut context)
+        };
+        let result = result
+            .and_then(rewrite_scalar_subqueries)
+            .and_then(|plan| {
+                if sql.contains(ASOF_JOIN_MARKER) {
+                    rewrite_asof_joins(&plan)
+                } else {
+                    Ok(plan)
+                }
+            })
             .context(error::PlanSqlSnafu { sql })
             .map_err(BoxedError::new)
             .context(QueryPlanSnafu)?;
@@ -94,15 +115,27 @@ where
             Statement::Query(qb) => self.query_to_plan(qb),
             Statement::Explain(explain) => self.explain_to_plan(explain),
             Statement::ShowTables(_)
+            | Statement::ShowTableStatus(_)
             | Statement::ShowDatabases(_)
             | Statement::ShowCreateTable(_)
             | Statement::DescribeTable(_)
             | Statement::CreateTable(_)
+            | Statement::CreateCatalog(_)
             | Statement::CreateDatabase(_)
             | Statement::Alter(_)
+            | Statement::AlterDatabase(_)
             | Statement::Insert(_)
             | Statement::DropTable(_)
-            | Statement::Use(_) => unreachable!(),
+            | Statement::UndropTable(_)
+            | Statement::Use(_)
+            | Statement::ShowProcesslist(_)
+            | Statement::Kill(_)
+            | Statement::CreateAlertRule(_)
+            | Statement::DropAlertRule(_)
+            | Statement::ShowAlertRules
+            | Statement::AnalyzeTable(_)
+            | Statement::CheckTable(_)
+            | Statement::ExplainDdl(_) => unreachable!(),
         }
     }
 }
@@ -110,17 +143,53 @@ where
 pub struct DfContextProviderAdapter {
     state: QueryEngineState,
     query_ctx: QueryContextRef,
+    /// Hints of the query being planned.
+    hints: Hints,
+    /// Versions of the tables provided, `None` if any provided table is not versioned.
+    table_versions: Mutex<Option<Vec<TableVersion>>>,
 }
 
 impl DfContextProviderAdapter {
     pub fn new(state: QueryEngineState, query_ctx: QueryContextRef) -> Self {
-        Self { state, query_ctx }
+        Self {
+            state,
+            query_ctx,
+            hints: Hints::default(),
+            table_versions: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    /// Plans the query with its `hints`.
+    pub fn with_hints(mut self, hints: Hints) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Returns versions of the tables provided to the planner, `None` if the plan can't
+    /// be validated against them.
+    pub(crate) fn table_versions(self) -> Option<Vec<TableVersion>> {
+        self.table_versions.into_inner().unwrap()
     }
 }
 
 impl ContextProvider for DfContextProviderAdapter {
     fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
-        self.state.get_table_provider(self.query_ctx.clone(), name)
+        let source = self.state.get_table_provider(
+            self.query_ctx.clone(),
+            name,
+            self.hints.max_scan_rows,
+        )?;
+        let mut table_versions = self.table_versions.lock().unwrap();
+        // Plans reading tables without versions are not cached.
+        match QueryEngineState::source_table(&source).and_then(|table| TableVersion::of(&table)) {
+            Some(version) => {
+                if let Some(versions) = table_versions.as_mut() {
+                    versions.push(version);
+                }
+            }
+            None => *table_versions = None,
+        }
+        Ok(source)
     }
 
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
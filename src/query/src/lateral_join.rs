@@ -0,0 +1,1180 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lateral joins and correlated subqueries.
+//!
+//! DataFusion plans subqueries referencing columns of the outer query, but can only execute
+//! the ones it decorrelates to joins. [LateralJoin] evaluates such subqueries for each row of
+//! its input, with the outer columns bound to the values of the row, so that time range
+//! lookups like "metrics within 5 minutes of each incident" can be expressed in SQL:
+//! - `FROM a, LATERAL (SELECT ...) x` and `FROM a [LEFT] JOIN LATERAL (SELECT ...) x ON ...` in
+//!   the top level query are planned by [plan_lateral_query()];
+//! - correlated scalar subqueries in projections, e.g. `SELECT (SELECT max(m.cpu) FROM metrics m
+//!   WHERE m.ts <= i.ts) FROM incidents i`, are rewritten by [rewrite_scalar_subqueries()],
+//!   which decorrelates the subqueries correlated by equalities to joins with aggregations.
+//!
+//! Outer columns can only be referenced in the WHERE clause of the subqueries.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{new_null_array, ArrayRef, UInt32Array};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::{ArrowError, Result as ArrowResult};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::TableReference;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::planner::ExtensionPlanner;
+use datafusion::physical_plan::udaf::AggregateUDF;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalPlanner, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion::sql::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion::sql::sqlparser::ast::{
+    Expr as SqlExpr, Ident, ObjectName, Query as SpQuery, Select, SelectItem, SetExpr, TableAlias,
+    TableFactor, TableWithJoins, WildcardAdditionalOptions, With,
+};
+use datafusion_common::{Column, DFSchema, DFSchemaRef, DataFusionError, ScalarValue};
+use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
+use datafusion_expr::expr_visitor::{ExprVisitable, ExpressionVisitor, Recursion};
+use datafusion_expr::logical_plan::builder::build_join_schema;
+use datafusion_expr::utils::{expr_to_columns, from_plan};
+use datafusion_expr::{
+    AggregateFunction, BinaryExpr, Expr, Extension, Filter, JoinType, LogicalPlan,
+    LogicalPlanBuilder, Operator, Subquery, TableSource, UserDefinedLogicalNode,
+};
+use datatypes::arrow::compute;
+use datatypes::arrow::datatypes::DataType;
+use futures::{Stream, StreamExt};
+
+use crate::asof_join::{concat_batches, split_conjunction};
+
+/// Name prefix of the tables standing in for LATERAL subqueries while planning the query.
+const LATERAL_TABLE_PREFIX: &str = "__lateral_";
+/// Alias prefix of the scalar subqueries joined to their outer query.
+const SCALAR_SUBQUERY_PREFIX: &str = "__scalar_subquery_";
+/// Name prefix of the columns of decorrelated scalar subqueries to join on.
+const SCALAR_SUBQUERY_KEY_PREFIX: &str = "__key_";
+
+/// Returns whether the FROM clause of the top level SELECT in `query` has LATERAL subqueries.
+pub(crate) fn has_lateral(query: &SpQuery) -> bool {
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return false;
+    };
+    select.from.iter().any(|from| {
+        is_lateral(&from.relation) || from.joins.iter().any(|join| is_lateral(&join.relation))
+    })
+}
+
+fn is_lateral(relation: &TableFactor) -> bool {
+    matches!(relation, TableFactor::Derived { lateral: true, .. })
+}
+
+/// Plans `query` whose top level SELECT has LATERAL subqueries.
+///
+/// Each LATERAL subquery is planned in the scope of the FROM items before it, then replaced
+/// by a placeholder table, so the rest of the query is planned as usual. Joins with the
+/// placeholders are replaced by [LateralJoin]s afterwards.
+pub(crate) fn plan_lateral_query<S: ContextProvider>(
+    provider: &S,
+    mut query: SpQuery,
+    param_types: &[DataType],
+) -> DfResult<LogicalPlan> {
+    let provider = LateralContextProvider {
+        inner: provider,
+        tables: RefCell::new(HashMap::new()),
+        param_types,
+    };
+    let with = query.with.clone();
+    if let SetExpr::Select(select) = query.body.as_mut() {
+        for i in 0..select.from.len() {
+            if i > 0 && is_lateral(&select.from[i].relation) {
+                let preceding = select.from[..i].to_vec();
+                let relation = &mut select.from[i].relation;
+                *relation = provider.plan_lateral(with.clone(), preceding, relation.clone())?;
+            }
+            for j in 0..select.from[i].joins.len() {
+                if !is_lateral(&select.from[i].joins[j].relation) {
+                    continue;
+                }
+                let mut preceding = select.from[..i].to_vec();
+                preceding.push(TableWithJoins {
+                    relation: select.from[i].relation.clone(),
+                    joins: select.from[i].joins[..j].to_vec(),
+                });
+                let relation = &mut select.from[i].joins[j].relation;
+                *relation = provider.plan_lateral(with.clone(), preceding, relation.clone())?;
+            }
+        }
+    }
+
+    let plan = SqlToRel::new(&provider).query_to_plan(query, &mut provider.new_context())?;
+    rewrite_lateral_joins(&plan)
+}
+
+/// Resolves the placeholder tables of LATERAL subqueries to [LateralTable]s and other
+/// tables by the `inner` provider.
+struct LateralContextProvider<'a, S> {
+    inner: &'a S,
+    tables: RefCell<HashMap<String, Arc<LateralTable>>>,
+    param_types: &'a [DataType],
+}
+
+impl<'a, S: ContextProvider> LateralContextProvider<'a, S> {
+    fn new_context(&self) -> PlannerContext {
+        PlannerContext::new_with_prepare_param_data_types(self.param_types.to_vec())
+    }
+
+    /// Plans the LATERAL subquery `relation` in the scope of the `preceding` FROM items, and
+    /// returns the placeholder table to replace it.
+    fn plan_lateral(
+        &self,
+        with: Option<With>,
+        preceding: Vec<TableWithJoins>,
+        relation: TableFactor,
+    ) -> DfResult<TableFactor> {
+        let TableFactor::Derived { subquery, alias, .. } = relation else {
+            unreachable!("relation is checked to be a LATERAL subquery");
+        };
+        // DataFusion plans correlated subqueries in WHERE clauses, so the subquery is planned
+        // as `SELECT * FROM <preceding> WHERE EXISTS (<subquery>)`.
+        let select = Select {
+            distinct: false,
+            top: None,
+            projection: vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())],
+            into: None,
+            from: preceding,
+            lateral_views: vec![],
+            selection: Some(SqlExpr::Exists {
+                subquery,
+                negated: false,
+            }),
+            group_by: vec![],
+            cluster_by: vec![],
+            distribute_by: vec![],
+            sort_by: vec![],
+            having: None,
+            qualify: None,
+        };
+        let query = SpQuery {
+            with,
+            body: Box::new(SetExpr::Select(Box::new(select))),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            fetch: None,
+            lock: None,
+        };
+        let plan = SqlToRel::new(self).query_to_plan(query, &mut self.new_context())?;
+        let subquery = find_exists_subquery(&plan).ok_or_else(|| {
+            DataFusionError::Internal("LATERAL subquery is not planned as EXISTS".to_string())
+        })?;
+
+        let mut tables = self.tables.borrow_mut();
+        let name = format!("{LATERAL_TABLE_PREFIX}{}", tables.len());
+        let _ = tables.insert(name.clone(), Arc::new(LateralTable::new(subquery)));
+        let alias = alias.unwrap_or_else(|| TableAlias {
+            name: Ident::new(&name),
+            columns: vec![],
+        });
+        Ok(TableFactor::Table {
+            name: ObjectName(vec![Ident::new(name)]),
+            alias: Some(alias),
+            args: None,
+            with_hints: vec![],
+        })
+    }
+}
+
+impl<'a, S: ContextProvider> ContextProvider for LateralContextProvider<'a, S> {
+    fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
+        if let TableReference::Bare { table } = name {
+            if let Some(table) = self.tables.borrow().get(table) {
+                return Ok(table.clone());
+            }
+        }
+        self.inner.get_table_provider(name)
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.inner.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.inner.get_aggregate_meta(name)
+    }
+
+    fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
+        self.inner.get_variable_type(variable_names)
+    }
+
+    fn get_config_option(&self, variable: &str) -> Option<ScalarValue> {
+        self.inner.get_config_option(variable)
+    }
+}
+
+fn find_exists_subquery(plan: &LogicalPlan) -> Option<LogicalPlan> {
+    if let LogicalPlan::Filter(filter) = plan {
+        if let Expr::Exists { subquery, .. } = filter.predicate() {
+            return Some(subquery.subquery.as_ref().clone());
+        }
+    }
+    plan.inputs().into_iter().find_map(find_exists_subquery)
+}
+
+/// Placeholder table of a planned LATERAL subquery.
+struct LateralTable {
+    plan: LogicalPlan,
+    schema: SchemaRef,
+}
+
+impl LateralTable {
+    fn new(plan: LogicalPlan) -> Self {
+        let schema = Arc::new(Schema::new(
+            plan.schema()
+                .fields()
+                .iter()
+                .map(|field| field.field().clone())
+                .collect(),
+        ));
+        Self { plan, schema }
+    }
+}
+
+impl TableSource for LateralTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Replaces the joins with placeholder tables of LATERAL subqueries by [LateralJoin]s.
+fn rewrite_lateral_joins(plan: &LogicalPlan) -> DfResult<LogicalPlan> {
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(rewrite_lateral_joins)
+        .collect::<DfResult<Vec<_>>>()?;
+
+    let lateral_join = match plan {
+        LogicalPlan::CrossJoin(_) => match lateral_subquery(&inputs[1])? {
+            Some(subquery) => Some(LateralJoin::try_new(
+                inputs[0].clone(),
+                subquery,
+                false,
+                false,
+            )?),
+            None => None,
+        },
+        LogicalPlan::Join(join) => match lateral_subquery(&inputs[1])? {
+            Some(subquery) => {
+                let outer = match join.join_type {
+                    JoinType::Inner => false,
+                    JoinType::Left => true,
+                    join_type => {
+                        return Err(DataFusionError::Plan(format!(
+                            "LATERAL subquery can't be used with {join_type} JOIN"
+                        )))
+                    }
+                };
+                // The join condition is evaluated in the subquery, so the rows of the left
+                // input without any matching row are kept by LEFT JOINs.
+                let condition = join
+                    .on
+                    .iter()
+                    .map(|(l, r)| Expr::Column(l.clone()).eq(Expr::Column(r.clone())))
+                    .chain(join.filter.clone())
+                    .reduce(Expr::and);
+                let subquery = match condition {
+                    Some(condition) => {
+                        LogicalPlan::Filter(Filter::try_new(condition, Arc::new(subquery))?)
+                    }
+                    None => subquery,
+                };
+                Some(LateralJoin::try_new(
+                    inputs[0].clone(),
+                    subquery,
+                    outer,
+                    false,
+                )?)
+            }
+            None => None,
+        },
+        _ => None,
+    };
+
+    match lateral_join {
+        Some(lateral_join) => Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(lateral_join),
+        })),
+        None => from_plan(plan, &plan.expressions(), &inputs),
+    }
+}
+
+/// Returns the aliased subquery if `plan` scans a placeholder table of LATERAL subqueries.
+fn lateral_subquery(plan: &LogicalPlan) -> DfResult<Option<LogicalPlan>> {
+    let (scan, alias) = match plan {
+        LogicalPlan::SubqueryAlias(alias) => match alias.input.as_ref() {
+            LogicalPlan::TableScan(scan) => (scan, &alias.alias),
+            _ => return Ok(None),
+        },
+        LogicalPlan::TableScan(scan) => (scan, &scan.table_name),
+        _ => return Ok(None),
+    };
+    match scan.source.as_any().downcast_ref::<LateralTable>() {
+        Some(table) => LogicalPlanBuilder::from(table.plan.clone())
+            .alias(alias)?
+            .build()
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Replaces correlated scalar subqueries in projections of `plan` by columns of the
+/// subqueries joined to the input of the projections. Subqueries correlated by equalities
+/// are decorrelated to aggregations grouped by the correlated columns and joined by LEFT
+/// JOINs, other subqueries are evaluated for each input row by [LateralJoin]s.
+///
+/// Uncorrelated scalar subqueries are left to DataFusion.
+pub(crate) fn rewrite_scalar_subqueries(plan: LogicalPlan) -> DfResult<LogicalPlan> {
+    let mut next_alias = 0;
+    Ok(rewrite_projections(&plan, &mut next_alias)?.unwrap_or(plan))
+}
+
+/// Returns the rewritten `plan`, `None` if there is nothing to rewrite.
+fn rewrite_projections(
+    plan: &LogicalPlan,
+    next_alias: &mut usize,
+) -> DfResult<Option<LogicalPlan>> {
+    let mut rewritten = false;
+    let mut inputs = Vec::with_capacity(plan.inputs().len());
+    for input in plan.inputs() {
+        match rewrite_projections(input, next_alias)? {
+            Some(input) => {
+                rewritten = true;
+                inputs.push(input);
+            }
+            None => inputs.push(input.clone()),
+        }
+    }
+
+    let LogicalPlan::Projection(projection) = plan else {
+        return if rewritten {
+            from_plan(plan, &plan.expressions(), &inputs).map(Some)
+        } else {
+            Ok(None)
+        };
+    };
+    let outer_schema = inputs[0].schema().clone();
+    if !projection
+        .expr
+        .iter()
+        .any(|expr| has_correlated_subquery(expr, &outer_schema))
+    {
+        return if rewritten {
+            from_plan(plan, &plan.expressions(), &inputs).map(Some)
+        } else {
+            Ok(None)
+        };
+    }
+
+    let mut rewriter = ScalarSubqueryRewriter {
+        next_alias,
+        outer_schema: &outer_schema,
+        subqueries: Vec::new(),
+    };
+    let mut exprs = Vec::with_capacity(projection.expr.len());
+    for expr in &projection.expr {
+        if !has_correlated_subquery(expr, &outer_schema) {
+            exprs.push(expr.clone());
+            continue;
+        }
+        let name = expr.display_name()?;
+        // Keeps the output names of the projection.
+        exprs.push(match expr.clone().rewrite(&mut rewriter)? {
+            expr @ Expr::Alias(_, _) => expr,
+            expr => expr.alias(name),
+        });
+    }
+    let mut input = inputs.remove(0);
+    for subquery in rewriter.subqueries {
+        input = match subquery {
+            CorrelatedSubquery::Join {
+                plan,
+                outer_keys,
+                keys,
+            } => LogicalPlanBuilder::from(input)
+                .join(plan, JoinType::Left, (outer_keys, keys), None)?
+                .build()?,
+            CorrelatedSubquery::Lateral(plan) => LogicalPlan::Extension(Extension {
+                node: Arc::new(LateralJoin::try_new(input, plan, true, true)?),
+            }),
+        };
+    }
+    from_plan(plan, &exprs, &[input]).map(Some)
+}
+
+/// Returns whether `expr` has scalar subqueries referencing columns of `outer_schema`.
+fn has_correlated_subquery(expr: &Expr, outer_schema: &DFSchema) -> bool {
+    expr.accept(ScalarSubqueryFinder {
+        outer_schema,
+        found: false,
+    })
+    .map(|finder| finder.found)
+    .unwrap_or(false)
+}
+
+fn is_correlated(subquery: &LogicalPlan, outer_schema: &DFSchema) -> bool {
+    let mut columns = HashSet::new();
+    collect_outer_columns(subquery, outer_schema, &mut columns);
+    !columns.is_empty()
+}
+
+struct ScalarSubqueryFinder<'a> {
+    outer_schema: &'a DFSchema,
+    found: bool,
+}
+
+impl<'a> ExpressionVisitor for ScalarSubqueryFinder<'a> {
+    fn pre_visit(mut self, expr: &Expr) -> DfResult<Recursion<Self>> {
+        if let Expr::ScalarSubquery(Subquery { subquery }) = expr {
+            if is_correlated(subquery, self.outer_schema) {
+                self.found = true;
+                return Ok(Recursion::Stop(self));
+            }
+        }
+        Ok(Recursion::Continue(self))
+    }
+}
+
+/// A correlated scalar subquery to join with its outer query.
+enum CorrelatedSubquery {
+    /// The decorrelated subquery, joined by a LEFT JOIN on the `outer_keys` and the `keys`
+    /// of the subquery.
+    Join {
+        plan: LogicalPlan,
+        outer_keys: Vec<Column>,
+        keys: Vec<Column>,
+    },
+    /// The subquery evaluated for each outer row.
+    Lateral(LogicalPlan),
+}
+
+/// Replaces correlated scalar subqueries by the column of the aliased subqueries.
+struct ScalarSubqueryRewriter<'a> {
+    next_alias: &'a mut usize,
+    outer_schema: &'a DFSchema,
+    subqueries: Vec<CorrelatedSubquery>,
+}
+
+impl<'a> ExprRewriter for ScalarSubqueryRewriter<'a> {
+    fn mutate(&mut self, expr: Expr) -> DfResult<Expr> {
+        let subquery = match expr {
+            Expr::ScalarSubquery(Subquery { subquery })
+                if is_correlated(&subquery, self.outer_schema) =>
+            {
+                subquery
+            }
+            expr => return Ok(expr),
+        };
+        let fields = subquery.schema().fields();
+        if fields.len() != 1 {
+            return Err(DataFusionError::Plan(format!(
+                "Scalar subquery must return exactly one column, have: {}",
+                fields.len()
+            )));
+        }
+        let name = fields[0].name().clone();
+        let alias = format!("{SCALAR_SUBQUERY_PREFIX}{}", self.next_alias);
+        *self.next_alias += 1;
+        let subquery = match decorrelate(&subquery, self.outer_schema)? {
+            Some((plan, outer_keys, keys)) => CorrelatedSubquery::Join {
+                plan: LogicalPlanBuilder::from(plan).alias(&alias)?.build()?,
+                outer_keys,
+                keys: keys
+                    .into_iter()
+                    .map(|key| Column::new(Some(alias.clone()), key))
+                    .collect(),
+            },
+            None => CorrelatedSubquery::Lateral(
+                LogicalPlanBuilder::from(subquery.as_ref().clone())
+                    .alias(&alias)?
+                    .build()?,
+            ),
+        };
+        self.subqueries.push(subquery);
+        Ok(Expr::Column(Column::new(Some(alias), name)))
+    }
+}
+
+/// Decorrelates the scalar `subquery` aggregating rows filtered by equalities with outer
+/// columns, e.g. `SELECT max(m.cpu) FROM metrics m WHERE m.host = i.host`, to the
+/// aggregation grouped by the inner columns of the equalities. Returns the decorrelated
+/// plan, the outer columns and the names of the columns of the plan to join them with,
+/// or `None` if the subquery can't be decorrelated.
+///
+/// Outer rows without any matching group are joined with NULLs, so only aggregations
+/// returning NULL on empty input are decorrelated, e.g. not `count()`.
+fn decorrelate(
+    subquery: &LogicalPlan,
+    outer_schema: &DFSchema,
+) -> DfResult<Option<(LogicalPlan, Vec<Column>, Vec<String>)>> {
+    let (exprs, aggregate) = match subquery {
+        LogicalPlan::Projection(projection) => (Some(&projection.expr), projection.input.as_ref()),
+        plan => (None, plan),
+    };
+    let LogicalPlan::Aggregate(aggregate) = aggregate else {
+        return Ok(None);
+    };
+    // Projections like `coalesce(max(a), 0)` don't keep NULLs of empty groups.
+    let keeps_nulls = exprs.map_or(true, |exprs| exprs.iter().all(is_column));
+    if !aggregate.group_expr.is_empty()
+        || !aggregate.aggr_expr.iter().all(is_null_on_empty)
+        || !keeps_nulls
+    {
+        return Ok(None);
+    }
+    let LogicalPlan::Filter(filter) = aggregate.input.as_ref() else {
+        return Ok(None);
+    };
+
+    let local_schemas = [filter.input.schema()];
+    let is_outer = |column: &Column| {
+        !is_local_column(column, &local_schemas) && outer_schema.has_column(column)
+    };
+    let mut conjuncts = Vec::new();
+    split_conjunction(filter.predicate(), &mut conjuncts);
+    let mut outer_keys = Vec::new();
+    let mut inner_keys = Vec::new();
+    let mut local_predicates = Vec::new();
+    for conjunct in conjuncts {
+        let mut columns = HashSet::new();
+        expr_to_columns(conjunct, &mut columns)?;
+        if !columns.iter().any(is_outer) {
+            local_predicates.push(conjunct.clone());
+            continue;
+        }
+        let Expr::BinaryExpr(BinaryExpr { left, op: Operator::Eq, right }) = conjunct else {
+            return Ok(None);
+        };
+        let (outer, inner) = match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(l), Expr::Column(r)) if is_outer(l) => (l, r),
+            (Expr::Column(l), Expr::Column(r)) if is_outer(r) => (r, l),
+            _ => return Ok(None),
+        };
+        if !is_local_column(inner, &local_schemas) {
+            return Ok(None);
+        }
+        outer_keys.push(outer.clone());
+        inner_keys.push(inner.clone());
+    }
+
+    let mut builder = LogicalPlanBuilder::from(filter.input.as_ref().clone());
+    if let Some(predicate) = local_predicates.into_iter().reduce(Expr::and) {
+        builder = builder.filter(predicate)?;
+    }
+    let plan = builder
+        .aggregate(
+            inner_keys.iter().cloned().map(Expr::Column),
+            aggregate.aggr_expr.clone(),
+        )?
+        .build()?;
+    // Outer columns referenced elsewhere, e.g. by the input of the filter.
+    if is_correlated(&plan, outer_schema) {
+        return Ok(None);
+    }
+
+    let exprs = match exprs {
+        Some(exprs) => exprs.clone(),
+        None => aggregate
+            .schema
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect(),
+    };
+    let keys = (0..inner_keys.len())
+        .map(|i| format!("{SCALAR_SUBQUERY_KEY_PREFIX}{i}"))
+        .collect::<Vec<_>>();
+    let key_exprs = inner_keys
+        .into_iter()
+        .zip(&keys)
+        .map(|(key, name)| Expr::Column(key).alias(name));
+    let plan = LogicalPlanBuilder::from(plan)
+        .project(exprs.into_iter().chain(key_exprs))?
+        .build()?;
+    Ok(Some((plan, outer_keys, keys)))
+}
+
+fn is_column(expr: &Expr) -> bool {
+    match expr {
+        Expr::Column(_) => true,
+        Expr::Alias(expr, _) => is_column(expr),
+        _ => false,
+    }
+}
+
+fn is_null_on_empty(expr: &Expr) -> bool {
+    match expr {
+        Expr::AggregateFunction { fun, .. } => matches!(
+            fun,
+            AggregateFunction::Min
+                | AggregateFunction::Max
+                | AggregateFunction::Sum
+                | AggregateFunction::Avg
+        ),
+        Expr::Alias(expr, _) => is_null_on_empty(expr),
+        _ => false,
+    }
+}
+
+/// Logical plan node that joins each row of the input with the rows of the subquery
+/// evaluated with the outer columns bound to the values of the row.
+#[derive(Debug)]
+pub struct LateralJoin {
+    input: LogicalPlan,
+    subquery: LogicalPlan,
+    /// Whether rows of the input are kept, with nulls, if the subquery returns no row.
+    outer: bool,
+    /// Whether the subquery must return at most one row.
+    single_row: bool,
+    schema: DFSchemaRef,
+}
+
+impl LateralJoin {
+    fn try_new(
+        input: LogicalPlan,
+        subquery: LogicalPlan,
+        outer: bool,
+        single_row: bool,
+    ) -> DfResult<Self> {
+        let schema = Self::join_schema(&input, &subquery, outer)?;
+        Ok(Self {
+            input,
+            subquery,
+            outer,
+            single_row,
+            schema,
+        })
+    }
+
+    fn join_schema(
+        input: &LogicalPlan,
+        subquery: &LogicalPlan,
+        outer: bool,
+    ) -> DfResult<DFSchemaRef> {
+        let join_type = if outer {
+            JoinType::Left
+        } else {
+            JoinType::Inner
+        };
+        build_join_schema(input.schema(), subquery.schema(), &join_type).map(Arc::new)
+    }
+}
+
+impl UserDefinedLogicalNode for LateralJoin {
+    fn as_any(&self) -> &dyn Any {
+        self as _
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    /// Returns the outer columns referenced by the subquery.
+    fn expressions(&self) -> Vec<Expr> {
+        let mut columns = HashSet::new();
+        collect_outer_columns(&self.subquery, self.input.schema(), &mut columns);
+        columns.into_iter().map(Expr::Column).collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LateralJoin: outer={}, single_row={}",
+            self.outer, self.single_row
+        )
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode> {
+        assert_eq!(1, inputs.len());
+
+        let schema = Self::join_schema(&inputs[0], &self.subquery, self.outer)
+            .unwrap_or_else(|_| self.schema.clone());
+        Arc::new(Self {
+            input: inputs[0].clone(),
+            subquery: self.subquery.clone(),
+            outer: self.outer,
+            single_row: self.single_row,
+            schema,
+        })
+    }
+}
+
+/// Returns whether `column` is resolved by the inputs of the plan whose input schemas
+/// are `local_schemas`.
+fn is_local_column(column: &Column, local_schemas: &[&DFSchemaRef]) -> bool {
+    local_schemas.iter().any(|schema| schema.has_column(column))
+}
+
+fn collect_outer_columns(
+    plan: &LogicalPlan,
+    outer_schema: &DFSchema,
+    columns: &mut HashSet<Column>,
+) {
+    let local_schemas = plan
+        .inputs()
+        .into_iter()
+        .map(|input| input.schema())
+        .collect::<Vec<_>>();
+    let mut referenced = HashSet::new();
+    for expr in plan.expressions() {
+        let _ = expr_to_columns(&expr, &mut referenced);
+    }
+    columns.extend(referenced.into_iter().filter(|column| {
+        !is_local_column(column, &local_schemas) && outer_schema.has_column(column)
+    }));
+    for input in plan.inputs() {
+        collect_outer_columns(input, outer_schema, columns);
+    }
+}
+
+/// Binds the outer columns referenced by `plan` to the `values` of the row of `outer_schema`.
+fn bind_outer_columns(
+    plan: &LogicalPlan,
+    outer_schema: &DFSchema,
+    values: &[ScalarValue],
+) -> DfResult<LogicalPlan> {
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(|input| bind_outer_columns(input, outer_schema, values))
+        .collect::<DfResult<Vec<_>>>()?;
+    let local_schemas = plan
+        .inputs()
+        .into_iter()
+        .map(|input| input.schema())
+        .collect::<Vec<_>>();
+    let mut binder = OuterColumnBinder {
+        local_schemas: &local_schemas,
+        outer_schema,
+        values,
+    };
+    let exprs = plan
+        .expressions()
+        .into_iter()
+        .map(|expr| expr.rewrite(&mut binder))
+        .collect::<DfResult<Vec<_>>>()?;
+    from_plan(plan, &exprs, &inputs)
+}
+
+struct OuterColumnBinder<'a> {
+    local_schemas: &'a [&'a DFSchemaRef],
+    outer_schema: &'a DFSchema,
+    values: &'a [ScalarValue],
+}
+
+impl<'a> ExprRewriter for OuterColumnBinder<'a> {
+    fn mutate(&mut self, expr: Expr) -> DfResult<Expr> {
+        let Expr::Column(column) = &expr else {
+            return Ok(expr);
+        };
+        if is_local_column(column, self.local_schemas) || !self.outer_schema.has_column(column) {
+            return Ok(expr);
+        }
+        let index = self.outer_schema.index_of_column(column)?;
+        Ok(Expr::Literal(self.values[index].clone()))
+    }
+}
+
+/// Plans [LateralJoin] nodes to [LateralJoinExec].
+pub struct LateralJoinPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for LateralJoinPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        session_state: &SessionState,
+    ) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(node) = node.as_any().downcast_ref::<LateralJoin>() else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(LateralJoinExec {
+            input: physical_inputs[0].clone(),
+            subquery: Arc::new(LateralSubquery {
+                plan: node.subquery.clone(),
+                outer_schema: logical_inputs[0].schema().clone(),
+                outer: node.outer,
+                single_row: node.single_row,
+                schema: Arc::new(node.schema.as_ref().into()),
+                session_state: session_state.clone(),
+            }),
+            metric: ExecutionPlanMetricsSet::new(),
+        })))
+    }
+}
+
+/// Executes the [LateralJoin] by planning and executing the subquery for each input row.
+pub struct LateralJoinExec {
+    input: Arc<dyn ExecutionPlan>,
+    subquery: Arc<LateralSubquery>,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl fmt::Debug for LateralJoinExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LateralJoinExec")
+            .field("input", &self.input)
+            .field("subquery", &self.subquery.plan)
+            .field("outer", &self.subquery.outer)
+            .field("single_row", &self.subquery.single_row)
+            .finish()
+    }
+}
+
+impl ExecutionPlan for LateralJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.subquery.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(1, children.len());
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            subquery: self.subquery.clone(),
+            metric: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context.clone())?;
+        let subquery = self.subquery.clone();
+        let metric = BaselineMetrics::new(&self.metric, partition);
+        let stream = input
+            .then(move |batch| {
+                let subquery = subquery.clone();
+                let context = context.clone();
+                async move {
+                    subquery
+                        .join(batch?, context)
+                        .await
+                        .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+                }
+            })
+            .boxed();
+        Ok(Box::pin(LateralJoinStream {
+            schema: self.schema(),
+            stream,
+            metric,
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "LateralJoinExec: outer={}, single_row={}",
+                self.subquery.outer, self.subquery.single_row
+            ),
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The subquery of a [LateralJoinExec] and how its rows are joined.
+struct LateralSubquery {
+    plan: LogicalPlan,
+    outer_schema: DFSchemaRef,
+    outer: bool,
+    single_row: bool,
+    /// Schema of the joined rows.
+    schema: SchemaRef,
+    session_state: SessionState,
+}
+
+impl LateralSubquery {
+    async fn join(&self, batch: RecordBatch, context: Arc<TaskContext>) -> DfResult<RecordBatch> {
+        let subquery_schema: SchemaRef = Arc::new(self.plan.schema().as_ref().into());
+        let mut joined = Vec::with_capacity(batch.num_rows());
+        for row in 0..batch.num_rows() {
+            let values = batch
+                .columns()
+                .iter()
+                .map(|array| ScalarValue::try_from_array(array, row))
+                .collect::<DfResult<Vec<_>>>()?;
+            let plan = bind_outer_columns(&self.plan, &self.outer_schema, &values)?;
+            let plan = self.session_state.create_physical_plan(&plan).await?;
+            let batches = datafusion::physical_plan::collect(plan, context.clone()).await?;
+            let rows = concat_batches(subquery_schema.clone(), &batches)?;
+            if self.single_row && rows.num_rows() > 1 {
+                return Err(DataFusionError::Execution(format!(
+                    "Scalar subquery returned {} rows, expect at most 1",
+                    rows.num_rows()
+                )));
+            }
+
+            // Rows without any matching row are joined with nulls by outer joins.
+            let (num_rows, right_columns): (usize, Vec<ArrayRef>) = match rows.num_rows() {
+                0 if !self.outer => continue,
+                0 => (
+                    1,
+                    subquery_schema
+                        .fields()
+                        .iter()
+                        .map(|field| new_null_array(field.data_type(), 1))
+                        .collect(),
+                ),
+                num_rows => (num_rows, rows.columns().to_vec()),
+            };
+            let left_rows = UInt32Array::from(vec![row as u32; num_rows]);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|array| compute::take(array, &left_rows, None))
+                .collect::<ArrowResult<Vec<ArrayRef>>>()?
+                .into_iter()
+                .chain(right_columns)
+                .collect();
+            joined.push(RecordBatch::try_new(self.schema.clone(), columns)?);
+        }
+        Ok(concat_batches(self.schema.clone(), &joined)?)
+    }
+}
+
+struct LateralJoinStream {
+    schema: SchemaRef,
+    stream: futures::stream::BoxStream<'static, ArrowResult<RecordBatch>>,
+    metric: BaselineMetrics,
+}
+
+impl RecordBatchStream for LateralJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for LateralJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let poll = this.stream.poll_next_unpin(cx);
+        this.metric.record_poll(poll)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::datatypes::Field;
+    use datafusion_expr::logical_plan::table_scan;
+    use datafusion_expr::{col, count, lit, max};
+
+    use super::*;
+
+    fn scan(name: &str) -> LogicalPlanBuilder {
+        table_scan(
+            Some(name),
+            &Schema::new(vec![
+                Field::new("host", DataType::Utf8, true),
+                Field::new("cpu", DataType::Float64, true),
+                Field::new("ts", DataType::Int64, true),
+            ]),
+            None,
+        )
+        .unwrap()
+    }
+
+    /// Plans `SELECT i.host, (<subquery>) FROM i`.
+    fn plan_scalar_subquery(subquery: LogicalPlan) -> LogicalPlan {
+        let subquery = Expr::ScalarSubquery(Subquery {
+            subquery: Arc::new(subquery),
+        });
+        scan("i")
+            .project(vec![col("i.host"), subquery.alias("max_cpu")])
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    /// Returns `SELECT <aggr> FROM m WHERE <predicate>`.
+    fn aggregate_subquery(aggr: Expr, predicate: Option<Expr>) -> LogicalPlan {
+        let mut builder = scan("m");
+        if let Some(predicate) = predicate {
+            let input = builder.build().unwrap();
+            builder = LogicalPlanBuilder::from(LogicalPlan::Filter(
+                Filter::try_new(predicate, Arc::new(input)).unwrap(),
+            ));
+        }
+        let name = aggr.display_name().unwrap();
+        builder
+            .aggregate(Vec::<Expr>::new(), vec![aggr])
+            .unwrap()
+            .project(vec![Expr::Column(Column::from_name(name))])
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn is_lateral_join(plan: &LogicalPlan) -> bool {
+        matches!(plan, LogicalPlan::Extension(ext) if ext.node.as_any().is::<LateralJoin>())
+    }
+
+    #[test]
+    fn test_rewrite_scalar_subqueries() {
+        // Uncorrelated subqueries are not rewritten.
+        let plan = plan_scalar_subquery(aggregate_subquery(max(col("m.cpu")), None));
+        let rewritten = rewrite_scalar_subqueries(plan.clone()).unwrap();
+        assert_eq!(format!("{plan:?}"), format!("{rewritten:?}"));
+
+        // Subqueries correlated by equalities are decorrelated to joins.
+        let predicate = col("m.host")
+            .eq(col("i.host"))
+            .and(col("m.ts").gt(lit(1000i64)));
+        let plan = plan_scalar_subquery(aggregate_subquery(max(col("m.cpu")), Some(predicate)));
+        let rewritten = rewrite_scalar_subqueries(plan).unwrap();
+        let LogicalPlan::Projection(projection) = &rewritten else {
+            unreachable!()
+        };
+        let LogicalPlan::Join(join) = projection.input.as_ref() else {
+            panic!("expect join, have: {rewritten:?}");
+        };
+        assert_eq!(JoinType::Left, join.join_type);
+        assert_eq!(
+            vec![(
+                Column::from_qualified_name("i.host"),
+                Column::from_qualified_name("__scalar_subquery_0.__key_0")
+            )],
+            join.on
+        );
+        assert_eq!(
+            vec!["host", "max_cpu"],
+            rewritten
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>()
+        );
+
+        // Counts of empty groups are 0, which are evaluated for each row.
+        let predicate = col("m.host").eq(col("i.host"));
+        let plan = plan_scalar_subquery(aggregate_subquery(count(col("m.cpu")), Some(predicate)));
+        let rewritten = rewrite_scalar_subqueries(plan).unwrap();
+        let LogicalPlan::Projection(projection) = &rewritten else {
+            unreachable!()
+        };
+        assert!(is_lateral_join(&projection.input));
+
+        // Subqueries correlated by inequalities are evaluated for each row.
+        let predicate = col("m.ts").lt_eq(col("i.ts"));
+        let plan = plan_scalar_subquery(aggregate_subquery(max(col("m.cpu")), Some(predicate)));
+        let rewritten = rewrite_scalar_subqueries(plan).unwrap();
+        let LogicalPlan::Projection(projection) = &rewritten else {
+            unreachable!()
+        };
+        assert!(is_lateral_join(&projection.input));
+    }
+
+    #[test]
+    fn test_bind_outer_columns() {
+        let metrics = table_scan(
+            Some("m"),
+            &Schema::new(vec![
+                Field::new("host", DataType::Utf8, true),
+                Field::new("cpu", DataType::Float64, true),
+            ]),
+            None,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let condition = col("m.host").eq(col("i.host"));
+        let plan = LogicalPlan::Filter(Filter::try_new(condition, Arc::new(metrics)).unwrap());
+        let outer_schema = DFSchema::try_from_qualified_schema(
+            "i",
+            &Schema::new(vec![Field::new("host", DataType::Utf8, true)]),
+        )
+        .unwrap();
+
+        let mut columns = HashSet::new();
+        collect_outer_columns(&plan, &outer_schema, &mut columns);
+        assert_eq!(
+            HashSet::from([Column::from_qualified_name("i.host")]),
+            columns
+        );
+
+        let values = [ScalarValue::Utf8(Some("a".to_string()))];
+        let plan = bind_outer_columns(&plan, &outer_schema, &values).unwrap();
+        let LogicalPlan::Filter(filter) = plan else {
+            unreachable!()
+        };
+        assert_eq!(&col("m.host").eq(lit("a")), filter.predicate());
+    }
+}
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod asof_join;
 mod datafusion;
 pub mod error;
 pub mod executor;
 mod function;
+mod lateral_join;
 pub mod logical_optimizer;
 mod metric;
 mod optimizer;
@@ -23,9 +25,12 @@ pub mod parser;
 pub mod physical_optimizer;
 pub mod physical_planner;
 pub mod plan;
+mod plan_cache;
 pub mod planner;
 pub mod query_engine;
+mod selectivity;
 pub mod sql;
+mod table_function;
 
 pub use crate::datafusion::DfContextProviderAdapter;
 pub use crate::query_engine::{
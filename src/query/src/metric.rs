@@ -20,3 +20,5 @@ pub static METRIC_OPTIMIZE_LOGICAL_ELAPSED: &str = "query.optimize_logicalplan_e
 pub static METRIC_OPTIMIZE_PHYSICAL_ELAPSED: &str = "query.optimize_physicalplan_elapsed";
 pub static METRIC_CREATE_PHYSICAL_ELAPSED: &str = "query.create_physicalplan_elapsed";
 pub static METRIC_EXEC_PLAN_ELAPSED: &str = "query.execute_plan_elapsed";
+pub static METRIC_PLAN_CACHE_HIT: &str = "query.plan_cache_hit";
+pub static METRIC_PLAN_CACHE_MISS: &str = "query.plan_cache_miss";
@@ -16,21 +16,25 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use common_time::timestamp::{TimeUnit, Timestamp};
+use common_time::Interval;
 use datafusion::optimizer::optimizer::OptimizerRule;
 use datafusion::optimizer::OptimizerConfig;
 use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
 use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
 use datafusion_expr::{
-    Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
+    Between, BinaryExpr, Cast, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
 };
 use datatypes::arrow::compute;
-use datatypes::arrow::datatypes::DataType;
+use datatypes::arrow::datatypes::{
+    DataType, IntervalDayTimeType, IntervalMonthDayNanoType, TimeUnit as ArrowTimeUnit,
+};
 
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
 /// Specifically:
 /// - string literal of timestamp is converted to `Expr::Literal(ScalarValue::TimestampMillis)`
 /// - string literal of boolean is converted to `Expr::Literal(ScalarValue::Boolean)`
+/// - string literal casted to interval, e.g. `'15m'::interval`, is converted to an interval literal
 pub struct TypeConversionRule;
 
 impl OptimizerRule for TypeConversionRule {
@@ -142,7 +146,9 @@ impl<'a> TypeConverter<'a> {
 
     fn cast_scalar_value(value: &ScalarValue, target_type: &DataType) -> Result<ScalarValue> {
         match (target_type, value) {
-            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => string_to_timestamp_ms(v),
+            (DataType::Timestamp(unit, _), ScalarValue::Utf8(Some(v))) => {
+                string_to_timestamp(v, unit)
+            }
             (DataType::Boolean, ScalarValue::Utf8(Some(v))) => match v.to_lowercase().as_str() {
                 "true" => Ok(ScalarValue::Boolean(Some(true))),
                 "false" => Ok(ScalarValue::Boolean(Some(false))),
@@ -245,6 +251,15 @@ impl<'a> ExprRewriter for TypeConverter<'a> {
                     negated,
                 }
             }
+            Expr::Cast(Cast { expr, data_type }) => match (*expr, data_type) {
+                (Expr::Literal(ScalarValue::Utf8(Some(v))), DataType::Interval(_)) => {
+                    Expr::Literal(string_to_interval(&v)?)
+                }
+                (expr, data_type) => Expr::Cast(Cast {
+                    expr: Box::new(expr),
+                    data_type,
+                }),
+            },
             Expr::Literal(value) => match value {
                 ScalarValue::TimestampSecond(Some(i), _) => {
                     timestamp_to_timestamp_ms_expr(i, TimeUnit::Second)
@@ -278,15 +293,52 @@ fn timestamp_to_timestamp_ms_expr(val: i64, unit: TimeUnit) -> Expr {
     Expr::Literal(ScalarValue::TimestampMillisecond(Some(timestamp), None))
 }
 
-fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
-    Ok(ScalarValue::TimestampMillisecond(
-        Some(
-            Timestamp::from_str(string)
-                .map(|t| t.value() / 1_000_000)
-                .map_err(|e| DataFusionError::External(Box::new(e)))?,
-        ),
-        None,
-    ))
+/// Parses the string to a timestamp literal of `unit`, so the precision of the column
+/// is kept.
+fn string_to_timestamp(string: &str, unit: &ArrowTimeUnit) -> Result<ScalarValue> {
+    let timestamp =
+        Timestamp::from_str(string).map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let convert = |unit| {
+        timestamp
+            .convert_to(unit)
+            .map(|t| t.value())
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!("timestamp {string} overflows for unit {unit:?}"))
+            })
+    };
+    let value = match unit {
+        ArrowTimeUnit::Second => {
+            ScalarValue::TimestampSecond(Some(convert(TimeUnit::Second)?), None)
+        }
+        ArrowTimeUnit::Millisecond => {
+            ScalarValue::TimestampMillisecond(Some(convert(TimeUnit::Millisecond)?), None)
+        }
+        ArrowTimeUnit::Microsecond => {
+            ScalarValue::TimestampMicrosecond(Some(convert(TimeUnit::Microsecond)?), None)
+        }
+        ArrowTimeUnit::Nanosecond => {
+            ScalarValue::TimestampNanosecond(Some(convert(TimeUnit::Nanosecond)?), None)
+        }
+    };
+    Ok(value)
+}
+
+/// Converts strings like `15m` or `5 minutes` to an interval literal, uses
+/// `IntervalDayTime` if possible as it's what DataFusion plans `INTERVAL '...'` into.
+fn string_to_interval(string: &str) -> Result<ScalarValue> {
+    let interval =
+        Interval::from_str(string).map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let value = match interval.to_day_time() {
+        Some((days, millis)) => {
+            ScalarValue::IntervalDayTime(Some(IntervalDayTimeType::make_value(days, millis)))
+        }
+        None => ScalarValue::IntervalMonthDayNano(Some(IntervalMonthDayNanoType::make_value(
+            interval.months(),
+            interval.days(),
+            interval.nanos(),
+        ))),
+    };
+    Ok(value)
 }
 
 #[cfg(test)]
@@ -294,21 +346,35 @@ mod tests {
     use std::collections::HashMap;
 
     use datafusion_common::{Column, DFField, DFSchema};
+    use datatypes::arrow::datatypes::IntervalUnit;
 
     use super::*;
 
     #[test]
     fn test_string_to_timestamp_ms() {
         assert!(matches!(
-            string_to_timestamp_ms("2022-02-02 19:00:00+08:00").unwrap(),
+            string_to_timestamp("2022-02-02 19:00:00+08:00", &ArrowTimeUnit::Millisecond).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1643799600000), None)
         ));
         assert!(matches!(
-            string_to_timestamp_ms("2009-02-13 23:31:30Z").unwrap(),
+            string_to_timestamp("2009-02-13 23:31:30Z", &ArrowTimeUnit::Millisecond).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1234567890000), None)
         ));
     }
 
+    #[test]
+    fn test_string_to_timestamp_ns() {
+        assert!(matches!(
+            string_to_timestamp("2009-02-13 23:31:30.000000001Z", &ArrowTimeUnit::Nanosecond)
+                .unwrap(),
+            ScalarValue::TimestampNanosecond(Some(1234567890000000001), None)
+        ));
+        assert!(matches!(
+            string_to_timestamp("2009-02-13 23:31:30Z", &ArrowTimeUnit::Second).unwrap(),
+            ScalarValue::TimestampSecond(Some(1234567890), None)
+        ));
+    }
+
     #[test]
     fn test_timestamp_to_timestamp_ms_expr() {
         assert!(matches!(
@@ -348,8 +414,6 @@ mod tests {
 
     #[test]
     fn test_convert_timestamp_str() {
-        use datatypes::arrow::datatypes::TimeUnit as ArrowTimeUnit;
-
         let schema_ref = Arc::new(
             DFSchema::new_with_metadata(
                 vec![DFField::new(
@@ -405,4 +469,47 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_string_to_interval() {
+        assert_eq!(
+            ScalarValue::IntervalDayTime(Some(IntervalDayTimeType::make_value(0, 900_000))),
+            string_to_interval("15m").unwrap()
+        );
+        assert_eq!(
+            ScalarValue::IntervalDayTime(Some(IntervalDayTimeType::make_value(1, 300_000))),
+            string_to_interval("1 day 5 minutes").unwrap()
+        );
+        assert_eq!(
+            ScalarValue::IntervalMonthDayNano(Some(IntervalMonthDayNanoType::make_value(1, 2, 0))),
+            string_to_interval("1 month 2 days").unwrap()
+        );
+        assert!(string_to_interval("15 parsecs").is_err());
+    }
+
+    #[test]
+    fn test_convert_interval_cast() {
+        let schema_ref = Arc::new(DFSchema::empty());
+        let mut converter = TypeConverter {
+            schemas: vec![&schema_ref],
+        };
+
+        let cast = Expr::Cast(Cast {
+            expr: Box::new(Expr::Literal(ScalarValue::Utf8(Some("1h".to_string())))),
+            data_type: DataType::Interval(IntervalUnit::MonthDayNano),
+        });
+        assert_eq!(
+            Expr::Literal(ScalarValue::IntervalDayTime(Some(
+                IntervalDayTimeType::make_value(0, 3_600_000)
+            ))),
+            converter.mutate(cast).unwrap()
+        );
+
+        // Casts of other types are kept as is.
+        let cast = Expr::Cast(Cast {
+            expr: Box::new(Expr::Literal(ScalarValue::Utf8(Some("1".to_string())))),
+            data_type: DataType::Int64,
+        });
+        assert_eq!(cast.clone(), converter.mutate(cast).unwrap());
+    }
 }
@@ -0,0 +1,156 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of logical plans of the statements repeatedly issued by clients like dashboards,
+//! so planning them again can be skipped.
+
+use std::sync::Arc;
+
+use catalog::CatalogListRef;
+use moka::sync::Cache;
+use session::context::QueryContextRef;
+use sql::statements::statement::Statement;
+use table::metadata::TableId;
+use table::TableRef;
+
+use crate::plan::LogicalPlan;
+
+/// Max number of plans in the cache.
+pub(crate) const PLAN_CACHE_CAPACITY: u64 = 1024;
+
+/// Key of a cached plan. Besides the statement, it contains everything in the query
+/// context that planning depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PlanCacheKey {
+    /// Normalized text of the statement.
+    fingerprint: String,
+    catalog: String,
+    schema: String,
+    username: String,
+    roles: Vec<String>,
+    row_version: bool,
+    /// Tables are planned to be scanned with the max rows hinted.
+    max_scan_rows: Option<u64>,
+}
+
+impl PlanCacheKey {
+    /// Returns the key of the plan of `stmt`, or `None` if the plan of `stmt` should not
+    /// be cached.
+    pub(crate) fn try_new(stmt: &Statement, query_ctx: &QueryContextRef) -> Option<Self> {
+        let Statement::Query(query) = stmt else {
+            return None;
+        };
+        // Plans of prepared statements depend on the types of their parameters.
+        if !query.param_types().is_empty() || query.hints.no_cache {
+            return None;
+        }
+
+        let user = query_ctx.current_user();
+        Some(Self {
+            // Formatting the parsed statement normalizes its whitespaces and keywords.
+            fingerprint: query.inner.to_string(),
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            username: user.username().to_string(),
+            roles: user.roles().to_vec(),
+            row_version: query_ctx.row_version(),
+            max_scan_rows: query.hints.max_scan_rows,
+        })
+    }
+}
+
+/// Version of a table that a plan is built against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TableVersion {
+    catalog: String,
+    schema: String,
+    table: String,
+    table_id: TableId,
+    schema_version: u32,
+}
+
+impl TableVersion {
+    /// Returns the version of the `table`, `None` if the table has no info to tell its
+    /// version, e.g. virtual tables, whose plans should not be cached.
+    pub(crate) fn of(table: &TableRef) -> Option<Self> {
+        let table_info = table.try_table_info()?;
+        Some(Self {
+            catalog: table_info.catalog_name.clone(),
+            schema: table_info.schema_name.clone(),
+            table: table_info.name.clone(),
+            table_id: table_info.ident.table_id,
+            schema_version: table.schema().version(),
+        })
+    }
+
+    /// Returns whether the table is still at this version, i.e. it's not dropped,
+    /// renamed, recreated or altered.
+    fn is_current(&self, catalog_list: &CatalogListRef) -> bool {
+        let table = catalog_list
+            .catalog(&self.catalog)
+            .ok()
+            .flatten()
+            .and_then(|catalog| catalog.schema(&self.schema).ok().flatten())
+            .and_then(|schema| schema.table(&self.table).ok().flatten());
+        table.map_or(false, |table| Self::of(&table).as_ref() == Some(self))
+    }
+}
+
+struct CachedPlan {
+    plan: LogicalPlan,
+    tables: Vec<TableVersion>,
+}
+
+pub(crate) struct PlanCache {
+    cache: Cache<PlanCacheKey, Arc<CachedPlan>>,
+}
+
+impl PlanCache {
+    pub(crate) fn new(capacity: u64) -> Self {
+        Self {
+            cache: Cache::new(capacity),
+        }
+    }
+
+    /// Returns the cached plan of `key` if all tables it reads are unchanged since it's
+    /// planned. Outdated plans are evicted.
+    pub(crate) fn get(
+        &self,
+        key: &PlanCacheKey,
+        catalog_list: &CatalogListRef,
+    ) -> Option<LogicalPlan> {
+        let cached = self.cache.get(key)?;
+        if cached
+            .tables
+            .iter()
+            .all(|table| table.is_current(catalog_list))
+        {
+            Some(cached.plan.clone())
+        } else {
+            self.cache.invalidate(key);
+            None
+        }
+    }
+
+    /// Caches the `plan` built against the `tables`.
+    pub(crate) fn insert(&self, key: PlanCacheKey, plan: LogicalPlan, tables: Vec<TableVersion>) {
+        self.cache
+            .insert(key, Arc::new(CachedPlan { plan, tables }));
+    }
+
+    /// Evicts all plans, for changes that invalidate plans of all tables.
+    pub(crate) fn clear(&self) {
+        self.cache.invalidate_all();
+    }
+}
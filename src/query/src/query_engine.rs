@@ -18,6 +18,7 @@ mod state;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use catalog::resource_usage::ResourceAccountantRef;
 use catalog::CatalogListRef;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_function::scalars::{FunctionRef, FUNCTION_REGISTRY};
@@ -26,6 +27,7 @@ use common_query::prelude::ScalarUdf;
 use common_query::Output;
 use datatypes::schema::Schema;
 use session::context::QueryContextRef;
+use table::masking::MaskingPolicy;
 
 use crate::datafusion::DatafusionQueryEngine;
 use crate::error::Result;
@@ -48,6 +50,10 @@ pub trait QueryEngine: Send + Sync {
 
     async fn execute(&self, plan: &LogicalPlan) -> Result<Output>;
 
+    /// Optimizes the logical `plan` and creates the optimized physical plan of it, without
+    /// executing the plan.
+    async fn to_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn PhysicalPlan>>;
+
     async fn execute_physical(&self, plan: &Arc<dyn PhysicalPlan>) -> Result<Output>;
 
     fn register_udf(&self, udf: ScalarUdf);
@@ -55,6 +61,11 @@ pub trait QueryEngine: Send + Sync {
     fn register_aggregate_function(&self, func: AggregateFunctionMetaRef);
 
     fn register_function(&self, func: FunctionRef);
+
+    fn register_resource_accountant(&self, accountant: ResourceAccountantRef);
+
+    /// Registers policies to mask sensitive columns in query results.
+    fn register_masking_policies(&self, policies: Vec<MaskingPolicy>);
 }
 
 pub struct QueryEngineFactory {
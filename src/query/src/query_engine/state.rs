@@ -14,31 +14,42 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
+use catalog::resource_usage::ResourceAccountantRef;
 use catalog::CatalogListRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::physical_plan::{SessionContext, TaskContext};
 use common_query::prelude::ScalarUdf;
 use datafusion::catalog::TableReference;
+use datafusion::datasource::DefaultTableSource;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::{QueryPlanner, SessionConfig, SessionState};
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
 use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::physical_plan::{ExecutionPlan, PhysicalPlanner};
-use datafusion_common::ScalarValue;
+use datafusion_common::{DataFusionError, ScalarValue};
 use datafusion_expr::{LogicalPlan as DfLogicalPlan, TableSource};
 use datafusion_optimizer::optimizer::Optimizer;
 use datafusion_sql::planner::ContextProvider;
 use datatypes::arrow::datatypes::DataType;
 use promql::extension_plan::PromExtensionPlanner;
 use session::context::QueryContextRef;
+use table::masking::{self, ColumnMasks, MaskingPolicy};
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
 
+use crate::asof_join::{asof_join_marker, AsofJoinPlanner};
 use crate::datafusion::DfCatalogListAdapter;
+use crate::lateral_join::LateralJoinPlanner;
 use crate::optimizer::TypeConversionRule;
+use crate::plan_cache::{PlanCache, PLAN_CACHE_CAPACITY};
+use crate::selectivity::estimate_filter_statistics;
+use crate::table_function::TableFunctionPlanner;
 
 /// Query engine global state
 // TODO(yingwen): This QueryEngineState still relies on datafusion, maybe we can define a trait for it,
@@ -49,6 +60,9 @@ pub struct QueryEngineState {
     df_context: SessionContext,
     catalog_list: CatalogListRef,
     aggregate_functions: Arc<RwLock<HashMap<String, AggregateFunctionMetaRef>>>,
+    resource_accountant: Arc<RwLock<Option<ResourceAccountantRef>>>,
+    masking_policies: Arc<RwLock<Vec<MaskingPolicy>>>,
+    plan_cache: Arc<PlanCache>,
 }
 
 impl fmt::Debug for QueryEngineState {
@@ -73,11 +87,15 @@ impl QueryEngineState {
         session_state.query_planner = Arc::new(DfQueryPlanner::new());
 
         let df_context = SessionContext::with_state(session_state);
+        df_context.register_udf(asof_join_marker().into_df_udf());
 
         Self {
             df_context,
             catalog_list,
             aggregate_functions: Arc::new(RwLock::new(HashMap::new())),
+            resource_accountant: Arc::new(RwLock::new(None)),
+            masking_policies: Arc::new(RwLock::new(Vec::new())),
+            plan_cache: Arc::new(PlanCache::new(PLAN_CACHE_CAPACITY)),
         }
     }
 
@@ -104,31 +122,171 @@ impl QueryEngineState {
             .insert(func.name(), func);
     }
 
+    /// Register the accountant that bytes scanned by queries are accounted to.
+    pub fn register_resource_accountant(&self, accountant: ResourceAccountantRef) {
+        *self.resource_accountant.write().unwrap() = Some(accountant);
+        // Cached plans count scanned bytes to the previous accountant.
+        self.plan_cache.clear();
+    }
+
+    /// Register the policies to mask sensitive columns in query results, replacing the
+    /// policies registered before.
+    pub fn register_masking_policies(&self, policies: Vec<MaskingPolicy>) {
+        *self.masking_policies.write().unwrap() = policies;
+        // Cached plans mask columns by the previous policies.
+        self.plan_cache.clear();
+    }
+
     #[inline]
     pub fn catalog_list(&self) -> &CatalogListRef {
         &self.catalog_list
     }
 
+    #[inline]
+    pub(crate) fn plan_cache(&self) -> &PlanCache {
+        &self.plan_cache
+    }
+
     #[inline]
     pub(crate) fn task_ctx(&self) -> Arc<TaskContext> {
         self.df_context.task_ctx()
     }
 
+    /// Returns the source of table `name`, whose scans fail once more than
+    /// `max_scan_rows` rows are scanned.
     pub(crate) fn get_table_provider(
         &self,
         query_ctx: QueryContextRef,
         name: TableReference,
+        max_scan_rows: Option<u64>,
     ) -> DfResult<Arc<dyn TableSource>> {
         let state = self.df_context.state();
-        if let TableReference::Bare { table } = name {
-            let name = TableReference::Partial {
-                schema: &query_ctx.current_schema(),
+        let masks = self.column_masks(&query_ctx, name);
+        let current_catalog = query_ctx.current_catalog();
+        let current_schema = query_ctx.current_schema();
+        let name = match name {
+            TableReference::Bare { table } => TableReference::Full {
+                catalog: &current_catalog,
+                schema: &current_schema,
                 table,
-            };
-            state.get_table_provider(name)
+            },
+            TableReference::Partial { schema, table } => TableReference::Full {
+                catalog: &current_catalog,
+                schema,
+                table,
+            },
+            name @ TableReference::Full { .. } => name,
+        };
+        if let TableReference::Full { catalog, .. } = name {
+            if !query_ctx.current_user().can_access_catalog(catalog) {
+                return Err(DataFusionError::Plan(format!(
+                    "User {} is not allowed to access catalog {}",
+                    query_ctx.current_user().username(),
+                    catalog
+                )));
+            }
+        }
+        let source = state.get_table_provider(name)?;
+
+        let scanned_bytes = self
+            .resource_accountant
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|accountant| {
+                accountant.scanned_bytes_counter(
+                    query_ctx.current_user().username(),
+                    &query_ctx.current_catalog(),
+                    &query_ctx.current_schema(),
+                )
+            });
+        Self::adapt_table_source(
+            source,
+            query_ctx.row_version(),
+            scanned_bytes,
+            masks,
+            max_scan_rows,
+        )
+    }
+
+    /// Returns masks of the columns in table `name` that the current user is not
+    /// allowed to see.
+    fn column_masks(&self, query_ctx: &QueryContextRef, name: TableReference) -> ColumnMasks {
+        let policies = self.masking_policies.read().unwrap();
+        if policies.is_empty() {
+            return ColumnMasks::new();
+        }
+
+        let (catalog, schema, table) = match name {
+            TableReference::Bare { table } => (
+                query_ctx.current_catalog(),
+                query_ctx.current_schema(),
+                table,
+            ),
+            TableReference::Partial { schema, table } => {
+                (query_ctx.current_catalog(), schema.to_string(), table)
+            }
+            TableReference::Full {
+                catalog,
+                schema,
+                table,
+            } => (catalog.to_string(), schema.to_string(), table),
+        };
+        masking::column_masks(
+            &policies,
+            &catalog,
+            &schema,
+            table,
+            query_ctx.current_user().roles(),
+        )
+    }
+
+    /// Exposes the row version of the table behind `source` if `row_version` is true and
+    /// the table supports it, counts bytes scanned from the table to `scanned_bytes`,
+    /// masks the columns in `masks` and limits the rows scanned to `max_scan_rows`.
+    /// Returns the `source` as is if there is nothing to adapt.
+    fn adapt_table_source(
+        source: Arc<dyn TableSource>,
+        row_version: bool,
+        scanned_bytes: Option<Arc<AtomicU64>>,
+        masks: ColumnMasks,
+        max_scan_rows: Option<u64>,
+    ) -> DfResult<Arc<dyn TableSource>> {
+        let Some(table) = Self::source_table(&source) else {
+            return Ok(source);
+        };
+        let row_version = row_version && table.supports_row_version();
+        if !row_version && scanned_bytes.is_none() && masks.is_empty() && max_scan_rows.is_none() {
+            return Ok(source);
+        }
+
+        let provider = if row_version {
+            DfTableProviderAdapter::with_row_version(table)?
         } else {
-            state.get_table_provider(name)
+            DfTableProviderAdapter::new(table)
+        };
+        let provider = match scanned_bytes {
+            Some(scanned_bytes) => provider.with_scanned_bytes(scanned_bytes),
+            None => provider,
         }
+        .with_masks(masks)
+        .with_max_scan_rows(max_scan_rows);
+        Ok(Arc::new(DefaultTableSource::new(Arc::new(provider))))
+    }
+
+    /// Returns the table behind `source`, `None` if `source` is not backed by a
+    /// [TableRef].
+    pub(crate) fn source_table(source: &Arc<dyn TableSource>) -> Option<TableRef> {
+        source
+            .as_any()
+            .downcast_ref::<DefaultTableSource>()
+            .and_then(|source| {
+                source
+                    .table_provider
+                    .as_any()
+                    .downcast_ref::<DfTableProviderAdapter>()
+            })
+            .map(|adapter| adapter.table())
     }
 
     pub(crate) fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
@@ -160,6 +318,8 @@ impl QueryEngineState {
     ) -> DfResult<Arc<dyn ExecutionPlan>> {
         let state = self.df_context.state();
         let config = &state.config;
+        // Estimates the filtered rows first, so the optimizers see the estimated sizes.
+        plan = estimate_filter_statistics(plan)?;
         for optimizer in &state.physical_optimizers {
             plan = optimizer.optimize(plan, config)?;
         }
@@ -188,9 +348,12 @@ impl QueryPlanner for DfQueryPlanner {
 impl DfQueryPlanner {
     fn new() -> Self {
         Self {
-            physical_planner: DefaultPhysicalPlanner::with_extension_planners(vec![Arc::new(
-                PromExtensionPlanner {},
-            )]),
+            physical_planner: DefaultPhysicalPlanner::with_extension_planners(vec![
+                Arc::new(PromExtensionPlanner {}),
+                Arc::new(AsofJoinPlanner),
+                Arc::new(LateralJoinPlanner),
+                Arc::new(TableFunctionPlanner),
+            ]),
         }
     }
 }
@@ -0,0 +1,369 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimates the number of rows passing filters from the column statistics collected by
+//! `ANALYZE TABLE`, so the physical optimizers (e.g. the join selection) see the sizes of
+//! filtered inputs instead of the sizes of the whole tables.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::expressions::{
+    BinaryExpr, Column, IsNotNullExpr, IsNullExpr, Literal, NotExpr,
+};
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::{
+    ColumnStatistics, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion_common::ScalarValue;
+use datafusion_expr::Operator;
+use datatypes::arrow::datatypes::SchemaRef;
+
+/// Selectivity of predicates whose selectivity can't be estimated.
+const UNKNOWN_SELECTIVITY: f64 = 1.0;
+
+/// Wraps each filter over an input with column statistics in an [EstimatedStatisticsExec]
+/// reporting the estimated number of rows passing the filter.
+pub(crate) fn estimate_filter_statistics(
+    plan: Arc<dyn ExecutionPlan>,
+) -> DfResult<Arc<dyn ExecutionPlan>> {
+    if plan.as_any().is::<EstimatedStatisticsExec>() {
+        // Already estimated.
+        return Ok(plan);
+    }
+
+    let children = plan.children();
+    let new_children = children
+        .iter()
+        .map(|child| estimate_filter_statistics(child.clone()))
+        .collect::<DfResult<Vec<_>>>()?;
+    let changed = children
+        .iter()
+        .zip(&new_children)
+        .any(|(child, new_child)| !Arc::ptr_eq(child, new_child));
+    let plan = if changed {
+        plan.with_new_children(new_children)?
+    } else {
+        plan
+    };
+
+    let filter = match plan.as_any().downcast_ref::<FilterExec>() {
+        Some(filter) => filter,
+        None => return Ok(plan),
+    };
+    let input_statistics = filter.input().statistics();
+    let (num_rows, column_statistics) = match (
+        input_statistics.num_rows,
+        input_statistics.column_statistics.as_ref(),
+    ) {
+        (Some(num_rows), Some(column_statistics)) => (num_rows, column_statistics),
+        _ => return Ok(plan),
+    };
+
+    let selectivity = predicate_selectivity(filter.predicate(), num_rows, column_statistics);
+    let prorate = |n: usize| (n as f64 * selectivity).ceil() as usize;
+    let statistics = Statistics {
+        num_rows: Some(prorate(num_rows)),
+        total_byte_size: input_statistics.total_byte_size.map(prorate),
+        column_statistics: input_statistics.column_statistics.clone(),
+        is_exact: false,
+    };
+    Ok(Arc::new(EstimatedStatisticsExec::new(plan, statistics)))
+}
+
+/// Returns the estimated fraction of rows satisfying the `predicate`.
+fn predicate_selectivity(
+    predicate: &Arc<dyn PhysicalExpr>,
+    num_rows: usize,
+    column_statistics: &[ColumnStatistics],
+) -> f64 {
+    let any = predicate.as_any();
+    let selectivity = if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        match binary.op() {
+            Operator::And => {
+                predicate_selectivity(binary.left(), num_rows, column_statistics)
+                    * predicate_selectivity(binary.right(), num_rows, column_statistics)
+            }
+            Operator::Or => {
+                let left = predicate_selectivity(binary.left(), num_rows, column_statistics);
+                let right = predicate_selectivity(binary.right(), num_rows, column_statistics);
+                left + right - left * right
+            }
+            op => comparison_selectivity(binary, *op, num_rows, column_statistics)
+                .unwrap_or(UNKNOWN_SELECTIVITY),
+        }
+    } else if let Some(is_null) = any.downcast_ref::<IsNullExpr>() {
+        null_fraction(is_null.arg(), num_rows, column_statistics).unwrap_or(UNKNOWN_SELECTIVITY)
+    } else if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+        null_fraction(is_not_null.arg(), num_rows, column_statistics)
+            .map_or(UNKNOWN_SELECTIVITY, |fraction| 1.0 - fraction)
+    } else if let Some(not) = any.downcast_ref::<NotExpr>() {
+        1.0 - predicate_selectivity(not.arg(), num_rows, column_statistics)
+    } else {
+        UNKNOWN_SELECTIVITY
+    };
+    selectivity.clamp(0.0, 1.0)
+}
+
+/// Returns the statistics of `expr` if it's a column.
+fn column_of<'a>(
+    expr: &Arc<dyn PhysicalExpr>,
+    column_statistics: &'a [ColumnStatistics],
+) -> Option<&'a ColumnStatistics> {
+    let column = expr.as_any().downcast_ref::<Column>()?;
+    column_statistics.get(column.index())
+}
+
+fn null_fraction(
+    expr: &Arc<dyn PhysicalExpr>,
+    num_rows: usize,
+    column_statistics: &[ColumnStatistics],
+) -> Option<f64> {
+    let stats = column_of(expr, column_statistics)?;
+    if num_rows == 0 {
+        return Some(0.0);
+    }
+    Some(stats.null_count? as f64 / num_rows as f64)
+}
+
+/// Estimates the selectivity of `column op literal` (or `literal op column`).
+fn comparison_selectivity(
+    binary: &BinaryExpr,
+    op: Operator,
+    num_rows: usize,
+    column_statistics: &[ColumnStatistics],
+) -> Option<f64> {
+    let (column, literal, op) = match (
+        binary.left().as_any().downcast_ref::<Literal>(),
+        binary.right().as_any().downcast_ref::<Literal>(),
+    ) {
+        (None, Some(literal)) => (binary.left(), literal.value(), op),
+        (Some(literal), None) => (binary.right(), literal.value(), swap_operator(op)?),
+        _ => return None,
+    };
+    let stats = column_of(column, column_statistics)?;
+    let not_null = 1.0 - null_fraction(column, num_rows, column_statistics).unwrap_or(0.0);
+
+    let equal = || {
+        let out_of_range = match (&stats.min_value, &stats.max_value) {
+            (Some(min), Some(max)) => literal < min || literal > max,
+            _ => false,
+        };
+        if out_of_range {
+            Some(0.0)
+        } else {
+            let distinct_count = stats.distinct_count?.max(1);
+            Some(not_null / distinct_count as f64)
+        }
+    };
+    let less_than = || {
+        let min = scalar_to_f64(stats.min_value.as_ref()?)?;
+        let max = scalar_to_f64(stats.max_value.as_ref()?)?;
+        let value = scalar_to_f64(literal)?;
+        let fraction = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else if value > min {
+            1.0
+        } else {
+            0.0
+        };
+        Some(not_null * fraction)
+    };
+
+    match op {
+        Operator::Eq => equal(),
+        Operator::NotEq => equal().map(|selectivity| not_null - selectivity),
+        Operator::Lt | Operator::LtEq => less_than(),
+        Operator::Gt | Operator::GtEq => less_than().map(|selectivity| not_null - selectivity),
+        _ => None,
+    }
+}
+
+/// Returns the operator of `b op a` equivalent to `a op b`.
+fn swap_operator(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq | Operator::NotEq => Some(op),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        _ => None,
+    }
+}
+
+fn scalar_to_f64(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Int8(v) => v.map(|v| v as f64),
+        ScalarValue::Int16(v) => v.map(|v| v as f64),
+        ScalarValue::Int32(v) => v.map(|v| v as f64),
+        ScalarValue::Int64(v) => v.map(|v| v as f64),
+        ScalarValue::UInt8(v) => v.map(|v| v as f64),
+        ScalarValue::UInt16(v) => v.map(|v| v as f64),
+        ScalarValue::UInt32(v) => v.map(|v| v as f64),
+        ScalarValue::UInt64(v) => v.map(|v| v as f64),
+        ScalarValue::Float32(v) => v.map(|v| v as f64),
+        ScalarValue::Float64(v) => *v,
+        ScalarValue::Date32(v) => v.map(|v| v as f64),
+        ScalarValue::Date64(v) => v.map(|v| v as f64),
+        ScalarValue::TimestampSecond(v, _)
+        | ScalarValue::TimestampMillisecond(v, _)
+        | ScalarValue::TimestampMicrosecond(v, _)
+        | ScalarValue::TimestampNanosecond(v, _) => v.map(|v| v as f64),
+        _ => None,
+    }
+}
+
+/// Passes through the output of its input, reporting estimated statistics of the input.
+#[derive(Debug)]
+pub(crate) struct EstimatedStatisticsExec {
+    input: Arc<dyn ExecutionPlan>,
+    statistics: Statistics,
+}
+
+impl EstimatedStatisticsExec {
+    pub(crate) fn new(input: Arc<dyn ExecutionPlan>, statistics: Statistics) -> Self {
+        Self { input, statistics }
+    }
+}
+
+impl ExecutionPlan for EstimatedStatisticsExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(1, children.len());
+        Ok(Arc::new(Self::new(
+            children[0].clone(),
+            self.statistics.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        self.input.execute(partition, context)
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "EstimatedStatisticsExec: rows={:?}",
+                self.statistics.num_rows
+            ),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::physical_plan::expressions::{binary, col, lit};
+    use datatypes::arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    fn column_statistics() -> Vec<ColumnStatistics> {
+        vec![
+            ColumnStatistics {
+                null_count: Some(0),
+                max_value: Some(ScalarValue::Int64(Some(100))),
+                min_value: Some(ScalarValue::Int64(Some(0))),
+                distinct_count: Some(50),
+            },
+            ColumnStatistics {
+                null_count: Some(500),
+                max_value: None,
+                min_value: None,
+                distinct_count: None,
+            },
+        ]
+    }
+
+    fn predicate(column: &str, op: Operator, value: i64) -> Arc<dyn PhysicalExpr> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]);
+        binary(col(column, &schema).unwrap(), op, lit(value), &schema).unwrap()
+    }
+
+    fn assert_selectivity(expect: f64, actual: f64) {
+        assert!((expect - actual).abs() < 1e-9, "{expect} != {actual}");
+    }
+
+    #[test]
+    fn test_predicate_selectivity() {
+        let stats = column_statistics();
+        let selectivity = |predicate| predicate_selectivity(&predicate, 1000, &stats);
+
+        assert_selectivity(0.02, selectivity(predicate("a", Operator::Eq, 10)));
+        assert_selectivity(0.0, selectivity(predicate("a", Operator::Eq, 1000)));
+        assert_selectivity(0.98, selectivity(predicate("a", Operator::NotEq, 10)));
+        assert_selectivity(0.25, selectivity(predicate("a", Operator::Lt, 25)));
+        assert_selectivity(0.75, selectivity(predicate("a", Operator::GtEq, 25)));
+        assert_selectivity(1.0, selectivity(predicate("a", Operator::Lt, 200)));
+        // Without min/max values.
+        assert_selectivity(1.0, selectivity(predicate("b", Operator::Lt, 25)));
+        assert_selectivity(1.0, selectivity(predicate("b", Operator::NotEq, 25)));
+
+        let and = Arc::new(BinaryExpr::new(
+            predicate("a", Operator::Lt, 50),
+            Operator::And,
+            predicate("a", Operator::Eq, 10),
+        )) as Arc<dyn PhysicalExpr>;
+        assert_selectivity(0.01, selectivity(and));
+        let or = Arc::new(BinaryExpr::new(
+            predicate("a", Operator::Lt, 50),
+            Operator::Or,
+            predicate("a", Operator::Lt, 50),
+        )) as Arc<dyn PhysicalExpr>;
+        assert_selectivity(0.75, selectivity(or));
+        let is_null = Arc::new(IsNullExpr::new(Arc::new(Column::new("b", 1))));
+        assert_selectivity(0.5, selectivity(is_null));
+    }
+}
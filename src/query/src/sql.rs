@@ -15,18 +15,21 @@
 use std::sync::Arc;
 
 use catalog::CatalogManagerRef;
-use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use datatypes::prelude::*;
-use datatypes::schema::{ColumnSchema, Schema};
-use datatypes::vectors::{Helper, StringVector};
+use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema};
+use datatypes::types::TimestampType;
+use datatypes::vectors::{Helper, StringVector, TimestampMillisecondVector, UInt64Vector};
 use once_cell::sync::Lazy;
 use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::statements::explain::Explain;
-use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
+use sql::statements::show::{ShowDatabases, ShowKind, ShowTableStatus, ShowTables};
 use sql::statements::statement::Statement;
+use table::metadata::TableInfo;
+use table::table::TableStatistics;
 use table::TableRef;
 
 use crate::error::{self, Result};
@@ -45,6 +48,39 @@ const SEMANTIC_TYPE_PRIMARY_KEY: &str = "PRIMARY KEY";
 const SEMANTIC_TYPE_VALUE: &str = "VALUE";
 const SEMANTIC_TYPE_TIME_INDEX: &str = "TIME INDEX";
 
+const SHOW_CREATE_TABLE_TABLE_COLUMN: &str = "Table";
+const SHOW_CREATE_TABLE_CREATE_COLUMN: &str = "Create Table";
+
+const EXPLAIN_DDL_ITEM_COLUMN: &str = "Item";
+const EXPLAIN_DDL_DETAIL_COLUMN: &str = "Detail";
+
+/// Columns of `SHOW TABLE STATUS`, the same as MySQL.
+const SHOW_TABLE_STATUS_COLUMNS: [(&str, ColumnType); 18] = [
+    ("Name", ColumnType::String),
+    ("Engine", ColumnType::String),
+    ("Version", ColumnType::UInt64),
+    ("Row_format", ColumnType::String),
+    ("Rows", ColumnType::UInt64),
+    ("Avg_row_length", ColumnType::UInt64),
+    ("Data_length", ColumnType::UInt64),
+    ("Max_data_length", ColumnType::UInt64),
+    ("Index_length", ColumnType::UInt64),
+    ("Data_free", ColumnType::UInt64),
+    ("Auto_increment", ColumnType::UInt64),
+    ("Create_time", ColumnType::Timestamp),
+    ("Update_time", ColumnType::Timestamp),
+    ("Check_time", ColumnType::Timestamp),
+    ("Collation", ColumnType::String),
+    ("Checksum", ColumnType::UInt64),
+    ("Create_options", ColumnType::String),
+    ("Comment", ColumnType::String),
+];
+
+const CHECK_TABLE_TABLE_COLUMN: &str = "Table";
+const CHECK_TABLE_OP_COLUMN: &str = "Op";
+const CHECK_TABLE_MSG_TYPE_COLUMN: &str = "Msg_type";
+const CHECK_TABLE_MSG_TEXT_COLUMN: &str = "Msg_text";
+
 const NULLABLE_YES: &str = "YES";
 const NULLABLE_NO: &str = "NO";
 
@@ -78,7 +114,26 @@ static DESCRIBE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
-pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -> Result<Output> {
+static SHOW_CREATE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new(
+            SHOW_CREATE_TABLE_TABLE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            SHOW_CREATE_TABLE_CREATE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]))
+});
+
+pub fn show_databases(
+    stmt: ShowDatabases,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
     // TODO(LFC): supports WHERE
     ensure!(
         matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
@@ -87,11 +142,12 @@ pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -
         }
     );
 
+    let catalog_name = query_ctx.current_catalog();
     let catalog = catalog_manager
-        .catalog(DEFAULT_CATALOG_NAME)
+        .catalog(&catalog_name)
         .context(error::CatalogSnafu)?
         .context(error::CatalogNotFoundSnafu {
-            catalog: DEFAULT_CATALOG_NAME,
+            catalog: &catalog_name,
         })?;
     let mut databases = catalog.schema_names().context(error::CatalogSnafu)?;
     // TODO(dennis): Specify the order of the results in catalog manager API
@@ -156,6 +212,190 @@ pub fn show_tables(
     Ok(Output::RecordBatches(records))
 }
 
+/// Type of a column in the output of `SHOW TABLE STATUS`.
+#[derive(Clone, Copy)]
+enum ColumnType {
+    String,
+    UInt64,
+    Timestamp,
+}
+
+static SHOW_TABLE_STATUS_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    let columns = SHOW_TABLE_STATUS_COLUMNS
+        .iter()
+        .map(|(name, column_type)| {
+            let data_type = match column_type {
+                ColumnType::String => ConcreteDataType::string_datatype(),
+                ColumnType::UInt64 => ConcreteDataType::uint64_datatype(),
+                ColumnType::Timestamp => ConcreteDataType::timestamp_millisecond_datatype(),
+            };
+            // Only the name is always known.
+            ColumnSchema::new(*name, data_type, *name != "Name")
+        })
+        .collect();
+    Arc::new(Schema::new(columns))
+});
+
+/// A row of `SHOW TABLE STATUS`, columns not listed are always `NULL`.
+#[derive(Default)]
+struct TableStatus {
+    name: String,
+    engine: Option<String>,
+    version: Option<u64>,
+    rows: Option<u64>,
+    avg_row_length: Option<u64>,
+    data_length: Option<u64>,
+    create_time: Option<i64>,
+    update_time: Option<i64>,
+    comment: Option<String>,
+}
+
+impl TableStatus {
+    /// Returns the status of the `table`, its sizes are estimated from the `statistics` of
+    /// its regions.
+    fn new(name: String, table: &TableRef, statistics: Option<TableStatistics>) -> Self {
+        let Some(statistics) = statistics else {
+            // Tables without statistics, e.g. virtual tables, may not have table info, so
+            // only their names are shown.
+            return Self { name, ..Default::default() };
+        };
+
+        let info = table.table_info();
+        Self {
+            name,
+            engine: Some(info.meta.engine.clone()),
+            version: Some(info.ident.version),
+            rows: Some(statistics.num_rows),
+            avg_row_length: Some(
+                statistics
+                    .num_bytes
+                    .checked_div(statistics.num_rows)
+                    .unwrap_or(0),
+            ),
+            data_length: Some(statistics.num_bytes),
+            create_time: Some(info.meta.created_on.timestamp_millis()),
+            update_time: statistics.last_write_millis,
+            comment: Some(info.desc.clone().unwrap_or_default()),
+        }
+    }
+}
+
+/// Shows the status of tables like MySQL, the number of rows and the bytes of data are
+/// estimated from the statistics of regions of the tables.
+pub async fn show_table_status(
+    stmt: ShowTableStatus,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    ensure!(
+        matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
+        error::UnsupportedExprSnafu {
+            name: stmt.kind.to_string(),
+        }
+    );
+
+    let catalog_name = query_ctx.current_catalog();
+    let schema_name = stmt.database.unwrap_or_else(|| query_ctx.current_schema());
+    let schema = catalog_manager
+        .schema(&catalog_name, &schema_name)
+        .context(error::CatalogSnafu)?
+        .context(error::SchemaNotFoundSnafu {
+            schema: &schema_name,
+        })?;
+    let mut table_names = schema.table_names().context(error::CatalogSnafu)?;
+    table_names.sort();
+    if let ShowKind::Like(ident) = stmt.kind {
+        let matched =
+            Helper::like_utf8(table_names, &ident.value).context(error::VectorComputationSnafu)?;
+        table_names = (0..matched.len())
+            .filter_map(|i| match matched.get(i) {
+                Value::String(name) => Some(name.as_utf8().to_string()),
+                _ => None,
+            })
+            .collect();
+    }
+
+    // Statistics reported remotely are read once for all tables.
+    let mut reported = catalog_manager
+        .schema_statistics(&catalog_name, &schema_name)
+        .await
+        .context(error::CatalogSnafu)?;
+    let mut statuses = Vec::with_capacity(table_names.len());
+    for table_name in table_names {
+        // The table may be dropped after its name is listed.
+        let Some(table) = schema.table(&table_name).context(error::CatalogSnafu)? else {
+            continue;
+        };
+        let statistics = match &mut reported {
+            Some(reported) => reported.remove(&table_name),
+            None => table
+                .collect_statistics()
+                .await
+                .map_err(BoxedError::new)
+                .context(error::QueryExecutionSnafu)?,
+        };
+        statuses.push(TableStatus::new(table_name, &table, statistics));
+    }
+
+    let num_rows = statuses.len();
+    let nulls = |column_type| -> VectorRef {
+        match column_type {
+            ColumnType::String => Arc::new(StringVector::from(vec![None::<String>; num_rows])),
+            ColumnType::UInt64 => Arc::new(UInt64Vector::from(vec![None; num_rows])),
+            ColumnType::Timestamp => {
+                Arc::new(TimestampMillisecondVector::from(vec![None; num_rows]))
+            }
+        }
+    };
+    let columns = SHOW_TABLE_STATUS_COLUMNS
+        .iter()
+        .map(|(name, column_type)| -> VectorRef {
+            match *name {
+                "Name" => Arc::new(StringVector::from(
+                    statuses.iter().map(|s| s.name.clone()).collect::<Vec<_>>(),
+                )),
+                "Engine" => Arc::new(StringVector::from(
+                    statuses
+                        .iter()
+                        .map(|s| s.engine.clone())
+                        .collect::<Vec<_>>(),
+                )),
+                "Version" => Arc::new(UInt64Vector::from(
+                    statuses.iter().map(|s| s.version).collect::<Vec<_>>(),
+                )),
+                "Rows" => Arc::new(UInt64Vector::from(
+                    statuses.iter().map(|s| s.rows).collect::<Vec<_>>(),
+                )),
+                "Avg_row_length" => Arc::new(UInt64Vector::from(
+                    statuses
+                        .iter()
+                        .map(|s| s.avg_row_length)
+                        .collect::<Vec<_>>(),
+                )),
+                "Data_length" => Arc::new(UInt64Vector::from(
+                    statuses.iter().map(|s| s.data_length).collect::<Vec<_>>(),
+                )),
+                "Create_time" => Arc::new(TimestampMillisecondVector::from(
+                    statuses.iter().map(|s| s.create_time).collect::<Vec<_>>(),
+                )),
+                "Update_time" => Arc::new(TimestampMillisecondVector::from(
+                    statuses.iter().map(|s| s.update_time).collect::<Vec<_>>(),
+                )),
+                "Comment" => Arc::new(StringVector::from(
+                    statuses
+                        .iter()
+                        .map(|s| s.comment.clone())
+                        .collect::<Vec<_>>(),
+                )),
+                _ => nulls(*column_type),
+            }
+        })
+        .collect();
+    let records = RecordBatches::try_from_columns(SHOW_TABLE_STATUS_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
 pub async fn explain(
     stmt: Box<Explain>,
     query_engine: QueryEngineRef,
@@ -166,6 +406,72 @@ pub async fn explain(
     query_engine.execute(&plan).await
 }
 
+/// Builds the output of `EXPLAIN DDL` from the `(item, detail)` pairs, like the action the
+/// statement would take, the placement of its regions and the data it would affect.
+pub fn explain_ddl_output(items: Vec<(String, String)>) -> Result<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        ColumnSchema::new(
+            EXPLAIN_DDL_ITEM_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            EXPLAIN_DDL_DETAIL_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]));
+    let (items, details): (Vec<_>, Vec<_>) = items.into_iter().unzip();
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(items)),
+        Arc::new(StringVector::from(details)),
+    ];
+    let records =
+        RecordBatches::try_from_columns(schema, columns).context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// Builds the output of `CHECK TABLE` and `REPAIR TABLE` like MySQL, one row for each
+/// `(message type, message text)` of the `op` on the table.
+pub fn check_table_output(
+    table_name: &str,
+    op: &str,
+    messages: Vec<(String, String)>,
+) -> Result<Output> {
+    let schema = Arc::new(Schema::new(
+        [
+            CHECK_TABLE_TABLE_COLUMN,
+            CHECK_TABLE_OP_COLUMN,
+            CHECK_TABLE_MSG_TYPE_COLUMN,
+            CHECK_TABLE_MSG_TEXT_COLUMN,
+        ]
+        .into_iter()
+        .map(|name| ColumnSchema::new(name, ConcreteDataType::string_datatype(), false))
+        .collect(),
+    ));
+    let num_rows = messages.len();
+    let (msg_types, msg_texts): (Vec<_>, Vec<_>) = messages.into_iter().unzip();
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(vec![table_name; num_rows])),
+        Arc::new(StringVector::from(vec![op; num_rows])),
+        Arc::new(StringVector::from(msg_types)),
+        Arc::new(StringVector::from(msg_texts)),
+    ];
+    let records =
+        RecordBatches::try_from_columns(schema, columns).context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// Describes the rows a DDL would affect for `EXPLAIN DDL`, estimated from the statistics
+/// of the table instead of scanning it, so rows overwritten or deleted are also counted.
+/// Returns "unknown" if the table has no statistics.
+pub fn estimated_affected_rows(statistics: Option<TableStatistics>) -> String {
+    statistics.map_or_else(
+        || "unknown".to_string(),
+        |statistics| statistics.num_rows.to_string(),
+    )
+}
+
 pub fn describe_table(table: TableRef) -> Result<Output> {
     let table_info = table.table_info();
     let columns_schemas = table_info.meta.schema.column_schemas();
@@ -181,6 +487,144 @@ pub fn describe_table(table: TableRef) -> Result<Output> {
     Ok(Output::RecordBatches(records))
 }
 
+pub fn show_create_table(table: TableRef) -> Result<Output> {
+    let table_info = table.table_info();
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(vec![table_info.name.clone()])),
+        Arc::new(StringVector::from(vec![create_table_sql(&table_info)])),
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+/// Renders the `CREATE TABLE` statement that creates a table like the table of `table_info`,
+/// including the comments of the table and its columns.
+fn create_table_sql(table_info: &TableInfo) -> String {
+    let meta = &table_info.meta;
+    let column_schemas = meta.schema.column_schemas();
+
+    let mut lines = column_schemas
+        .iter()
+        .map(|column_schema| format!("  {}", column_sql(column_schema)))
+        .collect::<Vec<_>>();
+    if let Some(time_index) = column_schemas.iter().find(|c| c.is_time_index()) {
+        lines.push(format!("  TIME INDEX ({})", time_index.name));
+    }
+    if !meta.primary_key_indices.is_empty() {
+        let primary_keys = meta
+            .primary_key_indices
+            .iter()
+            .map(|i| column_schemas[*i].name.as_str())
+            .collect::<Vec<_>>();
+        lines.push(format!("  PRIMARY KEY ({})", primary_keys.join(", ")));
+    }
+
+    let mut sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (\n{}\n)\nENGINE={}",
+        table_info.name,
+        lines.join(",\n"),
+        meta.engine
+    );
+    if let Some(desc) = &table_info.desc {
+        sql.push_str(&format!("\nCOMMENT {}", quote_string(desc)));
+    }
+    // The engine is already in the `ENGINE` clause.
+    let mut options = meta
+        .options
+        .iter()
+        .filter(|(k, _)| k.as_str() != "engine")
+        .map(|(k, v)| format!("  {k} = {}", quote_string(v)))
+        .collect::<Vec<_>>();
+    if !options.is_empty() {
+        options.sort();
+        sql.push_str(&format!("\nWITH(\n{}\n)", options.join(",\n")));
+    }
+    sql
+}
+
+fn column_sql(column_schema: &ColumnSchema) -> String {
+    let mut sql = format!(
+        "{} {}",
+        column_schema.name,
+        sql_type_name(&column_schema.data_type)
+    );
+    sql.push_str(if column_schema.is_nullable() {
+        " NULL"
+    } else {
+        " NOT NULL"
+    });
+    match column_schema.default_constraint() {
+        Some(ColumnDefaultConstraint::Function(expr)) => {
+            sql.push_str(&format!(" DEFAULT {expr}"));
+        }
+        Some(ColumnDefaultConstraint::Value(value)) => {
+            sql.push_str(&format!(" DEFAULT {}", sql_value(value)));
+        }
+        None => {}
+    }
+    if let Some(comment) = column_schema.comment() {
+        sql.push_str(&format!(" COMMENT {}", quote_string(comment)));
+    }
+    sql
+}
+
+/// Returns the name of `data_type` in SQL, which could be parsed back to `data_type`.
+fn sql_type_name(data_type: &ConcreteDataType) -> String {
+    match data_type {
+        ConcreteDataType::Boolean(_) => "BOOLEAN".to_string(),
+        ConcreteDataType::Int8(_) => "TINYINT".to_string(),
+        ConcreteDataType::Int16(_) => "SMALLINT".to_string(),
+        ConcreteDataType::Int32(_) => "INT".to_string(),
+        ConcreteDataType::Int64(_) => "BIGINT".to_string(),
+        ConcreteDataType::UInt8(_) => "TINYINT UNSIGNED".to_string(),
+        ConcreteDataType::UInt16(_) => "SMALLINT UNSIGNED".to_string(),
+        ConcreteDataType::UInt32(_) => "INT UNSIGNED".to_string(),
+        ConcreteDataType::UInt64(_) => "BIGINT UNSIGNED".to_string(),
+        ConcreteDataType::Float32(_) => "FLOAT".to_string(),
+        ConcreteDataType::Float64(_) => "DOUBLE".to_string(),
+        ConcreteDataType::Decimal128(t) => format!("DECIMAL({}, {})", t.precision(), t.scale()),
+        ConcreteDataType::String(_) => "STRING".to_string(),
+        ConcreteDataType::Binary(_) => "VARBINARY".to_string(),
+        ConcreteDataType::Date(_) => "DATE".to_string(),
+        ConcreteDataType::DateTime(_) => "DATETIME".to_string(),
+        ConcreteDataType::Timestamp(t) => {
+            let precision = match t {
+                TimestampType::Second(_) => 0,
+                TimestampType::Millisecond(_) => 3,
+                TimestampType::Microsecond(_) => 6,
+                TimestampType::Nanosecond(_) => 9,
+            };
+            format!("TIMESTAMP({precision})")
+        }
+        ConcreteDataType::Histogram(_) => "HISTOGRAM".to_string(),
+        ConcreteDataType::Null(_) | ConcreteDataType::List(_) => data_type.name().to_string(),
+    }
+}
+
+fn sql_value(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Boolean(_)
+        | Value::UInt8(_)
+        | Value::UInt16(_)
+        | Value::UInt32(_)
+        | Value::UInt64(_)
+        | Value::Int8(_)
+        | Value::Int16(_)
+        | Value::Int32(_)
+        | Value::Int64(_)
+        | Value::Float32(_)
+        | Value::Float64(_)
+        | Value::Decimal128(_) => value.to_string(),
+        _ => quote_string(&value.to_string()),
+    }
+}
+
+fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 fn describe_column_names(columns_schemas: &[ColumnSchema]) -> VectorRef {
     Arc::new(StringVector::from_iterator(
         columns_schemas.iter().map(|cs| cs.name.as_str()),
@@ -240,6 +684,7 @@ fn describe_column_semantic_types(
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use common_query::Output;
@@ -249,16 +694,79 @@ mod test {
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema, SchemaRef};
     use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt32Vector, VectorRef};
     use snafu::ResultExt;
+    use sql::dialect::GenericDialect;
+    use sql::parser::ParserContext;
+    use sql::statements::statement::Statement;
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
     use table::test_util::MemTable;
     use table::TableRef;
 
     use crate::error;
     use crate::error::Result;
     use crate::sql::{
-        describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
+        create_table_sql, describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
         SEMANTIC_TYPE_TIME_INDEX, SEMANTIC_TYPE_VALUE,
     };
 
+    #[test]
+    fn test_create_table_sql() {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true)
+                .with_comment("host's name"),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(0.0f64.into())))
+                .unwrap(),
+        ]);
+        let meta = TableMetaBuilder::default()
+            .schema(Arc::new(schema))
+            .primary_key_indices(vec![0])
+            .engine("mito")
+            .next_column_id(3)
+            .options(HashMap::from([
+                ("engine".to_string(), "mito".to_string()),
+                ("ttl".to_string(), "7d".to_string()),
+            ]))
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::new("monitor", meta)
+            .desc(Some("cpu usage of hosts".to_string()))
+            .build()
+            .unwrap();
+
+        let sql = create_table_sql(&table_info);
+        assert_eq!(
+            r#"CREATE TABLE IF NOT EXISTS monitor (
+  host STRING NULL COMMENT 'host''s name',
+  ts TIMESTAMP(3) NOT NULL,
+  cpu DOUBLE NULL DEFAULT 0,
+  TIME INDEX (ts),
+  PRIMARY KEY (host)
+)
+ENGINE=mito
+COMMENT 'cpu usage of hosts'
+WITH(
+  ttl = '7d'
+)"#,
+            sql
+        );
+
+        // The rendered statement could be parsed back.
+        let stmts = ParserContext::create_with_dialect(&sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateTable(create_table) => {
+                assert_eq!(Some("cpu usage of hosts"), create_table.comment.as_deref());
+                assert_eq!(3, create_table.columns.len());
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_describe_table_multiple_columns() -> Result<()> {
         let table_name = "test_table";
@@ -0,0 +1,1051 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Table functions evaluating simple statistical models over a time series, so that basic
+//! predictive dashboards could be built in SQL:
+//! - `forecast(series, horizon)` predicts the points within `horizon` (e.g. `'1h'`) after
+//!   the last point by Holt's linear trend method, a double EWMA of the level and the trend;
+//! - `seasonal_forecast(series, horizon, season)` predicts the points within `horizon` after
+//!   the last point by the seasonal naive method, which repeats the values one season earlier;
+//! - `anomaly_score(series)` scores each point by how many standard deviations it is away
+//!   from the EWMA of the points before it.
+//!
+//! The `series` is a table or a subquery, whose first timestamp column and first numeric
+//! column are the timestamps and the values of the points. Predicted points are spaced by
+//! the average interval of the series. Forecasts output columns `ts` and `value`, anomaly
+//! scores output columns `ts`, `value` and `score`.
+//!
+//! Table functions in FROM clauses are planned by [plan_table_function_query()], which
+//! replaces them by placeholder tables while planning the query and by [TableFunction]
+//! nodes afterwards. The nodes are executed by [TableFunctionExec].
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{
+    Array, ArrayRef, Float64Array, Int64Array, TimestampMillisecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::error::{ArrowError, Result as ArrowResult};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::TableReference;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::planner::ExtensionPlanner;
+use datafusion::physical_plan::udaf::AggregateUDF;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalPlanner, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion::sql::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion::sql::sqlparser::ast::{
+    Expr as SqlExpr, FunctionArg, FunctionArgExpr, Ident, ObjectName, Query as SpQuery, Select,
+    SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins, Value as SqlValue,
+    WildcardAdditionalOptions,
+};
+use datafusion_common::{DFSchemaRef, DataFusionError, ScalarValue};
+use datafusion_expr::utils::from_plan;
+use datafusion_expr::{
+    Expr, Extension, LogicalPlan, LogicalPlanBuilder, TableSource, UserDefinedLogicalNode,
+};
+use datatypes::arrow::compute;
+use futures::{ready, Stream, StreamExt};
+
+/// Name prefix of the tables standing in for table functions while planning the query.
+const TABLE_FUNCTION_PREFIX: &str = "__table_function_";
+/// Max number of points predicted by a forecast.
+const MAX_FORECAST_POINTS: i64 = 100_000;
+/// Smoothing factor of the level in [holt_forecast()].
+const LEVEL_ALPHA: f64 = 0.5;
+/// Smoothing factor of the trend in [holt_forecast()].
+const TREND_BETA: f64 = 0.3;
+/// Smoothing factor of the mean and variance in [anomaly_scores()].
+const ANOMALY_ALPHA: f64 = 0.3;
+
+/// Table function over a time series, durations are in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesFunction {
+    Forecast { horizon: i64 },
+    SeasonalForecast { horizon: i64, season: i64 },
+    AnomalyScore,
+}
+
+impl SeriesFunction {
+    /// Creates the function `name` with the duration arguments, returns `None` if there is
+    /// no such function.
+    fn try_new(name: &str, durations: &[i64]) -> Option<DfResult<Self>> {
+        let function = match (name, durations) {
+            ("forecast", [horizon]) => Ok(Self::Forecast { horizon: *horizon }),
+            ("forecast", _) => Err(invalid_args_num(name, 1, durations.len())),
+            ("seasonal_forecast", [horizon, season]) => Ok(Self::SeasonalForecast {
+                horizon: *horizon,
+                season: *season,
+            }),
+            ("seasonal_forecast", _) => Err(invalid_args_num(name, 2, durations.len())),
+            ("anomaly_score", []) => Ok(Self::AnomalyScore),
+            ("anomaly_score", _) => Err(invalid_args_num(name, 0, durations.len())),
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Forecast { .. } => "forecast",
+            Self::SeasonalForecast { .. } => "seasonal_forecast",
+            Self::AnomalyScore => "anomaly_score",
+        }
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        let mut fields = vec![
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Float64, true),
+        ];
+        if let Self::AnomalyScore = self {
+            fields.push(Field::new("score", DataType::Float64, true));
+        }
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Evaluates the function over `points`, which are `(timestamp_millis, value)` pairs
+    /// sorted by timestamp.
+    fn evaluate(&self, schema: SchemaRef, points: &[(i64, f64)]) -> ArrowResult<RecordBatch> {
+        let (timestamps, values, scores): (Vec<_>, Vec<_>, _) = match self {
+            Self::Forecast { horizon } => {
+                let (timestamps, values) = holt_forecast(points, *horizon)?.into_iter().unzip();
+                (timestamps, values, None)
+            }
+            Self::SeasonalForecast { horizon, season } => {
+                let (timestamps, values) = seasonal_naive_forecast(points, *horizon, *season)?
+                    .into_iter()
+                    .unzip();
+                (timestamps, values, None)
+            }
+            Self::AnomalyScore => (
+                points.iter().map(|(ts, _)| *ts).collect(),
+                points.iter().map(|(_, value)| Some(*value)).collect(),
+                Some(anomaly_scores(points)),
+            ),
+        };
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(TimestampMillisecondArray::from(timestamps)),
+            Arc::new(Float64Array::from(values)),
+        ];
+        if let Some(scores) = scores {
+            columns.push(Arc::new(Float64Array::from(scores)));
+        }
+        RecordBatch::try_new(schema, columns)
+    }
+}
+
+impl fmt::Display for SeriesFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let duration =
+            |millis: i64| humantime::format_duration(Duration::from_millis(millis as u64));
+        match self {
+            Self::Forecast { horizon } => write!(f, "forecast(horizon={})", duration(*horizon)),
+            Self::SeasonalForecast { horizon, season } => write!(
+                f,
+                "seasonal_forecast(horizon={}, season={})",
+                duration(*horizon),
+                duration(*season)
+            ),
+            Self::AnomalyScore => write!(f, "anomaly_score()"),
+        }
+    }
+}
+
+fn invalid_args_num(name: &str, expected: usize, actual: usize) -> DataFusionError {
+    DataFusionError::Plan(format!(
+        "Table function {name} expects a series and {expected} durations, have {actual} durations"
+    ))
+}
+
+/// Returns the average interval of `points`, `None` if the points don't span any time.
+fn average_interval(points: &[(i64, f64)]) -> Option<i64> {
+    let (first, last) = (points.first()?, points.last()?);
+    let interval = (last.0 - first.0) / (points.len() as i64 - 1).max(1);
+    (interval > 0).then_some(interval)
+}
+
+/// Returns the timestamps to predict within `horizon` after `last`, spaced by `step`. The
+/// timestamp `last + horizon` is predicted if the `horizon` is shorter than the `step`.
+fn forecast_timestamps(last: i64, step: Option<i64>, horizon: i64) -> ArrowResult<Vec<i64>> {
+    let Some(step) = step.filter(|step| *step <= horizon) else {
+        return Ok(vec![last + horizon]);
+    };
+    let num_points = horizon / step;
+    if num_points > MAX_FORECAST_POINTS {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Too many points to forecast, horizon: {horizon}ms, interval: {step}ms, limit: \
+             {MAX_FORECAST_POINTS}"
+        )));
+    }
+    Ok((1..=num_points).map(|i| last + i * step).collect())
+}
+
+/// Predicts the points within `horizon` after the last of `points` by Holt's linear trend
+/// method.
+fn holt_forecast(points: &[(i64, f64)], horizon: i64) -> ArrowResult<Vec<(i64, Option<f64>)>> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Ok(vec![]);
+    };
+    let step = average_interval(points);
+    let timestamps = forecast_timestamps(last.0, step, horizon)?;
+    let Some(step) = step else {
+        return Ok(timestamps.into_iter().map(|ts| (ts, Some(last.1))).collect());
+    };
+
+    let mut level = first.1;
+    let mut trend = points[1].1 - first.1;
+    for (_, value) in &points[1..] {
+        let prev_level = level;
+        level = LEVEL_ALPHA * value + (1.0 - LEVEL_ALPHA) * (level + trend);
+        trend = TREND_BETA * (level - prev_level) + (1.0 - TREND_BETA) * trend;
+    }
+    Ok(timestamps
+        .into_iter()
+        .map(|ts| {
+            let steps = (ts - last.0) as f64 / step as f64;
+            (ts, Some(level + trend * steps))
+        })
+        .collect())
+}
+
+/// Predicts the points within `horizon` after the last of `points` by the seasonal naive
+/// method, i.e. the value at the same phase of the last complete season. The value is
+/// `None` if the points don't cover that time.
+fn seasonal_naive_forecast(
+    points: &[(i64, f64)],
+    horizon: i64,
+    season: i64,
+) -> ArrowResult<Vec<(i64, Option<f64>)>> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Ok(vec![]);
+    };
+    let timestamps = forecast_timestamps(last.0, average_interval(points), horizon)?;
+    Ok(timestamps
+        .into_iter()
+        .map(|ts| {
+            let seasons = (ts - last.0 + season - 1) / season;
+            let reference = ts - seasons * season;
+            if reference < first.0 {
+                return (ts, None);
+            }
+            // The last point at or before the reference time.
+            let index = points.partition_point(|(ts, _)| *ts <= reference);
+            (ts, Some(points[index - 1].1))
+        })
+        .collect())
+}
+
+/// Returns how many standard deviations each of `points` is away from the EWMA of the
+/// points before it. Scores of the first 2 points are `None`.
+fn anomaly_scores(points: &[(i64, f64)]) -> Vec<Option<f64>> {
+    let mut scores = Vec::with_capacity(points.len());
+    let (mut mean, mut variance) = (0.0, 0.0);
+    for (i, (_, value)) in points.iter().enumerate() {
+        if i < 2 {
+            scores.push(None);
+        } else {
+            let deviation = (value - mean).abs();
+            let score = if variance == 0.0 {
+                if deviation == 0.0 {
+                    0.0
+                } else {
+                    f64::INFINITY
+                }
+            } else {
+                deviation / f64::sqrt(variance)
+            };
+            scores.push(Some(score));
+        }
+
+        if i == 0 {
+            mean = *value;
+        } else {
+            let diff = value - mean;
+            let incr = ANOMALY_ALPHA * diff;
+            mean += incr;
+            variance = (1.0 - ANOMALY_ALPHA) * (variance + diff * incr);
+        }
+    }
+    scores
+}
+
+/// Returns whether the FROM clauses in `query` have any table function.
+pub(crate) fn has_table_function(query: &SpQuery) -> bool {
+    let ctes = query.with.iter().flat_map(|with| &with.cte_tables);
+    ctes.into_iter().any(|cte| has_table_function(&cte.query))
+        || set_expr_has_table_function(&query.body)
+}
+
+fn set_expr_has_table_function(set_expr: &SetExpr) -> bool {
+    match set_expr {
+        SetExpr::Select(select) => select.from.iter().any(from_has_table_function),
+        SetExpr::Query(query) => has_table_function(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_has_table_function(left) || set_expr_has_table_function(right)
+        }
+        _ => false,
+    }
+}
+
+fn from_has_table_function(from: &TableWithJoins) -> bool {
+    relation_has_table_function(&from.relation)
+        || from
+            .joins
+            .iter()
+            .any(|join| relation_has_table_function(&join.relation))
+}
+
+fn relation_has_table_function(relation: &TableFactor) -> bool {
+    match relation {
+        TableFactor::Table { name, args, .. } => {
+            args.is_some() && table_function_name(name).is_some()
+        }
+        TableFactor::Derived { subquery, .. } => has_table_function(subquery),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => from_has_table_function(table_with_joins),
+        _ => false,
+    }
+}
+
+/// Returns the lower case name of the table function `name`, `None` if `name` is not a
+/// table function.
+fn table_function_name(name: &ObjectName) -> Option<String> {
+    let [ident] = name.0.as_slice() else {
+        return None;
+    };
+    let name = ident.value.to_lowercase();
+    SeriesFunction::try_new(&name, &[])
+        .is_some()
+        .then_some(name)
+}
+
+/// Plans `query` whose FROM clauses have table functions.
+pub(crate) fn plan_table_function_query<S: ContextProvider>(
+    provider: &S,
+    mut query: SpQuery,
+    param_types: &[DataType],
+) -> DfResult<LogicalPlan> {
+    let provider = TableFunctionContextProvider {
+        inner: provider,
+        tables: RefCell::new(HashMap::new()),
+        param_types,
+    };
+    provider.rewrite_query(&mut query)?;
+    let plan = SqlToRel::new(&provider).query_to_plan(query, &mut provider.new_context())?;
+    replace_table_function_scans(&plan)
+}
+
+/// Resolves the placeholder tables of table functions to [TableFunctionTable]s and other
+/// tables by the `inner` provider.
+struct TableFunctionContextProvider<'a, S> {
+    inner: &'a S,
+    tables: RefCell<HashMap<String, Arc<TableFunctionTable>>>,
+    param_types: &'a [DataType],
+}
+
+impl<'a, S: ContextProvider> TableFunctionContextProvider<'a, S> {
+    fn new_context(&self) -> PlannerContext {
+        PlannerContext::new_with_prepare_param_data_types(self.param_types.to_vec())
+    }
+
+    /// Replaces the table functions in FROM clauses of `query` by placeholder tables.
+    fn rewrite_query(&self, query: &mut SpQuery) -> DfResult<()> {
+        if let Some(with) = &mut query.with {
+            for cte in &mut with.cte_tables {
+                self.rewrite_query(&mut cte.query)?;
+            }
+        }
+        self.rewrite_set_expr(&mut query.body)
+    }
+
+    fn rewrite_set_expr(&self, set_expr: &mut SetExpr) -> DfResult<()> {
+        match set_expr {
+            SetExpr::Select(select) => {
+                for from in &mut select.from {
+                    self.rewrite_from(from)?;
+                }
+                Ok(())
+            }
+            SetExpr::Query(query) => self.rewrite_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.rewrite_set_expr(left)?;
+                self.rewrite_set_expr(right)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn rewrite_from(&self, from: &mut TableWithJoins) -> DfResult<()> {
+        self.rewrite_relation(&mut from.relation)?;
+        for join in &mut from.joins {
+            self.rewrite_relation(&mut join.relation)?;
+        }
+        Ok(())
+    }
+
+    fn rewrite_relation(&self, relation: &mut TableFactor) -> DfResult<()> {
+        match relation {
+            TableFactor::Table {
+                name,
+                alias,
+                args: Some(args),
+                ..
+            } => {
+                if let Some(function_name) = table_function_name(name) {
+                    *relation = self.plan_table_function(&function_name, args, alias.take())?;
+                }
+                Ok(())
+            }
+            TableFactor::Derived { subquery, .. } => self.rewrite_query(subquery),
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.rewrite_from(table_with_joins),
+            _ => Ok(()),
+        }
+    }
+
+    /// Plans the table function `name` with `args`, and returns the placeholder table to
+    /// replace it. The placeholder is aliased by the function name if there is no `alias`.
+    fn plan_table_function(
+        &self,
+        name: &str,
+        args: &[FunctionArg],
+        alias: Option<TableAlias>,
+    ) -> DfResult<TableFactor> {
+        let args = args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Ok(expr),
+                arg => Err(DataFusionError::Plan(format!(
+                    "Invalid argument of table function {name}: {arg}"
+                ))),
+            })
+            .collect::<DfResult<Vec<_>>>()?;
+        let Some((series, durations)) = args.split_first() else {
+            return Err(DataFusionError::Plan(format!(
+                "Table function {name} expects a series"
+            )));
+        };
+        let durations = durations
+            .iter()
+            .map(|duration| parse_duration_millis(name, duration))
+            .collect::<DfResult<Vec<_>>>()?;
+        let function = SeriesFunction::try_new(name, &durations).ok_or_else(|| {
+            DataFusionError::Internal(format!("{name} is not a table function"))
+        })??;
+        let input = self.plan_series(name, series)?;
+
+        let mut tables = self.tables.borrow_mut();
+        let table_name = format!("{TABLE_FUNCTION_PREFIX}{}", tables.len());
+        let _ = tables.insert(
+            table_name.clone(),
+            Arc::new(TableFunctionTable::new(function, input)),
+        );
+        let alias = alias.unwrap_or_else(|| TableAlias {
+            name: Ident::new(name),
+            columns: vec![],
+        });
+        Ok(TableFactor::Table {
+            name: ObjectName(vec![Ident::new(table_name)]),
+            alias: Some(alias),
+            args: None,
+            with_hints: vec![],
+        })
+    }
+
+    /// Plans the `series` argument of table function `name`, the plan outputs the
+    /// timestamps and the values of the points.
+    fn plan_series(&self, name: &str, series: &SqlExpr) -> DfResult<LogicalPlan> {
+        let mut query = match series {
+            SqlExpr::Subquery(query) => query.as_ref().clone(),
+            SqlExpr::Identifier(ident) => select_all(ObjectName(vec![ident.clone()])),
+            SqlExpr::CompoundIdentifier(idents) => select_all(ObjectName(idents.clone())),
+            expr => {
+                return Err(DataFusionError::Plan(format!(
+                    "The series of table function {name} must be a table or a subquery, \
+                     have: {expr}"
+                )))
+            }
+        };
+        self.rewrite_query(&mut query)?;
+        let plan = SqlToRel::new(self).query_to_plan(query, &mut self.new_context())?;
+        let plan = replace_table_function_scans(&plan)?;
+
+        let fields = plan.schema().fields();
+        let timestamp = fields
+            .iter()
+            .find(|field| matches!(field.data_type(), DataType::Timestamp(_, _)));
+        let value = fields.iter().find(|field| is_numeric(field.data_type()));
+        let (Some(timestamp), Some(value)) = (timestamp, value) else {
+            return Err(DataFusionError::Plan(format!(
+                "The series of table function {name} must have a timestamp column and a \
+                 numeric column, have: {}",
+                plan.schema()
+            )));
+        };
+        let exprs = vec![
+            Expr::Column(timestamp.qualified_column()),
+            Expr::Column(value.qualified_column()),
+        ];
+        LogicalPlanBuilder::from(plan).project(exprs)?.build()
+    }
+}
+
+impl<'a, S: ContextProvider> ContextProvider for TableFunctionContextProvider<'a, S> {
+    fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
+        if let TableReference::Bare { table } = name {
+            if let Some(table) = self.tables.borrow().get(table) {
+                return Ok(table.clone());
+            }
+        }
+        self.inner.get_table_provider(name)
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.inner.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.inner.get_aggregate_meta(name)
+    }
+
+    fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
+        self.inner.get_variable_type(variable_names)
+    }
+
+    fn get_config_option(&self, variable: &str) -> Option<ScalarValue> {
+        self.inner.get_config_option(variable)
+    }
+}
+
+/// Parses the duration argument like `'1h'` of table function `name` to milliseconds.
+fn parse_duration_millis(name: &str, duration: &SqlExpr) -> DfResult<i64> {
+    let millis = match duration {
+        SqlExpr::Value(SqlValue::SingleQuotedString(s)) => humantime::parse_duration(s)
+            .ok()
+            .map(|d| d.as_millis() as i64)
+            .filter(|millis| *millis > 0),
+        _ => None,
+    };
+    millis.ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "The durations of table function {name} must be positive durations like '1h', \
+             have: {duration}"
+        ))
+    })
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+    )
+}
+
+/// Builds `SELECT * FROM <table>`.
+fn select_all(table: ObjectName) -> SpQuery {
+    let select = Select {
+        distinct: false,
+        top: None,
+        projection: vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())],
+        into: None,
+        from: vec![TableWithJoins {
+            relation: TableFactor::Table {
+                name: table,
+                alias: None,
+                args: None,
+                with_hints: vec![],
+            },
+            joins: vec![],
+        }],
+        lateral_views: vec![],
+        selection: None,
+        group_by: vec![],
+        cluster_by: vec![],
+        distribute_by: vec![],
+        sort_by: vec![],
+        having: None,
+        qualify: None,
+    };
+    SpQuery {
+        with: None,
+        body: Box::new(SetExpr::Select(Box::new(select))),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+        lock: None,
+    }
+}
+
+/// Placeholder table of a table function.
+struct TableFunctionTable {
+    function: SeriesFunction,
+    /// Plan of the series.
+    input: LogicalPlan,
+    schema: SchemaRef,
+}
+
+impl TableFunctionTable {
+    fn new(function: SeriesFunction, input: LogicalPlan) -> Self {
+        Self {
+            function,
+            input,
+            schema: function.output_schema(),
+        }
+    }
+}
+
+impl TableSource for TableFunctionTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Replaces the scans of placeholder tables of table functions by [TableFunction]s.
+fn replace_table_function_scans(plan: &LogicalPlan) -> DfResult<LogicalPlan> {
+    if let LogicalPlan::TableScan(scan) = plan {
+        if let Some(table) = scan.source.as_any().downcast_ref::<TableFunctionTable>() {
+            return Ok(LogicalPlan::Extension(Extension {
+                node: Arc::new(TableFunction {
+                    function: table.function,
+                    input: table.input.clone(),
+                    schema: scan.projected_schema.clone(),
+                }),
+            }));
+        }
+    }
+
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(replace_table_function_scans)
+        .collect::<DfResult<Vec<_>>>()?;
+    from_plan(plan, &plan.expressions(), &inputs)
+}
+
+/// Logical plan node of a table function, whose input outputs the timestamps and the
+/// values of the series.
+#[derive(Debug)]
+pub struct TableFunction {
+    function: SeriesFunction,
+    input: LogicalPlan,
+    schema: DFSchemaRef,
+}
+
+impl UserDefinedLogicalNode for TableFunction {
+    fn as_any(&self) -> &dyn Any {
+        self as _
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TableFunction: {}", self.function)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode> {
+        assert_eq!(1, inputs.len());
+
+        Arc::new(Self {
+            function: self.function,
+            input: inputs[0].clone(),
+            schema: self.schema.clone(),
+        })
+    }
+}
+
+/// Plans [TableFunction] nodes to [TableFunctionExec].
+pub struct TableFunctionPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for TableFunctionPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(node) = node.as_any().downcast_ref::<TableFunction>() else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(TableFunctionExec::new(
+            node.function,
+            physical_inputs[0].clone(),
+        ))))
+    }
+}
+
+/// Executes the table function by collecting all points of the series.
+#[derive(Debug)]
+pub struct TableFunctionExec {
+    function: SeriesFunction,
+    input: Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl TableFunctionExec {
+    pub fn new(function: SeriesFunction, input: Arc<dyn ExecutionPlan>) -> Self {
+        let input = if input.output_partitioning().partition_count() > 1 {
+            Arc::new(CoalescePartitionsExec::new(input))
+        } else {
+            input
+        };
+        Self {
+            function,
+            input,
+            schema: function.output_schema(),
+            metric: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for TableFunctionExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(1, children.len());
+        Ok(Arc::new(Self::new(self.function, children[0].clone())))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let input = self.input.execute(0, context)?;
+        Ok(Box::pin(TableFunctionStream {
+            function: self.function,
+            schema: self.schema.clone(),
+            input,
+            points: Vec::new(),
+            finished: false,
+            metric: BaselineMetrics::new(&self.metric, partition),
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "TableFunctionExec: {}", self.function),
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+struct TableFunctionStream {
+    function: SeriesFunction,
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    /// `(timestamp_millis, value)` of the points read from the input.
+    points: Vec<(i64, f64)>,
+    finished: bool,
+    metric: BaselineMetrics,
+}
+
+impl TableFunctionStream {
+    fn push_points(&mut self, batch: &RecordBatch) -> ArrowResult<()> {
+        let timestamps = compute::cast(
+            batch.column(0),
+            &DataType::Timestamp(TimeUnit::Millisecond, None),
+        )?;
+        let timestamps = compute::cast(&timestamps, &DataType::Int64)?;
+        let timestamps = timestamps
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .expect("casted to int64");
+        let values = compute::cast(batch.column(1), &DataType::Float64)?;
+        let values = values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .expect("casted to float64");
+        for row in 0..batch.num_rows() {
+            if timestamps.is_valid(row) && values.is_valid(row) {
+                self.points.push((timestamps.value(row), values.value(row)));
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> ArrowResult<RecordBatch> {
+        let _timer = self.metric.elapsed_compute().timer();
+        self.points.sort_by_key(|(ts, _)| *ts);
+        self.function.evaluate(self.schema.clone(), &self.points)
+    }
+}
+
+impl RecordBatchStream for TableFunctionStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for TableFunctionStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        loop {
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    if let Err(e) = self.push_points(&batch) {
+                        self.finished = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+                Some(Err(e)) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                None => {
+                    self.finished = true;
+                    let result = self.evaluate();
+                    let poll = Poll::Ready(Some(result));
+                    return self.metric.record_poll(poll);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::{Int32Array, TimestampSecondArray};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+    use sql::dialect::GenericDialect;
+    use sql::parser::ParserContext;
+    use sql::statements::statement::Statement;
+
+    use super::*;
+
+    fn new_points(values: &[f64]) -> Vec<(i64, f64)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i as i64 * 1000, *v))
+            .collect()
+    }
+
+    fn assert_approx_eq(expect: f64, actual: Option<f64>) {
+        let actual = actual.unwrap();
+        assert!((expect - actual).abs() < 1e-9, "{expect} != {actual}");
+    }
+
+    #[test]
+    fn test_holt_forecast() {
+        assert!(holt_forecast(&[], 1000).unwrap().is_empty());
+        assert_eq!(
+            vec![(1000, Some(3.0))],
+            holt_forecast(&[(0, 3.0)], 1000).unwrap()
+        );
+
+        // A linear trend is followed.
+        let points = new_points(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let forecast = holt_forecast(&points, 2000).unwrap();
+        assert_eq!(2, forecast.len());
+        assert_eq!(5000, forecast[0].0);
+        assert_approx_eq(6.0, forecast[0].1);
+        assert_eq!(6000, forecast[1].0);
+        assert_approx_eq(7.0, forecast[1].1);
+
+        // A constant series stays constant, the horizon shorter than the interval is
+        // predicted as is.
+        let points = new_points(&[2.0; 5]);
+        assert_eq!(
+            vec![(4500, Some(2.0))],
+            holt_forecast(&points, 500).unwrap()
+        );
+
+        // Too many points to predict.
+        assert!(holt_forecast(&points, 1000 * (MAX_FORECAST_POINTS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_seasonal_naive_forecast() {
+        // Season of 3 seconds.
+        let points = new_points(&[1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+        assert_eq!(
+            vec![(6000, Some(1.0)), (7000, Some(2.0)), (8000, Some(3.0))],
+            seasonal_naive_forecast(&points, 3000, 3000).unwrap()
+        );
+        assert_eq!(
+            vec![(6000, Some(1.0)), (7000, Some(2.0))],
+            seasonal_naive_forecast(&points, 2000, 3000).unwrap()
+        );
+        // The season is longer than the series.
+        assert_eq!(
+            vec![(6000, None)],
+            seasonal_naive_forecast(&points, 1000, 10_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_anomaly_scores() {
+        assert_eq!(vec![None, None], anomaly_scores(&new_points(&[1.0, 2.0])));
+        assert_eq!(
+            vec![None, None, Some(0.0), Some(f64::INFINITY)],
+            anomaly_scores(&new_points(&[1.0, 1.0, 1.0, 2.0]))
+        );
+
+        let scores = anomaly_scores(&new_points(&[1.0, 2.0, 1.0, 2.0, 1.5, 10.0]));
+        assert!(scores[4].unwrap() < 1.0);
+        assert!(scores[5].unwrap() > 3.0);
+    }
+
+    #[test]
+    fn test_has_table_function() {
+        let has_table_function = |sql: &str| {
+            let statement = ParserContext::create_with_dialect(sql, &GenericDialect {})
+                .unwrap()
+                .remove(0);
+            match statement {
+                Statement::Query(query) => super::has_table_function(&query.inner),
+                _ => unreachable!(),
+            }
+        };
+
+        assert!(has_table_function("SELECT * FROM forecast(cpu, '1h')"));
+        assert!(has_table_function(
+            "SELECT * FROM (SELECT * FROM ANOMALY_SCORE((SELECT ts, v FROM cpu))) s"
+        ));
+        assert!(has_table_function(
+            "WITH f AS (SELECT * FROM seasonal_forecast(cpu, '1h', '1d')) SELECT * FROM f"
+        ));
+        assert!(!has_table_function("SELECT * FROM forecast"));
+        assert!(!has_table_function("SELECT forecast(ts, v, '1h') FROM cpu"));
+    }
+
+    #[tokio::test]
+    async fn test_table_function_exec() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Second, None), true),
+            Field::new("v", DataType::Int32, true),
+        ]));
+        let new_batch = |timestamps: Vec<Option<i64>>, values: Vec<Option<i32>>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(TimestampSecondArray::from(timestamps)) as _,
+                    Arc::new(Int32Array::from(values)) as _,
+                ],
+            )
+            .unwrap()
+        };
+        // Points out of order and with nulls.
+        let batches = vec![
+            new_batch(vec![Some(3), Some(1)], vec![Some(5), Some(2)]),
+            new_batch(vec![Some(2), None, Some(4)], vec![Some(2), Some(4), None]),
+        ];
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap());
+        let exec = Arc::new(TableFunctionExec::new(SeriesFunction::AnomalyScore, input));
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        let expected = "\
++---------------------+-------+-------+
+| ts                  | value | score |
++---------------------+-------+-------+
+| 1970-01-01T00:00:01 | 2     |       |
+| 1970-01-01T00:00:02 | 2     |       |
+| 1970-01-01T00:00:03 | 5     | inf   |
++---------------------+-------+-------+";
+        assert_eq!(expected, result_literal);
+    }
+}
@@ -15,10 +15,13 @@
 //! Script engine
 
 use std::any::Any;
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use common_error::ext::ErrorExt;
+use common_error::ext::{BoxedError, ErrorExt};
 use common_query::Output;
+use session::context::QueryContextRef;
+use table::requests::InsertRequest;
 
 #[async_trait]
 pub trait Script {
@@ -51,9 +54,27 @@ pub trait ScriptEngine {
     ) -> std::result::Result<Self::Script, Self::Error>;
 }
 
+/// Handler to insert the rows written by scripts into tables, expected to check the
+/// inserts as the ones from SQL.
+#[async_trait]
+pub trait InsertHandler: Send + Sync {
+    /// Inserts `request` on behalf of the user in `query_ctx` who executes the script,
+    /// returns the number of rows inserted.
+    async fn insert(
+        &self,
+        request: InsertRequest,
+        query_ctx: QueryContextRef,
+    ) -> std::result::Result<usize, BoxedError>;
+}
+
+pub type InsertHandlerRef = Arc<dyn InsertHandler>;
+
 /// Evaluate script context
-#[derive(Debug, Default)]
-pub struct EvalContext {}
+#[derive(Default)]
+pub struct EvalContext {
+    /// Context of the query executing the script, a default context is used if not set.
+    pub query_ctx: Option<QueryContextRef>,
+}
 
 /// Compile script context
 #[derive(Debug, Default)]
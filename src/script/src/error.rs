@@ -61,6 +61,40 @@ pub enum Error {
         source: crate::python::error::Error,
     },
 
+    #[cfg(feature = "wasm")]
+    #[snafu(display(
+        "Failed to compile WebAssembly script, name: {}, source: {}",
+        name,
+        source
+    ))]
+    CompileWasm {
+        name: String,
+        #[snafu(backtrace)]
+        source: crate::wasm::error::Error,
+    },
+
+    #[cfg(feature = "wasm")]
+    #[snafu(display("Failed to execute WebAssembly script {}, source: {}", name, source))]
+    ExecuteWasm {
+        name: String,
+        #[snafu(backtrace)]
+        source: crate::wasm::error::Error,
+    },
+
+    #[cfg(feature = "wasm")]
+    #[snafu(display("Failed to create WebAssembly engine, source: {}", source))]
+    CreateWasmEngine {
+        #[snafu(backtrace)]
+        source: crate::wasm::error::Error,
+    },
+
+    #[snafu(display("Unknown engine {} of script {}", engine, name))]
+    UnknownEngine {
+        name: String,
+        engine: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Script not found, name: {}", name))]
     ScriptNotFound { backtrace: Backtrace, name: String },
 
@@ -94,7 +128,11 @@ impl ErrorExt for Error {
             CompilePython { source, .. } | ExecutePython { source, .. } => source.status_code(),
             FindScript { source, .. } => source.status_code(),
             CollectRecords { source } => source.status_code(),
-            ScriptNotFound { .. } => StatusCode::InvalidArguments,
+            #[cfg(feature = "wasm")]
+            CompileWasm { source, .. }
+            | ExecuteWasm { source, .. }
+            | CreateWasmEngine { source } => source.status_code(),
+            ScriptNotFound { .. } | UnknownEngine { .. } => StatusCode::InvalidArguments,
         }
     }
 
@@ -19,3 +19,5 @@ pub mod manager;
 #[cfg(feature = "python")]
 pub mod python;
 mod table;
+#[cfg(feature = "wasm")]
+pub mod wasm;
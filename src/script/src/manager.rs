@@ -20,16 +20,35 @@ use catalog::CatalogManagerRef;
 use common_query::Output;
 use common_telemetry::logging;
 use query::QueryEngineRef;
+use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
 
-use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
-use crate::error::{CompilePythonSnafu, ExecutePythonSnafu, Result, ScriptNotFoundSnafu};
+use crate::engine::{CompileContext, EvalContext, InsertHandlerRef, Script, ScriptEngine};
+use crate::error::{
+    CompilePythonSnafu, ExecutePythonSnafu, Result, ScriptNotFoundSnafu, UnknownEngineSnafu,
+};
+#[cfg(feature = "wasm")]
+use crate::error::{CompileWasmSnafu, CreateWasmEngineSnafu, ExecuteWasmSnafu};
 use crate::python::{PyEngine, PyScript};
 use crate::table::ScriptsTable;
+#[cfg(feature = "wasm")]
+use crate::wasm::{is_wasm_script, WasmEngine, WasmScript};
+
+/// A compiled script, registered as UDF.
+#[derive(Clone)]
+pub enum CompiledScript {
+    Python(Arc<PyScript>),
+    #[cfg(feature = "wasm")]
+    Wasm(Arc<WasmScript>),
+}
 
 pub struct ScriptManager {
-    compiled: RwLock<HashMap<String, Arc<PyScript>>>,
+    compiled: RwLock<HashMap<String, CompiledScript>>,
     py_engine: PyEngine,
+    #[cfg(feature = "wasm")]
+    wasm_engine: WasmEngine,
+    #[cfg(feature = "wasm")]
+    query_engine: QueryEngineRef,
     table: ScriptsTable,
 }
 
@@ -41,37 +60,99 @@ impl ScriptManager {
         Ok(Self {
             compiled: RwLock::new(HashMap::default()),
             py_engine: PyEngine::new(query_engine.clone()),
+            #[cfg(feature = "wasm")]
+            wasm_engine: WasmEngine::try_new().context(CreateWasmEngineSnafu)?,
+            #[cfg(feature = "wasm")]
+            query_engine: query_engine.clone(),
             table: ScriptsTable::new(catalog_manager, query_engine).await?,
         })
     }
 
-    async fn compile(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
-        let script = Arc::new(
-            self.py_engine
+    /// Allows scripts to write tables, which are inserted by `insert_handler`.
+    pub fn with_insert_handler(mut self, insert_handler: InsertHandlerRef) -> Self {
+        self.py_engine = self.py_engine.with_insert_handler(insert_handler);
+        self
+    }
+
+    /// Allows scripts running in CPython, see [PyEngine::with_cpython_backend].
+    pub fn with_cpython_backend(mut self, enable: bool) -> Self {
+        self.py_engine = self.py_engine.with_cpython_backend(enable);
+        self
+    }
+
+    /// Returns the name of the engine to run `script`, WebAssembly modules are run by the
+    /// WebAssembly engine if the `wasm` feature is enabled, others by the Python engine.
+    fn detect_engine(&self, script: &str) -> &str {
+        #[cfg(feature = "wasm")]
+        if is_wasm_script(script) {
+            return self.wasm_engine.name();
+        }
+        #[cfg(not(feature = "wasm"))]
+        let _ = script;
+        self.py_engine.name()
+    }
+
+    async fn compile(&self, name: &str, script: &str, engine: &str) -> Result<CompiledScript> {
+        let compiled_script = if engine == self.py_engine.name() {
+            let script = self
+                .py_engine
                 .compile(script, CompileContext::default())
                 .await
-                .context(CompilePythonSnafu { name })?,
-        );
+                .context(CompilePythonSnafu { name })?;
+            script.register_udf();
+            CompiledScript::Python(Arc::new(script))
+        } else {
+            self.compile_other(name, script, engine).await?
+        };
 
-        let mut compiled = self.compiled.write().unwrap();
-        compiled.insert(name.to_string(), script.clone());
+        logging::info!("Compiled script and registered it as UDF: {}", name);
 
-        logging::info!("Compiled and cached script: {}", name);
+        let mut compiled = self.compiled.write().unwrap();
+        compiled.insert(name.to_string(), compiled_script.clone());
 
-        script.as_ref().register_udf();
+        Ok(compiled_script)
+    }
 
-        logging::info!("Script register as UDF: {}", name);
+    #[cfg(feature = "wasm")]
+    async fn compile_other(
+        &self,
+        name: &str,
+        script: &str,
+        engine: &str,
+    ) -> Result<CompiledScript> {
+        if engine != self.wasm_engine.name() {
+            return UnknownEngineSnafu { name, engine }.fail();
+        }
+        let script = self
+            .wasm_engine
+            .compile(script, CompileContext::default())
+            .await
+            .context(CompileWasmSnafu { name })?;
+        script.register_udf(&self.query_engine);
+        Ok(CompiledScript::Wasm(Arc::new(script)))
+    }
 
-        Ok(script)
+    #[cfg(not(feature = "wasm"))]
+    async fn compile_other(
+        &self,
+        name: &str,
+        _script: &str,
+        engine: &str,
+    ) -> Result<CompiledScript> {
+        UnknownEngineSnafu { name, engine }.fail()
     }
 
-    pub async fn insert_and_compile(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
-        let compiled_script = self.compile(name, script).await?;
-        self.table.insert(name, script).await?;
+    /// Compiles `script` by the engine detected from its content, registers it as UDF and
+    /// inserts it into the scripts table.
+    pub async fn insert_and_compile(&self, name: &str, script: &str) -> Result<CompiledScript> {
+        let engine = self.detect_engine(script).to_string();
+        let compiled_script = self.compile(name, script, &engine).await?;
+        self.table.insert(name, script, &engine).await?;
         Ok(compiled_script)
     }
 
-    pub async fn execute(&self, name: &str) -> Result<Output> {
+    /// Executes the script `name` on behalf of the user in `query_ctx`.
+    pub async fn execute(&self, name: &str, query_ctx: QueryContextRef) -> Result<Output> {
         let script = {
             let s = self.compiled.read().unwrap().get(name).cloned();
 
@@ -84,16 +165,26 @@ impl ScriptManager {
 
         let script = script.context(ScriptNotFoundSnafu { name })?;
 
-        script
-            .execute(EvalContext::default())
-            .await
-            .context(ExecutePythonSnafu { name })
+        let ctx = EvalContext {
+            query_ctx: Some(query_ctx),
+        };
+        match script {
+            CompiledScript::Python(script) => script
+                .execute(ctx)
+                .await
+                .context(ExecutePythonSnafu { name }),
+            // WebAssembly scripts are only called as functions, which fails with the reason.
+            #[cfg(feature = "wasm")]
+            CompiledScript::Wasm(script) => {
+                script.execute(ctx).await.context(ExecuteWasmSnafu { name })
+            }
+        }
     }
 
-    async fn try_find_script_and_compile(&self, name: &str) -> Result<Option<Arc<PyScript>>> {
-        let script = self.table.find_script_by_name(name).await?;
+    async fn try_find_script_and_compile(&self, name: &str) -> Result<Option<CompiledScript>> {
+        let (script, engine) = self.table.find_script_by_name(name).await?;
 
-        Ok(Some(self.compile(name, &script).await?))
+        Ok(Some(self.compile(name, &script, &engine).await?))
     }
 }
 
@@ -113,13 +204,13 @@ mod tests {
     use storage::EngineImpl;
     use tempdir::TempDir;
 
-    #[tokio::test]
-    async fn test_insert_find_compile_script() {
-        let wal_dir = TempDir::new("test_insert_find_compile_script_wal").unwrap();
+    /// Returns the script manager and the directories it writes.
+    async fn new_script_manager(name: &str) -> (ScriptManager, TempDir, TempDir) {
+        let wal_dir = TempDir::new(&format!("{name}_wal")).unwrap();
         let wal_dir_str = wal_dir.path().to_string_lossy();
 
         common_telemetry::init_default_ut_logging();
-        let (_dir, object_store) = new_test_object_store("test_insert_find_compile_script").await;
+        let (dir, object_store) = new_test_object_store(name).await;
         let log_config = LogConfig {
             log_file_dir: wal_dir_str.to_string(),
             ..Default::default()
@@ -148,6 +239,12 @@ mod tests {
             .await
             .unwrap();
         catalog_manager.start().await.unwrap();
+        (mgr, wal_dir, dir)
+    }
+
+    #[tokio::test]
+    async fn test_insert_find_compile_script() {
+        let (mgr, _wal_dir, _dir) = new_script_manager("test_insert_find_compile_script").await;
 
         let name = "test";
         mgr.table
@@ -158,6 +255,7 @@ mod tests {
 def test(n):
     return n + 1;
 "#,
+                "python",
             )
             .await
             .unwrap();
@@ -176,4 +274,31 @@ def test(n):
             assert!(cached.get(name).is_some());
         }
     }
+
+    #[cfg(feature = "wasm")]
+    #[tokio::test]
+    async fn test_insert_compile_wasm_script() {
+        let (mgr, _wal_dir, _dir) = new_script_manager("test_insert_compile_wasm_script").await;
+
+        let name = "ident";
+        let script = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 0))
+  (func (export "ident") (param $a i32) (param $len i32) (result i32) (local.get $a)))
+"#;
+        let compiled = mgr.insert_and_compile(name, script).await.unwrap();
+        assert!(matches!(compiled, CompiledScript::Wasm(_)));
+        let (_, engine) = mgr.table.find_script_by_name(name).await.unwrap();
+        assert_eq!("wasm", engine);
+
+        // Compiled by the engine stored in the scripts table.
+        mgr.compiled.write().unwrap().clear();
+        let compiled = mgr.try_find_script_and_compile(name).await.unwrap();
+        assert!(matches!(compiled, Some(CompiledScript::Wasm(_))));
+
+        // WebAssembly scripts could only be called as functions.
+        let query_ctx = session::context::QueryContext::arc();
+        assert!(mgr.execute(name, query_ctx).await.is_err());
+    }
 }
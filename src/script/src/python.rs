@@ -16,6 +16,8 @@
 
 mod builtins;
 pub(crate) mod coprocessor;
+#[cfg(feature = "pyo3_backend")]
+mod cpython;
 mod engine;
 pub mod error;
 #[cfg(test)]
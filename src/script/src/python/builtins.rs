@@ -276,6 +276,7 @@ fn eval_aggr_fn<T: AggregateExpr>(
 #[pymodule]
 pub(crate) mod greptime_builtin {
     // P.S.: not extract to file because not-inlined proc macro attribute is *unstable*
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use arrow::compute::kernels::{aggregate, boolean, comparison};
@@ -291,14 +292,16 @@ pub(crate) mod greptime_builtin {
     use datatypes::arrow::{self, compute};
     use datatypes::vectors::{ConstantVector, Float64Vector, Helper, Int64Vector, VectorRef};
     use paste::paste;
-    use rustpython_vm::builtins::{PyFloat, PyFunction, PyInt, PyStr};
+    use rustpython_vm::builtins::{PyDictRef, PyFloat, PyFunction, PyInt, PyStr};
     use rustpython_vm::function::{FuncArgs, KwArgs, OptionalArg};
     use rustpython_vm::{AsObject, PyObjectRef, PyPayload, PyRef, PyResult, VirtualMachine};
+    use table::requests::InsertRequest;
 
     use crate::python::builtins::{
         all_to_f64, eval_aggr_fn, from_df_err, try_into_columnar_value, try_into_py_obj,
         type_cast_error,
     };
+    use crate::python::coprocessor::inserter;
     use crate::python::utils::{is_instance, py_vec_obj_to_array, PyVectorRef};
     use crate::python::vector::val_to_pyobj;
     use crate::python::PyVector;
@@ -308,6 +311,66 @@ pub(crate) mod greptime_builtin {
         PyVector::new(args, vm)
     }
 
+    /// Inserts `columns`, a dict from column names to vectors or constants, into `table`
+    /// and returns the number of rows inserted. Constants are repeated to the length of
+    /// the vectors.
+    #[pyfunction]
+    fn insert(table: String, columns: PyDictRef, vm: &VirtualMachine) -> PyResult<usize> {
+        let inserter = inserter().ok_or_else(|| {
+            vm.new_runtime_error("Inserting into tables is not allowed here".to_string())
+        })?;
+        // Names are resolved against the database of the user executing the script.
+        let current_catalog = inserter.query_ctx.current_catalog();
+        let current_schema = inserter.query_ctx.current_schema();
+        let names = table.split('.').collect::<Vec<_>>();
+        let (catalog_name, schema_name, table_name) = match names[..] {
+            [table] => (current_catalog.as_str(), current_schema.as_str(), table),
+            [schema, table] => (current_catalog.as_str(), schema, table),
+            [catalog, schema, table] => (catalog, schema, table),
+            _ => return Err(vm.new_value_error(format!("Invalid table name: {table}"))),
+        };
+
+        let columns = (&columns)
+            .into_iter()
+            .map(|(name, value)| Ok((name.try_into_value::<String>(vm)?, value)))
+            .collect::<PyResult<Vec<_>>>()?;
+        let num_rows = columns
+            .iter()
+            .filter_map(|(_, value)| value.payload::<PyVector>())
+            .map(|vector| vector.as_vector_ref().len())
+            .max()
+            .unwrap_or(1);
+        let mut columns_values = HashMap::with_capacity(columns.len());
+        for (name, value) in columns {
+            let vector = py_vec_obj_to_array(&value, vm, num_rows)
+                .map_err(|e| vm.new_type_error(format!("Invalid values of column {name}: {e}")))?;
+            if vector.len() != num_rows {
+                return Err(vm.new_value_error(format!(
+                    "Column {name} has {} rows, expect {num_rows}",
+                    vector.len()
+                )));
+            }
+            columns_values.insert(name, vector);
+        }
+        let request = InsertRequest {
+            catalog_name: catalog_name.to_string(),
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            columns_values,
+        };
+
+        // Inserts in a dedicated thread like `query.sql()`, as the script may run in the
+        // runtime.
+        std::thread::spawn(move || -> std::result::Result<usize, String> {
+            let rt = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+            rt.block_on(inserter.handler.insert(request, inserter.query_ctx))
+                .map_err(|e| e.to_string())
+        })
+        .join()
+        .map_err(|e| vm.new_system_error(format!("Dedicated thread for insert panic: {e:?}")))?
+        .map_err(|e| vm.new_runtime_error(e))
+    }
+
     // the main binding code, due to proc macro things, can't directly use a simpler macro
     // because pyfunction is not a attr?
     // ------
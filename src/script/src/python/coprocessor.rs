@@ -35,14 +35,16 @@ use rustpython_vm::class::PyClassImpl;
 use rustpython_vm::AsObject;
 #[cfg(test)]
 use serde::Deserialize;
+use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
 use vm::builtins::{PyBaseExceptionRef, PyList, PyListRef, PyTuple};
 use vm::convert::ToPyObject;
 use vm::scope::Scope;
 use vm::{pyclass, Interpreter, PyObjectRef, PyPayload, PyResult, VirtualMachine};
 
+use crate::engine::InsertHandlerRef;
 use crate::python::builtins::greptime_builtin;
-use crate::python::coprocessor::parse::DecoratorArgs;
+use crate::python::coprocessor::parse::{BackendType, DecoratorArgs};
 use crate::python::error::{
     ensure, ret_other_error_with, ArrowSnafu, NewRecordBatchSnafu, OtherSnafu, Result,
     TypeCastSnafu,
@@ -52,6 +54,31 @@ use crate::python::PyVector;
 
 thread_local!(static INTERPRETER: RefCell<Option<Arc<Interpreter>>> = RefCell::new(None));
 
+/// Inserts the rows written by `greptime.insert()` on behalf of the user executing the
+/// script.
+#[derive(Clone)]
+pub(crate) struct ScriptInserter {
+    pub(crate) handler: InsertHandlerRef,
+    pub(crate) query_ctx: QueryContextRef,
+}
+
+// Inserter of `greptime.insert()` in the script running on this thread, which is unset
+// unless the script is allowed to write tables.
+thread_local!(static INSERTER: RefCell<Option<ScriptInserter>> = RefCell::new(None));
+
+/// Runs `f` with `inserter` inserting the rows written by `greptime.insert()`.
+pub(crate) fn with_inserter<T>(inserter: Option<ScriptInserter>, f: impl FnOnce() -> T) -> T {
+    let prev = INSERTER.with(|h| h.replace(inserter));
+    let ret = f();
+    INSERTER.with(|h| *h.borrow_mut() = prev);
+    ret
+}
+
+/// Returns the inserter of `greptime.insert()` in the running script.
+pub(crate) fn inserter() -> Option<ScriptInserter> {
+    INSERTER.with(|h| h.borrow().clone())
+}
+
 #[cfg_attr(test, derive(Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AnnotationInfo {
@@ -82,6 +109,10 @@ pub struct Coprocessor {
     pub code_obj: Option<CodeObject>,
     #[cfg_attr(test, serde(skip))]
     pub query_engine: Option<QueryEngineWeakRef>,
+    /// The function defined by the script in CPython, defined on the first run.
+    #[cfg(feature = "pyo3_backend")]
+    #[cfg_attr(test, serde(skip))]
+    pub cpython_func: Arc<once_cell::sync::OnceCell<pyo3::PyObject>>,
 }
 
 #[derive(Clone)]
@@ -123,7 +154,7 @@ impl Coprocessor {
     /// generate [`Schema`] according to return names, types,
     /// if no annotation
     /// the datatypes of the actual columns is used directly
-    fn gen_schema(&self, cols: &[VectorRef]) -> Result<SchemaRef> {
+    pub(crate) fn gen_schema(&self, cols: &[VectorRef]) -> Result<SchemaRef> {
         let names = &self.deco_args.ret_names;
         let anno = &self.return_types;
         ensure!(
@@ -166,7 +197,7 @@ impl Coprocessor {
     }
 
     /// check if real types and annotation types(if have) is the same, if not try cast columns to annotated type
-    fn check_and_cast_type(&self, cols: &mut [VectorRef]) -> Result<()> {
+    pub(crate) fn check_and_cast_type(&self, cols: &mut [VectorRef]) -> Result<()> {
         let return_types = &self.return_types;
         // allow ignore Return Type Annotation
         if return_types.is_empty() {
@@ -530,11 +561,39 @@ pub(crate) fn exec_parsed(copr: &Coprocessor, rb: &RecordBatch) -> Result<Record
     // 3. get args from `rb`, and cast them into PyVector
     let args: Vec<PyVector> = select_from_rb(rb, &copr.deco_args.arg_names)?;
     check_args_anno_real_type(&args, copr, rb)?;
+    if copr.deco_args.backend == BackendType::CPython {
+        let args = args.iter().map(PyVector::as_vector_ref).collect();
+        return exec_with_cpython(copr, rb, args);
+    }
     let interpreter = init_interpreter();
     // 4. then set args in scope and compile then run `CodeObject` which already append a new `Call` node
     exec_with_cached_vm(copr, rb, args, &interpreter)
 }
 
+#[cfg(feature = "pyo3_backend")]
+fn exec_with_cpython(
+    copr: &Coprocessor,
+    rb: &RecordBatch,
+    args: Vec<VectorRef>,
+) -> Result<RecordBatch> {
+    crate::python::cpython::cpython_exec_parsed(copr, rb, args)
+}
+
+#[cfg(not(feature = "pyo3_backend"))]
+fn exec_with_cpython(
+    copr: &Coprocessor,
+    _rb: &RecordBatch,
+    _args: Vec<VectorRef>,
+) -> Result<RecordBatch> {
+    OtherSnafu {
+        reason: format!(
+            "Coprocessor {} requires the CPython backend, which is not enabled",
+            copr.name
+        ),
+    }
+    .fail()
+}
+
 /// execute script just like [`exec_coprocessor`] do,
 /// but instead of return a internal [`Error`] type,
 /// return a friendly String format of error
@@ -556,7 +615,7 @@ pub fn exec_copr_print(
 
 #[cfg(test)]
 mod tests {
-    use crate::python::coprocessor::parse::parse_and_compile_copr;
+    use crate::python::coprocessor::parse::{parse_and_compile_copr, BackendType};
 
     #[test]
     fn test_parse_copr() {
@@ -583,5 +642,71 @@ def test(a, b, c):
         assert_eq!(copr.return_types, vec![None]);
         assert_eq!(copr.script, script);
         assert!(copr.code_obj.is_some());
+        assert_eq!(deco_args.backend, BackendType::RustPython);
+    }
+
+    #[test]
+    fn test_parse_copr_backend() {
+        let script = r#"
+@copr(args=["a"], returns=["r"], backend="{backend}")
+def test(a):
+    return a
+"#;
+        let parse = |backend: &str| {
+            parse_and_compile_copr(&script.replace("{backend}", backend), None)
+                .map(|copr| copr.deco_args.backend)
+        };
+        assert_eq!(BackendType::RustPython, parse("rustpython").unwrap());
+        assert!(parse("jython").is_err());
+        if cfg!(feature = "pyo3_backend") {
+            assert_eq!(BackendType::CPython, parse("cpython").unwrap());
+        } else {
+            assert!(parse("cpython").is_err());
+        }
+    }
+
+    #[cfg(feature = "pyo3_backend")]
+    #[test]
+    fn test_exec_cpython_copr() {
+        use std::sync::Arc;
+
+        use common_recordbatch::RecordBatch;
+        use datatypes::prelude::*;
+        use datatypes::schema::{ColumnSchema, Schema};
+        use datatypes::vectors::{Float64Vector, Int64Vector};
+
+        use crate::python::coprocessor::exec_coprocessor;
+
+        let script = r#"
+import statistics
+
+@copr(args=["a", "b"], returns=["sum", "mean", "flag"], backend="cpython")
+def test(a: vector[i64], b: vector[f64]) -> (vector[f64], vector[f64], vector[bool]):
+    return [x + y for x, y in zip(a, b)], statistics.mean(b), True
+"#;
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("a", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new("b", ConcreteDataType::float64_datatype(), false),
+        ]));
+        let rb = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(Int64Vector::from_slice([1, 2, 3])) as _,
+                Arc::new(Float64Vector::from_slice([0.5, 1.0, 1.5])) as _,
+            ],
+        )
+        .unwrap();
+
+        let ret = exec_coprocessor(script, &rb).unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([1.5, 3.0, 4.5]));
+        assert_eq!(&expect, ret.column(0));
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([1.0, 1.0, 1.0]));
+        assert_eq!(&expect, ret.column(1));
+        assert_eq!(3, ret.column(2).len());
+
+        // Errors raised in CPython are reported as runtime errors.
+        let script = script.replace("statistics.mean(b)", "1 / 0");
+        let err = exec_coprocessor(&script, &rb).unwrap_err();
+        assert!(err.to_string().contains("ZeroDivisionError"), "{err}");
     }
 }
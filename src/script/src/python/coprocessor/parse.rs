@@ -32,6 +32,8 @@ pub struct DecoratorArgs {
     pub arg_names: Vec<String>,
     pub ret_names: Vec<String>,
     pub sql: Option<String>,
+    #[cfg_attr(test, serde(default))]
+    pub backend: BackendType,
     // maybe add a URL for connecting or what?
     // also predicate for timed triggered or conditional triggered?
 }
@@ -52,6 +54,40 @@ macro_rules! fail_parse_error {
     };
 }
 
+/// The Python implementation that runs a coprocessor.
+#[cfg_attr(test, derive(Deserialize))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendType {
+    /// The embedded RustPython interpreter, which only allows a few stdlib modules.
+    #[default]
+    RustPython,
+    /// The CPython interpreter linked by pyo3, which can import any module installed in
+    /// the host, like `numpy` and `pandas`. Requires the `pyo3_backend` feature.
+    CPython,
+}
+
+impl BackendType {
+    fn try_from_name(name: &str, loc: &Location) -> Result<Self> {
+        match name {
+            "rustpython" => Ok(Self::RustPython),
+            "cpython" => {
+                ensure!(
+                    cfg!(feature = "pyo3_backend"),
+                    ret_parse_error(
+                        format!("Backend `{name}` requires the `pyo3_backend` feature"),
+                        Some(loc.to_owned())
+                    )
+                );
+                Ok(Self::CPython)
+            }
+            _ => fail_parse_error!(
+                format!("Unknown backend: `{name}`, expect `rustpython` or `cpython`"),
+                Some(loc.to_owned())
+            ),
+        }
+    }
+}
+
 fn py_str_to_string(s: &ast::Expr<()>) -> Result<String> {
     if let ast::ExprKind::Constant {
         value: ast::Constant::Str(v),
@@ -262,13 +298,13 @@ fn parse_annotation(sub: &ast::Expr<()>) -> Result<AnnotationInfo> {
 /// parse a list of keyword and return args and returns list from keywords
 fn parse_keywords(keywords: &Vec<ast::Keyword<()>>) -> Result<DecoratorArgs> {
     // more keys maybe add to this list of `avail_key`(like `sql` for querying and maybe config for connecting to database?), for better extension using a `HashSet` in here
-    let avail_key = HashSet::from(["args", "returns", "sql"]);
-    let opt_keys = HashSet::from(["sql"]);
+    let avail_key = HashSet::from(["args", "returns", "sql", "backend"]);
+    let opt_keys = HashSet::from(["sql", "backend"]);
     let mut visited_key = HashSet::new();
     let len_min = avail_key.len() - opt_keys.len();
     let len_max = avail_key.len();
     ensure!(
-        // "sql" is optional(for now)
+        // "sql" and "backend" are optional(for now)
         keywords.len() >= len_min && keywords.len() <= len_max,
         CoprParseSnafu {
             reason: format!(
@@ -301,6 +337,10 @@ fn parse_keywords(keywords: &Vec<ast::Keyword<()>>) -> Result<DecoratorArgs> {
                     "args" => ret_args.arg_names = pylist_to_vec(&kw.node.value)?,
                     "returns" => ret_args.ret_names = pylist_to_vec(&kw.node.value)?,
                     "sql" => ret_args.sql = Some(py_str_to_string(&kw.node.value)?),
+                    "backend" => {
+                        let name = py_str_to_string(&kw.node.value)?;
+                        ret_args.backend = BackendType::try_from_name(&name, &kw.location)?
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -506,6 +546,8 @@ pub fn parse_and_compile_copr(
                     return_types,
                     script: script.to_owned(),
                     query_engine: query_engine.as_ref().map(|e| Arc::downgrade(e).into()),
+                    #[cfg(feature = "pyo3_backend")]
+                    cpython_func: Default::default(),
                 });
             }
         } else if matches!(
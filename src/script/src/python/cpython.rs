@@ -0,0 +1,283 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs coprocessors in the CPython interpreter linked by pyo3, so that they can import
+//! third party modules like `numpy` and `pandas`.
+//!
+//! Unlike the RustPython backend, the arguments are passed to the coprocessor as Python
+//! `array.array`s (or lists if they contain nulls or aren't numbers) and the `greptime`
+//! module is not available. A coprocessor may return anything iterable (a list, a numpy
+//! array or a pandas series) or a constant for each column.
+
+use std::sync::Arc;
+
+use common_recordbatch::RecordBatch;
+use datatypes::arrow::array::Array;
+use datatypes::data_type::{ConcreteDataType, DataType};
+use datatypes::value::Value;
+use datatypes::vectors::{
+    BooleanVector, Float64Vector, Int64Vector, NullVector, StringVector, VectorRef,
+};
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyList, PyString, PyTuple};
+use pyo3::{PyAny, PyErr, PyObject, PyResult, Python, ToPyObject};
+use snafu::{Backtrace, GenerateImplicitData, OptionExt, ResultExt};
+
+use crate::python::coprocessor::Coprocessor;
+use crate::python::error::{ensure, Error, NewRecordBatchSnafu, OtherSnafu, Result};
+
+/// Defines the decorators of coprocessors, which do nothing in CPython.
+const PRELUDE: &str = r#"
+def copr(**kwargs):
+    return lambda func: func
+
+coprocessor = copr
+"#;
+
+fn format_py_error(err: PyErr, py: Python) -> Error {
+    let msg = match err.traceback(py).map(|tb| tb.format()) {
+        Some(Ok(tb)) => format!("{tb}{err}"),
+        _ => err.to_string(),
+    };
+    Error::PyRuntime {
+        msg,
+        backtrace: Backtrace::generate(),
+    }
+}
+
+fn value_to_py(value: &Value, py: Python) -> PyObject {
+    match value {
+        Value::Null => py.None(),
+        Value::Boolean(v) => v.to_object(py),
+        Value::UInt8(v) => v.to_object(py),
+        Value::UInt16(v) => v.to_object(py),
+        Value::UInt32(v) => v.to_object(py),
+        Value::UInt64(v) => v.to_object(py),
+        Value::Int8(v) => v.to_object(py),
+        Value::Int16(v) => v.to_object(py),
+        Value::Int32(v) => v.to_object(py),
+        Value::Int64(v) => v.to_object(py),
+        Value::Float32(v) => v.0.to_object(py),
+        Value::Float64(v) => v.0.to_object(py),
+        Value::Decimal128(v) => v.to_f64().to_object(py),
+        Value::String(v) => v.as_utf8().to_object(py),
+        Value::Binary(v) => PyBytes::new(py, v).to_object(py),
+        Value::Date(v) => v.val().to_object(py),
+        Value::DateTime(v) => v.val().to_object(py),
+        Value::Timestamp(v) => v.value().to_object(py),
+        Value::List(v) => match v.items() {
+            Some(items) => {
+                PyList::new(py, items.iter().map(|item| value_to_py(item, py))).to_object(py)
+            }
+            None => py.None(),
+        },
+        Value::Histogram(v) => {
+            PyTuple::new(py, [v.bounds().to_object(py), v.counts().to_object(py)]).to_object(py)
+        }
+    }
+}
+
+/// Returns the typecode of `array.array` holding values of `data_type`.
+fn array_typecode(data_type: &ConcreteDataType) -> Option<&'static str> {
+    let typecode = match data_type {
+        ConcreteDataType::Int8(_) => "b",
+        ConcreteDataType::Int16(_) => "h",
+        ConcreteDataType::Int32(_) => "i",
+        ConcreteDataType::Int64(_) => "q",
+        ConcreteDataType::UInt8(_) => "B",
+        ConcreteDataType::UInt16(_) => "H",
+        ConcreteDataType::UInt32(_) => "I",
+        ConcreteDataType::UInt64(_) => "Q",
+        ConcreteDataType::Float32(_) => "f",
+        ConcreteDataType::Float64(_) => "d",
+        _ => return None,
+    };
+    Some(typecode)
+}
+
+/// Converts `vector` to a Python `array.array` by copying its buffer if it's a vector of
+/// numbers without nulls, otherwise to a list of values.
+fn vector_to_py(vector: &VectorRef, py: Python) -> PyResult<PyObject> {
+    let data_type = vector.data_type();
+    if let Some(typecode) = array_typecode(&data_type).filter(|_| vector.null_count() == 0) {
+        let array = vector.to_arrow_array();
+        let data = array.data();
+        // Safety: primitive types have fixed width.
+        let width = data_type.as_arrow_type().primitive_width().unwrap();
+        let start = data.offset() * width;
+        let bytes = &data.buffers()[0].as_slice()[start..start + data.len() * width];
+        let array = py
+            .import("array")?
+            .getattr("array")?
+            .call1((typecode, PyBytes::new(py, bytes)))?;
+        return Ok(array.to_object(py));
+    }
+
+    let values = (0..vector.len()).map(|i| value_to_py(&vector.get(i), py));
+    Ok(PyList::new(py, values).to_object(py))
+}
+
+/// Converts a column of 64-bit numbers exposing its buffer, i.e. an `array.array` or a one
+/// dimensional numpy array, from its bytes at once. Returns `None` for other objects.
+fn buffer_to_vector(obj: &PyAny) -> PyResult<Option<VectorRef>> {
+    let typecode = match (obj.getattr("typecode"), obj.getattr("dtype")) {
+        (Ok(typecode), _) => typecode,
+        (_, Ok(dtype)) => dtype.getattr("char")?,
+        _ => return Ok(None),
+    };
+    let (Ok(typecode), Ok(itemsize)) = (typecode.extract::<&str>(), obj.getattr("itemsize"))
+    else {
+        return Ok(None);
+    };
+    // `array.array` has no `ndim`.
+    let ndim = obj
+        .getattr("ndim")
+        .map_or(Ok(1), |ndim| ndim.extract::<usize>())?;
+    if itemsize.extract::<usize>()? != 8 || ndim != 1 {
+        return Ok(None);
+    }
+
+    let bytes = obj.call_method0("tobytes")?;
+    let chunks = bytes.downcast::<PyBytes>()?.as_bytes().chunks_exact(8);
+    // Safety: chunks are exactly 8 bytes.
+    let vector: VectorRef = match typecode {
+        "d" => Arc::new(Float64Vector::from_vec(
+            chunks
+                .map(|c| f64::from_ne_bytes(c.try_into().unwrap()))
+                .collect(),
+        )),
+        // `l` is the typecode of `int64` in numpy on 64-bit Linux and macOS.
+        "q" | "l" => Arc::new(Int64Vector::from_vec(
+            chunks
+                .map(|c| i64::from_ne_bytes(c.try_into().unwrap()))
+                .collect(),
+        )),
+        _ => return Ok(None),
+    };
+    Ok(Some(vector))
+}
+
+fn collect<'a, T: pyo3::FromPyObject<'a>>(items: &[&'a PyAny]) -> PyResult<Vec<Option<T>>> {
+    items
+        .iter()
+        .map(|item| {
+            if item.is_none() {
+                Ok(None)
+            } else {
+                item.extract().map(Some)
+            }
+        })
+        .collect()
+}
+
+/// Converts a column returned by the coprocessor to a vector, whose type is inferred from
+/// the first value that is not `None`. A constant is repeated `num_rows` times.
+fn py_to_vector(obj: &PyAny, num_rows: usize) -> PyResult<VectorRef> {
+    if let Some(vector) = buffer_to_vector(obj)? {
+        return Ok(vector);
+    }
+    let items = if obj.downcast::<PyString>().is_err() && obj.iter().is_ok() {
+        obj.iter()?.collect::<PyResult<Vec<_>>>()?
+    } else {
+        vec![obj; num_rows]
+    };
+    let Some(first) = items.iter().find(|item| !item.is_none()) else {
+        return Ok(Arc::new(NullVector::new(items.len())));
+    };
+
+    let vector: VectorRef = if first.downcast::<PyBool>().is_ok() {
+        Arc::new(BooleanVector::from(collect::<bool>(&items)?))
+    } else if first.downcast::<PyFloat>().is_ok() {
+        Arc::new(Float64Vector::from(collect::<f64>(&items)?))
+    } else if first.downcast::<PyString>().is_ok() {
+        Arc::new(StringVector::from(collect::<String>(&items)?))
+    } else if first.extract::<i64>().is_ok() {
+        Arc::new(Int64Vector::from(collect::<i64>(&items)?))
+    } else {
+        Arc::new(Float64Vector::from(collect::<f64>(&items)?))
+    };
+    Ok(vector)
+}
+
+/// Runs the script of `copr` in CPython and returns the coprocessor function defined.
+fn define_func<'py>(copr: &Coprocessor, py: Python<'py>) -> Result<&'py PyAny> {
+    let globals = PyDict::new(py);
+    // Postpones the evaluation of annotations like `vector[f64]`, which are only meaningful
+    // to the coprocessor parser.
+    let script = format!(
+        "from __future__ import annotations\n{PRELUDE}{}",
+        copr.script
+    );
+    py.run(&script, Some(globals), None)
+        .map_err(|e| format_py_error(e, py))?;
+
+    globals
+        .get_item(copr.name.as_str())
+        .with_context(|| OtherSnafu {
+            reason: format!("Can't find coprocessor function {}", copr.name),
+        })
+}
+
+/// Runs the coprocessor `copr` against `rb` in CPython, `args` are the columns selected
+/// from `rb` by the coprocessor's `args`.
+pub(crate) fn cpython_exec_parsed(
+    copr: &Coprocessor,
+    rb: &RecordBatch,
+    args: Vec<VectorRef>,
+) -> Result<RecordBatch> {
+    let mut cols = Python::with_gil(|py| -> Result<Vec<VectorRef>> {
+        // `OnceCell::get_or_try_init()` may deadlock as the GIL could be released while
+        // running the script, defining the function more than once is harmless.
+        let func = match copr.cpython_func.get() {
+            Some(func) => func.as_ref(py),
+            None => {
+                let func = define_func(copr, py)?;
+                copr.cpython_func
+                    .get_or_init(|| func.to_object(py))
+                    .as_ref(py)
+            }
+        };
+        let args = args
+            .iter()
+            .map(|arg| vector_to_py(arg, py))
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|e| format_py_error(e, py))?;
+        let ret = func
+            .call1(PyTuple::new(py, args))
+            .map_err(|e| format_py_error(e, py))?;
+
+        let num_rows = rb.num_rows();
+        let cols = match ret.downcast::<PyTuple>() {
+            Ok(tuple) => tuple
+                .iter()
+                .map(|col| py_to_vector(col, num_rows))
+                .collect::<PyResult<Vec<_>>>(),
+            Err(_) => py_to_vector(ret, num_rows).map(|col| vec![col]),
+        };
+        cols.map_err(|e| format_py_error(e, py))
+    })?;
+    ensure!(
+        cols.len() == copr.deco_args.ret_names.len(),
+        OtherSnafu {
+            reason: format!(
+                "The number of return Vector is wrong, expect {}, found {}",
+                copr.deco_args.ret_names.len(),
+                cols.len()
+            )
+        }
+    );
+
+    copr.check_and_cast_type(&mut cols)?;
+    let schema = copr.gen_schema(&cols)?;
+    RecordBatch::new(schema, cols).context(NewRecordBatchSnafu)
+}
@@ -36,8 +36,10 @@ use session::context::QueryContext;
 use snafu::{ensure, ResultExt};
 use sql::statements::statement::Statement;
 
-use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
-use crate::python::coprocessor::{exec_parsed, parse, AnnotationInfo, CoprocessorRef};
+use crate::engine::{CompileContext, EvalContext, InsertHandlerRef, Script, ScriptEngine};
+use crate::python::coprocessor::{
+    exec_parsed, parse, with_inserter, AnnotationInfo, CoprocessorRef, ScriptInserter,
+};
 use crate::python::error::{self, Result};
 
 const PY_ENGINE: &str = "python";
@@ -160,6 +162,7 @@ impl Function for PyUDF {
 pub struct PyScript {
     query_engine: QueryEngineRef,
     copr: CoprocessorRef,
+    insert_handler: Option<InsertHandlerRef>,
 }
 
 impl PyScript {
@@ -175,6 +178,7 @@ impl PyScript {
 pub struct CoprStream {
     stream: SendableRecordBatchStream,
     copr: CoprocessorRef,
+    inserter: Option<ScriptInserter>,
 }
 
 impl RecordBatchStream for CoprStream {
@@ -190,9 +194,11 @@ impl Stream for CoprStream {
         match Pin::new(&mut self.stream).poll_next(cx) {
             Poll::Pending => Poll::Pending,
             Poll::Ready(Some(Ok(recordbatch))) => {
-                let batch = exec_parsed(&self.copr, &recordbatch)
-                    .map_err(BoxedError::new)
-                    .context(ExternalSnafu)?;
+                let batch = with_inserter(self.inserter.clone(), || {
+                    exec_parsed(&self.copr, &recordbatch)
+                })
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
 
                 Poll::Ready(Some(Ok(batch)))
             }
@@ -218,7 +224,8 @@ impl Script for PyScript {
         self
     }
 
-    async fn execute(&self, _ctx: EvalContext) -> Result<Output> {
+    async fn execute(&self, ctx: EvalContext) -> Result<Output> {
+        let query_ctx = ctx.query_ctx.unwrap_or_else(QueryContext::arc);
         if let Some(sql) = &self.copr.deco_args.sql {
             let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
             ensure!(
@@ -227,11 +234,19 @@ impl Script for PyScript {
             );
             let plan = self
                 .query_engine
-                .statement_to_plan(stmt, Arc::new(QueryContext::new()))?;
+                .statement_to_plan(stmt, query_ctx.clone())?;
             let res = self.query_engine.execute(&plan).await?;
             let copr = self.copr.clone();
+            let inserter = self
+                .insert_handler
+                .clone()
+                .map(|handler| ScriptInserter { handler, query_ctx });
             match res {
-                Output::Stream(stream) => Ok(Output::Stream(Box::pin(CoprStream { copr, stream }))),
+                Output::Stream(stream) => Ok(Output::Stream(Box::pin(CoprStream {
+                    copr,
+                    stream,
+                    inserter,
+                }))),
                 _ => unreachable!(),
             }
         } else {
@@ -243,11 +258,33 @@ impl Script for PyScript {
 
 pub struct PyEngine {
     query_engine: QueryEngineRef,
+    /// Scripts are not allowed to write tables if absent.
+    insert_handler: Option<InsertHandlerRef>,
+    /// Whether coprocessors running in CPython are allowed.
+    enable_cpython: bool,
 }
 
 impl PyEngine {
     pub fn new(query_engine: QueryEngineRef) -> Self {
-        Self { query_engine }
+        Self {
+            query_engine,
+            insert_handler: None,
+            enable_cpython: false,
+        }
+    }
+
+    /// Allows scripts to write tables by `greptime.insert()`, which are inserted by
+    /// `insert_handler`.
+    pub fn with_insert_handler(mut self, insert_handler: InsertHandlerRef) -> Self {
+        self.insert_handler = Some(insert_handler);
+        self
+    }
+
+    /// Allows coprocessors declared with `backend="cpython"`. They are rejected by default
+    /// as they could import any module installed in the host, e.g. `os` and `subprocess`.
+    pub fn with_cpython_backend(mut self, enable: bool) -> Self {
+        self.enable_cpython = enable;
+        self
     }
 }
 
@@ -269,10 +306,20 @@ impl ScriptEngine for PyEngine {
             script,
             Some(self.query_engine.clone()),
         )?);
+        ensure!(
+            self.enable_cpython || copr.deco_args.backend != parse::BackendType::CPython,
+            error::OtherSnafu {
+                reason: format!(
+                    "Coprocessor {} requires the CPython backend, which is disabled by the server",
+                    copr.name
+                ),
+            }
+        );
 
         Ok(PyScript {
             copr,
             query_engine: self.query_engine.clone(),
+            insert_handler: self.insert_handler.clone(),
         })
     }
 }
@@ -283,12 +330,15 @@ mod tests {
     use catalog::{CatalogList, CatalogProvider, SchemaProvider};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use common_recordbatch::util;
-    use datatypes::prelude::ScalarVector;
+    use datatypes::prelude::{ConcreteDataType, ScalarVector};
     use datatypes::vectors::{Float64Vector, Int64Vector};
     use query::QueryEngineFactory;
+    use session::context::QueryContextRef;
+    use table::requests::InsertRequest;
     use table::table::numbers::NumbersTable;
 
     use super::*;
+    use crate::engine::InsertHandler;
 
     fn sample_script_engine() -> PyEngine {
         let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
@@ -311,6 +361,29 @@ mod tests {
         PyEngine::new(query_engine.clone())
     }
 
+    #[tokio::test]
+    async fn test_cpython_backend_disabled() {
+        let script = r#"
+@copr(args=["number"], returns=["number"], sql="select * from numbers", backend="cpython")
+def test(number):
+    return number
+"#;
+        let err = sample_script_engine()
+            .compile(script, CompileContext::default())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().to_lowercase().contains("cpython"), "{err}");
+
+        if cfg!(feature = "pyo3_backend") {
+            let _ = sample_script_engine()
+                .with_cpython_backend(true)
+                .compile(script, CompileContext::default())
+                .await
+                .unwrap();
+        }
+    }
+
     #[tokio::test]
     async fn test_sql_in_py() {
         let script_engine = sample_script_engine();
@@ -337,6 +410,79 @@ def test(number)->vector[u32]:
         assert_eq!(rb.column(0).len(), 100);
     }
 
+    #[derive(Default)]
+    struct MockInsertHandler {
+        requests: std::sync::Mutex<Vec<InsertRequest>>,
+    }
+
+    #[async_trait]
+    impl InsertHandler for MockInsertHandler {
+        async fn insert(
+            &self,
+            request: InsertRequest,
+            _query_ctx: QueryContextRef,
+        ) -> std::result::Result<usize, BoxedError> {
+            let rows = request
+                .columns_values
+                .values()
+                .next()
+                .map(|vector| vector.len())
+                .unwrap_or(0);
+            self.requests.lock().unwrap().push(request);
+            Ok(rows)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_in_py() {
+        let source = r#"
+import greptime as gt
+
+@copr(args=["number"], returns = ["rows"], sql = "select number from numbers limit 10")
+def test(number):
+    return gt.insert("public.squares", {"n": number, "square": number * number, "tag": "a"})
+"#;
+        // Scripts are not allowed to write tables without the insert handler.
+        let script = sample_script_engine()
+            .compile(source, CompileContext::default())
+            .await
+            .unwrap();
+        let Output::Stream(stream) = script.execute(EvalContext::default()).await.unwrap() else {
+            unreachable!()
+        };
+        assert!(util::collect(stream).await.is_err());
+
+        let handler = Arc::new(MockInsertHandler::default());
+        let script = sample_script_engine()
+            .with_insert_handler(handler.clone())
+            .compile(source, CompileContext::default())
+            .await
+            .unwrap();
+        let Output::Stream(stream) = script.execute(EvalContext::default()).await.unwrap() else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        let rows = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Vector>()
+            .unwrap();
+        assert_eq!(10, rows.get_data(0).unwrap());
+
+        let requests = handler.requests.lock().unwrap();
+        assert_eq!(1, requests.len());
+        let request = &requests[0];
+        assert_eq!(DEFAULT_CATALOG_NAME, request.catalog_name);
+        assert_eq!("public", request.schema_name);
+        assert_eq!("squares", request.table_name);
+        assert_eq!(3, request.columns_values.len());
+        assert_eq!(10, request.columns_values["tag"].len());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            request.columns_values["tag"].data_type()
+        );
+    }
+
     #[tokio::test]
     async fn test_compile_execute() {
         let script_engine = sample_script_engine();
@@ -810,7 +810,18 @@ pub fn pyobj_try_to_typed_val(
                     None
                 }
             }
-            ConcreteDataType::List(_) => unreachable!(),
+            ConcreteDataType::Decimal128(_) => {
+                // Decimals are passed as strings to avoid losing precision.
+                if is_instance::<PyStr>(&obj, vm) {
+                    obj.try_into_value::<String>(vm)
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .map(value::Value::Decimal128)
+                } else {
+                    None
+                }
+            }
+            ConcreteDataType::List(_) | ConcreteDataType::Histogram(_) => unreachable!(),
             ConcreteDataType::Date(_)
             | ConcreteDataType::DateTime(_)
             | ConcreteDataType::Timestamp(_) => {
@@ -896,6 +907,8 @@ pub fn val_to_pyobj(val: value::Value, vm: &VirtualMachine) -> PyObjectRef {
         value::Value::DateTime(v) => vm.ctx.new_int(v.val()).into(),
         // FIXME(dennis): lose the timestamp unit here
         Value::Timestamp(v) => vm.ctx.new_int(v.value()).into(),
+        // FIXME: may lose precision, python's `decimal.Decimal` is better.
+        value::Value::Decimal128(v) => vm.ctx.new_float(v.to_f64()).into(),
         value::Value::List(list) => {
             let list = list.items().as_ref();
             match list {
@@ -906,6 +919,15 @@ pub fn val_to_pyobj(val: value::Value, vm: &VirtualMachine) -> PyObjectRef {
                 None => vm.ctx.new_list(Vec::new()).into(),
             }
         }
+        value::Value::Histogram(h) => {
+            let to_list = |values: &[f64]| {
+                let values = values.iter().map(|v| vm.ctx.new_float(*v).into()).collect();
+                vm.ctx.new_list(values).into()
+            };
+            vm.ctx
+                .new_tuple(vec![to_list(h.bounds()), to_list(h.counts())])
+                .into()
+        }
     }
 }
 
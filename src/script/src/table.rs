@@ -85,7 +85,8 @@ impl ScriptsTable {
         })
     }
 
-    pub async fn insert(&self, name: &str, script: &str) -> Result<()> {
+    /// Inserts `script` named `name`, which is run by the script engine `engine`.
+    pub async fn insert(&self, name: &str, script: &str, engine: &str) -> Result<()> {
         let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(7);
         columns_values.insert(
             "name".to_string(),
@@ -95,10 +96,9 @@ impl ScriptsTable {
             "script".to_string(),
             Arc::new(StringVector::from(vec![script])) as _,
         );
-        // TODO(dennis): we only supports python right now.
         columns_values.insert(
             "engine".to_string(),
-            Arc::new(StringVector::from(vec!["python"])) as _,
+            Arc::new(StringVector::from(vec![engine])) as _,
         );
         // Timestamp in key part is intentionally left to 0
         columns_values.insert(
@@ -140,11 +140,16 @@ impl ScriptsTable {
         Ok(())
     }
 
-    pub async fn find_script_by_name(&self, name: &str) -> Result<String> {
+    /// Returns the script named `name` and its engine.
+    pub async fn find_script_by_name(&self, name: &str) -> Result<(String, String)> {
         // FIXME(dennis): SQL injection
         // TODO(dennis): we use sql to find the script, the better way is use a function
         //               such as `find_record_by_primary_key` in table_engine.
-        let sql = format!("select script from {} where name='{}'", self.name(), name);
+        let sql = format!(
+            "select script, engine from {} where name='{}'",
+            self.name(),
+            name
+        );
         let stmt = QueryLanguageParser::parse_sql(&sql).unwrap();
         let plan = self
             .query_engine
@@ -167,21 +172,28 @@ impl ScriptsTable {
         ensure!(!records.is_empty(), ScriptNotFoundSnafu { name });
 
         assert_eq!(records.len(), 1);
-        assert_eq!(records[0].num_columns(), 1);
-
-        let script_column = records[0].column(0);
-        let script_column = script_column
-            .as_any()
-            .downcast_ref::<StringVector>()
-            .with_context(|| CastTypeSnafu {
-                msg: format!(
-                    "can't downcast {:?} array into string vector",
-                    script_column.data_type()
-                ),
-            })?;
+        assert_eq!(records[0].num_columns(), 2);
+
+        let string_column = |i: usize| {
+            let column = records[0].column(i);
+            column
+                .as_any()
+                .downcast_ref::<StringVector>()
+                .with_context(|| CastTypeSnafu {
+                    msg: format!(
+                        "can't downcast {:?} array into string vector",
+                        column.data_type()
+                    ),
+                })
+        };
+        let script_column = string_column(0)?;
+        let engine_column = string_column(1)?;
 
         assert_eq!(script_column.len(), 1);
-        Ok(script_column.get_data(0).unwrap().to_string())
+        Ok((
+            script_column.get_data(0).unwrap().to_string(),
+            engine_column.get_data(0).unwrap().to_string(),
+        ))
     }
 
     #[inline]
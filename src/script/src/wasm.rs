@@ -0,0 +1,42 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! WebAssembly script engine
+
+mod engine;
+pub mod error;
+
+pub use self::engine::{WasmEngine, WasmLimits, WasmScript};
+
+/// Returns whether `script` is a WebAssembly module, in the text format or base64 encoded
+/// binary, rather than a Python script.
+pub fn is_wasm_script(script: &str) -> bool {
+    let script = script.trim_start();
+    // "AGFzbQ" is the base64 encoded magic number "\0asm" of WebAssembly binaries.
+    script.starts_with("(module") || script.starts_with("AGFzbQ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_wasm_script() {
+        assert!(is_wasm_script("\n(module (memory (export \"memory\") 1))"));
+        assert!(is_wasm_script("AGFzbQEAAAA="));
+        assert!(!is_wasm_script(
+            "@copr(returns=['n'])\ndef test() -> vector[i64]:\n  pass"
+        ));
+    }
+}
@@ -0,0 +1,537 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! WebAssembly script engine
+//!
+//! A script is a WebAssembly module, either in the text format or base64 encoded binary,
+//! which imports nothing and exports:
+//! - `memory`: the linear memory.
+//! - `alloc(size: i32) -> i32`: allocates `size` bytes in `memory` and returns the offset.
+//! - One function `<name>(arg_0: i32, ..., arg_n: i32, len: i32) -> i32`, which is
+//!   registered as the UDF `<name>`. Each `arg_i` is the offset of `len` little endian
+//!   `f64` values of an argument, and the function returns the offset of `len` `f64`
+//!   results.
+//!
+//! Every call of the UDF runs in a new instance of the module, which is limited by
+//! [WasmLimits].
+//!
+//! Scripts are uploaded like Python scripts, by `POST /v1/scripts?name=<name>`, and the
+//! [ScriptManager](crate::manager::ScriptManager) compiles them by this engine once they
+//! are detected to be WebAssembly modules.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_function::scalars::function::FunctionContext;
+use common_function::scalars::{Function, FUNCTION_REGISTRY};
+use common_query::error::WasmUdfSnafu;
+use common_query::prelude::{Signature, Volatility};
+use common_query::Output;
+use datatypes::arrow::array::{Array, Float64Array};
+use datatypes::arrow::compute;
+use datatypes::arrow::datatypes::DataType as ArrowDataType;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::{Float64Vector, VectorRef};
+use query::QueryEngineRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use wasmtime::{
+    Config, Engine, ExternType, Instance, Module, Store, StoreLimits, StoreLimitsBuilder, Val,
+    ValType,
+};
+
+use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
+use crate::wasm::error::{
+    self, CastArgumentSnafu, CompileModuleSnafu, DecodeModuleSnafu, InvalidModuleSnafu, Result,
+};
+
+const WASM_ENGINE: &str = "wasm";
+const MEMORY_EXPORT: &str = "memory";
+const ALLOC_EXPORT: &str = "alloc";
+const VALUE_SIZE: usize = std::mem::size_of::<f64>();
+
+/// Resources a call of the WebAssembly UDF may consume.
+#[derive(Debug, Clone, Copy)]
+pub struct WasmLimits {
+    /// Fuel of a call, roughly the number of WebAssembly instructions it may execute.
+    pub fuel: u64,
+    /// Max size of the linear memory in bytes.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 1_000_000_000,
+            max_memory_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+struct WasmUdf {
+    name: String,
+    num_args: usize,
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+}
+
+impl std::fmt::Display for WasmUdf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.name, self.num_args)
+    }
+}
+
+impl WasmUdf {
+    fn runtime_error(&self, err: impl std::fmt::Display) -> error::Error {
+        error::RuntimeSnafu {
+            name: &self.name,
+            msg: err.to_string(),
+        }
+        .build()
+    }
+
+    /// Calls the function with `columns` in a new instance of the module.
+    fn call(&self, columns: &[VectorRef]) -> Result<VectorRef> {
+        let args = columns
+            .iter()
+            .map(|column| {
+                let array = compute::cast(&column.to_arrow_array(), &ArrowDataType::Float64)
+                    .context(CastArgumentSnafu)?;
+                // Safety: the array is just casted to f64.
+                Ok(array
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .unwrap()
+                    .clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let len = args.iter().map(|arg| arg.len()).max().unwrap_or(0);
+        let size = i32::try_from(len * VALUE_SIZE).map_err(|e| self.runtime_error(e))?;
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.limits.max_memory_bytes)
+            .instances(1)
+            .build();
+        let mut store: Store<StoreLimits> = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store
+            .add_fuel(self.limits.fuel)
+            .map_err(|e| self.runtime_error(e))?;
+
+        let instance =
+            Instance::new(&mut store, &self.module, &[]).map_err(|e| self.runtime_error(e))?;
+        // Safety: exports are checked while compiling the module.
+        let memory = instance.get_memory(&mut store, MEMORY_EXPORT).unwrap();
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, ALLOC_EXPORT)
+            .map_err(|e| self.runtime_error(e))?;
+        let func = instance.get_func(&mut store, &self.name).unwrap();
+
+        let mut params = Vec::with_capacity(args.len() + 1);
+        for arg in &args {
+            let bytes = arg
+                .values()
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>();
+            let offset = alloc
+                .call(&mut store, size)
+                .map_err(|e| self.runtime_error(e))?;
+            memory
+                .write(&mut store, offset as u32 as usize, &bytes)
+                .map_err(|e| self.runtime_error(e))?;
+            params.push(Val::I32(offset));
+        }
+        params.push(Val::I32(len as i32));
+
+        let mut results = [Val::I32(0)];
+        func.call(&mut store, &params, &mut results)
+            .map_err(|e| self.runtime_error(e))?;
+        // Safety: the result type is checked while compiling the module.
+        let offset = results[0].unwrap_i32() as u32 as usize;
+        let mut bytes = vec![0; len * VALUE_SIZE];
+        memory
+            .read(&store, offset, &mut bytes)
+            .map_err(|e| self.runtime_error(e))?;
+
+        // The result is null if any of the arguments is null.
+        let values = bytes
+            .chunks_exact(VALUE_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let is_null = args.iter().any(|arg| arg.is_null(i));
+                // Safety: chunks are exactly `VALUE_SIZE` bytes.
+                (!is_null).then(|| f64::from_le_bytes(chunk.try_into().unwrap()))
+            })
+            .collect::<Vec<_>>();
+        Ok(Arc::new(Float64Vector::from(values)))
+    }
+}
+
+impl Function for WasmUdf {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn return_type(
+        &self,
+        _input_types: &[ConcreteDataType],
+    ) -> common_query::error::Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::uniform(
+            self.num_args,
+            ConcreteDataType::numerics(),
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(
+        &self,
+        _func_ctx: FunctionContext,
+        columns: &[VectorRef],
+    ) -> common_query::error::Result<VectorRef> {
+        self.call(columns)
+            .map_err(|e| WasmUdfSnafu { msg: e.to_string() }.build())
+    }
+}
+
+pub struct WasmScript {
+    udf: Arc<WasmUdf>,
+}
+
+impl WasmScript {
+    /// Returns the name of the function exported by the module.
+    pub fn name(&self) -> &str {
+        &self.udf.name
+    }
+
+    /// Registers the function exported by the module as UDF.
+    pub fn register_udf(&self, query_engine: &QueryEngineRef) {
+        FUNCTION_REGISTRY.register(self.udf.clone());
+        query_engine.register_function(self.udf.clone());
+    }
+}
+
+#[async_trait]
+impl Script for WasmScript {
+    type Error = error::Error;
+
+    fn engine_name(&self) -> &str {
+        WASM_ENGINE
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self, _ctx: EvalContext) -> Result<Output> {
+        error::UnsupportedExecuteSnafu {
+            name: &self.udf.name,
+        }
+        .fail()
+    }
+}
+
+pub struct WasmEngine {
+    engine: Engine,
+    limits: WasmLimits,
+}
+
+impl WasmEngine {
+    pub fn try_new() -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)
+            .map_err(|e| error::CreateEngineSnafu { msg: e.to_string() }.build())?;
+        Ok(Self {
+            engine,
+            limits: WasmLimits::default(),
+        })
+    }
+
+    pub fn with_limits(mut self, limits: WasmLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// Decodes `script` to the module in the text format or binary.
+fn decode_module(script: &str) -> Result<Vec<u8>> {
+    let script = script.trim();
+    if script.starts_with('(') {
+        Ok(script.as_bytes().to_vec())
+    } else {
+        base64::decode(script).context(DecodeModuleSnafu)
+    }
+}
+
+/// Checks the imports and exports of `module`, returns the name and number of arguments
+/// of the function to register as UDF.
+fn check_module(module: &Module) -> Result<(String, usize)> {
+    ensure!(
+        module.imports().next().is_none(),
+        InvalidModuleSnafu {
+            reason: "module must not import anything",
+        }
+    );
+
+    let is_i32s = |types: &mut dyn Iterator<Item = ValType>| types.all(|ty| ty == ValType::I32);
+    let mut has_memory = false;
+    let mut has_alloc = false;
+    let mut funcs = Vec::new();
+    for export in module.exports() {
+        match (export.name(), export.ty()) {
+            (MEMORY_EXPORT, ExternType::Memory(_)) => has_memory = true,
+            (ALLOC_EXPORT, ExternType::Func(ty)) => {
+                has_alloc = ty.params().len() == 1
+                    && ty.results().len() == 1
+                    && is_i32s(&mut ty.params().chain(ty.results()));
+            }
+            (name, ExternType::Func(ty)) => {
+                ensure!(
+                    ty.params().len() >= 2
+                        && ty.results().len() == 1
+                        && is_i32s(&mut ty.params().chain(ty.results())),
+                    InvalidModuleSnafu {
+                        reason: format!(
+                            "function {name} should take i32 offsets of arguments and \
+                             an i32 length, and return an i32 offset"
+                        ),
+                    }
+                );
+                funcs.push((name.to_string(), ty.params().len() - 1));
+            }
+            _ => (),
+        }
+    }
+    ensure!(
+        has_memory,
+        InvalidModuleSnafu {
+            reason: format!("module must export the `{MEMORY_EXPORT}` memory"),
+        }
+    );
+    ensure!(
+        has_alloc,
+        InvalidModuleSnafu {
+            reason: format!("module must export function `{ALLOC_EXPORT}(i32) -> i32`"),
+        }
+    );
+    ensure!(
+        funcs.len() == 1,
+        InvalidModuleSnafu {
+            reason: format!(
+                "module must export exactly one function, found {}",
+                funcs.len()
+            ),
+        }
+    );
+    Ok(funcs.pop().unwrap())
+}
+
+#[async_trait]
+impl ScriptEngine for WasmEngine {
+    type Error = error::Error;
+    type Script = WasmScript;
+
+    fn name(&self) -> &str {
+        WASM_ENGINE
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn compile(&self, script: &str, _ctx: CompileContext) -> Result<WasmScript> {
+        let bytes = decode_module(script)?;
+        let module = Module::new(&self.engine, bytes).map_err(|e| {
+            CompileModuleSnafu {
+                msg: format!("{e:?}"),
+            }
+            .build()
+        })?;
+        let (name, num_args) = check_module(&module)?;
+
+        Ok(WasmScript {
+            udf: Arc::new(WasmUdf {
+                name,
+                num_args,
+                engine: self.engine.clone(),
+                module,
+                limits: self.limits,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::local::{MemoryCatalogProvider, MemorySchemaProvider};
+    use catalog::{CatalogList, CatalogProvider, SchemaProvider};
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_recordbatch::util;
+    use datatypes::prelude::ScalarVector;
+    use datatypes::vectors::Int64Vector;
+    use query::parser::QueryLanguageParser;
+    use query::QueryEngineFactory;
+    use session::context::QueryContext;
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+
+    const ALLOC: &str = r#"
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 0))
+  (func $alloc (export "alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $size)))
+    (local.get $ptr))
+"#;
+
+    const ADD: &str = r#"
+  (func (export "add") (param $a i32) (param $b i32) (param $len i32) (result i32)
+    (local $out i32)
+    (local $i i32)
+    (local $offset i32)
+    (local.set $out (call $alloc (i32.mul (local.get $len) (i32.const 8))))
+    (block $done
+      (loop $next_row
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (local.set $offset (i32.mul (local.get $i) (i32.const 8)))
+        (f64.store
+          (i32.add (local.get $out) (local.get $offset))
+          (f64.add
+            (f64.load (i32.add (local.get $a) (local.get $offset)))
+            (f64.load (i32.add (local.get $b) (local.get $offset)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next_row)))
+    (local.get $out))
+"#;
+
+    const SPIN: &str = r#"
+  (func (export "spin") (param $a i32) (param $len i32) (result i32)
+    (loop $forever (br $forever))
+    (i32.const 0))
+"#;
+
+    fn module(funcs: &[&str]) -> String {
+        format!("(module {ALLOC} {})", funcs.join(" "))
+    }
+
+    async fn compile(script: &str) -> Result<WasmScript> {
+        WasmEngine::try_new()
+            .unwrap()
+            .with_limits(WasmLimits {
+                fuel: 1_000_000,
+                ..Default::default()
+            })
+            .compile(script, CompileContext::default())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_call_wasm_udf() {
+        let script = compile(&module(&[ADD])).await.unwrap();
+        assert_eq!("add", script.name());
+        assert_eq!(2, script.udf.num_args);
+
+        let a: VectorRef = Arc::new(Float64Vector::from_slice([0.5, 1.5, 2.5]));
+        let b: VectorRef = Arc::new(Int64Vector::from(vec![Some(1), None, Some(3)]));
+        let result = script.udf.call(&[a, b]).unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from(vec![Some(1.5), None, Some(5.5)]));
+        assert_eq!(expect, result);
+
+        let err = script.execute(EvalContext::default()).await.err().unwrap();
+        assert!(matches!(err, error::Error::UnsupportedExecute { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_udf_out_of_fuel() {
+        let script = compile(&module(&[SPIN])).await.unwrap();
+        let a: VectorRef = Arc::new(Float64Vector::from_slice([1.0]));
+        let err = script.udf.call(&[a]).unwrap_err();
+        assert!(matches!(err, error::Error::Runtime { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_wasm_module() {
+        assert!(matches!(
+            compile("not base64!").await.err().unwrap(),
+            error::Error::DecodeModule { .. }
+        ));
+        assert!(matches!(
+            compile("(module (func").await.err().unwrap(),
+            error::Error::CompileModule { .. }
+        ));
+
+        let invalid_modules = [
+            // No function to register.
+            module(&[]),
+            // More than one function.
+            module(&[ADD, SPIN]),
+            // Imports are not allowed.
+            format!(r#"(module (import "env" "f" (func)) {ALLOC} {SPIN})"#),
+            // No memory.
+            r#"(module (func (export "f") (param i32 i32) (result i32) (i32.const 0)))"#
+                .to_string(),
+        ];
+        for module in invalid_modules {
+            let err = compile(&module).await.err().unwrap();
+            assert!(matches!(err, error::Error::InvalidModule { .. }), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wasm_udf_in_sql() {
+        let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
+        let default_schema = Arc::new(MemorySchemaProvider::new());
+        default_schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::default()))
+            .unwrap();
+        let default_catalog = Arc::new(MemoryCatalogProvider::new());
+        default_catalog
+            .register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema)
+            .unwrap();
+        catalog_list
+            .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)
+            .unwrap();
+        let query_engine = QueryEngineFactory::new(catalog_list).query_engine();
+
+        let script = compile(&module(&[ADD])).await.unwrap();
+        script.register_udf(&query_engine);
+
+        let sql = "select add(number, number) from numbers limit 3";
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let plan = query_engine
+            .statement_to_plan(stmt, Arc::new(QueryContext::new()))
+            .unwrap();
+        let Output::Stream(stream) = query_engine.execute(&plan).await.unwrap() else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        let result = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Vector>()
+            .unwrap();
+        assert_eq!(
+            vec![Some(0.0), Some(2.0), Some(4.0)],
+            result.iter_data().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_error::prelude::{ErrorCompat, ErrorExt, StatusCode};
+use datatypes::arrow::error::ArrowError;
+use snafu::{Backtrace, Snafu};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum Error {
+    #[snafu(display("Failed to create WebAssembly engine: {}", msg))]
+    CreateEngine { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to decode WebAssembly module, source: {}", source))]
+    DecodeModule {
+        source: base64::DecodeError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to compile WebAssembly module: {}", msg))]
+    CompileModule { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid WebAssembly module: {}", reason))]
+    InvalidModule {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to run WebAssembly function {}: {}", name, msg))]
+    Runtime {
+        name: String,
+        msg: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to cast arguments of WebAssembly function, source: {}", source))]
+    CastArgument {
+        source: ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("WebAssembly script {} can only be called as a function", name))]
+    UnsupportedExecute { name: String, backtrace: Backtrace },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl ErrorExt for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::CreateEngine { .. } | Error::CastArgument { .. } => StatusCode::Internal,
+            Error::Runtime { .. } => StatusCode::EngineExecuteQuery,
+            Error::DecodeModule { .. }
+            | Error::CompileModule { .. }
+            | Error::InvalidModule { .. }
+            | Error::UnsupportedExecute { .. } => StatusCode::InvalidArguments,
+        }
+    }
+
+    fn backtrace_opt(&self) -> Option<&Backtrace> {
+        ErrorCompat::backtrace(self)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
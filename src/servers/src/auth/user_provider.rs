@@ -60,14 +60,14 @@ impl TryFrom<&str> for StaticUserProvider {
                             None
                         }
                     })
-                    .collect::<HashMap<String, Vec<u8>>>();
+                    .collect::<Vec<_>>();
 
                 ensure!(!credential.is_empty(), InvalidConfigSnafu {
                     value: content.to_string(),
                     msg: "StaticUserProviderOption file must contains at least one valid credential",
                 });
 
-                Ok(StaticUserProvider { users: credential, })
+                Ok(StaticUserProvider::new(credential))
             }
             "cmd" => content
                 .split(',')
@@ -78,8 +78,8 @@ impl TryFrom<&str> for StaticUserProvider {
                     })?;
                     Ok((k.to_string(), v.as_bytes().to_vec()))
                 })
-                .collect::<Result<HashMap<String, Vec<u8>>>>()
-                .map(|users| StaticUserProvider { users }),
+                .collect::<Result<Vec<_>>>()
+                .map(StaticUserProvider::new),
             _ => InvalidConfigSnafu {
                 value: mode.to_string(),
                 msg: "StaticUserProviderOption must be in format `file:<path>` or `cmd:<values>`",
@@ -91,6 +91,39 @@ impl TryFrom<&str> for StaticUserProvider {
 
 pub struct StaticUserProvider {
     users: HashMap<String, Vec<u8>>,
+    roles: HashMap<String, Vec<String>>,
+}
+
+impl StaticUserProvider {
+    /// Creates a provider from `(user, password)` pairs. The user could be followed by
+    /// the roles granted to it, in format `user:role1|role2`. Roles follow the last `:`,
+    /// so a user whose name contains `:` must be followed by its roles, which could be
+    /// empty, e.g. `a:b:`.
+    pub fn new(credentials: Vec<(String, Vec<u8>)>) -> Self {
+        let mut users = HashMap::with_capacity(credentials.len());
+        let mut roles = HashMap::with_capacity(credentials.len());
+        for (user, pwd) in credentials {
+            let (username, user_roles) = match user.rsplit_once(':') {
+                Some((username, user_roles)) => (
+                    username.to_string(),
+                    user_roles
+                        .split('|')
+                        .filter(|role| !role.is_empty())
+                        .map(|role| role.to_string())
+                        .collect(),
+                ),
+                None => (user, Vec::new()),
+            };
+            roles.insert(username.clone(), user_roles);
+            users.insert(username, pwd);
+        }
+        Self { users, roles }
+    }
+
+    fn user_info(&self, username: &str) -> UserInfo {
+        let roles = self.roles.get(username).cloned().unwrap_or_default();
+        UserInfo::new(username).with_roles(roles)
+    }
 }
 
 #[async_trait]
@@ -113,7 +146,7 @@ impl UserProvider for StaticUserProvider {
                 match input_pwd {
                     Password::PlainText(pwd) => {
                         return if save_pwd == pwd.as_bytes() {
-                            Ok(UserInfo::new(username))
+                            Ok(self.user_info(username))
                         } else {
                             UserPasswordMismatchSnafu {
                                 username: username.to_string(),
@@ -123,7 +156,7 @@ impl UserProvider for StaticUserProvider {
                     }
                     Password::MysqlNativePassword(auth_data, salt) => {
                         auth_mysql(auth_data, salt, username, save_pwd)
-                            .map(|_| UserInfo::new(username))
+                            .map(|_| self.user_info(username))
                     }
                     Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
                         password_type: "pg_md5",
@@ -243,6 +276,59 @@ pub mod test {
         test_authenticate(&provider, "admin", "654321").await;
     }
 
+    #[tokio::test]
+    async fn test_provider_with_roles() {
+        let provider =
+            StaticUserProvider::try_from("cmd:root:admin|auditor=123456,guest=654321").unwrap();
+        test_authenticate(&provider, "root", "123456").await;
+
+        let user_info = provider
+            .authenticate(
+                Identity::UserId("root", None),
+                Password::PlainText("123456"),
+            )
+            .await
+            .unwrap();
+        assert_eq!("root", user_info.username());
+        assert_eq!(["admin", "auditor"], user_info.roles());
+
+        let user_info = provider
+            .authenticate(
+                Identity::UserId("guest", None),
+                Password::PlainText("654321"),
+            )
+            .await
+            .unwrap();
+        assert!(user_info.roles().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_provider_with_colon_in_username() {
+        let provider =
+            StaticUserProvider::try_from("cmd:tenant:root:admin=123456,tenant:guest:=654321")
+                .unwrap();
+
+        let user_info = provider
+            .authenticate(
+                Identity::UserId("tenant:root", None),
+                Password::PlainText("123456"),
+            )
+            .await
+            .unwrap();
+        assert_eq!("tenant:root", user_info.username());
+        assert_eq!(["admin"], user_info.roles());
+
+        let user_info = provider
+            .authenticate(
+                Identity::UserId("tenant:guest", None),
+                Password::PlainText("654321"),
+            )
+            .await
+            .unwrap();
+        assert_eq!("tenant:guest", user_info.username());
+        assert!(user_info.roles().is_empty());
+    }
+
     #[tokio::test]
     async fn test_file_provider() {
         let dir = TempDir::new("test_file_provider").unwrap();
@@ -0,0 +1,414 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server side cursors, which let clients of SQL sessions iterate huge result sets in
+//! chunks instead of receiving them at once:
+//!
+//! ```sql
+//! DECLARE name [NO SCROLL] CURSOR [WITH | WITHOUT HOLD] FOR query;
+//! FETCH [NEXT | FORWARD] [count | ALL] {FROM | IN} name;
+//! CLOSE {name | ALL};
+//! ```
+//!
+//! The result of the query is kept as a stream, so only the rows being fetched are
+//! materialized.
+//!
+//! The statements are supported in both Postgres and MySQL sessions. They are not valid
+//! MySQL outside stored procedures though, so MySQL clients and BI tools don't use them on
+//! their own, and `COM_STMT_FETCH` is not supported since server side prepared statements
+//! are not supported yet. Postgres portals always return all their rows, even if the
+//! `Execute` message limits the rows to return, as the response can't suspend the portal.
+//!
+//! A session holds at most [DEFAULT_MAX_CURSORS] cursors, cursors not fetched for
+//! [DEFAULT_CURSOR_IDLE_TIMEOUT] are closed.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches, SendableRecordBatchStream};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use tokio::sync::Mutex;
+
+use crate::error::{self, Result};
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+static DECLARE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?is)^\s*DECLARE\s+(\w+)\s+(?:NO\s+SCROLL\s+)?CURSOR\s+",
+        r"(?:WITH(?:OUT)?\s+HOLD\s+)?FOR\s+(.+?)\s*;?\s*$",
+    ))
+    .unwrap()
+});
+static FETCH_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?is)^\s*FETCH\s+(?:(?:NEXT|FORWARD)\s+|(?:FORWARD\s+)?(ALL|\d+)\s+)?",
+        r"(?:FROM|IN)\s+(\w+)\s*;?\s*$",
+    ))
+    .unwrap()
+});
+static CLOSE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)^\s*CLOSE\s+(\w+)\s*;?\s*$").unwrap());
+
+/// Statements to manage cursors, names of cursors are case insensitive.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CursorStatement<'a> {
+    Declare {
+        name: String,
+        query: &'a str,
+    },
+    /// Fetches `count` rows, all remaining rows if `count` is `None`.
+    Fetch {
+        name: String,
+        count: Option<usize>,
+    },
+    /// Closes the cursor `name`, all cursors if `name` is `None`.
+    Close {
+        name: Option<String>,
+    },
+}
+
+impl<'a> CursorStatement<'a> {
+    /// Parses the cursor statement, returns `None` if `sql` is not a cursor statement.
+    pub fn parse(sql: &'a str) -> Option<Self> {
+        if let Some(captures) = DECLARE_PATTERN.captures(sql) {
+            return Some(CursorStatement::Declare {
+                name: captures[1].to_lowercase(),
+                query: captures.get(2)?.as_str(),
+            });
+        }
+        if let Some(captures) = FETCH_PATTERN.captures(sql) {
+            let count = match captures.get(1) {
+                Some(count) if count.as_str().eq_ignore_ascii_case("all") => None,
+                Some(count) => Some(count.as_str().parse().ok()?),
+                None => Some(1),
+            };
+            return Some(CursorStatement::Fetch {
+                name: captures[2].to_lowercase(),
+                count,
+            });
+        }
+        CLOSE_PATTERN.captures(sql).map(|captures| {
+            let name = captures[1].to_lowercase();
+            CursorStatement::Close {
+                name: (name != "all").then_some(name),
+            }
+        })
+    }
+}
+
+/// Max number of cursors of a session.
+pub const DEFAULT_MAX_CURSORS: usize = 64;
+/// Cursors not fetched for the duration are closed.
+pub const DEFAULT_CURSOR_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Cursors declared in a session.
+pub struct Cursors {
+    cursors: Arc<Mutex<HashMap<String, Cursor>>>,
+    max_cursors: usize,
+    idle_timeout: Duration,
+    reaper_started: AtomicBool,
+}
+
+impl Default for Cursors {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CURSORS, DEFAULT_CURSOR_IDLE_TIMEOUT)
+    }
+}
+
+struct Cursor {
+    stream: SendableRecordBatchStream,
+    /// Rows of the last polled batch that are not fetched yet.
+    pending: Option<RecordBatch>,
+    last_accessed: Instant,
+}
+
+impl Cursors {
+    pub fn new(max_cursors: usize, idle_timeout: Duration) -> Self {
+        Self {
+            cursors: Arc::new(Mutex::new(HashMap::new())),
+            max_cursors,
+            idle_timeout,
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Executes the cursor `statement`, queries of cursors are executed by `query_handler`.
+    pub async fn execute(
+        &self,
+        statement: CursorStatement<'_>,
+        query_handler: &ServerSqlQueryHandlerRef,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        match statement {
+            CursorStatement::Declare { name, query } => {
+                ensure!(
+                    !self.cursors.lock().await.contains_key(&name),
+                    error::InvalidQuerySnafu {
+                        reason: format!("cursor {name} already exists"),
+                    }
+                );
+                let stream = match execute_query(query, query_handler, query_ctx).await? {
+                    Output::Stream(stream) => stream,
+                    Output::RecordBatches(recordbatches) => recordbatches.as_stream(),
+                    Output::AffectedRows(_) => {
+                        return error::InvalidQuerySnafu {
+                            reason: format!("cursor {name} must be declared for a query"),
+                        }
+                        .fail()
+                    }
+                };
+                self.insert(name, stream).await?;
+                Ok(Output::AffectedRows(0))
+            }
+            CursorStatement::Fetch { name, count } => {
+                let mut cursors = self.cursors.lock().await;
+                let cursor = cursors.get_mut(&name).context(error::InvalidQuerySnafu {
+                    reason: format!("cursor {name} does not exist"),
+                })?;
+                cursor
+                    .fetch(count.unwrap_or(usize::MAX))
+                    .await
+                    .map(Output::RecordBatches)
+            }
+            CursorStatement::Close { name: Some(name) } => {
+                let closed = self.cursors.lock().await.remove(&name);
+                ensure!(
+                    closed.is_some(),
+                    error::InvalidQuerySnafu {
+                        reason: format!("cursor {name} does not exist"),
+                    }
+                );
+                Ok(Output::AffectedRows(0))
+            }
+            CursorStatement::Close { name: None } => {
+                self.cursors.lock().await.clear();
+                Ok(Output::AffectedRows(0))
+            }
+        }
+    }
+
+    async fn insert(&self, name: String, stream: SendableRecordBatchStream) -> Result<()> {
+        let mut cursors = self.cursors.lock().await;
+        remove_idle_cursors(&mut cursors, self.idle_timeout);
+        ensure!(
+            cursors.len() < self.max_cursors,
+            error::InvalidQuerySnafu {
+                reason: format!(
+                    "too many cursors, at most {} cursors are allowed",
+                    self.max_cursors
+                ),
+            }
+        );
+        let cursor = Cursor {
+            stream,
+            pending: None,
+            last_accessed: Instant::now(),
+        };
+        let _ = cursors.insert(name, cursor);
+        drop(cursors);
+
+        self.start_reaper();
+        Ok(())
+    }
+
+    /// Starts a task to close idle cursors periodically, which exits after the cursors are
+    /// dropped with the session.
+    fn start_reaper(&self) {
+        if self.reaper_started.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let cursors = Arc::downgrade(&self.cursors);
+        let idle_timeout = self.idle_timeout;
+        let _ = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(idle_timeout / 2).await;
+                let cursors = match cursors.upgrade() {
+                    Some(cursors) => cursors,
+                    None => break,
+                };
+                remove_idle_cursors(&mut *cursors.lock().await, idle_timeout);
+            }
+        });
+    }
+}
+
+async fn execute_query(
+    query: &str,
+    query_handler: &ServerSqlQueryHandlerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let mut outputs = query_handler.do_query(query, query_ctx).await;
+    ensure!(
+        outputs.len() == 1,
+        error::InvalidQuerySnafu {
+            reason: "cursor must be declared for a single query",
+        }
+    );
+    outputs.remove(0)
+}
+
+fn remove_idle_cursors(cursors: &mut HashMap<String, Cursor>, idle_timeout: Duration) {
+    cursors.retain(|_, cursor| cursor.last_accessed.elapsed() < idle_timeout);
+}
+
+impl Cursor {
+    /// Fetches at most `count` rows from the cursor.
+    async fn fetch(&mut self, count: usize) -> Result<RecordBatches> {
+        self.last_accessed = Instant::now();
+        let schema = self.stream.schema();
+        let mut batches = Vec::new();
+        let mut remaining = count;
+        while remaining > 0 {
+            let batch = match self.pending.take() {
+                Some(batch) => batch,
+                None => match self.stream.next().await {
+                    Some(batch) => batch.context(error::CollectRecordbatchSnafu)?,
+                    None => break,
+                },
+            };
+            if batch.num_rows() > remaining {
+                batches.push(slice_batch(&batch, 0, remaining)?);
+                let pending_rows = batch.num_rows() - remaining;
+                self.pending = Some(slice_batch(&batch, remaining, pending_rows)?);
+                break;
+            }
+            remaining -= batch.num_rows();
+            batches.push(batch);
+        }
+        RecordBatches::try_new(schema, batches).context(error::CollectRecordbatchSnafu)
+    }
+}
+
+fn slice_batch(batch: &RecordBatch, offset: usize, length: usize) -> Result<RecordBatch> {
+    RecordBatch::try_from_df_record_batch(
+        batch.schema.clone(),
+        batch.df_record_batch().slice(offset, length),
+    )
+    .context(error::CollectRecordbatchSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::UInt32Vector;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_cursor_statement() {
+        assert_eq!(
+            Some(CursorStatement::Declare {
+                name: "c".to_string(),
+                query: "select * from numbers",
+            }),
+            CursorStatement::parse(
+                "DECLARE C NO SCROLL CURSOR WITH HOLD FOR select * from numbers;"
+            )
+        );
+        assert_eq!(
+            Some(CursorStatement::Fetch {
+                name: "c".to_string(),
+                count: Some(100),
+            }),
+            CursorStatement::parse("fetch forward 100 from c")
+        );
+        assert_eq!(
+            Some(CursorStatement::Fetch {
+                name: "c".to_string(),
+                count: Some(1),
+            }),
+            CursorStatement::parse("FETCH NEXT IN c")
+        );
+        assert_eq!(
+            Some(CursorStatement::Fetch {
+                name: "c".to_string(),
+                count: None,
+            }),
+            CursorStatement::parse("FETCH ALL FROM c")
+        );
+        assert_eq!(
+            Some(CursorStatement::Close { name: None }),
+            CursorStatement::parse("close all")
+        );
+        assert_eq!(
+            Some(CursorStatement::Close {
+                name: Some("c".to_string())
+            }),
+            CursorStatement::parse("CLOSE c;")
+        );
+        assert_eq!(None, CursorStatement::parse("select * from cursors"));
+    }
+
+    fn new_stream() -> SendableRecordBatchStream {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )]));
+        let batches = [vec![0, 1, 2], vec![3, 4, 5, 6]]
+            .into_iter()
+            .map(|numbers| {
+                let column: VectorRef = Arc::new(UInt32Vector::from_vec(numbers));
+                RecordBatch::new(schema.clone(), vec![column]).unwrap()
+            })
+            .collect();
+        RecordBatches::try_new(schema, batches).unwrap().as_stream()
+    }
+
+    #[tokio::test]
+    async fn test_cursor_limits() {
+        let cursors = Cursors::new(2, Duration::from_millis(100));
+        cursors.insert("a".to_string(), new_stream()).await.unwrap();
+        cursors.insert("b".to_string(), new_stream()).await.unwrap();
+        assert!(cursors.insert("c".to_string(), new_stream()).await.is_err());
+
+        // Idle cursors are closed.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(cursors.cursors.lock().await.is_empty());
+        cursors.insert("c".to_string(), new_stream()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cursor_fetch() {
+        let mut cursor = Cursor {
+            stream: new_stream(),
+            pending: None,
+            last_accessed: Instant::now(),
+        };
+
+        let mut fetched = Vec::new();
+        for count in [2, 2, 2, 2, 2] {
+            let recordbatches = cursor.fetch(count).await.unwrap();
+            let numbers = recordbatches
+                .iter()
+                .flat_map(|batch| batch.rows())
+                .map(|row| row[0].clone())
+                .collect::<Vec<_>>();
+            fetched.push(numbers);
+        }
+        let expected = [vec![0, 1], vec![2, 3], vec![4, 5], vec![6], vec![]]
+            .into_iter()
+            .map(|numbers| numbers.into_iter().map(Value::UInt32).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, fetched);
+    }
+}
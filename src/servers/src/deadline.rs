@@ -0,0 +1,274 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deadlines supplied by clients, queries are abandoned once their deadlines pass so
+//! that they stop consuming resources for clients that have given up waiting.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use session::context::QueryContext;
+use snafu::{ensure, IntoError, OptionExt};
+use tokio::time::Sleep;
+
+use crate::error::{self, Result};
+
+/// Metadata key of the timeout of gRPC requests, see
+/// <https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-HTTP2.md>.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Max number of digits of the value of `grpc-timeout`.
+const GRPC_TIMEOUT_MAX_DIGITS: usize = 8;
+
+/// Parses the value of the `grpc-timeout` metadata, like `100m` for 100 milliseconds.
+pub fn parse_grpc_timeout(value: &str) -> Result<Duration> {
+    let invalid = || error::InvalidTimeoutSnafu { timeout: value }.build();
+    ensure!(
+        value.len() >= 2 && value.len() <= GRPC_TIMEOUT_MAX_DIGITS + 1,
+        error::InvalidTimeoutSnafu { timeout: value }
+    );
+    let (digits, unit) = value.split_at(value.len() - 1);
+    let amount = digits.parse::<u64>().map_err(|_| invalid())?;
+    let timeout = match unit {
+        "H" => Duration::from_secs(amount * 3600),
+        "M" => Duration::from_secs(amount * 60),
+        "S" => Duration::from_secs(amount),
+        "m" => Duration::from_millis(amount),
+        "u" => Duration::from_micros(amount),
+        "n" => Duration::from_nanos(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(timeout)
+}
+
+/// Parses the timeout of HTTP requests in human readable form, like `30s` or `1m`.
+pub fn parse_http_timeout(value: &str) -> Result<Duration> {
+    humantime::parse_duration(value)
+        .ok()
+        .context(error::InvalidTimeoutSnafu { timeout: value })
+}
+
+/// Sets the deadline of the queries of `query_ctx` to `timeout` from now.
+pub fn set_timeout(query_ctx: &QueryContext, timeout: Duration) -> Result<()> {
+    let _ = query_ctx
+        .set_timeout(timeout)
+        .context(error::InvalidTimeoutSnafu {
+            timeout: format!("{timeout:?}"),
+        })?;
+    Ok(())
+}
+
+/// Runs the query `future` until the `deadline`. If the query outputs a stream, the
+/// stream ends with an error once the deadline passes.
+pub async fn run_until<F>(deadline: Option<Instant>, future: F) -> Result<Output>
+where
+    F: Future<Output = Result<Output>>,
+{
+    let Some(deadline) = deadline else {
+        return future.await;
+    };
+    match tokio::time::timeout_at(deadline.into(), future).await {
+        Ok(result) => result.map(|output| with_deadline(output, deadline)),
+        Err(_) => error::DeadlineExceededSnafu.fail(),
+    }
+}
+
+/// Runs the `future` of queries until the `deadline`, like [run_until]. Queries not
+/// finished before the deadline are abandoned with an error.
+pub async fn run_all_until<F>(deadline: Option<Instant>, future: F) -> Vec<Result<Output>>
+where
+    F: Future<Output = Vec<Result<Output>>>,
+{
+    let Some(deadline) = deadline else {
+        return future.await;
+    };
+    match tokio::time::timeout_at(deadline.into(), future).await {
+        Ok(results) => results
+            .into_iter()
+            .map(|result| result.map(|output| with_deadline(output, deadline)))
+            .collect(),
+        Err(_) => vec![error::DeadlineExceededSnafu.fail()],
+    }
+}
+
+fn with_deadline(output: Output, deadline: Instant) -> Output {
+    match output {
+        Output::Stream(stream) => Output::Stream(Box::pin(DeadlineRecordBatchStream {
+            schema: stream.schema(),
+            stream: Some(stream),
+            sleep: Box::pin(tokio::time::sleep_until(deadline.into())),
+        })),
+        output => output,
+    }
+}
+
+/// Output stream of a query that ends with an error once the deadline passes. The
+/// inner stream is dropped at the deadline, which cancels the scans behind it.
+struct DeadlineRecordBatchStream {
+    schema: SchemaRef,
+    stream: Option<SendableRecordBatchStream>,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl Stream for DeadlineRecordBatchStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.stream.is_none() {
+            return Poll::Ready(None);
+        }
+        if self.sleep.as_mut().poll(cx).is_ready() {
+            self.stream = None;
+            let error = error::DeadlineExceededSnafu.build();
+            return Poll::Ready(Some(Err(ExternalSnafu.into_error(BoxedError::new(error)))));
+        }
+
+        // Safety: the stream is checked above.
+        let stream = self.stream.as_mut().unwrap();
+        match stream.as_mut().poll_next(cx) {
+            Poll::Ready(None) => {
+                self.stream = None;
+                Poll::Ready(None)
+            }
+            poll => poll,
+        }
+    }
+}
+
+impl RecordBatchStream for DeadlineRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::UInt64Vector;
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(Duration::from_secs(7200), parse_grpc_timeout("2H").unwrap());
+        assert_eq!(Duration::from_secs(180), parse_grpc_timeout("3M").unwrap());
+        assert_eq!(Duration::from_secs(5), parse_grpc_timeout("5S").unwrap());
+        assert_eq!(
+            Duration::from_millis(100),
+            parse_grpc_timeout("100m").unwrap()
+        );
+        assert_eq!(
+            Duration::from_micros(42),
+            parse_grpc_timeout("42u").unwrap()
+        );
+        assert_eq!(
+            Duration::from_nanos(99999999),
+            parse_grpc_timeout("99999999n").unwrap()
+        );
+
+        for value in ["", "m", "100", "100x", "-1S", "123456789S"] {
+            let err = parse_grpc_timeout(value).unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+    }
+
+    #[test]
+    fn test_parse_http_timeout() {
+        assert_eq!(Duration::from_secs(30), parse_http_timeout("30s").unwrap());
+        assert_eq!(
+            Duration::from_millis(1500),
+            parse_http_timeout("1s 500ms").unwrap()
+        );
+        assert!(parse_http_timeout("soon").is_err());
+    }
+
+    #[test]
+    fn test_set_timeout() {
+        let query_ctx = QueryContext::new();
+        set_timeout(&query_ctx, Duration::from_secs(30)).unwrap();
+        assert!(query_ctx.deadline().is_some());
+
+        let err = set_timeout(&query_ctx, Duration::MAX).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    #[tokio::test]
+    async fn test_run_until_deadline() {
+        let output = run_until(None, async { Ok(Output::AffectedRows(1)) })
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let err = run_until(Some(deadline), async {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(Output::AffectedRows(0))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(StatusCode::DeadlineExceeded, err.status_code());
+
+        let results = run_all_until(Some(deadline), futures::future::pending()).await;
+        assert_eq!(1, results.len());
+        assert_eq!(
+            StatusCode::DeadlineExceeded,
+            results[0].as_ref().unwrap_err().status_code()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_output_stream_deadline() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::uint64_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(UInt64Vector::from_slice([1, 2])) as _],
+        )
+        .unwrap();
+        let stream = RecordBatches::try_new(schema, vec![batch.clone(), batch])
+            .unwrap()
+            .as_stream();
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let output = run_until(Some(deadline), async move { Ok(Output::Stream(stream)) })
+            .await
+            .unwrap();
+        let Output::Stream(mut stream) = output else {
+            unreachable!()
+        };
+        assert!(stream.next().await.unwrap().is_ok());
+
+        tokio::time::sleep_until(deadline.into()).await;
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::DeadlineExceeded, err.status_code());
+        assert!(stream.next().await.is_none());
+    }
+}
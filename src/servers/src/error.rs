@@ -63,6 +63,12 @@ pub enum Error {
     #[snafu(display("Failed to start gRPC server, source: {}", source))]
     StartGrpc { source: tonic::transport::Error },
 
+    #[snafu(display("Failed to setup TLS of gRPC server, source: {}", source))]
+    GrpcTls {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
     #[snafu(display("{} server is already started", server))]
     AlreadyStarted {
         server: String,
@@ -260,6 +266,94 @@ pub enum Error {
 
     #[snafu(display("Cannot find requested database: {}-{}", catalog, schema))]
     DatabaseNotFound { catalog: String, schema: String },
+
+    #[snafu(display("Cannot find requested catalog: {}", catalog))]
+    CatalogNotFound { catalog: String },
+
+    #[snafu(display("Cannot find requested table: {}", table))]
+    TableNotFound { table: String },
+
+    #[snafu(display("Failed to convert schema of table {}, source: {}", table, source))]
+    ConvertTableSchema {
+        table: String,
+        #[snafu(backtrace)]
+        source: api::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to convert default constraint of column {}, source: {}",
+        column,
+        source
+    ))]
+    ConvertColumnDefaultConstraint {
+        column: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display(
+        "gRPC request of {} bytes exceeds the max message size {}",
+        size,
+        limit
+    ))]
+    GrpcRequestTooLarge { size: usize, limit: usize },
+
+    #[snafu(display(
+        "Too many in-flight gRPC requests from connection {}, limit: {}",
+        addr,
+        limit
+    ))]
+    TooManyInFlightRequests { addr: SocketAddr, limit: usize },
+
+    #[snafu(display("Failed to read changes of table {}, source: {}", table, source))]
+    ReadChanges {
+        table: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Failed to convert changes of table {}, source: {}", table, source))]
+    ConvertChanges {
+        table: String,
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
+    #[snafu(display("Invalid export target {}, reason: {}", target, reason))]
+    InvalidExportTarget {
+        target: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to encode exported rows, source: {}", source))]
+    EncodeExportRows {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write exported rows to {}, source: {}", target, source))]
+    WriteExport {
+        target: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write exported rows to {}, source: {}", target, source))]
+    WriteExportFile {
+        target: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid timeout of the request: {}", timeout))]
+    InvalidTimeout {
+        timeout: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("The deadline of the query is exceeded"))]
+    DeadlineExceeded { backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -279,7 +373,10 @@ impl ErrorExt for Error {
             | InvalidPromRemoteReadQueryResult { .. }
             | TcpBind { .. }
             | CatalogError { .. }
-            | BuildingContext { .. } => StatusCode::Internal,
+            | ConvertTableSchema { .. }
+            | ConvertColumnDefaultConstraint { .. }
+            | BuildingContext { .. }
+            | EncodeExportRows { .. } => StatusCode::Internal,
 
             InsertScript { source, .. }
             | ExecuteScript { source, .. }
@@ -290,6 +387,8 @@ impl ErrorExt for Error {
             | ExecuteAlter { source, .. }
             | PutOpentsdbDataPoint { source, .. } => source.status_code(),
 
+            GrpcTls { source } => source.status_code(),
+
             NotSupported { .. }
             | InvalidQuery { .. }
             | InfluxdbLineProtocol { .. }
@@ -300,7 +399,10 @@ impl ErrorExt for Error {
             | DecompressPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
             | InvalidFlightTicket { .. }
-            | TimePrecision { .. } => StatusCode::InvalidArguments,
+            | GrpcRequestTooLarge { .. }
+            | TimePrecision { .. }
+            | InvalidExportTarget { .. }
+            | InvalidTimeout { .. } => StatusCode::InvalidArguments,
 
             InfluxdbLinesWrite { source, .. } | ConvertFlightMessage { source } => {
                 source.status_code()
@@ -319,7 +421,17 @@ impl ErrorExt for Error {
             | InvalidBase64Value { .. }
             | InvalidUtf8Value { .. } => StatusCode::InvalidAuthHeader,
 
-            DatabaseNotFound { .. } => StatusCode::DatabaseNotFound,
+            DatabaseNotFound { .. } | CatalogNotFound { .. } => StatusCode::DatabaseNotFound,
+            TableNotFound { .. } => StatusCode::TableNotFound,
+
+            TooManyInFlightRequests { .. } => StatusCode::RateLimited,
+
+            ReadChanges { source, .. } => source.status_code(),
+            ConvertChanges { source, .. } => source.status_code(),
+
+            WriteExport { .. } | WriteExportFile { .. } => StatusCode::StorageUnavailable,
+
+            DeadlineExceeded { .. } => StatusCode::DeadlineExceeded,
         }
     }
 
@@ -336,18 +448,34 @@ impl From<Error> for tonic::Status {
     fn from(err: Error) -> Self {
         let mut headers = HeaderMap::<HeaderValue>::with_capacity(2);
 
-        // If either of the status_code or error msg cannot convert to valid HTTP header value
-        // (which is a very rare case), just ignore. Client will use Tonic status code and message.
-        if let Ok(code) = HeaderValue::from_bytes(err.status_code().to_string().as_bytes()) {
-            headers.insert(INNER_ERROR_CODE, code);
-        }
+        // The numeric status code is stable, so clients could rely on it.
+        let status_code = err.status_code();
+        headers.insert(INNER_ERROR_CODE, HeaderValue::from(status_code as u32));
+        // If the error msg cannot convert to valid HTTP header value (which is a very rare
+        // case), just ignore. Client will use Tonic status code and message.
         let root_error = err.iter_chain().last().unwrap();
         if let Ok(err_msg) = HeaderValue::from_bytes(root_error.to_string().as_bytes()) {
             headers.insert(INNER_ERROR_MSG, err_msg);
         }
 
         let metadata = MetadataMap::from_headers(headers);
-        tonic::Status::with_metadata(Code::Internal, err.to_string(), metadata)
+        let code = match err {
+            Error::GrpcRequestTooLarge { .. } | Error::TooManyInFlightRequests { .. } => {
+                Code::ResourceExhausted
+            }
+            Error::CatalogNotFound { .. }
+            | Error::DatabaseNotFound { .. }
+            | Error::TableNotFound { .. } => Code::NotFound,
+            Error::Auth { .. } if status_code == StatusCode::AccessDenied => Code::PermissionDenied,
+            Error::Auth { .. }
+            | Error::NotFoundAuthHeader { .. }
+            | Error::InvalidAuthorizationHeader { .. }
+            | Error::UnsupportedAuthScheme { .. } => Code::Unauthenticated,
+            // The deadline may be exceeded by the handler or the datanodes behind it.
+            _ if status_code == StatusCode::DeadlineExceeded => Code::DeadlineExceeded,
+            _ => Code::Internal,
+        };
+        tonic::Status::with_metadata(code, err.to_string(), metadata)
     }
 }
 
@@ -365,7 +493,7 @@ impl From<auth::Error> for Error {
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
+        let (status, error_message) = match &self {
             Error::InfluxdbLineProtocol { .. }
             | Error::InfluxdbLinesWrite { .. }
             | Error::InvalidOpentsdbLine { .. }
@@ -378,6 +506,7 @@ impl IntoResponse for Error {
             _ => (HttpStatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
         let body = Json(json!({
+            "code": self.status_code() as u32,
             "error": error_message,
         }));
         (status, body).into_response()
@@ -12,13 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod catalog;
+mod change_stream;
 mod flight;
+mod in_flight;
+mod message_size;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use api::v1::catalog_server::{Catalog, CatalogServer};
+use api::v1::change_stream_server::{ChangeStream, ChangeStreamServer};
 use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
 use async_trait::async_trait;
+use catalog::CatalogManagerRef;
+use common_grpc::tls::{self, GrpcTlsOption};
+use common_grpc::token::{ClusterToken, VerifyTokenInterceptor};
 use common_runtime::Runtime;
 use common_telemetry::logging::info;
 use futures::FutureExt;
@@ -27,16 +36,54 @@ use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
 use tokio_stream::wrappers::TcpListenerStream;
+use tonic::codegen::InterceptedService;
 
-use crate::error::{AlreadyStartedSnafu, Result, StartGrpcSnafu, TcpBindSnafu};
+use crate::auth::UserProviderRef;
+use crate::error::{AlreadyStartedSnafu, GrpcTlsSnafu, Result, StartGrpcSnafu, TcpBindSnafu};
+use crate::grpc::catalog::CatalogHandler;
+use crate::grpc::change_stream::ChangeStreamHandler;
 use crate::grpc::flight::FlightHandler;
+use crate::grpc::message_size::MaxMessageSizeLayer;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 use crate::server::Server;
 
+/// Default max size of a gRPC request message, 512MB.
+pub const DEFAULT_MAX_GRPC_MESSAGE_SIZE: usize = 512 * 1024 * 1024;
+
+/// Limits of requests to the gRPC server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrpcServerConfig {
+    /// Max size of a request message in bytes, checked before the message is decoded.
+    pub max_message_size: usize,
+    /// Max number of concurrent HTTP/2 streams of a connection, unlimited if not set.
+    pub max_concurrent_streams: Option<u32>,
+    /// Max number of in-flight requests of a connection, unlimited if not set. Requests
+    /// exceeding it are rejected.
+    pub max_in_flight_requests_per_connection: Option<usize>,
+}
+
+impl Default for GrpcServerConfig {
+    fn default() -> Self {
+        Self {
+            max_message_size: DEFAULT_MAX_GRPC_MESSAGE_SIZE,
+            max_concurrent_streams: None,
+            max_in_flight_requests_per_connection: None,
+        }
+    }
+}
+
 pub struct GrpcServer {
     query_handler: ServerGrpcQueryHandlerRef,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     runtime: Arc<Runtime>,
+    config: GrpcServerConfig,
+    catalog_manager: Option<CatalogManagerRef>,
+    /// Catalog manager whose tables are subscribed by the change stream service.
+    change_stream_catalog_manager: Option<CatalogManagerRef>,
+    tls: Option<GrpcTlsOption>,
+    /// Requests without this token are rejected, used by servers internal to a cluster.
+    cluster_token: Option<ClusterToken>,
+    user_provider: Option<UserProviderRef>,
 }
 
 impl GrpcServer {
@@ -45,13 +92,76 @@ impl GrpcServer {
             query_handler,
             shutdown_tx: Mutex::new(None),
             runtime,
+            config: GrpcServerConfig::default(),
+            catalog_manager: None,
+            change_stream_catalog_manager: None,
+            tls: None,
+            cluster_token: None,
+            user_provider: None,
         }
     }
 
+    /// Serves the catalog-browsing service over `catalog_manager`.
+    pub fn with_catalog_manager(mut self, catalog_manager: CatalogManagerRef) -> Self {
+        self.catalog_manager = Some(catalog_manager);
+        self
+    }
+
+    /// Serves the change stream service over tables in `catalog_manager`, which must
+    /// support [Table::read_changes](table::Table::read_changes).
+    pub fn with_change_stream(mut self, catalog_manager: CatalogManagerRef) -> Self {
+        self.change_stream_catalog_manager = Some(catalog_manager);
+        self
+    }
+
+    /// Limits requests to the server by `config`.
+    pub fn with_config(mut self, config: GrpcServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Serves requests over TLS by `tls`, or plaintext if it's `None`.
+    pub fn with_tls(mut self, tls: Option<GrpcTlsOption>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Rejects requests without the `cluster_token`, all requests are accepted if it's
+    /// `None`.
+    pub fn with_cluster_token(mut self, cluster_token: Option<ClusterToken>) -> Self {
+        self.cluster_token = cluster_token;
+        self
+    }
+
+    /// Authenticates requests to the catalog-browsing service by `user_provider`, all
+    /// requests are accepted if it's `None`.
+    pub fn with_user_provider(mut self, user_provider: Option<UserProviderRef>) -> Self {
+        self.user_provider = user_provider;
+        self
+    }
+
     pub fn create_service(&self) -> FlightServiceServer<impl FlightService> {
-        let service = FlightHandler::new(self.query_handler.clone(), self.runtime.clone());
+        let service = FlightHandler::new(self.query_handler.clone(), self.runtime.clone())
+            .with_in_flight_limit(self.config.max_in_flight_requests_per_connection);
         FlightServiceServer::new(service)
     }
+
+    fn create_catalog_service(&self) -> Option<CatalogServer<impl Catalog>> {
+        self.catalog_manager.clone().map(|catalog_manager| {
+            CatalogServer::new(CatalogHandler::new(
+                catalog_manager,
+                self.user_provider.clone(),
+            ))
+        })
+    }
+
+    fn create_change_stream_service(&self) -> Option<ChangeStreamServer<impl ChangeStream>> {
+        self.change_stream_catalog_manager
+            .clone()
+            .map(|catalog_manager| {
+                ChangeStreamServer::new(ChangeStreamHandler::new(catalog_manager))
+            })
+    }
 }
 
 #[async_trait]
@@ -88,12 +198,37 @@ impl Server for GrpcServer {
             (listener, addr)
         };
 
+        let verifier = VerifyTokenInterceptor::new(self.cluster_token.clone());
+        let router = tonic::transport::Server::builder()
+            .max_concurrent_streams(self.config.max_concurrent_streams)
+            .layer(MaxMessageSizeLayer::new(self.config.max_message_size))
+            .add_service(InterceptedService::new(
+                self.create_service(),
+                verifier.clone(),
+            ))
+            .add_optional_service(
+                self.create_catalog_service()
+                    .map(|service| InterceptedService::new(service, verifier.clone())),
+            )
+            .add_optional_service(
+                self.create_change_stream_service()
+                    .map(|service| InterceptedService::new(service, verifier)),
+            );
         // Would block to serve requests.
-        tonic::transport::Server::builder()
-            .add_service(self.create_service())
-            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
-            .await
-            .context(StartGrpcSnafu)?;
+        let served = match &self.tls {
+            Some(option) => {
+                let incoming = tls::tls_incoming(listener, option.clone()).context(GrpcTlsSnafu)?;
+                router
+                    .serve_with_incoming_shutdown(incoming, rx.map(drop))
+                    .await
+            }
+            None => {
+                router
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
+                    .await
+            }
+        };
+        served.context(StartGrpcSnafu)?;
 
         Ok(addr)
     }
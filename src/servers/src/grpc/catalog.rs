@@ -0,0 +1,454 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::helper::ColumnDataTypeWrapper;
+use api::v1::catalog_server::Catalog;
+use api::v1::{
+    ColumnDef, GetTableSchemaRequest, GetTableSchemaResponse, ListDatabasesRequest,
+    ListDatabasesResponse, ListTablesRequest, ListTablesResponse, RequestHeader,
+};
+use async_trait::async_trait;
+use catalog::{CatalogManagerRef, SchemaProviderRef};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use datatypes::schema::ColumnSchema;
+use session::context::UserInfo;
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+use tonic::{Request, Response, Status};
+
+use crate::auth::UserProviderRef;
+use crate::error::{self, Result};
+use crate::http::authorize::{authenticate_credential, parse_auth_header};
+
+/// Browses the databases and tables in the catalog manager.
+pub(crate) struct CatalogHandler {
+    catalog_manager: CatalogManagerRef,
+    /// Authenticates the requests if set, so users could only browse the databases they
+    /// have access to.
+    user_provider: Option<UserProviderRef>,
+}
+
+impl CatalogHandler {
+    pub(crate) fn new(
+        catalog_manager: CatalogManagerRef,
+        user_provider: Option<UserProviderRef>,
+    ) -> Self {
+        Self {
+            catalog_manager,
+            user_provider,
+        }
+    }
+
+    /// Authenticates the user of `request` by its `authorization` metadata, which is the
+    /// same as the HTTP authorization header. Returns `None` if there is no user provider.
+    async fn authenticate<T>(&self, request: &Request<T>) -> Result<Option<UserInfo>> {
+        let Some(user_provider) = &self.user_provider else {
+            return Ok(None);
+        };
+        let auth_header = request
+            .metadata()
+            .get(http::header::AUTHORIZATION.as_str())
+            .context(error::NotFoundAuthHeaderSnafu)?
+            .to_str()
+            .ok()
+            .context(error::InvalidAuthorizationHeaderSnafu)?;
+        let (scheme, credential) = parse_auth_header(auth_header)?;
+        let user_info = authenticate_credential(user_provider, scheme, credential)
+            .await
+            .context(error::AuthSnafu)?;
+        Ok(Some(user_info))
+    }
+
+    /// Checks whether the user has access to `schema` in `catalog`.
+    async fn authorize(
+        &self,
+        user_info: Option<&UserInfo>,
+        catalog: &str,
+        schema: &str,
+    ) -> Result<()> {
+        match (&self.user_provider, user_info) {
+            (Some(user_provider), Some(user_info)) => user_provider
+                .authorize(catalog, schema, user_info)
+                .await
+                .context(error::AuthSnafu),
+            _ => Ok(()),
+        }
+    }
+
+    fn schema(&self, header: Option<&RequestHeader>) -> Result<SchemaProviderRef> {
+        let (catalog, schema) = catalog_and_schema(header);
+        self.catalog_manager
+            .schema(catalog, schema)
+            .context(error::CatalogSnafu)?
+            .context(error::DatabaseNotFoundSnafu { catalog, schema })
+    }
+
+    async fn list_databases_inner(
+        &self,
+        request: ListDatabasesRequest,
+        user_info: Option<&UserInfo>,
+    ) -> Result<ListDatabasesResponse> {
+        let (catalog, _) = catalog_and_schema(request.header.as_ref());
+        let _ = self
+            .catalog_manager
+            .catalog(catalog)
+            .context(error::CatalogSnafu)?
+            .context(error::CatalogNotFoundSnafu { catalog })?;
+        let page = self
+            .catalog_manager
+            .list_schemas(
+                catalog,
+                page_token(&request.page_token),
+                page_limit(request.limit),
+            )
+            .await
+            .context(error::CatalogSnafu)?;
+        // Databases the user has no access to are left out.
+        let mut databases = Vec::with_capacity(page.items.len());
+        for database in page.items {
+            if self.authorize(user_info, catalog, &database).await.is_ok() {
+                databases.push(database);
+            }
+        }
+        Ok(ListDatabasesResponse {
+            databases,
+            next_page_token: page.next_token.unwrap_or_default(),
+        })
+    }
+
+    async fn list_tables_inner(
+        &self,
+        request: ListTablesRequest,
+        user_info: Option<&UserInfo>,
+    ) -> Result<ListTablesResponse> {
+        let header = request.header.as_ref();
+        let (catalog, schema) = catalog_and_schema(header);
+        self.authorize(user_info, catalog, schema).await?;
+        let _ = self.schema(header)?;
+        let page = self
+            .catalog_manager
+            .list_tables(
+                catalog,
+                schema,
+                page_token(&request.page_token),
+                page_limit(request.limit),
+            )
+            .await
+            .context(error::CatalogSnafu)?;
+        Ok(ListTablesResponse {
+            tables: page.items,
+            next_page_token: page.next_token.unwrap_or_default(),
+        })
+    }
+
+    async fn get_table_schema_inner(
+        &self,
+        request: GetTableSchemaRequest,
+        user_info: Option<&UserInfo>,
+    ) -> Result<GetTableSchemaResponse> {
+        let (catalog, schema) = catalog_and_schema(request.header.as_ref());
+        self.authorize(user_info, catalog, schema).await?;
+        let table_name = &request.table_name;
+        let table = self
+            .schema(request.header.as_ref())?
+            .table(table_name)
+            .context(error::CatalogSnafu)?
+            .context(error::TableNotFoundSnafu { table: table_name })?;
+
+        let schema = table.schema();
+        let column_defs = schema
+            .column_schemas()
+            .iter()
+            .map(|column_schema| column_schema_to_def(table_name, column_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let time_index = schema
+            .timestamp_column()
+            .map(|column_schema| column_schema.name.clone())
+            .unwrap_or_default();
+        // Virtual tables, e.g. system tables, have no table info nor primary keys.
+        let primary_keys = table
+            .try_table_info()
+            .map(|table_info| {
+                table_info
+                    .meta
+                    .primary_key_indices
+                    .iter()
+                    .map(|idx| schema.column_schemas()[*idx].name.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(GetTableSchemaResponse {
+            column_defs,
+            time_index,
+            primary_keys,
+        })
+    }
+}
+
+#[async_trait]
+impl Catalog for CatalogHandler {
+    async fn list_databases(
+        &self,
+        request: Request<ListDatabasesRequest>,
+    ) -> std::result::Result<Response<ListDatabasesResponse>, Status> {
+        let user_info = self.authenticate(&request).await?;
+        let response = self
+            .list_databases_inner(request.into_inner(), user_info.as_ref())
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn list_tables(
+        &self,
+        request: Request<ListTablesRequest>,
+    ) -> std::result::Result<Response<ListTablesResponse>, Status> {
+        let user_info = self.authenticate(&request).await?;
+        let response = self
+            .list_tables_inner(request.into_inner(), user_info.as_ref())
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_table_schema(
+        &self,
+        request: Request<GetTableSchemaRequest>,
+    ) -> std::result::Result<Response<GetTableSchemaResponse>, Status> {
+        let user_info = self.authenticate(&request).await?;
+        let response = self
+            .get_table_schema_inner(request.into_inner(), user_info.as_ref())
+            .await?;
+        Ok(Response::new(response))
+    }
+}
+
+/// Returns the catalog and schema in `header`, defaults are used if they are empty.
+pub(crate) fn catalog_and_schema(header: Option<&RequestHeader>) -> (&str, &str) {
+    let (catalog, schema) = header
+        .map(|header| (header.catalog.as_str(), header.schema.as_str()))
+        .unwrap_or_default();
+    let catalog = if catalog.is_empty() {
+        DEFAULT_CATALOG_NAME
+    } else {
+        catalog
+    };
+    let schema = if schema.is_empty() {
+        DEFAULT_SCHEMA_NAME
+    } else {
+        schema
+    };
+    (catalog, schema)
+}
+
+/// Returns the token of the page to list, `None` to list the first page.
+fn page_token(token: &[u8]) -> Option<&[u8]> {
+    if token.is_empty() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+/// Returns the max number of entries in a page, a limit of 0 means no limit.
+fn page_limit(limit: u32) -> usize {
+    if limit == 0 {
+        usize::MAX
+    } else {
+        limit as usize
+    }
+}
+
+fn column_schema_to_def(table: &str, column_schema: &ColumnSchema) -> Result<ColumnDef> {
+    let datatype = ColumnDataTypeWrapper::try_from(column_schema.data_type.clone())
+        .context(error::ConvertTableSchemaSnafu { table })?
+        .datatype();
+    let default_constraint =
+        match column_schema.default_constraint() {
+            Some(constraint) => constraint.clone().try_into().context(
+                error::ConvertColumnDefaultConstraintSnafu {
+                    column: &column_schema.name,
+                },
+            )?,
+            None => vec![],
+        };
+    Ok(ColumnDef {
+        name: column_schema.name.clone(),
+        datatype: datatype as i32,
+        is_nullable: column_schema.is_nullable(),
+        default_constraint,
+        comment: column_schema.comment().unwrap_or_default().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::ColumnDataType;
+    use catalog::local::MemoryCatalogManager;
+    use table::test_util::MemTable;
+
+    use super::*;
+    use crate::auth::user_provider::StaticUserProvider;
+
+    fn new_handler() -> CatalogHandler {
+        let catalog_manager = Arc::new(MemoryCatalogManager::default());
+        catalog_manager
+            .schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap()
+            .register_table(
+                "numbers".to_string(),
+                Arc::new(MemTable::default_numbers_table()),
+            )
+            .unwrap();
+        CatalogHandler::new(catalog_manager, None)
+    }
+
+    fn header(catalog: &str, schema: &str) -> Option<RequestHeader> {
+        Some(RequestHeader {
+            catalog: catalog.to_string(),
+            schema: schema.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_catalog_handler() {
+        let handler = new_handler();
+
+        let response = handler
+            .list_databases(Request::new(ListDatabasesRequest::default()))
+            .await
+            .unwrap();
+        assert_eq!(vec!["public"], response.into_inner().databases);
+        let status = handler
+            .list_databases(Request::new(ListDatabasesRequest {
+                header: header("absent", ""),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::NotFound, status.code());
+
+        let response = handler
+            .list_tables(Request::new(ListTablesRequest {
+                header: header("", "public"),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(vec!["numbers"], response.tables);
+        assert!(response.next_page_token.is_empty());
+
+        handler
+            .catalog_manager
+            .schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap()
+            .register_table(
+                "numbers_2".to_string(),
+                Arc::new(MemTable::default_numbers_table()),
+            )
+            .unwrap();
+        let request = |page_token| ListTablesRequest {
+            header: None,
+            limit: 1,
+            page_token,
+        };
+        let response = handler
+            .list_tables(Request::new(request(vec![])))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(vec!["numbers"], response.tables);
+        let response = handler
+            .list_tables(Request::new(request(response.next_page_token)))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(vec!["numbers_2"], response.tables);
+        assert!(response.next_page_token.is_empty());
+        let status = handler
+            .list_tables(Request::new(ListTablesRequest {
+                header: header("", "absent"),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::NotFound, status.code());
+
+        let response = handler
+            .get_table_schema(Request::new(GetTableSchemaRequest {
+                header: None,
+                table_name: "numbers".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            vec![ColumnDef {
+                name: "uint32s".to_string(),
+                datatype: ColumnDataType::Uint32 as i32,
+                is_nullable: true,
+                default_constraint: vec![],
+                comment: String::new(),
+            }],
+            response.column_defs
+        );
+        assert!(response.time_index.is_empty());
+        assert!(response.primary_keys.is_empty());
+        let status = handler
+            .get_table_schema(Request::new(GetTableSchemaRequest {
+                header: None,
+                table_name: "absent".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::NotFound, status.code());
+    }
+
+    #[tokio::test]
+    async fn test_catalog_handler_auth() {
+        let mut handler = new_handler();
+        handler.user_provider = Some(Arc::new(
+            StaticUserProvider::try_from("cmd:root=123456").unwrap(),
+        ));
+        let request = |auth_header: Option<&str>| {
+            let mut request = Request::new(ListTablesRequest::default());
+            if let Some(auth_header) = auth_header {
+                let _ = request
+                    .metadata_mut()
+                    .insert("authorization", auth_header.parse().unwrap());
+            }
+            request
+        };
+
+        let status = handler.list_tables(request(None)).await.unwrap_err();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+        // base64encode("root:654321") == "cm9vdDo2NTQzMjE="
+        let status = handler
+            .list_tables(request(Some("Basic cm9vdDo2NTQzMjE=")))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+        // base64encode("root:123456") == "cm9vdDoxMjM0NTY="
+        let response = handler
+            .list_tables(request(Some("Basic cm9vdDoxMjM0NTY=")))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(vec!["numbers"], response.tables);
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use api::v1::change_stream_server::ChangeStream;
+use api::v1::{ChangeEvent, ChangeType, SubscribeRequest};
+use async_trait::async_trait;
+use catalog::CatalogManagerRef;
+use common_grpc_expr::change_batch_to_columns;
+use futures::Stream;
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{OpType, SequenceNumber};
+use table::TableRef;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::error::{self, Result};
+use crate::grpc::catalog::catalog_and_schema;
+
+/// Interval to poll new changes after all changes of the subscribed tables are emitted.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Max number of writes of a table read from the WAL at a time.
+const READ_BATCH_SIZE: usize = 64;
+/// Number of events buffered for a slow subscriber.
+const EVENT_CHANNEL_SIZE: usize = 128;
+
+/// Streams committed changes of tables in the catalog manager.
+pub(crate) struct ChangeStreamHandler {
+    catalog_manager: CatalogManagerRef,
+}
+
+impl ChangeStreamHandler {
+    pub(crate) fn new(catalog_manager: CatalogManagerRef) -> Self {
+        Self { catalog_manager }
+    }
+
+    fn subscriptions(&self, request: SubscribeRequest) -> Result<Vec<Subscription>> {
+        let (catalog, schema) = catalog_and_schema(request.header.as_ref());
+        let schema_provider = self
+            .catalog_manager
+            .schema(catalog, schema)
+            .context(error::CatalogSnafu)?
+            .context(error::DatabaseNotFoundSnafu { catalog, schema })?;
+
+        request
+            .tables
+            .into_iter()
+            .map(|subscription| {
+                let table_name = subscription.table_name;
+                let table = schema_provider
+                    .table(&table_name)
+                    .context(error::CatalogSnafu)?
+                    .context(error::TableNotFoundSnafu { table: &table_name })?;
+                let schema = table.schema();
+                let primary_keys = table
+                    .table_info()
+                    .meta
+                    .primary_key_indices
+                    .iter()
+                    .map(|idx| schema.column_schemas()[*idx].name.clone())
+                    .collect();
+                Ok(Subscription {
+                    table,
+                    table_name,
+                    primary_keys,
+                    next_sequence: subscription.start_sequence,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Position of a subscriber in the changes of a table.
+struct Subscription {
+    table: TableRef,
+    table_name: String,
+    primary_keys: Vec<String>,
+    next_sequence: SequenceNumber,
+}
+
+impl Subscription {
+    /// Reads changes after the position and moves the position forward.
+    async fn poll(&mut self) -> Result<Vec<ChangeEvent>> {
+        let batches = self
+            .table
+            .read_changes(self.next_sequence, READ_BATCH_SIZE)
+            .await
+            .context(error::ReadChangesSnafu {
+                table: &self.table_name,
+            })?;
+        if let Some(last) = batches.last() {
+            self.next_sequence = last.sequence + 1;
+        }
+
+        batches
+            .iter()
+            .map(|batch| {
+                let columns = change_batch_to_columns(batch, &self.primary_keys).context(
+                    error::ConvertChangesSnafu {
+                        table: &self.table_name,
+                    },
+                )?;
+                let change_type = match batch.op_type {
+                    OpType::Put => ChangeType::Put,
+                    OpType::Delete => ChangeType::Delete,
+                };
+                Ok(ChangeEvent {
+                    table_name: self.table_name.clone(),
+                    sequence: batch.sequence,
+                    change_type: change_type as i32,
+                    columns,
+                    row_count: batch.num_rows() as u32,
+                })
+            })
+            .collect()
+    }
+}
+
+type ChangeEventStream =
+    Pin<Box<dyn Stream<Item = std::result::Result<ChangeEvent, Status>> + Send>>;
+
+#[async_trait]
+impl ChangeStream for ChangeStreamHandler {
+    type SubscribeStream = ChangeEventStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> std::result::Result<Response<Self::SubscribeStream>, Status> {
+        let mut subscriptions = self.subscriptions(request.into_inner())?;
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
+
+        common_runtime::spawn_bg(async move {
+            loop {
+                let mut caught_up = true;
+                for subscription in &mut subscriptions {
+                    let events = match subscription.poll().await {
+                        Ok(events) => events,
+                        Err(e) => {
+                            let _ = tx.send(Err(e.into())).await;
+                            return;
+                        }
+                    };
+                    caught_up &= events.is_empty();
+                    for event in events {
+                        // The subscriber cancels the stream.
+                        if tx.send(Ok(event)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+
+                if caught_up {
+                    tokio::select! {
+                        _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                        _ = tx.closed() => return,
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::Arc;
+
+    use api::v1::{RequestHeader, TableSubscription};
+    use catalog::local::MemoryCatalogManager;
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_query::logical_plan::Expr;
+    use common_query::physical_plan::PhysicalPlanRef;
+    use datatypes::schema::SchemaRef;
+    use datatypes::vectors::UInt32Vector;
+    use futures::StreamExt;
+    use store_api::storage::ChangeBatch;
+    use table::metadata::TableInfoRef;
+    use table::test_util::MemTable;
+    use table::Table;
+
+    use super::*;
+
+    /// Table whose WAL holds a put of two rows at sequence 1 and a delete at sequence 2.
+    struct ChangesTable {
+        inner: MemTable,
+    }
+
+    #[async_trait]
+    impl Table for ChangesTable {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.inner.schema()
+        }
+
+        fn table_info(&self) -> TableInfoRef {
+            self.inner.table_info()
+        }
+
+        async fn scan(
+            &self,
+            projection: Option<&Vec<usize>>,
+            filters: &[Expr],
+            limit: Option<usize>,
+        ) -> table::Result<PhysicalPlanRef> {
+            self.inner.scan(projection, filters, limit).await
+        }
+
+        async fn read_changes(
+            &self,
+            start_sequence: SequenceNumber,
+            _limit: usize,
+        ) -> table::Result<Vec<ChangeBatch>> {
+            let schema = self.schema();
+            Ok([(1, OpType::Put, vec![1, 2]), (2, OpType::Delete, vec![1])]
+                .into_iter()
+                .filter(|(sequence, _, _)| *sequence >= start_sequence)
+                .map(|(sequence, op_type, values)| ChangeBatch {
+                    sequence,
+                    op_type,
+                    schema: schema.clone(),
+                    columns: vec![Arc::new(UInt32Vector::from_vec(values))],
+                })
+                .collect())
+        }
+    }
+
+    fn new_handler() -> ChangeStreamHandler {
+        let catalog_manager = Arc::new(MemoryCatalogManager::default());
+        catalog_manager
+            .schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap()
+            .register_table(
+                "numbers".to_string(),
+                Arc::new(ChangesTable {
+                    inner: MemTable::default_numbers_table(),
+                }),
+            )
+            .unwrap();
+        ChangeStreamHandler::new(catalog_manager)
+    }
+
+    fn subscribe_request(table_name: &str, start_sequence: u64) -> Request<SubscribeRequest> {
+        Request::new(SubscribeRequest {
+            header: Some(RequestHeader {
+                catalog: String::new(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+            }),
+            tables: vec![TableSubscription {
+                table_name: table_name.to_string(),
+                start_sequence,
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let handler = new_handler();
+
+        let mut stream = handler
+            .subscribe(subscribe_request("numbers", 0))
+            .await
+            .unwrap()
+            .into_inner();
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!("numbers", event.table_name);
+        assert_eq!(1, event.sequence);
+        assert_eq!(ChangeType::Put as i32, event.change_type);
+        assert_eq!(2, event.row_count);
+        assert_eq!("uint32s", event.columns[0].column_name);
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(2, event.sequence);
+        assert_eq!(ChangeType::Delete as i32, event.change_type);
+        assert_eq!(1, event.row_count);
+
+        // Resumes after the first change.
+        let mut stream = handler
+            .subscribe(subscribe_request("numbers", 2))
+            .await
+            .unwrap()
+            .into_inner();
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(2, event.sequence);
+
+        let status = handler
+            .subscribe(subscribe_request("absent", 0))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(tonic::Code::NotFound, status.code());
+    }
+}
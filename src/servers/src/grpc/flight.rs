@@ -16,6 +16,7 @@ mod stream;
 
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::{GreptimeRequest, RequestHeader};
 use arrow_flight::flight_service_server::FlightService;
@@ -27,15 +28,18 @@ use async_trait::async_trait;
 use common_grpc::flight::{FlightEncoder, FlightMessage};
 use common_query::Output;
 use common_runtime::Runtime;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use prost::Message;
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{Channel, QueryContext, QueryContextRef};
 use snafu::{OptionExt, ResultExt};
 use tokio::sync::oneshot;
+use tonic::metadata::MetadataMap;
 use tonic::{Request, Response, Status, Streaming};
 
+use crate::deadline::{self, GRPC_TIMEOUT_HEADER};
 use crate::error;
 use crate::grpc::flight::stream::FlightRecordBatchStream;
+use crate::grpc::in_flight::InFlightLimiter;
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 
 type TonicResult<T> = Result<T, Status>;
@@ -44,11 +48,21 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync +
 pub(crate) struct FlightHandler {
     handler: ServerGrpcQueryHandlerRef,
     runtime: Arc<Runtime>,
+    in_flight_limiter: InFlightLimiter,
 }
 
 impl FlightHandler {
     pub(crate) fn new(handler: ServerGrpcQueryHandlerRef, runtime: Arc<Runtime>) -> Self {
-        Self { handler, runtime }
+        Self {
+            handler,
+            runtime,
+            in_flight_limiter: InFlightLimiter::default(),
+        }
+    }
+
+    pub(crate) fn with_in_flight_limit(mut self, limit: Option<usize>) -> Self {
+        self.in_flight_limiter = InFlightLimiter::new(limit);
+        self
     }
 }
 
@@ -89,6 +103,8 @@ impl FlightService for FlightHandler {
     type DoGetStream = TonicStream<FlightData>;
 
     async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        let in_flight_guard = self.in_flight_limiter.acquire(request.remote_addr())?;
+        let timeout = grpc_timeout(request.metadata())?;
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_slice()).context(error::InvalidFlightTicketSnafu)?;
@@ -97,6 +113,9 @@ impl FlightService for FlightHandler {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
         let query_ctx = create_query_context(request.header.as_ref());
+        if let Some(timeout) = timeout {
+            deadline::set_timeout(&query_ctx, timeout)?;
+        }
 
         let (tx, rx) = oneshot::channel();
         let handler = self.handler.clone();
@@ -104,8 +123,11 @@ impl FlightService for FlightHandler {
         // Executes requests in another runtime to
         // 1. prevent the execution from being cancelled unexpected by Tonic runtime;
         // 2. avoid the handler blocks the gRPC runtime incidentally.
+        let ctx = query_ctx.clone();
         self.runtime.spawn(async move {
-            let result = handler.do_query(query, query_ctx).await;
+            // The execution is abandoned once the deadline of the client passes.
+            let deadline = ctx.deadline();
+            let result = deadline::run_until(deadline, handler.do_query(query, ctx)).await;
 
             // Ignore the sending result.
             // Usually an error indicates the rx at Tonic side is dropped (due to request timeout).
@@ -116,8 +138,13 @@ impl FlightService for FlightHandler {
         // This unwrap is used to poison the upper layer.
         let output = rx.await.unwrap()?;
 
-        let stream = to_flight_data_stream(output);
-        Ok(Response::new(stream))
+        let stream = to_flight_data_stream(output, query_ctx.write_sequence());
+        // The request is in flight until all its output is sent.
+        let stream = stream.map(move |data| {
+            let _ = &in_flight_guard;
+            data
+        });
+        Ok(Response::new(Box::pin(stream)))
     }
 
     type DoPutStream = TonicStream<PutResult>;
@@ -154,7 +181,7 @@ impl FlightService for FlightHandler {
     }
 }
 
-fn to_flight_data_stream(output: Output) -> TonicStream<FlightData> {
+fn to_flight_data_stream(output: Output, write_sequence: Option<u64>) -> TonicStream<FlightData> {
     match output {
         Output::Stream(stream) => {
             let stream = FlightRecordBatchStream::new(stream);
@@ -165,16 +192,33 @@ fn to_flight_data_stream(output: Output) -> TonicStream<FlightData> {
             Box::pin(stream) as _
         }
         Output::AffectedRows(rows) => {
-            let stream = tokio_stream::once(Ok(
-                FlightEncoder::default().encode(FlightMessage::AffectedRows(rows))
-            ));
+            let message = match write_sequence {
+                Some(sequence) => FlightMessage::WalAck {
+                    affected_rows: rows,
+                    sequence,
+                },
+                None => FlightMessage::AffectedRows(rows),
+            };
+            let stream = tokio_stream::once(Ok(FlightEncoder::default().encode(message)));
             Box::pin(stream) as _
         }
     }
 }
 
+/// Returns the timeout the client sets by the `grpc-timeout` metadata.
+fn grpc_timeout(metadata: &MetadataMap) -> error::Result<Option<Duration>> {
+    let Some(value) = metadata.get(GRPC_TIMEOUT_HEADER) else {
+        return Ok(None);
+    };
+    let value = value.to_str().ok().context(error::InvalidTimeoutSnafu {
+        timeout: format!("{value:?}"),
+    })?;
+    deadline::parse_grpc_timeout(value).map(Some)
+}
+
 fn create_query_context(header: Option<&RequestHeader>) -> QueryContextRef {
     let ctx = QueryContext::arc();
+    ctx.set_channel(Channel::Grpc);
     if let Some(header) = header {
         if !header.catalog.is_empty() {
             ctx.set_current_catalog(&header.catalog);
@@ -0,0 +1,99 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use snafu::ensure;
+
+use crate::error::{Result, TooManyInFlightRequestsSnafu};
+
+/// Limits the number of in-flight requests of each connection, connections are identified
+/// by their remote addresses.
+#[derive(Debug, Default)]
+pub(crate) struct InFlightLimiter {
+    limit: Option<usize>,
+    counts: Arc<Mutex<HashMap<SocketAddr, usize>>>,
+}
+
+impl InFlightLimiter {
+    /// Creates a limiter, requests are unlimited if `limit` is not set.
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            counts: Arc::default(),
+        }
+    }
+
+    /// Starts a request from `addr`, the request is in flight until the returned guard
+    /// is dropped. Returns error if there are too many in-flight requests from `addr`.
+    pub(crate) fn acquire(&self, addr: Option<SocketAddr>) -> Result<Option<InFlightGuard>> {
+        let (Some(limit), Some(addr)) = (self.limit, addr) else {
+            return Ok(None);
+        };
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(addr).or_default();
+        ensure!(*count < limit, TooManyInFlightRequestsSnafu { addr, limit });
+        *count += 1;
+
+        Ok(Some(InFlightGuard {
+            addr,
+            counts: self.counts.clone(),
+        }))
+    }
+}
+
+pub(crate) struct InFlightGuard {
+    addr: SocketAddr,
+    counts: Arc<Mutex<HashMap<SocketAddr, usize>>>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().unwrap();
+        if let Some(count) = counts.get_mut(&self.addr) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.addr);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_flight_limiter() {
+        let limiter = InFlightLimiter::new(Some(2));
+        let addr1 = Some("127.0.0.1:5000".parse().unwrap());
+        let addr2 = Some("127.0.0.1:5001".parse().unwrap());
+
+        let guard1 = limiter.acquire(addr1).unwrap();
+        let _guard2 = limiter.acquire(addr1).unwrap();
+        assert!(limiter.acquire(addr1).is_err());
+        // Requests of other connections are not affected.
+        let _guard3 = limiter.acquire(addr2).unwrap();
+
+        drop(guard1);
+        assert!(limiter.acquire(addr1).unwrap().is_some());
+        // Requests without remote address are not limited.
+        assert!(limiter.acquire(None).unwrap().is_none());
+
+        let limiter = InFlightLimiter::new(None);
+        assert!(limiter.acquire(addr1).unwrap().is_none());
+    }
+}
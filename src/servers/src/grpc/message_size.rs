@@ -0,0 +1,196 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::task::{Context, Poll};
+
+use futures::StreamExt;
+use hyper::{Body, Request};
+use tower::{Layer, Service};
+
+use crate::error::Error;
+
+/// Length of the prefix of a gRPC message, a compressed flag followed by the length of
+/// the message in big endian u32.
+const MESSAGE_PREFIX_LEN: usize = 5;
+
+/// Rejects gRPC requests carrying a message larger than `max_message_size`, by checking
+/// the length prefixes of the messages as the request body arrives, before any message
+/// is buffered or decoded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MaxMessageSizeLayer {
+    max_message_size: usize,
+}
+
+impl MaxMessageSizeLayer {
+    pub(crate) fn new(max_message_size: usize) -> Self {
+        Self { max_message_size }
+    }
+}
+
+impl<S> Layer<S> for MaxMessageSizeLayer {
+    type Service = MaxMessageSize<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MaxMessageSize {
+            inner,
+            max_message_size: self.max_message_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MaxMessageSize<S> {
+    inner: S,
+    max_message_size: usize,
+}
+
+impl<S> Service<Request<Body>> for MaxMessageSize<S>
+where
+    S: Service<Request<Body>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let max_message_size = self.max_message_size;
+        let request = request.map(|body| {
+            let mut prefixes = MessagePrefixes::new(max_message_size);
+            // Fails the body once a message is too large, the error is surfaced by tonic
+            // as the status of the request.
+            Body::wrap_stream(body.map(move |chunk| {
+                let chunk = chunk?;
+                prefixes.check(&chunk).map_err(|size| {
+                    tonic::Status::from(Error::GrpcRequestTooLarge {
+                        size,
+                        limit: max_message_size,
+                    })
+                })?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(chunk)
+            }))
+        });
+        self.inner.call(request)
+    }
+}
+
+/// Tracks the length prefixes of the gRPC messages in a request body.
+struct MessagePrefixes {
+    max_message_size: usize,
+    prefix: [u8; MESSAGE_PREFIX_LEN],
+    /// Bytes of the `prefix` read.
+    prefix_len: usize,
+    /// Bytes of the current message not read yet.
+    remaining: usize,
+}
+
+impl MessagePrefixes {
+    fn new(max_message_size: usize) -> Self {
+        Self {
+            max_message_size,
+            prefix: [0; MESSAGE_PREFIX_LEN],
+            prefix_len: 0,
+            remaining: 0,
+        }
+    }
+
+    /// Reads the next `chunk` of the body, returns the size of the message as error if
+    /// it exceeds the `max_message_size`.
+    fn check(&mut self, mut chunk: &[u8]) -> Result<(), usize> {
+        while !chunk.is_empty() {
+            if self.remaining > 0 {
+                let n = self.remaining.min(chunk.len());
+                self.remaining -= n;
+                chunk = &chunk[n..];
+                continue;
+            }
+
+            let n = (MESSAGE_PREFIX_LEN - self.prefix_len).min(chunk.len());
+            self.prefix[self.prefix_len..self.prefix_len + n].copy_from_slice(&chunk[..n]);
+            self.prefix_len += n;
+            chunk = &chunk[n..];
+            if self.prefix_len == MESSAGE_PREFIX_LEN {
+                let size = u32::from_be_bytes(self.prefix[1..].try_into().unwrap()) as usize;
+                if size > self.max_message_size {
+                    return Err(size);
+                }
+                self.remaining = size;
+                self.prefix_len = 0;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tonic::Code;
+
+    use super::*;
+
+    fn message(size: usize) -> Vec<u8> {
+        let mut message = vec![0];
+        message.extend_from_slice(&(size as u32).to_be_bytes());
+        message.extend(std::iter::repeat(1).take(size));
+        message
+    }
+
+    #[test]
+    fn test_message_prefixes() {
+        let mut prefixes = MessagePrefixes::new(10);
+        let mut body = message(10);
+        body.extend(message(0));
+        body.extend(message(3));
+        // Prefixes split across chunks are also checked.
+        for chunk in body.chunks(2) {
+            prefixes.check(chunk).unwrap();
+        }
+
+        let mut prefixes = MessagePrefixes::new(10);
+        let body = [message(4), message(11)].concat();
+        assert_eq!(
+            Err(11),
+            prefixes.check(&body[..7]).and(prefixes.check(&body[7..]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_message_size() {
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await;
+            Ok::<_, Infallible>(body)
+        });
+        let mut service = MaxMessageSizeLayer::new(10).layer(inner);
+
+        let body = service
+            .call(Request::new(Body::from(message(10))))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message(10), body.to_vec());
+
+        let err = service
+            .call(Request::new(Body::from(message(11))))
+            .await
+            .unwrap()
+            .unwrap_err();
+        let status = tonic::Status::from_error(Box::new(err));
+        assert_eq!(Code::ResourceExhausted, status.code());
+    }
+}
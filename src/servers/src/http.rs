@@ -13,9 +13,12 @@
 // limitations under the License.
 
 pub mod authorize;
+pub mod export;
 pub mod handler;
 pub mod influxdb;
+pub mod job;
 pub mod opentsdb;
+pub mod procedure;
 pub mod prometheus;
 pub mod script;
 
@@ -32,6 +35,8 @@ use axum::response::{Html, Json};
 use axum::{routing, BoxError, Extension, Router};
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
+use common_procedure::job::{JobManager, JobManagerRef};
+use common_procedure::ProcedureManagerRef;
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::logging::info;
@@ -40,7 +45,7 @@ use futures::FutureExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use session::context::QueryContext;
+use session::context::{Channel, QueryContext};
 use snafu::{ensure, ResultExt};
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
@@ -50,7 +55,9 @@ use tower_http::auth::AsyncRequireAuthorizationLayer;
 use tower_http::trace::TraceLayer;
 
 use self::authorize::HttpAuth;
+use self::export::{ExportS3Options, ExportState};
 use self::influxdb::influxdb_write;
+use self::job::SqlJobState;
 use crate::auth::UserProviderRef;
 use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
@@ -70,7 +77,11 @@ pub(crate) fn query_context_from_db(
         let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
 
         match query_handler.is_valid_schema(catalog, schema) {
-            Ok(true) => Ok(Arc::new(QueryContext::with(catalog, schema))),
+            Ok(true) => {
+                let ctx = QueryContext::with(catalog, schema);
+                ctx.set_channel(Channel::Http);
+                Ok(Arc::new(ctx))
+            }
             Ok(false) => Err(JsonResponse::with_error(
                 format!("Database not found: {db}"),
                 StatusCode::DatabaseNotFound,
@@ -81,7 +92,9 @@ pub(crate) fn query_context_from_db(
             )),
         }
     } else {
-        Ok(QueryContext::arc())
+        let ctx = QueryContext::arc();
+        ctx.set_channel(Channel::Http);
+        Ok(ctx)
     }
 }
 
@@ -97,6 +110,8 @@ pub struct HttpServer {
     script_handler: Option<ScriptHandlerRef>,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
+    job_manager: JobManagerRef,
+    procedure_manager: Option<ProcedureManagerRef>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -104,6 +119,12 @@ pub struct HttpOptions {
     pub addr: String,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Dir to write results exported to `file://` targets, exporting to files is disabled
+    /// if it's not set.
+    pub export_dir: Option<String>,
+    /// S3 targets results could be exported to, exporting to S3 is disabled if it's not
+    /// set.
+    pub export_s3: Option<ExportS3Options>,
 }
 
 impl Default for HttpOptions {
@@ -111,6 +132,8 @@ impl Default for HttpOptions {
         Self {
             addr: "127.0.0.1:4000".to_string(),
             timeout: Duration::from_secs(30),
+            export_dir: None,
+            export_s3: None,
         }
     }
 }
@@ -221,6 +244,35 @@ impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
 pub enum JsonOutput {
     AffectedRows(usize),
     Records(HttpRecordsOutput),
+    Error(JsonError),
+}
+
+/// Payload of a statement or script that failed in a batch.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
+pub struct JsonError {
+    code: u32,
+    error: String,
+}
+
+impl JsonError {
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// How a batch of statements or scripts proceeds once one of them fails.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OnError {
+    /// Stops the batch, the rest are not executed.
+    #[default]
+    Stop,
+    /// Executes the rest of the batch.
+    Continue,
 }
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -264,47 +316,68 @@ impl JsonResponse {
         // well. It hides successful execution results from error response
         let mut results = Vec::with_capacity(outputs.len());
         for out in outputs {
-            match out {
-                Ok(Output::AffectedRows(rows)) => {
-                    results.push(JsonOutput::AffectedRows(rows));
-                }
-                Ok(Output::Stream(stream)) => {
-                    // TODO(sunng87): streaming response
-                    match util::collect(stream).await {
-                        Ok(rows) => match HttpRecordsOutput::try_from(rows) {
-                            Ok(rows) => {
-                                results.push(JsonOutput::Records(rows));
-                            }
-                            Err(err) => {
-                                return Self::with_error(err, StatusCode::Internal);
-                            }
-                        },
-
-                        Err(e) => {
-                            return Self::with_error(
-                                format!("Recordbatch error: {e}"),
-                                e.status_code(),
-                            );
-                        }
-                    }
-                }
-                Ok(Output::RecordBatches(rbs)) => match HttpRecordsOutput::try_from(rbs.take()) {
-                    Ok(rows) => {
-                        results.push(JsonOutput::Records(rows));
+            match Self::output_to_json(out).await {
+                Ok(output) => results.push(output),
+                Err((error, code)) => return Self::with_error(error, code),
+            }
+        }
+        Self::with_output(Some(results))
+    }
+
+    /// Create a json response from results of a batch, one payload per statement or
+    /// script. The error of the response is the first one failed in the batch.
+    async fn from_batch_output(outputs: Vec<Result<Output>>, on_error: OnError) -> Self {
+        let mut results = Vec::with_capacity(outputs.len());
+        let mut first_error = None;
+        for out in outputs {
+            match Self::output_to_json(out).await {
+                Ok(output) => results.push(output),
+                Err((error, code)) => {
+                    if first_error.is_none() {
+                        first_error = Some((error.clone(), code));
                     }
-                    Err(err) => {
-                        return Self::with_error(err, StatusCode::Internal);
+                    results.push(JsonOutput::Error(JsonError {
+                        code: code as u32,
+                        error,
+                    }));
+                    // Streams of the rest statements may fail lazily after the
+                    // statement failed.
+                    if on_error == OnError::Stop {
+                        break;
                     }
-                },
-                Err(e) => {
-                    return Self::with_error(
-                        format!("Query engine output error: {e}"),
-                        e.status_code(),
-                    );
                 }
             }
         }
-        Self::with_output(Some(results))
+
+        let mut resp = Self::with_output(Some(results));
+        if let Some((error, code)) = first_error {
+            resp.error = Some(error);
+            resp.code = code as u32;
+        }
+        resp
+    }
+
+    /// Converts `output` to its json payload, returns the error message and code if
+    /// the `output` fails.
+    async fn output_to_json(
+        output: Result<Output>,
+    ) -> std::result::Result<JsonOutput, (String, StatusCode)> {
+        match output {
+            Ok(Output::AffectedRows(rows)) => Ok(JsonOutput::AffectedRows(rows)),
+            Ok(Output::Stream(stream)) => {
+                // TODO(sunng87): streaming response
+                let rows = util::collect(stream)
+                    .await
+                    .map_err(|e| (format!("Recordbatch error: {e}"), e.status_code()))?;
+                HttpRecordsOutput::try_from(rows)
+                    .map(JsonOutput::Records)
+                    .map_err(|err| (err, StatusCode::Internal))
+            }
+            Ok(Output::RecordBatches(rbs)) => HttpRecordsOutput::try_from(rbs.take())
+                .map(JsonOutput::Records)
+                .map_err(|err| (err, StatusCode::Internal)),
+            Err(e) => Err((format!("Query engine output error: {e}"), e.status_code())),
+        }
     }
 
     pub fn code(&self) -> u32 {
@@ -353,6 +426,8 @@ impl HttpServer {
             user_provider: None,
             script_handler: None,
             shutdown_tx: Mutex::new(None),
+            job_manager: Arc::new(JobManager::default()),
+            procedure_manager: None,
         }
     }
 
@@ -388,6 +463,21 @@ impl HttpServer {
         self.prom_handler.get_or_insert(handler);
     }
 
+    /// Sets the manager running jobs submitted through the HTTP API, jobs are only
+    /// kept in memory by default.
+    pub fn set_job_manager(&mut self, job_manager: JobManagerRef) {
+        self.job_manager = job_manager;
+    }
+
+    /// Sets the manager running procedures, which exposes the APIs to intervene in them.
+    pub fn set_procedure_manager(&mut self, procedure_manager: ProcedureManagerRef) {
+        debug_assert!(
+            self.procedure_manager.is_none(),
+            "Procedure manager can be set only once!"
+        );
+        self.procedure_manager.get_or_insert(procedure_manager);
+    }
+
     pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
         debug_assert!(
             self.user_provider.is_none(),
@@ -419,7 +509,24 @@ impl HttpServer {
             .finish_api(&mut api)
             .layer(Extension(Arc::new(api)));
 
-        let mut router = Router::new().nest(&format!("/{HTTP_API_VERSION}"), sql_router);
+        let mut router = Router::new()
+            .nest(&format!("/{HTTP_API_VERSION}"), sql_router)
+            .nest(
+                &format!("/{HTTP_API_VERSION}/sql/export"),
+                self.route_export(ExportState {
+                    sql_handler: self.sql_handler.clone(),
+                    job_manager: self.job_manager.clone(),
+                    export_dir: self.options.export_dir.clone(),
+                    export_s3: self.options.export_s3.clone(),
+                }),
+            )
+            .nest(
+                &format!("/{HTTP_API_VERSION}/jobs"),
+                self.route_job(SqlJobState {
+                    sql_handler: self.sql_handler.clone(),
+                    job_manager: self.job_manager.clone(),
+                }),
+            );
 
         if let Some(opentsdb_handler) = self.opentsdb_handler.clone() {
             router = router.nest(
@@ -442,6 +549,13 @@ impl HttpServer {
             );
         }
 
+        if let Some(procedure_manager) = self.procedure_manager.clone() {
+            router = router.nest(
+                &format!("/{HTTP_API_VERSION}/procedures"),
+                self.route_procedure(procedure_manager),
+            );
+        }
+
         router = router.route("/metrics", routing::get(handler::metrics));
 
         router = router.route(
@@ -482,6 +596,35 @@ impl HttpServer {
             .with_state(api_state)
     }
 
+    fn route_export<S>(&self, export_state: ExportState) -> Router<S> {
+        Router::new()
+            .route("/", routing::post(export::export))
+            .route("/:job_id", routing::get(export::export_status))
+            .with_state(export_state)
+    }
+
+    fn route_job<S>(&self, sql_job_state: SqlJobState) -> Router<S> {
+        Router::new()
+            .route("/", routing::get(job::list_jobs))
+            .route("/:job_id", routing::get(job::job_status))
+            .route("/:job_id/cancel", routing::post(job::cancel_job))
+            .with_state(sql_job_state.job_manager.clone())
+            .merge(
+                Router::new()
+                    .route("/sql", routing::post(job::submit_sql))
+                    .with_state(sql_job_state),
+            )
+    }
+
+    fn route_procedure<S>(&self, procedure_manager: ProcedureManagerRef) -> Router<S> {
+        Router::new()
+            .route(
+                "/:procedure_id/intervene",
+                routing::post(procedure::intervene),
+            )
+            .with_state(procedure_manager)
+    }
+
     fn route_prom<S>(&self, prom_handler: PrometheusProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/write", routing::post(prometheus::remote_write))
@@ -686,4 +829,42 @@ mod test {
             panic!("invalid output type");
         }
     }
+
+    #[tokio::test]
+    async fn test_batch_output() {
+        use common_error::status_code::StatusCode;
+
+        let outputs = || {
+            vec![
+                Ok(Output::AffectedRows(1)),
+                crate::error::InvalidQuerySnafu { reason: "test" }.fail(),
+                Ok(Output::AffectedRows(2)),
+            ]
+        };
+
+        let json_resp = JsonResponse::from_batch_output(outputs(), OnError::Continue).await;
+        assert!(!json_resp.success());
+        assert_eq!(StatusCode::InvalidArguments as u32, json_resp.code());
+        let error = json_resp.error().unwrap();
+        assert!(error.contains("Invalid query: test"), "{error}");
+        let output = json_resp.output().unwrap();
+        assert_eq!(3, output.len());
+        assert_eq!(JsonOutput::AffectedRows(1), output[0]);
+        match &output[1] {
+            JsonOutput::Error(e) => {
+                assert_eq!(StatusCode::InvalidArguments as u32, e.code());
+                assert_eq!(error, e.error());
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(JsonOutput::AffectedRows(2), output[2]);
+
+        let json_resp = JsonResponse::from_batch_output(outputs(), OnError::Stop).await;
+        assert!(!json_resp.success());
+        assert_eq!(2, json_resp.output().unwrap().len());
+
+        let json_resp = JsonResponse::from_output(outputs()).await;
+        assert!(!json_resp.success());
+        assert!(json_resp.output().is_none());
+    }
 }
@@ -132,7 +132,15 @@ async fn authenticate<B: Send + Sync + 'static>(
     let (scheme, credential) = auth_header(request).map_err(|e| IllegalParam {
         msg: format!("failed to get http authorize header, err: {e:?}"),
     })?;
+    authenticate_credential(user_provider, scheme, credential).await
+}
 
+/// Authenticates the user by the `credential` of an authorization header in `scheme`.
+pub(crate) async fn authenticate_credential(
+    user_provider: &UserProviderRef,
+    scheme: AuthScheme,
+    credential: Credential<'_>,
+) -> crate::auth::Result<UserInfo> {
     match scheme {
         AuthScheme::Basic => {
             let (username, password) = decode_basic(credential).map_err(|e| IllegalParam {
@@ -174,7 +182,7 @@ impl TryFrom<&str> for AuthScheme {
     }
 }
 
-type Credential<'a> = &'a str;
+pub(crate) type Credential<'a> = &'a str;
 
 fn auth_header<B>(req: &Request<B>) -> Result<(AuthScheme, Credential)> {
     let auth_header = req
@@ -183,7 +191,11 @@ fn auth_header<B>(req: &Request<B>) -> Result<(AuthScheme, Credential)> {
         .context(error::NotFoundAuthHeaderSnafu)?
         .to_str()
         .context(error::InvisibleASCIISnafu)?;
+    parse_auth_header(auth_header)
+}
 
+/// Parses the value of an authorization header like `Basic <credential>`.
+pub(crate) fn parse_auth_header(auth_header: &str) -> Result<(AuthScheme, Credential)> {
     let (auth_scheme, encoded_credentials) = auth_header
         .split_once(' ')
         .context(error::InvalidAuthorizationHeaderSnafu)?;
@@ -0,0 +1,580 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exports results of queries to object storage asynchronously, so clients don't have to
+//! download huge results through the HTTP API.
+
+use std::path::{Component, Path as FsPath, PathBuf};
+
+use axum::extract::{Json, Path, State};
+use common_error::prelude::ErrorExt;
+use common_procedure::job::{JobContext, JobManagerRef};
+use common_query::Output;
+use common_recordbatch::RecordBatch;
+use common_telemetry::warn;
+use datatypes::value::Value;
+use futures::StreamExt;
+use object_store::services::s3::Builder as S3Builder;
+use object_store::{ObjectMultipart, ObjectPart, ObjectStore};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::{ensure, ResultExt};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::{
+    CollectRecordbatchSnafu, EncodeExportRowsSnafu, InvalidExportTargetSnafu, InvalidQuerySnafu,
+    Result, WriteExportFileSnafu, WriteExportSnafu,
+};
+use crate::http::job::{self, JobResponse};
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Kind of the export jobs.
+pub const EXPORT_JOB_KIND: &str = "export";
+
+/// Size of parts uploaded to the target, S3 requires parts except the last one to be at
+/// least 5 MiB.
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma separated values with a header line.
+    #[default]
+    Csv,
+    /// One JSON object per line.
+    Json,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ExportRequest {
+    pub db: Option<String>,
+    pub sql: String,
+    /// Uri of the object to write, e.g. `s3://bucket/path/result.csv`, or
+    /// `file://path/result.csv` relative to the export dir of the server.
+    pub target: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Credentials and endpoint of the S3 target. Credentials are required, the server
+    /// never exports with its own credentials.
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ExportState {
+    pub sql_handler: ServerSqlQueryHandlerRef,
+    pub job_manager: JobManagerRef,
+    /// Dir to write `file://` targets, exporting to files is disabled if it's not set.
+    pub export_dir: Option<String>,
+    /// S3 targets allowed, exporting to S3 is disabled if it's not set.
+    pub export_s3: Option<ExportS3Options>,
+}
+
+/// S3 buckets and endpoints results could be exported to. The buckets of the database
+/// itself shouldn't be allowed, or users could overwrite its data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportS3Options {
+    pub allowed_buckets: Vec<String>,
+    /// Endpoints the requests could set, requests without an endpoint use the default
+    /// endpoint of AWS S3.
+    pub allowed_endpoints: Vec<String>,
+}
+
+/// Handler to submit a job exporting the query result, returns the job whose status
+/// could be polled through the job APIs.
+#[axum_macros::debug_handler]
+pub async fn export(
+    State(state): State<ExportState>,
+    Json(request): Json<ExportRequest>,
+) -> Json<JobResponse> {
+    let query_ctx =
+        match super::query_context_from_db(state.sql_handler.clone(), request.db.clone()) {
+            Ok(query_ctx) => query_ctx,
+            Err(resp) => return Json(resp.into()),
+        };
+    let target = match ExportTarget::try_new(
+        &request,
+        state.export_dir.as_deref(),
+        state.export_s3.as_ref(),
+    ) {
+        Ok(target) => target,
+        Err(e) => return Json(JobResponse::with_error(e.to_string(), e.status_code())),
+    };
+
+    let description = format!("Export '{}' to {}", request.sql, request.target);
+    let sql_handler = state.sql_handler.clone();
+    let result = state
+        .job_manager
+        .submit(EXPORT_JOB_KIND, &description, move |ctx| async move {
+            let rows = run_export(sql_handler, query_ctx, &request, target, &ctx)
+                .await
+                .map_err(common_procedure::Error::external)?;
+            Ok(format!("{rows} rows exported to {}", request.target))
+        })
+        .await;
+    let resp = match result {
+        Ok(job) => JobResponse::with_job(job),
+        Err(e) => JobResponse::with_error(e.to_string(), e.status_code()),
+    };
+    Json(resp)
+}
+
+/// Handler to get the status of an export job, same as the status API of jobs.
+#[axum_macros::debug_handler]
+pub async fn export_status(
+    State(state): State<ExportState>,
+    Path(job_id): Path<String>,
+) -> Json<JobResponse> {
+    job::job_status(State(state.job_manager), Path(job_id)).await
+}
+
+async fn run_export(
+    sql_handler: ServerSqlQueryHandlerRef,
+    query_ctx: QueryContextRef,
+    request: &ExportRequest,
+    target: ExportTarget,
+    ctx: &JobContext,
+) -> Result<usize> {
+    let mut outputs = sql_handler.do_query(&request.sql, query_ctx).await;
+    ensure!(
+        outputs.len() == 1,
+        InvalidQuerySnafu {
+            reason: "expect exactly one statement to export",
+        }
+    );
+
+    let mut stream = match outputs.remove(0)? {
+        Output::AffectedRows(_) => {
+            return InvalidQuerySnafu {
+                reason: "expect a query returning rows to export",
+            }
+            .fail();
+        }
+        Output::RecordBatches(batches) => batches.as_stream(),
+        Output::Stream(stream) => stream,
+    };
+
+    let mut writer = ExportWriter::try_new(target, &request.target).await?;
+    let mut encoder = RowsEncoder::new(request.format);
+    let result = async {
+        while let Some(batch) = stream.next().await {
+            encoder.encode(&batch.context(CollectRecordbatchSnafu)?)?;
+            if encoder.buf.len() >= PART_SIZE {
+                writer.write(std::mem::take(&mut encoder.buf)).await?;
+            }
+            ctx.set_progress(encoder.rows as u64, None);
+        }
+        writer.write(std::mem::take(&mut encoder.buf)).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => writer.complete().await?,
+        Err(e) => {
+            writer.abort().await;
+            return Err(e);
+        }
+    }
+    Ok(encoder.rows)
+}
+
+enum ExportTarget {
+    /// A file under the export dir of the server.
+    File(PathBuf),
+    /// An object in a S3 bucket.
+    S3 {
+        store: ObjectStore,
+        /// Path of the object in the bucket.
+        path: String,
+    },
+}
+
+impl ExportTarget {
+    fn try_new(
+        request: &ExportRequest,
+        export_dir: Option<&str>,
+        export_s3: Option<&ExportS3Options>,
+    ) -> Result<Self> {
+        let target = &request.target;
+        let invalid = |reason: &str| {
+            InvalidExportTargetSnafu {
+                target,
+                reason: reason.to_string(),
+            }
+            .build()
+        };
+
+        if let Some(path) = target.strip_prefix("file://") {
+            let Some(export_dir) = export_dir else {
+                return Err(invalid("export to files is disabled, no export dir is configured"));
+            };
+            // Files are confined to the export dir, so the path can't be absolute or
+            // contain `..`.
+            let path = FsPath::new(path);
+            ensure!(
+                path.file_name().is_some()
+                    && path
+                        .components()
+                        .all(|component| matches!(component, Component::Normal(_))),
+                InvalidExportTargetSnafu {
+                    target,
+                    reason: "expect the path of a file relative to the export dir",
+                }
+            );
+            Ok(Self::File(FsPath::new(export_dir).join(path)))
+        } else if let Some(bucket_and_path) = target.strip_prefix("s3://") {
+            let Some((bucket, path)) = bucket_and_path.split_once('/') else {
+                return Err(invalid("expect s3://<bucket>/<path>"));
+            };
+            ensure!(
+                !bucket.is_empty() && !path.is_empty() && !path.ends_with('/'),
+                InvalidExportTargetSnafu {
+                    target,
+                    reason: "expect s3://<bucket>/<path>",
+                }
+            );
+
+            let Some(export_s3) = export_s3 else {
+                return Err(invalid("export to S3 is disabled, no S3 targets are allowed"));
+            };
+            ensure!(
+                export_s3
+                    .allowed_buckets
+                    .iter()
+                    .any(|allowed| allowed == bucket),
+                InvalidExportTargetSnafu {
+                    target,
+                    reason: format!("bucket {bucket} is not allowed"),
+                }
+            );
+            if let Some(endpoint) = &request.endpoint {
+                ensure!(
+                    export_s3.allowed_endpoints.contains(endpoint),
+                    InvalidExportTargetSnafu {
+                        target,
+                        reason: format!("endpoint {endpoint} is not allowed"),
+                    }
+                );
+            }
+            let (access_key_id, secret_access_key) =
+                match (&request.access_key_id, &request.secret_access_key) {
+                    (Some(id), Some(secret)) if !id.is_empty() && !secret.is_empty() => {
+                        (id, secret)
+                    }
+                    _ => return Err(invalid("access_key_id and secret_access_key are required")),
+                };
+
+            let mut builder = S3Builder::default();
+            builder
+                .root("/")
+                .bucket(bucket)
+                .access_key_id(access_key_id)
+                .secret_access_key(secret_access_key)
+                // Never falls back to the credentials of the server, e.g. from the env or the
+                // instance profile.
+                .disable_credential_loader();
+            if let Some(region) = &request.region {
+                builder.region(region);
+            }
+            if let Some(endpoint) = &request.endpoint {
+                builder.endpoint(endpoint);
+            }
+            let accessor = builder.build().map_err(|e| invalid(&e.to_string()))?;
+            Ok(Self::S3 {
+                store: ObjectStore::new(accessor),
+                path: path.to_string(),
+            })
+        } else {
+            Err(invalid("only file:// and s3:// targets are supported"))
+        }
+    }
+}
+
+/// Writes the encoded rows to the target as they are encoded, so the whole result is never
+/// buffered in memory.
+enum ExportWriter<'a> {
+    File {
+        file: File,
+        path: PathBuf,
+        target: &'a str,
+    },
+    /// Uploads rows to S3 in parts.
+    S3 {
+        multipart: ObjectMultipart,
+        parts: Vec<ObjectPart>,
+        target: &'a str,
+    },
+}
+
+impl<'a> ExportWriter<'a> {
+    async fn try_new(export_target: ExportTarget, target: &'a str) -> Result<ExportWriter<'a>> {
+        match export_target {
+            ExportTarget::File(path) => {
+                let file = File::create(&path)
+                    .await
+                    .context(WriteExportFileSnafu { target })?;
+                Ok(Self::File { file, path, target })
+            }
+            ExportTarget::S3 { store, path } => {
+                let multipart = store
+                    .object(&path)
+                    .create_multipart()
+                    .await
+                    .context(WriteExportSnafu { target })?;
+                Ok(Self::S3 {
+                    multipart,
+                    parts: Vec::new(),
+                    target,
+                })
+            }
+        }
+    }
+
+    async fn write(&mut self, buf: Vec<u8>) -> Result<()> {
+        match self {
+            Self::File { file, target, .. } => file
+                .write_all(&buf)
+                .await
+                .context(WriteExportFileSnafu { target: *target }),
+            Self::S3 {
+                multipart,
+                parts,
+                target,
+            } => {
+                // An empty result is still uploaded as an empty object.
+                if buf.is_empty() && !parts.is_empty() {
+                    return Ok(());
+                }
+                // Part numbers start from 1.
+                let part = multipart
+                    .write(parts.len() + 1, buf)
+                    .await
+                    .context(WriteExportSnafu { target: *target })?;
+                parts.push(part);
+                Ok(())
+            }
+        }
+    }
+
+    async fn complete(self) -> Result<()> {
+        match self {
+            Self::File { file, target, .. } => file
+                .sync_all()
+                .await
+                .context(WriteExportFileSnafu { target }),
+            Self::S3 {
+                multipart,
+                parts,
+                target,
+            } => multipart
+                .complete(parts)
+                .await
+                .map(|_| ())
+                .context(WriteExportSnafu { target }),
+        }
+    }
+
+    /// Removes the partially written result.
+    async fn abort(self) {
+        let (result, target) = match self {
+            Self::File { file, path, target } => {
+                drop(file);
+                (
+                    tokio::fs::remove_file(&path)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    target,
+                )
+            }
+            Self::S3 {
+                multipart, target, ..
+            } => (multipart.abort().await.map_err(|e| e.to_string()), target),
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to remove the partially exported result {}: {}",
+                target, e
+            );
+        }
+    }
+}
+
+/// Encodes rows of the query result in the export format.
+struct RowsEncoder {
+    format: ExportFormat,
+    buf: Vec<u8>,
+    rows: usize,
+    header_written: bool,
+}
+
+impl RowsEncoder {
+    fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            buf: Vec::new(),
+            rows: 0,
+            header_written: false,
+        }
+    }
+
+    fn encode(&mut self, batch: &RecordBatch) -> Result<()> {
+        let names = batch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>();
+
+        match self.format {
+            ExportFormat::Csv => {
+                if !self.header_written {
+                    let header = names.iter().map(|name| csv_field(name)).collect::<Vec<_>>();
+                    self.write_line(&header.join(","));
+                    self.header_written = true;
+                }
+                for row in batch.rows() {
+                    let fields = row.iter().map(csv_value).collect::<Vec<_>>();
+                    self.write_line(&fields.join(","));
+                }
+            }
+            ExportFormat::Json => {
+                for row in batch.rows() {
+                    let mut object = serde_json::Map::with_capacity(names.len());
+                    for (name, value) in names.iter().zip(row) {
+                        let value =
+                            serde_json::Value::try_from(value).context(EncodeExportRowsSnafu)?;
+                        object.insert(name.to_string(), value);
+                    }
+                    serde_json::to_writer(&mut self.buf, &object).context(EncodeExportRowsSnafu)?;
+                    self.buf.push(b'\n');
+                }
+            }
+        }
+        self.rows += batch.num_rows();
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) {
+        self.buf.extend_from_slice(line.as_bytes());
+        self.buf.push(b'\n');
+    }
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s.as_utf8()),
+        value => csv_field(&value.to_string()),
+    }
+}
+
+/// Quotes the `field` if it contains any special character of CSV.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::status_code::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_csv_value() {
+        assert_eq!("", csv_value(&Value::Null));
+        assert_eq!("42", csv_value(&Value::Int64(42)));
+        assert_eq!("abc", csv_value(&Value::from("abc")));
+        assert_eq!("\"a,b\"", csv_value(&Value::from("a,b")));
+        assert_eq!("\"say \"\"hi\"\"\"", csv_value(&Value::from("say \"hi\"")));
+    }
+
+    #[test]
+    fn test_export_target() {
+        let request = |target: &str| ExportRequest {
+            target: target.to_string(),
+            access_key_id: Some("key".to_string()),
+            secret_access_key: Some("secret".to_string()),
+            region: Some("us-east-1".to_string()),
+            ..Default::default()
+        };
+
+        let export_dir = Some("/data/export");
+        let export_s3 = ExportS3Options {
+            allowed_buckets: vec!["bucket".to_string()],
+            allowed_endpoints: vec!["http://127.0.0.1:9000".to_string()],
+        };
+        let export_s3 = Some(&export_s3);
+        let target =
+            ExportTarget::try_new(&request("s3://bucket/dir/result.csv"), None, export_s3).unwrap();
+        assert!(matches!(target, ExportTarget::S3 { path, .. } if path == "dir/result.csv"));
+        let mut minio_request = request("s3://bucket/result.csv");
+        minio_request.endpoint = Some("http://127.0.0.1:9000".to_string());
+        assert!(ExportTarget::try_new(&minio_request, None, export_s3).is_ok());
+        let target =
+            ExportTarget::try_new(&request("file://dir/result.csv"), export_dir, None).unwrap();
+        let expect = FsPath::new("/data/export/dir/result.csv");
+        assert!(matches!(target, ExportTarget::File(path) if path == expect));
+
+        // S3 targets must be allowed and have explicit credentials.
+        let mut invalid_requests = vec![
+            (request("s3://bucket/result.csv"), None),
+            (request("s3://other/result.csv"), export_s3),
+        ];
+        let mut internal_endpoint = request("s3://bucket/result.csv");
+        internal_endpoint.endpoint = Some("http://169.254.169.254".to_string());
+        invalid_requests.push((internal_endpoint, export_s3));
+        let mut no_secret = request("s3://bucket/result.csv");
+        no_secret.secret_access_key = None;
+        invalid_requests.push((no_secret, export_s3));
+        let mut empty_key = request("s3://bucket/result.csv");
+        empty_key.access_key_id = Some(String::new());
+        invalid_requests.push((empty_key, export_s3));
+        for (request, export_s3) in invalid_requests {
+            let err = ExportTarget::try_new(&request, None, export_s3)
+                .err()
+                .unwrap();
+            assert_eq!(
+                StatusCode::InvalidArguments,
+                err.status_code(),
+                "{request:?}"
+            );
+        }
+
+        for (target, export_dir) in [
+            ("file://result.csv", None),
+            ("file:///tmp/export/result.csv", export_dir),
+            ("file://../result.csv", export_dir),
+            ("file://dir/../../result.csv", export_dir),
+            ("file://", export_dir),
+            ("s3://bucket", export_dir),
+            ("s3://bucket/dir/", export_dir),
+            ("oss://bucket/result.csv", export_dir),
+            ("/tmp/result.csv", export_dir),
+        ] {
+            let err = ExportTarget::try_new(&request(target), export_dir, export_s3)
+                .err()
+                .unwrap();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code(), "{target}");
+        }
+    }
+}
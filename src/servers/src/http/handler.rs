@@ -18,18 +18,29 @@ use std::time::Instant;
 use aide::transform::TransformOperation;
 use axum::extract::{Json, Query, State};
 use axum::Extension;
+use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_telemetry::metric;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use session::context::UserInfo;
 
-use crate::http::{ApiState, JsonResponse};
+use crate::deadline;
+use crate::http::{ApiState, JsonResponse, OnError};
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SqlQuery {
     pub db: Option<String>,
     pub sql: Option<String>,
+    /// Whether to expose the version of rows as the `__version` column of tables,
+    /// for debugging rows overwritten by the same key.
+    pub row_version: Option<bool>,
+    /// Timeout of the query like `30s`, the query is abandoned once it times out.
+    pub timeout: Option<String>,
+    /// Executes the statements as a batch and returns the result of each statement,
+    /// the failed ones included, if set. Also decides whether to execute the rest
+    /// statements after one fails.
+    pub on_error: Option<OnError>,
 }
 
 /// Handler to execute sql
@@ -45,7 +56,28 @@ pub async fn sql(
     let resp = if let Some(sql) = &params.sql {
         match super::query_context_from_db(sql_handler.clone(), params.db) {
             Ok(query_ctx) => {
-                JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
+                query_ctx.set_row_version(params.row_version.unwrap_or(false));
+                let timeout = params.timeout.as_deref().map(|timeout| {
+                    let timeout = deadline::parse_http_timeout(timeout)?;
+                    deadline::set_timeout(&query_ctx, timeout)
+                });
+                match timeout.transpose() {
+                    Ok(_) => {
+                        if let Some(on_error) = params.on_error {
+                            query_ctx.set_continue_on_error(on_error == OnError::Continue);
+                        }
+                        let deadline = query_ctx.deadline();
+                        let outputs = sql_handler.do_query(sql, query_ctx);
+                        let outputs = deadline::run_all_until(deadline, outputs).await;
+                        match params.on_error {
+                            Some(on_error) => {
+                                JsonResponse::from_batch_output(outputs, on_error).await
+                            }
+                            None => JsonResponse::from_output(outputs).await,
+                        }
+                    }
+                    Err(e) => JsonResponse::with_error(e.to_string(), e.status_code()),
+                }
             }
             Err(resp) => resp,
         }
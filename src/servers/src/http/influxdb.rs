@@ -17,7 +17,9 @@ use std::sync::Arc;
 
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
 use session::context::QueryContext;
 
@@ -30,20 +32,31 @@ pub async fn influxdb_write(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
     Query(mut params): Query<HashMap<String, String>>,
     lines: String,
-) -> Result<(StatusCode, ())> {
+) -> Result<Response> {
     let db = params
         .remove("db")
         .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
-    let ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &db));
+    let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(&db);
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
 
     let precision = params
         .get("precision")
         .map(|val| parse_time_precision(val))
         .transpose()?;
+    // In partial mode, the valid lines are written and the failed ones are reported in
+    // the response, so clients could retry only the failed lines.
+    let partial = params
+        .get("partial")
+        .map(|val| val == "true")
+        .unwrap_or(false);
     let request = InfluxdbRequest { precision, lines };
 
+    if partial {
+        let output = handler.exec_partial(&request, ctx).await?;
+        return Ok((StatusCode::OK, Json(output)).into_response());
+    }
     handler.exec(&request, ctx).await?;
-    Ok((StatusCode::NO_CONTENT, ()))
+    Ok(StatusCode::NO_CONTENT.into_response())
 }
 
 fn parse_time_precision(value: &str) -> Result<Precision> {
@@ -0,0 +1,269 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP APIs to track and cancel background jobs, such as exports, and to run maintenance
+//! statements as jobs.
+
+use axum::extract::{Json, Path, State};
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_procedure::job::{JobId, JobManagerRef, JobRecord};
+use common_query::Output;
+use common_recordbatch::util;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::statement::Statement;
+
+use crate::error::{CollectRecordbatchSnafu, InvalidQuerySnafu, Result};
+use crate::http::JsonResponse;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Kind of the jobs running `ANALYZE TABLE`.
+pub const ANALYZE_JOB_KIND: &str = "analyze";
+/// Kind of the jobs running `CHECK TABLE`.
+pub const CHECK_JOB_KIND: &str = "check";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobResponse {
+    code: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job: Option<JobRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jobs: Option<Vec<JobRecord>>,
+}
+
+impl JobResponse {
+    pub(crate) fn with_error(error: String, error_code: StatusCode) -> Self {
+        Self {
+            code: error_code as u32,
+            error: Some(error),
+            job: None,
+            jobs: None,
+        }
+    }
+
+    pub(crate) fn with_job(job: JobRecord) -> Self {
+        Self {
+            code: StatusCode::Success as u32,
+            error: None,
+            job: Some(job),
+            jobs: None,
+        }
+    }
+
+    fn with_jobs(jobs: Vec<JobRecord>) -> Self {
+        Self {
+            code: StatusCode::Success as u32,
+            error: None,
+            job: None,
+            jobs: Some(jobs),
+        }
+    }
+
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == (StatusCode::Success as u32)
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    pub fn job(&self) -> Option<&JobRecord> {
+        self.job.as_ref()
+    }
+
+    pub fn jobs(&self) -> Option<&Vec<JobRecord>> {
+        self.jobs.as_ref()
+    }
+}
+
+impl From<JsonResponse> for JobResponse {
+    fn from(resp: JsonResponse) -> Self {
+        Self {
+            code: resp.code,
+            error: resp.error,
+            job: None,
+            jobs: None,
+        }
+    }
+}
+
+fn parse_job_id(job_id: &str) -> std::result::Result<JobId, JobResponse> {
+    JobId::parse_str(job_id).map_err(|_| {
+        JobResponse::with_error(
+            format!("Invalid job id: {job_id}"),
+            StatusCode::InvalidArguments,
+        )
+    })
+}
+
+/// Handler to list all jobs.
+#[axum_macros::debug_handler]
+pub async fn list_jobs(State(job_manager): State<JobManagerRef>) -> Json<JobResponse> {
+    Json(JobResponse::with_jobs(job_manager.list_jobs()))
+}
+
+/// Handler to get the status and progress of a job.
+#[axum_macros::debug_handler]
+pub async fn job_status(
+    State(job_manager): State<JobManagerRef>,
+    Path(job_id): Path<String>,
+) -> Json<JobResponse> {
+    let job_id = match parse_job_id(&job_id) {
+        Ok(job_id) => job_id,
+        Err(resp) => return Json(resp),
+    };
+    let resp = match job_manager.job(job_id) {
+        Some(job) => JobResponse::with_job(job),
+        None => JobResponse::with_error(
+            format!("Job not found: {job_id}"),
+            StatusCode::InvalidArguments,
+        ),
+    };
+    Json(resp)
+}
+
+/// Handler to cancel a running job.
+#[axum_macros::debug_handler]
+pub async fn cancel_job(
+    State(job_manager): State<JobManagerRef>,
+    Path(job_id): Path<String>,
+) -> Json<JobResponse> {
+    let job_id = match parse_job_id(&job_id) {
+        Ok(job_id) => job_id,
+        Err(resp) => return Json(resp),
+    };
+    let resp = match job_manager.cancel(job_id) {
+        // The record is returned before the job actually stops.
+        Ok(()) => match job_manager.job(job_id) {
+            Some(job) => JobResponse::with_job(job),
+            None => JobResponse::with_error(
+                format!("Job not found: {job_id}"),
+                StatusCode::InvalidArguments,
+            ),
+        },
+        Err(e) => JobResponse::with_error(e.to_string(), e.status_code()),
+    };
+    Json(resp)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SqlJobRequest {
+    pub db: Option<String>,
+    /// The maintenance statement to run, `ANALYZE TABLE` or `CHECK TABLE`.
+    pub sql: String,
+}
+
+#[derive(Clone)]
+pub struct SqlJobState {
+    pub sql_handler: ServerSqlQueryHandlerRef,
+    pub job_manager: JobManagerRef,
+}
+
+/// Handler to submit a job running a maintenance statement, returns the job whose status
+/// and result could be polled through the job APIs.
+#[axum_macros::debug_handler]
+pub async fn submit_sql(
+    State(state): State<SqlJobState>,
+    Json(request): Json<SqlJobRequest>,
+) -> Json<JobResponse> {
+    let query_ctx =
+        match super::query_context_from_db(state.sql_handler.clone(), request.db.clone()) {
+            Ok(query_ctx) => query_ctx,
+            Err(resp) => return Json(resp.into()),
+        };
+    let kind = match sql_job_kind(&request.sql) {
+        Ok(kind) => kind,
+        Err(e) => return Json(JobResponse::with_error(e.to_string(), e.status_code())),
+    };
+
+    let sql_handler = state.sql_handler.clone();
+    let sql = request.sql.clone();
+    let result = state
+        .job_manager
+        .submit(kind, &request.sql, move |_ctx| async move {
+            let mut outputs = sql_handler.do_query(&sql, query_ctx).await;
+            let output = outputs
+                .remove(0)
+                .map_err(common_procedure::Error::external)?;
+            output_message(output)
+                .await
+                .map_err(common_procedure::Error::external)
+        })
+        .await;
+    let resp = match result {
+        Ok(job) => JobResponse::with_job(job),
+        Err(e) => JobResponse::with_error(e.to_string(), e.status_code()),
+    };
+    Json(resp)
+}
+
+/// Returns the kind of the job running `sql`, which must be exactly one maintenance
+/// statement.
+fn sql_job_kind(sql: &str) -> Result<&'static str> {
+    let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).map_err(|e| {
+        InvalidQuerySnafu {
+            reason: e.to_string(),
+        }
+        .build()
+    })?;
+    ensure!(
+        stmts.len() == 1,
+        InvalidQuerySnafu {
+            reason: "expect exactly one statement to run as a job",
+        }
+    );
+    match &stmts[0] {
+        Statement::AnalyzeTable(_) => Ok(ANALYZE_JOB_KIND),
+        Statement::CheckTable(_) => Ok(CHECK_JOB_KIND),
+        _ => InvalidQuerySnafu {
+            reason: "only ANALYZE TABLE and CHECK TABLE could run as jobs",
+        }
+        .fail(),
+    }
+}
+
+/// Describes the result of a finished statement as the message of its job.
+async fn output_message(output: Output) -> Result<String> {
+    let batches = match output {
+        Output::AffectedRows(rows) => return Ok(format!("{rows} rows affected")),
+        Output::RecordBatches(batches) => batches,
+        Output::Stream(stream) => util::collect_batches(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+    };
+    batches.pretty_print().context(CollectRecordbatchSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_job_kind() {
+        assert_eq!(ANALYZE_JOB_KIND, sql_job_kind("ANALYZE TABLE t").unwrap());
+        assert_eq!(CHECK_JOB_KIND, sql_job_kind("CHECK TABLE t").unwrap());
+        assert!(sql_job_kind("SELECT * FROM t").is_err());
+        assert!(sql_job_kind("CHECK TABLE t; CHECK TABLE t2").is_err());
+    }
+}
@@ -17,6 +17,7 @@ use std::collections::HashMap;
 use axum::extract::{Query, RawBody, State};
 use axum::http::StatusCode as HttpStatusCode;
 use axum::Json;
+use common_error::prelude::ErrorExt;
 use hyper::Body;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
@@ -134,6 +135,8 @@ async fn parse_data_points(body: Body) -> Result<Vec<DataPointRequest>> {
 #[derive(Serialize, Deserialize, Debug)]
 struct OpentsdbDetailError {
     datapoint: DataPointRequest,
+    /// The numeric status code of the error.
+    code: u32,
     error: String,
 }
 
@@ -156,6 +159,7 @@ impl OpentsdbDebuggingResponse {
         if let Some(details) = self.errors.as_mut() {
             let error = OpentsdbDetailError {
                 datapoint,
+                code: error.status_code() as u32,
                 error: error.to_string(),
             };
             details.push(error);
@@ -0,0 +1,99 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP APIs for operators to intervene in procedures that can't make progress by
+//! themselves, see [Intervention].
+
+use axum::extract::{Json, Path, Query, State};
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_procedure::{Intervention, ProcedureId, ProcedureManagerRef};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcedureResponse {
+    code: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ProcedureResponse {
+    fn ok() -> Self {
+        Self {
+            code: StatusCode::Success as u32,
+            error: None,
+        }
+    }
+
+    fn with_error(error: String, error_code: StatusCode) -> Self {
+        Self {
+            code: error_code as u32,
+            error: Some(error),
+        }
+    }
+
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == (StatusCode::Success as u32)
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InterventionQuery {
+    /// The intervention to apply, `abort`, `retry-now` or `skip-step`.
+    pub action: String,
+}
+
+/// Handler to apply a manual intervention to a procedure.
+#[axum_macros::debug_handler]
+pub async fn intervene(
+    State(procedure_manager): State<ProcedureManagerRef>,
+    Path(procedure_id): Path<String>,
+    Query(query): Query<InterventionQuery>,
+) -> Json<ProcedureResponse> {
+    let procedure_id = match ProcedureId::parse_str(&procedure_id) {
+        Ok(procedure_id) => procedure_id,
+        Err(_) => {
+            return Json(ProcedureResponse::with_error(
+                format!("Invalid procedure id: {procedure_id}"),
+                StatusCode::InvalidArguments,
+            ))
+        }
+    };
+    let intervention = match query.action.parse::<Intervention>() {
+        Ok(intervention) => intervention,
+        Err(e) => {
+            return Json(ProcedureResponse::with_error(
+                e.to_string(),
+                StatusCode::InvalidArguments,
+            ))
+        }
+    };
+
+    let resp = match procedure_manager
+        .intervene(procedure_id, intervention)
+        .await
+    {
+        Ok(()) => ProcedureResponse::ok(),
+        Err(e) => ProcedureResponse::with_error(e.to_string(), e.status_code()),
+    };
+    Json(resp)
+}
@@ -18,12 +18,12 @@ use api::prometheus::remote::{ReadRequest, WriteRequest};
 use axum::extract::{Query, RawBody, State};
 use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use hyper::Body;
 use prost::Message;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use session::context::QueryContext;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
@@ -50,12 +50,7 @@ pub async fn remote_write(
     RawBody(body): RawBody,
 ) -> Result<(StatusCode, ())> {
     let request = decode_remote_write_request(body).await?;
-
-    let ctx = if let Some(db) = params.db {
-        Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &db))
-    } else {
-        QueryContext::arc()
-    };
+    let ctx = query_context_of(params);
 
     handler.write(request, ctx).await?;
     Ok((StatusCode::NO_CONTENT, ()))
@@ -81,16 +76,23 @@ pub async fn remote_read(
     RawBody(body): RawBody,
 ) -> Result<PrometheusResponse> {
     let request = decode_remote_read_request(body).await?;
-
-    let ctx = if let Some(db) = params.db {
-        Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, &db))
-    } else {
-        QueryContext::arc()
-    };
+    let ctx = query_context_of(params);
 
     handler.read(request, ctx).await
 }
 
+/// Returns the context to query the database in `params`, the database name may be
+/// prefixed by the catalog name, e.g. `catalog-schema`.
+fn query_context_of(params: DatabaseQuery) -> QueryContextRef {
+    match params.db {
+        Some(db) => {
+            let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(&db);
+            Arc::new(QueryContext::with(catalog, schema))
+        }
+        None => QueryContext::arc(),
+    }
+}
+
 async fn decode_remote_write_request(body: Body) -> Result<WriteRequest> {
     let body = hyper::body::to_bytes(body)
         .await
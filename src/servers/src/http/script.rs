@@ -15,11 +15,13 @@
 use std::time::Instant;
 
 use axum::extract::{Json, Query, RawBody, State};
+use axum::Extension;
 use common_error::ext::ErrorExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use session::context::{Channel, QueryContext, UserInfo};
 
-use crate::http::{ApiState, JsonResponse};
+use crate::http::{ApiState, JsonResponse, OnError};
 
 macro_rules! json_err {
     ($e: expr) => {{
@@ -73,7 +75,12 @@ pub async fn scripts(
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ScriptQuery {
+    /// Name of the script, or names of the scripts separated by commas to run them in
+    /// order as a batch.
     pub name: Option<String>,
+    /// Returns the result of each script, the failed ones included, if set. Also decides
+    /// whether to run the rest scripts after one fails.
+    pub on_error: Option<OnError>,
 }
 
 /// Handler to execute script
@@ -81,19 +88,39 @@ pub struct ScriptQuery {
 pub async fn run_script(
     State(state): State<ApiState>,
     Query(params): Query<ScriptQuery>,
+    Extension(user_info): Extension<UserInfo>,
 ) -> Json<JsonResponse> {
     if let Some(script_handler) = &state.script_handler {
         let start = Instant::now();
-        let name = params.name.as_ref();
+        let names = params
+            .name
+            .as_deref()
+            .map(|name| name.split(',').map(str::trim).collect::<Vec<_>>())
+            .unwrap_or_default();
 
-        if name.is_none() || name.unwrap().is_empty() {
+        if names.is_empty() || names.iter().any(|name| name.is_empty()) {
             json_err!("invalid name");
         }
 
-        // TODO(sunng87): query_context and db name resolution
-
-        let output = script_handler.execute_script(name.unwrap()).await;
-        let resp = JsonResponse::from_output(vec![output]).await;
+        // TODO(sunng87): db name resolution
+        // Scripts read and write tables on behalf of the user.
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_channel(Channel::Http);
+        query_ctx.set_current_user(user_info);
+
+        let mut outputs = Vec::with_capacity(names.len());
+        for name in names {
+            let output = script_handler.execute_script(name, query_ctx.clone()).await;
+            let failed = output.is_err();
+            outputs.push(output);
+            if failed && params.on_error != Some(OnError::Continue) {
+                break;
+            }
+        }
+        let resp = match params.on_error {
+            Some(on_error) => JsonResponse::from_batch_output(outputs, on_error).await,
+            None => JsonResponse::from_output(outputs).await,
+        };
 
         Json(resp.with_execution_time(start.elapsed().as_millis()))
     } else {
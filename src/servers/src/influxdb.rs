@@ -16,10 +16,11 @@ use std::collections::HashMap;
 
 use api::v1::InsertRequest as GrpcInsertRequest;
 use common_grpc::writer::{LinesWriter, Precision};
-use influxdb_line_protocol::{parse_lines, FieldValue};
+use influxdb_line_protocol::{parse_lines, FieldValue, ParsedLine};
 use snafu::ResultExt;
 
 use crate::error::{Error, InfluxdbLineProtocolSnafu, InfluxdbLinesWriteSnafu};
+use crate::query_handler::WriteFailure;
 
 pub const INFLUXDB_TIMESTAMP_COLUMN_NAME: &str = "ts";
 pub const DEFAULT_TIME_PRECISION: Precision = Precision::Nanosecond;
@@ -43,78 +44,160 @@ impl TryFrom<&InfluxdbRequest> for Vec<GrpcInsertRequest> {
         let line_len = lines.len();
 
         for line in lines {
-            let table_name = line.series.measurement;
+            let table_name = line.series.measurement.to_string();
             let writer = writers
-                .entry(table_name.to_string())
+                .entry(table_name)
                 .or_insert_with(|| LinesWriter::with_lines(line_len));
+            write_line(writer, line, value.precision)?;
+        }
+
+        Ok(writers
+            .into_iter()
+            .map(|(table_name, writer)| into_insert_request(table_name, writer))
+            .collect())
+    }
+}
 
-            let tags = line.series.tag_set;
-            if let Some(tags) = tags {
-                for (k, v) in tags {
-                    writer
-                        .write_tag(k.as_str(), v.as_str())
-                        .context(InfluxdbLinesWriteSnafu)?;
+/// Insert request of a table, converted from some lines of an [InfluxdbRequest].
+#[derive(Debug)]
+pub struct InfluxdbTableInsert {
+    /// Indices of the lines written by the request.
+    pub line_indices: Vec<usize>,
+    pub request: GrpcInsertRequest,
+}
+
+/// An [InfluxdbRequest] converted for partial writes.
+#[derive(Debug, Default)]
+pub struct InfluxdbPartialInserts {
+    pub inserts: Vec<InfluxdbTableInsert>,
+    /// Lines failed to parse or convert.
+    pub failures: Vec<WriteFailure>,
+}
+
+impl InfluxdbRequest {
+    /// Converts the request into insert requests of each table. Unlike the conversion
+    /// into `Vec<GrpcInsertRequest>`, the lines failed to parse are reported instead of
+    /// failing the whole request. If lines of a table could not be converted (e.g. the
+    /// types of a field mismatch), all lines of the table are reported.
+    ///
+    /// Indices of lines are 0-based and count the empty lines and comments.
+    pub fn to_partial_inserts(&self) -> InfluxdbPartialInserts {
+        let mut partial = InfluxdbPartialInserts::default();
+        let mut table_lines: Vec<(TableName, Vec<(usize, ParsedLine)>)> = Vec::new();
+        let mut table_indices: HashMap<TableName, usize> = HashMap::new();
+
+        for (index, line) in self.lines.lines().enumerate() {
+            let line = match parse_lines(line).next() {
+                None => continue,
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    let error = Error::InfluxdbLineProtocol { source: e };
+                    partial.failures.push(WriteFailure::new(index, &error));
+                    continue;
                 }
-            }
+            };
+            let table_name = line.series.measurement.to_string();
+            let table_index = *table_indices.entry(table_name.clone()).or_insert_with(|| {
+                table_lines.push((table_name, Vec::new()));
+                table_lines.len() - 1
+            });
+            table_lines[table_index].1.push((index, line));
+        }
 
-            let fields = line.field_set;
-            for (k, v) in fields {
-                let column_name = k.as_str();
-                match v {
-                    FieldValue::I64(value) => {
-                        writer
-                            .write_i64(column_name, value)
-                            .context(InfluxdbLinesWriteSnafu)?;
-                    }
-                    FieldValue::U64(value) => {
-                        writer
-                            .write_u64(column_name, value)
-                            .context(InfluxdbLinesWriteSnafu)?;
-                    }
-                    FieldValue::F64(value) => {
-                        writer
-                            .write_f64(column_name, value)
-                            .context(InfluxdbLinesWriteSnafu)?;
-                    }
-                    FieldValue::String(value) => {
-                        writer
-                            .write_string(column_name, value.as_str())
-                            .context(InfluxdbLinesWriteSnafu)?;
-                    }
-                    FieldValue::Boolean(value) => {
-                        writer
-                            .write_bool(column_name, value)
-                            .context(InfluxdbLinesWriteSnafu)?;
-                    }
+        for (table_name, lines) in table_lines {
+            let mut writer = LinesWriter::with_lines(lines.len());
+            let mut line_indices = Vec::with_capacity(lines.len());
+            let mut result = Ok(());
+            for (index, line) in lines {
+                line_indices.push(index);
+                if result.is_ok() {
+                    result = write_line(&mut writer, line, self.precision);
                 }
             }
 
-            if let Some(timestamp) = line.timestamp {
-                let precision = if let Some(val) = &value.precision {
-                    *val
-                } else {
-                    DEFAULT_TIME_PRECISION
-                };
+            match result {
+                Ok(()) => partial.inserts.push(InfluxdbTableInsert {
+                    line_indices,
+                    request: into_insert_request(table_name, writer),
+                }),
+                Err(e) => partial.failures.extend(
+                    line_indices
+                        .into_iter()
+                        .map(|index| WriteFailure::new(index, &e)),
+                ),
+            }
+        }
+        partial.failures.sort_by_key(|failure| failure.index);
+
+        partial
+    }
+}
+
+fn write_line(
+    writer: &mut LinesWriter,
+    line: ParsedLine,
+    precision: Option<Precision>,
+) -> Result<(), Error> {
+    let tags = line.series.tag_set;
+    if let Some(tags) = tags {
+        for (k, v) in tags {
+            writer
+                .write_tag(k.as_str(), v.as_str())
+                .context(InfluxdbLinesWriteSnafu)?;
+        }
+    }
+
+    let fields = line.field_set;
+    for (k, v) in fields {
+        let column_name = k.as_str();
+        match v {
+            FieldValue::I64(value) => {
                 writer
-                    .write_ts(INFLUXDB_TIMESTAMP_COLUMN_NAME, (timestamp, precision))
+                    .write_i64(column_name, value)
+                    .context(InfluxdbLinesWriteSnafu)?;
+            }
+            FieldValue::U64(value) => {
+                writer
+                    .write_u64(column_name, value)
+                    .context(InfluxdbLinesWriteSnafu)?;
+            }
+            FieldValue::F64(value) => {
+                writer
+                    .write_f64(column_name, value)
+                    .context(InfluxdbLinesWriteSnafu)?;
+            }
+            FieldValue::String(value) => {
+                writer
+                    .write_string(column_name, value.as_str())
+                    .context(InfluxdbLinesWriteSnafu)?;
+            }
+            FieldValue::Boolean(value) => {
+                writer
+                    .write_bool(column_name, value)
                     .context(InfluxdbLinesWriteSnafu)?;
             }
-
-            writer.commit();
         }
+    }
 
-        Ok(writers
-            .into_iter()
-            .map(|(table_name, writer)| {
-                let (columns, row_count) = writer.finish();
-                GrpcInsertRequest {
-                    table_name,
-                    region_number: 0,
-                    columns,
-                    row_count,
-                }
-            })
-            .collect())
+    if let Some(timestamp) = line.timestamp {
+        let precision = precision.unwrap_or(DEFAULT_TIME_PRECISION);
+        writer
+            .write_ts(INFLUXDB_TIMESTAMP_COLUMN_NAME, (timestamp, precision))
+            .context(InfluxdbLinesWriteSnafu)?;
+    }
+
+    writer.commit();
+    Ok(())
+}
+
+fn into_insert_request(table_name: TableName, writer: LinesWriter) -> GrpcInsertRequest {
+    let (columns, row_count) = writer.finish();
+    GrpcInsertRequest {
+        table_name,
+        region_number: 0,
+        columns,
+        row_count,
+        ..Default::default()
     }
 }
 
@@ -123,10 +206,51 @@ mod tests {
     use api::v1::column::{SemanticType, Values};
     use api::v1::{Column, ColumnDataType};
     use common_base::BitVec;
+    use common_error::prelude::StatusCode;
 
     use super::*;
     use crate::influxdb::InfluxdbRequest;
 
+    #[test]
+    fn test_to_partial_inserts() {
+        let lines = r"monitor1,host=host1 cpu=66.6 1663840496100023100
+monitor1,host=host2 cpu=
+monitor2,host=host3 cpu=66.5 1663840496100023102
+
+monitor2,host=host4 cpu=1i 1663840496400340003
+monitor3,host=host5 cpu=66.3 1663840496400340003";
+        let influxdb_req = InfluxdbRequest {
+            precision: None,
+            lines: lines.to_string(),
+        };
+
+        let partial = influxdb_req.to_partial_inserts();
+        let inserts = partial
+            .inserts
+            .iter()
+            .map(|insert| {
+                (
+                    insert.request.table_name.as_str(),
+                    insert.line_indices.clone(),
+                    insert.request.row_count,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("monitor1", vec![0], 1), ("monitor3", vec![5], 1)],
+            inserts
+        );
+
+        // Line 1 is malformed, and the types of cpu in lines of monitor2 mismatch.
+        let failures = partial
+            .failures
+            .iter()
+            .map(|failure| (failure.index, failure.code))
+            .collect::<Vec<_>>();
+        let code = StatusCode::InvalidArguments as u32;
+        assert_eq!(vec![(1, code), (2, code), (4, code)], failures);
+    }
+
     #[test]
     fn test_convert_influxdb_lines() {
         let lines = r"
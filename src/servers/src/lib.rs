@@ -19,6 +19,8 @@ use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use serde::{Deserialize, Serialize};
 
 pub mod auth;
+pub mod cursor;
+pub mod deadline;
 pub mod error;
 pub mod grpc;
 pub mod http;
@@ -15,7 +15,7 @@
 //! Use regex to filter out some MySQL federated components' emitted statements.
 //! Inspired by Databend's "[mysql_federated.rs](https://github.com/datafuselabs/databend/blob/ac706bf65845e6895141c96c0a10bad6fdc2d367/src/query/service/src/servers/mysql/mysql_federated.rs)".
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use common_query::Output;
@@ -34,12 +34,15 @@ const MYSQL_VERSION: &str = "8.0.26";
 static SELECT_VAR_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new("(?i)^(SELECT @@(.*))").unwrap());
 static MYSQL_CONN_JAVA_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new("(?i)^(/\\* mysql-connector-j(.*))").unwrap());
-static SHOW_LOWER_CASE_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new("(?i)^(SHOW VARIABLES LIKE 'lower_case_table_names'(.*))").unwrap());
 static SHOW_COLLATION_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new("(?i)^(show collation where(.*))").unwrap());
-static SHOW_VARIABLES_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new("(?i)^(SHOW VARIABLES(.*))").unwrap());
+// SHOW [GLOBAL | SESSION] VARIABLES [LIKE 'pattern' | WHERE expr]
+static SHOW_VARIABLES_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)^\s*SHOW\s+(?:GLOBAL\s+|SESSION\s+)?VARIABLES(?:\s+LIKE\s+'([^']*)'|\s+WHERE\s+(.*))?\s*;?\s*$").unwrap()
+});
+// Variable names in `WHERE Variable_name = 'xx' OR Variable_name = 'yy'`.
+static VARIABLE_NAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)Variable_name\s*=\s*'([^']*)'").unwrap());
 
 static SELECT_VERSION_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^(SELECT VERSION\(\s*\))").unwrap());
@@ -50,10 +53,6 @@ static SELECT_DATABASE_PATTERN: Lazy<Regex> =
 static SELECT_TIME_DIFF_FUNC_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new("(?i)^(SELECT TIMEDIFF\\(NOW\\(\\), UTC_TIMESTAMP\\(\\)\\))").unwrap());
 
-// sqlalchemy < 1.4.30
-static SHOW_SQL_MODE_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new("(?i)^(SHOW VARIABLES LIKE 'sql_mode'(.*))").unwrap());
-
 static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
     RegexSet::new([
         // Txn.
@@ -63,7 +62,13 @@ static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
 
         // Set.
         "(?i)^(SET NAMES(.*))",
+        "(?i)^(SET CHARACTER SET(.*))",
+        "(?i)^(SET character_set_client(.*))",
+        "(?i)^(SET character_set_connection(.*))",
         "(?i)^(SET character_set_results(.*))",
+        "(?i)^(SET collation_connection(.*))",
+        "(?i)^(SET time_zone(.*))",
+        "(?i)^(SET TRANSACTION(.*))",
         "(?i)^(SET net_write_timeout(.*))",
         "(?i)^(SET FOREIGN_KEY_CHECKS(.*))",
         "(?i)^(SET AUTOCOMMIT(.*))",
@@ -117,23 +122,66 @@ static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
     ]).unwrap()
 });
 
-static VAR_VALUES: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
-    HashMap::from([
-        ("tx_isolation", "REPEATABLE-READ"),
-        ("session.tx_isolation", "REPEATABLE-READ"),
-        ("transaction_isolation", "REPEATABLE-READ"),
-        ("session.transaction_isolation", "REPEATABLE-READ"),
-        ("session.transaction_read_only", "0"),
-        ("time_zone", "UTC"),
-        ("system_time_zone", "UTC"),
-        ("max_allowed_packet", "134217728"),
+// Values of the system variables clients read on connect, by `SELECT @@var` or
+// `SHOW VARIABLES`. Names are without the `GLOBAL.` or `SESSION.` scope.
+static VAR_VALUES: Lazy<BTreeMap<&str, &str>> = Lazy::new(|| {
+    BTreeMap::from([
+        ("autocommit", "1"),
+        ("character_set_client", "utf8mb4"),
+        ("character_set_connection", "utf8mb4"),
+        ("character_set_database", "utf8mb4"),
+        ("character_set_results", "utf8mb4"),
+        ("character_set_server", "utf8mb4"),
+        ("collation_connection", "utf8mb4_general_ci"),
+        ("collation_database", "utf8mb4_general_ci"),
+        ("collation_server", "utf8mb4_general_ci"),
         ("interactive_timeout", "31536000"),
-        ("wait_timeout", "31536000"),
+        ("lower_case_table_names", "0"),
+        ("max_allowed_packet", "134217728"),
         ("net_write_timeout", "31536000"),
+        ("sql_mode", "ONLY_FULL_GROUP_BY STRICT_TRANS_TABLES NO_ZERO_IN_DATE NO_ZERO_DATE ERROR_FOR_DIVISION_BY_ZERO NO_ENGINE_SUBSTITUTION"),
+        ("system_time_zone", "UTC"),
+        ("time_zone", "UTC"),
+        ("transaction_isolation", "REPEATABLE-READ"),
+        ("transaction_read_only", "0"),
+        ("tx_isolation", "REPEATABLE-READ"),
+        ("tx_read_only", "0"),
+        ("version", MYSQL_VERSION),
         ("version_comment", "Greptime"),
+        ("wait_timeout", "31536000"),
     ])
 });
 
+/// Returns the value of the system variable `name`, which may be prefixed by its scope
+/// like `session.autocommit`.
+fn variable_value(name: &str) -> Option<&'static str> {
+    let name = ["global.", "session.", "local."]
+        .iter()
+        .find_map(|scope| name.strip_prefix(scope))
+        .unwrap_or(name);
+    VAR_VALUES.get(name).copied()
+}
+
+/// Matches `s` against the SQL LIKE `pattern` case-insensitively, `\` escapes the
+/// following `%` or `_`.
+fn like_match(pattern: &str, s: &str) -> bool {
+    fn matches(pattern: &[char], s: &[char]) -> bool {
+        match pattern.split_first() {
+            None => s.is_empty(),
+            Some((&'%', rest)) => (0..=s.len()).any(|i| matches(rest, &s[i..])),
+            Some((&'_', rest)) => !s.is_empty() && matches(rest, &s[1..]),
+            Some((&'\\', rest)) if !rest.is_empty() => {
+                s.first() == rest.first() && matches(&rest[1..], &s[1..])
+            }
+            Some((c, rest)) => s.first() == Some(c) && matches(rest, &s[1..]),
+        }
+    }
+
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let s = s.to_lowercase().chars().collect::<Vec<_>>();
+    matches(&pattern, &s)
+}
+
 // Recordbatches for select function.
 // Format:
 // |function_name|
@@ -154,14 +202,15 @@ fn select_function(name: &str, value: &str) -> RecordBatches {
 // Format is:
 // | Variable_name | Value |
 // | xx            | yy    |
-fn show_variables(name: &str, value: &str) -> RecordBatches {
+fn show_variables(variables: Vec<(&str, &str)>) -> RecordBatches {
     let schema = Arc::new(Schema::new(vec![
         ColumnSchema::new("Variable_name", ConcreteDataType::string_datatype(), true),
         ColumnSchema::new("Value", ConcreteDataType::string_datatype(), true),
     ]));
+    let (names, values): (Vec<_>, Vec<_>) = variables.into_iter().unzip();
     let columns = vec![
-        Arc::new(StringVector::from(vec![name])) as _,
-        Arc::new(StringVector::from(vec![value])) as _,
+        Arc::new(StringVector::from(names)) as _,
+        Arc::new(StringVector::from(values)) as _,
     ];
     RecordBatches::try_from_columns(schema, columns)
         // unwrap is safe because the schema and data are definitely able to form a recordbatch, they are all string type
@@ -194,8 +243,8 @@ fn select_variable(query: &str) -> Option<Output> {
         match var_as.len() {
             1 => {
                 // @@aa
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0]).unwrap_or("0");
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is '@@aa'
                 fields.push(ColumnSchema::new(
@@ -207,8 +256,8 @@ fn select_variable(query: &str) -> Option<Output> {
             2 => {
                 // @@bb as cc:
                 // var is 'bb'.
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0]).unwrap_or("0");
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is 'cc'.
                 fields.push(ColumnSchema::new(
@@ -239,12 +288,27 @@ fn check_select_variable(query: &str) -> Option<Output> {
 }
 
 fn check_show_variables(query: &str) -> Option<Output> {
-    let recordbatches = if SHOW_SQL_MODE_PATTERN.is_match(query) {
-        Some(show_variables("sql_mode", "ONLY_FULL_GROUP_BY STRICT_TRANS_TABLES NO_ZERO_IN_DATE NO_ZERO_DATE ERROR_FOR_DIVISION_BY_ZERO NO_ENGINE_SUBSTITUTION"))
-    } else if SHOW_LOWER_CASE_PATTERN.is_match(query) {
-        Some(show_variables("lower_case_table_names", "0"))
-    } else if SHOW_COLLATION_PATTERN.is_match(query) || SHOW_VARIABLES_PATTERN.is_match(query) {
-        Some(show_variables("", ""))
+    let recordbatches = if let Some(captures) = SHOW_VARIABLES_PATTERN.captures(query) {
+        let variables = VAR_VALUES.iter().map(|(name, value)| (*name, *value));
+        let variables = if let Some(pattern) = captures.get(1) {
+            variables
+                .filter(|(name, _)| like_match(pattern.as_str(), name))
+                .collect()
+        } else if let Some(filter) = captures.get(2) {
+            // Only `Variable_name = 'xx'` conditions are supported in WHERE.
+            let names = VARIABLE_NAME_PATTERN
+                .captures_iter(filter.as_str())
+                .map(|c| c[1].to_lowercase())
+                .collect::<Vec<_>>();
+            variables
+                .filter(|(name, _)| names.iter().any(|n| n == name))
+                .collect()
+        } else {
+            variables.collect()
+        };
+        Some(show_variables(variables))
+    } else if SHOW_COLLATION_PATTERN.is_match(query) {
+        Some(show_variables(vec![("", "")]))
     } else {
         None
     };
@@ -298,6 +362,19 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("sql_mode", "sql_mode"));
+        assert!(like_match("SQL_MODE", "sql_mode"));
+        assert!(like_match("character_set_%", "character_set_client"));
+        assert!(like_match("%time_zone", "system_time_zone"));
+        assert!(like_match("tx_isolatio_", "tx_isolation"));
+        assert!(like_match("gtid\\_mode", "gtid_mode"));
+        assert!(!like_match("gtid\\_mode", "gtid-mode"));
+        assert!(!like_match("version", "version_comment"));
+        assert!(!like_match("_", ""));
+    }
+
     #[test]
     fn test_check() {
         let query = "select 1";
@@ -327,6 +404,15 @@ mod test {
 +-----------+";
         test(query, expected);
 
+        let query = "select @@version, @@session.autocommit";
+        let expected = "\
++-----------+----------------------+
+| @@version | @@session.autocommit |
++-----------+----------------------+
+| 8.0.26    | 1                    |
++-----------+----------------------+";
+        test(query, expected);
+
         let query = "SELECT @@version_comment LIMIT 1";
         let expected = "\
 +-------------------+
@@ -349,22 +435,51 @@ mod test {
         // complex variables
         let query = "/* mysql-connector-java-8.0.17 (Revision: 16a712ddb3f826a1933ab42b0039f7fb9eebc6ec) */SELECT  @@session.auto_increment_increment AS auto_increment_increment, @@character_set_client AS character_set_client, @@character_set_connection AS character_set_connection, @@character_set_results AS character_set_results, @@character_set_server AS character_set_server, @@collation_server AS collation_server, @@collation_connection AS collation_connection, @@init_connect AS init_connect, @@interactive_timeout AS interactive_timeout, @@license AS license, @@lower_case_table_names AS lower_case_table_names, @@max_allowed_packet AS max_allowed_packet, @@net_write_timeout AS net_write_timeout, @@performance_schema AS performance_schema, @@sql_mode AS sql_mode, @@system_time_zone AS system_time_zone, @@time_zone AS time_zone, @@transaction_isolation AS transaction_isolation, @@wait_timeout AS wait_timeout;";
         let expected = "\
-+--------------------------+----------------------+--------------------------+-----------------------+----------------------+------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+----------+------------------+-----------+-----------------------+---------------+
-| auto_increment_increment | character_set_client | character_set_connection | character_set_results | character_set_server | collation_server | collation_connection | init_connect | interactive_timeout | license | lower_case_table_names | max_allowed_packet | net_write_timeout | performance_schema | sql_mode | system_time_zone | time_zone | transaction_isolation | wait_timeout; |
-+--------------------------+----------------------+--------------------------+-----------------------+----------------------+------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+----------+------------------+-----------+-----------------------+---------------+
-| 0                        | 0                    | 0                        | 0                     | 0                    | 0                | 0                    | 0            | 31536000            | 0       | 0                      | 134217728          | 31536000          | 0                  | 0        | UTC              | UTC       | REPEATABLE-READ       | 31536000      |
-+--------------------------+----------------------+--------------------------+-----------------------+----------------------+------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+----------+------------------+-----------+-----------------------+---------------+";
++--------------------------+----------------------+--------------------------+-----------------------+----------------------+--------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+-----------------------------------------------------------------------------------------------------------------------+------------------+-----------+-----------------------+---------------+
+| auto_increment_increment | character_set_client | character_set_connection | character_set_results | character_set_server | collation_server   | collation_connection | init_connect | interactive_timeout | license | lower_case_table_names | max_allowed_packet | net_write_timeout | performance_schema | sql_mode                                                                                                              | system_time_zone | time_zone | transaction_isolation | wait_timeout; |
++--------------------------+----------------------+--------------------------+-----------------------+----------------------+--------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+-----------------------------------------------------------------------------------------------------------------------+------------------+-----------+-----------------------+---------------+
+| 0                        | utf8mb4              | utf8mb4                  | utf8mb4               | utf8mb4              | utf8mb4_general_ci | utf8mb4_general_ci   | 0            | 31536000            | 0       | 0                      | 134217728          | 31536000          | 0                  | ONLY_FULL_GROUP_BY STRICT_TRANS_TABLES NO_ZERO_IN_DATE NO_ZERO_DATE ERROR_FOR_DIVISION_BY_ZERO NO_ENGINE_SUBSTITUTION | UTC              | UTC       | REPEATABLE-READ       | 31536000      |
++--------------------------+----------------------+--------------------------+-----------------------+----------------------+--------------------+----------------------+--------------+---------------------+---------+------------------------+--------------------+-------------------+--------------------+-----------------------------------------------------------------------------------------------------------------------+------------------+-----------+-----------------------+---------------+";
         test(query, expected);
 
-        let query = "show variables";
+        let query = "show variables like 'character_set_c%'";
+        let expected = "\
++--------------------------+---------+
+| Variable_name            | Value   |
++--------------------------+---------+
+| character_set_client     | utf8mb4 |
+| character_set_connection | utf8mb4 |
++--------------------------+---------+";
+        test(query, expected);
+
+        let query = "SHOW SESSION VARIABLES WHERE Variable_name = 'autocommit' OR Variable_name = 'version'";
+        let expected = "\
++---------------+--------+
+| Variable_name | Value  |
++---------------+--------+
+| autocommit    | 1      |
+| version       | 8.0.26 |
++---------------+--------+";
+        test(query, expected);
+
+        // mysqldump checks GTID mode, which is unknown.
+        let query = "SHOW VARIABLES LIKE 'gtid\\_mode'";
         let expected = "\
 +---------------+-------+
 | Variable_name | Value |
 +---------------+-------+
-|               |       |
 +---------------+-------+";
         test(query, expected);
 
+        let output = check("show variables", Arc::new(QueryContext::new()));
+        match output.unwrap() {
+            Output::RecordBatches(r) => assert_eq!(
+                VAR_VALUES.len(),
+                r.iter().map(|b| b.num_rows()).sum::<usize>()
+            ),
+            _ => unreachable!(),
+        }
+
         let query = "show variables like 'lower_case_table_names'";
         let expected = "\
 +------------------------+-------+
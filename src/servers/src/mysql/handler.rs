@@ -29,6 +29,7 @@ use snafu::ensure;
 use tokio::io::AsyncWrite;
 
 use crate::auth::{Identity, Password, UserProviderRef};
+use crate::cursor::{CursorStatement, Cursors};
 use crate::error::{self, Result};
 use crate::mysql::writer::MysqlResultWriter;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
@@ -39,6 +40,7 @@ pub struct MysqlInstanceShim {
     salt: [u8; 20],
     session: Arc<Session>,
     user_provider: Option<UserProviderRef>,
+    cursors: Cursors,
 }
 
 impl MysqlInstanceShim {
@@ -65,6 +67,7 @@ impl MysqlInstanceShim {
             salt: scramble,
             session: Arc::new(Session::new(client_addr, Channel::Mysql)),
             user_provider,
+            cursors: Cursors::default(),
         }
     }
 
@@ -78,6 +81,12 @@ impl MysqlInstanceShim {
         let output =
             if let Some(output) = crate::mysql::federated::check(query, self.session.context()) {
                 vec![Ok(output)]
+            } else if let Some(statement) = CursorStatement::parse(query) {
+                let output = self
+                    .cursors
+                    .execute(statement, &self.query_handler, self.session.context())
+                    .await;
+                vec![output]
             } else {
                 self.query_handler
                     .do_query(query, self.session.context())
@@ -14,13 +14,14 @@
 
 use std::ops::Deref;
 
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::error;
-use common_time::datetime::DateTime;
-use common_time::timestamp::TimeUnit;
+use common_time::timestamp::{TimeUnit, Timestamp};
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{ColumnSchema, SchemaRef};
+use futures::StreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
 };
@@ -29,11 +30,6 @@ use tokio::io::AsyncWrite;
 
 use crate::error::{self, Error, Result};
 
-struct QueryResult {
-    recordbatches: Vec<RecordBatch>,
-    schema: SchemaRef,
-}
-
 pub struct MysqlResultWriter<'a, W: AsyncWrite + Unpin> {
     // `QueryResultWriter` will be consumed when the write completed (see
     // QueryResultWriter::completed), thus we use an option to wrap it.
@@ -51,23 +47,9 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         })?;
         match output {
             Ok(output) => match output {
-                Output::Stream(stream) => {
-                    let schema = stream.schema().clone();
-                    let recordbatches = util::collect(stream)
-                        .await
-                        .context(error::CollectRecordbatchSnafu)?;
-                    let query_result = QueryResult {
-                        recordbatches,
-                        schema,
-                    };
-                    Self::write_query_result(query, query_result, writer).await?
-                }
+                Output::Stream(stream) => Self::write_query_result(query, stream, writer).await?,
                 Output::RecordBatches(recordbatches) => {
-                    let query_result = QueryResult {
-                        schema: recordbatches.schema(),
-                        recordbatches: recordbatches.take(),
-                    };
-                    Self::write_query_result(query, query_result, writer).await?
+                    Self::write_query_result(query, recordbatches.as_stream(), writer).await?
                 }
                 Output::AffectedRows(rows) => Self::write_affected_rows(writer, rows).await?,
             },
@@ -85,16 +67,19 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         Ok(())
     }
 
+    /// Writes rows of the `stream` as they are polled, so the result set is never
+    /// materialized as a whole.
     async fn write_query_result(
         query: &str,
-        query_result: QueryResult,
+        mut stream: SendableRecordBatchStream,
         writer: QueryResultWriter<'a, W>,
     ) -> Result<()> {
-        match create_mysql_column_def(&query_result.schema) {
+        match create_mysql_column_def(&stream.schema()) {
             Ok(column_def) => {
                 let mut row_writer = writer.start(&column_def).await?;
-                for recordbatch in &query_result.recordbatches {
-                    Self::write_recordbatch(&mut row_writer, recordbatch).await?;
+                while let Some(recordbatch) = stream.next().await {
+                    let recordbatch = recordbatch.context(error::CollectRecordbatchSnafu)?;
+                    Self::write_recordbatch(&mut row_writer, &recordbatch).await?;
                 }
                 row_writer.finish().await?;
                 Ok(())
@@ -124,12 +109,11 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Value::Float64(v) => row_writer.write_col(v.0)?,
                     Value::String(v) => row_writer.write_col(v.as_utf8())?,
                     Value::Binary(v) => row_writer.write_col(v.deref())?,
-                    Value::Date(v) => row_writer.write_col(v.val())?,
-                    Value::DateTime(v) => row_writer.write_col(v.val())?,
-                    Value::Timestamp(v) => row_writer.write_col(
-                        // safety: converting timestamp with whatever unit to second will not cause overflow
-                        DateTime::new(v.convert_to(TimeUnit::Second).unwrap().value()).to_string(),
-                    )?,
+                    Value::Date(v) => row_writer.write_col(v.to_string())?,
+                    Value::DateTime(v) => row_writer.write_col(v.to_string())?,
+                    Value::Decimal128(v) => row_writer.write_col(v.to_string())?,
+                    Value::Histogram(v) => row_writer.write_col(v.to_string())?,
+                    Value::Timestamp(v) => row_writer.write_col(format_timestamp(&v))?,
                     Value::List(_) => {
                         return Err(Error::Internal {
                             err_msg: format!(
@@ -152,12 +136,55 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     ) -> Result<()> {
         error!(error; "Failed to execute query '{}'", query);
 
-        let kind = ErrorKind::ER_INTERNAL_ERROR;
-        w.error(kind, error.to_string().as_bytes()).await?;
+        let status_code = error.status_code();
+        let kind = mysql_error_kind(status_code);
+        // MySQL clients could only get our status code from the message, as the error
+        // code of the packet must be a MySQL one.
+        let message = format!("({}): {}", status_code as u32, error);
+        w.error(kind, message.as_bytes()).await?;
         Ok(())
     }
 }
 
+/// Returns the closest MySQL error kind of the `status_code`.
+fn mysql_error_kind(status_code: StatusCode) -> ErrorKind {
+    match status_code {
+        StatusCode::InvalidSyntax => ErrorKind::ER_PARSE_ERROR,
+        StatusCode::InvalidArguments => ErrorKind::ER_WRONG_ARGUMENTS,
+        StatusCode::Unsupported => ErrorKind::ER_NOT_SUPPORTED_YET,
+        StatusCode::TableAlreadyExists => ErrorKind::ER_TABLE_EXISTS_ERROR,
+        StatusCode::TableNotFound => ErrorKind::ER_NO_SUCH_TABLE,
+        StatusCode::TableColumnNotFound => ErrorKind::ER_BAD_FIELD_ERROR,
+        StatusCode::TableColumnExists => ErrorKind::ER_DUP_FIELDNAME,
+        StatusCode::DatabaseNotFound => ErrorKind::ER_BAD_DB_ERROR,
+        StatusCode::TableReadOnly => ErrorKind::ER_OPEN_AS_READONLY,
+        StatusCode::UserNotFound
+        | StatusCode::UnsupportedPasswordType
+        | StatusCode::UserPasswordMismatch
+        | StatusCode::AuthHeaderNotFound
+        | StatusCode::InvalidAuthHeader => ErrorKind::ER_ACCESS_DENIED_ERROR,
+        StatusCode::AccessDenied => ErrorKind::ER_DBACCESS_DENIED_ERROR,
+        StatusCode::RateLimited => ErrorKind::ER_USER_LIMIT_REACHED,
+        StatusCode::Cancelled | StatusCode::DeadlineExceeded => ErrorKind::ER_QUERY_INTERRUPTED,
+        _ => ErrorKind::ER_INTERNAL_ERROR,
+    }
+}
+
+/// Formats the timestamp like MySQL's `DATETIME(fsp)`, the fractional digits are
+/// decided by the unit so sub-second precision is kept.
+fn format_timestamp(ts: &Timestamp) -> String {
+    let format = match ts.unit() {
+        TimeUnit::Second => "%Y-%m-%d %H:%M:%S",
+        TimeUnit::Millisecond => "%Y-%m-%d %H:%M:%S%.3f",
+        TimeUnit::Microsecond => "%Y-%m-%d %H:%M:%S%.6f",
+        TimeUnit::Nanosecond => "%Y-%m-%d %H:%M:%S%.9f",
+    };
+    match ts.to_chrono_datetime() {
+        Some(datetime) => datetime.format(format).to_string(),
+        None => ts.to_iso8601_string(),
+    }
+}
+
 fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
     let column_type = match column_schema.data_type {
         ConcreteDataType::Null(_) => Ok(ColumnType::MYSQL_TYPE_NULL),
@@ -171,13 +198,16 @@ fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
         ConcreteDataType::Int64(_) | ConcreteDataType::UInt64(_) => {
             Ok(ColumnType::MYSQL_TYPE_LONGLONG)
         }
-        ConcreteDataType::Float32(_) | ConcreteDataType::Float64(_) => {
-            Ok(ColumnType::MYSQL_TYPE_FLOAT)
+        ConcreteDataType::Float32(_) => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+        ConcreteDataType::Float64(_) => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
+        ConcreteDataType::Binary(_) => Ok(ColumnType::MYSQL_TYPE_BLOB),
+        ConcreteDataType::String(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        ConcreteDataType::Date(_) => Ok(ColumnType::MYSQL_TYPE_DATE),
+        ConcreteDataType::DateTime(_) | ConcreteDataType::Timestamp(_) => {
+            Ok(ColumnType::MYSQL_TYPE_DATETIME)
         }
-        ConcreteDataType::Binary(_) | ConcreteDataType::String(_) => {
-            Ok(ColumnType::MYSQL_TYPE_VARCHAR)
-        }
-        ConcreteDataType::Timestamp(_) => Ok(ColumnType::MYSQL_TYPE_DATETIME),
+        ConcreteDataType::Decimal128(_) => Ok(ColumnType::MYSQL_TYPE_NEWDECIMAL),
+        ConcreteDataType::Histogram(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
         _ => error::InternalSnafu {
             err_msg: format!(
                 "not implemented for column datatype {:?}",
@@ -190,13 +220,31 @@ fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
         column: column_schema.name.clone(),
         coltype: column_type,
 
-        // TODO(LFC): Currently "table" and "colflags" are not relevant in MySQL server
-        //   implementation, will revisit them again in the future.
+        // TODO(LFC): Currently "table" is not relevant in MySQL server
+        //   implementation, will revisit it again in the future.
         table: "".to_string(),
-        colflags: ColumnFlags::empty(),
+        colflags: mysql_column_flags(column_schema),
     })
 }
 
+/// Returns the flags of the MySQL column, JDBC drivers rely on them to pick the Java type
+/// of the column.
+fn mysql_column_flags(column_schema: &ColumnSchema) -> ColumnFlags {
+    let mut colflags = ColumnFlags::empty();
+    // Without the unsigned flag, clients decode u64 values greater than i64::MAX as negative.
+    if column_schema.data_type.is_unsigned() {
+        colflags |= ColumnFlags::UNSIGNED_FLAG;
+    }
+    if !column_schema.is_nullable() {
+        colflags |= ColumnFlags::NOT_NULL_FLAG;
+    }
+    // Blobs without the binary flag are reported as TEXT.
+    if matches!(column_schema.data_type, ConcreteDataType::Binary(_)) {
+        colflags |= ColumnFlags::BLOB_FLAG | ColumnFlags::BINARY_FLAG;
+    }
+    colflags
+}
+
 /// Creates MySQL columns definition from our column schema.
 pub fn create_mysql_column_def(schema: &SchemaRef) -> Result<Vec<Column>> {
     schema
@@ -205,3 +253,68 @@ pub fn create_mysql_column_def(schema: &SchemaRef) -> Result<Vec<Column>> {
         .map(create_mysql_column)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(
+            "2023-01-01 00:00:00",
+            format_timestamp(&Timestamp::new_second(1_672_531_200))
+        );
+        assert_eq!(
+            "2023-01-01 00:00:00.123",
+            format_timestamp(&Timestamp::new_millisecond(1_672_531_200_123))
+        );
+        assert_eq!(
+            "2023-01-01 00:00:00.000000001",
+            format_timestamp(&Timestamp::new_nanosecond(1_672_531_200_000_000_001))
+        );
+    }
+
+    #[test]
+    fn test_mysql_column_flags() {
+        let column = create_mysql_column(&ColumnSchema::new(
+            "a",
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ))
+        .unwrap();
+        assert_eq!(ColumnType::MYSQL_TYPE_LONGLONG, column.coltype);
+        assert_eq!(
+            ColumnFlags::UNSIGNED_FLAG | ColumnFlags::NOT_NULL_FLAG,
+            column.colflags
+        );
+
+        let column = create_mysql_column(&ColumnSchema::new(
+            "b",
+            ConcreteDataType::binary_datatype(),
+            true,
+        ))
+        .unwrap();
+        assert_eq!(ColumnType::MYSQL_TYPE_BLOB, column.coltype);
+        assert_eq!(
+            ColumnFlags::BLOB_FLAG | ColumnFlags::BINARY_FLAG,
+            column.colflags
+        );
+
+        let column = create_mysql_column(&ColumnSchema::new(
+            "c",
+            ConcreteDataType::float64_datatype(),
+            true,
+        ))
+        .unwrap();
+        assert_eq!(ColumnType::MYSQL_TYPE_DOUBLE, column.coltype);
+        assert_eq!(ColumnFlags::empty(), column.colflags);
+
+        let column = create_mysql_column(&ColumnSchema::new(
+            "d",
+            ConcreteDataType::date_datatype(),
+            true,
+        ))
+        .unwrap();
+        assert_eq!(ColumnType::MYSQL_TYPE_DATE, column.coltype);
+    }
+}
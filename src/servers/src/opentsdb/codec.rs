@@ -169,6 +169,7 @@ impl DataPoint {
             region_number: 0,
             columns,
             row_count: 1,
+            ..Default::default()
         }
     }
 
@@ -177,7 +178,7 @@ impl DataPoint {
         // 999999999999 (12 digits) is "Sun Sep 09 2001 01:46:39 UTC",
         // so timestamp digits less than 13 means we got seconds here.
         // (We are not expecting to store data that is 21 years ago, are we?)
-        if t.abs().to_string().len() < 13 {
+        if t.unsigned_abs().to_string().len() < 13 {
             t * 1000
         } else {
             t
@@ -244,6 +245,10 @@ mod test {
             data_point.tags,
             vec![("host".to_string(), "web01".to_string())]
         );
+
+        let data_point =
+            DataPoint::try_create("put sys.procs.running -9223372036854775808 42").unwrap();
+        assert_eq!(data_point.ts_millis, i64::MIN);
     }
 
     #[test]
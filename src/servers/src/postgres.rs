@@ -31,12 +31,13 @@ use pgwire::api::auth::ServerParameterProvider;
 use pgwire::api::store::MemPortalStore;
 use pgwire::api::{ClientInfo, MakeHandler};
 pub use server::PostgresServer;
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{Channel, QueryContext, QueryContextRef};
 use sql::statements::statement::Statement;
 
 use self::auth_handler::PgLoginVerifier;
 use self::handler::POCQueryParser;
 use crate::auth::UserProviderRef;
+use crate::cursor::Cursors;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 
 pub(crate) struct GreptimeDBStartupParameters {
@@ -74,6 +75,7 @@ pub struct PostgresServerHandler {
     param_provider: Arc<GreptimeDBStartupParameters>,
 
     query_ctx: QueryContextRef,
+    cursors: Cursors,
     portal_store: Arc<MemPortalStore<(Statement, String)>>,
     query_parser: Arc<POCQueryParser>,
 }
@@ -93,13 +95,16 @@ impl MakeHandler for MakePostgresServerHandler {
     type Handler = Arc<PostgresServerHandler>;
 
     fn make(&self) -> Self::Handler {
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_channel(Channel::Postgres);
         Arc::new(PostgresServerHandler {
             query_handler: self.query_handler.clone(),
             login_verifier: PgLoginVerifier::new(self.user_provider.clone()),
             force_tls: self.force_tls,
             param_provider: self.param_provider.clone(),
 
-            query_ctx: QueryContext::arc(),
+            query_ctx,
+            cursors: Cursors::default(),
             portal_store: Arc::new(MemPortalStore::new()),
             query_parser: self.query_parser.clone(),
         })
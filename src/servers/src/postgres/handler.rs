@@ -24,6 +24,7 @@ use common_time::timestamp::TimeUnit;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{Schema, SchemaRef};
 use futures::{future, stream, Stream, StreamExt};
+use once_cell::sync::Lazy;
 use pgwire::api::portal::Portal;
 use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
 use pgwire::api::results::{
@@ -34,23 +35,49 @@ use pgwire::api::stmt::{QueryParser, StoredStatement};
 use pgwire::api::store::MemPortalStore;
 use pgwire::api::{ClientInfo, Type};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
+use regex::Regex;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
 use sql::statements::statement::Statement;
 
 use super::PostgresServerHandler;
+use crate::cursor::CursorStatement;
 use crate::error::{self, Error, Result};
 
+/// Transactions are not supported, statements are committed once executed. `BEGIN` and
+/// `COMMIT` are accepted as no-ops, so clients wrapping reads in transactions, e.g. to read
+/// portals in chunks, still work. `ROLLBACK` is not accepted as nothing could be rolled back.
+static TRANSACTION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^\s*(BEGIN|START\s+TRANSACTION|COMMIT)\s*;?\s*$").unwrap());
+
 #[async_trait]
 impl SimpleQueryHandler for PostgresServerHandler {
     async fn do_query<C>(&self, _client: &C, query: &str) -> PgWireResult<Vec<Response>>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        let outputs = self
-            .query_handler
-            .do_query(query, self.query_ctx.clone())
-            .await;
+        if let Some(captures) = TRANSACTION_PATTERN.captures(query) {
+            let command = if captures[1].eq_ignore_ascii_case("COMMIT") {
+                "COMMIT"
+            } else {
+                "BEGIN"
+            };
+            return Ok(vec![Response::Execution(Tag::new_for_execution(
+                command, None,
+            ))]);
+        }
+
+        let outputs = if let Some(statement) = CursorStatement::parse(query) {
+            let output = self
+                .cursors
+                .execute(statement, &self.query_handler, self.query_ctx.clone())
+                .await;
+            vec![output]
+        } else {
+            self.query_handler
+                .do_query(query, self.query_ctx.clone())
+                .await
+        };
 
         let mut results = Vec::with_capacity(outputs.len());
 
@@ -172,6 +199,8 @@ fn encode_text_value(value: &Value, builder: &mut TextDataRowEncoder) -> PgWireR
         Value::Date(v) => builder.append_field(Some(&v.to_string())),
         Value::DateTime(v) => builder.append_field(Some(&v.to_string())),
         Value::Timestamp(v) => builder.append_field(Some(&v.to_iso8601_string())),
+        Value::Decimal128(v) => builder.append_field(Some(&v.to_string())),
+        Value::Histogram(v) => builder.append_field(Some(&v.to_string())),
         Value::List(_) => Err(PgWireError::ApiError(Box::new(Error::Internal {
             err_msg: format!(
                 "cannot write value {:?} in postgres protocol: unimplemented",
@@ -185,11 +214,12 @@ fn encode_binary_value(value: &Value, builder: &mut BinaryDataRowEncoder) -> PgW
     match value {
         Value::Null => builder.append_field(&None::<&i8>),
         Value::Boolean(v) => builder.append_field(v),
-        Value::UInt8(v) => builder.append_field(&(*v as i8)),
-        Value::UInt16(v) => builder.append_field(&(*v as i16)),
-        Value::UInt32(v) => builder.append_field(&(*v as i32)),
+        // Unsigned integers are widened to the signed type that holds all of their values.
+        Value::UInt8(v) => builder.append_field(&(*v as i16)),
+        Value::UInt16(v) => builder.append_field(&(*v as i32)),
+        Value::UInt32(v) => builder.append_field(&(*v as i64)),
         Value::UInt64(v) => builder.append_field(&(*v as i64)),
-        Value::Int8(v) => builder.append_field(v),
+        Value::Int8(v) => builder.append_field(&(*v as i16)),
         Value::Int16(v) => builder.append_field(v),
         Value::Int32(v) => builder.append_field(v),
         Value::Int64(v) => builder.append_field(v),
@@ -200,6 +230,8 @@ fn encode_binary_value(value: &Value, builder: &mut BinaryDataRowEncoder) -> PgW
         // TODO(sunng87): correct date/time types encoding
         Value::Date(v) => builder.append_field(&v.to_string()),
         Value::DateTime(v) => builder.append_field(&v.to_string()),
+        Value::Decimal128(v) => builder.append_field(&v.to_string()),
+        Value::Histogram(v) => builder.append_field(&v.to_string()),
         Value::Timestamp(v) => {
             // convert timestamp to SystemTime
             if let Some(ts) = v.convert_to(TimeUnit::Microsecond) {
@@ -224,10 +256,15 @@ fn type_gt_to_pg(origin: &ConcreteDataType) -> Result<Type> {
     match origin {
         &ConcreteDataType::Null(_) => Ok(Type::UNKNOWN),
         &ConcreteDataType::Boolean(_) => Ok(Type::BOOL),
-        &ConcreteDataType::Int8(_) | &ConcreteDataType::UInt8(_) => Ok(Type::CHAR),
-        &ConcreteDataType::Int16(_) | &ConcreteDataType::UInt16(_) => Ok(Type::INT2),
-        &ConcreteDataType::Int32(_) | &ConcreteDataType::UInt32(_) => Ok(Type::INT4),
-        &ConcreteDataType::Int64(_) | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
+        // Postgres has neither 1-byte nor unsigned integers, `CHAR` is a character type for
+        // clients, so we use the smallest integer type that holds all values of the type.
+        &ConcreteDataType::Int8(_) | &ConcreteDataType::UInt8(_) | &ConcreteDataType::Int16(_) => {
+            Ok(Type::INT2)
+        }
+        &ConcreteDataType::UInt16(_) | &ConcreteDataType::Int32(_) => Ok(Type::INT4),
+        &ConcreteDataType::UInt32(_)
+        | &ConcreteDataType::Int64(_)
+        | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
         &ConcreteDataType::Float32(_) => Ok(Type::FLOAT4),
         &ConcreteDataType::Float64(_) => Ok(Type::FLOAT8),
         &ConcreteDataType::Binary(_) => Ok(Type::BYTEA),
@@ -235,6 +272,8 @@ fn type_gt_to_pg(origin: &ConcreteDataType) -> Result<Type> {
         &ConcreteDataType::Date(_) => Ok(Type::DATE),
         &ConcreteDataType::DateTime(_) => Ok(Type::TIMESTAMP),
         &ConcreteDataType::Timestamp(_) => Ok(Type::TIMESTAMP),
+        &ConcreteDataType::Decimal128(_) => Ok(Type::NUMERIC),
+        &ConcreteDataType::Histogram(_) => Ok(Type::TEXT),
         &ConcreteDataType::List(_) => error::InternalSnafu {
             err_msg: format!("not implemented for column datatype {origin:?}"),
         }
@@ -370,6 +409,8 @@ impl ExtendedQueryHandler for PostgresServerHandler {
             sql = sql.replace(&format!("${}", i + 1), &parameter_to_string(portal, i)?);
         }
 
+        // All rows are returned even if `max_rows` is set, as the response can't suspend the
+        // portal, and clients would take the first chunk as the whole result otherwise.
         let output = self
             .query_handler
             .do_query(&sql, self.query_ctx.clone())
@@ -436,13 +477,13 @@ mod test {
         let pg_field_info = vec![
             FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
             FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
+            FieldInfo::new("int8s".into(), None, None, Type::INT2),
             FieldInfo::new("int16s".into(), None, None, Type::INT2),
             FieldInfo::new("int32s".into(), None, None, Type::INT4),
             FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8),
             FieldInfo::new("uint64s".into(), None, None, Type::INT8),
             FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
             FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
@@ -461,12 +502,12 @@ mod test {
         let schema = vec![
             FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
             FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8),
             FieldInfo::new("uint64s".into(), None, None, Type::INT8),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
+            FieldInfo::new("int8s".into(), None, None, Type::INT2),
+            FieldInfo::new("int8s".into(), None, None, Type::INT2),
             FieldInfo::new("int16s".into(), None, None, Type::INT2),
             FieldInfo::new("int16s".into(), None, None, Type::INT2),
             FieldInfo::new("int32s".into(), None, None, Type::INT4),
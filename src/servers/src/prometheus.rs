@@ -350,6 +350,7 @@ fn to_grpc_insert_request(mut timeseries: TimeSeries) -> Result<GrpcInsertReques
         region_number: 0,
         columns,
         row_count: row_count as u32,
+        ..Default::default()
     })
 }
 
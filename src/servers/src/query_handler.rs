@@ -29,7 +29,9 @@ use std::sync::Arc;
 
 use api::prometheus::remote::{ReadRequest, WriteRequest};
 use async_trait::async_trait;
+use common_error::prelude::ErrorExt;
 use common_query::Output;
+use serde::Serialize;
 use session::context::QueryContextRef;
 
 use crate::error::Result;
@@ -45,7 +47,8 @@ pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
 #[async_trait]
 pub trait ScriptHandler {
     async fn insert_script(&self, name: &str, script: &str) -> Result<()>;
-    async fn execute_script(&self, name: &str) -> Result<Output>;
+    /// Executes the script `name` on behalf of the user in `query_ctx`.
+    async fn execute_script(&self, name: &str, query_ctx: QueryContextRef) -> Result<Output>;
 }
 
 #[async_trait]
@@ -53,6 +56,42 @@ pub trait InfluxdbLineProtocolHandler {
     /// A successful request will not return a response.
     /// Only on error will the socket return a line of data.
     async fn exec(&self, request: &InfluxdbRequest, ctx: QueryContextRef) -> Result<()>;
+
+    /// Writes the lines could be written and reports the failed ones, instead of failing
+    /// the whole request.
+    async fn exec_partial(
+        &self,
+        request: &InfluxdbRequest,
+        ctx: QueryContextRef,
+    ) -> Result<PartialWriteOutput>;
+}
+
+/// Output of a batched write that tolerates failures of some rows.
+#[derive(Debug, Default, Serialize)]
+pub struct PartialWriteOutput {
+    /// Number of rows written.
+    pub success: usize,
+    pub failures: Vec<WriteFailure>,
+}
+
+/// A failed row (or sub-request) of a batched write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WriteFailure {
+    /// Index of the row in the batch.
+    pub index: usize,
+    /// The numeric status code of the error.
+    pub code: u32,
+    pub error: String,
+}
+
+impl WriteFailure {
+    pub fn new(index: usize, error: &dyn ErrorExt) -> Self {
+        Self {
+            index,
+            code: error.status_code() as u32,
+            error: error.to_string(),
+        }
+    }
 }
 
 #[async_trait]
@@ -13,12 +13,23 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
 use axum::body::Body;
-use axum::extract::{Json, Query, RawBody, State};
+use axum::extract::{Json, Path, Query, RawBody, State};
+use common_procedure::job::{JobManager, JobStatus};
+use common_procedure::{
+    BoxedProcedureLoader, Intervention, ProcedureId, ProcedureManager, ProcedureManagerRef,
+    ProcedureState, ProcedureWithId,
+};
 use common_telemetry::metric;
 use metrics::counter;
-use servers::http::{handler as http_handler, script as script_handler, ApiState, JsonOutput};
+use servers::http::export::{self, ExportFormat, ExportRequest, ExportState};
+use servers::http::{
+    handler as http_handler, job, procedure, script as script_handler, ApiState, JsonOutput,
+    OnError,
+};
 use session::context::UserInfo;
 use table::test_util::MemTable;
 
@@ -122,20 +133,241 @@ def test(n):
     assert!(json.output().is_none());
 }
 
+#[tokio::test]
+async fn test_run_scripts_batch() {
+    common_telemetry::init_default_ut_logging();
+
+    let script = r#"
+@copr(sql='select uint32s as number from numbers', args=['number'], returns=['n'])
+def test(n):
+    return n;
+"#
+    .to_string();
+    let state = ApiState {
+        sql_handler: create_testing_sql_query_handler(MemTable::default_numbers_table()),
+        script_handler: Some(create_testing_script_handler(
+            MemTable::default_numbers_table(),
+        )),
+    };
+    let Json(json) = script_handler::scripts(
+        State(state.clone()),
+        create_script_query(),
+        RawBody(Body::from(script)),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+
+    let Json(json) = script_handler::run_script(
+        State(state.clone()),
+        Query(script_handler::ScriptQuery {
+            name: Some("test, test".to_string()),
+            on_error: Some(OnError::Continue),
+        }),
+        axum::Extension(UserInfo::default()),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+    let output = json.output().unwrap();
+    assert_eq!(2, output.len());
+    for output in output {
+        match output {
+            JsonOutput::Records(records) => assert_eq!(100, records.num_rows()),
+            _ => unreachable!(),
+        }
+    }
+
+    let Json(json) = script_handler::run_script(
+        State(state),
+        Query(script_handler::ScriptQuery {
+            name: Some("test,".to_string()),
+            on_error: None,
+        }),
+        axum::Extension(UserInfo::default()),
+    )
+    .await;
+    assert!(!json.success());
+    assert_eq!(json.error().unwrap(), "Invalid argument: invalid name");
+}
+
+#[tokio::test]
+async fn test_export() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = tempdir::TempDir::new("test_export").unwrap();
+    let path = dir.path().join("result.csv");
+    let job_manager = Arc::new(JobManager::default());
+    let state = ExportState {
+        sql_handler: create_testing_sql_query_handler(MemTable::default_numbers_table()),
+        job_manager: job_manager.clone(),
+        export_dir: Some(dir.path().to_string_lossy().to_string()),
+        export_s3: None,
+    };
+
+    let Json(json) = export::export(
+        State(state.clone()),
+        Json(ExportRequest {
+            sql: "select uint32s from numbers where uint32s < 3".to_string(),
+            target: "file://result.csv".to_string(),
+            format: ExportFormat::Csv,
+            ..Default::default()
+        }),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+    let job_id = json.job().unwrap().id.to_string();
+
+    let job = loop {
+        let Json(json) = job::job_status(State(job_manager.clone()), Path(job_id.clone())).await;
+        let job = json.job().unwrap().clone();
+        if job.status.is_finished() {
+            break job;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    };
+    assert_eq!(JobStatus::Succeeded, job.status, "{job:?}");
+    assert_eq!(export::EXPORT_JOB_KIND, job.kind);
+    assert_eq!(3, job.progress);
+    assert_eq!(
+        "uint32s\n0\n1\n2\n",
+        std::fs::read_to_string(&path).unwrap()
+    );
+    let Json(json) = export::export_status(State(state.clone()), Path(job_id.clone())).await;
+    assert_eq!(Some(&job), json.job());
+
+    let Json(json) = export::export(
+        State(state.clone()),
+        Json(ExportRequest {
+            sql: "select uint32s from numbers".to_string(),
+            target: "ftp://127.0.0.1/result.csv".to_string(),
+            ..Default::default()
+        }),
+    )
+    .await;
+    assert!(!json.success());
+    assert!(json.job().is_none());
+
+    let Json(json) = job::list_jobs(State(job_manager.clone())).await;
+    assert_eq!(1, json.jobs().unwrap().len());
+
+    let Json(json) = job::cancel_job(State(job_manager.clone()), Path(job_id)).await;
+    assert!(!json.success());
+    let Json(json) = job::job_status(State(job_manager.clone()), Path("invalid".to_string())).await;
+    assert!(!json.success());
+
+    // Only maintenance statements run as jobs.
+    let Json(json) = job::submit_sql(
+        State(job::SqlJobState {
+            sql_handler: state.sql_handler.clone(),
+            job_manager,
+        }),
+        Json(job::SqlJobRequest {
+            db: None,
+            sql: "select uint32s from numbers".to_string(),
+        }),
+    )
+    .await;
+    assert!(!json.success());
+    assert!(json.job().is_none());
+}
+
+#[derive(Default)]
+struct MockProcedureManager {
+    interventions: Mutex<Vec<(ProcedureId, Intervention)>>,
+}
+
+#[async_trait]
+impl ProcedureManager for MockProcedureManager {
+    fn register_loader(
+        &self,
+        _name: &str,
+        _loader: BoxedProcedureLoader,
+    ) -> common_procedure::Result<()> {
+        unimplemented!()
+    }
+
+    async fn submit(&self, _procedure: ProcedureWithId) -> common_procedure::Result<()> {
+        unimplemented!()
+    }
+
+    async fn recover(&self) -> common_procedure::Result<()> {
+        unimplemented!()
+    }
+
+    async fn procedure_state(
+        &self,
+        _procedure_id: ProcedureId,
+    ) -> common_procedure::Result<Option<ProcedureState>> {
+        unimplemented!()
+    }
+
+    async fn intervene(
+        &self,
+        procedure_id: ProcedureId,
+        intervention: Intervention,
+    ) -> common_procedure::Result<()> {
+        self.interventions
+            .lock()
+            .unwrap()
+            .push((procedure_id, intervention));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_intervene_procedure() {
+    let manager = Arc::new(MockProcedureManager::default());
+    let procedure_id = ProcedureId::random();
+    let intervene = |procedure_id: String, action: &str| {
+        procedure::intervene(
+            State(manager.clone() as ProcedureManagerRef),
+            Path(procedure_id),
+            Query(procedure::InterventionQuery {
+                action: action.to_string(),
+            }),
+        )
+    };
+
+    let Json(json) = intervene(procedure_id.to_string(), "skip-step").await;
+    assert!(json.success(), "{json:?}");
+    let Json(json) = intervene(procedure_id.to_string(), "abort").await;
+    assert!(json.success(), "{json:?}");
+    assert_eq!(
+        vec![
+            (procedure_id, Intervention::SkipStep),
+            (procedure_id, Intervention::Abort)
+        ],
+        *manager.interventions.lock().unwrap()
+    );
+
+    let Json(json) = intervene(procedure_id.to_string(), "retry").await;
+    assert!(!json.success());
+    assert_eq!("Unknown intervention: retry", json.error().unwrap());
+    let Json(json) = intervene("invalid".to_string(), "abort").await;
+    assert!(!json.success());
+    assert_eq!(2, manager.interventions.lock().unwrap().len());
+}
+
 fn create_script_query() -> Query<script_handler::ScriptQuery> {
     Query(script_handler::ScriptQuery {
         name: Some("test".to_string()),
+        on_error: None,
     })
 }
 
 fn create_invalid_script_query() -> Query<script_handler::ScriptQuery> {
-    Query(script_handler::ScriptQuery { name: None })
+    Query(script_handler::ScriptQuery {
+        name: None,
+        on_error: None,
+    })
 }
 
 fn create_query() -> Query<http_handler::SqlQuery> {
     Query(http_handler::SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         db: None,
+        row_version: None,
+        timeout: None,
+        on_error: None,
     })
 }
 
@@ -24,7 +24,7 @@ use servers::error::{Error, Result};
 use servers::http::{HttpOptions, HttpServer};
 use servers::influxdb::InfluxdbRequest;
 use servers::query_handler::sql::SqlQueryHandler;
-use servers::query_handler::InfluxdbLineProtocolHandler;
+use servers::query_handler::{InfluxdbLineProtocolHandler, PartialWriteOutput};
 use session::context::QueryContextRef;
 use tokio::sync::mpsc;
 
@@ -45,6 +45,26 @@ impl InfluxdbLineProtocolHandler for DummyInstance {
 
         Ok(())
     }
+
+    async fn exec_partial(
+        &self,
+        request: &InfluxdbRequest,
+        ctx: QueryContextRef,
+    ) -> Result<PartialWriteOutput> {
+        let partial = request.to_partial_inserts();
+        let mut output = PartialWriteOutput {
+            success: 0,
+            failures: partial.failures,
+        };
+        for insert in partial.inserts {
+            output.success += insert.request.row_count as usize;
+            let _ = self
+                .tx
+                .send((ctx.current_schema(), insert.request.table_name))
+                .await;
+        }
+        Ok(output)
+    }
 }
 
 #[async_trait]
@@ -151,6 +171,23 @@ async fn test_influxdb_write() {
     assert_eq!(result.status(), 400);
     assert!(!result.text().await.is_empty());
 
+    // partial write
+    let result = client
+        .post("/v1/influxdb/write?db=influxdb&partial=true")
+        .body("monitor,   host=host1 cpu=1.2 1664370459457010101\nmonitor2,host=host1 cpu=1.2 1664370459457010101")
+        .header(
+            http::header::AUTHORIZATION,
+            "basic Z3JlcHRpbWU6Z3JlcHRpbWU=",
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    let body: serde_json::Value = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(1, body["success"]);
+    assert_eq!(1, body["failures"].as_array().unwrap().len());
+    assert_eq!(0, body["failures"][0]["index"]);
+    assert_eq!(1004, body["failures"][0]["code"]);
+
     let mut metrics = vec![];
     while let Ok(s) = rx.try_recv() {
         metrics.push(s);
@@ -159,7 +196,8 @@ async fn test_influxdb_write() {
         metrics,
         vec![
             ("public".to_string(), "monitor".to_string()),
-            ("influxdb".to_string(), "monitor".to_string())
+            ("influxdb".to_string(), "monitor".to_string()),
+            ("influxdb".to_string(), "monitor2".to_string())
         ]
     );
 }
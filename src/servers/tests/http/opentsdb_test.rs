@@ -127,7 +127,7 @@ async fn test_opentsdb_put() {
     assert_eq!(result.status(), 400);
     assert_eq!(
         result.text().await,
-        "{\"error\":\"Invalid OpenTSDB Json request, source: expected value at line 1 column 1\"}"
+        "{\"code\":1004,\"error\":\"Invalid OpenTSDB Json request, source: expected value at line 1 column 1\"}"
     );
 
     // internal server error
@@ -139,7 +139,7 @@ async fn test_opentsdb_put() {
     assert_eq!(result.status(), 500);
     assert_eq!(
         result.text().await,
-        "{\"error\":\"Internal error: Internal error: expected\"}"
+        "{\"code\":1003,\"error\":\"Internal error: Internal error: expected\"}"
     );
 
     let mut metrics = vec![];
@@ -182,7 +182,7 @@ async fn test_opentsdb_debug_put() {
         .send()
         .await;
     assert_eq!(result.status(), 200);
-    assert_eq!(result.text().await, "{\"success\":0,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"should_failed\",\"timestamp\":1000,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"error\":\"Internal error: expected\"}]}");
+    assert_eq!(result.text().await, "{\"success\":0,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"should_failed\",\"timestamp\":1000,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"code\":1003,\"error\":\"Internal error: expected\"}]}");
 
     // multiple data point summary debug put
     let result = client
@@ -207,7 +207,7 @@ async fn test_opentsdb_debug_put() {
         .send()
         .await;
     assert_eq!(result.status(), 200);
-    assert_eq!(result.text().await, "{\"success\":1,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"should_failed\",\"timestamp\":1000,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"error\":\"Internal error: expected\"}]}");
+    assert_eq!(result.text().await, "{\"success\":1,\"failed\":1,\"errors\":[{\"datapoint\":{\"metric\":\"should_failed\",\"timestamp\":1000,\"value\":1.0,\"tags\":{\"host\":\"web01\"}},\"code\":1003,\"error\":\"Internal error: expected\"}]}");
 
     let mut metrics = vec![];
     while let Ok(s) = rx.try_recv() {
@@ -120,10 +120,13 @@ impl ScriptHandler for DummyInstance {
         Ok(())
     }
 
-    async fn execute_script(&self, name: &str) -> Result<Output> {
+    async fn execute_script(&self, name: &str, query_ctx: QueryContextRef) -> Result<Output> {
         let py_script = self.scripts.read().unwrap().get(name).unwrap().clone();
 
-        Ok(py_script.execute(EvalContext::default()).await.unwrap())
+        let ctx = EvalContext {
+            query_ctx: Some(query_ctx),
+        };
+        Ok(py_script.execute(ctx).await.unwrap())
     }
 }
 
@@ -119,8 +119,8 @@ pub fn all_datatype_testing_data() -> TestingData {
         ColumnType::MYSQL_TYPE_LONG,
         ColumnType::MYSQL_TYPE_LONGLONG,
         ColumnType::MYSQL_TYPE_FLOAT,
-        ColumnType::MYSQL_TYPE_FLOAT,
-        ColumnType::MYSQL_TYPE_VARCHAR,
+        ColumnType::MYSQL_TYPE_DOUBLE,
+        ColumnType::MYSQL_TYPE_BLOB,
         ColumnType::MYSQL_TYPE_VARCHAR,
     ];
     let columns: Vec<VectorRef> = vec![
@@ -354,6 +354,41 @@ async fn test_db_name() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orm_metadata_commands() -> Result<()> {
+    let table = MemTable::default_numbers_table();
+    let mysql_server = create_mysql_server(table, Default::default())?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+
+    let mut connection = create_connection_default_db_name(server_addr.port(), false)
+        .await
+        .unwrap();
+    connection.query_drop("SET NAMES utf8mb4").await.unwrap();
+
+    let version_comment = connection
+        .query_first::<String, _>("SELECT @@version_comment LIMIT 1")
+        .await
+        .unwrap();
+    assert_eq!(Some("Greptime".to_string()), version_comment);
+
+    let variables = connection
+        .query::<(String, String), _>("SHOW VARIABLES LIKE 'character_set_client'")
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![("character_set_client".to_string(), "utf8mb4".to_string())],
+        variables
+    );
+
+    let variables = connection
+        .query::<(String, String), _>("SHOW VARIABLES LIKE 'gtid\\_mode'")
+        .await
+        .unwrap();
+    assert!(variables.is_empty());
+    Ok(())
+}
+
 async fn do_test_query_all_datatypes(server_tls: TlsOption, client_tls: bool) -> Result<()> {
     common_telemetry::init_default_ut_logging();
     let TestingData {
@@ -351,6 +351,58 @@ async fn test_extended_query() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_cursor() -> Result<()> {
+    let server_port = start_test_server(TlsOption::default()).await?;
+    let client = create_connection_with_given_db(server_port, DEFAULT_SCHEMA_NAME)
+        .await
+        .unwrap();
+    let _ = client
+        .simple_query("DECLARE c CURSOR FOR SELECT uint32s FROM numbers ORDER BY uint32s")
+        .await
+        .unwrap();
+
+    for start in [0, 40, 80] {
+        let result = client.simple_query("FETCH 40 FROM c").await.unwrap();
+        let expected = (start..(start + 40).min(100))
+            .map(|n: u32| n.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, unwrap_results(&result));
+    }
+    let result = client.simple_query("FETCH NEXT FROM c").await.unwrap();
+    assert!(unwrap_results(&result).is_empty());
+
+    let _ = client.simple_query("CLOSE c").await.unwrap();
+    assert!(client.simple_query("FETCH NEXT FROM c").await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_portal() -> Result<()> {
+    let server_port = start_test_server(TlsOption::default()).await?;
+    let mut client = create_connection_with_given_db(server_port, DEFAULT_SCHEMA_NAME)
+        .await
+        .unwrap();
+    let transaction = client.transaction().await.unwrap();
+    let stmt = transaction
+        .prepare("SELECT uint32s FROM numbers ORDER BY uint32s")
+        .await
+        .unwrap();
+    let portal = transaction.bind(&stmt, &[]).await.unwrap();
+
+    // The portal is never suspended, so all rows are returned by the first execution even
+    // if it asks for fewer rows.
+    let rows = transaction.query_portal(&portal, 40).await.unwrap();
+    let values = rows
+        .iter()
+        .map(|row| row.get::<usize, i32>(0))
+        .collect::<Vec<_>>();
+    assert_eq!((0..100).collect::<Vec<_>>(), values);
+
+    transaction.commit().await.unwrap();
+    Ok(())
+}
+
 async fn start_test_server(server_tls: TlsOption) -> Result<u16> {
     common_telemetry::init_default_ut_logging();
     let table = MemTable::default_numbers_table();
@@ -24,3 +24,13 @@ openssl x509 -req \
 
 # Create private key of pkcs8 format from rsa key.
 openssl pkcs8 -topk8 -inform PEM -in ./server-rsa.key -outform pem -nocrypt -out server-pkcs8.key
+
+# Create the server private key of EC, in SEC1 format, and its certificate.
+openssl ecparam -name prime256v1 -genkey -noout -out server-ec.key
+openssl req -new -key server-ec.key -out server-ec.csr -config csr.conf
+openssl x509 -req \
+    -in server-ec.csr \
+    -CA root-ca.crt -CAkey root-ca.key \
+    -CAcreateserial -out server-ec.crt \
+    -days 365 \
+    -sha256 -extfile cert.conf
@@ -14,9 +14,11 @@
 
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_telemetry::debug;
 
@@ -26,6 +28,18 @@ pub type ConnInfoRef = Arc<ConnInfo>;
 pub struct QueryContext {
     current_catalog: ArcSwap<String>,
     current_schema: ArcSwap<String>,
+    /// Whether to expose the version of rows as the `__version` column of tables.
+    row_version: AtomicBool,
+    /// User who issues the queries.
+    current_user: ArcSwap<UserInfo>,
+    /// Protocol the queries come from.
+    channel: ArcSwapOption<Channel>,
+    /// Sequence of the last write acknowledged once in the WAL buffer, 0 if there is none.
+    write_sequence: AtomicU64,
+    /// Deadline supplied by the client, after which the queries are abandoned.
+    deadline: ArcSwapOption<Instant>,
+    /// Whether to execute the remaining statements of a batch after one fails.
+    continue_on_error: AtomicBool,
 }
 
 impl Default for QueryContext {
@@ -54,6 +68,12 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(DEFAULT_CATALOG_NAME.to_string())),
             current_schema: ArcSwap::new(Arc::new(DEFAULT_SCHEMA_NAME.to_string())),
+            row_version: AtomicBool::new(false),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            channel: ArcSwapOption::empty(),
+            write_sequence: AtomicU64::new(0),
+            deadline: ArcSwapOption::empty(),
+            continue_on_error: AtomicBool::new(false),
         }
     }
 
@@ -61,6 +81,12 @@ impl QueryContext {
         Self {
             current_catalog: ArcSwap::new(Arc::new(catalog.to_string())),
             current_schema: ArcSwap::new(Arc::new(schema.to_string())),
+            row_version: AtomicBool::new(false),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            channel: ArcSwapOption::empty(),
+            write_sequence: AtomicU64::new(0),
+            deadline: ArcSwapOption::empty(),
+            continue_on_error: AtomicBool::new(false),
         }
     }
 
@@ -80,6 +106,73 @@ impl QueryContext {
         )
     }
 
+    pub fn row_version(&self) -> bool {
+        self.row_version.load(Ordering::Relaxed)
+    }
+
+    pub fn set_row_version(&self, row_version: bool) {
+        self.row_version.store(row_version, Ordering::Relaxed);
+    }
+
+    pub fn continue_on_error(&self) -> bool {
+        self.continue_on_error.load(Ordering::Relaxed)
+    }
+
+    pub fn set_continue_on_error(&self, continue_on_error: bool) {
+        self.continue_on_error
+            .store(continue_on_error, Ordering::Relaxed);
+    }
+
+    pub fn current_user(&self) -> Arc<UserInfo> {
+        self.current_user.load().clone()
+    }
+
+    pub fn set_current_user(&self, user_info: UserInfo) {
+        self.current_user.store(Arc::new(user_info));
+    }
+
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel.load().as_deref().copied()
+    }
+
+    pub fn set_channel(&self, channel: Channel) {
+        self.channel.store(Some(Arc::new(channel)));
+    }
+
+    /// Returns the sequence of the last write acknowledged once in the WAL buffer, or of the
+    /// latest write visible to the snapshot taken by the request.
+    pub fn write_sequence(&self) -> Option<u64> {
+        match self.write_sequence.load(Ordering::Relaxed) {
+            0 => None,
+            sequence => Some(sequence),
+        }
+    }
+
+    pub fn set_write_sequence(&self, sequence: u64) {
+        self.write_sequence.store(sequence, Ordering::Relaxed);
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline.load().as_deref().copied()
+    }
+
+    /// Sets the deadline of the queries, an earlier deadline set before is kept.
+    pub fn set_deadline(&self, deadline: Instant) {
+        let deadline = match self.deadline() {
+            Some(current) => current.min(deadline),
+            None => deadline,
+        };
+        self.deadline.store(Some(Arc::new(deadline)));
+    }
+
+    /// Sets the deadline of the queries to `timeout` from now, returns `None` without setting
+    /// it if the deadline overflows.
+    pub fn set_timeout(&self, timeout: Duration) -> Option<Instant> {
+        let deadline = Instant::now().checked_add(timeout)?;
+        self.set_deadline(deadline);
+        self.deadline()
+    }
+
     pub fn set_current_catalog(&self, catalog: &str) {
         let last = self.current_catalog.swap(Arc::new(catalog.to_string()));
         debug!(
@@ -90,16 +183,24 @@ impl QueryContext {
 }
 
 pub const DEFAULT_USERNAME: &str = "greptime";
+/// Role granting administrative statements, e.g. repairing tables.
+pub const ADMIN_ROLE: &str = "admin";
+/// Prefix of roles granting access to catalogs, e.g. `catalog:tenant1` grants access to
+/// the catalog `tenant1`.
+pub const CATALOG_ROLE_PREFIX: &str = "catalog:";
 
 #[derive(Clone, Debug)]
 pub struct UserInfo {
     username: String,
+    /// Roles granted to the user, which decide the columns the user could see unmasked.
+    roles: Vec<String>,
 }
 
 impl Default for UserInfo {
     fn default() -> Self {
         Self {
             username: DEFAULT_USERNAME.to_string(),
+            roles: Vec::new(),
         }
     }
 }
@@ -109,10 +210,44 @@ impl UserInfo {
         self.username.as_str()
     }
 
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
     pub fn new(username: impl Into<String>) -> Self {
         Self {
             username: username.into(),
+            roles: Vec::new(),
+        }
+    }
+
+    pub fn with_roles(mut self, roles: Vec<String>) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Returns true if the user is granted [ADMIN_ROLE], or is the default user of servers
+    /// without authentication.
+    pub fn is_admin(&self) -> bool {
+        self.username == DEFAULT_USERNAME || self.roles.iter().any(|role| role == ADMIN_ROLE)
+    }
+
+    /// Returns true if the user could access the `catalog`, which is granted by a role
+    /// prefixed with [CATALOG_ROLE_PREFIX]. Users without such roles could only access the
+    /// default catalog, while admins could access all catalogs.
+    pub fn can_access_catalog(&self, catalog: &str) -> bool {
+        if self.is_admin() {
+            return true;
         }
+        let mut granted = self
+            .roles
+            .iter()
+            .filter_map(|role| role.strip_prefix(CATALOG_ROLE_PREFIX))
+            .peekable();
+        if granted.peek().is_none() {
+            return catalog == DEFAULT_CATALOG_NAME;
+        }
+        granted.any(|granted| granted == catalog)
     }
 }
 
@@ -130,7 +265,7 @@ impl ConnInfo {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Channel {
     Grpc,
     Http,
@@ -141,9 +276,26 @@ pub enum Channel {
     Prometheus,
 }
 
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Channel::Grpc => "grpc",
+            Channel::Http => "http",
+            Channel::Mysql => "mysql",
+            Channel::Postgres => "postgres",
+            Channel::Opentsdb => "opentsdb",
+            Channel::Influxdb => "influxdb",
+            Channel::Prometheus => "prometheus",
+        };
+        write!(f, "{name}")
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::context::{Channel, UserInfo};
+    use std::time::{Duration, Instant};
+
+    use crate::context::{Channel, QueryContext, UserInfo};
     use crate::Session;
 
     #[test]
@@ -153,13 +305,54 @@ mod test {
         assert_eq!(session.user_info().username(), "greptime");
         session.set_user_info(UserInfo::new("root"));
         assert_eq!(session.user_info().username(), "root");
+        assert_eq!(session.context().current_user().username(), "root");
 
         // test channel
         assert_eq!(session.conn_info().channel, Channel::Mysql);
+        assert_eq!(session.context().channel(), Some(Channel::Mysql));
         assert_eq!(
             session.conn_info().client_host.ip().to_string(),
             "127.0.0.1"
         );
         assert_eq!(session.conn_info().client_host.port(), 9000);
     }
+
+    #[test]
+    fn test_deadline() {
+        let ctx = QueryContext::new();
+        assert!(ctx.deadline().is_none());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        ctx.set_deadline(deadline);
+        assert_eq!(Some(deadline), ctx.deadline());
+
+        // A later deadline doesn't extend the earlier one.
+        ctx.set_timeout(Duration::from_secs(3600));
+        assert_eq!(Some(deadline), ctx.deadline());
+
+        ctx.set_timeout(Duration::from_millis(1));
+        assert!(ctx.deadline().unwrap() < deadline);
+
+        // The deadline is kept if the timeout overflows.
+        let current = ctx.deadline();
+        assert!(ctx.set_timeout(Duration::MAX).is_none());
+        assert_eq!(current, ctx.deadline());
+    }
+
+    #[test]
+    fn test_can_access_catalog() {
+        let admin = UserInfo::default();
+        assert!(admin.can_access_catalog("greptime"));
+        assert!(admin.can_access_catalog("tenant1"));
+
+        let user = UserInfo::new("alice");
+        assert!(user.can_access_catalog("greptime"));
+        assert!(!user.can_access_catalog("tenant1"));
+
+        let user = UserInfo::new("alice")
+            .with_roles(vec!["reader".to_string(), "catalog:tenant1".to_string()]);
+        assert!(!user.can_access_catalog("greptime"));
+        assert!(user.can_access_catalog("tenant1"));
+        assert!(!user.can_access_catalog("tenant2"));
+    }
 }
@@ -29,8 +29,10 @@ pub struct Session {
 
 impl Session {
     pub fn new(addr: SocketAddr, channel: Channel) -> Self {
+        let query_ctx = Arc::new(QueryContext::new());
+        query_ctx.set_channel(channel);
         Session {
-            query_ctx: Arc::new(QueryContext::new()),
+            query_ctx,
             user_info: ArcSwap::new(Arc::new(UserInfo::default())),
             conn_info: Arc::new(ConnInfo::new(addr, channel)),
         }
@@ -46,6 +48,7 @@ impl Session {
         self.user_info.load().clone()
     }
     pub fn set_user_info(&self, user_info: UserInfo) {
+        self.query_ctx.set_current_user(user_info.clone());
         self.user_info.store(Arc::new(user_info));
     }
 }
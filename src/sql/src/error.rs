@@ -82,6 +82,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Invalid database option, key: {}, error: {}", key, msg))]
+    InvalidDatabaseOption {
+        key: String,
+        msg: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("SQL data type not supported yet: {:?}", t))]
     SqlTypeNotSupported {
         t: crate::ast::DataType,
@@ -137,6 +144,37 @@ pub enum Error {
     #[snafu(display("Invalid sql value: {}", value))]
     InvalidSqlValue { value: String, backtrace: Backtrace },
 
+    #[snafu(display(
+        "Columns and values number mismatch, columns: {}, values: {}",
+        columns,
+        values
+    ))]
+    ColumnValuesNumberMismatch {
+        columns: usize,
+        values: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to build default value, column: {}, source: {}",
+        column,
+        source
+    ))]
+    ColumnDefaultValue {
+        column: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display(
+        "No valid default value can be built automatically, column: {}",
+        column,
+    ))]
+    ColumnNoneDefaultValue {
+        column: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display(
         "Converting timestamp {:?} to unit {:?} overflow",
         timestamp,
@@ -166,13 +204,17 @@ impl ErrorExt for Error {
             | InvalidDefault { .. } => StatusCode::InvalidSyntax,
 
             InvalidColumnOption { .. }
+            | InvalidDatabaseOption { .. }
             | InvalidDatabaseName { .. }
             | ColumnTypeMismatch { .. }
             | InvalidTableName { .. } => StatusCode::InvalidArguments,
             UnsupportedAlterTableStatement { .. } => StatusCode::InvalidSyntax,
             SerializeColumnDefaultConstraint { source, .. } => source.status_code(),
             ConvertToGrpcDataType { source, .. } => source.status_code(),
-            InvalidSqlValue { .. } => StatusCode::InvalidArguments,
+            InvalidSqlValue { .. }
+            | ColumnValuesNumberMismatch { .. }
+            | ColumnNoneDefaultValue { .. } => StatusCode::InvalidArguments,
+            ColumnDefaultValue { source, .. } => source.status_code(),
             TimestampOverflow { .. } => StatusCode::InvalidArguments,
         }
     }
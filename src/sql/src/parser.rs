@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
 use snafu::{ensure, ResultExt};
+use sqlparser::ast::{ObjectName, Statement as SpStatement};
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
@@ -21,11 +24,19 @@ use sqlparser::tokenizer::{Token, Tokenizer};
 use crate::error::{
     self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu, TokenizerSnafu,
 };
+use crate::parsers::query_parser::{extract_hints, rewrite_asof_joins};
+use crate::statements::alert::DropAlertRule;
+use crate::statements::analyze::AnalyzeTable;
+use crate::statements::check::CheckTable;
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
-use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::drop::{DropTable, UndropTable};
+use crate::statements::explain::{Explain, ExplainDdl, ExplainFormat};
+use crate::statements::kill::Kill;
+use crate::statements::show::{
+    ShowCreateTable, ShowDatabases, ShowKind, ShowProcesslist, ShowTableStatus, ShowTables,
+};
 use crate::statements::statement::Statement;
+use crate::statements::use_database::UseDatabase;
 
 /// GrepTime SQL parser context, a simple wrapper for Datafusion SQL parser.
 pub struct ParserContext<'a> {
@@ -40,6 +51,8 @@ impl<'a> ParserContext<'a> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
 
         let tokens: Vec<Token> = tokenizer.tokenize().context(TokenizerSnafu { sql })?;
+        let mut hints = extract_hints(&tokens)?;
+        let tokens = rewrite_asof_joins(sql, tokens)?;
 
         let mut parser_ctx = ParserContext {
             sql,
@@ -60,7 +73,15 @@ impl<'a> ParserContext<'a> {
                 return parser_ctx.unsupported(parser_ctx.peek_token_as_string());
             }
 
-            let statement = parser_ctx.parse_statement()?;
+            let mut statement = parser_ctx.parse_statement()?;
+            if let Some(hints) = hints.remove(&stmts.len()) {
+                match &mut statement {
+                    Statement::Query(query) => query.hints = hints,
+                    Statement::Explain(explain) => explain.hints = hints,
+                    Statement::Insert(insert) => insert.hints = hints,
+                    _ => {}
+                }
+            }
             stmts.push(statement);
             expecting_statement_delimiter = true;
         }
@@ -101,18 +122,34 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::DROP => self.parse_drop(),
 
+                    Keyword::ANALYZE => {
+                        self.parser.next_token();
+                        self.parse_analyze()
+                    }
+
                     Keyword::USE => {
                         self.parser.next_token();
+                        self.parse_use()
+                    }
+
+                    _ if w.value.eq_ignore_ascii_case("KILL") => {
+                        self.parser.next_token();
+                        self.parse_kill()
+                    }
+
+                    _ if w.value.eq_ignore_ascii_case("UNDROP") => {
+                        self.parser.next_token();
+                        self.parse_undrop()
+                    }
+
+                    _ if w.value.eq_ignore_ascii_case("CHECK") => {
+                        self.parser.next_token();
+                        self.parse_check(false)
+                    }
 
-                        let database_name =
-                            self.parser
-                                .parse_identifier()
-                                .context(error::UnexpectedSnafu {
-                                    sql: self.sql,
-                                    expected: "a database name",
-                                    actual: self.peek_token_as_string(),
-                                })?;
-                        Ok(Statement::Use(database_name.value))
+                    _ if w.value.eq_ignore_ascii_case("REPAIR") => {
+                        self.parser.next_token();
+                        self.parse_check(true)
                     }
 
                     // todo(hl) support more statements.
@@ -141,12 +178,36 @@ impl<'a> ParserContext<'a> {
         } else if self.matches_keyword(Keyword::TABLES) {
             self.parser.next_token();
             self.parse_show_tables()
+        } else if self.consume_token("TABLE") {
+            if self.consume_token("STATUS") {
+                let (database, kind) = self.parse_show_database_and_kind()?;
+                Ok(Statement::ShowTableStatus(ShowTableStatus {
+                    kind,
+                    database,
+                }))
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
         } else if self.consume_token("CREATE") {
             if self.consume_token("TABLE") {
                 self.parse_show_create_table()
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
+        } else if self.consume_token("ALERT") {
+            if self.consume_token("RULES") {
+                Ok(Statement::ShowAlertRules)
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
+        } else if self.consume_token("PROCESSLIST") {
+            Ok(Statement::ShowProcesslist(ShowProcesslist { full: false }))
+        } else if self.consume_token("FULL") {
+            if self.consume_token("PROCESSLIST") {
+                Ok(Statement::ShowProcesslist(ShowProcesslist { full: true }))
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
         } else {
             self.unsupported(self.peek_token_as_string())
         }
@@ -168,19 +229,19 @@ impl<'a> ParserContext<'a> {
                 name: table_name.to_string(),
             }
         );
-        Ok(Statement::ShowCreateTable(ShowCreateTable {
-            table_name: table_name.to_string(),
-        }))
+        Ok(Statement::ShowCreateTable(ShowCreateTable { table_name }))
     }
 
     fn parse_show_tables(&mut self) -> Result<Statement> {
+        let (database, kind) = self.parse_show_database_and_kind()?;
+        Ok(Statement::ShowTables(ShowTables { kind, database }))
+    }
+
+    /// Parses `[IN | FROM <database>] [LIKE <pattern> | WHERE <expr>]` following
+    /// `SHOW TABLES` or `SHOW TABLE STATUS`.
+    fn parse_show_database_and_kind(&mut self) -> Result<(Option<String>, ShowKind)> {
         let database = match self.parser.peek_token() {
-            Token::EOF | Token::SemiColon => {
-                return Ok(Statement::ShowTables(ShowTables {
-                    kind: ShowKind::All,
-                    database: None,
-                }));
-            }
+            Token::EOF | Token::SemiColon => return Ok((None, ShowKind::All)),
 
             // SHOW TABLES [in | FROM] [DATABASE]
             Token::Word(w) => match w.keyword {
@@ -238,7 +299,7 @@ impl<'a> ParserContext<'a> {
             _ => return self.unsupported(self.peek_token_as_string()),
         };
 
-        Ok(Statement::ShowTables(ShowTables { kind, database }))
+        Ok((database, kind))
     }
 
     /// Parses DESCRIBE statements
@@ -270,6 +331,13 @@ impl<'a> ParserContext<'a> {
     }
 
     fn parse_explain(&mut self) -> Result<Statement> {
+        if self.consume_token("DDL") {
+            return self.parse_explain_ddl();
+        }
+        if self.peek_explain_format() {
+            return self.parse_explain_with_format();
+        }
+
         let explain_statement =
             self.parser
                 .parse_explain(false)
@@ -282,8 +350,149 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::Explain(Explain::try_from(explain_statement)?))
     }
 
+    /// Returns true if the next tokens are `(FORMAT`.
+    fn peek_explain_format(&self) -> bool {
+        let Token::Word(w) = self.parser.peek_nth_token(1) else {
+            return false;
+        };
+        self.parser.peek_token() == Token::LParen && w.keyword == Keyword::FORMAT
+    }
+
+    /// Parses `EXPLAIN (FORMAT <format>) <query>`, the `EXPLAIN` keyword is already consumed.
+    fn parse_explain_with_format(&mut self) -> Result<Statement> {
+        // Consumes the `(FORMAT` tokens.
+        self.parser.next_token();
+        self.parser.next_token();
+        let Ok(format) = ExplainFormat::from_str(&self.peek_token_as_string()) else {
+            return self.expected("SUBSTRAIT or JSON", self.parser.peek_token());
+        };
+        self.parser.next_token();
+        if !self.parser.consume_token(&Token::RParen) {
+            return self.expected(")", self.parser.peek_token());
+        }
+
+        let actual = self.peek_token_as_string();
+        let query = self
+            .parser
+            .parse_query()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a query statement",
+                actual,
+            })?;
+        let mut explain = Explain::try_from(SpStatement::Explain {
+            describe_alias: false,
+            analyze: false,
+            verbose: false,
+            statement: Box::new(SpStatement::Query(Box::new(query))),
+            format: None,
+        })?;
+        explain.format = Some(format);
+        Ok(Statement::Explain(explain))
+    }
+
+    /// Parses `EXPLAIN DDL <statement>`, the `EXPLAIN DDL` keywords are already consumed.
+    fn parse_explain_ddl(&mut self) -> Result<Statement> {
+        let actual = self.peek_token_as_string();
+        let statement = self.parse_statement()?;
+        if !matches!(statement, Statement::CreateTable(_) | Statement::Alter(_)) {
+            return self.unsupported(format!("EXPLAIN DDL {actual}"));
+        }
+        Ok(Statement::ExplainDdl(ExplainDdl {
+            statement: Box::new(statement),
+        }))
+    }
+
+    /// Parses `USE [<catalog>.]<schema>`, the `USE` keyword is already consumed.
+    fn parse_use(&mut self) -> Result<Statement> {
+        let database_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a database name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        let mut parts = database_name.0.into_iter().map(|ident| ident.value);
+        let stmt = match (parts.next(), parts.next(), parts.next()) {
+            (Some(schema_name), None, None) => UseDatabase {
+                catalog_name: None,
+                schema_name,
+            },
+            (Some(catalog_name), Some(schema_name), None) => UseDatabase {
+                catalog_name: Some(catalog_name),
+                schema_name,
+            },
+            _ => {
+                return error::InvalidSqlSnafu {
+                    msg: format!(
+                        "expect [<catalog>.]<schema> after USE, actual: {}",
+                        self.sql
+                    ),
+                }
+                .fail();
+            }
+        };
+        Ok(Statement::Use(stmt))
+    }
+
+    /// Parses `KILL [QUERY] <id>`, the `KILL` keyword is already consumed.
+    fn parse_kill(&mut self) -> Result<Statement> {
+        if !self.consume_token("QUERY") && !matches!(self.parser.peek_token(), Token::Number(..)) {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let query_id =
+            self.parser
+                .parse_literal_uint()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a query id",
+                    actual: self.peek_token_as_string(),
+                })?;
+        Ok(Statement::Kill(Kill { query_id }))
+    }
+
+    /// Parses `ANALYZE TABLE <table>`, the `ANALYZE` keyword is already consumed.
+    fn parse_analyze(&mut self) -> Result<Statement> {
+        let table_name = self.parse_table_after_keyword()?;
+        Ok(Statement::AnalyzeTable(AnalyzeTable { table_name }))
+    }
+
+    /// Parses `CHECK TABLE <table>` or `REPAIR TABLE <table>` if `repair` is true, the
+    /// `CHECK` or `REPAIR` keyword is already consumed.
+    fn parse_check(&mut self, repair: bool) -> Result<Statement> {
+        let table_name = self.parse_table_after_keyword()?;
+        Ok(Statement::CheckTable(CheckTable { table_name, repair }))
+    }
+
+    /// Parses `TABLE <table>` and returns the table name.
+    fn parse_table_after_keyword(&mut self) -> Result<ObjectName> {
+        if !self.consume_token("TABLE") {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string(),
+            }
+        );
+        Ok(table_name)
+    }
+
     fn parse_drop(&mut self) -> Result<Statement> {
         self.parser.next_token();
+        if self.consume_token("ALERT") {
+            return self.parse_drop_alert_rule();
+        }
         if !self.matches_keyword(Keyword::TABLE) {
             return self.unsupported(self.peek_token_as_string());
         }
@@ -307,6 +516,52 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::DropTable(DropTable::new(table_ident)))
     }
 
+    /// Parses `UNDROP TABLE <name>`, the `UNDROP` keyword is already consumed.
+    fn parse_undrop(&mut self) -> Result<Statement> {
+        if !self.matches_keyword(Keyword::TABLE) {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        self.parser.next_token();
+
+        let table_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_ident.to_string()
+            }
+        );
+
+        Ok(Statement::UndropTable(UndropTable::new(table_ident)))
+    }
+
+    /// Parses `DROP ALERT RULE [IF EXISTS] <name>`, the `DROP ALERT` keywords are already
+    /// consumed.
+    fn parse_drop_alert_rule(&mut self) -> Result<Statement> {
+        if !self.consume_token("RULE") {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "an alert rule name",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::DropAlertRule(DropAlertRule {
+            name: name.value,
+            if_exists,
+        }))
+    }
+
     // Report unexpected token
     pub(crate) fn expected<T>(&self, expected: &str, found: Token) -> Result<T> {
         Err(ParserError::ParserError(format!(
@@ -600,4 +855,21 @@ mod tests {
             ])))
         )
     }
+
+    #[test]
+    pub fn test_undrop_table() {
+        let sql = "UNDROP TABLE my_schema.foo";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::UndropTable(UndropTable::new(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
+        );
+
+        let sql = "UNDROP DATABASE foo";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
 }
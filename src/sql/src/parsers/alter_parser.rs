@@ -13,20 +13,62 @@
 // limitations under the License.
 
 use snafu::ResultExt;
+use sqlparser::ast::Value;
 use sqlparser::keywords::Keyword;
-use sqlparser::parser::ParserError;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
-use crate::statements::alter::{AlterTable, AlterTableOperation};
+use crate::statements::alter::{
+    AlterDatabase, AlterDatabaseOperation, AlterTable, AlterTableOperation,
+};
 use crate::statements::statement::Statement;
 
+const READ_ONLY: &str = "READ_ONLY";
+const MODIFY: &str = "MODIFY";
+
 impl<'a> ParserContext<'a> {
     pub(crate) fn parse_alter(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        if self
+            .parser
+            .parse_one_of_keywords(&[Keyword::DATABASE, Keyword::SCHEMA])
+            .is_some()
+        {
+            let alter_database = self
+                .parse_alter_database()
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            return Ok(Statement::AlterDatabase(alter_database));
+        }
+        self.parser.prev_token();
+
         let alter_table = self.parse().context(error::SyntaxSnafu { sql: self.sql })?;
         Ok(Statement::Alter(alter_table))
     }
 
+    /// Parses `ALTER DATABASE` statement after the `DATABASE` keyword.
+    fn parse_alter_database(&mut self) -> std::result::Result<AlterDatabase, ParserError> {
+        let parser = &mut self.parser;
+        let name = parser.parse_object_name()?;
+
+        let alter_operation = if parser.parse_keyword(Keyword::RENAME) {
+            parser.expect_keyword(Keyword::TO)?;
+            let new_name = parser.parse_identifier()?.value;
+            AlterDatabaseOperation::RenameDatabase { new_name }
+        } else {
+            let options = parser.parse_options(Keyword::SET)?;
+            if options.is_empty() {
+                return Err(ParserError::ParserError(format!(
+                    "expect keyword RENAME or SET after ALTER DATABASE, found {}",
+                    parser.peek_token()
+                )));
+            }
+            AlterDatabaseOperation::SetMetadata { options }
+        };
+        Ok(AlterDatabase::new(name, alter_operation))
+    }
+
     fn parse(&mut self) -> std::result::Result<AlterTable, ParserError> {
         let parser = &mut self.parser;
         parser.expect_keywords(&[Keyword::ALTER, Keyword::TABLE])?;
@@ -38,8 +80,13 @@ impl<'a> ParserContext<'a> {
                 AlterTableOperation::AddConstraint(constraint)
             } else {
                 let _ = parser.parse_keyword(Keyword::COLUMN);
+                let add_if_not_exists =
+                    parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
                 let column_def = parser.parse_column_def()?;
-                AlterTableOperation::AddColumn { column_def }
+                AlterTableOperation::AddColumn {
+                    column_def,
+                    add_if_not_exists,
+                }
             }
         } else if parser.parse_keyword(Keyword::DROP) {
             if parser.parse_keyword(Keyword::COLUMN) {
@@ -62,9 +109,37 @@ impl<'a> ParserContext<'a> {
                 }
             };
             AlterTableOperation::RenameTable { new_table_name }
+        } else if parser.parse_keyword(Keyword::SET) {
+            let option = parser.parse_identifier()?;
+            if !option.value.eq_ignore_ascii_case(READ_ONLY) {
+                return Err(ParserError::ParserError(format!(
+                    "expect {READ_ONLY} after ALTER TABLE SET, found {option}"
+                )));
+            }
+            parser.expect_token(&Token::Eq)?;
+            let read_only = match parser.parse_value()? {
+                Value::Boolean(read_only) => read_only,
+                value => {
+                    return Err(ParserError::ParserError(format!(
+                        "expect boolean value for {READ_ONLY}, found {value}"
+                    )))
+                }
+            };
+            AlterTableOperation::SetReadOnly { read_only }
+        } else if parser.parse_keyword(Keyword::COMMENT) {
+            let _ = parser.consume_token(&Token::Eq);
+            let comment = parse_comment(parser)?;
+            AlterTableOperation::SetComment { comment }
+        } else if parse_modify(parser) {
+            let _ = parser.parse_keyword(Keyword::COLUMN);
+            let name = parser.parse_identifier()?;
+            parser.expect_keyword(Keyword::COMMENT)?;
+            let comment = parse_comment(parser)?;
+            AlterTableOperation::SetColumnComment { name, comment }
         } else {
             return Err(ParserError::ParserError(format!(
-                "expect keyword ADD or DROP or RENAME after ALTER TABLE, found {}",
+                "expect keyword ADD, DROP, RENAME, SET, COMMENT or MODIFY after ALTER TABLE, \
+                 found {}",
                 parser.peek_token()
             )));
         };
@@ -72,6 +147,27 @@ impl<'a> ParserContext<'a> {
     }
 }
 
+/// Consumes the next token if it's `MODIFY`, which is not a keyword of sqlparser.
+fn parse_modify(parser: &mut Parser) -> bool {
+    match parser.peek_token() {
+        Token::Word(w) if w.value.eq_ignore_ascii_case(MODIFY) => {
+            let _ = parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Parses the string literal of a comment.
+fn parse_comment(parser: &mut Parser) -> std::result::Result<String, ParserError> {
+    match parser.next_token() {
+        Token::SingleQuotedString(comment, ..) => Ok(comment),
+        unexpected => Err(ParserError::ParserError(format!(
+            "expect a string literal as comment, found {unexpected}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -81,6 +177,69 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_display_alter_statements() {
+        let sqls = [
+            "ALTER DATABASE db1 RENAME TO db2",
+            "ALTER DATABASE db1 SET (ttl = '1d')",
+            "ALTER TABLE t ADD COLUMN IF NOT EXISTS c INT NULL COMMENT 'c'",
+            "ALTER TABLE t DROP COLUMN c",
+            "ALTER TABLE t RENAME t2",
+            "ALTER TABLE t SET READ_ONLY = true",
+            "ALTER TABLE t COMMENT 'it''s a table'",
+            "ALTER TABLE t MODIFY COLUMN c COMMENT 'c'",
+        ];
+        for sql in sqls {
+            let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+                .unwrap()
+                .remove(0);
+            let displayed = match &stmt {
+                Statement::AlterDatabase(alter) => alter.to_string(),
+                Statement::Alter(alter) => alter.to_string(),
+                _ => unreachable!(),
+            };
+            assert_eq!(sql, displayed);
+        }
+    }
+
+    #[test]
+    fn test_parse_alter_database() {
+        let sql = "ALTER DATABASE db1 RENAME TO db2";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::AlterDatabase(alter_database) => {
+                assert_eq!("db1", alter_database.name().to_string());
+                assert_eq!(
+                    &AlterDatabaseOperation::RenameDatabase {
+                        new_name: "db2".to_string()
+                    },
+                    alter_database.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER SCHEMA db1 SET (OWNER = 'alice', description = 'team a')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::AlterDatabase(alter_database) => {
+                assert_eq!("db1", alter_database.name().to_string());
+                let metadata = alter_database.alter_operation().metadata();
+                assert_eq!(2, metadata.len());
+                assert_eq!("alice", metadata["owner"]);
+                assert_eq!("team a", metadata["description"]);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER DATABASE db1 DROP a";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expect keyword RENAME or SET after ALTER DATABASE"));
+    }
+
     #[test]
     fn test_parse_alter_add_column() {
         let sql = "ALTER TABLE my_metric_1 ADD tagk_i STRING Null;";
@@ -96,7 +255,11 @@ mod tests {
                 let alter_operation = alter_table.alter_operation();
                 assert_matches!(alter_operation, AlterTableOperation::AddColumn { .. });
                 match alter_operation {
-                    AlterTableOperation::AddColumn { column_def } => {
+                    AlterTableOperation::AddColumn {
+                        column_def,
+                        add_if_not_exists,
+                    } => {
+                        assert!(!add_if_not_exists);
                         assert_eq!("tagk_i", column_def.name.value);
                         assert_eq!(DataType::String, column_def.data_type);
                         assert!(column_def
@@ -111,6 +274,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_alter_add_column_if_not_exists() {
+        let sql = "ALTER TABLE my_metric_1 ADD COLUMN IF NOT EXISTS tagk_i STRING Null;";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        match result.remove(0) {
+            Statement::Alter(alter_table) => match alter_table.alter_operation() {
+                AlterTableOperation::AddColumn {
+                    column_def,
+                    add_if_not_exists,
+                } => {
+                    assert!(add_if_not_exists);
+                    assert_eq!("tagk_i", column_def.name.value);
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_parse_alter_drop_column() {
         let sql = "ALTER TABLE my_metric_1 DROP a";
@@ -146,9 +330,9 @@ mod tests {
     fn test_parse_alter_rename_table() {
         let sql = "ALTER TABLE test_table table_t";
         let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
-        assert!(result
-            .to_string()
-            .contains("expect keyword ADD or DROP or RENAME after ALTER TABLE"));
+        assert!(result.to_string().contains(
+            "expect keyword ADD, DROP, RENAME, SET, COMMENT or MODIFY after ALTER TABLE"
+        ));
 
         let sql = "ALTER TABLE test_table RENAME table_t";
         let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
@@ -172,4 +356,99 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_parse_alter_set_read_only() {
+        let sql = "ALTER TABLE test_table SET READ_ONLY = true";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        match statement {
+            Statement::Alter(alter_table) => {
+                assert_eq!("test_table", alter_table.table_name().0[0].value);
+                assert_eq!(
+                    &AlterTableOperation::SetReadOnly { read_only: true },
+                    alter_table.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table SET read_only = FALSE";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Alter(alter_table) => {
+                assert_eq!(
+                    &AlterTableOperation::SetReadOnly { read_only: false },
+                    alter_table.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table SET READ_ONLY = 1";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result
+            .to_string()
+            .contains("expect boolean value for READ_ONLY"));
+
+        let sql = "ALTER TABLE test_table SET TTL = true";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result
+            .to_string()
+            .contains("expect READ_ONLY after ALTER TABLE SET"));
+    }
+
+    #[test]
+    fn test_parse_alter_comment() {
+        let sql = "ALTER TABLE test_table COMMENT = 'cpu usage of hosts'";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Alter(alter_table) => {
+                assert_eq!(
+                    &AlterTableOperation::SetComment {
+                        comment: "cpu usage of hosts".to_string()
+                    },
+                    alter_table.alter_operation()
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table MODIFY COLUMN cpu COMMENT 'usage in percent'";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Alter(alter_table) => match alter_table.alter_operation() {
+                AlterTableOperation::SetColumnComment { name, comment } => {
+                    assert_eq!("cpu", name.value);
+                    assert_eq!("usage in percent", comment);
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table modify cpu comment ''";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match result.remove(0) {
+            Statement::Alter(alter_table) => assert_eq!(
+                &AlterTableOperation::SetColumnComment {
+                    name: "cpu".into(),
+                    comment: String::new(),
+                },
+                alter_table.alter_operation()
+            ),
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE test_table COMMENT 1";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result
+            .to_string()
+            .contains("expect a string literal as comment"));
+
+        let sql = "ALTER TABLE test_table MODIFY COLUMN cpu 'usage'";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::time::Duration;
 
 use itertools::Itertools;
 use mito::engine;
@@ -30,8 +31,9 @@ use crate::error::{
     SyntaxSnafu,
 };
 use crate::parser::ParserContext;
+use crate::statements::alert::{AlertExpr, CreateAlertRule};
 use crate::statements::create::{
-    CreateDatabase, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
+    CreateCatalog, CreateDatabase, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
 };
 use crate::statements::statement::Statement;
 use crate::statements::{sql_data_type_to_concrete_data_type, sql_value_to_value};
@@ -51,12 +53,123 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::SCHEMA | Keyword::DATABASE => self.parse_create_database(),
 
+                _ if w.value.eq_ignore_ascii_case("ALERT") => self.parse_create_alert_rule(),
+
+                _ if w.value.eq_ignore_ascii_case("CATALOG") => self.parse_create_catalog(),
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
         }
     }
 
+    /// Parses `CREATE ALERT RULE [IF NOT EXISTS] <name> EVERY '<interval>' WEBHOOK '<url>'
+    /// AS <query | PROMQL '<expr>'>`.
+    fn parse_create_alert_rule(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        if !self.consume_token("RULE") {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "an alert rule name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        if !self.consume_token("EVERY") {
+            return self.expected("EVERY", self.parser.peek_token());
+        }
+        let interval = self.parse_alert_interval()?;
+
+        if !self.consume_token("WEBHOOK") {
+            return self.expected("WEBHOOK", self.parser.peek_token());
+        }
+        let webhook = self
+            .parser
+            .parse_literal_string()
+            .context(SyntaxSnafu { sql: self.sql })?;
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let expr = if self.consume_token("PROMQL") {
+            AlertExpr::Promql(
+                self.parser
+                    .parse_literal_string()
+                    .context(SyntaxSnafu { sql: self.sql })?,
+            )
+        } else {
+            let actual = self.peek_token_as_string();
+            let query = self
+                .parser
+                .parse_query()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a query statement",
+                    actual,
+                })?;
+            AlertExpr::Sql(query.to_string())
+        };
+
+        Ok(Statement::CreateAlertRule(CreateAlertRule {
+            name: name.value,
+            if_not_exists,
+            interval,
+            webhook,
+            expr,
+        }))
+    }
+
+    /// Parses the evaluation interval of an alert rule, e.g. `'30s'` or `'1m'`.
+    fn parse_alert_interval(&mut self) -> Result<Duration> {
+        let interval = self
+            .parser
+            .parse_literal_string()
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let duration = humantime::parse_duration(&interval).map_err(|e| {
+            error::InvalidSqlSnafu {
+                msg: format!("invalid alert rule interval '{interval}': {e}"),
+            }
+            .build()
+        })?;
+        ensure!(
+            !duration.is_zero(),
+            error::InvalidSqlSnafu {
+                msg: "alert rule interval must be positive",
+            }
+        );
+        Ok(duration)
+    }
+
+    /// Parses `CREATE CATALOG [IF NOT EXISTS] <name>`.
+    fn parse_create_catalog(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let catalog_name = self
+            .parser
+            .parse_identifier()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a catalog name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Statement::CreateCatalog(CreateCatalog {
+            name: catalog_name.value,
+            if_not_exists,
+        }))
+    }
+
     fn parse_create_database(&mut self) -> Result<Statement> {
         self.parser.next_token();
 
@@ -73,10 +186,19 @@ impl<'a> ParserContext<'a> {
                 actual: self.peek_token_as_string(),
             })?;
 
-        Ok(Statement::CreateDatabase(CreateDatabase {
+        let options = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        let create_database = CreateDatabase {
             name: database_name,
             if_not_exists,
-        }))
+            options,
+        };
+        create_database.validate_options()?;
+
+        Ok(Statement::CreateDatabase(create_database))
     }
 
     fn parse_create_table(&mut self) -> Result<Statement> {
@@ -99,6 +221,7 @@ impl<'a> ParserContext<'a> {
         let partitions = self.parse_partitions()?;
 
         let engine = self.parse_table_engine()?;
+        let comment = self.parse_table_comment()?;
         let options = self
             .parser
             .parse_options(Keyword::WITH)
@@ -111,6 +234,7 @@ impl<'a> ParserContext<'a> {
             engine,
             constraints,
             options,
+            comment,
             table_id: 0, // table id is assigned by catalog manager
             partitions,
         };
@@ -508,6 +632,19 @@ impl<'a> ParserContext<'a> {
             unexpected => self.expected("Engine is missing", unexpected),
         }
     }
+
+    /// Parses the optional `COMMENT [=] 'xxx'` of the table.
+    fn parse_table_comment(&mut self) -> Result<Option<String>> {
+        if !self.parser.parse_keyword(Keyword::COMMENT) {
+            return Ok(None);
+        }
+        let _ = self.parser.consume_token(&Token::Eq);
+
+        match self.parser.next_token() {
+            Token::SingleQuotedString(comment, ..) => Ok(Some(comment)),
+            unexpected => self.expected("string literal", unexpected),
+        }
+    }
 }
 
 fn validate_create(create_table: &CreateTable) -> Result<()> {
@@ -720,6 +857,66 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_display_create_statements() {
+        let sqls = [
+            "CREATE DATABASE IF NOT EXISTS db WITH (ttl = '7d')",
+            r"CREATE TABLE IF NOT EXISTS demo (
+  host STRING COMMENT 'it''s a host',
+  ts TIMESTAMP TIME INDEX,
+  cpu DOUBLE DEFAULT 0,
+  PRIMARY KEY (host)
+)
+PARTITION BY RANGE COLUMNS (host) (
+  PARTITION r0 VALUES LESS THAN ('a'),
+  PARTITION r1 VALUES LESS THAN (MAXVALUE)
+)
+ENGINE=mito
+COMMENT 'demo table'
+WITH (ttl = '7d')",
+        ];
+        for sql in sqls {
+            let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+                .unwrap()
+                .remove(0);
+            let displayed = match &stmt {
+                Statement::CreateDatabase(create) => create.to_string(),
+                Statement::CreateTable(create) => create.to_string(),
+                _ => unreachable!(),
+            };
+            let reparsed = ParserContext::create_with_dialect(&displayed, &GenericDialect {})
+                .unwrap()
+                .remove(0);
+            assert_eq!(stmt, reparsed, "{displayed}");
+        }
+    }
+
+    #[test]
+    fn test_parse_create_catalog() {
+        let sql = "create catalog";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+
+        let sql = "create catalog tenant_a";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::CreateCatalog(CreateCatalog {
+                name: "tenant_a".to_string(),
+                if_not_exists: false,
+            })],
+            stmts
+        );
+
+        let sql = "CREATE CATALOG IF NOT EXISTS tenant_a";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::CreateCatalog(CreateCatalog {
+                name: "tenant_a".to_string(),
+                if_not_exists: true,
+            })],
+            stmts
+        );
+    }
+
     #[test]
     fn test_parse_create_database() {
         let sql = "create database";
@@ -749,11 +946,54 @@ mod tests {
             Statement::CreateDatabase(c) => {
                 assert_eq!(c.name.to_string(), "prometheus");
                 assert!(c.if_not_exists);
+                assert!(c.options.is_empty());
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create database prometheus with (TTL='30d', regions=4, compression='zstd')";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+
+        assert_eq!(1, stmts.len());
+        match &stmts[0] {
+            Statement::CreateDatabase(c) => {
+                assert_eq!(c.name.to_string(), "prometheus");
+                let options = c.options();
+                assert_eq!(3, options.len());
+                assert_eq!("30d", options["ttl"]);
+                assert_eq!("4", options["regions"]);
+                assert_eq!("zstd", options["compression"]);
             }
             _ => unreachable!(),
         }
     }
 
+    #[test]
+    fn test_parse_create_database_invalid_options() {
+        let cases = [
+            ("create database db with (foo='bar')", "unknown option"),
+            ("create database db with (ttl='abc')", "invalid duration"),
+            ("create database db with (ttl='0s')", "ttl must be positive"),
+            (
+                "create database db with (regions='abc')",
+                "not a valid number",
+            ),
+            (
+                "create database db with (regions=0)",
+                "regions must be positive",
+            ),
+            (
+                "create database db with (compression='foo')",
+                "unknown compression",
+            ),
+        ];
+        for (sql, expect) in cases {
+            let err = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+            assert_matches!(err, error::Error::InvalidDatabaseOption { .. });
+            assert!(err.to_string().contains(expect), "{sql}: {err}");
+        }
+    }
+
     #[test]
     fn test_validate_create() {
         let sql = r"
@@ -1214,6 +1454,64 @@ ENGINE=mito";
         assert_eq!(column.data_type.to_string(), data_type);
     }
 
+    #[test]
+    fn test_parse_create_table_options() {
+        let sql = r"create table demo(
+                             host string,
+                             ts timestamp,
+                             cpu float64,
+                             TIME INDEX (ts),
+                             PRIMARY KEY(host)) engine=mito
+                             with(regions=1, DEDUP_STRATEGY='sum');
+         ";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &result[0] {
+            Statement::CreateTable(c) => {
+                let options = c.table_options();
+                assert_eq!(2, options.len());
+                assert_eq!("1", options["regions"]);
+                assert_eq!("sum", options["dedup_strategy"]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_table_comment() {
+        let sql = r"create table demo(
+                             host string comment 'host name',
+                             ts timestamp,
+                             cpu float64,
+                             TIME INDEX (ts),
+                             PRIMARY KEY(host)) engine=mito comment='cpu usage of hosts'
+                             with(regions=1);
+         ";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &result[0] {
+            Statement::CreateTable(c) => {
+                assert_eq!(Some("cpu usage of hosts"), c.comment.as_deref());
+                assert_eq!(1, c.table_options().len());
+                assert!(matches!(
+                    &c.columns[0].options[0].option,
+                    ColumnOption::Comment(comment) if comment == "host name"
+                ));
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create table demo(ts timestamp time index) comment 'no engine'";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &result[0] {
+            Statement::CreateTable(c) => {
+                assert_eq!(Some("no engine"), c.comment.as_deref());
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create table demo(ts timestamp time index) comment = 1";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     pub fn test_parse_create_table() {
         let sql = r"create table demo(
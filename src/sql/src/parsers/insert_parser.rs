@@ -17,6 +17,7 @@ use sqlparser::ast::Statement as SpStatement;
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
+use crate::statements::hint::Hints;
 use crate::statements::insert::Insert;
 use crate::statements::statement::Statement;
 
@@ -30,9 +31,10 @@ impl<'a> ParserContext<'a> {
             .context(error::SyntaxSnafu { sql: self.sql })?;
 
         match spstatement {
-            SpStatement::Insert { .. } => {
-                Ok(Statement::Insert(Box::new(Insert { inner: spstatement })))
-            }
+            SpStatement::Insert { .. } => Ok(Statement::Insert(Box::new(Insert {
+                inner: spstatement,
+                hints: Hints::default(),
+            }))),
             unexp => error::UnsupportedSnafu {
                 sql: self.sql.to_string(),
                 keyword: unexp.to_string(),
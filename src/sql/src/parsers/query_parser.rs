@@ -12,11 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use snafu::prelude::*;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Whitespace};
 
 use crate::error::{self, Result};
 use crate::parser::ParserContext;
-use crate::statements::query::Query;
+use crate::statements::hint::Hints;
+use crate::statements::query::{Query, ASOF_JOIN_MARKER};
 use crate::statements::statement::Statement;
 
 impl<'a> ParserContext<'a> {
@@ -31,11 +36,124 @@ impl<'a> ParserContext<'a> {
     }
 }
 
+/// Extracts hints of the statements in `tokens`, which are given by the comment `/*+ ... */`
+/// right after the first `SELECT` of a statement. Returns the hints by the ordinals of the
+/// statements, empty statements between successive delimiters are not counted.
+pub(crate) fn extract_hints(tokens: &[Token]) -> Result<HashMap<usize, Hints>> {
+    let mut hints = HashMap::new();
+    let mut ordinal = 0;
+    // Whether the current statement has any token, and whether it has a `SELECT`.
+    let mut non_empty = false;
+    let mut selected = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::SemiColon => {
+                if non_empty {
+                    ordinal += 1;
+                }
+                non_empty = false;
+                selected = false;
+            }
+            Token::Whitespace(_) | Token::EOF => {}
+            Token::Word(w) if w.keyword == Keyword::SELECT && !selected => {
+                non_empty = true;
+                selected = true;
+                let comment = tokens[i + 1..]
+                    .iter()
+                    .map_while(|t| match t {
+                        Token::Whitespace(whitespace) => Some(whitespace),
+                        _ => None,
+                    })
+                    .find_map(|whitespace| match whitespace {
+                        Whitespace::MultiLineComment(comment) => Some(comment),
+                        _ => None,
+                    });
+                if let Some(text) = comment.and_then(|comment| comment.strip_prefix('+')) {
+                    let _ = hints.insert(ordinal, Hints::parse(text)?);
+                }
+            }
+            _ => non_empty = true,
+        }
+    }
+    Ok(hints)
+}
+
+/// Rewrites each `ASOF JOIN b ON <cond>` in `tokens` to `JOIN b ON __asof_join__() AND <cond>`,
+/// see [ASOF_JOIN_MARKER].
+///
+/// `ASOF` before `JOIN` is the join keyword unless it is quoted or follows `AS`, so a table
+/// aliased `asof` must be written as `t AS asof` or `t "asof"`.
+pub(crate) fn rewrite_asof_joins(sql: &str, tokens: Vec<Token>) -> Result<Vec<Token>> {
+    let missing_on = || {
+        error::InvalidSqlSnafu {
+            msg: format!("ASOF JOIN requires an ON condition, sql: {sql}"),
+        }
+        .fail()
+    };
+    let is_word =
+        |token: &Token, keyword: Keyword| matches!(token, Token::Word(w) if w.keyword == keyword);
+
+    let mut rewritten = Vec::with_capacity(tokens.len());
+    // Parenthesis depths of the ASOF JOINs waiting for their ON conditions.
+    let mut pending = Vec::new();
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case("ASOF") => {
+                let is_significant = |t: &&Token| !matches!(t, Token::Whitespace(_));
+                let prev = rewritten.iter().rev().find(is_significant);
+                let next = tokens[i + 1..].iter().find(is_significant);
+                if !prev.map_or(false, |prev| is_word(prev, Keyword::AS))
+                    && next.map_or(false, |next| is_word(next, Keyword::JOIN))
+                {
+                    pending.push(depth);
+                    continue;
+                }
+            }
+            Token::Word(w) if w.keyword == Keyword::ON && pending.last() == Some(&depth) => {
+                let _ = pending.pop();
+                rewritten.extend([
+                    token.clone(),
+                    Token::Whitespace(Whitespace::Space),
+                    Token::make_word(ASOF_JOIN_MARKER, None),
+                    Token::LParen,
+                    Token::RParen,
+                    Token::Whitespace(Whitespace::Space),
+                    Token::make_keyword("AND"),
+                ]);
+                continue;
+            }
+            Token::Word(w) if w.keyword == Keyword::USING && pending.last() == Some(&depth) => {
+                return missing_on();
+            }
+            Token::LParen => depth += 1,
+            Token::RParen | Token::SemiColon | Token::EOF => {
+                if pending.last() == Some(&depth) {
+                    return missing_on();
+                }
+                if *token == Token::RParen {
+                    depth -= 1;
+                }
+            }
+            _ => {}
+        }
+        rewritten.push(token.clone());
+    }
+    if !pending.is_empty() {
+        return missing_on();
+    }
+
+    Ok(rewritten)
+}
+
 #[cfg(test)]
 mod tests {
     use sqlparser::dialect::GenericDialect;
 
     use crate::parser::ParserContext;
+    use crate::statements::hint::Hints;
+    use crate::statements::query::ASOF_JOIN_MARKER;
+    use crate::statements::statement::Statement;
 
     #[test]
     pub fn test_parse_query() {
@@ -47,6 +165,42 @@ mod tests {
         let _ = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
     }
 
+    #[test]
+    pub fn test_parse_asof_join() {
+        let sql = "SELECT * FROM metrics m \
+           ASOF JOIN (SELECT * FROM hosts h JOIN regions r ON h.region = r.id) d \
+           ON m.host = d.host AND m.ts >= d.ts \
+           JOIN zones z ON d.zone = z.id";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Query(query) = stmts.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT * FROM metrics AS m \
+            JOIN (SELECT * FROM hosts AS h JOIN regions AS r ON h.region = r.id) AS d \
+            ON __asof_join__() AND m.host = d.host AND m.ts >= d.ts \
+            JOIN zones AS z ON d.zone = z.id",
+            query.inner.to_string()
+        );
+
+        // Tables aliased `asof` are joined as usual.
+        for sql in [
+            "SELECT * FROM metrics AS asof JOIN hosts h ON asof.host = h.host",
+            "SELECT * FROM metrics \"asof\" JOIN hosts h ON \"asof\".host = h.host",
+        ] {
+            let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            let Statement::Query(query) = stmts.remove(0) else {
+                unreachable!()
+            };
+            assert!(!query.inner.to_string().contains(ASOF_JOIN_MARKER), "{sql}");
+        }
+
+        let sql = "SELECT * FROM metrics m ASOF JOIN hosts h USING (host)";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        let sql = "SELECT * FROM metrics m ASOF JOIN hosts h";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     pub fn test_parse_invalid_query() {
         let sql = "SELECT * FROM table_1 WHERE";
@@ -57,4 +211,61 @@ mod tests {
             .to_string()
             .contains("Expected an expression"));
     }
+
+    #[test]
+    pub fn test_parse_query_hints() {
+        let sql = "SELECT /*+ READ_REPLICA, MAX_SCAN_ROWS(1e7), NO_CACHE */ * FROM t1;; \
+           SELECT /* not a hint */ * FROM t2; \
+           SELECT * FROM t3 WHERE a IN (SELECT /*+ NO_CACHE */ a FROM t4); \
+           WITH c AS (SELECT /*+ NO_CACHE */ * FROM t5) SELECT * FROM c";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let hints = stmts
+            .iter()
+            .map(|stmt| match stmt {
+                Statement::Query(query) => query.hints.clone(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Hints {
+                read_replica: true,
+                max_scan_rows: Some(10_000_000),
+                no_cache: true,
+            },
+            hints[0]
+        );
+        assert!(hints[1].is_empty());
+        // Only the hints after the first SELECT of the statement are applied.
+        assert!(hints[2].is_empty());
+        assert!(hints[3].no_cache);
+
+        // Unknown hints are ignored.
+        let sql = "SELECT /*+ NO_SUCH_HINT, NO_CACHE */ * FROM t1";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::Query(query) => assert!(query.hints.no_cache),
+            _ => unreachable!(),
+        }
+        let sql = "SELECT /*+ NO_CACHE(1) */ * FROM t1";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+
+        // Hints of the explained query and the source query of INSERT are kept.
+        let sql = "EXPLAIN SELECT /*+ READ_REPLICA */ * FROM t1; \
+           INSERT INTO t1 SELECT /*+ READ_REPLICA */ * FROM t2";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::Explain(explain) => {
+                assert!(explain.hints.read_replica);
+                assert!(explain.query().unwrap().hints.read_replica);
+            }
+            _ => unreachable!(),
+        }
+        match &stmts[1] {
+            Statement::Insert(insert) => {
+                assert!(insert.hints.read_replica);
+                assert!(insert.query().unwrap().hints.read_replica);
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -12,15 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod alert;
 pub mod alter;
+pub mod analyze;
+pub mod check;
 pub mod create;
 pub mod describe;
 pub mod drop;
 pub mod explain;
+pub mod hint;
 pub mod insert;
+pub mod kill;
 pub mod query;
 pub mod show;
 pub mod statement;
+pub mod use_database;
 use std::str::FromStr;
 
 use api::helper::ColumnDataTypeWrapper;
@@ -28,15 +34,18 @@ use common_base::bytes::Bytes;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_time::Timestamp;
 use datatypes::data_type::DataType;
+use datatypes::decimal::{
+    self, Decimal128, DECIMAL128_DEFAULT_PRECISION, DECIMAL128_DEFAULT_SCALE,
+};
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
-use datatypes::types::DateTimeType;
+use datatypes::types::{DateTimeType, Decimal128Type, HistogramType};
 use datatypes::value::Value;
 use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::ast::{
-    ColumnDef, ColumnOption, ColumnOptionDef, DataType as SqlDataType, Expr, ObjectName,
-    Value as SqlValue,
+    ColumnDef, ColumnOption, ColumnOptionDef, DataType as SqlDataType, ExactNumberInfo, Expr,
+    ObjectName, Value as SqlValue,
 };
 use crate::error::{
     self, ColumnTypeMismatchSnafu, ConvertToGrpcDataTypeSnafu, InvalidSqlValueSnafu,
@@ -90,6 +99,7 @@ fn parse_string_to_value(
 
     match data_type {
         ConcreteDataType::String(_) => Ok(Value::String(s.into())),
+        ConcreteDataType::Binary(_) => Ok(Value::Binary(Bytes::from(s.into_bytes()))),
         ConcreteDataType::Date(_) => {
             if let Ok(date) = common_time::date::Date::from_str(&s) {
                 Ok(Value::Date(date))
@@ -170,6 +180,10 @@ macro_rules! parse_number_to_value {
 
 /// Convert a sql value into datatype's value
 pub fn sql_number_to_value(data_type: &ConcreteDataType, n: &str) -> Result<Value> {
+    if let ConcreteDataType::Decimal128(t) = data_type {
+        return parse_sql_decimal(t, n);
+    }
+
     parse_number_to_value!(
         data_type,
         n,
@@ -188,6 +202,16 @@ pub fn sql_number_to_value(data_type: &ConcreteDataType, n: &str) -> Result<Valu
     // TODO(hl): also Date/DateTime
 }
 
+fn parse_sql_decimal(decimal_type: &Decimal128Type, n: &str) -> Result<Value> {
+    let decimal = parse_sql_number::<Decimal128>(n)?;
+    let decimal = decimal
+        .rescale_to(decimal_type.precision(), decimal_type.scale())
+        .with_context(|| ParseSqlValueSnafu {
+            msg: format!("Fail to parse number {n}, overflow for {decimal_type:?}"),
+        })?;
+    Ok(Value::Decimal128(decimal))
+}
+
 fn parse_sql_number<R: FromStr + std::fmt::Debug>(n: &str) -> Result<R>
 where
     <R as FromStr>::Err: std::fmt::Debug,
@@ -224,12 +248,33 @@ pub fn sql_value_to_value(
         SqlValue::DoubleQuotedString(s) | SqlValue::SingleQuotedString(s) => {
             parse_string_to_value(column_name, s.to_owned(), data_type)?
         }
-        SqlValue::HexStringLiteral(s) => parse_hex_string(s)?,
+        SqlValue::HexStringLiteral(s) => {
+            ensure!(
+                matches!(data_type, ConcreteDataType::Binary(_)),
+                ColumnTypeMismatchSnafu {
+                    column_name,
+                    expect: data_type.clone(),
+                    actual: ConcreteDataType::binary_datatype(),
+                }
+            );
+
+            parse_hex_string(s)?
+        }
         SqlValue::Placeholder(s) => return InvalidSqlValueSnafu { value: s }.fail(),
         _ => todo!("Other sql value"),
     })
 }
 
+/// Returns the comment in `COMMENT 'xxx'` option of the column, empty if there is no comment.
+fn parse_column_comment(opts: &[ColumnOptionDef]) -> String {
+    opts.iter()
+        .find_map(|o| match &o.option {
+            ColumnOption::Comment(comment) => Some(comment.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
 fn parse_column_default_constraint(
     column_name: &str,
     data_type: &ConcreteDataType,
@@ -280,6 +325,7 @@ pub fn column_def_to_schema(column_def: &ColumnDef, is_time_index: bool) -> Resu
 
     ColumnSchema::new(name, data_type, is_nullable)
         .with_time_index(is_time_index)
+        .with_comment(parse_column_comment(&column_def.options))
         .with_default_constraint(default_constraint)
         .context(error::InvalidDefaultSnafu {
             column: &column_def.name.value,
@@ -309,6 +355,7 @@ pub fn sql_column_def_to_grpc_column_def(col: ColumnDef) -> Result<api::v1::Colu
         datatype: data_type,
         is_nullable,
         default_constraint: default_constraint.unwrap_or_default(),
+        comment: parse_column_comment(&col.options),
     })
 }
 
@@ -340,6 +387,11 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
                     .eq_ignore_ascii_case(DateTimeType::default().name())
                 {
                     Ok(ConcreteDataType::datetime_datatype())
+                } else if type_name
+                    .value
+                    .eq_ignore_ascii_case(HistogramType::default().name())
+                {
+                    Ok(ConcreteDataType::histogram_datatype())
                 } else {
                     error::SqlTypeNotSupportedSnafu {
                         t: data_type.clone(),
@@ -352,7 +404,36 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
             }
             .fail(),
         },
-        SqlDataType::Timestamp(_, _) => Ok(ConcreteDataType::timestamp_millisecond_datatype()),
+        SqlDataType::Timestamp(precision, _) => match precision {
+            None | Some(3) => Ok(ConcreteDataType::timestamp_millisecond_datatype()),
+            Some(0) => Ok(ConcreteDataType::timestamp_second_datatype()),
+            Some(6) => Ok(ConcreteDataType::timestamp_microsecond_datatype()),
+            Some(9) => Ok(ConcreteDataType::timestamp_nanosecond_datatype()),
+            _ => error::SqlTypeNotSupportedSnafu {
+                t: data_type.clone(),
+            }
+            .fail(),
+        },
+        SqlDataType::Decimal(info) | SqlDataType::Numeric(info) => {
+            let (precision, scale) = match info {
+                ExactNumberInfo::None => (
+                    DECIMAL128_DEFAULT_PRECISION as u64,
+                    DECIMAL128_DEFAULT_SCALE as u64,
+                ),
+                ExactNumberInfo::Precision(p) => (*p, 0),
+                ExactNumberInfo::PrecisionAndScale(p, s) => (*p, *s),
+            };
+            let not_supported = || {
+                error::SqlTypeNotSupportedSnafu {
+                    t: data_type.clone(),
+                }
+                .build()
+            };
+            let precision = u8::try_from(precision).map_err(|_| not_supported())?;
+            let scale = i8::try_from(scale).map_err(|_| not_supported())?;
+            decimal::validate_precision_and_scale(precision, scale).map_err(|_| not_supported())?;
+            Ok(ConcreteDataType::decimal128_datatype(precision, scale))
+        }
         _ => error::SqlTypeNotSupportedSnafu {
             t: data_type.clone(),
         }
@@ -413,10 +494,31 @@ mod tests {
             SqlDataType::Custom(ObjectName(vec![Ident::new("datetime")]), vec![]),
             ConcreteDataType::datetime_datatype(),
         );
+        check_type(
+            SqlDataType::Custom(ObjectName(vec![Ident::new("histogram")]), vec![]),
+            ConcreteDataType::histogram_datatype(),
+        );
         check_type(
             SqlDataType::Timestamp(None, TimezoneInfo::None),
             ConcreteDataType::timestamp_millisecond_datatype(),
         );
+        check_type(
+            SqlDataType::Timestamp(Some(0), TimezoneInfo::None),
+            ConcreteDataType::timestamp_second_datatype(),
+        );
+        check_type(
+            SqlDataType::Timestamp(Some(6), TimezoneInfo::None),
+            ConcreteDataType::timestamp_microsecond_datatype(),
+        );
+        check_type(
+            SqlDataType::Timestamp(Some(9), TimezoneInfo::None),
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+        );
+        assert!(sql_data_type_to_concrete_data_type(&SqlDataType::Timestamp(
+            Some(5),
+            TimezoneInfo::None
+        ))
+        .is_err());
         check_type(
             SqlDataType::Varbinary(None),
             ConcreteDataType::binary_datatype(),
@@ -437,6 +539,22 @@ mod tests {
             SqlDataType::UnsignedTinyInt(None),
             ConcreteDataType::uint8_datatype(),
         );
+        check_type(
+            SqlDataType::Decimal(ExactNumberInfo::None),
+            ConcreteDataType::decimal128_datatype(10, 0),
+        );
+        check_type(
+            SqlDataType::Decimal(ExactNumberInfo::PrecisionAndScale(20, 4)),
+            ConcreteDataType::decimal128_datatype(20, 4),
+        );
+        check_type(
+            SqlDataType::Numeric(ExactNumberInfo::Precision(12)),
+            ConcreteDataType::decimal128_datatype(12, 0),
+        );
+        assert!(sql_data_type_to_concrete_data_type(&SqlDataType::Decimal(
+            ExactNumberInfo::PrecisionAndScale(39, 2)
+        ))
+        .is_err());
     }
 
     #[test]
@@ -449,6 +567,13 @@ mod tests {
 
         let v = sql_number_to_value(&ConcreteDataType::string_datatype(), "999");
         assert!(v.is_err(), "parse value error is: {v:?}");
+
+        let v = sql_number_to_value(&ConcreteDataType::decimal128_datatype(10, 2), "3.5").unwrap();
+        assert_eq!(Value::Decimal128(Decimal128::new(350, 10, 2)), v);
+        let v = sql_number_to_value(&ConcreteDataType::decimal128_datatype(10, 2), "3.555");
+        assert_eq!(Value::Decimal128(Decimal128::new(356, 10, 2)), v.unwrap());
+        let v = sql_number_to_value(&ConcreteDataType::decimal128_datatype(4, 2), "123.4");
+        assert!(v.is_err(), "parse value error is: {v:?}");
     }
 
     #[test]
@@ -503,6 +628,18 @@ mod tests {
         let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
         assert!(v.is_err());
         assert!(format!("{v:?}").contains("invalid character"), "v is {v:?}",);
+
+        let sql_val = SqlValue::HexStringLiteral("48656c6c6f".to_string());
+        let v = sql_value_to_value("a", &ConcreteDataType::int32_datatype(), &sql_val);
+        assert!(v.is_err());
+
+        let sql_val = SqlValue::SingleQuotedString("Hello".to_string());
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val).unwrap();
+        assert_eq!(Value::Binary(Bytes::from(b"Hello".as_slice())), v);
+
+        let sql_val = SqlValue::Number("18446744073709551615".to_string(), false);
+        let v = sql_value_to_value("a", &ConcreteDataType::uint64_datatype(), &sql_val).unwrap();
+        assert_eq!(Value::UInt64(u64::MAX), v);
     }
 
     #[test]
@@ -676,6 +813,21 @@ mod tests {
 
         let grpc_column_def = sql_column_def_to_grpc_column_def(column_def).unwrap();
         assert!(!grpc_column_def.is_nullable);
+        assert!(grpc_column_def.comment.is_empty());
+
+        // test comment
+        let column_def = ColumnDef {
+            name: "col".into(),
+            data_type: SqlDataType::Double,
+            collation: None,
+            options: vec![ColumnOptionDef {
+                name: None,
+                option: ColumnOption::Comment("usage in percent".to_string()),
+            }],
+        };
+
+        let grpc_column_def = sql_column_def_to_grpc_column_def(column_def).unwrap();
+        assert_eq!("usage in percent", grpc_column_def.comment);
     }
 
     #[test]
@@ -723,6 +875,20 @@ mod tests {
         assert_eq!(ConcreteDataType::string_datatype(), column_schema.data_type);
         assert!(!column_schema.is_nullable());
         assert!(!column_schema.is_time_index());
+        assert!(column_schema.comment().is_none());
+
+        let column_def = ColumnDef {
+            name: "col3".into(),
+            data_type: SqlDataType::String,
+            collation: None,
+            options: vec![ColumnOptionDef {
+                name: None,
+                option: ColumnOption::Comment("host name".to_string()),
+            }],
+        };
+
+        let column_schema = column_def_to_schema(&column_def, false).unwrap();
+        assert_eq!(Some("host name"), column_schema.comment());
     }
 
     #[test]
@@ -0,0 +1,128 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+/// Expression evaluated by an alert rule, the rule fires while the expression
+/// returns any row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertExpr {
+    Sql(String),
+    Promql(String),
+}
+
+/// SQL structure for `CREATE ALERT RULE [IF NOT EXISTS] <name> EVERY '<interval>'
+/// WEBHOOK '<url>' AS <query | PROMQL '<expr>'>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateAlertRule {
+    pub name: String,
+    pub if_not_exists: bool,
+    /// Interval between two evaluations of the rule.
+    pub interval: Duration,
+    /// Url notified when the rule starts firing or is resolved.
+    pub webhook: String,
+    pub expr: AlertExpr,
+}
+
+/// SQL structure for `DROP ALERT RULE [IF EXISTS] <name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropAlertRule {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_create_alert_rule() {
+        let sql = "CREATE ALERT RULE high_cpu EVERY '1m' WEBHOOK 'http://127.0.0.1:8080/alert' \
+                   AS SELECT host FROM monitor WHERE cpu > 90";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_eq!(
+            Statement::CreateAlertRule(CreateAlertRule {
+                name: "high_cpu".to_string(),
+                if_not_exists: false,
+                interval: Duration::from_secs(60),
+                webhook: "http://127.0.0.1:8080/alert".to_string(),
+                expr: AlertExpr::Sql("SELECT host FROM monitor WHERE cpu > 90".to_string()),
+            }),
+            stmts[0]
+        );
+
+        let sql = "CREATE ALERT RULE IF NOT EXISTS up EVERY '30s' WEBHOOK 'http://a/b' \
+                   AS PROMQL 'up == 0'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::CreateAlertRule(CreateAlertRule {
+                name: "up".to_string(),
+                if_not_exists: true,
+                interval: Duration::from_secs(30),
+                webhook: "http://a/b".to_string(),
+                expr: AlertExpr::Promql("up == 0".to_string()),
+            }),
+            stmts[0]
+        );
+
+        for sql in [
+            "CREATE ALERT RULE r WEBHOOK 'http://a' AS SELECT 1",
+            "CREATE ALERT RULE r EVERY 'abc' WEBHOOK 'http://a' AS SELECT 1",
+            "CREATE ALERT RULE r EVERY '0s' WEBHOOK 'http://a' AS SELECT 1",
+            "CREATE ALERT RULE r EVERY '1m' AS SELECT 1",
+            "CREATE ALERT RULE r EVERY '1m' WEBHOOK 'http://a'",
+            "CREATE ALERT r EVERY '1m' WEBHOOK 'http://a' AS SELECT 1",
+        ] {
+            assert!(
+                ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err(),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_drop_and_show_alert_rules() {
+        let stmts =
+            ParserContext::create_with_dialect("DROP ALERT RULE r", &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::DropAlertRule(DropAlertRule {
+                name: "r".to_string(),
+                if_exists: false,
+            }),
+            stmts[0]
+        );
+
+        let stmts =
+            ParserContext::create_with_dialect("drop alert rule if exists r", &GenericDialect {})
+                .unwrap();
+        assert_eq!(
+            Statement::DropAlertRule(DropAlertRule {
+                name: "r".to_string(),
+                if_exists: true,
+            }),
+            stmts[0]
+        );
+
+        let stmts =
+            ParserContext::create_with_dialect("SHOW ALERT RULES", &GenericDialect {}).unwrap();
+        assert_eq!(Statement::ShowAlertRules, stmts[0]);
+
+        assert!(ParserContext::create_with_dialect("SHOW ALERT", &GenericDialect {}).is_err());
+    }
+}
@@ -12,10 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
 use api::v1::{alter_expr, AddColumn, AlterExpr, DropColumn};
-use sqlparser::ast::{ColumnDef, Ident, ObjectName, TableConstraint};
+use itertools::Itertools;
+use sqlparser::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint};
 
 use crate::error::UnsupportedAlterTableStatementSnafu;
+use crate::statements::create::{options_to_map, quote_string};
 use crate::statements::{sql_column_def_to_grpc_column_def, table_idents_to_full_name};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -45,12 +50,108 @@ impl AlterTable {
 pub enum AlterTableOperation {
     /// `ADD <table_constraint>`
     AddConstraint(TableConstraint),
-    /// `ADD [ COLUMN ] <column_def>`
-    AddColumn { column_def: ColumnDef },
+    /// `ADD [ COLUMN ] [ IF NOT EXISTS ] <column_def>`
+    AddColumn {
+        column_def: ColumnDef,
+        add_if_not_exists: bool,
+    },
     /// `DROP COLUMN <name>`
     DropColumn { name: Ident },
     /// `RENAME <new_table_name>`
     RenameTable { new_table_name: String },
+    /// `SET READ_ONLY = { true | false }`
+    SetReadOnly { read_only: bool },
+    /// `COMMENT [=] '<comment>'`
+    SetComment { comment: String },
+    /// `MODIFY [ COLUMN ] <name> COMMENT '<comment>'`
+    SetColumnComment { name: Ident, comment: String },
+}
+
+impl Display for AlterTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ALTER TABLE {} ", self.table_name)?;
+        match &self.alter_operation {
+            AlterTableOperation::AddConstraint(constraint) => write!(f, "ADD {constraint}"),
+            AlterTableOperation::AddColumn {
+                column_def,
+                add_if_not_exists,
+            } => {
+                f.write_str("ADD COLUMN ")?;
+                if *add_if_not_exists {
+                    f.write_str("IF NOT EXISTS ")?;
+                }
+                write!(f, "{column_def}")
+            }
+            AlterTableOperation::DropColumn { name } => write!(f, "DROP COLUMN {name}"),
+            AlterTableOperation::RenameTable { new_table_name } => {
+                write!(f, "RENAME {new_table_name}")
+            }
+            AlterTableOperation::SetReadOnly { read_only } => {
+                write!(f, "SET READ_ONLY = {read_only}")
+            }
+            AlterTableOperation::SetComment { comment } => {
+                write!(f, "COMMENT {}", quote_string(comment))
+            }
+            AlterTableOperation::SetColumnComment { name, comment } => {
+                write!(f, "MODIFY COLUMN {name} COMMENT {}", quote_string(comment))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlterDatabase {
+    name: ObjectName,
+    alter_operation: AlterDatabaseOperation,
+}
+
+impl AlterDatabase {
+    pub(crate) fn new(name: ObjectName, alter_operation: AlterDatabaseOperation) -> Self {
+        Self {
+            name,
+            alter_operation,
+        }
+    }
+
+    pub fn name(&self) -> &ObjectName {
+        &self.name
+    }
+
+    pub fn alter_operation(&self) -> &AlterDatabaseOperation {
+        &self.alter_operation
+    }
+}
+
+impl Display for AlterDatabase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ALTER DATABASE {} ", self.name)?;
+        match &self.alter_operation {
+            AlterDatabaseOperation::RenameDatabase { new_name } => {
+                write!(f, "RENAME TO {new_name}")
+            }
+            AlterDatabaseOperation::SetMetadata { options } => {
+                write!(f, "SET ({})", options.iter().join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlterDatabaseOperation {
+    /// `RENAME TO <new_name>`
+    RenameDatabase { new_name: String },
+    /// `SET (<key> = <value>, ...)`
+    SetMetadata { options: Vec<SqlOption> },
+}
+
+impl AlterDatabaseOperation {
+    /// Returns the metadata to set, the keys are in lowercase.
+    pub fn metadata(&self) -> HashMap<String, String> {
+        match self {
+            AlterDatabaseOperation::RenameDatabase { .. } => HashMap::new(),
+            AlterDatabaseOperation::SetMetadata { options } => options_to_map(options),
+        }
+    }
 }
 
 /// Convert `AlterTable` statement to `AlterExpr` for gRPC
@@ -67,14 +168,16 @@ impl TryFrom<AlterTable> for AlterExpr {
                 }
                 .fail();
             }
-            AlterTableOperation::AddColumn { column_def } => {
-                alter_expr::Kind::AddColumns(api::v1::AddColumns {
-                    add_columns: vec![AddColumn {
-                        column_def: Some(sql_column_def_to_grpc_column_def(column_def)?),
-                        is_key: false,
-                    }],
-                })
-            }
+            AlterTableOperation::AddColumn {
+                column_def,
+                add_if_not_exists,
+            } => alter_expr::Kind::AddColumns(api::v1::AddColumns {
+                add_columns: vec![AddColumn {
+                    column_def: Some(sql_column_def_to_grpc_column_def(column_def)?),
+                    is_key: false,
+                    add_if_not_exists,
+                }],
+            }),
             AlterTableOperation::DropColumn { name } => {
                 alter_expr::Kind::DropColumns(api::v1::DropColumns {
                     drop_columns: vec![DropColumn { name: name.value }],
@@ -83,6 +186,18 @@ impl TryFrom<AlterTable> for AlterExpr {
             AlterTableOperation::RenameTable { new_table_name } => {
                 alter_expr::Kind::RenameTable(api::v1::RenameTable { new_table_name })
             }
+            AlterTableOperation::SetReadOnly { read_only } => {
+                alter_expr::Kind::SetReadOnly(api::v1::SetReadOnly { read_only })
+            }
+            AlterTableOperation::SetComment { comment } => {
+                alter_expr::Kind::SetComment(api::v1::SetComment { comment })
+            }
+            AlterTableOperation::SetColumnComment { name, comment } => {
+                alter_expr::Kind::SetColumnComment(api::v1::SetColumnComment {
+                    column_name: name.value,
+                    comment,
+                })
+            }
         };
         let expr = AlterExpr {
             catalog_name,
@@ -0,0 +1,49 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+
+/// SQL structure for `ANALYZE TABLE <table>`, which collects statistics of the columns
+/// of the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzeTable {
+    pub table_name: ObjectName,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::Ident;
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_analyze_table() {
+        let stmts = ParserContext::create_with_dialect("ANALYZE TABLE db.demo", &GenericDialect {})
+            .unwrap();
+        assert_eq!(1, stmts.len());
+        assert_eq!(
+            Statement::AnalyzeTable(AnalyzeTable {
+                table_name: ObjectName(vec![Ident::new("db"), Ident::new("demo")]),
+            }),
+            stmts[0]
+        );
+
+        for sql in ["ANALYZE", "ANALYZE demo", "ANALYZE TABLE"] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+
+/// SQL structure for `CHECK TABLE <table>` and `REPAIR TABLE <table>`, which check whether
+/// files referenced by the table exist in the storage. `REPAIR TABLE` also removes
+/// references to missing files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckTable {
+    pub table_name: ObjectName,
+    pub repair: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::ast::Ident;
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_check_table() {
+        let stmts =
+            ParserContext::create_with_dialect("CHECK TABLE db.demo", &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_eq!(
+            Statement::CheckTable(CheckTable {
+                table_name: ObjectName(vec![Ident::new("db"), Ident::new("demo")]),
+                repair: false,
+            }),
+            stmts[0]
+        );
+
+        let stmts =
+            ParserContext::create_with_dialect("repair table demo", &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::CheckTable(CheckTable {
+                table_name: ObjectName(vec![Ident::new("demo")]),
+                repair: true,
+            }),
+            stmts[0]
+        );
+
+        for sql in ["CHECK", "CHECK demo", "CHECK TABLE", "REPAIR TABLE"] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+}
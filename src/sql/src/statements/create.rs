@@ -12,11 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use snafu::{ensure, OptionExt};
+
 use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
+use crate::error::{InvalidDatabaseOptionSnafu, Result};
 
 /// Time index name, used in table constraints.
 pub const TIME_INDEX: &str = "__time_index";
 
+/// Compression algorithms allowed in the `compression` database option.
+const VALID_COMPRESSIONS: [&str; 5] = ["none", "lz4", "snappy", "zstd", "gzip"];
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreateTable {
     /// Create if not exists
@@ -29,24 +39,208 @@ pub struct CreateTable {
     pub constraints: Vec<TableConstraint>,
     /// Table options in `WITH`.
     pub options: Vec<SqlOption>,
+    /// Comment of the table in `COMMENT 'xxx'`.
+    pub comment: Option<String>,
     pub partitions: Option<Partitions>,
 }
 
+impl CreateTable {
+    /// Returns the table options in `WITH`, the option names are in lowercase.
+    pub fn table_options(&self) -> HashMap<String, String> {
+        options_to_map(&self.options)
+    }
+}
+
+/// Formats the statement as SQL that could be parsed back to it.
+impl Display for CreateTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CREATE TABLE ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        let constraints = self.constraints.iter().map(|constraint| match constraint {
+            TableConstraint::Unique {
+                name: Some(name),
+                columns,
+                is_primary: false,
+            } if name.value == TIME_INDEX => format!("TIME INDEX ({})", columns.iter().join(", ")),
+            constraint => constraint.to_string(),
+        });
+        let definitions = self
+            .columns
+            .iter()
+            .map(|column| column.to_string())
+            .chain(constraints)
+            .join(", ");
+        write!(f, "{} ({definitions})", self.name)?;
+        if let Some(partitions) = &self.partitions {
+            write!(f, " {partitions}")?;
+        }
+        write!(f, " ENGINE={}", self.engine)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " COMMENT {}", quote_string(comment))?;
+        }
+        fmt_options(f, &self.options)
+    }
+}
+
+/// Quotes the string as a SQL string literal.
+pub(crate) fn quote_string(s: &str) -> SqlValue {
+    SqlValue::SingleQuotedString(s.to_string())
+}
+
+fn fmt_options(f: &mut Formatter<'_>, options: &[SqlOption]) -> std::fmt::Result {
+    if options.is_empty() {
+        return Ok(());
+    }
+    write!(f, " WITH ({})", options.iter().join(", "))
+}
+
+/// Converts options in `WITH` to a map, the option names are in lowercase and
+/// quoted string values are unquoted.
+pub(crate) fn options_to_map(options: &[SqlOption]) -> HashMap<String, String> {
+    options
+        .iter()
+        .map(|option| {
+            let value = match &option.value {
+                SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s) => s.clone(),
+                value => value.to_string(),
+            };
+            (option.name.value.to_lowercase(), value)
+        })
+        .collect()
+}
+
+/// The "PARTITION BY RANGE COLUMNS" clause. The partition columns decide which region a
+/// row is routed to, and they don't have to be part of the primary key, which only
+/// decides the order of rows inside a region.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Partitions {
     pub column_list: Vec<Ident>,
     pub entries: Vec<PartitionEntry>,
 }
 
+impl Display for Partitions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PARTITION BY RANGE COLUMNS ({}) ({})",
+            self.column_list.iter().join(", "),
+            self.entries.iter().join(", ")
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PartitionEntry {
     pub name: Ident,
     pub value_list: Vec<SqlValue>,
 }
 
+impl Display for PartitionEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PARTITION {} VALUES LESS THAN ({})",
+            self.name,
+            self.value_list.iter().join(", ")
+        )
+    }
+}
+
+/// `CREATE CATALOG [IF NOT EXISTS] <name>`, the catalog is created with a default schema.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateCatalog {
+    pub name: String,
+    /// Create if not exists
+    pub if_not_exists: bool,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreateDatabase {
     pub name: ObjectName,
     /// Create if not exists
     pub if_not_exists: bool,
+    /// Default options of tables created in the database, in `WITH`.
+    pub options: Vec<SqlOption>,
+}
+
+impl CreateDatabase {
+    /// Returns the database options in `WITH`, the option names are in lowercase.
+    pub fn options(&self) -> HashMap<String, String> {
+        options_to_map(&self.options)
+    }
+
+    /// Checks the database options in `WITH`, only `ttl`, `regions` and `compression` are
+    /// accepted.
+    pub fn validate_options(&self) -> Result<()> {
+        for (key, value) in self.options() {
+            match key.as_str() {
+                "ttl" => {
+                    let ttl = humantime::parse_duration(&value).map_err(|e| {
+                        InvalidDatabaseOptionSnafu {
+                            key: &key,
+                            msg: format!("invalid duration '{value}': {e}"),
+                        }
+                        .build()
+                    })?;
+                    ensure!(
+                        !ttl.is_zero(),
+                        InvalidDatabaseOptionSnafu {
+                            key: &key,
+                            msg: "ttl must be positive",
+                        }
+                    );
+                }
+                "regions" => {
+                    let regions =
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .with_context(|| InvalidDatabaseOptionSnafu {
+                                key: &key,
+                                msg: format!("'{value}' is not a valid number of regions"),
+                            })?;
+                    ensure!(
+                        regions > 0,
+                        InvalidDatabaseOptionSnafu {
+                            key: &key,
+                            msg: "regions must be positive",
+                        }
+                    );
+                }
+                "compression" => {
+                    ensure!(
+                        VALID_COMPRESSIONS.contains(&value.to_lowercase().as_str()),
+                        InvalidDatabaseOptionSnafu {
+                            key: &key,
+                            msg: format!(
+                                "unknown compression '{value}', expect one of {}",
+                                VALID_COMPRESSIONS.join(", ")
+                            ),
+                        }
+                    );
+                }
+                _ => {
+                    return InvalidDatabaseOptionSnafu {
+                        key: &key,
+                        msg: "unknown option",
+                    }
+                    .fail();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for CreateDatabase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CREATE DATABASE ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        write!(f, "{}", self.name)?;
+        fmt_options(f, &self.options)
+    }
 }
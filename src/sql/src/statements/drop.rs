@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::{Display, Formatter};
+
 use sqlparser::ast::ObjectName;
 
 /// DROP TABLE statement.
@@ -30,3 +32,32 @@ impl DropTable {
         &self.table_name
     }
 }
+
+impl Display for DropTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP TABLE {}", self.table_name)
+    }
+}
+
+/// UNDROP TABLE statement, recovers the latest dropped table with the name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndropTable {
+    table_name: ObjectName,
+}
+
+impl UndropTable {
+    /// Creates a statement for `UNDROP TABLE`
+    pub fn new(table_name: ObjectName) -> Self {
+        Self { table_name }
+    }
+
+    pub fn table_name(&self) -> &ObjectName {
+        &self.table_name
+    }
+}
+
+impl Display for UndropTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UNDROP TABLE {}", self.table_name)
+    }
+}
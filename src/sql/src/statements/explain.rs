@@ -12,21 +12,74 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
 use sqlparser::ast::Statement as SpStatement;
 
 use crate::error::Error;
+use crate::statements::hint::Hints;
+use crate::statements::query::Query;
+use crate::statements::statement::Statement;
 
 /// Explain statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Explain {
     pub inner: SpStatement,
+    /// Format of `EXPLAIN (FORMAT <format>) <query>`, which explains how the query is split
+    /// into fragments executed by the frontend and datanodes.
+    pub format: Option<ExplainFormat>,
+    /// Hints given by the comment `/*+ ... */` after the `SELECT` of the explained query.
+    pub hints: Hints,
 }
 
 impl TryFrom<SpStatement> for Explain {
     type Error = Error;
 
     fn try_from(value: SpStatement) -> Result<Self, Self::Error> {
-        Ok(Explain { inner: value })
+        Ok(Explain {
+            inner: value,
+            format: None,
+            hints: Hints::default(),
+        })
+    }
+}
+
+impl Explain {
+    /// Returns the query to explain with the hints, `None` if the statement to explain is
+    /// not a query.
+    pub fn query(&self) -> Option<Query> {
+        let mut query = match &self.inner {
+            SpStatement::Explain { statement, .. } => match statement.as_ref() {
+                SpStatement::Query(query) => Query::try_from(query.as_ref().clone()).ok()?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        query.hints = self.hints.clone();
+        Some(query)
+    }
+}
+
+/// Output formats of the distributed plan in `EXPLAIN (FORMAT <format>)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainFormat {
+    /// Shows the substrait plans shipped to datanodes.
+    Substrait,
+    /// Shows the whole distributed plan as a JSON document.
+    Json,
+}
+
+impl FromStr for ExplainFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "substrait" => Ok(ExplainFormat::Substrait),
+            "json" => Ok(ExplainFormat::Json),
+            _ => Err(format!(
+                "unknown explain format {s}, expect SUBSTRAIT or JSON"
+            )),
+        }
     }
 }
 
@@ -35,3 +88,82 @@ impl ToString for Explain {
         self.inner.to_string()
     }
 }
+
+/// SQL structure for `EXPLAIN DDL <statement>`, which validates the `CREATE TABLE` or
+/// `ALTER TABLE` statement against current metadata and reports what it would do,
+/// without applying anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainDdl {
+    pub statement: Box<Statement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+
+    #[test]
+    fn test_parse_explain_format() {
+        let sql = "EXPLAIN (FORMAT substrait) SELECT * FROM foo";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Explain(explain) = &stmts[0] else {
+            unreachable!()
+        };
+        assert_eq!(Some(ExplainFormat::Substrait), explain.format);
+        assert_eq!(
+            "SELECT * FROM foo",
+            explain.query().unwrap().inner.to_string()
+        );
+
+        let sql = "explain (format JSON) select * from foo";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Explain(explain) = &stmts[0] else {
+            unreachable!()
+        };
+        assert_eq!(Some(ExplainFormat::Json), explain.format);
+
+        let sql = "EXPLAIN SELECT * FROM foo";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Explain(explain) = &stmts[0] else {
+            unreachable!()
+        };
+        assert_eq!(None, explain.format);
+
+        for sql in [
+            "EXPLAIN (FORMAT graphviz) SELECT * FROM foo",
+            "EXPLAIN (FORMAT json SELECT * FROM foo",
+            "EXPLAIN (FORMAT json) ANALYZE SELECT * FROM foo",
+            "EXPLAIN (FORMAT json) INSERT INTO foo VALUES (1)",
+        ] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_explain_ddl() {
+        let sql = "EXPLAIN DDL CREATE TABLE foo (ts TIMESTAMP TIME INDEX, v DOUBLE)";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::ExplainDdl(explain) = &stmts[0] else {
+            unreachable!()
+        };
+        assert!(matches!(*explain.statement, Statement::CreateTable(_)));
+
+        let sql = "explain ddl ALTER TABLE foo ADD COLUMN k STRING";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::ExplainDdl(explain) = &stmts[0] else {
+            unreachable!()
+        };
+        assert!(matches!(*explain.statement, Statement::Alter(_)));
+
+        for sql in [
+            "EXPLAIN DDL",
+            "EXPLAIN DDL DROP TABLE foo",
+            "EXPLAIN DDL SELECT * FROM foo",
+        ] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+}
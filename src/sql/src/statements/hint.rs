@@ -0,0 +1,171 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+
+use common_telemetry::warn;
+
+use crate::error::{self, Result};
+
+/// Hints of a query, given by the comment `/*+ ... */` right after its `SELECT`, like
+/// `SELECT /*+ READ_REPLICA, MAX_SCAN_ROWS(1e7), NO_CACHE */ * FROM t`. They control how
+/// the query is executed without changing the state of the session.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Hints {
+    /// `READ_REPLICA`: regions of the tables are read from their followers instead of
+    /// their leaders if there are any, in the distributed mode.
+    pub read_replica: bool,
+    /// `MAX_SCAN_ROWS(n)`: the query fails once more than `n` rows are scanned from
+    /// any of its tables.
+    pub max_scan_rows: Option<u64>,
+    /// `NO_CACHE`: the plan of the query is neither looked up in nor added to the plan
+    /// cache.
+    pub no_cache: bool,
+}
+
+impl Hints {
+    pub fn is_empty(&self) -> bool {
+        *self == Hints::default()
+    }
+
+    /// Parses the `text` of the hint comment without the leading `+`. Hints are
+    /// separated by commas or whitespaces, and their names are case insensitive.
+    ///
+    /// Unknown hints are ignored with a warning, like other databases do, so that queries
+    /// with hints of newer versions still run.
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = |msg: String| error::InvalidSqlSnafu { msg }.build();
+
+        let mut hints = Hints::default();
+        let mut rest = text.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        while !rest.is_empty() {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Err(invalid(format!("Invalid hints /*+{text}*/")));
+            }
+            let (name, tail) = rest.split_at(len);
+            let tail = tail.trim_start();
+            let (arg, tail) = match tail.strip_prefix('(') {
+                Some(tail) => {
+                    let end = tail.find(')').ok_or_else(|| {
+                        invalid(format!("Unclosed arguments of hint {name} in /*+{text}*/"))
+                    })?;
+                    (Some(tail[..end].trim()), &tail[end + 1..])
+                }
+                None => (None, tail),
+            };
+
+            match (name.to_ascii_uppercase().as_str(), arg) {
+                ("READ_REPLICA", None) => hints.read_replica = true,
+                ("NO_CACHE", None) => hints.no_cache = true,
+                ("MAX_SCAN_ROWS", Some(arg)) => {
+                    let rows = parse_row_count(arg).ok_or_else(|| {
+                        invalid(format!("Invalid row count of hint {name} in /*+{text}*/"))
+                    })?;
+                    hints.max_scan_rows = Some(rows);
+                }
+                ("READ_REPLICA" | "NO_CACHE" | "MAX_SCAN_ROWS", _) => {
+                    return Err(invalid(format!(
+                        "Invalid arguments of hint {name} in /*+{text}*/"
+                    )));
+                }
+                _ => warn!("Ignored unknown hint {} in /*+{}*/", name, text),
+            }
+            rest = tail.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        }
+        Ok(hints)
+    }
+}
+
+/// Parses a non-negative integral row count, which could be written in scientific
+/// notation like `1e7`.
+fn parse_row_count(s: &str) -> Option<u64> {
+    if let Ok(rows) = s.parse::<u64>() {
+        return Some(rows);
+    }
+    let rows = s.parse::<f64>().ok()?;
+    (rows.is_finite() && rows >= 0.0 && rows.fract() == 0.0 && rows <= u64::MAX as f64)
+        .then_some(rows as u64)
+}
+
+impl Display for Hints {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut hints = Vec::new();
+        if self.read_replica {
+            hints.push("READ_REPLICA".to_string());
+        }
+        if let Some(rows) = self.max_scan_rows {
+            hints.push(format!("MAX_SCAN_ROWS({rows})"));
+        }
+        if self.no_cache {
+            hints.push("NO_CACHE".to_string());
+        }
+        write!(f, "/*+ {} */", hints.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hints() {
+        let hints = Hints::parse(" READ_REPLICA, MAX_SCAN_ROWS(1e7), NO_CACHE ").unwrap();
+        assert_eq!(
+            Hints {
+                read_replica: true,
+                max_scan_rows: Some(10_000_000),
+                no_cache: true,
+            },
+            hints
+        );
+        assert_eq!(
+            "/*+ READ_REPLICA, MAX_SCAN_ROWS(10000000), NO_CACHE */",
+            hints.to_string()
+        );
+
+        let hints = Hints::parse("no_cache max_scan_rows ( 100 )").unwrap();
+        assert!(!hints.read_replica);
+        assert_eq!(Some(100), hints.max_scan_rows);
+        assert!(hints.no_cache);
+
+        assert!(Hints::parse("").unwrap().is_empty());
+
+        let hints = Hints::parse("NO_CAHCE, INDEX(t idx), READ_REPLICA").unwrap();
+        assert_eq!(
+            Hints {
+                read_replica: true,
+                ..Default::default()
+            },
+            hints
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_hints() {
+        for text in [
+            "MAX_SCAN_ROWS",
+            "MAX_SCAN_ROWS(abc)",
+            "MAX_SCAN_ROWS(-1)",
+            "MAX_SCAN_ROWS(1.5)",
+            "MAX_SCAN_ROWS(100",
+            "NO_CACHE(1)",
+            "NO_CACHE; DROP",
+        ] {
+            assert!(Hints::parse(text).is_err(), "{text}");
+        }
+    }
+}
@@ -12,17 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
+
+use datatypes::data_type::DataType;
+use datatypes::prelude::MutableVector;
+use datatypes::schema::ColumnSchema;
+use datatypes::value::{self, ValueRef};
+use datatypes::vectors::VectorRef;
+use snafu::{ensure, OptionExt, ResultExt};
 use sqlparser::ast::{ObjectName, SetExpr, Statement, UnaryOperator, Values};
 use sqlparser::parser::ParserError;
 
 use crate::ast::{Expr, Value};
 use crate::error::{self, Result};
-use crate::statements::table_idents_to_full_name;
+use crate::statements::hint::Hints;
+use crate::statements::query::Query;
+use crate::statements::{sql_value_to_value, table_idents_to_full_name};
+
+const DEFAULT_PLACEHOLDER_VALUE: &str = "default";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Insert {
     // Can only be sqlparser::ast::Statement::Insert variant
     pub inner: Statement,
+    /// Hints given by the comment `/*+ ... */` after the `SELECT` of `INSERT ... SELECT`.
+    pub hints: Hints,
 }
 
 impl Insert {
@@ -48,58 +62,155 @@ impl Insert {
     }
 
     pub fn values(&self) -> Result<Vec<Vec<Value>>> {
-        let values = match &self.inner {
+        sql_exprs_to_values(self.value_rows()?)
+    }
+
+    /// Returns the number of rows in the `VALUES` list.
+    pub fn rows_num(&self) -> Result<usize> {
+        Ok(self.value_rows()?.len())
+    }
+
+    /// Converts the `VALUES` list into one vector for each of the `column_schemas`, the
+    /// columns the values are inserted to. Values are pushed to the vectors column by column
+    /// from the SQL expressions, without converting the rows first.
+    pub fn values_to_vectors(&self, column_schemas: &[&ColumnSchema]) -> Result<Vec<VectorRef>> {
+        let rows = self.value_rows()?;
+        for row in rows {
+            ensure!(
+                row.len() == column_schemas.len(),
+                error::ColumnValuesNumberMismatchSnafu {
+                    columns: column_schemas.len(),
+                    values: row.len(),
+                }
+            );
+        }
+
+        column_schemas
+            .iter()
+            .enumerate()
+            .map(|(i, column_schema)| {
+                let mut builder = column_schema.data_type.create_mutable_vector(rows.len());
+                // The default value of the column, created on first use.
+                let mut default = None;
+                for row in rows {
+                    push_expr_to_vector(column_schema, &row[i], &mut default, &mut builder)?;
+                }
+                Ok(builder.to_vector())
+            })
+            .collect()
+    }
+
+    /// Returns the source query of `INSERT ... SELECT` with the hints, `None` if the rows
+    /// to insert are given by `VALUES`.
+    pub fn query(&self) -> Option<Query> {
+        match &self.inner {
+            Statement::Insert { source, .. } if !matches!(&*source.body, SetExpr::Values(_)) => {
+                let mut query = Query::try_from(source.as_ref().clone()).ok()?;
+                query.hints = self.hints.clone();
+                Some(query)
+            }
+            _ => None,
+        }
+    }
+
+    fn value_rows(&self) -> Result<&[Vec<Expr>]> {
+        match &self.inner {
             Statement::Insert { source, .. } => match &*source.body {
-                SetExpr::Values(Values { rows, .. }) => sql_exprs_to_values(rows)?,
-                _ => unreachable!(),
+                SetExpr::Values(Values { rows, .. }) => Ok(rows),
+                body => error::ParseSqlValueSnafu {
+                    msg: format!("unsupported insert source: {body}"),
+                }
+                .fail(),
             },
             _ => unreachable!(),
-        };
-        Ok(values)
+        }
     }
 }
 
-fn sql_exprs_to_values(exprs: &Vec<Vec<Expr>>) -> Result<Vec<Vec<Value>>> {
-    let mut values = Vec::with_capacity(exprs.len());
-    for es in exprs.iter() {
-        let mut vs = Vec::with_capacity(es.len());
-        for expr in es.iter() {
-            vs.push(match expr {
-                Expr::Value(v) => v.clone(),
-                Expr::Identifier(ident) => {
-                    if ident.quote_style.is_none() {
-                        Value::Placeholder(ident.value.clone())
-                    } else {
-                        Value::SingleQuotedString(ident.value.clone())
-                    }
-                }
-                Expr::UnaryOp { op, expr }
-                    if matches!(op, UnaryOperator::Minus | UnaryOperator::Plus) =>
-                {
-                    if let Expr::Value(Value::Number(s, b)) = &**expr {
-                        match op {
-                            UnaryOperator::Minus => Value::Number(format!("-{s}"), *b),
-                            UnaryOperator::Plus => Value::Number(s.to_string(), *b),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        return error::ParseSqlValueSnafu {
-                            msg: format!("{expr:?}"),
-                        }
-                        .fail();
-                    }
+fn sql_exprs_to_values(exprs: &[Vec<Expr>]) -> Result<Vec<Vec<Value>>> {
+    exprs
+        .iter()
+        .map(|es| es.iter().map(sql_expr_to_value).collect())
+        .collect()
+}
+
+fn sql_expr_to_value(expr: &Expr) -> Result<Value> {
+    Ok(match expr {
+        Expr::Value(v) => v.clone(),
+        Expr::Identifier(ident) => {
+            if ident.quote_style.is_none() {
+                Value::Placeholder(ident.value.clone())
+            } else {
+                Value::SingleQuotedString(ident.value.clone())
+            }
+        }
+        Expr::UnaryOp { op, expr } if matches!(op, UnaryOperator::Minus | UnaryOperator::Plus) => {
+            if let Expr::Value(Value::Number(s, b)) = &**expr {
+                match op {
+                    UnaryOperator::Minus => Value::Number(format!("-{s}"), *b),
+                    UnaryOperator::Plus => Value::Number(s.to_string(), *b),
+                    _ => unreachable!(),
                 }
-                _ => {
-                    return error::ParseSqlValueSnafu {
-                        msg: format!("{expr:?}"),
-                    }
-                    .fail()
+            } else {
+                return error::ParseSqlValueSnafu {
+                    msg: format!("{expr:?}"),
                 }
-            });
+                .fail();
+            }
         }
-        values.push(vs);
-    }
-    Ok(values)
+        _ => {
+            return error::ParseSqlValueSnafu {
+                msg: format!("{expr:?}"),
+            }
+            .fail()
+        }
+    })
+}
+
+/// Pushes the value of `expr` to the `builder` of the column. String literals of string
+/// columns are pushed without copying them, and the `default` value of the column is
+/// created on first use and reused by the following rows.
+fn push_expr_to_vector(
+    column_schema: &ColumnSchema,
+    expr: &Expr,
+    default: &mut Option<value::Value>,
+    builder: &mut Box<dyn MutableVector>,
+) -> Result<()> {
+    let sql_val = match expr {
+        Expr::Value(v) => Cow::Borrowed(v),
+        _ => Cow::Owned(sql_expr_to_value(expr)?),
+    };
+    let value = match sql_val.as_ref() {
+        Value::Placeholder(s) if s.eq_ignore_ascii_case(DEFAULT_PLACEHOLDER_VALUE) => {
+            if default.is_none() {
+                let value = column_schema
+                    .create_default()
+                    .context(error::ColumnDefaultValueSnafu {
+                        column: &column_schema.name,
+                    })?
+                    .context(error::ColumnNoneDefaultValueSnafu {
+                        column: &column_schema.name,
+                    })?;
+                *default = Some(value);
+            }
+            // Safety: the default value is created above and has the type of the column.
+            builder
+                .push_value_ref(default.as_ref().unwrap().as_value_ref())
+                .unwrap();
+            return Ok(());
+        }
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s)
+            if column_schema.data_type.is_string() =>
+        {
+            // Safety: the builder is a string vector builder.
+            builder.push_value_ref(ValueRef::String(s)).unwrap();
+            return Ok(());
+        }
+        sql_val => sql_value_to_value(&column_schema.name, &column_schema.data_type, sql_val)?,
+    };
+    // Safety: the value is converted to the type of the column.
+    builder.push_value_ref(value.as_value_ref()).unwrap();
+    Ok(())
 }
 
 impl TryFrom<Statement> for Insert {
@@ -107,7 +218,10 @@ impl TryFrom<Statement> for Insert {
 
     fn try_from(value: Statement) -> std::result::Result<Self, Self::Error> {
         match value {
-            Statement::Insert { .. } => Ok(Insert { inner: value }),
+            Statement::Insert { .. } => Ok(Insert {
+                inner: value,
+                hints: Hints::default(),
+            }),
             unexp => Err(ParserError::ParserError(format!(
                 "Not expected to be {unexp}"
             ))),
@@ -117,9 +231,12 @@ impl TryFrom<Statement> for Insert {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use sqlparser::dialect::GenericDialect;
 
     use super::*;
+    use crate::error::Error;
     use crate::parser::ParserContext;
 
     #[test]
@@ -209,4 +326,83 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_insert_values_to_vectors() {
+        use common_error::prelude::{ErrorExt, StatusCode};
+        use datatypes::prelude::ConcreteDataType;
+        use datatypes::schema::ColumnDefaultConstraint;
+        use datatypes::vectors::{Int64Vector, StringVector};
+
+        use crate::statements::statement::Statement;
+
+        let host = ColumnSchema::new("host", ConcreteDataType::string_datatype(), true)
+            .with_default_constraint(Some(ColumnDefaultConstraint::Value(value::Value::String(
+                "localhost".into(),
+            ))))
+            .unwrap();
+        let cpu = ColumnSchema::new("cpu", ConcreteDataType::int64_datatype(), true);
+        let column_schemas = [&host, &cpu];
+
+        let sql = "INSERT INTO my_table VALUES('a', 1), (DEFAULT, -2), (\"c\", NULL)";
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0);
+        let insert = match stmt {
+            Statement::Insert(insert) => insert,
+            _ => unreachable!(),
+        };
+        assert_eq!(3, insert.rows_num().unwrap());
+        let vectors = insert.values_to_vectors(&column_schemas).unwrap();
+        let expected: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["a", "localhost", "c"])),
+            Arc::new(Int64Vector::from(vec![Some(1), Some(-2), None])),
+        ];
+        assert_eq!(expected, vectors);
+
+        // The number of values mismatches the columns.
+        let sql = "INSERT INTO my_table VALUES('a', 1), ('b')";
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0);
+        let insert = match stmt {
+            Statement::Insert(insert) => insert,
+            _ => unreachable!(),
+        };
+        let err = insert.values_to_vectors(&column_schemas).unwrap_err();
+        assert!(matches!(err, Error::ColumnValuesNumberMismatch { .. }));
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        // The column has no default value.
+        let sql = "INSERT INTO my_table VALUES('a', DEFAULT)";
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0);
+        let insert = match stmt {
+            Statement::Insert(insert) => insert,
+            _ => unreachable!(),
+        };
+        let cpu = ColumnSchema::new("cpu", ConcreteDataType::int64_datatype(), false);
+        let err = insert.values_to_vectors(&[&host, &cpu]).unwrap_err();
+        assert!(matches!(err, Error::ColumnNoneDefaultValue { .. }));
+    }
+
+    #[test]
+    fn test_insert_select() {
+        use crate::statements::statement::Statement;
+
+        let sql = "INSERT INTO my_table SELECT * FROM other_table";
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0);
+        match stmt {
+            Statement::Insert(insert) => {
+                assert!(insert.query().is_some());
+                assert!(insert.values().is_err());
+                assert!(insert.rows_num().is_err());
+                assert!(insert.values_to_vectors(&[]).is_err());
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -0,0 +1,41 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// SQL structure for `KILL [QUERY] <id>`, which cancels the running query `id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kill {
+    pub query_id: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_kill() {
+        for sql in ["KILL 42", "KILL QUERY 42", "kill query 42;"] {
+            let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            assert_eq!(1, stmts.len());
+            assert_eq!(Statement::Kill(Kill { query_id: 42 }), stmts[0]);
+        }
+
+        for sql in ["KILL", "KILL QUERY", "KILL CONNECTION 42", "KILL QUERY abc"] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+}
@@ -16,12 +16,22 @@ use datatypes::prelude::ConcreteDataType;
 use sqlparser::ast::Query as SpQuery;
 
 use crate::error::Error;
+use crate::statements::hint::Hints;
+
+/// Name of the function marking the condition of an `ASOF JOIN`.
+///
+/// sqlparser doesn't know `ASOF JOIN`, so `a ASOF JOIN b ON <cond>` is parsed as
+/// `a JOIN b ON __asof_join__() AND <cond>`, and the query planner turns joins with the
+/// marker into ASOF joins.
+pub const ASOF_JOIN_MARKER: &str = "__asof_join__";
 
 /// Query statement instance.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Query {
     pub inner: SpQuery,
     pub param_types: Vec<ConcreteDataType>,
+    /// Hints given by the comment `/*+ ... */` after the `SELECT` of the query.
+    pub hints: Hints,
 }
 
 /// Automatically converts from sqlparser Query instance to SqlQuery.
@@ -32,6 +42,7 @@ impl TryFrom<SpQuery> for Query {
         Ok(Query {
             inner: q,
             param_types: vec![],
+            hints: Hints::default(),
         })
     }
 }
@@ -14,7 +14,7 @@
 
 use std::fmt;
 
-use crate::ast::{Expr, Ident};
+use crate::ast::{Expr, Ident, ObjectName};
 
 /// Show kind for SQL expressions like `SHOW DATABASE` or `SHOW TABLE`
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,10 +54,24 @@ pub struct ShowTables {
     pub database: Option<String>,
 }
 
+/// SQL structure for `SHOW TABLE STATUS`, which shows the statistics of tables like MySQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowTableStatus {
+    pub kind: ShowKind,
+    pub database: Option<String>,
+}
+
 /// SQL structure for `SHOW CREATE TABLE`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowCreateTable {
-    pub table_name: String,
+    pub table_name: ObjectName,
+}
+
+/// SQL structure for `SHOW [FULL] PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowProcesslist {
+    /// Whether to show the full text of queries instead of a truncated one.
+    pub full: bool,
 }
 
 #[cfg(test)]
@@ -124,7 +138,7 @@ mod tests {
         assert_matches!(&stmts[0], Statement::ShowCreateTable { .. });
         match &stmts[0] {
             Statement::ShowCreateTable(show) => {
-                let table_name = show.table_name.as_str();
+                let table_name = show.table_name.to_string();
                 assert_eq!(table_name, "test");
             }
             _ => {
@@ -137,4 +151,53 @@ mod tests {
         let sql = "SHOW CREATE TABLE";
         ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
     }
+
+    #[test]
+    pub fn test_show_processlist() {
+        let stmts =
+            ParserContext::create_with_dialect("SHOW PROCESSLIST", &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::ShowProcesslist(ShowProcesslist { full: false }),
+            stmts[0]
+        );
+
+        let stmts = ParserContext::create_with_dialect("SHOW FULL PROCESSLIST", &GenericDialect {})
+            .unwrap();
+        assert_eq!(
+            Statement::ShowProcesslist(ShowProcesslist { full: true }),
+            stmts[0]
+        );
+
+        ParserContext::create_with_dialect("SHOW FULL TABLES", &GenericDialect {}).unwrap_err();
+    }
+
+    #[test]
+    pub fn test_show_table_status() {
+        let stmts =
+            ParserContext::create_with_dialect("SHOW TABLE STATUS", &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::ShowTableStatus(ShowTableStatus {
+                kind: ShowKind::All,
+                database: None,
+            }),
+            stmts[0]
+        );
+
+        let stmts = ParserContext::create_with_dialect(
+            "SHOW TABLE STATUS FROM test LIKE cpu",
+            &GenericDialect {},
+        )
+        .unwrap();
+        assert_eq!(
+            Statement::ShowTableStatus(ShowTableStatus {
+                kind: ShowKind::Like(Ident::new("cpu")),
+                database: Some("test".to_string()),
+            }),
+            stmts[0]
+        );
+
+        ParserContext::create_with_dialect("SHOW TABLE", &GenericDialect {}).unwrap_err();
+        ParserContext::create_with_dialect("SHOW TABLE STATUS test", &GenericDialect {})
+            .unwrap_err();
+    }
 }
@@ -12,14 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::statements::alter::AlterTable;
-use crate::statements::create::{CreateDatabase, CreateTable};
+use crate::statements::alert::{CreateAlertRule, DropAlertRule};
+use crate::statements::alter::{AlterDatabase, AlterTable};
+use crate::statements::analyze::AnalyzeTable;
+use crate::statements::check::CheckTable;
+use crate::statements::create::{CreateCatalog, CreateDatabase, CreateTable};
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
-use crate::statements::explain::Explain;
+use crate::statements::drop::{DropTable, UndropTable};
+use crate::statements::explain::{Explain, ExplainDdl};
 use crate::statements::insert::Insert;
+use crate::statements::kill::Kill;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::show::{
+    ShowCreateTable, ShowDatabases, ShowProcesslist, ShowTableStatus, ShowTables,
+};
+use crate::statements::use_database::UseDatabase;
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -33,21 +40,46 @@ pub enum Statement {
     CreateTable(CreateTable),
     // DROP TABLE
     DropTable(DropTable),
+    /// UNDROP TABLE
+    UndropTable(UndropTable),
+    /// CREATE CATALOG
+    CreateCatalog(CreateCatalog),
     // CREATE DATABASE
     CreateDatabase(CreateDatabase),
     /// ALTER TABLE
     Alter(AlterTable),
+    /// ALTER DATABASE
+    AlterDatabase(AlterDatabase),
     // Databases.
     ShowDatabases(ShowDatabases),
     // SHOW TABLES
     ShowTables(ShowTables),
+    // SHOW TABLE STATUS
+    ShowTableStatus(ShowTableStatus),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW [FULL] PROCESSLIST
+    ShowProcesslist(ShowProcesslist),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
-    Use(String),
+    // EXPLAIN DDL <CREATE TABLE | ALTER TABLE>
+    ExplainDdl(ExplainDdl),
+    /// USE [<catalog>.]<schema>
+    Use(UseDatabase),
+    // KILL [QUERY] <id>
+    Kill(Kill),
+    /// ANALYZE TABLE
+    AnalyzeTable(AnalyzeTable),
+    /// CHECK TABLE | REPAIR TABLE
+    CheckTable(CheckTable),
+    /// CREATE ALERT RULE
+    CreateAlertRule(CreateAlertRule),
+    /// DROP ALERT RULE
+    DropAlertRule(DropAlertRule),
+    /// SHOW ALERT RULES
+    ShowAlertRules,
 }
 
 /// Comment hints from SQL.
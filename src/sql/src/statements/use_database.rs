@@ -0,0 +1,56 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// SQL structure for `USE [<catalog>.]<schema>`, which switches the current schema, and
+/// the current catalog if given, of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UseDatabase {
+    pub catalog_name: Option<String>,
+    pub schema_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    #[test]
+    fn test_parse_use() {
+        let stmts = ParserContext::create_with_dialect("USE public", &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::Use(UseDatabase {
+                catalog_name: None,
+                schema_name: "public".to_string(),
+            })],
+            stmts
+        );
+
+        let stmts =
+            ParserContext::create_with_dialect("use tenant_a.public;", &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::Use(UseDatabase {
+                catalog_name: Some("tenant_a".to_string()),
+                schema_name: "public".to_string(),
+            })],
+            stmts
+        );
+
+        for sql in ["USE", "USE a.b.c"] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+}
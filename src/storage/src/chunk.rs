@@ -16,17 +16,17 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
-use common_telemetry::debug;
+use common_telemetry::{debug, warn};
 use common_time::range::TimestampRange;
-use snafu::ResultExt;
-use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
+use snafu::{ensure, ResultExt};
+use store_api::storage::{Chunk, ChunkReader, DedupStrategy, SchemaRef, SequenceNumber};
 use table::predicate::{Predicate, TimeRangePredicateBuilder};
 
 use crate::error::{self, Error, Result};
 use crate::memtable::{IterContext, MemtableRef};
-use crate::read::{BoxedBatchReader, DedupReader, MergeReaderBuilder};
+use crate::read::{BoxedBatchReader, ConcatReader, DedupReader, IterReader, MergeReaderBuilder};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
-use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
+use crate::sst::{AccessLayerRef, FileHandle, FileQuarantineRef, LevelMetas, ReadOptions, Visitor};
 
 /// Chunk reader implementation.
 // Now we use async-trait to implement the chunk reader, which is easier to implement than
@@ -35,6 +35,8 @@ use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 pub struct ChunkReaderImpl {
     schema: ProjectedSchemaRef,
     batch_reader: BoxedBatchReader,
+    /// Schema of output chunks if the sequence of rows is also needed.
+    schema_with_sequence: Option<SchemaRef>,
 }
 
 #[async_trait]
@@ -42,7 +44,9 @@ impl ChunkReader for ChunkReaderImpl {
     type Error = Error;
 
     fn schema(&self) -> &SchemaRef {
-        self.schema.projected_user_schema()
+        self.schema_with_sequence
+            .as_ref()
+            .unwrap_or_else(|| self.schema.projected_user_schema())
     }
 
     async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
@@ -51,7 +55,11 @@ impl ChunkReader for ChunkReaderImpl {
             None => return Ok(None),
         };
 
-        let chunk = self.schema.batch_to_chunk(&batch);
+        let chunk = if self.schema_with_sequence.is_some() {
+            self.schema.batch_to_chunk_with_sequence(&batch)
+        } else {
+            self.schema.batch_to_chunk(&batch)
+        };
 
         Ok(Some(chunk))
     }
@@ -62,8 +70,19 @@ impl ChunkReaderImpl {
         ChunkReaderImpl {
             schema,
             batch_reader,
+            schema_with_sequence: None,
         }
     }
+
+    /// Appends the sequence of rows to the output chunks.
+    fn with_sequence(mut self) -> Result<ChunkReaderImpl> {
+        let schema = self
+            .schema
+            .projected_user_schema_with_sequence()
+            .context(error::InvalidProjectionSnafu)?;
+        self.schema_with_sequence = Some(schema);
+        Ok(self)
+    }
 }
 
 /// Builder to create a new [ChunkReaderImpl] from scan request.
@@ -71,10 +90,14 @@ pub struct ChunkReaderBuilder {
     schema: RegionSchemaRef,
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
+    with_sequence: bool,
+    dedup_strategy: DedupStrategy,
+    append_mode: bool,
     sst_layer: AccessLayerRef,
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
     files_to_read: Vec<FileHandle>,
+    file_quarantine: Option<FileQuarantineRef>,
 }
 
 impl ChunkReaderBuilder {
@@ -83,10 +106,14 @@ impl ChunkReaderBuilder {
             schema,
             projection: None,
             filters: vec![],
+            with_sequence: false,
+            dedup_strategy: DedupStrategy::default(),
+            append_mode: false,
             sst_layer,
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
             files_to_read: Vec::new(),
+            file_quarantine: None,
         }
     }
 
@@ -106,6 +133,33 @@ impl ChunkReaderBuilder {
         self
     }
 
+    pub fn with_sequence(mut self, with_sequence: bool) -> Self {
+        self.with_sequence = with_sequence;
+        self
+    }
+
+    /// Sets the strategy to resolve rows with the same key.
+    pub fn dedup_strategy(mut self, strategy: DedupStrategy) -> Self {
+        self.dedup_strategy = strategy;
+        self.update_keep_duplicates();
+        self
+    }
+
+    /// Sets whether the region is append-only, rows of append-only regions are read in
+    /// the order of their sources and are not deduplicated.
+    pub fn append_mode(mut self, append_mode: bool) -> Self {
+        self.append_mode = append_mode;
+        self.update_keep_duplicates();
+        self
+    }
+
+    fn update_keep_duplicates(&mut self) {
+        // Memtables dedup rows by themselves, so we need to ask them to keep duplicate
+        // rows if the strategy is not keeping the last row or rows are not deduplicated.
+        self.iter_ctx.keep_duplicates =
+            self.append_mode || self.dedup_strategy != DedupStrategy::KeepLast;
+    }
+
     pub fn batch_size(mut self, batch_size: usize) -> Self {
         self.iter_ctx.batch_size = batch_size;
         self
@@ -121,6 +175,12 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Sets the quarantine of files failed to read, quarantined files are not read again.
+    pub fn file_quarantine(mut self, quarantine: FileQuarantineRef) -> Self {
+        self.file_quarantine = Some(quarantine);
+        self
+    }
+
     pub fn pick_ssts(mut self, ssts: &LevelMetas) -> Result<Self> {
         ssts.visit_levels(&mut self)?;
 
@@ -134,14 +194,10 @@ impl ChunkReaderBuilder {
                 .context(error::InvalidProjectionSnafu)?,
         );
 
-        let num_sources = self.memtables.len() + self.files_to_read.len();
-        let mut reader_builder = MergeReaderBuilder::with_capacity(schema.clone(), num_sources)
-            .batch_size(self.iter_ctx.batch_size);
-
         self.iter_ctx.projected_schema = Some(schema.clone());
+        let mut memtable_iters = Vec::with_capacity(self.memtables.len());
         for mem in self.memtables {
-            let iter = mem.iter(&self.iter_ctx)?;
-            reader_builder = reader_builder.push_batch_iter(iter);
+            memtable_iters.push(mem.iter(&self.iter_ctx)?);
         }
 
         let read_opts = ReadOptions {
@@ -149,6 +205,7 @@ impl ChunkReaderBuilder {
             projected_schema: schema.clone(),
             predicate: Predicate::new(self.filters),
         };
+        let mut sst_readers = Vec::with_capacity(self.files_to_read.len());
         for file in &self.files_to_read {
             if !Self::file_in_range(file, time_range_predicate) {
                 debug!(
@@ -157,18 +214,77 @@ impl ChunkReaderBuilder {
                 );
                 continue;
             }
-            let reader = self
-                .sst_layer
-                .read_sst(file.file_name(), &read_opts)
-                .await?;
+            let reader = Self::read_sst(
+                &self.sst_layer,
+                self.file_quarantine.as_ref(),
+                file,
+                &read_opts,
+            )
+            .await?;
+
+            sst_readers.push(reader);
+        }
 
-            reader_builder = reader_builder.push_batch_reader(reader);
+        let reader: BoxedBatchReader = if self.append_mode {
+            let readers = memtable_iters
+                .into_iter()
+                .map(|iter| Box::new(IterReader::new(iter)) as BoxedBatchReader)
+                .chain(sst_readers)
+                .collect();
+            Box::new(ConcatReader::new(readers))
+        } else {
+            let num_sources = memtable_iters.len() + sst_readers.len();
+            let mut reader_builder = MergeReaderBuilder::with_capacity(schema.clone(), num_sources)
+                .batch_size(self.iter_ctx.batch_size);
+            for iter in memtable_iters {
+                reader_builder = reader_builder.push_batch_iter(iter);
+            }
+            for reader in sst_readers {
+                reader_builder = reader_builder.push_batch_reader(reader);
+            }
+            Box::new(
+                DedupReader::new(schema.clone(), reader_builder.build())
+                    .strategy(self.dedup_strategy),
+            )
+        };
+
+        let chunk_reader = ChunkReaderImpl::new(schema, reader);
+        if self.with_sequence {
+            chunk_reader.with_sequence()
+        } else {
+            Ok(chunk_reader)
         }
+    }
 
-        let reader = reader_builder.build();
-        let reader = DedupReader::new(schema.clone(), reader);
+    /// Reads the SST `file`, the file is quarantined if it fails to decode.
+    async fn read_sst(
+        sst_layer: &AccessLayerRef,
+        quarantine: Option<&FileQuarantineRef>,
+        file: &FileHandle,
+        read_opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
+        let file_name = file.file_name();
+        let Some(quarantine) = quarantine else {
+            return sst_layer.read_sst(file_name, file.tier(), read_opts).await;
+        };
+        ensure!(
+            !quarantine.contains(file_name),
+            error::QuarantinedSstSnafu { file: file_name }
+        );
 
-        Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
+        sst_layer
+            .read_sst(file_name, file.tier(), read_opts)
+            .await
+            .map_err(|e| {
+                if e.is_corrupted_sst() {
+                    warn!(
+                        "Quarantine SST file {} failed to read, err: {:?}",
+                        file_name, e
+                    );
+                    quarantine.add(file_name);
+                }
+                e
+            })
     }
 
     /// Build time range predicate from schema and filters.
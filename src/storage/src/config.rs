@@ -14,5 +14,33 @@
 
 //! storage engine config
 
+use object_store::ObjectStore;
+
+use crate::data_dir::DataDir;
+use crate::encryption::EncryptorRef;
+use crate::listener::EventListenerRef;
+use crate::wal::WalRetentionRef;
+use crate::write_behind::SstUploaderRef;
+
 #[derive(Debug, Default, Clone)]
-pub struct EngineConfig {}
+pub struct EngineConfig {
+    /// Uploader of SST files if flushed SST files are written to local disk first and
+    /// uploaded to the object store in background. Ignored if `data_dirs` is not empty.
+    pub sst_uploader: Option<SstUploaderRef>,
+    /// Local directories to place regions to, a new region is placed to the directory with
+    /// the most available space. Regions are placed to the object store of the engine if empty.
+    pub data_dirs: Vec<DataDir>,
+    /// Encryptor of SST files and WAL entries, data is not encrypted if not set.
+    pub encryptor: Option<EncryptorRef>,
+    /// Whether to store token indexes of string columns in SST files, which prune row
+    /// groups for `LIKE` and `matches()` filters.
+    pub sst_token_index: bool,
+    /// Listeners of engine events, notified after the default listener that logs events
+    /// and records metrics.
+    pub event_listeners: Vec<EventListenerRef>,
+    /// Object store of the cold tier, SST files of tables with the `cold_after` option are
+    /// moved to it once all their rows are old enough. Files are never moved if not set.
+    pub cold_store: Option<ObjectStore>,
+    /// Keeps flushed entries in the WAL, flushed entries are purged if not set.
+    pub wal_retention: Option<WalRetentionRef>,
+}
@@ -0,0 +1,168 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local data directories to place regions to, so regions could spread across several
+//! drives without RAID.
+
+use common_telemetry::logging;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+
+/// A local data directory regions could be placed to.
+#[derive(Debug, Clone)]
+pub struct DataDir {
+    path: String,
+    object_store: ObjectStore,
+}
+
+impl DataDir {
+    /// Creates a data directory in `path`, `object_store` must be rooted at `path`.
+    pub fn new(path: &str, object_store: ObjectStore) -> DataDir {
+        DataDir {
+            path: path.to_string(),
+            object_store,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn object_store(&self) -> &ObjectStore {
+        &self.object_store
+    }
+
+    /// Returns the bytes available to the process in the disk of the directory.
+    fn available_space(&self) -> std::io::Result<u64> {
+        available_space(&self.path)
+    }
+}
+
+#[cfg(unix)]
+fn available_space(path: &str) -> std::io::Result<u64> {
+    use std::ffi::CString;
+
+    let c_path =
+        CString::new(path).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // Safety: `stat` is a plain C struct and is only read after `statvfs` fills it.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // Types of the fields differ between platforms.
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+    Ok(available)
+}
+
+#[cfg(not(unix))]
+fn available_space(_path: &str) -> std::io::Result<u64> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "available space of disk is only supported on unix",
+    ))
+}
+
+/// Picks the directory with the most available space to place a new region, the first
+/// directory is picked if there is a tie. Returns `None` if `dirs` is empty.
+pub(crate) fn pick_data_dir(dirs: &[DataDir]) -> Option<&DataDir> {
+    dirs.iter()
+        .map(|dir| {
+            let available = dir.available_space().unwrap_or_else(|e| {
+                logging::warn!(
+                    "Failed to get available space of data dir {}, err: {}",
+                    dir.path,
+                    e
+                );
+                0
+            });
+            (dir, available)
+        })
+        .rev()
+        .max_by_key(|(_, available)| *available)
+        .map(|(dir, _)| dir)
+}
+
+/// Finds the directory holding the region whose manifest is in `manifest_dir`.
+pub(crate) async fn find_data_dir<'a>(
+    dirs: &'a [DataDir],
+    manifest_dir: &str,
+) -> Result<Option<&'a DataDir>> {
+    for dir in dirs {
+        let exists = dir
+            .object_store
+            .object(manifest_dir)
+            .is_exist()
+            .await
+            .context(error::ReadObjectSnafu { path: manifest_dir })?;
+        if exists {
+            return Ok(Some(dir));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::backend::fs::Builder;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn new_data_dir(dir: &TempDir) -> DataDir {
+        let path = dir.path().to_str().unwrap();
+        let accessor = Builder::default().root(path).build().unwrap();
+        DataDir::new(path, ObjectStore::new(accessor))
+    }
+
+    #[test]
+    fn test_pick_data_dir() {
+        assert!(pick_data_dir(&[]).is_none());
+
+        let dir1 = TempDir::new("test_pick_data_dir").unwrap();
+        let dir2 = TempDir::new("test_pick_data_dir").unwrap();
+        let dirs = [new_data_dir(&dir1), new_data_dir(&dir2)];
+        assert!(dirs[0].available_space().unwrap() > 0);
+        assert!(pick_data_dir(&dirs).is_some());
+
+        // Directories failed to get the available space are picked last.
+        let missing = DataDir::new("/no/such/dir", dirs[0].object_store().clone());
+        let dirs = [missing, new_data_dir(&dir2)];
+        let picked = pick_data_dir(&dirs).unwrap();
+        assert_eq!(dirs[1].path(), picked.path());
+    }
+
+    #[tokio::test]
+    async fn test_find_data_dir() {
+        let dir1 = TempDir::new("test_find_data_dir").unwrap();
+        let dir2 = TempDir::new("test_find_data_dir").unwrap();
+        let dirs = [new_data_dir(&dir1), new_data_dir(&dir2)];
+        dirs[1]
+            .object_store()
+            .object("region/manifest/00000000000000000000.json")
+            .write("{}")
+            .await
+            .unwrap();
+
+        let found = find_data_dir(&dirs, "region/manifest/").await.unwrap();
+        assert_eq!(dirs[1].path(), found.unwrap().path());
+        assert!(find_data_dir(&dirs, "other/manifest/")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
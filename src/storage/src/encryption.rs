@@ -0,0 +1,335 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encryption of data at rest.
+//!
+//! Data is encrypted by AES-256-GCM with keys from a [KeyProvider]. The ciphertext is bound
+//! to where the data belongs by associated data, so encrypted SST files or WAL entries can't
+//! be swapped with each other, see [sst_associated_data] and [wal_associated_data].
+//!
+//! Plain data is rejected once encryption is enabled, so data can't be replaced by plain
+//! data silently. Data written before encryption is enabled is only readable while migrating,
+//! see [Encryptor::with_allow_plaintext].
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use snafu::{ensure, OptionExt};
+use store_api::storage::{RegionId, SequenceNumber};
+
+use crate::error::{self, Result};
+
+/// Magic number of encrypted data. It can't be the start of SST files or WAL entries, which
+/// start with `PAR1` and a small varint respectively.
+const MAGIC: &[u8; 4] = b"\xffGEC";
+const NONCE_LEN: usize = 12;
+
+/// Length of data keys in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// A 256-bit data key.
+pub type DataKey = [u8; KEY_LEN];
+
+/// Provides keys to encrypt and decrypt data, like a client of a key management service.
+pub trait KeyProvider: Send + Sync + Debug {
+    /// Returns the id of the key to encrypt new data.
+    fn current_key_id(&self) -> String;
+
+    /// Returns the key with `key_id`.
+    fn key(&self, key_id: &str) -> Result<DataKey>;
+}
+
+pub type KeyProviderRef = Arc<dyn KeyProvider>;
+
+/// [KeyProvider] with keys given in config.
+///
+/// Keys no longer used to encrypt new data should be kept to decrypt old data.
+#[derive(Debug)]
+pub struct StaticKeyProvider {
+    current_key_id: String,
+    keys: HashMap<String, DataKey>,
+}
+
+impl StaticKeyProvider {
+    /// Creates a provider with hex-encoded `keys`, new data is encrypted by the key with
+    /// `current_key_id`.
+    pub fn try_new(current_key_id: &str, keys: &HashMap<String, String>) -> Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|(key_id, key)| {
+                let key = hex::decode(key)
+                    .ok()
+                    .and_then(|key| DataKey::try_from(key).ok())
+                    .with_context(|| error::InvalidEncryptionKeySnafu {
+                        key_id,
+                        reason: format!("expect {KEY_LEN} bytes in hex"),
+                    })?;
+                Ok((key_id.clone(), key))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        ensure!(
+            keys.contains_key(current_key_id),
+            error::UnknownEncryptionKeySnafu {
+                key_id: current_key_id,
+            }
+        );
+
+        Ok(Self {
+            current_key_id: current_key_id.to_string(),
+            keys,
+        })
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn current_key_id(&self) -> String {
+        self.current_key_id.clone()
+    }
+
+    fn key(&self, key_id: &str) -> Result<DataKey> {
+        self.keys
+            .get(key_id)
+            .copied()
+            .context(error::UnknownEncryptionKeySnafu { key_id })
+    }
+}
+
+/// Returns the associated data of the SST file in `file_path`, which is its file name. File
+/// names are unique among all regions, and the dir is not bound as tables could be moved.
+pub(crate) fn sst_associated_data(file_path: &str) -> &[u8] {
+    file_path.rsplit('/').next().unwrap_or(file_path).as_bytes()
+}
+
+/// Returns the associated data of the WAL entry with `sequence` of the region.
+pub(crate) fn wal_associated_data(region_id: RegionId, sequence: SequenceNumber) -> Vec<u8> {
+    format!("wal/{region_id}/{sequence}").into_bytes()
+}
+
+/// Encrypts and decrypts data by AES-256-GCM.
+#[derive(Debug)]
+pub struct Encryptor {
+    key_provider: KeyProviderRef,
+    allow_plaintext: bool,
+}
+
+pub type EncryptorRef = Arc<Encryptor>;
+
+impl Encryptor {
+    pub fn new(key_provider: KeyProviderRef) -> Encryptor {
+        Encryptor {
+            key_provider,
+            allow_plaintext: false,
+        }
+    }
+
+    /// Returns plain data as is on decryption if `allow_plaintext` is true, which should
+    /// only be set while data written before encryption is enabled still exists.
+    pub fn with_allow_plaintext(mut self, allow_plaintext: bool) -> Encryptor {
+        self.allow_plaintext = allow_plaintext;
+        self
+    }
+
+    /// Encrypts `data` by the current key, the ciphertext is bound to the associated data
+    /// `aad`, which is required to decrypt it.
+    ///
+    /// Data format:
+    ///
+    /// ```text
+    /// +-------+---------------------+--------+-------+---------------------+
+    /// | Magic | Key id length (u8)  | Key id | Nonce | Ciphertext with tag |
+    /// +-------+---------------------+--------+-------+---------------------+
+    /// ```
+    pub fn encrypt(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let key_id = self.key_provider.current_key_id();
+        ensure!(
+            key_id.len() <= u8::MAX as usize,
+            error::InvalidEncryptionKeySnafu {
+                key_id: &key_id,
+                reason: "key id is too long",
+            }
+        );
+        let cipher = self.cipher(&key_id)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload { msg: data, aad };
+        let ciphertext = cipher.encrypt(&nonce, payload).map_err(|e| {
+            error::EncryptSnafu {
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+
+        let mut buf =
+            Vec::with_capacity(MAGIC.len() + 1 + key_id.len() + NONCE_LEN + ciphertext.len());
+        buf.extend_from_slice(MAGIC);
+        buf.push(key_id.len() as u8);
+        buf.extend_from_slice(key_id.as_bytes());
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(&ciphertext);
+        Ok(buf)
+    }
+
+    /// Decrypts `data` encrypted by [Encryptor::encrypt] with the same associated data
+    /// `aad`. Plain data is rejected unless plaintext is allowed, then it's returned as is.
+    pub fn decrypt<'a>(&self, data: &'a [u8], aad: &[u8]) -> Result<Cow<'a, [u8]>> {
+        let rest = match data.strip_prefix(MAGIC.as_slice()) {
+            Some(rest) => rest,
+            None if self.allow_plaintext => return Ok(Cow::Borrowed(data)),
+            None => {
+                return error::DecryptSnafu {
+                    reason: "data is not encrypted",
+                }
+                .fail()
+            }
+        };
+        let (key_id_len, rest) = rest.split_first().context(error::DecryptSnafu {
+            reason: "data is truncated",
+        })?;
+        let key_id_len = *key_id_len as usize;
+        ensure!(
+            rest.len() >= key_id_len + NONCE_LEN,
+            error::DecryptSnafu {
+                reason: "data is truncated",
+            }
+        );
+        let (key_id, rest) = rest.split_at(key_id_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let key_id = std::str::from_utf8(key_id).map_err(|_| {
+            error::DecryptSnafu {
+                reason: "key id is not utf8",
+            }
+            .build()
+        })?;
+
+        let cipher = self.cipher(key_id)?;
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|e| {
+                error::DecryptSnafu {
+                    reason: format!("{e}, key id: {key_id}"),
+                }
+                .build()
+            })?;
+        Ok(Cow::Owned(plaintext))
+    }
+
+    fn cipher(&self, key_id: &str) -> Result<Aes256Gcm> {
+        let key = self.key_provider.key(key_id)?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+}
+
+/// Decrypts `data` bound to `aad` if `encryptor` is given.
+pub(crate) fn maybe_decrypt<'a>(
+    encryptor: Option<&EncryptorRef>,
+    data: &'a [u8],
+    aad: &[u8],
+) -> Result<Cow<'a, [u8]>> {
+    match encryptor {
+        Some(encryptor) => encryptor.decrypt(data, aad),
+        None => Ok(Cow::Borrowed(data)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn new_key_provider(current_key_id: &str) -> KeyProviderRef {
+        let keys = HashMap::from([
+            ("k1".to_string(), "11".repeat(KEY_LEN)),
+            ("k2".to_string(), "22".repeat(KEY_LEN)),
+        ]);
+        Arc::new(StaticKeyProvider::try_new(current_key_id, &keys).unwrap())
+    }
+
+    pub(crate) fn new_encryptor(current_key_id: &str) -> EncryptorRef {
+        Arc::new(Encryptor::new(new_key_provider(current_key_id)))
+    }
+
+    /// Returns an encryptor still reading plain data.
+    pub(crate) fn new_migrating_encryptor(current_key_id: &str) -> EncryptorRef {
+        Arc::new(Encryptor::new(new_key_provider(current_key_id)).with_allow_plaintext(true))
+    }
+
+    #[test]
+    fn test_static_key_provider() {
+        let provider = StaticKeyProvider::try_new(
+            "k1",
+            &HashMap::from([("k1".to_string(), "ab".repeat(KEY_LEN))]),
+        )
+        .unwrap();
+        assert_eq!("k1", provider.current_key_id());
+        assert_eq!([0xab; KEY_LEN], provider.key("k1").unwrap());
+        assert!(provider.key("k2").is_err());
+
+        // Unknown current key.
+        assert!(StaticKeyProvider::try_new("k2", &HashMap::new()).is_err());
+        // Invalid keys.
+        for key in ["ab", "zz"] {
+            let keys = HashMap::from([("k1".to_string(), key.repeat(KEY_LEN))]);
+            assert!(StaticKeyProvider::try_new("k1", &keys).is_err());
+        }
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let encryptor = new_encryptor("k1");
+        let data = b"hello world";
+        let aad = sst_associated_data("greptime/public/1024/1024_0000000000/a.parquet");
+        assert_eq!(b"a.parquet", aad);
+        let encrypted = encryptor.encrypt(data, aad).unwrap();
+        assert_ne!(data.as_slice(), &encrypted[encrypted.len() - data.len()..]);
+        assert_eq!(
+            data.as_slice(),
+            &*encryptor.decrypt(&encrypted, aad).unwrap()
+        );
+
+        // Nonces are random.
+        assert_ne!(encrypted, encryptor.encrypt(data, aad).unwrap());
+
+        // Data encrypted by an old key is still readable after rotation.
+        let rotated = new_encryptor("k2");
+        assert_eq!(data.as_slice(), &*rotated.decrypt(&encrypted, aad).unwrap());
+
+        // Data can't be read as other data.
+        assert!(encryptor.decrypt(&encrypted, b"b.parquet").is_err());
+        assert_ne!(wal_associated_data(1, 2), wal_associated_data(1, 3));
+        assert_ne!(wal_associated_data(1, 2), wal_associated_data(2, 2));
+
+        // Plain data is only returned as is while migrating.
+        assert!(encryptor.decrypt(b"PAR1", aad).is_err());
+        let migrating = new_migrating_encryptor("k1");
+        assert!(matches!(
+            migrating.decrypt(b"PAR1", aad).unwrap(),
+            Cow::Borrowed(b"PAR1")
+        ));
+        assert!(migrating.decrypt(&encrypted, b"b.parquet").is_err());
+
+        // Tampered or truncated data.
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 0xff;
+        assert!(encryptor.decrypt(&tampered, aad).is_err());
+        assert!(encryptor
+            .decrypt(&encrypted[..MAGIC.len() + 3], aad)
+            .is_err());
+    }
+}
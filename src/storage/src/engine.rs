@@ -21,18 +21,23 @@ use object_store::{util, ObjectStore};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, RegionDescriptor, StorageEngine,
+    CreateOptions, EngineContext, OpenOptions, Region, RegionDescriptor, RegionStat, StorageEngine,
 };
 
 use crate::background::JobPoolImpl;
 use crate::config::EngineConfig;
+use crate::data_dir::{find_data_dir, pick_data_dir, DataDir};
+use crate::encryption::EncryptorRef;
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
+use crate::listener::{EventListenerRef, EventListeners};
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
 use crate::region::{RegionImpl, StoreConfig};
 use crate::sst::FsAccessLayer;
+use crate::wal::WalRetentionRef;
+use crate::write_behind::SstUploaderRef;
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
@@ -61,8 +66,8 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.open_region(name, opts).await
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        self.inner.close_region(region).await
     }
 
     async fn create_region(
@@ -81,6 +86,15 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<Self::Region>> {
         Ok(self.inner.get_region(name))
     }
+
+    async fn region_stat(
+        &self,
+        _ctx: &EngineContext,
+        name: &str,
+        opts: &OpenOptions,
+    ) -> Result<Option<RegionStat>> {
+        self.inner.region_stat(name, opts).await
+    }
 }
 
 impl<S: LogStore> EngineImpl<S> {
@@ -210,12 +224,25 @@ struct EngineInner<S: LogStore> {
     memtable_builder: MemtableBuilderRef,
     flush_scheduler: FlushSchedulerRef,
     flush_strategy: FlushStrategyRef,
+    sst_uploader: Option<SstUploaderRef>,
+    data_dirs: Vec<DataDir>,
+    encryptor: Option<EncryptorRef>,
+    sst_token_index: bool,
+    event_listener: EventListenerRef,
+    cold_store: Option<ObjectStore>,
+    wal_retention: Option<WalRetentionRef>,
 }
 
 impl<S: LogStore> EngineInner<S> {
-    pub fn new(_config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
+    pub fn new(config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
         let job_pool = Arc::new(JobPoolImpl {});
         let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
+        // SST files are written to local disks directly if regions are placed to data dirs.
+        let sst_uploader = if config.data_dirs.is_empty() {
+            config.sst_uploader
+        } else {
+            None
+        };
 
         Self {
             object_store,
@@ -224,6 +251,13 @@ impl<S: LogStore> EngineInner<S> {
             memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
             flush_scheduler,
             flush_strategy: Arc::new(SizeBasedStrategy::default()),
+            sst_uploader,
+            data_dirs: config.data_dirs,
+            encryptor: config.encryptor,
+            sst_token_index: config.sst_token_index,
+            event_listener: Arc::new(EventListeners::new(config.event_listeners)),
+            cold_store: config.cold_store,
+            wal_retention: config.wal_retention,
         }
     }
 
@@ -259,7 +293,12 @@ impl<S: LogStore> EngineInner<S> {
 
         let mut guard = SlotGuard::new(name, &self.regions);
 
-        let store_config = self.region_store_config(&opts.parent_dir, name);
+        let manifest_dir = region_manifest_dir(&util::normalize_dir(&opts.parent_dir), name);
+        let object_store = match find_data_dir(&self.data_dirs, &manifest_dir).await? {
+            Some(data_dir) => data_dir.object_store().clone(),
+            None => self.object_store.clone(),
+        };
+        let store_config = self.region_store_config(&opts.parent_dir, name, object_store);
 
         let region = match RegionImpl::open(name.to_string(), store_config, opts).await? {
             None => return Ok(None),
@@ -289,7 +328,18 @@ impl<S: LogStore> EngineInner<S> {
                 .context(error::InvalidRegionDescSnafu {
                     region: &region_name,
                 })?;
-        let store_config = self.region_store_config(&opts.parent_dir, &region_name);
+        let object_store = match pick_data_dir(&self.data_dirs) {
+            Some(data_dir) => {
+                info!(
+                    "Storage engine place region {} to data dir {}",
+                    region_name,
+                    data_dir.path()
+                );
+                data_dir.object_store().clone()
+            }
+            None => self.object_store.clone(),
+        };
+        let store_config = self.region_store_config(&opts.parent_dir, &region_name, object_store);
 
         let region = RegionImpl::create(metadata, store_config).await?;
 
@@ -300,18 +350,58 @@ impl<S: LogStore> EngineInner<S> {
         Ok(region)
     }
 
+    async fn region_stat(&self, name: &str, opts: &OpenOptions) -> Result<Option<RegionStat>> {
+        let manifest_dir = region_manifest_dir(&util::normalize_dir(&opts.parent_dir), name);
+        let object_store = match find_data_dir(&self.data_dirs, &manifest_dir).await? {
+            Some(data_dir) => data_dir.object_store().clone(),
+            None => self.object_store.clone(),
+        };
+        let store_config = self.region_store_config(&opts.parent_dir, name, object_store);
+        RegionImpl::stat_from_manifest(&store_config).await
+    }
+
     fn get_region(&self, name: &str) -> Option<RegionImpl<S>> {
         let slot = self.regions.read().unwrap().get(name).cloned()?;
         slot.get_ready_region()
     }
 
-    fn region_store_config(&self, parent_dir: &str, region_name: &str) -> StoreConfig<S> {
+    /// Removes the region from the engine, the region is recovered from its manifest and WAL
+    /// once opened again.
+    async fn close_region(&self, region: RegionImpl<S>) -> Result<()> {
+        region.close().await?;
+
+        let name = region.name();
+        let mut regions = self.regions.write().unwrap();
+        if matches!(regions.get(name), Some(RegionSlot::Ready(_))) {
+            regions.remove(name);
+        }
+        info!("Storage engine close region {}", region.id());
+        Ok(())
+    }
+
+    fn region_store_config(
+        &self,
+        parent_dir: &str,
+        region_name: &str,
+        object_store: ObjectStore,
+    ) -> StoreConfig<S> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let mut sst_layer = FsAccessLayer::new(sst_dir, object_store.clone())
+            .with_token_index(self.sst_token_index);
+        if let Some(uploader) = &self.sst_uploader {
+            sst_layer = sst_layer.with_sst_uploader(uploader.clone());
+        }
+        if let Some(encryptor) = &self.encryptor {
+            sst_layer = sst_layer.with_encryptor(encryptor.clone());
+        }
+        if let Some(cold_store) = &self.cold_store {
+            sst_layer = sst_layer.with_cold_store(cold_store.clone());
+        }
+        let sst_layer = Arc::new(sst_layer);
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
-        let manifest = RegionManifest::new(&manifest_dir, self.object_store.clone());
+        let manifest = RegionManifest::new(&manifest_dir, object_store);
 
         StoreConfig {
             log_store: self.log_store.clone(),
@@ -320,6 +410,9 @@ impl<S: LogStore> EngineInner<S> {
             memtable_builder: self.memtable_builder.clone(),
             flush_scheduler: self.flush_scheduler.clone(),
             flush_strategy: self.flush_strategy.clone(),
+            encryptor: self.encryptor.clone(),
+            event_listener: self.event_listener.clone(),
+            wal_retention: self.wal_retention.clone(),
         }
     }
 }
@@ -329,7 +422,6 @@ mod tests {
     use datatypes::type_id::LogicalTypeId;
     use log_store::test_util::log_store_util;
     use object_store::backend::fs::Builder;
-    use store_api::storage::Region;
     use tempdir::TempDir;
 
     use super::*;
@@ -367,4 +459,94 @@ mod tests {
 
         assert!(engine.get_region(&ctx, "no such region").unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_close_region() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("test_engine_wal").await;
+        let dir = TempDir::new("test_close_region").unwrap();
+        let store_dir = dir.path().to_string_lossy();
+        let accessor = Builder::default().root(&store_dir).build().unwrap();
+        let object_store = ObjectStore::new(accessor);
+        let engine = EngineImpl::new(EngineConfig::default(), Arc::new(log_store), object_store);
+
+        let region_name = "region-0";
+        let desc = RegionDescBuilder::new(region_name)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_value_column(("v1", LogicalTypeId::Float32, true))
+            .build();
+        let ctx = EngineContext::default();
+        let region = engine
+            .create_region(&ctx, desc, &CreateOptions::default())
+            .await
+            .unwrap();
+
+        engine.close_region(&ctx, region).await.unwrap();
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_none());
+
+        // The closed region could be opened again.
+        let region = engine
+            .open_region(&ctx, region_name, &OpenOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(region_name, region.name());
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_place_regions_to_data_dirs() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("test_engine_wal").await;
+        let log_store = Arc::new(log_store);
+        let new_object_store = |dir: &TempDir| {
+            let accessor = Builder::default()
+                .root(&dir.path().to_string_lossy())
+                .build()
+                .unwrap();
+            ObjectStore::new(accessor)
+        };
+        let dir = TempDir::new("test_place_regions").unwrap();
+        let object_store = new_object_store(&dir);
+        let data_dirs = [
+            TempDir::new("test_place_regions").unwrap(),
+            TempDir::new("test_place_regions").unwrap(),
+        ];
+        let config = EngineConfig {
+            data_dirs: data_dirs
+                .iter()
+                .map(|dir| DataDir::new(&dir.path().to_string_lossy(), new_object_store(dir)))
+                .collect(),
+            ..Default::default()
+        };
+
+        let engine = EngineImpl::new(config.clone(), log_store.clone(), object_store.clone());
+        let region_name = "region-0";
+        let desc = RegionDescBuilder::new(region_name)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_value_column(("v1", LogicalTypeId::Float32, true))
+            .build();
+        let ctx = EngineContext::default();
+        engine
+            .create_region(&ctx, desc, &CreateOptions::default())
+            .await
+            .unwrap();
+
+        // The region is placed to one of the data dirs.
+        let manifest_dir = region_manifest_dir("", region_name);
+        let placed = data_dirs
+            .iter()
+            .filter(|dir| dir.path().join(&manifest_dir).exists())
+            .count();
+        assert_eq!(1, placed);
+        assert!(!dir.path().join(&manifest_dir).exists());
+
+        let engine = EngineImpl::new(config, log_store, object_store);
+        let region = engine
+            .open_region(&ctx, region_name, &OpenOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(region_name, region.name());
+    }
 }
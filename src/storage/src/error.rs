@@ -125,6 +125,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to sync WAL, WAL region_id: {}, source: {}", region_id, source))]
+    SyncWal {
+        region_id: RegionId,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to encode WAL header, source {}", source))]
     EncodeWalHeader {
         backtrace: Backtrace,
@@ -412,10 +419,93 @@ pub enum Error {
 
     #[snafu(display("Failed to decode parquet file time range, msg: {}", msg))]
     DecodeParquetTimeRange { msg: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "Checksum mismatch of SST file {}, expected: {}, actual: {}",
+        path,
+        expected,
+        actual
+    ))]
+    SstChecksumMismatch {
+        path: String,
+        expected: u32,
+        actual: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "SST file {} is quarantined after failing to read or verify, scrub the region to release it",
+        file
+    ))]
+    QuarantinedSst { file: String, backtrace: Backtrace },
+
+    #[snafu(display("Cold store of SST files is not configured"))]
+    NoColdStore { backtrace: Backtrace },
+
+    #[snafu(display("Region {} is read-only", region))]
+    ReadOnlyRegion {
+        region: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid backlog {} of write-behind, it must be positive", backlog))]
+    InvalidWriteBehindBacklog {
+        backlog: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to list local files in {}, source: {}", dir, source))]
+    ListLocalFiles {
+        dir: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("SST uploader is stopped, failed to upload file {}", path))]
+    UploaderStopped { path: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid encryption key {}, reason: {}", key_id, reason))]
+    InvalidEncryptionKey {
+        key_id: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Unknown encryption key {}", key_id))]
+    UnknownEncryptionKey {
+        key_id: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to encrypt data, reason: {}", reason))]
+    Encrypt {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to decrypt data, reason: {}", reason))]
+    Decrypt {
+        reason: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Returns true if the error shows the content of an SST file is corrupted, failures
+    /// to access the object store are transient and don't imply that.
+    pub fn is_corrupted_sst(&self) -> bool {
+        match self {
+            Error::SstChecksumMismatch { .. } => true,
+            Error::ReadParquet { source, .. } => {
+                !matches!(source, parquet::errors::ParquetError::External(_))
+            }
+            _ => false,
+        }
+    }
+}
+
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         use Error::*;
@@ -432,6 +522,9 @@ impl ErrorExt for Error {
             | TypeMismatch { .. }
             | HasNull { .. }
             | UnequalLengths { .. }
+            | InvalidWriteBehindBacklog { .. }
+            | InvalidEncryptionKey { .. }
+            | UnknownEncryptionKey { .. }
             | MoreColumnThanExpected { .. } => StatusCode::InvalidArguments,
 
             Utf8 { .. }
@@ -452,6 +545,7 @@ impl ErrorExt for Error {
             | NoDefaultToRead { .. }
             | NewRecordBatch { .. }
             | BatchCorrupted { .. }
+            | SstChecksumMismatch { .. }
             | DecodeArrow { .. }
             | EncodeArrow { .. }
             | ParseSchema { .. } => StatusCode::Unexpected,
@@ -463,11 +557,16 @@ impl ErrorExt for Error {
             | ListObjects { .. }
             | DeleteObject { .. }
             | WriteWal { .. }
+            | SyncWal { .. }
             | DecodeWalHeader { .. }
             | EncodeWalHeader { .. }
             | ManifestProtocolForbidRead { .. }
             | ManifestProtocolForbidWrite { .. }
             | ReadParquet { .. }
+            | QuarantinedSst { .. }
+            | NoColdStore { .. }
+            | ListLocalFiles { .. }
+            | UploaderStopped { .. }
             | InvalidRegionState { .. }
             | ReadWal { .. } => StatusCode::StorageUnavailable,
 
@@ -481,6 +580,10 @@ impl ErrorExt for Error {
             ConvertChunk { source, .. } => source.status_code(),
             MarkWalObsolete { source, .. } => source.status_code(),
             DecodeParquetTimeRange { .. } => StatusCode::Unexpected,
+
+            Encrypt { .. } | Decrypt { .. } => StatusCode::Internal,
+
+            ReadOnlyRegion { .. } => StatusCode::Unsupported,
         }
     }
 
@@ -507,6 +610,24 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_is_corrupted_sst() {
+        let err = Error::ReadParquet {
+            file: "test.parquet".to_string(),
+            source: parquet::errors::ParquetError::EOF("footer".to_string()),
+            backtrace: Backtrace::generate(),
+        };
+        assert!(err.is_corrupted_sst());
+
+        let io_err = IoError::new(std::io::ErrorKind::TimedOut, "timeout");
+        let err = Error::ReadParquet {
+            file: "test.parquet".to_string(),
+            source: parquet::errors::ParquetError::External(Box::new(io_err)),
+            backtrace: Backtrace::generate(),
+        };
+        assert!(!err.is_corrupted_sst());
+    }
+
     #[test]
     fn test_invalid_region_desc_error() {
         let err = throw_metadata_error()
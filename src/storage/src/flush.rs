@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
+#[cfg(feature = "failpoints")]
+use common_base::failpoint;
 use common_telemetry::logging;
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
@@ -23,11 +26,12 @@ use uuid::Uuid;
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
 use crate::error::{CancelledSnafu, Result};
+use crate::listener::{EventListenerRef, FlushBegin, FlushEnd};
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{IterContext, MemtableId, MemtableRef};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileMeta, SstInfo, WriteOptions};
+use crate::sst::{AccessLayerRef, FileMeta, FileTier, SstInfo, WriteOptions};
 use crate::wal::Wal;
 
 /// Default write buffer size (32M).
@@ -160,6 +164,8 @@ pub struct FlushJob<S: LogStore> {
     pub wal: Wal<S>,
     /// Region manifest service, used to persist metadata.
     pub manifest: RegionManifest,
+    /// Listener notified when the flush begins and ends.
+    pub event_listener: EventListenerRef,
 }
 
 impl<S: LogStore> FlushJob<S> {
@@ -168,6 +174,11 @@ impl<S: LogStore> FlushJob<S> {
             return CancelledSnafu {}.fail();
         }
 
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("flush_write_sst", self.shared.name()) {
+            return Err(injected_io_error("flush_write_sst"));
+        }
+
         let mut futures = Vec::with_capacity(self.memtables.len());
         let iter_ctx = IterContext {
             for_flush: true,
@@ -188,6 +199,9 @@ impl<S: LogStore> FlushJob<S> {
                 let SstInfo {
                     start_timestamp,
                     end_timestamp,
+                    file_size,
+                    num_rows,
+                    checksum,
                 } = self
                     .sst_layer
                     .write_sst(&file_name, iter, &WriteOptions::default())
@@ -198,6 +212,10 @@ impl<S: LogStore> FlushJob<S> {
                     start_timestamp,
                     end_timestamp,
                     level: 0,
+                    file_size,
+                    num_rows,
+                    checksum: Some(checksum),
+                    tier: FileTier::Hot,
                 })
             });
         }
@@ -209,16 +227,20 @@ impl<S: LogStore> FlushJob<S> {
             .into_iter()
             .collect();
 
-        logging::info!("Successfully flush memtables to files: {:?}", metas);
         Ok(metas)
     }
 
     async fn write_manifest_and_apply(&self, file_metas: &[FileMeta]) -> Result<()> {
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("flush_write_manifest", self.shared.name()) {
+            return Err(injected_io_error("flush_write_manifest"));
+        }
         let edit = RegionEdit {
             region_version: self.shared.version_control.metadata().version(),
             flushed_sequence: self.flush_sequence,
             files_to_add: file_metas.to_vec(),
             files_to_remove: Vec::default(),
+            last_write_millis: self.shared.last_write_millis(),
         };
 
         self.writer
@@ -227,25 +249,68 @@ impl<S: LogStore> FlushJob<S> {
                 &self.shared,
                 &self.manifest,
                 edit,
-                self.max_memtable_id,
+                Some(self.max_memtable_id),
             )
             .await?;
+        // Crashing here leaves the WAL of the flushed data to replay on recovery.
+        #[cfg(feature = "failpoints")]
+        if failpoint::triggered("flush_before_obsolete_wal", self.shared.name()) {
+            panic!("Injected crash at flush_before_obsolete_wal");
+        }
         self.wal.obsolete(self.flush_sequence).await
     }
 
+    async fn flush(&self, ctx: &Context) -> Result<Vec<FileMeta>> {
+        let file_metas = self.write_memtables_to_layer(ctx).await?;
+        self.write_manifest_and_apply(&file_metas).await?;
+        Ok(file_metas)
+    }
+
     /// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
     fn generate_sst_file_name() -> String {
         format!("{}.parquet", Uuid::new_v4().hyphenated())
     }
 }
 
+/// Returns an IO error injected by the fail point `name`, the fail points of flush are
+/// scoped by the region name.
+#[cfg(feature = "failpoints")]
+fn injected_io_error(name: &str) -> crate::error::Error {
+    use snafu::IntoError;
+
+    let e = object_store::Error::new(
+        object_store::ErrorKind::Unexpected,
+        &format!("Injected failure at {name}"),
+    );
+    crate::error::FlushIoSnafu.into_error(e)
+}
+
 #[async_trait]
 impl<S: LogStore> Job for FlushJob<S> {
     // TODO(yingwen): [flush] Support in-job parallelism (Flush memtables concurrently)
     async fn run(&mut self, ctx: &Context) -> Result<()> {
-        let file_metas = self.write_memtables_to_layer(ctx).await?;
-        self.write_manifest_and_apply(&file_metas).await?;
-        Ok(())
+        self.event_listener.on_flush_begin(&FlushBegin {
+            region_name: self.shared.name(),
+            flush_sequence: self.flush_sequence,
+            memtables: self.memtables.len(),
+            memtable_bytes: self.memtables.iter().map(|m| m.bytes_allocated()).sum(),
+        });
+
+        let timer = Instant::now();
+        let result = self.flush(ctx).await;
+        let (files, error) = match &result {
+            Ok(file_metas) => (file_metas.as_slice(), None),
+            Err(e) => (&[][..], Some(e)),
+        };
+        self.event_listener.on_flush_end(&FlushEnd {
+            region_name: self.shared.name(),
+            flush_sequence: self.flush_sequence,
+            files,
+            elapsed: timer.elapsed(),
+            error,
+        });
+
+        result.map(|_| ())
     }
 }
 
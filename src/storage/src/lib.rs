@@ -18,12 +18,16 @@ mod background;
 mod chunk;
 pub mod codec;
 pub mod config;
+pub mod data_dir;
+pub mod encryption;
 mod engine;
 pub mod error;
 mod flush;
+pub mod listener;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
+pub mod metric;
 pub mod proto;
 pub mod read;
 pub mod region;
@@ -36,5 +40,7 @@ mod test_util;
 mod version;
 mod wal;
 pub mod write_batch;
+pub mod write_behind;
 
 pub use engine::EngineImpl;
+pub use wal::{WalRetention, WalRetentionRef};
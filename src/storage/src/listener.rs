@@ -0,0 +1,138 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Listeners of storage engine events, embedding applications could register their own
+//! listeners via [EngineConfig](crate::config::EngineConfig) to observe background jobs.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_telemetry::logging;
+use metrics::{counter, histogram};
+use store_api::storage::SequenceNumber;
+
+use crate::error::Error;
+use crate::metric::{
+    METRIC_FLUSH_BYTES_TOTAL, METRIC_FLUSH_ELAPSED, METRIC_FLUSH_ERRORS_TOTAL, METRIC_FLUSH_TOTAL,
+};
+pub use crate::sst::FileMeta;
+
+/// A flush job of a region is about to write its memtables.
+#[derive(Debug)]
+pub struct FlushBegin<'a> {
+    pub region_name: &'a str,
+    /// Last sequence of data to flush.
+    pub flush_sequence: SequenceNumber,
+    /// Number of memtables to flush.
+    pub memtables: usize,
+    /// Bytes allocated by the memtables to flush.
+    pub memtable_bytes: usize,
+}
+
+/// A flush job of a region is finished.
+#[derive(Debug)]
+pub struct FlushEnd<'a> {
+    pub region_name: &'a str,
+    /// Last sequence of data flushed.
+    pub flush_sequence: SequenceNumber,
+    /// SST files written by the flush, empty if the flush failed.
+    pub files: &'a [FileMeta],
+    pub elapsed: Duration,
+    /// The error if the flush failed.
+    pub error: Option<&'a Error>,
+}
+
+/// Listener of storage engine events.
+///
+/// Listeners are called in the background jobs, so they should return quickly.
+pub trait EventListener: Send + Sync + fmt::Debug {
+    fn on_flush_begin(&self, _event: &FlushBegin) {}
+
+    fn on_flush_end(&self, _event: &FlushEnd) {}
+}
+
+pub type EventListenerRef = Arc<dyn EventListener>;
+
+/// The default listener that logs events and records metrics of them.
+#[derive(Debug, Default)]
+pub struct LoggingListener;
+
+impl EventListener for LoggingListener {
+    fn on_flush_begin(&self, event: &FlushBegin) {
+        logging::info!(
+            "Begin to flush region {}, flush_sequence: {}, memtables: {}, memtable_bytes: {}",
+            event.region_name,
+            event.flush_sequence,
+            event.memtables,
+            event.memtable_bytes
+        );
+    }
+
+    fn on_flush_end(&self, event: &FlushEnd) {
+        let region = event.region_name.to_string();
+        histogram!(METRIC_FLUSH_ELAPSED, event.elapsed, "region" => region.clone());
+        if let Some(e) = event.error {
+            counter!(METRIC_FLUSH_ERRORS_TOTAL, 1, "region" => region);
+            logging::error!(
+                e; "Failed to flush region {}, flush_sequence: {}, elapsed: {:?}",
+                event.region_name,
+                event.flush_sequence,
+                event.elapsed
+            );
+            return;
+        }
+
+        let bytes = event.files.iter().map(|f| f.file_size).sum::<u64>();
+        counter!(METRIC_FLUSH_TOTAL, 1, "region" => region.clone());
+        counter!(METRIC_FLUSH_BYTES_TOTAL, bytes, "region" => region);
+        logging::info!(
+            "Finish flushing region {}, flush_sequence: {}, files: {:?}, bytes: {}, elapsed: {:?}",
+            event.region_name,
+            event.flush_sequence,
+            event.files.iter().map(|f| &f.file_name).collect::<Vec<_>>(),
+            bytes,
+            event.elapsed
+        );
+    }
+}
+
+/// Notifies the [LoggingListener] and then the listeners registered by users, in the
+/// order they are registered.
+#[derive(Debug)]
+pub(crate) struct EventListeners {
+    listeners: Vec<EventListenerRef>,
+}
+
+impl EventListeners {
+    pub(crate) fn new(custom_listeners: Vec<EventListenerRef>) -> EventListeners {
+        let mut listeners: Vec<EventListenerRef> = vec![Arc::new(LoggingListener)];
+        listeners.extend(custom_listeners);
+        EventListeners { listeners }
+    }
+}
+
+impl EventListener for EventListeners {
+    fn on_flush_begin(&self, event: &FlushBegin) {
+        for listener in &self.listeners {
+            listener.on_flush_begin(event);
+        }
+    }
+
+    fn on_flush_end(&self, event: &FlushEnd) {
+        for listener in &self.listeners {
+            listener.on_flush_end(event);
+        }
+    }
+}
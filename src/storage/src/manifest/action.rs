@@ -76,6 +76,10 @@ pub struct RegionEdit {
     pub flushed_sequence: SequenceNumber,
     pub files_to_add: Vec<FileMeta>,
     pub files_to_remove: Vec<FileMeta>,
+    /// Unix timestamp in milliseconds of the last write to the region when the edit is
+    /// made, so the time is still known after the WAL is purged.
+    #[serde(default)]
+    pub last_write_millis: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -17,7 +17,7 @@ use store_api::storage::SequenceNumber;
 
 use crate::manifest::action::*;
 use crate::metadata::RegionMetadata;
-use crate::sst::FileMeta;
+use crate::sst::{FileMeta, FileTier};
 use crate::test_util::descriptor_util::RegionDescBuilder;
 
 pub fn build_region_meta() -> RegionMetadata {
@@ -38,6 +38,7 @@ pub fn build_region_edit(
     RegionEdit {
         region_version: 0,
         flushed_sequence: sequence,
+        last_write_millis: None,
         files_to_add: files_to_add
             .iter()
             .map(|f| FileMeta {
@@ -45,6 +46,10 @@ pub fn build_region_edit(
                 start_timestamp: None,
                 end_timestamp: None,
                 level: 0,
+                file_size: 0,
+                num_rows: 0,
+                checksum: None,
+                tier: FileTier::Hot,
             })
             .collect(),
         files_to_remove: files_to_remove
@@ -54,6 +59,10 @@ pub fn build_region_edit(
                 start_timestamp: None,
                 end_timestamp: None,
                 level: 0,
+                file_size: 0,
+                num_rows: 0,
+                checksum: None,
+                tier: FileTier::Hot,
             })
             .collect(),
     }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod btree;
+mod dictionary;
 mod inserter;
 #[cfg(test)]
 pub mod tests;
@@ -77,6 +78,9 @@ pub struct IterContext {
     /// Returns all rows, ignores sequence visibility and key duplication.
     pub for_flush: bool,
 
+    /// Returns all visible rows, including rows with duplicate keys.
+    pub keep_duplicates: bool,
+
     /// Schema the reader expect to read.
     ///
     /// Set to `None` to read all columns.
@@ -90,6 +94,7 @@ impl Default for IterContext {
             // All data in memory is visible by default.
             visible_sequence: SequenceNumber::MAX,
             for_flush: false,
+            keep_duplicates: false,
             projected_schema: None,
         }
     }
@@ -16,7 +16,7 @@ use std::cmp::Ordering;
 use std::collections::{btree_map, BTreeMap};
 use std::ops::Bound;
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 use datatypes::data_type::DataType;
 use datatypes::prelude::*;
@@ -25,6 +25,7 @@ use datatypes::vectors::{UInt64Vector, UInt64VectorBuilder, UInt8Vector, UInt8Ve
 use store_api::storage::{OpType, SequenceNumber};
 
 use crate::error::Result;
+use crate::memtable::dictionary::TagDictionary;
 use crate::memtable::{
     BatchIterator, BoxedBatchIterator, IterContext, KeyValues, Memtable, MemtableId, RowOrdering,
 };
@@ -42,6 +43,8 @@ pub struct BTreeMemtable {
     id: MemtableId,
     schema: RegionSchemaRef,
     map: Arc<RwLockMap>,
+    /// Dictionary of tag values in `map`.
+    dictionary: Mutex<TagDictionary>,
     estimated_bytes: AtomicUsize,
 }
 
@@ -51,6 +54,7 @@ impl BTreeMemtable {
             id,
             schema,
             map: Arc::new(RwLock::new(BTreeMap::new())),
+            dictionary: Mutex::new(TagDictionary::default()),
             estimated_bytes: AtomicUsize::new(0),
         }
     }
@@ -70,7 +74,8 @@ impl Memtable for BTreeMemtable {
             .fetch_add(kvs.estimated_memory_size(), AtomicOrdering::Relaxed);
 
         let mut map = self.map.write().unwrap();
-        let iter_row = IterRow::new(kvs);
+        let mut dictionary = self.dictionary.lock().unwrap();
+        let iter_row = IterRow::new(kvs, &mut dictionary);
         for (inner_key, row_value) in iter_row {
             map.insert(inner_key, row_value);
         }
@@ -156,6 +161,10 @@ impl BTreeIterator {
 
         let (keys, sequences, op_types, values) = if self.ctx.for_flush {
             collect_iter(iter, self.ctx.batch_size)
+        } else if self.ctx.keep_duplicates {
+            let visible_sequence = self.ctx.visible_sequence;
+            let iter = iter.filter(|(k, _)| k.is_visible(visible_sequence));
+            collect_iter(iter, self.ctx.batch_size)
         } else {
             let iter = MapIterWrapper::new(iter, self.ctx.visible_sequence);
             collect_iter(iter, self.ctx.batch_size)
@@ -166,7 +175,11 @@ impl BTreeIterator {
         }
         self.last_key = keys.last().map(|k| {
             let mut last_key = (*k).clone();
-            last_key.reset_for_seek();
+            // Rows with the same row key as the last key should be returned in next batch
+            // if we need to keep duplicates, otherwise seek to next row key.
+            if !self.ctx.keep_duplicates {
+                last_key.reset_for_seek();
+            }
             last_key
         });
 
@@ -281,14 +294,16 @@ impl<'a> Iterator for MapIterWrapper<'a, InnerKey, RowValue> {
 
 struct IterRow<'a> {
     kvs: &'a KeyValues,
+    dictionary: &'a mut TagDictionary,
     index: usize,
     len: usize,
 }
 
 impl<'a> IterRow<'a> {
-    fn new(kvs: &KeyValues) -> IterRow {
+    fn new(kvs: &'a KeyValues, dictionary: &'a mut TagDictionary) -> IterRow<'a> {
         IterRow {
             kvs,
+            dictionary,
             index: 0,
             len: kvs.len(),
         }
@@ -299,7 +314,8 @@ impl<'a> IterRow<'a> {
             .kvs
             .keys
             .iter()
-            .map(|vector| vector.get(self.index))
+            .enumerate()
+            .map(|(column, vector)| self.dictionary.intern(column, vector.get(self.index)))
             .collect();
         let inner_key = InnerKey {
             row_key,
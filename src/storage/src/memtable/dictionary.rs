@@ -0,0 +1,100 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use datatypes::value::Value;
+
+/// Max number of distinct values a column of [TagDictionary] holds. Values of columns
+/// with higher cardinality are rarely repeated, so new values are no longer interned
+/// once the limit is reached.
+const MAX_VALUES_PER_COLUMN: usize = 4096;
+
+/// Dictionary of the string values of row key columns in a memtable, rows with the
+/// same tag value share one copy of the value instead of holding their own.
+#[derive(Debug, Default)]
+pub struct TagDictionary {
+    /// Distinct values of each row key column.
+    columns: Vec<BTreeSet<Value>>,
+}
+
+impl TagDictionary {
+    /// Returns the copy of `value` in the dictionary of row key column at `column`,
+    /// adds `value` to the dictionary if it's absent. Values other than strings are
+    /// returned as is.
+    pub fn intern(&mut self, column: usize, value: Value) -> Value {
+        if !matches!(value, Value::String(_)) {
+            return value;
+        }
+        if self.columns.len() <= column {
+            self.columns.resize_with(column + 1, BTreeSet::new);
+        }
+
+        let values = &mut self.columns[column];
+        if let Some(interned) = values.get(&value) {
+            // Cloning a string value only bumps the reference count of its buffer.
+            return interned.clone();
+        }
+        if values.len() < MAX_VALUES_PER_COLUMN {
+            values.insert(value.clone());
+        }
+        value
+    }
+
+    /// Returns the number of distinct values of row key column at `column`.
+    pub fn num_values(&self, column: usize) -> usize {
+        self.columns.get(column).map_or(0, BTreeSet::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut dictionary = TagDictionary::default();
+        assert_eq!(Value::from("a"), dictionary.intern(0, Value::from("a")));
+        assert_eq!(Value::from("a"), dictionary.intern(0, Value::from("a")));
+        assert_eq!(Value::from("b"), dictionary.intern(0, Value::from("b")));
+        assert_eq!(Value::from("a"), dictionary.intern(2, Value::from("a")));
+        assert_eq!(Value::from(1i64), dictionary.intern(1, Value::from(1i64)));
+        assert_eq!(Value::Null, dictionary.intern(1, Value::Null));
+
+        assert_eq!(2, dictionary.num_values(0));
+        assert_eq!(0, dictionary.num_values(1));
+        assert_eq!(1, dictionary.num_values(2));
+        assert_eq!(0, dictionary.num_values(3));
+    }
+
+    #[test]
+    fn test_intern_shares_buffer() {
+        let mut dictionary = TagDictionary::default();
+        let first = dictionary.intern(0, Value::from("host"));
+        let second = dictionary.intern(0, Value::from("host"));
+        let (Value::String(first), Value::String(second)) = (first, second) else {
+            unreachable!()
+        };
+        assert_eq!(first.as_utf8().as_ptr(), second.as_utf8().as_ptr());
+    }
+
+    #[test]
+    fn test_max_values() {
+        let mut dictionary = TagDictionary::default();
+        for i in 0..MAX_VALUES_PER_COLUMN + 10 {
+            dictionary.intern(0, Value::from(i.to_string()));
+        }
+        assert_eq!(MAX_VALUES_PER_COLUMN, dictionary.num_values(0));
+    }
+}
@@ -409,6 +409,60 @@ fn test_duplicate_key_across_batch() {
     });
 }
 
+#[test]
+fn test_keep_duplicate_key_across_batch() {
+    let tester = MemtableTester::default();
+    tester.run_testcase(|ctx| {
+        write_kvs(
+            &*ctx.memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2000, 1), (2001, 2)], // keys
+            &[(Some(1), None), (None, None), (None, None), (None, None)], // values
+        );
+
+        write_kvs(
+            &*ctx.memtable,
+            11, // sequence
+            OpType::Put,
+            &[(1000, 1), (2001, 2)],                   // keys
+            &[(Some(1231), None), (Some(1232), None)], // values
+        );
+
+        let batch_sizes = [1, 2, 3, 4, 5];
+        for batch_size in batch_sizes {
+            let iter_ctx = IterContext {
+                batch_size,
+                keep_duplicates: true,
+                ..Default::default()
+            };
+
+            let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
+            check_iter_content(
+                &mut *iter,
+                &[
+                    (1000, 1),
+                    (1000, 1),
+                    (1000, 2),
+                    (2000, 1),
+                    (2001, 2),
+                    (2001, 2),
+                ], // keys
+                &[11, 10, 10, 10, 11, 10], // sequences
+                &[OpType::Put; 6],         // op_types
+                &[
+                    (Some(1231), None),
+                    (Some(1), None),
+                    (None, None),
+                    (None, None),
+                    (Some(1232), None),
+                    (None, None),
+                ], // values
+            );
+        }
+    });
+}
+
 #[test]
 fn test_duplicate_key_in_batch() {
     let tester = MemtableTester::default();
@@ -75,6 +75,10 @@ impl MemtableVersion {
             + self.mutable.bytes_allocated()
     }
 
+    pub fn total_num_rows(&self) -> usize {
+        self.immutables.iter().map(|m| m.num_rows()).sum::<usize>() + self.mutable.num_rows()
+    }
+
     /// Creates a new `MemtableVersion` that removes immutable memtables
     /// less than or equal to max_memtable_id.
     pub fn remove_immutables(&self, max_memtable_id: MemtableId) -> MemtableVersion {
@@ -0,0 +1,24 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! storage metrics
+
+pub const METRIC_SCRUB_VERIFIED_FILES_TOTAL: &str = "storage.scrub.verified_files_total";
+pub const METRIC_SCRUB_CORRUPTED_FILES_TOTAL: &str = "storage.scrub.corrupted_files_total";
+pub const METRIC_COLD_FILES_MOVED_TOTAL: &str = "storage.tiering.cold_files_moved_total";
+pub const METRIC_WRITE_BEHIND_PENDING_FILES: &str = "storage.write_behind.pending_files";
+pub const METRIC_FLUSH_TOTAL: &str = "storage.flush.total";
+pub const METRIC_FLUSH_ERRORS_TOTAL: &str = "storage.flush.errors_total";
+pub const METRIC_FLUSH_BYTES_TOTAL: &str = "storage.flush.bytes_total";
+pub const METRIC_FLUSH_ELAPSED: &str = "storage.flush.elapsed";
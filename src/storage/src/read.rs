@@ -29,6 +29,7 @@ pub use merge::{MergeReader, MergeReaderBuilder};
 use snafu::{ensure, ResultExt};
 
 use crate::error::{self, Result};
+use crate::memtable::BoxedBatchIterator;
 
 /// Storage internal representation of a batch of rows.
 // Now the structure of `Batch` is still unstable, all pub fields may be changed.
@@ -295,6 +296,24 @@ impl BatchReader for ConcatReader {
     }
 }
 
+/// Reader that reads batches from a [BatchIterator](crate::memtable::BatchIterator).
+pub struct IterReader {
+    iter: BoxedBatchIterator,
+}
+
+impl IterReader {
+    pub fn new(iter: BoxedBatchIterator) -> IterReader {
+        IterReader { iter }
+    }
+}
+
+#[async_trait]
+impl BatchReader for IterReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.iter.next().transpose()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+
 use async_trait::async_trait;
 use common_base::BitVec;
-use datatypes::prelude::ScalarVector;
-use datatypes::vectors::BooleanVector;
+use datatypes::prelude::{DataType, ScalarVector, Value};
+use datatypes::vectors::{BooleanVector, VectorRef};
+use snafu::ResultExt;
+use store_api::storage::{DedupStrategy, OpType};
 
-use crate::error::Result;
-use crate::read::{Batch, BatchOp, BatchReader};
+use crate::error::{self, Result};
+use crate::read::{Batch, BatchBuilder, BatchOp, BatchReader};
 use crate::schema::ProjectedSchemaRef;
 
 /// A reader that dedup rows from inner reader.
@@ -31,6 +35,12 @@ pub struct DedupReader<R> {
     prev_batch: Option<Batch>,
     /// Reused bitmap buffer.
     selected: BitVec,
+    /// Strategy to resolve rows with the same key.
+    strategy: DedupStrategy,
+    /// Rows of the last key from the previous batch, which are not merged yet as the
+    /// same key may continue in the next batch. Only used by strategies other than
+    /// [DedupStrategy::KeepLast].
+    pending: Option<Batch>,
 }
 
 impl<R> DedupReader<R> {
@@ -40,9 +50,18 @@ impl<R> DedupReader<R> {
             reader,
             prev_batch: None,
             selected: BitVec::default(),
+            strategy: DedupStrategy::default(),
+            pending: None,
         }
     }
 
+    /// Sets the strategy to resolve rows with the same key, default is
+    /// [DedupStrategy::KeepLast].
+    pub fn strategy(mut self, strategy: DedupStrategy) -> DedupReader<R> {
+        self.strategy = strategy;
+        self
+    }
+
     /// Take `batch` and then returns a new batch with no duplicated rows.
     ///
     /// This method may returns empty `Batch`.
@@ -73,16 +92,161 @@ impl<R> DedupReader<R> {
         // Filter duplicate rows.
         self.schema.filter(&batch, &filter)
     }
+
+    /// Take `batch` and then returns a new batch whose rows with the same key are merged
+    /// into one row by the strategy. Rows of the last key are kept in `pending` until
+    /// next batch arrives.
+    ///
+    /// This method may returns empty `Batch`.
+    fn merge_batch(&mut self, batch: Batch) -> Result<Batch> {
+        if batch.is_empty() {
+            return Ok(batch);
+        }
+
+        let batch = match self.pending.take() {
+            Some(pending) => concat_batches(&pending, &batch)?,
+            None => batch,
+        };
+
+        self.selected.clear();
+        self.selected.resize(batch.num_rows(), false);
+        self.schema.find_unique(&batch, &mut self.selected, None);
+
+        // Start offsets of each key, the first row always starts a new key.
+        let starts: Vec<_> = self.selected.iter_ones().collect();
+        let last_start = *starts.last().unwrap();
+        let ranges = starts.windows(2).map(|w| w[0]..w[1]);
+        let merged = self.merge_rows(&batch, ranges)?;
+
+        self.pending = Some(batch.slice(last_start, batch.num_rows() - last_start));
+
+        Ok(merged)
+    }
+
+    /// Merges rows in each of the `ranges` into one row, each range should contain all
+    /// rows of a key, ordered by sequence desc.
+    fn merge_rows(
+        &self,
+        batch: &Batch,
+        ranges: impl Iterator<Item = Range<usize>>,
+    ) -> Result<Batch> {
+        let schema = self.schema.schema_to_read();
+        let value_indices = schema.row_key_end()..schema.user_column_end();
+        let op_types = batch.column(schema.op_type_index());
+        let mut builders: Vec<_> = batch
+            .columns()
+            .iter()
+            .map(|column| column.data_type().create_mutable_vector(batch.num_rows()))
+            .collect();
+
+        for range in ranges {
+            // Rows older than a deleted row are invisible.
+            let end = range
+                .clone()
+                .find(|i| op_types.get(*i) == Value::UInt8(OpType::Delete.as_u8()))
+                .unwrap_or(range.end);
+            if end == range.start {
+                // The key is deleted.
+                continue;
+            }
+            let rows = range.start..end;
+
+            for (idx, (builder, column)) in builders.iter_mut().zip(batch.columns()).enumerate() {
+                let value = if value_indices.contains(&idx) {
+                    merge_values(self.strategy, column, rows.clone())
+                } else {
+                    // Keys and internal columns come from the latest row.
+                    column.get(rows.start)
+                };
+                builder
+                    .push_value_ref(value.as_value_ref())
+                    .context(error::PushBatchSnafu)?;
+            }
+        }
+
+        let columns = builders.iter_mut().map(|b| b.to_vector()).collect();
+        Ok(Batch::new(columns))
+    }
+}
+
+/// Concatenates `first` and `second` into one batch.
+fn concat_batches(first: &Batch, second: &Batch) -> Result<Batch> {
+    let data_types: Vec<_> = first.columns().iter().map(|c| c.data_type()).collect();
+    let mut builder =
+        BatchBuilder::with_capacity(&data_types, first.num_rows() + second.num_rows());
+    builder.extend_slice_of(first, 0, first.num_rows())?;
+    builder.extend_slice_of(second, 0, second.num_rows())?;
+    builder.build()
+}
+
+/// Merges values of `column` in `rows`, which are ordered by sequence desc.
+fn merge_values(strategy: DedupStrategy, column: &VectorRef, rows: Range<usize>) -> Value {
+    let latest = || column.get(rows.start);
+    let non_null_values = || {
+        rows.clone()
+            .map(move |i| column.get(i))
+            .filter(|v| !v.is_null())
+    };
+
+    match strategy {
+        DedupStrategy::KeepLast => latest(),
+        DedupStrategy::KeepFirst => column.get(rows.end - 1),
+        DedupStrategy::Sum => non_null_values()
+            .try_fold(Value::Null, sum_values)
+            // Not a numeric column or the sum overflows, keeps the latest value.
+            .unwrap_or_else(latest),
+        DedupStrategy::Max => non_null_values().max().unwrap_or(Value::Null),
+        DedupStrategy::Min => non_null_values().min().unwrap_or(Value::Null),
+    }
+}
+
+/// Adds two values of the same type, returns `None` if they are not numeric values or
+/// the sum of integers overflows.
+fn sum_values(lhs: Value, rhs: Value) -> Option<Value> {
+    macro_rules! sum_values {
+        ($lhs: ident, $rhs: ident, $($Type: ident),*) => {
+            match ($lhs, $rhs) {
+                (Value::Null, v) | (v, Value::Null) => Some(v),
+                $((Value::$Type(a), Value::$Type(b)) => a.checked_add(b).map(Value::$Type),)*
+                (Value::Float32(a), Value::Float32(b)) => Some(Value::Float32(a + b)),
+                (Value::Float64(a), Value::Float64(b)) => Some(Value::Float64(a + b)),
+                _ => None,
+            }
+        };
+    }
+
+    sum_values!(lhs, rhs, UInt8, UInt16, UInt32, UInt64, Int8, Int16, Int32, Int64)
 }
 
 #[async_trait]
 impl<R: BatchReader> BatchReader for DedupReader<R> {
     async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        if self.strategy == DedupStrategy::KeepLast {
+            while let Some(batch) = self.reader.next_batch().await? {
+                let filtered = self.dedup_batch(batch)?;
+                // Skip empty batch.
+                if !filtered.is_empty() {
+                    return Ok(Some(filtered));
+                }
+            }
+
+            return Ok(None);
+        }
+
         while let Some(batch) = self.reader.next_batch().await? {
-            let filtered = self.dedup_batch(batch)?;
+            let merged = self.merge_batch(batch)?;
             // Skip empty batch.
-            if !filtered.is_empty() {
-                return Ok(Some(filtered));
+            if !merged.is_empty() {
+                return Ok(Some(merged));
+            }
+        }
+
+        // Merges rows of the last key.
+        if let Some(pending) = self.pending.take() {
+            let num_rows = pending.num_rows();
+            let merged = self.merge_rows(&pending, std::iter::once(0..num_rows))?;
+            if !merged.is_empty() {
+                return Ok(Some(merged));
             }
         }
 
@@ -178,4 +342,119 @@ mod tests {
         let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
         assert_eq!(&expect, &result[..]);
     }
+
+    async fn collect_with_strategy(
+        strategy: DedupStrategy,
+        batches: &[&[(i64, i64, u64, OpType)]],
+    ) -> Vec<(i64, Option<i64>)> {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(batches);
+        let mut reader = DedupReader::new(schema, reader).strategy(strategy);
+
+        read_util::collect_kv_batch(&mut reader).await
+    }
+
+    #[tokio::test]
+    async fn test_merge_keep_first() {
+        let result = collect_with_strategy(
+            DedupStrategy::KeepFirst,
+            &[
+                // key, value, sequence, op_type
+                &[
+                    (100, 1, 1000, OpType::Put),
+                    (100, 2, 999, OpType::Put),
+                    (100, 3, 998, OpType::Put),
+                    (101, 1, 1000, OpType::Put),
+                ],
+                &[
+                    (101, 2, 999, OpType::Put),
+                    (102, 12, 1000, OpType::Put),
+                    (103, 13, 1000, OpType::Put),
+                ],
+                &[(103, 2, 999, OpType::Put)],
+            ],
+        )
+        .await;
+        let expect = [
+            (100, Some(3)),
+            (101, Some(2)),
+            (102, Some(12)),
+            (103, Some(2)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_sum() {
+        let result = collect_with_strategy(
+            DedupStrategy::Sum,
+            &[
+                // key, value, sequence, op_type
+                &[
+                    (100, 1, 1000, OpType::Put),
+                    (100, 2, 999, OpType::Put),
+                    (100, 3, 998, OpType::Put),
+                    (101, 1, 1000, OpType::Put),
+                ],
+                &[],
+                &[(101, 2, 999, OpType::Put)],
+                &[
+                    (102, 5, 1000, OpType::Put),
+                    (102, 0, 999, OpType::Delete),
+                    (102, 7, 998, OpType::Put),
+                    (103, 0, 1000, OpType::Delete),
+                ],
+                &[(103, 4, 999, OpType::Put), (104, 8, 1000, OpType::Put)],
+            ],
+        )
+        .await;
+        let expect = [
+            (100, Some(6)),
+            (101, Some(3)),
+            (102, Some(5)),
+            (104, Some(8)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_max_min() {
+        let batches: &[&[(i64, i64, u64, OpType)]] = &[
+            // key, value, sequence, op_type
+            &[
+                (100, 2, 1000, OpType::Put),
+                (100, 3, 999, OpType::Put),
+                (100, 1, 998, OpType::Put),
+            ],
+            &[(100, 4, 997, OpType::Put), (101, 5, 1000, OpType::Put)],
+        ];
+
+        let result = collect_with_strategy(DedupStrategy::Max, batches).await;
+        assert_eq!(&[(100, Some(4)), (101, Some(5))], &result[..]);
+
+        let result = collect_with_strategy(DedupStrategy::Min, batches).await;
+        assert_eq!(&[(100, Some(1)), (101, Some(5))], &result[..]);
+    }
+
+    #[test]
+    fn test_sum_values() {
+        assert_eq!(
+            Some(Value::Int64(3)),
+            sum_values(Value::Int64(1), Value::Int64(2))
+        );
+        assert_eq!(
+            Some(Value::Float64(3.5.into())),
+            sum_values(Value::Float64(1.5.into()), Value::Float64(2.0.into()))
+        );
+        assert_eq!(
+            Some(Value::UInt32(2)),
+            sum_values(Value::Null, Value::UInt32(2))
+        );
+        assert_eq!(
+            None,
+            sum_values(Value::String("a".into()), Value::String("b".into()))
+        );
+        assert_eq!(None, sum_values(Value::UInt8(200), Value::UInt8(100)));
+        assert_eq!(None, sum_values(Value::Int64(i64::MIN), Value::Int64(-1)));
+    }
 }
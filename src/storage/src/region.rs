@@ -15,35 +15,46 @@
 #[cfg(test)]
 mod tests;
 mod writer;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_telemetry::logging;
-use snafu::ResultExt;
+use common_time::Timestamp;
+use futures::TryStreamExt;
+use metrics::counter;
+use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, OpenOptions, ReadContext, Region, RegionId, SequenceNumber, WriteContext,
-    WriteResponse,
+    AlterRequest, ChangeBatch, FileCheckStat, OpenOptions, ReadContext, Region, RegionId,
+    RegionStat, ScrubStat, SequenceNumber, WriteContext, WriteResponse,
 };
+use tokio::sync::Mutex;
 
+use crate::encryption::EncryptorRef;
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
+use crate::listener::EventListenerRef;
 use crate::manifest::action::{
-    RawRegionMetadata, RegionChange, RegionMetaAction, RegionMetaActionList,
+    RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
 };
 use crate::manifest::region::RegionManifest;
 use crate::memtable::MemtableBuilderRef;
 use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
+use crate::metric::{
+    METRIC_COLD_FILES_MOVED_TOTAL, METRIC_SCRUB_CORRUPTED_FILES_TOTAL,
+    METRIC_SCRUB_VERIFIED_FILES_TOTAL,
+};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
 use crate::schema::compat::CompatWrite;
 use crate::snapshot::SnapshotImpl;
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileMeta, FileQuarantine, FileQuarantineRef, FileTier};
 use crate::version::{
     Version, VersionControl, VersionControlRef, VersionEdit, INIT_COMMITTED_SEQUENCE,
 };
-use crate::wal::Wal;
+use crate::wal::{Wal, WalRetentionRef};
 use crate::write_batch::WriteBatch;
 
 /// [Region] implementation.
@@ -86,6 +97,14 @@ impl<S: LogStore> Region for RegionImpl<S> {
         self.inner.write(ctx, request).await
     }
 
+    async fn fence(&self, sequence: SequenceNumber) -> Result<()> {
+        self.inner.fence(sequence).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+
     fn snapshot(&self, _ctx: &ReadContext) -> Result<SnapshotImpl> {
         Ok(self.inner.create_snapshot())
     }
@@ -101,6 +120,38 @@ impl<S: LogStore> Region for RegionImpl<S> {
     async fn alter(&self, request: AlterRequest) -> Result<()> {
         self.inner.alter(request).await
     }
+
+    fn stat(&self) -> RegionStat {
+        self.inner.stat()
+    }
+
+    async fn scrub(&self) -> Result<ScrubStat> {
+        self.inner.scrub().await
+    }
+
+    async fn check_files(&self, repair: bool) -> Result<FileCheckStat> {
+        self.inner.check_files(repair).await
+    }
+
+    async fn move_cold_files(&self, before: Timestamp) -> Result<usize> {
+        self.inner.move_cold_files(before).await
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        self.inner.refresh().await
+    }
+
+    async fn set_writable(&self) -> Result<()> {
+        self.inner.set_writable().await
+    }
+
+    async fn read_changes(
+        &self,
+        start_sequence: SequenceNumber,
+        limit: usize,
+    ) -> Result<Vec<ChangeBatch>> {
+        self.inner.read_changes(start_sequence, limit).await
+    }
 }
 
 /// Storage related config for region.
@@ -114,6 +165,12 @@ pub struct StoreConfig<S> {
     pub memtable_builder: MemtableBuilderRef,
     pub flush_scheduler: FlushSchedulerRef,
     pub flush_strategy: FlushStrategyRef,
+    /// Encryptor of WAL entries, WAL entries are not encrypted if not set.
+    pub encryptor: Option<EncryptorRef>,
+    /// Listener of events of the region, e.g. flush.
+    pub event_listener: EventListenerRef,
+    /// Keeps flushed entries in the WAL, flushed entries are purged if not set.
+    pub wal_retention: Option<WalRetentionRef>,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
@@ -155,20 +212,29 @@ impl<S: LogStore> RegionImpl<S> {
         let id = metadata.id();
         let name = metadata.name().to_string();
         let version_control = VersionControl::with_version(version);
-        let wal = Wal::new(id, store_config.log_store);
+        let wal = Wal::new(id, store_config.log_store)
+            .with_encryptor(store_config.encryptor)
+            .with_retention(store_config.wal_retention);
 
         let inner = Arc::new(RegionInner {
             shared: Arc::new(SharedData {
                 id,
                 name,
                 version_control: Arc::new(version_control),
+                last_write_millis: AtomicI64::new(0),
             }),
             writer: Arc::new(RegionWriter::new(store_config.memtable_builder)),
             wal,
             flush_strategy: store_config.flush_strategy,
             flush_scheduler: store_config.flush_scheduler,
+            event_listener: store_config.event_listener,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            written_rows: AtomicU64::new(0),
+            synced_sequence: AtomicU64::new(0),
+            file_quarantine: Arc::new(FileQuarantine::default()),
+            moved_hot_files: Mutex::new(Some(Vec::new())),
+            read_only: AtomicBool::new(false),
         });
 
         RegionImpl { inner }
@@ -180,7 +246,7 @@ impl<S: LogStore> RegionImpl<S> {
     pub async fn open(
         name: String,
         store_config: StoreConfig<S>,
-        _opts: &OpenOptions,
+        opts: &OpenOptions,
     ) -> Result<Option<RegionImpl<S>>> {
         // Load version meta data from manifest.
         let (version, mut recovered_metadata) = match Self::recover_from_manifest(
@@ -202,8 +268,12 @@ impl<S: LogStore> RegionImpl<S> {
         let flushed_sequence = version.flushed_sequence();
         let version_control = Arc::new(VersionControl::with_version(version));
 
-        let recovered_metadata_after_flushed =
-            recovered_metadata.split_off(&(flushed_sequence + 1));
+        // A read-only region doesn't replay the WAL, so it applies the last metadata directly.
+        let recovered_metadata_after_flushed = if opts.read_only {
+            RecoveredMetadataMap::new()
+        } else {
+            recovered_metadata.split_off(&(flushed_sequence + 1))
+        };
         // apply the last flushed metadata
         if let Some((sequence, (manifest_version, metadata))) = recovered_metadata.pop_last() {
             let metadata: RegionMetadataRef = Arc::new(
@@ -228,12 +298,20 @@ impl<S: LogStore> RegionImpl<S> {
             );
         }
 
-        let wal = Wal::new(metadata.id(), store_config.log_store);
-        wal.obsolete(flushed_sequence).await?;
+        let wal = Wal::new(metadata.id(), store_config.log_store)
+            .with_encryptor(store_config.encryptor)
+            .with_retention(store_config.wal_retention);
+        if opts.read_only {
+            // Rows flushed by the writer of the region are visible.
+            version_control.set_committed_sequence(flushed_sequence);
+        } else {
+            wal.obsolete(flushed_sequence).await?;
+        }
         let shared = Arc::new(SharedData {
             id: metadata.id(),
             name,
             version_control,
+            last_write_millis: AtomicI64::new(0),
         });
 
         let writer = Arc::new(RegionWriter::new(store_config.memtable_builder));
@@ -241,15 +319,18 @@ impl<S: LogStore> RegionImpl<S> {
             shared: &shared,
             flush_strategy: &store_config.flush_strategy,
             flush_scheduler: &store_config.flush_scheduler,
+            event_listener: &store_config.event_listener,
             sst_layer: &store_config.sst_layer,
             wal: &wal,
             writer: &writer,
             manifest: &store_config.manifest,
         };
         // Replay all unflushed data.
-        writer
-            .replay(recovered_metadata_after_flushed, writer_ctx)
-            .await?;
+        if !opts.read_only {
+            writer
+                .replay(recovered_metadata_after_flushed, writer_ctx)
+                .await?;
+        }
 
         let inner = Arc::new(RegionInner {
             shared,
@@ -257,18 +338,49 @@ impl<S: LogStore> RegionImpl<S> {
             wal,
             flush_strategy: store_config.flush_strategy,
             flush_scheduler: store_config.flush_scheduler,
+            event_listener: store_config.event_listener,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            written_rows: AtomicU64::new(0),
+            synced_sequence: AtomicU64::new(0),
+            file_quarantine: Arc::new(FileQuarantine::default()),
+            // Collected from the storage by the first move.
+            moved_hot_files: Mutex::new(None),
+            read_only: AtomicBool::new(opts.read_only),
         });
 
         Ok(Some(RegionImpl { inner }))
     }
 
+    /// Reads the statistics of a region from its manifest without opening the region, rows
+    /// in the WAL are not counted. Returns `None` if the region doesn't exist.
+    pub async fn stat_from_manifest(store_config: &StoreConfig<S>) -> Result<Option<RegionStat>> {
+        let (version, _) =
+            Self::recover_from_manifest(&store_config.manifest, &store_config.memtable_builder)
+                .await?;
+        Ok(version.map(|version| {
+            let ssts = version.ssts();
+            RegionStat {
+                region_id: version.metadata().id(),
+                approximate_rows: ssts.num_rows(),
+                sst_bytes: ssts.file_size(),
+                sst_files: ssts.files().count() as u64,
+                ..Default::default()
+            }
+        }))
+    }
+
     /// Get ID of this region.
     pub fn id(&self) -> RegionId {
         self.inner.shared.id()
     }
 
+    /// Waits for the flush in progress before the region is closed, so the region reopened
+    /// later recovers from the manifest updated by the flush.
+    pub(crate) async fn close(&self) -> Result<()> {
+        self.inner.writer.wait_flush_done().await
+    }
+
     async fn recover_from_manifest(
         manifest: &RegionManifest,
         memtable_builder: &MemtableBuilderRef,
@@ -344,9 +456,11 @@ impl<S: LogStore> RegionImpl<S> {
         if let RegionMetaAction::Edit(e) = action {
             let edit = VersionEdit {
                 files_to_add: e.files_to_add,
+                files_to_remove: e.files_to_remove,
                 flushed_sequence: Some(e.flushed_sequence),
                 manifest_version,
                 max_memtable_id: None,
+                last_write_millis: e.last_write_millis,
             };
             version.map(|mut v| {
                 v.apply_edit(edit);
@@ -386,6 +500,7 @@ impl<S: LogStore> RegionImpl<S> {
             shared: &inner.shared,
             flush_strategy: &inner.flush_strategy,
             flush_scheduler: &inner.flush_scheduler,
+            event_listener: &inner.event_listener,
             sst_layer: &inner.sst_layer,
             wal: &inner.wal,
             writer: &inner.writer,
@@ -405,6 +520,9 @@ pub struct SharedData {
     name: String,
     // TODO(yingwen): Maybe no need to use Arc for version control.
     pub version_control: VersionControlRef,
+    /// Unix timestamp in milliseconds of the last write written or replayed since the
+    /// region is opened, 0 if none.
+    last_write_millis: AtomicI64,
 }
 
 impl SharedData {
@@ -417,6 +535,17 @@ impl SharedData {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Returns the unix timestamp in milliseconds of the last write to the region, which
+    /// is recovered from the manifest and the WAL after reopening, `None` if not known.
+    pub fn last_write_millis(&self) -> Option<i64> {
+        let millis = Some(self.last_write_millis.load(Ordering::Relaxed)).filter(|m| *m > 0);
+        millis.max(self.version_control.current().last_write_millis())
+    }
+
+    pub(crate) fn update_last_write_millis(&self, millis: i64) {
+        let _ = self.last_write_millis.fetch_max(millis, Ordering::Relaxed);
+    }
 }
 
 pub type SharedDataRef = Arc<SharedData>;
@@ -428,8 +557,23 @@ struct RegionInner<S: LogStore> {
     wal: Wal<S>,
     flush_strategy: FlushStrategyRef,
     flush_scheduler: FlushSchedulerRef,
+    event_listener: EventListenerRef,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
+    /// Number of rows written since the region is opened.
+    written_rows: AtomicU64,
+    /// Writes whose sequence is less than or equal to this sequence are synced to the WAL.
+    synced_sequence: AtomicU64,
+    /// Files failed to read or verify.
+    file_quarantine: FileQuarantineRef,
+    /// Hot copies of the files moved to the cold tier. They are deleted by the next move so
+    /// snapshots of older versions could still read them. The lock also serializes moves.
+    ///
+    /// `None` if the hot copies left by moves before the region is opened are not collected
+    /// yet, they are listed from the storage as the manifest only records the cold ones.
+    moved_hot_files: Mutex<Option<Vec<String>>>,
+    /// Whether the region is opened read-only, see [OpenOptions::read_only].
+    read_only: AtomicBool,
 }
 
 impl<S: LogStore> RegionInner<S> {
@@ -448,7 +592,12 @@ impl<S: LogStore> RegionInner<S> {
         let version = self.version_control().current();
         let sequence = self.version_control().committed_sequence();
 
-        SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+        SnapshotImpl::new(
+            version,
+            sequence,
+            self.sst_layer.clone(),
+            self.file_quarantine.clone(),
+        )
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -460,20 +609,364 @@ impl<S: LogStore> RegionInner<S> {
         request.compat_write(schema.user_schema())
     }
 
+    fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            !self.read_only.load(Ordering::Acquire),
+            error::ReadOnlyRegionSnafu {
+                region: &self.shared.name,
+            }
+        );
+        Ok(())
+    }
+
     /// Write to writer directly.
     async fn write(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
+        self.ensure_writable()?;
         let writer_ctx = WriterContext {
             shared: &self.shared,
             flush_strategy: &self.flush_strategy,
             flush_scheduler: &self.flush_scheduler,
+            event_listener: &self.event_listener,
             sst_layer: &self.sst_layer,
             wal: &self.wal,
             writer: &self.writer,
             manifest: &self.manifest,
         };
+        let num_rows = request.num_rows_to_mutate() as u64;
         // The writer would also try to compat the schema of write batch if it finds out the
         // schema version of request is less than current schema version.
-        self.writer.write(ctx, request, writer_ctx).await
+        let response = self.writer.write(ctx, request, writer_ctx).await?;
+        self.written_rows.fetch_add(num_rows, Ordering::Relaxed);
+        Ok(response)
+    }
+
+    async fn fence(&self, sequence: SequenceNumber) -> Result<()> {
+        if sequence <= self.synced_sequence.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        // Writes are appended to the WAL before the committed sequence is bumped, so all
+        // writes until the committed sequence are durable once the WAL is synced.
+        let committed_sequence = self.version_control().committed_sequence();
+        self.wal.sync().await?;
+        self.synced_sequence
+            .fetch_max(committed_sequence, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        // Read-only regions never write memtables.
+        if self.read_only.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            event_listener: &self.event_listener,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+        self.writer.flush(writer_ctx).await
+    }
+
+    fn stat(&self) -> RegionStat {
+        let version = self.version_control().current();
+        let memtables = version.memtables();
+        let ssts = version.ssts();
+
+        RegionStat {
+            region_id: self.shared.id,
+            approximate_rows: memtables.total_num_rows() as u64 + ssts.num_rows(),
+            memtable_bytes: memtables.total_bytes_allocated() as u64,
+            sst_bytes: ssts.file_size(),
+            sst_files: ssts.files().count() as u64,
+            written_rows: self.written_rows.load(Ordering::Relaxed),
+            last_write_millis: self.shared.last_write_millis(),
+            corrupted_files: self.file_quarantine.files(),
+        }
+    }
+
+    async fn scrub(&self) -> Result<ScrubStat> {
+        let version = self.version_control().current();
+        let mut stat = ScrubStat::default();
+        for file in version.ssts().files() {
+            // Files written before checksums are recorded are verified by decoding them.
+            match self
+                .sst_layer
+                .verify_sst(file.file_name(), file.tier(), file.checksum())
+                .await
+            {
+                Ok(()) => {
+                    self.file_quarantine.remove(file.file_name());
+                    stat.verified_files += 1;
+                }
+                // The file may be fine, it's verified again in the next scrub.
+                Err(e) if !e.is_corrupted_sst() => {
+                    logging::warn!(
+                        "Failed to read SST file {} of region {} to verify, err: {:?}",
+                        file.file_name(),
+                        self.shared.name,
+                        e
+                    );
+                }
+                Err(e) => {
+                    self.file_quarantine.add(file.file_name());
+                    logging::error!(
+                        e; "Failed to verify SST file {} of region {}",
+                        file.file_name(),
+                        self.shared.name
+                    );
+                    stat.corrupted_files.push(file.file_name().to_string());
+                }
+            }
+        }
+
+        let region = self.shared.name.clone();
+        counter!(
+            METRIC_SCRUB_VERIFIED_FILES_TOTAL,
+            stat.verified_files as u64,
+            "region" => region.clone()
+        );
+        counter!(
+            METRIC_SCRUB_CORRUPTED_FILES_TOTAL,
+            stat.corrupted_files.len() as u64,
+            "region" => region
+        );
+        Ok(stat)
+    }
+
+    async fn check_files(&self, repair: bool) -> Result<FileCheckStat> {
+        // Files referenced by the version are not purged until the version is dropped, so
+        // they are listed after the version is taken.
+        let version = self.version_control().current();
+        let mut listed = HashSet::new();
+        for tier in [FileTier::Hot, FileTier::Cold] {
+            if tier == FileTier::Cold && !self.sst_layer.has_cold_tier() {
+                continue;
+            }
+            for file_name in self.sst_layer.list_ssts(tier).await? {
+                listed.insert((file_name, tier));
+            }
+        }
+
+        let mut stat = FileCheckStat::default();
+        let mut files_to_remove = Vec::new();
+        for file in version.ssts().files() {
+            stat.referenced_files += 1;
+            if !listed.remove(&(file.file_name().to_string(), file.tier())) {
+                stat.missing_files.push(file.file_name().to_string());
+                files_to_remove.push(file.meta().clone());
+            }
+        }
+        let mut orphan_files = listed
+            .into_iter()
+            .map(|(file_name, _)| file_name)
+            .collect::<Vec<_>>();
+        orphan_files.sort_unstable();
+        stat.orphan_files = orphan_files;
+
+        if !stat.missing_files.is_empty() {
+            logging::warn!(
+                "Missing SST files {:?} of region {}",
+                stat.missing_files,
+                self.shared.name
+            );
+        }
+        // Files of a read-only region are repaired by the writer of the region.
+        if !repair || files_to_remove.is_empty() || self.read_only.load(Ordering::Acquire) {
+            return Ok(stat);
+        }
+
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: version.flushed_sequence(),
+            files_to_add: vec![],
+            files_to_remove,
+            last_write_millis: self.shared.last_write_millis(),
+        };
+        self.writer
+            .write_edit_and_apply(&self.wal, &self.shared, &self.manifest, edit, None)
+            .await?;
+        for file_name in &stat.missing_files {
+            self.file_quarantine.remove(file_name);
+        }
+        stat.repaired = true;
+
+        logging::info!(
+            "Removed references to missing SST files {:?} of region {}",
+            stat.missing_files,
+            self.shared.name
+        );
+        Ok(stat)
+    }
+
+    /// Lists hot copies of the files in the cold tier of `version`, which are left by moves
+    /// before the region is opened.
+    async fn list_moved_hot_files(&self, version: &Version) -> Result<Vec<String>> {
+        let cold_files = version
+            .ssts()
+            .files()
+            .filter(|file| file.tier() == FileTier::Cold)
+            .map(|file| file.file_name())
+            .collect::<HashSet<_>>();
+        if cold_files.is_empty() {
+            return Ok(Vec::new());
+        }
+        let hot_files = self.sst_layer.list_ssts(FileTier::Hot).await?;
+        Ok(hot_files
+            .into_iter()
+            .filter(|file_name| cold_files.contains(file_name.as_str()))
+            .collect())
+    }
+
+    async fn move_cold_files(&self, before: Timestamp) -> Result<usize> {
+        // Files of a read-only region are moved by the writer of the region.
+        if !self.sst_layer.has_cold_tier() || self.read_only.load(Ordering::Acquire) {
+            return Ok(0);
+        }
+        let mut moved_hot_files = self.moved_hot_files.lock().await;
+        let version = self.version_control().current();
+        let hot_files = match moved_hot_files.take() {
+            Some(hot_files) => hot_files,
+            None => self.list_moved_hot_files(&version).await?,
+        };
+        let mut undeleted = Vec::new();
+        for file_name in hot_files {
+            if let Err(e) = self.sst_layer.delete_sst(&file_name, FileTier::Hot).await {
+                logging::warn!(
+                    "Failed to delete hot SST file {} of region {}, err: {:?}",
+                    file_name,
+                    self.shared.name,
+                    e
+                );
+                // Retries the file in the next move.
+                undeleted.push(file_name);
+            }
+        }
+        let moved_hot_files = moved_hot_files.insert(undeleted);
+
+        let mut files_to_remove = Vec::new();
+        for file in version.ssts().files() {
+            let is_cold = file
+                .end_timestamp()
+                .map_or(false, |end_timestamp| end_timestamp < before);
+            if file.tier() != FileTier::Hot || !is_cold {
+                continue;
+            }
+            match self.sst_layer.copy_to_cold(file.file_name()).await {
+                Ok(()) => files_to_remove.push(file.meta().clone()),
+                // Retries the file in the next move.
+                Err(e) => logging::warn!(
+                    "Failed to move SST file {} of region {} to cold tier, err: {:?}",
+                    file.file_name(),
+                    self.shared.name,
+                    e
+                ),
+            }
+        }
+        if files_to_remove.is_empty() {
+            return Ok(0);
+        }
+
+        let files_to_add = files_to_remove
+            .iter()
+            .map(|meta| FileMeta {
+                tier: FileTier::Cold,
+                ..meta.clone()
+            })
+            .collect();
+        let num_files = files_to_remove.len();
+        moved_hot_files.extend(files_to_remove.iter().map(|meta| meta.file_name.clone()));
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: version.flushed_sequence(),
+            files_to_add,
+            files_to_remove,
+            last_write_millis: self.shared.last_write_millis(),
+        };
+        self.writer
+            .write_edit_and_apply(&self.wal, &self.shared, &self.manifest, edit, None)
+            .await?;
+
+        logging::info!(
+            "Moved {} SST files of region {} to cold tier",
+            num_files,
+            self.shared.name
+        );
+        counter!(
+            METRIC_COLD_FILES_MOVED_TOTAL,
+            num_files as u64,
+            "region" => self.shared.name.clone()
+        );
+        Ok(num_files)
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        if !self.read_only.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        self.writer.refresh(&self.shared, &self.manifest).await
+    }
+
+    async fn set_writable(&self) -> Result<()> {
+        if !self.read_only.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        self.writer.refresh(&self.shared, &self.manifest).await?;
+        // Entries of the region left in the WAL, e.g. by a former writer on this node, are
+        // older than the flushed data.
+        let flushed_sequence = self.version_control().current().flushed_sequence();
+        self.wal.obsolete(flushed_sequence).await?;
+        self.read_only.store(false, Ordering::Release);
+
+        logging::info!(
+            "Region {} is writable, flushed sequence: {}",
+            self.shared.name,
+            flushed_sequence
+        );
+        Ok(())
+    }
+
+    async fn read_changes(
+        &self,
+        start_sequence: SequenceNumber,
+        limit: usize,
+    ) -> Result<Vec<ChangeBatch>> {
+        let committed_sequence = self.version_control().committed_sequence();
+        let mut batches = Vec::new();
+        if limit == 0 || start_sequence > committed_sequence {
+            return Ok(batches);
+        }
+
+        let mut stream = self.wal.read_from_wal(start_sequence).await?;
+        let mut num_writes = 0;
+        while let Some((sequence, _header, payload)) = stream.try_next().await? {
+            if sequence < start_sequence {
+                continue;
+            }
+            // Writes after the committed sequence are not visible yet.
+            if sequence > committed_sequence {
+                break;
+            }
+            // Entries without payload, e.g. entries written by alter, have no rows.
+            let Some(payload) = payload else { continue };
+            for mutation in payload.mutations {
+                batches.push(ChangeBatch {
+                    sequence,
+                    op_type: mutation.op_type,
+                    schema: payload.schema.clone(),
+                    columns: mutation.record_batch.columns().to_vec(),
+                });
+            }
+            num_writes += 1;
+            if num_writes >= limit {
+                break;
+            }
+        }
+        Ok(batches)
     }
 
     async fn alter(&self, request: AlterRequest) -> Result<()> {
@@ -483,6 +976,7 @@ impl<S: LogStore> RegionInner<S> {
             self.shared.name,
             request
         );
+        self.ensure_writable()?;
 
         let alter_ctx = AlterContext {
             shared: &self.shared,
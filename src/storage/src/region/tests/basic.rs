@@ -16,7 +16,7 @@
 
 use common_telemetry::info;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{OpenOptions, SequenceNumber, WriteResponse};
+use store_api::storage::{Durability, OpType, OpenOptions, Region, SequenceNumber, WriteResponse};
 use tempdir::TempDir;
 
 use crate::error::Result;
@@ -274,3 +274,72 @@ async fn test_put_delete_absent_key() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_async_durability_and_fence() {
+    let dir = TempDir::new("async-durability").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+    tester.base.as_mut().unwrap().write_ctx.durability = Durability::Async;
+
+    let data = vec![(1000, Some(100)), (1001, Some(101))];
+    let resp = tester.put(&data).await;
+    assert_eq!(tester.committed_sequence(), resp.sequence);
+    let resp = tester.put(&[(1002, Some(102))]).await;
+    assert_eq!(tester.committed_sequence(), resp.sequence);
+
+    // Acknowledged writes are visible before the fence.
+    let expect = vec![(1000, Some(100)), (1001, Some(101)), (1002, Some(102))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    let region = &tester.base().region;
+    region.fence(resp.sequence).await.unwrap();
+    // Fencing a synced sequence is a no-op.
+    region.fence(resp.sequence - 1).await.unwrap();
+
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_read_changes() {
+    let dir = TempDir::new("read-changes").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+
+    let first = tester.put(&[(1000, Some(100)), (1001, Some(101))]).await;
+    let second = tester.put(&[(1002, Some(102))]).await;
+    let third = tester.delete(&[1000]).await;
+
+    let region = &tester.base().region;
+    let batches = region.read_changes(0, 10).await.unwrap();
+    let changes: Vec<_> = batches
+        .iter()
+        .map(|b| (b.sequence, b.op_type, b.num_rows()))
+        .collect();
+    assert_eq!(
+        vec![
+            (first.sequence, OpType::Put, 2),
+            (second.sequence, OpType::Put, 1),
+            (third.sequence, OpType::Delete, 1),
+        ],
+        changes
+    );
+
+    // Reads from the second write, at most one write.
+    let batches = region.read_changes(second.sequence, 1).await.unwrap();
+    assert_eq!(1, batches.len());
+    assert_eq!(second.sequence, batches[0].sequence);
+
+    // Writes after the committed sequence can't be read.
+    assert!(region
+        .read_changes(third.sequence + 1, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Changes are read from the WAL after reopen.
+    tester.reopen().await;
+    let batches = tester.base().region.read_changes(0, 10).await.unwrap();
+    assert_eq!(3, batches.len());
+}
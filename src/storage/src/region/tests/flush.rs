@@ -15,16 +15,24 @@
 //! Region flush tests.
 
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use common_time::Timestamp;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
-use store_api::storage::{OpenOptions, WriteResponse};
+use object_store::backend::fs::Builder;
+use object_store::ObjectStore;
+use store_api::storage::{
+    OpenOptions, ReadContext, Region, ScanRequest, Snapshot, WriteContext, WriteResponse,
+};
 use tempdir::TempDir;
 
 use crate::engine;
+use crate::error::Error;
 use crate::flush::{FlushStrategy, FlushStrategyRef};
+use crate::listener::{EventListener, EventListeners, FlushBegin, FlushEnd};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::{RegionImpl, SharedDataRef};
+use crate::sst::{FileTier, FsAccessLayer};
 use crate::test_util::config_util;
 
 const REGION_NAME: &str = "region-flush-0";
@@ -35,9 +43,24 @@ async fn create_region_for_flush(
     enable_version_column: bool,
     flush_strategy: FlushStrategyRef,
 ) -> RegionImpl<RaftEngineLogStore> {
-    let metadata = tests::new_metadata(REGION_NAME, enable_version_column);
+    create_named_region_for_flush(
+        REGION_NAME,
+        store_dir,
+        enable_version_column,
+        flush_strategy,
+    )
+    .await
+}
 
-    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+async fn create_named_region_for_flush(
+    region_name: &str,
+    store_dir: &str,
+    enable_version_column: bool,
+    flush_strategy: FlushStrategyRef,
+) -> RegionImpl<RaftEngineLogStore> {
+    let metadata = tests::new_metadata(region_name, enable_version_column);
+
+    let mut store_config = config_util::new_store_config(region_name, store_dir).await;
     store_config.flush_strategy = flush_strategy;
 
     RegionImpl::create(metadata, store_config).await.unwrap()
@@ -46,16 +69,28 @@ async fn create_region_for_flush(
 /// Tester for region flush.
 struct FlushTester {
     base: Option<FileTesterBase>,
+    region_name: String,
     store_dir: String,
     flush_strategy: FlushStrategyRef,
 }
 
 impl FlushTester {
     async fn new(store_dir: &str, flush_strategy: FlushStrategyRef) -> FlushTester {
-        let region = create_region_for_flush(store_dir, false, flush_strategy.clone()).await;
+        FlushTester::with_region_name(REGION_NAME, store_dir, flush_strategy).await
+    }
+
+    async fn with_region_name(
+        region_name: &str,
+        store_dir: &str,
+        flush_strategy: FlushStrategyRef,
+    ) -> FlushTester {
+        let region =
+            create_named_region_for_flush(region_name, store_dir, false, flush_strategy.clone())
+                .await;
 
         FlushTester {
             base: Some(FileTesterBase::with_region(region)),
+            region_name: region_name.to_string(),
             store_dir: store_dir.to_string(),
             flush_strategy: flush_strategy.clone(),
         }
@@ -68,10 +103,11 @@ impl FlushTester {
         }
         self.base = None;
         // Reopen the region.
-        let mut store_config = config_util::new_store_config(REGION_NAME, &self.store_dir).await;
+        let mut store_config =
+            config_util::new_store_config(&self.region_name, &self.store_dir).await;
         store_config.flush_strategy = self.flush_strategy.clone();
         let opts = OpenOptions::default();
-        let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+        let region = RegionImpl::open(self.region_name.clone(), store_config, &opts)
             .await
             .unwrap()
             .unwrap();
@@ -190,6 +226,109 @@ async fn test_flush_empty() {
     assert_eq!(expect, output);
 }
 
+/// Records (is begin, flush sequence, memtables or files) of flush events.
+#[derive(Debug, Default)]
+struct FlushRecorder {
+    events: Mutex<Vec<(bool, u64, usize)>>,
+}
+
+impl EventListener for FlushRecorder {
+    fn on_flush_begin(&self, event: &FlushBegin) {
+        assert_eq!(REGION_NAME, event.region_name);
+        self.events
+            .lock()
+            .unwrap()
+            .push((true, event.flush_sequence, event.memtables));
+    }
+
+    fn on_flush_end(&self, event: &FlushEnd) {
+        assert!(event.error.is_none());
+        self.events
+            .lock()
+            .unwrap()
+            .push((false, event.flush_sequence, event.files.len()));
+    }
+}
+
+#[tokio::test]
+async fn test_flush_event_listener() {
+    let dir = TempDir::new("flush-event-listener").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let recorder = Arc::new(FlushRecorder::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    store_config.event_listener = Arc::new(EventListeners::new(vec![recorder.clone()]));
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(2, events.len());
+    let (begin, begin_sequence, memtables) = events[0];
+    assert!(begin);
+    assert!(memtables > 0);
+    let (begin, end_sequence, files) = events[1];
+    assert!(!begin);
+    assert_eq!(begin_sequence, end_sequence);
+    assert_eq!(1, files);
+}
+
+#[tokio::test]
+async fn test_region_stat() {
+    let dir = TempDir::new("region-stat").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+    assert!(tester.base().region.stat().last_write_millis.is_none());
+
+    let before_write = common_time::util::current_time_millis();
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    let stat = tester.base().region.stat();
+    assert_eq!(tester.base().region.id(), stat.region_id);
+    assert_eq!(2, stat.written_rows);
+    assert!(stat.last_write_millis.unwrap() >= before_write);
+    assert_eq!(2, stat.approximate_rows);
+    assert!(stat.memtable_bytes > 0);
+    assert_eq!(0, stat.sst_bytes);
+
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+
+    let stat = tester.base().region.stat();
+    assert_eq!(3, stat.written_rows);
+    assert_eq!(3, stat.approximate_rows);
+    assert!(stat.sst_bytes > 0);
+
+    // The time of the last write is recovered from the manifest after reopening.
+    let last_write_millis = stat.last_write_millis;
+    tester.reopen().await;
+    let stat = tester.base().region.stat();
+    assert_eq!(0, stat.written_rows);
+    assert_eq!(last_write_millis, stat.last_write_millis);
+
+    // And from the WAL if the write is not flushed.
+    flush_switch.set_should_flush(false);
+    tester.put(&[(4000, Some(400))]).await;
+    let last_write_millis = tester.base().region.stat().last_write_millis;
+    assert!(last_write_millis >= stat.last_write_millis);
+    tester.reopen().await;
+    assert_eq!(
+        last_write_millis,
+        tester.base().region.stat().last_write_millis
+    );
+}
+
 #[tokio::test]
 async fn test_read_after_flush() {
     common_telemetry::init_default_ut_logging();
@@ -269,3 +408,426 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_scrub() {
+    let dir = TempDir::new("scrub").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+    let region = &tester.base().region;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    let stat = region.scrub().await.unwrap();
+    assert_eq!(1, stat.verified_files);
+    assert!(stat.corrupted_files.is_empty());
+
+    // Flip a byte of the SST file.
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    let entry = std::fs::read_dir(&sst_dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut bytes = std::fs::read(entry.path()).unwrap();
+    bytes[0] ^= 0xff;
+    std::fs::write(entry.path(), bytes).unwrap();
+
+    let file_name = entry.file_name().to_str().unwrap().to_string();
+    let stat = region.scrub().await.unwrap();
+    assert_eq!(0, stat.verified_files);
+    assert_eq!(vec![file_name.clone()], stat.corrupted_files);
+    assert_eq!(vec![file_name], region.stat().corrupted_files);
+}
+
+#[tokio::test]
+async fn test_quarantine_unreadable_sst() {
+    let dir = TempDir::new("quarantine").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+    let region = &tester.base().region;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    // Move the SST file away.
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    let entry = std::fs::read_dir(&sst_dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let file_name = entry.file_name().to_str().unwrap().to_string();
+    let moved = dir.path().join(&file_name);
+    std::fs::rename(entry.path(), &moved).unwrap();
+
+    let read_ctx = ReadContext::default();
+    let scan = || async {
+        let snapshot = region.snapshot(&read_ctx).unwrap();
+        snapshot.scan(&read_ctx, ScanRequest::default()).await
+    };
+    // Failures to access the file don't imply it's corrupted.
+    let err = scan().await.err().unwrap();
+    assert!(matches!(err, Error::ReadObject { .. }), "{err:?}");
+    assert!(region.stat().corrupted_files.is_empty());
+
+    std::fs::write(entry.path(), b"not a parquet file").unwrap();
+    let err = scan().await.err().unwrap();
+    assert!(matches!(err, Error::ReadParquet { .. }), "{err:?}");
+    assert_eq!(vec![file_name.clone()], region.stat().corrupted_files);
+
+    // Reads fail fast even if the file is back, until a scrub verifies it.
+    std::fs::rename(&moved, entry.path()).unwrap();
+    let err = scan().await.err().unwrap();
+    assert!(matches!(err, Error::QuarantinedSst { .. }), "{err:?}");
+
+    let stat = region.scrub().await.unwrap();
+    assert_eq!(1, stat.verified_files);
+    assert!(region.stat().corrupted_files.is_empty());
+    assert_eq!(
+        vec![(1000, Some(100)), (2000, Some(200))],
+        tester.full_scan().await
+    );
+}
+
+#[tokio::test]
+async fn test_check_files() {
+    let dir = TempDir::new("check_files").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+    let region = &tester.base().region;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    let stat = region.check_files(false).await.unwrap();
+    assert_eq!(1, stat.referenced_files);
+    assert!(stat.missing_files.is_empty());
+    assert!(stat.orphan_files.is_empty());
+
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    let entry = std::fs::read_dir(&sst_dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .find(|entry| entry.file_name().to_str().unwrap().ends_with(".parquet"))
+        .unwrap();
+    let file_name = entry.file_name().to_str().unwrap().to_string();
+    std::fs::remove_file(entry.path()).unwrap();
+    std::fs::write(format!("{sst_dir}/orphan.parquet"), b"orphan").unwrap();
+
+    let stat = region.check_files(false).await.unwrap();
+    assert_eq!(vec![file_name.clone()], stat.missing_files);
+    assert_eq!(vec!["orphan.parquet".to_string()], stat.orphan_files);
+    assert!(!stat.repaired);
+
+    // The reference to the missing file is removed, so the region could be read again.
+    let stat = region.check_files(true).await.unwrap();
+    assert_eq!(vec![file_name], stat.missing_files);
+    assert!(stat.repaired);
+    let output = tester.full_scan().await;
+    assert!(!output.contains(&(1000, Some(100))));
+
+    let stat = region.check_files(true).await.unwrap();
+    assert_eq!(0, stat.referenced_files);
+    assert!(stat.missing_files.is_empty());
+    assert!(!stat.repaired);
+}
+
+#[tokio::test]
+async fn test_read_only_region() {
+    let dir = TempDir::new("read-only-region").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let standby_log = TempDir::new("read-only-region-log").unwrap();
+    let standby_log_dir = standby_log.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    // Open the region read-only with another WAL.
+    let store_config =
+        config_util::new_store_config_with_log_dir(REGION_NAME, store_dir, standby_log_dir).await;
+    let opts = OpenOptions {
+        read_only: true,
+        ..Default::default()
+    };
+    let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+        .await
+        .unwrap()
+        .unwrap();
+    let standby = FileTesterBase::with_region(region);
+    // Only flushed rows are visible.
+    assert_eq!(vec![(1000, Some(100))], standby.full_scan().await);
+
+    let write_batch = standby.region.write_request();
+    let err = standby
+        .region
+        .write(&WriteContext::default(), write_batch)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::ReadOnlyRegion { .. }), "{err:?}");
+
+    // Flush another file and catch up with it.
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+    assert_eq!(vec![(1000, Some(100))], standby.full_scan().await);
+    standby.region.refresh().await.unwrap();
+    let expect = vec![(1000, Some(100)), (2000, Some(200))];
+    assert_eq!(expect, standby.full_scan().await);
+
+    // Take over writes from the writer, unflushed rows of the writer are lost.
+    tester.base().close().await;
+    standby.region.set_writable().await.unwrap();
+    standby.put(&[(4000, Some(400))]).await;
+    let expect = vec![(1000, Some(100)), (2000, Some(200)), (4000, Some(400))];
+    assert_eq!(expect, standby.full_scan().await);
+}
+
+fn parquet_files(sst_dir: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(sst_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+        .filter(|name| name.ends_with(".parquet"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Create a region whose SST files could be moved to the cold store in `cold_dir`.
+async fn open_region_with_cold_store(
+    store_dir: &str,
+    cold_dir: &str,
+    flush_strategy: FlushStrategyRef,
+    create: bool,
+) -> RegionImpl<RaftEngineLogStore> {
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_strategy;
+    let new_store = |dir: &str| ObjectStore::new(Builder::default().root(dir).build().unwrap());
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    store_config.sst_layer = Arc::new(
+        FsAccessLayer::new(&sst_dir, new_store(store_dir)).with_cold_store(new_store(cold_dir)),
+    );
+
+    if create {
+        let metadata = tests::new_metadata(REGION_NAME, false);
+        RegionImpl::create(metadata, store_config).await.unwrap()
+    } else {
+        RegionImpl::open(
+            REGION_NAME.to_string(),
+            store_config,
+            &OpenOptions::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+    }
+}
+
+fn file_tiers(region: &RegionImpl<RaftEngineLogStore>) -> Vec<(String, FileTier)> {
+    let version = region.inner.version_control().current();
+    let mut tiers: Vec<_> = version
+        .ssts()
+        .files()
+        .map(|file| (file.file_name().to_string(), file.tier()))
+        .collect();
+    tiers.sort_by(|a, b| a.0.cmp(&b.0));
+    tiers
+}
+
+#[tokio::test]
+async fn test_move_cold_files() {
+    let dir = TempDir::new("move-cold-files").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let cold = TempDir::new("move-cold-files-cold").unwrap();
+    let cold_dir = cold.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let region = open_region_with_cold_store(store_dir, cold_dir, flush_switch.clone(), true).await;
+    let tester = FileTesterBase::with_region(region);
+
+    // Flush the old row and the recent row to two files.
+    tester.put(&[(1000, Some(100))]).await;
+    flush_switch.set_should_flush(true);
+    tester.put(&[(100_000, Some(200))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    tester.put(&[(200_000, Some(300))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    let hot_sst_dir = format!("{store_dir}/{sst_dir}");
+    let cold_sst_dir = format!("{cold_dir}/{sst_dir}");
+    assert_eq!(2, parquet_files(&hot_sst_dir).len());
+
+    let before = Timestamp::new_millisecond(50_000);
+    assert_eq!(1, tester.region.move_cold_files(before).await.unwrap());
+    let cold_files = parquet_files(&cold_sst_dir);
+    assert_eq!(1, cold_files.len());
+    let tiers = file_tiers(&tester.region);
+    assert!(tiers.contains(&(cold_files[0].clone(), FileTier::Cold)));
+    assert_eq!(
+        1,
+        tiers
+            .iter()
+            .filter(|(_, tier)| *tier == FileTier::Hot)
+            .count()
+    );
+
+    let expect = vec![
+        (1000, Some(100)),
+        (100_000, Some(200)),
+        (200_000, Some(300)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // Cold files are not moved again, and their hot copies are deleted by the next move.
+    assert_eq!(0, tester.region.move_cold_files(before).await.unwrap());
+    let hot_files = parquet_files(&hot_sst_dir);
+    assert_eq!(1, hot_files.len());
+    assert!(!hot_files.contains(&cold_files[0]));
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The tier of files is recovered from the manifest.
+    tester.close().await;
+    let region =
+        open_region_with_cold_store(store_dir, cold_dir, flush_switch.clone(), false).await;
+    assert_eq!(tiers, file_tiers(&region));
+    let tester = FileTesterBase::with_region(region);
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_delete_hot_copies_after_reopen() {
+    let dir = TempDir::new("delete-hot-copies").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let cold = TempDir::new("delete-hot-copies-cold").unwrap();
+    let cold_dir = cold.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let region = open_region_with_cold_store(store_dir, cold_dir, flush_switch.clone(), true).await;
+    let tester = FileTesterBase::with_region(region);
+    // Flush the old row to a file.
+    tester.put(&[(1000, Some(100))]).await;
+    flush_switch.set_should_flush(true);
+    tester.put(&[(100_000, Some(200))]).await;
+    tester.region.wait_flush_done().await.unwrap();
+    flush_switch.set_should_flush(false);
+
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    let hot_sst_dir = format!("{store_dir}/{sst_dir}");
+    let before = Timestamp::new_millisecond(50_000);
+    assert_eq!(1, tester.region.move_cold_files(before).await.unwrap());
+    // The hot copy is kept until the next move.
+    assert_eq!(1, parquet_files(&hot_sst_dir).len());
+
+    // The hot copy is still deleted by the next move after the region is reopened.
+    tester.close().await;
+    let region =
+        open_region_with_cold_store(store_dir, cold_dir, flush_switch.clone(), false).await;
+    let tester = FileTesterBase::with_region(region);
+    assert_eq!(0, tester.region.move_cold_files(before).await.unwrap());
+    assert!(parquet_files(&hot_sst_dir).is_empty());
+    assert_eq!(
+        vec![(1000, Some(100)), (100_000, Some(200))],
+        tester.full_scan().await
+    );
+}
+
+/// Tests injecting failures into flush, the fail points are scoped by the names of the
+/// regions created by the tests.
+#[cfg(feature = "failpoints")]
+mod failpoint {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_flush_after_failure() {
+        common_telemetry::init_default_ut_logging();
+        let region_name = "region-flush-failure";
+        let dir = TempDir::new("flush-failure").unwrap();
+        let store_dir = dir.path().to_str().unwrap();
+
+        let flush_switch = Arc::new(FlushSwitch::default());
+        let tester =
+            FlushTester::with_region_name(region_name, store_dir, flush_switch.clone()).await;
+
+        fail::cfg("flush_write_sst", &format!("return({region_name})")).unwrap();
+        tester.put(&[(1000, Some(100))]).await;
+        flush_switch.set_should_flush(true);
+        // Put element to trigger flush.
+        tester.put(&[(2000, Some(200))]).await;
+        assert!(tester.base().region.wait_flush_done().await.is_err());
+
+        // The memtables to flush are still readable.
+        let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", region_name));
+        assert!(!has_parquet_file(&sst_dir));
+        let expect = vec![(1000, Some(100)), (2000, Some(200))];
+        assert_eq!(expect, tester.full_scan().await);
+
+        // The next flush also flushes the memtables of the failed flush.
+        fail::remove("flush_write_sst");
+        tester.put(&[(3000, Some(300))]).await;
+        tester.wait_flush_done().await;
+        assert!(has_parquet_file(&sst_dir));
+        let expect = vec![(1000, Some(100)), (2000, Some(200)), (3000, Some(300))];
+        assert_eq!(expect, tester.full_scan().await);
+    }
+
+    #[tokio::test]
+    async fn test_recover_after_crash_in_flush() {
+        common_telemetry::init_default_ut_logging();
+        let region_name = "region-flush-crash";
+        let dir = TempDir::new("flush-crash").unwrap();
+        let store_dir = dir.path().to_str().unwrap();
+
+        let flush_switch = Arc::new(FlushSwitch::default());
+        let mut tester =
+            FlushTester::with_region_name(region_name, store_dir, flush_switch.clone()).await;
+
+        // Crash the flush job after the manifest is written, so the WAL of the flushed
+        // data is not obsoleted.
+        fail::cfg(
+            "flush_before_obsolete_wal",
+            &format!("return({region_name})"),
+        )
+        .unwrap();
+        tester.put(&[(1000, Some(100))]).await;
+        flush_switch.set_should_flush(true);
+        // Put element to trigger flush.
+        tester.put(&[(2000, Some(200))]).await;
+        assert!(tester.base().region.wait_flush_done().await.is_err());
+        fail::remove("flush_before_obsolete_wal");
+
+        let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", region_name));
+        assert!(has_parquet_file(&sst_dir));
+
+        // Data in the SST file is not replayed from the WAL again.
+        flush_switch.set_should_flush(false);
+        tester.reopen().await;
+        let expect = vec![(1000, Some(100)), (2000, Some(200))];
+        assert_eq!(expect, tester.full_scan().await);
+    }
+}
@@ -18,7 +18,7 @@ use std::sync::Arc;
 use datatypes::data_type::ConcreteDataType;
 use datatypes::prelude::ScalarVector;
 use datatypes::type_id::LogicalTypeId;
-use datatypes::vectors::{Int64Vector, TimestampMillisecondVector, VectorRef};
+use datatypes::vectors::{Int64Vector, TimestampMillisecondVector, UInt64Vector, VectorRef};
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use store_api::logstore::LogStore;
 use store_api::storage::{
@@ -117,6 +117,15 @@ fn append_chunk_to(chunk: &Chunk, dst: &mut Vec<Vec<i64>>) {
                         .unwrap();
                     row.push(val.into());
                 }
+                ConcreteDataType::UInt64(_) => {
+                    let val = col
+                        .as_any()
+                        .downcast_ref::<UInt64Vector>()
+                        .unwrap()
+                        .get_data(i)
+                        .unwrap();
+                    row.push(val as i64);
+                }
                 _ => unreachable!(),
             }
         }
@@ -147,10 +156,19 @@ impl<S: LogStore> ProjectionTester<S> {
     }
 
     async fn scan(&self, projection: Option<Vec<usize>>) -> Vec<Vec<i64>> {
+        self.scan_with_sequence(projection, false).await
+    }
+
+    async fn scan_with_sequence(
+        &self,
+        projection: Option<Vec<usize>>,
+        with_sequence: bool,
+    ) -> Vec<Vec<i64>> {
         let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
 
         let request = ScanRequest {
             projection,
+            with_sequence,
             ..Default::default()
         };
         let resp = snapshot.scan(&self.read_ctx, request).await.unwrap();
@@ -203,3 +221,26 @@ async fn test_projection_unordered() {
     let expect = vec![vec![100, 1], vec![101, 2], vec![102, 3], vec![103, 4]];
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_projection_with_sequence() {
+    let dir = TempDir::new("projection-with-sequence").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let tester = new_tester(store_dir).await;
+    tester.put(2, 1, 10, 100).await;
+    // Overwrite the row whose key is 1.
+    tester.put(1, 1, 10, 200).await;
+
+    // k0, v0, __sequence
+    let output = tester.scan_with_sequence(Some(vec![0, 2]), true).await;
+    assert_eq!(2, output.len());
+    assert_eq!(&[1, 200], &output[0][..2]);
+    assert_eq!(&[2, 100], &output[1][..2]);
+    // The overwritten row has a larger sequence.
+    assert!(output[0][2] > output[1][2]);
+
+    // All columns, with __sequence.
+    let output = tester.scan_with_sequence(None, true).await;
+    assert_eq!(5, output[0].len());
+}
@@ -15,16 +15,18 @@
 use std::sync::Arc;
 
 use common_telemetry::logging;
+use common_time::util;
 use futures::TryStreamExt;
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
-use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
-use store_api::storage::{AlterRequest, SequenceNumber, WriteContext, WriteResponse};
+use store_api::manifest::{self, Manifest, ManifestVersion, MetaAction, MetaActionIterator};
+use store_api::storage::{AlterRequest, Durability, SequenceNumber, WriteContext, WriteResponse};
 use tokio::sync::Mutex;
 
 use crate::background::JobHandle;
 use crate::error::{self, Result};
 use crate::flush::{FlushJob, FlushSchedulerRef, FlushStrategyRef};
+use crate::listener::EventListenerRef;
 use crate::manifest::action::{
     RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
 };
@@ -76,6 +78,27 @@ impl RegionWriter {
             .await
     }
 
+    /// Waits for the flush job in progress to finish.
+    pub async fn wait_flush_done(&self) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        if let Some(handle) = inner.flush_handle.take() {
+            handle.join().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Flushes all memtables and waits for the flush job to finish.
+    pub async fn flush<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        inner.trigger_flush(&writer_ctx).await?;
+        if let Some(handle) = inner.flush_handle.take() {
+            handle.join().await?;
+        }
+
+        Ok(())
+    }
+
     /// Replay data to memtables.
     pub async fn replay<S: LogStore>(
         &self,
@@ -88,14 +111,15 @@ impl RegionWriter {
             .await
     }
 
-    /// Write and apply the region edit.
+    /// Write and apply the region edit, memtables whose id is less than or equal to
+    /// `max_memtable_id` are removed if it is set.
     pub(crate) async fn write_edit_and_apply<S: LogStore>(
         &self,
         wal: &Wal<S>,
         shared: &SharedDataRef,
         manifest: &RegionManifest,
         edit: RegionEdit,
-        max_memtable_id: MemtableId,
+        max_memtable_id: Option<MemtableId>,
     ) -> Result<()> {
         let _lock = self.version_mutex.lock().await;
         // HACK: We won't acquire the write lock here because write stall would hold
@@ -112,7 +136,9 @@ impl RegionWriter {
         );
 
         let files_to_add = edit.files_to_add.clone();
+        let files_to_remove = edit.files_to_remove.clone();
         let flushed_sequence = edit.flushed_sequence;
+        let last_write_millis = edit.last_write_millis;
 
         // Persist the meta action.
         let mut action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit));
@@ -121,9 +147,11 @@ impl RegionWriter {
 
         let version_edit = VersionEdit {
             files_to_add,
+            files_to_remove,
             flushed_sequence: Some(flushed_sequence),
             manifest_version,
-            max_memtable_id: Some(max_memtable_id),
+            max_memtable_id,
+            last_write_millis,
         };
 
         // We could tolerate failure during persisting manifest version to the WAL, since it won't
@@ -201,6 +229,70 @@ impl RegionWriter {
             .await
     }
 
+    /// Applies actions persisted to the manifest by the writer of a read-only region since the
+    /// last refresh, the memtables of a read-only region are always empty.
+    pub async fn refresh(&self, shared: &SharedDataRef, manifest: &RegionManifest) -> Result<()> {
+        let inner = self.inner.lock().await;
+        let _lock = self.version_mutex.lock().await;
+
+        let version_control = &shared.version_control;
+        let mut iter = manifest
+            .scan(manifest.last_version(), manifest::MAX_VERSION)
+            .await?;
+        let mut last_manifest_version = None;
+        while let Some((manifest_version, action_list)) = iter.next_action().await? {
+            last_manifest_version = Some(manifest_version);
+            for action in action_list.actions {
+                match action {
+                    RegionMetaAction::Change(change) => {
+                        let metadata: RegionMetadataRef =
+                            Arc::new(change.metadata.try_into().context(
+                                error::InvalidRawRegionSnafu {
+                                    region: shared.name(),
+                                },
+                            )?);
+                        if metadata.version() <= version_control.metadata().version() {
+                            continue;
+                        }
+                        let new_mutable = inner.memtable_builder.build(metadata.schema().clone());
+                        version_control.freeze_mutable_and_apply_metadata(
+                            metadata,
+                            manifest_version,
+                            new_mutable,
+                        );
+                    }
+                    RegionMetaAction::Edit(edit) => {
+                        version_control.apply_edit(VersionEdit {
+                            files_to_add: edit.files_to_add,
+                            files_to_remove: edit.files_to_remove,
+                            flushed_sequence: Some(edit.flushed_sequence),
+                            manifest_version,
+                            max_memtable_id: None,
+                            last_write_millis: edit.last_write_millis,
+                        });
+                    }
+                    RegionMetaAction::Protocol(_) | RegionMetaAction::Remove(_) => (),
+                }
+            }
+        }
+
+        if let Some(manifest_version) = last_manifest_version {
+            manifest.update_state(manifest_version + 1, iter.last_protocol().clone());
+            logging::debug!(
+                "Refreshed region {} to manifest version {}",
+                shared.name(),
+                manifest_version
+            );
+        }
+        // Rows flushed by the writer of the region become visible.
+        let flushed_sequence = version_control.current().flushed_sequence();
+        if version_control.committed_sequence() < flushed_sequence {
+            version_control.set_committed_sequence(flushed_sequence);
+        }
+
+        Ok(())
+    }
+
     /// Allocate a sequence and persist the manifest version using that sequence to the wal.
     ///
     /// This method should be protected by the `version_mutex`.
@@ -216,20 +308,8 @@ impl RegionWriter {
         version_control.set_committed_sequence(next_sequence);
 
         let header = WalHeader::with_last_manifest_version(manifest_version);
-        wal.write_to_wal(next_sequence, header, None).await?;
-
-        Ok(())
-    }
-}
-
-// Private methods for tests.
-#[cfg(test)]
-impl RegionWriter {
-    pub async fn wait_flush_done(&self) -> Result<()> {
-        let mut inner = self.inner.lock().await;
-        if let Some(handle) = inner.flush_handle.take() {
-            handle.join().await?;
-        }
+        wal.write_to_wal(next_sequence, header, None, Durability::Sync)
+            .await?;
 
         Ok(())
     }
@@ -239,6 +319,7 @@ pub struct WriterContext<'a, S: LogStore> {
     pub shared: &'a SharedDataRef,
     pub flush_strategy: &'a FlushStrategyRef,
     pub flush_scheduler: &'a FlushSchedulerRef,
+    pub event_listener: &'a EventListenerRef,
     pub sst_layer: &'a AccessLayerRef,
     pub wal: &'a Wal<S>,
     pub writer: &'a RegionWriterRef,
@@ -286,7 +367,7 @@ impl WriterInner {
     async fn write<S: LogStore>(
         &mut self,
         version_mutex: &Mutex<()>,
-        _ctx: &WriteContext,
+        ctx: &WriteContext,
         mut request: WriteBatch,
         writer_ctx: WriterContext<'_, S>,
     ) -> Result<WriteResponse> {
@@ -307,21 +388,33 @@ impl WriterInner {
         let next_sequence = committed_sequence + 1;
 
         let version = version_control.current();
-        let wal_header = WalHeader::with_last_manifest_version(version.manifest_version());
+        let write_millis = util::current_time_millis();
+        let wal_header = WalHeader {
+            write_millis,
+            ..WalHeader::with_last_manifest_version(version.manifest_version())
+        };
         writer_ctx
             .wal
-            .write_to_wal(next_sequence, wal_header, Some(request.payload()))
+            .write_to_wal(
+                next_sequence,
+                wal_header,
+                Some(request.payload()),
+                ctx.durability,
+            )
             .await?;
 
         // Insert batch into memtable.
         let mut inserter = Inserter::new(next_sequence);
         inserter.insert_memtable(request.payload(), version.mutable_memtable())?;
+        writer_ctx.shared.update_last_write_millis(write_millis);
 
         // Update committed_sequence to make current batch visible. The `&mut self` of WriterInner
         // guarantees the writer is exclusive.
         version_control.set_committed_sequence(next_sequence);
 
-        Ok(WriteResponse {})
+        Ok(WriteResponse {
+            sequence: next_sequence,
+        })
     }
 
     async fn replay<S: LogStore>(
@@ -345,7 +438,7 @@ impl WriterInner {
             // Read starts from the first entry after last flushed entry, so the start sequence
             // should be flushed_sequence + 1.
             let mut stream = writer_ctx.wal.read_from_wal(flushed_sequence + 1).await?;
-            while let Some((req_sequence, _header, payload)) = stream.try_next().await? {
+            while let Some((req_sequence, header, payload)) = stream.try_next().await? {
                 while let Some((sequence_before_alter, _)) = next_apply_metadata {
                     // There might be multiple metadata changes to be applied, so a loop is necessary.
                     if req_sequence > sequence_before_alter {
@@ -394,6 +487,9 @@ impl WriterInner {
                     // out of memory during replay, but we need to do it carefully to avoid dead lock.
                     let mut inserter = Inserter::new(last_sequence);
                     inserter.insert_memtable(&payload, version.mutable_memtable())?;
+                    writer_ctx
+                        .shared
+                        .update_last_write_millis(header.write_millis);
                 }
             }
 
@@ -549,6 +645,7 @@ impl WriterInner {
             writer: ctx.writer.clone(),
             wal: ctx.wal.clone(),
             manifest: ctx.manifest.clone(),
+            event_listener: ctx.event_listener.clone(),
         };
 
         let flush_handle = ctx
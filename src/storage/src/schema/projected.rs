@@ -18,10 +18,10 @@ use std::sync::Arc;
 
 use common_base::BitVec;
 use common_error::prelude::*;
-use datatypes::prelude::ScalarVector;
-use datatypes::schema::{SchemaBuilder, SchemaRef};
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
 use datatypes::vectors::{BooleanVector, UInt8Vector};
-use store_api::storage::{Chunk, ColumnId, OpType};
+use store_api::storage::{consts, Chunk, ColumnId, OpType};
 
 use crate::error;
 use crate::metadata::{self, Result};
@@ -192,6 +192,34 @@ impl ProjectedSchema {
         Chunk::new(columns)
     }
 
+    /// Returns the projected user schema with the `__sequence` column appended.
+    pub fn projected_user_schema_with_sequence(&self) -> Result<SchemaRef> {
+        let mut column_schemas = self.projected_user_schema.column_schemas().to_vec();
+        column_schemas.push(ColumnSchema::new(
+            consts::SEQUENCE_COLUMN_NAME,
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ));
+
+        let schema = SchemaBuilder::try_from(column_schemas)
+            .context(metadata::ConvertSchemaSnafu)?
+            .version(self.projected_user_schema.version())
+            .build()
+            .context(metadata::InvalidSchemaSnafu)?;
+
+        Ok(Arc::new(schema))
+    }
+
+    /// Convert [Batch] into [Chunk], and appends the sequence column of the `batch`
+    /// to the output.
+    pub fn batch_to_chunk_with_sequence(&self, batch: &Batch) -> Chunk {
+        let mut chunk = self.batch_to_chunk(batch);
+        chunk
+            .columns
+            .push(batch.column(self.schema_to_read.sequence_index()).clone());
+        chunk
+    }
+
     /// Returns true if column with given `column_id` is needed (in projection).
     pub fn is_needed(&self, column_id: ColumnId) -> bool {
         self.projection
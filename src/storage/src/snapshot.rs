@@ -22,7 +22,7 @@ use store_api::storage::{
 
 use crate::chunk::{ChunkReaderBuilder, ChunkReaderImpl};
 use crate::error::{Error, Result};
-use crate::sst::AccessLayerRef;
+use crate::sst::{AccessLayerRef, FileQuarantineRef};
 use crate::version::VersionRef;
 
 /// [Snapshot] implementation.
@@ -31,6 +31,7 @@ pub struct SnapshotImpl {
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
     sst_layer: AccessLayerRef,
+    file_quarantine: FileQuarantineRef,
 }
 
 #[async_trait]
@@ -42,6 +43,10 @@ impl Snapshot for SnapshotImpl {
         self.version.user_schema()
     }
 
+    fn sequence(&self) -> SequenceNumber {
+        self.visible_sequence
+    }
+
     async fn scan(
         &self,
         ctx: &ReadContext,
@@ -58,8 +63,12 @@ impl Snapshot for SnapshotImpl {
                 .reserve_num_memtables(memtable_version.num_memtables())
                 .projection(request.projection)
                 .filters(request.filters)
+                .with_sequence(request.with_sequence)
+                .append_mode(request.append_mode)
+                .dedup_strategy(request.dedup_strategy)
                 .batch_size(ctx.batch_size)
                 .visible_sequence(visible_sequence)
+                .file_quarantine(self.file_quarantine.clone())
                 .pick_memtables(mutables.clone());
 
         for memtable in immutables {
@@ -81,11 +90,13 @@ impl SnapshotImpl {
         version: VersionRef,
         visible_sequence: SequenceNumber,
         sst_layer: AccessLayerRef,
+        file_quarantine: FileQuarantineRef,
     ) -> SnapshotImpl {
         SnapshotImpl {
             version,
             visible_sequence,
             sst_layer,
+            file_quarantine,
         }
     }
 
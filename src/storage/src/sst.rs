@@ -14,23 +14,43 @@
 
 mod parquet;
 
-use std::sync::Arc;
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use common_telemetry::debug;
 use common_time::Timestamp;
+use crc::{Crc, CRC_32_ISCSI};
+use futures::TryStreamExt;
 use object_store::{util, ObjectStore};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::errors::ParquetError;
 use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
 use table::predicate::Predicate;
 
-use crate::error::Result;
+use crate::encryption::{self, EncryptorRef};
+use crate::error::{
+    DeleteObjectSnafu, ListObjectsSnafu, NoColdStoreSnafu, ReadObjectSnafu, ReadParquetSnafu,
+    Result, SstChecksumMismatchSnafu, WriteObjectSnafu,
+};
 use crate::memtable::BoxedBatchIterator;
 use crate::read::BoxedBatchReader;
 use crate::schema::ProjectedSchemaRef;
 use crate::sst::parquet::{ParquetReader, ParquetWriter};
+use crate::write_behind::SstUploaderRef;
 
 /// Maximum level of SSTs.
 pub const MAX_LEVEL: usize = 1;
 
+const SST_CHECKSUM: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+/// Returns the CRC32 checksum of the content of a SST file.
+pub fn sst_checksum(bytes: &[u8]) -> u32 {
+    SST_CHECKSUM.checksum(bytes)
+}
+
 // We only has fixed number of level, so we array to hold elements. This implement
 // detail of LevelMetaVec should not be exposed to the user of [LevelMetas].
 type LevelMetaVec = [LevelMeta; MAX_LEVEL];
@@ -63,16 +83,21 @@ impl LevelMetas {
     ///
     /// # Panics
     /// Panics if level of [FileHandle] is greater than [MAX_LEVEL].
-    pub fn merge(&self, files_to_add: impl Iterator<Item = FileHandle>) -> LevelMetas {
+    pub fn merge(
+        &self,
+        files_to_add: impl Iterator<Item = FileHandle>,
+        files_to_remove: &[FileMeta],
+    ) -> LevelMetas {
         let mut merged = self.clone();
+        for file in files_to_remove {
+            merged.levels[usize::from(file.level)].remove_file(&file.file_name);
+        }
         for file in files_to_add {
             let level = file.level_index();
 
             merged.levels[level].add_file(file);
         }
 
-        // TODO(yingwen): Support file removal.
-
         merged
     }
 
@@ -88,6 +113,20 @@ impl LevelMetas {
         Ok(())
     }
 
+    /// Returns the total size of all SST files in bytes.
+    pub fn file_size(&self) -> u64 {
+        self.files().map(|file| file.file_size()).sum()
+    }
+
+    /// Returns the total number of rows in all SST files.
+    pub fn num_rows(&self) -> u64 {
+        self.files().map(|file| file.num_rows()).sum()
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &FileHandle> {
+        self.levels.iter().flat_map(|level| level.files.iter())
+    }
+
     #[cfg(test)]
     pub fn levels(&self) -> &[LevelMeta] {
         &self.levels
@@ -115,6 +154,10 @@ impl LevelMeta {
         self.files.push(file);
     }
 
+    fn remove_file(&mut self, file_name: &str) {
+        self.files.retain(|file| file.file_name() != file_name);
+    }
+
     fn visit_level<V: Visitor>(&self, visitor: &mut V) -> Result<()> {
         visitor.visit(self.level.into(), &self.files)
     }
@@ -169,6 +212,31 @@ impl FileHandle {
     pub fn end_timestamp(&self) -> Option<Timestamp> {
         self.inner.meta.end_timestamp
     }
+
+    #[inline]
+    pub fn file_size(&self) -> u64 {
+        self.inner.meta.file_size
+    }
+
+    #[inline]
+    pub fn num_rows(&self) -> u64 {
+        self.inner.meta.num_rows
+    }
+
+    #[inline]
+    pub fn checksum(&self) -> Option<u32> {
+        self.inner.meta.checksum
+    }
+
+    #[inline]
+    pub fn tier(&self) -> FileTier {
+        self.inner.meta.tier
+    }
+
+    #[inline]
+    pub fn meta(&self) -> &FileMeta {
+        &self.inner.meta
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -185,6 +253,36 @@ impl FileHandleInner {
     }
 }
 
+/// Files of a region failed to read or verify.
+///
+/// Reads of quarantined files fail fast instead of fetching them from the object store again,
+/// until a scrub verifies them.
+#[derive(Debug, Default)]
+pub struct FileQuarantine {
+    files: RwLock<BTreeSet<String>>,
+}
+
+pub type FileQuarantineRef = Arc<FileQuarantine>;
+
+impl FileQuarantine {
+    pub fn add(&self, file_name: &str) {
+        self.files.write().unwrap().insert(file_name.to_string());
+    }
+
+    pub fn remove(&self, file_name: &str) {
+        self.files.write().unwrap().remove(file_name);
+    }
+
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.files.read().unwrap().contains(file_name)
+    }
+
+    /// Returns the quarantined files in order.
+    pub fn files(&self) -> Vec<String> {
+        self.files.read().unwrap().iter().cloned().collect()
+    }
+}
+
 /// Immutable metadata of a sst file.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileMeta {
@@ -193,6 +291,30 @@ pub struct FileMeta {
     pub end_timestamp: Option<Timestamp>,
     /// SST level of the file.
     pub level: u8,
+    /// Size of the file in bytes, 0 if the file is written before the size is recorded.
+    #[serde(default)]
+    pub file_size: u64,
+    /// Number of rows in the file, 0 if the file is written before the number is recorded.
+    #[serde(default)]
+    pub num_rows: u64,
+    /// CRC32 checksum of the file, `None` if the file is written before the checksum is
+    /// recorded.
+    #[serde(default)]
+    pub checksum: Option<u32>,
+    /// Storage tier the file is placed in.
+    #[serde(default)]
+    pub tier: FileTier,
+}
+
+/// Storage tier of a SST file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileTier {
+    /// Files in the object store of the region, recently flushed files are hot.
+    #[default]
+    Hot,
+    /// Files moved to the cheaper cold store once all their rows are old enough.
+    Cold,
 }
 
 #[derive(Debug, Default)]
@@ -214,6 +336,9 @@ pub struct ReadOptions {
 pub struct SstInfo {
     pub start_timestamp: Option<Timestamp>,
     pub end_timestamp: Option<Timestamp>,
+    pub file_size: u64,
+    pub num_rows: u64,
+    pub checksum: u32,
 }
 
 /// SST access layer.
@@ -227,8 +352,35 @@ pub trait AccessLayer: Send + Sync + std::fmt::Debug {
         opts: &WriteOptions,
     ) -> Result<SstInfo>;
 
-    /// Read SST file with given `file_name` and schema.
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader>;
+    /// Read SST file with given `file_name` in `tier` and schema.
+    async fn read_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader>;
+
+    /// Verifies the content of SST file with given `file_name` in `tier` against its
+    /// `checksum`, or decodes all rows of the file if its checksum is unknown.
+    async fn verify_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        checksum: Option<u32>,
+    ) -> Result<()>;
+
+    /// Returns true if SST files could be moved to the cold tier.
+    fn has_cold_tier(&self) -> bool;
+
+    /// Copies the hot SST file with given `file_name` to the cold tier, the hot file is kept
+    /// until [AccessLayer::delete_sst] is called.
+    async fn copy_to_cold(&self, file_name: &str) -> Result<()>;
+
+    /// Deletes SST file with given `file_name` in `tier`.
+    async fn delete_sst(&self, file_name: &str, tier: FileTier) -> Result<()>;
+
+    /// Lists names of SST files in `tier`, including files written but not uploaded yet.
+    async fn list_ssts(&self, tier: FileTier) -> Result<Vec<String>>;
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -238,6 +390,11 @@ pub type AccessLayerRef = Arc<dyn AccessLayer>;
 pub struct FsAccessLayer {
     sst_dir: String,
     object_store: ObjectStore,
+    sst_uploader: Option<SstUploaderRef>,
+    encryptor: Option<EncryptorRef>,
+    token_index: bool,
+    /// Store of the cold SST files, files are placed under the same dir as the hot ones.
+    cold_store: Option<ObjectStore>,
 }
 
 impl FsAccessLayer {
@@ -245,13 +402,102 @@ impl FsAccessLayer {
         FsAccessLayer {
             sst_dir: util::normalize_dir(sst_dir),
             object_store,
+            sst_uploader: None,
+            encryptor: None,
+            token_index: false,
+            cold_store: None,
         }
     }
 
+    /// Stores token indexes of string columns in SST files if `token_index` is true.
+    pub fn with_token_index(mut self, token_index: bool) -> FsAccessLayer {
+        self.token_index = token_index;
+        self
+    }
+
+    /// Encrypts SST files by `encryptor`.
+    pub fn with_encryptor(mut self, encryptor: EncryptorRef) -> FsAccessLayer {
+        self.encryptor = Some(encryptor);
+        self
+    }
+
+    /// Places cold SST files in `cold_store`.
+    pub fn with_cold_store(mut self, cold_store: ObjectStore) -> FsAccessLayer {
+        self.cold_store = Some(cold_store);
+        self
+    }
+
+    /// Writes SST files to local disk first and uploads them by `uploader` in background.
+    pub fn with_sst_uploader(mut self, uploader: SstUploaderRef) -> FsAccessLayer {
+        self.sst_uploader = Some(uploader);
+        self
+    }
+
     #[inline]
     fn sst_file_path(&self, file_name: &str) -> String {
         format!("{}{}", self.sst_dir, file_name)
     }
+
+    /// Returns the store of files in `tier`.
+    fn tier_store(&self, tier: FileTier) -> Result<&ObjectStore> {
+        match tier {
+            FileTier::Hot => Ok(&self.object_store),
+            FileTier::Cold => self.cold_store.as_ref().context(NoColdStoreSnafu),
+        }
+    }
+
+    /// Returns the local store if the file in `file_path` is not uploaded yet.
+    fn pending_local_store(&self, file_path: &str) -> Option<ObjectStore> {
+        self.sst_uploader
+            .as_ref()
+            .filter(|uploader| uploader.is_pending(file_path))
+            .map(|uploader| uploader.local_store().clone())
+    }
+
+    async fn read_sst_from(
+        &self,
+        file_path: &str,
+        object_store: ObjectStore,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
+        let reader = ParquetReader::new(
+            file_path,
+            object_store,
+            opts.projected_schema.clone(),
+            opts.predicate.clone(),
+        )
+        .with_encryptor(self.encryptor.clone());
+
+        let stream = reader.chunk_stream().await?;
+        Ok(Box::new(stream))
+    }
+
+    /// Decodes all rows of the SST file in `bytes`.
+    fn decode_sst(&self, file_path: &str, bytes: Vec<u8>) -> Result<()> {
+        let bytes = match &self.encryptor {
+            Some(encryptor) => encryptor
+                .decrypt(&bytes, encryption::sst_associated_data(file_path))?
+                .into_owned(),
+            None => bytes,
+        };
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+            .and_then(|builder| builder.build())
+            .context(ReadParquetSnafu { file: file_path })?;
+        for batch in reader {
+            let _ = batch
+                .map_err(|e| ParquetError::ArrowError(e.to_string()))
+                .context(ReadParquetSnafu { file: file_path })?;
+        }
+        Ok(())
+    }
+
+    async fn read_bytes_from(file_path: &str, object_store: &ObjectStore) -> Result<Vec<u8>> {
+        object_store
+            .object(file_path)
+            .read()
+            .await
+            .context(ReadObjectSnafu { path: file_path })
+    }
 }
 
 #[async_trait]
@@ -265,20 +511,135 @@ impl AccessLayer for FsAccessLayer {
         // Now we only supports parquet format. We may allow caller to specific SST format in
         // WriteOptions in the future.
         let file_path = self.sst_file_path(file_name);
-        let writer = ParquetWriter::new(&file_path, iter, self.object_store.clone());
-        writer.write_sst(opts).await
+        let Some(uploader) = &self.sst_uploader else {
+            let writer = ParquetWriter::new(&file_path, iter, self.object_store.clone())
+                .with_encryptor(self.encryptor.clone())
+                .with_token_index(self.token_index);
+            return writer.write_sst(opts).await;
+        };
+
+        let writer = ParquetWriter::new(&file_path, iter, uploader.local_store().clone())
+            .with_encryptor(self.encryptor.clone())
+            .with_token_index(self.token_index);
+        let sst_info = writer.write_sst(opts).await?;
+        uploader.submit(file_path).await?;
+        Ok(sst_info)
     }
 
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader> {
+    async fn read_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
         let file_path = self.sst_file_path(file_name);
-        let reader = ParquetReader::new(
-            &file_path,
-            self.object_store.clone(),
-            opts.projected_schema.clone(),
-            opts.predicate.clone(),
+        if tier == FileTier::Cold {
+            let cold_store = self.tier_store(tier)?.clone();
+            return self.read_sst_from(&file_path, cold_store, opts).await;
+        }
+        if let Some(local_store) = self.pending_local_store(&file_path) {
+            match self.read_sst_from(&file_path, local_store, opts).await {
+                Ok(reader) => return Ok(reader),
+                // The file may be uploaded and removed from local disk just now.
+                Err(e) => debug!(
+                    "Failed to read SST {} from local disk, err: {:?}",
+                    file_path, e
+                ),
+            }
+        }
+
+        self.read_sst_from(&file_path, self.object_store.clone(), opts)
+            .await
+    }
+
+    async fn verify_sst(
+        &self,
+        file_name: &str,
+        tier: FileTier,
+        checksum: Option<u32>,
+    ) -> Result<()> {
+        let file_path = self.sst_file_path(file_name);
+        let local_bytes = match self.pending_local_store(&file_path) {
+            Some(local_store) if tier == FileTier::Hot => {
+                Self::read_bytes_from(&file_path, &local_store).await.ok()
+            }
+            _ => None,
+        };
+        let bytes = match local_bytes {
+            Some(bytes) => bytes,
+            None => Self::read_bytes_from(&file_path, self.tier_store(tier)?).await?,
+        };
+
+        let Some(checksum) = checksum else {
+            return self.decode_sst(&file_path, bytes);
+        };
+        let actual = sst_checksum(&bytes);
+        ensure!(
+            actual == checksum,
+            SstChecksumMismatchSnafu {
+                path: file_path,
+                expected: checksum,
+                actual,
+            }
         );
+        Ok(())
+    }
 
-        let stream = reader.chunk_stream().await?;
-        Ok(Box::new(stream))
+    fn has_cold_tier(&self) -> bool {
+        self.cold_store.is_some()
+    }
+
+    async fn copy_to_cold(&self, file_name: &str) -> Result<()> {
+        let cold_store = self.tier_store(FileTier::Cold)?;
+        let file_path = self.sst_file_path(file_name);
+        // Files not uploaded yet are not cold, so the hot file is always in the object store.
+        let bytes = Self::read_bytes_from(&file_path, &self.object_store).await?;
+        cold_store
+            .object(&file_path)
+            .write(bytes)
+            .await
+            .context(WriteObjectSnafu { path: &file_path })
+    }
+
+    async fn delete_sst(&self, file_name: &str, tier: FileTier) -> Result<()> {
+        let file_path = self.sst_file_path(file_name);
+        self.tier_store(tier)?
+            .object(&file_path)
+            .delete()
+            .await
+            .context(DeleteObjectSnafu { path: &file_path })
+    }
+
+    async fn list_ssts(&self, tier: FileTier) -> Result<Vec<String>> {
+        let dir = self.tier_store(tier)?.object(&self.sst_dir);
+        let mut file_names = BTreeSet::new();
+        let exists = dir.is_exist().await.context(ReadObjectSnafu {
+            path: &self.sst_dir,
+        })?;
+        if exists {
+            let objects = dir
+                .list()
+                .await
+                .context(ListObjectsSnafu {
+                    path: &self.sst_dir,
+                })?
+                .try_collect::<Vec<_>>()
+                .await
+                .context(ListObjectsSnafu {
+                    path: &self.sst_dir,
+                })?;
+            // Skips the manifest dir of the region.
+            file_names.extend(
+                objects
+                    .iter()
+                    .map(|object| object.name())
+                    .filter(|name| name.ends_with(".parquet"))
+                    .map(str::to_string),
+            );
+        }
+        if let (FileTier::Hot, Some(uploader)) = (tier, &self.sst_uploader) {
+            file_names.extend(uploader.pending_files_in(&self.sst_dir));
+        }
+        Ok(file_names.into_iter().collect())
     }
 }
@@ -15,13 +15,14 @@
 //! Parquet sst format.
 
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::pin::Pin;
 use std::sync::Arc;
 
 use async_compat::CompatExt;
 use async_stream::try_stream;
 use async_trait::async_trait;
-use common_telemetry::error;
+use common_telemetry::{error, warn};
 use common_time::timestamp::TimeUnit;
 use common_time::Timestamp;
 use datatypes::arrow::record_batch::RecordBatch;
@@ -30,13 +31,16 @@ use futures_util::{Stream, StreamExt, TryStreamExt};
 use object_store::ObjectStore;
 use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{Compression, Encoding};
-use parquet::file::metadata::KeyValue;
+use parquet::file::metadata::{FileMetaData as ParquetFileMetaData, KeyValue};
 use parquet::file::properties::WriterProperties;
 use parquet::format::FileMetaData;
+use parquet::schema::types::ColumnPath;
 use snafu::{OptionExt, ResultExt};
+use table::predicate::token::{TokenIndex, TokenIndexBuilder, TOKEN_INDEX_KEY};
 use table::predicate::Predicate;
-use tokio::io::BufReader;
+use tokio::io::{AsyncRead, AsyncSeek, BufReader};
 
+use crate::encryption::{self, EncryptorRef};
 use crate::error::{
     self, DecodeParquetTimeRangeSnafu, NewRecordBatchSnafu, ReadObjectSnafu, ReadParquetSnafu,
     Result, WriteObjectSnafu, WriteParquetSnafu,
@@ -54,6 +58,8 @@ pub struct ParquetWriter<'a> {
     iter: BoxedBatchIterator,
     object_store: ObjectStore,
     max_row_group_size: usize,
+    encryptor: Option<EncryptorRef>,
+    token_index: bool,
 }
 
 impl<'a> ParquetWriter<'a> {
@@ -67,9 +73,23 @@ impl<'a> ParquetWriter<'a> {
             iter,
             object_store,
             max_row_group_size: 4096, // TODO(hl): make this configurable
+            encryptor: None,
+            token_index: false,
         }
     }
 
+    /// Encrypts the SST file by `encryptor` if it is given.
+    pub fn with_encryptor(mut self, encryptor: Option<EncryptorRef>) -> Self {
+        self.encryptor = encryptor;
+        self
+    }
+
+    /// Stores a token index of string columns in the SST file if `token_index` is true.
+    pub fn with_token_index(mut self, token_index: bool) -> Self {
+        self.token_index = token_index;
+        self
+    }
+
     pub async fn write_sst(self, _opts: &sst::WriteOptions) -> Result<SstInfo> {
         self.write_rows(None).await
     }
@@ -77,22 +97,33 @@ impl<'a> ParquetWriter<'a> {
     /// Iterates memtable and writes rows to Parquet file.
     /// A chunk of records yielded from each iteration with a size given
     /// in config will be written to a single row group.
-    async fn write_rows(self, extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
+    async fn write_rows(self, mut extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
         let projected_schema = self.iter.schema();
         let store_schema = projected_schema.schema_to_read();
         let schema = store_schema.arrow_schema().clone();
         let object = self.object_store.object(self.file_path);
 
-        let writer_props = WriterProperties::builder()
-            .set_compression(Compression::ZSTD)
-            .set_encoding(Encoding::PLAIN)
-            .set_max_row_group_size(self.max_row_group_size)
-            .set_key_value_metadata(extra_meta.map(|map| {
-                map.iter()
-                    .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
-                    .collect::<Vec<_>>()
-            }))
-            .build();
+        // The token index is stored in the metadata written before any row, so all batches
+        // are collected first to build it.
+        let batches: Box<dyn Iterator<Item = Result<Batch>> + Send> = if self.token_index {
+            let batches = self.iter.collect::<Result<Vec<_>>>()?;
+            let index = build_token_index(store_schema, &batches, self.max_row_group_size);
+            extra_meta.get_or_insert_with(HashMap::new).insert(
+                TOKEN_INDEX_KEY.to_string(),
+                serde_json::to_string(&index).context(error::EncodeJsonSnafu)?,
+            );
+            Box::new(batches.into_iter().map(Ok))
+        } else {
+            Box::new(self.iter)
+        };
+
+        let key_value_metadata = extra_meta.map(|map| {
+            map.iter()
+                .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+        });
+        let writer_props =
+            writer_properties(store_schema, self.max_row_group_size, key_value_metadata);
 
         // TODO(hl): Since OpenDAL's writer is async and ArrowWriter requires a `std::io::Write`,
         // here we use a Vec<u8> to buffer all parquet bytes in memory and write to object store
@@ -100,7 +131,7 @@ impl<'a> ParquetWriter<'a> {
         let mut buf = vec![];
         let mut arrow_writer = ArrowWriter::try_new(&mut buf, schema.clone(), Some(writer_props))
             .context(WriteParquetSnafu)?;
-        for batch in self.iter {
+        for batch in batches {
             let batch = batch?;
             let arrow_batch = RecordBatch::try_new(
                 schema.clone(),
@@ -128,16 +159,52 @@ impl<'a> ParquetWriter<'a> {
                 }
             };
 
+        let buf = match &self.encryptor {
+            Some(encryptor) => {
+                encryptor.encrypt(&buf, encryption::sst_associated_data(self.file_path))?
+            }
+            None => buf,
+        };
+        let file_size = buf.len() as u64;
+        let checksum = sst::sst_checksum(&buf);
         object.write(buf).await.context(WriteObjectSnafu {
             path: object.path(),
         })?;
         Ok(SstInfo {
             start_timestamp,
             end_timestamp,
+            file_size,
+            num_rows: file_meta.num_rows as u64,
+            checksum,
         })
     }
 }
 
+/// Builds the token index of string columns in `batches`, which are written to row groups
+/// of `row_group_size` rows.
+fn build_token_index(
+    store_schema: &StoreSchema,
+    batches: &[Batch],
+    row_group_size: usize,
+) -> TokenIndex {
+    let string_columns = store_schema
+        .schema()
+        .column_schemas()
+        .iter()
+        .enumerate()
+        .filter(|(_, column_schema)| column_schema.data_type.is_string())
+        .collect::<Vec<_>>();
+    let mut builder = TokenIndexBuilder::new(row_group_size);
+    for batch in batches {
+        let columns = string_columns
+            .iter()
+            .map(|(idx, column_schema)| (column_schema.name.as_str(), batch.column(*idx)))
+            .collect::<Vec<_>>();
+        builder.push_batch(&columns, batch.num_rows());
+    }
+    builder.finish()
+}
+
 fn decode_timestamp_range(
     file_meta: &FileMetaData,
     store_schema: &StoreSchemaRef,
@@ -215,6 +282,7 @@ pub struct ParquetReader<'a> {
     object_store: ObjectStore,
     projected_schema: ProjectedSchemaRef,
     predicate: Predicate,
+    encryptor: Option<EncryptorRef>,
 }
 
 impl<'a> ParquetReader<'a> {
@@ -229,25 +297,48 @@ impl<'a> ParquetReader<'a> {
             object_store,
             projected_schema,
             predicate,
+            encryptor: None,
         }
     }
 
+    /// Decrypts the SST file by `encryptor` if it is given.
+    pub fn with_encryptor(mut self, encryptor: Option<EncryptorRef>) -> Self {
+        self.encryptor = encryptor;
+        self
+    }
+
     pub async fn chunk_stream(&self) -> Result<ChunkStream> {
-        let operator = self.object_store.clone();
-        let reader = operator
-            .object(self.file_path)
+        let object = self.object_store.object(self.file_path);
+        if let Some(encryptor) = &self.encryptor {
+            // The whole file is needed to decrypt it.
+            let bytes = object.read().await.context(ReadObjectSnafu {
+                path: self.file_path,
+            })?;
+            let aad = encryption::sst_associated_data(self.file_path);
+            let bytes = encryptor.decrypt(&bytes, aad)?.into_owned();
+            return self.build_chunk_stream(Cursor::new(bytes)).await;
+        }
+
+        let reader = object
             .reader()
             .await
             .context(ReadObjectSnafu {
                 path: self.file_path,
             })?
             .compat();
-        let buf_reader = BufReader::new(reader);
-        let builder = ParquetRecordBatchStreamBuilder::new(buf_reader)
-            .await
-            .context(ReadParquetSnafu {
-                file: self.file_path,
-            })?;
+        self.build_chunk_stream(BufReader::new(reader)).await
+    }
+
+    async fn build_chunk_stream<R>(&self, input: R) -> Result<ChunkStream>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+    {
+        let builder =
+            ParquetRecordBatchStreamBuilder::new(input)
+                .await
+                .context(ReadParquetSnafu {
+                    file: self.file_path,
+                })?;
         let arrow_schema = builder.schema().clone();
 
         let store_schema = Arc::new(StoreSchema::try_from(arrow_schema).context(
@@ -258,12 +349,19 @@ impl<'a> ParquetReader<'a> {
 
         let adapter = ReadAdapter::new(store_schema.clone(), self.projected_schema.clone())?;
 
-        let pruned_row_groups = self
+        let row_groups = builder.metadata().row_groups();
+        let mut valid_row_groups = self
             .predicate
-            .prune_row_groups(
-                store_schema.schema().clone(),
-                builder.metadata().row_groups(),
-            )
+            .prune_row_groups(store_schema.schema().clone(), row_groups);
+        if let Some(index) = self.token_index(builder.metadata().file_metadata()) {
+            let valid_by_tokens = self
+                .predicate
+                .prune_row_groups_by_tokens(&index, row_groups.len());
+            for (valid, valid_by_tokens) in valid_row_groups.iter_mut().zip(valid_by_tokens) {
+                *valid &= valid_by_tokens;
+            }
+        }
+        let pruned_row_groups = valid_row_groups
             .into_iter()
             .enumerate()
             .filter_map(|(idx, valid)| if valid { Some(idx) } else { None })
@@ -291,6 +389,27 @@ impl<'a> ParquetReader<'a> {
 
         ChunkStream::new(adapter, Box::pin(chunk_stream))
     }
+
+    /// Returns the token index stored in the file, `None` if the file has no index.
+    fn token_index(&self, file_meta: &ParquetFileMetaData) -> Option<TokenIndex> {
+        let value = file_meta
+            .key_value_metadata()?
+            .iter()
+            .find(|kv| kv.key == TOKEN_INDEX_KEY)?
+            .value
+            .as_ref()?;
+        match serde_json::from_str(value) {
+            Ok(index) => Some(index),
+            Err(e) => {
+                // Files are still readable without the index.
+                warn!(
+                    "Failed to decode token index of file {}, error: {}",
+                    self.file_path, e
+                );
+                None
+            }
+        }
+    }
 }
 
 pub type SendableChunkStream = Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>;
@@ -317,22 +436,72 @@ impl BatchReader for ChunkStream {
     }
 }
 
+/// Returns the properties to write SSTs of `store_schema`. Only string row key columns are
+/// dictionary encoded, as tags are usually of low cardinality while field values are
+/// rarely repeated.
+fn writer_properties(
+    store_schema: &StoreSchema,
+    max_row_group_size: usize,
+    key_value_metadata: Option<Vec<KeyValue>>,
+) -> WriterProperties {
+    let mut builder = WriterProperties::builder()
+        .set_compression(Compression::ZSTD)
+        .set_dictionary_enabled(false)
+        .set_encoding(Encoding::PLAIN)
+        .set_max_row_group_size(max_row_group_size)
+        .set_key_value_metadata(key_value_metadata);
+    let row_key_columns = &store_schema.schema().column_schemas()[..store_schema.row_key_end()];
+    for column_schema in row_key_columns
+        .iter()
+        .filter(|column_schema| column_schema.data_type.is_string())
+    {
+        builder = builder
+            .set_column_dictionary_enabled(ColumnPath::from(column_schema.name.clone()), true);
+    }
+    builder.build()
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use datatypes::arrow::array::{Array, ArrayRef, UInt64Array, UInt8Array};
     use datatypes::prelude::Vector;
+    use datatypes::type_id::LogicalTypeId;
     use datatypes::vectors::TimestampMillisecondVector;
     use object_store::backend::fs::Builder;
     use store_api::storage::OpType;
     use tempdir::TempDir;
 
     use super::*;
+    use crate::encryption::tests as encryption_tests;
     use crate::memtable::{
         tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
     };
+    use crate::metadata::RegionMetadata;
     use crate::schema::ProjectedSchema;
+    use crate::test_util::descriptor_util::RegionDescBuilder;
+
+    #[test]
+    fn test_writer_properties() {
+        let desc = RegionDescBuilder::new("test")
+            .push_key_column(("k0", LogicalTypeId::String, false))
+            .push_key_column(("k1", LogicalTypeId::Int64, false))
+            .push_value_column(("v0", LogicalTypeId::String, true))
+            .build();
+        let metadata: RegionMetadata = desc.try_into().unwrap();
+        let store_schema = metadata.schema().store_schema();
+
+        let props = writer_properties(store_schema, 1024, None);
+        assert!(props.dictionary_enabled(&ColumnPath::from("k0")));
+        assert!(!props.dictionary_enabled(&ColumnPath::from("k1")));
+        assert!(!props.dictionary_enabled(&ColumnPath::from("v0")));
+        assert!(!props.dictionary_enabled(&ColumnPath::from("timestamp")));
+        assert_eq!(
+            Some(Encoding::PLAIN),
+            props.encoding(&ColumnPath::from("v0"))
+        );
+    }
 
     #[tokio::test]
     async fn test_parquet_writer() {
@@ -470,6 +639,9 @@ mod tests {
         let SstInfo {
             start_timestamp,
             end_timestamp,
+            file_size,
+            num_rows,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -480,6 +652,8 @@ mod tests {
             Some(Timestamp::new_millisecond((rows_total - 1) as i64)),
             end_timestamp
         );
+        assert_eq!(rows_total as u64, num_rows);
+        assert!(file_size > 0);
 
         let operator = ObjectStore::new(
             object_store::backend::fs::Builder::default()
@@ -543,6 +717,7 @@ mod tests {
         let SstInfo {
             start_timestamp,
             end_timestamp,
+            ..
         } = writer
             .write_sst(&sst::WriteOptions::default())
             .await
@@ -578,4 +753,72 @@ mod tests {
                 .num_rows()
         );
     }
+
+    #[tokio::test]
+    async fn test_parquet_encryption() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (2002, 1)],                         // keys
+            &[(Some(1), Some(1234)), (Some(7), Some(1234))], // values
+        );
+
+        let dir = TempDir::new("write_parquet").unwrap();
+        let path = dir.path().to_str().unwrap();
+        let backend = Builder::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend);
+        let sst_file_name = "test-encrypt.parquet";
+        let encryptor = encryption_tests::new_encryptor("k1");
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone())
+            .with_encryptor(Some(encryptor.clone()));
+        let sst_info = writer
+            .write_sst(&sst::WriteOptions::default())
+            .await
+            .unwrap();
+
+        // The file is encrypted and the checksum covers the stored bytes.
+        let bytes = object_store.object(sst_file_name).read().await.unwrap();
+        assert!(!bytes.starts_with(b"PAR1"));
+        assert_eq!(bytes.len() as u64, sst_info.file_size);
+        assert_eq!(sst::sst_checksum(&bytes), sst_info.checksum);
+
+        let projected_schema = Arc::new(ProjectedSchema::new(schema, Some(vec![1])).unwrap());
+        let new_reader = |file_name, encryptor| {
+            ParquetReader::new(
+                file_name,
+                object_store.clone(),
+                projected_schema.clone(),
+                Predicate::empty(),
+            )
+            .with_encryptor(encryptor)
+        };
+        let mut stream = new_reader(sst_file_name, Some(encryptor.clone()))
+            .chunk_stream()
+            .await
+            .unwrap();
+        let batch = stream.next_batch().await.unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+
+        // The file is unreadable without the key.
+        assert!(new_reader(sst_file_name, None)
+            .chunk_stream()
+            .await
+            .is_err());
+
+        // The ciphertext is bound to its file name.
+        let copied_file_name = "test-encrypt-copied.parquet";
+        object_store
+            .object(copied_file_name)
+            .write(bytes)
+            .await
+            .unwrap();
+        assert!(new_reader(copied_file_name, Some(encryptor))
+            .chunk_stream()
+            .await
+            .is_err());
+    }
 }
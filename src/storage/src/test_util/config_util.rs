@@ -22,6 +22,7 @@ use object_store::ObjectStore;
 use crate::background::JobPoolImpl;
 use crate::engine;
 use crate::flush::{FlushSchedulerImpl, SizeBasedStrategy};
+use crate::listener::EventListeners;
 use crate::manifest::region::RegionManifest;
 use crate::memtable::DefaultMemtableBuilder;
 use crate::region::StoreConfig;
@@ -35,6 +36,15 @@ fn log_store_dir(store_dir: &str) -> String {
 pub async fn new_store_config(
     region_name: &str,
     store_dir: &str,
+) -> StoreConfig<RaftEngineLogStore> {
+    new_store_config_with_log_dir(region_name, store_dir, &log_store_dir(store_dir)).await
+}
+
+/// Create a new StoreConfig for test, whose WAL is placed in `log_dir`.
+pub async fn new_store_config_with_log_dir(
+    region_name: &str,
+    store_dir: &str,
+    log_dir: &str,
 ) -> StoreConfig<RaftEngineLogStore> {
     let parent_dir = "";
     let sst_dir = engine::region_sst_dir(parent_dir, region_name);
@@ -47,7 +57,7 @@ pub async fn new_store_config(
     let job_pool = Arc::new(JobPoolImpl {});
     let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
     let log_config = LogConfig {
-        log_file_dir: log_store_dir(store_dir),
+        log_file_dir: log_dir.to_string(),
         ..Default::default()
     };
     let log_store = Arc::new(RaftEngineLogStore::try_new(log_config).await.unwrap());
@@ -59,5 +69,8 @@ pub async fn new_store_config(
         memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
         flush_scheduler,
         flush_strategy: Arc::new(SizeBasedStrategy::default()),
+        encryptor: None,
+        event_listener: Arc::new(EventListeners::new(vec![])),
+        wal_retention: None,
     }
 }
@@ -129,9 +129,11 @@ impl VersionControl {
 #[derive(Debug)]
 pub struct VersionEdit {
     pub files_to_add: Vec<FileMeta>,
+    pub files_to_remove: Vec<FileMeta>,
     pub flushed_sequence: Option<SequenceNumber>,
     pub manifest_version: ManifestVersion,
     pub max_memtable_id: Option<MemtableId>,
+    pub last_write_millis: Option<i64>,
 }
 
 pub type VersionControlRef = Arc<VersionControl>;
@@ -157,6 +159,8 @@ pub struct Version {
     flushed_sequence: SequenceNumber,
     /// Current version of manifest.
     manifest_version: ManifestVersion,
+    /// Unix timestamp in milliseconds of the last write recorded by edits in the manifest.
+    last_write_millis: Option<i64>,
     // TODO(yingwen): Maybe also store last sequence to this version when switching
     // version, so we can know the newest data can read from this version.
 }
@@ -180,6 +184,7 @@ impl Version {
             ssts: Arc::new(LevelMetas::new()),
             flushed_sequence: 0,
             manifest_version,
+            last_write_millis: None,
         }
     }
 
@@ -226,6 +231,7 @@ impl Version {
         if self.manifest_version < edit.manifest_version {
             self.manifest_version = edit.manifest_version;
         }
+        self.last_write_millis = self.last_write_millis.max(edit.last_write_millis);
 
         if let Some(max_memtable_id) = edit.max_memtable_id {
             // Remove flushed memtables
@@ -235,7 +241,7 @@ impl Version {
         }
 
         let handles_to_add = edit.files_to_add.into_iter().map(FileHandle::new);
-        let merged_ssts = self.ssts.merge(handles_to_add);
+        let merged_ssts = self.ssts.merge(handles_to_add, &edit.files_to_remove);
 
         self.ssts = Arc::new(merged_ssts);
     }
@@ -267,6 +273,11 @@ impl Version {
     pub fn manifest_version(&self) -> ManifestVersion {
         self.manifest_version
     }
+
+    #[inline]
+    pub fn last_write_millis(&self) -> Option<i64> {
+        self.last_write_millis
+    }
 }
 
 #[cfg(test)]
@@ -21,22 +21,34 @@ use prost::Message;
 use snafu::{ensure, ResultExt};
 use store_api::logstore::entry::{Entry, Id};
 use store_api::logstore::LogStore;
-use store_api::storage::{RegionId, SequenceNumber};
+use store_api::storage::{Durability, RegionId, SequenceNumber};
 
 use crate::codec::{Decoder, Encoder};
+use crate::encryption::{self, EncryptorRef};
 use crate::error::{
     DecodeWalHeaderSnafu, EncodeWalHeaderSnafu, Error, MarkWalObsoleteSnafu, ReadWalSnafu, Result,
-    WalDataCorruptedSnafu, WriteWalSnafu,
+    SyncWalSnafu, WalDataCorruptedSnafu, WriteWalSnafu,
 };
 use crate::proto::wal::{self, WalHeader};
 use crate::write_batch::codec::{PayloadDecoder, PayloadEncoder};
 use crate::write_batch::Payload;
 
+/// Keeps entries in the WAL after they are flushed, e.g. for consumers tailing the WAL.
+pub trait WalRetention: Send + Sync + std::fmt::Debug {
+    /// Returns the sequence of the first entry of the region that must be kept, entries
+    /// before it could be purged. All flushed entries could be purged if `None`.
+    fn retained_sequence(&self, region_id: RegionId) -> Option<SequenceNumber>;
+}
+
+pub type WalRetentionRef = Arc<dyn WalRetention>;
+
 #[derive(Debug)]
 pub struct Wal<S: LogStore> {
     region_id: RegionId,
     namespace: S::Namespace,
     store: Arc<S>,
+    encryptor: Option<EncryptorRef>,
+    retention: Option<WalRetentionRef>,
 }
 
 pub type PayloadStream<'a> =
@@ -49,6 +61,8 @@ impl<S: LogStore> Clone for Wal<S> {
             region_id: self.region_id,
             namespace: self.namespace.clone(),
             store: self.store.clone(),
+            encryptor: self.encryptor.clone(),
+            retention: self.retention.clone(),
         }
     }
 }
@@ -60,10 +74,35 @@ impl<S: LogStore> Wal<S> {
             region_id,
             namespace,
             store,
+            encryptor: None,
+            retention: None,
         }
     }
 
+    /// Encrypts WAL entries by `encryptor` if it is given, plain entries are still readable.
+    pub fn with_encryptor(mut self, encryptor: Option<EncryptorRef>) -> Self {
+        self.encryptor = encryptor;
+        self
+    }
+
+    /// Keeps entries retained by `retention` when entries are marked obsolete.
+    pub fn with_retention(mut self, retention: Option<WalRetentionRef>) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Marks entries whose sequence is less than or equal to `seq` obsolete, except the
+    /// entries retained by the [WalRetention].
     pub async fn obsolete(&self, seq: SequenceNumber) -> Result<()> {
+        let retained = self
+            .retention
+            .as_ref()
+            .and_then(|retention| retention.retained_sequence(self.region_id));
+        let seq = match retained {
+            Some(0) => return Ok(()),
+            Some(retained) => seq.min(retained - 1),
+            None => seq,
+        };
         self.store
             .obsolete(self.namespace.clone(), seq)
             .await
@@ -73,6 +112,17 @@ impl<S: LogStore> Wal<S> {
             })
     }
 
+    /// Syncs all entries written to the WAL to disk.
+    pub async fn sync(&self) -> Result<()> {
+        self.store
+            .sync()
+            .await
+            .map_err(BoxedError::new)
+            .context(SyncWalSnafu {
+                region_id: self.region_id,
+            })
+    }
+
     #[inline]
     pub fn region_id(&self) -> RegionId {
         self.region_id
@@ -105,6 +155,7 @@ impl<S: LogStore> Wal<S> {
         seq: SequenceNumber,
         mut header: WalHeader,
         payload: Option<&Payload>,
+        durability: Durability,
     ) -> Result<Id> {
         if let Some(p) = payload {
             header.mutation_types = wal::gen_mutation_types(p);
@@ -128,8 +179,13 @@ impl<S: LogStore> Wal<S> {
                 })?;
         }
 
+        if let Some(encryptor) = &self.encryptor {
+            let aad = encryption::wal_associated_data(self.region_id, seq);
+            buf = encryptor.encrypt(&buf, &aad)?;
+        }
+
         // write bytes to wal
-        self.write(seq, &buf).await
+        self.write(seq, &buf, durability).await
     }
 
     pub async fn read_from_wal(&self, start_seq: SequenceNumber) -> Result<PayloadStream<'_>> {
@@ -156,17 +212,21 @@ impl<S: LogStore> Wal<S> {
         Ok(Box::pin(stream))
     }
 
-    async fn write(&self, seq: SequenceNumber, bytes: &[u8]) -> Result<u64> {
+    async fn write(
+        &self,
+        seq: SequenceNumber,
+        bytes: &[u8],
+        durability: Durability,
+    ) -> Result<u64> {
         let e = self.store.entry(bytes, seq, self.namespace.clone());
 
-        let response = self
-            .store
-            .append(e)
-            .await
-            .map_err(BoxedError::new)
-            .context(WriteWalSnafu {
-                region_id: self.region_id(),
-            })?;
+        let response = match durability {
+            Durability::Sync => self.store.append(e).await,
+            Durability::Async => self.store.append_unsynced(e).await,
+        };
+        let response = response.map_err(BoxedError::new).context(WriteWalSnafu {
+            region_id: self.region_id(),
+        })?;
 
         Ok(response.entry_id)
     }
@@ -176,7 +236,9 @@ impl<S: LogStore> Wal<S> {
         entry: E,
     ) -> Result<(SequenceNumber, WalHeader, Option<Payload>)> {
         let seq_num = entry.id();
-        let input = entry.data();
+        let aad = encryption::wal_associated_data(self.region_id, seq_num);
+        let input = encryption::maybe_decrypt(self.encryptor.as_ref(), entry.data(), &aad)?;
+        let input = input.as_ref();
 
         let wal_header_decoder = WalHeaderDecoder {};
         let (data_pos, header) = wal_header_decoder.decode(input)?;
@@ -247,6 +309,7 @@ mod tests {
     use log_store::test_util;
 
     use super::*;
+    use crate::encryption::tests as encryption_tests;
 
     #[tokio::test]
     pub async fn test_write_wal() {
@@ -254,11 +317,12 @@ mod tests {
             test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
         let wal = Wal::new(0, Arc::new(log_store));
 
-        let res = wal.write(0, b"test1").await.unwrap();
+        let res = wal.write(0, b"test1", Durability::Sync).await.unwrap();
 
         assert_eq!(0, res);
-        let res = wal.write(1, b"test2").await.unwrap();
+        let res = wal.write(1, b"test2", Durability::Async).await.unwrap();
         assert_eq!(1, res);
+        wal.sync().await.unwrap();
     }
 
     #[tokio::test]
@@ -269,7 +333,8 @@ mod tests {
         let wal = Wal::new(0, Arc::new(log_store));
         let header = WalHeader::with_last_manifest_version(111);
         let seq_num = 3;
-        wal.write_to_wal(seq_num, header, None).await?;
+        wal.write_to_wal(seq_num, header, None, Durability::Sync)
+            .await?;
 
         let mut stream = wal.read_from_wal(seq_num).await?;
         let mut data = vec![];
@@ -283,11 +348,93 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn test_read_encrypted_wal() -> Result<()> {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let log_store = Arc::new(log_store);
+        let plain_wal = Wal::new(0, log_store.clone());
+        plain_wal
+            .write_to_wal(
+                3,
+                WalHeader::with_last_manifest_version(111),
+                None,
+                Durability::Sync,
+            )
+            .await?;
+        let wal = Wal::new(0, log_store.clone())
+            .with_encryptor(Some(encryption_tests::new_encryptor("k1")));
+        wal.write_to_wal(
+            4,
+            WalHeader::with_last_manifest_version(112),
+            None,
+            Durability::Sync,
+        )
+        .await?;
+
+        // Plain entries are rejected once encryption is enabled.
+        let mut stream = wal.read_from_wal(3).await?;
+        assert!(stream.try_next().await.is_err());
+
+        // Both plain and encrypted entries are readable while migrating.
+        let migrating_wal = Wal::new(0, log_store.clone())
+            .with_encryptor(Some(encryption_tests::new_migrating_encryptor("k1")));
+        let mut stream = migrating_wal.read_from_wal(3).await?;
+        let mut versions = vec![];
+        while let Some((_, header, _)) = stream.try_next().await? {
+            versions.push(header.last_manifest_version);
+        }
+        assert_eq!(vec![111, 112], versions);
+
+        // Encrypted entries are unreadable without the key.
+        let mut stream = plain_wal.read_from_wal(4).await?;
+        assert!(stream.try_next().await.is_err());
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct FixedRetention(SequenceNumber);
+
+    impl WalRetention for FixedRetention {
+        fn retained_sequence(&self, _region_id: RegionId) -> Option<SequenceNumber> {
+            Some(self.0)
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_obsolete_retained_wal() -> Result<()> {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let wal =
+            Wal::new(0, Arc::new(log_store)).with_retention(Some(Arc::new(FixedRetention(2))));
+        for seq in 1..=3 {
+            wal.write_to_wal(
+                seq,
+                WalHeader::with_last_manifest_version(seq),
+                None,
+                Durability::Sync,
+            )
+            .await?;
+        }
+
+        // Entries from sequence 2 are kept.
+        wal.obsolete(3).await?;
+        let mut stream = wal.read_from_wal(2).await?;
+        let mut sequences = vec![];
+        while let Some((seq, _, _)) = stream.try_next().await? {
+            sequences.push(seq);
+        }
+        assert_eq!(vec![2, 3], sequences);
+
+        Ok(())
+    }
+
     #[test]
     pub fn test_wal_header_codec() {
         let wal_header = WalHeader {
             last_manifest_version: 99999999,
-            mutation_types: vec![],
+            ..Default::default()
         };
 
         let mut buf: Vec<u8> = vec![];
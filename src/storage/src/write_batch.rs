@@ -151,6 +151,12 @@ impl WriteBatch {
     pub fn payload(&self) -> &Payload {
         &self.payload
     }
+
+    /// Returns the number of rows this batch mutates.
+    #[inline]
+    pub fn num_rows_to_mutate(&self) -> usize {
+        self.num_rows_to_mutate
+    }
 }
 
 impl WriteBatch {
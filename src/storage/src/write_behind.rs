@@ -0,0 +1,275 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Write-behind buffer of SST files.
+//!
+//! Flushed SST files are written to local disk first and uploaded to the object store in
+//! background, so flushes don't fail while the object store is briefly unavailable.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common_telemetry::logging;
+use metrics::gauge;
+use object_store::ObjectStore;
+use snafu::{ensure, ResultExt};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use crate::error::{self, Result};
+use crate::metric::METRIC_WRITE_BEHIND_PENDING_FILES;
+
+/// Delay before retrying a failed upload.
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Uploads SST files buffered in local disk to the object store in background.
+#[derive(Debug)]
+pub struct SstUploader {
+    local_store: ObjectStore,
+    /// Paths of files buffered in local disk but not uploaded yet.
+    pending: Mutex<HashSet<String>>,
+    sender: Sender<String>,
+}
+
+pub type SstUploaderRef = Arc<SstUploader>;
+
+impl SstUploader {
+    /// Starts an uploader that uploads files in `local_store`, whose root is `local_dir`, to
+    /// `remote_store`. At most `backlog` files wait for uploading, submitting more files waits
+    /// until some files are uploaded.
+    ///
+    /// Files left in `local_dir` by last run are uploaded again.
+    pub async fn start(
+        local_dir: &str,
+        local_store: ObjectStore,
+        remote_store: ObjectStore,
+        backlog: usize,
+    ) -> Result<SstUploaderRef> {
+        ensure!(
+            backlog > 0,
+            error::InvalidWriteBehindBacklogSnafu { backlog }
+        );
+
+        let mut leftovers = Vec::new();
+        list_files(Path::new(local_dir), "", &mut leftovers)
+            .context(error::ListLocalFilesSnafu { dir: local_dir })?;
+
+        let (sender, receiver) = mpsc::channel(backlog);
+        let uploader = Arc::new(SstUploader {
+            local_store,
+            pending: Mutex::new(HashSet::new()),
+            sender,
+        });
+        let _handle = common_runtime::spawn_bg(uploader.clone().run(receiver, remote_store));
+
+        if !leftovers.is_empty() {
+            logging::info!(
+                "Upload {} SST files left in {} by last run",
+                leftovers.len(),
+                local_dir
+            );
+        }
+        for path in leftovers {
+            uploader.submit(path).await?;
+        }
+
+        Ok(uploader)
+    }
+
+    /// Returns the object store to write files to upload.
+    pub fn local_store(&self) -> &ObjectStore {
+        &self.local_store
+    }
+
+    /// Submits the file in `path` of the local store to upload, waits if the backlog is full.
+    pub async fn submit(&self, path: String) -> Result<()> {
+        self.add_pending(&path);
+        if self.sender.send(path.clone()).await.is_err() {
+            self.remove_pending(&path);
+            return error::UploaderStoppedSnafu { path }.fail();
+        }
+        Ok(())
+    }
+
+    /// Returns whether the file in `path` is in local disk and not uploaded yet.
+    pub fn is_pending(&self, path: &str) -> bool {
+        self.pending.lock().unwrap().contains(path)
+    }
+
+    /// Returns names of the files directly under `dir` not uploaded yet.
+    pub fn pending_files_in(&self, dir: &str) -> Vec<String> {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|path| path.strip_prefix(dir))
+            .filter(|name| !name.contains('/'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    async fn run(self: Arc<Self>, mut receiver: Receiver<String>, remote_store: ObjectStore) {
+        while let Some(path) = receiver.recv().await {
+            // The object store may be unavailable for a while, so retry until the file is
+            // uploaded.
+            while let Err(e) = self.upload(&remote_store, &path).await {
+                logging::error!(
+                    e; "Failed to upload SST file {}, retry in {:?}",
+                    path,
+                    UPLOAD_RETRY_DELAY
+                );
+                tokio::time::sleep(UPLOAD_RETRY_DELAY).await;
+            }
+        }
+    }
+
+    async fn upload(&self, remote_store: &ObjectStore, path: &str) -> Result<()> {
+        let local = self.local_store.object(path);
+        if !local
+            .is_exist()
+            .await
+            .context(error::ReadObjectSnafu { path })?
+        {
+            logging::warn!("SST file {} to upload is missing in local disk", path);
+            self.remove_pending(path);
+            return Ok(());
+        }
+
+        let bytes = local
+            .read()
+            .await
+            .context(error::ReadObjectSnafu { path })?;
+        remote_store
+            .object(path)
+            .write(bytes)
+            .await
+            .context(error::WriteObjectSnafu { path })?;
+
+        // Reads go to the object store once the file is no longer pending.
+        self.remove_pending(path);
+        local
+            .delete()
+            .await
+            .context(error::DeleteObjectSnafu { path })?;
+        logging::debug!("SST file {} uploaded", path);
+
+        Ok(())
+    }
+
+    fn add_pending(&self, path: &str) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.insert(path.to_string());
+        gauge!(METRIC_WRITE_BEHIND_PENDING_FILES, pending.len() as f64);
+    }
+
+    fn remove_pending(&self, path: &str) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.remove(path);
+        gauge!(METRIC_WRITE_BEHIND_PENDING_FILES, pending.len() as f64);
+    }
+}
+
+/// Lists files under `dir` recursively, pushes their paths relative to the root to `files`.
+/// Hidden entries, like the directory of atomic writes, are skipped.
+fn list_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> std::io::Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = format!("{prefix}{name}");
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), &format!("{path}/"), files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::backend::fs;
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn new_fs_store(dir: &str) -> ObjectStore {
+        let accessor = fs::Builder::default().root(dir).build().unwrap();
+        ObjectStore::new(accessor)
+    }
+
+    async fn wait_uploaded(uploader: &SstUploader, path: &str) {
+        for _ in 0..100 {
+            if !uploader.is_pending(path) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("SST file {path} is not uploaded");
+    }
+
+    #[tokio::test]
+    async fn test_upload() {
+        let local_dir = TempDir::new("write_behind_local").unwrap();
+        let local_dir = local_dir.path().to_str().unwrap();
+        let remote_dir = TempDir::new("write_behind_remote").unwrap();
+        let remote_store = new_fs_store(remote_dir.path().to_str().unwrap());
+
+        // A file left by last run.
+        let local_store = new_fs_store(local_dir);
+        local_store
+            .object("region/left.parquet")
+            .write("left")
+            .await
+            .unwrap();
+
+        let uploader = SstUploader::start(local_dir, local_store, remote_store.clone(), 1)
+            .await
+            .unwrap();
+        wait_uploaded(&uploader, "region/left.parquet").await;
+
+        uploader
+            .local_store()
+            .object("region/new.parquet")
+            .write("new")
+            .await
+            .unwrap();
+        uploader
+            .submit("region/new.parquet".to_string())
+            .await
+            .unwrap();
+        wait_uploaded(&uploader, "region/new.parquet").await;
+
+        for (path, content) in [
+            ("region/left.parquet", "left"),
+            ("region/new.parquet", "new"),
+        ] {
+            let bytes = remote_store.object(path).read().await.unwrap();
+            assert_eq!(content.as_bytes(), bytes);
+            assert!(!uploader
+                .local_store()
+                .object(path)
+                .is_exist()
+                .await
+                .unwrap());
+        }
+    }
+}
@@ -38,6 +38,18 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     /// the entry id.
     async fn append(&self, mut e: Self::Entry) -> Result<AppendResponse, Self::Error>;
 
+    /// Append an `Entry` to WAL like [LogStore::append], but never waits for the entry to be
+    /// synced to disk regardless of the sync policy. The entry becomes durable after the next
+    /// [LogStore::sync].
+    async fn append_unsynced(&self, e: Self::Entry) -> Result<AppendResponse, Self::Error> {
+        self.append(e).await
+    }
+
+    /// Sync all appended entries to disk.
+    async fn sync(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Append a batch of entries atomically and return the offset of first entry.
     async fn append_batch(
         &self,
@@ -34,9 +34,11 @@ pub use self::chunk::{Chunk, ChunkReader};
 pub use self::descriptors::*;
 pub use self::engine::{CreateOptions, EngineContext, OpenOptions, StorageEngine};
 pub use self::metadata::RegionMeta;
-pub use self::region::{Region, WriteContext};
+pub use self::region::{
+    ChangeBatch, Durability, FileCheckStat, Region, RegionStat, ScrubStat, WriteContext,
+};
 pub use self::requests::{
-    AddColumn, AlterOperation, AlterRequest, GetRequest, ScanRequest, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, DedupStrategy, GetRequest, ScanRequest, WriteRequest,
 };
 pub use self::responses::{GetResponse, ScanResponse, WriteResponse};
 pub use self::snapshot::{ReadContext, Snapshot};
@@ -22,7 +22,7 @@ use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 
 use crate::storage::descriptors::RegionDescriptor;
-use crate::storage::region::Region;
+use crate::storage::region::{Region, RegionStat};
 
 /// Storage engine provides primitive operations to store and access data.
 #[async_trait]
@@ -71,6 +71,15 @@ pub trait StorageEngine: Send + Sync + Clone + 'static {
         ctx: &EngineContext,
         name: &str,
     ) -> Result<Option<Self::Region>, Self::Error>;
+
+    /// Reads the statistics of a region from its manifest without opening it, rows not
+    /// flushed yet are not counted. Returns `Ok(None)` if region does not exists.
+    async fn region_stat(
+        &self,
+        ctx: &EngineContext,
+        name: &str,
+        opts: &OpenOptions,
+    ) -> Result<Option<RegionStat>, Self::Error>;
 }
 
 /// Storage engine context.
@@ -89,4 +98,8 @@ pub struct CreateOptions {
 pub struct OpenOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Opens the region read-only from its manifest, without replaying or writing the WAL.
+    /// A read-only region rejects writes and catches up with the writer of the region
+    /// by [Region::refresh](crate::storage::Region::refresh).
+    pub read_only: bool,
 }
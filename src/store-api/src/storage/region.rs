@@ -34,13 +34,16 @@
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_time::Timestamp;
+use datatypes::schema::SchemaRef;
+use datatypes::vectors::VectorRef;
 
 use crate::storage::engine::OpenOptions;
 use crate::storage::metadata::RegionMeta;
 use crate::storage::requests::{AlterRequest, WriteRequest};
 use crate::storage::responses::WriteResponse;
 use crate::storage::snapshot::{ReadContext, Snapshot};
-use crate::storage::RegionId;
+use crate::storage::{OpType, RegionId, SequenceNumber};
 
 /// Chunks of rows in storage engine.
 #[async_trait]
@@ -65,6 +68,14 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
         request: Self::WriteRequest,
     ) -> Result<WriteResponse, Self::Error>;
 
+    /// Waits until all writes whose sequence is less than or equal to `sequence` are
+    /// durable, used to fence writes acknowledged with [Durability::Async].
+    async fn fence(&self, sequence: SequenceNumber) -> Result<(), Self::Error>;
+
+    /// Flushes all rows in memtables to SST files and waits for the flush to finish, so
+    /// the region reopened later doesn't need to replay them from the WAL.
+    async fn flush(&self) -> Result<(), Self::Error>;
+
     /// Create a snapshot for read.
     fn snapshot(&self, ctx: &ReadContext) -> Result<Self::Snapshot, Self::Error>;
 
@@ -72,11 +83,126 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
     fn write_request(&self) -> Self::WriteRequest;
 
     async fn alter(&self, request: AlterRequest) -> Result<(), Self::Error>;
+
+    /// Returns the statistics of the region.
+    fn stat(&self) -> RegionStat;
+
+    /// Verifies the checksums of files of the region, files failed to verify are reported
+    /// in [RegionStat::corrupted_files] until the next scrub.
+    async fn scrub(&self) -> Result<ScrubStat, Self::Error>;
+
+    /// Checks the files referenced by the manifest of the region against the files in the
+    /// object store. References to missing files are removed from the manifest if `repair`
+    /// is true, so the rest of the region could still be read.
+    async fn check_files(&self, repair: bool) -> Result<FileCheckStat, Self::Error>;
+
+    /// Moves files whose rows are all older than `before` to the cold tier of the storage,
+    /// returns the number of files moved. Files in either tier are read transparently, and
+    /// nothing is moved if the storage has no cold tier.
+    async fn move_cold_files(&self, before: Timestamp) -> Result<usize, Self::Error>;
+
+    /// Catches up with the files and metadata persisted to the manifest by the writer of
+    /// the region. Writable regions are always up to date so this is a no-op for them.
+    async fn refresh(&self) -> Result<(), Self::Error>;
+
+    /// Turns a read-only region into writable after catching up with the manifest, so it
+    /// could take over writes once the previous writer of the region has stopped. Writes
+    /// not flushed by the previous writer are not recovered.
+    async fn set_writable(&self) -> Result<(), Self::Error>;
+
+    /// Reads committed writes whose sequence is greater than or equal to `start_sequence`
+    /// from the WAL, at most `limit` writes are returned. Writes already purged from the
+    /// WAL after flush can't be read, so the first write returned may have a sequence
+    /// greater than `start_sequence`.
+    async fn read_changes(
+        &self,
+        start_sequence: SequenceNumber,
+        limit: usize,
+    ) -> Result<Vec<ChangeBatch>, Self::Error>;
+}
+
+/// Rows of a committed write to the region, a write with several mutations is read as
+/// several batches sharing the same sequence.
+#[derive(Debug, Clone)]
+pub struct ChangeBatch {
+    /// Sequence of the write.
+    pub sequence: SequenceNumber,
+    pub op_type: OpType,
+    /// Schema of the columns, which doesn't contain internal columns and may be older
+    /// than the schema of the region.
+    pub schema: SchemaRef,
+    pub columns: Vec<VectorRef>,
+}
+
+impl ChangeBatch {
+    /// Returns the number of rows in the batch.
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map(|c| c.len()).unwrap_or(0)
+    }
+}
+
+/// Statistics of a region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionStat {
+    pub region_id: RegionId,
+    /// Approximate number of rows in memtables and SSTs, rows overwritten or deleted are
+    /// also counted.
+    pub approximate_rows: u64,
+    /// Bytes allocated by memtables.
+    pub memtable_bytes: u64,
+    /// Bytes of SST files.
+    pub sst_bytes: u64,
+    /// Number of SST files.
+    pub sst_files: u64,
+    /// Number of rows written since the region is opened.
+    pub written_rows: u64,
+    /// Unix timestamp in milliseconds of the last write to the region, kept across
+    /// restarts, `None` if not written yet.
+    pub last_write_millis: Option<i64>,
+    /// Files failed to verify by the last scrub.
+    pub corrupted_files: Vec<String>,
+}
+
+/// Result of a scrub of a region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrubStat {
+    /// Number of files whose checksums match.
+    pub verified_files: usize,
+    /// Files missing, unreadable or whose checksums mismatch.
+    pub corrupted_files: Vec<String>,
+}
+
+/// Result of checking the files of a region, see [Region::check_files].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCheckStat {
+    /// Number of files referenced by the manifest.
+    pub referenced_files: usize,
+    /// Files referenced by the manifest but missing in the object store.
+    pub missing_files: Vec<String>,
+    /// Files in the object store not referenced by the manifest. They may be written by
+    /// in-flight flushes or wait to be purged, so they are never deleted by the check.
+    pub orphan_files: Vec<String>,
+    /// Whether references to the missing files are removed from the manifest.
+    pub repaired: bool,
 }
 
 /// Context for write operations.
 #[derive(Debug, Clone, Default)]
-pub struct WriteContext {}
+pub struct WriteContext {
+    pub durability: Durability,
+}
+
+/// When a write is acknowledged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Acknowledges the write after it is appended to the WAL according to the sync policy of
+    /// the log store and applied to the region.
+    #[default]
+    Sync,
+    /// Acknowledges the write once it is in the WAL buffer, without waiting for the WAL to
+    /// be synced. The write becomes durable after [Region::fence] on its sequence returns.
+    Async,
+}
 
 impl From<&OpenOptions> for WriteContext {
     fn from(_opts: &OpenOptions) -> WriteContext {
@@ -49,6 +49,59 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Whether to append the sequence number of each row to the output, as the
+    /// last column named `__sequence`.
+    ///
+    /// Default is false.
+    pub with_sequence: bool,
+    /// How to resolve rows with the same row key.
+    pub dedup_strategy: DedupStrategy,
+    /// Whether the region is append-only. Rows of append-only regions are neither
+    /// deduplicated nor merged in row key order, `dedup_strategy` is ignored.
+    ///
+    /// Default is false.
+    pub append_mode: bool,
+}
+
+/// Strategy to resolve rows with the same row key (primary key + timestamp).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// Keeps the last written row.
+    #[default]
+    KeepLast,
+    /// Keeps the first written row.
+    KeepFirst,
+    /// Sums the values of numeric value columns, other value columns, and integer columns
+    /// whose sum overflows, keep the last written values.
+    Sum,
+    /// Keeps the max value of each value column.
+    Max,
+    /// Keeps the min value of each value column.
+    Min,
+}
+
+impl DedupStrategy {
+    /// Parses the strategy from its name, returns `None` if the name is unknown.
+    pub fn from_name(name: &str) -> Option<DedupStrategy> {
+        match name.to_lowercase().as_str() {
+            "keep_last" => Some(DedupStrategy::KeepLast),
+            "keep_first" => Some(DedupStrategy::KeepFirst),
+            "sum" => Some(DedupStrategy::Sum),
+            "max" => Some(DedupStrategy::Max),
+            "min" => Some(DedupStrategy::Min),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DedupStrategy::KeepLast => "keep_last",
+            DedupStrategy::KeepFirst => "keep_first",
+            DedupStrategy::Sum => "sum",
+            DedupStrategy::Max => "max",
+            DedupStrategy::Min => "min",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -206,4 +259,20 @@ mod tests {
         assert_eq!(1, desc.row_key.columns.len());
         assert_eq!(1, desc.default_cf.columns.len());
     }
+
+    #[test]
+    fn test_dedup_strategy_name() {
+        for strategy in [
+            DedupStrategy::KeepLast,
+            DedupStrategy::KeepFirst,
+            DedupStrategy::Sum,
+            DedupStrategy::Max,
+            DedupStrategy::Min,
+        ] {
+            assert_eq!(Some(strategy), DedupStrategy::from_name(strategy.as_str()));
+        }
+        assert_eq!(Some(DedupStrategy::Sum), DedupStrategy::from_name("SUM"));
+        assert_eq!(None, DedupStrategy::from_name("avg"));
+        assert_eq!(DedupStrategy::KeepLast, DedupStrategy::default());
+    }
 }
@@ -12,8 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::storage::SequenceNumber;
+
 #[derive(Debug)]
-pub struct WriteResponse {}
+pub struct WriteResponse {
+    /// Sequence of the write.
+    pub sequence: SequenceNumber,
+}
 
 #[derive(Debug)]
 pub struct ScanResponse<R> {
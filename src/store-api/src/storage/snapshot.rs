@@ -17,9 +17,9 @@ use common_error::ext::ErrorExt;
 use datatypes::schema::SchemaRef;
 
 use crate::storage::chunk::ChunkReader;
-use crate::storage::consts;
 use crate::storage::requests::{GetRequest, ScanRequest};
 use crate::storage::responses::{GetResponse, ScanResponse};
+use crate::storage::{consts, SequenceNumber};
 
 /// A consistent read-only view of region.
 #[async_trait]
@@ -29,6 +29,9 @@ pub trait Snapshot: Send + Sync {
 
     fn schema(&self) -> &SchemaRef;
 
+    /// Returns the max sequence of the writes visible to the snapshot.
+    fn sequence(&self) -> SequenceNumber;
+
     async fn scan(
         &self,
         ctx: &ReadContext,
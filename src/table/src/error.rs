@@ -105,6 +105,58 @@ pub enum Error {
 
     #[snafu(display("Unsupported operation: {}", operation))]
     Unsupported { operation: String },
+
+    #[snafu(display("Table {} is read-only", table_name))]
+    TableReadOnly {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Table {} already exists", table_name))]
+    TableExists {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Column {} of table {} is missing in the request",
+        column_name,
+        table_name
+    ))]
+    MissingColumn {
+        column_name: String,
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Column {} of table {} has {} rows, expect {} rows",
+        column_name,
+        table_name,
+        actual,
+        expected
+    ))]
+    ColumnLengthMismatch {
+        column_name: String,
+        table_name: String,
+        expected: usize,
+        actual: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Column {} of type {} could not be masked, only string columns are maskable",
+        column_name,
+        data_type
+    ))]
+    UnmaskableColumn {
+        column_name: String,
+        data_type: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Too many rows scanned, max rows to scan: {}", max_rows))]
+    TooManyScannedRows { max_rows: u64, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
@@ -123,6 +175,13 @@ impl ErrorExt for Error {
             Error::TableOperation { source } => source.status_code(),
             Error::ColumnNotExists { .. } => StatusCode::TableColumnNotFound,
             Error::Unsupported { .. } => StatusCode::Unsupported,
+            Error::TableReadOnly { .. } => StatusCode::TableReadOnly,
+            Error::TableExists { .. } => StatusCode::TableAlreadyExists,
+            Error::MissingColumn { .. } | Error::ColumnLengthMismatch { .. } => {
+                StatusCode::InvalidArguments
+            }
+            Error::UnmaskableColumn { .. } => StatusCode::Unsupported,
+            Error::TooManyScannedRows { .. } => StatusCode::InvalidArguments,
         }
     }
 
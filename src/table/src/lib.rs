@@ -14,6 +14,7 @@
 
 pub mod engine;
 pub mod error;
+pub mod masking;
 pub mod metadata;
 pub mod predicate;
 pub mod requests;
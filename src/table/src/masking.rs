@@ -0,0 +1,291 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Masking policies of sensitive columns, values of a masked column are replaced by
+//! their masked forms unless the user querying the table has a role that is allowed
+//! to see them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use datatypes::value::ValueRef;
+use datatypes::vectors::{StringVector, Vector, VectorRef};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Mask of the redacted values.
+const REDACTED: &str = "****";
+/// Number of trailing chars left visible by [MaskingMethod::Partial].
+const PARTIAL_VISIBLE_CHARS: usize = 4;
+
+/// Method to mask values of a string column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskingMethod {
+    /// Replaces the value by the hex encoded SHA-256 digest of it prefixed by the salt of
+    /// the policy, so masked values are still comparable with each other while guessed
+    /// values can't be checked against them without knowing the salt.
+    Hash,
+    /// Replaces the value by `****`.
+    Redact,
+    /// Replaces all but the last 4 chars of the value by `*`. Values not longer than 4
+    /// chars are masked entirely.
+    Partial,
+}
+
+impl MaskingMethod {
+    fn mask(&self, value: &str, salt: &str) -> String {
+        match self {
+            MaskingMethod::Hash => {
+                let digest = Sha256::new()
+                    .chain_update(salt.as_bytes())
+                    .chain_update(value.as_bytes())
+                    .finalize();
+                format!("{digest:x}")
+            }
+            MaskingMethod::Redact => REDACTED.to_string(),
+            MaskingMethod::Partial => {
+                let num_chars = value.chars().count();
+                if num_chars <= PARTIAL_VISIBLE_CHARS {
+                    return "*".repeat(num_chars);
+                }
+                let masked = num_chars - PARTIAL_VISIBLE_CHARS;
+                let visible = value.chars().skip(masked).collect::<String>();
+                format!("{}{visible}", "*".repeat(masked))
+            }
+        }
+    }
+}
+
+/// Mask of a column, made of the method and the salt of the policy masking it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMask {
+    pub method: MaskingMethod,
+    pub salt: String,
+}
+
+impl ColumnMask {
+    pub fn new(method: MaskingMethod) -> Self {
+        Self {
+            method,
+            salt: String::new(),
+        }
+    }
+
+    pub fn with_salt(mut self, salt: impl Into<String>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    pub fn mask(&self, value: &str) -> String {
+        self.method.mask(value, &self.salt)
+    }
+
+    /// Masks values of the string `vector`, nulls are left as is.
+    pub fn mask_vector(&self, vector: &VectorRef) -> VectorRef {
+        let masked = (0..vector.len())
+            .map(|i| match vector.get_ref(i) {
+                ValueRef::String(value) => Some(self.mask(value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        Arc::new(StringVector::from(masked))
+    }
+}
+
+/// Policy that masks a column of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "MaskingPolicyDef")]
+pub struct MaskingPolicy {
+    pub catalog: String,
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+    pub method: MaskingMethod,
+    /// Secret prefixed to the values hashed by [MaskingMethod::Hash], required by
+    /// the method.
+    pub salt: String,
+    /// Roles that could see the column unmasked.
+    pub unmasked_roles: Vec<String>,
+}
+
+/// Definition of [MaskingPolicy] in configs, which is validated before converted to
+/// the policy.
+#[derive(Deserialize)]
+struct MaskingPolicyDef {
+    #[serde(default = "default_catalog")]
+    catalog: String,
+    #[serde(default = "default_schema")]
+    schema: String,
+    table: String,
+    column: String,
+    method: MaskingMethod,
+    #[serde(default)]
+    salt: String,
+    #[serde(default)]
+    unmasked_roles: Vec<String>,
+}
+
+impl TryFrom<MaskingPolicyDef> for MaskingPolicy {
+    type Error = String;
+
+    fn try_from(def: MaskingPolicyDef) -> Result<Self, Self::Error> {
+        if def.method == MaskingMethod::Hash && def.salt.is_empty() {
+            return Err(format!(
+                "salt is required to hash column {} of table {}.{}.{}",
+                def.column, def.catalog, def.schema, def.table
+            ));
+        }
+        Ok(Self {
+            catalog: def.catalog,
+            schema: def.schema,
+            table: def.table,
+            column: def.column,
+            method: def.method,
+            salt: def.salt,
+            unmasked_roles: def.unmasked_roles,
+        })
+    }
+}
+
+fn default_catalog() -> String {
+    DEFAULT_CATALOG_NAME.to_string()
+}
+
+fn default_schema() -> String {
+    DEFAULT_SCHEMA_NAME.to_string()
+}
+
+/// Masks of columns of a table, keyed by column names.
+pub type ColumnMasks = HashMap<String, ColumnMask>;
+
+/// Returns masks of the columns of table `catalog.schema.table` that should be masked
+/// for a user with `roles`.
+pub fn column_masks(
+    policies: &[MaskingPolicy],
+    catalog: &str,
+    schema: &str,
+    table: &str,
+    roles: &[String],
+) -> ColumnMasks {
+    policies
+        .iter()
+        .filter(|policy| {
+            policy.catalog == catalog && policy.schema == schema && policy.table == table
+        })
+        .filter(|policy| {
+            !policy
+                .unmasked_roles
+                .iter()
+                .any(|role| roles.contains(role))
+        })
+        .map(|policy| {
+            let mask = ColumnMask::new(policy.method).with_salt(&policy.salt);
+            (policy.column.clone(), mask)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let hash = ColumnMask::new(MaskingMethod::Hash).with_salt("pepper");
+        let masked = hash.mask("123456");
+        assert_eq!(64, masked.len());
+        assert_eq!(masked, hash.mask("123456"));
+        assert_ne!(masked, hash.mask("654321"));
+        // The digest of the value without the salt is not revealed.
+        assert_ne!(
+            "8d969eef6ecad3c29a3a629280e686cf0c3f5d5a86aff3ca12020c923adc6c92",
+            masked
+        );
+        assert_eq!(format!("{:x}", Sha256::digest(b"pepper123456")), masked);
+        assert_ne!(
+            masked,
+            ColumnMask::new(MaskingMethod::Hash)
+                .with_salt("salt")
+                .mask("123456")
+        );
+
+        let redact = ColumnMask::new(MaskingMethod::Redact);
+        assert_eq!("****", redact.mask("123456"));
+        let partial = ColumnMask::new(MaskingMethod::Partial);
+        assert_eq!("**3456", partial.mask("123456"));
+        assert_eq!("***", partial.mask("abc"));
+        assert_eq!("*文名字好", partial.mask("中文名字好"));
+        assert_eq!("", partial.mask(""));
+    }
+
+    #[test]
+    fn test_mask_vector() {
+        let vector: VectorRef = Arc::new(StringVector::from(vec![Some("12345"), None]));
+        let masked = ColumnMask::new(MaskingMethod::Partial).mask_vector(&vector);
+        let expect: VectorRef = Arc::new(StringVector::from(vec![Some("*2345"), None]));
+        assert_eq!(expect, masked);
+    }
+
+    #[test]
+    fn test_column_masks() {
+        let policies: Vec<MaskingPolicy> = serde_json::from_str(
+            r#"[
+                {"table": "users", "column": "phone", "method": "partial", "unmasked_roles": ["admin"]},
+                {"table": "users", "column": "email", "method": "hash", "salt": "pepper"},
+                {"schema": "other", "table": "users", "column": "name", "method": "redact"}
+            ]"#,
+        )
+        .unwrap();
+
+        let masks = column_masks(&policies, "greptime", "public", "users", &[]);
+        assert_eq!(2, masks.len());
+        assert_eq!(ColumnMask::new(MaskingMethod::Partial), masks["phone"]);
+        assert_eq!(
+            ColumnMask::new(MaskingMethod::Hash).with_salt("pepper"),
+            masks["email"]
+        );
+
+        let masks = column_masks(
+            &policies,
+            "greptime",
+            "public",
+            "users",
+            &["admin".to_string()],
+        );
+        assert_eq!(1, masks.len());
+        assert_eq!(MaskingMethod::Hash, masks["email"].method);
+
+        let masks = column_masks(&policies, "greptime", "public", "orders", &[]);
+        assert!(masks.is_empty());
+    }
+
+    #[test]
+    fn test_hash_policy_requires_salt() {
+        let err = serde_json::from_str::<MaskingPolicy>(
+            r#"{"table": "users", "column": "email", "method": "hash"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("salt is required"));
+
+        let policy = serde_json::from_str::<MaskingPolicy>(
+            r#"{"table": "users", "column": "phone", "method": "redact"}"#,
+        )
+        .unwrap();
+        assert_eq!("greptime", policy.catalog);
+        assert_eq!("public", policy.schema);
+        assert!(policy.salt.is_empty());
+    }
+}
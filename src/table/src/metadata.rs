@@ -19,6 +19,7 @@ use chrono::{DateTime, Utc};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 pub use datatypes::error::{Error as ConvertError, Result as ConvertResult};
 use datatypes::schema::{ColumnSchema, RawSchema, Schema, SchemaBuilder, SchemaRef};
+use datatypes::value::Value;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
@@ -68,6 +69,49 @@ pub struct TableIdent {
     pub version: TableVersion,
 }
 
+/// Statistics of a column collected by `ANALYZE TABLE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStatistics {
+    /// Number of distinct non-null values.
+    pub distinct_count: u64,
+    pub null_count: u64,
+    /// Min non-null value, null if all values are null.
+    pub min_value: Value,
+    /// Max non-null value, null if all values are null.
+    pub max_value: Value,
+}
+
+/// Statistics of the columns of a table collected by `ANALYZE TABLE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableColumnStatistics {
+    /// Number of rows scanned to collect the statistics.
+    pub row_count: u64,
+    /// Statistics keyed by column names.
+    pub columns: HashMap<String, ColumnStatistics>,
+    pub analyzed_on: DateTime<Utc>,
+}
+
+impl TableColumnStatistics {
+    /// Creates statistics analyzed just now.
+    pub fn new(row_count: u64, columns: HashMap<String, ColumnStatistics>) -> Self {
+        Self {
+            row_count,
+            columns,
+            analyzed_on: Utc::now(),
+        }
+    }
+
+    /// Returns the fraction of null values in `column`, `None` if the column is not
+    /// analyzed.
+    pub fn null_fraction(&self, column: &str) -> Option<f64> {
+        let stats = self.columns.get(column)?;
+        if self.row_count == 0 {
+            return Some(0.0);
+        }
+        Some(stats.null_count as f64 / self.row_count as f64)
+    }
+}
+
 #[derive(Clone, Debug, Builder, PartialEq, Eq)]
 #[builder(pattern = "mutable")]
 pub struct TableMeta {
@@ -90,6 +134,13 @@ pub struct TableMeta {
     pub options: HashMap<String, String>,
     #[builder(default = "Utc::now()")]
     pub created_on: DateTime<Utc>,
+    /// Whether the table rejects writes.
+    #[builder(default)]
+    pub read_only: bool,
+    /// Statistics of columns collected by the last `ANALYZE TABLE`, `None` if the table
+    /// is never analyzed.
+    #[builder(default)]
+    pub column_statistics: Option<TableColumnStatistics>,
 }
 
 impl TableMetaBuilder {
@@ -139,6 +190,17 @@ impl TableMeta {
             AlterKind::DropColumns { names } => self.remove_columns(table_name, names),
             // No need to rebuild table meta when renaming tables.
             AlterKind::RenameTable { .. } => Ok(TableMetaBuilder::default()),
+            // The schema name is kept in the table info, the table meta is unchanged.
+            AlterKind::RenameSchema { .. } => Ok(self.unchanged()),
+            AlterKind::SetReadOnly { read_only } => Ok(self.set_read_only(*read_only)),
+            AlterKind::SetColumnStatistics { statistics } => {
+                Ok(self.set_column_statistics(statistics.clone()))
+            }
+            // The table comment is kept in the table info, the table meta is unchanged.
+            AlterKind::SetComment { .. } => Ok(self.unchanged()),
+            AlterKind::SetColumnComment { name, comment } => {
+                self.set_column_comment(table_name, name, comment)
+            }
         }
     }
 
@@ -176,11 +238,94 @@ impl TableMeta {
             .engine_options(self.engine_options.clone())
             .options(self.options.clone())
             .created_on(self.created_on)
-            .next_column_id(self.next_column_id);
+            .next_column_id(self.next_column_id)
+            .read_only(self.read_only)
+            .column_statistics(self.column_statistics.clone());
 
         builder
     }
 
+    fn unchanged(&self) -> TableMetaBuilder {
+        let mut meta_builder = self.new_meta_builder();
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .value_indices(self.value_indices.clone())
+            .region_numbers(self.region_numbers.clone());
+
+        meta_builder
+    }
+
+    fn set_read_only(&self, read_only: bool) -> TableMetaBuilder {
+        let mut meta_builder = self.new_meta_builder();
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .value_indices(self.value_indices.clone())
+            .region_numbers(self.region_numbers.clone())
+            .read_only(read_only);
+
+        meta_builder
+    }
+
+    fn set_column_statistics(&self, statistics: TableColumnStatistics) -> TableMetaBuilder {
+        let mut meta_builder = self.new_meta_builder();
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .value_indices(self.value_indices.clone())
+            .region_numbers(self.region_numbers.clone())
+            .column_statistics(Some(statistics));
+
+        meta_builder
+    }
+
+    fn set_column_comment(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        comment: &str,
+    ) -> Result<TableMetaBuilder> {
+        let table_schema = &self.schema;
+        ensure!(
+            table_schema.contains_column(column_name),
+            error::ColumnNotExistsSnafu {
+                column_name,
+                table_name,
+            }
+        );
+
+        let columns: Vec<_> = table_schema
+            .column_schemas()
+            .iter()
+            .map(|column_schema| {
+                if column_schema.name == column_name {
+                    column_schema.clone().with_comment(comment)
+                } else {
+                    column_schema.clone()
+                }
+            })
+            .collect();
+
+        // The comment doesn't change the layout of the data, so the schema version is kept.
+        let mut builder = SchemaBuilder::try_from_columns(columns)
+            .with_context(|_| error::SchemaBuildSnafu {
+                msg: format!("Failed to convert column schemas into schema for table {table_name}"),
+            })?
+            .version(table_schema.version());
+        for (k, v) in table_schema.metadata().iter() {
+            builder = builder.add_metadata(k, v);
+        }
+        let new_schema = builder.build().with_context(|_| error::SchemaBuildSnafu {
+            msg: format!("Table {table_name} cannot set comment of column {column_name}"),
+        })?;
+
+        let mut meta_builder = self.unchanged();
+        meta_builder.schema(Arc::new(new_schema));
+
+        Ok(meta_builder)
+    }
+
     fn add_columns(
         &self,
         table_name: &str,
@@ -309,9 +454,18 @@ impl TableMeta {
             .map(|name| new_schema.column_index_by_name(name).unwrap())
             .collect();
 
+        // Statistics of the removed columns are no longer valid.
+        let column_statistics = self.column_statistics.clone().map(|mut statistics| {
+            statistics
+                .columns
+                .retain(|name, _| !column_names.contains(name));
+            statistics
+        });
+
         meta_builder
             .schema(Arc::new(new_schema))
-            .primary_key_indices(primary_key_indices);
+            .primary_key_indices(primary_key_indices)
+            .column_statistics(column_statistics);
 
         Ok(meta_builder)
     }
@@ -389,6 +543,10 @@ pub struct RawTableMeta {
     pub engine_options: HashMap<String, String>,
     pub options: HashMap<String, String>,
     pub created_on: DateTime<Utc>,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub column_statistics: Option<TableColumnStatistics>,
 }
 
 impl From<TableMeta> for RawTableMeta {
@@ -403,6 +561,8 @@ impl From<TableMeta> for RawTableMeta {
             engine_options: meta.engine_options,
             options: meta.options,
             created_on: meta.created_on,
+            read_only: meta.read_only,
+            column_statistics: meta.column_statistics,
         }
     }
 }
@@ -421,6 +581,8 @@ impl TryFrom<RawTableMeta> for TableMeta {
             engine_options: raw.engine_options,
             options: raw.options,
             created_on: raw.created_on,
+            read_only: raw.read_only,
+            column_statistics: raw.column_statistics,
         })
     }
 }
@@ -525,10 +687,12 @@ mod tests {
                 AddColumnRequest {
                     column_schema: new_tag,
                     is_key: true,
+                    add_if_not_exists: false,
                 },
                 AddColumnRequest {
                     column_schema: new_field,
                     is_key: false,
+                    add_if_not_exists: false,
                 },
             ],
         };
@@ -668,6 +832,7 @@ mod tests {
             columns: vec![AddColumnRequest {
                 column_schema: ColumnSchema::new("col1", ConcreteDataType::string_datatype(), true),
                 is_key: false,
+                add_if_not_exists: false,
             }],
         };
 
@@ -734,6 +899,86 @@ mod tests {
         assert_eq!(StatusCode::InvalidArguments, err.status_code());
     }
 
+    #[test]
+    fn test_set_read_only() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+        assert!(!meta.read_only);
+
+        let alter_kind = AlterKind::SetReadOnly { read_only: true };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(new_meta.read_only);
+        assert_eq!(meta.schema, new_meta.schema);
+        assert_eq!(meta.primary_key_indices, new_meta.primary_key_indices);
+        assert_eq!(meta.value_indices, new_meta.value_indices);
+
+        // Adding columns keeps the table read-only.
+        let new_meta = add_columns_to_meta(&new_meta);
+        assert!(new_meta.read_only);
+
+        let raw = RawTableMeta::from(new_meta.clone());
+        assert_eq!(new_meta, TableMeta::try_from(raw).unwrap());
+    }
+
+    #[test]
+    fn test_set_column_comment() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+
+        let alter_kind = AlterKind::SetColumnComment {
+            name: "col2".to_string(),
+            comment: "the second column".to_string(),
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        let new_schema = &new_meta.schema;
+        assert_eq!(
+            Some("the second column"),
+            new_schema.column_schema_by_name("col2").unwrap().comment()
+        );
+        assert!(new_schema
+            .column_schema_by_name("col1")
+            .unwrap()
+            .comment()
+            .is_none());
+        assert_eq!(meta.schema.version(), new_schema.version());
+        assert_eq!(meta.schema.timestamp_index(), new_schema.timestamp_index());
+        assert_eq!(meta.primary_key_indices, new_meta.primary_key_indices);
+        assert_eq!(meta.value_indices, new_meta.value_indices);
+
+        let raw = RawTableMeta::from(new_meta.clone());
+        assert_eq!(new_meta, TableMeta::try_from(raw).unwrap());
+
+        let alter_kind = AlterKind::SetColumnComment {
+            name: "unknown".to_string(),
+            comment: "not exists".to_string(),
+        };
+        let err = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .err()
+            .unwrap();
+        assert_eq!(StatusCode::TableColumnNotFound, err.status_code());
+    }
+
     #[test]
     fn test_alloc_new_column() {
         let schema = Arc::new(new_test_schema());
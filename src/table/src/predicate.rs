@@ -23,8 +23,10 @@ use datatypes::schema::SchemaRef;
 use datatypes::value::scalar_value_to_timestamp;
 
 use crate::predicate::stats::RowGroupPruningStatistics;
+use crate::predicate::token::{TokenFilter, TokenIndex};
 
 mod stats;
+pub mod token;
 
 #[derive(Default, Clone)]
 pub struct Predicate {
@@ -68,6 +70,20 @@ impl Predicate {
         }
         res
     }
+
+    /// Prunes the `num_row_groups` row groups of a file by its token `index`, only
+    /// `LIKE` and `matches()` filters are used.
+    pub fn prune_row_groups_by_tokens(
+        &self,
+        index: &TokenIndex,
+        num_row_groups: usize,
+    ) -> Vec<bool> {
+        let mut filters = Vec::new();
+        for expr in &self.exprs {
+            TokenFilter::extract(expr.df_expr(), &mut filters);
+        }
+        index.prune(&filters, num_row_groups)
+    }
 }
 
 // tests for `TimeRangePredicateBuilder` locates in src/query/tests/time_range_filter_test.rs
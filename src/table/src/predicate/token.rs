@@ -0,0 +1,297 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-SST token indexes to prune row groups by `LIKE` and full-text `matches()` filters.
+
+use std::collections::{BTreeSet, HashMap};
+
+use common_base::tokenizer::{is_token_char, tokenize};
+use datafusion_common::ScalarValue;
+use datafusion_expr::{BinaryExpr, Like, Operator};
+use datatypes::value::ValueRef;
+use datatypes::vectors::VectorRef;
+use serde::{Deserialize, Serialize};
+
+use crate::predicate::DfExpr;
+
+/// Key of the token index in the key-value metadata of a Parquet file.
+pub const TOKEN_INDEX_KEY: &str = "greptime:token_index";
+/// Name of the full-text search function, `matches(column, query)`.
+const MATCHES_FUNCTION_NAME: &str = "matches";
+/// Row groups with more distinct tokens in a column than this are not indexed.
+const MAX_TOKENS_PER_ROW_GROUP: usize = 16384;
+
+/// Distinct tokens of string columns in each row group of a file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenIndex {
+    /// Tokens of each row group keyed by column name, `None` if there are too many tokens
+    /// in the row group to index.
+    columns: HashMap<String, Vec<Option<BTreeSet<String>>>>,
+}
+
+impl TokenIndex {
+    /// Returns whether each of the `num_row_groups` row groups may contain rows matching
+    /// all the `filters`.
+    pub fn prune(&self, filters: &[TokenFilter], num_row_groups: usize) -> Vec<bool> {
+        let mut res = vec![true; num_row_groups];
+        for filter in filters {
+            let Some(row_groups) = self.columns.get(filter.column()) else {
+                continue;
+            };
+            for (tokens, res) in row_groups.iter().zip(res.iter_mut()) {
+                if let Some(tokens) = tokens {
+                    *res &= filter.may_match(tokens);
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Builds a [TokenIndex] from batches of rows that are written to row groups of
+/// `row_group_size` rows in order.
+pub struct TokenIndexBuilder {
+    row_group_size: usize,
+    num_rows: usize,
+    index: TokenIndex,
+}
+
+impl TokenIndexBuilder {
+    pub fn new(row_group_size: usize) -> Self {
+        Self {
+            row_group_size,
+            num_rows: 0,
+            index: TokenIndex::default(),
+        }
+    }
+
+    /// Adds a batch of `num_rows` rows, `columns` are the names and values of the string
+    /// columns to index.
+    pub fn push_batch(&mut self, columns: &[(&str, &VectorRef)], num_rows: usize) {
+        for (name, vector) in columns {
+            let row_groups = self.index.columns.entry(name.to_string()).or_default();
+            for i in 0..vector.len() {
+                let row_group = (self.num_rows + i) / self.row_group_size;
+                if row_groups.len() <= row_group {
+                    row_groups.resize_with(row_group + 1, || Some(BTreeSet::new()));
+                }
+                let ValueRef::String(text) = vector.get_ref(i) else {
+                    continue;
+                };
+                if let Some(tokens) = &mut row_groups[row_group] {
+                    tokens.extend(tokenize(text));
+                    if tokens.len() > MAX_TOKENS_PER_ROW_GROUP {
+                        row_groups[row_group] = None;
+                    }
+                }
+            }
+        }
+        self.num_rows += num_rows;
+    }
+
+    pub fn finish(self) -> TokenIndex {
+        self.index
+    }
+}
+
+/// Filter on a string column that is satisfied only by texts containing some tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenFilter {
+    /// Texts contain all the `tokens`, from `matches(column, query)`.
+    Tokens { column: String, tokens: Vec<String> },
+    /// Each of the `fragments` is part of a token of the texts, from
+    /// `column LIKE '%pattern%'`.
+    Fragments {
+        column: String,
+        fragments: Vec<String>,
+    },
+}
+
+impl TokenFilter {
+    /// Extracts token filters from conjunctions in `expr`.
+    pub fn extract(expr: &DfExpr, filters: &mut Vec<TokenFilter>) {
+        match expr {
+            DfExpr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            }) => {
+                Self::extract(left, filters);
+                Self::extract(right, filters);
+            }
+            DfExpr::Like(like) | DfExpr::ILike(like) => {
+                filters.extend(Self::from_like(like));
+            }
+            DfExpr::ScalarUDF { fun, args } if fun.name == MATCHES_FUNCTION_NAME => {
+                if let [DfExpr::Column(column), DfExpr::Literal(ScalarValue::Utf8(Some(query)))] =
+                    args.as_slice()
+                {
+                    filters.push(TokenFilter::Tokens {
+                        column: column.name.clone(),
+                        tokens: tokenize(query).collect(),
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn from_like(like: &Like) -> Option<TokenFilter> {
+        if like.negated || like.escape_char.is_some() {
+            return None;
+        }
+        let (column, pattern) = match (like.expr.as_ref(), like.pattern.as_ref()) {
+            (DfExpr::Column(column), DfExpr::Literal(ScalarValue::Utf8(Some(pattern)))) => {
+                (column, pattern)
+            }
+            _ => return None,
+        };
+        // Texts matching the pattern contain each run of token chars in the pattern as a
+        // part of one of their tokens. `_` is a wildcard in patterns so it also splits
+        // the runs. Only ascii runs are kept as lowercasing some unicode chars depends on
+        // their context.
+        let fragments = pattern
+            .split(|c: char| c == '_' || !is_token_char(c))
+            .filter(|fragment| !fragment.is_empty() && fragment.is_ascii())
+            .map(|fragment| fragment.to_ascii_lowercase())
+            .collect();
+        Some(TokenFilter::Fragments {
+            column: column.name.clone(),
+            fragments,
+        })
+    }
+
+    fn column(&self) -> &str {
+        match self {
+            TokenFilter::Tokens { column, .. } | TokenFilter::Fragments { column, .. } => column,
+        }
+    }
+
+    /// Returns whether texts with `tokens` may satisfy the filter.
+    fn may_match(&self, tokens: &BTreeSet<String>) -> bool {
+        match self {
+            TokenFilter::Tokens { tokens: wanted, .. } => {
+                wanted.iter().all(|token| tokens.contains(token))
+            }
+            TokenFilter::Fragments { fragments, .. } => fragments
+                .iter()
+                .all(|fragment| tokens.iter().any(|token| token.contains(fragment.as_str()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_expr::{col, lit};
+    use datatypes::vectors::StringVector;
+
+    use super::*;
+
+    fn build_index(texts: &[Option<&str>], row_group_size: usize) -> TokenIndex {
+        let vector: VectorRef = Arc::new(StringVector::from(texts.to_vec()));
+        let mut builder = TokenIndexBuilder::new(row_group_size);
+        // Pushes rows one by one to cover row groups across batches.
+        for i in 0..vector.len() {
+            let row = vector.slice(i, 1);
+            builder.push_batch(&[("msg", &row)], 1);
+        }
+        builder.finish()
+    }
+
+    fn extract(expr: DfExpr) -> Vec<TokenFilter> {
+        let mut filters = Vec::new();
+        TokenFilter::extract(&expr, &mut filters);
+        filters
+    }
+
+    #[test]
+    fn test_extract_like() {
+        assert_eq!(
+            vec![TokenFilter::Fragments {
+                column: "msg".to_string(),
+                fragments: vec!["connection".to_string(), "reset".to_string()],
+            }],
+            extract(col("msg").like(lit("%Connection_Reset%")))
+        );
+        assert!(extract(col("msg").not_like(lit("%error%"))).is_empty());
+        assert_eq!(
+            2,
+            extract(col("msg").like(lit("%a%")).and(col("host").like(lit("b%")))).len()
+        );
+        assert!(extract(col("msg").like(lit("%a%")).or(col("msg").like(lit("%b%")))).is_empty());
+    }
+
+    #[test]
+    fn test_prune() {
+        let index = build_index(
+            &[
+                Some("GET /index.html 200"),
+                Some("connection reset by peer"),
+                None,
+                Some("GET /login 401"),
+                Some("timeout"),
+            ],
+            2,
+        );
+        let prune = |filter: TokenFilter| index.prune(&[filter], 3);
+        let fragments = |fragments: &[&str]| TokenFilter::Fragments {
+            column: "msg".to_string(),
+            fragments: fragments.iter().map(|s| s.to_string()).collect(),
+        };
+        let tokens = |tokens: &[&str]| TokenFilter::Tokens {
+            column: "msg".to_string(),
+            tokens: tokens.iter().map(|s| s.to_string()).collect(),
+        };
+
+        assert_eq!(
+            vec![true, false, false],
+            prune(fragments(&["conn", "peer"]))
+        );
+        assert_eq!(vec![true, true, false], prune(fragments(&["get"])));
+        assert_eq!(vec![false, false, true], prune(tokens(&["timeout"])));
+        assert_eq!(vec![false, false, false], prune(tokens(&["time"])));
+        assert_eq!(vec![true, true, true], prune(tokens(&[])));
+        // Columns without index are not pruned.
+        assert_eq!(
+            vec![true, true, true],
+            index.prune(
+                &[TokenFilter::Tokens {
+                    column: "host".to_string(),
+                    tokens: vec!["a".to_string()],
+                }],
+                3
+            )
+        );
+    }
+
+    #[test]
+    fn test_too_many_tokens() {
+        let texts = (0..=MAX_TOKENS_PER_ROW_GROUP)
+            .map(|i| format!("t{i}"))
+            .collect::<Vec<_>>();
+        let vector: VectorRef = Arc::new(StringVector::from(
+            texts.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        ));
+        let mut builder = TokenIndexBuilder::new(texts.len());
+        builder.push_batch(&[("msg", &vector)], vector.len());
+        let index = builder.finish();
+        let filter = TokenFilter::Tokens {
+            column: "msg".to_string(),
+            tokens: vec!["absent".to_string()],
+        };
+        assert_eq!(vec![true], index.prune(&[filter], 1));
+    }
+}
@@ -14,12 +14,13 @@
 
 //! Table and TableEngine requests
 use std::collections::HashMap;
+use std::fmt;
 
 use datatypes::prelude::VectorRef;
-use datatypes::schema::{ColumnSchema, SchemaRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use store_api::storage::RegionNumber;
 
-use crate::metadata::TableId;
+use crate::metadata::{TableColumnStatistics, TableId};
 
 /// Insert request
 #[derive(Debug)]
@@ -32,10 +33,33 @@ pub struct InsertRequest {
 
 #[derive(Debug, Clone)]
 pub struct CreateDatabaseRequest {
+    pub catalog_name: String,
     pub db_name: String,
     pub create_if_not_exists: bool,
+    /// Default options of tables created in the database.
+    pub options: HashMap<String, String>,
+}
+
+/// Alter database request
+#[derive(Debug, Clone)]
+pub struct AlterDatabaseRequest {
+    pub catalog_name: String,
+    pub db_name: String,
+    pub alter_kind: AlterDatabaseKind,
 }
 
+#[derive(Debug, Clone)]
+pub enum AlterDatabaseKind {
+    /// Renames the database, tables of the database are moved to the new name.
+    RenameDatabase { new_name: String },
+    /// Sets the key-value metadata of the database, e.g. owner and description.
+    SetMetadata { metadata: HashMap<String, String> },
+}
+
+/// Key of the table option naming the engine to create the table, the default engine of
+/// the datanode is used if absent.
+pub const ENGINE_OPTION: &str = "engine";
+
 /// Create table request
 #[derive(Debug, Clone)]
 pub struct CreateTableRequest {
@@ -81,13 +105,105 @@ impl AlterTableRequest {
 pub struct AddColumnRequest {
     pub column_schema: ColumnSchema,
     pub is_key: bool,
+    /// Skips the column instead of failing if the column already exists.
+    pub add_if_not_exists: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum AlterKind {
-    AddColumns { columns: Vec<AddColumnRequest> },
-    DropColumns { names: Vec<String> },
-    RenameTable { new_table_name: String },
+    AddColumns {
+        columns: Vec<AddColumnRequest>,
+    },
+    DropColumns {
+        names: Vec<String>,
+    },
+    RenameTable {
+        new_table_name: String,
+    },
+    /// Moves the table into the schema its schema is renamed to.
+    RenameSchema {
+        new_schema_name: String,
+    },
+    /// Marks the table as read-only, or writable again. A read-only table rejects
+    /// all writes but still serves reads.
+    SetReadOnly {
+        read_only: bool,
+    },
+    /// Replaces the column statistics of the table with those collected by
+    /// `ANALYZE TABLE`.
+    SetColumnStatistics {
+        statistics: TableColumnStatistics,
+    },
+    /// Sets the comment of the table, `None` removes the comment.
+    SetComment {
+        comment: Option<String>,
+    },
+    /// Sets the comment of the column `name`, an empty comment removes the comment.
+    SetColumnComment {
+        name: String,
+        comment: String,
+    },
+}
+
+impl AlterKind {
+    /// Removes columns to add with `add_if_not_exists` that already exist in `schema`, so
+    /// applying the same alteration again is a no-op.
+    ///
+    /// Returns `None` if there is nothing left to alter.
+    pub fn skip_existing_columns(&self, schema: &Schema) -> Option<AlterKind> {
+        match self {
+            AlterKind::AddColumns { columns } => {
+                let columns: Vec<_> = columns
+                    .iter()
+                    .filter(|c| {
+                        !(c.add_if_not_exists && schema.contains_column(&c.column_schema.name))
+                    })
+                    .cloned()
+                    .collect();
+                if columns.is_empty() {
+                    None
+                } else {
+                    Some(AlterKind::AddColumns { columns })
+                }
+            }
+            other => Some(other.clone()),
+        }
+    }
+}
+
+impl fmt::Display for AlterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterKind::AddColumns { columns } => {
+                let names = columns
+                    .iter()
+                    .map(|c| c.column_schema.name.as_str())
+                    .collect::<Vec<_>>();
+                write!(f, "add columns [{}]", names.join(", "))
+            }
+            AlterKind::DropColumns { names } => write!(f, "drop columns [{}]", names.join(", ")),
+            AlterKind::RenameTable { new_table_name } => write!(f, "rename to {new_table_name}"),
+            AlterKind::RenameSchema { new_schema_name } => {
+                write!(f, "move to schema {new_schema_name}")
+            }
+            AlterKind::SetReadOnly { read_only } => write!(f, "set read_only = {read_only}"),
+            AlterKind::SetColumnStatistics { statistics } => write!(
+                f,
+                "set statistics of columns analyzed on {}",
+                statistics.analyzed_on
+            ),
+            AlterKind::SetComment { comment } => {
+                write!(
+                    f,
+                    "set comment = '{}'",
+                    comment.as_deref().unwrap_or_default()
+                )
+            }
+            AlterKind::SetColumnComment { name, comment } => {
+                write!(f, "set comment of column {name} = '{comment}'")
+            }
+        }
+    }
 }
 
 /// Drop table request
@@ -98,6 +214,32 @@ pub struct DropTableRequest {
     pub table_name: String,
 }
 
+/// Request to recover the latest dropped table with the name
+#[derive(Debug)]
+pub struct UndropTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
+
+/// Analyze table request
+#[derive(Debug)]
+pub struct AnalyzeTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
+
+/// Request to check whether files referenced by the table exist in the storage
+#[derive(Debug)]
+pub struct CheckTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    /// Removes references to missing files if true.
+    pub repair: bool,
+}
+
 /// Delete (by primary key) request
 #[derive(Debug)]
 pub struct DeleteRequest {
@@ -106,3 +248,60 @@ pub struct DeleteRequest {
     /// The key is the column name, and the value is the column value.
     pub key_column_values: HashMap<String, VectorRef>,
 }
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+
+    use super::*;
+
+    fn add_column(name: &str, add_if_not_exists: bool) -> AddColumnRequest {
+        AddColumnRequest {
+            column_schema: ColumnSchema::new(name, ConcreteDataType::int32_datatype(), true),
+            is_key: false,
+            add_if_not_exists,
+        }
+    }
+
+    fn column_names(alter_kind: &AlterKind) -> Vec<&str> {
+        match alter_kind {
+            AlterKind::AddColumns { columns } => columns
+                .iter()
+                .map(|c| c.column_schema.name.as_str())
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_skip_existing_columns() {
+        let schema = Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]);
+
+        let alter_kind = AlterKind::AddColumns {
+            columns: vec![add_column("a", true), add_column("b", true)],
+        };
+        let alter_kind = alter_kind.skip_existing_columns(&schema).unwrap();
+        assert_eq!(vec!["b"], column_names(&alter_kind));
+
+        // Existing columns without `add_if_not_exists` are kept, so the alteration still fails.
+        let alter_kind = AlterKind::AddColumns {
+            columns: vec![add_column("a", false)],
+        };
+        let alter_kind = alter_kind.skip_existing_columns(&schema).unwrap();
+        assert_eq!(vec!["a"], column_names(&alter_kind));
+
+        let alter_kind = AlterKind::AddColumns {
+            columns: vec![add_column("a", true)],
+        };
+        assert!(alter_kind.skip_existing_columns(&schema).is_none());
+
+        let alter_kind = AlterKind::DropColumns {
+            names: vec!["a".to_string()],
+        };
+        assert!(alter_kind.skip_existing_columns(&schema).is_some());
+    }
+}
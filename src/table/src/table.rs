@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod adapter;
+pub mod epoch;
 pub mod numbers;
 pub mod scan;
 
@@ -22,14 +23,58 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
-use datatypes::schema::SchemaRef;
+use datafusion::physical_plan::Statistics;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
+use snafu::ResultExt;
+use store_api::storage::{ChangeBatch, FileCheckStat, RegionStat, ScrubStat, SequenceNumber};
 
-use crate::error::{Result, UnsupportedSnafu};
+use crate::error::{Result, SchemaBuildSnafu, UnsupportedSnafu};
 use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
 use crate::requests::{AlterTableRequest, DeleteRequest, InsertRequest};
 
 pub type AlterContext = anymap::Map<dyn Any + Send + Sync>;
 
+/// Name of the pseudo column holding the version of rows, see [Table::scan_with_row_version].
+pub const ROW_VERSION_COLUMN_NAME: &str = "__version";
+
+tokio::task_local! {
+    /// Max sequence of the writes read by the table scans in the scope, the scans out of
+    /// the scope read the latest writes. See [Table::snapshot_sequence].
+    pub static SCAN_SEQUENCE: SequenceNumber;
+}
+
+/// Estimated size of a table, rows overwritten or deleted are also counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableStatistics {
+    pub num_rows: u64,
+    /// Bytes of the data in memory and in files.
+    pub num_bytes: u64,
+    /// Unix timestamp in milliseconds of the last write to the table, `None` if not written
+    /// yet.
+    pub last_write_millis: Option<i64>,
+}
+
+impl TableStatistics {
+    /// Returns the statistics of a scan of the table that returns at most `limit` rows,
+    /// the bytes are prorated by the number of rows to scan.
+    pub fn scan_statistics(&self, limit: Option<usize>) -> Statistics {
+        let num_rows = self.num_rows as usize;
+        let rows_to_scan = limit.map_or(num_rows, |limit| limit.min(num_rows));
+        let bytes_to_scan = if num_rows == 0 {
+            0
+        } else {
+            (self.num_bytes as f64 * rows_to_scan as f64 / num_rows as f64) as usize
+        };
+        Statistics {
+            num_rows: Some(rows_to_scan),
+            total_byte_size: Some(bytes_to_scan),
+            column_statistics: None,
+            is_exact: false,
+        }
+    }
+}
+
 /// Table abstraction.
 #[async_trait]
 pub trait Table: Send + Sync {
@@ -43,6 +88,12 @@ pub trait Table: Send + Sync {
     /// Get a reference to the table info.
     fn table_info(&self) -> TableInfoRef;
 
+    /// Get a reference to the table info, `None` if the table has no info, e.g. virtual
+    /// tables whose [Table::table_info] is unreachable.
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        Some(self.table_info())
+    }
+
     /// Get the type of this table for metadata/catalog purposes.
     fn table_type(&self) -> TableType {
         TableType::Base
@@ -58,6 +109,34 @@ pub trait Table: Send + Sync {
         .fail()?
     }
 
+    /// Insert values into table like [Table::insert], but acknowledges once the values are
+    /// in the WAL buffer, without waiting for the WAL to be synced.
+    ///
+    /// Returns number of inserted rows and the sequence of the write, which could be passed
+    /// to [Table::fence] to wait for the write to become durable.
+    async fn insert_unsynced(&self, _request: InsertRequest) -> Result<(usize, SequenceNumber)> {
+        UnsupportedSnafu {
+            operation: "INSERT with WAL acknowledgment",
+        }
+        .fail()?
+    }
+
+    /// Waits until all writes to the table whose sequence is less than or equal to `sequence`
+    /// are durable.
+    async fn fence(&self, _sequence: SequenceNumber) -> Result<()> {
+        UnsupportedSnafu { operation: "FENCE" }.fail()?
+    }
+
+    /// Returns the sequence of the latest write visible to readers. Scans in the scope of
+    /// [SCAN_SEQUENCE] with the sequence read the table as of now, regardless of the writes
+    /// coming later.
+    async fn snapshot_sequence(&self) -> Result<SequenceNumber> {
+        UnsupportedSnafu {
+            operation: "SNAPSHOT",
+        }
+        .fail()?
+    }
+
     /// Scan the table and returns a SendableRecordBatchStream.
     async fn scan(
         &self,
@@ -70,6 +149,29 @@ pub trait Table: Send + Sync {
         limit: Option<usize>,
     ) -> Result<PhysicalPlanRef>;
 
+    /// Returns whether the table supports [Table::scan_with_row_version].
+    fn supports_row_version(&self) -> bool {
+        false
+    }
+
+    /// Scan the table like [Table::scan], but with a pseudo column [ROW_VERSION_COLUMN_NAME]
+    /// appended to the schema of the table, see [schema_with_row_version]. The version
+    /// of a row is the sequence of the write that the row comes from, so rows with the
+    /// same key overwrite each other in the order of their versions.
+    ///
+    /// The `projection` refers to the columns of the schema with the pseudo column.
+    async fn scan_with_row_version(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        UnsupportedSnafu {
+            operation: "SCAN WITH ROW VERSION",
+        }
+        .fail()?
+    }
+
     /// Tests whether the table provider can make use of a filter expression
     /// to optimise data retrieval.
     fn supports_filter_pushdown(&self, _filter: &Expr) -> Result<FilterPushDownType> {
@@ -93,10 +195,96 @@ pub trait Table: Send + Sync {
         }
         .fail()?
     }
+
+    /// Returns the statistics of the regions of the table.
+    fn region_stats(&self) -> Vec<RegionStat> {
+        vec![]
+    }
+
+    /// Returns the estimated size of the table aggregated from the statistics of its
+    /// regions, `None` if the table has no region.
+    fn statistics(&self) -> Option<TableStatistics> {
+        let stats = self.region_stats();
+        if stats.is_empty() {
+            return None;
+        }
+        Some(TableStatistics {
+            num_rows: stats.iter().map(|stat| stat.approximate_rows).sum(),
+            num_bytes: stats
+                .iter()
+                .map(|stat| stat.memtable_bytes + stat.sst_bytes)
+                .sum(),
+            last_write_millis: stats.iter().filter_map(|stat| stat.last_write_millis).max(),
+        })
+    }
+
+    /// Collects the estimated size of the table, from the nodes serving its regions if the
+    /// regions are remote. Defaults to [Table::statistics].
+    async fn collect_statistics(&self) -> Result<Option<TableStatistics>> {
+        Ok(self.statistics())
+    }
+
+    /// Verifies the checksums of files of the regions of the table.
+    async fn scrub(&self) -> Result<Vec<ScrubStat>> {
+        Ok(vec![])
+    }
+
+    /// Checks whether files referenced by the regions of the table exist in the storage,
+    /// removes references to missing files if `repair` is true.
+    async fn check_files(&self, _repair: bool) -> Result<Vec<FileCheckStat>> {
+        Ok(vec![])
+    }
+
+    /// Returns false if the regions of the table are opened lazily and not opened yet.
+    async fn is_opened(&self) -> bool {
+        true
+    }
+
+    /// Catches up with the changes persisted by the datanode writing the table if the table
+    /// is opened as a standby, see [Region::refresh](store_api::storage::Region::refresh).
+    async fn refresh(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Turns a table opened as a standby writable, see
+    /// [Region::set_writable](store_api::storage::Region::set_writable).
+    async fn set_writable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reads committed writes of the table from the WAL, see
+    /// [Region::read_changes](store_api::storage::Region::read_changes).
+    async fn read_changes(
+        &self,
+        _start_sequence: SequenceNumber,
+        _limit: usize,
+    ) -> Result<Vec<ChangeBatch>> {
+        UnsupportedSnafu {
+            operation: "READ CHANGES",
+        }
+        .fail()?
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
 
+/// Returns the `schema` with the pseudo column [ROW_VERSION_COLUMN_NAME] appended.
+pub fn schema_with_row_version(schema: &SchemaRef) -> Result<SchemaRef> {
+    let mut column_schemas = schema.column_schemas().to_vec();
+    column_schemas.push(ColumnSchema::new(
+        ROW_VERSION_COLUMN_NAME,
+        ConcreteDataType::uint64_datatype(),
+        false,
+    ));
+
+    let schema = SchemaBuilder::try_from(column_schemas)
+        .and_then(|builder| builder.version(schema.version()).build())
+        .context(SchemaBuildSnafu {
+            msg: "Failed to append row version column",
+        })?;
+    Ok(Arc::new(schema))
+}
+
 #[async_trait::async_trait]
 pub trait TableIdProvider {
     async fn next_table_id(&self) -> Result<TableId>;
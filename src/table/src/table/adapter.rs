@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 
 use common_query::logical_plan::Expr;
@@ -26,21 +28,70 @@ use datafusion::error::Result as DfResult;
 use datafusion::execution::context::SessionState;
 use datafusion::prelude::SessionContext;
 use datafusion_expr::expr::Expr as DfExpr;
+use datafusion_expr::utils::expr_to_columns;
 use datatypes::schema::{SchemaRef as TableSchemaRef, SchemaRef};
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
+use crate::masking::ColumnMasks;
 use crate::metadata::TableInfoRef;
-use crate::table::{FilterPushDownType, Table, TableRef, TableType};
+use crate::table::scan::{MaskingScan, MaxRowsScan, ScannedBytesCountingScan};
+use crate::table::{schema_with_row_version, FilterPushDownType, Table, TableRef, TableType};
 
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    /// Schema with the row version column, `Some` if the row version is exposed.
+    schema_with_row_version: Option<TableSchemaRef>,
+    /// Counter of bytes scanned from the table.
+    scanned_bytes: Option<Arc<AtomicU64>>,
+    /// Masks of the sensitive columns.
+    masks: ColumnMasks,
+    /// Max rows to scan from the table, the scan fails once it's exceeded.
+    max_scan_rows: Option<u64>,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self {
+            table,
+            schema_with_row_version: None,
+            scanned_bytes: None,
+            masks: ColumnMasks::new(),
+            max_scan_rows: None,
+        }
+    }
+
+    /// Creates an adapter that exposes the row version of the `table` as a pseudo
+    /// column, see [Table::scan_with_row_version].
+    pub fn with_row_version(table: TableRef) -> Result<Self> {
+        let schema = schema_with_row_version(&table.schema())?;
+        Ok(Self {
+            table,
+            schema_with_row_version: Some(schema),
+            scanned_bytes: None,
+            masks: ColumnMasks::new(),
+            max_scan_rows: None,
+        })
+    }
+
+    /// Adds bytes scanned from the table to `scanned_bytes`.
+    pub fn with_scanned_bytes(mut self, scanned_bytes: Arc<AtomicU64>) -> Self {
+        self.scanned_bytes = Some(scanned_bytes);
+        self
+    }
+
+    /// Masks values of the columns in `masks`. Filters referencing masked columns are
+    /// not pushed down to the table, so they are evaluated against the masked values.
+    pub fn with_masks(mut self, masks: ColumnMasks) -> Self {
+        self.masks = masks;
+        self
+    }
+
+    /// Fails the scans of the table once more than `max_scan_rows` rows are scanned.
+    pub fn with_max_scan_rows(mut self, max_scan_rows: Option<u64>) -> Self {
+        self.max_scan_rows = max_scan_rows;
+        self
     }
 
     pub fn table(&self) -> TableRef {
@@ -55,7 +106,10 @@ impl TableProvider for DfTableProviderAdapter {
     }
 
     fn schema(&self) -> DfSchemaRef {
-        self.table.schema().arrow_schema().clone()
+        match &self.schema_with_row_version {
+            Some(schema) => schema.arrow_schema().clone(),
+            None => self.table.schema().arrow_schema().clone(),
+        }
     }
 
     fn table_type(&self) -> DfTableType {
@@ -74,11 +128,43 @@ impl TableProvider for DfTableProviderAdapter {
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
         let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let inner = if self.schema_with_row_version.is_some() {
+            self.table
+                .scan_with_row_version(projection, &filters, limit)
+                .await?
+        } else {
+            self.table.scan(projection, &filters, limit).await?
+        };
+        let inner = match &self.scanned_bytes {
+            Some(scanned_bytes) => {
+                Arc::new(ScannedBytesCountingScan::new(inner, scanned_bytes.clone())) as _
+            }
+            None => inner,
+        };
+        let inner = match self.max_scan_rows {
+            Some(max_rows) => Arc::new(MaxRowsScan::new(inner, max_rows)) as _,
+            None => inner,
+        };
+        let inner = if self.masks.is_empty() {
+            inner
+        } else {
+            Arc::new(MaskingScan::try_new(inner, &self.masks)?) as _
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 
     fn supports_filter_pushdown(&self, filter: &DfExpr) -> DfResult<DfTableProviderFilterPushDown> {
+        if !self.masks.is_empty() {
+            let mut columns = HashSet::new();
+            expr_to_columns(filter, &mut columns)?;
+            if columns
+                .iter()
+                .any(|column| self.masks.contains_key(&column.name))
+            {
+                return Ok(DfTableProviderFilterPushDown::Unsupported);
+            }
+        }
+
         let p = self
             .table
             .supports_filter_pushdown(&filter.clone().into())?;
@@ -124,6 +210,10 @@ impl Table for TableAdapter {
         unreachable!("Should not call table_info of TableAdaptor directly")
     }
 
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        None
+    }
+
     fn table_type(&self) -> TableType {
         match self.table_provider.table_type() {
             DfTableType::Base => TableType::Base,
@@ -0,0 +1,233 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reads a table at the schema epoch pinned by a reader.
+//!
+//! The epoch of a table schema is its [version](datatypes::schema::Schema::version), which
+//! is bumped by each ALTER. A query pins the epoch of every table it reads when it's planned,
+//! e.g. the frontend ships the schema it planned with to the datanodes. Regions of a table
+//! apply an ALTER one after another, so a region may be at a different epoch than the query.
+//! Reading such a region at the pinned epoch, instead of its own, keeps the query from
+//! scanning regions with mixed schemas.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::schema::{Schema, SchemaRef};
+
+use crate::error::Result;
+use crate::metadata::{FilterPushDownType, TableInfo, TableInfoRef, TableType};
+use crate::table::{Table, TableStatistics};
+use crate::TableRef;
+
+/// A table read at a pinned schema epoch, columns of the pinned schema are read from the
+/// columns of the same names in the current schema of the table.
+pub struct EpochPinnedTable {
+    inner: TableRef,
+    schema: SchemaRef,
+    /// Indices of the columns of the pinned schema in the current schema.
+    column_indices: Vec<usize>,
+}
+
+impl EpochPinnedTable {
+    /// Pins the `inner` table to the `pinned` schema, returns `None` if the pinned schema is
+    /// at the same epoch as the current schema, or any column of the pinned schema is absent
+    /// in, or has a different type from, the current schema.
+    ///
+    /// A table is readable at any earlier epoch that only lacks the columns added later, and
+    /// at any later epoch that only lacks the columns dropped later. Schemas at the same epoch
+    /// are the same, so a pinned schema that differs from the current one at the same epoch
+    /// isn't made by ALTER and the table isn't readable at it.
+    pub fn try_new(inner: TableRef, pinned: &Schema) -> Option<Self> {
+        let current = inner.schema();
+        if pinned.version() == current.version() {
+            return None;
+        }
+        let mut column_schemas = Vec::with_capacity(pinned.num_columns());
+        let mut column_indices = Vec::with_capacity(pinned.num_columns());
+        for column in pinned.column_schemas() {
+            let index = current.column_index_by_name(&column.name)?;
+            let current_column = &current.column_schemas()[index];
+            if current_column.data_type != column.data_type {
+                return None;
+            }
+            // Takes the column from the current schema to keep its time index and metadata.
+            column_schemas.push(current_column.clone());
+            column_indices.push(index);
+        }
+
+        Some(Self {
+            inner,
+            schema: Arc::new(Schema::try_new(column_schemas).ok()?),
+            column_indices,
+        })
+    }
+}
+
+#[async_trait]
+impl Table for EpochPinnedTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        let mut info = TableInfo::clone(&self.inner.table_info());
+        info.meta.schema = self.schema.clone();
+        Arc::new(info)
+    }
+
+    fn try_table_info(&self) -> Option<TableInfoRef> {
+        let mut info = TableInfo::clone(&self.inner.try_table_info()?);
+        info.meta.schema = self.schema.clone();
+        Some(Arc::new(info))
+    }
+
+    fn table_type(&self) -> TableType {
+        self.inner.table_type()
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        // Filters refer to columns by names, which are the same at both epochs.
+        let projection = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|index| self.column_indices[*index])
+                .collect(),
+            None => self.column_indices.clone(),
+        };
+        self.inner.scan(Some(&projection), filters, limit).await
+    }
+
+    fn supports_filter_pushdown(&self, filter: &Expr) -> Result<FilterPushDownType> {
+        self.inner.supports_filter_pushdown(filter)
+    }
+
+    fn statistics(&self) -> Option<TableStatistics> {
+        self.inner.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::SessionContext;
+    use common_recordbatch::{util, RecordBatch};
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, SchemaBuilder};
+    use datatypes::vectors::{Int32Vector, StringVector};
+
+    use super::*;
+    use crate::test_util::MemTable;
+
+    fn new_table() -> TableRef {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::int32_datatype(), true),
+            // Added by the latest ALTER.
+            ColumnSchema::new("memory", ConcreteDataType::int32_datatype(), true),
+        ];
+        let schema = SchemaBuilder::try_from(column_schemas)
+            .unwrap()
+            .version(1)
+            .build()
+            .unwrap();
+        let schema = Arc::new(schema);
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["host1", "host2"])),
+            Arc::new(Int32Vector::from_slice([1, 2])),
+            Arc::new(Int32Vector::from_slice([10, 20])),
+        ];
+        let recordbatch = RecordBatch::new(schema, columns).unwrap();
+        Arc::new(MemTable::new("demo", recordbatch))
+    }
+
+    async fn scan(table: &EpochPinnedTable, projection: Option<&Vec<usize>>) -> Vec<VectorRef> {
+        let ctx = SessionContext::new();
+        let plan = table.scan(projection, &[], None).await.unwrap();
+        let stream = plan.execute(0, ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        batches[0].columns().to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_read_at_pinned_epoch() {
+        // The schema before the latest ALTER, in which the columns are in another order.
+        let pinned = Schema::new(vec![
+            ColumnSchema::new("cpu", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+        ]);
+        let table = EpochPinnedTable::try_new(new_table(), &pinned).unwrap();
+        let column_names: Vec<_> = table
+            .schema()
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.clone())
+            .collect();
+        assert_eq!(vec!["cpu", "host"], column_names);
+
+        let columns = scan(&table, None).await;
+        let expected: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from_slice([1, 2])),
+            Arc::new(StringVector::from(vec!["host1", "host2"])),
+        ];
+        assert_eq!(expected, columns);
+
+        let columns = scan(&table, Some(&vec![1])).await;
+        let expected: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec!["host1", "host2"]))];
+        assert_eq!(expected, columns);
+    }
+
+    #[test]
+    fn test_incompatible_epoch() {
+        // The column is dropped in the current schema.
+        let pinned = Schema::new(vec![ColumnSchema::new(
+            "disk",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]);
+        assert!(EpochPinnedTable::try_new(new_table(), &pinned).is_none());
+
+        // The type of the column is changed.
+        let pinned = Schema::new(vec![ColumnSchema::new(
+            "cpu",
+            ConcreteDataType::float64_datatype(),
+            true,
+        )]);
+        assert!(EpochPinnedTable::try_new(new_table(), &pinned).is_none());
+
+        // The schema differs from the current one at the same epoch.
+        let pinned = SchemaBuilder::try_from(vec![ColumnSchema::new(
+            "cpu",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )])
+        .unwrap()
+        .version(1)
+        .build()
+        .unwrap();
+        assert!(EpochPinnedTable::try_new(new_table(), &pinned).is_none());
+    }
+}
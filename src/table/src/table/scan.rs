@@ -14,19 +14,31 @@
 
 use std::any::Any;
 use std::fmt::{Debug, Formatter};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 
+use common_error::ext::BoxedError;
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
 use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef};
-use common_recordbatch::SendableRecordBatchStream;
+use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::Statistics;
+use datatypes::data_type::DataType;
 use datatypes::schema::SchemaRef;
-use snafu::OptionExt;
+use futures::Stream;
+use snafu::{ensure, IntoError, OptionExt};
+
+use crate::error::{Result as TableResult, TooManyScannedRowsSnafu, UnmaskableColumnSnafu};
+use crate::masking::{ColumnMask, ColumnMasks};
 
 pub struct SimpleTableScan {
     stream: Mutex<Option<SendableRecordBatchStream>>,
     schema: SchemaRef,
+    statistics: Statistics,
 }
 
 impl Debug for SimpleTableScan {
@@ -34,6 +46,7 @@ impl Debug for SimpleTableScan {
         f.debug_struct("SimpleTableScan")
             .field("stream", &"<SendableRecordBatchStream>")
             .field("schema", &self.schema)
+            .field("statistics", &self.statistics)
             .finish()
     }
 }
@@ -44,8 +57,15 @@ impl SimpleTableScan {
         Self {
             stream: Mutex::new(Some(stream)),
             schema,
+            statistics: Statistics::default(),
         }
     }
+
+    /// Sets the estimated statistics of the scanned data.
+    pub fn with_statistics(mut self, statistics: Statistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
 }
 
 impl PhysicalPlan for SimpleTableScan {
@@ -77,17 +97,347 @@ impl PhysicalPlan for SimpleTableScan {
         let mut stream = self.stream.lock().unwrap();
         stream.take().context(query_error::ExecuteRepeatedlySnafu)
     }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+}
+
+/// Adds the memory size of record batches scanned by the `inner` plan to a counter.
+#[derive(Debug)]
+pub struct ScannedBytesCountingScan {
+    inner: PhysicalPlanRef,
+    scanned_bytes: Arc<AtomicU64>,
+}
+
+impl ScannedBytesCountingScan {
+    pub fn new(inner: PhysicalPlanRef, scanned_bytes: Arc<AtomicU64>) -> Self {
+        Self {
+            inner,
+            scanned_bytes,
+        }
+    }
+
+    /// Returns the scan whose scanned bytes are counted.
+    pub fn inner(&self) -> &PhysicalPlanRef {
+        &self.inner
+    }
+}
+
+impl PhysicalPlan for ScannedBytesCountingScan {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn children(&self) -> Vec<PhysicalPlanRef> {
+        self.inner.children()
+    }
+
+    fn with_new_children(&self, children: Vec<PhysicalPlanRef>) -> QueryResult<PhysicalPlanRef> {
+        Ok(Arc::new(Self::new(
+            self.inner.with_new_children(children)?,
+            self.scanned_bytes.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> QueryResult<SendableRecordBatchStream> {
+        let stream = self.inner.execute(partition, context)?;
+        Ok(Box::pin(ScannedBytesCountingStream {
+            stream,
+            scanned_bytes: self.scanned_bytes.clone(),
+        }))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.inner.statistics()
+    }
+}
+
+struct ScannedBytesCountingStream {
+    stream: SendableRecordBatchStream,
+    scanned_bytes: Arc<AtomicU64>,
+}
+
+impl Stream for ScannedBytesCountingStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.stream).poll_next(cx);
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            let bytes = batch
+                .columns()
+                .iter()
+                .map(|column| column.memory_size())
+                .sum::<usize>();
+            self.scanned_bytes
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for ScannedBytesCountingStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+/// Fails once more than `max_rows` rows are scanned by the `inner` plan, rows of all
+/// the partitions are counted together.
+#[derive(Debug)]
+pub struct MaxRowsScan {
+    inner: PhysicalPlanRef,
+    max_rows: u64,
+    scanned_rows: Arc<AtomicU64>,
+}
+
+impl MaxRowsScan {
+    pub fn new(inner: PhysicalPlanRef, max_rows: u64) -> Self {
+        Self {
+            inner,
+            max_rows,
+            scanned_rows: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl PhysicalPlan for MaxRowsScan {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn children(&self) -> Vec<PhysicalPlanRef> {
+        self.inner.children()
+    }
+
+    fn with_new_children(&self, children: Vec<PhysicalPlanRef>) -> QueryResult<PhysicalPlanRef> {
+        Ok(Arc::new(Self::new(
+            self.inner.with_new_children(children)?,
+            self.max_rows,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> QueryResult<SendableRecordBatchStream> {
+        let stream = self.inner.execute(partition, context)?;
+        Ok(Box::pin(MaxRowsStream {
+            schema: stream.schema(),
+            stream: Some(stream),
+            max_rows: self.max_rows,
+            scanned_rows: self.scanned_rows.clone(),
+        }))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.inner.statistics()
+    }
+}
+
+struct MaxRowsStream {
+    schema: SchemaRef,
+    /// The inner stream, `None` once it ends or the max rows are exceeded.
+    stream: Option<SendableRecordBatchStream>,
+    max_rows: u64,
+    scanned_rows: Arc<AtomicU64>,
+}
+
+impl Stream for MaxRowsStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(stream) = self.stream.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let poll = stream.as_mut().poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                let rows = batch.num_rows() as u64;
+                let scanned_rows = self.scanned_rows.fetch_add(rows, Ordering::Relaxed) + rows;
+                if scanned_rows > self.max_rows {
+                    // Drops the inner stream to stop scanning.
+                    self.stream = None;
+                    let error = TooManyScannedRowsSnafu {
+                        max_rows: self.max_rows,
+                    }
+                    .build();
+                    return Poll::Ready(Some(
+                        Err(ExternalSnafu.into_error(BoxedError::new(error))),
+                    ));
+                }
+            }
+            Poll::Ready(None) => self.stream = None,
+            _ => {}
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for MaxRowsStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Masks values of the sensitive columns scanned by the `inner` plan.
+#[derive(Debug)]
+pub struct MaskingScan {
+    inner: PhysicalPlanRef,
+    /// Masks of the output columns, indexed by the position of the column.
+    masks: Arc<Vec<Option<ColumnMask>>>,
+}
+
+impl MaskingScan {
+    /// Creates a scan that masks columns of the `inner` plan with `masks`, returns
+    /// error if any masked column in the output is not a string column.
+    pub fn try_new(inner: PhysicalPlanRef, masks: &ColumnMasks) -> TableResult<Self> {
+        let masks = inner
+            .schema()
+            .column_schemas()
+            .iter()
+            .map(|column_schema| {
+                let Some(mask) = masks.get(&column_schema.name) else {
+                    return Ok(None);
+                };
+                ensure!(
+                    column_schema.data_type.is_string(),
+                    UnmaskableColumnSnafu {
+                        column_name: &column_schema.name,
+                        data_type: column_schema.data_type.name().to_string(),
+                    }
+                );
+                Ok(Some(mask.clone()))
+            })
+            .collect::<TableResult<Vec<_>>>()?;
+        Ok(Self {
+            inner,
+            masks: Arc::new(masks),
+        })
+    }
+
+    fn with_masks(inner: PhysicalPlanRef, masks: Arc<Vec<Option<ColumnMask>>>) -> Self {
+        Self { inner, masks }
+    }
+}
+
+impl PhysicalPlan for MaskingScan {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn children(&self) -> Vec<PhysicalPlanRef> {
+        self.inner.children()
+    }
+
+    fn with_new_children(&self, children: Vec<PhysicalPlanRef>) -> QueryResult<PhysicalPlanRef> {
+        Ok(Arc::new(Self::with_masks(
+            self.inner.with_new_children(children)?,
+            self.masks.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> QueryResult<SendableRecordBatchStream> {
+        let stream = self.inner.execute(partition, context)?;
+        Ok(Box::pin(MaskingStream {
+            stream,
+            masks: self.masks.clone(),
+        }))
+    }
+
+    fn statistics(&self) -> Statistics {
+        // Only the row count and size are kept, as the masked values differ from the
+        // values the column statistics describe.
+        let statistics = self.inner.statistics();
+        Statistics {
+            column_statistics: None,
+            ..statistics
+        }
+    }
+}
+
+struct MaskingStream {
+    stream: SendableRecordBatchStream,
+    masks: Arc<Vec<Option<ColumnMask>>>,
+}
+
+impl MaskingStream {
+    fn mask(&self, batch: RecordBatch) -> RecordBatchResult<RecordBatch> {
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.masks.iter())
+            .map(|(column, mask)| match mask {
+                Some(mask) => mask.mask_vector(column),
+                None => column.clone(),
+            })
+            .collect::<Vec<_>>();
+        RecordBatch::new(batch.schema.clone(), columns)
+    }
+}
+
+impl Stream for MaskingStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.stream).poll_next(cx) {
+            Poll::Ready(Some(Ok(batch))) => Poll::Ready(Some(self.mask(batch))),
+            poll => poll,
+        }
+    }
+}
+
+impl RecordBatchStream for MaskingStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use common_error::prelude::{ErrorExt, StatusCode};
     use common_recordbatch::{util, RecordBatch, RecordBatches};
     use datafusion::prelude::SessionContext;
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, Schema};
-    use datatypes::vectors::Int32Vector;
+    use datatypes::vectors::{Int32Vector, StringVector};
+    use futures::StreamExt;
 
     use super::*;
+    use crate::masking::MaskingMethod;
 
     #[tokio::test]
     async fn test_simple_table_scan() {
@@ -131,4 +481,102 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_scanned_bytes_counting_scan() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice(&[1, 2, 3])) as _],
+        )
+        .unwrap();
+        let expected_bytes = batch.column(0).memory_size() as u64;
+        let recordbatches = RecordBatches::try_new(schema, vec![batch.clone(), batch]).unwrap();
+
+        let scanned_bytes = Arc::new(AtomicU64::new(0));
+        let scan = ScannedBytesCountingScan::new(
+            Arc::new(SimpleTableScan::new(recordbatches.as_stream())),
+            scanned_bytes.clone(),
+        );
+        let stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        let recordbatches = util::collect(stream).await.unwrap();
+        assert_eq!(2, recordbatches.len());
+        assert_eq!(expected_bytes * 2, scanned_bytes.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_max_rows_scan() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_slice(&[1, 2, 3])) as _],
+        )
+        .unwrap();
+        let new_inner = || {
+            let recordbatches =
+                RecordBatches::try_new(schema.clone(), vec![batch.clone(), batch.clone()]).unwrap();
+            Arc::new(SimpleTableScan::new(recordbatches.as_stream())) as PhysicalPlanRef
+        };
+
+        let scan = MaxRowsScan::new(new_inner(), 6);
+        let stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        assert_eq!(2, util::collect(stream).await.unwrap().len());
+
+        let scan = MaxRowsScan::new(new_inner(), 5);
+        let mut stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        assert!(stream.next().await.unwrap().is_ok());
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_masking_scan() {
+        let ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), false),
+            ColumnSchema::new("phone", ConcreteDataType::string_datatype(), true),
+        ]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Vector::from_slice(&[1, 2])) as _,
+                Arc::new(StringVector::from(vec![Some("13800001234"), None])) as _,
+            ],
+        )
+        .unwrap();
+        let new_inner = || {
+            let recordbatches =
+                RecordBatches::try_new(schema.clone(), vec![batch.clone()]).unwrap();
+            Arc::new(SimpleTableScan::new(recordbatches.as_stream())) as PhysicalPlanRef
+        };
+
+        let masks =
+            ColumnMasks::from([("phone".to_string(), ColumnMask::new(MaskingMethod::Partial))]);
+        let scan = MaskingScan::try_new(new_inner(), &masks).unwrap();
+        let stream = scan.execute(0, ctx.task_ctx()).unwrap();
+        let recordbatches = util::collect(stream).await.unwrap();
+        let expect = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Vector::from_slice(&[1, 2])) as _,
+                Arc::new(StringVector::from(vec![Some("*******1234"), None])) as _,
+            ],
+        )
+        .unwrap();
+        assert_eq!(vec![expect], recordbatches);
+
+        let masks = ColumnMasks::from([("a".to_string(), ColumnMask::new(MaskingMethod::Redact))]);
+        assert!(MaskingScan::try_new(new_inner(), &masks).is_err());
+    }
 }
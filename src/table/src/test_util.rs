@@ -13,9 +13,11 @@
 // limitations under the License.
 
 mod empty_table;
+mod memory_engine;
 mod memtable;
 mod mock_engine;
 
 pub use empty_table::EmptyTable;
+pub use memory_engine::{MemoryTable, MemoryTableEngine, MEMORY_ENGINE};
 pub use memtable::MemTable;
 pub use mock_engine::MockTableEngine;
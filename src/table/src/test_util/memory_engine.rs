@@ -0,0 +1,451 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_query::prelude::Expr;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::schema::{Schema, SchemaRef};
+use datatypes::vectors::VectorRef;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::engine::{EngineContext, TableEngine, TableReference};
+use crate::error::{
+    ColumnLengthMismatchSnafu, ColumnNotExistsSnafu, MissingColumnSnafu, Result,
+    SchemaConversionSnafu, TableExistsSnafu, TableOperationSnafu, TableProjectionSnafu,
+    TablesRecordBatchSnafu, UnsupportedSnafu,
+};
+use crate::metadata::{TableInfoBuilder, TableInfoRef, TableMetaBuilder, TableType};
+use crate::requests::{
+    AlterTableRequest, CreateTableRequest, DropTableRequest, InsertRequest, OpenTableRequest,
+};
+use crate::table::scan::SimpleTableScan;
+use crate::{Table, TableRef};
+
+pub const MEMORY_ENGINE: &str = "memory";
+
+/// Table engine that keeps all tables and their data in memory, there is no WAL and no
+/// object store, so the data is lost once the engine is dropped. It is intended for tests
+/// that don't care about persistence.
+#[derive(Default)]
+pub struct MemoryTableEngine {
+    tables: RwLock<HashMap<String, TableRef>>,
+}
+
+impl MemoryTableEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TableEngine for MemoryTableEngine {
+    fn name(&self) -> &str {
+        MEMORY_ENGINE
+    }
+
+    async fn create_table(
+        &self,
+        _ctx: &EngineContext,
+        request: CreateTableRequest,
+    ) -> Result<TableRef> {
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: &request.table_name,
+        };
+        let full_table_name = table_ref.to_string();
+
+        let mut tables = self.tables.write().unwrap();
+        if let Some(table) = tables.get(&full_table_name) {
+            ensure!(
+                request.create_if_not_exists,
+                TableExistsSnafu {
+                    table_name: full_table_name,
+                }
+            );
+            return Ok(table.clone());
+        }
+
+        let table: TableRef = Arc::new(MemoryTable::new(request));
+        tables.insert(full_table_name, table.clone());
+        Ok(table)
+    }
+
+    async fn open_table(
+        &self,
+        ctx: &EngineContext,
+        request: OpenTableRequest,
+    ) -> Result<Option<TableRef>> {
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: &request.table_name,
+        };
+        self.get_table(ctx, &table_ref)
+    }
+
+    async fn alter_table(
+        &self,
+        _ctx: &EngineContext,
+        _request: AlterTableRequest,
+    ) -> Result<TableRef> {
+        UnsupportedSnafu {
+            operation: "ALTER TABLE",
+        }
+        .fail()
+    }
+
+    fn get_table(
+        &self,
+        _ctx: &EngineContext,
+        table_ref: &TableReference,
+    ) -> Result<Option<TableRef>> {
+        Ok(self
+            .tables
+            .read()
+            .unwrap()
+            .get(&table_ref.to_string())
+            .cloned())
+    }
+
+    fn table_exists(&self, _ctx: &EngineContext, table_ref: &TableReference) -> bool {
+        self.tables
+            .read()
+            .unwrap()
+            .contains_key(&table_ref.to_string())
+    }
+
+    async fn drop_table(&self, _ctx: &EngineContext, request: DropTableRequest) -> Result<bool> {
+        let table_ref = TableReference {
+            catalog: &request.catalog_name,
+            schema: &request.schema_name,
+            table: &request.table_name,
+        };
+        Ok(self
+            .tables
+            .write()
+            .unwrap()
+            .remove(&table_ref.to_string())
+            .is_some())
+    }
+}
+
+/// Table of the [MemoryTableEngine], rows are appended to the table in the order of insertion.
+pub struct MemoryTable {
+    info: TableInfoRef,
+    batches: RwLock<Vec<RecordBatch>>,
+}
+
+impl MemoryTable {
+    pub fn new(request: CreateTableRequest) -> Self {
+        let table_meta = TableMetaBuilder::default()
+            .schema(request.schema)
+            .primary_key_indices(request.primary_key_indices)
+            .engine(MEMORY_ENGINE)
+            .region_numbers(request.region_numbers)
+            .next_column_id(0)
+            .options(request.table_options)
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .table_id(request.id)
+            .catalog_name(request.catalog_name)
+            .schema_name(request.schema_name)
+            .name(request.table_name)
+            .meta(table_meta)
+            .table_type(TableType::Base)
+            .desc(request.desc)
+            .build()
+            .unwrap();
+
+        Self {
+            info: Arc::new(table_info),
+            batches: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Returns the schema of the columns in `projection`.
+    fn projected_schema(&self, projection: Option<&Vec<usize>>) -> Result<SchemaRef> {
+        let schema = self.schema();
+        let Some(indices) = projection else {
+            return Ok(schema);
+        };
+        let arrow_schema = schema
+            .arrow_schema()
+            .project(indices)
+            .context(TableProjectionSnafu)?;
+        Ok(Arc::new(
+            Schema::try_from(arrow_schema).context(SchemaConversionSnafu)?,
+        ))
+    }
+}
+
+#[async_trait]
+impl Table for MemoryTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.info.clone()
+    }
+
+    async fn insert(&self, mut request: InsertRequest) -> Result<usize> {
+        let schema = self.schema();
+        for column_name in request.columns_values.keys() {
+            ensure!(
+                schema.column_schema_by_name(column_name).is_some(),
+                ColumnNotExistsSnafu {
+                    column_name,
+                    table_name: &self.info.name,
+                }
+            );
+        }
+
+        let num_rows = request
+            .columns_values
+            .values()
+            .next()
+            .map(|vector| vector.len())
+            .unwrap_or_default();
+        for (column_name, vector) in &request.columns_values {
+            ensure!(
+                vector.len() == num_rows,
+                ColumnLengthMismatchSnafu {
+                    column_name,
+                    table_name: &self.info.name,
+                    expected: num_rows,
+                    actual: vector.len(),
+                }
+            );
+        }
+        let mut columns = Vec::with_capacity(schema.num_columns());
+        for column_schema in schema.column_schemas() {
+            let vector = match request.columns_values.remove(&column_schema.name) {
+                Some(vector) => vector,
+                None => column_schema
+                    .create_default_vector(num_rows)
+                    .map_err(BoxedError::new)
+                    .context(TableOperationSnafu)?
+                    .with_context(|| MissingColumnSnafu {
+                        column_name: &column_schema.name,
+                        table_name: &self.info.name,
+                    })?,
+            };
+            columns.push(vector);
+        }
+
+        let batch = RecordBatch::new(schema, columns)
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        self.batches.write().unwrap().push(batch);
+        Ok(num_rows)
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        let schema = self.projected_schema(projection)?;
+        let mut remaining = limit.unwrap_or(usize::MAX);
+        let mut batches = Vec::new();
+        for batch in self.batches.read().unwrap().iter() {
+            if remaining == 0 {
+                break;
+            }
+            let num_rows = batch.num_rows().min(remaining);
+            remaining -= num_rows;
+
+            let columns: Vec<VectorRef> = match projection {
+                Some(indices) => indices.iter().map(|i| batch.column(*i).clone()).collect(),
+                None => batch.columns().to_vec(),
+            };
+            let columns = columns.into_iter().map(|column| column.slice(0, num_rows));
+            batches.push(
+                RecordBatch::new(schema.clone(), columns)
+                    .map_err(BoxedError::new)
+                    .context(TablesRecordBatchSnafu)?,
+            );
+        }
+
+        let batches = RecordBatches::try_new(schema, batches)
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(batches.as_stream())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use common_recordbatch::util;
+    use datafusion::prelude::SessionContext;
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
+    use datatypes::vectors::{Int64Vector, StringVector};
+
+    use super::*;
+
+    fn new_create_request(table_name: &str) -> CreateTableRequest {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new("ts", ConcreteDataType::int64_datatype(), false)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(Value::Int64(0))))
+                .unwrap(),
+        ];
+        CreateTableRequest {
+            id: 1024,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: table_name.to_string(),
+            desc: None,
+            schema: Arc::new(Schema::new(column_schemas)),
+            region_numbers: vec![0],
+            primary_key_indices: vec![0],
+            create_if_not_exists: false,
+            table_options: HashMap::new(),
+        }
+    }
+
+    fn new_insert_request(hosts: &[&str]) -> InsertRequest {
+        let columns_values = HashMap::from([
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(hosts.to_vec())) as VectorRef,
+            ),
+            (
+                "cpu".to_string(),
+                Arc::new(Int64Vector::from_vec(vec![1; hosts.len()])) as VectorRef,
+            ),
+        ]);
+        InsertRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            columns_values,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_engine_create_drop() {
+        let engine = MemoryTableEngine::new();
+        let ctx = EngineContext::default();
+        let table_ref = TableReference::full("greptime", "public", "demo");
+        assert_eq!(MEMORY_ENGINE, engine.name());
+        assert!(!engine.table_exists(&ctx, &table_ref));
+
+        let table = engine
+            .create_table(&ctx, new_create_request("demo"))
+            .await
+            .unwrap();
+        assert_eq!(1024, table.table_info().ident.table_id);
+        assert_eq!(MEMORY_ENGINE, table.table_info().meta.engine);
+        assert!(engine.table_exists(&ctx, &table_ref));
+
+        let err = engine
+            .create_table(&ctx, new_create_request("demo"))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::TableAlreadyExists, err.status_code());
+        let mut request = new_create_request("demo");
+        request.create_if_not_exists = true;
+        assert!(engine.create_table(&ctx, request).await.is_ok());
+
+        let request = OpenTableRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+            table_id: 1024,
+            region_numbers: vec![0],
+        };
+        assert!(engine.open_table(&ctx, request).await.unwrap().is_some());
+
+        let request = DropTableRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "demo".to_string(),
+        };
+        assert!(engine.drop_table(&ctx, request).await.unwrap());
+        assert!(!engine.table_exists(&ctx, &table_ref));
+        assert!(engine.get_table(&ctx, &table_ref).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_table_insert_scan() {
+        let ctx = SessionContext::new();
+        let table = MemoryTable::new(new_create_request("demo"));
+        assert_eq!(
+            2,
+            table.insert(new_insert_request(&["a", "b"])).await.unwrap()
+        );
+        assert_eq!(1, table.insert(new_insert_request(&["c"])).await.unwrap());
+
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        let expected = "\
++------+-----+----+
+| host | cpu | ts |
++------+-----+----+
+| a    | 1   | 0  |
+| b    | 1   | 0  |
+| c    | 1   | 0  |
++------+-----+----+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+
+        let stream = table.scan(Some(&vec![0]), &[], Some(1)).await.unwrap();
+        let stream = stream.execute(0, ctx.task_ctx()).unwrap();
+        let batches = util::collect_batches(stream).await.unwrap();
+        let expected = "\
++------+
+| host |
++------+
+| a    |
++------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+
+        // Column without default value is required.
+        let mut request = new_insert_request(&["d"]);
+        request.columns_values.remove("host");
+        let err = table.insert(request).await.unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        let mut request = new_insert_request(&["d"]);
+        request.columns_values.insert(
+            "memory".to_string(),
+            Arc::new(Int64Vector::from_vec(vec![1])),
+        );
+        let err = table.insert(request).await.unwrap_err();
+        assert_eq!(StatusCode::TableColumnNotFound, err.status_code());
+        // Columns should have the same number of rows.
+        let mut request = new_insert_request(&["d", "e"]);
+        request
+            .columns_values
+            .insert("cpu".to_string(), Arc::new(Int64Vector::from_vec(vec![1])));
+        let err = table.insert(request).await.unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+}
@@ -22,6 +22,7 @@ use std::time::Duration;
 use axum::Router;
 use catalog::CatalogManagerRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
+use common_grpc::token::ClusterToken;
 use common_runtime::Builder as RuntimeBuilder;
 use datanode::datanode::{
     DatanodeOptions, FileConfig, ObjectStoreConfig, OssConfig, S3Config, WalConfig,
@@ -331,6 +332,14 @@ pub async fn setup_test_promql_app_with_frontend(
 pub async fn setup_grpc_server(
     store_type: StorageType,
     name: &str,
+) -> (String, TestGuard, Arc<GrpcServer>) {
+    setup_grpc_server_with_cluster_token(store_type, name, None).await
+}
+
+pub async fn setup_grpc_server_with_cluster_token(
+    store_type: StorageType,
+    name: &str,
+    cluster_token: Option<ClusterToken>,
 ) -> (String, TestGuard, Arc<GrpcServer>) {
     common_telemetry::init_default_ut_logging();
 
@@ -350,10 +359,10 @@ pub async fn setup_grpc_server(
 
     let fe_instance = frontend::instance::Instance::new_standalone(instance.clone());
     let fe_instance_ref = Arc::new(fe_instance);
-    let fe_grpc_server = Arc::new(GrpcServer::new(
-        ServerGrpcQueryHandlerAdaptor::arc(fe_instance_ref),
-        runtime,
-    ));
+    let fe_grpc_server = Arc::new(
+        GrpcServer::new(ServerGrpcQueryHandlerAdaptor::arc(fe_instance_ref), runtime)
+            .with_cluster_token(cluster_token),
+    );
     let grpc_server_clone = fe_grpc_server.clone();
 
     let fe_grpc_addr_clone = fe_grpc_addr.clone();
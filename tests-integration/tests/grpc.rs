@@ -14,14 +14,18 @@
 use api::v1::alter_expr::Kind;
 use api::v1::column::SemanticType;
 use api::v1::{
-    column, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef, CreateTableExpr,
-    InsertRequest, TableId,
+    column, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef, Compression,
+    CreateTableExpr, InsertRequest, TableId,
 };
 use client::{Client, Database};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_grpc::token::ClusterToken;
 use common_query::Output;
 use servers::server::Server;
-use tests_integration::test_util::{setup_grpc_server, StorageType};
+use tests_integration::test_util::{
+    setup_grpc_server, setup_grpc_server_with_cluster_token, StorageType,
+};
 
 #[macro_export]
 macro_rules! grpc_test {
@@ -54,7 +58,9 @@ macro_rules! grpc_tests {
                 $service,
 
                 test_auto_create_table,
+                test_insert_compressed,
                 test_insert_and_select,
+                test_cluster_token,
             );
         )*
     };
@@ -71,6 +77,40 @@ pub async fn test_auto_create_table(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_insert_compressed(store_type: StorageType) {
+    let (addr, mut guard, fe_grpc_server) =
+        setup_grpc_server(store_type, "insert_compressed").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let mut db = Database::with_client(grpc_client);
+    db.set_compression(Compression::Lz4);
+    insert_and_assert(&db).await;
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
+pub async fn test_cluster_token(store_type: StorageType) {
+    let token = ClusterToken::new("secret");
+    let (addr, mut guard, fe_grpc_server) =
+        setup_grpc_server_with_cluster_token(store_type, "cluster_token", Some(token.clone()))
+            .await;
+
+    // Requests without the token or with a wrong token are rejected.
+    let db = Database::with_client(Client::with_urls(vec![addr.clone()]));
+    assert!(db.sql("SELECT 1").await.is_err());
+    let channel_manager =
+        ChannelManager::with_config(ChannelConfig::new().cluster_token(ClusterToken::new("guess")));
+    let db = Database::with_client(Client::with_manager_and_urls(channel_manager, vec![&addr]));
+    assert!(db.sql("SELECT 1").await.is_err());
+
+    let channel_manager = ChannelManager::with_config(ChannelConfig::new().cluster_token(token));
+    let db = Database::with_client(Client::with_manager_and_urls(channel_manager, vec![addr]));
+    insert_and_assert(&db).await;
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 fn expect_data() -> (Column, Column, Column, Column) {
     // testing data:
     let expected_host_col = Column {
@@ -95,6 +135,7 @@ fn expect_data() -> (Column, Column, Column, Column) {
         null_mask: vec![2],
         semantic_type: SemanticType::Field as i32,
         datatype: ColumnDataType::Float64 as i32,
+        compressed_values: vec![],
     };
     let expected_mem_col = Column {
         column_name: "memory".to_string(),
@@ -105,6 +146,7 @@ fn expect_data() -> (Column, Column, Column, Column) {
         null_mask: vec![4],
         semantic_type: SemanticType::Field as i32,
         datatype: ColumnDataType::Float64 as i32,
+        compressed_values: vec![],
     };
     let expected_ts_col = Column {
         column_name: "ts".to_string(),
@@ -144,6 +186,7 @@ pub async fn test_insert_and_select(store_type: StorageType) {
         datatype: ColumnDataType::Int64.into(),
         is_nullable: true,
         default_constraint: vec![],
+        comment: String::new(),
     };
     let kind = Kind::AddColumns(AddColumns {
         add_columns: vec![AddColumn {
@@ -181,6 +224,7 @@ async fn insert_and_assert(db: &Database) {
             expected_ts_col.clone(),
         ],
         row_count: 4,
+        ..Default::default()
     };
     let result = db.insert(request).await;
     result.unwrap();
@@ -228,24 +272,28 @@ fn testing_create_expr() -> CreateTableExpr {
             datatype: ColumnDataType::String as i32,
             is_nullable: false,
             default_constraint: vec![],
+            comment: String::new(),
         },
         ColumnDef {
             name: "cpu".to_string(),
             datatype: ColumnDataType::Float64 as i32,
             is_nullable: true,
             default_constraint: vec![],
+            comment: String::new(),
         },
         ColumnDef {
             name: "memory".to_string(),
             datatype: ColumnDataType::Float64 as i32,
             is_nullable: true,
             default_constraint: vec![],
+            comment: String::new(),
         },
         ColumnDef {
             name: "ts".to_string(),
             datatype: ColumnDataType::TimestampMillisecond as i32, // timestamp
             is_nullable: true,
             default_constraint: vec![],
+            comment: String::new(),
         },
     ];
     CreateTableExpr {